@@ -0,0 +1,227 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Result};
+use std::path::{Path, PathBuf};
+
+use serde_yaml::Value;
+
+fn main() -> Result<()> {
+    let spec_path = PathBuf::from("../../api/openapi/openapi.yaml");
+    generate_api_models(&spec_path, Path::new("src/gen/api_models.rs"))?;
+    println!("cargo:rerun-if-changed={}", spec_path.display());
+    Ok(())
+}
+
+/// Render `components/schemas` of the OpenAPI spec as serde models (see
+/// `crate::client::models`).
+///
+/// Object schemas become structs. Properties outside `required` become
+/// `Option`s left out of request bodies when unset; nullable properties become
+/// `Option`s sent as `null`. Schemas with no fixed shape (`oneOf`, free-form
+/// objects) become `serde_json::Value` and string enums stay `String`, so a
+/// server that grows a variant does not break older CLIs.
+///
+/// The output is only rewritten when it changes so the checked-in file stays
+/// stable across builds.
+fn generate_api_models(spec_path: &Path, out_path: &Path) -> Result<()> {
+    let raw = fs::read_to_string(spec_path)?;
+    let spec: Value = serde_yaml::from_str(&raw).map_err(io::Error::other)?;
+    let schemas = spec
+        .get("components")
+        .and_then(|components| components.get("schemas"))
+        .and_then(Value::as_mapping)
+        .ok_or_else(|| io::Error::other("openapi.yaml has no components/schemas"))?;
+
+    let mut out = String::new();
+    out.push_str(
+        "// This file is @generated by cli/ghostctl/build.rs from api/openapi/openapi.yaml.\n",
+    );
+    out.push_str("// Do not edit by hand.\n\n");
+    out.push_str("use std::collections::BTreeMap;\n\n");
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+
+    for (name, schema) in schemas {
+        let name = name
+            .as_str()
+            .ok_or_else(|| io::Error::other("non-string schema name"))?;
+        out.push('\n');
+        write_docs(&mut out, "", schema);
+
+        let Some(properties) = object_properties(&spec, schema)? else {
+            let _ = writeln!(out, "pub type {name} = {};", rust_type(schema));
+            continue;
+        };
+
+        out.push_str("#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]\n");
+        let _ = writeln!(out, "pub struct {name} {{");
+        for (field, property, required) in properties {
+            write_docs(&mut out, "    ", property);
+            let mut ty = rust_type(property);
+            let ident = field_ident(&field);
+            let mut serde = Vec::new();
+            if ident.trim_start_matches("r#") != field {
+                serde.push(format!("rename = {field:?}"));
+            }
+            if is_nullable(property) {
+                ty = format!("Option<{ty}>");
+                serde.push("default".to_string());
+            } else if !required {
+                ty = format!("Option<{ty}>");
+                serde.push("default".to_string());
+                serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if !serde.is_empty() {
+                let _ = writeln!(out, "    #[serde({})]", serde.join(", "));
+            }
+            let _ = writeln!(out, "    pub {ident}: {ty},");
+        }
+        out.push_str("}\n");
+    }
+
+    if fs::read_to_string(out_path).ok().as_deref() != Some(out.as_str()) {
+        fs::write(out_path, out)?;
+    }
+    Ok(())
+}
+
+/// A property name, its schema, and whether it is required.
+type Property<'a> = (String, &'a Value, bool);
+
+/// Properties of an object schema, merging `allOf` parts. `None` when the
+/// schema is not an object with properties.
+fn object_properties<'a>(spec: &'a Value, schema: &'a Value) -> Result<Option<Vec<Property<'a>>>> {
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_sequence) {
+        let mut merged = Vec::new();
+        for part in parts {
+            let part = resolve(spec, part)?;
+            let Some(properties) = object_properties(spec, part)? else {
+                return Ok(None);
+            };
+            merged.extend(properties);
+        }
+        return Ok(Some(merged));
+    }
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_mapping) else {
+        return Ok(None);
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let name = name
+                .as_str()
+                .ok_or_else(|| io::Error::other("non-string property name"))?;
+            Ok((name.to_string(), property, required.contains(&name)))
+        })
+        .collect::<Result<_>>()
+        .map(Some)
+}
+
+/// Rust type of a property schema, ignoring nullability.
+fn rust_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some([only]) = schema
+        .get("allOf")
+        .and_then(Value::as_sequence)
+        .map(Vec::as_slice)
+    {
+        return rust_type(only);
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => Some(ty.as_str()),
+        Some(Value::Sequence(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|ty| *ty != "null"),
+        _ => None,
+    };
+    match ty {
+        Some("string") => "String".to_string(),
+        Some("integer") => "i64".to_string(),
+        Some("number") => "f64".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("array") => match schema.get("items") {
+            Some(items) => format!("Vec<{}>", rust_type(items)),
+            None => "Vec<serde_json::Value>".to_string(),
+        },
+        Some("object") if schema.get("properties").is_none() => {
+            match schema.get("additionalProperties") {
+                Some(values) if values.is_mapping() => {
+                    format!("BTreeMap<String, {}>", rust_type(values))
+                }
+                _ => "serde_json::Value".to_string(),
+            }
+        }
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    schema.get("nullable").and_then(Value::as_bool) == Some(true)
+        || schema
+            .get("type")
+            .and_then(Value::as_sequence)
+            .is_some_and(|types| types.iter().any(|ty| ty.as_str() == Some("null")))
+}
+
+fn write_docs(out: &mut String, indent: &str, schema: &Value) {
+    let Some(description) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    for line in description.trim().lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            let _ = writeln!(out, "{indent}///");
+        } else {
+            let _ = writeln!(out, "{indent}/// {line}");
+        }
+    }
+}
+
+fn field_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
+        "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+        "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type",
+        "unsafe", "use", "where", "while", "yield",
+    ];
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+/// Follow a local `$ref` (`#/components/...`).
+fn resolve<'a>(spec: &'a Value, node: &'a Value) -> Result<&'a Value> {
+    let Some(reference) = node.get("$ref").and_then(Value::as_str) else {
+        return Ok(node);
+    };
+    let pointer = reference
+        .strip_prefix("#/")
+        .ok_or_else(|| io::Error::other(format!("unsupported $ref: {reference}")))?;
+    pointer.split('/').try_fold(spec, |value, key| {
+        value
+            .get(key)
+            .ok_or_else(|| io::Error::other(format!("unresolved $ref: {reference}")))
+    })
+}
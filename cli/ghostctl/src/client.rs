@@ -1,11 +1,27 @@
 //! HTTP client for API communication.
 
 use anyhow::{Context, Result};
+use plfm_proto::errors;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::config::{Config, Credentials};
-use crate::error::CliError;
+use crate::error::{CliError, FieldViolation};
+use crate::http_trace::RequestTrace;
+
+/// Request and response bodies generated from `api/openapi/openapi.yaml`.
+///
+/// Command modules build requests and decode responses with these types, so a
+/// field renamed in the spec fails to compile here instead of drifting.
+/// Resources whose types the server shares through `plfm_api_types` use those
+/// instead.
+#[allow(dead_code)]
+pub mod models {
+    include!("gen/api_models.rs");
+}
+
+/// Header carrying the server-assigned request ID.
+const REQUEST_ID_HEADER: &str = "x-request-id";
 
 /// API client for communicating with the control plane.
 #[derive(Debug, Clone)]
@@ -46,24 +62,25 @@ impl ApiClient {
 
     /// Make a GET request.
     pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, CliError> {
-        let response = self.client.get(self.url(path)).send().await?;
+        let (response, trace) = self.send(self.client.get(self.url(path))).await?;
 
-        self.handle_response(response).await
+        self.handle_response(response, &trace).await
     }
 
     /// Make a GET request to an NDJSON endpoint and return the raw response body.
     pub async fn get_ndjson_stream(&self, path: &str) -> Result<reqwest::Response, CliError> {
-        let response = self
-            .client
-            .get(self.url(path))
-            .header(ACCEPT, "application/x-ndjson")
-            .send()
+        let (response, trace) = self
+            .send(
+                self.client
+                    .get(self.url(path))
+                    .header(ACCEPT, "application/x-ndjson"),
+            )
             .await?;
 
         if response.status().is_success() {
             Ok(response)
         } else {
-            self.handle_error(response).await
+            self.handle_error(response, &trace).await
         }
     }
 
@@ -78,9 +95,9 @@ impl ApiClient {
         if let Some(key) = idempotency_key {
             request = request.header(crate::idempotency::IDEMPOTENCY_KEY_HEADER, key);
         }
-        let response = request.send().await?;
+        let (response, trace) = self.send(request).await?;
 
-        self.handle_response(response).await
+        self.handle_response(response, &trace).await
     }
 
     /// Make a PUT request with an optional Idempotency-Key.
@@ -94,9 +111,9 @@ impl ApiClient {
         if let Some(key) = idempotency_key {
             request = request.header(crate::idempotency::IDEMPOTENCY_KEY_HEADER, key);
         }
-        let response = request.send().await?;
+        let (response, trace) = self.send(request).await?;
 
-        self.handle_response(response).await
+        self.handle_response(response, &trace).await
     }
 
     /// Make a PATCH request with an optional Idempotency-Key.
@@ -110,9 +127,9 @@ impl ApiClient {
         if let Some(key) = idempotency_key {
             request = request.header(crate::idempotency::IDEMPOTENCY_KEY_HEADER, key);
         }
-        let response = request.send().await?;
+        let (response, trace) = self.send(request).await?;
 
-        self.handle_response(response).await
+        self.handle_response(response, &trace).await
     }
 
     /// Make a DELETE request with an optional Idempotency-Key.
@@ -126,12 +143,36 @@ impl ApiClient {
             request = request.header(crate::idempotency::IDEMPOTENCY_KEY_HEADER, key);
         }
 
-        let response = request.send().await?;
+        let (response, trace) = self.send(request).await?;
 
         if response.status().is_success() {
             Ok(())
         } else {
-            self.handle_error(response).await
+            self.handle_error(response, &trace).await
+        }
+    }
+
+    /// Send a request, tracing it (see [`crate::http_trace`]).
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<(reqwest::Response, RequestTrace), CliError> {
+        let request = request.build()?;
+        let trace = RequestTrace::start(request.method(), request.url());
+
+        match self.client.execute(request).await {
+            Ok(response) => {
+                let request_id = response
+                    .headers()
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok());
+                trace.response(response.status().as_u16(), request_id);
+                Ok((response, trace))
+            }
+            Err(e) => {
+                trace.network_error(&e);
+                Err(e.into())
+            }
         }
     }
 
@@ -139,6 +180,7 @@ impl ApiClient {
     async fn handle_response<T: DeserializeOwned>(
         &self,
         response: reqwest::Response,
+        trace: &RequestTrace,
     ) -> Result<T, CliError> {
         let status = response.status();
 
@@ -148,22 +190,27 @@ impl ApiClient {
                 .await
                 .map_err(|e| CliError::Other(anyhow::anyhow!("Failed to parse response: {}", e)))
         } else {
-            self.handle_error(response).await
+            self.handle_error(response, trace).await
         }
     }
 
     /// Handle an error response.
-    async fn handle_error<T>(&self, response: reqwest::Response) -> Result<T, CliError> {
+    async fn handle_error<T>(
+        &self,
+        response: reqwest::Response,
+        trace: &RequestTrace,
+    ) -> Result<T, CliError> {
         let status = response.status().as_u16();
+        let header_request_id = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
         let body = response.text().await.unwrap_or_default();
 
         let problem = serde_json::from_str::<ProblemDetailsResponse>(&body).ok();
         let legacy = serde_json::from_str::<LegacyApiErrorResponse>(&body).ok();
 
-        if status == 401 {
-            return Err(CliError::NotAuthenticated);
-        }
-
         let code = problem
             .as_ref()
             .and_then(|problem| problem.code.as_deref())
@@ -184,15 +231,26 @@ impl ApiClient {
         let request_id = problem
             .as_ref()
             .and_then(|problem| problem.request_id.clone())
-            .or_else(|| legacy.as_ref().and_then(|error| error.request_id.clone()));
+            .or_else(|| legacy.as_ref().and_then(|error| error.request_id.clone()))
+            .or(header_request_id);
         let retryable = problem
             .as_ref()
             .and_then(|problem| problem.retryable)
+            .or_else(|| errors::lookup(&code).map(|spec| spec.retryable))
             .unwrap_or(false);
         let retry_after_seconds = problem
             .as_ref()
             .and_then(|problem| problem.retry_after_seconds);
 
+        let violations = problem
+            .and_then(|problem| problem.details)
+            .unwrap_or_default();
+
+        trace.failed(status, request_id.as_deref(), &code, &message);
+        if status == 401 {
+            return Err(CliError::NotAuthenticated);
+        }
+
         Err(CliError::api(
             status,
             code,
@@ -200,7 +258,8 @@ impl ApiClient {
             request_id,
             retryable,
             retry_after_seconds,
-        ))
+        )
+        .with_violations(violations))
     }
 }
 
@@ -218,6 +277,8 @@ struct ProblemDetailsResponse {
     request_id: Option<String>,
     retryable: Option<bool>,
     retry_after_seconds: Option<u32>,
+    #[serde(default)]
+    details: Option<Vec<FieldViolation>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,455 @@
+//! Alert commands.
+//!
+//! Alert rules are org-scoped; the control plane evaluates them and emits
+//! `alert.fired` / `alert.resolved` events, which notification rules can
+//! deliver.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::client::models::{
+    Alert, AlertCondition, AlertRule, AlertRuleRequest, ListAlertRulesResponse, ListAlertsResponse,
+};
+use crate::client::ApiClient;
+use crate::error::CliError;
+use crate::output::{
+    print_output, print_receipt, print_receipt_no_resource, print_single, OutputFormat, Receipt,
+    ReceiptNextStep, ReceiptNoResource,
+};
+
+use super::CommandContext;
+
+/// Alert commands.
+#[derive(Debug, Args)]
+pub struct AlertsCommand {
+    #[command(subcommand)]
+    command: AlertsSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum AlertsSubcommand {
+    /// List pending and firing alerts in an org.
+    List(ListAlertsArgs),
+
+    /// Manage alert rules.
+    #[command(subcommand)]
+    Rules(RulesSubcommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum RulesSubcommand {
+    /// List alert rules.
+    List,
+
+    /// Get an alert rule.
+    Get(RuleIdArgs),
+
+    /// Create an alert rule.
+    ///
+    /// Use the global --app / --env flags to scope an env condition to one
+    /// app or env; without them the rule covers every env in the org.
+    Create(RuleArgs),
+
+    /// Replace an alert rule. Open alerts for the rule are cleared.
+    Update(UpdateRuleArgs),
+
+    /// Delete an alert rule.
+    Delete(RuleIdArgs),
+}
+
+#[derive(Debug, Args)]
+struct ListAlertsArgs {
+    /// Only show alerts in this state: pending or firing.
+    #[arg(long)]
+    state: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct RuleIdArgs {
+    /// Alert rule ID.
+    rule: String,
+}
+
+#[derive(Debug, Args)]
+struct RuleArgs {
+    /// Rule name (unique within the org).
+    #[arg(long)]
+    name: String,
+
+    /// Condition: env_unhealthy, instance_restarts, slo_burn_rate,
+    /// node_offline, or projection_lag (the last two are operator-only).
+    #[arg(long)]
+    condition: String,
+
+    /// Threshold for instance_restarts (failures), slo_burn_rate (burn rate
+    /// multiple), or projection_lag (events).
+    #[arg(long)]
+    threshold: Option<i64>,
+
+    /// Window for instance_restarts (default 900) or slo_burn_rate (default
+    /// 3600), in seconds.
+    #[arg(long)]
+    window_seconds: Option<i64>,
+
+    /// Seconds the condition must hold before the alert fires.
+    #[arg(long)]
+    for_seconds: Option<i64>,
+
+    /// Create the rule disabled.
+    #[arg(long, default_value_t = false)]
+    disabled: bool,
+}
+
+#[derive(Debug, Args)]
+struct UpdateRuleArgs {
+    /// Alert rule ID.
+    rule: String,
+
+    #[command(flatten)]
+    spec: RuleArgs,
+}
+
+impl AlertsCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            AlertsSubcommand::List(args) => list_alerts(ctx, args).await,
+            AlertsSubcommand::Rules(RulesSubcommand::List) => list_rules(ctx).await,
+            AlertsSubcommand::Rules(RulesSubcommand::Get(args)) => get_rule(ctx, args).await,
+            AlertsSubcommand::Rules(RulesSubcommand::Create(args)) => create_rule(ctx, args).await,
+            AlertsSubcommand::Rules(RulesSubcommand::Update(args)) => update_rule(ctx, args).await,
+            AlertsSubcommand::Rules(RulesSubcommand::Delete(args)) => delete_rule(ctx, args).await,
+        }
+    }
+}
+
+/// Table row for an open alert.
+#[derive(Debug, Serialize, Tabled)]
+struct AlertRow {
+    #[tabled(rename = "Rule")]
+    rule_name: String,
+
+    #[tabled(rename = "Condition")]
+    condition: String,
+
+    #[tabled(rename = "Target")]
+    target: String,
+
+    #[tabled(rename = "State")]
+    state: String,
+
+    #[tabled(rename = "Summary")]
+    summary: String,
+
+    #[tabled(rename = "Since")]
+    since: String,
+}
+
+impl From<Alert> for AlertRow {
+    fn from(alert: Alert) -> Self {
+        Self {
+            rule_name: alert.rule_name,
+            condition: alert.condition,
+            target: alert.target,
+            state: alert.state,
+            summary: alert.summary,
+            since: alert.fired_at.unwrap_or(alert.pending_since),
+        }
+    }
+}
+
+/// Table row for an alert rule.
+#[derive(Debug, Serialize, Tabled)]
+struct AlertRuleRow {
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Name")]
+    name: String,
+
+    #[tabled(rename = "Condition")]
+    condition: String,
+
+    #[tabled(rename = "For")]
+    for_seconds: String,
+
+    #[tabled(rename = "Scope")]
+    scope: String,
+
+    #[tabled(rename = "Enabled")]
+    enabled: bool,
+}
+
+impl From<AlertRule> for AlertRuleRow {
+    fn from(rule: AlertRule) -> Self {
+        let scope = match (rule.app_id, rule.env_id) {
+            (_, Some(env_id)) => env_id,
+            (Some(app_id), None) => app_id,
+            (None, None) => "-".to_string(),
+        };
+        Self {
+            id: rule.id,
+            name: rule.name,
+            condition: describe_condition(&rule.condition),
+            for_seconds: format!("{}s", rule.for_seconds),
+            scope,
+            enabled: rule.enabled,
+        }
+    }
+}
+
+/// Condition type plus its parameters, e.g. `instance_restarts >= 5 / 900s`.
+fn describe_condition(condition: &AlertCondition) -> String {
+    let kind = condition["type"].as_str().unwrap_or("-");
+    match (
+        condition["threshold"].as_i64(),
+        condition["window_seconds"].as_i64(),
+    ) {
+        (Some(threshold), Some(window)) => format!("{kind} >= {threshold} / {window}s"),
+        (Some(threshold), None) => format!("{kind} > {threshold}"),
+        _ => kind.to_string(),
+    }
+}
+
+/// Build the tagged condition object from flags. Parameter validation is left
+/// to the API, which knows which conditions take which parameters.
+fn condition_from_args(args: &RuleArgs) -> AlertCondition {
+    let mut condition = serde_json::json!({ "type": args.condition });
+    if let Some(threshold) = args.threshold {
+        condition["threshold"] = threshold.into();
+    }
+    if let Some(window_seconds) = args.window_seconds {
+        condition["window_seconds"] = window_seconds.into();
+    }
+    condition
+}
+
+/// Build a rule request, scoping it to the --app / --env flags when given.
+async fn rule_request(
+    ctx: &CommandContext,
+    client: &ApiClient,
+    org_id: plfm_id::OrgId,
+    args: &RuleArgs,
+) -> Result<AlertRuleRequest> {
+    let mut app_id = None;
+    let mut env_id = None;
+    if let Some(app) = ctx.app.as_deref() {
+        let resolved = crate::resolve::resolve_app_id(client, org_id, app).await?;
+        if let Some(env) = ctx.env.as_deref() {
+            let env = crate::resolve::resolve_env_id(client, org_id, resolved, env).await?;
+            env_id = Some(env.to_string());
+        }
+        app_id = Some(resolved.to_string());
+    } else if ctx.env.is_some() {
+        anyhow::bail!("--env requires --app when scoping an alert rule");
+    }
+
+    Ok(AlertRuleRequest {
+        name: args.name.clone(),
+        condition: condition_from_args(args),
+        for_seconds: args.for_seconds,
+        app_id,
+        env_id,
+        enabled: Some(!args.disabled),
+    })
+}
+
+fn rule_not_found(rule: &str) -> impl FnOnce(CliError) -> CliError + '_ {
+    move |e| match e {
+        CliError::Api { status: 404, .. } => {
+            CliError::NotFound(format!("Alert rule '{rule}' not found"))
+        }
+        other => other,
+    }
+}
+
+async fn list_alerts(ctx: CommandContext, args: ListAlertsArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let mut path = format!("/v1/orgs/{org_id}/alerts");
+    if let Some(state) = args.state.as_deref() {
+        path.push_str(&format!("?state={state}"));
+    }
+
+    let response: ListAlertsResponse = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Table => {
+            let rows: Vec<AlertRow> = response.items.into_iter().map(AlertRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
+        OutputFormat::Json => print_single(&response, ctx.format),
+    }
+
+    Ok(())
+}
+
+async fn list_rules(ctx: CommandContext) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let response: ListAlertRulesResponse = client
+        .get(&format!("/v1/orgs/{org_id}/alerts/rules"))
+        .await?;
+
+    match ctx.format {
+        OutputFormat::Table => {
+            let rows: Vec<AlertRuleRow> =
+                response.items.into_iter().map(AlertRuleRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
+        OutputFormat::Json => print_single(&response, ctx.format),
+    }
+
+    Ok(())
+}
+
+async fn get_rule(ctx: CommandContext, args: RuleIdArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let response: AlertRule = client
+        .get(&format!("/v1/orgs/{org_id}/alerts/rules/{}", args.rule))
+        .await
+        .map_err(rule_not_found(&args.rule))?;
+
+    print_single(&response, ctx.format);
+    Ok(())
+}
+
+async fn create_rule(ctx: CommandContext, args: RuleArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let request = rule_request(&ctx, &client, org_id, &args).await?;
+    let path = format!("/v1/orgs/{org_id}/alerts/rules");
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => {
+            crate::idempotency::default_idempotency_key("alerts.rules.create", &path, &request)?
+        }
+    };
+
+    let response: AlertRule = client
+        .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let rule_id = response.id.clone();
+    let org_id_str = org_id.to_string();
+    let next = vec![
+        ReceiptNextStep {
+            label: "Next",
+            cmd: format!("vt --org {} alerts list", org_id_str.clone()),
+        },
+        ReceiptNextStep {
+            label: "Debug",
+            cmd: format!("vt events tail --org {}", org_id_str.clone()),
+        },
+    ];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!("Created alert rule '{}' ({})", response.name, rule_id),
+            status: "accepted",
+            kind: "alerts.rules.create",
+            resource_key: "alert_rule",
+            resource: &response,
+            ids: serde_json::json!({
+                "rule_id": rule_id,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+async fn update_rule(ctx: CommandContext, args: UpdateRuleArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let request = rule_request(&ctx, &client, org_id, &args.spec).await?;
+    let path = format!("/v1/orgs/{org_id}/alerts/rules/{}", args.rule);
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => {
+            crate::idempotency::default_idempotency_key("alerts.rules.update", &path, &request)?
+        }
+    };
+
+    let response: AlertRule = client
+        .put_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await
+        .map_err(rule_not_found(&args.rule))?;
+
+    let rule_id = response.id.clone();
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!(
+            "vt --org {} alerts rules get {}",
+            org_id_str.clone(),
+            rule_id
+        ),
+    }];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!("Updated alert rule '{}' ({})", response.name, rule_id),
+            status: "accepted",
+            kind: "alerts.rules.update",
+            resource_key: "alert_rule",
+            resource: &response,
+            ids: serde_json::json!({
+                "rule_id": rule_id,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+async fn delete_rule(ctx: CommandContext, args: RuleIdArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let path = format!("/v1/orgs/{org_id}/alerts/rules/{}", args.rule);
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key_no_body("alerts.rules.delete", &path),
+    };
+
+    client
+        .delete_with_idempotency_key(&path, Some(idempotency_key.as_str()))
+        .await
+        .map_err(rule_not_found(&args.rule))?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!("vt --org {} alerts rules list", org_id_str.clone()),
+    }];
+
+    print_receipt_no_resource(
+        ctx.format,
+        ReceiptNoResource {
+            message: format!("Deleted alert rule '{}'", args.rule),
+            status: "accepted",
+            kind: "alerts.rules.delete",
+            ids: serde_json::json!({
+                "rule_id": args.rule,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
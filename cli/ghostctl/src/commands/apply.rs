@@ -9,10 +9,15 @@ use std::time::Duration;
 
 use anyhow::Result;
 use clap::Args;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tokio::time::{sleep, Instant};
 
+use crate::client::models::{
+    CreateDeployRequest, CreateReleaseRequest, Deploy, ProcessScale, Release, ScaleState,
+    ScaleUpdateRequest, SecretsReload,
+};
 use crate::client::ApiClient;
+use crate::error::CliError;
 use crate::manifest::ManifestValidationError;
 use crate::output::{
     print_info, print_receipt, print_single, OutputFormat, Receipt, ReceiptNextStep,
@@ -27,7 +32,7 @@ const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(5 * 60); // 5 minutes
 const POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Terminal deploy statuses that indicate the deploy is done.
-const TERMINAL_STATUSES: &[&str] = &["succeeded", "failed"];
+const TERMINAL_STATUSES: &[&str] = &["succeeded", "failed", "halted"];
 
 /// Apply a manifest (create release + deploy).
 #[derive(Debug, Args)]
@@ -44,6 +49,13 @@ pub struct ApplyCommand {
     #[arg(long = "process-type")]
     pub process_type: Vec<String>,
 
+    #[command(flatten)]
+    pub metadata: super::releases::ReleaseMetadataArgs,
+
+    /// Why the change is being made (recorded on the deploy and any scale change).
+    #[arg(long, short = 'm')]
+    pub reason: Option<String>,
+
     /// Print the plan without making any API calls.
     #[arg(long)]
     pub dry_run: bool,
@@ -53,7 +65,7 @@ pub struct ApplyCommand {
     pub wait: bool,
 
     /// Timeout for waiting (e.g., "5m", "300s"). Default is 5 minutes.
-    #[arg(long, value_name = "DURATION")]
+    #[arg(long, visible_alias = "timeout", value_name = "DURATION")]
     pub wait_timeout: Option<String>,
 
     /// Do not wait for deploy (default behavior, explicit flag for clarity).
@@ -61,37 +73,6 @@ pub struct ApplyCommand {
     pub no_wait: bool,
 }
 
-#[derive(Debug, Serialize)]
-struct CreateReleaseRequest {
-    image_ref: String,
-    image_digest: String,
-    manifest_schema_version: i32,
-    manifest_hash: String,
-    command: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ReleaseResponse {
-    id: String,
-}
-
-#[derive(Debug, Serialize)]
-struct CreateDeployRequest {
-    release_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    process_types: Option<Vec<String>>,
-    strategy: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct DeployResponse {
-    id: String,
-    #[serde(default)]
-    status: String,
-    #[serde(default)]
-    message: Option<String>,
-}
-
 #[derive(Debug, Serialize)]
 struct ApplyPlan {
     dry_run: bool,
@@ -152,7 +133,7 @@ async fn wait_for_deploy(
     deploy_id: &str,
     timeout: Duration,
     format: OutputFormat,
-) -> Result<DeployResponse> {
+) -> Result<Deploy> {
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/deploys/{}",
         org_id, app_id, env_id, deploy_id
@@ -162,7 +143,7 @@ async fn wait_for_deploy(
     let mut last_status = String::new();
 
     loop {
-        let response: DeployResponse = client.get(&path).await?;
+        let response: Deploy = client.get(&path).await?;
 
         // Print status updates (only in table mode, and only when status changes)
         if matches!(format, OutputFormat::Table) && response.status != last_status {
@@ -174,23 +155,22 @@ async fn wait_for_deploy(
         if TERMINAL_STATUSES.contains(&response.status.as_str()) {
             if response.status == "succeeded" {
                 return Ok(response);
-            } else {
-                anyhow::bail!(
-                    "Deploy {} {}: {}",
-                    deploy_id,
-                    response.status,
-                    response.message.as_deref().unwrap_or("no details")
-                );
             }
+            return Err(CliError::DeployFailed {
+                deploy_id: deploy_id.to_string(),
+                message: response.message.unwrap_or_else(|| "no details".to_string()),
+                status: response.status,
+            }
+            .into());
         }
 
         // Check timeout
         if start.elapsed() > timeout {
-            anyhow::bail!(
+            return Err(CliError::Timeout(format!(
                 "Timeout waiting for deploy {} to finish (last status: {})",
-                deploy_id,
-                response.status
-            );
+                deploy_id, response.status
+            ))
+            .into());
         }
 
         sleep(POLL_INTERVAL).await;
@@ -201,7 +181,7 @@ impl ApplyCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         let org_ident = ctx.require_org()?;
         let app_ident = ctx.require_app()?;
-        let env_ident = require_env(&ctx)?;
+        let env_ident = ctx.require_env()?;
 
         let manifest_path = self.manifest.unwrap_or_else(|| PathBuf::from("vt.toml"));
         let contents = std::fs::read_to_string(&manifest_path).map_err(|e| {
@@ -211,7 +191,11 @@ impl ApplyCommand {
         let errors = crate::manifest::validate_manifest_toml_str(&contents)?;
         if !errors.is_empty() {
             print_manifest_errors(&errors);
-            anyhow::bail!("Manifest validation failed ({} error(s))", errors.len());
+            return Err(CliError::Validation(format!(
+                "Manifest validation failed ({} error(s))",
+                errors.len()
+            ))
+            .into());
         }
 
         let manifest_hash = crate::manifest::manifest_hash_from_toml_str(&contents)?;
@@ -229,6 +213,7 @@ impl ApplyCommand {
             .first()
             .ok_or_else(|| anyhow::anyhow!("manifest must include at least one process type"))?;
         let command = command_from_manifest(&manifest_json, primary_process)?;
+        let scale_settings = scale_settings_from_manifest(&manifest_json, &process_types);
 
         if self.dry_run {
             let plan = ApplyPlan {
@@ -266,6 +251,16 @@ impl ApplyCommand {
                     println!("- command: {}", command_list);
                     println!("- actions:");
                     println!("  - create release (schema=v1)");
+                    if !scale_settings.is_empty() {
+                        let settings_list: Vec<&str> = scale_settings
+                            .iter()
+                            .map(|s| s.process_type.as_str())
+                            .collect();
+                        println!(
+                            "  - set rollout limits / secrets reload ({})",
+                            settings_list.join(",")
+                        );
+                    }
                     println!("  - create deploy (strategy=rolling)");
                 }
             }
@@ -285,9 +280,10 @@ impl ApplyCommand {
         let release_req = CreateReleaseRequest {
             image_ref: image_ref.clone(),
             image_digest: image_digest.clone(),
-            manifest_schema_version: 1,
+            manifest_schema_version: Some(1),
             manifest_hash: manifest_hash.clone(),
             command: command.clone(),
+            metadata: self.metadata.clone().into_metadata(),
         };
         let release_idem = match ctx.idempotency_key.as_deref() {
             Some(key) => key.to_string(),
@@ -298,11 +294,44 @@ impl ApplyCommand {
             )?,
         };
 
-        let release: ReleaseResponse = client
+        let release: Release = client
             .post_with_idempotency_key(&release_path, &release_req, Some(release_idem.as_str()))
             .await?;
 
-        // 2) Create deploy for selected process types.
+        // 2) Copy manifest rollout limits and secrets reload modes to the env
+        //    scale; the scheduler reads them from there.
+        if !scale_settings.is_empty() {
+            let scale_path = format!("/v1/orgs/{}/apps/{}/envs/{}/scale", org_id, app_id, env_id);
+            let current: ScaleState = client.get(&scale_path).await?;
+            let processes: Vec<ProcessScale> = scale_settings
+                .into_iter()
+                .map(|mut settings| {
+                    if let Some(p) = current
+                        .processes
+                        .iter()
+                        .find(|p| p.process_type == settings.process_type)
+                    {
+                        settings.desired = p.desired;
+                    }
+                    settings
+                })
+                .collect();
+            let scale_req = ScaleUpdateRequest {
+                processes,
+                expected_version: Some(current.resource_version.unwrap_or(0)),
+                reason: self.reason.clone(),
+            };
+            let scale_idem = crate::idempotency::default_idempotency_key(
+                "envs.set_scale",
+                &scale_path,
+                &scale_req,
+            )?;
+            let _: serde_json::Value = client
+                .put_with_idempotency_key(&scale_path, &scale_req, Some(scale_idem.as_str()))
+                .await?;
+        }
+
+        // 3) Create deploy for selected process types.
         let deploy_path = format!(
             "/v1/orgs/{}/apps/{}/envs/{}/deploys",
             org_id, app_id, env_id
@@ -310,7 +339,8 @@ impl ApplyCommand {
         let deploy_req = CreateDeployRequest {
             release_id: release.id.clone(),
             process_types: Some(process_types.clone()),
-            strategy: "rolling".to_string(),
+            strategy: Some("rolling".to_string()),
+            reason: self.reason.clone(),
         };
         let deploy_idem = match ctx.idempotency_key.as_deref() {
             Some(key) => key.to_string(),
@@ -321,7 +351,7 @@ impl ApplyCommand {
             )?,
         };
 
-        let deploy: DeployResponse = client
+        let deploy: Deploy = client
             .post_with_idempotency_key(&deploy_path, &deploy_req, Some(deploy_idem.as_str()))
             .await?;
 
@@ -447,12 +477,6 @@ impl ApplyCommand {
     }
 }
 
-fn require_env(ctx: &CommandContext) -> Result<&str> {
-    ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })
-}
-
 fn print_manifest_errors(errors: &[ManifestValidationError]) {
     for err in errors {
         println!(
@@ -489,6 +513,71 @@ fn process_types_from_manifest(manifest_json: &serde_json::Value) -> Result<Vec<
     Ok(out)
 }
 
+/// Scale entries carrying the rollout limits from
+/// `[processes.<type>.rollout]` and the secrets reload mode from
+/// `[processes.<type>.secrets]` for the selected process types. `desired`
+/// defaults to 1 (the scheduler's default when no scale is set) and is
+/// replaced by the env's current scale before sending.
+fn scale_settings_from_manifest(
+    manifest_json: &serde_json::Value,
+    process_types: &[String],
+) -> Vec<ProcessScale> {
+    let Some(processes) = manifest_json.get("processes").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    process_types
+        .iter()
+        .filter_map(|process_type| {
+            let process = processes.get(process_type)?;
+            let rollout = process.get("rollout");
+            let max_surge = rollout.and_then(|r| r.get("max_surge")).cloned();
+            let max_unavailable = rollout.and_then(|r| r.get("max_unavailable")).cloned();
+            let secrets_reload = process
+                .get("secrets")
+                .and_then(secrets_reload_from_manifest);
+            if max_surge.is_none() && max_unavailable.is_none() && secrets_reload.is_none() {
+                return None;
+            }
+            Some(ProcessScale {
+                process_type: process_type.clone(),
+                desired: 1,
+                max_surge,
+                max_unavailable,
+                secrets_reload,
+                ..ProcessScale::default()
+            })
+        })
+        .collect()
+}
+
+/// The reload mode of a `[processes.<type>.secrets]` table; `None` when
+/// `reload` is not set, which keeps the env's current mode.
+fn secrets_reload_from_manifest(secrets: &serde_json::Value) -> Option<SecretsReload> {
+    let mode = secrets.get("reload")?.as_str()?;
+    let mut reload = SecretsReload {
+        mode: mode.to_string(),
+        ..SecretsReload::default()
+    };
+    match mode {
+        "signal" => {
+            reload.signal = secrets
+                .get("reload_signal")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+        }
+        "exec" => {
+            reload.command = secrets
+                .get("reload_command")
+                .and_then(|v| serde_json::from_value(v.clone()).ok());
+            reload.timeout_seconds = secrets
+                .get("reload_timeout_seconds")
+                .and_then(|v| v.as_i64());
+        }
+        _ => {}
+    }
+    Some(reload)
+}
+
 fn default_command() -> Vec<String> {
     vec!["./start".to_string()]
 }
@@ -568,3 +657,67 @@ fn digest_from_image_ref(image_ref: &str) -> Result<String> {
     };
     normalize_image_digest(digest)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_settings_from_manifest_reads_selected_process_types() {
+        let manifest = serde_json::json!({
+            "processes": {
+                "web": { "rollout": { "max_surge": "25%", "max_unavailable": 0 } },
+                "worker": { "rollout": { "max_unavailable": 1 } },
+                "cron": {}
+            }
+        });
+        let selected = vec!["cron".to_string(), "web".to_string()];
+        assert_eq!(
+            scale_settings_from_manifest(&manifest, &selected),
+            vec![ProcessScale {
+                process_type: "web".to_string(),
+                desired: 1,
+                max_surge: Some(serde_json::json!("25%")),
+                max_unavailable: Some(serde_json::json!(0)),
+                ..ProcessScale::default()
+            }]
+        );
+
+        let worker = scale_settings_from_manifest(&manifest, &["worker".to_string()]);
+        assert_eq!(
+            serde_json::to_value(&worker[0]).unwrap(),
+            serde_json::json!({ "process_type": "worker", "desired": 1, "max_unavailable": 1 })
+        );
+    }
+
+    #[test]
+    fn scale_settings_from_manifest_reads_secrets_reload() {
+        let manifest = serde_json::json!({
+            "processes": {
+                "web": { "secrets": { "required": true, "reload": "signal", "reload_signal": "SIGUSR1" } },
+                "worker": { "secrets": {
+                    "reload": "exec",
+                    "reload_command": ["/app/bin/reload"],
+                    "reload_timeout_seconds": 10
+                } },
+                "cron": { "secrets": { "required": true } }
+            }
+        });
+        let selected = vec!["cron".to_string(), "web".to_string(), "worker".to_string()];
+        let settings = scale_settings_from_manifest(&manifest, &selected);
+        assert_eq!(settings.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&settings[0].secrets_reload).unwrap(),
+            serde_json::json!({ "mode": "signal", "signal": "SIGUSR1" })
+        );
+        assert_eq!(
+            serde_json::to_value(&settings[1].secrets_reload).unwrap(),
+            serde_json::json!({
+                "mode": "exec",
+                "command": ["/app/bin/reload"],
+                "timeout_seconds": 10
+            })
+        );
+        assert_eq!(settings[1].max_surge, None);
+    }
+}
@@ -1,8 +1,10 @@
 //! Application commands.
 
 use anyhow::Result;
+use chrono::SecondsFormat;
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use plfm_api_types::apps::{AppResponse, CreateAppRequest, ListAppsResponse, UpdateAppRequest};
+use serde::Serialize;
 use tabled::Tabled;
 
 use crate::error::CliError;
@@ -94,9 +96,9 @@ impl AppsCommand {
     }
 }
 
-/// Application response from API.
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct AppResponse {
+/// Table row for an application.
+#[derive(Debug, Serialize, Tabled)]
+struct AppRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -107,13 +109,24 @@ struct AppResponse {
     name: String,
 
     #[tabled(rename = "Description", display = "display_option")]
-    #[serde(default)]
     description: Option<String>,
 
     #[tabled(rename = "Created")]
     created_at: String,
 }
 
+impl From<AppResponse> for AppRow {
+    fn from(app: AppResponse) -> Self {
+        Self {
+            id: app.id,
+            org_id: app.org_id,
+            name: app.name,
+            description: app.description,
+            created_at: app.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+}
+
 const APP_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.App";
 const LIST_APPS_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.ListAppsResponse";
 
@@ -121,30 +134,6 @@ fn display_option(opt: &Option<String>) -> String {
     opt.as_deref().unwrap_or("-").to_string()
 }
 
-/// List response from API.
-#[derive(Debug, Serialize, Deserialize)]
-struct ListAppsResponse {
-    items: Vec<AppResponse>,
-    next_cursor: Option<String>,
-}
-
-/// Create app request.
-#[derive(Debug, Serialize)]
-struct CreateAppRequest {
-    name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-struct UpdateAppRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    expected_version: i32,
-}
-
 /// List all applications in the current org.
 async fn list_apps(ctx: CommandContext, args: ListAppsArgs) -> Result<()> {
     let client = ctx.client()?;
@@ -158,7 +147,10 @@ async fn list_apps(ctx: CommandContext, args: ListAppsArgs) -> Result<()> {
     let response: ListAppsResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<AppRow> = response.items.into_iter().map(AppRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_proto_single(&response, ctx.format, LIST_APPS_TYPE_URL),
     }
     Ok(())
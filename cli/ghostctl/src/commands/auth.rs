@@ -6,9 +6,11 @@ use anyhow::Result;
 use chrono::{Duration as ChronoDuration, Utc};
 use clap::{Args, Subcommand};
 use colored::Colorize;
+use plfm_proto::errors::codes;
 use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 
+use crate::client::models::{DeviceStartResponse, DeviceTokenRequest, WhoAmIResponse};
 use crate::config::Credentials;
 use crate::error::CliError;
 use crate::output::{print_info, print_success};
@@ -44,44 +46,16 @@ struct LoginArgs {
     token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct OrgMembership {
-    org_id: String,
-    role: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct WhoAmIResponse {
-    subject_type: String,
-    subject_id: String,
-    #[serde(default)]
-    display_name: Option<String>,
-    org_memberships: Vec<OrgMembership>,
-    scopes: Vec<String>,
-}
-
+/// Hand-written rather than `models::DeviceStartRequest`: the server reads
+/// `device_name`, which the spec documents as `client_name`.
 #[derive(Debug, Serialize)]
 struct DeviceStartRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     device_name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DeviceStartResponse {
-    device_code: String,
-    user_code: String,
-    verification_uri: String,
-    #[serde(default)]
-    verification_uri_complete: Option<String>,
-    expires_in_seconds: i64,
-    poll_interval_seconds: u32,
-}
-
-#[derive(Debug, Serialize)]
-struct DeviceTokenRequest {
-    device_code: String,
-}
-
+/// Hand-written rather than `models::DeviceTokenResponse`: the server sends
+/// `token_type` and may omit `refresh_token`, unlike the spec.
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 struct TokenResponse {
@@ -171,11 +145,11 @@ async fn device_login(ctx: &CommandContext) -> Result<TokenResponse> {
         match token_result {
             Ok(token) => return Ok(token),
             Err(CliError::Api { code, message, .. }) => match code.as_str() {
-                "authorization_pending" => {}
-                "slow_down" => {
+                codes::AUTHORIZATION_PENDING => {}
+                codes::SLOW_DOWN => {
                     poll_interval = poll_interval.saturating_add(5);
                 }
-                "access_denied" | "expired_token" | "invalid_grant" => {
+                codes::ACCESS_DENIED | codes::EXPIRED_TOKEN | codes::INVALID_GRANT => {
                     return Err(anyhow::anyhow!("Login failed: {message}"));
                 }
                 _ => {
@@ -0,0 +1,191 @@
+//! Config vars commands.
+//!
+//! Config vars are non-secret, env-scoped environment variables. Unlike
+//! secrets they are stored in plaintext and printed as-is. Every change
+//! rolls the env's instances, unless their process type reloads in place.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::client::models::ConfigVars;
+use crate::error::CliError;
+use crate::output::{
+    print_output, print_receipt, print_single, OutputFormat, Receipt, ReceiptNextStep,
+};
+
+use super::CommandContext;
+
+/// Config vars commands.
+#[derive(Debug, Args)]
+pub struct ConfigVarsCommand {
+    #[command(subcommand)]
+    command: ConfigVarsSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigVarsSubcommand {
+    /// List config vars for the current environment.
+    #[command(alias = "ls")]
+    List,
+
+    /// Set config vars: KEY=VALUE (repeatable). Other vars are kept.
+    Set(SetConfigVarsArgs),
+
+    /// Remove config vars by key (repeatable).
+    Unset(UnsetConfigVarsArgs),
+}
+
+#[derive(Debug, Args)]
+struct SetConfigVarsArgs {
+    /// Vars to set, as KEY=VALUE.
+    #[arg(required = true, value_name = "KEY=VALUE")]
+    vars: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct UnsetConfigVarsArgs {
+    /// Keys to remove.
+    #[arg(required = true, value_name = "KEY")]
+    keys: Vec<String>,
+}
+
+/// Body of `PATCH .../config-vars`; `None` removes the key.
+#[derive(Debug, Serialize)]
+struct PatchConfigVarsRequest {
+    vars: BTreeMap<String, Option<String>>,
+}
+
+/// Table row for a config var.
+#[derive(Debug, Serialize, Tabled)]
+struct ConfigVarRow {
+    #[tabled(rename = "Key")]
+    key: String,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+impl ConfigVarsCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            ConfigVarsSubcommand::List => list_config_vars(ctx).await,
+            ConfigVarsSubcommand::Set(args) => {
+                let vars = parse_assignments(&args.vars)?;
+                patch_config_vars(ctx, vars, "config_vars.set").await
+            }
+            ConfigVarsSubcommand::Unset(args) => {
+                let vars = args.keys.into_iter().map(|key| (key, None)).collect();
+                patch_config_vars(ctx, vars, "config_vars.unset").await
+            }
+        }
+    }
+}
+
+/// Parse `KEY=VALUE` arguments; the value may itself contain `=`.
+fn parse_assignments(args: &[String]) -> Result<BTreeMap<String, Option<String>>> {
+    let mut vars = BTreeMap::new();
+    for arg in args {
+        let Some((key, value)) = arg.split_once('=') else {
+            return Err(CliError::Validation(format!(
+                "Invalid config var '{arg}'. Use format KEY=VALUE"
+            ))
+            .into());
+        };
+        vars.insert(key.to_string(), Some(value.to_string()));
+    }
+    Ok(vars)
+}
+
+async fn list_config_vars(ctx: CommandContext) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
+    let env_id =
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
+
+    let path = format!("/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars");
+    let response: ConfigVars = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Json => print_single(&response, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<ConfigVarRow> = response
+                .vars
+                .into_iter()
+                .map(|(key, value)| ConfigVarRow { key, value })
+                .collect();
+            print_output(&rows, ctx.format);
+        }
+    }
+
+    Ok(())
+}
+
+async fn patch_config_vars(
+    ctx: CommandContext,
+    vars: BTreeMap<String, Option<String>>,
+    kind: &'static str,
+) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
+    let env_id =
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
+
+    let path = format!("/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars");
+    let request = PatchConfigVarsRequest { vars };
+
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key("config_vars.patch", &path, &request)?,
+    };
+
+    let response: ConfigVars = client
+        .patch_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let org_id_str = org_id.to_string();
+    let app_id_str = app_id.to_string();
+    let env_id_str = env_id.to_string();
+    let next = vec![
+        ReceiptNextStep {
+            label: "Next",
+            cmd: format!(
+                "vt --org {} --app {} --env {} status",
+                org_id_str, app_id_str, env_id_str
+            ),
+        },
+        ReceiptNextStep {
+            label: "Debug",
+            cmd: format!(
+                "vt events tail --org {} --app {} --env {}",
+                org_id_str, app_id_str, env_id_str
+            ),
+        },
+    ];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Updated config vars for {}/{}/{} (version {}); instances will be replaced",
+                org_id_str, app_id_str, env_id_str, response.resource_version
+            ),
+            status: "accepted",
+            kind,
+            resource_key: "config_vars",
+            resource: &response,
+            ids: serde_json::json!({
+                "org_id": org_id_str,
+                "app_id": app_id_str,
+                "env_id": env_id_str,
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
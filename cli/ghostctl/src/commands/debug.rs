@@ -5,7 +5,7 @@ use prost_reflect::prost::Message;
 use prost_reflect::{
     DescriptorPool, DeserializeOptions, DynamicMessage, MessageDescriptor, MethodDescriptor, Value,
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::io::{BufRead, BufReader};
 use std::str::FromStr;
 use tokio_stream::StreamExt;
@@ -16,7 +16,8 @@ use tonic::transport::Endpoint;
 use tonic::{Request, Status};
 
 use super::CommandContext;
-use crate::output::print_single;
+use crate::client::models::{Event, EventsResponse};
+use crate::output::{print_info, print_single, OutputFormat};
 use crate::resolve;
 
 #[derive(Debug, Args)]
@@ -29,6 +30,8 @@ pub struct DebugCommand {
 enum DebugSubcommand {
     DecodeEvent(DecodeEventArgs),
     GrpcCall(GrpcCallArgs),
+    /// Show the most recent failed API request (method, URL, status, request ID).
+    LastRequest,
 }
 
 #[derive(Debug, Args)]
@@ -64,33 +67,10 @@ struct GrpcCallArgs {
     insecure: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct EventRecord {
-    event_id: i64,
-    occurred_at: String,
-    event_type: String,
-    #[serde(default)]
-    aggregate_type: Option<String>,
-    #[serde(default)]
-    aggregate_id: Option<String>,
-    #[serde(default)]
-    aggregate_seq: Option<i64>,
-    #[serde(default)]
-    actor_id: Option<String>,
-    #[serde(default)]
-    payload: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct EventsResponse {
-    items: Vec<EventRecord>,
-    next_after_event_id: i64,
-}
-
 #[derive(Debug, Serialize)]
 struct DecodeEventHttpOutput {
     source: &'static str,
-    event: EventRecord,
+    event: Event,
 }
 
 #[derive(Debug, Serialize)]
@@ -183,8 +163,39 @@ impl DebugCommand {
         match self.command {
             DebugSubcommand::DecodeEvent(args) => decode_event(ctx, args).await,
             DebugSubcommand::GrpcCall(args) => grpc_call(ctx, args).await,
+            DebugSubcommand::LastRequest => last_request(ctx),
+        }
+    }
+}
+
+fn last_request(ctx: CommandContext) -> Result<()> {
+    let Some(last) = crate::http_trace::load_last_request()? else {
+        match ctx.format {
+            OutputFormat::Json => print_single(&serde_json::Value::Null, ctx.format),
+            OutputFormat::Table => print_info("No failed request recorded."),
+        }
+        return Ok(());
+    };
+
+    match ctx.format {
+        OutputFormat::Json => print_single(&last, ctx.format),
+        OutputFormat::Table => {
+            println!("At:          {}", last.at.to_rfc3339());
+            println!("Request:     {} {}", last.method, last.url);
+            println!(
+                "Status:      {}",
+                last.status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "no response".to_string())
+            );
+            println!("Duration:    {}ms", last.duration_ms);
+            println!("Request ID:  {}", last.request_id.as_deref().unwrap_or("-"));
+            println!("Error code:  {}", last.error_code.as_deref().unwrap_or("-"));
+            println!("Message:     {}", last.message);
+            println!("CLI version: {}", last.cli_version);
         }
     }
+    Ok(())
 }
 
 async fn decode_event(ctx: CommandContext, args: DecodeEventArgs) -> Result<()> {
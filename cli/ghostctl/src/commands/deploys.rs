@@ -4,10 +4,11 @@ use std::time::Duration;
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tabled::Tabled;
 use tokio::time::{sleep, Instant};
 
+use crate::client::models::{CreateDeployRequest, Deploy, ListDeploysResponse, RollbackRequest};
 use crate::client::ApiClient;
 use crate::error::CliError;
 use crate::output::{
@@ -42,6 +43,15 @@ enum DeploysSubcommand {
 
     /// Get deploy details.
     Get(GetDeployArgs),
+
+    /// Pause an in-progress rollout.
+    Pause(RolloutControlArgs),
+
+    /// Resume a paused rollout.
+    Resume(RolloutControlArgs),
+
+    /// Promote a rollout to 100% (resumes it if paused).
+    Promote(RolloutControlArgs),
 }
 
 #[derive(Debug, Args)]
@@ -68,12 +78,16 @@ struct CreateDeployArgs {
     #[arg(long, default_value = "rolling")]
     strategy: String,
 
+    /// Why the deploy is being made (recorded in the deploy history and events).
+    #[arg(long, short = 'm')]
+    reason: Option<String>,
+
     /// Wait for deploy to complete before returning.
     #[arg(long)]
     wait: bool,
 
     /// Timeout for waiting (e.g., "5m", "300s"). Default is 5 minutes.
-    #[arg(long, value_name = "DURATION")]
+    #[arg(long, visible_alias = "timeout", value_name = "DURATION")]
     wait_timeout: Option<String>,
 
     /// Do not wait for deploy (default behavior, explicit flag for clarity).
@@ -86,12 +100,16 @@ struct RollbackArgs {
     /// Release ID to roll back to.
     release: String,
 
+    /// Why the rollback is being made (recorded in the deploy history and events).
+    #[arg(long, short = 'm')]
+    reason: Option<String>,
+
     /// Wait for rollback to complete before returning.
     #[arg(long)]
     wait: bool,
 
     /// Timeout for waiting (e.g., "5m", "300s"). Default is 5 minutes.
-    #[arg(long, value_name = "DURATION")]
+    #[arg(long, visible_alias = "timeout", value_name = "DURATION")]
     wait_timeout: Option<String>,
 
     /// Do not wait for rollback (default behavior, explicit flag for clarity).
@@ -105,6 +123,12 @@ struct GetDeployArgs {
     deploy: String,
 }
 
+#[derive(Debug, Args)]
+struct RolloutControlArgs {
+    /// Deploy ID.
+    deploy: String,
+}
+
 impl DeploysCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         match self.command {
@@ -112,13 +136,16 @@ impl DeploysCommand {
             DeploysSubcommand::Create(args) => create_deploy(ctx, args).await,
             DeploysSubcommand::Rollback(args) => rollback(ctx, args).await,
             DeploysSubcommand::Get(args) => get_deploy(ctx, args).await,
+            DeploysSubcommand::Pause(args) => control_rollout(ctx, args, "pause").await,
+            DeploysSubcommand::Resume(args) => control_rollout(ctx, args, "resume").await,
+            DeploysSubcommand::Promote(args) => control_rollout(ctx, args, "promote").await,
         }
     }
 }
 
-/// Deploy response from API.
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct DeployResponse {
+/// Table row for a deploy.
+#[derive(Debug, Serialize, Tabled)]
+struct DeployRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -144,11 +171,16 @@ struct DeployResponse {
     status: String,
 
     #[tabled(rename = "Message", display = "display_option")]
-    #[serde(default)]
     message: Option<String>,
 
+    #[tabled(rename = "Reason", display = "display_option")]
+    reason: Option<String>,
+
+    #[tabled(rename = "Promoted")]
+    promoted: bool,
+
     #[tabled(rename = "Ver")]
-    resource_version: i32,
+    resource_version: i64,
 
     #[tabled(rename = "Created")]
     created_at: String,
@@ -157,6 +189,27 @@ struct DeployResponse {
     updated_at: String,
 }
 
+impl From<Deploy> for DeployRow {
+    fn from(deploy: Deploy) -> Self {
+        Self {
+            id: deploy.id,
+            org_id: deploy.org_id,
+            app_id: deploy.app_id,
+            env_id: deploy.env_id,
+            kind: deploy.kind,
+            release_id: deploy.release_id,
+            process_types: deploy.process_types,
+            status: deploy.status,
+            message: deploy.message,
+            reason: deploy.reason,
+            promoted: deploy.promoted.unwrap_or(false),
+            resource_version: deploy.resource_version,
+            created_at: deploy.created_at,
+            updated_at: deploy.updated_at,
+        }
+    }
+}
+
 fn display_option(opt: &Option<String>) -> String {
     opt.as_deref().unwrap_or("-").to_string()
 }
@@ -169,33 +222,11 @@ fn display_process_types(process_types: &[String]) -> String {
     }
 }
 
-/// List response from API.
-#[derive(Debug, Serialize, Deserialize)]
-struct ListDeploysResponse {
-    items: Vec<DeployResponse>,
-    next_cursor: Option<String>,
-}
-
-/// Create deploy request.
-#[derive(Debug, Serialize)]
-struct CreateDeployRequest {
-    release_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    process_types: Option<Vec<String>>,
-    strategy: String,
-}
-
-/// Rollback request.
-#[derive(Debug, Serialize)]
-struct RollbackRequest {
-    release_id: String,
-}
-
 /// Terminal deploy statuses that indicate the deploy is done.
-const TERMINAL_STATUSES: &[&str] = &["completed", "failed", "cancelled"];
+const TERMINAL_STATUSES: &[&str] = &["completed", "failed", "cancelled", "halted"];
 
 /// Parse a duration string like "5m", "300s", "2h" into a Duration.
-fn parse_duration(s: &str) -> Result<Duration> {
+pub(super) fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim();
     if s.is_empty() {
         anyhow::bail!("duration cannot be empty");
@@ -231,7 +262,7 @@ async fn wait_for_deploy(
     deploy_id: &str,
     timeout: Duration,
     format: OutputFormat,
-) -> Result<DeployResponse> {
+) -> Result<Deploy> {
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/deploys/{}",
         org_id, app_id, env_id, deploy_id
@@ -241,7 +272,7 @@ async fn wait_for_deploy(
     let mut last_status = String::new();
 
     loop {
-        let response: DeployResponse = client.get(&path).await?;
+        let response: Deploy = client.get(&path).await?;
 
         // Print status updates (only in table mode, and only when status changes)
         if matches!(format, OutputFormat::Table) && response.status != last_status {
@@ -253,41 +284,38 @@ async fn wait_for_deploy(
         if TERMINAL_STATUSES.contains(&response.status.as_str()) {
             if response.status == "completed" {
                 return Ok(response);
-            } else {
-                anyhow::bail!(
-                    "Deploy {} {}: {}",
-                    deploy_id,
-                    response.status,
-                    response.message.as_deref().unwrap_or("no details")
-                );
             }
+            let details = response.message.as_deref().unwrap_or("no details");
+            let message = match response.failed_reason.as_deref() {
+                Some(reason) => format!("{details} ({reason})"),
+                None => details.to_string(),
+            };
+            return Err(CliError::DeployFailed {
+                deploy_id: deploy_id.to_string(),
+                status: response.status,
+                message,
+            }
+            .into());
         }
 
         // Check timeout
         if start.elapsed() > timeout {
-            anyhow::bail!(
+            return Err(CliError::Timeout(format!(
                 "Timeout waiting for deploy {} to complete (last status: {})",
-                deploy_id,
-                response.status
-            );
+                deploy_id, response.status
+            ))
+            .into());
         }
 
         sleep(POLL_INTERVAL).await;
     }
 }
 
-/// Require an env to be specified.
-fn require_env(ctx: &CommandContext) -> Result<&str> {
-    ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })
-}
-
 /// List all deploys for the current env.
 async fn list_deploys(ctx: CommandContext, args: ListDeploysArgs) -> Result<()> {
     let org = ctx.require_org()?;
     let app = ctx.require_app()?;
-    let env = require_env(&ctx)?;
+    let env = ctx.require_env()?;
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, org).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, app).await?;
@@ -304,7 +332,10 @@ async fn list_deploys(ctx: CommandContext, args: ListDeploysArgs) -> Result<()>
     let response: ListDeploysResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<DeployRow> = response.items.into_iter().map(DeployRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_single(&response, ctx.format),
     }
     Ok(())
@@ -314,7 +345,7 @@ async fn list_deploys(ctx: CommandContext, args: ListDeploysArgs) -> Result<()>
 async fn create_deploy(ctx: CommandContext, args: CreateDeployArgs) -> Result<()> {
     let org = ctx.require_org()?;
     let app = ctx.require_app()?;
-    let env = require_env(&ctx)?;
+    let env = ctx.require_env()?;
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, org).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, app).await?;
@@ -335,7 +366,8 @@ async fn create_deploy(ctx: CommandContext, args: CreateDeployArgs) -> Result<()
         } else {
             Some(args.process_type)
         },
-        strategy: args.strategy,
+        strategy: Some(args.strategy),
+        reason: args.reason,
     };
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/deploys",
@@ -346,7 +378,7 @@ async fn create_deploy(ctx: CommandContext, args: CreateDeployArgs) -> Result<()
         None => crate::idempotency::default_idempotency_key("deploys.create", &path, &request)?,
     };
 
-    let response: DeployResponse = client
+    let response: Deploy = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -450,7 +482,7 @@ async fn create_deploy(ctx: CommandContext, args: CreateDeployArgs) -> Result<()
 async fn rollback(ctx: CommandContext, args: RollbackArgs) -> Result<()> {
     let org = ctx.require_org()?;
     let app = ctx.require_app()?;
-    let env = require_env(&ctx)?;
+    let env = ctx.require_env()?;
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, org).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, app).await?;
@@ -466,6 +498,7 @@ async fn rollback(ctx: CommandContext, args: RollbackArgs) -> Result<()> {
 
     let request = RollbackRequest {
         release_id: args.release.clone(),
+        reason: args.reason,
     };
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/rollbacks",
@@ -476,7 +509,7 @@ async fn rollback(ctx: CommandContext, args: RollbackArgs) -> Result<()> {
         None => crate::idempotency::default_idempotency_key("rollbacks.create", &path, &request)?,
     };
 
-    let response: DeployResponse = client
+    let response: Deploy = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -580,13 +613,13 @@ async fn rollback(ctx: CommandContext, args: RollbackArgs) -> Result<()> {
 async fn get_deploy(ctx: CommandContext, args: GetDeployArgs) -> Result<()> {
     let org = ctx.require_org()?;
     let app = ctx.require_app()?;
-    let env = require_env(&ctx)?;
+    let env = ctx.require_env()?;
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, org).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, app).await?;
     let env_id = crate::resolve::resolve_env_id(&client, org_id, app_id, env).await?;
 
-    let response: DeployResponse = client
+    let response: Deploy = client
         .get(&format!(
             "/v1/orgs/{}/apps/{}/envs/{}/deploys/{}",
             org_id, app_id, env_id, args.deploy
@@ -602,3 +635,75 @@ async fn get_deploy(ctx: CommandContext, args: GetDeployArgs) -> Result<()> {
     print_single(&response, ctx.format);
     Ok(())
 }
+
+/// Pause, resume, or promote a deploy's rollout.
+async fn control_rollout(
+    ctx: CommandContext,
+    args: RolloutControlArgs,
+    action: &'static str,
+) -> Result<()> {
+    let org = ctx.require_org()?;
+    let app = ctx.require_app()?;
+    let env = ctx.require_env()?;
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, org).await?;
+    let app_id = crate::resolve::resolve_app_id(&client, org_id, app).await?;
+    let env_id = crate::resolve::resolve_env_id(&client, org_id, app_id, env).await?;
+
+    let path = format!(
+        "/v1/orgs/{}/apps/{}/envs/{}/deploys/{}/{}",
+        org_id, app_id, env_id, args.deploy, action
+    );
+    let kind = format!("deploys.{action}");
+    let request = serde_json::json!({});
+
+    // No derived default key: pause/resume are repeatable over a deploy's life,
+    // and the server already treats a repeated action as a no-op.
+    let response: Deploy = client
+        .post_with_idempotency_key(&path, &request, ctx.idempotency_key.as_deref())
+        .await
+        .map_err(|e| match e {
+            CliError::Api { status: 404, .. } => {
+                CliError::NotFound(format!("Deploy '{}' not found", args.deploy))
+            }
+            other => other,
+        })?;
+
+    let deploy_id = response.id.clone();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!(
+            "vt --org {} --app {} --env {} deploys get {}",
+            org_id, app_id, env_id, deploy_id
+        ),
+    }];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Deploy {} is {}{}",
+                deploy_id,
+                response.status,
+                if response.promoted == Some(true) {
+                    " (promoted)"
+                } else {
+                    ""
+                }
+            ),
+            status: response.status.as_str(),
+            kind: &kind,
+            resource_key: "deploy",
+            resource: &response,
+            ids: serde_json::json!({
+                "deploy_id": deploy_id,
+                "env_id": env_id.to_string(),
+                "app_id": app_id.to_string(),
+                "org_id": org_id.to_string()
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
@@ -1,14 +1,19 @@
 //! Environment commands.
 
 use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::{Args, Subcommand};
 use serde::{Deserialize, Serialize};
 use tabled::Tabled;
 
+use crate::client::models::{
+    CreateEnvRequest, Env, ListEnvsResponse, SloDay, SloObjective, SloObjectiveRequest, SloReport,
+    UpdateEnvRequest,
+};
 use crate::error::CliError;
 use crate::output::{
-    print_output, print_proto_single, print_receipt, print_single, print_success, OutputFormat,
-    Receipt, ReceiptNextStep,
+    print_info, print_output, print_proto_single, print_receipt, print_single, print_success,
+    OutputFormat, Receipt, ReceiptNextStep,
 };
 
 use super::CommandContext;
@@ -36,6 +41,18 @@ enum EnvsSubcommand {
 
     /// Set the default environment in local context.
     Use(UseEnvArgs),
+
+    /// List configuration changes (release, scale, secrets, routes).
+    History(EnvHistoryArgs),
+
+    /// Compare the configuration at two points in time.
+    Diff(EnvDiffArgs),
+
+    /// Show availability against the environment's objective (SLO).
+    Slo(EnvSloArgs),
+
+    /// Set the environment's availability objective.
+    SetSlo(SetEnvSloArgs),
 }
 
 #[derive(Debug, Args)]
@@ -47,12 +64,28 @@ struct ListEnvsArgs {
     /// Pagination cursor (opaque).
     #[arg(long)]
     cursor: Option<String>,
+
+    /// Sort key.
+    #[arg(long, value_parser = ["id", "name", "created_at", "updated_at"])]
+    order_by: Option<String>,
+
+    /// Sort descending.
+    #[arg(long)]
+    desc: bool,
 }
 
 #[derive(Debug, Args)]
 struct CreateEnvArgs {
     /// Environment name (e.g., production, staging).
     name: String,
+
+    /// Create an ephemeral preview environment, torn down when its TTL elapses.
+    #[arg(long)]
+    preview: bool,
+
+    /// Preview lifetime (e.g., 72h, 7d; default 72h, 1h-30d).
+    #[arg(long, requires = "preview", value_parser = parse_ttl)]
+    ttl: Option<i64>,
 }
 
 #[derive(Debug, Args)]
@@ -76,6 +109,58 @@ struct UseEnvArgs {
     env: String,
 }
 
+#[derive(Debug, Args)]
+struct EnvHistoryArgs {
+    /// Environment ID or name (defaults to current context).
+    env: Option<String>,
+
+    /// Only changes of this kind (release, scale, secrets, route).
+    #[arg(long)]
+    kind: Option<String>,
+
+    /// Return changes with event_id > after.
+    #[arg(long, default_value = "0")]
+    after: i64,
+
+    /// Maximum number of items to return (1-200).
+    #[arg(long, default_value = "50")]
+    limit: i64,
+}
+
+#[derive(Debug, Args)]
+struct EnvDiffArgs {
+    /// Environment ID or name (defaults to current context).
+    env: Option<String>,
+
+    /// Start of the comparison (RFC 3339).
+    #[arg(long)]
+    from: DateTime<Utc>,
+
+    /// End of the comparison (RFC 3339, defaults to now).
+    #[arg(long)]
+    to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Args)]
+struct EnvSloArgs {
+    /// Environment ID or name (defaults to current context).
+    env: Option<String>,
+
+    /// Window in days, e.g. 7d (1d-90d).
+    #[arg(long, default_value = "30d")]
+    window: String,
+}
+
+#[derive(Debug, Args)]
+struct SetEnvSloArgs {
+    /// Environment ID or name (defaults to current context).
+    env: Option<String>,
+
+    /// Availability objective in percent (50-99.999), e.g. 99.9.
+    #[arg(long)]
+    objective: f64,
+}
+
 impl EnvsCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         match self.command {
@@ -84,13 +169,17 @@ impl EnvsCommand {
             EnvsSubcommand::Update(args) => update_env(ctx, args).await,
             EnvsSubcommand::Get(args) => get_env(ctx, args).await,
             EnvsSubcommand::Use(args) => use_env(ctx, args).await,
+            EnvsSubcommand::History(args) => env_history(ctx, args).await,
+            EnvsSubcommand::Diff(args) => env_diff(ctx, args).await,
+            EnvsSubcommand::Slo(args) => env_slo(ctx, args).await,
+            EnvsSubcommand::SetSlo(args) => set_env_slo(ctx, args).await,
         }
     }
 }
 
-/// Environment response from API.
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct EnvResponse {
+/// Table row for an environment.
+#[derive(Debug, Serialize, Tabled)]
+struct EnvRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -103,32 +192,32 @@ struct EnvResponse {
     #[tabled(rename = "Name")]
     name: String,
 
-    #[tabled(rename = "Created")]
-    created_at: String,
-}
+    #[tabled(rename = "Hostname")]
+    managed_hostname: String,
 
-const ENV_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.Env";
-const LIST_ENVS_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.ListEnvsResponse";
+    #[tabled(rename = "Expires")]
+    expires_at: String,
 
-/// List response from API.
-#[derive(Debug, Serialize, Deserialize)]
-struct ListEnvsResponse {
-    items: Vec<EnvResponse>,
-    next_cursor: Option<String>,
+    #[tabled(rename = "Created")]
+    created_at: String,
 }
 
-/// Create env request.
-#[derive(Debug, Serialize)]
-struct CreateEnvRequest {
-    name: String,
+impl From<Env> for EnvRow {
+    fn from(env: Env) -> Self {
+        Self {
+            id: env.id,
+            app_id: env.app_id,
+            org_id: env.org_id,
+            name: env.name,
+            managed_hostname: env.managed_hostname.unwrap_or_default(),
+            expires_at: env.expires_at.unwrap_or_else(|| "-".to_string()),
+            created_at: env.created_at,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct UpdateEnvRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
-    expected_version: i32,
-}
+const ENV_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.Env";
+const LIST_ENVS_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.ListEnvsResponse";
 
 /// List all environments in the current app.
 async fn list_envs(ctx: CommandContext, args: ListEnvsArgs) -> Result<()> {
@@ -140,11 +229,20 @@ async fn list_envs(ctx: CommandContext, args: ListEnvsArgs) -> Result<()> {
     if let Some(cursor) = args.cursor.as_deref() {
         path.push_str(&format!("&cursor={cursor}"));
     }
+    if let Some(order_by) = args.order_by.as_deref() {
+        path.push_str(&format!("&order_by={order_by}"));
+    }
+    if args.desc {
+        path.push_str("&desc=true");
+    }
 
     let response: ListEnvsResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<EnvRow> = response.items.into_iter().map(EnvRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_proto_single(&response, ctx.format, LIST_ENVS_TYPE_URL),
     }
     Ok(())
@@ -158,6 +256,8 @@ async fn create_env(ctx: CommandContext, args: CreateEnvArgs) -> Result<()> {
 
     let request = CreateEnvRequest {
         name: args.name.clone(),
+        preview: args.preview.then_some(true),
+        ttl_seconds: args.ttl,
     };
     let path = format!("/v1/orgs/{}/apps/{}/envs", org, app);
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -165,12 +265,17 @@ async fn create_env(ctx: CommandContext, args: CreateEnvArgs) -> Result<()> {
         None => crate::idempotency::default_idempotency_key("envs.create", &path, &request)?,
     };
 
-    let response: EnvResponse = client
+    let response: Env = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
     let env_id = response.id.clone();
     let env_name = response.name.clone();
+    let expiry = response
+        .expires_at
+        .as_deref()
+        .map(|at| format!(", expires {at}"))
+        .unwrap_or_default();
     let org_id_str = org.to_string();
     let app_id_str = app.to_string();
     let next = vec![
@@ -207,11 +312,12 @@ async fn create_env(ctx: CommandContext, args: CreateEnvArgs) -> Result<()> {
         ctx.format,
         Receipt {
             message: format!(
-                "Created environment '{}' ({}) in {}/{}",
+                "Created environment '{}' ({}) in {}/{}{}",
                 env_name,
                 env_id.as_str(),
                 org_id_str.as_str(),
-                app_id_str.as_str()
+                app_id_str.as_str(),
+                expiry
             ),
             status: "accepted",
             kind: "envs.create",
@@ -237,7 +343,7 @@ async fn update_env(ctx: CommandContext, args: UpdateEnvArgs) -> Result<()> {
 
     let request = UpdateEnvRequest {
         name: args.name.clone(),
-        expected_version: args.expected_version,
+        expected_version: Some(args.expected_version.into()),
     };
     let path = format!("/v1/orgs/{}/apps/{}/envs/{}", org_id, app_id, env_id);
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -245,7 +351,7 @@ async fn update_env(ctx: CommandContext, args: UpdateEnvArgs) -> Result<()> {
         None => crate::idempotency::default_idempotency_key("envs.update", &path, &request)?,
     };
 
-    let response: EnvResponse = client
+    let response: Env = client
         .patch_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await
         .map_err(|e| match e {
@@ -311,7 +417,7 @@ async fn get_env(ctx: CommandContext, args: GetEnvArgs) -> Result<()> {
     let app = crate::resolve::resolve_app_id(&client, org, ctx.require_app()?).await?;
     let env_id = crate::resolve::resolve_env_id(&client, org, app, &args.env).await?;
 
-    let response: EnvResponse = client
+    let response: Env = client
         .get(&format!("/v1/orgs/{}/apps/{}/envs/{}", org, app, env_id))
         .await
         .map_err(|e| match e {
@@ -358,3 +464,279 @@ async fn use_env(mut ctx: CommandContext, args: UseEnvArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// Configuration change from the env history API.
+#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
+struct EnvHistoryItem {
+    #[tabled(rename = "ID")]
+    event_id: i64,
+
+    #[tabled(rename = "Occurred At")]
+    occurred_at: String,
+
+    #[tabled(rename = "Kind")]
+    kind: String,
+
+    #[tabled(rename = "Change")]
+    summary: String,
+
+    #[tabled(rename = "Actor")]
+    actor_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EnvHistoryResponse {
+    items: Vec<EnvHistoryItem>,
+    next_cursor: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
+struct EnvConfigChange {
+    #[tabled(rename = "Key")]
+    key: String,
+
+    #[tabled(rename = "From")]
+    #[tabled(display = "display_value")]
+    #[serde(default)]
+    from: Option<serde_json::Value>,
+
+    #[tabled(rename = "To")]
+    #[tabled(display = "display_value")]
+    #[serde(default)]
+    to: Option<serde_json::Value>,
+}
+
+fn display_value(value: &Option<serde_json::Value>) -> String {
+    match value {
+        None => "-".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EnvDiffResponse {
+    from: String,
+    to: String,
+    changes: Vec<EnvConfigChange>,
+}
+
+fn require_env<'a>(ctx: &'a CommandContext, env: Option<&'a str>) -> Result<&'a str> {
+    match env {
+        Some(env) => Ok(env),
+        None => ctx.require_env(),
+    }
+}
+
+/// Parse a preview TTL into seconds: days (`7d`) or anything `parse_duration` takes.
+fn parse_ttl(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    if let Some(days) = value.strip_suffix('d') {
+        let days: i64 = days
+            .parse()
+            .map_err(|_| format!("invalid ttl '{value}' (expected e.g. 72h or 7d)"))?;
+        return days
+            .checked_mul(86_400)
+            .ok_or_else(|| format!("ttl '{value}' is too large"));
+    }
+    super::deploys::parse_duration(value)
+        .map(|d| d.as_secs() as i64)
+        .map_err(|e| e.to_string())
+}
+
+/// List configuration changes of an environment.
+async fn env_history(ctx: CommandContext, args: EnvHistoryArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app = crate::resolve::resolve_app_id(&client, org, ctx.require_app()?).await?;
+    let env_ident = require_env(&ctx, args.env.as_deref())?;
+    let env_id = crate::resolve::resolve_env_id(&client, org, app, env_ident).await?;
+
+    let mut path = format!(
+        "/v1/orgs/{}/apps/{}/envs/{}/config/history?after_event_id={}&limit={}",
+        org, app, env_id, args.after, args.limit
+    );
+    if let Some(kind) = args.kind.as_deref() {
+        path.push_str(&format!("&kind={kind}"));
+    }
+
+    let response: EnvHistoryResponse = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Json => print_single(&response, ctx.format),
+    }
+    Ok(())
+}
+
+/// Compare an environment's configuration at two points in time.
+async fn env_diff(ctx: CommandContext, args: EnvDiffArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app = crate::resolve::resolve_app_id(&client, org, ctx.require_app()?).await?;
+    let env_ident = require_env(&ctx, args.env.as_deref())?;
+    let env_id = crate::resolve::resolve_env_id(&client, org, app, env_ident).await?;
+
+    let mut path = format!(
+        "/v1/orgs/{}/apps/{}/envs/{}/config/diff?from={}",
+        org,
+        app,
+        env_id,
+        args.from.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    if let Some(to) = args.to {
+        path.push_str(&format!(
+            "&to={}",
+            to.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+
+    let response: EnvDiffResponse = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Table => {
+            print_info(&format!(
+                "Changes from {} to {}",
+                response.from, response.to
+            ));
+            print_output(&response.changes, ctx.format)
+        }
+        OutputFormat::Json => print_single(&response, ctx.format),
+    }
+    Ok(())
+}
+
+/// Table row for one day of an SLO report.
+#[derive(Debug, Serialize, Tabled)]
+struct SloDayRow {
+    #[tabled(rename = "Day")]
+    day: String,
+
+    #[tabled(rename = "Availability")]
+    availability: String,
+
+    #[tabled(rename = "Downtime")]
+    downtime: String,
+}
+
+fn format_percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.3}%"))
+}
+
+impl From<SloDay> for SloDayRow {
+    fn from(day: SloDay) -> Self {
+        Self {
+            day: day.day,
+            availability: format_percent(day.availability),
+            downtime: format!("{}s", day.downtime_seconds),
+        }
+    }
+}
+
+/// Show an environment's availability against its objective.
+async fn env_slo(ctx: CommandContext, args: EnvSloArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app = crate::resolve::resolve_app_id(&client, org, ctx.require_app()?).await?;
+    let env_ident = require_env(&ctx, args.env.as_deref())?;
+    let env_id = crate::resolve::resolve_env_id(&client, org, app, env_ident).await?;
+
+    let path = format!(
+        "/v1/orgs/{}/apps/{}/envs/{}/slo?window={}",
+        org, app, env_id, args.window
+    );
+    let report: SloReport = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Table => {
+            print_info(&format!(
+                "Availability {} over {}d (objective {}%); error budget {}s, {}s left; \
+                 burn rate {} ({} over the last hour)",
+                format_percent(report.availability),
+                report.window_days,
+                report.objective,
+                report.budget_seconds,
+                report.remaining_seconds,
+                report
+                    .burn_rate
+                    .map_or_else(|| "-".to_string(), |r| format!("{r}x")),
+                report
+                    .burn_rate_1h
+                    .map_or_else(|| "-".to_string(), |r| format!("{r}x")),
+            ));
+            let rows: Vec<SloDayRow> = report.days.into_iter().map(SloDayRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
+        OutputFormat::Json => print_single(&report, ctx.format),
+    }
+    Ok(())
+}
+
+/// Set an environment's availability objective.
+async fn set_env_slo(ctx: CommandContext, args: SetEnvSloArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app = crate::resolve::resolve_app_id(&client, org, ctx.require_app()?).await?;
+    let env_ident = require_env(&ctx, args.env.as_deref())?;
+    let env_id = crate::resolve::resolve_env_id(&client, org, app, env_ident).await?;
+
+    let request = SloObjectiveRequest {
+        objective: args.objective,
+    };
+    let path = format!("/v1/orgs/{}/apps/{}/envs/{}/slo", org, app, env_id);
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key("envs.slo.set", &path, &request)?,
+    };
+
+    let response: SloObjective = client
+        .put_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let env_id_str = env_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!(
+            "vt --org {} --app {} envs slo {}",
+            org,
+            app,
+            env_id_str.clone()
+        ),
+    }];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Set availability objective of env {} to {}%",
+                env_id_str, response.objective
+            ),
+            status: "accepted",
+            kind: "envs.slo.set",
+            resource_key: "slo",
+            resource: &response,
+            ids: serde_json::json!({
+                "org_id": org.to_string(),
+                "app_id": app.to_string(),
+                "env_id": env_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ttl_accepts_hours_and_days() {
+        assert_eq!(parse_ttl("72h"), Ok(72 * 3600));
+        assert_eq!(parse_ttl("7d"), Ok(7 * 86_400));
+        assert_eq!(parse_ttl("3600"), Ok(3600));
+        assert!(parse_ttl("soon").is_err());
+        assert!(parse_ttl("xd").is_err());
+    }
+}
@@ -1,10 +1,17 @@
 //! Events command (org-scoped event querying/tailing).
+//!
+//! Filters map to query parameters of the events API, so the server does the
+//! filtering and `--limit` counts matching events only.
 
 use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::{Args, Subcommand};
+use plfm_id::{AppId, EnvId, OrgId};
 use serde::{Deserialize, Serialize};
 use tabled::Tabled;
 
+use crate::client::ApiClient;
+use crate::error::CliError;
 use crate::output::{print_output, print_single, OutputFormat};
 
 use super::CommandContext;
@@ -18,26 +25,36 @@ pub struct EventsCommand {
 
 #[derive(Debug, Subcommand)]
 enum EventsSubcommand {
-    /// List events (one-shot).
+    /// List events (one-shot, or streaming with --follow).
     List(EventsListArgs),
 
     /// Tail events (polling).
     Tail(EventsTailArgs),
 }
 
+/// Filters shared by `list` and `tail`.
 #[derive(Debug, Args)]
-struct EventsListArgs {
-    /// Return events with event_id > after_event_id.
-    #[arg(long, default_value = "0")]
-    after: i64,
+struct EventFilterArgs {
+    /// Filter by event type; a trailing `*` matches a prefix (e.g. `deploy.*`).
+    #[arg(long = "type", visible_alias = "event-type")]
+    event_type: Option<String>,
 
-    /// Max number of events to return (1-200).
-    #[arg(long, default_value = "50")]
-    limit: i64,
+    /// Filter by aggregate: `<type>` or `<type>/<id>` (e.g. `deploy/dep_123`).
+    #[arg(long, value_name = "TYPE[/ID]")]
+    aggregate: Option<String>,
 
-    /// Filter by exact event type.
+    /// Filter by actor ID (user, service principal, or system component).
     #[arg(long)]
-    event_type: Option<String>,
+    actor: Option<String>,
+
+    /// Filter by actor type (user, service_principal, system).
+    #[arg(long)]
+    actor_type: Option<String>,
+
+    /// Only events newer than a duration (e.g. 30m, 1h, 7d) or an RFC 3339
+    /// timestamp.
+    #[arg(long)]
+    since: Option<String>,
 
     /// Filter by app_id (defaults to current context if set).
     #[arg(long)]
@@ -49,33 +66,49 @@ struct EventsListArgs {
 }
 
 #[derive(Debug, Args)]
-struct EventsTailArgs {
+struct EventsListArgs {
     /// Return events with event_id > after_event_id.
     #[arg(long, default_value = "0")]
     after: i64,
 
-    /// Max number of events to fetch per poll (1-200).
+    /// Max number of events to return (1-200).
     #[arg(long, default_value = "50")]
     limit: i64,
 
-    /// Filter by exact event type.
-    #[arg(long)]
-    event_type: Option<String>,
+    #[command(flatten)]
+    filter: EventFilterArgs,
 
-    /// Filter by app_id (defaults to current context if set).
-    #[arg(long)]
-    app_id: Option<String>,
+    /// Keep streaming new matching events.
+    #[arg(long, short = 'f')]
+    follow: bool,
 
-    /// Filter by env_id (defaults to current context if set).
-    #[arg(long)]
-    env_id: Option<String>,
+    /// Poll interval in milliseconds (with --follow).
+    #[arg(long, default_value = "1000")]
+    poll_ms: u64,
+}
+
+#[derive(Debug, Args)]
+struct EventsTailArgs {
+    /// Return events with event_id > after_event_id.
+    #[arg(long, default_value = "0")]
+    after: i64,
+
+    /// Max number of events to fetch per poll (1-200).
+    #[arg(long, default_value = "50")]
+    limit: i64,
+
+    #[command(flatten)]
+    filter: EventFilterArgs,
 
     /// Poll interval in milliseconds.
     #[arg(long, default_value = "1000")]
     poll_ms: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
+/// Fields the table shows; the list asks only for these (plus `event_id`).
+const TABLE_FIELDS: &str = "occurred_at,event_type,aggregate_type,aggregate_id,actor_id";
+
+#[derive(Debug, Serialize, Deserialize, Tabled)]
 struct EventRow {
     #[tabled(rename = "ID")]
     event_id: i64,
@@ -95,15 +128,22 @@ struct EventRow {
     #[tabled(display = "display_option")]
     #[serde(default)]
     aggregate_id: Option<String>,
+
+    #[tabled(rename = "Actor")]
+    #[tabled(display = "display_option")]
+    #[serde(default)]
+    actor_id: Option<String>,
 }
 
 fn display_option(opt: &Option<String>) -> String {
     opt.as_deref().unwrap_or("-").to_string()
 }
 
+/// Like `models::EventsResponse`, but items are kept as raw JSON so
+/// `--json`/`--format jsonl` print every field.
 #[derive(Debug, Serialize, Deserialize)]
 struct EventsResponse {
-    items: Vec<EventRow>,
+    items: Vec<serde_json::Value>,
     next_after_event_id: i64,
 }
 
@@ -119,6 +159,10 @@ struct EventStreamLine {
     #[serde(default)]
     aggregate_id: Option<String>,
     #[serde(default)]
+    actor_type: Option<String>,
+    #[serde(default)]
+    actor_id: Option<String>,
+    #[serde(default)]
     app_id: Option<String>,
     #[serde(default)]
     env_id: Option<String>,
@@ -130,122 +174,74 @@ impl EventsCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         match self.command {
             EventsSubcommand::List(args) => list_events(ctx, args).await,
-            EventsSubcommand::Tail(args) => tail_events(ctx, args).await,
+            EventsSubcommand::Tail(args) => {
+                tail_events(ctx, args.after, args.limit, args.filter, args.poll_ms).await
+            }
         }
     }
 }
 
 async fn list_events(ctx: CommandContext, args: EventsListArgs) -> Result<()> {
+    if args.follow {
+        return tail_events(ctx, args.after, args.limit, args.filter, args.poll_ms).await;
+    }
+
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
-
-    let app_ident = args
-        .app_id
-        .or_else(|| ctx.resolve_app().map(|s| s.to_string()));
-    let env_ident = args
-        .env_id
-        .or_else(|| ctx.resolve_env().map(|s| s.to_string()));
-
-    let app_id = match app_ident.as_deref() {
-        None => None,
-        Some(ident) => Some(crate::resolve::resolve_app_id(&client, org_id, ident).await?),
-    };
-
-    let env_id = match env_ident.as_deref() {
-        None => None,
-        Some(ident) => match app_id {
-            Some(app_id) => {
-                Some(crate::resolve::resolve_env_id(&client, org_id, app_id, ident).await?)
-            }
-            None => {
-                if let Ok(id) = ident.parse::<plfm_id::EnvId>() {
-                    Some(id)
-                } else {
-                    anyhow::bail!(
-                        "Resolving env name '{}' requires app context (use --app or --app-id).",
-                        ident
-                    );
-                }
-            }
-        },
-    };
+    let query = filter_query(&ctx, &client, org_id, args.filter).await?;
 
     let mut path = format!(
-        "/v1/orgs/{}/events?after_event_id={}&limit={}",
-        org_id, args.after, args.limit
+        "/v1/orgs/{}/events?after_event_id={}&limit={}{}",
+        org_id, args.after, args.limit, query
     );
-
-    if let Some(event_type) = args.event_type.as_deref() {
-        path.push_str(&format!("&event_type={event_type}"));
-    }
-    if let Some(app_id) = app_id.as_ref() {
-        path.push_str(&format!("&app_id={app_id}"));
-    }
-    if let Some(env_id) = env_id.as_ref() {
-        path.push_str(&format!("&env_id={env_id}"));
+    if !ctx.json_lines && matches!(ctx.format, OutputFormat::Table) {
+        path.push_str(&format!("&fields={TABLE_FIELDS}"));
     }
-
     let response: EventsResponse = client.get(&path).await?;
 
+    if ctx.json_lines {
+        for item in &response.items {
+            println!("{}", serde_json::to_string(item)?);
+        }
+        return Ok(());
+    }
+
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows = response
+                .items
+                .iter()
+                .cloned()
+                .map(serde_json::from_value::<EventRow>)
+                .collect::<Result<Vec<_>, _>>()?;
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_single(&response, ctx.format),
     }
 
     Ok(())
 }
 
-async fn tail_events(ctx: CommandContext, args: EventsTailArgs) -> Result<()> {
+async fn tail_events(
+    ctx: CommandContext,
+    after: i64,
+    limit: i64,
+    filter: EventFilterArgs,
+    poll_ms: u64,
+) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
-
-    let app_ident = args
-        .app_id
-        .or_else(|| ctx.resolve_app().map(|s| s.to_string()));
-    let env_ident = args
-        .env_id
-        .or_else(|| ctx.resolve_env().map(|s| s.to_string()));
-
-    let app_id = match app_ident.as_deref() {
-        None => None,
-        Some(ident) => Some(crate::resolve::resolve_app_id(&client, org_id, ident).await?),
-    };
-
-    let env_id = match env_ident.as_deref() {
-        None => None,
-        Some(ident) => match app_id {
-            Some(app_id) => {
-                Some(crate::resolve::resolve_env_id(&client, org_id, app_id, ident).await?)
-            }
-            None => {
-                if let Ok(id) = ident.parse::<plfm_id::EnvId>() {
-                    Some(id)
-                } else {
-                    anyhow::bail!(
-                        "Resolving env name '{}' requires app context (use --app or --app-id).",
-                        ident
-                    );
-                }
-            }
-        },
-    };
-
-    let mut path = format!(
-        "/v1/orgs/{}/events/stream?after_event_id={}&limit={}",
-        org_id, args.after, args.limit
+    let query = filter_query(&ctx, &client, org_id, filter).await?;
+
+    let path = format!(
+        "/v1/orgs/{}/events/stream?after_event_id={}&limit={}{}&poll_ms={}",
+        org_id,
+        after,
+        limit,
+        query,
+        poll_ms.max(100)
     );
 
-    if let Some(event_type) = args.event_type.as_deref() {
-        path.push_str(&format!("&event_type={event_type}"));
-    }
-    if let Some(app_id) = app_id.as_ref() {
-        path.push_str(&format!("&app_id={app_id}"));
-    }
-    if let Some(env_id) = env_id.as_ref() {
-        path.push_str(&format!("&env_id={env_id}"));
-    }
-    path.push_str(&format!("&poll_ms={}", args.poll_ms.max(100)));
-
     let mut response = client.get_ndjson_stream(&path).await?;
     let mut buffer = String::new();
 
@@ -271,7 +267,11 @@ async fn tail_events(ctx: CommandContext, args: EventsTailArgs) -> Result<()> {
                             (Some(t), Some(id)) => format!("{}/{}", t, id),
                             _ => "-".to_string(),
                         };
-                        println!("{}\t{}\t{}\t{}", event.seq, event.ts, event.event_type, agg);
+                        let actor = event.actor_id.as_deref().unwrap_or("-");
+                        println!(
+                            "{}\t{}\t{}\t{}\t{}",
+                            event.seq, event.ts, event.event_type, agg, actor
+                        );
                     }
                 }
             }
@@ -280,3 +280,152 @@ async fn tail_events(ctx: CommandContext, args: EventsTailArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// Resolve the filters into `&key=value` query parameters.
+async fn filter_query(
+    ctx: &CommandContext,
+    client: &ApiClient,
+    org_id: OrgId,
+    filter: EventFilterArgs,
+) -> Result<String> {
+    let (app_id, env_id) = resolve_scope(ctx, client, org_id, filter.app_id, filter.env_id).await?;
+
+    let mut query = String::new();
+    if let Some(event_type) = filter.event_type.as_deref() {
+        query.push_str(&format!("&event_type={event_type}"));
+    }
+    if let Some(aggregate) = filter.aggregate.as_deref() {
+        let (aggregate_type, aggregate_id) = parse_aggregate(aggregate)?;
+        query.push_str(&format!("&aggregate_type={aggregate_type}"));
+        if let Some(aggregate_id) = aggregate_id {
+            query.push_str(&format!("&aggregate_id={aggregate_id}"));
+        }
+    }
+    if let Some(actor) = filter.actor.as_deref() {
+        query.push_str(&format!("&actor_id={actor}"));
+    }
+    if let Some(actor_type) = filter.actor_type.as_deref() {
+        query.push_str(&format!("&actor_type={actor_type}"));
+    }
+    if let Some(since) = filter.since.as_deref() {
+        let since = parse_since(since, Utc::now())?;
+        // `Z` rather than `+00:00`: a raw `+` would decode as a space.
+        query.push_str(&format!(
+            "&since={}",
+            since.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+    if let Some(app_id) = app_id.as_ref() {
+        query.push_str(&format!("&app_id={app_id}"));
+    }
+    if let Some(env_id) = env_id.as_ref() {
+        query.push_str(&format!("&env_id={env_id}"));
+    }
+    Ok(query)
+}
+
+/// Resolve the app/env filters, falling back to the current context.
+async fn resolve_scope(
+    ctx: &CommandContext,
+    client: &ApiClient,
+    org_id: OrgId,
+    app_id: Option<String>,
+    env_id: Option<String>,
+) -> Result<(Option<AppId>, Option<EnvId>)> {
+    let app_ident = app_id.or_else(|| ctx.resolve_app().map(|s| s.to_string()));
+    let env_ident = env_id.or_else(|| ctx.resolve_env().map(|s| s.to_string()));
+
+    let app_id = match app_ident.as_deref() {
+        None => None,
+        Some(ident) => Some(crate::resolve::resolve_app_id(client, org_id, ident).await?),
+    };
+
+    let env_id = match env_ident.as_deref() {
+        None => None,
+        Some(ident) => match app_id {
+            Some(app_id) => {
+                Some(crate::resolve::resolve_env_id(client, org_id, app_id, ident).await?)
+            }
+            None => {
+                if let Ok(id) = ident.parse::<EnvId>() {
+                    Some(id)
+                } else {
+                    anyhow::bail!(
+                        "Resolving env name '{}' requires app context (use --app or --app-id).",
+                        ident
+                    );
+                }
+            }
+        },
+    };
+
+    Ok((app_id, env_id))
+}
+
+/// Split `--aggregate` into type and optional ID.
+fn parse_aggregate(value: &str) -> Result<(&str, Option<&str>), CliError> {
+    let (aggregate_type, aggregate_id) = match value.split_once('/') {
+        Some((t, id)) => (t, Some(id)),
+        None => (value, None),
+    };
+    if aggregate_type.is_empty() || aggregate_id.is_some_and(str::is_empty) {
+        return Err(CliError::Validation(format!(
+            "invalid --aggregate '{value}': expected <type> or <type>/<id>"
+        )));
+    }
+    Ok((aggregate_type, aggregate_id))
+}
+
+/// `--since` as an absolute time: an RFC 3339 timestamp, or a duration
+/// (`90s`, `30m`, `1h`, `7d`) before `now`.
+fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, CliError> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let duration = match value.strip_suffix('d') {
+        Some(days) => days
+            .parse::<u64>()
+            .ok()
+            .map(|d| std::time::Duration::from_secs(d * 24 * 60 * 60)),
+        None => super::deploys::parse_duration(value).ok(),
+    };
+    duration
+        .and_then(|d| chrono::Duration::from_std(d).ok())
+        .map(|d| now - d)
+        .ok_or_else(|| {
+            CliError::Validation(format!(
+                "invalid --since '{value}': expected a duration (30m, 1h, 7d) or an RFC 3339 timestamp"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_since_accepts_durations_and_timestamps() {
+        let now = DateTime::parse_from_rfc3339("2024-06-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let at = |s: &str| parse_since(s, now).unwrap().to_rfc3339();
+
+        assert_eq!(at("1h"), "2024-06-10T11:00:00+00:00");
+        assert_eq!(at("30m"), "2024-06-10T11:30:00+00:00");
+        assert_eq!(at("7d"), "2024-06-03T12:00:00+00:00");
+        assert_eq!(at("2024-06-01T00:00:00+02:00"), "2024-05-31T22:00:00+00:00");
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("", now).is_err());
+    }
+
+    #[test]
+    fn parse_aggregate_splits_type_and_id() {
+        assert_eq!(parse_aggregate("deploy").unwrap(), ("deploy", None));
+        assert_eq!(
+            parse_aggregate("deploy/dep_123").unwrap(),
+            ("deploy", Some("dep_123"))
+        );
+        assert!(parse_aggregate("/dep_123").is_err());
+        assert!(parse_aggregate("deploy/").is_err());
+    }
+}
@@ -23,6 +23,7 @@ use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::mpsc;
 use tokio_tungstenite::tungstenite::Message;
 
+use crate::client::models::ExecGrantResponse;
 use crate::output::{print_info, print_single, print_success, OutputFormat};
 
 use super::CommandContext;
@@ -93,6 +94,8 @@ pub struct ExecCommand {
 // Request/Response Types
 // =============================================================================
 
+/// Hand-written rather than `models::ExecGrantRequest`: the spec does not
+/// document `cols`, `rows` or `env` yet.
 #[derive(Debug, Serialize)]
 struct ExecGrantRequest {
     command: Vec<String>,
@@ -105,14 +108,6 @@ struct ExecGrantRequest {
     env: Option<std::collections::HashMap<String, String>>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct ExecGrantResponse {
-    session_id: String,
-    connect_url: String,
-    session_token: String,
-    expires_in_seconds: i64,
-}
-
 // =============================================================================
 // Control Messages
 // =============================================================================
@@ -141,7 +136,7 @@ impl ExecCommand {
         let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
         let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
         let env_id =
-            crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+            crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
         let use_tty = !self.no_tty && self.tty;
 
@@ -492,12 +487,6 @@ impl Drop for RawModeGuard {
     }
 }
 
-fn require_env(ctx: &CommandContext) -> Result<&str> {
-    ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })
-}
-
 // =============================================================================
 // Tests
 // =============================================================================
@@ -0,0 +1,356 @@
+//! Init command (scaffold a new app from a built-in template).
+//!
+//! `vt init <template>` writes a manifest, Dockerfile, and CI workflow for a
+//! web service, background worker, or cron app, and can create the app and
+//! its first environment via the API.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use plfm_api_types::apps::{AppResponse, CreateAppRequest};
+
+use crate::client::models::{CreateEnvRequest, Env};
+use crate::output::{print_receipt_no_resource, OutputFormat, ReceiptNextStep, ReceiptNoResource};
+
+use super::CommandContext;
+
+const CI_WORKFLOW_PATH: &str = ".github/workflows/vt-deploy.yml";
+const CI_WORKFLOW: &str = include_str!("../../templates/ci/github-actions.yml");
+
+/// Built-in app templates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Template {
+    /// HTTP service listening on a port.
+    Web,
+    /// Long-running background worker without ports.
+    Worker,
+    /// Scheduled jobs run by supercronic.
+    Cron,
+}
+
+impl Template {
+    fn as_str(self) -> &'static str {
+        match self {
+            Template::Web => "web",
+            Template::Worker => "worker",
+            Template::Cron => "cron",
+        }
+    }
+
+    /// Files of the template (path relative to the target dir, contents).
+    fn files(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Template::Web => &[
+                ("vt.toml", include_str!("../../templates/web/vt.toml")),
+                ("Dockerfile", include_str!("../../templates/web/Dockerfile")),
+            ],
+            Template::Worker => &[
+                ("vt.toml", include_str!("../../templates/worker/vt.toml")),
+                (
+                    "Dockerfile",
+                    include_str!("../../templates/worker/Dockerfile"),
+                ),
+            ],
+            Template::Cron => &[
+                ("vt.toml", include_str!("../../templates/cron/vt.toml")),
+                (
+                    "Dockerfile",
+                    include_str!("../../templates/cron/Dockerfile"),
+                ),
+                ("crontab", include_str!("../../templates/cron/crontab")),
+            ],
+        }
+    }
+}
+
+/// Scaffold a new app from a template.
+#[derive(Debug, Args)]
+pub struct InitCommand {
+    /// Template to use.
+    #[arg(value_enum)]
+    template: Template,
+
+    /// App name (defaults to the target directory name).
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Port the web service listens on.
+    #[arg(long, default_value = "8080")]
+    port: u16,
+
+    /// Directory to write files into.
+    #[arg(long, default_value = ".")]
+    dir: PathBuf,
+
+    /// Environment name used by the CI workflow (and created with --create).
+    #[arg(long = "env-name", default_value = "prod")]
+    env_name: String,
+
+    /// Skip the CI workflow.
+    #[arg(long)]
+    no_ci: bool,
+
+    /// Overwrite existing files.
+    #[arg(long)]
+    force: bool,
+
+    /// Also create the app and environment via the API.
+    #[arg(long)]
+    create: bool,
+}
+
+/// Template parameters.
+#[derive(Debug, Clone)]
+struct TemplateParams {
+    app_name: String,
+    port: u16,
+    env_name: String,
+    org_name: String,
+}
+
+/// Substitute `{{...}}` placeholders. Other `{{ ... }}` sequences (such as
+/// GitHub Actions expressions) are left untouched.
+fn render(template: &str, params: &TemplateParams) -> String {
+    template
+        .replace("{{app_name}}", &params.app_name)
+        .replace("{{port}}", &params.port.to_string())
+        .replace("{{env_name}}", &params.env_name)
+        .replace("{{org_name}}", &params.org_name)
+}
+
+/// App names become image names and manifest `app.name`: keep them simple.
+fn validate_app_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        anyhow::bail!(
+            "invalid app name '{name}': use 1-64 lowercase letters, digits, and '-', starting with a letter (pass --name)"
+        );
+    }
+    Ok(())
+}
+
+fn default_app_name(dir: &Path) -> Option<String> {
+    let dir = std::fs::canonicalize(dir).ok()?;
+    Some(dir.file_name()?.to_string_lossy().to_lowercase())
+}
+
+/// Render the files a template would write.
+fn scaffold(
+    template: Template,
+    params: &TemplateParams,
+    include_ci: bool,
+) -> Vec<(&'static str, String)> {
+    let mut files: Vec<(&'static str, String)> = template
+        .files()
+        .iter()
+        .map(|(path, contents)| (*path, render(contents, params)))
+        .collect();
+    if include_ci {
+        files.push((CI_WORKFLOW_PATH, render(CI_WORKFLOW, params)));
+    }
+    files
+}
+
+impl InitCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        let app_name = match self.name.clone() {
+            Some(name) => name,
+            None => default_app_name(&self.dir).ok_or_else(|| {
+                anyhow::anyhow!("could not derive an app name from the directory; pass --name")
+            })?,
+        };
+        validate_app_name(&app_name)?;
+
+        let params = TemplateParams {
+            app_name: app_name.clone(),
+            port: self.port,
+            env_name: self.env_name.clone(),
+            org_name: ctx.resolve_org().unwrap_or("your-org").to_string(),
+        };
+        let files = scaffold(self.template, &params, !self.no_ci);
+
+        if !self.force {
+            let existing: Vec<String> = files
+                .iter()
+                .map(|(path, _)| self.dir.join(path))
+                .filter(|path| path.exists())
+                .map(|path| path.display().to_string())
+                .collect();
+            if !existing.is_empty() {
+                anyhow::bail!(
+                    "refusing to overwrite existing files (use --force): {}",
+                    existing.join(", ")
+                );
+            }
+        }
+
+        let errors = crate::manifest::validate_manifest_toml_str(&files[0].1)?;
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "template produced an invalid manifest ({} error(s))",
+                errors.len()
+            );
+        }
+
+        let mut written = Vec::new();
+        for (path, contents) in &files {
+            let path = self.dir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&path, contents)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            written.push(path.display().to_string());
+        }
+
+        let mut ids = serde_json::json!({ "files": written.clone() });
+        if self.create {
+            let (app_id, env_id) = create_app_and_env(&ctx, &app_name, &self.env_name).await?;
+            ids["app_id"] = serde_json::json!(app_id);
+            ids["env_id"] = serde_json::json!(env_id);
+        }
+
+        let mut next = Vec::new();
+        if !self.create {
+            next.push(ReceiptNextStep {
+                label: "Next",
+                cmd: format!("vt apps create {app_name}"),
+            });
+            next.push(ReceiptNextStep {
+                label: "Next",
+                cmd: format!("vt --app {app_name} envs create {}", self.env_name),
+            });
+        }
+        next.push(ReceiptNextStep {
+            label: "Next",
+            cmd: format!(
+                "vt --app {app_name} --env {} secrets confirm --none",
+                self.env_name
+            ),
+        });
+        next.push(ReceiptNextStep {
+            label: "Next",
+            cmd: format!(
+                "vt --app {app_name} --env {} deploy --image-digest <sha256:...>",
+                self.env_name
+            ),
+        });
+
+        print_receipt_no_resource(
+            ctx.format,
+            ReceiptNoResource {
+                message: format!(
+                    "Scaffolded {} app '{}' ({} files)",
+                    self.template.as_str(),
+                    app_name,
+                    files.len()
+                ),
+                status: "created",
+                kind: "init",
+                ids,
+                next: &next,
+            },
+        );
+
+        if matches!(ctx.format, OutputFormat::Table) {
+            for path in &written {
+                println!("  {path}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Create the app and its first environment, returning their IDs.
+async fn create_app_and_env(
+    ctx: &CommandContext,
+    app_name: &str,
+    env_name: &str,
+) -> Result<(String, String)> {
+    let client = ctx.client()?;
+    let org = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let request = CreateAppRequest {
+        name: app_name.to_string(),
+        description: None,
+    };
+    let path = format!("/v1/orgs/{}/apps", org);
+    let key = crate::idempotency::default_idempotency_key("apps.create", &path, &request)?;
+    let app: AppResponse = client
+        .post_with_idempotency_key(&path, &request, Some(key.as_str()))
+        .await?;
+
+    let request = CreateEnvRequest {
+        name: env_name.to_string(),
+        ..Default::default()
+    };
+    let path = format!("/v1/orgs/{}/apps/{}/envs", org, app.id);
+    let key = crate::idempotency::default_idempotency_key("envs.create", &path, &request)?;
+    let env: Env = client
+        .post_with_idempotency_key(&path, &request, Some(key.as_str()))
+        .await?;
+
+    Ok((app.id, env.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> TemplateParams {
+        TemplateParams {
+            app_name: "hello".to_string(),
+            port: 3000,
+            env_name: "staging".to_string(),
+            org_name: "acme".to_string(),
+        }
+    }
+
+    #[test]
+    fn templates_render_valid_manifests() {
+        for template in [Template::Web, Template::Worker, Template::Cron] {
+            let files = scaffold(template, &params(), true);
+            let (path, manifest) = &files[0];
+            assert_eq!(*path, "vt.toml");
+            assert!(!manifest.contains("{{"), "{template:?} left a placeholder");
+            let errors = crate::manifest::validate_manifest_toml_str(manifest).unwrap();
+            assert!(errors.is_empty(), "{template:?} manifest is invalid");
+            assert_eq!(files.last().unwrap().0, CI_WORKFLOW_PATH);
+        }
+    }
+
+    #[test]
+    fn render_substitutes_params_and_keeps_ci_expressions() {
+        let files = scaffold(Template::Web, &params(), true);
+        let manifest = &files[0].1;
+        assert!(manifest.contains("internal = 3000"));
+        assert!(manifest.contains("name = \"hello\""));
+
+        let workflow = &files.last().unwrap().1;
+        assert!(workflow.contains("VT_ENV: staging"));
+        assert!(workflow.contains("VT_ORG: acme"));
+        assert!(workflow.contains("${{ github.sha }}"));
+
+        let without_ci = scaffold(Template::Cron, &params(), false);
+        assert_eq!(
+            without_ci.iter().map(|(p, _)| *p).collect::<Vec<_>>(),
+            vec!["vt.toml", "Dockerfile", "crontab"]
+        );
+    }
+
+    #[test]
+    fn app_name_validation() {
+        assert!(validate_app_name("my-app2").is_ok());
+        assert!(validate_app_name("").is_err());
+        assert!(validate_app_name("2app").is_err());
+        assert!(validate_app_name("My_App").is_err());
+    }
+}
@@ -2,9 +2,11 @@
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use colored::Colorize;
+use serde::Serialize;
 use tabled::Tabled;
 
+use crate::client::models::{Instance, InstanceConsole, ListInstancesResponse};
 use crate::error::CliError;
 use crate::output::{print_output, print_single, OutputFormat};
 
@@ -24,6 +26,10 @@ enum InstancesSubcommand {
 
     /// Get instance details.
     Get(GetInstanceArgs),
+
+    /// Show the end of an instance's serial console (kernel and early-boot
+    /// output).
+    Console(ConsoleArgs),
 }
 
 #[derive(Debug, Args)]
@@ -51,18 +57,29 @@ struct GetInstanceArgs {
     instance: String,
 }
 
+#[derive(Debug, Args)]
+struct ConsoleArgs {
+    /// Instance ID.
+    instance: String,
+
+    /// Show at most this many bytes from the end (max 65536).
+    #[arg(long)]
+    tail_bytes: Option<u32>,
+}
+
 impl InstancesCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         match self.command {
             InstancesSubcommand::List(args) => list_instances(ctx, args).await,
             InstancesSubcommand::Get(args) => get_instance(ctx, args).await,
+            InstancesSubcommand::Console(args) => instance_console(ctx, args).await,
         }
     }
 }
 
-/// Instance response from API.
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct InstanceResponse {
+/// Table row for an instance.
+#[derive(Debug, Serialize, Tabled)]
+struct InstanceRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -73,39 +90,40 @@ struct InstanceResponse {
     status: String,
 
     #[tabled(rename = "Node", display = "display_option")]
-    #[serde(default)]
     node_id: Option<String>,
 
-    #[tabled(rename = "Gen", display = "display_option_i32")]
-    #[serde(default)]
-    generation: Option<i32>,
+    #[tabled(rename = "Gen", display = "display_option")]
+    generation: Option<i64>,
 
     #[tabled(rename = "Last Transition", display = "display_option")]
-    #[serde(default)]
     last_transition_at: Option<String>,
 
     #[tabled(rename = "Failure", display = "display_option")]
-    #[serde(default)]
     failure_reason: Option<String>,
 
     #[tabled(rename = "Created")]
     created_at: String,
 }
 
-fn display_option(opt: &Option<String>) -> String {
-    opt.as_deref().unwrap_or("-").to_string()
-}
-
-fn display_option_i32(opt: &Option<i32>) -> String {
-    opt.map(|v| v.to_string())
-        .unwrap_or_else(|| "-".to_string())
+impl From<Instance> for InstanceRow {
+    fn from(instance: Instance) -> Self {
+        Self {
+            id: instance.id,
+            process_type: instance.process_type,
+            status: instance.status,
+            node_id: instance.node_id,
+            generation: instance.generation,
+            last_transition_at: instance.last_transition_at,
+            failure_reason: instance.failure_reason,
+            created_at: instance.created_at,
+        }
+    }
 }
 
-/// List response from API.
-#[derive(Debug, Serialize, Deserialize)]
-struct ListInstancesResponse {
-    items: Vec<InstanceResponse>,
-    next_cursor: Option<String>,
+fn display_option<T: std::fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "-".to_string(), ToString::to_string)
 }
 
 /// List instances.
@@ -114,9 +132,7 @@ async fn list_instances(ctx: CommandContext, args: ListInstancesArgs) -> Result<
 
     let org_ident = ctx.require_org()?;
     let app_ident = ctx.require_app()?;
-    let env_ident = ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })?;
+    let env_ident = ctx.require_env()?;
     let org_id = crate::resolve::resolve_org_id(&client, org_ident).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, app_ident).await?;
     let env_id = crate::resolve::resolve_env_id(&client, org_id, app_id, env_ident).await?;
@@ -145,7 +161,11 @@ async fn list_instances(ctx: CommandContext, args: ListInstancesArgs) -> Result<
     let response: ListInstancesResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<InstanceRow> =
+                response.items.into_iter().map(InstanceRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_single(&response, ctx.format),
     }
     Ok(())
@@ -157,14 +177,12 @@ async fn get_instance(ctx: CommandContext, args: GetInstanceArgs) -> Result<()>
 
     let org_ident = ctx.require_org()?;
     let app_ident = ctx.require_app()?;
-    let env_ident = ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })?;
+    let env_ident = ctx.require_env()?;
     let org_id = crate::resolve::resolve_org_id(&client, org_ident).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, app_ident).await?;
     let env_id = crate::resolve::resolve_env_id(&client, org_id, app_id, env_ident).await?;
 
-    let response: InstanceResponse = client
+    let response: Instance = client
         .get(&format!(
             "/v1/orgs/{}/apps/{}/envs/{}/instances/{}",
             org_id, app_id, env_id, args.instance
@@ -180,3 +198,33 @@ async fn get_instance(ctx: CommandContext, args: GetInstanceArgs) -> Result<()>
     print_single(&response, ctx.format);
     Ok(())
 }
+
+/// Show an instance's serial console.
+///
+/// The console is addressed by instance ID alone, so no org/app/env context
+/// is needed.
+async fn instance_console(ctx: CommandContext, args: ConsoleArgs) -> Result<()> {
+    let client = ctx.client()?;
+
+    let mut path = format!("/v1/instances/{}/console", args.instance);
+    if let Some(tail_bytes) = args.tail_bytes {
+        path.push_str(&format!("?tail_bytes={tail_bytes}"));
+    }
+    let response: InstanceConsole = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Table => {
+            let header = format!(
+                "# node {} boot {}, captured {}",
+                response.node_id, response.boot_id, response.captured_at
+            );
+            eprintln!("{}", header.dimmed());
+            if response.truncated {
+                eprintln!("{}", "# (earlier output truncated)".dimmed());
+            }
+            print!("{}", response.content);
+        }
+        OutputFormat::Json => print_single(&response, ctx.format),
+    }
+    Ok(())
+}
@@ -33,6 +33,8 @@ pub struct LogsCommand {
     timestamps: bool,
 }
 
+/// Hand-written rather than `models::LogLine`: the spec does not document
+/// `type`, `stream` or `truncated` yet.
 #[derive(Debug, Serialize, Deserialize)]
 struct LogLine {
     ts: String,
@@ -58,9 +60,7 @@ impl LogsCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         let org_ident = ctx.require_org()?;
         let app_ident = ctx.require_app()?;
-        let env_ident = ctx.resolve_env().ok_or_else(|| {
-            anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-        })?;
+        let env_ident = ctx.require_env()?;
 
         let client = ctx.client()?;
         let org_id = crate::resolve::resolve_org_id(&client, org_ident).await?;
@@ -1,46 +1,72 @@
 //! CLI commands.
 
+mod alerts;
 mod apply;
 mod apps;
 mod auth;
+mod config_vars;
 mod context;
 mod debug;
 mod deploys;
 mod envs;
 mod events;
 mod exec;
+mod init;
 mod instances;
 mod logs;
 mod manifest;
 mod nodes;
 mod orgs;
+mod plugin;
 mod projects;
 mod releases;
+mod resources;
 mod routes;
 mod scale;
 mod secrets;
 mod status;
+mod trash;
 mod volumes;
 
+use std::ffi::OsString;
+use std::future::Future;
+use std::sync::OnceLock;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
 use crate::client::ApiClient;
-use crate::config::{Config, Credentials};
+use crate::config::{CliContext, Config, Credentials};
 use crate::output::OutputFormat;
+use crate::picker::{self, PickItem};
 
 /// plfm-vt CLI - Deploy and manage applications on the platform.
 #[derive(Debug, Parser)]
 #[command(name = "vt")]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// Output format (table or json).
+    /// Output format (table, json, or jsonl).
+    ///
+    /// `jsonl` prints one JSON object per line from list and streaming
+    /// commands that support it (`vt events`); elsewhere it behaves like `json`.
     #[arg(long, global = true, default_value = "table")]
     format: String,
 
     #[arg(long, global = true, help = "Output JSON (alias for --format json).")]
     json: bool,
 
+    /// Suppress success and progress messages (errors still go to stderr).
+    ///
+    /// Exit codes: 0 success, 1 failure, 2 invalid input, 3 not found,
+    /// 4 conflict, 5 timed out waiting, 6 auth, 7 unavailable (retry),
+    /// 10 deploy failed.
+    #[arg(long, short, global = true)]
+    quiet: bool,
+
+    /// Log a redacted summary of every API request to stderr.
+    #[arg(long, global = true, env = "VT_DEBUG_HTTP")]
+    debug_http: bool,
+
     /// Organization ID or name.
     #[arg(long, global = true, env = "VT_ORG")]
     org: Option<String>,
@@ -59,6 +85,13 @@ pub struct Cli {
     #[arg(long, global = true)]
     idempotency_key: Option<String>,
 
+    /// Never prompt for a missing app/env; fail instead.
+    ///
+    /// Prompts are also skipped when stdin or stderr is not a terminal, or
+    /// when CI is set.
+    #[arg(long, global = true, env = "VT_NO_INTERACTIVE")]
+    no_interactive: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -93,6 +126,9 @@ enum Commands {
     #[command(visible_alias = "apply")]
     Deploy(apply::ApplyCommand),
 
+    /// Scaffold a new app (manifest, Dockerfile, CI) from a template.
+    Init(init::InitCommand),
+
     /// Show desired vs current state for the app/environment.
     Status(status::StatusCommand),
 
@@ -105,6 +141,9 @@ enum Commands {
     /// Set process scaling.
     Scale(scale::ScaleCommand),
 
+    /// Override cpu/memory/disk for a process type.
+    Resources(resources::ResourcesCommand),
+
     /// View application logs.
     Logs(logs::LogsCommand),
 
@@ -120,17 +159,33 @@ enum Commands {
     /// Manage routes (hostname bindings).
     Routes(routes::RoutesCommand),
 
+    /// Manage alert rules and view open alerts.
+    Alerts(alerts::AlertsCommand),
+
     /// Manage environment secrets.
     Secrets(secrets::SecretsCommand),
 
+    /// Manage non-secret environment config vars.
+    ConfigVars(config_vars::ConfigVarsCommand),
+
     /// Manage volumes, attachments, and snapshots.
     Volumes(volumes::VolumesCommand),
 
+    /// List and restore deleted resources.
+    Trash(trash::TrashCommand),
+
     /// Debug commands for operators (admin only).
     Debug(debug::DebugCommand),
 
+    /// List and run CLI plugins (`vt-<name>` executables on PATH).
+    Plugin(plugin::PluginCommand),
+
     /// Show CLI version.
     Version,
+
+    /// Run a plugin: `vt <name>` executes `vt-<name>` from PATH.
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
 }
 
 impl Cli {
@@ -140,11 +195,14 @@ impl Cli {
             OutputFormat::Json
         } else {
             match self.format.as_str() {
-                "json" => OutputFormat::Json,
+                "json" | "jsonl" => OutputFormat::Json,
                 _ => OutputFormat::Table,
             }
         };
 
+        crate::output::set_quiet(self.quiet);
+        crate::http_trace::set_enabled(self.debug_http);
+
         let config = Config::load()?;
         let credentials = Credentials::load()?;
 
@@ -153,10 +211,14 @@ impl Cli {
             config,
             credentials,
             format,
+            json_lines: !self.json && self.format == "jsonl",
             org: self.org,
             app: self.app,
             env: self.env,
             idempotency_key: self.idempotency_key,
+            interactive: !self.no_interactive && picker::is_interactive(),
+            picked_app: OnceLock::new(),
+            picked_env: OnceLock::new(),
         };
 
         match self.command {
@@ -169,22 +231,29 @@ impl Cli {
             Commands::Releases(cmd) => cmd.run(ctx).await,
             Commands::Deploys(cmd) => cmd.run(ctx).await,
             Commands::Deploy(cmd) => cmd.run(ctx).await,
+            Commands::Init(cmd) => cmd.run(ctx).await,
             Commands::Status(cmd) => cmd.run(ctx).await,
             Commands::Nodes(cmd) => cmd.run(ctx).await,
             Commands::Instances(cmd) => cmd.run(ctx).await,
             Commands::Scale(cmd) => cmd.run(ctx).await,
+            Commands::Resources(cmd) => cmd.run(ctx).await,
             Commands::Logs(cmd) => cmd.run(ctx).await,
             Commands::Exec(cmd) => cmd.run(ctx).await,
             Commands::Manifest(cmd) => cmd.run(ctx).await,
             Commands::Events(cmd) => cmd.run(ctx).await,
             Commands::Routes(cmd) => cmd.run(ctx).await,
+            Commands::Alerts(cmd) => cmd.run(ctx).await,
             Commands::Secrets(cmd) => cmd.run(ctx).await,
+            Commands::ConfigVars(cmd) => cmd.run(ctx).await,
             Commands::Volumes(cmd) => cmd.run(ctx).await,
+            Commands::Trash(cmd) => cmd.run(ctx).await,
             Commands::Debug(cmd) => cmd.run(ctx).await,
+            Commands::Plugin(cmd) => cmd.run(ctx).await,
             Commands::Version => {
                 println!("vt {}", env!("CARGO_PKG_VERSION"));
                 Ok(())
             }
+            Commands::External(args) => plugin::run_external(args, ctx),
         }
     }
 }
@@ -194,10 +263,17 @@ pub struct CommandContext {
     pub config: Config,
     pub credentials: Option<Credentials>,
     pub format: OutputFormat,
+    /// `--format jsonl`: `format` is `Json`, and commands that can emit one
+    /// object per line do so.
+    pub json_lines: bool,
     pub org: Option<String>,
     pub app: Option<String>,
     pub env: Option<String>,
     pub idempotency_key: Option<String>,
+    /// Whether a missing app/env may be picked interactively.
+    pub interactive: bool,
+    picked_app: OnceLock<String>,
+    picked_env: OnceLock<String>,
 }
 
 impl CommandContext {
@@ -213,12 +289,18 @@ impl CommandContext {
 
     /// Resolve the current app, preferring flag over context.
     pub fn resolve_app(&self) -> Option<&str> {
-        self.app.as_deref().or(self.config.context.app.as_deref())
+        self.app
+            .as_deref()
+            .or(self.config.context.app.as_deref())
+            .or(self.picked_app.get().map(String::as_str))
     }
 
     /// Resolve the current env, preferring flag over context.
     pub fn resolve_env(&self) -> Option<&str> {
-        self.env.as_deref().or(self.config.context.env.as_deref())
+        self.env
+            .as_deref()
+            .or(self.config.context.env.as_deref())
+            .or(self.picked_env.get().map(String::as_str))
     }
 
     /// Require an org to be specified.
@@ -229,9 +311,115 @@ impl CommandContext {
     }
 
     /// Require an app to be specified.
+    ///
+    /// In an interactive terminal a missing app is picked from the org's apps
+    /// and saved as the default context.
     pub fn require_app(&self) -> Result<&str> {
-        self.resolve_app().ok_or_else(|| {
-            anyhow::anyhow!("No application specified. Use --app or set a default context.")
-        })
+        if let Some(app) = self.resolve_app() {
+            return Ok(app);
+        }
+        if !self.interactive {
+            anyhow::bail!("No application specified. Use --app or set a default context.");
+        }
+        let app = block_on(self.pick_app())?;
+        Ok(self.picked_app.get_or_init(|| app))
     }
+
+    /// Require an env to be specified.
+    ///
+    /// In an interactive terminal a missing env is picked from the app's envs
+    /// and saved as the default context.
+    pub fn require_env(&self) -> Result<&str> {
+        if let Some(env) = self.resolve_env() {
+            return Ok(env);
+        }
+        if !self.interactive {
+            anyhow::bail!("No environment specified. Use --env or set a default context.");
+        }
+        let app = self.require_app()?;
+        let env = block_on(self.pick_env(app))?;
+        Ok(self.picked_env.get_or_init(|| env))
+    }
+
+    async fn pick_app(&self) -> Result<String> {
+        let client = self.client()?;
+        let org_id = crate::resolve::resolve_org_id(&client, self.require_org()?).await?;
+        let apps = crate::resolve::list_apps(&client, org_id).await?;
+        if apps.is_empty() {
+            anyhow::bail!(
+                "No applications in this organization. Create one with 'vt apps create'."
+            );
+        }
+
+        let items: Vec<PickItem> = apps
+            .iter()
+            .map(|app| PickItem {
+                label: app.name.clone(),
+                detail: app.id.clone(),
+            })
+            .collect();
+        let app = &apps[choose("app", &items)?];
+
+        self.remember(|context| {
+            context.org = Some(org_id.to_string());
+            context.app = Some(app.id.clone());
+        });
+        Ok(app.id.clone())
+    }
+
+    async fn pick_env(&self, app: &str) -> Result<String> {
+        let client = self.client()?;
+        let org_id = crate::resolve::resolve_org_id(&client, self.require_org()?).await?;
+        let app_id = crate::resolve::resolve_app_id(&client, org_id, app).await?;
+        let envs = crate::resolve::list_envs(&client, org_id, app_id).await?;
+        if envs.is_empty() {
+            anyhow::bail!("No environments in this application. Create one with 'vt envs create'.");
+        }
+
+        let items: Vec<PickItem> = envs
+            .iter()
+            .map(|env| PickItem {
+                label: env.name.clone(),
+                detail: env.id.clone(),
+            })
+            .collect();
+        let env = &envs[choose("env", &items)?];
+
+        self.remember(|context| {
+            context.org = Some(org_id.to_string());
+            context.app = Some(app_id.to_string());
+            context.env = Some(env.id.clone());
+        });
+        Ok(env.id.clone())
+    }
+
+    /// Save an interactive selection as the default context. Failing to save
+    /// only costs a prompt next time, so it is not an error.
+    fn remember(&self, update: impl FnOnce(&mut CliContext)) {
+        let mut config = self.config.clone();
+        update(&mut config.context);
+        if let Err(e) = config.save() {
+            eprintln!("warning: failed to save context: {e:#}");
+        }
+    }
+}
+
+/// Pick one of `items` (skipping the prompt when there is only one).
+fn choose(kind: &str, items: &[PickItem]) -> Result<usize> {
+    let index = if items.len() == 1 {
+        0
+    } else {
+        picker::pick(&format!("Select {kind}:"), items)?
+            .ok_or_else(|| anyhow::anyhow!("No {kind} selected. Use --{kind} to choose one."))?
+    };
+    eprintln!(
+        "Using {kind} {} ({}); saved as default context",
+        items[index].label, items[index].detail
+    );
+    Ok(index)
+}
+
+/// Run a future to completion from the synchronous context accessors.
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
 }
@@ -35,6 +35,14 @@ struct ListNodesArgs {
     /// Pagination cursor (opaque).
     #[arg(long)]
     cursor: Option<String>,
+
+    /// Sort key.
+    #[arg(long, value_parser = ["id", "created_at", "updated_at"])]
+    order_by: Option<String>,
+
+    /// Sort descending.
+    #[arg(long)]
+    desc: bool,
 }
 
 #[derive(Debug, Args)]
@@ -101,6 +109,12 @@ async fn list_nodes(ctx: CommandContext, args: ListNodesArgs) -> Result<()> {
     if let Some(cursor) = args.cursor.as_deref() {
         path.push_str(&format!("&cursor={cursor}"));
     }
+    if let Some(order_by) = args.order_by.as_deref() {
+        path.push_str(&format!("&order_by={order_by}"));
+    }
+    if args.desc {
+        path.push_str("&desc=true");
+    }
 
     let response: ListNodesResponse = client.get(&path).await?;
 
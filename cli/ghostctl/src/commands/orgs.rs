@@ -1,10 +1,16 @@
 //! Organization commands.
 
 use anyhow::Result;
+use chrono::SecondsFormat;
 use clap::{Args, Subcommand, ValueEnum};
-use serde::{Deserialize, Serialize};
+use plfm_api_types::orgs::{CreateOrgRequest, ListOrgsResponse, OrgResponse, UpdateOrgRequest};
+use serde::Serialize;
 use tabled::Tabled;
 
+use crate::client::models::{
+    CreateMemberRequest, ListMembersResponse, Member, StatusPage, StatusPageRequest,
+    UpdateMemberRequest,
+};
 use crate::error::CliError;
 use crate::output::{
     print_output, print_proto_single, print_receipt, print_receipt_no_resource, print_single,
@@ -39,6 +45,9 @@ enum OrgsSubcommand {
 
     /// Manage organization members.
     Members(MembersCommand),
+
+    /// Manage the public organization status page.
+    StatusPage(StatusPageCommand),
 }
 
 #[derive(Debug, Args)]
@@ -77,13 +86,14 @@ impl OrgsCommand {
             OrgsSubcommand::Get(args) => get_org(ctx, args).await,
             OrgsSubcommand::Use(args) => use_org(ctx, args).await,
             OrgsSubcommand::Members(cmd) => cmd.run(ctx).await,
+            OrgsSubcommand::StatusPage(cmd) => cmd.run(ctx).await,
         }
     }
 }
 
-/// Organization response from API.
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct OrgResponse {
+/// Table row for an organization.
+#[derive(Debug, Serialize, Tabled)]
+struct OrgRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -94,29 +104,18 @@ struct OrgResponse {
     created_at: String,
 }
 
-const ORG_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.Org";
-const LIST_ORGS_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.ListOrgsResponse";
-
-/// List response from API.
-#[derive(Debug, Serialize, Deserialize)]
-struct ListOrgsResponse {
-    items: Vec<OrgResponse>,
-    #[allow(dead_code)]
-    total: i64,
-}
-
-/// Create org request.
-#[derive(Debug, Serialize)]
-struct CreateOrgRequest {
-    name: String,
+impl From<OrgResponse> for OrgRow {
+    fn from(org: OrgResponse) -> Self {
+        Self {
+            id: org.id,
+            name: org.name,
+            created_at: org.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct UpdateOrgRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
-    expected_version: i32,
-}
+const ORG_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.Org";
+const LIST_ORGS_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.ListOrgsResponse";
 
 // =============================================================================
 // Org Members
@@ -184,8 +183,7 @@ struct RemoveMemberArgs {
     member_id: String,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, ValueEnum)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, Copy, ValueEnum)]
 enum MemberRoleArg {
     Owner,
     Admin,
@@ -193,40 +191,52 @@ enum MemberRoleArg {
     Readonly,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct MemberResponse {
+impl MemberRoleArg {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Admin => "admin",
+            Self::Developer => "developer",
+            Self::Readonly => "readonly",
+        }
+    }
+}
+
+/// Table row for an org member.
+#[derive(Debug, Serialize, Tabled)]
+struct MemberRow {
     #[tabled(rename = "ID")]
     id: String,
 
-    #[tabled(rename = "Email")]
-    email: String,
+    #[tabled(rename = "Email", display = "display_option")]
+    email: Option<String>,
 
     #[tabled(rename = "Role")]
     role: String,
 
-    #[tabled(rename = "Ver")]
-    resource_version: i32,
-
-    #[tabled(rename = "Updated")]
-    updated_at: String,
-}
+    #[tabled(rename = "Ver", display = "display_option")]
+    resource_version: Option<i64>,
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ListMembersResponse {
-    items: Vec<MemberResponse>,
-    next_cursor: Option<String>,
+    #[tabled(rename = "Updated", display = "display_option")]
+    updated_at: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct CreateMemberRequest {
-    email: String,
-    role: MemberRoleArg,
+impl From<Member> for MemberRow {
+    fn from(member: Member) -> Self {
+        Self {
+            id: member.id,
+            email: member.email,
+            role: member.role,
+            resource_version: member.resource_version,
+            updated_at: member.updated_at,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct UpdateMemberRequest {
-    role: MemberRoleArg,
-    expected_version: i32,
+fn display_option<T: std::fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "-".to_string(), ToString::to_string)
 }
 
 impl MembersCommand {
@@ -252,7 +262,10 @@ async fn list_members(ctx: CommandContext, args: ListMembersArgs) -> Result<()>
     let response: ListMembersResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<MemberRow> = response.items.into_iter().map(MemberRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_single(&response, ctx.format),
     }
 
@@ -265,7 +278,7 @@ async fn add_member(ctx: CommandContext, args: AddMemberArgs) -> Result<()> {
 
     let request = CreateMemberRequest {
         email: args.email,
-        role: args.role,
+        role: args.role.as_str().to_string(),
     };
     let path = format!("/v1/orgs/{org_id}/members");
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -273,13 +286,13 @@ async fn add_member(ctx: CommandContext, args: AddMemberArgs) -> Result<()> {
         None => crate::idempotency::default_idempotency_key("members.create", &path, &request)?,
     };
 
-    let response: MemberResponse = client
+    let response: Member = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
     let org_id_str = org_id.to_string();
     let member_id = response.id.clone();
-    let member_email = response.email.clone();
+    let member_email = response.email.clone().unwrap_or_default();
     let member_role = response.role.clone();
     let next = vec![
         ReceiptNextStep {
@@ -326,8 +339,8 @@ async fn update_member(ctx: CommandContext, args: UpdateMemberArgs) -> Result<()
     }
 
     let request = UpdateMemberRequest {
-        role: args.role,
-        expected_version: args.expected_version,
+        role: args.role.as_str().to_string(),
+        expected_version: args.expected_version.into(),
     };
     let path = format!("/v1/orgs/{org_id}/members/{}", args.member_id);
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -335,13 +348,13 @@ async fn update_member(ctx: CommandContext, args: UpdateMemberArgs) -> Result<()
         None => crate::idempotency::default_idempotency_key("members.update", &path, &request)?,
     };
 
-    let response: MemberResponse = client
+    let response: Member = client
         .patch_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
     let org_id_str = org_id.to_string();
     let member_id = response.id.clone();
-    let member_email = response.email.clone();
+    let member_email = response.email.clone().unwrap_or_default();
     let member_role = response.role.clone();
     let next = vec![
         ReceiptNextStep {
@@ -431,6 +444,205 @@ async fn remove_member(ctx: CommandContext, args: RemoveMemberArgs) -> Result<()
     Ok(())
 }
 
+// =============================================================================
+// Status Page
+// =============================================================================
+
+#[derive(Debug, Args)]
+struct StatusPageCommand {
+    #[command(subcommand)]
+    command: StatusPageSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum StatusPageSubcommand {
+    /// Show the org status page and its public link (admin only).
+    Get,
+
+    /// Create or replace the org status page (admin only).
+    Set(SetStatusPageArgs),
+
+    /// Issue a new public token, revoking shared links (admin only).
+    RotateToken,
+
+    /// Delete the org status page (admin only).
+    Delete,
+}
+
+#[derive(Debug, Args)]
+struct SetStatusPageArgs {
+    /// Page title.
+    #[arg(long)]
+    title: String,
+
+    /// Environment ID to show (repeatable; default: every env in the org).
+    #[arg(long = "env-id")]
+    env_ids: Vec<String>,
+
+    /// Keep the page configured but stop serving it publicly.
+    #[arg(long)]
+    disabled: bool,
+}
+
+impl StatusPageCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            StatusPageSubcommand::Get => get_status_page(ctx).await,
+            StatusPageSubcommand::Set(args) => set_status_page(ctx, args).await,
+            StatusPageSubcommand::RotateToken => rotate_status_page_token(ctx).await,
+            StatusPageSubcommand::Delete => delete_status_page(ctx).await,
+        }
+    }
+}
+
+fn status_page_not_found(e: CliError) -> CliError {
+    match e {
+        CliError::Api { status: 404, .. } => CliError::NotFound(
+            "Status page not found (create one with `vt orgs status-page set`)".to_string(),
+        ),
+        other => other,
+    }
+}
+
+async fn get_status_page(ctx: CommandContext) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let response: StatusPage = client
+        .get(&format!("/v1/orgs/{org_id}/status/page"))
+        .await
+        .map_err(status_page_not_found)?;
+
+    print_single(&response, ctx.format);
+    Ok(())
+}
+
+async fn set_status_page(ctx: CommandContext, args: SetStatusPageArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let request = StatusPageRequest {
+        title: args.title,
+        env_ids: Some(args.env_ids),
+        enabled: Some(!args.disabled),
+    };
+    let path = format!("/v1/orgs/{org_id}/status/page");
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key("status_page.put", &path, &request)?,
+    };
+
+    let response: StatusPage = client
+        .put_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!("vt orgs status-page get --org {}", org_id_str.clone()),
+    }];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Saved status page '{}' for org {} ({})",
+                response.title,
+                org_id_str,
+                if response.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            ),
+            status: "accepted",
+            kind: "orgs.status_page.set",
+            resource_key: "status_page",
+            resource: &response,
+            ids: serde_json::json!({ "org_id": org_id_str }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+async fn rotate_status_page_token(ctx: CommandContext) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    // Every rotation must reach the server, so no default idempotency key.
+    let response: StatusPage = client
+        .post_with_idempotency_key(
+            &format!("/v1/orgs/{org_id}/status/page/token"),
+            &serde_json::json!({}),
+            ctx.idempotency_key.as_deref(),
+        )
+        .await
+        .map_err(status_page_not_found)?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!("vt orgs status-page get --org {}", org_id_str.clone()),
+    }];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Rotated status page token for org {org_id_str}; previous links no longer work"
+            ),
+            status: "accepted",
+            kind: "orgs.status_page.rotate_token",
+            resource_key: "status_page",
+            resource: &response,
+            ids: serde_json::json!({ "org_id": org_id_str }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+async fn delete_status_page(ctx: CommandContext) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let path = format!("/v1/orgs/{org_id}/status/page");
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key_no_body("status_page.delete", &path),
+    };
+
+    client
+        .delete_with_idempotency_key(&path, Some(idempotency_key.as_str()))
+        .await
+        .map_err(status_page_not_found)?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!(
+            "vt orgs status-page set --org {} --title <title>",
+            org_id_str.clone()
+        ),
+    }];
+
+    print_receipt_no_resource(
+        ctx.format,
+        ReceiptNoResource {
+            message: format!("Deleted status page for org {org_id_str}"),
+            status: "accepted",
+            kind: "orgs.status_page.delete",
+            ids: serde_json::json!({ "org_id": org_id_str }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
 /// List all organizations.
 async fn list_orgs(ctx: CommandContext) -> Result<()> {
     let client = ctx.client()?;
@@ -438,7 +650,10 @@ async fn list_orgs(ctx: CommandContext) -> Result<()> {
     let response: ListOrgsResponse = client.get("/v1/orgs").await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<OrgRow> = response.items.into_iter().map(OrgRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_proto_single(&response, ctx.format, LIST_ORGS_TYPE_URL),
     }
     Ok(())
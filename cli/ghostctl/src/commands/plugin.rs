@@ -0,0 +1,301 @@
+//! Plugin commands (external `vt-<name>` executables).
+//!
+//! Any `vt <name> ...` that is not a built-in command runs `vt-<name>` from
+//! PATH with the remaining arguments, kubectl-style. The plugin inherits
+//! stdio and receives the resolved CLI context via environment variables:
+//!
+//! - `VT_API_URL`: control plane base URL
+//! - `VT_TOKEN`: bearer token (only when logged in)
+//! - `VT_ORG`, `VT_APP`, `VT_ENV`: resolved context (only when set)
+//! - `VT_FORMAT`: `table` or `json`
+//! - `VT_CLI_VERSION`: version of the invoking `vt`
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::error::exit_code;
+use crate::output::{print_info, print_output, OutputFormat};
+
+use super::{Cli, CommandContext};
+
+const PLUGIN_PREFIX: &str = "vt-";
+
+/// Manage CLI plugins.
+#[derive(Debug, Args)]
+pub struct PluginCommand {
+    #[command(subcommand)]
+    command: PluginSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum PluginSubcommand {
+    /// List plugins found on PATH.
+    List,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+struct PluginItem {
+    #[tabled(rename = "NAME")]
+    name: String,
+    #[tabled(rename = "PATH")]
+    path: String,
+    #[tabled(rename = "STATUS")]
+    status: &'static str,
+}
+
+impl PluginCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            PluginSubcommand::List => list(ctx),
+        }
+    }
+}
+
+fn list(ctx: CommandContext) -> Result<()> {
+    let builtins = builtin_commands();
+    let mut seen = HashSet::new();
+
+    let items: Vec<PluginItem> = discover(&path_dirs())
+        .into_iter()
+        .map(|(name, path)| {
+            // Built-ins always win; otherwise the first match on PATH does.
+            let status = if builtins.contains(&name) {
+                "shadowed by built-in"
+            } else if !seen.insert(name.clone()) {
+                "shadowed"
+            } else {
+                "ok"
+            };
+            PluginItem {
+                name,
+                path: path.display().to_string(),
+                status,
+            }
+        })
+        .collect();
+
+    if items.is_empty() && matches!(ctx.format, OutputFormat::Table) {
+        print_info("No plugins found. Install an executable named vt-<name> on PATH.");
+        return Ok(());
+    }
+
+    print_output(&items, ctx.format);
+    Ok(())
+}
+
+/// Run `vt-<name>` for an unknown subcommand and exit with its status.
+pub fn run_external(args: Vec<OsString>, ctx: CommandContext) -> Result<()> {
+    let (name, rest) = args
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("missing plugin name"))?;
+    let name = name
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("plugin name is not valid UTF-8"))?;
+    if !is_valid_plugin_name(name) {
+        anyhow::bail!("unknown command '{name}'");
+    }
+
+    let path = find_plugin(name, &path_dirs()).ok_or_else(|| {
+        anyhow::anyhow!(
+            "unknown command '{name}' (no {PLUGIN_PREFIX}{name} found on PATH; see `vt --help` or `vt plugin list`)"
+        )
+    })?;
+
+    let status = std::process::Command::new(&path)
+        .args(rest)
+        .envs(plugin_env(&ctx))
+        .status()
+        .with_context(|| format!("failed to run plugin {}", path.display()))?;
+
+    // Signals have no code; mirror the shell convention of 128 + signal.
+    let code = status.code().unwrap_or_else(|| {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            status.signal().map(|s| 128 + s).unwrap_or(1)
+        }
+        #[cfg(not(unix))]
+        {
+            1
+        }
+    });
+    if code != exit_code::SUCCESS {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Context passed to plugins as environment variables.
+fn plugin_env(ctx: &CommandContext) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("VT_API_URL", ctx.config.api_url().to_string()),
+        (
+            "VT_FORMAT",
+            match ctx.format {
+                OutputFormat::Json => "json",
+                OutputFormat::Table => "table",
+            }
+            .to_string(),
+        ),
+        ("VT_CLI_VERSION", env!("CARGO_PKG_VERSION").to_string()),
+    ];
+    if let Some(creds) = &ctx.credentials {
+        env.push(("VT_TOKEN", creds.token.clone()));
+    }
+    if let Some(org) = ctx.resolve_org() {
+        env.push(("VT_ORG", org.to_string()));
+    }
+    if let Some(app) = ctx.resolve_app() {
+        env.push(("VT_APP", app.to_string()));
+    }
+    if let Some(env_name) = ctx.resolve_env() {
+        env.push(("VT_ENV", env_name.to_string()));
+    }
+    env
+}
+
+/// Plugin names map to file names, so keep them to a safe character set.
+fn is_valid_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn builtin_commands() -> HashSet<String> {
+    let cmd = Cli::command();
+    cmd.get_subcommands()
+        .flat_map(|sub| {
+            std::iter::once(sub.get_name().to_string())
+                .chain(sub.get_all_aliases().map(str::to_string))
+        })
+        .chain(std::iter::once("help".to_string()))
+        .collect()
+}
+
+fn path_dirs() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default()
+}
+
+/// First `vt-<name>` executable in `dirs`.
+fn find_plugin(name: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| candidates(dir, name))
+        .find(|path| is_executable(path))
+}
+
+fn candidates(dir: &Path, name: &str) -> Vec<PathBuf> {
+    let base = dir.join(format!("{PLUGIN_PREFIX}{name}"));
+    if cfg!(windows) {
+        vec![base.with_extension("exe"), base]
+    } else {
+        vec![base]
+    }
+}
+
+/// Every `vt-*` executable in `dirs`, in PATH order (duplicates included).
+fn discover(dirs: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    let mut found = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut in_dir: Vec<(String, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let stem = if cfg!(windows) {
+                    path.file_stem()?.to_str()?.to_string()
+                } else {
+                    path.file_name()?.to_str()?.to_string()
+                };
+                let name = stem.strip_prefix(PLUGIN_PREFIX)?;
+                (is_valid_plugin_name(name) && is_executable(&path))
+                    .then(|| (name.to_string(), path))
+            })
+            .collect();
+        in_dir.sort();
+        found.extend(in_dir);
+    }
+    found
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_file(dir: &Path, name: &str, mode: u32) {
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("vt-plugin-test-{}-{}", label, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn discovers_executables_in_path_order() {
+        let first = temp_dir("first");
+        let second = temp_dir("second");
+        write_file(&first, "vt-lint", 0o755);
+        write_file(&first, "vt-notes.txt", 0o644);
+        write_file(&first, "other-tool", 0o755);
+        write_file(&second, "vt-lint", 0o755);
+        write_file(&second, "vt-db", 0o755);
+
+        let dirs = vec![first.clone(), second.clone()];
+        let names: Vec<String> = discover(&dirs).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, vec!["lint", "db", "lint"]);
+
+        assert_eq!(find_plugin("lint", &dirs), Some(first.join("vt-lint")));
+        assert_eq!(find_plugin("db", &dirs), Some(second.join("vt-db")));
+        assert_eq!(find_plugin("notes.txt", &dirs), None);
+
+        let _ = std::fs::remove_dir_all(first);
+        let _ = std::fs::remove_dir_all(second);
+    }
+
+    #[test]
+    fn plugin_names_are_restricted() {
+        assert!(is_valid_plugin_name("db-shell"));
+        assert!(!is_valid_plugin_name(""));
+        assert!(!is_valid_plugin_name("-x"));
+        assert!(!is_valid_plugin_name("../evil"));
+    }
+
+    #[test]
+    fn builtins_include_aliases() {
+        let builtins = builtin_commands();
+        assert!(builtins.contains("deploy"));
+        assert!(builtins.contains("apply"));
+        assert!(builtins.contains("plugin"));
+    }
+}
@@ -1,8 +1,12 @@
 //! Project commands.
 
 use anyhow::Result;
+use chrono::SecondsFormat;
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use plfm_api_types::projects::{
+    CreateProjectRequest, ListProjectsResponse, ProjectResponse, UpdateProjectRequest,
+};
+use serde::Serialize;
 use tabled::Tabled;
 
 use crate::error::CliError;
@@ -78,8 +82,9 @@ impl ProjectsCommand {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct ProjectResponse {
+/// Table row for a project.
+#[derive(Debug, Serialize, Tabled)]
+struct ProjectRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -96,28 +101,24 @@ struct ProjectResponse {
     updated_at: String,
 }
 
+impl From<ProjectResponse> for ProjectRow {
+    fn from(project: ProjectResponse) -> Self {
+        Self {
+            id: project.id,
+            org_id: project.org_id,
+            name: project.name,
+            resource_version: project.resource_version,
+            updated_at: project
+                .updated_at
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+}
+
 const PROJECT_TYPE_URL: &str = "type.googleapis.com/plfm.controlplane.v1.Project";
 const LIST_PROJECTS_TYPE_URL: &str =
     "type.googleapis.com/plfm.controlplane.v1.ListProjectsResponse";
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ListProjectsResponse {
-    items: Vec<ProjectResponse>,
-    next_cursor: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-struct CreateProjectRequest {
-    name: String,
-}
-
-#[derive(Debug, Serialize)]
-struct UpdateProjectRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
-    expected_version: i32,
-}
-
 async fn list_projects(ctx: CommandContext, args: ListProjectsArgs) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
@@ -130,7 +131,10 @@ async fn list_projects(ctx: CommandContext, args: ListProjectsArgs) -> Result<()
     let response: ListProjectsResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<ProjectRow> = response.items.into_iter().map(ProjectRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_proto_single(&response, ctx.format, LIST_PROJECTS_TYPE_URL),
     }
 
@@ -4,9 +4,10 @@ use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tabled::Tabled;
 
+use crate::client::models::{CreateReleaseRequest, ListReleasesResponse, Release, ReleaseMetadata};
 use crate::error::CliError;
 use crate::output::{
     print_output, print_receipt, print_single, OutputFormat, Receipt, ReceiptNextStep,
@@ -30,6 +31,7 @@ enum ReleasesSubcommand {
     Create(CreateReleaseArgs),
 
     /// Get release details.
+    #[command(alias = "show")]
     Get(GetReleaseArgs),
 }
 
@@ -42,6 +44,22 @@ struct ListReleasesArgs {
     /// Pagination cursor (opaque).
     #[arg(long)]
     cursor: Option<String>,
+
+    /// Sort key.
+    #[arg(long, value_parser = ["id", "created_at"])]
+    order_by: Option<String>,
+
+    /// Sort descending.
+    #[arg(long)]
+    desc: bool,
+
+    /// Only releases built from this git ref.
+    #[arg(long)]
+    git_ref: Option<String>,
+
+    /// Only releases built from this git commit.
+    #[arg(long)]
+    git_commit: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -64,6 +82,56 @@ struct CreateReleaseArgs {
     /// Manifest hash (sha256:...).
     #[arg(long)]
     manifest_hash: Option<String>,
+
+    #[command(flatten)]
+    metadata: ReleaseMetadataArgs,
+}
+
+/// Source and build provenance recorded with a release.
+#[derive(Debug, Clone, Args)]
+pub(super) struct ReleaseMetadataArgs {
+    /// Git ref the image was built from (branch, tag, or PR head ref).
+    #[arg(long)]
+    git_ref: Option<String>,
+
+    /// Git commit SHA the image was built from.
+    #[arg(long)]
+    git_commit: Option<String>,
+
+    /// Git branch the commit was on.
+    #[arg(long)]
+    git_branch: Option<String>,
+
+    /// URL of the CI run that built the image.
+    #[arg(long)]
+    ci_run_url: Option<String>,
+
+    /// Digest of the image's SBOM (sha256:...).
+    #[arg(long)]
+    sbom_digest: Option<String>,
+
+    /// Builder identity (CI system, workflow, or build service).
+    #[arg(long)]
+    builder: Option<String>,
+}
+
+impl ReleaseMetadataArgs {
+    /// Release metadata for the request, or `None` when no flag was given.
+    pub(super) fn into_metadata(self) -> Option<ReleaseMetadata> {
+        let metadata = ReleaseMetadata {
+            git_ref: self.git_ref,
+            git_commit: self.git_commit.map(|commit| commit.to_ascii_lowercase()),
+            git_branch: self.git_branch,
+            ci_run_url: self.ci_run_url,
+            sbom_digest: self.sbom_digest,
+            builder: self.builder,
+        };
+        if metadata == ReleaseMetadata::default() {
+            None
+        } else {
+            Some(metadata)
+        }
+    }
 }
 
 #[derive(Debug, Args)]
@@ -82,9 +150,9 @@ impl ReleasesCommand {
     }
 }
 
-/// Release response from API.
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct ReleaseResponse {
+/// Table row for a release.
+#[derive(Debug, Serialize, Tabled)]
+struct ReleaseRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -101,32 +169,44 @@ struct ReleaseResponse {
     image_digest: String,
 
     #[tabled(rename = "Manifest Ver")]
-    manifest_schema_version: i32,
+    manifest_schema_version: i64,
 
     #[tabled(rename = "Manifest Hash")]
     manifest_hash: String,
 
+    #[tabled(rename = "Git Ref")]
+    git_ref: String,
+
+    #[tabled(rename = "Commit")]
+    git_commit: String,
+
     #[tabled(rename = "Ver")]
-    resource_version: i32,
+    resource_version: i64,
 
     #[tabled(rename = "Created")]
     created_at: String,
 }
 
-/// List response from API.
-#[derive(Debug, Serialize, Deserialize)]
-struct ListReleasesResponse {
-    items: Vec<ReleaseResponse>,
-    next_cursor: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-struct CreateReleaseRequest {
-    image_ref: String,
-    image_digest: String,
-    manifest_schema_version: i32,
-    manifest_hash: String,
-    command: Vec<String>,
+impl From<Release> for ReleaseRow {
+    fn from(release: Release) -> Self {
+        Self {
+            id: release.id,
+            org_id: release.org_id,
+            app_id: release.app_id,
+            image_ref: release.image_ref,
+            image_digest: release.image_digest,
+            manifest_schema_version: release.manifest_schema_version,
+            manifest_hash: release.manifest_hash,
+            git_ref: release.metadata.git_ref.unwrap_or_else(|| "-".to_string()),
+            git_commit: release
+                .metadata
+                .git_commit
+                .map(|commit| commit.chars().take(12).collect())
+                .unwrap_or_else(|| "-".to_string()),
+            resource_version: release.resource_version,
+            created_at: release.created_at,
+        }
+    }
 }
 
 /// List all releases for the current app.
@@ -142,11 +222,26 @@ async fn list_releases(ctx: CommandContext, args: ListReleasesArgs) -> Result<()
     if let Some(cursor) = args.cursor.as_deref() {
         path.push_str(&format!("&cursor={cursor}"));
     }
+    if let Some(order_by) = args.order_by.as_deref() {
+        path.push_str(&format!("&order_by={order_by}"));
+    }
+    if args.desc {
+        path.push_str("&desc=true");
+    }
+    if let Some(git_ref) = args.git_ref.as_deref() {
+        path.push_str(&format!("&git_ref={git_ref}"));
+    }
+    if let Some(git_commit) = args.git_commit.as_deref() {
+        path.push_str(&format!("&git_commit={git_commit}"));
+    }
 
     let response: ListReleasesResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<ReleaseRow> = response.items.into_iter().map(ReleaseRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_single(&response, ctx.format),
     }
     Ok(())
@@ -183,9 +278,10 @@ async fn create_release(ctx: CommandContext, args: CreateReleaseArgs) -> Result<
     let request = CreateReleaseRequest {
         image_ref: args.image_ref.clone(),
         image_digest: args.image_digest.clone(),
-        manifest_schema_version: args.manifest_schema_version,
+        manifest_schema_version: Some(args.manifest_schema_version.into()),
         manifest_hash,
         command,
+        metadata: args.metadata.into_metadata(),
     };
     let path = format!("/v1/orgs/{}/apps/{}/releases", org, app);
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -193,7 +289,7 @@ async fn create_release(ctx: CommandContext, args: CreateReleaseArgs) -> Result<
         None => crate::idempotency::default_idempotency_key("releases.create", &path, &request)?,
     };
 
-    let response: ReleaseResponse = client
+    let response: Release = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -259,7 +355,7 @@ async fn get_release(ctx: CommandContext, args: GetReleaseArgs) -> Result<()> {
     let org = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app = crate::resolve::resolve_app_id(&client, org, ctx.require_app()?).await?;
 
-    let response: ReleaseResponse = client
+    let response: Release = client
         .get(&format!(
             "/v1/orgs/{}/apps/{}/releases/{}",
             org, app, args.release
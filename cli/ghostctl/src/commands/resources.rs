@@ -0,0 +1,217 @@
+//! Process resource override commands.
+//!
+//! Overrides cpu/memory/disk for one process type of the current env without
+//! a new release. Every change rolls that process type's instances.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::client::models::{ProcessResources, PutProcessResourcesRequest};
+use crate::error::CliError;
+use crate::output::{
+    print_output, print_receipt, print_single, OutputFormat, Receipt, ReceiptNextStep,
+};
+
+use super::volumes::parse_size;
+use super::CommandContext;
+
+/// Process resource override commands.
+#[derive(Debug, Args)]
+pub struct ResourcesCommand {
+    #[command(subcommand)]
+    command: ResourcesSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ResourcesSubcommand {
+    /// Show a process type's resource overrides.
+    Get(GetResourcesArgs),
+
+    /// Override resources for a process type. Unset flags keep their current override.
+    Set(SetResourcesArgs),
+
+    /// Clear all overrides for a process type (release and scale values apply).
+    Reset(GetResourcesArgs),
+}
+
+#[derive(Debug, Args)]
+struct GetResourcesArgs {
+    /// Process type (e.g. web).
+    process_type: String,
+}
+
+#[derive(Debug, Args)]
+struct SetResourcesArgs {
+    /// Process type (e.g. web).
+    process_type: String,
+
+    /// vCPU request (e.g. 0.5, 2).
+    #[arg(long)]
+    cpu: Option<f64>,
+
+    /// Memory limit: bytes, or a number with a unit (e.g. 512MiB, 2GiB).
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    memory: Option<i64>,
+
+    /// Scratch disk: bytes, or a number with a unit (e.g. 10GiB).
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    disk: Option<i64>,
+}
+
+/// Table row for a process type's overrides.
+#[derive(Debug, Serialize, Tabled)]
+struct ResourcesRow {
+    #[tabled(rename = "Process")]
+    process_type: String,
+    #[tabled(rename = "CPU")]
+    cpu: String,
+    #[tabled(rename = "Memory")]
+    memory: String,
+    #[tabled(rename = "Disk")]
+    disk: String,
+    #[tabled(rename = "Version")]
+    version: i64,
+}
+
+impl From<&ProcessResources> for ResourcesRow {
+    fn from(resources: &ProcessResources) -> Self {
+        let or_default = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        Self {
+            process_type: resources.process_type.clone(),
+            cpu: or_default(resources.cpu_request.map(|cpu| cpu.to_string())),
+            memory: or_default(resources.memory_limit_bytes.map(|b| b.to_string())),
+            disk: or_default(resources.ephemeral_disk_bytes.map(|b| b.to_string())),
+            version: resources.resource_version,
+        }
+    }
+}
+
+impl ResourcesCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            ResourcesSubcommand::Get(args) => get_resources(ctx, args).await,
+            ResourcesSubcommand::Set(args) => {
+                if args.cpu.is_none() && args.memory.is_none() && args.disk.is_none() {
+                    return Err(CliError::Validation(
+                        "Specify at least one of --cpu, --memory or --disk".to_string(),
+                    )
+                    .into());
+                }
+                put_resources(ctx, &args.process_type, "resources.set", |current| {
+                    PutProcessResourcesRequest {
+                        cpu_request: args.cpu.or(current.cpu_request),
+                        memory_limit_bytes: args.memory.or(current.memory_limit_bytes),
+                        ephemeral_disk_bytes: args.disk.or(current.ephemeral_disk_bytes),
+                        expected_version: Some(current.resource_version),
+                    }
+                })
+                .await
+            }
+            ResourcesSubcommand::Reset(args) => {
+                put_resources(ctx, &args.process_type, "resources.reset", |current| {
+                    PutProcessResourcesRequest {
+                        expected_version: Some(current.resource_version),
+                        ..PutProcessResourcesRequest::default()
+                    }
+                })
+                .await
+            }
+        }
+    }
+}
+
+async fn get_resources(ctx: CommandContext, args: GetResourcesArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
+    let env_id =
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
+
+    let path = format!(
+        "/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{}/resources",
+        args.process_type
+    );
+    let response: ProcessResources = client.get(&path).await?;
+
+    match ctx.format {
+        OutputFormat::Json => print_single(&response, ctx.format),
+        OutputFormat::Table => print_output(&[ResourcesRow::from(&response)], ctx.format),
+    }
+
+    Ok(())
+}
+
+/// Read the current overrides, then replace them with `build(current)`.
+async fn put_resources(
+    ctx: CommandContext,
+    process_type: &str,
+    kind: &'static str,
+    build: impl FnOnce(&ProcessResources) -> PutProcessResourcesRequest,
+) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
+    let env_id =
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
+
+    let path =
+        format!("/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{process_type}/resources");
+    let current: ProcessResources = client.get(&path).await?;
+    let request = build(&current);
+
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => {
+            crate::idempotency::default_idempotency_key("process_resources.put", &path, &request)?
+        }
+    };
+
+    let response: ProcessResources = client
+        .put_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let org_id_str = org_id.to_string();
+    let app_id_str = app_id.to_string();
+    let env_id_str = env_id.to_string();
+    let next = vec![
+        ReceiptNextStep {
+            label: "Next",
+            cmd: format!(
+                "vt --org {} --app {} --env {} status",
+                org_id_str, app_id_str, env_id_str
+            ),
+        },
+        ReceiptNextStep {
+            label: "Debug",
+            cmd: format!(
+                "vt events tail --org {} --app {} --env {}",
+                org_id_str, app_id_str, env_id_str
+            ),
+        },
+    ];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Updated resources for {} in {}/{}/{} (version {}); its instances will be replaced",
+                process_type, org_id_str, app_id_str, env_id_str, response.resource_version
+            ),
+            status: "accepted",
+            kind,
+            resource_key: "resources",
+            resource: &response,
+            ids: serde_json::json!({
+                "org_id": org_id_str,
+                "app_id": app_id_str,
+                "env_id": env_id_str,
+                "process_type": process_type,
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
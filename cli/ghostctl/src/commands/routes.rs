@@ -2,9 +2,13 @@
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tabled::Tabled;
 
+use crate::client::models::{
+    CreateRouteRequest, ListRoutesResponse, Route, RouteAccessPolicy, RouteIngressStatus,
+    RouteVerification, UpdateRouteRequest,
+};
 use crate::error::CliError;
 use crate::output::{
     print_output, print_receipt, print_receipt_no_resource, print_single, OutputFormat, Receipt,
@@ -36,6 +40,9 @@ enum RoutesSubcommand {
 
     /// Delete a route.
     Delete(DeleteRouteArgs),
+
+    /// Check the hostname's DNS ownership record now.
+    Verify(VerifyRouteArgs),
 }
 
 #[derive(Debug, Args)]
@@ -64,7 +71,7 @@ struct CreateRouteArgs {
     #[arg(long)]
     listen_port: i32,
 
-    /// Protocol hint: tls_passthrough or tcp_raw.
+    /// Protocol hint: tls_passthrough, tls_terminate, or tcp_raw.
     #[arg(long)]
     protocol_hint: String,
 
@@ -87,6 +94,27 @@ struct CreateRouteArgs {
     /// Require a dedicated IPv4 allocation for this route.
     #[arg(long, default_value_t = false)]
     ipv4_required: bool,
+
+    /// Serve only on the overlay network, for service-to-service traffic.
+    /// The hostname must end in the internal suffix (default `.internal`).
+    #[arg(long, default_value_t = false)]
+    internal: bool,
+
+    /// Admit only clients from this CIDR (repeatable).
+    #[arg(long = "allow-cidr")]
+    allow_cidrs: Vec<String>,
+
+    /// Refuse clients from this CIDR (repeatable).
+    #[arg(long = "deny-cidr")]
+    deny_cidrs: Vec<String>,
+
+    /// Admit only TLS clients with this JA3 hash or JA4 fingerprint (repeatable).
+    #[arg(long = "allow-fingerprint")]
+    allow_fingerprints: Vec<String>,
+
+    /// Refuse TLS clients with this JA3 hash or JA4 fingerprint (repeatable).
+    #[arg(long = "deny-fingerprint")]
+    deny_fingerprints: Vec<String>,
 }
 
 #[derive(Debug, Args)]
@@ -117,6 +145,27 @@ struct UpdateRouteArgs {
     /// Whether IPv4 is required.
     #[arg(long)]
     ipv4_required: Option<bool>,
+
+    /// Access policy flags replace the route's whole policy.
+    /// Admit only clients from this CIDR (repeatable).
+    #[arg(long = "allow-cidr")]
+    allow_cidrs: Vec<String>,
+
+    /// Refuse clients from this CIDR (repeatable).
+    #[arg(long = "deny-cidr")]
+    deny_cidrs: Vec<String>,
+
+    /// Admit only TLS clients with this JA3 hash or JA4 fingerprint (repeatable).
+    #[arg(long = "allow-fingerprint")]
+    allow_fingerprints: Vec<String>,
+
+    /// Refuse TLS clients with this JA3 hash or JA4 fingerprint (repeatable).
+    #[arg(long = "deny-fingerprint")]
+    deny_fingerprints: Vec<String>,
+
+    /// Remove the route's access policy.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["allow_cidrs", "deny_cidrs", "allow_fingerprints", "deny_fingerprints"])]
+    clear_access_policy: bool,
 }
 
 #[derive(Debug, Args)]
@@ -125,8 +174,15 @@ struct DeleteRouteArgs {
     route: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct RouteResponse {
+#[derive(Debug, Args)]
+struct VerifyRouteArgs {
+    /// Route ID.
+    route: String,
+}
+
+/// Table row for a route.
+#[derive(Debug, Serialize, Tabled)]
+struct RouteRow {
     #[tabled(rename = "ID")]
     id: String,
 
@@ -137,7 +193,7 @@ struct RouteResponse {
     hostname: String,
 
     #[tabled(rename = "Listen")]
-    listen_port: i32,
+    listen_port: i64,
 
     #[tabled(rename = "Proto")]
     protocol_hint: String,
@@ -146,7 +202,7 @@ struct RouteResponse {
     backend_process_type: String,
 
     #[tabled(rename = "Port")]
-    backend_port: i32,
+    backend_port: i64,
 
     #[tabled(rename = "PP")]
     proxy_protocol: String,
@@ -154,44 +210,121 @@ struct RouteResponse {
     #[tabled(rename = "IPv4")]
     ipv4_required: bool,
 
-    #[tabled(rename = "Ver")]
-    resource_version: i32,
+    #[tabled(rename = "Internal")]
+    internal: bool,
+
+    #[tabled(rename = "Verified")]
+    verification: String,
 
-    #[tabled(rename = "Updated")]
-    updated_at: String,
+    #[tabled(rename = "Live")]
+    ingress: String,
+
+    #[tabled(rename = "Ver", display = "display_option")]
+    resource_version: Option<i64>,
+
+    #[tabled(rename = "Updated", display = "display_option")]
+    updated_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ListRoutesResponse {
-    items: Vec<RouteResponse>,
-    next_cursor: Option<String>,
+impl From<Route> for RouteRow {
+    fn from(route: Route) -> Self {
+        Self {
+            verification: verification_status(&route).to_string(),
+            ingress: match &route.ingress {
+                Some(RouteIngressStatus {
+                    live,
+                    total,
+                    updated_at: Some(_),
+                }) => format!("{live}/{total}"),
+                _ => "-".to_string(),
+            },
+            id: route.id,
+            env_id: route.env_id,
+            hostname: route.hostname,
+            listen_port: route.listen_port,
+            protocol_hint: route.protocol_hint,
+            backend_process_type: route.backend_process_type,
+            backend_port: route.backend_port,
+            proxy_protocol: route.proxy_protocol,
+            ipv4_required: route.ipv4_required.unwrap_or(false),
+            internal: route.internal.unwrap_or(false),
+            resource_version: route.resource_version,
+            updated_at: route.updated_at,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct CreateRouteRequest {
-    hostname: String,
-    listen_port: i32,
-    protocol_hint: String,
-    backend_process_type: String,
-    backend_port: i32,
-    proxy_protocol: String,
-    backend_expects_proxy_protocol: bool,
-    ipv4_required: bool,
+fn display_option<T: std::fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "-".to_string(), ToString::to_string)
 }
 
-#[derive(Debug, Serialize)]
-struct UpdateRouteRequest {
-    expected_version: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    backend_process_type: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    backend_port: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    proxy_protocol: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    backend_expects_proxy_protocol: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    ipv4_required: Option<bool>,
+/// The access policy built from allow/deny flags, or `None` when no flag
+/// was given.
+fn access_policy_from_flags(
+    allow_cidrs: &[String],
+    deny_cidrs: &[String],
+    allow_fingerprints: &[String],
+    deny_fingerprints: &[String],
+) -> Option<RouteAccessPolicy> {
+    let list = |values: &[String]| (!values.is_empty()).then(|| values.to_vec());
+    let policy = RouteAccessPolicy {
+        allow_cidrs: list(allow_cidrs),
+        deny_cidrs: list(deny_cidrs),
+        allow_fingerprints: list(allow_fingerprints),
+        deny_fingerprints: list(deny_fingerprints),
+    };
+    (policy != RouteAccessPolicy::default()).then_some(policy)
+}
+
+/// Hostname ownership state; routes from servers that predate verification
+/// are verified.
+fn verification_status(route: &Route) -> &str {
+    route
+        .verification
+        .as_ref()
+        .map_or("verified", |verification| verification.status.as_str())
+}
+
+/// DNS instructions for an unverified hostname, one line each.
+fn verification_instructions(verification: &RouteVerification, hostname: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let (Some(name), Some(value)) = (
+        &verification.txt_record_name,
+        &verification.txt_record_value,
+    ) {
+        lines.push(format!("TXT   {name} \"{value}\""));
+    }
+    if let Some(target) = &verification.cname_target {
+        lines.push(format!("CNAME {hostname} -> {target}"));
+    }
+    lines
+}
+
+/// Tell the user how to prove ownership of a hostname that is not live yet.
+fn print_verification_instructions(route: &Route, format: OutputFormat) {
+    if !matches!(format, OutputFormat::Table) || verification_status(route) == "verified" {
+        return;
+    }
+    let Some(verification) = &route.verification else {
+        return;
+    };
+    if let Some(error) = &verification.error {
+        eprintln!("Verification failed: {error}");
+    }
+    let lines = verification_instructions(verification, &route.hostname);
+    if lines.is_empty() {
+        return;
+    }
+    eprintln!(
+        "Route '{}' is not served until DNS proves ownership. Create one of:",
+        route.hostname
+    );
+    for line in lines {
+        eprintln!("  {line}");
+    }
+    eprintln!("Then run: vt routes verify {}", route.id);
 }
 
 impl RoutesCommand {
@@ -202,22 +335,17 @@ impl RoutesCommand {
             RoutesSubcommand::Create(args) => create_route(ctx, args).await,
             RoutesSubcommand::Update(args) => update_route(ctx, args).await,
             RoutesSubcommand::Delete(args) => delete_route(ctx, args).await,
+            RoutesSubcommand::Verify(args) => verify_route(ctx, args).await,
         }
     }
 }
 
-fn require_env(ctx: &CommandContext) -> Result<&str> {
-    ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })
-}
-
 async fn list_routes(ctx: CommandContext, args: ListRoutesArgs) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let mut path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/routes?limit={}",
@@ -230,7 +358,10 @@ async fn list_routes(ctx: CommandContext, args: ListRoutesArgs) -> Result<()> {
     let response: ListRoutesResponse = client.get(&path).await?;
 
     match ctx.format {
-        OutputFormat::Table => print_output(&response.items, ctx.format),
+        OutputFormat::Table => {
+            let rows: Vec<RouteRow> = response.items.into_iter().map(RouteRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
         OutputFormat::Json => print_single(&response, ctx.format),
     }
 
@@ -242,9 +373,9 @@ async fn get_route(ctx: CommandContext, args: GetRouteArgs) -> Result<()> {
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
-    let response: RouteResponse = client
+    let response: Route = client
         .get(&format!(
             "/v1/orgs/{}/apps/{}/envs/{}/routes/{}",
             org_id, app_id, env_id, args.route
@@ -258,6 +389,7 @@ async fn get_route(ctx: CommandContext, args: GetRouteArgs) -> Result<()> {
         })?;
 
     print_single(&response, ctx.format);
+    print_verification_instructions(&response, ctx.format);
     Ok(())
 }
 
@@ -266,17 +398,24 @@ async fn create_route(ctx: CommandContext, args: CreateRouteArgs) -> Result<()>
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let request = CreateRouteRequest {
         hostname: args.hostname.clone(),
-        listen_port: args.listen_port,
+        listen_port: args.listen_port.into(),
         protocol_hint: args.protocol_hint.clone(),
         backend_process_type: args.backend_process_type.clone(),
-        backend_port: args.backend_port,
-        proxy_protocol: args.proxy_protocol.clone(),
-        backend_expects_proxy_protocol: args.backend_expects_proxy_protocol,
-        ipv4_required: args.ipv4_required,
+        backend_port: args.backend_port.into(),
+        proxy_protocol: Some(args.proxy_protocol.clone()),
+        backend_expects_proxy_protocol: Some(args.backend_expects_proxy_protocol),
+        ipv4_required: Some(args.ipv4_required),
+        internal: Some(args.internal),
+        access_policy: access_policy_from_flags(
+            &args.allow_cidrs,
+            &args.deny_cidrs,
+            &args.allow_fingerprints,
+            &args.deny_fingerprints,
+        ),
     };
     let path = format!("/v1/orgs/{}/apps/{}/envs/{}/routes", org_id, app_id, env_id);
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -284,7 +423,7 @@ async fn create_route(ctx: CommandContext, args: CreateRouteArgs) -> Result<()>
         None => crate::idempotency::default_idempotency_key("routes.create", &path, &request)?,
     };
 
-    let response: RouteResponse = client
+    let response: Route = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -347,6 +486,7 @@ async fn create_route(ctx: CommandContext, args: CreateRouteArgs) -> Result<()>
             next: &next,
         },
     );
+    print_verification_instructions(&response, ctx.format);
 
     Ok(())
 }
@@ -356,15 +496,25 @@ async fn update_route(ctx: CommandContext, args: UpdateRouteArgs) -> Result<()>
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let request = UpdateRouteRequest {
-        expected_version: args.expected_version,
+        expected_version: Some(args.expected_version.into()),
         backend_process_type: args.backend_process_type.clone(),
-        backend_port: args.backend_port,
+        backend_port: args.backend_port.map(i64::from),
         proxy_protocol: args.proxy_protocol.clone(),
         backend_expects_proxy_protocol: args.backend_expects_proxy_protocol,
         ipv4_required: args.ipv4_required,
+        access_policy: if args.clear_access_policy {
+            Some(RouteAccessPolicy::default())
+        } else {
+            access_policy_from_flags(
+                &args.allow_cidrs,
+                &args.deny_cidrs,
+                &args.allow_fingerprints,
+                &args.deny_fingerprints,
+            )
+        },
     };
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/routes/{}",
@@ -375,7 +525,7 @@ async fn update_route(ctx: CommandContext, args: UpdateRouteArgs) -> Result<()>
         None => crate::idempotency::default_idempotency_key("routes.update", &path, &request)?,
     };
 
-    let response: RouteResponse = client
+    let response: Route = client
         .patch_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await
         .map_err(|e| match e {
@@ -438,7 +588,7 @@ async fn delete_route(ctx: CommandContext, args: DeleteRouteArgs) -> Result<()>
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/routes/{}",
@@ -502,3 +652,73 @@ async fn delete_route(ctx: CommandContext, args: DeleteRouteArgs) -> Result<()>
 
     Ok(())
 }
+
+async fn verify_route(ctx: CommandContext, args: VerifyRouteArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
+    let env_id =
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
+
+    let path = format!(
+        "/v1/orgs/{}/apps/{}/envs/{}/routes/{}/verify",
+        org_id, app_id, env_id, args.route
+    );
+
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key_no_body("routes.verify", &path),
+    };
+
+    let response: Route = client
+        .post_with_idempotency_key(
+            &path,
+            &serde_json::json!({}),
+            Some(idempotency_key.as_str()),
+        )
+        .await
+        .map_err(|e| match e {
+            CliError::Api { status: 404, .. } => {
+                CliError::NotFound(format!("Route '{}' not found", args.route))
+            }
+            other => other,
+        })?;
+
+    let route_id = response.id.clone();
+    let org_id_str = org_id.to_string();
+    let app_id_str = app_id.to_string();
+    let env_id_str = env_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!(
+            "vt --org {} --app {} --env {} routes list",
+            org_id_str.clone(),
+            app_id_str.clone(),
+            env_id_str.clone()
+        ),
+    }];
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message: format!(
+                "Verified route '{}' ({})",
+                response.hostname,
+                route_id.as_str()
+            ),
+            status: "accepted",
+            kind: "routes.verify",
+            resource_key: "route",
+            resource: &response,
+            ids: serde_json::json!({
+                "route_id": route_id,
+                "env_id": env_id_str,
+                "app_id": app_id_str,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
@@ -1,15 +1,28 @@
 //! Scale command (set environment scale).
 
+use std::time::Duration;
+
 use anyhow::Result;
 use clap::Args;
 use serde::{Deserialize, Serialize};
 use tabled::Tabled;
+use tokio::time::{sleep, Instant};
 
+use crate::client::models::{ProcessScale, ScaleState, ScaleUpdateRequest};
+use crate::client::ApiClient;
 use crate::error::CliError;
-use crate::output::{print_output, print_receipt, OutputFormat, Receipt, ReceiptNextStep};
+use crate::output::{
+    print_info, print_output, print_receipt, print_success, OutputFormat, Receipt, ReceiptNextStep,
+};
 
 use super::CommandContext;
 
+/// Default timeout for waiting on scale convergence.
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Polling interval for env status checks.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Scale command - set the number of instances for a process type.
 #[derive(Debug, Args)]
 pub struct ScaleCommand {
@@ -17,31 +30,48 @@ pub struct ScaleCommand {
     /// Can be specified multiple times.
     #[arg(required = true)]
     processes: Vec<String>,
+
+    /// Wait until the environment has the desired number of ready instances.
+    #[arg(long)]
+    wait: bool,
+
+    /// Timeout for waiting (e.g., "5m", "300s"). Default is 5 minutes.
+    #[arg(long, visible_alias = "timeout", value_name = "DURATION")]
+    wait_timeout: Option<String>,
+
+    /// Why the scale is being changed (recorded on the scale event).
+    #[arg(long, short = 'm')]
+    reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct ScaleState {
-    #[allow(dead_code)]
-    env_id: String,
-    processes: Vec<ProcessScale>,
-    #[allow(dead_code)]
-    updated_at: String,
-    #[serde(default)]
-    resource_version: Option<i32>,
+/// Instance counts from the env status endpoint.
+#[derive(Debug, Deserialize)]
+struct EnvStatusCounts {
+    instances: InstanceCounts,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Tabled)]
-struct ProcessScale {
-    #[tabled(rename = "Process")]
-    process_type: String,
-    #[tabled(rename = "Desired")]
+#[derive(Debug, Deserialize)]
+struct InstanceCounts {
     desired: i32,
+    ready: i32,
+    booting: i32,
+    draining: i32,
+    failed: i32,
+}
+
+impl InstanceCounts {
+    fn converged(&self) -> bool {
+        self.ready == self.desired && self.booting == 0 && self.draining == 0
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct ScaleUpdateRequest {
-    processes: Vec<ProcessScale>,
-    expected_version: i32,
+/// Table row for a process type's scale.
+#[derive(Debug, Serialize, Tabled)]
+struct ProcessScaleRow {
+    #[tabled(rename = "Process")]
+    process_type: String,
+    #[tabled(rename = "Desired")]
+    desired: i64,
 }
 
 impl ScaleCommand {
@@ -50,9 +80,7 @@ impl ScaleCommand {
 
         let org_ident = ctx.require_org()?;
         let app_ident = ctx.require_app()?;
-        let env_ident = ctx.resolve_env().ok_or_else(|| {
-            anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-        })?;
+        let env_ident = ctx.require_env()?;
         let org_id = crate::resolve::resolve_org_id(&client, org_ident).await?;
         let app_id = crate::resolve::resolve_app_id(&client, org_id, app_ident).await?;
         let env_id = crate::resolve::resolve_env_id(&client, org_id, app_id, env_ident).await?;
@@ -61,42 +89,51 @@ impl ScaleCommand {
         let mut process_counts = std::collections::BTreeMap::<String, i32>::new();
         for spec in &self.processes {
             let Some((process_type_raw, count_raw)) = spec.split_once('=') else {
-                return Err(anyhow::anyhow!(
+                return Err(CliError::Validation(format!(
                     "Invalid process specification '{}'. Use format TYPE=COUNT (e.g., web=3)",
                     spec
-                ));
+                ))
+                .into());
             };
 
             let process_type = process_type_raw.trim().to_string();
             if process_type.is_empty() {
-                return Err(anyhow::anyhow!(
+                return Err(CliError::Validation(format!(
                     "Invalid process specification '{}'. process type cannot be empty.",
                     spec
-                ));
+                ))
+                .into());
             }
 
             let count: i32 = count_raw.parse().map_err(|_| {
-                anyhow::anyhow!(
+                CliError::Validation(format!(
                     "Invalid count '{}' for process type '{}'. Must be a number.",
-                    count_raw,
-                    process_type
-                )
+                    count_raw, process_type
+                ))
             })?;
             if count < 0 {
-                return Err(anyhow::anyhow!(
+                return Err(CliError::Validation(format!(
                     "Count must be non-negative for process type '{}'",
                     process_type
-                ));
+                ))
+                .into());
             }
 
             if process_counts.insert(process_type.clone(), count).is_some() {
-                return Err(anyhow::anyhow!(
+                return Err(CliError::Validation(format!(
                     "Process type '{}' specified multiple times",
                     process_type
-                ));
+                ))
+                .into());
             }
         }
 
+        let wait_timeout = match self.wait_timeout.as_deref() {
+            Some(t) => super::deploys::parse_duration(t)
+                .map_err(|e| CliError::Validation(e.to_string()))?,
+            None => DEFAULT_WAIT_TIMEOUT,
+        };
+
         let path = format!("/v1/orgs/{}/apps/{}/envs/{}/scale", org_id, app_id, env_id);
 
         let current: ScaleState = client.get(&path).await.map_err(|e| match e {
@@ -111,13 +148,15 @@ impl ScaleCommand {
             .into_iter()
             .map(|(process_type, desired)| ProcessScale {
                 process_type,
-                desired,
+                desired: desired.into(),
+                ..ProcessScale::default()
             })
             .collect();
 
         let request = ScaleUpdateRequest {
-            processes: processes.clone(),
-            expected_version,
+            processes,
+            expected_version: Some(expected_version),
+            reason: self.reason.clone(),
         };
         let idempotency_key = match ctx.idempotency_key.as_deref() {
             Some(key) => key.to_string(),
@@ -187,11 +226,57 @@ impl ScaleCommand {
         );
 
         if matches!(ctx.format, OutputFormat::Table) {
-            let mut rows = response.processes.clone();
+            let mut rows: Vec<ProcessScaleRow> = response
+                .processes
+                .iter()
+                .map(|p| ProcessScaleRow {
+                    process_type: p.process_type.clone(),
+                    desired: p.desired,
+                })
+                .collect();
             rows.sort_by(|a, b| a.process_type.cmp(&b.process_type));
             print_output(&rows, ctx.format);
         }
 
+        if self.wait {
+            let status_path = format!("/v1/orgs/{}/apps/{}/envs/{}/status", org_id, app_id, env_id);
+            wait_for_scale(&client, &status_path, wait_timeout).await?;
+            print_success(&format!("Environment {} converged", env_id_str));
+        }
+
         Ok(())
     }
 }
+
+/// Wait until ready instances match the desired count with nothing booting
+/// or draining.
+async fn wait_for_scale(client: &ApiClient, status_path: &str, timeout: Duration) -> Result<()> {
+    let start = Instant::now();
+    let mut last = String::new();
+
+    loop {
+        let status: EnvStatusCounts = client.get(status_path).await?;
+        let counts = &status.instances;
+        if counts.converged() {
+            return Ok(());
+        }
+
+        let summary = format!(
+            "{}/{} ready, {} booting, {} draining, {} failed",
+            counts.ready, counts.desired, counts.booting, counts.draining, counts.failed
+        );
+        if summary != last {
+            print_info(&summary);
+            last = summary;
+        }
+
+        if start.elapsed() > timeout {
+            return Err(CliError::Timeout(format!(
+                "Timeout waiting for scale to converge ({last})"
+            ))
+            .into());
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
@@ -7,9 +7,9 @@ use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
-use tabled::Tabled;
+use serde::Serialize;
 
+use crate::client::models::{PutSecretsEnvFileRequest, PutSecretsMapRequest, SecretsMetadata};
 use crate::output::{print_receipt, print_single, OutputFormat, Receipt, ReceiptNextStep};
 
 use super::CommandContext;
@@ -51,18 +51,7 @@ struct ConfirmSecretsArgs {
     none: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
-struct SecretsMetadata {
-    #[tabled(rename = "Env ID")]
-    env_id: String,
-    #[tabled(rename = "Bundle ID")]
-    bundle_id: String,
-    #[tabled(rename = "Version ID")]
-    current_version_id: String,
-    #[tabled(rename = "Updated")]
-    updated_at: String,
-}
-
+/// Body of `PUT .../secrets`, one of the spec's `PutSecretsRequest` variants.
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 enum PutSecretsRequest {
@@ -70,17 +59,6 @@ enum PutSecretsRequest {
     Map(PutSecretsMapRequest),
 }
 
-#[derive(Debug, Serialize)]
-struct PutSecretsEnvFileRequest {
-    format: String,
-    data: String,
-}
-
-#[derive(Debug, Serialize)]
-struct PutSecretsMapRequest {
-    values: BTreeMap<String, String>,
-}
-
 impl SecretsCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
         match self.command {
@@ -91,18 +69,12 @@ impl SecretsCommand {
     }
 }
 
-fn require_env(ctx: &CommandContext) -> Result<&str> {
-    ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })
-}
-
 async fn get_secrets(ctx: CommandContext) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/secrets",
@@ -142,7 +114,7 @@ async fn set_secrets(ctx: CommandContext, args: SetSecretsArgs) -> Result<()> {
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let path = format!(
         "/v1/orgs/{}/apps/{}/envs/{}/secrets",
@@ -250,7 +222,7 @@ async fn confirm_secrets_none(ctx: CommandContext, args: ConfirmSecretsArgs) ->
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let path = format!("/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/secrets");
     let request = PutSecretsRequest::Map(PutSecretsMapRequest {
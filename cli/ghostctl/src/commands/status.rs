@@ -4,10 +4,17 @@
 //! - Current release ID, desired release ID
 //! - Instance counts (desired vs running)
 //! - Endpoint status
-//! - Last reconcile time and last error if any
+//! - Recent events, last reconcile time and last error if any
+//!
+//! `--watch` redraws the dashboard until interrupted.
+
+use std::fmt::Write as _;
+use std::time::Duration;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::Args;
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
 
 use crate::output::{print_single, OutputFormat};
@@ -20,11 +27,23 @@ pub struct StatusCommand {
     /// Show verbose details.
     #[arg(long, short)]
     verbose: bool,
+
+    /// Refresh the dashboard until interrupted (JSON: one object per line).
+    #[arg(long, short)]
+    watch: bool,
+
+    /// Refresh interval in seconds for --watch.
+    #[arg(long, default_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
 }
 
 impl StatusCommand {
     pub async fn run(self, ctx: CommandContext) -> Result<()> {
-        show_status(ctx, self.verbose).await
+        if self.watch {
+            watch_status(ctx, self.verbose, Duration::from_secs(self.interval)).await
+        } else {
+            show_status(ctx, self.verbose).await
+        }
     }
 }
 
@@ -70,6 +89,10 @@ struct EnvStatusResponse {
     #[serde(default)]
     last_error: Option<String>,
 
+    /// Most recent events, newest first.
+    #[serde(default)]
+    recent_events: Vec<StatusEvent>,
+
     /// Overall status (healthy, degraded, failed).
     status: String,
 }
@@ -113,108 +136,239 @@ struct RouteStatus {
     backend_count: i32,
 }
 
-/// Show status for the current app and environment.
-async fn show_status(ctx: CommandContext, verbose: bool) -> Result<()> {
-    let client = ctx.client()?;
+/// Environment event summary.
+#[derive(Debug, Serialize, Deserialize)]
+struct StatusEvent {
+    event_id: i64,
+    occurred_at: DateTime<Utc>,
+    event_type: String,
+    aggregate_type: String,
+    aggregate_id: String,
+}
 
+/// Resolved path of the env status endpoint.
+async fn status_path(ctx: &CommandContext, client: &crate::client::ApiClient) -> Result<String> {
     let org_ident = ctx.require_org()?;
     let app_ident = ctx.require_app()?;
-    let env_ident = ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })?;
+    let env_ident = ctx.require_env()?;
+
+    let org_id = crate::resolve::resolve_org_id(client, org_ident).await?;
+    let app_id = crate::resolve::resolve_app_id(client, org_id, app_ident).await?;
+    let env_id = crate::resolve::resolve_env_id(client, org_id, app_id, env_ident).await?;
+
+    Ok(format!(
+        "/v1/orgs/{}/apps/{}/envs/{}/status",
+        org_id, app_id, env_id
+    ))
+}
 
-    let org_id = crate::resolve::resolve_org_id(&client, org_ident).await?;
-    let app_id = crate::resolve::resolve_app_id(&client, org_id, app_ident).await?;
-    let env_id = crate::resolve::resolve_env_id(&client, org_id, app_id, env_ident).await?;
+/// Show status for the current app and environment.
+async fn show_status(ctx: CommandContext, verbose: bool) -> Result<()> {
+    let client = ctx.client()?;
+    let path = status_path(&ctx, &client).await?;
 
     // Fetch environment status
-    let response: EnvStatusResponse = client
-        .get(&format!(
-            "/v1/orgs/{}/apps/{}/envs/{}/status",
-            org_id, app_id, env_id
-        ))
-        .await?;
+    let response: EnvStatusResponse = client.get(&path).await?;
 
     match ctx.format {
         OutputFormat::Json => {
             print_single(&response, ctx.format);
         }
         OutputFormat::Table => {
-            print_status_table(&response, verbose);
+            print!("{}", render_dashboard(&response, verbose, Utc::now()));
         }
     }
 
     Ok(())
 }
 
-/// Print status in a human-readable table format.
-fn print_status_table(status: &EnvStatusResponse, verbose: bool) {
-    println!("App:         {}", status.app_name);
-    println!("Environment: {}", status.env_name);
-    println!("Status:      {}", format_status(&status.status));
-    println!();
+/// Redraw the status every `interval` until interrupted.
+///
+/// Fetch errors are shown and retried rather than ending the watch: the API
+/// being briefly unreachable is exactly when people are watching.
+async fn watch_status(ctx: CommandContext, verbose: bool, interval: Duration) -> Result<()> {
+    let client = ctx.client()?;
+    let path = status_path(&ctx, &client).await?;
+    let mut last: Option<String> = None;
+
+    loop {
+        let result: Result<EnvStatusResponse, _> = client.get(&path).await;
+        let now = Utc::now();
+
+        match ctx.format {
+            OutputFormat::Json => match &result {
+                Ok(response) => println!("{}", serde_json::to_string(response)?),
+                Err(e) => eprintln!("fetch failed: {e:#}"),
+            },
+            OutputFormat::Table => {
+                let body = match &result {
+                    Ok(response) => {
+                        let body = render_dashboard(response, verbose, now);
+                        last = Some(body.clone());
+                        body
+                    }
+                    Err(e) => format!(
+                        "{}\n{}\n\n{}",
+                        format!("Fetch failed: {e:#} (retrying)").red().bold(),
+                        "Showing last known status:".dimmed(),
+                        last.as_deref().unwrap_or("(none yet)\n")
+                    ),
+                };
+                // Clear the screen and move home before each frame.
+                print!("\x1b[2J\x1b[H");
+                println!(
+                    "{}",
+                    format!(
+                        "Every {}s: vt status    {}",
+                        interval.as_secs(),
+                        now.format("%H:%M:%S UTC")
+                    )
+                    .dimmed()
+                );
+                println!();
+                print!("{body}");
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Render the status dashboard.
+fn render_dashboard(status: &EnvStatusResponse, verbose: bool, now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "App:         {}", status.app_name.bold());
+    let _ = writeln!(out, "Environment: {}", status.env_name.bold());
+    let _ = writeln!(out, "Status:      {}", format_status(&status.status));
+    let _ = writeln!(out);
 
     // Release info
-    println!("RELEASE");
-    println!(
+    let _ = writeln!(out, "{}", "RELEASE".bold());
+    let _ = writeln!(
+        out,
         "  Current:  {}",
         status.current_release_id.as_deref().unwrap_or("-")
     );
-    println!(
+    let _ = writeln!(
+        out,
         "  Desired:  {}",
         status.desired_release_id.as_deref().unwrap_or("-")
     );
-    println!(
+    let _ = writeln!(
+        out,
         "  Synced:   {}",
-        if status.release_synced { "yes" } else { "no" }
+        if status.release_synced {
+            "yes".green()
+        } else {
+            "no (rolling out)".yellow()
+        }
     );
-    println!();
+    let _ = writeln!(out);
 
     // Instance counts
-    println!("INSTANCES");
-    println!(
-        "  Desired: {}  Ready: {}  Booting: {}  Draining: {}  Failed: {}",
-        status.instances.desired,
-        status.instances.ready,
-        status.instances.booting,
-        status.instances.draining,
-        status.instances.failed
+    let counts = &status.instances;
+    let ready = format!("{}/{} ready", counts.ready, counts.desired);
+    let ready = if counts.ready >= counts.desired {
+        ready.green()
+    } else {
+        ready.yellow()
+    };
+    let _ = writeln!(out, "{}", "INSTANCES".bold());
+    let _ = writeln!(
+        out,
+        "  {}  {}  {}  {}",
+        ready,
+        count_label("booting", counts.booting, |s| s.yellow()),
+        count_label("draining", counts.draining, |s| s.cyan()),
+        count_label("failed", counts.failed, |s| s.red().bold())
     );
-    println!();
+    let _ = writeln!(out);
 
     // Routes/endpoints
     if !status.routes.is_empty() {
-        println!("ROUTES");
+        let _ = writeln!(out, "{}", "ROUTES".bold());
         for route in &status.routes {
-            println!(
-                "  {} → :{} ({}, {} backends)",
-                route.hostname, route.target_port, route.status, route.backend_count
+            let backends = format!("{} backends", route.backend_count);
+            let backends = if route.backend_count > 0 {
+                backends.green()
+            } else {
+                backends.red()
+            };
+            let _ = writeln!(
+                out,
+                "  {} → :{} ({}, {})",
+                route.hostname, route.target_port, route.status, backends
             );
         }
-        println!();
+        let _ = writeln!(out);
+    }
+
+    if !status.recent_events.is_empty() {
+        let _ = writeln!(out, "{}", "RECENT EVENTS".bold());
+        for event in &status.recent_events {
+            let _ = writeln!(
+                out,
+                "  {:>8}  {:<32} {}/{}",
+                format_age(event.occurred_at, now).dimmed(),
+                event.event_type,
+                event.aggregate_type,
+                event.aggregate_id
+            );
+        }
+        let _ = writeln!(out);
+    }
+
+    if let Some(err) = &status.last_error {
+        let _ = writeln!(out, "{}", "LAST ERROR".bold());
+        let _ = writeln!(out, "  {}", err.red());
+        let _ = writeln!(out);
     }
 
     // Reconciliation info
     if verbose {
-        println!("RECONCILIATION");
+        let _ = writeln!(out, "{}", "RECONCILIATION".bold());
         if let Some(ts) = &status.last_reconcile_at {
-            println!("  Last:  {}", ts);
+            let _ = writeln!(out, "  Last:  {}", ts);
         }
-        if let Some(err) = &status.last_error {
-            println!("  Error: {}", err);
-        }
-        println!();
+        let _ = writeln!(out);
     }
+
+    out
 }
 
-/// Format status with color hints (when appropriate).
-fn format_status(status: &str) -> &str {
-    // In a real CLI, we'd use colors here
+/// Non-zero counts are colored; zero counts are dimmed.
+fn count_label(
+    label: &str,
+    count: i32,
+    color: impl Fn(colored::ColoredString) -> colored::ColoredString,
+) -> colored::ColoredString {
+    let text = format!("{label} {count}");
+    if count > 0 {
+        color(text.normal())
+    } else {
+        text.dimmed()
+    }
+}
+
+/// Compact age such as `42s ago`, `5m ago`, `3h ago`, `2d ago`.
+fn format_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - at).num_seconds().max(0);
+    match secs {
+        0..=59 => format!("{secs}s ago"),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// Format status with color.
+fn format_status(status: &str) -> colored::ColoredString {
     match status {
-        "healthy" => "healthy ✓",
-        "degraded" => "degraded ⚠",
-        "failed" => "failed ✗",
-        other => other,
+        "healthy" => "● healthy".green().bold(),
+        "degraded" => "● degraded".yellow().bold(),
+        "failed" => "● failed".red().bold(),
+        other => other.normal(),
     }
 }
 
@@ -257,5 +411,54 @@ mod tests {
         assert_eq!(status.instances.ready, 3);
         assert!(status.release_synced);
         assert_eq!(status.routes.len(), 1);
+        assert!(status.recent_events.is_empty());
+    }
+
+    #[test]
+    fn test_dashboard_lists_events_and_last_error() {
+        let json = r#"{
+            "env_id": "env_123",
+            "env_name": "production",
+            "app_id": "app_456",
+            "app_name": "myapp",
+            "desired_release_id": "rel_new",
+            "release_synced": false,
+            "instances": {"desired": 3, "ready": 1, "booting": 1, "draining": 0, "failed": 1},
+            "last_error": "health check timed out",
+            "recent_events": [
+                {
+                    "event_id": 7,
+                    "occurred_at": "2025-12-19T11:58:00Z",
+                    "event_type": "instance.status_changed",
+                    "aggregate_type": "instance",
+                    "aggregate_id": "inst_1"
+                }
+            ],
+            "status": "failed"
+        }"#;
+        let status: EnvStatusResponse = serde_json::from_str(json).unwrap();
+        let now = "2025-12-19T12:00:00Z".parse().unwrap();
+
+        let out = render_dashboard(&status, false, now);
+        assert!(out.contains("1/3 ready"));
+        assert!(out.contains("instance.status_changed"));
+        assert!(out.contains("instance/inst_1"));
+        assert!(out.contains("2m ago"));
+        assert!(out.contains("health check timed out"));
+        assert!(!out.contains("ROUTES"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now: DateTime<Utc> = "2025-12-19T12:00:00Z".parse().unwrap();
+        let ago = |secs| now - chrono::Duration::seconds(secs);
+        assert_eq!(format_age(ago(5), now), "5s ago");
+        assert_eq!(format_age(ago(300), now), "5m ago");
+        assert_eq!(format_age(ago(7200), now), "2h ago");
+        assert_eq!(format_age(ago(200_000), now), "2d ago");
+        assert_eq!(
+            format_age(now + chrono::Duration::seconds(3), now),
+            "0s ago"
+        );
     }
 }
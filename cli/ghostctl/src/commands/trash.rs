@@ -0,0 +1,226 @@
+//! Trash commands.
+//!
+//! Deleted apps, envs, routes, and volumes stay restorable for a retention
+//! window (7 days by default) before the control plane purges them.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::client::models::{RestoreResponse, TrashItem, TrashListResponse};
+use crate::error::CliError;
+use crate::output::{
+    print_output, print_receipt_no_resource, print_single, OutputFormat, ReceiptNextStep,
+    ReceiptNoResource,
+};
+
+use super::CommandContext;
+
+/// Trash commands.
+#[derive(Debug, Args)]
+pub struct TrashCommand {
+    #[command(subcommand)]
+    command: TrashSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum TrashSubcommand {
+    /// List deleted resources that can still be restored.
+    List(ListTrashArgs),
+
+    /// Restore a deleted resource.
+    ///
+    /// Restoring an app also restores the envs deleted with it; restoring an
+    /// env also restores its routes and volumes. Envs come back scaled to
+    /// zero and volumes come back unattached.
+    Restore(RestoreArgs),
+}
+
+#[derive(Debug, Args)]
+struct ListTrashArgs {
+    /// Maximum number of items to return (1-200).
+    #[arg(long, default_value = "50")]
+    limit: i64,
+}
+
+#[derive(Debug, Args)]
+struct RestoreArgs {
+    /// ID of the deleted app, env, route, or volume (see `vt trash list`).
+    id: String,
+}
+
+impl TrashCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            TrashSubcommand::List(args) => list_trash(ctx, args).await,
+            TrashSubcommand::Restore(args) => restore(ctx, args).await,
+        }
+    }
+}
+
+/// Table row for a trash item.
+#[derive(Debug, Serialize, Tabled)]
+struct TrashRow {
+    #[tabled(rename = "Kind")]
+    kind: String,
+
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Name")]
+    name: String,
+
+    #[tabled(rename = "Deleted")]
+    deleted_at: String,
+
+    #[tabled(rename = "Purge After")]
+    purge_after: String,
+}
+
+impl From<TrashItem> for TrashRow {
+    fn from(item: TrashItem) -> Self {
+        Self {
+            kind: item.kind,
+            id: item.id,
+            name: item.name.unwrap_or_else(|| "-".to_string()),
+            deleted_at: item.deleted_at.unwrap_or_else(|| "-".to_string()),
+            purge_after: item.purge_after.unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+/// API path that restores `item`. Volumes use `undelete`, since `restore`
+/// restores a volume from a snapshot.
+fn restore_path(org_id: &str, item: &TrashItem) -> Option<String> {
+    let base = format!("/v1/orgs/{org_id}");
+    match item.kind.as_str() {
+        "app" => Some(format!("{base}/apps/{}/restore", item.id)),
+        "env" => Some(format!(
+            "{base}/apps/{}/envs/{}/restore",
+            item.app_id.as_deref()?,
+            item.id
+        )),
+        "route" => Some(format!(
+            "{base}/apps/{}/envs/{}/routes/{}/restore",
+            item.app_id.as_deref()?,
+            item.env_id.as_deref()?,
+            item.id
+        )),
+        "volume" => Some(format!("{base}/volumes/{}/undelete", item.id)),
+        _ => None,
+    }
+}
+
+async fn list_trash(ctx: CommandContext, args: ListTrashArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let response: TrashListResponse = client
+        .get(&format!("/v1/orgs/{org_id}/trash?limit={}", args.limit))
+        .await?;
+
+    match ctx.format {
+        OutputFormat::Table => {
+            let rows: Vec<TrashRow> = response.items.into_iter().map(TrashRow::from).collect();
+            print_output(&rows, ctx.format)
+        }
+        OutputFormat::Json => print_single(&response, ctx.format),
+    }
+
+    Ok(())
+}
+
+async fn restore(ctx: CommandContext, args: RestoreArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+    let org_id_str = org_id.to_string();
+
+    // The trash knows where the resource lived, so only the ID is needed.
+    let trash: TrashListResponse = client
+        .get(&format!("/v1/orgs/{org_id}/trash?limit=200"))
+        .await?;
+    let item = trash
+        .items
+        .into_iter()
+        .find(|item| item.id == args.id)
+        .ok_or_else(|| {
+            CliError::NotFound(format!(
+                "'{}' is not in the trash (never deleted, already restored, or purged)",
+                args.id
+            ))
+        })?;
+    let path = restore_path(&org_id_str, &item)
+        .ok_or_else(|| anyhow::anyhow!("Cannot restore a {} from the CLI", item.kind))?;
+
+    let response: RestoreResponse = client
+        .post_with_idempotency_key(&path, &serde_json::json!({}), None)
+        .await?;
+
+    let restored: Vec<String> = response
+        .restored
+        .iter()
+        .map(|item| format!("{} {}", item.kind, item.id))
+        .collect();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!("vt --org {org_id_str} trash list"),
+    }];
+
+    print_receipt_no_resource(
+        ctx.format,
+        ReceiptNoResource {
+            message: format!("Restored {}", restored.join(", ")),
+            status: "accepted",
+            kind: "trash.restore",
+            ids: serde_json::json!({
+                "id": args.id,
+                "org_id": org_id_str,
+                "restored": response.restored
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: &str, id: &str) -> TrashItem {
+        TrashItem {
+            kind: kind.to_string(),
+            id: id.to_string(),
+            app_id: Some("app_1".to_string()),
+            env_id: Some("env_1".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_restore_path() {
+        assert_eq!(
+            restore_path("org_1", &item("app", "app_1")).as_deref(),
+            Some("/v1/orgs/org_1/apps/app_1/restore")
+        );
+        assert_eq!(
+            restore_path("org_1", &item("env", "env_1")).as_deref(),
+            Some("/v1/orgs/org_1/apps/app_1/envs/env_1/restore")
+        );
+        assert_eq!(
+            restore_path("org_1", &item("route", "rt_1")).as_deref(),
+            Some("/v1/orgs/org_1/apps/app_1/envs/env_1/routes/rt_1/restore")
+        );
+        assert_eq!(
+            restore_path("org_1", &item("volume", "vol_1")).as_deref(),
+            Some("/v1/orgs/org_1/volumes/vol_1/undelete")
+        );
+
+        let mut orphan = item("env", "env_2");
+        orphan.app_id = None;
+        assert_eq!(restore_path("org_1", &orphan), None);
+        assert_eq!(restore_path("org_1", &item("release", "rel_1")), None);
+    }
+}
@@ -2,19 +2,31 @@
 //!
 //! Volumes are org-scoped resources that can be attached to env/process types.
 
+use std::time::Duration;
+
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tabled::Tabled;
+use tokio::time::sleep;
 
+use crate::client::models::{
+    AttachVolumeRequest, CreateSnapshotRequest, CreateVolumeRequest, DetachVolumeRequest,
+    ListSnapshotsResponse, ListVolumesResponse, ResizeVolumeRequest, RestoreVolumeRequest,
+    SetBackupPolicyRequest, Snapshot, Volume, VolumeAttachment, VolumeRestore,
+};
+use crate::client::ApiClient;
 use crate::error::CliError;
 use crate::output::{
-    print_output, print_receipt, print_receipt_no_resource, print_single, OutputFormat, Receipt,
-    ReceiptNextStep, ReceiptNoResource,
+    print_info, print_output, print_receipt, print_receipt_no_resource, print_single,
+    print_success, OutputFormat, Receipt, ReceiptNextStep, ReceiptNoResource,
 };
 
 use super::CommandContext;
 
+/// Polling interval while waiting for an in-place restore.
+const RESTORE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Volume commands.
 #[derive(Debug, Args)]
 pub struct VolumesCommand {
@@ -36,10 +48,16 @@ enum VolumesSubcommand {
     /// Delete a volume (idempotent).
     Delete(DeleteVolumeArgs),
 
+    /// Grow a volume (volumes cannot shrink).
+    Resize(ResizeVolumeArgs),
+
+    /// Set a volume's scheduled snapshot policy, or turn it off with --off.
+    BackupPolicy(BackupPolicyArgs),
+
     /// Attach a volume to the current env/process type.
     Attach(AttachVolumeArgs),
 
-    /// Detach a volume attachment (idempotent).
+    /// Detach a volume (or a single attachment by ID).
     Detach(DetachVolumeArgs),
 
     /// Create a snapshot.
@@ -48,7 +66,7 @@ enum VolumesSubcommand {
     /// List snapshots for a volume.
     SnapshotList(SnapshotListArgs),
 
-    /// Restore a volume from a snapshot (creates a new volume).
+    /// Restore a volume from a snapshot (into a new volume, or in place).
     Restore(RestoreVolumeArgs),
 }
 
@@ -61,6 +79,14 @@ struct ListVolumesArgs {
     /// Pagination cursor (opaque).
     #[arg(long)]
     cursor: Option<String>,
+
+    /// Sort key.
+    #[arg(long, value_parser = ["id", "size_bytes", "created_at", "updated_at"])]
+    order_by: Option<String>,
+
+    /// Sort descending.
+    #[arg(long)]
+    desc: bool,
 }
 
 #[derive(Debug, Args)]
@@ -94,6 +120,38 @@ struct DeleteVolumeArgs {
     volume: String,
 }
 
+#[derive(Debug, Args)]
+struct ResizeVolumeArgs {
+    /// Volume ID.
+    volume: String,
+
+    /// New size: bytes, or a number with a unit (e.g. 20GiB, 500MiB, 2TB).
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    size: i64,
+}
+
+#[derive(Debug, Args)]
+struct BackupPolicyArgs {
+    /// Volume ID.
+    volume: String,
+
+    /// Cron schedule in UTC (e.g. "0 3 * * *", "@daily").
+    #[arg(long, required_unless_present = "off")]
+    schedule: Option<String>,
+
+    /// Keep at most this many successful scheduled snapshots.
+    #[arg(long, value_name = "COUNT")]
+    keep: Option<i32>,
+
+    /// Prune scheduled snapshots older than this (e.g. 30d, 12h).
+    #[arg(long, value_name = "AGE", value_parser = parse_max_age)]
+    max_age: Option<i64>,
+
+    /// Turn scheduled snapshots off.
+    #[arg(long, conflicts_with_all = ["schedule", "keep", "max_age"])]
+    off: bool,
+}
+
 #[derive(Debug, Args)]
 struct AttachVolumeArgs {
     /// Volume ID.
@@ -114,8 +172,16 @@ struct AttachVolumeArgs {
 
 #[derive(Debug, Args)]
 struct DetachVolumeArgs {
-    /// Attachment ID.
-    attachment: String,
+    /// Volume ID, or an attachment ID (vat_...) to detach that attachment.
+    volume: String,
+
+    /// Attachment to detach when the volume has several.
+    #[arg(long)]
+    attachment: Option<String>,
+
+    /// Only detach from this process type in the current env.
+    #[arg(long)]
+    process_type: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -152,8 +218,17 @@ struct RestoreVolumeArgs {
     snapshot_id: String,
 
     /// Name for the new volume.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "in_place")]
     new_volume_name: Option<String>,
+
+    /// Overwrite this volume instead of creating a new one. Every process
+    /// type mounting it must be scaled to 0 first.
+    #[arg(long)]
+    in_place: bool,
+
+    /// With --in-place, wait for the restore to finish, showing progress.
+    #[arg(long, requires = "in_place")]
+    wait: bool,
 }
 
 impl VolumesCommand {
@@ -163,6 +238,8 @@ impl VolumesCommand {
             VolumesSubcommand::Create(args) => create_volume(ctx, args).await,
             VolumesSubcommand::Get(args) => get_volume(ctx, args).await,
             VolumesSubcommand::Delete(args) => delete_volume(ctx, args).await,
+            VolumesSubcommand::Resize(args) => resize_volume(ctx, args).await,
+            VolumesSubcommand::BackupPolicy(args) => set_backup_policy(ctx, args).await,
             VolumesSubcommand::Attach(args) => attach_volume(ctx, args).await,
             VolumesSubcommand::Detach(args) => detach_volume(ctx, args).await,
             VolumesSubcommand::SnapshotCreate(args) => snapshot_create(ctx, args).await,
@@ -172,90 +249,21 @@ impl VolumesCommand {
     }
 }
 
-fn require_env(ctx: &CommandContext) -> Result<&str> {
-    ctx.resolve_env().ok_or_else(|| {
-        anyhow::anyhow!("No environment specified. Use --env or set a default context.")
-    })
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct VolumeAttachmentResponse {
-    id: String,
-    volume_id: String,
-    env_id: String,
-    process_type: String,
-    mount_path: String,
-    #[serde(default)]
-    read_only: bool,
-    created_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct VolumeResponse {
-    id: String,
-    org_id: String,
-    #[serde(default)]
-    name: Option<String>,
-    size_bytes: i64,
-    filesystem: String,
-    created_at: String,
-    #[serde(default)]
-    updated_at: Option<String>,
-    #[serde(default)]
-    attachments: Vec<VolumeAttachmentResponse>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ListVolumesResponse {
-    items: Vec<VolumeResponse>,
-    next_cursor: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-struct CreateVolumeRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
-    size_bytes: i64,
-    filesystem: String,
-    backup_enabled: bool,
-}
-
-#[derive(Debug, Serialize)]
-struct CreateVolumeAttachmentRequest {
-    volume_id: String,
-    process_type: String,
-    mount_path: String,
-    #[serde(default)]
-    read_only: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SnapshotResponse {
-    id: String,
-    volume_id: String,
-    created_at: String,
-    status: String,
-    #[serde(default)]
-    size_bytes: Option<i64>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ListSnapshotsResponse {
-    items: Vec<SnapshotResponse>,
-    next_cursor: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-struct CreateSnapshotRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    note: Option<String>,
+fn restore_finished(restore: &VolumeRestore) -> bool {
+    matches!(restore.status.as_str(), "succeeded" | "failed")
 }
 
-#[derive(Debug, Serialize)]
-struct RestoreVolumeRequest {
-    snapshot_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    new_volume_name: Option<String>,
+fn restore_progress(restore: &VolumeRestore) -> String {
+    match (restore.bytes_restored, restore.bytes_total) {
+        (Some(done), Some(total)) if total > 0 => {
+            format!(
+                "{} {}% ({done}/{total} bytes)",
+                restore.status,
+                done * 100 / total
+            )
+        }
+        _ => restore.status.clone(),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Tabled)]
@@ -268,20 +276,36 @@ struct VolumeListRow {
     size_bytes: i64,
     #[tabled(rename = "FS")]
     filesystem: String,
+    #[tabled(rename = "Node")]
+    home_node_id: String,
+    #[tabled(rename = "Resize")]
+    resize_status: String,
+    #[tabled(rename = "Backups")]
+    backups: String,
     #[tabled(rename = "Attachments")]
     attachments: usize,
     #[tabled(rename = "Created")]
     created_at: String,
 }
 
-impl From<&VolumeResponse> for VolumeListRow {
-    fn from(v: &VolumeResponse) -> Self {
+impl From<&Volume> for VolumeListRow {
+    fn from(v: &Volume) -> Self {
         Self {
             id: v.id.clone(),
             name: v.name.clone().unwrap_or_else(|| "-".to_string()),
             size_bytes: v.size_bytes,
             filesystem: v.filesystem.clone(),
-            attachments: v.attachments.len(),
+            home_node_id: v.home_node_id.clone().unwrap_or_else(|| "-".to_string()),
+            resize_status: v.resize_status.clone().unwrap_or_else(|| "-".to_string()),
+            backups: v
+                .backup_policy
+                .as_ref()
+                .map(|p| match p.last_run_status.as_deref() {
+                    Some(status) => format!("{} (last: {status})", p.schedule),
+                    None => p.schedule.clone(),
+                })
+                .unwrap_or_else(|| "-".to_string()),
+            attachments: v.attachments.as_ref().map_or(0, Vec::len),
             created_at: v.created_at.clone(),
         }
     }
@@ -295,6 +319,12 @@ async fn list_volumes(ctx: CommandContext, args: ListVolumesArgs) -> Result<()>
     if let Some(cursor) = args.cursor.as_deref() {
         path.push_str(&format!("&cursor={cursor}"));
     }
+    if let Some(order_by) = args.order_by.as_deref() {
+        path.push_str(&format!("&order_by={order_by}"));
+    }
+    if args.desc {
+        path.push_str("&desc=true");
+    }
 
     let response: ListVolumesResponse = client.get(&path).await?;
     match ctx.format {
@@ -314,8 +344,8 @@ async fn create_volume(ctx: CommandContext, args: CreateVolumeArgs) -> Result<()
     let request = CreateVolumeRequest {
         name: args.name.clone(),
         size_bytes: args.size_bytes,
-        filesystem: args.filesystem.clone(),
-        backup_enabled: !args.no_backup,
+        filesystem: Some(args.filesystem.clone()),
+        backup_enabled: Some(!args.no_backup),
     };
 
     let path = format!("/v1/orgs/{org_id}/volumes");
@@ -324,7 +354,7 @@ async fn create_volume(ctx: CommandContext, args: CreateVolumeArgs) -> Result<()
         None => crate::idempotency::default_idempotency_key("volumes.create", &path, &request)?,
     };
 
-    let response: VolumeResponse = client
+    let response: Volume = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -368,7 +398,7 @@ async fn get_volume(ctx: CommandContext, args: GetVolumeArgs) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
 
-    let response: VolumeResponse = client
+    let response: Volume = client
         .get(&format!("/v1/orgs/{org_id}/volumes/{}", args.volume))
         .await
         .map_err(|e| match e {
@@ -413,31 +443,144 @@ async fn delete_volume(ctx: CommandContext, args: DeleteVolumeArgs) -> Result<()
     Ok(())
 }
 
+async fn resize_volume(ctx: CommandContext, args: ResizeVolumeArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let request = ResizeVolumeRequest {
+        size_bytes: args.size,
+    };
+
+    let path = format!("/v1/orgs/{org_id}/volumes/{}/resize", args.volume);
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key("volumes.resize", &path, &request)?,
+    };
+
+    let response: Volume = client
+        .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!("vt --org {} volumes get {}", org_id_str, response.id),
+    }];
+
+    let message = match response.resize_status.as_deref() {
+        Some("pending") => format!(
+            "Resizing volume {} to {} bytes (the home node grows it online)",
+            response.id, response.size_bytes
+        ),
+        _ => format!("Volume {} is {} bytes", response.id, response.size_bytes),
+    };
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message,
+            status: "accepted",
+            kind: "volumes.resize",
+            resource_key: "volume",
+            resource: &response,
+            ids: serde_json::json!({
+                "volume_id": response.id,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+async fn set_backup_policy(ctx: CommandContext, args: BackupPolicyArgs) -> Result<()> {
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    let request = SetBackupPolicyRequest {
+        schedule: args.schedule.clone(),
+        retain_count: args.keep.map(i64::from),
+        retain_max_age_secs: args.max_age,
+    };
+
+    let path = format!("/v1/orgs/{org_id}/volumes/{}/backup-policy", args.volume);
+    let idempotency_key = match ctx.idempotency_key.as_deref() {
+        Some(key) => key.to_string(),
+        None => crate::idempotency::default_idempotency_key(
+            "volumes.set_backup_policy",
+            &path,
+            &request,
+        )?,
+    };
+
+    let response: Volume = client
+        .put_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
+        .await?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!(
+            "vt --org {} volumes snapshot-list {}",
+            org_id_str, response.id
+        ),
+    }];
+
+    let message = match response.backup_policy.as_ref() {
+        Some(policy) => match policy.next_run_at.as_deref() {
+            Some(next_run) => format!(
+                "Scheduled snapshots for volume {} ({}); next run {next_run}",
+                response.id, policy.schedule
+            ),
+            None => format!(
+                "Scheduled snapshots for volume {} ({})",
+                response.id, policy.schedule
+            ),
+        },
+        None => format!("Turned off scheduled snapshots for volume {}", response.id),
+    };
+
+    print_receipt(
+        ctx.format,
+        Receipt {
+            message,
+            status: "accepted",
+            kind: "volumes.set_backup_policy",
+            resource_key: "volume",
+            resource: &response,
+            ids: serde_json::json!({
+                "volume_id": response.id,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
 async fn attach_volume(ctx: CommandContext, args: AttachVolumeArgs) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
-    let request = CreateVolumeAttachmentRequest {
-        volume_id: args.volume.clone(),
+    let request = AttachVolumeRequest {
+        env_id: env_id.to_string(),
         process_type: args.process_type.clone(),
         mount_path: args.mount_path.clone(),
-        read_only: args.read_only,
+        read_only: Some(args.read_only),
     };
 
-    let path = format!("/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/volume-attachments");
+    let path = format!("/v1/orgs/{org_id}/volumes/{}/attach", args.volume);
     let idempotency_key = match ctx.idempotency_key.as_deref() {
         Some(key) => key.to_string(),
-        None => crate::idempotency::default_idempotency_key(
-            "volume_attachments.create",
-            &path,
-            &request,
-        )?,
+        None => crate::idempotency::default_idempotency_key("volumes.attach", &path, &request)?,
     };
 
-    let response: VolumeAttachmentResponse = client
+    let response: VolumeAttachment = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -472,7 +615,7 @@ async fn attach_volume(ctx: CommandContext, args: AttachVolumeArgs) -> Result<()
                 attachment_id.as_str()
             ),
             status: "accepted",
-            kind: "volume_attachments.create",
+            kind: "volumes.attach",
             resource_key: "volume_attachment",
             resource: &response,
             ids: serde_json::json!({
@@ -490,22 +633,78 @@ async fn attach_volume(ctx: CommandContext, args: AttachVolumeArgs) -> Result<()
 }
 
 async fn detach_volume(ctx: CommandContext, args: DetachVolumeArgs) -> Result<()> {
+    if args.volume.starts_with("vat_") {
+        return detach_attachment(ctx, &args.volume).await;
+    }
+
+    let client = ctx.client()?;
+    let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
+
+    // Narrowing by process type only makes sense within an env.
+    let env_id = match &args.process_type {
+        Some(_) => {
+            let app_id =
+                crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
+            Some(
+                crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?)
+                    .await?
+                    .to_string(),
+            )
+        }
+        None => None,
+    };
+
+    let request = DetachVolumeRequest {
+        attachment_id: args.attachment.clone(),
+        env_id,
+        process_type: args.process_type.clone(),
+    };
+    let path = format!("/v1/orgs/{org_id}/volumes/{}/detach", args.volume);
+    let _: serde_json::Value = client
+        .post_with_idempotency_key(&path, &request, ctx.idempotency_key.as_deref())
+        .await?;
+
+    let org_id_str = org_id.to_string();
+    let next = vec![ReceiptNextStep {
+        label: "Next",
+        cmd: format!("vt --org {} volumes get {}", org_id_str, args.volume),
+    }];
+
+    print_receipt_no_resource(
+        ctx.format,
+        ReceiptNoResource {
+            message: format!("Detached volume {}", args.volume),
+            status: "accepted",
+            kind: "volumes.detach",
+            ids: serde_json::json!({
+                "volume_id": args.volume,
+                "attachment_id": args.attachment,
+                "org_id": org_id_str
+            }),
+            next: &next,
+        },
+    );
+
+    Ok(())
+}
+
+/// Detach one attachment of the current env by ID.
+async fn detach_attachment(ctx: CommandContext, attachment_id: &str) -> Result<()> {
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
     let app_id = crate::resolve::resolve_app_id(&client, org_id, ctx.require_app()?).await?;
     let env_id =
-        crate::resolve::resolve_env_id(&client, org_id, app_id, require_env(&ctx)?).await?;
+        crate::resolve::resolve_env_id(&client, org_id, app_id, ctx.require_env()?).await?;
 
     let path = format!(
         "/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/volume-attachments/{}",
-        args.attachment
+        attachment_id
     );
     client.delete_with_idempotency_key(&path, None).await?;
 
     let org_id_str = org_id.to_string();
     let app_id_str = app_id.to_string();
     let env_id_str = env_id.to_string();
-    let attachment_id = args.attachment.clone();
     let next = vec![ReceiptNextStep {
         label: "Next",
         cmd: format!("vt --org {} volumes list", org_id_str.clone()),
@@ -542,7 +741,7 @@ async fn snapshot_create(ctx: CommandContext, args: SnapshotCreateArgs) -> Resul
         None => crate::idempotency::default_idempotency_key("snapshots.create", &path, &request)?,
     };
 
-    let response: SnapshotResponse = client
+    let response: Snapshot = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -613,10 +812,12 @@ async fn restore_volume(ctx: CommandContext, args: RestoreVolumeArgs) -> Result<
     let client = ctx.client()?;
     let org_id = crate::resolve::resolve_org_id(&client, ctx.require_org()?).await?;
 
-    let path = format!("/v1/orgs/{org_id}/volumes/{}/restore", args.volume);
+    let volume_path = format!("/v1/orgs/{org_id}/volumes/{}", args.volume);
+    let path = format!("{volume_path}/restore");
     let request = RestoreVolumeRequest {
         snapshot_id: args.snapshot_id,
         new_volume_name: args.new_volume_name,
+        in_place: args.in_place.then_some(true),
     };
 
     let idempotency_key = match ctx.idempotency_key.as_deref() {
@@ -624,7 +825,7 @@ async fn restore_volume(ctx: CommandContext, args: RestoreVolumeArgs) -> Result<
         None => crate::idempotency::default_idempotency_key("volumes.restore", &path, &request)?,
     };
 
-    let response: VolumeResponse = client
+    let response: Volume = client
         .post_with_idempotency_key(&path, &request, Some(idempotency_key.as_str()))
         .await?;
 
@@ -641,10 +842,19 @@ async fn restore_volume(ctx: CommandContext, args: RestoreVolumeArgs) -> Result<
         },
     ];
 
+    let message = if args.in_place {
+        format!(
+            "Restoring volume {} in place from snapshot {} (the home node overwrites it)",
+            response.id, request.snapshot_id
+        )
+    } else {
+        format!("Restored volume {}", response.id.as_str())
+    };
+
     print_receipt(
         ctx.format,
         Receipt {
-            message: format!("Restored volume {}", response.id.as_str()),
+            message,
             status: "accepted",
             kind: "volumes.restore",
             resource_key: "volume",
@@ -657,5 +867,147 @@ async fn restore_volume(ctx: CommandContext, args: RestoreVolumeArgs) -> Result<
         },
     );
 
+    if args.wait {
+        let restore_id = response.restore.as_ref().map(|r| r.id.clone());
+        wait_for_restore(&client, &volume_path, restore_id.as_deref()).await?;
+    }
+
     Ok(())
 }
+
+/// Poll the volume until its in-place restore finishes, printing progress.
+async fn wait_for_restore(
+    client: &ApiClient,
+    volume_path: &str,
+    restore_id: Option<&str>,
+) -> Result<()> {
+    let mut last = String::new();
+
+    loop {
+        let volume: Volume = client.get(volume_path).await?;
+        let Some(restore) = volume
+            .restore
+            .filter(|r| restore_id.is_none_or(|id| r.id == id))
+        else {
+            return Err(
+                CliError::NotFound("Restore no longer reported by the volume".to_string()).into(),
+            );
+        };
+
+        if restore_finished(&restore) {
+            if restore.status == "failed" {
+                return Err(anyhow::anyhow!(
+                    "Restore {} failed: {}",
+                    restore.id,
+                    restore.failed_reason.as_deref().unwrap_or("unknown error")
+                ));
+            }
+            print_success(&format!("Volume {} restored", volume.id));
+            return Ok(());
+        }
+
+        let summary = restore_progress(&restore);
+        if summary != last {
+            print_info(&summary);
+            last = summary;
+        }
+
+        sleep(RESTORE_POLL_INTERVAL).await;
+    }
+}
+
+/// Parse a size given in bytes or with a unit suffix. Decimal (KB, MB, GB,
+/// TB) and binary (KiB, MiB, GiB, TiB) units are accepted, case-insensitively;
+/// a bare `K`/`M`/`G`/`T` is binary.
+pub(super) fn parse_size(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: i64 = number
+        .parse()
+        .map_err(|_| format!("invalid size '{value}' (expected e.g. 20GiB or 21474836480)"))?;
+
+    let multiplier: i64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        other => return Err(format!("unknown size unit '{other}'")),
+    };
+
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{value}' is too large"))
+}
+
+/// Parse a retention age: days (`30d`) or anything `parse_duration` takes.
+fn parse_max_age(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    if let Some(days) = value.strip_suffix('d') {
+        let days: i64 = days
+            .parse()
+            .map_err(|_| format!("invalid age '{value}' (expected e.g. 30d or 12h)"))?;
+        return days
+            .checked_mul(86_400)
+            .ok_or_else(|| format!("age '{value}' is too large"));
+    }
+    super::deploys::parse_duration(value)
+        .map(|d| d.as_secs() as i64)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_accepts_bytes_and_units() {
+        assert_eq!(parse_size("1073741824"), Ok(1 << 30));
+        assert_eq!(parse_size("20GiB"), Ok(20 << 30));
+        assert_eq!(parse_size("20g"), Ok(20 << 30));
+        assert_eq!(parse_size("2TB"), Ok(2_000_000_000_000));
+        assert_eq!(parse_size("500 MiB"), Ok(500 << 20));
+        assert!(parse_size("GiB").is_err());
+        assert!(parse_size("10XB").is_err());
+        assert!(parse_size("-1").is_err());
+        assert!(parse_size("9999999999TiB").is_err());
+    }
+
+    #[test]
+    fn parse_max_age_accepts_days_and_durations() {
+        assert_eq!(parse_max_age("30d"), Ok(30 * 86_400));
+        assert_eq!(parse_max_age("12h"), Ok(12 * 3_600));
+        assert_eq!(parse_max_age("7200"), Ok(7_200));
+        assert!(parse_max_age("d").is_err());
+        assert!(parse_max_age("3w").is_err());
+    }
+
+    #[test]
+    fn restore_progress_shows_percentage_once_total_is_known() {
+        let mut restore = VolumeRestore {
+            id: "rst_1".to_string(),
+            snapshot_id: "snp_1".to_string(),
+            status: "queued".to_string(),
+            bytes_restored: None,
+            bytes_total: None,
+            failed_reason: None,
+        };
+        assert_eq!(restore_progress(&restore), "queued");
+        assert!(!restore_finished(&restore));
+
+        restore.status = "running".to_string();
+        restore.bytes_restored = Some(256);
+        restore.bytes_total = Some(1024);
+        assert_eq!(restore_progress(&restore), "running 25% (256/1024 bytes)");
+
+        restore.status = "failed".to_string();
+        assert!(restore_finished(&restore));
+    }
+}
@@ -19,7 +19,7 @@ const CONFIG_FILE: &str = "config.json";
 const CREDENTIALS_FILE: &str = "credentials.json";
 
 /// Get the config directory path.
-fn config_dir() -> Result<PathBuf> {
+pub fn config_dir() -> Result<PathBuf> {
     ProjectDirs::from("com", "plfm", "vt")
         .map(|dirs| dirs.config_dir().to_path_buf())
         .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))
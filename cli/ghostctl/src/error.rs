@@ -1,8 +1,17 @@
 //! Error handling and display for the CLI.
 
 use colored::Colorize;
+use plfm_proto::errors;
+use serde::Deserialize;
 use thiserror::Error;
 
+/// A field-level violation attached to a validation error.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldViolation {
+    pub field: String,
+    pub message: String,
+}
+
 /// CLI-specific errors.
 #[derive(Debug, Error)]
 pub enum CliError {
@@ -17,6 +26,7 @@ pub enum CliError {
         request_id: Option<String>,
         retryable: bool,
         retry_after_seconds: Option<u32>,
+        violations: Vec<FieldViolation>,
     },
 
     #[error("Network error: {0}")]
@@ -25,10 +35,80 @@ pub enum CliError {
     #[error("Resource not found: {0}")]
     NotFound(String),
 
+    /// Invalid local input (arguments, manifest) caught before any API call.
+    #[error("{0}")]
+    Validation(String),
+
+    /// Gave up waiting for an operation to converge.
+    #[error("{0}")]
+    Timeout(String),
+
+    /// A deploy (or rollback) reached a terminal status other than success.
+    #[error("Deploy {deploy_id} {status}: {message}")]
+    DeployFailed {
+        deploy_id: String,
+        status: String,
+        message: String,
+    },
+
     #[error("{0}")]
     Other(#[from] anyhow::Error),
 }
 
+/// Process exit codes. These are a stable contract for scripts and CI:
+/// codes are only ever added, never renumbered.
+pub mod exit_code {
+    /// Success.
+    pub const SUCCESS: i32 = 0;
+    /// Unclassified failure.
+    pub const FAILURE: i32 = 1;
+    /// Invalid input: usage errors, local validation, API 400/422.
+    pub const VALIDATION: i32 = 2;
+    /// Resource not found (API 404).
+    pub const NOT_FOUND: i32 = 3;
+    /// Conflict or failed precondition (API 409/412).
+    pub const CONFLICT: i32 = 4;
+    /// Timed out waiting for convergence (`--wait`).
+    pub const TIMEOUT: i32 = 5;
+    /// Not authenticated or not permitted (API 401/403).
+    pub const AUTH: i32 = 6;
+    /// Network failure, rate limiting, or server error; safe to retry.
+    pub const UNAVAILABLE: i32 = 7;
+    /// Deploy or rollback failed.
+    pub const DEPLOY_FAILED: i32 = 10;
+}
+
+impl CliError {
+    /// Exit code for this error (see [`exit_code`]).
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::NotAuthenticated => exit_code::AUTH,
+            Self::Api { status, .. } => match *status {
+                400 | 422 => exit_code::VALIDATION,
+                401 | 403 => exit_code::AUTH,
+                404 => exit_code::NOT_FOUND,
+                409 | 412 => exit_code::CONFLICT,
+                408 | 429 | 500..=599 => exit_code::UNAVAILABLE,
+                _ => exit_code::FAILURE,
+            },
+            Self::Network(_) => exit_code::UNAVAILABLE,
+            Self::NotFound(_) => exit_code::NOT_FOUND,
+            Self::Validation(_) => exit_code::VALIDATION,
+            Self::Timeout(_) => exit_code::TIMEOUT,
+            Self::DeployFailed { .. } => exit_code::DEPLOY_FAILED,
+            Self::Other(err) => exit_code_for(err),
+        }
+    }
+}
+
+/// Exit code for an error returned by a command.
+pub fn exit_code_for(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<CliError>())
+        .map(CliError::exit_code)
+        .unwrap_or(exit_code::FAILURE)
+}
+
 impl CliError {
     /// Create an API error from response details.
     pub fn api(
@@ -46,6 +126,31 @@ impl CliError {
             request_id,
             retryable,
             retry_after_seconds,
+            violations: Vec::new(),
+        }
+    }
+
+    /// Attach field-level violations to an API error.
+    pub fn with_violations(self, violations: Vec<FieldViolation>) -> Self {
+        match self {
+            Self::Api {
+                status,
+                code,
+                message,
+                request_id,
+                retryable,
+                retry_after_seconds,
+                ..
+            } => Self::Api {
+                status,
+                code,
+                message,
+                request_id,
+                retryable,
+                retry_after_seconds,
+                violations,
+            },
+            other => other,
         }
     }
 }
@@ -76,11 +181,19 @@ pub fn print_error(err: &anyhow::Error) {
                 );
             }
             CliError::Api {
+                code,
                 request_id,
                 retryable,
                 retry_after_seconds,
+                violations,
                 ..
             } => {
+                for violation in violations {
+                    eprintln!("  {}: {}", violation.field.bold(), violation.message);
+                }
+                if let Some(hint) = errors::lookup(code).and_then(|spec| spec.hint) {
+                    eprintln!("\n{}", format!("Hint: {hint}").yellow());
+                }
                 if let Some(request_id) = request_id {
                     eprintln!("\nRequest ID: {}", request_id);
                 }
@@ -102,3 +215,36 @@ pub fn print_error(err: &anyhow::Error) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_statuses_map_to_exit_codes() {
+        let code = |status| CliError::api(status, "x", "x", None, false, None).exit_code();
+        assert_eq!(code(400), exit_code::VALIDATION);
+        assert_eq!(code(422), exit_code::VALIDATION);
+        assert_eq!(code(401), exit_code::AUTH);
+        assert_eq!(code(404), exit_code::NOT_FOUND);
+        assert_eq!(code(409), exit_code::CONFLICT);
+        assert_eq!(code(412), exit_code::CONFLICT);
+        assert_eq!(code(503), exit_code::UNAVAILABLE);
+        assert_eq!(code(418), exit_code::FAILURE);
+    }
+
+    #[test]
+    fn exit_code_looks_through_context() {
+        let err = anyhow::Error::from(CliError::Timeout("waiting".into())).context("deploy");
+        assert_eq!(exit_code_for(&err), exit_code::TIMEOUT);
+
+        let err = anyhow::Error::from(CliError::DeployFailed {
+            deploy_id: "dep_1".into(),
+            status: "failed".into(),
+            message: "boom".into(),
+        });
+        assert_eq!(exit_code_for(&err), exit_code::DEPLOY_FAILED);
+
+        assert_eq!(exit_code_for(&anyhow::anyhow!("plain")), exit_code::FAILURE);
+    }
+}
@@ -0,0 +1,1606 @@
+// This file is @generated by cli/ghostctl/build.rs from api/openapi/openapi.yaml.
+// Do not edit by hand.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    pub r#type: String,
+    pub title: String,
+    pub status: i64,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Stable error code from api/errors/catalog.json
+    pub code: String,
+    /// Functional area of the error code (e.g. `apps`, `auth`, `request`)
+    pub domain: String,
+    pub request_id: String,
+    pub retryable: bool,
+    pub retry_after_seconds: i64,
+    /// Field-level violations for validation errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeleteResponse {
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pagination {
+    pub items: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceStartRequest {
+    pub client_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceStartResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_uri_complete: Option<String>,
+    pub expires_in_seconds: i64,
+    pub poll_interval_seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceTokenRequest {
+    pub device_code: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceTokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in_seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub expires_in_seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RefreshTokenResponse {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub expires_in_seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RevokeTokenRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RevokeTokenResponse {
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WhoAmIResponse {
+    pub subject_type: String,
+    pub subject_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub org_memberships: Vec<OrgMembership>,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrgMembership {
+    pub org_id: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Org {
+    pub id: String,
+    pub name: String,
+    pub resource_version: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateOrgRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateOrgRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub expected_version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub org_id: String,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub resource_version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListProjectsResponse {
+    pub items: Vec<Project>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateProjectRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateProjectRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub expected_version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListOrgsResponse {
+    pub items: Vec<Org>,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub id: String,
+    pub org_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub role: String,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListMembersResponse {
+    pub items: Vec<Member>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateMemberRequest {
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub role: String,
+    pub expected_version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct App {
+    pub id: String,
+    pub org_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Labels>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListAppsResponse {
+    pub items: Vec<App>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateAppRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateAppRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub expected_version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Env {
+    pub id: String,
+    pub app_id: String,
+    pub org_id: String,
+    pub name: String,
+    /// Platform-managed hostname (`<env>-<app>-<org>.apps.<platform-domain>`),
+    /// present when managed hostnames are enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub managed_hostname: Option<String>,
+    /// Ephemeral preview environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<bool>,
+    /// When a preview environment is torn down by the cleanup worker;
+    /// absent for regular environments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Labels>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+}
+
+/// Items matching the list's filters, returned with `include_total=true`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageTotal {
+    /// Matching items, at most 10000.
+    pub count: i64,
+    /// More than 10000 items match.
+    pub capped: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListEnvsResponse {
+    pub items: Vec<Env>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateEnvRequest {
+    pub name: String,
+    /// Create an ephemeral preview environment. It is torn down (volumes
+    /// included) once `ttl_seconds` has elapsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<bool>,
+    /// Preview lifetime in seconds (default 259200, 72 hours). Requires `preview`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateEnvRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Release {
+    pub id: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub image_ref: String,
+    pub image_digest: String,
+    pub manifest_hash: String,
+    pub manifest_schema_version: i64,
+    pub command: Vec<String>,
+    pub metadata: ReleaseMetadata,
+    pub resource_version: i64,
+    pub created_at: String,
+}
+
+/// Source and build provenance recorded with a release. Copied into
+/// `deploy.created` events as `release_metadata`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseMetadata {
+    /// Git ref the image was built from (branch, tag, or PR head ref).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Git commit SHA the image was built from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// Git branch the commit was on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// URL of the CI run that built the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci_run_url: Option<String>,
+    /// Digest of the image's SBOM.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom_digest: Option<String>,
+    /// Identity of the builder (CI system, workflow, or build service).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseImage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_ref: Option<String>,
+    pub index_or_manifest_digest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_digests: Option<Vec<ResolvedImageDigest>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedImageDigest {
+    pub os: String,
+    pub arch: String,
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateReleaseRequest {
+    /// OCI reference (must be pinned by digest in v1)
+    pub image_ref: String,
+    /// Image digest (sha256:...)
+    pub image_digest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_schema_version: Option<i64>,
+    /// Hash of manifest content (sha256:...)
+    pub manifest_hash: String,
+    /// Fully resolved entrypoint command
+    pub command: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ReleaseMetadata>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListReleasesResponse {
+    pub items: Vec<Release>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deploy {
+    pub id: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub kind: String,
+    pub release_id: String,
+    pub process_types: Vec<String>,
+    pub status: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Aggregated failure reason codes (e.g. `oom_killed=3, image_pull_failed=1`) when the deploy failed or was halted by its failure budget.
+    #[serde(default)]
+    pub failed_reason: Option<String>,
+    /// Why the deploy was made, as given when it was created.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Rollout was promoted to 100% and skips remaining rolling steps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted: Option<bool>,
+    pub resource_version: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateDeployRequest {
+    pub release_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_types: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// Why the deploy is being made (changelog entry, ticket, incident). Recorded on `deploy.created`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RollbackRequest {
+    pub release_id: String,
+    /// Why the rollback is being made. Recorded on `deploy.created`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListDeploysResponse {
+    pub items: Vec<Deploy>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScaleState {
+    pub env_id: String,
+    pub processes: Vec<ProcessScale>,
+    pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessScale {
+    pub process_type: String,
+    pub desired: i64,
+    /// Node labels an instance's node must carry. Omit to keep the current selector.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_selector: Option<BTreeMap<String, String>>,
+    /// Node taints this process type tolerates. Omit to keep the current tolerations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerations: Option<Vec<Toleration>>,
+    /// Scratch disk per instance in bytes. Omit to keep the current size (4 GiB when never set). Changing it replaces the process type's instances. Counts against max_total_ephemeral_disk_bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_bytes: Option<i64>,
+    /// Instances a rolling deploy may start above desired. Percentages round up. Omit to keep the current limit (1 when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_surge: Option<RolloutLimit>,
+    /// Instances a rolling deploy may take below desired. Percentages round down. Omit to keep the current limit (0 when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_unavailable: Option<RolloutLimit>,
+    /// How running instances pick up a new secrets or config vars version. Omit to keep the current mode (restart when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets_reload: Option<SecretsReload>,
+}
+
+/// restart replaces instances with a rolling restart. file, signal and exec rewrite /run/secrets/platform.env and hand new config vars to guest-init inside running instances; signal then sends a signal to the workload, and exec runs a hook command as the workload user.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecretsReload {
+    pub mode: String,
+    /// Signal sent in signal mode (default SIGHUP).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<String>,
+    /// Reload hook argv; required in exec mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    /// Reload hook timeout in exec mode (default 30).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<i64>,
+}
+
+/// An instance count, or a percentage of desired replicas such as "25%". If surge and unavailability both resolve to 0, one instance may be unavailable.
+pub type RolloutLimit = serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Toleration {
+    pub key: String,
+    /// Absent matches any value of the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Absent matches both effects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScaleUpdateRequest {
+    pub processes: Vec<ProcessScale>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<i64>,
+    /// Why the scale is being changed. Recorded on `env.scale_set`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlacementPreviewRequest {
+    /// Desired replicas to preview. Process types not listed keep the env's current scale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processes: Option<Vec<ProcessScale>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlacementPreview {
+    pub env_id: String,
+    /// True when every new instance found a node.
+    pub feasible: bool,
+    pub processes: Vec<ProcessPlacementPreview>,
+    pub nodes: Vec<NodePlacementPreview>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessPlacementPreview {
+    pub process_type: String,
+    pub release_id: String,
+    pub desired: i64,
+    pub running: i64,
+    pub new_instances: i64,
+    pub memory_bytes: i64,
+    pub cpu_cores: i64,
+    /// Node ID for each new instance that could be placed.
+    pub placements: Vec<String>,
+    pub unplaced: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unplaced_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodePlacementPreview {
+    pub node_id: String,
+    pub allocatable_memory_bytes: i64,
+    pub allocatable_cpu_cores: i64,
+    /// Capacity left after the previewed instances are placed.
+    pub available_memory_bytes: i64,
+    pub available_cpu_cores: i64,
+    pub planned_instances: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+pub type BatchOperation = serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchScaleOperation {
+    pub op: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub processes: Vec<ProcessScale>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchRestartOperation {
+    pub op: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub process_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchOperationResult {
+    pub index: i64,
+    pub op: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchResponse {
+    pub succeeded: i64,
+    pub failed: i64,
+    pub results: Vec<BatchOperationResult>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Instance {
+    pub id: String,
+    pub env_id: String,
+    pub process_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<i64>,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_transition_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstanceConsole {
+    pub instance_id: String,
+    pub node_id: String,
+    /// Boot that produced the upload; `content` may also cover earlier boots.
+    pub boot_id: String,
+    pub content: String,
+    /// Whether older output exists beyond `content`.
+    pub truncated: bool,
+    /// When the console was last written.
+    pub captured_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListInstancesResponse {
+    pub items: Vec<Instance>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    pub id: String,
+    pub env_id: String,
+    pub hostname: String,
+    pub listen_port: i64,
+    pub protocol_hint: String,
+    pub backend_process_type: String,
+    pub backend_port: i64,
+    pub proxy_protocol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv4_required: Option<bool>,
+    /// Served only on the overlay network, by internal ingress listeners.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<RouteVerification>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingress: Option<RouteIngressStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Labels>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+}
+
+/// Client allow/deny rules enforced by the ingress per connection. Deny entries win; a non-empty allow list admits only matching clients.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteAccessPolicy {
+    /// Client CIDRs (or bare addresses) admitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_cidrs: Option<Vec<String>>,
+    /// Client CIDRs (or bare addresses) refused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny_cidrs: Option<Vec<String>>,
+    /// TLS client JA3 hashes or JA4 strings admitted. TLS routes only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_fingerprints: Option<Vec<String>>,
+    /// TLS client JA3 hashes or JA4 strings refused. TLS routes only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny_fingerprints: Option<Vec<String>>,
+}
+
+/// Hostname ownership state. Only verified routes are served.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteVerification {
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<String>,
+    /// TXT record to create; present until verified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txt_record_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txt_record_value: Option<String>,
+    /// Alternative CNAME target, when enabled by the operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cname_target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Ingresses with the route programmed and at least one backend, out of those reporting for the org.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteIngressStatus {
+    pub live: i64,
+    pub total: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListRoutesResponse {
+    pub items: Vec<Route>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateRouteRequest {
+    pub hostname: String,
+    pub listen_port: i64,
+    pub protocol_hint: String,
+    pub backend_process_type: String,
+    pub backend_port: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_expects_proxy_protocol: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv4_required: Option<bool>,
+    /// Serve only on the overlay network. The hostname must be under the internal suffix (default `.internal`) and ipv4_required must be false.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateRouteRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_process_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_port: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_expects_proxy_protocol: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv4_required: Option<bool>,
+    /// Replaces the whole policy; an empty object clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecretsMetadata {
+    pub env_id: String,
+    pub bundle_id: String,
+    pub current_version_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+    pub updated_at: String,
+}
+
+pub type PutSecretsRequest = serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PutSecretsEnvFileRequest {
+    pub format: String,
+    /// Raw secrets file content in platform format
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PutSecretsMapRequest {
+    pub values: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigVars {
+    pub env_id: String,
+    /// Environment variables handed to every instance of the env.
+    pub vars: BTreeMap<String, String>,
+    /// Config vars version; 0 until vars are first set.
+    pub resource_version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PutConfigVarsRequest {
+    /// Full set of config vars. Keys match `[A-Za-z_][A-Za-z0-9_]*` (at most
+    /// 256 bytes); at most 500 vars and 256 KiB in total.
+    pub vars: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PatchConfigVarsRequest {
+    /// Config vars to set; a `null` value removes the var.
+    pub vars: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessResources {
+    pub env_id: String,
+    pub process_type: String,
+    /// vCPU override; absent when the release value applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_request: Option<f64>,
+    /// Memory override in bytes; absent when the release value applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_bytes: Option<i64>,
+    /// Scratch disk override in bytes; absent when the env scale value applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_bytes: Option<i64>,
+    /// Override version; 0 until overrides are first set.
+    pub resource_version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PutProcessResourcesRequest {
+    #[serde(default)]
+    pub cpu_request: Option<f64>,
+    #[serde(default)]
+    pub memory_limit_bytes: Option<i64>,
+    #[serde(default)]
+    pub ephemeral_disk_bytes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Volume {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<i64>,
+    pub org_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub size_bytes: i64,
+    pub filesystem: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Labels>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Node holding the volume's data; set when an instance first mounts it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_node_id: Option<String>,
+    /// Outcome of the most recent resize; absent if never resized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resize_status: Option<String>,
+    /// Why the most recent resize failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resize_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_policy: Option<BackupPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restore: Option<VolumeRestore>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<VolumeAttachment>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListVolumesResponse {
+    pub items: Vec<Volume>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateVolumeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub size_bytes: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filesystem: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_enabled: Option<bool>,
+}
+
+/// Most recent in-place restore; absent if the volume was never restored in place.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumeRestore {
+    pub id: String,
+    pub snapshot_id: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_restored: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_total: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_reason: Option<String>,
+}
+
+/// Scheduled snapshot policy; absent when scheduled snapshots are off.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupPolicy {
+    /// Five-field cron expression, evaluated in UTC.
+    pub schedule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retain_count: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retain_max_age_secs: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<String>,
+    /// When the most recent scheduled snapshot was queued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetBackupPolicyRequest {
+    /// Cron expression (UTC), e.g. `0 3 * * *` or `@daily`; null turns scheduled snapshots off.
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Keep at most this many successful scheduled snapshots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retain_count: Option<i64>,
+    /// Prune scheduled snapshots older than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retain_max_age_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResizeVolumeRequest {
+    /// New size; must not be smaller than the current size.
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumeAttachment {
+    pub id: String,
+    pub volume_id: String,
+    pub env_id: String,
+    pub process_type: String,
+    pub mount_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateVolumeAttachmentRequest {
+    pub volume_id: String,
+    pub process_type: String,
+    pub mount_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttachVolumeRequest {
+    pub env_id: String,
+    pub process_type: String,
+    pub mount_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DetachVolumeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub volume_id: String,
+    pub created_at: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListSnapshotsResponse {
+    pub items: Vec<Snapshot>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateSnapshotRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RestoreVolumeRequest {
+    pub snapshot_id: String,
+    /// Not allowed with in_place.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_volume_name: Option<String>,
+    /// Overwrite this volume instead of creating a new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_place: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogLine {
+    pub ts: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_type: Option<String>,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogsResponse {
+    pub items: Vec<LogLine>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecGrantRequest {
+    pub command: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecGrantResponse {
+    pub session_id: String,
+    pub connect_url: String,
+    pub session_token: String,
+    pub expires_in_seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub event_id: i64,
+    pub occurred_at: String,
+    pub event_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate_seq: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<String>,
+    /// Protobuf JSON mapping of the event payload when available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventsResponse {
+    pub items: Vec<Event>,
+    /// Highest event_id returned (or `after_event_id`), for tailing.
+    pub next_after_event_id: i64,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppDeletionResponse {
+    pub app_id: String,
+    pub status: String,
+    pub delete_volumes: bool,
+    #[serde(default)]
+    pub requested_at: Option<String>,
+    /// Environments of the app not yet torn down.
+    pub envs_remaining: i64,
+}
+
+pub type EnvTeardownStep = String;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeletionStep {
+    pub name: EnvTeardownStep,
+    pub status: String,
+    /// Items still outstanding for the step.
+    pub remaining: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvDeletionResponse {
+    pub env_id: String,
+    pub status: String,
+    pub delete_volumes: bool,
+    #[serde(default)]
+    pub requested_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_step: Option<EnvTeardownStep>,
+    pub steps: Vec<DeletionStep>,
+}
+
+/// User-assigned key/value labels (at most 64). Keys are 1-128 characters
+/// of `[A-Za-z0-9._/-]`; values are up to 63 characters of `[A-Za-z0-9._-]`.
+/// Both must start and end with an alphanumeric character.
+pub type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PatchLabelsRequest {
+    /// Labels to set; a `null` value removes the label.
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LabelsResponse {
+    pub labels: Labels,
+    pub resource_version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub r#type: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    /// Which field matched the query
+    pub matched: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub items: Vec<SearchResult>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrashItem {
+    pub kind: String,
+    pub id: String,
+    /// App/env/volume name, or route hostname
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+    /// When the resource stops being restorable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purge_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrashListResponse {
+    pub items: Vec<TrashItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RestoreResponse {
+    /// Everything the restore brought back, the requested resource first
+    pub restored: Vec<TrashItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PutRouteCertificateRequest {
+    /// Leaf certificate followed by intermediates, PEM (at most 8).
+    pub certificate_pem: String,
+    /// Private key for the leaf, PEM (PKCS#8, PKCS#1, or SEC1).
+    pub private_key_pem: String,
+    /// Serve this certificate to clients without SNI on the route's listen port.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_for_non_sni: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteCertificate {
+    pub route_id: String,
+    pub hostname: String,
+    pub certificate_pem: String,
+    /// Hex SHA-256 of the leaf certificate DER.
+    pub fingerprint_sha256: String,
+    pub dns_names: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    pub default_for_non_sni: bool,
+    /// True once the certificate is inside the expiry warning window.
+    pub expiring: bool,
+    pub uploaded_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackendInstance {
+    pub instance_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub process_type: String,
+    pub overlay_ipv6: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackendSyncResponse {
+    /// Sync protocol version (currently 1).
+    pub version: i64,
+    pub mode: String,
+    /// Pass back as `since` to receive later changes. For a full snapshot, use the first page's cursor.
+    pub cursor: i64,
+    /// Routable instances (added or changed, in delta mode).
+    pub items: Vec<BackendInstance>,
+    /// Instances no longer routable (delta mode).
+    pub removed: Vec<String>,
+    pub has_more: bool,
+    /// Full snapshot paging; pass as `after` for the next page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IngressStatusReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_ips: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routes: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IngressStatusReportResponse {
+    pub ingress_id: String,
+    pub live_route_count: i64,
+    pub next_report_secs: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ingress {
+    pub ingress_id: String,
+    pub public_ips: Vec<String>,
+    pub route_count: i64,
+    pub last_report_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListIngressesResponse {
+    pub items: Vec<Ingress>,
+}
+
+pub type NotificationChannelKind = String;
+
+pub type NotificationTrigger = String;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateNotificationChannelRequest {
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    /// smtp: `{host, port?, tls?, username?, from, to}` (tls is `starttls`,
+    /// `tls`, or `none`); webhook: `{url}`; slack: `{}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+    /// Slack incoming webhook URL (required), SMTP password, or webhook
+    /// signing secret. Never returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateNotificationChannelRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+    /// Replaces the stored secret; omit to keep it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationChannel {
+    pub id: String,
+    pub org_id: String,
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    pub config: serde_json::Value,
+    pub has_secret: bool,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListNotificationChannelsResponse {
+    pub items: Vec<NotificationChannel>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationRuleRequest {
+    pub name: String,
+    pub trigger: NotificationTrigger,
+    pub channel_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_template: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    pub id: String,
+    pub org_id: String,
+    pub name: String,
+    pub trigger: NotificationTrigger,
+    pub channel_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_template: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListNotificationRulesResponse {
+    pub items: Vec<NotificationRule>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationDelivery {
+    pub id: String,
+    pub channel_id: String,
+    pub rule_id: String,
+    pub event_id: i64,
+    pub trigger: NotificationTrigger,
+    pub state: String,
+    pub attempts: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListNotificationDeliveriesResponse {
+    pub items: Vec<NotificationDelivery>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+pub type AlertCondition = serde_json::Value;
+
+/// An env has failed instances or fewer ready instances than desired replicas.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvUnhealthyCondition {
+    pub r#type: String,
+}
+
+/// A node is offline (platform condition; operators only).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeOfflineCondition {
+    pub r#type: String,
+}
+
+/// A projection is more than threshold events behind (platform condition; operators only).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectionLagCondition {
+    pub r#type: String,
+    pub threshold: i64,
+}
+
+/// An env's instances failed at least threshold times within the window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstanceRestartsCondition {
+    pub r#type: String,
+    pub threshold: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_seconds: Option<i64>,
+}
+
+/// An env is spending its error budget at least threshold times faster than its objective allows, over the window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloBurnRateCondition {
+    pub r#type: String,
+    /// Burn rate multiple; 1 spends exactly the budget.
+    pub threshold: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertRuleRequest {
+    pub name: String,
+    pub condition: AlertCondition,
+    /// Seconds the condition must hold before the alert fires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub for_seconds: Option<i64>,
+    /// Scope for env conditions; omit for every app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    /// Scope for env conditions; omit for every env.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: String,
+    pub org_id: String,
+    pub name: String,
+    pub condition: AlertCondition,
+    pub for_seconds: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    pub enabled: bool,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListAlertRulesResponse {
+    pub items: Vec<AlertRule>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub condition: String,
+    /// The env ID, node ID, or projection name the condition holds on.
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    pub state: String,
+    /// Observed value (ready instances, lag, or restarts).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<i64>,
+    pub summary: String,
+    pub pending_since: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fired_at: Option<String>,
+    pub last_evaluated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListAlertsResponse {
+    pub items: Vec<Alert>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusPageRequest {
+    pub title: String,
+    /// Envs to show; empty or omitted shows every env in the org.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_ids: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusPage {
+    pub org_id: String,
+    pub title: String,
+    pub env_ids: Vec<String>,
+    pub enabled: bool,
+    /// Current public token; rotate it to revoke shared links.
+    pub token: String,
+    /// Path of the public summary, token included.
+    pub public_path: String,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PublicStatusEnv {
+    /// app/env
+    pub name: String,
+    pub status: String,
+    /// Percent of the window with at least one ready instance; null before any history.
+    #[serde(default)]
+    pub uptime_24h: Option<f64>,
+    #[serde(default)]
+    pub uptime_7d: Option<f64>,
+    #[serde(default)]
+    pub uptime_30d: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PublicStatusIncident {
+    pub title: String,
+    /// app/env
+    pub env: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub started_at: String,
+    /// Null while the alert is still firing.
+    #[serde(default)]
+    pub resolved_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PublicStatus {
+    pub title: String,
+    /// The worst status among shown envs, ignoring stopped ones.
+    pub status: String,
+    pub generated_at: String,
+    pub envs: Vec<PublicStatusEnv>,
+    /// Incidents of the last 30 days, newest first (at most 20).
+    pub incidents: Vec<PublicStatusIncident>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloObjectiveRequest {
+    /// Availability objective, in percent.
+    pub objective: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloObjective {
+    pub env_id: String,
+    pub objective: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloDay {
+    pub day: String,
+    /// Percent of observed time available; null when nothing was observed.
+    #[serde(default)]
+    pub availability: Option<f64>,
+    pub observed_seconds: i64,
+    pub downtime_seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloReport {
+    pub env_id: String,
+    /// Availability objective, in percent (default 99.9).
+    pub objective: f64,
+    pub window_days: i64,
+    pub since: String,
+    pub until: String,
+    /// Seconds during which some instance was live.
+    pub observed_seconds: i64,
+    pub available_seconds: i64,
+    /// Instance-seconds spent ready, summed over instances.
+    pub ready_instance_seconds: i64,
+    pub failed_instance_seconds: i64,
+    /// Percent of observed time available; null when nothing was observed.
+    #[serde(default)]
+    pub availability: Option<f64>,
+    /// Downtime the objective allows over the observed time.
+    pub budget_seconds: i64,
+    pub consumed_seconds: i64,
+    /// Negative once the budget is exhausted.
+    pub remaining_seconds: i64,
+    /// Budget spend rate over the window; 1 spends exactly the budget.
+    #[serde(default)]
+    pub burn_rate: Option<f64>,
+    /// Budget spend rate over the last hour.
+    #[serde(default)]
+    pub burn_rate_1h: Option<f64>,
+    /// Oldest first; the last entry is today so far.
+    pub days: Vec<SloDay>,
+}
@@ -0,0 +1,237 @@
+//! HTTP request tracing.
+//!
+//! With `--debug-http` every API request logs a one-line summary to stderr
+//! (method, redacted URL, status, request ID, timing). Independently of the
+//! flag, the most recent failed request is saved so `vt debug last-request`
+//! can show it after the fact.
+//!
+//! Only summaries are recorded: never headers or bodies, and query parameters
+//! that look like credentials are redacted.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// File (in the config dir) holding the most recent failed request.
+const LAST_REQUEST_FILE: &str = "last-request.json";
+
+/// Query parameter names containing any of these are redacted.
+const SENSITIVE_PARAMS: &[&str] = &["token", "secret", "password", "key", "signature", "code"];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enable request logging (`--debug-http`).
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Summary of a failed request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastRequest {
+    pub at: DateTime<Utc>,
+    pub method: String,
+    pub url: String,
+    /// HTTP status; absent when the request never got a response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    pub message: String,
+    pub cli_version: String,
+}
+
+/// An in-flight request.
+#[derive(Debug)]
+pub struct RequestTrace {
+    method: String,
+    url: String,
+    started: Instant,
+}
+
+impl RequestTrace {
+    pub fn start(method: &reqwest::Method, url: &reqwest::Url) -> Self {
+        let trace = Self {
+            method: method.to_string(),
+            url: redact_url(url),
+            started: Instant::now(),
+        };
+        if enabled() {
+            eprintln!("{} {} {}", "[http]".dimmed(), trace.method, trace.url);
+        }
+        trace
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    /// Log the response status line.
+    pub fn response(&self, status: u16, request_id: Option<&str>) {
+        if !enabled() {
+            return;
+        }
+        let status_text = if status < 400 {
+            status.to_string().green()
+        } else {
+            status.to_string().red()
+        };
+        eprintln!(
+            "{} {} {} -> {} in {}ms{}",
+            "[http]".dimmed(),
+            self.method,
+            self.url,
+            status_text,
+            self.elapsed_ms(),
+            request_id
+                .map(|id| format!(" (request-id {id})"))
+                .unwrap_or_default()
+        );
+    }
+
+    /// Record a request that got an error response.
+    pub fn failed(&self, status: u16, request_id: Option<&str>, error_code: &str, message: &str) {
+        self.save(LastRequest {
+            at: Utc::now(),
+            method: self.method.clone(),
+            url: self.url.clone(),
+            status: Some(status),
+            duration_ms: self.elapsed_ms(),
+            request_id: request_id.map(str::to_string),
+            error_code: Some(error_code.to_string()),
+            message: message.to_string(),
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        });
+    }
+
+    /// Record (and log) a request that never got a response.
+    pub fn network_error(&self, error: &reqwest::Error) {
+        if enabled() {
+            eprintln!(
+                "{} {} {} -> {} after {}ms",
+                "[http]".dimmed(),
+                self.method,
+                self.url,
+                "network error".red(),
+                self.elapsed_ms()
+            );
+        }
+        // The error message embeds the raw URL; swap in the redacted one.
+        let mut message = error.to_string();
+        if let Some(url) = error.url() {
+            message = message.replace(url.as_str(), &self.url);
+        }
+        self.save(LastRequest {
+            at: Utc::now(),
+            method: self.method.clone(),
+            url: self.url.clone(),
+            status: None,
+            duration_ms: self.elapsed_ms(),
+            request_id: None,
+            error_code: None,
+            message,
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        });
+    }
+
+    /// Best effort: failing to save must not mask the original error.
+    fn save(&self, last: LastRequest) {
+        let result = crate::config::config_dir().and_then(|dir| {
+            std::fs::create_dir_all(&dir)?;
+            let contents = serde_json::to_string_pretty(&last)?;
+            std::fs::write(dir.join(LAST_REQUEST_FILE), contents)?;
+            Ok(())
+        });
+        if let (Err(e), true) = (result, enabled()) {
+            eprintln!("{} failed to save last request: {e:#}", "[http]".dimmed());
+        }
+    }
+}
+
+/// Load the most recent failed request, if any.
+pub fn load_last_request() -> Result<Option<LastRequest>> {
+    let path = crate::config::config_dir()?.join(LAST_REQUEST_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let last = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(last))
+}
+
+/// URL with credentials removed: userinfo is dropped and sensitive query
+/// parameters are replaced with `REDACTED`.
+fn redact_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let lower = name.to_ascii_lowercase();
+                let value = if SENSITIVE_PARAMS.iter().any(|s| lower.contains(s)) {
+                    "REDACTED".to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_credentials_in_urls() {
+        let url: reqwest::Url =
+            "https://user:pw@api.example.com/v1/orgs?limit=5&access_token=abc&api_key=xyz"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            redact_url(&url),
+            "https://api.example.com/v1/orgs?limit=5&access_token=REDACTED&api_key=REDACTED"
+        );
+
+        let url: reqwest::Url = "http://localhost:8080/v1/orgs".parse().unwrap();
+        assert_eq!(redact_url(&url), "http://localhost:8080/v1/orgs");
+    }
+
+    #[test]
+    fn last_request_round_trips() {
+        let last = LastRequest {
+            at: Utc::now(),
+            method: "POST".to_string(),
+            url: "http://localhost:8080/v1/orgs".to_string(),
+            status: Some(500),
+            duration_ms: 42,
+            request_id: Some("req_123".to_string()),
+            error_code: Some("internal_error".to_string()),
+            message: "boom".to_string(),
+            cli_version: "0.1.0".to_string(),
+        };
+        let json = serde_json::to_string(&last).unwrap();
+        let parsed: LastRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.status, Some(500));
+        assert_eq!(parsed.request_id.as_deref(), Some("req_123"));
+    }
+}
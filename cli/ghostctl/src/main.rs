@@ -10,9 +10,11 @@ mod client;
 mod commands;
 mod config;
 mod error;
+mod http_trace;
 mod idempotency;
 mod manifest;
 mod output;
+mod picker;
 mod resolve;
 
 use commands::Cli;
@@ -25,7 +27,7 @@ async fn main() -> Result<()> {
     if let Err(e) = cli.run().await {
         // Print error in a user-friendly way
         error::print_error(&e);
-        std::process::exit(1);
+        std::process::exit(error::exit_code_for(&e));
     }
 
     Ok(())
@@ -118,4 +118,84 @@ memory = "256Mi"
         let errors = validate_manifest_toml_str(manifest).unwrap();
         assert!(errors.is_empty());
     }
+
+    #[test]
+    fn manifest_validation_checks_rollout_limits() {
+        let manifest = |max_surge: &str| {
+            format!(
+                r#"
+schema_version = "v1"
+
+[processes.web]
+command = ["./start"]
+
+[processes.web.resources]
+memory = "256Mi"
+
+[processes.web.rollout]
+max_surge = {max_surge}
+max_unavailable = 0
+"#
+            )
+        };
+
+        assert!(validate_manifest_toml_str(&manifest("\"25%\""))
+            .unwrap()
+            .is_empty());
+        assert!(validate_manifest_toml_str(&manifest("2"))
+            .unwrap()
+            .is_empty());
+        assert!(!validate_manifest_toml_str(&manifest("\"150%\""))
+            .unwrap()
+            .is_empty());
+        assert!(!validate_manifest_toml_str(&manifest("-1"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn manifest_validation_checks_secrets_reload() {
+        let manifest = |secrets: &str| {
+            format!(
+                r#"
+schema_version = "v1"
+
+[processes.web]
+command = ["./start"]
+
+[processes.web.resources]
+memory = "256Mi"
+
+[processes.web.secrets]
+{secrets}
+"#
+            )
+        };
+
+        for valid in [
+            "reload = \"restart\"",
+            "reload = \"signal\"\nreload_signal = \"SIGUSR1\"",
+            "reload = \"exec\"\nreload_command = [\"/app/reload\"]\nreload_timeout_seconds = 5",
+        ] {
+            assert!(
+                validate_manifest_toml_str(&manifest(valid))
+                    .unwrap()
+                    .is_empty(),
+                "{valid}"
+            );
+        }
+        for invalid in [
+            "reload = \"sighup\"",
+            "reload = \"signal\"\nreload_signal = \"SIGKILL\"",
+            "reload = \"exec\"",
+            "reload = \"exec\"\nreload_command = [\"/app/reload\"]\nreload_timeout_seconds = 0",
+        ] {
+            assert!(
+                !validate_manifest_toml_str(&manifest(invalid))
+                    .unwrap()
+                    .is_empty(),
+                "{invalid}"
+            );
+        }
+    }
 }
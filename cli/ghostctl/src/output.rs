@@ -1,5 +1,6 @@
 //! Output formatting for CLI commands.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
 
 use colored::Colorize;
@@ -20,6 +21,18 @@ pub enum OutputFormat {
     Json,
 }
 
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Suppress success/info messages and receipts (`--quiet`). Errors, data
+/// output, and JSON are unaffected; scripts should rely on the exit code.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
 /// Print data in the specified format.
 pub fn print_output<T: Serialize + Tabled>(data: &[T], format: OutputFormat) {
     match format {
@@ -64,11 +77,17 @@ pub fn print_proto_single<T: Serialize>(data: &T, format: OutputFormat, type_url
 
 /// Print a success message.
 pub fn print_success(message: &str) {
+    if is_quiet() {
+        return;
+    }
     println!("{} {}", "Success:".green().bold(), message);
 }
 
 /// Print an info message.
 pub fn print_info(message: &str) {
+    if is_quiet() {
+        return;
+    }
     println!("{} {}", "Info:".blue().bold(), message);
 }
 
@@ -0,0 +1,235 @@
+//! Interactive fuzzy picker.
+//!
+//! Used when a command needs an app or environment that was not given via
+//! flags or saved context. Draws on stderr so stdout stays clean for `--json`.
+
+use std::io::{IsTerminal, Write};
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+};
+
+const MAX_VISIBLE: usize = 10;
+
+/// A selectable entry.
+#[derive(Debug, Clone)]
+pub struct PickItem {
+    /// Matched against the query (e.g. the resource name).
+    pub label: String,
+    /// Shown dimmed next to the label (e.g. the resource ID).
+    pub detail: String,
+}
+
+/// Whether we can prompt: stdin and stderr are terminals and not running in CI.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal()
+        && std::io::stderr().is_terminal()
+        && std::env::var_os("CI").is_none()
+}
+
+/// Case-insensitive subsequence match of `query` in `candidate`.
+///
+/// Consecutive matches and matches at word boundaries score higher, gaps
+/// lower. Returns `None` when `candidate` does not contain the query.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut prev: Option<usize> = None;
+
+    for q in query.to_lowercase().chars() {
+        let idx = pos + candidate[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if prev.is_some_and(|p| p + 1 == idx) {
+            score += 5;
+        }
+        if idx == 0 || matches!(candidate[idx - 1], '-' | '_' | '.' | ' ') {
+            score += 3;
+        }
+        score -= (idx - pos) as i64;
+        prev = Some(idx);
+        pos = idx + 1;
+    }
+
+    Some(score)
+}
+
+/// Indices of the items matching `query`, best match first (ties keep the
+/// original order).
+pub fn filter(query: &str, items: &[PickItem]) -> Vec<usize> {
+    let mut scored: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| fuzzy_score(query, &item.label).map(|s| (s, i)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Let the user choose one of `items`. Returns `None` if they cancel
+/// (Esc or Ctrl-C).
+pub fn pick(prompt: &str, items: &[PickItem]) -> Result<Option<usize>> {
+    let mut out = std::io::stderr();
+    terminal::enable_raw_mode()?;
+    let result = run(&mut out, prompt, items);
+    let _ = terminal::disable_raw_mode();
+    result
+}
+
+fn run(out: &mut impl Write, prompt: &str, items: &[PickItem]) -> Result<Option<usize>> {
+    let mut query = String::new();
+    let mut selected = 0usize;
+    let mut drawn = 0u16;
+
+    loop {
+        let matches = filter(&query, items);
+        selected = selected.min(matches.len().saturating_sub(1));
+        drawn = render(out, prompt, &query, items, &matches, selected, drawn)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return clear(out, drawn).map(|_| None),
+            KeyCode::Char('c') if ctrl => return clear(out, drawn).map(|_| None),
+            KeyCode::Enter => {
+                if let Some(&index) = matches.get(selected) {
+                    clear(out, drawn)?;
+                    return Ok(Some(index));
+                }
+            }
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => selected += 1,
+            KeyCode::Char('n') if ctrl => selected += 1,
+            KeyCode::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Redraw the prompt and the visible matches; returns the number of lines
+/// below the prompt line.
+fn render(
+    out: &mut impl Write,
+    prompt: &str,
+    query: &str,
+    items: &[PickItem],
+    matches: &[usize],
+    selected: usize,
+    drawn: u16,
+) -> Result<u16> {
+    let width = terminal::size().map(|(w, _)| w as usize).unwrap_or(80);
+
+    queue!(out, cursor::MoveToColumn(0))?;
+    if drawn > 0 {
+        queue!(out, cursor::MoveUp(drawn))?;
+    }
+    queue!(
+        out,
+        terminal::Clear(ClearType::FromCursorDown),
+        Print(truncate(
+            &format!("? {prompt} {query}  ({}/{})", matches.len(), items.len()),
+            width
+        )),
+    )?;
+
+    let start = selected.saturating_sub(MAX_VISIBLE - 1);
+    let mut lines = 0u16;
+    for (row, &index) in matches.iter().enumerate().skip(start).take(MAX_VISIBLE) {
+        let item = &items[index];
+        let marker = if row == selected { ">" } else { " " };
+        let label = truncate(&format!("{marker} {}", item.label), width);
+        let detail = truncate(
+            &format!("  {}", item.detail),
+            width.saturating_sub(label.chars().count()),
+        );
+        queue!(out, Print("\r\n"))?;
+        if row == selected {
+            queue!(
+                out,
+                SetAttribute(Attribute::Bold),
+                Print(label),
+                SetAttribute(Attribute::Reset)
+            )?;
+        } else {
+            queue!(out, Print(label))?;
+        }
+        queue!(
+            out,
+            SetAttribute(Attribute::Dim),
+            Print(detail),
+            SetAttribute(Attribute::Reset)
+        )?;
+        lines += 1;
+    }
+    if matches.is_empty() {
+        queue!(out, Print("\r\n  (no matches)"))?;
+        lines += 1;
+    }
+
+    out.flush()?;
+    Ok(lines)
+}
+
+fn clear(out: &mut impl Write, drawn: u16) -> Result<()> {
+    queue!(out, cursor::MoveToColumn(0))?;
+    if drawn > 0 {
+        queue!(out, cursor::MoveUp(drawn))?;
+    }
+    queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+    out.flush()?;
+    Ok(())
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    s.chars().take(width.saturating_sub(1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(labels: &[&str]) -> Vec<PickItem> {
+        labels
+            .iter()
+            .map(|l| PickItem {
+                label: l.to_string(),
+                detail: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fuzzy_score_matches_subsequences() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("api", "billing-api").is_some());
+        assert!(fuzzy_score("BAPI", "billing-api").is_some());
+        assert!(fuzzy_score("ipa", "billing-api").is_none());
+    }
+
+    #[test]
+    fn filter_prefers_contiguous_and_boundary_matches() {
+        let items = items(&["web-frontend", "worker", "api-web", "billing"]);
+        assert_eq!(filter("web", &items), vec![0, 2]);
+        assert_eq!(filter("wo", &items), vec![1, 0]);
+        assert_eq!(filter("", &items), vec![0, 1, 2, 3]);
+        assert!(filter("zzz", &items).is_empty());
+    }
+}
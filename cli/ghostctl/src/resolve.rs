@@ -26,10 +26,11 @@ struct ListAppsResponse {
     next_cursor: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct AppItem {
-    id: String,
-    name: String,
+/// App as returned by the list endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppItem {
+    pub id: String,
+    pub name: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,10 +39,11 @@ struct ListEnvsResponse {
     next_cursor: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct EnvItem {
-    id: String,
-    name: String,
+/// Environment as returned by the list endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnvItem {
+    pub id: String,
+    pub name: String,
 }
 
 pub async fn resolve_org_id(client: &ApiClient, org_ident: &str) -> Result<plfm_id::OrgId> {
@@ -88,6 +90,56 @@ pub async fn resolve_org_id(client: &ApiClient, org_ident: &str) -> Result<plfm_
     }
 }
 
+/// All apps of an org (follows pagination).
+pub async fn list_apps(client: &ApiClient, org_id: plfm_id::OrgId) -> Result<Vec<AppItem>> {
+    let mut cursor: Option<String> = None;
+    let mut apps = Vec::new();
+
+    loop {
+        let mut path = format!("/v1/orgs/{org_id}/apps?limit=200");
+        if let Some(c) = cursor.as_deref() {
+            path.push_str(&format!("&cursor={c}"));
+        }
+
+        let response: ListAppsResponse = client.get(&path).await?;
+        apps.extend(response.items);
+
+        cursor = response.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(apps)
+}
+
+/// All environments of an app (follows pagination).
+pub async fn list_envs(
+    client: &ApiClient,
+    org_id: plfm_id::OrgId,
+    app_id: plfm_id::AppId,
+) -> Result<Vec<EnvItem>> {
+    let mut cursor: Option<String> = None;
+    let mut envs = Vec::new();
+
+    loop {
+        let mut path = format!("/v1/orgs/{org_id}/apps/{app_id}/envs?limit=200");
+        if let Some(c) = cursor.as_deref() {
+            path.push_str(&format!("&cursor={c}"));
+        }
+
+        let response: ListEnvsResponse = client.get(&path).await?;
+        envs.extend(response.items);
+
+        cursor = response.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(envs)
+}
+
 pub async fn resolve_app_id(
     client: &ApiClient,
     org_id: plfm_id::OrgId,
@@ -102,31 +154,16 @@ pub async fn resolve_app_id(
         return Ok(id);
     }
 
-    let mut cursor: Option<String> = None;
     let mut matches: Vec<plfm_id::AppId> = Vec::new();
-
-    loop {
-        let mut path = format!("/v1/orgs/{org_id}/apps?limit=200");
-        if let Some(c) = cursor.as_deref() {
-            path.push_str(&format!("&cursor={c}"));
-        }
-
-        let response: ListAppsResponse = client.get(&path).await?;
-        for app in response.items {
-            if app.name == app_ident {
-                let id = app.id.parse::<plfm_id::AppId>().with_context(|| {
-                    format!(
-                        "API returned invalid app id '{}' for app '{}'",
-                        app.id, app.name
-                    )
-                })?;
-                matches.push(id);
-            }
-        }
-
-        cursor = response.next_cursor;
-        if cursor.is_none() {
-            break;
+    for app in list_apps(client, org_id).await? {
+        if app.name == app_ident {
+            let id = app.id.parse::<plfm_id::AppId>().with_context(|| {
+                format!(
+                    "API returned invalid app id '{}' for app '{}'",
+                    app.id, app.name
+                )
+            })?;
+            matches.push(id);
         }
     }
 
@@ -164,31 +201,16 @@ pub async fn resolve_env_id(
         return Ok(id);
     }
 
-    let mut cursor: Option<String> = None;
     let mut matches: Vec<plfm_id::EnvId> = Vec::new();
-
-    loop {
-        let mut path = format!("/v1/orgs/{org_id}/apps/{app_id}/envs?limit=200");
-        if let Some(c) = cursor.as_deref() {
-            path.push_str(&format!("&cursor={c}"));
-        }
-
-        let response: ListEnvsResponse = client.get(&path).await?;
-        for env in response.items {
-            if env.name == env_ident {
-                let id = env.id.parse::<plfm_id::EnvId>().with_context(|| {
-                    format!(
-                        "API returned invalid env id '{}' for env '{}'",
-                        env.id, env.name
-                    )
-                })?;
-                matches.push(id);
-            }
-        }
-
-        cursor = response.next_cursor;
-        if cursor.is_none() {
-            break;
+    for env in list_envs(client, org_id, app_id).await? {
+        if env.name == env_ident {
+            let id = env.id.parse::<plfm_id::EnvId>().with_context(|| {
+                format!(
+                    "API returned invalid env id '{}' for env '{}'",
+                    env.id, env.name
+                )
+            })?;
+            matches.push(id);
         }
     }
 
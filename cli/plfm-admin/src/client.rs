@@ -0,0 +1,126 @@
+//! HTTP client for the control plane's operator endpoints.
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Method, RequestBuilder};
+use serde_json::Value;
+
+/// Talks JSON to `<api_url>/v1` with an operator bearer token.
+#[derive(Debug, Clone)]
+pub struct AdminClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl AdminClient {
+    pub fn new(api_url: &str, token: &str) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).context("Invalid token format")?,
+        );
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url: format!("{}/v1", api_url.trim_end_matches('/')),
+        })
+    }
+
+    pub async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<Value> {
+        self.send(self.request(Method::GET, path).query(query))
+            .await
+    }
+
+    pub async fn post(&self, path: &str, body: Option<Value>) -> Result<Value> {
+        let request = self.request(Method::POST, path);
+        self.send(match body {
+            Some(body) => request.json(&body),
+            None => request,
+        })
+        .await
+    }
+
+    pub async fn put(&self, path: &str, body: Value) -> Result<Value> {
+        self.send(self.request(Method::PUT, path).json(&body)).await
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<Value> {
+        self.send(self.request(Method::DELETE, path)).await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.base_url, path))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Value> {
+        let response = request
+            .send()
+            .await
+            .context("Failed to reach the control plane")?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read response body")?;
+
+        if !status.is_success() {
+            return Err(anyhow!(problem_message(status.as_u16(), &body)));
+        }
+        if body.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&body).context("Control plane returned invalid JSON")
+    }
+}
+
+/// One-line summary of an error response (`application/problem+json`, or
+/// whatever the server sent).
+fn problem_message(status: u16, body: &str) -> String {
+    let Ok(problem) = serde_json::from_str::<Value>(body) else {
+        return format!("HTTP {status}: {}", body.trim());
+    };
+    let field = |name: &str| problem.get(name).and_then(Value::as_str);
+
+    let mut message = match (field("code"), field("detail")) {
+        (Some(code), Some(detail)) => format!("{detail} ({code}, HTTP {status})"),
+        _ => format!("HTTP {status}: {problem}"),
+    };
+    if let Some(request_id) = field("request_id") {
+        message.push_str(&format!(" [request_id: {request_id}]"));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_message() {
+        let body = r#"{"type":"https://plfm.dev/problems/projection_not_paused","status":409,
+            "detail":"Projection 'apps' must be paused before it is rebuilt",
+            "code":"projection_not_paused","request_id":"req_1"}"#;
+        assert_eq!(
+            problem_message(409, body),
+            "Projection 'apps' must be paused before it is rebuilt \
+             (projection_not_paused, HTTP 409) [request_id: req_1]"
+        );
+        assert_eq!(
+            problem_message(502, "bad gateway\n"),
+            "HTTP 502: bad gateway"
+        );
+    }
+
+    #[test]
+    fn test_base_url_appends_version() {
+        let client = AdminClient::new("http://localhost:8080/", "user:ops@example.com").unwrap();
+        assert_eq!(client.base_url, "http://localhost:8080/v1");
+    }
+}
@@ -0,0 +1,192 @@
+//! Control-plane database backup commands.
+//!
+//! Base backups and WAL are pushed to object storage by WAL-G on the
+//! Postgres host. `status` asks the control plane how WAL archiving is
+//! doing; `list` reads the base backups straight from object storage, so it
+//! works while the control plane is down. WAL-G is configured through its
+//! own environment (`WALG_S3_PREFIX`, `AWS_*`, ...).
+
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use serde_json::Value;
+
+use super::CommandContext;
+use crate::output::{self, Output};
+
+#[derive(Debug, Args)]
+pub struct BackupCommand {
+    #[command(subcommand)]
+    command: BackupSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum BackupSubcommand {
+    /// Show WAL archiving health as reported by the control plane.
+    Status {
+        /// Report stale when nothing was archived for this many seconds.
+        #[arg(long)]
+        max_age_secs: Option<i64>,
+    },
+
+    /// List base backups in object storage, oldest first.
+    List(WalgArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WalgArgs {
+    /// WAL-G binary.
+    #[arg(long, env = "PLFM_WALG_BIN", default_value = "wal-g")]
+    pub wal_g: String,
+}
+
+impl BackupCommand {
+    /// Whether the command needs the control plane (and a token).
+    pub fn is_remote(&self) -> bool {
+        matches!(self.command, BackupSubcommand::Status { .. })
+    }
+
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            BackupSubcommand::Status { max_age_secs } => {
+                let query: Vec<(&str, String)> = max_age_secs
+                    .map(|secs| ("max_age_secs", secs.to_string()))
+                    .into_iter()
+                    .collect();
+                let response = ctx.client.get("/_admin/backups", &query).await?;
+                ctx.output.object(
+                    &response,
+                    &[
+                        ("STATE", "state"),
+                        ("ARCHIVE MODE", "archive_mode"),
+                        ("LAST ARCHIVED", "last_archived_wal"),
+                        ("ARCHIVED AT", "last_archived_at"),
+                        ("FAILED", "failed_count"),
+                        ("LAST FAILED", "last_failed_wal"),
+                        ("FAILED AT", "last_failed_at"),
+                    ],
+                );
+            }
+            BackupSubcommand::List(args) => list(&args, ctx.output)?,
+        }
+        Ok(())
+    }
+
+    /// Run a command that only talks to object storage.
+    pub fn run_local(self, output: Output) -> Result<()> {
+        match self.command {
+            BackupSubcommand::List(args) => list(&args, output),
+            BackupSubcommand::Status { .. } => unreachable!("status needs the control plane"),
+        }
+    }
+}
+
+fn list(args: &WalgArgs, output: Output) -> Result<()> {
+    let raw = backup_list_json(&args.wal_g)?;
+    if output.json {
+        output.value(&raw);
+        return Ok(());
+    }
+    let rows = raw.as_array().map(Vec::as_slice).unwrap_or_default();
+    print!(
+        "{}",
+        output::table(
+            rows,
+            &[
+                ("BACKUP", "backup_name"),
+                ("STARTED", "start_time"),
+                ("FINISHED", "finish_time"),
+                ("HOST", "hostname"),
+                ("SIZE", "compressed_size"),
+            ],
+        )
+    );
+    Ok(())
+}
+
+/// A base backup that restores can start from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseBackup {
+    pub name: String,
+    pub finish_time: DateTime<Utc>,
+}
+
+/// Base backups in object storage, oldest first.
+pub fn list_base_backups(wal_g: &str) -> Result<Vec<BaseBackup>> {
+    parse_base_backups(&backup_list_json(wal_g)?)
+}
+
+fn backup_list_json(wal_g: &str) -> Result<Value> {
+    let output = Command::new(wal_g)
+        .args(["backup-list", "--json", "--detail"])
+        .output()
+        .with_context(|| format!("Failed to run {wal_g}"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{wal_g} backup-list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    // An empty storage prints nothing at all.
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Value::Array(Vec::new()));
+    }
+    serde_json::from_slice(&output.stdout).context("Unexpected backup-list output")
+}
+
+/// Parse `wal-g backup-list --json --detail` output.
+fn parse_base_backups(raw: &Value) -> Result<Vec<BaseBackup>> {
+    let items = raw
+        .as_array()
+        .context("Unexpected backup-list output: expected an array")?;
+    let mut backups = items
+        .iter()
+        .map(|item| {
+            let name = item
+                .get("backup_name")
+                .and_then(Value::as_str)
+                .context("backup-list entry without backup_name")?;
+            let finish_time = item
+                .get("finish_time")
+                .and_then(Value::as_str)
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .with_context(|| format!("Backup {name} has no valid finish_time"))?;
+            Ok(BaseBackup {
+                name: name.to_string(),
+                finish_time: finish_time.with_timezone(&Utc),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    backups.sort_by_key(|b| b.finish_time);
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_base_backups() {
+        let raw = json!([
+            {
+                "backup_name": "base_000000010000000000000009",
+                "start_time": "2026-10-16T02:00:00.1Z",
+                "finish_time": "2026-10-16T02:00:41.5Z",
+            },
+            {
+                "backup_name": "base_000000010000000000000004",
+                "start_time": "2026-10-15T02:00:00.1Z",
+                "finish_time": "2026-10-15T02:00:38.2+00:00",
+            },
+        ]);
+        let backups = parse_base_backups(&raw).unwrap();
+        assert_eq!(backups[0].name, "base_000000010000000000000004");
+        assert_eq!(backups[1].name, "base_000000010000000000000009");
+
+        assert!(parse_base_backups(&json!([{ "backup_name": "base_1" }])).is_err());
+        assert!(parse_base_backups(&json!({})).is_err());
+    }
+}
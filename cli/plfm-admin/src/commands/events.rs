@@ -0,0 +1,304 @@
+//! Event log commands: reading the log and verifying its integrity.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::Value;
+
+use super::CommandContext;
+
+/// Events per request (the server's maximum).
+const PAGE_SIZE: usize = 200;
+
+#[derive(Debug, Args)]
+pub struct EventsCommand {
+    #[command(subcommand)]
+    command: EventsSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum EventsSubcommand {
+    /// Print matching events in order, oldest first.
+    Cat(CatArgs),
+
+    /// Check event hashes, aggregate chains, and signed checkpoints; exits
+    /// non-zero if anything was tampered with.
+    Verify(VerifyArgs),
+}
+
+#[derive(Debug, Args)]
+struct VerifyArgs {
+    /// Start after this event ID.
+    #[arg(long, default_value_t = 0)]
+    after: i64,
+
+    /// Events per request.
+    #[arg(long, default_value_t = 10_000)]
+    page_size: i64,
+}
+
+#[derive(Debug, Args)]
+struct CatArgs {
+    /// Aggregate ID (e.g. `inst_...`, `dep_...`).
+    #[arg(long)]
+    aggregate: Option<String>,
+
+    /// Aggregate type (e.g. `instance`, `deploy`).
+    #[arg(long)]
+    aggregate_type: Option<String>,
+
+    /// Event type, or a prefix ending in `*` (e.g. `deploy.*`).
+    #[arg(long = "type")]
+    event_type: Option<String>,
+
+    /// Only events of this org.
+    #[arg(long)]
+    org: Option<String>,
+
+    /// Only events of this app.
+    #[arg(long)]
+    app: Option<String>,
+
+    /// Only events of this env.
+    #[arg(long)]
+    env: Option<String>,
+
+    /// Only events at or after this RFC 3339 timestamp.
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Start after this event ID.
+    #[arg(long, default_value_t = 0)]
+    after: i64,
+
+    /// Stop after this many events (default: all).
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+impl EventsCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            EventsSubcommand::Cat(args) => cat(ctx, args).await,
+            EventsSubcommand::Verify(args) => verify(ctx, args).await,
+        }
+    }
+}
+
+async fn cat(ctx: CommandContext, args: CatArgs) -> Result<()> {
+    let filters = args.filters();
+    let mut after = args.after;
+    let mut remaining = args.limit.unwrap_or(usize::MAX);
+
+    while remaining > 0 {
+        let page_size = remaining.min(PAGE_SIZE);
+        let mut query = filters.clone();
+        query.push(("after_event_id", after.to_string()));
+        query.push(("limit", page_size.to_string()));
+
+        let response = ctx.client.get("/_admin/events", &query).await?;
+        let items = response
+            .get("items")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        for event in &items {
+            if ctx.output.json {
+                println!("{event}");
+            } else {
+                println!("{}", event_line(event));
+            }
+        }
+
+        remaining -= items.len().min(remaining);
+        if items.len() < page_size {
+            break;
+        }
+        after = response
+            .get("next_after_event_id")
+            .and_then(Value::as_i64)
+            .unwrap_or(after);
+    }
+    Ok(())
+}
+
+async fn verify(ctx: CommandContext, args: VerifyArgs) -> Result<()> {
+    let mut after = Some(args.after);
+    let mut findings = 0;
+    let (mut events, mut unchained, mut checkpoints, mut uncheckpointed) = (0, 0, 0, 0);
+
+    while let Some(after_event_id) = after {
+        let query = [
+            ("after_event_id", after_event_id.to_string()),
+            ("limit", args.page_size.to_string()),
+        ];
+        let report = ctx.client.get("/_admin/events/verify", &query).await?;
+        let count = |name: &str| report.get(name).and_then(Value::as_u64).unwrap_or(0);
+        events += count("events_checked");
+        unchained += count("unchained_events");
+        checkpoints += count("checkpoints_checked");
+        uncheckpointed += count("uncheckpointed_events");
+
+        for finding in report
+            .get("findings")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            findings += 1;
+            if ctx.output.json {
+                println!("{finding}");
+            } else {
+                println!("{}", finding_line(finding));
+            }
+        }
+        after = report.get("next_after_event_id").and_then(Value::as_i64);
+    }
+
+    if !ctx.output.json {
+        println!(
+            "Checked {events} events ({unchained} unchained, {uncheckpointed} not yet checkpointed) \
+             and {checkpoints} checkpoints: {findings} findings"
+        );
+    }
+    if findings > 0 {
+        anyhow::bail!("event log verification found {findings} problems");
+    }
+    Ok(())
+}
+
+/// `<kind> <event or checkpoint> <detail>` on one line.
+fn finding_line(finding: &Value) -> String {
+    let field = |name: &str| finding.get(name).and_then(Value::as_str).unwrap_or("-");
+    let subject = match finding.get("event_id").and_then(Value::as_i64) {
+        Some(event_id) => format!(
+            "event {event_id} {}/{}",
+            field("aggregate_type"),
+            field("aggregate_id")
+        ),
+        None => format!(
+            "checkpoint {}",
+            finding
+                .get("checkpoint_id")
+                .and_then(Value::as_i64)
+                .unwrap_or(0)
+        ),
+    };
+    format!("{} {subject}: {}", field("kind"), field("detail"))
+}
+
+impl CatArgs {
+    fn filters(&self) -> Vec<(&'static str, String)> {
+        [
+            ("aggregate_id", &self.aggregate),
+            ("aggregate_type", &self.aggregate_type),
+            ("event_type", &self.event_type),
+            ("org_id", &self.org),
+            ("app_id", &self.app),
+            ("env_id", &self.env),
+            ("since", &self.since),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.clone()?)))
+        .collect()
+    }
+}
+
+/// `<event_id> <occurred_at> <type> <aggregate> <actor> <payload>` on one line.
+fn event_line(event: &Value) -> String {
+    let field = |name: &str| event.get(name).and_then(Value::as_str).unwrap_or("-");
+    let event_id = event.get("event_id").and_then(Value::as_i64).unwrap_or(0);
+    let aggregate_seq = event
+        .get("aggregate_seq")
+        .and_then(Value::as_i64)
+        .map(|seq| format!("#{seq}"))
+        .unwrap_or_default();
+    let payload = event
+        .get("payload")
+        .map(Value::to_string)
+        .unwrap_or_default();
+
+    format!(
+        "{event_id} {} {} {}/{}{aggregate_seq} {}:{} {payload}",
+        field("occurred_at"),
+        field("event_type"),
+        field("aggregate_type"),
+        field("aggregate_id"),
+        field("actor_type"),
+        field("actor_id"),
+    )
+    .trim_end()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_event_line() {
+        let event = json!({
+            "event_id": 42,
+            "occurred_at": "2026-01-02T03:04:05Z",
+            "event_type": "instance.allocated",
+            "aggregate_type": "instance",
+            "aggregate_id": "inst_1",
+            "aggregate_seq": 1,
+            "actor_type": "system",
+            "actor_id": "scheduler",
+            "payload": {"node_id": "node_1"},
+        });
+        assert_eq!(
+            event_line(&event),
+            "42 2026-01-02T03:04:05Z instance.allocated instance/inst_1#1 system:scheduler {\"node_id\":\"node_1\"}"
+        );
+    }
+
+    #[test]
+    fn test_finding_line() {
+        let finding = json!({
+            "kind": "chain_broken",
+            "event_id": 7,
+            "aggregate_type": "app",
+            "aggregate_id": "app_1",
+            "detail": "prev_hash does not match aggregate_seq 2",
+        });
+        assert_eq!(
+            finding_line(&finding),
+            "chain_broken event 7 app/app_1: prev_hash does not match aggregate_seq 2"
+        );
+
+        let finding = json!({
+            "kind": "checkpoint_mismatch",
+            "checkpoint_id": 3,
+            "detail": "events in range do not produce the sealed root",
+        });
+        assert_eq!(
+            finding_line(&finding),
+            "checkpoint_mismatch checkpoint 3: events in range do not produce the sealed root"
+        );
+    }
+
+    #[test]
+    fn test_filters_skip_unset() {
+        let args = CatArgs {
+            aggregate: Some("inst_1".to_string()),
+            aggregate_type: None,
+            event_type: Some("instance.*".to_string()),
+            org: None,
+            app: None,
+            env: None,
+            since: None,
+            after: 0,
+            limit: None,
+        };
+        assert_eq!(
+            args.filters(),
+            vec![
+                ("aggregate_id", "inst_1".to_string()),
+                ("event_type", "instance.*".to_string()),
+            ]
+        );
+    }
+}
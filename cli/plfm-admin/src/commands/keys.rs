@@ -0,0 +1,137 @@
+//! Key rotation commands.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::{Map, Value};
+
+use super::CommandContext;
+use crate::output::Column;
+
+const JOB_COLUMNS: &[Column] = &[
+    ("JOB", "job_id"),
+    ("STATE", "state"),
+    ("ORG", "org_id"),
+    ("TARGET KEY", "target_key_id"),
+    ("TOTAL", "total"),
+    ("REWRAPPED", "rewrapped"),
+    ("FAILED", "failed"),
+    ("UPDATED", "updated_at"),
+];
+
+#[derive(Debug, Args)]
+pub struct KeysCommand {
+    #[command(subcommand)]
+    command: KeysSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum KeysSubcommand {
+    /// Show the secrets KMS provider, current KEK, and rows per stored key.
+    Status,
+
+    /// Start a background job re-wrapping data keys under the current KEK.
+    Rotate(RotateArgs),
+
+    /// List key rotation jobs, newest first.
+    Jobs {
+        /// Maximum number of jobs to show.
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+
+    /// Show one key rotation job.
+    Job {
+        /// Job ID.
+        job: String,
+    },
+
+    /// Cancel a running key rotation job.
+    Cancel {
+        /// Job ID.
+        job: String,
+    },
+
+    /// Retire the active platform CA and start signing with a new one.
+    RotateCa,
+}
+
+#[derive(Debug, Args)]
+struct RotateArgs {
+    /// Only re-wrap this org's secret material.
+    #[arg(long)]
+    org: Option<String>,
+
+    /// Rows per batch (server default 100, max 1000).
+    #[arg(long)]
+    batch_size: Option<i32>,
+}
+
+impl KeysCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            KeysSubcommand::Status => {
+                let response = ctx.client.get("/_admin/secrets/keys", &[]).await?;
+                if !ctx.output.json {
+                    ctx.output.object(
+                        &response,
+                        &[
+                            ("PROVIDER", "provider"),
+                            ("CURRENT KEY", "current_platform_key_id"),
+                        ],
+                    );
+                    println!();
+                }
+                ctx.output.list(
+                    &response,
+                    "items",
+                    &[
+                        ("KEY", "master_key_id"),
+                        ("CURRENT", "current"),
+                        ("SECRETS", "secret_material"),
+                        ("PKI", "pki_authorities"),
+                    ],
+                );
+            }
+            KeysSubcommand::Rotate(args) => {
+                let mut body = Map::new();
+                if let Some(org) = args.org {
+                    body.insert("org_id".to_string(), Value::from(org));
+                }
+                if let Some(batch_size) = args.batch_size {
+                    body.insert("batch_size".to_string(), Value::from(batch_size));
+                }
+                let response = ctx
+                    .client
+                    .post("/_admin/secrets/rotations", Some(Value::Object(body)))
+                    .await?;
+                ctx.output.object(&response, JOB_COLUMNS);
+            }
+            KeysSubcommand::Jobs { limit } => {
+                let response = ctx
+                    .client
+                    .get("/_admin/secrets/rotations", &[("limit", limit.to_string())])
+                    .await?;
+                ctx.output.list(&response, "items", JOB_COLUMNS);
+            }
+            KeysSubcommand::Job { job } => {
+                let response = ctx
+                    .client
+                    .get(&format!("/_admin/secrets/rotations/{job}"), &[])
+                    .await?;
+                ctx.output.object(&response, JOB_COLUMNS);
+            }
+            KeysSubcommand::Cancel { job } => {
+                let response = ctx
+                    .client
+                    .post(&format!("/_admin/secrets/rotations/{job}/cancel"), None)
+                    .await?;
+                ctx.output.object(&response, JOB_COLUMNS);
+            }
+            KeysSubcommand::RotateCa => {
+                let response = ctx.client.post("/_admin/pki/rotate", None).await?;
+                ctx.output.object(&response, &[("CA", "ca_id")]);
+            }
+        }
+        Ok(())
+    }
+}
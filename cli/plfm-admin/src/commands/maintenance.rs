@@ -0,0 +1,88 @@
+//! Maintenance mode commands.
+//!
+//! While maintenance is on the API rejects writes with `503
+//! maintenance_mode`; reads and node agent traffic keep working.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use super::CommandContext;
+use crate::output::Column;
+
+const MAINTENANCE_COLUMNS: &[Column] = &[
+    ("ENABLED", "enabled"),
+    ("REASON", "maintenance.reason"),
+    ("STARTED AT", "maintenance.started_at"),
+    ("STARTED BY", "maintenance.started_by"),
+    ("RETRY AFTER", "maintenance.retry_after_secs"),
+    ("IN-FLIGHT WRITES", "in_flight_writes"),
+    ("DRAINED", "drained"),
+];
+
+#[derive(Debug, Args)]
+pub struct MaintenanceCommand {
+    #[command(subcommand)]
+    command: MaintenanceSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum MaintenanceSubcommand {
+    /// Show whether maintenance mode is on.
+    Status,
+
+    /// Make the API read-only and wait for in-flight work to drain.
+    On {
+        /// Reason shown to clients whose writes are rejected.
+        #[arg(long)]
+        reason: Option<String>,
+        /// Seconds clients are told to wait before retrying a write.
+        #[arg(long)]
+        retry_after: Option<u32>,
+        /// Seconds to wait for in-flight writes and projections (max 300).
+        #[arg(long)]
+        drain_timeout: Option<u64>,
+    },
+
+    /// Accept writes again.
+    Off,
+}
+
+impl MaintenanceCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        let response = match self.command {
+            MaintenanceSubcommand::Status => ctx.client.get("/_admin/maintenance", &[]).await?,
+            MaintenanceSubcommand::On {
+                reason,
+                retry_after,
+                drain_timeout,
+            } => {
+                let response = ctx
+                    .client
+                    .put(
+                        "/_admin/maintenance",
+                        json!({
+                            "enabled": true,
+                            "reason": reason,
+                            "retry_after_secs": retry_after,
+                            "drain_timeout_secs": drain_timeout,
+                        }),
+                    )
+                    .await?;
+                if response["drained"] == json!(false) {
+                    eprintln!(
+                        "warning: in-flight work did not drain in time; check `plfm-admin projections list`"
+                    );
+                }
+                response
+            }
+            MaintenanceSubcommand::Off => {
+                ctx.client
+                    .put("/_admin/maintenance", json!({ "enabled": false }))
+                    .await?
+            }
+        };
+        ctx.output.object(&response, MAINTENANCE_COLUMNS);
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! Command definitions.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use crate::client::AdminClient;
+use crate::output::Output;
+
+mod backup;
+mod events;
+mod keys;
+mod maintenance;
+mod nodes;
+mod projections;
+mod quotas;
+mod restore;
+
+#[derive(Debug, Parser)]
+#[command(name = "plfm-admin", about = "plfm-vt control plane operator tools")]
+pub struct Cli {
+    /// Control plane API URL.
+    #[arg(
+        long,
+        global = true,
+        env = "PLFM_API_URL",
+        default_value = "http://localhost:8080"
+    )]
+    api_url: String,
+
+    /// Bearer token of a platform operator.
+    #[arg(long, global = true, env = "PLFM_ADMIN_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Print raw JSON responses.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Inspect, pause, resume, and rebuild projections.
+    Projections(projections::ProjectionsCommand),
+
+    /// Show which replica leads each singleton worker.
+    Leaders,
+
+    /// Inspect the event log across orgs.
+    Events(events::EventsCommand),
+
+    /// Show and override per-org quotas.
+    Quotas(quotas::QuotasCommand),
+
+    /// Review nodes waiting for enrollment approval.
+    Nodes(nodes::NodesCommand),
+
+    /// Rotate the secrets KEK and the platform CA.
+    Keys(keys::KeysCommand),
+
+    /// Make the API read-only for database maintenance, and back.
+    Maintenance(maintenance::MaintenanceCommand),
+
+    /// Check WAL archiving and list base backups of the control-plane database.
+    Backup(backup::BackupCommand),
+
+    /// Restore the control-plane database to a point in time, into a new data
+    /// directory.
+    Restore(restore::RestoreArgs),
+}
+
+/// Shared state for command execution.
+pub struct CommandContext {
+    pub client: AdminClient,
+    pub output: Output,
+}
+
+impl Cli {
+    pub async fn run(self) -> Result<()> {
+        let output = Output { json: self.json };
+
+        // These only need object storage, so they work while the control
+        // plane is down.
+        let command = match self.command {
+            Command::Restore(args) => return args.run(output),
+            Command::Backup(cmd) if !cmd.is_remote() => return cmd.run_local(output),
+            command => command,
+        };
+
+        let Some(token) = self.token.as_deref() else {
+            anyhow::bail!("No operator token: pass --token or set PLFM_ADMIN_TOKEN");
+        };
+        let ctx = CommandContext {
+            client: AdminClient::new(&self.api_url, token)?,
+            output,
+        };
+
+        match command {
+            Command::Projections(cmd) => cmd.run(ctx).await,
+            Command::Leaders => leaders(ctx).await,
+            Command::Events(cmd) => cmd.run(ctx).await,
+            Command::Quotas(cmd) => cmd.run(ctx).await,
+            Command::Nodes(cmd) => cmd.run(ctx).await,
+            Command::Keys(cmd) => cmd.run(ctx).await,
+            Command::Maintenance(cmd) => cmd.run(ctx).await,
+            Command::Backup(cmd) => cmd.run(ctx).await,
+            Command::Restore(_) => unreachable!("restore runs locally"),
+        }
+    }
+}
+
+async fn leaders(ctx: CommandContext) -> Result<()> {
+    let response = ctx.client.get("/_admin/leaders", &[]).await?;
+    ctx.output.list(
+        &response,
+        "items",
+        &[
+            ("ROLE", "role"),
+            ("HOLDER", "holder"),
+            ("SINCE", "since"),
+            ("LEASED UNTIL", "leased_until"),
+        ],
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_events_cat_parses_aggregate() {
+        let cli = Cli::try_parse_from([
+            "plfm-admin",
+            "--token",
+            "t",
+            "events",
+            "cat",
+            "--aggregate",
+            "inst_01HV4Z2WQXKJNM8GPQY6VBKC3D",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Command::Events(_)));
+        assert_eq!(cli.token.as_deref(), Some("t"));
+    }
+
+    #[test]
+    fn test_restore_parses_without_token() {
+        let cli = Cli::try_parse_from([
+            "plfm-admin",
+            "restore",
+            "--target-time",
+            "2026-10-16T09:30:00Z",
+            "--data-dir",
+            "/var/lib/postgresql/restore",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Command::Restore(_)));
+        assert!(cli.token.is_none());
+
+        assert!(Cli::try_parse_from([
+            "plfm-admin",
+            "restore",
+            "--target-time",
+            "yesterday",
+            "--data-dir",
+            "/tmp/restore",
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Node enrollment approval commands.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use super::CommandContext;
+
+#[derive(Debug, Args)]
+pub struct NodesCommand {
+    #[command(subcommand)]
+    command: NodesSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum NodesSubcommand {
+    /// List nodes waiting in the approval queue.
+    Pending,
+
+    /// Approve a pending node (it becomes `active`).
+    Approve {
+        /// Node ID.
+        node: String,
+    },
+
+    /// Deny a pending node.
+    Deny {
+        /// Node ID.
+        node: String,
+        /// Reason recorded with the decision.
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+impl NodesCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        let response = match self.command {
+            NodesSubcommand::Pending => {
+                let response = ctx
+                    .client
+                    .get(
+                        "/nodes",
+                        &[
+                            ("state", "pending_approval".to_string()),
+                            ("limit", "200".to_string()),
+                        ],
+                    )
+                    .await?;
+                ctx.output.list(
+                    &response,
+                    "items",
+                    &[
+                        ("ID", "id"),
+                        ("MTLS SUBJECT", "agent_mtls_subject"),
+                        ("PUBLIC IPV6", "public_ipv6"),
+                        ("CREATED", "created_at"),
+                    ],
+                );
+                return Ok(());
+            }
+            NodesSubcommand::Approve { node } => {
+                ctx.client
+                    .post(&format!("/_admin/nodes/{node}/approve"), None)
+                    .await?
+            }
+            NodesSubcommand::Deny { node, reason } => {
+                ctx.client
+                    .post(
+                        &format!("/_admin/nodes/{node}/deny"),
+                        Some(json!({ "reason": reason })),
+                    )
+                    .await?
+            }
+        };
+        ctx.output
+            .object(&response, &[("NODE", "node_id"), ("STATE", "state")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! Projection commands.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::Value;
+
+use super::CommandContext;
+
+#[derive(Debug, Args)]
+pub struct ProjectionsCommand {
+    #[command(subcommand)]
+    command: ProjectionsSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ProjectionsSubcommand {
+    /// Checkpoint, lag, apply rate, errors, and owner of every projection.
+    List,
+
+    /// Stop applying events to a projection.
+    Pause(ProjectionArgs),
+
+    /// Resume a paused projection from its checkpoint.
+    Resume(ProjectionArgs),
+
+    /// Replay a projection from the start of the event log.
+    ///
+    /// Pauses the projection, rewinds its checkpoint, and resumes it.
+    /// Replay upserts over existing rows; it does not truncate tables.
+    Rebuild(RebuildArgs),
+}
+
+#[derive(Debug, Args)]
+struct ProjectionArgs {
+    /// Projection name (see `plfm-admin projections list`).
+    name: String,
+}
+
+#[derive(Debug, Args)]
+struct RebuildArgs {
+    /// Projection name (see `plfm-admin projections list`).
+    name: String,
+
+    /// Leave the projection paused after rewinding it, e.g. to truncate
+    /// its tables before it replays.
+    #[arg(long)]
+    no_resume: bool,
+}
+
+impl ProjectionsCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            ProjectionsSubcommand::List => list(ctx).await,
+            ProjectionsSubcommand::Pause(args) => set_paused(ctx, &args.name, true).await,
+            ProjectionsSubcommand::Resume(args) => set_paused(ctx, &args.name, false).await,
+            ProjectionsSubcommand::Rebuild(args) => rebuild(ctx, args).await,
+        }
+    }
+}
+
+async fn list(ctx: CommandContext) -> Result<()> {
+    let response = ctx.client.get("/_admin/projections", &[]).await?;
+    ctx.output.list(
+        &response,
+        "items",
+        &[
+            ("NAME", "projection_name"),
+            ("CHECKPOINT", "last_applied_event_id"),
+            ("LAG", "lag"),
+            ("PENDING", "pending_events"),
+            ("EVENTS/S", "events_per_second"),
+            ("PAUSED", "paused"),
+            ("OWNER", "owner.holder"),
+            ("LAST ERROR", "last_error.message"),
+        ],
+    );
+    Ok(())
+}
+
+async fn set_paused(ctx: CommandContext, name: &str, paused: bool) -> Result<()> {
+    let action = if paused { "pause" } else { "resume" };
+    let response = ctx.client.post(&action_path(name, action), None).await?;
+    if ctx.output.json {
+        ctx.output.value(&response);
+    } else {
+        println!("Projection {name} {action}d");
+    }
+    Ok(())
+}
+
+async fn rebuild(ctx: CommandContext, args: RebuildArgs) -> Result<()> {
+    let name = args.name.as_str();
+    let projections = ctx.client.get("/_admin/projections", &[]).await?;
+    let was_paused = is_paused(&projections, name);
+
+    if !was_paused {
+        ctx.client.post(&action_path(name, "pause"), None).await?;
+    }
+    let response = match ctx.client.post(&action_path(name, "rebuild"), None).await {
+        Ok(response) => response,
+        Err(e) => {
+            // Leave the projection as we found it.
+            if !was_paused {
+                ctx.client.post(&action_path(name, "resume"), None).await?;
+            }
+            return Err(e);
+        }
+    };
+    if !args.no_resume {
+        ctx.client.post(&action_path(name, "resume"), None).await?;
+    }
+
+    if ctx.output.json {
+        ctx.output.value(&response);
+    } else if args.no_resume {
+        println!(
+            "Projection {name} rewound and left paused; run `plfm-admin projections resume {name}` to replay"
+        );
+    } else {
+        println!(
+            "Projection {name} is replaying from the start; follow it with `plfm-admin projections list`"
+        );
+    }
+    Ok(())
+}
+
+fn action_path(name: &str, action: &str) -> String {
+    format!("/_admin/projections/{name}/{action}")
+}
+
+/// Whether `name` is paused in a `GET /_admin/projections` response.
+fn is_paused(projections: &Value, name: &str) -> bool {
+    projections
+        .get("items")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .find(|item| item.get("projection_name").and_then(Value::as_str) == Some(name))
+        .and_then(|item| item.get("paused"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_is_paused() {
+        let response = json!({
+            "max_event_id": 10,
+            "items": [
+                {"projection_name": "apps", "paused": true},
+                {"projection_name": "envs", "paused": false},
+            ],
+        });
+        assert!(is_paused(&response, "apps"));
+        assert!(!is_paused(&response, "envs"));
+        assert!(!is_paused(&response, "unknown"));
+    }
+
+    #[test]
+    fn test_action_path() {
+        assert_eq!(
+            action_path("instances", "rebuild"),
+            "/_admin/projections/instances/rebuild"
+        );
+    }
+}
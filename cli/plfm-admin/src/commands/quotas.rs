@@ -0,0 +1,85 @@
+//! Quota override commands.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use super::CommandContext;
+use crate::output::Column;
+
+const QUOTA_COLUMNS: &[Column] = &[
+    ("DIMENSION", "dimension"),
+    ("LIMIT", "limit"),
+    ("DEFAULT", "default_limit"),
+    ("OVERRIDE", "override_limit"),
+    ("USAGE", "usage"),
+];
+
+#[derive(Debug, Args)]
+pub struct QuotasCommand {
+    #[command(subcommand)]
+    command: QuotasSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum QuotasSubcommand {
+    /// Show every quota dimension of an org with its usage.
+    Get {
+        /// Org ID.
+        org: String,
+    },
+
+    /// Override an org's limit for one dimension.
+    Set {
+        /// Org ID.
+        org: String,
+        /// Dimension (e.g. `max_instances`).
+        dimension: String,
+        /// New limit (bytes for `*_bytes` dimensions).
+        limit: i64,
+    },
+
+    /// Remove an override, restoring the default limit.
+    Clear {
+        /// Org ID.
+        org: String,
+        /// Dimension (e.g. `max_instances`).
+        dimension: String,
+    },
+}
+
+impl QuotasCommand {
+    pub async fn run(self, ctx: CommandContext) -> Result<()> {
+        match self.command {
+            QuotasSubcommand::Get { org } => {
+                let response = ctx
+                    .client
+                    .get(&format!("/_admin/orgs/{org}/quotas"), &[])
+                    .await?;
+                ctx.output.list(&response, "items", QUOTA_COLUMNS);
+            }
+            QuotasSubcommand::Set {
+                org,
+                dimension,
+                limit,
+            } => {
+                let response = ctx
+                    .client
+                    .put(
+                        &format!("/_admin/orgs/{org}/quotas/{dimension}"),
+                        json!({ "limit": limit }),
+                    )
+                    .await?;
+                ctx.output.object(&response, QUOTA_COLUMNS);
+            }
+            QuotasSubcommand::Clear { org, dimension } => {
+                let response = ctx
+                    .client
+                    .delete(&format!("/_admin/orgs/{org}/quotas/{dimension}"))
+                    .await?;
+                ctx.output.object(&response, QUOTA_COLUMNS);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Point-in-time restore of the control-plane database.
+//!
+//! Restores into a new, empty data directory and never touches the running
+//! cluster: fetch the newest base backup that finished before the target
+//! time, then configure Postgres to replay archived WAL up to the target and
+//! promote. Starting Postgres on the directory and switching the control
+//! plane over stay manual steps (docs/ops/07-backup-restore-runbook.md).
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+
+use super::backup::{list_base_backups, BaseBackup, WalgArgs};
+use crate::output::Output;
+
+#[derive(Debug, Args)]
+pub struct RestoreArgs {
+    /// Recover up to this instant (RFC 3339, e.g. 2026-10-16T09:30:00Z).
+    #[arg(long)]
+    target_time: DateTime<Utc>,
+
+    /// New Postgres data directory; must not exist or be empty.
+    #[arg(long)]
+    data_dir: PathBuf,
+
+    /// Base backup to start from (default: the newest one before the target).
+    #[arg(long)]
+    backup: Option<String>,
+
+    /// Print the plan without fetching anything.
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    wal_g: WalgArgs,
+}
+
+impl RestoreArgs {
+    pub fn run(self, output: Output) -> Result<()> {
+        if self.target_time > Utc::now() {
+            anyhow::bail!("--target-time is in the future");
+        }
+        ensure_empty(&self.data_dir)?;
+
+        let backups = list_base_backups(&self.wal_g.wal_g)?;
+        let base = select_backup(&backups, self.target_time, self.backup.as_deref())?;
+
+        if output.json {
+            output.value(&serde_json::json!({
+                "backup_name": base.name,
+                "backup_finish_time": base.finish_time,
+                "target_time": self.target_time,
+                "data_dir": self.data_dir,
+                "dry_run": self.dry_run,
+            }));
+        } else {
+            println!(
+                "Restoring {} (finished {}) to {} into {}",
+                base.name,
+                base.finish_time.to_rfc3339(),
+                self.target_time.to_rfc3339(),
+                self.data_dir.display()
+            );
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let status = Command::new(&self.wal_g.wal_g)
+            .arg("backup-fetch")
+            .arg(&self.data_dir)
+            .arg(&base.name)
+            .status()
+            .with_context(|| format!("Failed to run {}", self.wal_g.wal_g))?;
+        if !status.success() {
+            anyhow::bail!("{} backup-fetch failed ({status})", self.wal_g.wal_g);
+        }
+
+        configure_recovery(&self.data_dir, self.target_time, &self.wal_g.wal_g)?;
+
+        if !output.json {
+            println!();
+            println!("Recovery configured. Next:");
+            println!(
+                "  1. Start Postgres on {} and wait for it to promote",
+                self.data_dir.display()
+            );
+            println!("     (SELECT pg_is_in_recovery() returns false).");
+            println!("  2. Verify: SELECT max(occurred_at) FROM events;");
+            println!("  3. Follow docs/ops/07-backup-restore-runbook.md to switch over.");
+        }
+        Ok(())
+    }
+}
+
+/// Refuse to restore over anything.
+fn ensure_empty(data_dir: &Path) -> Result<()> {
+    match fs::read_dir(data_dir) {
+        Ok(mut entries) => {
+            if entries.next().is_some() {
+                anyhow::bail!("{} is not empty", data_dir.display());
+            }
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Cannot read {}", data_dir.display())),
+    }
+}
+
+/// The base backup to replay from: `requested`, or the newest one that
+/// finished at or before `target`. Recovery cannot stop before the end of
+/// its base backup, so a later backup is never usable.
+fn select_backup<'a>(
+    backups: &'a [BaseBackup],
+    target: DateTime<Utc>,
+    requested: Option<&str>,
+) -> Result<&'a BaseBackup> {
+    let base = match requested {
+        Some(name) => backups
+            .iter()
+            .find(|b| b.name == name)
+            .with_context(|| format!("Base backup {name} not found"))?,
+        None => backups
+            .iter()
+            .filter(|b| b.finish_time <= target)
+            .max_by_key(|b| b.finish_time)
+            .with_context(|| match backups.first() {
+                Some(oldest) => format!(
+                    "No base backup finished before {}; the oldest finished at {}",
+                    target.to_rfc3339(),
+                    oldest.finish_time.to_rfc3339()
+                ),
+                None => "No base backups found".to_string(),
+            })?,
+    };
+    if base.finish_time > target {
+        anyhow::bail!(
+            "Base backup {} finished at {}, after the target time",
+            base.name,
+            base.finish_time.to_rfc3339()
+        );
+    }
+    Ok(base)
+}
+
+/// Recovery settings appended to `postgresql.auto.conf`.
+///
+/// Archiving is switched off so the restored cluster cannot write a new
+/// timeline into the production WAL archive before it is validated.
+fn recovery_settings(target: DateTime<Utc>, wal_g: &str) -> String {
+    format!(
+        "\n# Added by plfm-admin restore\n\
+         restore_command = '{wal_g} wal-fetch \"%f\" \"%p\"'\n\
+         recovery_target_time = '{}'\n\
+         recovery_target_action = 'promote'\n\
+         archive_mode = 'off'\n",
+        target.format("%Y-%m-%d %H:%M:%S%.6f+00")
+    )
+}
+
+fn configure_recovery(data_dir: &Path, target: DateTime<Utc>, wal_g: &str) -> Result<()> {
+    let conf = data_dir.join("postgresql.auto.conf");
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&conf)
+        .and_then(|mut file| file.write_all(recovery_settings(target, wal_g).as_bytes()))
+        .with_context(|| format!("Failed to write {}", conf.display()))?;
+
+    let signal = data_dir.join("recovery.signal");
+    fs::write(&signal, b"").with_context(|| format!("Failed to write {}", signal.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup(name: &str, finish_time: &str) -> BaseBackup {
+        BaseBackup {
+            name: name.to_string(),
+            finish_time: finish_time.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_select_backup() {
+        let backups = [
+            backup("base_a", "2026-10-14T02:00:40Z"),
+            backup("base_b", "2026-10-15T02:00:40Z"),
+            backup("base_c", "2026-10-16T02:00:40Z"),
+        ];
+        let at = |t: &str| t.parse::<DateTime<Utc>>().unwrap();
+
+        let base = select_backup(&backups, at("2026-10-15T12:00:00Z"), None).unwrap();
+        assert_eq!(base.name, "base_b");
+        let base = select_backup(&backups, at("2026-10-15T02:00:40Z"), None).unwrap();
+        assert_eq!(base.name, "base_b");
+
+        let base = select_backup(&backups, at("2026-10-15T12:00:00Z"), Some("base_a")).unwrap();
+        assert_eq!(base.name, "base_a");
+        assert!(select_backup(&backups, at("2026-10-15T12:00:00Z"), Some("base_c")).is_err());
+        assert!(select_backup(&backups, at("2026-10-15T12:00:00Z"), Some("base_x")).is_err());
+
+        assert!(select_backup(&backups, at("2026-10-13T00:00:00Z"), None).is_err());
+        assert!(select_backup(&[], at("2026-10-13T00:00:00Z"), None).is_err());
+    }
+
+    #[test]
+    fn test_configure_recovery() {
+        let dir = std::env::temp_dir().join(format!("plfm-admin-restore-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("postgresql.auto.conf"), "work_mem = '8MB'\n").unwrap();
+        assert!(ensure_empty(&dir).is_err());
+
+        let target = "2026-10-16T09:30:00.25Z".parse().unwrap();
+        configure_recovery(&dir, target, "wal-g").unwrap();
+
+        let conf = fs::read_to_string(dir.join("postgresql.auto.conf")).unwrap();
+        assert!(conf.starts_with("work_mem = '8MB'\n"));
+        assert!(conf.contains("restore_command = 'wal-g wal-fetch \"%f\" \"%p\"'\n"));
+        assert!(conf.contains("recovery_target_time = '2026-10-16 09:30:00.250000+00'\n"));
+        assert!(conf.contains("recovery_target_action = 'promote'\n"));
+        assert!(dir.join("recovery.signal").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(ensure_empty(&dir).is_ok());
+    }
+}
@@ -0,0 +1,24 @@
+//! plfm-admin - operator CLI for the plfm-vt control plane.
+//!
+//! Wraps the operator endpoints under `/v1/_admin` (and the few operator
+//! actions elsewhere): projection pause/resume/rebuild, cross-org event
+//! inspection, quota overrides, node approval, key rotation, and backup
+//! status. Every call needs a token for a user listed in
+//! `PLFM_OPERATOR_EMAILS`. Listing base backups and point-in-time restore
+//! go to object storage through WAL-G instead.
+//!
+//! See: docs/specs/api/http-api.md, docs/ops/07-backup-restore-runbook.md
+
+use anyhow::Result;
+use clap::Parser;
+
+mod client;
+mod commands;
+mod output;
+
+use commands::Cli;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    Cli::parse().run().await
+}
@@ -0,0 +1,125 @@
+//! Rendering responses: raw JSON with `--json`, otherwise aligned tables.
+
+use serde_json::Value;
+
+/// A table column: header and the response field it shows.
+pub type Column = (&'static str, &'static str);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    pub json: bool,
+}
+
+impl Output {
+    /// Print `value` as JSON, or `items` of it as a table.
+    pub fn list(&self, value: &Value, items: &str, columns: &[Column]) {
+        if self.json {
+            self.value(value);
+            return;
+        }
+        let rows = value
+            .get(items)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        print!("{}", table(rows, columns));
+    }
+
+    /// Print a single object as JSON, or as `field: value` lines.
+    pub fn object(&self, value: &Value, columns: &[Column]) {
+        if self.json {
+            self.value(value);
+            return;
+        }
+        let width = columns.iter().map(|(h, _)| h.len()).max().unwrap_or(0);
+        for (header, field) in columns {
+            println!("{header:<width$}  {}", cell(value, field));
+        }
+    }
+
+    pub fn value(&self, value: &Value) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        );
+    }
+}
+
+/// Render rows as space-aligned columns under a header line.
+pub fn table(rows: &[Value], columns: &[Column]) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|(_, field)| cell(row, field)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, (header, _))| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    let headers = columns.iter().map(|(header, _)| header.to_string());
+    for line in std::iter::once(headers.collect()).chain(cells) {
+        let line: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// A field of `row` as display text. Dotted names reach into nested
+/// objects; missing and null values show as `-`.
+pub fn cell(row: &Value, field: &str) -> String {
+    let value = field.split('.').try_fold(row, |value, key| value.get(key));
+    match value {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_table_aligns_columns() {
+        let rows = [
+            json!({"projection_name": "apps", "lag": 0, "owner": {"holder": "cp-1"}}),
+            json!({"projection_name": "instances", "lag": 1200, "owner": null}),
+        ];
+        let columns = [
+            ("NAME", "projection_name"),
+            ("LAG", "lag"),
+            ("OWNER", "owner.holder"),
+        ];
+        assert_eq!(
+            table(&rows, &columns),
+            "NAME       LAG   OWNER\n\
+             apps       0     cp-1\n\
+             instances  1200  -\n"
+        );
+    }
+
+    #[test]
+    fn test_cell_formats_values() {
+        let row = json!({"paused": true, "error": {"message": "boom"}, "none": null});
+        assert_eq!(cell(&row, "paused"), "true");
+        assert_eq!(cell(&row, "error.message"), "boom");
+        assert_eq!(cell(&row, "none"), "-");
+        assert_eq!(cell(&row, "missing.field"), "-");
+    }
+}
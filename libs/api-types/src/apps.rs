@@ -0,0 +1,174 @@
+//! Applications: `/v1/orgs/{org_id}/apps`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Labels, Validate, Validator};
+
+/// Request to create a new application.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateAppRequest {
+    /// Application name (unique within org).
+    pub name: String,
+
+    /// Optional description.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateAppRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub expected_version: i32,
+}
+
+impl Validate for CreateAppRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Application", 100);
+    }
+}
+
+impl Validate for UpdateAppRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.name("name", name, "Application", 100);
+        }
+    }
+}
+
+/// Teardown status of an application deletion.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AppDeletionResponse {
+    /// Application ID.
+    pub app_id: String,
+
+    /// Overall status (in_progress, completed).
+    pub status: String,
+
+    /// Whether volumes are deleted as part of the teardown.
+    pub delete_volumes: bool,
+
+    /// When deletion was requested.
+    pub requested_at: Option<DateTime<Utc>>,
+
+    /// Environments of the app not yet torn down.
+    pub envs_remaining: i64,
+}
+
+/// Response for a single application.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AppResponse {
+    /// Application ID.
+    pub id: String,
+
+    /// Organization ID.
+    pub org_id: String,
+
+    /// Application name.
+    pub name: String,
+
+    /// Optional description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// User-assigned labels.
+    pub labels: Labels,
+
+    /// Resource version for optimistic concurrency.
+    pub resource_version: i32,
+
+    /// When the app was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the app was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response for listing applications.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListAppsResponse {
+    /// List of applications.
+    pub items: Vec<AppResponse>,
+
+    /// Next cursor (null if no more results).
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_app_request_deserialization() {
+        let json = r#"{"name": "my-app", "description": "A test app"}"#;
+        let req: CreateAppRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.name, "my-app");
+        assert_eq!(req.description, Some("A test app".to_string()));
+    }
+
+    #[test]
+    fn test_create_app_request_without_description() {
+        let json = r#"{"name": "my-app"}"#;
+        let req: CreateAppRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.name, "my-app");
+        assert_eq!(req.description, None);
+    }
+
+    #[test]
+    fn test_app_response_serialization() {
+        let response = AppResponse {
+            id: "app_123".to_string(),
+            org_id: "org_456".to_string(),
+            name: "Test App".to_string(),
+            description: Some("A test".to_string()),
+            labels: Labels::from([("team".to_string(), "payments".to_string())]),
+            resource_version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"id\":\"app_123\""));
+        assert!(json.contains("\"org_id\":\"org_456\""));
+        assert!(json.contains("\"labels\":{\"team\":\"payments\"}"));
+    }
+
+    #[test]
+    fn test_app_response_round_trip() {
+        let response = AppResponse {
+            id: "app_123".to_string(),
+            org_id: "org_456".to_string(),
+            name: "Test App".to_string(),
+            description: None,
+            labels: Labels::new(),
+            resource_version: 3,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        let decoded: AppResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.id, response.id);
+        assert_eq!(decoded.resource_version, 3);
+        assert_eq!(decoded.created_at, response.created_at);
+    }
+
+    #[test]
+    fn test_app_deletion_response_serialization() {
+        let response = AppDeletionResponse {
+            app_id: "app_123".to_string(),
+            status: "in_progress".to_string(),
+            delete_volumes: true,
+            requested_at: Some(Utc::now()),
+            envs_remaining: 2,
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"status\":\"in_progress\""));
+        assert!(json.contains("\"delete_volumes\":true"));
+        assert!(json.contains("\"envs_remaining\":2"));
+    }
+}
@@ -0,0 +1,22 @@
+//! Request and response types of the control-plane HTTP API.
+//!
+//! The control plane's handlers and the `vt` CLI both use these types, so a
+//! renamed or retyped field is a compile error on both sides instead of a
+//! runtime decode failure. Request types carry their [`Validate`] impls so
+//! the rules travel with the shape.
+//!
+//! Types move here from `services/control-plane/src/api/v1` one resource at a
+//! time; endpoints not covered yet are described by the models the CLI
+//! generates from `api/openapi/openapi.yaml`.
+
+use std::collections::BTreeMap;
+
+pub mod apps;
+pub mod orgs;
+pub mod projects;
+mod validation;
+
+pub use validation::{FieldError, Validate, Validator};
+
+/// Labels attached to a resource.
+pub type Labels = BTreeMap<String, String>;
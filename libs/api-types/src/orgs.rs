@@ -0,0 +1,89 @@
+//! Organizations: `/v1/orgs`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Validate, Validator};
+
+/// Request to create a new organization.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateOrgRequest {
+    /// Organization name.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateOrgRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub expected_version: i32,
+}
+
+impl Validate for CreateOrgRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Organization", 100);
+    }
+}
+
+impl Validate for UpdateOrgRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.name("name", name, "Organization", 100);
+        }
+    }
+}
+
+/// Response for a single organization.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OrgResponse {
+    /// Organization ID.
+    pub id: String,
+
+    /// Organization name.
+    pub name: String,
+
+    /// Resource version for optimistic concurrency.
+    pub resource_version: i32,
+
+    /// When the org was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the org was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response for listing organizations.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListOrgsResponse {
+    /// List of organizations.
+    pub items: Vec<OrgResponse>,
+
+    /// Total count (for pagination).
+    pub total: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_org_request_deserialization() {
+        let json = r#"{"name": "Acme Corp"}"#;
+        let req: CreateOrgRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.name, "Acme Corp");
+    }
+
+    #[test]
+    fn test_org_response_serialization() {
+        let response = OrgResponse {
+            id: "org_123".to_string(),
+            name: "Test Org".to_string(),
+            resource_version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"id\":\"org_123\""));
+    }
+}
@@ -0,0 +1,95 @@
+//! Projects: `/v1/orgs/{org_id}/projects`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Validate, Validator};
+
+/// Request to create a new project.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateProjectRequest {
+    /// Project name (unique within org).
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateProjectRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub expected_version: i32,
+}
+
+impl Validate for CreateProjectRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Project", 100);
+    }
+}
+
+impl Validate for UpdateProjectRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.name("name", name, "Project", 100);
+        }
+    }
+}
+
+/// Response for a single project.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProjectResponse {
+    /// Project ID.
+    pub id: String,
+
+    /// Organization ID.
+    pub org_id: String,
+
+    /// Project name.
+    pub name: String,
+
+    /// Resource version for optimistic concurrency.
+    pub resource_version: i32,
+
+    /// When the project was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the project was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response for listing projects.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListProjectsResponse {
+    /// List of projects.
+    pub items: Vec<ProjectResponse>,
+
+    /// Next cursor (or null when there are no more items).
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_project_request_deserialization() {
+        let json = r#"{"name":"my-project"}"#;
+        let req: CreateProjectRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.name, "my-project");
+    }
+
+    #[test]
+    fn test_project_response_serialization() {
+        let response = ProjectResponse {
+            id: "prj_01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
+            org_id: "org_01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
+            name: "my-project".to_string(),
+            resource_version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"id\""));
+        assert!(json.contains("\"org_id\""));
+        assert!(json.contains("\"name\""));
+    }
+}
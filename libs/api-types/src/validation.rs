@@ -0,0 +1,103 @@
+//! Field-level validation of request payloads.
+//!
+//! [`Validate`] impls record every violation on a [`Validator`]; the server
+//! turns them into a single problem response whose `details` list all of
+//! them.
+
+use serde::{Deserialize, Serialize};
+
+/// A field-level violation reported in `details` for validation errors.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Dotted path of the offending field (e.g. `labels.team`).
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Field-level checks for a request payload.
+pub trait Validate {
+    fn validate(&self, v: &mut Validator);
+}
+
+/// Collects violations from a [`Validate`] impl.
+#[derive(Debug, Default)]
+pub struct Validator {
+    violations: Vec<(&'static str, FieldError)>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a violation of `code` on `field` unless `ok`.
+    pub fn check(&mut self, ok: bool, code: &'static str, field: &str, message: &str) {
+        if !ok {
+            self.violations
+                .push((code, FieldError::new(field, message)));
+        }
+    }
+
+    /// Standard non-empty, bounded-length check for resource names.
+    pub fn name(&mut self, field: &str, value: &str, label: &str, max_len: usize) {
+        if value.is_empty() {
+            self.check(
+                false,
+                "invalid_name",
+                field,
+                &format!("{label} name cannot be empty"),
+            );
+        } else if value.len() > max_len {
+            self.check(
+                false,
+                "invalid_name",
+                field,
+                &format!("{label} name cannot exceed {max_len} characters"),
+            );
+        }
+    }
+
+    /// The recorded violations as (problem code, field error), in the order
+    /// they were found.
+    pub fn into_violations(self) -> Vec<(&'static str, FieldError)> {
+        self.violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_checks() {
+        let mut v = Validator::new();
+        v.name("name", "", "Widget", 10);
+        v.name("name", &"x".repeat(11), "Widget", 10);
+        v.name("name", "ok", "Widget", 10);
+
+        let messages: Vec<_> = v
+            .into_violations()
+            .into_iter()
+            .map(|(code, detail)| (code, detail.message))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("invalid_name", "Widget name cannot be empty".to_string()),
+                (
+                    "invalid_name",
+                    "Widget name cannot exceed 10 characters".to_string()
+                ),
+            ]
+        );
+    }
+}
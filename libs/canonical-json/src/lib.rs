@@ -0,0 +1,320 @@
+//! Canonical JSON per RFC 8785 (JSON Canonicalization Scheme).
+//!
+//! Hashes of JSON documents (spec hashes, idempotency request hashes, event
+//! payload hashes) must not depend on how a document happened to be
+//! serialized. JCS fixes every degree of freedom:
+//!
+//! - No whitespace between tokens.
+//! - Object members sorted by their keys' UTF-16 code units.
+//! - Strings escape only `"`, `\` and control characters below U+0020, the
+//!   latter as `\b`, `\t`, `\n`, `\f`, `\r` or `\u00xx`. Everything else,
+//!   including non-BMP characters, is written as UTF-8.
+//! - Numbers are IEEE 754 doubles written as ECMAScript's
+//!   `Number.prototype.toString` does: shortest round-trip digits, plain
+//!   notation for magnitudes in `[1e-6, 1e21)`, exponent notation otherwise,
+//!   and `-0` as `0`.
+//!
+//! Integers beyond ±2^53 cannot be represented exactly as doubles and are
+//! rounded, as in every other JCS implementation. Documents that need such
+//! values exactly should carry them as strings.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serialize `value` as canonical JSON.
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(value, &mut out);
+    out
+}
+
+/// Serialize any serializable value as canonical JSON.
+pub fn serialize<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    Ok(to_string(&serde_json::to_value(value)?))
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            // Without `arbitrary_precision`, every number has an f64 value.
+            write_number(n.as_f64().unwrap_or_default(), out);
+        }
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut members: Vec<_> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_value(value, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Write a finite double the way ECMAScript's `Number.prototype.toString`
+/// does (ECMA-262, Number::toString).
+fn write_number(value: f64, out: &mut String) {
+    if value == 0.0 {
+        out.push('0');
+        return;
+    }
+    if value < 0.0 {
+        out.push('-');
+    }
+
+    // `{:e}` yields the shortest digits that round-trip, e.g. `1.2345e-7`.
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("`{:e}` always has an exponent");
+    let mut digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().expect("`{:e}` exponent is an integer");
+    break_tie(value.abs(), &mut digits, exponent);
+
+    // The value is 0.<digits> * 10^n.
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(n as usize);
+        out.push_str(int);
+        out.push('.');
+        out.push_str(frac);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            out.push('.');
+            out.push_str(rest);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+}
+
+/// When the value lies exactly halfway between two shortest candidates,
+/// ECMAScript picks the even one; Rust picks the larger.
+fn break_tie(value: f64, digits: &mut String, exponent: i32) {
+    // A tie needs a 5 right after the shortest digits; check that cheaply
+    // before expanding the value exactly.
+    let next = format!("{value:.*e}", digits.len());
+    if !next.split_once('e').is_some_and(|(m, _)| m.ends_with('5')) {
+        return;
+    }
+
+    // 800 significant digits hold the exact value of any double.
+    let exact = format!("{value:.800e}");
+    let (mantissa, exact_exponent) = exact.split_once('e').expect("`{:e}` has an exponent");
+    if exact_exponent.parse::<i32>() != Ok(exponent) {
+        return;
+    }
+    let exact: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let (lower, rest) = exact.split_at(digits.len());
+    let halfway = rest.starts_with('5') && rest[1..].bytes().all(|b| b == b'0');
+    let even = lower
+        .bytes()
+        .next_back()
+        .is_some_and(|b| (b - b'0') % 2 == 0);
+    if !halfway || !even || lower == digits.as_str() {
+        return;
+    }
+    let candidate = format!("{lower}e{}", exponent + 1 - lower.len() as i32);
+    if candidate.parse::<f64>() == Ok(value) {
+        *digits = lower.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn number(bits: u64) -> String {
+        let mut out = String::new();
+        write_number(f64::from_bits(bits), &mut out);
+        out
+    }
+
+    /// RFC 8785 Appendix B.
+    #[test]
+    fn numbers_match_rfc_vectors() {
+        let vectors: &[(u64, &str)] = &[
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2"),
+        ];
+        for (bits, expected) in vectors {
+            assert_eq!(number(*bits), *expected, "{bits:#018x}");
+        }
+    }
+
+    #[test]
+    fn numbers_round_trip() {
+        let mut bits: u64 = 0x9e37_79b9_7f4a_7c15;
+        for _ in 0..20_000 {
+            bits ^= bits << 13;
+            bits ^= bits >> 7;
+            bits ^= bits << 17;
+            let value = f64::from_bits(bits);
+            if value.is_finite() {
+                assert_eq!(number(bits).parse::<f64>().unwrap(), value, "{bits:#018x}");
+            }
+        }
+    }
+
+    #[test]
+    fn integers_and_doubles_agree() {
+        assert_eq!(to_string(&json!(1)), "1");
+        assert_eq!(to_string(&json!(1.0)), "1");
+        assert_eq!(to_string(&json!(-1.5)), "-1.5");
+        assert_eq!(to_string(&json!(1e-7)), "1e-7");
+        assert_eq!(to_string(&json!(u64::MAX)), "18446744073709552000");
+    }
+
+    #[test]
+    fn strings_escape_only_what_jcs_requires() {
+        let value = json!("\u{20ac}$\u{f}\nA'B\"\\\\\"/\u{7f}\u{2028}\u{1F600}");
+        assert_eq!(
+            to_string(&value),
+            format!(r#""€$\u000f\nA'B\"\\\\\"/{}""#, "\u{7f}\u{2028}\u{1F600}")
+        );
+        assert_eq!(to_string(&json!("\u{8}\u{c}")), r#""\b\f""#);
+    }
+
+    /// RFC 8785 Section 3.2.3: keys sort by UTF-16 code units, so a non-BMP
+    /// character (surrogate 0xD83D) sorts before U+FB33.
+    #[test]
+    fn keys_sort_by_utf16_code_units() {
+        let value = json!({
+            "\u{20ac}": "Euro Sign",
+            "\r": "Carriage Return",
+            "\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "\u{1F600}": "Emoji: Grinning Face",
+            "\u{0080}": "Control",
+            "\u{00f6}": "Latin Small Letter O With Diaeresis"
+        });
+        let keys: Vec<String> = to_string(&value)
+            .trim_matches(|c| c == '{' || c == '}')
+            .split(',')
+            .map(|member| member.split(':').nth(1).unwrap().to_string())
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "\"Carriage Return\"",
+                "\"One\"",
+                "\"Control\"",
+                "\"Latin Small Letter O With Diaeresis\"",
+                "\"Euro Sign\"",
+                "\"Emoji: Grinning Face\"",
+                "\"Hebrew Letter Dalet With Dagesh\"",
+            ]
+        );
+    }
+
+    /// RFC 8785 Section 3.2.2 example.
+    #[test]
+    fn matches_rfc_example() {
+        let value: Value = serde_json::from_str(
+            r#"{
+                "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                "string": "€$\u000F\u000aA'B"\\\\"\/",
+                "literals": [null, true, false]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            to_string(&value),
+            concat!(
+                r#"{"literals":[null,true,false],"#,
+                r#""numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#,
+                r#""string":"€$\u000f\nA'B\"\\\\\"/"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn serializes_structs() {
+        #[derive(Serialize)]
+        struct Spec {
+            name: &'static str,
+            cpu: f64,
+            env: std::collections::HashMap<&'static str, &'static str>,
+        }
+        let spec = Spec {
+            name: "web",
+            cpu: 2.0,
+            env: [("B", "2"), ("A", "1")].into_iter().collect(),
+        };
+        assert_eq!(
+            serialize(&spec).unwrap(),
+            r#"{"cpu":2,"env":{"A":"1","B":"2"},"name":"web"}"#
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Applications: `/v1/orgs/{org_id}/apps`.
+
+use plfm_api_types::apps::{AppDeletionResponse, AppResponse, CreateAppRequest, UpdateAppRequest};
+
+use crate::{paths, Client, Error, IfExists, Versioned};
+
+impl Client {
+    pub async fn create_app(
+        &self,
+        org_id: &str,
+        req: &CreateAppRequest,
+        if_exists: IfExists,
+    ) -> Result<Versioned<AppResponse>, Error> {
+        self.create(&paths::apps(org_id), req, if_exists).await
+    }
+
+    pub async fn get_app(
+        &self,
+        org_id: &str,
+        app_id: &str,
+    ) -> Result<Option<Versioned<AppResponse>>, Error> {
+        self.get(&paths::app(org_id, app_id)).await
+    }
+
+    /// Look an app up by name, e.g. to import it.
+    pub async fn get_app_by_name(
+        &self,
+        org_id: &str,
+        name: &str,
+    ) -> Result<Option<Versioned<AppResponse>>, Error> {
+        self.get(&paths::app_by_name(org_id, name)).await
+    }
+
+    /// Update an app. The version check is `req.expected_version`.
+    pub async fn update_app(
+        &self,
+        org_id: &str,
+        app_id: &str,
+        req: &UpdateAppRequest,
+    ) -> Result<Versioned<AppResponse>, Error> {
+        self.update(&paths::app(org_id, app_id), req, None).await
+    }
+
+    /// Request deletion of an app; `None` when it is already gone.
+    pub async fn delete_app(
+        &self,
+        org_id: &str,
+        app_id: &str,
+    ) -> Result<Option<AppDeletionResponse>, Error> {
+        self.delete_json(&paths::app(org_id, app_id), None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_app_by_name() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/orgs/org_1/apps/by-name/web"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"3\"")
+                    .set_body_json(json!({
+                        "id": "app_1",
+                        "org_id": "org_1",
+                        "name": "web",
+                        "labels": {},
+                        "resource_version": 3,
+                        "created_at": "2026-01-01T00:00:00Z",
+                        "updated_at": "2026-01-02T00:00:00Z"
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let app = client
+            .get_app_by_name("org_1", "web")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(app.value.id, "app_1");
+        assert_eq!(app.version, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_delete_missing_app() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "status": 404,
+                "code": "app_not_found"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        assert!(client.delete_app("org_1", "app_1").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,444 @@
+//! HTTP transport: authentication, consistency tokens, versions and errors.
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderValue, AUTHORIZATION, ETAG, IF_MATCH};
+use reqwest::{Method, RequestBuilder, Response, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::consistency::ConsistencyTokens;
+use crate::error::{Error, Problem};
+
+const CONSISTENCY_TOKEN_HEADER: &str = "x-consistency-token";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a create does when a resource with the same name already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IfExists {
+    /// Fail with a conflict.
+    #[default]
+    Fail,
+    /// Return the existing resource if its settings match the request.
+    Return,
+}
+
+impl IfExists {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IfExists::Fail => "fail",
+            IfExists::Return => "return",
+        }
+    }
+}
+
+/// A resource with the version its response carried in `ETag`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+    pub value: T,
+    /// `None` when the endpoint does not version the resource.
+    pub version: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    base_url: String,
+    token: Option<String>,
+    timeout: Duration,
+    user_agent: String,
+    consistency_token: Option<String>,
+}
+
+impl ClientBuilder {
+    /// Bearer token sent on every request.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Start from a token saved by an earlier session, so its first reads
+    /// observe that session's writes.
+    pub fn consistency_token(mut self, token: impl Into<String>) -> Self {
+        self.consistency_token = Some(token.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Client, Error> {
+        let base_url = Url::parse(&self.base_url)
+            .map_err(|e| Error::Config(format!("invalid base URL '{}': {e}", self.base_url)))?;
+        if base_url.cannot_be_a_base() {
+            return Err(Error::Config(format!(
+                "invalid base URL '{}': not an http(s) URL",
+                self.base_url
+            )));
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(self.user_agent)
+            .build()?;
+
+        let tokens = ConsistencyTokens::default();
+        if let Some(token) = &self.consistency_token {
+            tokens.observe(token);
+        }
+
+        Ok(Client {
+            http,
+            base_url,
+            token: self.token,
+            tokens,
+        })
+    }
+}
+
+/// Client for the control-plane API. Cheap to clone; clones share the
+/// connection pool and the consistency token.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    token: Option<String>,
+    tokens: ConsistencyTokens,
+}
+
+impl Client {
+    /// `base_url` is the API root, without `/v1`.
+    pub fn builder(base_url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            base_url: base_url.into(),
+            token: None,
+            timeout: DEFAULT_TIMEOUT,
+            user_agent: concat!("plfm-client/", env!("CARGO_PKG_VERSION")).to_string(),
+            consistency_token: None,
+        }
+    }
+
+    /// The newest consistency token seen, for callers that persist it
+    /// between sessions.
+    pub fn consistency_token(&self) -> Option<String> {
+        self.tokens.current()
+    }
+
+    /// GET a resource; `None` when it does not exist.
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+    ) -> Result<Option<Versioned<T>>, Error> {
+        let request = self.request(Method::GET, path, &[]);
+        match self.send(request).await {
+            Ok(response) => versioned(response).await.map(Some),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// POST a create. With [`IfExists::Return`] a resource with the same
+    /// name and settings is returned instead of a conflict.
+    pub async fn create<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: &B,
+        if_exists: IfExists,
+    ) -> Result<Versioned<T>, Error> {
+        let request = self
+            .request(Method::POST, path, &[("if_exists", if_exists.as_str())])
+            .body(serde_json::to_vec(body)?)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        versioned(self.send(request).await?).await
+    }
+
+    /// PATCH a resource. An update that changes nothing returns the resource
+    /// unchanged, with the same version.
+    pub async fn update<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: &B,
+        if_match: Option<i32>,
+    ) -> Result<Versioned<T>, Error> {
+        let request = with_if_match(self.request(Method::PATCH, path, &[]), if_match)
+            .body(serde_json::to_vec(body)?)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        versioned(self.send(request).await?).await
+    }
+
+    /// DELETE a resource. A resource that is already gone is not an error.
+    pub async fn delete(&self, path: &[&str], if_match: Option<i32>) -> Result<(), Error> {
+        self.delete_json::<serde_json::Value>(path, if_match)
+            .await
+            .map(drop)
+    }
+
+    /// DELETE returning the response body (e.g. a teardown status); `None`
+    /// when the resource is already gone or the response has no body.
+    pub(crate) async fn delete_json<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        if_match: Option<i32>,
+    ) -> Result<Option<T>, Error> {
+        let request = with_if_match(self.request(Method::DELETE, path, &[]), if_match);
+        match self.send(request).await {
+            Ok(response) => {
+                let body = response.bytes().await?;
+                if body.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(serde_json::from_slice(&body).map_err(decode_error)?))
+                }
+            }
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn url(&self, path: &[&str], query: &[(&str, &str)]) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base URL checked by the builder")
+            .pop_if_empty()
+            .push("v1")
+            .extend(path);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        url
+    }
+
+    fn request(&self, method: Method, path: &[&str], query: &[(&str, &str)]) -> RequestBuilder {
+        let mut request = self.http.request(method, self.url(path, query));
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        if let Some(token) = self.tokens.current() {
+            request = request.header(CONSISTENCY_TOKEN_HEADER, token);
+        }
+        request
+    }
+
+    /// Send a request, keep its consistency token and turn error statuses
+    /// into [`Error::Api`].
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        let response = request.send().await?;
+        if let Some(token) = response
+            .headers()
+            .get(CONSISTENCY_TOKEN_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            self.tokens.observe(token);
+        }
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(Error::Api(Box::new(Problem::from_body(
+            status.as_u16(),
+            &body,
+        ))))
+    }
+}
+
+fn with_if_match(request: RequestBuilder, version: Option<i32>) -> RequestBuilder {
+    match version {
+        Some(version) => request.header(IF_MATCH, format!("\"{version}\"")),
+        None => request,
+    }
+}
+
+async fn versioned<T: DeserializeOwned>(response: Response) -> Result<Versioned<T>, Error> {
+    let version = response.headers().get(ETAG).and_then(parse_etag);
+    let body = response.bytes().await?;
+    let value = serde_json::from_slice(&body).map_err(decode_error)?;
+    Ok(Versioned { value, version })
+}
+
+fn decode_error(e: serde_json::Error) -> Error {
+    Error::Api(Box::new(Problem {
+        code: "invalid_response".to_string(),
+        detail: format!("failed to decode response body: {e}"),
+        ..Problem::default()
+    }))
+}
+
+/// Parse a version ETag: `"3"`, or `W/"3"`.
+fn parse_etag(value: &HeaderValue) -> Option<i32> {
+    let value = value.to_str().ok()?.trim();
+    let value = value.strip_prefix("W/").unwrap_or(value);
+    value.strip_prefix('"')?.strip_suffix('"')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn client(server: &MockServer) -> Client {
+        Client::builder(server.uri()).token("tok").build().unwrap()
+    }
+
+    #[test]
+    fn test_parse_etag() {
+        let parse = |s: &str| parse_etag(&HeaderValue::from_str(s).unwrap());
+        assert_eq!(parse("\"3\""), Some(3));
+        assert_eq!(parse("W/\"12\""), Some(12));
+        assert_eq!(parse("3"), None);
+        assert_eq!(parse("\"abc\""), None);
+    }
+
+    #[test]
+    fn test_url_encodes_segments() {
+        let client = Client::builder("http://localhost:8080/api/")
+            .build()
+            .unwrap();
+        let url = client.url(&["orgs", "org_1", "apps", "by-name", "a b/c"], &[]);
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/api/v1/orgs/org_1/apps/by-name/a%20b%2Fc"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_base_url() {
+        assert!(matches!(
+            Client::builder("mailto:ops@example.com").build(),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_threads_consistency_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/orgs/org_1/apps"))
+            .and(header("authorization", "Bearer tok"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .insert_header("x-consistency-token", "ct1_2a")
+                    .insert_header("etag", "\"1\"")
+                    .set_body_json(json!({"id": "app_1"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/orgs/org_1/apps/app_1"))
+            .and(header("x-consistency-token", "ct1_2a"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"1\"")
+                    .set_body_json(json!({"id": "app_1"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        let created: Versioned<serde_json::Value> = client
+            .create(
+                &["orgs", "org_1", "apps"],
+                &json!({"name": "web"}),
+                IfExists::Fail,
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.version, Some(1));
+        assert_eq!(client.consistency_token().as_deref(), Some("ct1_2a"));
+
+        let read = client
+            .clone()
+            .get::<serde_json::Value>(&["orgs", "org_1", "apps", "app_1"])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.value["id"], "app_1");
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_is_none() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "status": 404,
+                "code": "app_not_found",
+                "title": "Not Found",
+                "detail": "Application not found"
+            })))
+            .mount(&server)
+            .await;
+
+        let found = client(&server)
+            .get::<serde_json::Value>(&["orgs", "org_1", "apps", "by-name", "web"])
+            .await
+            .unwrap();
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_sends_if_exists_and_decodes_conflict() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/orgs/org_1/apps"))
+            .and(query_param("if_exists", "return"))
+            .respond_with(ResponseTemplate::new(409).set_body_json(json!({
+                "status": 409,
+                "code": "app_name_exists",
+                "domain": "apps",
+                "title": "Conflict",
+                "detail": "Application 'web' already exists in this organization",
+                "request_id": "req_1"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .create::<_, serde_json::Value>(
+                &["orgs", "org_1", "apps"],
+                &json!({"name": "web", "description": "other"}),
+                IfExists::Return,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.is_conflict());
+        assert_eq!(err.code(), Some("app_name_exists"));
+        assert_eq!(err.problem().unwrap().request_id, "req_1");
+    }
+
+    #[tokio::test]
+    async fn test_update_sends_if_match() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/v1/orgs/org_1/apps/app_1/labels"))
+            .and(header("if-match", "\"4\""))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"5\"")
+                    .set_body_json(json!({"labels": {}})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let updated: Versioned<serde_json::Value> = client(&server)
+            .update(
+                &["orgs", "org_1", "apps", "app_1", "labels"],
+                &json!({"set": {}}),
+                Some(4),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.version, Some(5));
+    }
+}
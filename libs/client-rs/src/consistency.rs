@@ -0,0 +1,74 @@
+//! The consistency token of a client session.
+
+use std::sync::{Arc, Mutex};
+
+const TOKEN_PREFIX: &str = "ct1_";
+
+/// The newest `X-Consistency-Token` seen by a client.
+///
+/// Tokens name a position in the event log; the server holds a request that
+/// carries one until its views have caught up to that position. Keeping the
+/// newest token seen means every request observes every earlier write of
+/// the session.
+#[derive(Debug, Clone, Default)]
+pub struct ConsistencyTokens(Arc<Mutex<Option<String>>>);
+
+impl ConsistencyTokens {
+    pub fn current(&self) -> Option<String> {
+        self.0.lock().expect("token lock poisoned").clone()
+    }
+
+    /// Keep `token` if it is newer than the current one. A token in an
+    /// unknown format replaces the current one.
+    pub fn observe(&self, token: &str) {
+        let mut current = self.0.lock().expect("token lock poisoned");
+        let newer = match (current.as_deref().and_then(position), position(token)) {
+            (Some(current), Some(candidate)) => candidate > current,
+            _ => current.as_deref() != Some(token),
+        };
+        if newer {
+            *current = Some(token.to_string());
+        }
+    }
+}
+
+/// The event position a token names.
+fn position(token: &str) -> Option<i64> {
+    match token.strip_prefix(TOKEN_PREFIX) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => token.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_newest_token() {
+        let tokens = ConsistencyTokens::default();
+        assert_eq!(tokens.current(), None);
+
+        tokens.observe("ct1_1f");
+        tokens.observe("ct1_a");
+        assert_eq!(tokens.current().as_deref(), Some("ct1_1f"));
+
+        tokens.observe("ct1_100");
+        assert_eq!(tokens.current().as_deref(), Some("ct1_100"));
+    }
+
+    #[test]
+    fn test_clones_share_the_token() {
+        let tokens = ConsistencyTokens::default();
+        tokens.clone().observe("42");
+        assert_eq!(tokens.current().as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_unknown_format_replaces() {
+        let tokens = ConsistencyTokens::default();
+        tokens.observe("ct1_ff");
+        tokens.observe("ct2_abc");
+        assert_eq!(tokens.current().as_deref(), Some("ct2_abc"));
+    }
+}
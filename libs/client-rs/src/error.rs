@@ -0,0 +1,145 @@
+//! Client errors.
+
+use std::fmt;
+
+use plfm_api_types::FieldError;
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid client configuration: {0}")]
+    Config(String),
+    /// The API answered with an error.
+    #[error("{0}")]
+    Api(Box<Problem>),
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("failed to encode request body: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+impl Error {
+    pub fn problem(&self) -> Option<&Problem> {
+        match self {
+            Error::Api(problem) => Some(problem),
+            _ => None,
+        }
+    }
+
+    /// The stable error code, e.g. `app_name_exists`.
+    pub fn code(&self) -> Option<&str> {
+        self.problem().map(|p| p.code.as_str())
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.problem().map(|p| p.status)
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.status() == Some(409)
+    }
+}
+
+/// An RFC 7807 problem details body.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Problem {
+    #[serde(default)]
+    pub status: u16,
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub domain: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub detail: String,
+    #[serde(default)]
+    pub request_id: String,
+    #[serde(default)]
+    pub retryable: bool,
+    /// 0 when the server gave no hint.
+    #[serde(default)]
+    pub retry_after_seconds: u32,
+    /// Field violations of a validation error.
+    #[serde(default)]
+    pub details: Option<Vec<FieldError>>,
+}
+
+impl Problem {
+    /// Decode an error body. Bodies that are not problem details (a proxy's
+    /// error page) become a problem with code `http_<status>`.
+    pub(crate) fn from_body(status: u16, body: &str) -> Self {
+        match serde_json::from_str::<Problem>(body) {
+            Ok(problem) if !problem.code.is_empty() => Problem {
+                status: if problem.status == 0 {
+                    status
+                } else {
+                    problem.status
+                },
+                ..problem
+            },
+            _ => Problem {
+                status,
+                code: format!("http_{status}"),
+                detail: body.chars().take(512).collect(),
+                ..Problem::default()
+            },
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = if self.detail.is_empty() {
+            &self.title
+        } else {
+            &self.detail
+        };
+        write!(f, "{} ({}): {}", self.code, self.status, message)?;
+        if !self.request_id.is_empty() {
+            write!(f, " [request {}]", self.request_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_problem_details() {
+        let body = r#"{
+            "type": "https://docs.plfm.dev/errors/app_name_exists",
+            "title": "Conflict",
+            "status": 409,
+            "detail": "Application 'web' already exists in this organization",
+            "code": "app_name_exists",
+            "domain": "apps",
+            "request_id": "req_1",
+            "retryable": false,
+            "retry_after_seconds": 0
+        }"#;
+        let problem = Problem::from_body(409, body);
+
+        assert_eq!(problem.code, "app_name_exists");
+        assert_eq!(problem.domain, "apps");
+        assert_eq!(
+            problem.to_string(),
+            "app_name_exists (409): Application 'web' already exists in this organization [request req_1]"
+        );
+    }
+
+    #[test]
+    fn test_non_problem_body() {
+        let problem = Problem::from_body(502, "<html>Bad Gateway</html>");
+        assert_eq!(problem.code, "http_502");
+        assert_eq!(problem.status, 502);
+        assert_eq!(problem.detail, "<html>Bad Gateway</html>");
+    }
+}
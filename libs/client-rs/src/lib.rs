@@ -0,0 +1,30 @@
+//! Client for the control-plane HTTP API, for tools that manage platform
+//! resources declaratively (the Terraform/OpenTofu provider wraps it).
+//!
+//! - Read-after-write: every response's `X-Consistency-Token` is kept and
+//!   sent on later requests, so a read issued after a write observes it.
+//!   Clones of a [`Client`] share the token.
+//! - Versions: responses carry the resource version from `ETag`
+//!   ([`Versioned`]); updates and deletes can send it back as `If-Match`.
+//! - Create-or-return: creates take [`IfExists`], so a create repeated after
+//!   an unknown outcome returns the resource the first attempt made.
+//! - Import: apps, envs, routes and volumes can be looked up by name (routes
+//!   by hostname), see [`paths`].
+//! - Errors: problem details are decoded into [`Problem`].
+//!
+//! Apps have typed helpers built on `plfm-api-types`; other resources use
+//! the generic [`Client::get`], [`Client::create`], [`Client::update`] and
+//! [`Client::delete`] with the caller's own types until their types move to
+//! `plfm-api-types`.
+//!
+//! See: docs/specs/api/http-api.md
+
+mod apps;
+mod client;
+mod consistency;
+mod error;
+pub mod paths;
+
+pub use client::{Client, ClientBuilder, IfExists, Versioned};
+pub use consistency::ConsistencyTokens;
+pub use error::{Error, Problem};
@@ -0,0 +1,73 @@
+//! Path segments of resources, for the generic [`Client`](crate::Client)
+//! methods. Segments are percent-encoded by the client, so names may be
+//! passed as they are.
+
+pub fn apps(org_id: &str) -> [&str; 3] {
+    ["orgs", org_id, "apps"]
+}
+
+pub fn app<'a>(org_id: &'a str, app_id: &'a str) -> [&'a str; 4] {
+    ["orgs", org_id, "apps", app_id]
+}
+
+pub fn app_by_name<'a>(org_id: &'a str, name: &'a str) -> [&'a str; 5] {
+    ["orgs", org_id, "apps", "by-name", name]
+}
+
+pub fn envs<'a>(org_id: &'a str, app_id: &'a str) -> [&'a str; 5] {
+    ["orgs", org_id, "apps", app_id, "envs"]
+}
+
+pub fn env<'a>(org_id: &'a str, app_id: &'a str, env_id: &'a str) -> [&'a str; 6] {
+    ["orgs", org_id, "apps", app_id, "envs", env_id]
+}
+
+pub fn env_by_name<'a>(org_id: &'a str, app_id: &'a str, name: &'a str) -> [&'a str; 7] {
+    ["orgs", org_id, "apps", app_id, "envs", "by-name", name]
+}
+
+pub fn routes<'a>(org_id: &'a str, app_id: &'a str, env_id: &'a str) -> [&'a str; 7] {
+    ["orgs", org_id, "apps", app_id, "envs", env_id, "routes"]
+}
+
+pub fn route<'a>(
+    org_id: &'a str,
+    app_id: &'a str,
+    env_id: &'a str,
+    route_id: &'a str,
+) -> [&'a str; 8] {
+    [
+        "orgs", org_id, "apps", app_id, "envs", env_id, "routes", route_id,
+    ]
+}
+
+pub fn route_by_hostname<'a>(
+    org_id: &'a str,
+    app_id: &'a str,
+    env_id: &'a str,
+    hostname: &'a str,
+) -> [&'a str; 9] {
+    [
+        "orgs",
+        org_id,
+        "apps",
+        app_id,
+        "envs",
+        env_id,
+        "routes",
+        "by-hostname",
+        hostname,
+    ]
+}
+
+pub fn volumes(org_id: &str) -> [&str; 3] {
+    ["orgs", org_id, "volumes"]
+}
+
+pub fn volume<'a>(org_id: &'a str, volume_id: &'a str) -> [&'a str; 4] {
+    ["orgs", org_id, "volumes", volume_id]
+}
+
+pub fn volume_by_name<'a>(org_id: &'a str, name: &'a str) -> [&'a str; 5] {
+    ["orgs", org_id, "volumes", "by-name", name]
+}
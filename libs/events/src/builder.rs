@@ -0,0 +1,553 @@
+//! Typed builder for events about to be appended to the log.
+//!
+//! Handlers and background workers used to assemble append structs field by
+//! field, which made it easy to forget the idempotency key, pass the wrong
+//! aggregate type for an event, or drop correlation entirely. [`NewEvent`]
+//! instead takes its audit context from an [`EventSource`] (the request or
+//! worker doing the write), its event type and aggregate type from the
+//! [`EventPayload`], and its correlation/causation IDs from the
+//! [`CausalEvent`] that triggered it, if any.
+//!
+//! ```
+//! use plfm_events::{ActorType, AppCreatedPayload, EventSource, NewEvent};
+//! use plfm_id::{AppId, OrgId};
+//!
+//! struct Ctx;
+//!
+//! impl EventSource for Ctx {
+//!     fn actor_type(&self) -> ActorType {
+//!         ActorType::User
+//!     }
+//!     fn actor_id(&self) -> &str {
+//!         "user_123"
+//!     }
+//!     fn request_id(&self) -> &str {
+//!         "req_123"
+//!     }
+//! }
+//!
+//! let (org_id, app_id) = (OrgId::new(), AppId::new());
+//! let event = NewEvent::builder(&Ctx)
+//!     .aggregate_id(app_id.to_string())
+//!     .aggregate_seq(1)
+//!     .org_id(org_id)
+//!     .app_id(app_id)
+//!     .payload(&AppCreatedPayload {
+//!         app_id,
+//!         org_id,
+//!         name: "web".to_string(),
+//!         description: None,
+//!     })
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(event.event_type, "app.created");
+//! assert_eq!(event.correlation_id.as_deref(), Some("req_123"));
+//! ```
+
+use plfm_id::{AppId, EnvId, EventId, OrgId};
+use serde::Serialize;
+
+use crate::envelope::{ActorType, AggregateType};
+use crate::error::EventError;
+use crate::types::*;
+
+/// A payload struct that maps to exactly one event type.
+pub trait EventPayload: Serialize {
+    /// Event type name (one of [`event_types`]).
+    const EVENT_TYPE: &'static str;
+    /// Aggregate the event belongs to.
+    const AGGREGATE_TYPE: AggregateType;
+    /// Payload schema version.
+    const EVENT_VERSION: i32 = 1;
+}
+
+/// Audit context for an event: who is writing it and under which request.
+///
+/// Implemented by the control plane's request context and by background
+/// workers acting as the system.
+pub trait EventSource {
+    /// Type of actor writing the event.
+    fn actor_type(&self) -> ActorType;
+
+    /// Identifier of the actor.
+    fn actor_id(&self) -> &str;
+
+    /// Request ID the event is recorded under.
+    fn request_id(&self) -> &str;
+
+    /// Client-provided idempotency key, if any.
+    fn idempotency_key(&self) -> Option<&str> {
+        None
+    }
+
+    /// Correlation ID for the write. Defaults to the request ID, so every
+    /// event written by one request shares a correlation ID.
+    fn correlation_id(&self) -> Option<&str> {
+        Some(self.request_id())
+    }
+}
+
+/// A previously stored event that triggered a new one.
+pub trait CausalEvent {
+    /// Event ID of the triggering event.
+    fn event_id(&self) -> EventId;
+
+    /// Correlation ID of the triggering event.
+    fn correlation_id(&self) -> Option<&str>;
+
+    /// Request ID of the triggering event.
+    fn request_id(&self) -> &str;
+}
+
+/// Simple [`EventSource`] for the system actor (schedulers, reconcilers,
+/// cleanup workers).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemSource {
+    actor_id: String,
+    request_id: String,
+}
+
+impl SystemSource {
+    /// Creates a system source for the named worker with a fresh request ID.
+    pub fn new(actor_id: impl Into<String>) -> Self {
+        Self {
+            actor_id: actor_id.into(),
+            request_id: plfm_id::RequestId::new().to_string(),
+        }
+    }
+
+    /// Records events under an explicit request ID.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = request_id.into();
+        self
+    }
+}
+
+impl EventSource for SystemSource {
+    fn actor_type(&self) -> ActorType {
+        ActorType::System
+    }
+
+    fn actor_id(&self) -> &str {
+        &self.actor_id
+    }
+
+    fn request_id(&self) -> &str {
+        &self.request_id
+    }
+}
+
+/// An event ready to append, with every envelope field filled in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewEvent {
+    pub aggregate_type: AggregateType,
+    pub aggregate_id: String,
+    pub aggregate_seq: i32,
+    pub event_type: String,
+    pub event_version: i32,
+    pub actor_type: ActorType,
+    pub actor_id: String,
+    pub org_id: Option<OrgId>,
+    pub request_id: String,
+    pub idempotency_key: Option<String>,
+    pub app_id: Option<AppId>,
+    pub env_id: Option<EnvId>,
+    pub correlation_id: Option<String>,
+    pub causation_id: Option<EventId>,
+    pub payload: serde_json::Value,
+}
+
+impl NewEvent {
+    /// Starts a builder with the actor, request, idempotency, and correlation
+    /// fields taken from `source`.
+    pub fn builder(source: &impl EventSource) -> NewEventBuilder {
+        NewEventBuilder {
+            aggregate_type: None,
+            aggregate_id: None,
+            aggregate_seq: None,
+            event_type: None,
+            event_version: 1,
+            actor_type: source.actor_type(),
+            actor_id: source.actor_id().to_string(),
+            org_id: None,
+            request_id: source.request_id().to_string(),
+            idempotency_key: source.idempotency_key().map(str::to_string),
+            app_id: None,
+            env_id: None,
+            correlation_id: source.correlation_id().map(str::to_string),
+            causation_id: None,
+            payload: None,
+        }
+    }
+}
+
+/// Builder for [`NewEvent`].
+#[derive(Debug)]
+pub struct NewEventBuilder {
+    aggregate_type: Option<AggregateType>,
+    aggregate_id: Option<String>,
+    aggregate_seq: Option<i32>,
+    event_type: Option<String>,
+    event_version: i32,
+    actor_type: ActorType,
+    actor_id: String,
+    org_id: Option<OrgId>,
+    request_id: String,
+    idempotency_key: Option<String>,
+    app_id: Option<AppId>,
+    env_id: Option<EnvId>,
+    correlation_id: Option<String>,
+    causation_id: Option<EventId>,
+    payload: Option<Result<serde_json::Value, EventError>>,
+}
+
+impl NewEventBuilder {
+    pub fn aggregate_id(mut self, aggregate_id: impl Into<String>) -> Self {
+        self.aggregate_id = Some(aggregate_id.into());
+        self
+    }
+
+    pub fn aggregate_seq(mut self, seq: i32) -> Self {
+        self.aggregate_seq = Some(seq);
+        self
+    }
+
+    pub fn org_id(mut self, org_id: OrgId) -> Self {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn app_id(mut self, app_id: AppId) -> Self {
+        self.app_id = Some(app_id);
+        self
+    }
+
+    pub fn env_id(mut self, env_id: EnvId) -> Self {
+        self.env_id = Some(env_id);
+        self
+    }
+
+    /// Drops the idempotency key inherited from the source, for follow-up
+    /// events that must not be deduplicated against the original write.
+    pub fn without_idempotency_key(mut self) -> Self {
+        self.idempotency_key = None;
+        self
+    }
+
+    /// Links the event to the event that triggered it: the causation ID is
+    /// the trigger's event ID and the correlation ID is inherited from the
+    /// trigger (falling back to its request ID).
+    pub fn caused_by(mut self, trigger: &impl CausalEvent) -> Self {
+        self.causation_id = Some(trigger.event_id());
+        self.correlation_id = Some(
+            trigger
+                .correlation_id()
+                .unwrap_or_else(|| trigger.request_id())
+                .to_string(),
+        );
+        self
+    }
+
+    /// Sets a typed payload; the event type, aggregate type, and version
+    /// come from the payload type.
+    pub fn payload<P: EventPayload>(mut self, payload: &P) -> Self {
+        self.aggregate_type = Some(P::AGGREGATE_TYPE);
+        self.event_type = Some(P::EVENT_TYPE.to_string());
+        self.event_version = P::EVENT_VERSION;
+        self.payload = Some(serde_json::to_value(payload).map_err(EventError::from));
+        self
+    }
+
+    /// Sets an untyped payload for events without a payload struct.
+    pub fn json_payload(
+        mut self,
+        aggregate_type: AggregateType,
+        event_type: impl Into<String>,
+        payload: serde_json::Value,
+    ) -> Self {
+        self.aggregate_type = Some(aggregate_type);
+        self.event_type = Some(event_type.into());
+        self.payload = Some(Ok(payload));
+        self
+    }
+
+    /// Builds the event, failing if the aggregate or payload is missing or
+    /// the payload did not serialize.
+    pub fn build(self) -> Result<NewEvent, EventError> {
+        let payload = self.payload.ok_or(EventError::MissingField("payload"))??;
+        Ok(NewEvent {
+            aggregate_type: self
+                .aggregate_type
+                .ok_or(EventError::MissingField("aggregate_type"))?,
+            aggregate_id: self
+                .aggregate_id
+                .ok_or(EventError::MissingField("aggregate_id"))?,
+            aggregate_seq: self
+                .aggregate_seq
+                .ok_or(EventError::MissingField("aggregate_seq"))?,
+            event_type: self
+                .event_type
+                .ok_or(EventError::MissingField("event_type"))?,
+            event_version: self.event_version,
+            actor_type: self.actor_type,
+            actor_id: self.actor_id,
+            org_id: self.org_id,
+            request_id: self.request_id,
+            idempotency_key: self.idempotency_key,
+            app_id: self.app_id,
+            env_id: self.env_id,
+            correlation_id: self.correlation_id,
+            causation_id: self.causation_id,
+            payload,
+        })
+    }
+}
+
+macro_rules! event_payloads {
+    ($($payload:ty => $event_type:ident, $aggregate:ident;)*) => {
+        $(
+            impl EventPayload for $payload {
+                const EVENT_TYPE: &'static str = event_types::$event_type;
+                const AGGREGATE_TYPE: AggregateType = AggregateType::$aggregate;
+            }
+        )*
+    };
+}
+
+event_payloads! {
+    OrgCreatedPayload => ORG_CREATED, Org;
+    OrgUpdatedPayload => ORG_UPDATED, Org;
+    OrgMemberAddedPayload => ORG_MEMBER_ADDED, OrgMember;
+    OrgMemberRoleUpdatedPayload => ORG_MEMBER_ROLE_UPDATED, OrgMember;
+    OrgMemberRemovedPayload => ORG_MEMBER_REMOVED, OrgMember;
+    ServicePrincipalCreatedPayload => SERVICE_PRINCIPAL_CREATED, ServicePrincipal;
+    ServicePrincipalScopesUpdatedPayload => SERVICE_PRINCIPAL_SCOPES_UPDATED, ServicePrincipal;
+    ServicePrincipalSecretRotatedPayload => SERVICE_PRINCIPAL_SECRET_ROTATED, ServicePrincipal;
+    ServicePrincipalDeletedPayload => SERVICE_PRINCIPAL_DELETED, ServicePrincipal;
+    ProjectCreatedPayload => PROJECT_CREATED, Project;
+    ProjectUpdatedPayload => PROJECT_UPDATED, Project;
+    ProjectDeletedPayload => PROJECT_DELETED, Project;
+    AppCreatedPayload => APP_CREATED, App;
+    AppUpdatedPayload => APP_UPDATED, App;
+    AppLabelsUpdatedPayload => APP_LABELS_UPDATED, App;
+    AppDeletionRequestedPayload => APP_DELETION_REQUESTED, App;
+    AppDeletedPayload => APP_DELETED, App;
+    AppRestoredPayload => APP_RESTORED, App;
+    AppPurgedPayload => APP_PURGED, App;
+    EnvCreatedPayload => ENV_CREATED, Env;
+    EnvUpdatedPayload => ENV_UPDATED, Env;
+    EnvLabelsUpdatedPayload => ENV_LABELS_UPDATED, Env;
+    EnvDeletionRequestedPayload => ENV_DELETION_REQUESTED, Env;
+    EnvDeletedPayload => ENV_DELETED, Env;
+    EnvRestoredPayload => ENV_RESTORED, Env;
+    EnvPurgedPayload => ENV_PURGED, Env;
+    EnvScaleSetPayload => ENV_SCALE_SET, Env;
+    EnvDesiredReleaseSetPayload => ENV_DESIRED_RELEASE_SET, Env;
+    EnvRestartRequestedPayload => ENV_RESTART_REQUESTED, Env;
+    EnvIpv4AddonEnabledPayload => ENV_IPV4_ADDON_ENABLED, Env;
+    EnvIpv4AddonDisabledPayload => ENV_IPV4_ADDON_DISABLED, Env;
+    EnvConfigVarsSetPayload => ENV_CONFIG_VARS_SET, Env;
+    EnvProcessResourcesSetPayload => ENV_PROCESS_RESOURCES_SET, Env;
+    ReleaseCreatedPayload => RELEASE_CREATED, Release;
+    DeployCreatedPayload => DEPLOY_CREATED, Deploy;
+    DeployStatusChangedPayload => DEPLOY_STATUS_CHANGED, Deploy;
+    DeployPromotedPayload => DEPLOY_PROMOTED, Deploy;
+    DeployRolloutStuckPayload => DEPLOY_ROLLOUT_STUCK, Deploy;
+    RouteCreatedPayload => ROUTE_CREATED, Route;
+    RouteUpdatedPayload => ROUTE_UPDATED, Route;
+    RouteLabelsUpdatedPayload => ROUTE_LABELS_UPDATED, Route;
+    RouteDeletedPayload => ROUTE_DELETED, Route;
+    RouteRestoredPayload => ROUTE_RESTORED, Route;
+    RoutePurgedPayload => ROUTE_PURGED, Route;
+    RouteCertSetPayload => ROUTE_CERT_SET, RouteCertificate;
+    RouteCertRemovedPayload => ROUTE_CERT_REMOVED, RouteCertificate;
+    RouteCertExpiringPayload => ROUTE_CERT_EXPIRING, RouteCertificate;
+    RouteVerificationSucceededPayload => ROUTE_VERIFICATION_SUCCEEDED, Route;
+    RouteVerificationFailedPayload => ROUTE_VERIFICATION_FAILED, Route;
+    SecretBundleCreatedPayload => SECRET_BUNDLE_CREATED, SecretBundle;
+    SecretBundleVersionSetPayload => SECRET_BUNDLE_VERSION_SET, SecretBundle;
+    SecretBundleArchivedPayload => SECRET_BUNDLE_ARCHIVED, SecretBundle;
+    KeyRotationStartedPayload => KEY_ROTATION_STARTED, KeyRotation;
+    KeyRotationCompletedPayload => KEY_ROTATION_COMPLETED, KeyRotation;
+    VolumeCreatedPayload => VOLUME_CREATED, Volume;
+    VolumeLabelsUpdatedPayload => VOLUME_LABELS_UPDATED, Volume;
+    VolumeDeletedPayload => VOLUME_DELETED, Volume;
+    VolumeRestoredPayload => VOLUME_RESTORED, Volume;
+    VolumePurgedPayload => VOLUME_PURGED, Volume;
+    VolumeResizedPayload => VOLUME_RESIZED, Volume;
+    VolumeResizeCompletedPayload => VOLUME_RESIZE_COMPLETED, Volume;
+    VolumeResizeFailedPayload => VOLUME_RESIZE_FAILED, Volume;
+    VolumeBackupPolicySetPayload => VOLUME_BACKUP_POLICY_SET, Volume;
+    VolumeAttachmentCreatedPayload => VOLUME_ATTACHMENT_CREATED, VolumeAttachment;
+    VolumeAttachmentDeletedPayload => VOLUME_ATTACHMENT_DELETED, VolumeAttachment;
+    VolumeAttachedPayload => VOLUME_ATTACHED, VolumeAttachment;
+    VolumeDetachedPayload => VOLUME_DETACHED, VolumeAttachment;
+    SnapshotCreatedPayload => SNAPSHOT_CREATED, Snapshot;
+    SnapshotStatusChangedPayload => SNAPSHOT_STATUS_CHANGED, Snapshot;
+    SnapshotDeletedPayload => SNAPSHOT_DELETED, Snapshot;
+    RestoreJobCreatedPayload => RESTORE_JOB_CREATED, RestoreJob;
+    RestoreJobStatusChangedPayload => RESTORE_JOB_STATUS_CHANGED, RestoreJob;
+    InstanceAllocatedPayload => INSTANCE_ALLOCATED, Instance;
+    InstanceDesiredStateChangedPayload => INSTANCE_DESIRED_STATE_CHANGED, Instance;
+    InstanceStatusChangedPayload => INSTANCE_STATUS_CHANGED, Instance;
+    InstanceConfigUpdatedPayload => INSTANCE_CONFIG_UPDATED, Instance;
+    InstanceDriftDetectedPayload => INSTANCE_DRIFT_DETECTED, Instance;
+    NodeEnrolledPayload => NODE_ENROLLED, Node;
+    NodeStateChangedPayload => NODE_STATE_CHANGED, Node;
+    NodeCapacityUpdatedPayload => NODE_CAPACITY_UPDATED, Node;
+    NodeEnrollmentRequestedPayload => NODE_ENROLLMENT_REQUESTED, Node;
+    NodeEnrollmentApprovedPayload => NODE_ENROLLMENT_APPROVED, Node;
+    NodeEnrollmentDeniedPayload => NODE_ENROLLMENT_DENIED, Node;
+    NodeLabelsUpdatedPayload => NODE_LABELS_UPDATED, Node;
+    NodeTaintsUpdatedPayload => NODE_TAINTS_UPDATED, Node;
+    ExecSessionGrantedPayload => EXEC_SESSION_GRANTED, ExecSession;
+    ExecSessionConnectedPayload => EXEC_SESSION_CONNECTED, ExecSession;
+    ExecSessionEndedPayload => EXEC_SESSION_ENDED, ExecSession;
+    AlertFiredPayload => ALERT_FIRED, AlertRule;
+    AlertResolvedPayload => ALERT_RESOLVED, AlertRule;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestSource {
+        idempotency_key: Option<&'static str>,
+    }
+
+    impl EventSource for TestSource {
+        fn actor_type(&self) -> ActorType {
+            ActorType::User
+        }
+
+        fn actor_id(&self) -> &str {
+            "user_123"
+        }
+
+        fn request_id(&self) -> &str {
+            "req_123"
+        }
+
+        fn idempotency_key(&self) -> Option<&str> {
+            self.idempotency_key
+        }
+    }
+
+    struct Trigger {
+        correlation_id: Option<&'static str>,
+    }
+
+    impl CausalEvent for Trigger {
+        fn event_id(&self) -> EventId {
+            EventId::new(41)
+        }
+
+        fn correlation_id(&self) -> Option<&str> {
+            self.correlation_id
+        }
+
+        fn request_id(&self) -> &str {
+            "req_trigger"
+        }
+    }
+
+    fn app_deleted() -> AppDeletedPayload {
+        AppDeletedPayload {
+            app_id: AppId::new(),
+        }
+    }
+
+    #[test]
+    fn test_builder_fills_context_from_source() {
+        let source = TestSource {
+            idempotency_key: Some("idem_1"),
+        };
+        let event = NewEvent::builder(&source)
+            .aggregate_id("app_1")
+            .aggregate_seq(3)
+            .payload(&app_deleted())
+            .build()
+            .unwrap();
+
+        assert_eq!(event.actor_type, ActorType::User);
+        assert_eq!(event.actor_id, "user_123");
+        assert_eq!(event.request_id, "req_123");
+        assert_eq!(event.idempotency_key.as_deref(), Some("idem_1"));
+        assert_eq!(event.correlation_id.as_deref(), Some("req_123"));
+        assert_eq!(event.causation_id, None);
+        assert_eq!(event.event_type, event_types::APP_DELETED);
+        assert_eq!(event.aggregate_type, AggregateType::App);
+        assert_eq!(event.event_version, 1);
+    }
+
+    #[test]
+    fn test_caused_by_propagates_correlation() {
+        let source = SystemSource::new("scheduler").with_request_id("req_sys");
+        let event = NewEvent::builder(&source)
+            .aggregate_id("app_1")
+            .aggregate_seq(2)
+            .caused_by(&Trigger {
+                correlation_id: Some("corr_1"),
+            })
+            .payload(&app_deleted())
+            .build()
+            .unwrap();
+        assert_eq!(event.actor_type, ActorType::System);
+        assert_eq!(event.request_id, "req_sys");
+        assert_eq!(event.causation_id, Some(EventId::new(41)));
+        assert_eq!(event.correlation_id.as_deref(), Some("corr_1"));
+
+        let event = NewEvent::builder(&source)
+            .aggregate_id("app_1")
+            .aggregate_seq(2)
+            .caused_by(&Trigger {
+                correlation_id: None,
+            })
+            .payload(&app_deleted())
+            .build()
+            .unwrap();
+        assert_eq!(event.correlation_id.as_deref(), Some("req_trigger"));
+    }
+
+    #[test]
+    fn test_build_requires_aggregate_and_payload() {
+        let source = TestSource {
+            idempotency_key: None,
+        };
+        let err = NewEvent::builder(&source)
+            .aggregate_seq(1)
+            .payload(&app_deleted())
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, EventError::MissingField("aggregate_id")));
+
+        let err = NewEvent::builder(&source)
+            .aggregate_id("app_1")
+            .aggregate_seq(1)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, EventError::MissingField("payload")));
+    }
+
+    #[test]
+    fn test_json_payload() {
+        let source = TestSource {
+            idempotency_key: Some("idem_1"),
+        };
+        let event = NewEvent::builder(&source)
+            .aggregate_id("org_1")
+            .aggregate_seq(1)
+            .without_idempotency_key()
+            .json_payload(
+                AggregateType::Org,
+                event_types::ORG_UPDATED,
+                serde_json::json!({"name": "Acme"}),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(event.event_type, event_types::ORG_UPDATED);
+        assert_eq!(event.idempotency_key, None);
+        assert_eq!(event.payload["name"], "Acme");
+    }
+}
@@ -1,8 +1,11 @@
 //! Event envelope - the common wrapper for all events.
 
 use chrono::{DateTime, Utc};
-use plfm_id::{AggregateSeq, AppId, EnvId, EventId, OrgId, RequestId};
+use plfm_id::{AggregateSeq, AnyResourceId, AppId, EnvId, EventId, OrgId, RequestId};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::EventError;
 
 /// Actor type for audit logging.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -49,6 +52,9 @@ pub enum AggregateType {
     Instance,
     Node,
     ExecSession,
+    KeyRotation,
+    RouteCertificate,
+    AlertRule,
 }
 
 impl std::fmt::Display for AggregateType {
@@ -71,11 +77,54 @@ impl std::fmt::Display for AggregateType {
             AggregateType::Instance => "instance",
             AggregateType::Node => "node",
             AggregateType::ExecSession => "exec_session",
+            AggregateType::KeyRotation => "key_rotation",
+            AggregateType::RouteCertificate => "route_certificate",
+            AggregateType::AlertRule => "alert_rule",
         };
         write!(f, "{}", s)
     }
 }
 
+impl AggregateType {
+    /// The aggregate an ID is the root of, if any.
+    ///
+    /// Route certificates are keyed by their route's ID, so a route ID maps
+    /// to [`AggregateType::Route`] only.
+    pub fn for_id(id: &AnyResourceId) -> Option<Self> {
+        let aggregate_type = match id {
+            AnyResourceId::Org(_) => AggregateType::Org,
+            AnyResourceId::Project(_) => AggregateType::Project,
+            AnyResourceId::Member(_) => AggregateType::OrgMember,
+            AnyResourceId::ServicePrincipal(_) => AggregateType::ServicePrincipal,
+            AnyResourceId::App(_) => AggregateType::App,
+            AnyResourceId::Env(_) => AggregateType::Env,
+            AnyResourceId::Release(_) => AggregateType::Release,
+            AnyResourceId::Deploy(_) => AggregateType::Deploy,
+            AnyResourceId::Route(_) => AggregateType::Route,
+            AnyResourceId::SecretBundle(_) => AggregateType::SecretBundle,
+            AnyResourceId::Volume(_) => AggregateType::Volume,
+            AnyResourceId::VolumeAttachment(_) => AggregateType::VolumeAttachment,
+            AnyResourceId::Snapshot(_) => AggregateType::Snapshot,
+            AnyResourceId::RestoreJob(_) => AggregateType::RestoreJob,
+            AnyResourceId::Instance(_) => AggregateType::Instance,
+            AnyResourceId::Node(_) => AggregateType::Node,
+            AnyResourceId::ExecSession(_) => AggregateType::ExecSession,
+            AnyResourceId::Token(_)
+            | AnyResourceId::Build(_)
+            | AnyResourceId::Boot(_)
+            | AnyResourceId::Assignment(_)
+            | AnyResourceId::Plan(_)
+            | AnyResourceId::Endpoint(_)
+            | AnyResourceId::Certificate(_)
+            | AnyResourceId::SecretVersion(_)
+            | AnyResourceId::Request(_)
+            | AnyResourceId::Webhook(_)
+            | AnyResourceId::Job(_) => return None,
+        };
+        Some(aggregate_type)
+    }
+}
+
 /// The event envelope - common metadata for all events.
 ///
 /// This corresponds to the `api/schemas/event-envelope.json` schema.
@@ -146,6 +195,21 @@ impl<P> EventEnvelope<P> {
     }
 }
 
+impl<P: Serialize> EventEnvelope<P> {
+    /// SHA-256 of the payload's canonical JSON (RFC 8785), as
+    /// `sha256:<hex>`.
+    ///
+    /// The hash depends only on the payload's content, not on field order
+    /// or number formatting, so it is stable across serializers.
+    pub fn payload_hash(&self) -> Result<String, EventError> {
+        let canonical = plfm_canonical_json::serialize(&self.payload)?;
+        Ok(format!(
+            "sha256:{}",
+            hex::encode(Sha256::digest(canonical.as_bytes()))
+        ))
+    }
+}
+
 /// Builder for constructing event envelopes.
 #[derive(Debug)]
 pub struct EventEnvelopeBuilder<P> {
@@ -324,6 +388,58 @@ mod tests {
         assert_eq!(AggregateType::Org.to_string(), "org");
         assert_eq!(AggregateType::OrgMember.to_string(), "org_member");
         assert_eq!(AggregateType::SecretBundle.to_string(), "secret_bundle");
+        assert_eq!(AggregateType::KeyRotation.to_string(), "key_rotation");
+        assert_eq!(
+            AggregateType::RouteCertificate.to_string(),
+            "route_certificate"
+        );
+        assert_eq!(AggregateType::AlertRule.to_string(), "alert_rule");
+    }
+
+    #[test]
+    fn test_aggregate_type_for_id() {
+        let member = AnyResourceId::Member(plfm_id::MemberId::new());
+        assert_eq!(
+            AggregateType::for_id(&member),
+            Some(AggregateType::OrgMember)
+        );
+        let job = AnyResourceId::RestoreJob(plfm_id::RestoreJobId::new());
+        assert_eq!(AggregateType::for_id(&job), Some(AggregateType::RestoreJob));
+        let request = AnyResourceId::Request(RequestId::new());
+        assert_eq!(AggregateType::for_id(&request), None);
+
+        // Each mapped ID's kind matches the aggregate's name.
+        for prefix in AnyResourceId::PREFIXES {
+            let id = AnyResourceId::parse(&format!("{prefix}_01HV4Z2WQXKJNM8GPQY6VBKC3D")).unwrap();
+            if let Some(aggregate_type) = AggregateType::for_id(&id) {
+                let name = aggregate_type.to_string();
+                assert!(name == id.kind() || name == "org_member", "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_payload_hash_ignores_field_order() {
+        let build = |payload: serde_json::Value| {
+            EventEnvelope::builder()
+                .event_id(EventId::new(1))
+                .aggregate(AggregateType::App, "app_123")
+                .aggregate_seq(AggregateSeq::FIRST)
+                .event_type("app.created")
+                .actor(ActorType::User, "user_123")
+                .request_id(RequestId::new())
+                .payload(payload)
+                .build()
+        };
+        let a = build(serde_json::json!({"name": "web", "replicas": 2}));
+        let b = build(serde_json::json!({"replicas": 2.0, "name": "web"}));
+        let c = build(serde_json::json!({"name": "api", "replicas": 2}));
+
+        let hash = a.payload_hash().unwrap();
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash.len(), "sha256:".len() + 64);
+        assert_eq!(b.payload_hash().unwrap(), hash);
+        assert_ne!(c.payload_hash().unwrap(), hash);
     }
 
     #[test]
@@ -21,6 +21,10 @@ pub enum EventError {
     #[error("serialization error: {0}")]
     Serialization(String),
 
+    /// A required envelope field was not set.
+    #[error("missing event field: {0}")]
+    MissingField(&'static str),
+
     /// The aggregate sequence is invalid.
     #[error("invalid aggregate sequence: expected {expected}, got {actual}")]
     InvalidSequence { expected: i32, actual: i32 },
@@ -17,6 +17,12 @@
 //! - Audit context (`actor_type`, `actor_id`, `request_id`)
 //! - Correlation (`correlation_id`, `causation_id`)
 //!
+//! ## Building Events
+//!
+//! [`NewEvent::builder`] fills the audit and correlation fields from an
+//! [`EventSource`] and, optionally, the [`CausalEvent`] that triggered the
+//! write; typed payloads ([`EventPayload`]) supply the event and aggregate type.
+//!
 //! ## Event Types
 //!
 //! Events are organized by aggregate:
@@ -29,10 +35,12 @@
 //! - Node events (`node.*`)
 //! - Session events (`exec_session.*`)
 
+mod builder;
 mod envelope;
 mod error;
 mod types;
 
+pub use builder::*;
 pub use envelope::*;
 pub use error::EventError;
 pub use types::*;
@@ -3,6 +3,8 @@
 //! Each event type has a corresponding payload struct with the event-specific data.
 //! Events are versioned for schema evolution.
 
+use std::collections::BTreeMap;
+
 use plfm_id::{
     AppId, DeployId, EnvId, ExecSessionId, InstanceId, MemberId, NodeId, OrgId, ProjectId,
     ReleaseId, RestoreJobId, RouteId, SecretBundleId, SecretVersionId, ServicePrincipalId,
@@ -36,16 +38,27 @@ pub mod event_types {
     // Application
     pub const APP_CREATED: &str = "app.created";
     pub const APP_UPDATED: &str = "app.updated";
+    pub const APP_LABELS_UPDATED: &str = "app.labels_updated";
+    pub const APP_DELETION_REQUESTED: &str = "app.deletion_requested";
     pub const APP_DELETED: &str = "app.deleted";
+    pub const APP_RESTORED: &str = "app.restored";
+    pub const APP_PURGED: &str = "app.purged";
 
     // Environment
     pub const ENV_CREATED: &str = "env.created";
     pub const ENV_UPDATED: &str = "env.updated";
+    pub const ENV_LABELS_UPDATED: &str = "env.labels_updated";
+    pub const ENV_DELETION_REQUESTED: &str = "env.deletion_requested";
     pub const ENV_DELETED: &str = "env.deleted";
+    pub const ENV_RESTORED: &str = "env.restored";
+    pub const ENV_PURGED: &str = "env.purged";
     pub const ENV_SCALE_SET: &str = "env.scale_set";
     pub const ENV_DESIRED_RELEASE_SET: &str = "env.desired_release_set";
+    pub const ENV_RESTART_REQUESTED: &str = "env.restart_requested";
     pub const ENV_IPV4_ADDON_ENABLED: &str = "env.ipv4_addon_enabled";
     pub const ENV_IPV4_ADDON_DISABLED: &str = "env.ipv4_addon_disabled";
+    pub const ENV_CONFIG_VARS_SET: &str = "env.config_vars_set";
+    pub const ENV_PROCESS_RESOURCES_SET: &str = "env.process_resources_set";
 
     // Release
     pub const RELEASE_CREATED: &str = "release.created";
@@ -53,25 +66,52 @@ pub mod event_types {
     // Deploy
     pub const DEPLOY_CREATED: &str = "deploy.created";
     pub const DEPLOY_STATUS_CHANGED: &str = "deploy.status_changed";
+    pub const DEPLOY_PROMOTED: &str = "deploy.promoted";
+    pub const DEPLOY_ROLLOUT_STUCK: &str = "deploy.rollout_stuck";
 
     // Route
     pub const ROUTE_CREATED: &str = "route.created";
     pub const ROUTE_UPDATED: &str = "route.updated";
+    pub const ROUTE_LABELS_UPDATED: &str = "route.labels_updated";
     pub const ROUTE_DELETED: &str = "route.deleted";
+    pub const ROUTE_RESTORED: &str = "route.restored";
+    pub const ROUTE_PURGED: &str = "route.purged";
+    pub const ROUTE_CERT_SET: &str = "route.cert_set";
+    pub const ROUTE_CERT_REMOVED: &str = "route.cert_removed";
+    pub const ROUTE_CERT_EXPIRING: &str = "route.cert_expiring";
+    pub const ROUTE_VERIFICATION_SUCCEEDED: &str = "route.verification_succeeded";
+    pub const ROUTE_VERIFICATION_FAILED: &str = "route.verification_failed";
 
     // Secret Bundle
     pub const SECRET_BUNDLE_CREATED: &str = "secret_bundle.created";
     pub const SECRET_BUNDLE_VERSION_SET: &str = "secret_bundle.version_set";
+    pub const SECRET_BUNDLE_ARCHIVED: &str = "secret_bundle.archived";
+
+    // Secrets key rotation
+    pub const KEY_ROTATION_STARTED: &str = "key_rotation.started";
+    pub const KEY_ROTATION_COMPLETED: &str = "key_rotation.completed";
 
     // Volume
     pub const VOLUME_CREATED: &str = "volume.created";
+    pub const VOLUME_LABELS_UPDATED: &str = "volume.labels_updated";
     pub const VOLUME_DELETED: &str = "volume.deleted";
+    pub const VOLUME_RESTORED: &str = "volume.restored";
+    pub const VOLUME_PURGED: &str = "volume.purged";
+    pub const VOLUME_RESIZED: &str = "volume.resized";
+    pub const VOLUME_RESIZE_COMPLETED: &str = "volume.resize_completed";
+    pub const VOLUME_RESIZE_FAILED: &str = "volume.resize_failed";
+    pub const VOLUME_BACKUP_POLICY_SET: &str = "volume.backup_policy_set";
+    pub const VOLUME_ATTACHED: &str = "volume.attached";
+    pub const VOLUME_DETACHED: &str = "volume.detached";
+    /// Legacy name of `volume.attached`; still projected for replay.
     pub const VOLUME_ATTACHMENT_CREATED: &str = "volume_attachment.created";
+    /// Legacy name of `volume.detached`; still projected for replay.
     pub const VOLUME_ATTACHMENT_DELETED: &str = "volume_attachment.deleted";
 
     // Snapshot
     pub const SNAPSHOT_CREATED: &str = "snapshot.created";
     pub const SNAPSHOT_STATUS_CHANGED: &str = "snapshot.status_changed";
+    pub const SNAPSHOT_DELETED: &str = "snapshot.deleted";
 
     // Restore Job
     pub const RESTORE_JOB_CREATED: &str = "restore_job.created";
@@ -81,16 +121,27 @@ pub mod event_types {
     pub const INSTANCE_ALLOCATED: &str = "instance.allocated";
     pub const INSTANCE_DESIRED_STATE_CHANGED: &str = "instance.desired_state_changed";
     pub const INSTANCE_STATUS_CHANGED: &str = "instance.status_changed";
+    pub const INSTANCE_CONFIG_UPDATED: &str = "instance.config_updated";
+    pub const INSTANCE_DRIFT_DETECTED: &str = "instance.drift_detected";
 
     // Node
     pub const NODE_ENROLLED: &str = "node.enrolled";
     pub const NODE_STATE_CHANGED: &str = "node.state_changed";
     pub const NODE_CAPACITY_UPDATED: &str = "node.capacity_updated";
+    pub const NODE_ENROLLMENT_REQUESTED: &str = "node.enrollment_requested";
+    pub const NODE_ENROLLMENT_APPROVED: &str = "node.enrollment_approved";
+    pub const NODE_ENROLLMENT_DENIED: &str = "node.enrollment_denied";
+    pub const NODE_LABELS_UPDATED: &str = "node.labels_updated";
+    pub const NODE_TAINTS_UPDATED: &str = "node.taints_updated";
 
     // Exec Session
     pub const EXEC_SESSION_GRANTED: &str = "exec_session.granted";
     pub const EXEC_SESSION_CONNECTED: &str = "exec_session.connected";
     pub const EXEC_SESSION_ENDED: &str = "exec_session.ended";
+
+    // Alert
+    pub const ALERT_FIRED: &str = "alert.fired";
+    pub const ALERT_RESOLVED: &str = "alert.resolved";
 }
 
 // =============================================================================
@@ -105,6 +156,8 @@ pub enum DeployStatus {
     Rolling,
     Succeeded,
     Failed,
+    Paused,
+    Halted,
 }
 
 /// Instance desired state.
@@ -127,6 +180,18 @@ pub enum InstanceStatus {
     Failed,
 }
 
+impl InstanceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Booting => "booting",
+            Self::Ready => "ready",
+            Self::Draining => "draining",
+            Self::Stopped => "stopped",
+            Self::Failed => "failed",
+        }
+    }
+}
+
 /// Node state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -138,6 +203,29 @@ pub enum NodeState {
     Offline,
 }
 
+impl NodeState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Draining => "draining",
+            Self::Disabled => "disabled",
+            Self::Degraded => "degraded",
+            Self::Offline => "offline",
+        }
+    }
+}
+
+/// Scheduling effect of a node taint.
+///
+/// Serialized with the Kubernetes spelling (`NoSchedule`, `PreferNoSchedule`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaintEffect {
+    /// Instances without a matching toleration are never placed on the node.
+    NoSchedule,
+    /// The scheduler avoids the node for instances without a matching toleration.
+    PreferNoSchedule,
+}
+
 /// Snapshot/restore job status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -164,6 +252,85 @@ pub enum InstanceFailureReason {
     CrashLoopBackoff,
     TerminatedByOperator,
     NodeDraining,
+    KernelPanic,
+    ConfigHandshakeTimeout,
+    SecretsFetchFailed,
+    GuestInitFailed,
+}
+
+/// How the scheduler should retry an instance that failed with a given reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureRetryClass {
+    /// Host or infrastructure fault; retry promptly, preferably elsewhere.
+    Transient,
+    /// Caused by the workload or its image; retry with exponential backoff.
+    Backoff,
+    /// Retrying cannot help until the spec or configuration changes.
+    Terminal,
+}
+
+impl InstanceFailureReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ImagePullFailed => "image_pull_failed",
+            Self::RootfsBuildFailed => "rootfs_build_failed",
+            Self::FirecrackerStartFailed => "firecracker_start_failed",
+            Self::NetworkSetupFailed => "network_setup_failed",
+            Self::VolumeAttachFailed => "volume_attach_failed",
+            Self::SecretsMissing => "secrets_missing",
+            Self::SecretsInjectionFailed => "secrets_injection_failed",
+            Self::HealthcheckFailed => "healthcheck_failed",
+            Self::OomKilled => "oom_killed",
+            Self::CrashLoopBackoff => "crash_loop_backoff",
+            Self::TerminatedByOperator => "terminated_by_operator",
+            Self::NodeDraining => "node_draining",
+            Self::KernelPanic => "kernel_panic",
+            Self::ConfigHandshakeTimeout => "config_handshake_timeout",
+            Self::SecretsFetchFailed => "secrets_fetch_failed",
+            Self::GuestInitFailed => "guest_init_failed",
+        }
+    }
+
+    /// Retry class for this failure reason.
+    pub fn retry_class(&self) -> FailureRetryClass {
+        match self {
+            Self::ImagePullFailed
+            | Self::FirecrackerStartFailed
+            | Self::NetworkSetupFailed
+            | Self::VolumeAttachFailed
+            | Self::SecretsFetchFailed
+            | Self::ConfigHandshakeTimeout
+            | Self::NodeDraining => FailureRetryClass::Transient,
+            Self::RootfsBuildFailed
+            | Self::SecretsInjectionFailed
+            | Self::HealthcheckFailed
+            | Self::OomKilled
+            | Self::CrashLoopBackoff
+            | Self::KernelPanic
+            | Self::GuestInitFailed => FailureRetryClass::Backoff,
+            Self::SecretsMissing | Self::TerminatedByOperator => FailureRetryClass::Terminal,
+        }
+    }
+}
+
+/// Mismatch between desired instances and node-reported instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceDriftKind {
+    /// Desired on the node, but the node does not report it.
+    NotReported,
+    /// Reported by the node, but not desired there.
+    NotDesired,
+}
+
+impl InstanceDriftKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotReported => "not_reported",
+            Self::NotDesired => "not_desired",
+        }
+    }
 }
 
 /// Organization member role.
@@ -186,6 +353,9 @@ pub enum MemberRole {
 pub enum RouteProtocolHint {
     TlsPassthrough,
     TcpRaw,
+    /// TLS terminated at the edge with the route's uploaded certificate;
+    /// plaintext TCP is forwarded to the backend.
+    TlsTerminate,
 }
 
 /// Proxy Protocol mode for edge -> backend connections.
@@ -202,6 +372,16 @@ impl Default for RouteProxyProtocol {
     }
 }
 
+/// How a route's hostname ownership was proven.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteVerificationMethod {
+    /// `_plfm-challenge.<hostname>` TXT record carried the route's token.
+    Txt,
+    /// The hostname is a CNAME to the route's platform target.
+    Cname,
+}
+
 // =============================================================================
 // Event Payloads
 // =============================================================================
@@ -322,11 +502,42 @@ pub struct AppUpdatedPayload {
     pub description: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLabelsUpdatedPayload {
+    pub app_id: AppId,
+    pub org_id: OrgId,
+    /// Full label set after the update.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppDeletionRequestedPayload {
+    pub app_id: AppId,
+    pub org_id: OrgId,
+    #[serde(default)]
+    pub delete_volumes: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppDeletedPayload {
     pub app_id: AppId,
 }
 
+/// A deleted app was brought back from the trash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRestoredPayload {
+    pub app_id: AppId,
+    pub org_id: OrgId,
+}
+
+/// A deleted app left the trash for good; it can no longer be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppPurgedPayload {
+    pub app_id: AppId,
+    pub org_id: OrgId,
+}
+
 // -----------------------------------------------------------------------------
 // Environment Events
 // -----------------------------------------------------------------------------
@@ -337,6 +548,15 @@ pub struct EnvCreatedPayload {
     pub org_id: OrgId,
     pub app_id: AppId,
     pub name: String,
+    /// Platform-managed hostname, when managed hostnames are enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub managed_hostname: Option<String>,
+    /// Ephemeral preview env, torn down once it expires.
+    #[serde(default)]
+    pub preview: bool,
+    /// When a preview env expires (RFC 3339).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -348,17 +568,166 @@ pub struct EnvUpdatedPayload {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvLabelsUpdatedPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+    /// Full label set after the update.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvConfigVarsSetPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+    /// Config vars version, incremented on every change.
+    pub version: i32,
+    /// Full set of config vars after the change.
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProcessResourcesSetPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+    pub process_type: String,
+    /// Override version for this process type, incremented on every change.
+    pub version: i32,
+    /// vCPU request; the release value applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_request: Option<f64>,
+    /// Memory limit in bytes; the release value applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_bytes: Option<i64>,
+    /// Scratch disk in bytes; the env scale value applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvDeletionRequestedPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+    #[serde(default)]
+    pub delete_volumes: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvDeletedPayload {
     pub env_id: EnvId,
 }
 
+/// A deleted env was brought back from the trash, scaled to zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvRestoredPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+}
+
+/// A deleted env left the trash for good; it can no longer be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvPurgedPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvScaleSetPayload {
     pub env_id: EnvId,
     pub process_type: String,
     pub min_replicas: i32,
     pub max_replicas: i32,
+    /// Why the scale was changed, as given by the caller.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// How a process type's running instances pick up a new secrets version.
+///
+/// `Restart` (the default) replaces instances with a rolling restart. The
+/// other modes rewrite `/run/secrets/platform.env` in place inside the
+/// guest and then notify the workload, if at all.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SecretsReload {
+    #[default]
+    Restart,
+    /// Rewrite the file only; for workloads that re-read it themselves.
+    File,
+    /// Rewrite the file, then send `signal` to the workload process.
+    Signal {
+        #[serde(default = "default_reload_signal")]
+        signal: String,
+    },
+    /// Rewrite the file, then run `command` inside the guest as the
+    /// workload user; a non-zero exit is reported as a failed reload.
+    Exec {
+        command: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_seconds: Option<u32>,
+    },
+}
+
+impl SecretsReload {
+    /// Signals a workload may ask to receive.
+    pub const SIGNALS: [&'static str; 3] = ["SIGHUP", "SIGUSR1", "SIGUSR2"];
+    /// Reload hook timeout when none is set.
+    pub const DEFAULT_EXEC_TIMEOUT_SECONDS: u32 = 30;
+    /// Longest reload hook timeout allowed.
+    pub const MAX_EXEC_TIMEOUT_SECONDS: u32 = 300;
+
+    /// Whether a new secrets version is delivered to running instances
+    /// instead of replacing them.
+    pub fn in_place(&self) -> bool {
+        !matches!(self, SecretsReload::Restart)
+    }
+
+    /// Check the signal name, hook command and timeout.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SecretsReload::Restart | SecretsReload::File => Ok(()),
+            SecretsReload::Signal { signal } => {
+                if Self::SIGNALS.contains(&signal.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "signal must be one of {}",
+                        Self::SIGNALS.join(", ")
+                    ))
+                }
+            }
+            SecretsReload::Exec {
+                command,
+                timeout_seconds,
+            } => {
+                if command
+                    .first()
+                    .is_none_or(|program| program.trim().is_empty())
+                {
+                    return Err("command must name a program".to_string());
+                }
+                if timeout_seconds.is_some_and(|t| t == 0 || t > Self::MAX_EXEC_TIMEOUT_SECONDS) {
+                    return Err(format!(
+                        "timeout_seconds must be between 1 and {}",
+                        Self::MAX_EXEC_TIMEOUT_SECONDS
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn default_reload_signal() -> String {
+    "SIGHUP".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -368,6 +737,14 @@ pub struct EnvDesiredReleaseSetPayload {
     pub deploy_id: DeployId,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvRestartRequestedPayload {
+    pub env_id: EnvId,
+    pub org_id: OrgId,
+    pub app_id: AppId,
+    pub process_types: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvIpv4AddonEnabledPayload {
     pub env_id: EnvId,
@@ -395,6 +772,38 @@ pub struct ReleaseCreatedPayload {
     pub image_digest: String,
     pub manifest_hash: String,
     pub command: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ReleaseMetadata>,
+}
+
+/// Source and build provenance recorded with a release.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseMetadata {
+    /// Git ref the release was built from (branch, tag, or PR head ref).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Git commit SHA the image was built from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// Git branch the commit was on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// URL of the CI run that built the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci_run_url: Option<String>,
+    /// Digest of the image's SBOM (sha256:...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom_digest: Option<String>,
+    /// Identity of the builder (CI system, workflow, or build service).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder: Option<String>,
+}
+
+impl ReleaseMetadata {
+    /// Whether no metadata was recorded.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -412,6 +821,12 @@ pub struct DeployCreatedPayload {
     pub process_types: Vec<String>,
     pub strategy: String,
     pub initiated_at: String,
+    /// Metadata of the release being deployed, copied for traceability.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_metadata: Option<ReleaseMetadata>,
+    /// Why the deploy was made, as given by the caller.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -427,6 +842,24 @@ pub struct DeployStatusChangedPayload {
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployPromotedPayload {
+    pub deploy_id: DeployId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployRolloutStuckPayload {
+    pub deploy_id: DeployId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub process_type: String,
+    pub converging_secs: i64,
+    pub deadline_secs: i64,
+    pub message: String,
+}
+
 // -----------------------------------------------------------------------------
 // Route Events
 // -----------------------------------------------------------------------------
@@ -447,6 +880,43 @@ pub struct RouteCreatedPayload {
     pub ipv4_required: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env_ipv4_address: Option<String>,
+    /// Set when the hostname must be verified before the route is served.
+    /// Routes created without a token are active immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_token: Option<String>,
+    /// Served only on the overlay network, by internal ingress listeners.
+    #[serde(default)]
+    pub internal: bool,
+    /// Client filtering applied by the edge; absent when unrestricted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+}
+
+/// Connection-level client filtering for a route.
+///
+/// Deny lists win; a non-empty allow list admits only its entries.
+/// Fingerprints are JA3 hashes or JA4 strings of the TLS ClientHello and are
+/// only checked on TLS routes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteAccessPolicy {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_cidrs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_cidrs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_fingerprints: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_fingerprints: Vec<String>,
+}
+
+impl RouteAccessPolicy {
+    /// Whether the policy restricts nothing.
+    pub fn is_empty(&self) -> bool {
+        self.allow_cidrs.is_empty()
+            && self.deny_cidrs.is_empty()
+            && self.allow_fingerprints.is_empty()
+            && self.deny_fingerprints.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -466,6 +936,19 @@ pub struct RouteUpdatedPayload {
     pub ipv4_required: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env_ipv4_address: Option<Option<String>>,
+    /// Replaces the whole policy; an empty policy clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteLabelsUpdatedPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    /// Full label set after the update.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -476,6 +959,83 @@ pub struct RouteDeletedPayload {
     pub hostname: String,
 }
 
+/// A deleted route was brought back from the trash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteRestoredPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+}
+
+/// A deleted route left the trash for good; it can no longer be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutePurgedPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+}
+
+/// A custom certificate was uploaded for a route. Metadata only; the key
+/// never appears in events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteCertSetPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+    pub fingerprint_sha256: String,
+    /// RFC 3339.
+    pub not_after: String,
+    #[serde(default)]
+    pub default_for_non_sni: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteCertRemovedPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+    pub fingerprint_sha256: String,
+}
+
+/// Emitted once per certificate when it enters the expiry warning window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteCertExpiringPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+    pub fingerprint_sha256: String,
+    /// RFC 3339.
+    pub not_after: String,
+    pub days_remaining: i64,
+}
+
+/// Hostname ownership was proven; the route may now be served.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteVerificationSucceededPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+    pub method: RouteVerificationMethod,
+}
+
+/// Verification did not succeed within the verification window. The route
+/// stays inactive until a manual re-check succeeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteVerificationFailedPayload {
+    pub route_id: RouteId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    pub hostname: String,
+    pub reason: String,
+    pub attempts: i32,
+}
+
 // -----------------------------------------------------------------------------
 // Secret Bundle Events
 // -----------------------------------------------------------------------------
@@ -501,6 +1061,42 @@ pub struct SecretBundleVersionSetPayload {
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretBundleArchivedPayload {
+    pub bundle_id: SecretBundleId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+}
+
+// -----------------------------------------------------------------------------
+// Secrets Key Rotation Events
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotationStartedPayload {
+    pub job_id: String,
+    /// Restricts the job to one org's material; platform keys are included
+    /// only when absent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<OrgId>,
+    /// Platform key ID material is re-wrapped under.
+    pub target_key_id: String,
+    /// Rows in scope when the job started.
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotationCompletedPayload {
+    pub job_id: String,
+    /// `completed`, `failed`, or `cancelled`.
+    pub outcome: String,
+    pub scanned: i64,
+    pub rewrapped: i64,
+    pub failed: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 // -----------------------------------------------------------------------------
 // Volume Events
 // -----------------------------------------------------------------------------
@@ -516,12 +1112,83 @@ pub struct VolumeCreatedPayload {
     pub backup_enabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeLabelsUpdatedPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+    /// Full label set after the update.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VolumeDeletedPayload {
     pub volume_id: VolumeId,
     pub org_id: OrgId,
 }
 
+/// A deleted volume was brought back from the trash, unattached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeRestoredPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+}
+
+/// A deleted volume left the trash for good; its data may now be reclaimed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumePurgedPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+}
+
+/// A grow of a volume was requested. Volumes never shrink.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeResizedPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+    pub previous_size_bytes: i64,
+    pub size_bytes: i64,
+}
+
+/// The volume's home node grew the backing storage to `size_bytes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeResizeCompletedPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+    pub node_id: NodeId,
+    pub size_bytes: i64,
+}
+
+/// The volume's home node could not grow the backing storage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeResizeFailedPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+    pub node_id: NodeId,
+    pub size_bytes: i64,
+    /// Machine-readable cause, e.g. `insufficient_space`.
+    pub reason: String,
+    pub message: String,
+}
+
+/// The volume's scheduled snapshot policy was set or cleared.
+///
+/// `schedule` is a five-field cron expression evaluated in UTC; `None`
+/// turns scheduled snapshots off. Retention only prunes scheduled snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeBackupPolicySetPayload {
+    pub volume_id: VolumeId,
+    pub org_id: OrgId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Keep at most this many successful scheduled snapshots.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_count: Option<i32>,
+    /// Prune scheduled snapshots older than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_max_age_secs: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VolumeAttachmentCreatedPayload {
     pub attachment_id: VolumeAttachmentId,
@@ -543,6 +1210,29 @@ pub struct VolumeAttachmentDeletedPayload {
     pub process_type: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeAttachedPayload {
+    pub attachment_id: VolumeAttachmentId,
+    pub org_id: OrgId,
+    pub volume_id: VolumeId,
+    pub app_id: AppId,
+    pub env_id: EnvId,
+    pub process_type: String,
+    pub mount_path: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeDetachedPayload {
+    pub attachment_id: VolumeAttachmentId,
+    pub org_id: OrgId,
+    pub volume_id: VolumeId,
+    pub app_id: AppId,
+    pub env_id: EnvId,
+    pub process_type: String,
+    pub mount_path: String,
+}
+
 // -----------------------------------------------------------------------------
 // Snapshot Events
 // -----------------------------------------------------------------------------
@@ -555,6 +1245,9 @@ pub struct SnapshotCreatedPayload {
     pub status: JobStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+    /// Created by the volume's backup policy rather than on request.
+    #[serde(default)]
+    pub scheduled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -569,6 +1262,16 @@ pub struct SnapshotStatusChangedPayload {
     pub failed_reason: Option<String>,
 }
 
+/// A snapshot was removed, e.g. pruned by the volume's retention policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDeletedPayload {
+    pub snapshot_id: SnapshotId,
+    pub org_id: OrgId,
+    pub volume_id: VolumeId,
+    /// Why the snapshot was removed, e.g. `retention`.
+    pub reason: String,
+}
+
 // -----------------------------------------------------------------------------
 // Restore Job Events
 // -----------------------------------------------------------------------------
@@ -582,6 +1285,9 @@ pub struct RestoreJobCreatedPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub new_volume_name: Option<String>,
     pub status: JobStatus,
+    /// Overwrite `source_volume_id` instead of creating a new volume.
+    #[serde(default)]
+    pub in_place: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -593,6 +1299,12 @@ pub struct RestoreJobStatusChangedPayload {
     pub new_volume_id: Option<VolumeId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failed_reason: Option<String>,
+    /// Bytes written so far by an in-place restore.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_restored: Option<i64>,
+    /// Total bytes an in-place restore will write.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_total: Option<i64>,
 }
 
 // -----------------------------------------------------------------------------
@@ -618,6 +1330,9 @@ pub struct InstanceAllocatedPayload {
     pub release_id: ReleaseId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secrets_version_id: Option<SecretVersionId>,
+    /// Config vars version the instance boots with (`env.config_vars_set`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_vars_version: Option<i32>,
     pub overlay_ipv6: String,
     pub resources_snapshot: InstanceResourcesSnapshot,
     pub spec_hash: String,
@@ -635,6 +1350,19 @@ pub struct InstanceDesiredStateChangedPayload {
     pub reason: Option<String>,
 }
 
+/// Non-disruptive config change delivered to a running instance in place;
+/// bumps the instance's config generation but not its spec hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceConfigUpdatedPayload {
+    pub instance_id: InstanceId,
+    pub org_id: OrgId,
+    pub env_id: EnvId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets_version_id: Option<SecretVersionId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceStatusChangedPayload {
     pub instance_id: InstanceId,
@@ -644,6 +1372,9 @@ pub struct InstanceStatusChangedPayload {
     pub status: InstanceStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub boot_id: Option<String>,
+    /// Plan generation the reporting boot runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub microvm_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -655,6 +1386,23 @@ pub struct InstanceStatusChangedPayload {
     pub reported_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceDriftDetectedPayload {
+    pub instance_id: InstanceId,
+    pub node_id: NodeId,
+    /// Absent when the node reports an instance the control plane never allocated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<OrgId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<EnvId>,
+    pub kind: InstanceDriftKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reported_status: Option<String>,
+    pub detected_at: String,
+}
+
 // -----------------------------------------------------------------------------
 // Node Events
 // -----------------------------------------------------------------------------
@@ -683,6 +1431,83 @@ pub struct NodeCapacityUpdatedPayload {
     pub available_cpu_cores: i32,
     pub available_memory_bytes: i64,
     pub instance_count: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_used_bytes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_available_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeEnrollmentRequestedPayload {
+    pub node_id: NodeId,
+    pub hostname: String,
+    pub region: String,
+    pub cpu_cores: i32,
+    pub memory_bytes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootstrap_token_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeEnrollmentApprovedPayload {
+    pub node_id: NodeId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeEnrollmentDeniedPayload {
+    pub node_id: NodeId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeLabelsUpdatedPayload {
+    pub node_id: NodeId,
+    /// Full label set after the update.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// A taint that repels instances without a matching [`Toleration`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeTaint {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub effect: TaintEffect,
+}
+
+/// Allows placement on nodes carrying a matching [`NodeTaint`].
+///
+/// An absent `value` matches any value of the key; an absent `effect`
+/// matches both effects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toleration {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect: Option<TaintEffect>,
+}
+
+impl Toleration {
+    /// Whether this toleration matches `taint`.
+    pub fn tolerates(&self, taint: &NodeTaint) -> bool {
+        self.key == taint.key
+            && self
+                .value
+                .as_ref()
+                .is_none_or(|v| taint.value.as_ref() == Some(v))
+            && self.effect.is_none_or(|e| e == taint.effect)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTaintsUpdatedPayload {
+    pub node_id: NodeId,
+    /// Full taint set after the update.
+    #[serde(default)]
+    pub taints: Vec<NodeTaint>,
 }
 
 // -----------------------------------------------------------------------------
@@ -720,6 +1545,50 @@ pub struct ExecSessionEndedPayload {
     pub end_reason: Option<String>,
 }
 
+// -----------------------------------------------------------------------------
+// Alert Events
+// -----------------------------------------------------------------------------
+
+/// An alert rule's condition has held for its `for` duration on `target`
+/// (an env, node, or projection).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertFiredPayload {
+    pub rule_id: String,
+    pub org_id: OrgId,
+    pub rule_name: String,
+    /// `env_unhealthy`, `node_offline`, `projection_lag`, or `instance_restarts`.
+    pub condition: String,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<AppId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<EnvId>,
+    /// Observed value for threshold conditions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<i64>,
+    pub summary: String,
+    /// When the condition started holding (RFC 3339).
+    pub pending_since: String,
+}
+
+/// A firing alert's condition no longer holds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertResolvedPayload {
+    pub rule_id: String,
+    pub org_id: OrgId,
+    pub rule_name: String,
+    pub condition: String,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<AppId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<EnvId>,
+    /// RFC 3339.
+    pub fired_at: String,
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -738,6 +1607,10 @@ mod tests {
             serde_json::to_string(&DeployStatus::Rolling).unwrap(),
             "\"rolling\""
         );
+        assert_eq!(
+            serde_json::to_string(&DeployStatus::Paused).unwrap(),
+            "\"paused\""
+        );
     }
 
     #[test]
@@ -750,6 +1623,26 @@ mod tests {
             serde_json::to_string(&InstanceFailureReason::CrashLoopBackoff).unwrap(),
             "\"crash_loop_backoff\""
         );
+        assert_eq!(
+            serde_json::to_string(&InstanceFailureReason::ConfigHandshakeTimeout).unwrap(),
+            "\"config_handshake_timeout\""
+        );
+    }
+
+    #[test]
+    fn test_instance_failure_reason_retry_class() {
+        assert_eq!(
+            InstanceFailureReason::ImagePullFailed.retry_class(),
+            FailureRetryClass::Transient
+        );
+        assert_eq!(
+            InstanceFailureReason::KernelPanic.retry_class(),
+            FailureRetryClass::Backoff
+        );
+        assert_eq!(
+            InstanceFailureReason::SecretsMissing.retry_class(),
+            FailureRetryClass::Terminal
+        );
     }
 
     #[test]
@@ -772,6 +1665,7 @@ mod tests {
             node_id: NodeId::new(),
             status: InstanceStatus::Failed,
             boot_id: Some("boot_123".to_string()),
+            generation: Some(2),
             microvm_id: None,
             exit_code: Some(137),
             reason_code: Some(InstanceFailureReason::HealthcheckFailed),
@@ -796,6 +1690,104 @@ mod tests {
             let json = serde_json::to_string(&state).unwrap();
             let parsed: NodeState = serde_json::from_str(&json).unwrap();
             assert_eq!(state, parsed);
+            assert_eq!(json, format!("\"{}\"", state.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_as_str_matches_serde() {
+        for status in [
+            InstanceStatus::Booting,
+            InstanceStatus::Ready,
+            InstanceStatus::Draining,
+            InstanceStatus::Stopped,
+            InstanceStatus::Failed,
+        ] {
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                serde_json::Value::from(status.as_str())
+            );
+        }
+        for reason in [
+            InstanceFailureReason::ImagePullFailed,
+            InstanceFailureReason::SecretsInjectionFailed,
+            InstanceFailureReason::OomKilled,
+            InstanceFailureReason::ConfigHandshakeTimeout,
+            InstanceFailureReason::GuestInitFailed,
+        ] {
+            assert_eq!(
+                serde_json::to_value(reason).unwrap(),
+                serde_json::Value::from(reason.as_str())
+            );
         }
     }
+
+    #[test]
+    fn test_toleration_matching() {
+        let taint: NodeTaint =
+            serde_json::from_str(r#"{"key":"gpu","value":"a100","effect":"NoSchedule"}"#).unwrap();
+        assert_eq!(taint.effect, TaintEffect::NoSchedule);
+
+        let exact = Toleration {
+            key: "gpu".to_string(),
+            value: Some("a100".to_string()),
+            effect: Some(TaintEffect::NoSchedule),
+        };
+        assert!(exact.tolerates(&taint));
+
+        let any_value: Toleration = serde_json::from_str(r#"{"key":"gpu"}"#).unwrap();
+        assert!(any_value.tolerates(&taint));
+
+        let other_value = Toleration {
+            value: Some("h100".to_string()),
+            ..exact.clone()
+        };
+        assert!(!other_value.tolerates(&taint));
+
+        let other_effect = Toleration {
+            effect: Some(TaintEffect::PreferNoSchedule),
+            ..exact.clone()
+        };
+        assert!(!other_effect.tolerates(&taint));
+
+        let other_key = Toleration {
+            key: "spot".to_string(),
+            ..exact
+        };
+        assert!(!other_key.tolerates(&taint));
+    }
+
+    #[test]
+    fn test_secrets_reload_modes() {
+        let parse = |json: &str| serde_json::from_str::<SecretsReload>(json).unwrap();
+
+        assert_eq!(parse(r#"{"mode":"restart"}"#), SecretsReload::default());
+        assert!(!SecretsReload::default().in_place());
+
+        let signal = parse(r#"{"mode":"signal"}"#);
+        assert_eq!(
+            signal,
+            SecretsReload::Signal {
+                signal: "SIGHUP".to_string()
+            }
+        );
+        assert!(signal.in_place());
+        assert!(signal.validate().is_ok());
+        assert!(parse(r#"{"mode":"signal","signal":"SIGKILL"}"#)
+            .validate()
+            .is_err());
+
+        let exec = parse(r#"{"mode":"exec","command":["/app/reload"],"timeout_seconds":5}"#);
+        assert!(exec.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&exec).unwrap()["mode"],
+            serde_json::json!("exec")
+        );
+        assert!(parse(r#"{"mode":"exec","command":[]}"#).validate().is_err());
+        assert!(
+            parse(r#"{"mode":"exec","command":["/app/reload"],"timeout_seconds":0}"#)
+                .validate()
+                .is_err()
+        );
+    }
 }
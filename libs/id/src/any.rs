@@ -0,0 +1,247 @@
+//! Classifying IDs of any resource type by prefix.
+
+use std::str::FromStr;
+
+use crate::error::{BatchIdError, InvalidId};
+use crate::types::*;
+use crate::{IdError, Ulid};
+
+/// Defines [`AnyResourceId`] with one variant per typed ID.
+macro_rules! any_resource_id {
+    ($($variant:ident($ty:ident) => $kind:literal,)*) => {
+        /// An ID of any resource type, classified by its prefix.
+        ///
+        /// Lets generic code (search, event filters) validate and dispatch on
+        /// an ID without matching prefixes itself.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum AnyResourceId {
+            $($variant($ty),)*
+        }
+
+        impl AnyResourceId {
+            /// Prefixes of every ID type.
+            pub const PREFIXES: &'static [&'static str] = &[$($ty::PREFIX,)*];
+
+            /// Parses a prefixed ID of any known type.
+            pub fn parse(s: &str) -> Result<Self, IdError> {
+                if s.is_empty() {
+                    return Err(IdError::Empty);
+                }
+                let Some((prefix, _)) = s.split_once('_') else {
+                    return Err(IdError::MissingSeparator);
+                };
+                $(
+                    if prefix == $ty::PREFIX {
+                        return $ty::parse(s).map(Self::$variant);
+                    }
+                )*
+                Err(IdError::UnknownPrefix(prefix.to_string()))
+            }
+
+            /// The ID's prefix.
+            #[must_use]
+            pub fn prefix(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => $ty::PREFIX,)*
+                }
+            }
+
+            /// The resource type in snake_case (e.g. `app`, `restore_job`).
+            #[must_use]
+            pub fn kind(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => $kind,)*
+                }
+            }
+
+            /// The underlying ULID.
+            #[must_use]
+            pub fn ulid(&self) -> Ulid {
+                match self {
+                    $(Self::$variant(id) => id.ulid(),)*
+                }
+            }
+        }
+
+        impl std::fmt::Display for AnyResourceId {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(Self::$variant(id) => std::fmt::Display::fmt(id, f),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$ty> for AnyResourceId {
+                fn from(id: $ty) -> Self {
+                    Self::$variant(id)
+                }
+            }
+        )*
+    };
+}
+
+any_resource_id! {
+    Org(OrgId) => "org",
+    Project(ProjectId) => "project",
+    Member(MemberId) => "member",
+    ServicePrincipal(ServicePrincipalId) => "service_principal",
+    Token(TokenId) => "token",
+    App(AppId) => "app",
+    Env(EnvId) => "env",
+    Release(ReleaseId) => "release",
+    Deploy(DeployId) => "deploy",
+    Build(BuildId) => "build",
+    Instance(InstanceId) => "instance",
+    Boot(BootId) => "boot",
+    Node(NodeId) => "node",
+    Assignment(AssignmentId) => "assignment",
+    Plan(PlanId) => "plan",
+    Route(RouteId) => "route",
+    Endpoint(EndpointId) => "endpoint",
+    Certificate(CertificateId) => "certificate",
+    Volume(VolumeId) => "volume",
+    VolumeAttachment(VolumeAttachmentId) => "volume_attachment",
+    Snapshot(SnapshotId) => "snapshot",
+    RestoreJob(RestoreJobId) => "restore_job",
+    SecretBundle(SecretBundleId) => "secret_bundle",
+    SecretVersion(SecretVersionId) => "secret_version",
+    ExecSession(ExecSessionId) => "exec_session",
+    Request(RequestId) => "request",
+    Webhook(WebhookId) => "webhook",
+    Job(JobId) => "job",
+}
+
+impl FromStr for AnyResourceId {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl serde::Serialize for AnyResourceId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for AnyResourceId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parses every input as `T` (any typed ID, or [`AnyResourceId`]).
+///
+/// Either all inputs parse, in order, or every invalid one is reported.
+pub fn parse_many<T, I>(inputs: I) -> Result<Vec<T>, BatchIdError>
+where
+    T: FromStr<Err = IdError>,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut ids = Vec::new();
+    let mut invalid = Vec::new();
+    for (index, input) in inputs.into_iter().enumerate() {
+        let input = input.as_ref();
+        match input.parse::<T>() {
+            Ok(id) => ids.push(id),
+            Err(error) => invalid.push(InvalidId {
+                index,
+                input: input.to_string(),
+                error,
+            }),
+        }
+    }
+    if invalid.is_empty() {
+        Ok(ids)
+    } else {
+        Err(BatchIdError { invalid })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ULID: &str = "01HV4Z2WQXKJNM8GPQY6VBKC3D";
+
+    #[test]
+    fn classifies_every_prefix() {
+        for prefix in AnyResourceId::PREFIXES {
+            let id = AnyResourceId::parse(&format!("{prefix}_{ULID}")).unwrap();
+            assert_eq!(id.prefix(), *prefix);
+            assert_eq!(id.ulid().to_string(), ULID);
+            assert_eq!(id.to_string(), format!("{prefix}_{ULID}"));
+        }
+
+        let unique: std::collections::HashSet<_> = AnyResourceId::PREFIXES.iter().collect();
+        assert_eq!(unique.len(), AnyResourceId::PREFIXES.len());
+    }
+
+    #[test]
+    fn parses_into_typed_variant() {
+        let app = AppId::new();
+        let id: AnyResourceId = app.to_string().parse().unwrap();
+        assert_eq!(id, AnyResourceId::App(app));
+        assert_eq!(id.kind(), "app");
+
+        let id = AnyResourceId::parse(&format!("rjob_{ULID}")).unwrap();
+        assert!(matches!(id, AnyResourceId::RestoreJob(_)));
+        assert_eq!(id.kind(), "restore_job");
+    }
+
+    #[test]
+    fn rejects_unknown_and_malformed_ids() {
+        assert!(matches!(
+            AnyResourceId::parse(&format!("nope_{ULID}")),
+            Err(IdError::UnknownPrefix(p)) if p == "nope"
+        ));
+        assert!(matches!(
+            AnyResourceId::parse("app_bad"),
+            Err(IdError::InvalidUlid(_))
+        ));
+        assert!(matches!(AnyResourceId::parse(""), Err(IdError::Empty)));
+        assert!(matches!(
+            AnyResourceId::parse("app"),
+            Err(IdError::MissingSeparator)
+        ));
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let id = AnyResourceId::from(VolumeId::new());
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(serde_json::from_str::<AnyResourceId>(&json).unwrap(), id);
+    }
+
+    #[test]
+    fn parse_many_reports_every_invalid_input() {
+        let a = AppId::new().to_string();
+        let b = AppId::new().to_string();
+        let ids: Vec<AppId> = parse_many([&a, &b]).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[1].to_string(), b);
+
+        let env = EnvId::new().to_string();
+        let err = parse_many::<AppId, _>([a.as_str(), "", env.as_str()]).unwrap_err();
+        let indexes: Vec<usize> = err.invalid.iter().map(|i| i.index).collect();
+        assert_eq!(indexes, vec![1, 2]);
+        assert_eq!(err.invalid[0].error, IdError::Empty);
+        assert_eq!(err.invalid[1].input, env);
+        assert!(err
+            .to_string()
+            .starts_with("2 invalid ID(s), first at index 1"));
+
+        let mixed: Vec<AnyResourceId> = parse_many([&a, &env]).unwrap();
+        assert_eq!(mixed[1].kind(), "env");
+    }
+}
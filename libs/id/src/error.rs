@@ -27,10 +27,18 @@ pub enum IdError {
     #[error("ID missing underscore separator")]
     MissingSeparator,
 
+    /// The prefix does not belong to any ID type.
+    #[error("unknown ID prefix '{0}'")]
+    UnknownPrefix(String),
+
     /// The ULID portion of the ID is invalid.
     #[error("invalid ULID: {0}")]
     InvalidUlid(String),
 
+    /// The UUID cannot be used as an ID.
+    #[error("invalid UUID: {0}")]
+    InvalidUuid(String),
+
     /// The ID format is invalid.
     #[error("invalid ID format: {message}")]
     InvalidFormat { message: String },
@@ -46,7 +54,30 @@ impl IdError {
     pub fn is_prefix_error(&self) -> bool {
         matches!(
             self,
-            IdError::MissingPrefix { .. } | IdError::InvalidPrefix { .. }
+            IdError::MissingPrefix { .. }
+                | IdError::InvalidPrefix { .. }
+                | IdError::UnknownPrefix(_)
         )
     }
 }
+
+/// One input rejected by [`parse_many`](crate::parse_many).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidId {
+    /// Position of the input in the batch.
+    pub index: usize,
+    pub input: String,
+    pub error: IdError,
+}
+
+/// Every input rejected by [`parse_many`](crate::parse_many); never empty.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error(
+    "{} invalid ID(s), first at index {}: {}",
+    .invalid.len(),
+    .invalid[0].index,
+    .invalid[0].error
+)]
+pub struct BatchIdError {
+    pub invalid: Vec<InvalidId>,
+}
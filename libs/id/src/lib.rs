@@ -23,13 +23,39 @@
 //! - Sortability (ULID is time-ordered)
 //! - Uniqueness (ULID has 80 bits of randomness)
 //! - Human readability (clear prefixes)
+//!
+//! ## UUID Interoperability
+//!
+//! A ULID and a UUID are both 128 bits, so every ID converts losslessly with
+//! `to_uuid()` and `try_from_uuid()`. The UUID shares the ULID's bits, so its
+//! first 48 bits are the millisecond timestamp, as in a UUIDv7. Its version
+//! and variant bits are random, though, so it is not necessarily a valid
+//! UUIDv7. A UUIDv7 from an external system becomes an ID with the same
+//! timestamp and sort order.
+//!
+//! With the `uuid-strings` feature, parsers (and serde) also accept bare UUID
+//! text such as `0190b6d2-6f3a-7c21-9d4e-5a8b7c6d5e4f` for any ID type.
+//!
+//! ## Untyped IDs
+//!
+//! [`AnyResourceId`] parses an ID of any type and classifies it by prefix,
+//! and [`parse_many`] validates a batch of IDs, reporting every invalid one.
 
+mod any;
 mod error;
 mod macros;
 mod types;
+mod uuid_compat;
 
-pub use error::IdError;
+pub use any::{parse_many, AnyResourceId};
+pub use error::{BatchIdError, IdError, InvalidId};
 pub use types::*;
 
 /// Re-export ulid for consumers that need raw ULID operations
 pub use ulid::Ulid;
+
+/// Re-export uuid for consumers converting IDs to and from UUIDs
+pub use uuid::Uuid;
+
+#[doc(hidden)]
+pub use crate::uuid_compat::{ulid_from_uuid, ulid_from_uuid_text};
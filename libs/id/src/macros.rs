@@ -8,6 +8,7 @@
 /// - `parse()` to parse from string
 /// - `Display` and `FromStr` implementations
 /// - `Serialize` and `Deserialize` implementations
+/// - `to_uuid()`/`try_from_uuid()` and the matching `From`/`TryFrom`
 /// - `Ord`, `Hash`, and other standard traits
 ///
 /// # Example
@@ -54,15 +55,31 @@ macro_rules! define_id {
                 self.0.timestamp_ms()
             }
 
+            /// Returns the ID as a UUID with the same 128 bits.
+            #[must_use]
+            pub fn to_uuid(&self) -> $crate::Uuid {
+                $crate::Uuid::from_bytes(self.0.to_bytes())
+            }
+
+            /// Creates an ID from a UUID with the same 128 bits, such as one
+            /// from [`Self::to_uuid`] or a UUIDv7. The nil UUID is rejected.
+            pub fn try_from_uuid(uuid: $crate::Uuid) -> Result<Self, $crate::IdError> {
+                $crate::ulid_from_uuid(uuid).map(Self)
+            }
+
             /// Parses an ID from a string.
             ///
-            /// The string must be in the format `{prefix}_{ulid}`.
+            /// The string must be in the format `{prefix}_{ulid}`. With the
+            /// `uuid-strings` feature, bare UUID text is accepted too.
             pub fn parse(s: &str) -> Result<Self, $crate::IdError> {
                 if s.is_empty() {
                     return Err($crate::IdError::Empty);
                 }
 
                 let Some((prefix, ulid_str)) = s.split_once('_') else {
+                    if let Some(ulid) = $crate::ulid_from_uuid_text(s) {
+                        return ulid.map(Self);
+                    }
                     return Err($crate::IdError::MissingSeparator);
                 };
 
@@ -126,6 +143,20 @@ macro_rules! define_id {
             }
         }
 
+        impl From<$name> for $crate::Uuid {
+            fn from(id: $name) -> Self {
+                id.to_uuid()
+            }
+        }
+
+        impl TryFrom<$crate::Uuid> for $name {
+            type Error = $crate::IdError;
+
+            fn try_from(uuid: $crate::Uuid) -> Result<Self, Self::Error> {
+                Self::try_from_uuid(uuid)
+            }
+        }
+
         impl AsRef<$crate::Ulid> for $name {
             fn as_ref(&self) -> &$crate::Ulid {
                 &self.0
@@ -13,6 +13,7 @@ define_id!(OrgId, "org");
 define_id!(ProjectId, "prj");
 define_id!(MemberId, "mem");
 define_id!(ServicePrincipalId, "sp");
+define_id!(TokenId, "tok");
 
 // =============================================================================
 // Application Model
@@ -22,6 +23,7 @@ define_id!(AppId, "app");
 define_id!(EnvId, "env");
 define_id!(ReleaseId, "rel");
 define_id!(DeployId, "dep");
+define_id!(BuildId, "build");
 
 // =============================================================================
 // Runtime and Instances
@@ -31,6 +33,7 @@ define_id!(InstanceId, "inst");
 define_id!(BootId, "boot");
 define_id!(NodeId, "node");
 define_id!(AssignmentId, "asgn");
+define_id!(PlanId, "plan");
 
 // =============================================================================
 // Networking
@@ -38,6 +41,7 @@ define_id!(AssignmentId, "asgn");
 
 define_id!(RouteId, "rt");
 define_id!(EndpointId, "ep");
+define_id!(CertificateId, "cert");
 
 // =============================================================================
 // Storage
@@ -62,6 +66,13 @@ define_id!(SecretVersionId, "sv");
 define_id!(ExecSessionId, "exec");
 define_id!(RequestId, "req");
 
+// =============================================================================
+// Integrations and Background Work
+// =============================================================================
+
+define_id!(WebhookId, "whk");
+define_id!(JobId, "job");
+
 // =============================================================================
 // Events
 // =============================================================================
@@ -289,6 +300,95 @@ mod tests {
         assert_eq!(next.value(), 2);
     }
 
+    fn assert_roundtrips<T>(id: T, prefix: &str)
+    where
+        T: std::fmt::Display
+            + std::str::FromStr
+            + serde::Serialize
+            + serde::de::DeserializeOwned
+            + PartialEq
+            + std::fmt::Debug,
+        <T as std::str::FromStr>::Err: std::fmt::Debug,
+    {
+        let s = id.to_string();
+        assert!(s.starts_with(&format!("{prefix}_")), "{s} lacks {prefix}_");
+        assert_eq!(s.parse::<T>().unwrap(), id);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{s}\""));
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), id);
+    }
+
+    #[test]
+    fn test_platform_id_roundtrips() {
+        assert_roundtrips(TokenId::new(), "tok");
+        assert_roundtrips(WebhookId::new(), "whk");
+        assert_roundtrips(JobId::new(), "job");
+        assert_roundtrips(CertificateId::new(), "cert");
+        assert_roundtrips(PlanId::new(), "plan");
+        assert_roundtrips(BuildId::new(), "build");
+    }
+
+    #[test]
+    fn test_platform_ids_reject_other_prefixes() {
+        let ulid = "01HV4Z2WQXKJNM8GPQY6VBKC3D";
+        assert!(format!("tok_{ulid}").parse::<WebhookId>().is_err());
+        assert!(format!("job_{ulid}").parse::<BuildId>().is_err());
+        assert!(format!("cert_{ulid}").parse::<PlanId>().is_err());
+        assert!(format!("plan_{ulid}").parse::<JobId>().is_err());
+        assert!(format!("build_{ulid}").parse::<TokenId>().is_err());
+        assert!(format!("whk_{ulid}").parse::<CertificateId>().is_err());
+    }
+
+    #[test]
+    fn test_uuid_roundtrip() {
+        let id = AppId::new();
+        let uuid = id.to_uuid();
+        assert_eq!(uuid.as_bytes(), &id.ulid().to_bytes());
+        assert_eq!(AppId::try_from_uuid(uuid).unwrap(), id);
+        assert_eq!(AppId::try_from(crate::Uuid::from(id)).unwrap(), id);
+    }
+
+    #[test]
+    fn test_uuid_v7_keeps_timestamp() {
+        let uuid = crate::Uuid::now_v7();
+        let (secs, nanos) = uuid.get_timestamp().unwrap().to_unix();
+        let id = VolumeId::try_from_uuid(uuid).unwrap();
+        assert_eq!(
+            id.timestamp_ms(),
+            secs * 1000 + u64::from(nanos) / 1_000_000
+        );
+        assert_eq!(id.to_uuid(), uuid);
+    }
+
+    #[test]
+    fn test_nil_uuid_rejected() {
+        let result = OrgId::try_from_uuid(crate::Uuid::nil());
+        assert!(matches!(result, Err(crate::IdError::InvalidUuid(_))));
+    }
+
+    #[cfg(feature = "uuid-strings")]
+    #[test]
+    fn test_parse_accepts_uuid_text() {
+        let uuid = crate::Uuid::now_v7();
+        let id: EnvId = uuid.to_string().parse().unwrap();
+        assert_eq!(id.to_uuid(), uuid);
+        let id: EnvId = serde_json::from_str(&format!("\"{}\"", uuid.simple())).unwrap();
+        assert_eq!(id.to_uuid(), uuid);
+        // Still printed in the prefixed form.
+        assert!(id.to_string().starts_with("env_"));
+    }
+
+    #[cfg(not(feature = "uuid-strings"))]
+    #[test]
+    fn test_parse_rejects_uuid_text() {
+        let result: Result<EnvId, _> = crate::Uuid::now_v7().to_string().parse();
+        assert!(matches!(
+            result.unwrap_err(),
+            crate::IdError::MissingSeparator
+        ));
+    }
+
     #[test]
     fn test_all_id_prefixes_unique() {
         // Ensure all prefixes are unique
@@ -297,16 +397,20 @@ mod tests {
             ProjectId::PREFIX,
             MemberId::PREFIX,
             ServicePrincipalId::PREFIX,
+            TokenId::PREFIX,
             AppId::PREFIX,
             EnvId::PREFIX,
             ReleaseId::PREFIX,
             DeployId::PREFIX,
+            BuildId::PREFIX,
             InstanceId::PREFIX,
             BootId::PREFIX,
             NodeId::PREFIX,
             AssignmentId::PREFIX,
+            PlanId::PREFIX,
             RouteId::PREFIX,
             EndpointId::PREFIX,
+            CertificateId::PREFIX,
             VolumeId::PREFIX,
             VolumeAttachmentId::PREFIX,
             SnapshotId::PREFIX,
@@ -315,6 +419,8 @@ mod tests {
             SecretVersionId::PREFIX,
             ExecSessionId::PREFIX,
             RequestId::PREFIX,
+            WebhookId::PREFIX,
+            JobId::PREFIX,
         ];
 
         let unique: std::collections::HashSet<_> = prefixes.iter().collect();
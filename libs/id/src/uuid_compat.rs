@@ -0,0 +1,24 @@
+//! Conversions between ID ULIDs and UUIDs, used by `define_id!`.
+
+use ulid::Ulid;
+use uuid::Uuid;
+
+use crate::IdError;
+
+/// The ULID with the same bits as `uuid`. The nil UUID is refused: external
+/// systems use it for "no value", and no generated ID is nil.
+pub fn ulid_from_uuid(uuid: Uuid) -> Result<Ulid, IdError> {
+    if uuid.is_nil() {
+        return Err(IdError::InvalidUuid("nil UUID".to_string()));
+    }
+    Ok(Ulid::from_bytes(uuid.into_bytes()))
+}
+
+/// The ULID for bare UUID text, or `None` if UUID strings are not accepted
+/// (the `uuid-strings` feature is off) or `s` is not UUID text.
+pub fn ulid_from_uuid_text(s: &str) -> Option<Result<Ulid, IdError>> {
+    if !cfg!(feature = "uuid-strings") {
+        return None;
+    }
+    Uuid::try_parse(s).ok().map(ulid_from_uuid)
+}
@@ -0,0 +1,323 @@
+//! CA key material, certificate issuance, and CRL generation.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, CertificateRevocationListParams,
+    CertificateSigningRequestParams, DistinguishedName, DnType, IsCa, KeyIdMethod, KeyPair,
+    KeyUsagePurpose, RevocationReason, RevokedCertParams, SanType, SerialNumber,
+};
+
+use crate::{fingerprint_sha256, to_offset, Identity, PkiError};
+
+/// Certificates are backdated slightly to tolerate clock skew.
+const CLOCK_SKEW_ALLOWANCE_SECS: i64 = 60;
+
+const SERIAL_BYTES: usize = 16;
+
+/// A signing CA: its certificate and private key.
+pub struct CertificateAuthority {
+    cert: Certificate,
+    key: KeyPair,
+    cert_pem: String,
+    not_after: DateTime<Utc>,
+}
+
+/// A certificate issued by the CA.
+#[derive(Debug, Clone)]
+pub struct IssuedCertificate {
+    /// Serial number, lowercase hex.
+    pub serial: String,
+    pub cert_pem: String,
+    /// Leaf followed by the issuing CA.
+    pub chain_pem: String,
+    pub fingerprint_sha256: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+/// A revoked certificate, as listed in the CRL.
+#[derive(Debug, Clone)]
+pub struct Revocation {
+    /// Serial number, lowercase hex.
+    pub serial: String,
+    pub revoked_at: DateTime<Utc>,
+    /// Whether the revocation is due to key compromise (otherwise reported
+    /// as superseded).
+    pub key_compromise: bool,
+}
+
+impl std::fmt::Debug for CertificateAuthority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertificateAuthority")
+            .field("not_after", &self.not_after)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CertificateAuthority {
+    /// Generate a new self-signed root CA (ECDSA P-256).
+    pub fn generate(common_name: &str, validity: Duration) -> Result<Self, PkiError> {
+        let key = KeyPair::generate()?;
+        let now = Utc::now();
+        let not_after = now + validity;
+
+        let mut params = CertificateParams::default();
+        let mut dn = DistinguishedName::new();
+        dn.push(DnType::CommonName, common_name);
+        dn.push(DnType::OrganizationName, "plfm-vt");
+        params.distinguished_name = dn;
+        params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+        params.key_usages = vec![
+            KeyUsagePurpose::KeyCertSign,
+            KeyUsagePurpose::CrlSign,
+            KeyUsagePurpose::DigitalSignature,
+        ];
+        params.not_before = to_offset(now - Duration::seconds(CLOCK_SKEW_ALLOWANCE_SECS))?;
+        params.not_after = to_offset(not_after)?;
+        params.serial_number = Some(random_serial().1);
+        params.key_identifier_method = KeyIdMethod::Sha256;
+
+        let cert = params.self_signed(&key)?;
+        let cert_pem = cert.pem();
+        Ok(Self {
+            cert,
+            key,
+            cert_pem,
+            not_after,
+        })
+    }
+
+    /// Load a CA from its stored certificate and private key.
+    pub fn from_pem(cert_pem: &str, key_pem: &str) -> Result<Self, PkiError> {
+        let key = KeyPair::from_pem(key_pem).map_err(|e| PkiError::InvalidPem(e.to_string()))?;
+        let params = CertificateParams::from_ca_cert_pem(cert_pem)
+            .map_err(|e| PkiError::InvalidPem(e.to_string()))?;
+        let not_after = DateTime::from_timestamp(params.not_after.unix_timestamp(), 0)
+            .ok_or_else(|| PkiError::InvalidPem("CA expiry out of range".to_string()))?;
+
+        // Re-signing yields a certificate object with the same subject and
+        // key, which is all issuance needs; the stored PEM stays canonical.
+        let cert = params.self_signed(&key)?;
+        Ok(Self {
+            cert,
+            key,
+            cert_pem: cert_pem.to_string(),
+            not_after,
+        })
+    }
+
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    /// The CA private key, PKCS#8 PEM. Store encrypted.
+    pub fn key_pem(&self) -> String {
+        self.key.serialize_pem()
+    }
+
+    pub fn not_after(&self) -> DateTime<Utc> {
+        self.not_after
+    }
+
+    /// Issue a certificate for `identity` over the public key in `csr_pem`.
+    ///
+    /// Only the CSR's key is used: subject, SANs, lifetime, and usages come
+    /// from the identity and `ttl`. The lifetime is clipped to the CA's own.
+    pub fn issue(
+        &self,
+        csr_pem: &str,
+        identity: &Identity,
+        trust_domain: &str,
+        ttl: Duration,
+    ) -> Result<IssuedCertificate, PkiError> {
+        identity.validate()?;
+        let mut csr = CertificateSigningRequestParams::from_pem(csr_pem)
+            .map_err(|e| PkiError::InvalidCsr(e.to_string()))?;
+
+        let now = Utc::now();
+        let not_before = now - Duration::seconds(CLOCK_SKEW_ALLOWANCE_SECS);
+        let not_after = (now + ttl).min(self.not_after);
+        let (serial, serial_number) = random_serial();
+
+        let mut params = CertificateParams::default();
+        let mut dn = DistinguishedName::new();
+        dn.push(DnType::CommonName, identity.common_name.as_str());
+        params.distinguished_name = dn;
+        params.subject_alt_names = std::iter::once(identity.uri(trust_domain))
+            .map(|uri| san(uri, SanType::URI))
+            .chain(
+                identity
+                    .dns_names
+                    .iter()
+                    .map(|name| san(name.clone(), SanType::DnsName)),
+            )
+            .collect::<Result<_, _>>()?;
+        params.is_ca = IsCa::ExplicitNoCa;
+        params.key_usages = vec![
+            KeyUsagePurpose::DigitalSignature,
+            KeyUsagePurpose::KeyEncipherment,
+        ];
+        params.extended_key_usages = identity.kind.extended_key_usages();
+        params.not_before = to_offset(not_before)?;
+        params.not_after = to_offset(not_after)?;
+        params.serial_number = Some(serial_number);
+        params.use_authority_key_identifier_extension = true;
+        params.key_identifier_method = KeyIdMethod::Sha256;
+        csr.params = params;
+
+        let cert = csr.signed_by(&self.cert, &self.key)?;
+        let cert_pem = cert.pem();
+        Ok(IssuedCertificate {
+            serial,
+            chain_pem: format!("{}{}", cert_pem, self.cert_pem),
+            cert_pem,
+            fingerprint_sha256: fingerprint_sha256(cert.der()),
+            not_before,
+            not_after,
+        })
+    }
+
+    /// Sign a CRL listing `revoked`, valid until `next_update`.
+    pub fn crl(
+        &self,
+        revoked: &[Revocation],
+        crl_number: u64,
+        next_update: DateTime<Utc>,
+    ) -> Result<String, PkiError> {
+        let revoked_certs = revoked
+            .iter()
+            .map(|r| {
+                let serial = hex::decode(&r.serial)
+                    .map_err(|_| PkiError::InvalidPem(format!("invalid serial '{}'", r.serial)))?;
+                Ok(RevokedCertParams {
+                    serial_number: SerialNumber::from_slice(&serial),
+                    revocation_time: to_offset(r.revoked_at)?,
+                    reason_code: Some(if r.key_compromise {
+                        RevocationReason::KeyCompromise
+                    } else {
+                        RevocationReason::Superseded
+                    }),
+                    invalidity_date: None,
+                })
+            })
+            .collect::<Result<Vec<_>, PkiError>>()?;
+
+        let params = CertificateRevocationListParams {
+            this_update: to_offset(Utc::now())?,
+            next_update: to_offset(next_update)?,
+            crl_number: SerialNumber::from(crl_number),
+            issuing_distribution_point: None,
+            revoked_certs,
+            key_identifier_method: KeyIdMethod::Sha256,
+        };
+        Ok(params.signed_by(&self.cert, &self.key)?.pem()?)
+    }
+}
+
+fn san(value: String, ctor: fn(rcgen::Ia5String) -> SanType) -> Result<SanType, PkiError> {
+    rcgen::Ia5String::try_from(value.clone())
+        .map(ctor)
+        .map_err(|_| PkiError::InvalidIdentity(format!("'{value}' is not a valid SAN")))
+}
+
+/// A random positive serial number, as (hex, encoded).
+fn random_serial() -> (String, SerialNumber) {
+    let mut bytes = [0u8; SERIAL_BYTES];
+    rand::rng().fill_bytes(&mut bytes);
+    // Keep the DER INTEGER positive without a leading zero byte.
+    bytes[0] = (bytes[0] & 0x7f) | 0x01;
+    (hex::encode(bytes), SerialNumber::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IdentityKind, DEFAULT_TRUST_DOMAIN};
+
+    fn csr() -> String {
+        let key = KeyPair::generate().unwrap();
+        CertificateParams::new(vec![])
+            .unwrap()
+            .serialize_request(&key)
+            .unwrap()
+            .pem()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_issue_certificate() {
+        let ca = CertificateAuthority::generate("plfm-vt test CA", Duration::days(30)).unwrap();
+        let identity = Identity::new(IdentityKind::Node, "node_01HXYZ")
+            .with_common_name("node-01.example.com")
+            .with_dns_names(vec!["node-01.example.com".to_string()]);
+
+        let issued = ca
+            .issue(&csr(), &identity, DEFAULT_TRUST_DOMAIN, Duration::hours(24))
+            .unwrap();
+        assert_eq!(issued.serial.len(), SERIAL_BYTES * 2);
+        assert!(issued.chain_pem.starts_with(&issued.cert_pem));
+        assert!(issued.chain_pem.ends_with(ca.cert_pem()));
+        assert_eq!(issued.fingerprint_sha256.len(), 64);
+        assert!(issued.not_after <= Utc::now() + Duration::hours(24));
+
+        let params = CertificateParams::from_ca_cert_pem(&issued.cert_pem).unwrap();
+        assert!(params.subject_alt_names.contains(&SanType::URI(
+            "spiffe://plfm.internal/node/node_01HXYZ"
+                .try_into()
+                .unwrap()
+        )));
+        assert_eq!(params.is_ca, IsCa::ExplicitNoCa);
+    }
+
+    #[test]
+    fn test_issue_clips_to_ca_lifetime() {
+        let ca = CertificateAuthority::generate("short CA", Duration::hours(2)).unwrap();
+        let identity = Identity::new(IdentityKind::Ingress, "ingress-1");
+        let issued = ca
+            .issue(&csr(), &identity, DEFAULT_TRUST_DOMAIN, Duration::days(7))
+            .unwrap();
+        assert!(issued.not_after <= ca.not_after());
+    }
+
+    #[test]
+    fn test_issue_rejects_invalid_csr() {
+        let ca = CertificateAuthority::generate("test CA", Duration::days(1)).unwrap();
+        let identity = Identity::new(IdentityKind::Instance, "inst_1");
+        let err = ca
+            .issue(
+                "not a csr",
+                &identity,
+                DEFAULT_TRUST_DOMAIN,
+                Duration::hours(1),
+            )
+            .unwrap_err();
+        assert!(matches!(err, PkiError::InvalidCsr(_)));
+    }
+
+    #[test]
+    fn test_round_trip_and_crl() {
+        let ca = CertificateAuthority::generate("test CA", Duration::days(1)).unwrap();
+        let loaded = CertificateAuthority::from_pem(ca.cert_pem(), &ca.key_pem()).unwrap();
+        assert_eq!(loaded.cert_pem(), ca.cert_pem());
+        assert_eq!(loaded.not_after().timestamp(), ca.not_after().timestamp());
+
+        let identity = Identity::new(IdentityKind::Instance, "inst_1");
+        let issued = loaded
+            .issue(&csr(), &identity, DEFAULT_TRUST_DOMAIN, Duration::hours(1))
+            .unwrap();
+
+        let crl = loaded
+            .crl(
+                &[Revocation {
+                    serial: issued.serial,
+                    revoked_at: Utc::now(),
+                    key_compromise: true,
+                }],
+                1,
+                Utc::now() + Duration::hours(1),
+            )
+            .unwrap();
+        assert!(crl.starts_with("-----BEGIN X509 CRL-----"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Platform identities and their certificate profiles.
+
+use std::fmt;
+
+use chrono::Duration;
+use rcgen::ExtendedKeyUsagePurpose;
+
+use crate::PkiError;
+
+/// Trust domain used in identity URIs unless configured otherwise.
+pub const DEFAULT_TRUST_DOMAIN: &str = "plfm.internal";
+
+/// Kind of workload or component a certificate identifies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdentityKind {
+    /// A node agent.
+    Node,
+    /// An ingress instance.
+    Ingress,
+    /// A workload instance (microVM).
+    Instance,
+}
+
+impl IdentityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentityKind::Node => "node",
+            IdentityKind::Ingress => "ingress",
+            IdentityKind::Instance => "instance",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "node" => Some(IdentityKind::Node),
+            "ingress" => Some(IdentityKind::Ingress),
+            "instance" => Some(IdentityKind::Instance),
+            _ => None,
+        }
+    }
+
+    /// Lifetime of a certificate when the requester does not ask for one.
+    ///
+    /// Instances renew through their node agent, so their certificates are
+    /// the shortest lived.
+    pub fn default_ttl(self) -> Duration {
+        match self {
+            IdentityKind::Node | IdentityKind::Ingress => Duration::hours(24),
+            IdentityKind::Instance => Duration::hours(1),
+        }
+    }
+
+    /// Longest lifetime a certificate of this kind may have.
+    pub fn max_ttl(self) -> Duration {
+        match self {
+            IdentityKind::Node | IdentityKind::Ingress => Duration::days(7),
+            IdentityKind::Instance => Duration::hours(24),
+        }
+    }
+
+    /// Resolve a requested TTL against this kind's bounds.
+    pub fn ttl(self, requested: Option<Duration>) -> Result<Duration, PkiError> {
+        let ttl = requested.unwrap_or_else(|| self.default_ttl());
+        let max = self.max_ttl();
+        if ttl < Duration::minutes(1) || ttl > max {
+            return Err(PkiError::InvalidTtl {
+                requested_secs: ttl.num_seconds(),
+                max_secs: max.num_seconds(),
+            });
+        }
+        Ok(ttl)
+    }
+
+    pub(crate) fn extended_key_usages(self) -> Vec<ExtendedKeyUsagePurpose> {
+        // Every platform identity both serves and dials mTLS.
+        vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ]
+    }
+}
+
+impl fmt::Display for IdentityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The identity a certificate is issued to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub kind: IdentityKind,
+    /// Platform ID of the subject (node ID, ingress name, instance ID).
+    pub id: String,
+    /// Subject common name. Node certificates use the enrolled mTLS subject
+    /// so control-plane subject pinning matches.
+    pub common_name: String,
+    /// Additional DNS SANs.
+    pub dns_names: Vec<String>,
+}
+
+impl Identity {
+    pub fn new(kind: IdentityKind, id: impl Into<String>) -> Self {
+        let id = id.into();
+        Self {
+            kind,
+            common_name: id.clone(),
+            id,
+            dns_names: Vec::new(),
+        }
+    }
+
+    pub fn with_common_name(mut self, common_name: impl Into<String>) -> Self {
+        self.common_name = common_name.into();
+        self
+    }
+
+    pub fn with_dns_names(mut self, dns_names: Vec<String>) -> Self {
+        self.dns_names = dns_names;
+        self
+    }
+
+    /// SPIFFE-style identity URI, e.g. `spiffe://plfm.internal/node/node_01...`.
+    pub fn uri(&self, trust_domain: &str) -> String {
+        format!("spiffe://{}/{}/{}", trust_domain, self.kind, self.id)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), PkiError> {
+        let valid_segment = |s: &str| {
+            !s.is_empty()
+                && s.len() <= 128
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        };
+        if !valid_segment(&self.id) {
+            return Err(PkiError::InvalidIdentity(format!(
+                "id '{}' must be 1-128 characters of [A-Za-z0-9_.-]",
+                self.id
+            )));
+        }
+        if self.common_name.is_empty() || self.common_name.len() > 64 {
+            return Err(PkiError::InvalidIdentity(
+                "common name must be 1-64 characters".to_string(),
+            ));
+        }
+        if let Some(name) = self
+            .dns_names
+            .iter()
+            .find(|n| !n.is_ascii() || n.is_empty())
+        {
+            return Err(PkiError::InvalidIdentity(format!(
+                "invalid DNS name '{name}'"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_kind_round_trip() {
+        for kind in [
+            IdentityKind::Node,
+            IdentityKind::Ingress,
+            IdentityKind::Instance,
+        ] {
+            assert_eq!(IdentityKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(IdentityKind::parse("user"), None);
+    }
+
+    #[test]
+    fn test_ttl_bounds() {
+        assert_eq!(
+            IdentityKind::Instance.ttl(None).unwrap(),
+            Duration::hours(1)
+        );
+        assert!(IdentityKind::Node.ttl(Some(Duration::days(7))).is_ok());
+        assert!(IdentityKind::Node.ttl(Some(Duration::days(8))).is_err());
+        assert!(IdentityKind::Instance
+            .ttl(Some(Duration::seconds(30)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_identity_uri_and_validation() {
+        let identity = Identity::new(IdentityKind::Node, "node_01HXYZ");
+        assert_eq!(
+            identity.uri(DEFAULT_TRUST_DOMAIN),
+            "spiffe://plfm.internal/node/node_01HXYZ"
+        );
+        assert!(identity.validate().is_ok());
+
+        assert!(Identity::new(IdentityKind::Node, "a/b").validate().is_err());
+        assert!(Identity::new(IdentityKind::Node, "").validate().is_err());
+    }
+}
@@ -0,0 +1,341 @@
+//! Instance identity tokens: short-lived JWTs (ES256) naming an instance.
+//!
+//! The token's `sub` is the instance's SPIFFE-style URI, the same one its
+//! certificate carries. Verifiers check the signature against the published
+//! JWKS and the `iss`, `aud`, `nbf` and `exp` claims.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{
+    EcdsaKeyPair, KeyPair, UnparsedPublicKey, ECDSA_P256_SHA256_FIXED,
+    ECDSA_P256_SHA256_FIXED_SIGNING,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::PkiError;
+
+/// JWS algorithm of identity tokens.
+pub const IDENTITY_TOKEN_ALG: &str = "ES256";
+
+/// Allowed clock skew when checking `nbf` and `exp`.
+const CLOCK_SKEW_ALLOWANCE_SECS: i64 = 60;
+
+/// Length of an uncompressed P-256 point (`0x04 || x || y`).
+const P256_POINT_LEN: usize = 65;
+
+/// Claims of an instance identity token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityClaims {
+    pub iss: String,
+    /// Instance identity URI (`spiffe://<trust-domain>/instance/<id>`).
+    pub sub: String,
+    pub aud: String,
+    pub iat: i64,
+    pub nbf: i64,
+    pub exp: i64,
+    pub jti: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub process_type: String,
+    pub instance_id: String,
+    pub node_id: String,
+}
+
+/// A public signing key in JWK form (EC P-256).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub alg: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub kid: String,
+    pub x: String,
+    pub y: String,
+}
+
+impl Jwk {
+    fn from_point(kid: &str, point: &[u8]) -> Self {
+        Self {
+            kty: "EC".to_string(),
+            crv: "P-256".to_string(),
+            alg: IDENTITY_TOKEN_ALG.to_string(),
+            key_use: "sig".to_string(),
+            kid: kid.to_string(),
+            x: URL_SAFE_NO_PAD.encode(&point[1..33]),
+            y: URL_SAFE_NO_PAD.encode(&point[33..]),
+        }
+    }
+
+    fn point(&self) -> Result<Vec<u8>, PkiError> {
+        if self.kty != "EC" || self.crv != "P-256" {
+            return Err(PkiError::InvalidKey(format!(
+                "unsupported key type {} {}",
+                self.kty, self.crv
+            )));
+        }
+        let decode = |coord: &str| {
+            URL_SAFE_NO_PAD
+                .decode(coord)
+                .ok()
+                .filter(|bytes| bytes.len() == 32)
+                .ok_or_else(|| PkiError::InvalidKey("invalid EC coordinate".to_string()))
+        };
+        let mut point = Vec::with_capacity(P256_POINT_LEN);
+        point.push(0x04);
+        point.extend(decode(&self.x)?);
+        point.extend(decode(&self.y)?);
+        Ok(point)
+    }
+}
+
+/// An identity token signing key (ECDSA P-256).
+pub struct IdentityTokenKey {
+    kid: String,
+    pkcs8: Vec<u8>,
+    key: EcdsaKeyPair,
+}
+
+impl std::fmt::Debug for IdentityTokenKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentityTokenKey")
+            .field("kid", &self.kid)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdentityTokenKey {
+    /// Generate a new signing key.
+    pub fn generate(kid: impl Into<String>) -> Result<Self, PkiError> {
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+                .map_err(|_| PkiError::InvalidKey("key generation failed".to_string()))?;
+        Self::from_pkcs8(kid, pkcs8.as_ref())
+    }
+
+    /// Load a key from its PKCS#8 DER encoding.
+    pub fn from_pkcs8(kid: impl Into<String>, pkcs8: &[u8]) -> Result<Self, PkiError> {
+        let key = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            pkcs8,
+            &SystemRandom::new(),
+        )
+        .map_err(|e| PkiError::InvalidKey(e.to_string()))?;
+        Ok(Self {
+            kid: kid.into(),
+            pkcs8: pkcs8.to_vec(),
+            key,
+        })
+    }
+
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    /// PKCS#8 DER encoding of the private key.
+    pub fn pkcs8_der(&self) -> &[u8] {
+        &self.pkcs8
+    }
+
+    pub fn public_jwk(&self) -> Jwk {
+        Jwk::from_point(&self.kid, self.key.public_key().as_ref())
+    }
+
+    /// Sign `claims` as a compact JWS.
+    pub fn sign(&self, claims: &IdentityClaims) -> Result<String, PkiError> {
+        self.sign_jws("JWT", claims)
+    }
+
+    /// Sign any JSON payload as a compact JWS whose header carries `typ`.
+    /// Other control-plane statements (e.g. event log checkpoints) are
+    /// signed this way so the same JWKS verifies them.
+    pub fn sign_jws<T: Serialize>(&self, typ: &str, payload: &T) -> Result<String, PkiError> {
+        let header = serde_json::json!({
+            "alg": IDENTITY_TOKEN_ALG,
+            "typ": typ,
+            "kid": self.kid,
+        });
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(payload).expect("payload serializes"))
+        );
+        let signature = self
+            .key
+            .sign(&SystemRandom::new(), signing_input.as_bytes())
+            .map_err(|_| PkiError::InvalidKey("signing failed".to_string()))?;
+        Ok(format!(
+            "{signing_input}.{}",
+            URL_SAFE_NO_PAD.encode(signature.as_ref())
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    kid: String,
+}
+
+fn invalid(message: &str) -> PkiError {
+    PkiError::InvalidToken(message.to_string())
+}
+
+fn split(token: &str) -> Result<(&str, &str, &str), PkiError> {
+    let mut parts = token.split('.');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(header), Some(claims), Some(signature), None) => Ok((header, claims, signature)),
+        _ => Err(invalid("not a compact JWS")),
+    }
+}
+
+fn decode_json<T: DeserializeOwned>(part: &str, what: &str) -> Result<T, PkiError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| invalid(&format!("{what} is not base64url")))?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid(&format!("malformed {what}")))
+}
+
+/// Key ID named in a token's header, used to pick the verification key.
+pub fn token_key_id(token: &str) -> Result<String, PkiError> {
+    let (header, _, _) = split(token)?;
+    Ok(decode_json::<Header>(header, "header")?.kid)
+}
+
+/// Verify a token against the trusted keys and check its standard claims.
+pub fn verify_identity_token(
+    token: &str,
+    keys: &[Jwk],
+    issuer: &str,
+    audience: &str,
+    now: DateTime<Utc>,
+) -> Result<IdentityClaims, PkiError> {
+    let claims: IdentityClaims = verify_jws(token, keys)?;
+    if claims.iss != issuer {
+        return Err(invalid("wrong issuer"));
+    }
+    if claims.aud != audience {
+        return Err(invalid("wrong audience"));
+    }
+    let now = now.timestamp();
+    if claims.nbf > now + CLOCK_SKEW_ALLOWANCE_SECS {
+        return Err(invalid("not yet valid"));
+    }
+    if claims.exp <= now - CLOCK_SKEW_ALLOWANCE_SECS {
+        return Err(invalid("expired"));
+    }
+    Ok(claims)
+}
+
+/// Verify a compact JWS signed by one of `keys` and decode its payload.
+/// Checks only the signature; callers check the payload.
+pub fn verify_jws<T: DeserializeOwned>(token: &str, keys: &[Jwk]) -> Result<T, PkiError> {
+    let (header_part, claims_part, signature_part) = split(token)?;
+    let header: Header = decode_json(header_part, "header")?;
+    if header.alg != IDENTITY_TOKEN_ALG {
+        return Err(invalid("unsupported algorithm"));
+    }
+    let key = keys
+        .iter()
+        .find(|key| key.kid == header.kid)
+        .ok_or_else(|| invalid("unknown signing key"))?;
+
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature_part)
+        .map_err(|_| invalid("signature is not base64url"))?;
+    let signing_input = &token[..header_part.len() + 1 + claims_part.len()];
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, key.point()?)
+        .verify(signing_input.as_bytes(), &signature)
+        .map_err(|_| invalid("bad signature"))?;
+
+    decode_json(claims_part, "claims")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn claims(now: DateTime<Utc>) -> IdentityClaims {
+        IdentityClaims {
+            iss: "spiffe://plfm.internal".to_string(),
+            sub: "spiffe://plfm.internal/instance/inst_1".to_string(),
+            aud: "plfm.internal".to_string(),
+            iat: now.timestamp(),
+            nbf: now.timestamp(),
+            exp: (now + Duration::minutes(15)).timestamp(),
+            jti: "jti_1".to_string(),
+            org_id: "org_1".to_string(),
+            app_id: "app_1".to_string(),
+            env_id: "env_1".to_string(),
+            process_type: "web".to_string(),
+            instance_id: "inst_1".to_string(),
+            node_id: "node_1".to_string(),
+        }
+    }
+
+    fn verify(token: &str, keys: &[Jwk], now: DateTime<Utc>) -> Result<IdentityClaims, PkiError> {
+        verify_identity_token(token, keys, "spiffe://plfm.internal", "plfm.internal", now)
+    }
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let key = IdentityTokenKey::generate("itk_1").unwrap();
+        let loaded = IdentityTokenKey::from_pkcs8("itk_1", key.pkcs8_der()).unwrap();
+        let now = Utc::now();
+
+        let token = loaded.sign(&claims(now)).unwrap();
+        assert_eq!(token_key_id(&token).unwrap(), "itk_1");
+        let verified = verify(&token, &[key.public_jwk()], now).unwrap();
+        assert_eq!(verified, claims(now));
+    }
+
+    #[test]
+    fn test_verify_rejects_bad_tokens() {
+        let key = IdentityTokenKey::generate("itk_1").unwrap();
+        let other = IdentityTokenKey::generate("itk_1").unwrap();
+        let now = Utc::now();
+        let token = key.sign(&claims(now)).unwrap();
+
+        // Same kid, different key.
+        assert!(verify(&token, &[other.public_jwk()], now).is_err());
+        // Unknown kid.
+        let mut renamed = key.public_jwk();
+        renamed.kid = "itk_2".to_string();
+        assert!(verify(&token, &[renamed], now).is_err());
+        // Expired.
+        let later = now + Duration::minutes(20);
+        let err = verify(&token, &[key.public_jwk()], later).unwrap_err();
+        assert!(err.to_string().contains("expired"));
+        // Tampered claims.
+        let (header, _, signature) = split(&token).unwrap();
+        let mut forged = claims(now);
+        forged.instance_id = "inst_2".to_string();
+        let forged = format!(
+            "{header}.{}.{signature}",
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap())
+        );
+        assert!(verify(&forged, &[key.public_jwk()], now).is_err());
+        // Wrong audience.
+        let mut wrong_aud = claims(now);
+        wrong_aud.aud = "elsewhere".to_string();
+        let token = key.sign(&wrong_aud).unwrap();
+        assert!(verify(&token, &[key.public_jwk()], now).is_err());
+    }
+
+    #[test]
+    fn test_sign_jws_round_trip() {
+        let key = IdentityTokenKey::generate("itk_1").unwrap();
+        let payload = serde_json::json!({ "root": "ab12", "to_event_id": 42 });
+
+        let jws = key.sign_jws("plfm-checkpoint+jwt", &payload).unwrap();
+        let verified: serde_json::Value = verify_jws(&jws, &[key.public_jwk()]).unwrap();
+        assert_eq!(verified, payload);
+
+        let other = IdentityTokenKey::generate("itk_1").unwrap();
+        assert!(verify_jws::<serde_json::Value>(&jws, &[other.public_jwk()]).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Certificate authority for platform identities.
+//!
+//! The control plane runs a built-in CA that issues short-lived X.509
+//! certificates to:
+//! - node agents (mTLS to the control plane)
+//! - ingress (mTLS to backends and the control plane)
+//! - instances (workload identity)
+//!
+//! Certificates are issued from a PKCS#10 CSR, so private keys never leave
+//! the requester. Each certificate names its identity in a SPIFFE-style URI
+//! SAN (`spiffe://<trust-domain>/<kind>/<id>`).
+//!
+//! Revocation is primarily by short TTL; a CRL is also published for
+//! revocations that cannot wait for expiry. CA rotation keeps the previous
+//! CA in the trust bundle until the certificates it issued have expired.
+//!
+//! Instances also get short-lived signed JWTs naming the same identity
+//! ([`IdentityTokenKey`]), for callers that authenticate with bearer tokens
+//! rather than mTLS.
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use time::OffsetDateTime;
+
+mod ca;
+mod identity;
+mod identity_token;
+mod server_cert;
+
+pub use ca::{CertificateAuthority, IssuedCertificate, Revocation};
+pub use identity::{Identity, IdentityKind, DEFAULT_TRUST_DOMAIN};
+pub use identity_token::{
+    token_key_id, verify_identity_token, verify_jws, IdentityClaims, IdentityTokenKey, Jwk,
+    IDENTITY_TOKEN_ALG,
+};
+pub use server_cert::ServerCertificate;
+
+/// PKI errors.
+#[derive(Debug, Error)]
+pub enum PkiError {
+    /// The CSR could not be parsed or its signature is invalid.
+    #[error("invalid certificate signing request: {0}")]
+    InvalidCsr(String),
+
+    /// A stored certificate or key could not be parsed.
+    #[error("invalid PEM: {0}")]
+    InvalidPem(String),
+
+    /// A private key that does not belong to the certificate it came with.
+    #[error("private key does not match certificate")]
+    KeyMismatch,
+
+    /// Requested lifetime outside the identity's bounds.
+    #[error("invalid ttl: {requested_secs}s (must be between 60s and {max_secs}s)")]
+    InvalidTtl { requested_secs: i64, max_secs: i64 },
+
+    /// Identity fields that cannot be encoded in a certificate.
+    #[error("invalid identity: {0}")]
+    InvalidIdentity(String),
+
+    /// A token signing key that cannot be loaded or used.
+    #[error("invalid signing key: {0}")]
+    InvalidKey(String),
+
+    /// An identity token that does not verify.
+    #[error("invalid identity token: {0}")]
+    InvalidToken(String),
+
+    /// Certificate generation or signing failed.
+    #[error("certificate generation failed: {0}")]
+    Generation(#[from] rcgen::Error),
+}
+
+fn to_offset(ts: DateTime<Utc>) -> Result<OffsetDateTime, PkiError> {
+    OffsetDateTime::from_unix_timestamp(ts.timestamp())
+        .map_err(|e| PkiError::InvalidIdentity(format!("timestamp out of range: {e}")))
+}
+
+/// SHA-256 fingerprint of a DER-encoded certificate, lowercase hex.
+pub fn fingerprint_sha256(der: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(der))
+}
@@ -0,0 +1,194 @@
+//! Validation of user-supplied server certificates.
+//!
+//! Orgs may upload their own certificate chain and private key for a route
+//! hostname. Before the bundle is stored it must parse, cover the hostname,
+//! be currently valid, and the key must belong to the leaf certificate.
+
+use chrono::{DateTime, Utc};
+use rcgen::KeyPair;
+use x509_parser::extensions::GeneralName;
+use x509_parser::pem::Pem;
+
+use crate::{fingerprint_sha256, PkiError};
+
+/// Upper bound on certificates in an uploaded chain.
+const MAX_CHAIN_LEN: usize = 8;
+
+/// A parsed, self-consistent certificate chain and private key.
+#[derive(Clone)]
+pub struct ServerCertificate {
+    /// Leaf first, then intermediates, PEM.
+    pub chain_pem: String,
+    /// Private key for the leaf, PEM.
+    pub key_pem: String,
+    /// SHA-256 fingerprint of the leaf certificate, lowercase hex.
+    pub fingerprint_sha256: String,
+    /// DNS names from the leaf's subject alternative names.
+    pub dns_names: Vec<String>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+impl std::fmt::Debug for ServerCertificate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerCertificate")
+            .field("fingerprint_sha256", &self.fingerprint_sha256)
+            .field("dns_names", &self.dns_names)
+            .field("not_before", &self.not_before)
+            .field("not_after", &self.not_after)
+            .field("key_pem", &"<redacted>")
+            .finish()
+    }
+}
+
+impl ServerCertificate {
+    /// Parse a PEM chain (leaf first) and its private key.
+    ///
+    /// Fails if the chain is empty or malformed, or if the key does not
+    /// match the leaf. Hostname coverage and validity are checked
+    /// separately so callers can report them distinctly.
+    pub fn parse(chain_pem: &str, key_pem: &str) -> Result<Self, PkiError> {
+        let blocks = Pem::iter_from_buffer(chain_pem.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PkiError::InvalidPem(format!("certificate chain: {e}")))?;
+        // Anything else (notably a private key) must not end up stored in
+        // the plaintext chain column.
+        if let Some(other) = blocks.iter().find(|p| p.label != "CERTIFICATE") {
+            return Err(PkiError::InvalidPem(format!(
+                "certificate chain contains a {} block",
+                other.label
+            )));
+        }
+        let certs: Vec<&Pem> = blocks.iter().collect();
+        if certs.is_empty() {
+            return Err(PkiError::InvalidPem(
+                "certificate chain contains no CERTIFICATE blocks".to_string(),
+            ));
+        }
+        if certs.len() > MAX_CHAIN_LEN {
+            return Err(PkiError::InvalidPem(format!(
+                "certificate chain has {} certificates (max {MAX_CHAIN_LEN})",
+                certs.len()
+            )));
+        }
+        for pem in &certs[1..] {
+            pem.parse_x509()
+                .map_err(|e| PkiError::InvalidPem(format!("intermediate certificate: {e}")))?;
+        }
+
+        let leaf_pem = certs[0];
+        let leaf = leaf_pem
+            .parse_x509()
+            .map_err(|e| PkiError::InvalidPem(format!("leaf certificate: {e}")))?;
+
+        let key = KeyPair::from_pem(key_pem)
+            .map_err(|e| PkiError::InvalidPem(format!("private key: {e}")))?;
+        if key.public_key_raw() != leaf.public_key().subject_public_key.data.as_ref() {
+            return Err(PkiError::KeyMismatch);
+        }
+
+        let mut dns_names = Vec::new();
+        if let Ok(Some(san)) = leaf.subject_alternative_name() {
+            for name in &san.value.general_names {
+                if let GeneralName::DNSName(dns) = name {
+                    dns_names.push(normalize(dns));
+                }
+            }
+        }
+        // Legacy certificates without a SAN extension name the host in the CN.
+        if dns_names.is_empty() {
+            if let Some(cn) = leaf
+                .subject()
+                .iter_common_name()
+                .next()
+                .and_then(|cn| cn.as_str().ok())
+            {
+                dns_names.push(normalize(cn));
+            }
+        }
+
+        let validity = leaf.validity();
+        let not_before = DateTime::from_timestamp(validity.not_before.timestamp(), 0)
+            .ok_or_else(|| PkiError::InvalidPem("notBefore out of range".to_string()))?;
+        let not_after = DateTime::from_timestamp(validity.not_after.timestamp(), 0)
+            .ok_or_else(|| PkiError::InvalidPem("notAfter out of range".to_string()))?;
+
+        Ok(Self {
+            chain_pem: chain_pem.trim().to_string() + "\n",
+            key_pem: key_pem.to_string(),
+            fingerprint_sha256: fingerprint_sha256(&leaf_pem.contents),
+            dns_names,
+            not_before,
+            not_after,
+        })
+    }
+
+    /// Whether the leaf names `hostname`, directly or via a single-label
+    /// wildcard (`*.example.com` covers `a.example.com` only).
+    pub fn covers(&self, hostname: &str) -> bool {
+        let hostname = normalize(hostname);
+        self.dns_names
+            .iter()
+            .any(|name| match name.strip_prefix("*.") {
+                Some(suffix) => hostname
+                    .split_once('.')
+                    .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+                None => *name == hostname,
+            })
+    }
+
+    /// Whether `now` falls within the certificate's validity window.
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.not_before <= now && now < self.not_after
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.to_ascii_lowercase().trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertificateParams, KeyPair};
+
+    fn bundle(names: &[&str]) -> (String, String) {
+        let key = KeyPair::generate().unwrap();
+        let params =
+            CertificateParams::new(names.iter().map(|n| n.to_string()).collect::<Vec<_>>())
+                .unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+
+    #[test]
+    fn test_parse_and_cover() {
+        let (cert, key) = bundle(&["api.example.com", "*.apps.example.com"]);
+        let parsed = ServerCertificate::parse(&cert, &key).unwrap();
+        assert_eq!(parsed.fingerprint_sha256.len(), 64);
+        assert!(parsed.is_valid_at(Utc::now()));
+        assert!(parsed.covers("API.example.com."));
+        assert!(parsed.covers("web.apps.example.com"));
+        assert!(!parsed.covers("apps.example.com"));
+        assert!(!parsed.covers("a.b.apps.example.com"));
+        assert!(!parsed.covers("other.example.com"));
+        assert!(format!("{parsed:?}").contains("<redacted>"));
+    }
+
+    #[test]
+    fn test_rejects_mismatched_key() {
+        let (cert, _) = bundle(&["api.example.com"]);
+        let (_, other_key) = bundle(&["api.example.com"]);
+        let err = ServerCertificate::parse(&cert, &other_key).unwrap_err();
+        assert!(matches!(err, PkiError::KeyMismatch));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        let (_, key) = bundle(&["api.example.com"]);
+        assert!(matches!(
+            ServerCertificate::parse("not a cert", &key).unwrap_err(),
+            PkiError::InvalidPem(_)
+        ));
+    }
+}
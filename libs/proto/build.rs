@@ -1,5 +1,9 @@
-use std::io::Result;
-use std::path::PathBuf;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Result};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
 
 fn main() -> Result<()> {
     let proto_root = PathBuf::from("../../api/proto");
@@ -31,6 +35,7 @@ fn main() -> Result<()> {
         "plfm/events/v1/instance.proto",
         "plfm/events/v1/node.proto",
         "plfm/events/v1/exec.proto",
+        "plfm/events/v1/alert.proto",
         "plfm/agent/v1/workload.proto",
         "plfm/agent/v1/agent.proto",
         "plfm/agent/v1/runtime.proto",
@@ -55,5 +60,103 @@ fn main() -> Result<()> {
         );
     }
 
+    let catalog_path = PathBuf::from("../../api/errors/catalog.json");
+    generate_error_catalog(&catalog_path, Path::new("src/gen/plfm_error_catalog.rs"))?;
+    println!("cargo:rerun-if-changed={}", catalog_path.display());
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ErrorCatalog {
+    domains: Vec<ErrorDomain>,
+    errors: Vec<ErrorEntry>,
+}
+
+#[derive(Deserialize)]
+struct ErrorDomain {
+    name: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorEntry {
+    code: String,
+    domain: String,
+    status: u16,
+    retryable: bool,
+    description: String,
+    #[serde(default)]
+    hint: Option<String>,
+}
+
+/// Render api/errors/catalog.json as Rust constants (see `plfm_proto::errors`).
+///
+/// The output is only rewritten when it changes so the checked-in file stays
+/// stable across builds.
+fn generate_error_catalog(catalog_path: &Path, out_path: &Path) -> Result<()> {
+    let raw = fs::read_to_string(catalog_path)?;
+    let catalog: ErrorCatalog = serde_json::from_str(&raw).map_err(io::Error::other)?;
+
+    let mut out = String::new();
+    out.push_str(
+        "// This file is @generated by libs/proto/build.rs from api/errors/catalog.json.\n",
+    );
+    out.push_str("// Do not edit by hand.\n\n");
+
+    out.push_str("/// Error domains.\npub mod domains {\n");
+    for domain in &catalog.domains {
+        let _ = writeln!(out, "    /// {}", domain.description);
+        let _ = writeln!(
+            out,
+            "    pub const {}: &str = {:?};",
+            domain.name.to_uppercase(),
+            domain.name
+        );
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("/// Stable error codes.\npub mod codes {\n");
+    for error in &catalog.errors {
+        if !catalog.domains.iter().any(|d| d.name == error.domain) {
+            return Err(io::Error::other(format!(
+                "error code {} uses unknown domain {}",
+                error.code, error.domain
+            )));
+        }
+        let _ = writeln!(out, "    /// {}", error.description);
+        let _ = writeln!(
+            out,
+            "    pub const {}: &str = {:?};",
+            error.code.to_uppercase(),
+            error.code
+        );
+    }
+    out.push_str("}\n\n");
+
+    out.push_str(
+        "/// Every catalogued error, in catalog order.\npub const CATALOG: &[ErrorSpec] = &[\n",
+    );
+    for error in &catalog.errors {
+        let hint = match &error.hint {
+            Some(hint) => format!("Some({hint:?})"),
+            None => "None".to_string(),
+        };
+        let _ = write!(
+            out,
+            "    ErrorSpec {{\n        code: codes::{},\n        domain: domains::{},\n        status: {},\n        retryable: {},\n        description: {:?},\n        hint: {},\n    }},\n",
+            error.code.to_uppercase(),
+            error.domain.to_uppercase(),
+            error.status,
+            error.retryable,
+            error.description,
+            hint
+        );
+    }
+    out.push_str("];\n");
+
+    if fs::read_to_string(out_path).ok().as_deref() != Some(out.as_str()) {
+        fs::write(out_path, out)?;
+    }
     Ok(())
 }
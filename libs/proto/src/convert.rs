@@ -0,0 +1,631 @@
+//! Typed conversions between generated protobuf types and domain types.
+//!
+//! Domain enums convert into their protobuf counterparts infallibly. The
+//! reverse direction is fallible: `UNSPECIFIED` (and any protobuf value the
+//! domain does not model) is rejected instead of being silently mapped to a
+//! default. Identifier fields are parsed into typed `plfm-id` IDs.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use plfm_events as domain;
+use plfm_id::{EnvId, IdError, InstanceId, NodeId, OrgId};
+use thiserror::Error;
+
+use crate::common::v1 as common;
+use crate::events::v1 as events;
+
+/// Errors converting a protobuf value into a domain type.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    /// An enum field was left at its `UNSPECIFIED` zero value.
+    #[error("{0} is unspecified")]
+    Unspecified(&'static str),
+
+    /// An enum value has no domain equivalent.
+    #[error("{enum_name} value {value} is not supported")]
+    Unsupported {
+        enum_name: &'static str,
+        value: &'static str,
+    },
+
+    /// An enum field carried a number the protobuf schema does not define.
+    #[error("unknown enum value {0}")]
+    UnknownEnumValue(i32),
+
+    /// A required message field was absent.
+    #[error("missing field {0}")]
+    MissingField(&'static str),
+
+    /// An identifier field did not parse.
+    #[error("invalid {field}: {source}")]
+    InvalidId {
+        field: &'static str,
+        #[source]
+        source: IdError,
+    },
+
+    /// A timestamp field was out of range.
+    #[error("invalid timestamp in {0}")]
+    InvalidTimestamp(&'static str),
+}
+
+/// Decode a raw protobuf enum field into a domain enum.
+///
+/// `P` is the generated protobuf enum and `D` the domain enum, e.g.
+/// `decode_enum::<events::InstanceStatus, plfm_events::InstanceStatus>(raw)`.
+pub fn decode_enum<P, D>(value: i32) -> Result<D, ConversionError>
+where
+    P: TryFrom<i32, Error = prost::UnknownEnumValue>,
+    D: TryFrom<P, Error = ConversionError>,
+{
+    let proto = P::try_from(value).map_err(|_| ConversionError::UnknownEnumValue(value))?;
+    D::try_from(proto)
+}
+
+/// Parse an identifier field into a typed ID.
+pub fn parse_id<T>(field: &'static str, value: &str) -> Result<T, ConversionError>
+where
+    T: FromStr<Err = IdError>,
+{
+    value
+        .parse()
+        .map_err(|source| ConversionError::InvalidId { field, source })
+}
+
+fn parse_optional_id<T>(
+    field: &'static str,
+    value: Option<&str>,
+) -> Result<Option<T>, ConversionError>
+where
+    T: FromStr<Err = IdError>,
+{
+    value.map(|value| parse_id(field, value)).transpose()
+}
+
+/// Convert a UTC time into a protobuf timestamp.
+pub fn timestamp_from_datetime(at: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: at.timestamp(),
+        nanos: at.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// Convert a protobuf timestamp into a UTC time.
+pub fn datetime_from_timestamp(
+    field: &'static str,
+    ts: &prost_types::Timestamp,
+) -> Result<DateTime<Utc>, ConversionError> {
+    u32::try_from(ts.nanos)
+        .ok()
+        .and_then(|nanos| DateTime::from_timestamp(ts.seconds, nanos))
+        .ok_or(ConversionError::InvalidTimestamp(field))
+}
+
+// Domain payloads carry RFC 3339 strings; an unparseable value is dropped
+// rather than invented.
+fn rfc3339_to_timestamp(value: &str) -> Option<prost_types::Timestamp> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|at| timestamp_from_datetime(at.with_timezone(&Utc)))
+}
+
+fn rfc3339_from_timestamp(
+    field: &'static str,
+    ts: Option<&prost_types::Timestamp>,
+) -> Result<String, ConversionError> {
+    let ts = ts.ok_or(ConversionError::MissingField(field))?;
+    Ok(datetime_from_timestamp(field, ts)?.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// Generates `From<domain> for proto` and `TryFrom<proto> for domain` for an
+/// enum whose variants share names on both sides.
+macro_rules! enum_conversions {
+    (
+        $domain:ident :: $name:ident <=> $proto:ident {
+            $($variant:ident),+ $(,)?
+        }
+        $(unsupported { $($extra:ident),+ $(,)? })?
+    ) => {
+        impl From<$domain::$name> for $proto::$name {
+            fn from(value: $domain::$name) -> Self {
+                match value {
+                    $($domain::$name::$variant => Self::$variant,)+
+                }
+            }
+        }
+
+        impl TryFrom<$proto::$name> for $domain::$name {
+            type Error = ConversionError;
+
+            fn try_from(value: $proto::$name) -> Result<Self, Self::Error> {
+                match value {
+                    $($proto::$name::$variant => Ok(Self::$variant),)+
+                    $($($proto::$name::$extra => Err(ConversionError::Unsupported {
+                        enum_name: stringify!($name),
+                        value: value.as_str_name(),
+                    }),)+)?
+                    $proto::$name::Unspecified => {
+                        Err(ConversionError::Unspecified(stringify!($name)))
+                    }
+                }
+            }
+        }
+    };
+}
+
+enum_conversions!(domain::ActorType <=> common {
+    User,
+    ServicePrincipal,
+    System,
+});
+
+enum_conversions!(domain::AggregateType <=> common {
+    Org,
+    Project,
+    OrgMember,
+    ServicePrincipal,
+    App,
+    Env,
+    Release,
+    Deploy,
+    Route,
+    SecretBundle,
+    Volume,
+    VolumeAttachment,
+    Snapshot,
+    RestoreJob,
+    Instance,
+    Node,
+    ExecSession,
+    KeyRotation,
+    RouteCertificate,
+    AlertRule,
+});
+
+enum_conversions!(domain::DeployStatus <=> events {
+    Queued,
+    Rolling,
+    Succeeded,
+    Failed,
+    Paused,
+    Halted,
+});
+
+enum_conversions!(domain::InstanceDesiredState <=> events {
+    Running,
+    Draining,
+    Stopped,
+});
+
+enum_conversions!(domain::InstanceStatus <=> events {
+    Booting,
+    Ready,
+    Draining,
+    Stopped,
+    Failed,
+});
+
+enum_conversions!(domain::InstanceFailureReason <=> events {
+    ImagePullFailed,
+    RootfsBuildFailed,
+    FirecrackerStartFailed,
+    NetworkSetupFailed,
+    VolumeAttachFailed,
+    SecretsMissing,
+    SecretsInjectionFailed,
+    HealthcheckFailed,
+    OomKilled,
+    CrashLoopBackoff,
+    TerminatedByOperator,
+    NodeDraining,
+    KernelPanic,
+    ConfigHandshakeTimeout,
+    SecretsFetchFailed,
+    GuestInitFailed,
+});
+
+enum_conversions!(domain::InstanceDriftKind <=> events {
+    NotReported,
+    NotDesired,
+});
+
+enum_conversions!(domain::NodeState <=> events {
+    Active,
+    Draining,
+    Disabled,
+    Degraded,
+    Offline,
+} unsupported {
+    PendingApproval,
+});
+
+enum_conversions!(domain::TaintEffect <=> events {
+    NoSchedule,
+    PreferNoSchedule,
+});
+
+enum_conversions!(domain::JobStatus <=> events {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+});
+
+enum_conversions!(domain::MemberRole <=> events {
+    Owner,
+    Admin,
+    Developer,
+    Readonly,
+});
+
+enum_conversions!(domain::RouteProtocolHint <=> events {
+    TlsPassthrough,
+    TcpRaw,
+    TlsTerminate,
+});
+
+enum_conversions!(domain::RouteProxyProtocol <=> events {
+    Off,
+    V2,
+});
+
+enum_conversions!(domain::RouteVerificationMethod <=> events {
+    Txt,
+    Cname,
+});
+
+// -----------------------------------------------------------------------------
+// Event payloads
+// -----------------------------------------------------------------------------
+
+impl From<domain::InstanceStatusChangedPayload> for events::InstanceStatusChangedPayload {
+    fn from(payload: domain::InstanceStatusChangedPayload) -> Self {
+        Self {
+            instance_id: payload.instance_id.to_string(),
+            org_id: payload.org_id.to_string(),
+            env_id: payload.env_id.to_string(),
+            node_id: payload.node_id.to_string(),
+            status: events::InstanceStatus::from(payload.status).into(),
+            boot_id: payload.boot_id,
+            generation: payload.generation,
+            microvm_id: payload.microvm_id,
+            exit_code: payload.exit_code,
+            reason_code: payload
+                .reason_code
+                .map(|reason| events::InstanceFailureReason::from(reason).into()),
+            reported_at: rfc3339_to_timestamp(&payload.reported_at),
+            reason_detail: payload.reason_detail,
+        }
+    }
+}
+
+impl TryFrom<events::InstanceStatusChangedPayload> for domain::InstanceStatusChangedPayload {
+    type Error = ConversionError;
+
+    fn try_from(payload: events::InstanceStatusChangedPayload) -> Result<Self, Self::Error> {
+        Ok(Self {
+            instance_id: parse_id::<InstanceId>("instance_id", &payload.instance_id)?,
+            org_id: parse_id::<OrgId>("org_id", &payload.org_id)?,
+            env_id: parse_id::<EnvId>("env_id", &payload.env_id)?,
+            node_id: parse_id::<NodeId>("node_id", &payload.node_id)?,
+            status: decode_enum::<events::InstanceStatus, _>(payload.status)?,
+            boot_id: payload.boot_id,
+            generation: payload.generation,
+            microvm_id: payload.microvm_id,
+            exit_code: payload.exit_code,
+            reason_code: payload
+                .reason_code
+                .map(decode_enum::<events::InstanceFailureReason, _>)
+                .transpose()?,
+            reported_at: rfc3339_from_timestamp("reported_at", payload.reported_at.as_ref())?,
+            reason_detail: payload.reason_detail,
+        })
+    }
+}
+
+impl From<domain::InstanceDriftDetectedPayload> for events::InstanceDriftDetectedPayload {
+    fn from(payload: domain::InstanceDriftDetectedPayload) -> Self {
+        Self {
+            instance_id: payload.instance_id.to_string(),
+            node_id: payload.node_id.to_string(),
+            org_id: payload.org_id.map(|id| id.to_string()),
+            env_id: payload.env_id.map(|id| id.to_string()),
+            kind: events::InstanceDriftKind::from(payload.kind).into(),
+            detected_at: rfc3339_to_timestamp(&payload.detected_at),
+            desired_state: payload.desired_state,
+            reported_status: payload.reported_status,
+        }
+    }
+}
+
+impl TryFrom<events::InstanceDriftDetectedPayload> for domain::InstanceDriftDetectedPayload {
+    type Error = ConversionError;
+
+    fn try_from(payload: events::InstanceDriftDetectedPayload) -> Result<Self, Self::Error> {
+        Ok(Self {
+            instance_id: parse_id("instance_id", &payload.instance_id)?,
+            node_id: parse_id("node_id", &payload.node_id)?,
+            org_id: parse_optional_id("org_id", payload.org_id.as_deref())?,
+            env_id: parse_optional_id("env_id", payload.env_id.as_deref())?,
+            kind: decode_enum::<events::InstanceDriftKind, _>(payload.kind)?,
+            detected_at: rfc3339_from_timestamp("detected_at", payload.detected_at.as_ref())?,
+            desired_state: payload.desired_state,
+            reported_status: payload.reported_status,
+        })
+    }
+}
+
+impl From<domain::NodeStateChangedPayload> for events::NodeStateChangedPayload {
+    fn from(payload: domain::NodeStateChangedPayload) -> Self {
+        Self {
+            node_id: payload.node_id.to_string(),
+            old_state: events::NodeState::from(payload.old_state).into(),
+            new_state: events::NodeState::from(payload.new_state).into(),
+            reason: payload.reason,
+        }
+    }
+}
+
+impl TryFrom<events::NodeStateChangedPayload> for domain::NodeStateChangedPayload {
+    type Error = ConversionError;
+
+    fn try_from(payload: events::NodeStateChangedPayload) -> Result<Self, Self::Error> {
+        Ok(Self {
+            node_id: parse_id("node_id", &payload.node_id)?,
+            old_state: decode_enum::<events::NodeState, _>(payload.old_state)?,
+            new_state: decode_enum::<events::NodeState, _>(payload.new_state)?,
+            reason: payload.reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Round-trips every domain variant and checks the protobuf name agrees
+    /// with the domain's serde name, so the two enums cannot drift apart.
+    macro_rules! assert_round_trip {
+        ($domain:ident :: $name:ident, $proto:ident, $prefix:literal, [$($variant:ident),+ $(,)?]) => {
+            $(
+                let value = $domain::$name::$variant;
+                let proto = $proto::$name::from(value.clone());
+                assert_eq!($domain::$name::try_from(proto).unwrap(), value);
+
+                let serde_name = serde_json::to_value(&value).unwrap();
+                let serde_name = serde_name.as_str().unwrap();
+                assert_eq!(
+                    proto.as_str_name(),
+                    format!("{}_{}", $prefix, serde_name.to_uppercase()),
+                );
+            )+
+            assert_eq!(
+                $domain::$name::try_from($proto::$name::Unspecified),
+                Err(ConversionError::Unspecified(stringify!($name)))
+            );
+        };
+    }
+
+    #[test]
+    fn test_common_enums_round_trip() {
+        assert_round_trip!(
+            domain::ActorType,
+            common,
+            "ACTOR_TYPE",
+            [User, ServicePrincipal, System]
+        );
+        assert_round_trip!(
+            domain::AggregateType,
+            common,
+            "AGGREGATE_TYPE",
+            [
+                Org,
+                Project,
+                OrgMember,
+                ServicePrincipal,
+                App,
+                Env,
+                Release,
+                Deploy,
+                Route,
+                SecretBundle,
+                Volume,
+                VolumeAttachment,
+                Snapshot,
+                RestoreJob,
+                Instance,
+                Node,
+                ExecSession,
+                KeyRotation,
+                RouteCertificate,
+                AlertRule,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_enums_round_trip() {
+        assert_round_trip!(
+            domain::DeployStatus,
+            events,
+            "DEPLOY_STATUS",
+            [Queued, Rolling, Succeeded, Failed, Paused, Halted]
+        );
+        assert_round_trip!(
+            domain::InstanceDesiredState,
+            events,
+            "INSTANCE_DESIRED_STATE",
+            [Running, Draining, Stopped]
+        );
+        assert_round_trip!(
+            domain::InstanceStatus,
+            events,
+            "INSTANCE_STATUS",
+            [Booting, Ready, Draining, Stopped, Failed]
+        );
+        assert_round_trip!(
+            domain::InstanceFailureReason,
+            events,
+            "INSTANCE_FAILURE_REASON",
+            [
+                ImagePullFailed,
+                RootfsBuildFailed,
+                FirecrackerStartFailed,
+                NetworkSetupFailed,
+                VolumeAttachFailed,
+                SecretsMissing,
+                SecretsInjectionFailed,
+                HealthcheckFailed,
+                OomKilled,
+                CrashLoopBackoff,
+                TerminatedByOperator,
+                NodeDraining,
+                KernelPanic,
+                ConfigHandshakeTimeout,
+                SecretsFetchFailed,
+                GuestInitFailed,
+            ]
+        );
+        assert_round_trip!(
+            domain::InstanceDriftKind,
+            events,
+            "INSTANCE_DRIFT_KIND",
+            [NotReported, NotDesired]
+        );
+        assert_round_trip!(
+            domain::NodeState,
+            events,
+            "NODE_STATE",
+            [Active, Draining, Disabled, Degraded, Offline]
+        );
+        assert_round_trip!(
+            domain::JobStatus,
+            events,
+            "JOB_STATUS",
+            [Queued, Running, Succeeded, Failed]
+        );
+        assert_round_trip!(
+            domain::MemberRole,
+            events,
+            "MEMBER_ROLE",
+            [Owner, Admin, Developer, Readonly]
+        );
+        assert_round_trip!(
+            domain::RouteProtocolHint,
+            events,
+            "ROUTE_PROTOCOL_HINT",
+            [TlsPassthrough, TcpRaw, TlsTerminate]
+        );
+        assert_round_trip!(
+            domain::RouteProxyProtocol,
+            events,
+            "ROUTE_PROXY_PROTOCOL",
+            [Off, V2]
+        );
+        assert_round_trip!(
+            domain::RouteVerificationMethod,
+            events,
+            "ROUTE_VERIFICATION_METHOD",
+            [Txt, Cname]
+        );
+    }
+
+    #[test]
+    fn test_taint_effect_round_trip() {
+        // Taint effects serialize with the Kubernetes spelling, so only the
+        // round trip is checked here.
+        for effect in [
+            domain::TaintEffect::NoSchedule,
+            domain::TaintEffect::PreferNoSchedule,
+        ] {
+            let proto = events::TaintEffect::from(effect);
+            assert_eq!(domain::TaintEffect::try_from(proto).unwrap(), effect);
+        }
+    }
+
+    #[test]
+    fn test_unmodelled_values_are_rejected() {
+        assert_eq!(
+            domain::NodeState::try_from(events::NodeState::PendingApproval),
+            Err(ConversionError::Unsupported {
+                enum_name: "NodeState",
+                value: "NODE_STATE_PENDING_APPROVAL",
+            })
+        );
+        assert_eq!(
+            decode_enum::<events::InstanceStatus, domain::InstanceStatus>(99),
+            Err(ConversionError::UnknownEnumValue(99))
+        );
+    }
+
+    #[test]
+    fn test_instance_status_changed_round_trip() {
+        let payload = domain::InstanceStatusChangedPayload {
+            instance_id: InstanceId::new(),
+            org_id: OrgId::new(),
+            env_id: EnvId::new(),
+            node_id: NodeId::new(),
+            status: domain::InstanceStatus::Failed,
+            boot_id: Some("boot-1".to_string()),
+            generation: Some(4),
+            microvm_id: None,
+            exit_code: Some(137),
+            reason_code: Some(domain::InstanceFailureReason::OomKilled),
+            reason_detail: Some("killed".to_string()),
+            reported_at: "2026-01-02T03:04:05.123Z".to_string(),
+        };
+
+        let proto = events::InstanceStatusChangedPayload::from(payload.clone());
+        assert_eq!(proto.status, events::InstanceStatus::Failed as i32);
+        assert_eq!(
+            proto.reason_code,
+            Some(events::InstanceFailureReason::OomKilled as i32)
+        );
+
+        let back = domain::InstanceStatusChangedPayload::try_from(proto).unwrap();
+        assert_eq!(back.instance_id, payload.instance_id);
+        assert_eq!(back.org_id, payload.org_id);
+        assert_eq!(back.env_id, payload.env_id);
+        assert_eq!(back.node_id, payload.node_id);
+        assert_eq!(back.status, payload.status);
+        assert_eq!(back.boot_id, payload.boot_id);
+        assert_eq!(back.generation, payload.generation);
+        assert_eq!(back.exit_code, payload.exit_code);
+        assert_eq!(back.reason_code, payload.reason_code);
+        assert_eq!(back.reason_detail, payload.reason_detail);
+        assert_eq!(back.reported_at, payload.reported_at);
+    }
+
+    #[test]
+    fn test_payload_conversion_rejects_bad_ids() {
+        let proto = events::NodeStateChangedPayload {
+            node_id: "app_01HV4Z2WQXKJNM8GPQY6VBKC3D".to_string(),
+            old_state: events::NodeState::Active as i32,
+            new_state: events::NodeState::Draining as i32,
+            reason: None,
+        };
+        let err = domain::NodeStateChangedPayload::try_from(proto).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::InvalidId {
+                field: "node_id",
+                ..
+            }
+        ));
+
+        let payload = domain::NodeStateChangedPayload {
+            node_id: NodeId::new(),
+            old_state: domain::NodeState::Active,
+            new_state: domain::NodeState::Draining,
+            reason: Some("maintenance".to_string()),
+        };
+        let back = domain::NodeStateChangedPayload::try_from(
+            events::NodeStateChangedPayload::from(payload.clone()),
+        )
+        .unwrap();
+        assert_eq!(back.node_id, payload.node_id);
+        assert_eq!(back.new_state, domain::NodeState::Draining);
+        assert_eq!(back.reason, payload.reason);
+    }
+}
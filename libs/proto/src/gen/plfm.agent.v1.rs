@@ -90,6 +90,9 @@ pub struct WorkloadMount {
     /// Optional device hint for the agent.
     #[prost(string, optional, tag = "5")]
     pub device_hint: ::core::option::Option<::prost::alloc::string::String>,
+    /// Desired volume size in bytes; the agent grows the backing storage to match.
+    #[prost(int64, optional, tag = "6")]
+    pub size_bytes: ::core::option::Option<i64>,
 }
 /// Secret materialization configuration.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -112,6 +115,25 @@ pub struct WorkloadSecrets {
     /// Group id for secret file ownership.
     #[prost(int32, optional, tag = "6")]
     pub gid: ::core::option::Option<i32>,
+    /// How the guest applies a new version in place; absent means restart.
+    #[prost(message, optional, tag = "7")]
+    pub reload: ::core::option::Option<SecretsReload>,
+}
+/// How a new secrets version is applied to a running instance.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SecretsReload {
+    /// One of restart, file, signal or exec.
+    #[prost(string, tag = "1")]
+    pub mode: ::prost::alloc::string::String,
+    /// Signal sent to the workload in signal mode (e.g. SIGHUP).
+    #[prost(string, optional, tag = "2")]
+    pub signal: ::core::option::Option<::prost::alloc::string::String>,
+    /// Reload hook argv in exec mode.
+    #[prost(string, repeated, tag = "3")]
+    pub command: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Reload hook timeout in exec mode.
+    #[prost(uint32, optional, tag = "4")]
+    pub timeout_seconds: ::core::option::Option<u32>,
 }
 /// Fully resolved workload specification.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -236,9 +258,20 @@ pub struct InstanceStatusReport {
     /// Optional exit code.
     #[prost(int32, optional, tag = "5")]
     pub exit_code: ::core::option::Option<i32>,
+    /// Classified failure reason, set when status is failed.
+    #[prost(
+        enumeration = "super::super::events::v1::InstanceFailureReason",
+        optional,
+        tag = "6"
+    )]
+    pub reason_code: ::core::option::Option<i32>,
+    /// Plan generation the reporting boot runs. With boot_id, orders reports
+    /// so late ones from a superseded boot are not applied.
+    #[prost(int32, optional, tag = "7")]
+    pub generation: ::core::option::Option<i32>,
 }
 /// Heartbeat payload from a node.
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HeartbeatRequest {
     /// Current node state.
     #[prost(enumeration = "super::super::events::v1::NodeState", tag = "1")]
@@ -252,6 +285,19 @@ pub struct HeartbeatRequest {
     /// Active instance count.
     #[prost(int32, tag = "4")]
     pub instance_count: i32,
+    /// Every instance the agent tracks; unset when the agent does not report one.
+    #[prost(message, optional, tag = "5")]
+    pub inventory: ::core::option::Option<InstanceInventory>,
+}
+/// Instance inventory reported with a heartbeat.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InstanceInventory {
+    /// Instance status by instance identifier.
+    #[prost(map = "string, string", tag = "1")]
+    pub statuses: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 /// Heartbeat response payload.
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -340,6 +386,9 @@ pub struct EnrollRequest {
     /// Supported API versions the agent can speak.
     #[prost(string, repeated, tag = "12")]
     pub supported_api_versions: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Single-use bootstrap token minted by an operator.
+    #[prost(string, optional, tag = "13")]
+    pub bootstrap_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Enrollment response from control plane.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -431,6 +480,75 @@ pub struct SendWorkloadLogsResponse {
     #[prost(int32, tag = "2")]
     pub rejected: i32,
 }
+/// Exec tunnel message from the node agent.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecTunnelAgentMessage {
+    /// Exec session the frame belongs to.
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+    /// Exec stream frame from the guest (type byte followed by payload).
+    #[prost(bytes = "vec", tag = "2")]
+    pub frame: ::prost::alloc::vec::Vec<u8>,
+}
+/// Exec tunnel message from the control plane.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecTunnelServerMessage {
+    /// Exec session the message belongs to.
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+    #[prost(oneof = "exec_tunnel_server_message::Body", tags = "2, 3, 4")]
+    pub body: ::core::option::Option<exec_tunnel_server_message::Body>,
+}
+/// Nested message and enum types in `ExecTunnelServerMessage`.
+pub mod exec_tunnel_server_message {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Body {
+        /// Start a new session.
+        #[prost(message, tag = "2")]
+        Open(super::ExecTunnelOpen),
+        /// Exec stream frame from the client (type byte followed by payload).
+        #[prost(bytes, tag = "3")]
+        Frame(::prost::alloc::vec::Vec<u8>),
+        /// The client went away; tear the session down.
+        #[prost(message, tag = "4")]
+        Close(super::ExecTunnelClose),
+    }
+}
+/// Request to start an exec session in an instance.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecTunnelOpen {
+    /// Target instance identifier.
+    #[prost(string, tag = "1")]
+    pub instance_id: ::prost::alloc::string::String,
+    /// Command and arguments.
+    #[prost(string, repeated, tag = "2")]
+    pub command: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Whether to allocate a PTY.
+    #[prost(bool, tag = "3")]
+    pub tty: bool,
+    /// Initial terminal width.
+    #[prost(uint32, tag = "4")]
+    pub cols: u32,
+    /// Initial terminal height.
+    #[prost(uint32, tag = "5")]
+    pub rows: u32,
+    /// Extra environment variables.
+    #[prost(map = "string, string", tag = "6")]
+    pub env: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// Whether stdin is attached.
+    #[prost(bool, tag = "7")]
+    pub stdin: bool,
+}
+/// Session teardown notice.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecTunnelClose {
+    /// Stable reason code (e.g. `client_disconnect`).
+    #[prost(string, tag = "1")]
+    pub reason: ::prost::alloc::string::String,
+}
 /// Generated client implementations.
 pub mod node_agent_client {
     #![allow(
@@ -672,6 +790,34 @@ pub mod node_agent_client {
                 .insert(GrpcMethod::new("plfm.agent.v1.NodeAgent", "SendWorkloadLogs"));
             self.inner.unary(req, path, codec).await
         }
+        /// Carry exec sessions over the agent's connection. The agent keeps one
+        /// tunnel open; the control plane multiplexes sessions over it.
+        pub async fn exec_tunnel(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<
+                Message = super::ExecTunnelAgentMessage,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ExecTunnelServerMessage>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/plfm.agent.v1.NodeAgent/ExecTunnel",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("plfm.agent.v1.NodeAgent", "ExecTunnel"));
+            self.inner.streaming(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -729,6 +875,21 @@ pub mod node_agent_server {
             tonic::Response<super::SendWorkloadLogsResponse>,
             tonic::Status,
         >;
+        /// Server streaming response type for the ExecTunnel method.
+        type ExecTunnelStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::ExecTunnelServerMessage, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Carry exec sessions over the agent's connection. The agent keeps one
+        /// tunnel open; the control plane multiplexes sessions over it.
+        async fn exec_tunnel(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::ExecTunnelAgentMessage>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ExecTunnelStream>,
+            tonic::Status,
+        >;
     }
     /// Node agent gRPC service.
     #[derive(Debug)]
@@ -1074,6 +1235,54 @@ pub mod node_agent_server {
                     };
                     Box::pin(fut)
                 }
+                "/plfm.agent.v1.NodeAgent/ExecTunnel" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExecTunnelSvc<T: NodeAgent>(pub Arc<T>);
+                    impl<
+                        T: NodeAgent,
+                    > tonic::server::StreamingService<super::ExecTunnelAgentMessage>
+                    for ExecTunnelSvc<T> {
+                        type Response = super::ExecTunnelServerMessage;
+                        type ResponseStream = T::ExecTunnelStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::ExecTunnelAgentMessage>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as NodeAgent>::exec_tunnel(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExecTunnelSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -76,6 +76,12 @@ pub enum AggregateType {
     Node = 16,
     /// Exec session aggregate.
     ExecSession = 17,
+    /// Secrets key rotation job aggregate.
+    KeyRotation = 18,
+    /// Route custom TLS certificate aggregate.
+    RouteCertificate = 19,
+    /// Alert rule aggregate.
+    AlertRule = 20,
 }
 impl AggregateType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -102,6 +108,9 @@ impl AggregateType {
             Self::Instance => "AGGREGATE_TYPE_INSTANCE",
             Self::Node => "AGGREGATE_TYPE_NODE",
             Self::ExecSession => "AGGREGATE_TYPE_EXEC_SESSION",
+            Self::KeyRotation => "AGGREGATE_TYPE_KEY_ROTATION",
+            Self::RouteCertificate => "AGGREGATE_TYPE_ROUTE_CERTIFICATE",
+            Self::AlertRule => "AGGREGATE_TYPE_ALERT_RULE",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -125,6 +134,9 @@ impl AggregateType {
             "AGGREGATE_TYPE_INSTANCE" => Some(Self::Instance),
             "AGGREGATE_TYPE_NODE" => Some(Self::Node),
             "AGGREGATE_TYPE_EXEC_SESSION" => Some(Self::ExecSession),
+            "AGGREGATE_TYPE_KEY_ROTATION" => Some(Self::KeyRotation),
+            "AGGREGATE_TYPE_ROUTE_CERTIFICATE" => Some(Self::RouteCertificate),
+            "AGGREGATE_TYPE_ALERT_RULE" => Some(Self::AlertRule),
             _ => None,
         }
     }
@@ -98,6 +98,15 @@ pub struct Env {
     /// Creation timestamp.
     #[prost(message, optional, tag = "5")]
     pub created_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// Platform-managed hostname, when managed hostnames are enabled.
+    #[prost(string, optional, tag = "6")]
+    pub managed_hostname: ::core::option::Option<::prost::alloc::string::String>,
+    /// Ephemeral preview environment.
+    #[prost(bool, tag = "7")]
+    pub preview: bool,
+    /// When a preview environment is torn down.
+    #[prost(message, optional, tag = "8")]
+    pub expires_at: ::core::option::Option<::prost_types::Timestamp>,
 }
 /// Response payload for environment listings.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1741,6 +1750,9 @@ pub struct Route {
     /// Last update timestamp.
     #[prost(message, optional, tag = "15")]
     pub updated_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// Whether the route is served only on the overlay network.
+    #[prost(bool, tag = "16")]
+    pub internal: bool,
 }
 /// Response payload for route listings.
 #[derive(Clone, PartialEq, ::prost::Message)]
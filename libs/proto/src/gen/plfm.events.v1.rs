@@ -1,4 +1,20 @@
 // This file is @generated by prost-build.
+/// Connection-level client filtering applied by the edge.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteAccessPolicy {
+    /// Client CIDRs allowed to connect; empty allows any not denied.
+    #[prost(string, repeated, tag = "1")]
+    pub allow_cidrs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Client CIDRs refused.
+    #[prost(string, repeated, tag = "2")]
+    pub deny_cidrs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// TLS client fingerprints (JA3 hash or JA4) allowed; empty allows any not denied.
+    #[prost(string, repeated, tag = "3")]
+    pub allow_fingerprints: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// TLS client fingerprints refused.
+    #[prost(string, repeated, tag = "4")]
+    pub deny_fingerprints: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
 /// Payload for route created events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RouteCreatedPayload {
@@ -41,6 +57,15 @@ pub struct RouteCreatedPayload {
     /// Environment IPv4 address when allocated.
     #[prost(string, optional, tag = "13")]
     pub env_ipv4_address: ::core::option::Option<::prost::alloc::string::String>,
+    /// Hostname verification token; absent when no verification is required.
+    #[prost(string, optional, tag = "14")]
+    pub verification_token: ::core::option::Option<::prost::alloc::string::String>,
+    /// Whether the route is served only on the overlay network.
+    #[prost(bool, tag = "15")]
+    pub internal: bool,
+    /// Client filtering; absent when unrestricted.
+    #[prost(message, optional, tag = "16")]
+    pub access_policy: ::core::option::Option<RouteAccessPolicy>,
 }
 /// Payload for route change events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -72,6 +97,28 @@ pub struct RouteUpdatedPayload {
     /// Environment IPv4 address when allocated.
     #[prost(string, optional, tag = "9")]
     pub env_ipv4_address: ::core::option::Option<::prost::alloc::string::String>,
+    /// Replacement client filtering policy (empty lists clear it).
+    #[prost(message, optional, tag = "10")]
+    pub access_policy: ::core::option::Option<RouteAccessPolicy>,
+}
+/// Payload for route label updates.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteLabelsUpdatedPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Full label set after the update.
+    #[prost(map = "string, string", tag = "4")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 /// Payload for route deletion events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -89,6 +136,148 @@ pub struct RouteDeletedPayload {
     #[prost(string, tag = "4")]
     pub hostname: ::prost::alloc::string::String,
 }
+/// Payload for route restore events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteRestoredPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+}
+/// Payload for route purge events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RoutePurgedPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+}
+/// Payload for route certificate upload events. Never carries key material.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteCertSetPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+    /// SHA-256 fingerprint of the leaf certificate.
+    #[prost(string, tag = "5")]
+    pub fingerprint_sha256: ::prost::alloc::string::String,
+    /// Leaf certificate expiry (RFC 3339).
+    #[prost(string, tag = "6")]
+    pub not_after: ::prost::alloc::string::String,
+    /// Whether the certificate is served to clients that send no SNI.
+    #[prost(bool, tag = "7")]
+    pub default_for_non_sni: bool,
+}
+/// Payload for route certificate removal events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteCertRemovedPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+    /// SHA-256 fingerprint of the removed certificate.
+    #[prost(string, tag = "5")]
+    pub fingerprint_sha256: ::prost::alloc::string::String,
+}
+/// Payload for route certificate expiry warnings.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteCertExpiringPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+    /// SHA-256 fingerprint of the expiring certificate.
+    #[prost(string, tag = "5")]
+    pub fingerprint_sha256: ::prost::alloc::string::String,
+    /// Leaf certificate expiry (RFC 3339).
+    #[prost(string, tag = "6")]
+    pub not_after: ::prost::alloc::string::String,
+    /// Whole days until expiry (0 once expired).
+    #[prost(int64, tag = "7")]
+    pub days_remaining: i64,
+}
+/// Payload for route hostname verification success.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteVerificationSucceededPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+    /// Verification method that succeeded.
+    #[prost(enumeration = "RouteVerificationMethod", tag = "5")]
+    pub method: i32,
+}
+/// Payload for route hostname verification failure.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RouteVerificationFailedPayload {
+    /// Route identifier.
+    #[prost(string, tag = "1")]
+    pub route_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Route hostname.
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+    /// Last check failure reason.
+    #[prost(string, tag = "5")]
+    pub reason: ::prost::alloc::string::String,
+    /// Number of checks performed.
+    #[prost(int32, tag = "6")]
+    pub attempts: i32,
+}
 /// Protocol hint for route configuration.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -99,6 +288,8 @@ pub enum RouteProtocolHint {
     TlsPassthrough = 1,
     /// Raw TCP routing.
     TcpRaw = 2,
+    /// TLS terminated at the edge with an uploaded certificate.
+    TlsTerminate = 3,
 }
 impl RouteProtocolHint {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -110,6 +301,7 @@ impl RouteProtocolHint {
             Self::Unspecified => "ROUTE_PROTOCOL_HINT_UNSPECIFIED",
             Self::TlsPassthrough => "ROUTE_PROTOCOL_HINT_TLS_PASSTHROUGH",
             Self::TcpRaw => "ROUTE_PROTOCOL_HINT_TCP_RAW",
+            Self::TlsTerminate => "ROUTE_PROTOCOL_HINT_TLS_TERMINATE",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -118,6 +310,7 @@ impl RouteProtocolHint {
             "ROUTE_PROTOCOL_HINT_UNSPECIFIED" => Some(Self::Unspecified),
             "ROUTE_PROTOCOL_HINT_TLS_PASSTHROUGH" => Some(Self::TlsPassthrough),
             "ROUTE_PROTOCOL_HINT_TCP_RAW" => Some(Self::TcpRaw),
+            "ROUTE_PROTOCOL_HINT_TLS_TERMINATE" => Some(Self::TlsTerminate),
             _ => None,
         }
     }
@@ -155,6 +348,39 @@ impl RouteProxyProtocol {
         }
     }
 }
+/// How route hostname ownership was proven.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RouteVerificationMethod {
+    /// Verification method is unspecified.
+    Unspecified = 0,
+    /// TXT record challenge.
+    Txt = 1,
+    /// CNAME target check.
+    Cname = 2,
+}
+impl RouteVerificationMethod {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "ROUTE_VERIFICATION_METHOD_UNSPECIFIED",
+            Self::Txt => "ROUTE_VERIFICATION_METHOD_TXT",
+            Self::Cname => "ROUTE_VERIFICATION_METHOD_CNAME",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ROUTE_VERIFICATION_METHOD_UNSPECIFIED" => Some(Self::Unspecified),
+            "ROUTE_VERIFICATION_METHOD_TXT" => Some(Self::Txt),
+            "ROUTE_VERIFICATION_METHOD_CNAME" => Some(Self::Cname),
+            _ => None,
+        }
+    }
+}
 /// Payload for volume created events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct VolumeCreatedPayload {
@@ -177,6 +403,22 @@ pub struct VolumeCreatedPayload {
     #[prost(bool, tag = "6")]
     pub backup_enabled: bool,
 }
+/// Payload for volume label updates.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeLabelsUpdatedPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Full label set after the update.
+    #[prost(map = "string, string", tag = "3")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
 /// Payload for volume deletion events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct VolumeDeletedPayload {
@@ -187,6 +429,99 @@ pub struct VolumeDeletedPayload {
     #[prost(string, tag = "2")]
     pub org_id: ::prost::alloc::string::String,
 }
+/// Payload for volume restore events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeRestoredPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+}
+/// Payload for volume purge events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumePurgedPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+}
+/// Payload for volume resize requests (grow only).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeResizedPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Size before the resize, in bytes.
+    #[prost(int64, tag = "3")]
+    pub previous_size_bytes: i64,
+    /// Requested size, in bytes.
+    #[prost(int64, tag = "4")]
+    pub size_bytes: i64,
+}
+/// Payload for volume resize completion reported by the home node.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeResizeCompletedPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Node that grew the volume.
+    #[prost(string, tag = "3")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Size of the backing storage, in bytes.
+    #[prost(int64, tag = "4")]
+    pub size_bytes: i64,
+}
+/// Payload for volume resize failures reported by the home node.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeResizeFailedPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Node that attempted the resize.
+    #[prost(string, tag = "3")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Size that could not be reached, in bytes.
+    #[prost(int64, tag = "4")]
+    pub size_bytes: i64,
+    /// Machine-readable cause (e.g. insufficient_space).
+    #[prost(string, tag = "5")]
+    pub reason: ::prost::alloc::string::String,
+    /// Human-readable detail.
+    #[prost(string, tag = "6")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Payload for volume backup policy changes.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeBackupPolicySetPayload {
+    /// Volume identifier.
+    #[prost(string, tag = "1")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Five-field cron expression (UTC); unset disables scheduled snapshots.
+    #[prost(string, optional, tag = "3")]
+    pub schedule: ::core::option::Option<::prost::alloc::string::String>,
+    /// Number of successful scheduled snapshots to keep.
+    #[prost(int32, optional, tag = "4")]
+    pub retain_count: ::core::option::Option<i32>,
+    /// Maximum age of scheduled snapshots, in seconds.
+    #[prost(int64, optional, tag = "5")]
+    pub retain_max_age_secs: ::core::option::Option<i64>,
+}
 /// Payload for volume attachment created events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct VolumeAttachmentCreatedPayload {
@@ -234,6 +569,59 @@ pub struct VolumeAttachmentDeletedPayload {
     #[prost(string, tag = "5")]
     pub process_type: ::prost::alloc::string::String,
 }
+/// Payload for volume attached events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeAttachedPayload {
+    /// Attachment identifier.
+    #[prost(string, tag = "1")]
+    pub attachment_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Volume identifier.
+    #[prost(string, tag = "3")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "4")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "5")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Process type label.
+    #[prost(string, tag = "6")]
+    pub process_type: ::prost::alloc::string::String,
+    /// Mount path inside the workload.
+    #[prost(string, tag = "7")]
+    pub mount_path: ::prost::alloc::string::String,
+    /// Whether the mount is read-only.
+    #[prost(bool, tag = "8")]
+    pub read_only: bool,
+}
+/// Payload for volume detached events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeDetachedPayload {
+    /// Attachment identifier.
+    #[prost(string, tag = "1")]
+    pub attachment_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Volume identifier.
+    #[prost(string, tag = "3")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "4")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "5")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Process type label.
+    #[prost(string, tag = "6")]
+    pub process_type: ::prost::alloc::string::String,
+    /// Mount path the volume was mounted at.
+    #[prost(string, tag = "7")]
+    pub mount_path: ::prost::alloc::string::String,
+}
 /// Payload for snapshot creation events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SnapshotCreatedPayload {
@@ -252,6 +640,9 @@ pub struct SnapshotCreatedPayload {
     /// Optional snapshot note.
     #[prost(string, optional, tag = "5")]
     pub note: ::core::option::Option<::prost::alloc::string::String>,
+    /// Whether the volume's backup policy created the snapshot.
+    #[prost(bool, tag = "6")]
+    pub scheduled: bool,
 }
 /// Payload for snapshot status change events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -275,6 +666,22 @@ pub struct SnapshotStatusChangedPayload {
     #[prost(string, optional, tag = "6")]
     pub failed_reason: ::core::option::Option<::prost::alloc::string::String>,
 }
+/// Payload for snapshot deletion events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SnapshotDeletedPayload {
+    /// Snapshot identifier.
+    #[prost(string, tag = "1")]
+    pub snapshot_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Volume identifier.
+    #[prost(string, tag = "3")]
+    pub volume_id: ::prost::alloc::string::String,
+    /// Why the snapshot was removed (e.g. retention).
+    #[prost(string, tag = "4")]
+    pub reason: ::prost::alloc::string::String,
+}
 /// Payload for restore job creation events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RestoreJobCreatedPayload {
@@ -296,6 +703,9 @@ pub struct RestoreJobCreatedPayload {
     /// Restore job status.
     #[prost(enumeration = "JobStatus", tag = "6")]
     pub status: i32,
+    /// Overwrite the source volume instead of creating a new one.
+    #[prost(bool, tag = "7")]
+    pub in_place: bool,
 }
 /// Payload for restore job status change events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -315,6 +725,12 @@ pub struct RestoreJobStatusChangedPayload {
     /// Failure reason code.
     #[prost(string, optional, tag = "5")]
     pub failed_reason: ::core::option::Option<::prost::alloc::string::String>,
+    /// Bytes written so far by an in-place restore.
+    #[prost(int64, optional, tag = "6")]
+    pub bytes_restored: ::core::option::Option<i64>,
+    /// Total bytes an in-place restore will write.
+    #[prost(int64, optional, tag = "7")]
+    pub bytes_total: ::core::option::Option<i64>,
 }
 /// Job status for asynchronous volume operations.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
@@ -387,6 +803,9 @@ pub struct EventEnvelope {
     /// Aggregate identifier.
     #[prost(string, tag = "21")]
     pub aggregate_id: ::prost::alloc::string::String,
+    /// Per-aggregate sequence number (optimistic concurrency).
+    #[prost(uint32, tag = "22")]
+    pub aggregate_seq: u32,
     /// Event type string.
     #[prost(string, tag = "30")]
     pub event_type: ::prost::alloc::string::String,
@@ -647,16 +1066,147 @@ pub struct AppUpdatedPayload {
     #[prost(string, optional, tag = "4")]
     pub description: ::core::option::Option<::prost::alloc::string::String>,
 }
+/// Payload for app label updates.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AppLabelsUpdatedPayload {
+    /// Application identifier.
+    #[prost(string, tag = "1")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Full label set after the update.
+    #[prost(map = "string, string", tag = "3")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+/// Payload for app deletion requests (starts cascading teardown).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AppDeletionRequestedPayload {
+    /// Application identifier.
+    #[prost(string, tag = "1")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Delete volumes attached to the app's environments.
+    #[prost(bool, tag = "3")]
+    pub delete_volumes: bool,
+}
 /// Payload for app deletion events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AppDeletedPayload {
     /// Application identifier.
-    #[prost(string, tag = "1")]
+    #[prost(string, tag = "1")]
+    pub app_id: ::prost::alloc::string::String,
+}
+/// Payload for app restore events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AppRestoredPayload {
+    /// Application identifier.
+    #[prost(string, tag = "1")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+}
+/// Payload for app purge events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AppPurgedPayload {
+    /// Application identifier.
+    #[prost(string, tag = "1")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+}
+/// Payload for environment created events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvCreatedPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Environment name.
+    #[prost(string, tag = "4")]
+    pub name: ::prost::alloc::string::String,
+    /// Platform-managed hostname allocated for the environment, if enabled.
+    #[prost(string, optional, tag = "5")]
+    pub managed_hostname: ::core::option::Option<::prost::alloc::string::String>,
+    /// Ephemeral preview environment, torn down once it expires.
+    #[prost(bool, tag = "6")]
+    pub preview: bool,
+    /// When a preview environment expires (RFC 3339).
+    #[prost(string, optional, tag = "7")]
+    pub expires_at: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Payload for environment change events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvUpdatedPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
+    pub app_id: ::prost::alloc::string::String,
+    /// New environment name.
+    #[prost(string, optional, tag = "4")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Payload for environment label updates.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvLabelsUpdatedPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Full label set after the update.
+    #[prost(map = "string, string", tag = "4")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+/// Payload for environment config var changes.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvConfigVarsSetPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
     pub app_id: ::prost::alloc::string::String,
+    /// Config vars version, incremented on every change.
+    #[prost(int32, tag = "4")]
+    pub version: i32,
+    /// Full set of config vars after the change.
+    #[prost(map = "string, string", tag = "5")]
+    pub vars: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
-/// Payload for environment created events.
+/// Payload for per-process-type resource overrides.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct EnvCreatedPayload {
+pub struct EnvProcessResourcesSetPayload {
     /// Environment identifier.
     #[prost(string, tag = "1")]
     pub env_id: ::prost::alloc::string::String,
@@ -666,13 +1216,25 @@ pub struct EnvCreatedPayload {
     /// Application identifier.
     #[prost(string, tag = "3")]
     pub app_id: ::prost::alloc::string::String,
-    /// Environment name.
+    /// Process type the override applies to.
     #[prost(string, tag = "4")]
-    pub name: ::prost::alloc::string::String,
-}
-/// Payload for environment change events.
+    pub process_type: ::prost::alloc::string::String,
+    /// Override version for this process type, incremented on every change.
+    #[prost(int32, tag = "5")]
+    pub version: i32,
+    /// vCPU request; the release value applies when unset.
+    #[prost(double, optional, tag = "6")]
+    pub cpu_request: ::core::option::Option<f64>,
+    /// Memory limit in bytes; the release value applies when unset.
+    #[prost(int64, optional, tag = "7")]
+    pub memory_limit_bytes: ::core::option::Option<i64>,
+    /// Scratch disk in bytes; the env scale value applies when unset.
+    #[prost(int64, optional, tag = "8")]
+    pub ephemeral_disk_bytes: ::core::option::Option<i64>,
+}
+/// Payload for environment deletion requests (starts cascading teardown).
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct EnvUpdatedPayload {
+pub struct EnvDeletionRequestedPayload {
     /// Environment identifier.
     #[prost(string, tag = "1")]
     pub env_id: ::prost::alloc::string::String,
@@ -682,9 +1244,9 @@ pub struct EnvUpdatedPayload {
     /// Application identifier.
     #[prost(string, tag = "3")]
     pub app_id: ::prost::alloc::string::String,
-    /// New environment name.
-    #[prost(string, optional, tag = "4")]
-    pub name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Delete volumes attached to the environment.
+    #[prost(bool, tag = "4")]
+    pub delete_volumes: bool,
 }
 /// Payload for environment deletion events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -693,6 +1255,32 @@ pub struct EnvDeletedPayload {
     #[prost(string, tag = "1")]
     pub env_id: ::prost::alloc::string::String,
 }
+/// Payload for environment restore events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvRestoredPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
+    pub app_id: ::prost::alloc::string::String,
+}
+/// Payload for environment purge events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvPurgedPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
+    pub app_id: ::prost::alloc::string::String,
+}
 /// Payload for environment scale updates.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EnvScaleSetPayload {
@@ -708,6 +1296,9 @@ pub struct EnvScaleSetPayload {
     /// Maximum replica count.
     #[prost(int32, tag = "4")]
     pub max_replicas: i32,
+    /// Why the scale was changed, as given by the caller.
+    #[prost(string, optional, tag = "5")]
+    pub reason: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Payload for environment desired release changes.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -722,6 +1313,22 @@ pub struct EnvDesiredReleaseSetPayload {
     #[prost(string, tag = "3")]
     pub deploy_id: ::prost::alloc::string::String,
 }
+/// Payload for process restart requests.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnvRestartRequestedPayload {
+    /// Environment identifier.
+    #[prost(string, tag = "1")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Application identifier.
+    #[prost(string, tag = "3")]
+    pub app_id: ::prost::alloc::string::String,
+    /// Process types to restart.
+    #[prost(string, repeated, tag = "4")]
+    pub process_types: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
 /// Payload for enabling the IPv4 add-on.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EnvIpv4AddonEnabledPayload {
@@ -754,6 +1361,28 @@ pub struct EnvIpv4AddonDisabledPayload {
     #[prost(string, tag = "3")]
     pub allocation_id: ::prost::alloc::string::String,
 }
+/// Source and build provenance recorded with a release.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReleaseMetadata {
+    /// Git ref the release was built from (branch, tag, or PR head ref).
+    #[prost(string, optional, tag = "1")]
+    pub git_ref: ::core::option::Option<::prost::alloc::string::String>,
+    /// Git commit SHA the image was built from.
+    #[prost(string, optional, tag = "2")]
+    pub git_commit: ::core::option::Option<::prost::alloc::string::String>,
+    /// Git branch the commit was on.
+    #[prost(string, optional, tag = "3")]
+    pub git_branch: ::core::option::Option<::prost::alloc::string::String>,
+    /// URL of the CI run that built the image.
+    #[prost(string, optional, tag = "4")]
+    pub ci_run_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Digest of the image's SBOM (sha256:...).
+    #[prost(string, optional, tag = "5")]
+    pub sbom_digest: ::core::option::Option<::prost::alloc::string::String>,
+    /// Identity of the builder (CI system, workflow, or build service).
+    #[prost(string, optional, tag = "6")]
+    pub builder: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Payload for release created events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ReleaseCreatedPayload {
@@ -772,6 +1401,9 @@ pub struct ReleaseCreatedPayload {
     /// Release command.
     #[prost(string, repeated, tag = "5")]
     pub command: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Source and build provenance, when provided.
+    #[prost(message, optional, tag = "6")]
+    pub metadata: ::core::option::Option<ReleaseMetadata>,
 }
 /// Payload for deploy created events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -803,6 +1435,12 @@ pub struct DeployCreatedPayload {
     /// Deploy initiation timestamp.
     #[prost(message, optional, tag = "9")]
     pub initiated_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// Metadata of the release being deployed, copied for traceability.
+    #[prost(message, optional, tag = "10")]
+    pub release_metadata: ::core::option::Option<ReleaseMetadata>,
+    /// Why the deploy was made, as given by the caller.
+    #[prost(string, optional, tag = "11")]
+    pub reason: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Payload for deploy status change events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -829,6 +1467,44 @@ pub struct DeployStatusChangedPayload {
     #[prost(message, optional, tag = "7")]
     pub updated_at: ::core::option::Option<::prost_types::Timestamp>,
 }
+/// Payload for deploy promotion events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeployPromotedPayload {
+    /// Deploy identifier.
+    #[prost(string, tag = "1")]
+    pub deploy_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+}
+/// Payload for stuck rollout events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeployRolloutStuckPayload {
+    /// Deploy identifier.
+    #[prost(string, tag = "1")]
+    pub deploy_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Process type that has not converged.
+    #[prost(string, tag = "4")]
+    pub process_type: ::prost::alloc::string::String,
+    /// Time spent converging, in seconds.
+    #[prost(int64, tag = "5")]
+    pub converging_secs: i64,
+    /// Convergence deadline, in seconds.
+    #[prost(int64, tag = "6")]
+    pub deadline_secs: i64,
+    /// Human-readable summary.
+    #[prost(string, tag = "7")]
+    pub message: ::prost::alloc::string::String,
+}
 /// Lifecycle status for a deploy.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -843,6 +1519,10 @@ pub enum DeployStatus {
     Succeeded = 3,
     /// Deploy failed.
     Failed = 4,
+    /// Rollout is paused by an operator.
+    Paused = 5,
+    /// Rollout was halted after exceeding its failure budget.
+    Halted = 6,
 }
 impl DeployStatus {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -856,6 +1536,8 @@ impl DeployStatus {
             Self::Rolling => "DEPLOY_STATUS_ROLLING",
             Self::Succeeded => "DEPLOY_STATUS_SUCCEEDED",
             Self::Failed => "DEPLOY_STATUS_FAILED",
+            Self::Paused => "DEPLOY_STATUS_PAUSED",
+            Self::Halted => "DEPLOY_STATUS_HALTED",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -866,6 +1548,8 @@ impl DeployStatus {
             "DEPLOY_STATUS_ROLLING" => Some(Self::Rolling),
             "DEPLOY_STATUS_SUCCEEDED" => Some(Self::Succeeded),
             "DEPLOY_STATUS_FAILED" => Some(Self::Failed),
+            "DEPLOY_STATUS_PAUSED" => Some(Self::Paused),
+            "DEPLOY_STATUS_HALTED" => Some(Self::Halted),
             _ => None,
         }
     }
@@ -917,6 +1601,57 @@ pub struct SecretBundleVersionSetPayload {
     #[prost(message, optional, tag = "7")]
     pub updated_at: ::core::option::Option<::prost_types::Timestamp>,
 }
+/// Payload for secret bundle archival during environment teardown.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SecretBundleArchivedPayload {
+    /// Secret bundle identifier.
+    #[prost(string, tag = "1")]
+    pub bundle_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+}
+/// Payload for secrets key rotation jobs being started.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeyRotationStartedPayload {
+    /// Key rotation job identifier.
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
+    /// Organization the job is restricted to.
+    #[prost(string, optional, tag = "2")]
+    pub org_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Platform key ID material is re-wrapped under.
+    #[prost(string, tag = "3")]
+    pub target_key_id: ::prost::alloc::string::String,
+    /// Rows in scope when the job started.
+    #[prost(int64, tag = "4")]
+    pub total: i64,
+}
+/// Payload for secrets key rotation jobs finishing.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeyRotationCompletedPayload {
+    /// Key rotation job identifier.
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
+    /// Outcome: completed, failed, or cancelled.
+    #[prost(string, tag = "2")]
+    pub outcome: ::prost::alloc::string::String,
+    /// Rows examined.
+    #[prost(int64, tag = "3")]
+    pub scanned: i64,
+    /// Rows re-wrapped under the current key.
+    #[prost(int64, tag = "4")]
+    pub rewrapped: i64,
+    /// Rows that could not be re-wrapped.
+    #[prost(int64, tag = "5")]
+    pub failed: i64,
+    /// Error that stopped the job, if any.
+    #[prost(string, optional, tag = "6")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Resource snapshot captured for an instance.
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct InstanceResourcesSnapshot {
@@ -969,6 +1704,9 @@ pub struct InstanceAllocatedPayload {
     /// Deterministic spec hash.
     #[prost(string, tag = "12")]
     pub spec_hash: ::prost::alloc::string::String,
+    /// Config vars version the instance boots with.
+    #[prost(int32, optional, tag = "13")]
+    pub config_vars_version: ::core::option::Option<i32>,
 }
 /// Payload for desired state change events.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -992,6 +1730,25 @@ pub struct InstanceDesiredStateChangedPayload {
     #[prost(string, optional, tag = "6")]
     pub reason: ::core::option::Option<::prost::alloc::string::String>,
 }
+/// Payload for in-place config update events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InstanceConfigUpdatedPayload {
+    /// Instance identifier.
+    #[prost(string, tag = "1")]
+    pub instance_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Environment identifier.
+    #[prost(string, tag = "3")]
+    pub env_id: ::prost::alloc::string::String,
+    /// Secret version delivered to the instance.
+    #[prost(string, optional, tag = "4")]
+    pub secrets_version_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Reason for the update.
+    #[prost(string, optional, tag = "5")]
+    pub reason: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Payload for instance status change events.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct InstanceStatusChangedPayload {
@@ -1028,6 +1785,37 @@ pub struct InstanceStatusChangedPayload {
     /// Status report timestamp.
     #[prost(message, optional, tag = "11")]
     pub reported_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// Plan generation the reporting boot runs.
+    #[prost(int32, optional, tag = "12")]
+    pub generation: ::core::option::Option<i32>,
+}
+/// Payload for instance drift detection events.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InstanceDriftDetectedPayload {
+    /// Instance identifier.
+    #[prost(string, tag = "1")]
+    pub instance_id: ::prost::alloc::string::String,
+    /// Node the drift was observed on.
+    #[prost(string, tag = "2")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Organization identifier, when the instance is known.
+    #[prost(string, optional, tag = "3")]
+    pub org_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Environment identifier, when the instance is known.
+    #[prost(string, optional, tag = "4")]
+    pub env_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Kind of drift.
+    #[prost(enumeration = "InstanceDriftKind", tag = "5")]
+    pub kind: i32,
+    /// Desired state recorded for the instance, when known.
+    #[prost(string, optional, tag = "6")]
+    pub desired_state: ::core::option::Option<::prost::alloc::string::String>,
+    /// Status last reported by the node, when reported.
+    #[prost(string, optional, tag = "7")]
+    pub reported_status: ::core::option::Option<::prost::alloc::string::String>,
+    /// Detection timestamp.
+    #[prost(message, optional, tag = "8")]
+    pub detected_at: ::core::option::Option<::prost_types::Timestamp>,
 }
 /// Desired lifecycle state for an instance.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
@@ -1141,6 +1929,14 @@ pub enum InstanceFailureReason {
     TerminatedByOperator = 11,
     /// Instance drained due to node maintenance.
     NodeDraining = 12,
+    /// Guest kernel panicked during boot.
+    KernelPanic = 13,
+    /// Guest never completed the config handshake.
+    ConfigHandshakeTimeout = 14,
+    /// Host could not fetch the secrets bundle for the instance.
+    SecretsFetchFailed = 15,
+    /// Guest init failed for a reason not covered above.
+    GuestInitFailed = 16,
 }
 impl InstanceFailureReason {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1168,6 +1964,12 @@ impl InstanceFailureReason {
                 "INSTANCE_FAILURE_REASON_TERMINATED_BY_OPERATOR"
             }
             Self::NodeDraining => "INSTANCE_FAILURE_REASON_NODE_DRAINING",
+            Self::KernelPanic => "INSTANCE_FAILURE_REASON_KERNEL_PANIC",
+            Self::ConfigHandshakeTimeout => {
+                "INSTANCE_FAILURE_REASON_CONFIG_HANDSHAKE_TIMEOUT"
+            }
+            Self::SecretsFetchFailed => "INSTANCE_FAILURE_REASON_SECRETS_FETCH_FAILED",
+            Self::GuestInitFailed => "INSTANCE_FAILURE_REASON_GUEST_INIT_FAILED",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -1198,6 +2000,47 @@ impl InstanceFailureReason {
                 Some(Self::TerminatedByOperator)
             }
             "INSTANCE_FAILURE_REASON_NODE_DRAINING" => Some(Self::NodeDraining),
+            "INSTANCE_FAILURE_REASON_KERNEL_PANIC" => Some(Self::KernelPanic),
+            "INSTANCE_FAILURE_REASON_CONFIG_HANDSHAKE_TIMEOUT" => {
+                Some(Self::ConfigHandshakeTimeout)
+            }
+            "INSTANCE_FAILURE_REASON_SECRETS_FETCH_FAILED" => {
+                Some(Self::SecretsFetchFailed)
+            }
+            "INSTANCE_FAILURE_REASON_GUEST_INIT_FAILED" => Some(Self::GuestInitFailed),
+            _ => None,
+        }
+    }
+}
+/// Mismatch between desired instances and node-reported instances.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum InstanceDriftKind {
+    /// Drift kind is unspecified.
+    Unspecified = 0,
+    /// Instance is desired on the node but the node does not report it.
+    NotReported = 1,
+    /// Node reports the instance but it is not desired there.
+    NotDesired = 2,
+}
+impl InstanceDriftKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "INSTANCE_DRIFT_KIND_UNSPECIFIED",
+            Self::NotReported => "INSTANCE_DRIFT_KIND_NOT_REPORTED",
+            Self::NotDesired => "INSTANCE_DRIFT_KIND_NOT_DESIRED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "INSTANCE_DRIFT_KIND_UNSPECIFIED" => Some(Self::Unspecified),
+            "INSTANCE_DRIFT_KIND_NOT_REPORTED" => Some(Self::NotReported),
+            "INSTANCE_DRIFT_KIND_NOT_DESIRED" => Some(Self::NotDesired),
             _ => None,
         }
     }
@@ -1252,6 +2095,87 @@ pub struct NodeCapacityUpdatedPayload {
     /// Active instance count.
     #[prost(int32, tag = "4")]
     pub instance_count: i32,
+    /// Bytes allocated on the host by instance scratch disks.
+    #[prost(int64, optional, tag = "5")]
+    pub ephemeral_disk_used_bytes: ::core::option::Option<i64>,
+    /// Free bytes on the filesystem holding scratch disks.
+    #[prost(int64, optional, tag = "6")]
+    pub ephemeral_disk_available_bytes: ::core::option::Option<i64>,
+}
+/// Payload for node enrollment requests awaiting operator approval.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeEnrollmentRequestedPayload {
+    /// Node identifier.
+    #[prost(string, tag = "1")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Node hostname.
+    #[prost(string, tag = "2")]
+    pub hostname: ::prost::alloc::string::String,
+    /// Node region label.
+    #[prost(string, tag = "3")]
+    pub region: ::prost::alloc::string::String,
+    /// CPU core count.
+    #[prost(int32, tag = "4")]
+    pub cpu_cores: i32,
+    /// Total memory in bytes.
+    #[prost(int64, tag = "5")]
+    pub memory_bytes: i64,
+    /// Bootstrap token consumed by the enrollment.
+    #[prost(string, optional, tag = "6")]
+    pub bootstrap_token_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Payload for approved node enrollments.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeEnrollmentApprovedPayload {
+    /// Node identifier.
+    #[prost(string, tag = "1")]
+    pub node_id: ::prost::alloc::string::String,
+}
+/// Payload for denied node enrollments.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeEnrollmentDeniedPayload {
+    /// Node identifier.
+    #[prost(string, tag = "1")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Reason for the denial.
+    #[prost(string, optional, tag = "2")]
+    pub reason: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Payload for node label updates.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeLabelsUpdatedPayload {
+    /// Node identifier.
+    #[prost(string, tag = "1")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Full label set after the update.
+    #[prost(map = "string, string", tag = "2")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+/// A taint that repels instances without a matching toleration.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeTaint {
+    /// Taint key.
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Taint value.
+    #[prost(string, optional, tag = "2")]
+    pub value: ::core::option::Option<::prost::alloc::string::String>,
+    /// Scheduling effect.
+    #[prost(enumeration = "TaintEffect", tag = "3")]
+    pub effect: i32,
+}
+/// Payload for node taint updates.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeTaintsUpdatedPayload {
+    /// Node identifier.
+    #[prost(string, tag = "1")]
+    pub node_id: ::prost::alloc::string::String,
+    /// Full taint set after the update.
+    #[prost(message, repeated, tag = "2")]
+    pub taints: ::prost::alloc::vec::Vec<NodeTaint>,
 }
 /// Operational state of a node.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
@@ -1269,6 +2193,8 @@ pub enum NodeState {
     Degraded = 4,
     /// Node is offline.
     Offline = 5,
+    /// Node is enrolled but awaiting operator approval.
+    PendingApproval = 6,
 }
 impl NodeState {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1283,6 +2209,7 @@ impl NodeState {
             Self::Disabled => "NODE_STATE_DISABLED",
             Self::Degraded => "NODE_STATE_DEGRADED",
             Self::Offline => "NODE_STATE_OFFLINE",
+            Self::PendingApproval => "NODE_STATE_PENDING_APPROVAL",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -1294,6 +2221,40 @@ impl NodeState {
             "NODE_STATE_DISABLED" => Some(Self::Disabled),
             "NODE_STATE_DEGRADED" => Some(Self::Degraded),
             "NODE_STATE_OFFLINE" => Some(Self::Offline),
+            "NODE_STATE_PENDING_APPROVAL" => Some(Self::PendingApproval),
+            _ => None,
+        }
+    }
+}
+/// Scheduling effect of a node taint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TaintEffect {
+    /// Effect is unspecified.
+    Unspecified = 0,
+    /// Instances that do not tolerate the taint are never placed on the node.
+    NoSchedule = 1,
+    /// The scheduler avoids the node for instances that do not tolerate the taint.
+    PreferNoSchedule = 2,
+}
+impl TaintEffect {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "TAINT_EFFECT_UNSPECIFIED",
+            Self::NoSchedule => "TAINT_EFFECT_NO_SCHEDULE",
+            Self::PreferNoSchedule => "TAINT_EFFECT_PREFER_NO_SCHEDULE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TAINT_EFFECT_UNSPECIFIED" => Some(Self::Unspecified),
+            "TAINT_EFFECT_NO_SCHEDULE" => Some(Self::NoSchedule),
+            "TAINT_EFFECT_PREFER_NO_SCHEDULE" => Some(Self::PreferNoSchedule),
             _ => None,
         }
     }
@@ -1364,3 +2325,68 @@ pub struct ExecSessionEndedPayload {
     #[prost(string, optional, tag = "6")]
     pub end_reason: ::core::option::Option<::prost::alloc::string::String>,
 }
+/// Payload for alerts whose condition has held for the rule's duration.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AlertFiredPayload {
+    /// Alert rule identifier.
+    #[prost(string, tag = "1")]
+    pub rule_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Alert rule name.
+    #[prost(string, tag = "3")]
+    pub rule_name: ::prost::alloc::string::String,
+    /// Condition type (env_unhealthy, node_offline, projection_lag, instance_restarts).
+    #[prost(string, tag = "4")]
+    pub condition: ::prost::alloc::string::String,
+    /// What the condition holds on (env, node, or projection).
+    #[prost(string, tag = "5")]
+    pub target: ::prost::alloc::string::String,
+    /// Application identifier, for env conditions.
+    #[prost(string, optional, tag = "6")]
+    pub app_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Environment identifier, for env conditions.
+    #[prost(string, optional, tag = "7")]
+    pub env_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Observed value, for threshold conditions.
+    #[prost(int64, optional, tag = "8")]
+    pub value: ::core::option::Option<i64>,
+    /// Rule threshold, for threshold conditions.
+    #[prost(int64, optional, tag = "9")]
+    pub threshold: ::core::option::Option<i64>,
+    /// Human-readable description of the breach.
+    #[prost(string, tag = "10")]
+    pub summary: ::prost::alloc::string::String,
+    /// When the condition started holding (RFC 3339).
+    #[prost(string, tag = "11")]
+    pub pending_since: ::prost::alloc::string::String,
+}
+/// Payload for firing alerts whose condition no longer holds.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AlertResolvedPayload {
+    /// Alert rule identifier.
+    #[prost(string, tag = "1")]
+    pub rule_id: ::prost::alloc::string::String,
+    /// Organization identifier.
+    #[prost(string, tag = "2")]
+    pub org_id: ::prost::alloc::string::String,
+    /// Alert rule name.
+    #[prost(string, tag = "3")]
+    pub rule_name: ::prost::alloc::string::String,
+    /// Condition type.
+    #[prost(string, tag = "4")]
+    pub condition: ::prost::alloc::string::String,
+    /// What the condition held on.
+    #[prost(string, tag = "5")]
+    pub target: ::prost::alloc::string::String,
+    /// Application identifier, for env conditions.
+    #[prost(string, optional, tag = "6")]
+    pub app_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Environment identifier, for env conditions.
+    #[prost(string, optional, tag = "7")]
+    pub env_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// When the alert fired (RFC 3339).
+    #[prost(string, tag = "8")]
+    pub fired_at: ::prost::alloc::string::String,
+}
@@ -0,0 +1,2192 @@
+// This file is @generated by libs/proto/build.rs from api/errors/catalog.json.
+// Do not edit by hand.
+
+/// Error domains.
+pub mod domains {
+    /// Authentication and authorization
+    pub const AUTH: &str = "auth";
+    /// Organizations and quotas
+    pub const ORGS: &str = "orgs";
+    /// Org membership
+    pub const MEMBERS: &str = "members";
+    /// Projects
+    pub const PROJECTS: &str = "projects";
+    /// Apps
+    pub const APPS: &str = "apps";
+    /// Environments, scale, and process types
+    pub const ENVS: &str = "envs";
+    /// Releases and manifests
+    pub const RELEASES: &str = "releases";
+    /// Deploys and rollbacks
+    pub const DEPLOYS: &str = "deploys";
+    /// Instances
+    pub const INSTANCES: &str = "instances";
+    /// Routes and hostnames
+    pub const ROUTES: &str = "routes";
+    /// Address allocation
+    pub const NETWORKING: &str = "networking";
+    /// Secrets
+    pub const SECRETS: &str = "secrets";
+    /// Volumes, attachments, and snapshots
+    pub const VOLUMES: &str = "volumes";
+    /// Deleted resources awaiting purge, and restoring them
+    pub const TRASH: &str = "trash";
+    /// Exec sessions
+    pub const EXEC: &str = "exec";
+    /// Notification channels and rules
+    pub const NOTIFICATIONS: &str = "notifications";
+    /// Alert rules
+    pub const ALERTS: &str = "alerts";
+    /// Public org status pages
+    pub const STATUS_PAGES: &str = "status_pages";
+    /// Environment availability objectives
+    pub const SLO: &str = "slo";
+    /// Nodes (infrastructure)
+    pub const NODES: &str = "nodes";
+    /// Platform certificate authority
+    pub const PKI: &str = "pki";
+    /// Generic request validation
+    pub const REQUEST: &str = "request";
+    /// Idempotency and optimistic concurrency
+    pub const CONCURRENCY: &str = "concurrency";
+    /// Server-side failures
+    pub const SERVER: &str = "server";
+}
+
+/// Stable error codes.
+pub mod codes {
+    /// The user denied the device authorization request.
+    pub const ACCESS_DENIED: &str = "access_denied";
+    /// Device authorization has not been approved yet; keep polling.
+    pub const AUTHORIZATION_PENDING: &str = "authorization_pending";
+    /// The device code expired before it was approved.
+    pub const EXPIRED_TOKEN: &str = "expired_token";
+    /// The caller lacks permission for this operation.
+    pub const FORBIDDEN: &str = "forbidden";
+    /// The Authorization header is malformed.
+    pub const INVALID_AUTHORIZATION: &str = "invalid_authorization";
+    /// Client credentials are invalid.
+    pub const INVALID_CLIENT: &str = "invalid_client";
+    /// The grant (device code or refresh token) is invalid.
+    pub const INVALID_GRANT: &str = "invalid_grant";
+    /// A requested scope is not recognized.
+    pub const INVALID_SCOPE: &str = "invalid_scope";
+    /// The access token is invalid.
+    pub const INVALID_TOKEN: &str = "invalid_token";
+    /// Device token polling is too frequent; increase the interval.
+    pub const SLOW_DOWN: &str = "slow_down";
+    /// The token has already been used.
+    pub const TOKEN_CONSUMED: &str = "token_consumed";
+    /// The access token has expired.
+    pub const TOKEN_EXPIRED: &str = "token_expired";
+    /// The access token has been revoked.
+    pub const TOKEN_REVOKED: &str = "token_revoked";
+    /// Authentication is required.
+    pub const UNAUTHORIZED: &str = "unauthorized";
+    /// The grant type is not supported.
+    pub const UNSUPPORTED_GRANT_TYPE: &str = "unsupported_grant_type";
+    /// The org ID is malformed.
+    pub const INVALID_ORG_ID: &str = "invalid_org_id";
+    /// Quota limits must not be negative.
+    pub const INVALID_QUOTA_LIMIT: &str = "invalid_quota_limit";
+    /// The org does not exist or is not visible to the caller.
+    pub const ORG_NOT_FOUND: &str = "org_not_found";
+    /// No quota dimension with this name exists.
+    pub const QUOTA_DIMENSION_NOT_FOUND: &str = "quota_dimension_not_found";
+    /// The operation would exceed an org quota.
+    pub const QUOTA_EXCEEDED: &str = "quota_exceeded";
+    /// The email address is invalid.
+    pub const INVALID_EMAIL: &str = "invalid_email";
+    /// The member ID is malformed.
+    pub const INVALID_MEMBER_ID: &str = "invalid_member_id";
+    /// The org must keep at least one owner.
+    pub const LAST_OWNER: &str = "last_owner";
+    /// A member with this email already exists in the org.
+    pub const MEMBER_ALREADY_EXISTS: &str = "member_already_exists";
+    /// The member does not exist.
+    pub const MEMBER_NOT_FOUND: &str = "member_not_found";
+    /// The project ID is malformed.
+    pub const INVALID_PROJECT_ID: &str = "invalid_project_id";
+    /// A project with this name already exists in the org.
+    pub const PROJECT_NAME_EXISTS: &str = "project_name_exists";
+    /// The project does not exist.
+    pub const PROJECT_NOT_FOUND: &str = "project_not_found";
+    /// An app with this name already exists in the org.
+    pub const APP_NAME_EXISTS: &str = "app_name_exists";
+    /// The app does not exist.
+    pub const APP_NOT_FOUND: &str = "app_not_found";
+    /// No deletion is in progress for the resource.
+    pub const DELETION_NOT_FOUND: &str = "deletion_not_found";
+    /// The app ID is malformed.
+    pub const INVALID_APP_ID: &str = "invalid_app_id";
+    /// The environment is being deleted and no longer accepts changes.
+    pub const ENV_DELETION_PENDING: &str = "env_deletion_pending";
+    /// An env with this name already exists in the app.
+    pub const ENV_NAME_EXISTS: &str = "env_name_exists";
+    /// The env does not exist.
+    pub const ENV_NOT_FOUND: &str = "env_not_found";
+    /// The config vars are invalid.
+    pub const INVALID_CONFIG_VARS: &str = "invalid_config_vars";
+    /// The desired scale is invalid.
+    pub const INVALID_DESIRED: &str = "invalid_desired";
+    /// The env ID is malformed.
+    pub const INVALID_ENV_ID: &str = "invalid_env_id";
+    /// The per-instance scratch disk size is outside 1 GiB to 1 TiB.
+    pub const INVALID_EPHEMERAL_DISK_BYTES: &str = "invalid_ephemeral_disk_bytes";
+    /// The preview env settings are invalid.
+    pub const INVALID_PREVIEW: &str = "invalid_preview";
+    /// The process type name is invalid.
+    pub const INVALID_PROCESS_TYPE: &str = "invalid_process_type";
+    /// One or more process type names are invalid.
+    pub const INVALID_PROCESS_TYPES: &str = "invalid_process_types";
+    /// The process resource overrides are out of bounds.
+    pub const INVALID_RESOURCES: &str = "invalid_resources";
+    /// The secrets reload mode is invalid.
+    pub const INVALID_SECRETS_RELOAD: &str = "invalid_secrets_reload";
+    /// The process type is not part of the current release.
+    pub const PROCESS_TYPE_NOT_DEPLOYED: &str = "process_type_not_deployed";
+    /// The process type is not part of the env's desired release.
+    pub const UNKNOWN_PROCESS_TYPE: &str = "unknown_process_type";
+    /// The manifest defines a process type more than once.
+    pub const DUPLICATE_PROCESS_TYPE: &str = "duplicate_process_type";
+    /// The CPU request is invalid.
+    pub const INVALID_CPU_CORES: &str = "invalid_cpu_cores";
+    /// The image digest is invalid.
+    pub const INVALID_IMAGE_DIGEST: &str = "invalid_image_digest";
+    /// The image reference is invalid.
+    pub const INVALID_IMAGE_REF: &str = "invalid_image_ref";
+    /// The manifest hash is invalid.
+    pub const INVALID_MANIFEST_HASH: &str = "invalid_manifest_hash";
+    /// The memory request is invalid.
+    pub const INVALID_MEMORY: &str = "invalid_memory";
+    /// The process definitions are invalid.
+    pub const INVALID_PROCESSES: &str = "invalid_processes";
+    /// The release ID is malformed.
+    pub const INVALID_RELEASE_ID: &str = "invalid_release_id";
+    /// The release metadata is invalid.
+    pub const INVALID_RELEASE_METADATA: &str = "invalid_release_metadata";
+    /// The release does not exist.
+    pub const RELEASE_NOT_FOUND: &str = "release_not_found";
+    /// The deploy does not exist.
+    pub const DEPLOY_NOT_FOUND: &str = "deploy_not_found";
+    /// The deploy ID is malformed.
+    pub const INVALID_DEPLOY_ID: &str = "invalid_deploy_id";
+    /// The deploy is not in a state that allows this operation.
+    pub const INVALID_DEPLOY_STATE: &str = "invalid_deploy_state";
+    /// No serial console output has been captured for the instance.
+    pub const CONSOLE_NOT_FOUND: &str = "console_not_found";
+    /// The instance does not exist.
+    pub const INSTANCE_NOT_FOUND: &str = "instance_not_found";
+    /// The instance is not ready yet.
+    pub const INSTANCE_NOT_READY: &str = "instance_not_ready";
+    /// The instance is not running.
+    pub const INSTANCE_NOT_RUNNING: &str = "instance_not_running";
+    /// The instance ID is malformed.
+    pub const INVALID_INSTANCE_ID: &str = "invalid_instance_id";
+    /// The uploaded certificate does not name the route's hostname.
+    pub const CERTIFICATE_HOSTNAME_MISMATCH: &str = "certificate_hostname_mismatch";
+    /// The uploaded private key does not belong to the leaf certificate.
+    pub const CERTIFICATE_KEY_MISMATCH: &str = "certificate_key_mismatch";
+    /// The uploaded certificate is expired or not yet valid.
+    pub const CERTIFICATE_NOT_VALID: &str = "certificate_not_valid";
+    /// Another certificate is already the default for non-SNI clients on this listen port.
+    pub const DEFAULT_CERTIFICATE_TAKEN: &str = "default_certificate_taken";
+    /// The hostname is already bound to another route.
+    pub const HOSTNAME_IN_USE: &str = "hostname_in_use";
+    /// DNS does not yet prove ownership of the route hostname.
+    pub const HOSTNAME_NOT_VERIFIED: &str = "hostname_not_verified";
+    /// The hostname is inside a platform-reserved namespace (the managed apps domain or the internal route suffix).
+    pub const HOSTNAME_RESERVED: &str = "hostname_reserved";
+    /// The route access policy is invalid: a malformed CIDR or fingerprint, too many entries, or fingerprint rules on a tcp_raw route.
+    pub const INVALID_ACCESS_POLICY: &str = "invalid_access_policy";
+    /// The uploaded certificate chain or private key could not be parsed.
+    pub const INVALID_CERTIFICATE: &str = "invalid_certificate";
+    /// The hostname is invalid.
+    pub const INVALID_HOSTNAME: &str = "invalid_hostname";
+    /// The ingress status report is invalid: a malformed ingress ID, route ID, public IP, or backend count.
+    pub const INVALID_INGRESS_REPORT: &str = "invalid_ingress_report";
+    /// The internal route is invalid: its hostname is outside the internal suffix or it requires IPv4.
+    pub const INVALID_INTERNAL_ROUTE: &str = "invalid_internal_route";
+    /// The proxy protocol setting is invalid.
+    pub const INVALID_PROXY_PROTOCOL: &str = "invalid_proxy_protocol";
+    /// The route ID is malformed.
+    pub const INVALID_ROUTE_ID: &str = "invalid_route_id";
+    /// The route has no uploaded certificate.
+    pub const ROUTE_CERTIFICATE_NOT_FOUND: &str = "route_certificate_not_found";
+    /// The route does not exist.
+    pub const ROUTE_NOT_FOUND: &str = "route_not_found";
+    /// Certificates can only be uploaded for routes with protocol_hint tls_terminate.
+    pub const ROUTE_NOT_TLS_TERMINATE: &str = "route_not_tls_terminate";
+    /// The route requires the env IPv4 add-on.
+    pub const ROUTES_REQUIRE_IPV4: &str = "routes_require_ipv4";
+    /// Address allocation failed.
+    pub const IPAM_ERROR: &str = "ipam_error";
+    /// No IPv4 addresses are available.
+    pub const IPV4_POOL_EXHAUSTED: &str = "ipv4_pool_exhausted";
+    /// The secret version ID is malformed.
+    pub const INVALID_SECRET_VERSION_ID: &str = "invalid_secret_version_id";
+    /// The secrets bundle is malformed.
+    pub const INVALID_SECRETS_FORMAT: &str = "invalid_secrets_format";
+    /// A key rotation job is already running.
+    pub const KEY_ROTATION_IN_PROGRESS: &str = "key_rotation_in_progress";
+    /// The key rotation job does not exist.
+    pub const KEY_ROTATION_NOT_FOUND: &str = "key_rotation_not_found";
+    /// The key rotation job has already finished.
+    pub const KEY_ROTATION_NOT_RUNNING: &str = "key_rotation_not_running";
+    /// The secret version does not exist.
+    pub const SECRET_VERSION_NOT_FOUND: &str = "secret_version_not_found";
+    /// Stored secrets could not be decoded.
+    pub const SECRETS_DECODE_FAILED: &str = "secrets_decode_failed";
+    /// Stored secrets could not be decrypted.
+    pub const SECRETS_DECRYPT_FAILED: &str = "secrets_decrypt_failed";
+    /// Secrets could not be encrypted.
+    pub const SECRETS_ENCRYPTION_FAILED: &str = "secrets_encryption_failed";
+    /// No secrets have been set for the env.
+    pub const SECRETS_NOT_CONFIGURED: &str = "secrets_not_configured";
+    /// Secret data keys could not be re-wrapped because a key encryption key is unavailable.
+    pub const SECRETS_REWRAP_FAILED: &str = "secrets_rewrap_failed";
+    /// The secrets bundle exceeds the size limit.
+    pub const SECRETS_TOO_LARGE: &str = "secrets_too_large";
+    /// The stored secrets use an unsupported cipher.
+    pub const UNSUPPORTED_CIPHER: &str = "unsupported_cipher";
+    /// The volume has several attachments; the request must select one.
+    pub const AMBIGUOUS_ATTACHMENT: &str = "ambiguous_attachment";
+    /// The volume is already attached.
+    pub const ATTACHMENT_EXISTS: &str = "attachment_exists";
+    /// The volume attachment does not exist.
+    pub const ATTACHMENT_NOT_FOUND: &str = "attachment_not_found";
+    /// The volume attachment ID is malformed.
+    pub const INVALID_ATTACHMENT_ID: &str = "invalid_attachment_id";
+    /// Backup retention is invalid: retain_count must be at least 1, retain_max_age_secs at least one hour, and retention requires a schedule.
+    pub const INVALID_BACKUP_RETENTION: &str = "invalid_backup_retention";
+    /// The backup schedule is not a valid five-field cron expression.
+    pub const INVALID_BACKUP_SCHEDULE: &str = "invalid_backup_schedule";
+    /// The filesystem type is not supported.
+    pub const INVALID_FILESYSTEM: &str = "invalid_filesystem";
+    /// The mount path is invalid.
+    pub const INVALID_MOUNT_PATH: &str = "invalid_mount_path";
+    /// The name for the restored volume is invalid.
+    pub const INVALID_NEW_VOLUME_NAME: &str = "invalid_new_volume_name";
+    /// The volume size is invalid.
+    pub const INVALID_SIZE_BYTES: &str = "invalid_size_bytes";
+    /// The snapshot ID is malformed.
+    pub const INVALID_SNAPSHOT_ID: &str = "invalid_snapshot_id";
+    /// The volume ID is malformed.
+    pub const INVALID_VOLUME_ID: &str = "invalid_volume_id";
+    /// The snapshot does not exist.
+    pub const SNAPSHOT_NOT_FOUND: &str = "snapshot_not_found";
+    /// The snapshot has not succeeded, so it cannot be restored in place.
+    pub const SNAPSHOT_NOT_READY: &str = "snapshot_not_ready";
+    /// A process type mounting the volume still has instances; scale it to zero or detach the volume.
+    pub const VOLUME_ATTACHMENT_ACTIVE: &str = "volume_attachment_active";
+    /// The process type already mounts a volume on a different node.
+    pub const VOLUME_HOME_NODE_CONFLICT: &str = "volume_home_node_conflict";
+    /// The volume is already attached elsewhere.
+    pub const VOLUME_IN_USE: &str = "volume_in_use";
+    /// Several volumes in the organization have this name.
+    pub const VOLUME_NAME_AMBIGUOUS: &str = "volume_name_ambiguous";
+    /// A volume with this name already exists with different settings.
+    pub const VOLUME_NAME_EXISTS: &str = "volume_name_exists";
+    /// The volume does not exist.
+    pub const VOLUME_NOT_FOUND: &str = "volume_not_found";
+    /// The volume has no home node yet.
+    pub const VOLUME_NOT_PLACED: &str = "volume_not_placed";
+    /// An in-place restore of the volume has not finished yet.
+    pub const VOLUME_RESTORE_IN_PROGRESS: &str = "volume_restore_in_progress";
+    /// Volumes can only grow; the requested size is smaller than the current size.
+    pub const VOLUME_SHRINK_NOT_SUPPORTED: &str = "volume_shrink_not_supported";
+    /// The resource is still being torn down; restore it once deletion completes.
+    pub const RESTORE_DELETION_IN_PROGRESS: &str = "restore_deletion_in_progress";
+    /// The resource belongs to a deleted app or env; restore that first.
+    pub const RESTORE_PARENT_DELETED: &str = "restore_parent_deleted";
+    /// The preview env is past its expiry and would be torn down again.
+    pub const RESTORE_PREVIEW_EXPIRED: &str = "restore_preview_expired";
+    /// The resource was purged from the trash and can no longer be restored.
+    pub const RESTORE_WINDOW_EXPIRED: &str = "restore_window_expired";
+    /// The exec connection to the node failed.
+    pub const EXEC_PROXY_FAILED: &str = "exec_proxy_failed";
+    /// Too many exec sessions were requested.
+    pub const EXEC_RATE_LIMITED: &str = "exec_rate_limited";
+    /// The exec session grant has expired.
+    pub const EXEC_SESSION_EXPIRED: &str = "exec_session_expired";
+    /// The exec session does not exist.
+    pub const EXEC_SESSION_NOT_FOUND: &str = "exec_session_not_found";
+    /// The exec session is not in the granted state.
+    pub const EXEC_SESSION_NOT_GRANTED: &str = "exec_session_not_granted";
+    /// The exec command is invalid.
+    pub const INVALID_COMMAND: &str = "invalid_command";
+    /// The exec session ID is malformed.
+    pub const INVALID_EXEC_SESSION_ID: &str = "invalid_exec_session_id";
+    /// The channel config or secret is invalid for its kind.
+    pub const INVALID_NOTIFICATION_CHANNEL: &str = "invalid_notification_channel";
+    /// The rule references unknown channels, apps, or envs, or has too few or too many channels.
+    pub const INVALID_NOTIFICATION_RULE: &str = "invalid_notification_rule";
+    /// A subject or body template does not parse or uses a variable the trigger does not provide.
+    pub const INVALID_NOTIFICATION_TEMPLATE: &str = "invalid_notification_template";
+    /// A notification channel with this name already exists in the org.
+    pub const NOTIFICATION_CHANNEL_NAME_EXISTS: &str = "notification_channel_name_exists";
+    /// The notification channel does not exist in the org.
+    pub const NOTIFICATION_CHANNEL_NOT_FOUND: &str = "notification_channel_not_found";
+    /// A notification rule with this name already exists in the org.
+    pub const NOTIFICATION_RULE_NAME_EXISTS: &str = "notification_rule_name_exists";
+    /// The notification rule does not exist in the org.
+    pub const NOTIFICATION_RULE_NOT_FOUND: &str = "notification_rule_not_found";
+    /// The rule condition, duration, or scope is invalid, or scopes a platform condition to an app or env.
+    pub const INVALID_ALERT_RULE: &str = "invalid_alert_rule";
+    /// An alert rule with this name already exists in the org.
+    pub const ALERT_RULE_NAME_EXISTS: &str = "alert_rule_name_exists";
+    /// The alert rule does not exist in this org.
+    pub const ALERT_RULE_NOT_FOUND: &str = "alert_rule_not_found";
+    /// The page title or the list of shown environments is invalid.
+    pub const INVALID_STATUS_PAGE: &str = "invalid_status_page";
+    /// The org has no status page, it is disabled, or the public token is not current.
+    pub const STATUS_PAGE_NOT_FOUND: &str = "status_page_not_found";
+    /// The objective is outside 50-99.999 percent, or the window is not 1d-90d.
+    pub const INVALID_SLO: &str = "invalid_slo";
+    /// No unused, unrevoked bootstrap token has this ID.
+    pub const BOOTSTRAP_TOKEN_NOT_FOUND: &str = "bootstrap_token_not_found";
+    /// Node enrollment requires a bootstrap token.
+    pub const BOOTSTRAP_TOKEN_REQUIRED: &str = "bootstrap_token_required";
+    /// The bootstrap token is unknown, expired, revoked, or already used.
+    pub const INVALID_BOOTSTRAP_TOKEN: &str = "invalid_bootstrap_token";
+    /// The requested bootstrap token lifetime is out of range.
+    pub const INVALID_BOOTSTRAP_TOKEN_TTL: &str = "invalid_bootstrap_token_ttl";
+    /// The node ID is malformed.
+    pub const INVALID_NODE_ID: &str = "invalid_node_id";
+    /// The restore ID is not a valid restore job ID.
+    pub const INVALID_RESTORE_ID: &str = "invalid_restore_id";
+    /// The reported restore status is not running, succeeded, or failed.
+    pub const INVALID_RESTORE_STATUS: &str = "invalid_restore_status";
+    /// The reported snapshot status is not running, succeeded, or failed.
+    pub const INVALID_SNAPSHOT_STATUS: &str = "invalid_snapshot_status";
+    /// The node taint is invalid.
+    pub const INVALID_TAINT: &str = "invalid_taint";
+    /// The WireGuard public key is invalid.
+    pub const INVALID_WIREGUARD_KEY: &str = "invalid_wireguard_key";
+    /// The node overlay address is invalid.
+    pub const NODE_ADDRESS_INVALID: &str = "node_address_invalid";
+    /// The node has no overlay address.
+    pub const NODE_ADDRESS_MISSING: &str = "node_address_missing";
+    /// An operator denied the node's enrollment.
+    pub const NODE_ENROLLMENT_DENIED: &str = "node_enrollment_denied";
+    /// The client certificate subject does not match the subject the node enrolled with.
+    pub const NODE_IDENTITY_MISMATCH: &str = "node_identity_mismatch";
+    /// Node requests must carry the agent's client certificate subject.
+    pub const NODE_IDENTITY_REQUIRED: &str = "node_identity_required";
+    /// The node does not exist.
+    pub const NODE_NOT_FOUND: &str = "node_not_found";
+    /// The node is not awaiting enrollment approval.
+    pub const NODE_NOT_PENDING: &str = "node_not_pending";
+    /// The node is waiting for an operator to approve its enrollment.
+    pub const NODE_PENDING_APPROVAL: &str = "node_pending_approval";
+    /// No in-place restore exists with this ID.
+    pub const RESTORE_NOT_FOUND: &str = "restore_not_found";
+    /// The WireGuard key is already registered.
+    pub const WIREGUARD_KEY_EXISTS: &str = "wireguard_key_exists";
+    /// The certificate authority key could not be loaded or used.
+    pub const CA_UNAVAILABLE: &str = "ca_unavailable";
+    /// No unexpired, unrevoked certificate with this serial exists.
+    pub const CERTIFICATE_NOT_FOUND: &str = "certificate_not_found";
+    /// The identity cannot be encoded in a certificate (bad ID, common name, or DNS name).
+    pub const INVALID_CERTIFICATE_IDENTITY: &str = "invalid_certificate_identity";
+    /// The requested certificate lifetime is outside the bounds for this identity kind.
+    pub const INVALID_CERTIFICATE_TTL: &str = "invalid_certificate_ttl";
+    /// The instance identity token is missing, malformed, expired, not signed by a published key, or names a stopped instance.
+    pub const INVALID_IDENTITY_TOKEN: &str = "invalid_identity_token";
+    /// The certificate signing request is not a valid PEM-encoded PKCS#10 CSR.
+    pub const INVALID_CSR: &str = "invalid_csr";
+    /// The identity kind is not one of node, ingress, or instance.
+    pub const INVALID_IDENTITY_KIND: &str = "invalid_identity_kind";
+    /// The X-Consistency-Token header or min_event_id parameter is not a valid consistency token.
+    pub const INVALID_CONSISTENCY_TOKEN: &str = "invalid_consistency_token";
+    /// The request body could not be decompressed with its Content-Encoding.
+    pub const INVALID_CONTENT_ENCODING: &str = "invalid_content_encoding";
+    /// The pagination cursor is invalid.
+    pub const INVALID_CURSOR: &str = "invalid_cursor";
+    /// The expected_version field is invalid.
+    pub const INVALID_EXPECTED_VERSION: &str = "invalid_expected_version";
+    /// The Idempotency-Key header is invalid.
+    pub const INVALID_IDEMPOTENCY_KEY: &str = "invalid_idempotency_key";
+    /// The if_exists parameter is not one of fail or return.
+    pub const INVALID_IF_EXISTS: &str = "invalid_if_exists";
+    /// The If-Match header is malformed.
+    pub const INVALID_IF_MATCH: &str = "invalid_if_match";
+    /// The request body is not valid JSON for this endpoint.
+    pub const INVALID_JSON: &str = "invalid_json";
+    /// The kind filter is invalid.
+    pub const INVALID_KIND: &str = "invalid_kind";
+    /// The label selector is invalid.
+    pub const INVALID_LABEL_SELECTOR: &str = "invalid_label_selector";
+    /// The fields parameter names a field the list items do not have.
+    pub const INVALID_FIELDS: &str = "invalid_fields";
+    /// The include parameter names a relation the list does not embed.
+    pub const INVALID_INCLUDE: &str = "invalid_include";
+    /// The order_by parameter names a field the list cannot be sorted by.
+    pub const INVALID_ORDER_BY: &str = "invalid_order_by";
+    /// The labels are invalid.
+    pub const INVALID_LABELS: &str = "invalid_labels";
+    /// The resource name is invalid.
+    pub const INVALID_NAME: &str = "invalid_name";
+    /// The batch operations are invalid.
+    pub const INVALID_OPERATIONS: &str = "invalid_operations";
+    /// The query parameters are invalid.
+    pub const INVALID_QUERY: &str = "invalid_query";
+    /// The from/to range is invalid.
+    pub const INVALID_RANGE: &str = "invalid_range";
+    /// The change reason is too long or contains control characters.
+    pub const INVALID_REASON: &str = "invalid_reason";
+    /// The region is invalid.
+    pub const INVALID_REGION: &str = "invalid_region";
+    /// The request is invalid.
+    pub const INVALID_REQUEST: &str = "invalid_request";
+    /// The since timestamp is not valid RFC 3339.
+    pub const INVALID_SINCE: &str = "invalid_since";
+    /// The status value is invalid.
+    pub const INVALID_STATUS: &str = "invalid_status";
+    /// The time range is invalid.
+    pub const INVALID_TIME_RANGE: &str = "invalid_time_range";
+    /// The resource type filter is invalid.
+    pub const INVALID_TYPES: &str = "invalid_types";
+    /// The update has no valid fields.
+    pub const INVALID_UPDATE: &str = "invalid_update";
+    /// The JSON request body is nested too deeply.
+    pub const JSON_TOO_DEEP: &str = "json_too_deep";
+    /// The request body exceeds the size limit for this endpoint.
+    pub const PAYLOAD_TOO_LARGE: &str = "payload_too_large";
+    /// The request has too many entries.
+    pub const TOO_MANY_ENTRIES: &str = "too_many_entries";
+    /// The batch has too many operations.
+    pub const TOO_MANY_OPERATIONS: &str = "too_many_operations";
+    /// The request body must be application/json.
+    pub const UNSUPPORTED_MEDIA_TYPE: &str = "unsupported_media_type";
+    /// The request body's Content-Encoding is not accepted by this endpoint.
+    pub const UNSUPPORTED_CONTENT_ENCODING: &str = "unsupported_content_encoding";
+    /// The idempotency key was reused with a different request.
+    pub const IDEMPOTENCY_CONFLICT: &str = "idempotency_conflict";
+    /// A request with the same Idempotency-Key is still being processed.
+    pub const IDEMPOTENCY_IN_FLIGHT: &str = "idempotency_in_flight";
+    /// The resource version does not match If-Match or expected_version.
+    pub const PRECONDITION_FAILED: &str = "precondition_failed";
+    /// The operation requires If-Match or expected_version.
+    pub const PRECONDITION_REQUIRED: &str = "precondition_required";
+    /// The resource was modified concurrently.
+    pub const VERSION_CONFLICT: &str = "version_conflict";
+    /// An unexpected server error occurred.
+    pub const INTERNAL_ERROR: &str = "internal_error";
+    /// The control plane is read-only for maintenance.
+    pub const MAINTENANCE_MODE: &str = "maintenance_mode";
+    /// No projection with this name is registered.
+    pub const PROJECTION_NOT_FOUND: &str = "projection_not_found";
+    /// The projection must be paused before it is rebuilt.
+    pub const PROJECTION_NOT_PAUSED: &str = "projection_not_paused";
+    /// The write succeeded but the read model has not caught up yet.
+    pub const PROJECTION_TIMEOUT: &str = "projection_timeout";
+}
+
+/// Every catalogued error, in catalog order.
+pub const CATALOG: &[ErrorSpec] = &[
+    ErrorSpec {
+        code: codes::ACCESS_DENIED,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: false,
+        description: "The user denied the device authorization request.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::AUTHORIZATION_PENDING,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: true,
+        description: "Device authorization has not been approved yet; keep polling.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::EXPIRED_TOKEN,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: false,
+        description: "The device code expired before it was approved.",
+        hint: Some("Run `vt auth login` again."),
+    },
+    ErrorSpec {
+        code: codes::FORBIDDEN,
+        domain: domains::AUTH,
+        status: 403,
+        retryable: false,
+        description: "The caller lacks permission for this operation.",
+        hint: Some("Ask an org owner or admin for access."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_AUTHORIZATION,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "The Authorization header is malformed.",
+        hint: Some("Run `vt auth login` to refresh your credentials."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_CLIENT,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "Client credentials are invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_GRANT,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: false,
+        description: "The grant (device code or refresh token) is invalid.",
+        hint: Some("Run `vt auth login` again."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_SCOPE,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: false,
+        description: "A requested scope is not recognized.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_TOKEN,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "The access token is invalid.",
+        hint: Some("Run `vt auth login` to refresh your credentials."),
+    },
+    ErrorSpec {
+        code: codes::SLOW_DOWN,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: true,
+        description: "Device token polling is too frequent; increase the interval.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::TOKEN_CONSUMED,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "The token has already been used.",
+        hint: Some("Run `vt auth login` again."),
+    },
+    ErrorSpec {
+        code: codes::TOKEN_EXPIRED,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "The access token has expired.",
+        hint: Some("Run `vt auth login` to refresh your credentials."),
+    },
+    ErrorSpec {
+        code: codes::TOKEN_REVOKED,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "The access token has been revoked.",
+        hint: Some("Run `vt auth login` to refresh your credentials."),
+    },
+    ErrorSpec {
+        code: codes::UNAUTHORIZED,
+        domain: domains::AUTH,
+        status: 401,
+        retryable: false,
+        description: "Authentication is required.",
+        hint: Some("Run `vt auth login` to authenticate."),
+    },
+    ErrorSpec {
+        code: codes::UNSUPPORTED_GRANT_TYPE,
+        domain: domains::AUTH,
+        status: 400,
+        retryable: false,
+        description: "The grant type is not supported.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_ORG_ID,
+        domain: domains::ORGS,
+        status: 400,
+        retryable: false,
+        description: "The org ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_QUOTA_LIMIT,
+        domain: domains::ORGS,
+        status: 400,
+        retryable: false,
+        description: "Quota limits must not be negative.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ORG_NOT_FOUND,
+        domain: domains::ORGS,
+        status: 404,
+        retryable: false,
+        description: "The org does not exist or is not visible to the caller.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::QUOTA_DIMENSION_NOT_FOUND,
+        domain: domains::ORGS,
+        status: 404,
+        retryable: false,
+        description: "No quota dimension with this name exists.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::QUOTA_EXCEEDED,
+        domain: domains::ORGS,
+        status: 409,
+        retryable: false,
+        description: "The operation would exceed an org quota.",
+        hint: Some("Delete unused resources or request a quota increase."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_EMAIL,
+        domain: domains::MEMBERS,
+        status: 400,
+        retryable: false,
+        description: "The email address is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_MEMBER_ID,
+        domain: domains::MEMBERS,
+        status: 400,
+        retryable: false,
+        description: "The member ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::LAST_OWNER,
+        domain: domains::MEMBERS,
+        status: 409,
+        retryable: false,
+        description: "The org must keep at least one owner.",
+        hint: Some("Promote another member to owner first."),
+    },
+    ErrorSpec {
+        code: codes::MEMBER_ALREADY_EXISTS,
+        domain: domains::MEMBERS,
+        status: 409,
+        retryable: false,
+        description: "A member with this email already exists in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::MEMBER_NOT_FOUND,
+        domain: domains::MEMBERS,
+        status: 404,
+        retryable: false,
+        description: "The member does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_PROJECT_ID,
+        domain: domains::PROJECTS,
+        status: 400,
+        retryable: false,
+        description: "The project ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::PROJECT_NAME_EXISTS,
+        domain: domains::PROJECTS,
+        status: 409,
+        retryable: false,
+        description: "A project with this name already exists in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::PROJECT_NOT_FOUND,
+        domain: domains::PROJECTS,
+        status: 404,
+        retryable: false,
+        description: "The project does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::APP_NAME_EXISTS,
+        domain: domains::APPS,
+        status: 409,
+        retryable: false,
+        description: "An app with this name already exists in the org.",
+        hint: Some("Choose a different app name."),
+    },
+    ErrorSpec {
+        code: codes::APP_NOT_FOUND,
+        domain: domains::APPS,
+        status: 404,
+        retryable: false,
+        description: "The app does not exist.",
+        hint: Some("Run `vt apps list` to see available apps."),
+    },
+    ErrorSpec {
+        code: codes::DELETION_NOT_FOUND,
+        domain: domains::APPS,
+        status: 404,
+        retryable: false,
+        description: "No deletion is in progress for the resource.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_APP_ID,
+        domain: domains::APPS,
+        status: 400,
+        retryable: false,
+        description: "The app ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ENV_DELETION_PENDING,
+        domain: domains::ENVS,
+        status: 409,
+        retryable: false,
+        description: "The environment is being deleted and no longer accepts changes.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ENV_NAME_EXISTS,
+        domain: domains::ENVS,
+        status: 409,
+        retryable: false,
+        description: "An env with this name already exists in the app.",
+        hint: Some("Choose a different env name."),
+    },
+    ErrorSpec {
+        code: codes::ENV_NOT_FOUND,
+        domain: domains::ENVS,
+        status: 404,
+        retryable: false,
+        description: "The env does not exist.",
+        hint: Some("Run `vt envs list` to see available envs."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_CONFIG_VARS,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The config vars are invalid.",
+        hint: Some("Keys must match [A-Za-z_][A-Za-z0-9_]*; at most 500 vars and 256 KiB in total."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_DESIRED,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The desired scale is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_ENV_ID,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The env ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_EPHEMERAL_DISK_BYTES,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The per-instance scratch disk size is outside 1 GiB to 1 TiB.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_PREVIEW,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The preview env settings are invalid.",
+        hint: Some("Set ttl_seconds only with preview, between 1 hour and 30 days."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_PROCESS_TYPE,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The process type name is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_PROCESS_TYPES,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "One or more process type names are invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_RESOURCES,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The process resource overrides are out of bounds.",
+        hint: Some("cpu_request must be in (0, 64]; memory_limit_bytes between 64 MiB and 256 GiB; ephemeral_disk_bytes between 1 GiB and 1 TiB."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_SECRETS_RELOAD,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The secrets reload mode is invalid.",
+        hint: Some("Use restart, file, signal (SIGHUP, SIGUSR1 or SIGUSR2) or exec with a command and a timeout of 1 to 300 seconds."),
+    },
+    ErrorSpec {
+        code: codes::PROCESS_TYPE_NOT_DEPLOYED,
+        domain: domains::ENVS,
+        status: 409,
+        retryable: false,
+        description: "The process type is not part of the current release.",
+        hint: Some("Deploy a release that defines the process type first."),
+    },
+    ErrorSpec {
+        code: codes::UNKNOWN_PROCESS_TYPE,
+        domain: domains::ENVS,
+        status: 400,
+        retryable: false,
+        description: "The process type is not part of the env's desired release.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::DUPLICATE_PROCESS_TYPE,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The manifest defines a process type more than once.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_CPU_CORES,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The CPU request is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_IMAGE_DIGEST,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The image digest is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_IMAGE_REF,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The image reference is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_MANIFEST_HASH,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The manifest hash is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_MEMORY,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The memory request is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_PROCESSES,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The process definitions are invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_RELEASE_ID,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The release ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_RELEASE_METADATA,
+        domain: domains::RELEASES,
+        status: 400,
+        retryable: false,
+        description: "The release metadata is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::RELEASE_NOT_FOUND,
+        domain: domains::RELEASES,
+        status: 404,
+        retryable: false,
+        description: "The release does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::DEPLOY_NOT_FOUND,
+        domain: domains::DEPLOYS,
+        status: 404,
+        retryable: false,
+        description: "The deploy does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_DEPLOY_ID,
+        domain: domains::DEPLOYS,
+        status: 400,
+        retryable: false,
+        description: "The deploy ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_DEPLOY_STATE,
+        domain: domains::DEPLOYS,
+        status: 409,
+        retryable: false,
+        description: "The deploy is not in a state that allows this operation.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::CONSOLE_NOT_FOUND,
+        domain: domains::INSTANCES,
+        status: 404,
+        retryable: false,
+        description: "No serial console output has been captured for the instance.",
+        hint: Some("Console output is uploaded while the VM runs; retry once the instance has booted."),
+    },
+    ErrorSpec {
+        code: codes::INSTANCE_NOT_FOUND,
+        domain: domains::INSTANCES,
+        status: 404,
+        retryable: false,
+        description: "The instance does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INSTANCE_NOT_READY,
+        domain: domains::INSTANCES,
+        status: 400,
+        retryable: true,
+        description: "The instance is not ready yet.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INSTANCE_NOT_RUNNING,
+        domain: domains::INSTANCES,
+        status: 400,
+        retryable: false,
+        description: "The instance is not running.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_INSTANCE_ID,
+        domain: domains::INSTANCES,
+        status: 400,
+        retryable: false,
+        description: "The instance ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::CERTIFICATE_HOSTNAME_MISMATCH,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The uploaded certificate does not name the route's hostname.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::CERTIFICATE_KEY_MISMATCH,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The uploaded private key does not belong to the leaf certificate.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::CERTIFICATE_NOT_VALID,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The uploaded certificate is expired or not yet valid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::DEFAULT_CERTIFICATE_TAKEN,
+        domain: domains::ROUTES,
+        status: 409,
+        retryable: false,
+        description: "Another certificate is already the default for non-SNI clients on this listen port.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::HOSTNAME_IN_USE,
+        domain: domains::ROUTES,
+        status: 409,
+        retryable: false,
+        description: "The hostname is already bound to another route.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::HOSTNAME_NOT_VERIFIED,
+        domain: domains::ROUTES,
+        status: 409,
+        retryable: true,
+        description: "DNS does not yet prove ownership of the route hostname.",
+        hint: Some("Publish the TXT record (or CNAME) shown in the route verification details, then retry once DNS has propagated."),
+    },
+    ErrorSpec {
+        code: codes::HOSTNAME_RESERVED,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The hostname is inside a platform-reserved namespace (the managed apps domain or the internal route suffix).",
+        hint: Some("Use the environment's managed_hostname, a hostname under a domain you own, or create the route with internal: true."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_ACCESS_POLICY,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The route access policy is invalid: a malformed CIDR or fingerprint, too many entries, or fingerprint rules on a tcp_raw route.",
+        hint: Some("Use CIDRs like 203.0.113.0/24, JA3 hashes (32 hex digits) or JA4 strings, at most 256 per list."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_CERTIFICATE,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The uploaded certificate chain or private key could not be parsed.",
+        hint: Some("Send the leaf certificate first, then intermediates, as PEM; the key as PEM."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_HOSTNAME,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The hostname is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_INGRESS_REPORT,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The ingress status report is invalid: a malformed ingress ID, route ID, public IP, or backend count.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_INTERNAL_ROUTE,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The internal route is invalid: its hostname is outside the internal suffix or it requires IPv4.",
+        hint: Some("Use a hostname ending in the internal route suffix (default .internal) and leave ipv4_required unset."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_PROXY_PROTOCOL,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The proxy protocol setting is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_ROUTE_ID,
+        domain: domains::ROUTES,
+        status: 400,
+        retryable: false,
+        description: "The route ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ROUTE_CERTIFICATE_NOT_FOUND,
+        domain: domains::ROUTES,
+        status: 404,
+        retryable: false,
+        description: "The route has no uploaded certificate.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ROUTE_NOT_FOUND,
+        domain: domains::ROUTES,
+        status: 404,
+        retryable: false,
+        description: "The route does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ROUTE_NOT_TLS_TERMINATE,
+        domain: domains::ROUTES,
+        status: 409,
+        retryable: false,
+        description: "Certificates can only be uploaded for routes with protocol_hint tls_terminate.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ROUTES_REQUIRE_IPV4,
+        domain: domains::ROUTES,
+        status: 409,
+        retryable: false,
+        description: "The route requires the env IPv4 add-on.",
+        hint: Some("Enable IPv4 on the env first."),
+    },
+    ErrorSpec {
+        code: codes::IPAM_ERROR,
+        domain: domains::NETWORKING,
+        status: 500,
+        retryable: true,
+        description: "Address allocation failed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::IPV4_POOL_EXHAUSTED,
+        domain: domains::NETWORKING,
+        status: 409,
+        retryable: false,
+        description: "No IPv4 addresses are available.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SECRET_VERSION_ID,
+        domain: domains::SECRETS,
+        status: 400,
+        retryable: false,
+        description: "The secret version ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SECRETS_FORMAT,
+        domain: domains::SECRETS,
+        status: 400,
+        retryable: false,
+        description: "The secrets bundle is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::KEY_ROTATION_IN_PROGRESS,
+        domain: domains::SECRETS,
+        status: 409,
+        retryable: true,
+        description: "A key rotation job is already running.",
+        hint: Some("Wait for the running job to finish or cancel it before starting another."),
+    },
+    ErrorSpec {
+        code: codes::KEY_ROTATION_NOT_FOUND,
+        domain: domains::SECRETS,
+        status: 404,
+        retryable: false,
+        description: "The key rotation job does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::KEY_ROTATION_NOT_RUNNING,
+        domain: domains::SECRETS,
+        status: 409,
+        retryable: false,
+        description: "The key rotation job has already finished.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SECRET_VERSION_NOT_FOUND,
+        domain: domains::SECRETS,
+        status: 404,
+        retryable: false,
+        description: "The secret version does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SECRETS_DECODE_FAILED,
+        domain: domains::SECRETS,
+        status: 500,
+        retryable: false,
+        description: "Stored secrets could not be decoded.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SECRETS_DECRYPT_FAILED,
+        domain: domains::SECRETS,
+        status: 500,
+        retryable: false,
+        description: "Stored secrets could not be decrypted.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SECRETS_ENCRYPTION_FAILED,
+        domain: domains::SECRETS,
+        status: 500,
+        retryable: false,
+        description: "Secrets could not be encrypted.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SECRETS_NOT_CONFIGURED,
+        domain: domains::SECRETS,
+        status: 404,
+        retryable: false,
+        description: "No secrets have been set for the env.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SECRETS_REWRAP_FAILED,
+        domain: domains::SECRETS,
+        status: 500,
+        retryable: true,
+        description: "Secret data keys could not be re-wrapped because a key encryption key is unavailable.",
+        hint: Some("Check the KMS provider configuration and that retired master keys are listed in PLFM_SECRETS_PREVIOUS_MASTER_KEYS."),
+    },
+    ErrorSpec {
+        code: codes::SECRETS_TOO_LARGE,
+        domain: domains::SECRETS,
+        status: 400,
+        retryable: false,
+        description: "The secrets bundle exceeds the size limit.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::UNSUPPORTED_CIPHER,
+        domain: domains::SECRETS,
+        status: 500,
+        retryable: false,
+        description: "The stored secrets use an unsupported cipher.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::AMBIGUOUS_ATTACHMENT,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "The volume has several attachments; the request must select one.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ATTACHMENT_EXISTS,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "The volume is already attached.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ATTACHMENT_NOT_FOUND,
+        domain: domains::VOLUMES,
+        status: 404,
+        retryable: false,
+        description: "The volume attachment does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_ATTACHMENT_ID,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The volume attachment ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_BACKUP_RETENTION,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "Backup retention is invalid: retain_count must be at least 1, retain_max_age_secs at least one hour, and retention requires a schedule.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_BACKUP_SCHEDULE,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The backup schedule is not a valid five-field cron expression.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_FILESYSTEM,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The filesystem type is not supported.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_MOUNT_PATH,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The mount path is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_NEW_VOLUME_NAME,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The name for the restored volume is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SIZE_BYTES,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The volume size is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SNAPSHOT_ID,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The snapshot ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_VOLUME_ID,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "The volume ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SNAPSHOT_NOT_FOUND,
+        domain: domains::VOLUMES,
+        status: 404,
+        retryable: false,
+        description: "The snapshot does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::SNAPSHOT_NOT_READY,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: true,
+        description: "The snapshot has not succeeded, so it cannot be restored in place.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_ATTACHMENT_ACTIVE,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: true,
+        description: "A process type mounting the volume still has instances; scale it to zero or detach the volume.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_HOME_NODE_CONFLICT,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "The process type already mounts a volume on a different node.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_IN_USE,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "The volume is already attached elsewhere.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_NAME_AMBIGUOUS,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "Several volumes in the organization have this name.",
+        hint: Some("Look the volume up by ID, or rename the duplicates."),
+    },
+    ErrorSpec {
+        code: codes::VOLUME_NAME_EXISTS,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "A volume with this name already exists with different settings.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_NOT_FOUND,
+        domain: domains::VOLUMES,
+        status: 404,
+        retryable: false,
+        description: "The volume does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_NOT_PLACED,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: false,
+        description: "The volume has no home node yet.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_RESTORE_IN_PROGRESS,
+        domain: domains::VOLUMES,
+        status: 409,
+        retryable: true,
+        description: "An in-place restore of the volume has not finished yet.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VOLUME_SHRINK_NOT_SUPPORTED,
+        domain: domains::VOLUMES,
+        status: 400,
+        retryable: false,
+        description: "Volumes can only grow; the requested size is smaller than the current size.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::RESTORE_DELETION_IN_PROGRESS,
+        domain: domains::TRASH,
+        status: 409,
+        retryable: true,
+        description: "The resource is still being torn down; restore it once deletion completes.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::RESTORE_PARENT_DELETED,
+        domain: domains::TRASH,
+        status: 409,
+        retryable: false,
+        description: "The resource belongs to a deleted app or env; restore that first.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::RESTORE_PREVIEW_EXPIRED,
+        domain: domains::TRASH,
+        status: 409,
+        retryable: false,
+        description: "The preview env is past its expiry and would be torn down again.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::RESTORE_WINDOW_EXPIRED,
+        domain: domains::TRASH,
+        status: 409,
+        retryable: false,
+        description: "The resource was purged from the trash and can no longer be restored.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::EXEC_PROXY_FAILED,
+        domain: domains::EXEC,
+        status: 500,
+        retryable: true,
+        description: "The exec connection to the node failed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::EXEC_RATE_LIMITED,
+        domain: domains::EXEC,
+        status: 429,
+        retryable: true,
+        description: "Too many exec sessions were requested.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::EXEC_SESSION_EXPIRED,
+        domain: domains::EXEC,
+        status: 401,
+        retryable: false,
+        description: "The exec session grant has expired.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::EXEC_SESSION_NOT_FOUND,
+        domain: domains::EXEC,
+        status: 404,
+        retryable: false,
+        description: "The exec session does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::EXEC_SESSION_NOT_GRANTED,
+        domain: domains::EXEC,
+        status: 400,
+        retryable: false,
+        description: "The exec session is not in the granted state.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_COMMAND,
+        domain: domains::EXEC,
+        status: 400,
+        retryable: false,
+        description: "The exec command is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_EXEC_SESSION_ID,
+        domain: domains::EXEC,
+        status: 400,
+        retryable: false,
+        description: "The exec session ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_NOTIFICATION_CHANNEL,
+        domain: domains::NOTIFICATIONS,
+        status: 400,
+        retryable: false,
+        description: "The channel config or secret is invalid for its kind.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_NOTIFICATION_RULE,
+        domain: domains::NOTIFICATIONS,
+        status: 400,
+        retryable: false,
+        description: "The rule references unknown channels, apps, or envs, or has too few or too many channels.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_NOTIFICATION_TEMPLATE,
+        domain: domains::NOTIFICATIONS,
+        status: 400,
+        retryable: false,
+        description: "A subject or body template does not parse or uses a variable the trigger does not provide.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NOTIFICATION_CHANNEL_NAME_EXISTS,
+        domain: domains::NOTIFICATIONS,
+        status: 409,
+        retryable: false,
+        description: "A notification channel with this name already exists in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NOTIFICATION_CHANNEL_NOT_FOUND,
+        domain: domains::NOTIFICATIONS,
+        status: 404,
+        retryable: false,
+        description: "The notification channel does not exist in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NOTIFICATION_RULE_NAME_EXISTS,
+        domain: domains::NOTIFICATIONS,
+        status: 409,
+        retryable: false,
+        description: "A notification rule with this name already exists in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NOTIFICATION_RULE_NOT_FOUND,
+        domain: domains::NOTIFICATIONS,
+        status: 404,
+        retryable: false,
+        description: "The notification rule does not exist in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_ALERT_RULE,
+        domain: domains::ALERTS,
+        status: 400,
+        retryable: false,
+        description: "The rule condition, duration, or scope is invalid, or scopes a platform condition to an app or env.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ALERT_RULE_NAME_EXISTS,
+        domain: domains::ALERTS,
+        status: 409,
+        retryable: false,
+        description: "An alert rule with this name already exists in the org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::ALERT_RULE_NOT_FOUND,
+        domain: domains::ALERTS,
+        status: 404,
+        retryable: false,
+        description: "The alert rule does not exist in this org.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_STATUS_PAGE,
+        domain: domains::STATUS_PAGES,
+        status: 400,
+        retryable: false,
+        description: "The page title or the list of shown environments is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::STATUS_PAGE_NOT_FOUND,
+        domain: domains::STATUS_PAGES,
+        status: 404,
+        retryable: false,
+        description: "The org has no status page, it is disabled, or the public token is not current.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SLO,
+        domain: domains::SLO,
+        status: 400,
+        retryable: false,
+        description: "The objective is outside 50-99.999 percent, or the window is not 1d-90d.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::BOOTSTRAP_TOKEN_NOT_FOUND,
+        domain: domains::NODES,
+        status: 404,
+        retryable: false,
+        description: "No unused, unrevoked bootstrap token has this ID.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::BOOTSTRAP_TOKEN_REQUIRED,
+        domain: domains::NODES,
+        status: 401,
+        retryable: false,
+        description: "Node enrollment requires a bootstrap token.",
+        hint: Some("Ask an operator to mint one with POST /v1/_admin/node-bootstrap-tokens."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_BOOTSTRAP_TOKEN,
+        domain: domains::NODES,
+        status: 401,
+        retryable: false,
+        description: "The bootstrap token is unknown, expired, revoked, or already used.",
+        hint: Some("Bootstrap tokens are single-use; mint a new one."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_BOOTSTRAP_TOKEN_TTL,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The requested bootstrap token lifetime is out of range.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_NODE_ID,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The node ID is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_RESTORE_ID,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The restore ID is not a valid restore job ID.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_RESTORE_STATUS,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The reported restore status is not running, succeeded, or failed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SNAPSHOT_STATUS,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The reported snapshot status is not running, succeeded, or failed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_TAINT,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The node taint is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_WIREGUARD_KEY,
+        domain: domains::NODES,
+        status: 400,
+        retryable: false,
+        description: "The WireGuard public key is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_ADDRESS_INVALID,
+        domain: domains::NODES,
+        status: 500,
+        retryable: false,
+        description: "The node overlay address is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_ADDRESS_MISSING,
+        domain: domains::NODES,
+        status: 500,
+        retryable: false,
+        description: "The node has no overlay address.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_ENROLLMENT_DENIED,
+        domain: domains::NODES,
+        status: 403,
+        retryable: false,
+        description: "An operator denied the node's enrollment.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_IDENTITY_MISMATCH,
+        domain: domains::NODES,
+        status: 403,
+        retryable: false,
+        description: "The client certificate subject does not match the subject the node enrolled with.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_IDENTITY_REQUIRED,
+        domain: domains::NODES,
+        status: 401,
+        retryable: false,
+        description: "Node requests must carry the agent's client certificate subject.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_NOT_FOUND,
+        domain: domains::NODES,
+        status: 404,
+        retryable: false,
+        description: "The node does not exist.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_NOT_PENDING,
+        domain: domains::NODES,
+        status: 409,
+        retryable: false,
+        description: "The node is not awaiting enrollment approval.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::NODE_PENDING_APPROVAL,
+        domain: domains::NODES,
+        status: 403,
+        retryable: false,
+        description: "The node is waiting for an operator to approve its enrollment.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::RESTORE_NOT_FOUND,
+        domain: domains::NODES,
+        status: 404,
+        retryable: false,
+        description: "No in-place restore exists with this ID.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::WIREGUARD_KEY_EXISTS,
+        domain: domains::NODES,
+        status: 409,
+        retryable: false,
+        description: "The WireGuard key is already registered.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::CA_UNAVAILABLE,
+        domain: domains::PKI,
+        status: 500,
+        retryable: false,
+        description: "The certificate authority key could not be loaded or used.",
+        hint: Some("Check that the secrets master key the CA was created with is configured."),
+    },
+    ErrorSpec {
+        code: codes::CERTIFICATE_NOT_FOUND,
+        domain: domains::PKI,
+        status: 404,
+        retryable: false,
+        description: "No unexpired, unrevoked certificate with this serial exists.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_CERTIFICATE_IDENTITY,
+        domain: domains::PKI,
+        status: 400,
+        retryable: false,
+        description: "The identity cannot be encoded in a certificate (bad ID, common name, or DNS name).",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_CERTIFICATE_TTL,
+        domain: domains::PKI,
+        status: 400,
+        retryable: false,
+        description: "The requested certificate lifetime is outside the bounds for this identity kind.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_IDENTITY_TOKEN,
+        domain: domains::PKI,
+        status: 401,
+        retryable: false,
+        description: "The instance identity token is missing, malformed, expired, not signed by a published key, or names a stopped instance.",
+        hint: Some("Re-read the token from PLFM_IDENTITY_TOKEN_FILE; the host agent renews it before it expires."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_CSR,
+        domain: domains::PKI,
+        status: 400,
+        retryable: false,
+        description: "The certificate signing request is not a valid PEM-encoded PKCS#10 CSR.",
+        hint: Some("Send the CSR as PEM (-----BEGIN CERTIFICATE REQUEST-----)."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_IDENTITY_KIND,
+        domain: domains::PKI,
+        status: 400,
+        retryable: false,
+        description: "The identity kind is not one of node, ingress, or instance.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_CONSISTENCY_TOKEN,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The X-Consistency-Token header or min_event_id parameter is not a valid consistency token.",
+        hint: Some("Pass back a token returned by a previous response."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_CONTENT_ENCODING,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The request body could not be decompressed with its Content-Encoding.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_CURSOR,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The pagination cursor is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_EXPECTED_VERSION,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The expected_version field is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_IDEMPOTENCY_KEY,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The Idempotency-Key header is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_IF_EXISTS,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The if_exists parameter is not one of fail or return.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_IF_MATCH,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The If-Match header is malformed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_JSON,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The request body is not valid JSON for this endpoint.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_KIND,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The kind filter is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_LABEL_SELECTOR,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The label selector is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_FIELDS,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The fields parameter names a field the list items do not have.",
+        hint: Some("Use the field names listed in the error message."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_INCLUDE,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The include parameter names a relation the list does not embed.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_ORDER_BY,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The order_by parameter names a field the list cannot be sorted by.",
+        hint: Some("Use one of the sort keys listed in the error message."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_LABELS,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The labels are invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_NAME,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The resource name is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_OPERATIONS,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The batch operations are invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_QUERY,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The query parameters are invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_RANGE,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The from/to range is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_REASON,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The change reason is too long or contains control characters.",
+        hint: Some("Keep the reason to 1000 characters of text; newlines and tabs are allowed."),
+    },
+    ErrorSpec {
+        code: codes::INVALID_REGION,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The region is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_REQUEST,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The request is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_SINCE,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The since timestamp is not valid RFC 3339.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_STATUS,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The status value is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_TIME_RANGE,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The time range is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_TYPES,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The resource type filter is invalid.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::INVALID_UPDATE,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The update has no valid fields.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::JSON_TOO_DEEP,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The JSON request body is nested too deeply.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::PAYLOAD_TOO_LARGE,
+        domain: domains::REQUEST,
+        status: 413,
+        retryable: false,
+        description: "The request body exceeds the size limit for this endpoint.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::TOO_MANY_ENTRIES,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The request has too many entries.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::TOO_MANY_OPERATIONS,
+        domain: domains::REQUEST,
+        status: 400,
+        retryable: false,
+        description: "The batch has too many operations.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::UNSUPPORTED_MEDIA_TYPE,
+        domain: domains::REQUEST,
+        status: 415,
+        retryable: false,
+        description: "The request body must be application/json.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::UNSUPPORTED_CONTENT_ENCODING,
+        domain: domains::REQUEST,
+        status: 415,
+        retryable: false,
+        description: "The request body's Content-Encoding is not accepted by this endpoint.",
+        hint: Some("Only node log ingest accepts compressed bodies (gzip or zstd)."),
+    },
+    ErrorSpec {
+        code: codes::IDEMPOTENCY_CONFLICT,
+        domain: domains::CONCURRENCY,
+        status: 409,
+        retryable: false,
+        description: "The idempotency key was reused with a different request.",
+        hint: Some("Use a new idempotency key for a different request."),
+    },
+    ErrorSpec {
+        code: codes::IDEMPOTENCY_IN_FLIGHT,
+        domain: domains::CONCURRENCY,
+        status: 409,
+        retryable: true,
+        description: "A request with the same Idempotency-Key is still being processed.",
+        hint: Some("Retry shortly with the same Idempotency-Key."),
+    },
+    ErrorSpec {
+        code: codes::PRECONDITION_FAILED,
+        domain: domains::CONCURRENCY,
+        status: 412,
+        retryable: false,
+        description: "The resource version does not match If-Match or expected_version.",
+        hint: Some("Re-read the resource and retry."),
+    },
+    ErrorSpec {
+        code: codes::PRECONDITION_REQUIRED,
+        domain: domains::CONCURRENCY,
+        status: 428,
+        retryable: false,
+        description: "The operation requires If-Match or expected_version.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::VERSION_CONFLICT,
+        domain: domains::CONCURRENCY,
+        status: 409,
+        retryable: true,
+        description: "The resource was modified concurrently.",
+        hint: Some("Re-read the resource and retry."),
+    },
+    ErrorSpec {
+        code: codes::INTERNAL_ERROR,
+        domain: domains::SERVER,
+        status: 500,
+        retryable: false,
+        description: "An unexpected server error occurred.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::MAINTENANCE_MODE,
+        domain: domains::SERVER,
+        status: 503,
+        retryable: true,
+        description: "The control plane is read-only for maintenance.",
+        hint: Some("Retry the write after the Retry-After interval; reads keep working."),
+    },
+    ErrorSpec {
+        code: codes::PROJECTION_NOT_FOUND,
+        domain: domains::SERVER,
+        status: 404,
+        retryable: false,
+        description: "No projection with this name is registered.",
+        hint: None,
+    },
+    ErrorSpec {
+        code: codes::PROJECTION_NOT_PAUSED,
+        domain: domains::SERVER,
+        status: 409,
+        retryable: false,
+        description: "The projection must be paused before it is rebuilt.",
+        hint: Some("Pause the projection, rebuild, then resume it."),
+    },
+    ErrorSpec {
+        code: codes::PROJECTION_TIMEOUT,
+        domain: domains::SERVER,
+        status: 504,
+        retryable: true,
+        description: "The write succeeded but the read model has not caught up yet.",
+        hint: Some("Retry the read shortly."),
+    },
+];
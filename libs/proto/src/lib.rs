@@ -25,4 +25,69 @@ pub mod agent {
     }
 }
 
+pub mod convert;
+
 pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("gen/plfm_descriptor.bin");
+
+/// Catalog of stable API error codes, generated from `api/errors/catalog.json`.
+///
+/// Shared by the control plane (to stamp `domain` and `retryable` on problem
+/// responses) and the CLI (to render hints for known codes).
+pub mod errors {
+    /// A catalogued API error.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ErrorSpec {
+        /// Stable machine-readable code (the `code` field of problem responses).
+        pub code: &'static str,
+        /// Functional area the error belongs to.
+        pub domain: &'static str,
+        /// HTTP status the error is returned with.
+        pub status: u16,
+        /// Whether retrying the same request may succeed.
+        pub retryable: bool,
+        /// One-line description of the error.
+        pub description: &'static str,
+        /// Optional remediation hint for humans.
+        pub hint: Option<&'static str>,
+    }
+
+    include!("gen/plfm_error_catalog.rs");
+
+    /// Look up a catalogued error by code.
+    pub fn lookup(code: &str) -> Option<&'static ErrorSpec> {
+        CATALOG.iter().find(|spec| spec.code == code)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_codes_are_unique() {
+            let mut seen = std::collections::HashSet::new();
+            for spec in CATALOG {
+                assert!(seen.insert(spec.code), "duplicate code {}", spec.code);
+            }
+        }
+
+        #[test]
+        fn test_lookup() {
+            let spec = lookup(codes::APP_NOT_FOUND).unwrap();
+            assert_eq!(spec.domain, domains::APPS);
+            assert_eq!(spec.status, 404);
+            assert!(lookup("no_such_code").is_none());
+        }
+
+        #[test]
+        fn test_statuses_are_errors() {
+            for spec in CATALOG {
+                assert!(
+                    (400..600).contains(&spec.status),
+                    "{} has non-error status {}",
+                    spec.code,
+                    spec.status
+                );
+            }
+        }
+    }
+}
@@ -37,6 +37,10 @@ pub enum ReconcileError {
     /// Internal error.
     #[error("internal error: {0}")]
     Internal(String),
+
+    /// A rollout limit is neither a count nor a percentage from 0% to 100%.
+    #[error("invalid rollout limit '{0}': expected a count or a percentage from 0% to 100%")]
+    InvalidRolloutLimit(String),
 }
 
 /// Convergence status for a resource.
@@ -74,9 +78,9 @@ impl ConvergenceStatus {
 pub struct SpecHash(String);
 
 impl SpecHash {
-    /// Compute a spec hash from canonical JSON.
+    /// Compute a spec hash from the spec's canonical JSON (RFC 8785).
     pub fn from_json(json: &serde_json::Value) -> Self {
-        let canonical = canonical_json(json);
+        let canonical = plfm_canonical_json::to_string(json);
         let mut hasher = Sha256::new();
         hasher.update(canonical.as_bytes());
         let result = hasher.finalize();
@@ -95,47 +99,6 @@ impl std::fmt::Display for SpecHash {
     }
 }
 
-/// Produce canonical JSON (sorted keys, no extra whitespace).
-fn canonical_json(value: &serde_json::Value) -> String {
-    match value {
-        serde_json::Value::Object(map) => {
-            let mut pairs: Vec<_> = map.iter().collect();
-            pairs.sort_by_key(|(k, _)| *k);
-            let inner: Vec<String> = pairs
-                .iter()
-                .map(|(k, v)| format!("\"{}\":{}", escape_json_string(k), canonical_json(v)))
-                .collect();
-            format!("{{{}}}", inner.join(","))
-        }
-        serde_json::Value::Array(arr) => {
-            let inner: Vec<String> = arr.iter().map(canonical_json).collect();
-            format!("[{}]", inner.join(","))
-        }
-        serde_json::Value::String(s) => format!("\"{}\"", escape_json_string(s)),
-        serde_json::Value::Number(n) => n.to_string(),
-        serde_json::Value::Bool(b) => b.to_string(),
-        serde_json::Value::Null => "null".to_string(),
-    }
-}
-
-fn escape_json_string(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for c in s.chars() {
-        match c {
-            '"' => out.push_str("\\\""),
-            '\\' => out.push_str("\\\\"),
-            '\n' => out.push_str("\\n"),
-            '\r' => out.push_str("\\r"),
-            '\t' => out.push_str("\\t"),
-            c if c.is_control() => {
-                out.push_str(&format!("\\u{:04x}", c as u32));
-            }
-            c => out.push(c),
-        }
-    }
-    out
-}
-
 /// Instance classification based on spec hash.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InstanceClass {
@@ -170,26 +133,126 @@ where
     (matching, old)
 }
 
+/// A rollout bound: an instance count or a percentage of desired replicas.
+///
+/// Written as `3` or `"25%"` in JSON, and as `3` or `25%` in text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RolloutLimit {
+    /// A fixed number of instances.
+    Count(u32),
+    /// A percentage (0-100) of the desired replica count.
+    Percent(u32),
+}
+
+impl RolloutLimit {
+    /// Resolve against `desired` replicas.
+    ///
+    /// Percentages round up for surge and down for unavailability, as in
+    /// Kubernetes: a rollout may overshoot capacity slightly but never
+    /// availability.
+    pub fn resolve(self, desired: u32, round_up: bool) -> u32 {
+        match self {
+            Self::Count(count) => count,
+            Self::Percent(percent) => {
+                let scaled = u64::from(desired) * u64::from(percent);
+                let resolved = if round_up {
+                    scaled.div_ceil(100)
+                } else {
+                    scaled / 100
+                };
+                u32::try_from(resolved).unwrap_or(u32::MAX)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RolloutLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count(count) => write!(f, "{count}"),
+            Self::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+impl std::str::FromStr for RolloutLimit {
+    type Err = ReconcileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ReconcileError::InvalidRolloutLimit(s.to_string());
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => match percent.parse() {
+                Ok(percent) if percent <= 100 => Ok(Self::Percent(percent)),
+                _ => Err(invalid()),
+            },
+            None => s.parse().map(Self::Count).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl serde::Serialize for RolloutLimit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Count(count) => serializer.serialize_u32(*count),
+            Self::Percent(_) => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for RolloutLimit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Number(n) => n
+                .as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .map(Self::Count)
+                .ok_or_else(|| {
+                    serde::de::Error::custom("rollout count must be a non-negative integer")
+                }),
+            serde_json::Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+            _ => Err(serde::de::Error::custom(
+                "rollout limit must be an integer or a percentage string",
+            )),
+        }
+    }
+}
+
 /// Rollout strategy for stateless workloads.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RollingStrategy {
     /// Maximum number of instances that can be created above desired count.
-    pub max_surge: u32,
+    pub max_surge: RolloutLimit,
 
     /// Maximum number of instances that can be unavailable during rollout.
-    pub max_unavailable: u32,
+    pub max_unavailable: RolloutLimit,
 }
 
 impl Default for RollingStrategy {
     fn default() -> Self {
         Self {
-            max_surge: 1,
-            max_unavailable: 0,
+            max_surge: RolloutLimit::Count(1),
+            max_unavailable: RolloutLimit::Count(0),
         }
     }
 }
 
 impl RollingStrategy {
+    /// Resolve to `(max_surge, max_unavailable)` instance counts for
+    /// `desired_count` replicas.
+    ///
+    /// If both resolve to zero the rollout could never make progress, so one
+    /// instance may be unavailable instead (as in Kubernetes).
+    pub fn resolve(&self, desired_count: u32) -> (u32, u32) {
+        let surge = self.max_surge.resolve(desired_count, true);
+        let unavailable = self.max_unavailable.resolve(desired_count, false);
+        if surge == 0 && unavailable == 0 {
+            (0, 1)
+        } else {
+            (surge, unavailable)
+        }
+    }
+
     /// Calculate how many instances can be started in this reconciliation pass.
     ///
     /// Returns (new_to_start, old_to_drain).
@@ -200,10 +263,11 @@ impl RollingStrategy {
         matching_pending: u32,
         old_running: u32,
     ) -> (u32, u32) {
+        let (max_surge, max_unavailable) = self.resolve(desired_count);
         let total_running = matching_ready + matching_pending + old_running;
 
         // How many new instances can we start?
-        let max_total = desired_count + self.max_surge;
+        let max_total = desired_count.saturating_add(max_surge);
         let can_start = max_total.saturating_sub(total_running);
 
         // How many do we need to start?
@@ -211,7 +275,7 @@ impl RollingStrategy {
         let new_to_start = can_start.min(need_to_start);
 
         // How many old instances can we drain?
-        let min_available = desired_count.saturating_sub(self.max_unavailable);
+        let min_available = desired_count.saturating_sub(max_unavailable);
         // Availability is satisfied by any ready instance (matching or old).
         // Once we have more than `min_available` ready instances overall, we can safely drain old.
         let currently_available = matching_ready.saturating_add(old_running);
@@ -368,6 +432,331 @@ impl RetryTracker {
     }
 }
 
+/// Persisted retry state for one resource.
+///
+/// Timestamps are Unix seconds so records survive process restarts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryRecord {
+    /// Resource the failures belong to.
+    pub resource_key: String,
+
+    /// Failures in the current window.
+    pub count: u32,
+
+    /// First failure of the current window.
+    pub first_failure_at: i64,
+
+    /// Most recent failure that counted toward the budget.
+    pub last_failure_at: i64,
+
+    /// When the budget was exhausted, if it is.
+    pub exhausted_at: Option<i64>,
+
+    /// Times this resource has exhausted its budget.
+    pub exhaustions: u32,
+}
+
+/// Durable storage for [`PersistentRetryTracker`].
+pub trait RetryStore {
+    /// Storage error.
+    type Error;
+
+    /// Load the record for a resource.
+    fn get_retry(&self, resource_key: &str) -> Result<Option<RetryRecord>, Self::Error>;
+
+    /// Insert or replace a record.
+    fn put_retry(&self, record: &RetryRecord) -> Result<(), Self::Error>;
+
+    /// Delete the record for a resource.
+    fn delete_retry(&self, resource_key: &str) -> Result<(), Self::Error>;
+
+    /// List all records.
+    fn list_retries(&self) -> Result<Vec<RetryRecord>, Self::Error>;
+}
+
+impl<T: RetryStore + ?Sized> RetryStore for &T {
+    type Error = T::Error;
+
+    fn get_retry(&self, resource_key: &str) -> Result<Option<RetryRecord>, Self::Error> {
+        (**self).get_retry(resource_key)
+    }
+
+    fn put_retry(&self, record: &RetryRecord) -> Result<(), Self::Error> {
+        (**self).put_retry(record)
+    }
+
+    fn delete_retry(&self, resource_key: &str) -> Result<(), Self::Error> {
+        (**self).delete_retry(resource_key)
+    }
+
+    fn list_retries(&self) -> Result<Vec<RetryRecord>, Self::Error> {
+        (**self).list_retries()
+    }
+}
+
+/// Retry counts across a [`RetryStore`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryStats {
+    /// Resources with unexpired records.
+    pub tracked: u64,
+
+    /// Resources whose budget is currently exhausted.
+    pub exhausted: u64,
+
+    /// Budget exhaustions across unexpired records.
+    pub exhaustions: u64,
+}
+
+/// Retry tracker whose state lives in a [`RetryStore`].
+///
+/// Same budget as [`RetryTracker`], but a restarted process sees the
+/// failures recorded before it. An exhausted resource stays exhausted until
+/// its record expires, `ttl` after the failure that exhausted it; further
+/// failures do not extend that.
+#[derive(Debug, Clone)]
+pub struct PersistentRetryTracker<S> {
+    store: S,
+
+    /// Maximum retries per resource.
+    max_retries: u32,
+
+    /// Window in which failures are counted.
+    window: Duration,
+
+    /// Time after the last counted failure at which a record expires.
+    ttl: Duration,
+}
+
+impl<S: RetryStore> PersistentRetryTracker<S> {
+    /// Create a tracker over `store`.
+    pub fn new(store: S, max_retries: u32, window: Duration, ttl: Duration) -> Self {
+        Self {
+            store,
+            max_retries,
+            window,
+            ttl,
+        }
+    }
+
+    /// Record a failure for a resource.
+    ///
+    /// Returns true if retries are exhausted.
+    pub fn record_failure(&self, resource_key: &str) -> Result<bool, S::Error> {
+        self.record_failure_at(resource_key, chrono::Utc::now().timestamp())
+    }
+
+    /// Record a failure for a resource at `now` (Unix seconds).
+    pub fn record_failure_at(&self, resource_key: &str, now: i64) -> Result<bool, S::Error> {
+        let existing = self
+            .store
+            .get_retry(resource_key)?
+            .filter(|record| !self.is_expired(record, now));
+
+        let mut record = match existing {
+            Some(record) if record.exhausted_at.is_some() => return Ok(true),
+            Some(record) if now - record.first_failure_at <= secs(self.window) => record,
+            // Reset if outside window
+            Some(record) => RetryRecord {
+                count: 0,
+                first_failure_at: now,
+                exhausted_at: None,
+                ..record
+            },
+            None => RetryRecord {
+                resource_key: resource_key.to_string(),
+                count: 0,
+                first_failure_at: now,
+                last_failure_at: now,
+                exhausted_at: None,
+                exhaustions: 0,
+            },
+        };
+
+        record.count += 1;
+        record.last_failure_at = now;
+        let exhausted = record.count > self.max_retries;
+        if exhausted {
+            record.exhausted_at = Some(now);
+            record.exhaustions += 1;
+        }
+        self.store.put_retry(&record)?;
+
+        Ok(exhausted)
+    }
+
+    /// Check if retries are exhausted for a resource.
+    pub fn is_exhausted(&self, resource_key: &str) -> Result<bool, S::Error> {
+        self.is_exhausted_at(resource_key, chrono::Utc::now().timestamp())
+    }
+
+    /// Check if retries are exhausted for a resource at `now` (Unix seconds).
+    pub fn is_exhausted_at(&self, resource_key: &str, now: i64) -> Result<bool, S::Error> {
+        Ok(self
+            .store
+            .get_retry(resource_key)?
+            .is_some_and(|record| record.exhausted_at.is_some() && !self.is_expired(&record, now)))
+    }
+
+    /// Clear failure tracking for a resource (on success).
+    pub fn clear(&self, resource_key: &str) -> Result<(), S::Error> {
+        self.store.delete_retry(resource_key)
+    }
+
+    /// Delete expired records, returning how many were removed.
+    pub fn prune(&self) -> Result<usize, S::Error> {
+        self.prune_at(chrono::Utc::now().timestamp())
+    }
+
+    /// Delete records expired at `now` (Unix seconds).
+    pub fn prune_at(&self, now: i64) -> Result<usize, S::Error> {
+        let mut pruned = 0;
+        for record in self.store.list_retries()? {
+            if self.is_expired(&record, now) {
+                self.store.delete_retry(&record.resource_key)?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Counts over unexpired records, for metrics.
+    pub fn stats(&self) -> Result<RetryStats, S::Error> {
+        self.stats_at(chrono::Utc::now().timestamp())
+    }
+
+    /// Counts over records unexpired at `now` (Unix seconds).
+    pub fn stats_at(&self, now: i64) -> Result<RetryStats, S::Error> {
+        let mut stats = RetryStats::default();
+        for record in self.store.list_retries()? {
+            if self.is_expired(&record, now) {
+                continue;
+            }
+            stats.tracked += 1;
+            if record.exhausted_at.is_some() {
+                stats.exhausted += 1;
+            }
+            stats.exhaustions += u64::from(record.exhaustions);
+        }
+        Ok(stats)
+    }
+
+    fn is_expired(&self, record: &RetryRecord, now: i64) -> bool {
+        now - record.last_failure_at > secs(self.ttl)
+    }
+}
+
+fn secs(duration: Duration) -> i64 {
+    i64::try_from(duration.as_secs()).unwrap_or(i64::MAX)
+}
+
+/// Outcome of observing a resource with a [`ConvergenceTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvergenceCheck {
+    /// Observed status, with `Converging` turned into `Diverged` once the
+    /// resource has been converging for longer than the deadline.
+    pub status: ConvergenceStatus,
+
+    /// Time since the resource entered `Converging` (zero otherwise).
+    pub time_in_state: Duration,
+
+    /// True only for the first observation past the deadline, so a stuck
+    /// resource is reported once per episode.
+    pub newly_diverged: bool,
+}
+
+/// Convergence deadline tracker.
+///
+/// Records when each resource entered `Converging` and flags it `Diverged`
+/// once it has stayed there longer than the deadline. Any other observed
+/// status ends the episode; a later `Converging` starts a new one.
+#[derive(Debug, Clone)]
+pub struct ConvergenceTracker {
+    /// Maximum time in `Converging`; zero disables the deadline.
+    deadline: Duration,
+
+    /// Tracked episodes: resource_key -> (entered_converging, reported).
+    entered: BTreeMap<String, (Instant, bool)>,
+}
+
+impl ConvergenceTracker {
+    /// Create a new convergence tracker.
+    pub fn new(deadline: Duration) -> Self {
+        Self {
+            deadline,
+            entered: BTreeMap::new(),
+        }
+    }
+
+    /// The convergence deadline.
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+
+    /// Record the current status of a resource.
+    pub fn observe(&mut self, resource_key: &str, status: ConvergenceStatus) -> ConvergenceCheck {
+        self.observe_at(resource_key, status, Instant::now())
+    }
+
+    /// Record the status of a resource as of `now`.
+    pub fn observe_at(
+        &mut self,
+        resource_key: &str,
+        status: ConvergenceStatus,
+        now: Instant,
+    ) -> ConvergenceCheck {
+        if !matches!(
+            status,
+            ConvergenceStatus::Converging | ConvergenceStatus::Diverged
+        ) {
+            self.entered.remove(resource_key);
+            return ConvergenceCheck {
+                status,
+                time_in_state: Duration::ZERO,
+                newly_diverged: false,
+            };
+        }
+
+        let (entered, reported) = self
+            .entered
+            .entry(resource_key.to_string())
+            .or_insert((now, false));
+        let time_in_state = now.saturating_duration_since(*entered);
+
+        let exceeded = !self.deadline.is_zero() && time_in_state > self.deadline;
+        if !exceeded && status == ConvergenceStatus::Converging {
+            return ConvergenceCheck {
+                status,
+                time_in_state,
+                newly_diverged: false,
+            };
+        }
+
+        let newly_diverged = !*reported;
+        *reported = true;
+        ConvergenceCheck {
+            status: ConvergenceStatus::Diverged,
+            time_in_state,
+            newly_diverged,
+        }
+    }
+
+    /// Time a resource has spent converging, if it is being tracked.
+    pub fn time_in_state(&self, resource_key: &str) -> Option<Duration> {
+        self.entered
+            .get(resource_key)
+            .map(|(entered, _)| entered.elapsed())
+    }
+
+    /// Stop tracking resources for which `keep` returns false (e.g. ones
+    /// that no longer exist).
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str) -> bool,
+    {
+        self.entered.retain(|key, _| keep(key));
+    }
+}
+
 /// Default reconciliation interval.
 pub const DEFAULT_RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
 
@@ -377,6 +766,12 @@ pub const DEFAULT_MAX_RETRIES: u32 = 3;
 /// Default retry window.
 pub const DEFAULT_RETRY_WINDOW: Duration = Duration::from_secs(10 * 60); // 10 minutes
 
+/// Default lifetime of a persisted retry record after its last failure.
+pub const DEFAULT_RETRY_TTL: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+/// Default time a resource may spend converging before it is flagged diverged.
+pub const DEFAULT_CONVERGENCE_DEADLINE: Duration = Duration::from_secs(15 * 60); // 15 minutes
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,13 +785,17 @@ mod tests {
         let hash2 = SpecHash::from_json(&json2);
 
         assert_eq!(hash1, hash2);
+
+        // Numerically equal specs hash the same however they were written.
+        let json3 = serde_json::json!({"a": 1.0, "b": 2e0});
+        assert_eq!(SpecHash::from_json(&json3), hash1);
     }
 
     #[test]
     fn test_rolling_strategy() {
         let strategy = RollingStrategy {
-            max_surge: 1,
-            max_unavailable: 0,
+            max_surge: RolloutLimit::Count(1),
+            max_unavailable: RolloutLimit::Count(0),
         };
 
         // Starting fresh: can start 1 (max_surge allows desired + 1)
@@ -415,6 +814,60 @@ mod tests {
         assert_eq!(drain, 2); // All old can be drained since we have 3 ready
     }
 
+    #[test]
+    fn test_rollout_limit_percentages() {
+        // Surge rounds up, unavailability down.
+        let strategy = RollingStrategy {
+            max_surge: RolloutLimit::Percent(25),
+            max_unavailable: RolloutLimit::Percent(25),
+        };
+        assert_eq!(strategy.resolve(10), (3, 2));
+        assert_eq!(strategy.resolve(4), (1, 1));
+        assert_eq!(strategy.resolve(1), (1, 0));
+
+        // Limits that would stall the rollout allow one unavailable instance.
+        let strategy = RollingStrategy {
+            max_surge: RolloutLimit::Percent(0),
+            max_unavailable: RolloutLimit::Percent(10),
+        };
+        assert_eq!(strategy.resolve(5), (0, 1));
+        let (start, drain) = strategy.calculate_actions(5, 0, 0, 5);
+        assert_eq!((start, drain), (0, 1));
+
+        let strategy = RollingStrategy {
+            max_surge: RolloutLimit::Percent(50),
+            max_unavailable: RolloutLimit::Count(0),
+        };
+        let (start, drain) = strategy.calculate_actions(10, 0, 0, 10);
+        assert_eq!((start, drain), (5, 0));
+    }
+
+    #[test]
+    fn test_rollout_limit_parsing() {
+        assert_eq!("3".parse::<RolloutLimit>().unwrap(), RolloutLimit::Count(3));
+        assert_eq!(
+            " 25% ".parse::<RolloutLimit>().unwrap(),
+            RolloutLimit::Percent(25)
+        );
+        assert!("101%".parse::<RolloutLimit>().is_err());
+        assert!("-1".parse::<RolloutLimit>().is_err());
+        assert!("%".parse::<RolloutLimit>().is_err());
+        assert_eq!(RolloutLimit::Percent(25).to_string(), "25%");
+
+        let limits: Vec<RolloutLimit> = serde_json::from_str(r#"[2, "2", "50%"]"#).unwrap();
+        assert_eq!(
+            limits,
+            vec![
+                RolloutLimit::Count(2),
+                RolloutLimit::Count(2),
+                RolloutLimit::Percent(50)
+            ]
+        );
+        assert_eq!(serde_json::to_string(&limits).unwrap(), r#"[2,2,"50%"]"#);
+        assert!(serde_json::from_str::<RolloutLimit>("-1").is_err());
+        assert!(serde_json::from_str::<RolloutLimit>("true").is_err());
+    }
+
     #[test]
     fn test_classify_instances() {
         let desired = SpecHash("sha256:abc".to_string());
@@ -459,4 +912,147 @@ mod tests {
         tracker.clear("resource-1");
         assert!(!tracker.is_exhausted("resource-1"));
     }
+
+    #[test]
+    fn test_convergence_tracker() {
+        let mut tracker = ConvergenceTracker::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let check = tracker.observe_at("group-1", ConvergenceStatus::Converging, at(0));
+        assert_eq!(check.status, ConvergenceStatus::Converging);
+        assert_eq!(check.time_in_state, Duration::ZERO);
+
+        let check = tracker.observe_at("group-1", ConvergenceStatus::Converging, at(60));
+        assert_eq!(check.status, ConvergenceStatus::Converging);
+        assert_eq!(check.time_in_state, Duration::from_secs(60));
+
+        // Past the deadline: diverged, reported once.
+        let check = tracker.observe_at("group-1", ConvergenceStatus::Converging, at(61));
+        assert_eq!(check.status, ConvergenceStatus::Diverged);
+        assert!(check.newly_diverged);
+        let check = tracker.observe_at("group-1", ConvergenceStatus::Converging, at(90));
+        assert_eq!(check.status, ConvergenceStatus::Diverged);
+        assert!(!check.newly_diverged);
+
+        // Converging again after converging starts a new episode.
+        tracker.observe_at("group-1", ConvergenceStatus::Converged, at(100));
+        assert_eq!(tracker.time_in_state("group-1"), None);
+        let check = tracker.observe_at("group-1", ConvergenceStatus::Converging, at(200));
+        assert_eq!(check.status, ConvergenceStatus::Converging);
+        assert_eq!(check.time_in_state, Duration::ZERO);
+
+        tracker.retain(|key| key != "group-1");
+        assert_eq!(tracker.time_in_state("group-1"), None);
+    }
+
+    #[test]
+    fn test_convergence_tracker_without_deadline() {
+        let mut tracker = ConvergenceTracker::new(Duration::ZERO);
+        let start = Instant::now();
+
+        tracker.observe_at("group-1", ConvergenceStatus::Converging, start);
+        let check = tracker.observe_at(
+            "group-1",
+            ConvergenceStatus::Converging,
+            start + Duration::from_secs(86_400),
+        );
+        assert_eq!(check.status, ConvergenceStatus::Converging);
+        assert!(!check.newly_diverged);
+
+        // An explicit divergence is still reported.
+        let check = tracker.observe_at("group-1", ConvergenceStatus::Diverged, start);
+        assert_eq!(check.status, ConvergenceStatus::Diverged);
+        assert!(check.newly_diverged);
+    }
+
+    /// In-memory [`RetryStore`] for tests.
+    #[derive(Default)]
+    struct MemoryRetryStore(std::cell::RefCell<BTreeMap<String, RetryRecord>>);
+
+    impl RetryStore for MemoryRetryStore {
+        type Error = std::convert::Infallible;
+
+        fn get_retry(&self, resource_key: &str) -> Result<Option<RetryRecord>, Self::Error> {
+            Ok(self.0.borrow().get(resource_key).cloned())
+        }
+
+        fn put_retry(&self, record: &RetryRecord) -> Result<(), Self::Error> {
+            self.0
+                .borrow_mut()
+                .insert(record.resource_key.clone(), record.clone());
+            Ok(())
+        }
+
+        fn delete_retry(&self, resource_key: &str) -> Result<(), Self::Error> {
+            self.0.borrow_mut().remove(resource_key);
+            Ok(())
+        }
+
+        fn list_retries(&self) -> Result<Vec<RetryRecord>, Self::Error> {
+            Ok(self.0.borrow().values().cloned().collect())
+        }
+    }
+
+    #[test]
+    fn test_persistent_retry_tracker() {
+        let store = MemoryRetryStore::default();
+        let tracker = PersistentRetryTracker::new(
+            &store,
+            2,
+            Duration::from_secs(60),
+            Duration::from_secs(600),
+        );
+
+        assert!(!tracker.record_failure_at("resource-1", 0).unwrap());
+        assert!(!tracker.record_failure_at("resource-1", 10).unwrap());
+        assert!(tracker.record_failure_at("resource-1", 20).unwrap());
+        assert!(tracker.is_exhausted_at("resource-1", 20).unwrap());
+
+        // A new tracker over the same store (a restarted process) agrees.
+        let restarted = PersistentRetryTracker::new(
+            &store,
+            2,
+            Duration::from_secs(60),
+            Duration::from_secs(600),
+        );
+        assert!(restarted.is_exhausted_at("resource-1", 300).unwrap());
+
+        // Failures while exhausted do not extend the TTL.
+        assert!(restarted.record_failure_at("resource-1", 500).unwrap());
+        assert!(!restarted.is_exhausted_at("resource-1", 621).unwrap());
+
+        let stats = restarted.stats_at(300).unwrap();
+        assert_eq!(
+            stats,
+            RetryStats {
+                tracked: 1,
+                exhausted: 1,
+                exhaustions: 1
+            }
+        );
+        assert_eq!(restarted.prune_at(300).unwrap(), 0);
+        assert_eq!(restarted.prune_at(621).unwrap(), 1);
+        assert_eq!(restarted.stats_at(621).unwrap(), RetryStats::default());
+    }
+
+    #[test]
+    fn test_persistent_retry_tracker_window_and_clear() {
+        let store = MemoryRetryStore::default();
+        let tracker = PersistentRetryTracker::new(
+            &store,
+            1,
+            Duration::from_secs(60),
+            Duration::from_secs(600),
+        );
+
+        assert!(!tracker.record_failure_at("resource-1", 0).unwrap());
+        // Outside the window, counting starts over.
+        assert!(!tracker.record_failure_at("resource-1", 100).unwrap());
+        assert!(tracker.record_failure_at("resource-1", 110).unwrap());
+
+        tracker.clear("resource-1").unwrap();
+        assert!(!tracker.is_exhausted_at("resource-1", 110).unwrap());
+        assert!(!tracker.record_failure_at("resource-1", 120).unwrap());
+    }
 }
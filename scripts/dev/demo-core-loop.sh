@@ -90,16 +90,16 @@ cleanup() {
 
 trap cleanup EXIT
 
-echo "Waiting for /healthz..."
+echo "Waiting for /readyz..."
 for _ in $(seq 1 120); do
-    if curl -fsS "${API_URL%/}/healthz" >/dev/null 2>&1; then
+    if curl -fsS "${API_URL%/}/readyz" >/dev/null 2>&1; then
         break
     fi
     sleep 0.25
 done
 
-if ! curl -fsS "${API_URL%/}/healthz" >/dev/null 2>&1; then
-    echo "Control-plane did not become healthy. Tail log:" >&2
+if ! curl -fsS "${API_URL%/}/readyz" >/dev/null 2>&1; then
+    echo "Control-plane did not become ready. Tail log:" >&2
     tail -n 50 "${CP_LOG}" >&2 || true
     exit 1
 fi
@@ -0,0 +1,50 @@
+//! Embed `migrations/*.sql` in the binary so `control-plane migrate` and the
+//! startup schema check work wherever the binary is deployed.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Result;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let migrations_dir = manifest_dir.join("migrations");
+    println!("cargo:rerun-if-changed={}", migrations_dir.display());
+
+    // Same naming rules as sqlx's directory source: `<VERSION>_<DESCRIPTION>.sql`.
+    let mut migrations = Vec::new();
+    for entry in fs::read_dir(&migrations_dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(stem) = file_name.strip_suffix(".sql") else {
+            continue;
+        };
+        let Some((version, description)) = stem.split_once('_') else {
+            continue;
+        };
+        let Ok(version) = version.parse::<i64>() else {
+            continue;
+        };
+        println!("cargo:rerun-if-changed={}", path.display());
+        migrations.push((version, description.replace('_', " "), path));
+    }
+    migrations.sort_by_key(|(version, _, _)| *version);
+
+    let mut out =
+        String::from("/// `(version, description, sql)` of every migration in `migrations/`.\n");
+    out.push_str("pub(crate) static EMBEDDED_MIGRATIONS: &[(i64, &str, &str)] = &[\n");
+    for (version, description, path) in &migrations {
+        writeln!(
+            out,
+            "    ({version}, {description:?}, include_str!({:?})),",
+            path.display().to_string()
+        )
+        .unwrap();
+    }
+    out.push_str("];\n");
+
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("migrations.rs");
+    fs::write(out_path, out)
+}
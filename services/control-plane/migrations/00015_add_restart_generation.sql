@@ -0,0 +1,10 @@
+-- Migration: 00015_add_restart_generation
+-- Description: Track restart requests per (env_id, process_type)
+-- See: docs/specs/state/event-types.md (env.restart_requested)
+
+-- Bumped by env.restart_requested; the scheduler mixes non-zero generations
+-- into the spec hash so existing instances are rolled.
+ALTER TABLE env_desired_releases_view
+    ADD COLUMN IF NOT EXISTS restart_generation INT NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN env_desired_releases_view.restart_generation IS 'Restart request counter; changes force instance replacement';
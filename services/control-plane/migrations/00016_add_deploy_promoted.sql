@@ -0,0 +1,10 @@
+-- Migration: 00016_add_deploy_promoted
+-- Description: Track manual promotion of rolling deploys
+-- See: docs/specs/state/event-types.md (deploy.promoted)
+
+-- Set by deploy.promoted; the scheduler skips surge-limited rolling steps
+-- for groups whose desired deploy is promoted.
+ALTER TABLE deploys_view
+    ADD COLUMN IF NOT EXISTS promoted BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN deploys_view.promoted IS 'Operator promoted the rollout to 100% (deploy.promoted)';
@@ -0,0 +1,29 @@
+-- Migration: 00017_add_deletion_teardown
+-- Description: Track cascading app/env teardown and archived secret bundles
+-- See: docs/specs/state/event-types.md (env.deletion_requested, app.deletion_requested)
+
+-- Set by *.deletion_requested; the cleanup worker drives teardown for rows
+-- with a pending deletion until it emits env.deleted / app.deleted.
+ALTER TABLE apps_view
+    ADD COLUMN IF NOT EXISTS deletion_requested_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS delete_volumes BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE envs_view
+    ADD COLUMN IF NOT EXISTS deletion_requested_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS delete_volumes BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_apps_deletion_pending
+    ON apps_view (deletion_requested_at) WHERE deletion_requested_at IS NOT NULL AND NOT is_deleted;
+
+CREATE INDEX IF NOT EXISTS idx_envs_deletion_pending
+    ON envs_view (deletion_requested_at) WHERE deletion_requested_at IS NOT NULL AND NOT is_deleted;
+
+COMMENT ON COLUMN apps_view.deletion_requested_at IS 'When app deletion was requested (app.deletion_requested)';
+COMMENT ON COLUMN envs_view.deletion_requested_at IS 'When env deletion was requested (env.deletion_requested)';
+
+-- Set by secret_bundle.archived; archived bundles keep their material but
+-- are no longer delivered to instances.
+ALTER TABLE secret_bundles_view
+    ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN secret_bundles_view.archived_at IS 'When the bundle was archived by env teardown (secret_bundle.archived)';
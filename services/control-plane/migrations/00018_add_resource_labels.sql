@@ -0,0 +1,49 @@
+-- Migration: 00018_add_resource_labels
+-- Description: Key/value labels on apps, envs, routes, and volumes with label selector matching
+-- See: docs/specs/api/http-api.md (Labels and label selectors)
+
+-- Set by *.labels_updated; always holds the full label map for the resource.
+ALTER TABLE apps_view
+    ADD COLUMN IF NOT EXISTS labels JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+ALTER TABLE envs_view
+    ADD COLUMN IF NOT EXISTS labels JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+ALTER TABLE routes_view
+    ADD COLUMN IF NOT EXISTS labels JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+ALTER TABLE volumes_view
+    ADD COLUMN IF NOT EXISTS labels JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX IF NOT EXISTS idx_apps_labels ON apps_view USING GIN (labels);
+CREATE INDEX IF NOT EXISTS idx_envs_labels ON envs_view USING GIN (labels);
+CREATE INDEX IF NOT EXISTS idx_routes_labels ON routes_view USING GIN (labels);
+CREATE INDEX IF NOT EXISTS idx_volumes_labels ON volumes_view USING GIN (labels);
+
+-- Evaluate a parsed label selector against a label map. The selector has the
+-- shape {"eq": {k: v}, "neq": [{k: v}], "exists": [k], "not_exists": [k]};
+-- a NULL selector matches everything.
+CREATE OR REPLACE FUNCTION labels_match(labels JSONB, selector JSONB)
+RETURNS BOOLEAN
+LANGUAGE SQL
+IMMUTABLE
+AS $$
+    SELECT selector IS NULL OR (
+        labels @> COALESCE(selector->'eq', '{}'::jsonb)
+        AND NOT EXISTS (
+            SELECT 1 FROM jsonb_array_elements(COALESCE(selector->'neq', '[]'::jsonb)) AS n(term)
+            WHERE labels @> n.term
+        )
+        AND labels ?& ARRAY(
+            SELECT jsonb_array_elements_text(COALESCE(selector->'exists', '[]'::jsonb))
+        )
+        AND NOT labels ?| ARRAY(
+            SELECT jsonb_array_elements_text(COALESCE(selector->'not_exists', '[]'::jsonb))
+        )
+    )
+$$;
+
+COMMENT ON COLUMN apps_view.labels IS 'User-assigned labels (app.labels_updated)';
+COMMENT ON COLUMN envs_view.labels IS 'User-assigned labels (env.labels_updated)';
+COMMENT ON COLUMN routes_view.labels IS 'User-assigned labels (route.labels_updated)';
+COMMENT ON COLUMN volumes_view.labels IS 'User-assigned labels (volume.labels_updated)';
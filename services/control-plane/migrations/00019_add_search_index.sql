@@ -0,0 +1,35 @@
+-- Migration: 00019_add_search_index
+-- Description: Org-wide search index over apps, envs, releases, routes, volumes, and instances
+-- See: docs/specs/state/materialized-views.md (search_index_view)
+
+-- Maintained by the `search` projection. The projection starts from
+-- checkpoint 0, so existing resources are indexed by replaying the log.
+CREATE TABLE IF NOT EXISTS search_index_view (
+    resource_type TEXT NOT NULL CHECK (
+        resource_type IN ('app', 'env', 'release', 'route', 'volume', 'instance')
+    ),
+    resource_id TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    app_id TEXT,
+    env_id TEXT,
+    -- Human-facing name matched by prefix: app/env/volume name, route
+    -- hostname, release image ref, instance process type.
+    name TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (resource_type, resource_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_index_org_id_prefix
+    ON search_index_view (org_id, lower(resource_id) text_pattern_ops);
+
+CREATE INDEX IF NOT EXISTS idx_search_index_org_name_prefix
+    ON search_index_view (org_id, lower(name) text_pattern_ops);
+
+CREATE INDEX IF NOT EXISTS idx_search_index_app_id
+    ON search_index_view (app_id) WHERE app_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_search_index_env_id
+    ON search_index_view (env_id) WHERE env_id IS NOT NULL;
+
+COMMENT ON TABLE search_index_view IS 'Name/ID prefix search index for GET /v1/orgs/{org_id}/search';
@@ -0,0 +1,38 @@
+-- Migration: 00020_idempotency_ttl_and_locks
+-- Description: Per-record expiry for idempotency records and in-flight request locks
+-- See: docs/specs/api/http-api.md (Idempotency)
+
+-- Records now carry their own expiry (configurable TTL, minimum 24 hours).
+-- Expired records are ignored on lookup and removed by the cleanup worker.
+ALTER TABLE idempotency_records
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+UPDATE idempotency_records
+SET expires_at = created_at + INTERVAL '7 days'
+WHERE expires_at IS NULL;
+
+ALTER TABLE idempotency_records
+    ALTER COLUMN expires_at SET NOT NULL,
+    ALTER COLUMN expires_at SET DEFAULT now() + INTERVAL '7 days';
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_records_expires_at
+    ON idempotency_records (expires_at);
+
+-- In-flight locks serialize concurrent requests that share an Idempotency-Key,
+-- so a retry that races the original waits for (and then replays) its
+-- response instead of appending events a second time.
+--
+-- lock_key is a hash of (credentials, method, path, Idempotency-Key).
+-- locked_until bounds how long a crashed holder can block retries.
+CREATE TABLE IF NOT EXISTS idempotency_locks (
+    lock_key TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    locked_until TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_locks_locked_until
+    ON idempotency_locks (locked_until);
+
+COMMENT ON TABLE idempotency_locks IS 'Short-lived locks held while an idempotent request is being processed';
+COMMENT ON COLUMN idempotency_records.expires_at IS 'Record is ignored and eligible for cleanup after this time';
@@ -0,0 +1,15 @@
+-- Migration: 00021_event_payload_encoding
+-- Description: Allow protobuf-only event payloads
+-- See: docs/specs/wire-formats/wire-formats.md
+
+-- With PLFM_EVENT_PAYLOAD_ENCODING=protobuf, high-volume event types
+-- (instance.*, node.*) are stored with payload_bytes only and a NULL JSON
+-- payload. Readers decode payload_bytes back to JSON (dual-read), so existing
+-- JSON rows and new protobuf-only rows coexist.
+ALTER TABLE events ALTER COLUMN payload DROP NOT NULL;
+
+ALTER TABLE events ADD CONSTRAINT events_payload_present
+    CHECK (payload IS NOT NULL OR (payload_bytes IS NOT NULL AND payload_type_url IS NOT NULL))
+    NOT VALID;
+
+COMMENT ON COLUMN events.payload IS 'Event-specific data (JSON) - NULL when only payload_bytes is stored; MUST NOT contain secret values';
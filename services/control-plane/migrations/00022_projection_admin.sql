@@ -0,0 +1,16 @@
+-- Migration: 00022_projection_admin
+-- Description: Track pause state and the last apply error per projection
+-- See: docs/specs/state/materialized-views.md
+
+-- Operators can pause a projection from /v1/_admin/projections; the worker
+-- stops applying events to it (its checkpoint stays put) until resumed.
+ALTER TABLE projection_checkpoints ADD COLUMN IF NOT EXISTS paused BOOLEAN NOT NULL DEFAULT false;
+
+-- The most recent failure to apply an event. Kept after the projection
+-- recovers so operators can see what went wrong and when.
+ALTER TABLE projection_checkpoints ADD COLUMN IF NOT EXISTS last_error TEXT;
+ALTER TABLE projection_checkpoints ADD COLUMN IF NOT EXISTS last_error_event_id BIGINT;
+ALTER TABLE projection_checkpoints ADD COLUMN IF NOT EXISTS last_error_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN projection_checkpoints.paused IS 'When true the projection worker skips this projection until resumed';
+COMMENT ON COLUMN projection_checkpoints.last_error IS 'Message of the most recent failure to apply an event';
@@ -0,0 +1,21 @@
+-- Migration: 00023_events_notify
+-- Description: NOTIFY listeners when events are appended
+-- See: docs/specs/state/materialized-views.md
+
+-- The projection worker LISTENs on this channel so new events are applied
+-- without waiting for the next poll. The trigger is per statement and the
+-- payload is empty, so a batch append sends one notification and Postgres
+-- folds duplicates within a transaction; notifications are delivered on
+-- commit only.
+CREATE OR REPLACE FUNCTION notify_events_appended() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('plfm_events', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS events_appended_notify ON events;
+CREATE TRIGGER events_appended_notify
+    AFTER INSERT ON events
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION notify_events_appended();
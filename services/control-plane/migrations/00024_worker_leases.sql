@@ -0,0 +1,17 @@
+-- Migration: 00024_worker_leases
+-- Description: Time-bounded leases for background work shared across control-plane replicas
+-- See: docs/specs/state/materialized-views.md
+
+-- Each lease names a unit of background work (e.g. `projection:apps`) that
+-- exactly one replica may perform at a time. The holder renews the lease
+-- well before leased_until; if it dies, another replica takes the lease over
+-- once it lapses.
+CREATE TABLE IF NOT EXISTS worker_leases (
+    lease_name TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    leased_until TIMESTAMPTZ NOT NULL,
+    acquired_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE worker_leases IS 'Ownership of background work across control-plane replicas';
+COMMENT ON COLUMN worker_leases.acquired_at IS 'When the current holder took the lease (unchanged by renewals)';
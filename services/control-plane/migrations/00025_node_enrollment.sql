@@ -0,0 +1,36 @@
+-- Migration: 00025_node_enrollment
+-- Description: Node bootstrap tokens and pending-approval enrollment
+-- See: docs/specs/api/http-api.md
+
+-- Single-use tokens an operator mints for provisioning a node. Only the
+-- SHA-256 hash of the token is stored; the token itself is returned once at
+-- creation. A token is consumed atomically by the enrollment that uses it.
+CREATE TABLE IF NOT EXISTS node_bootstrap_tokens (
+    token_id TEXT PRIMARY KEY,
+    token_hash TEXT NOT NULL UNIQUE,
+    description TEXT,
+    -- Nodes enrolled with this token wait for operator approval.
+    requires_approval BOOLEAN NOT NULL DEFAULT false,
+    created_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    used_by_node_id TEXT,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_node_bootstrap_tokens_expires
+    ON node_bootstrap_tokens (expires_at)
+    WHERE used_at IS NULL AND revoked_at IS NULL;
+
+COMMENT ON TABLE node_bootstrap_tokens IS 'Single-use node enrollment tokens (hashed)';
+
+-- Nodes enrolled under approval sit in pending_approval until an operator
+-- approves (active) or denies (denied) them.
+ALTER TABLE nodes_view DROP CONSTRAINT IF EXISTS nodes_view_state_check;
+ALTER TABLE nodes_view ADD CONSTRAINT nodes_view_state_check CHECK (
+    state IN (
+        'active', 'draining', 'disabled', 'degraded', 'offline',
+        'pending_approval', 'denied'
+    )
+);
@@ -0,0 +1,55 @@
+-- Migration: 00026_pki
+-- Description: Built-in certificate authority for platform identities
+-- See: docs/security/08-platform-pki.md
+
+-- Signing CAs. Exactly one is active; rotation retires it and creates a new
+-- one. Retired CAs stay in the trust bundle until the certificates they
+-- issued have expired. The private key is envelope-encrypted with the
+-- secrets master key (same scheme as secret_material).
+CREATE TABLE IF NOT EXISTS pki_authorities (
+    ca_id TEXT PRIMARY KEY,
+    cert_pem TEXT NOT NULL,
+    key_cipher TEXT NOT NULL,
+    key_nonce BYTEA NOT NULL,
+    key_ciphertext BYTEA NOT NULL,
+    master_key_id TEXT NOT NULL,
+    wrapped_data_key BYTEA NOT NULL,
+    wrapped_data_key_nonce BYTEA NOT NULL,
+    not_after TIMESTAMPTZ NOT NULL,
+    crl_number BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    retired_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pki_authorities_active
+    ON pki_authorities ((true))
+    WHERE retired_at IS NULL;
+
+COMMENT ON TABLE pki_authorities IS 'Platform CA certificates and encrypted keys';
+
+-- Every certificate the CA has issued (metadata only; keys never leave the
+-- requester).
+CREATE TABLE IF NOT EXISTS pki_certificates (
+    serial TEXT PRIMARY KEY,
+    ca_id TEXT NOT NULL REFERENCES pki_authorities(ca_id),
+    kind TEXT NOT NULL CHECK (kind IN ('node', 'ingress', 'instance')),
+    subject_id TEXT NOT NULL,
+    common_name TEXT NOT NULL,
+    fingerprint_sha256 TEXT NOT NULL,
+    not_before TIMESTAMPTZ NOT NULL,
+    not_after TIMESTAMPTZ NOT NULL,
+    issued_by TEXT NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    revoked_at TIMESTAMPTZ,
+    revocation_reason TEXT CHECK (revocation_reason IN ('key_compromise', 'superseded'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_pki_certificates_subject
+    ON pki_certificates (kind, subject_id, issued_at DESC);
+
+-- CRLs list unexpired revoked certificates per CA.
+CREATE INDEX IF NOT EXISTS idx_pki_certificates_revoked
+    ON pki_certificates (ca_id, not_after)
+    WHERE revoked_at IS NOT NULL;
+
+COMMENT ON TABLE pki_certificates IS 'Certificates issued by the platform CA';
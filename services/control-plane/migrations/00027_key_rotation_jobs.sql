@@ -0,0 +1,38 @@
+-- Migration: 00027_key_rotation_jobs
+-- Description: Background jobs that re-wrap secret data keys after a KEK change
+-- See: docs/security/03-secret-handling.md
+
+-- One row per rotation job. The cleanup worker advances the active job one
+-- batch at a time and records the cursor after each batch, so a job resumes
+-- where it stopped after a restart or leader change.
+CREATE TABLE IF NOT EXISTS key_rotation_jobs (
+    job_id TEXT PRIMARY KEY,
+    -- Restrict the job to one org's material (NULL = all material, plus
+    -- platform CA keys).
+    org_id TEXT,
+    state TEXT NOT NULL CHECK (state IN ('running', 'completed', 'failed', 'cancelled')),
+    -- Platform key ID at job creation, for the audit trail.
+    target_key_id TEXT NOT NULL,
+    batch_size INT NOT NULL CHECK (batch_size > 0),
+    -- Last secret_material.material_id processed.
+    cursor TEXT,
+    total BIGINT NOT NULL DEFAULT 0,
+    scanned BIGINT NOT NULL DEFAULT 0,
+    rewrapped BIGINT NOT NULL DEFAULT 0,
+    failed BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    requested_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+-- At most one job runs at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_key_rotation_jobs_running
+    ON key_rotation_jobs ((true))
+    WHERE state = 'running';
+
+CREATE INDEX IF NOT EXISTS idx_key_rotation_jobs_created_at
+    ON key_rotation_jobs (created_at DESC);
+
+COMMENT ON TABLE key_rotation_jobs IS 'Secrets key rotation (data key re-wrap) jobs and their progress';
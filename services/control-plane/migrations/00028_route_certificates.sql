@@ -0,0 +1,43 @@
+-- Migration: 00028_route_certificates
+-- Description: Org-uploaded TLS certificates for tls_terminate routes
+-- See: docs/specs/networking/ingress-l4.md (TLS termination with uploaded certificates)
+
+-- One certificate per route. The chain is public and stored as-is; the
+-- private key is envelope-encrypted in secret_material under the org's key,
+-- so key rotation jobs re-wrap it with the rest of the org's secrets.
+CREATE TABLE IF NOT EXISTS route_certificates (
+    route_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    app_id TEXT NOT NULL,
+    env_id TEXT NOT NULL,
+    hostname TEXT NOT NULL,
+    listen_port INT NOT NULL,
+    chain_pem TEXT NOT NULL,
+    key_material_id TEXT NOT NULL REFERENCES secret_material (material_id),
+    fingerprint_sha256 TEXT NOT NULL,
+    dns_names TEXT[] NOT NULL DEFAULT '{}',
+    not_before TIMESTAMPTZ NOT NULL,
+    not_after TIMESTAMPTZ NOT NULL,
+    -- Serve this certificate to clients that send no SNI on listen_port.
+    default_for_non_sni BOOLEAN NOT NULL DEFAULT false,
+    -- Set when route.cert_expiring is emitted; cleared on re-upload.
+    expiry_warned_at TIMESTAMPTZ,
+    uploaded_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_route_certificates_org_id
+    ON route_certificates (org_id);
+
+-- Expiry scan: certificates not yet warned about.
+CREATE INDEX IF NOT EXISTS idx_route_certificates_not_after
+    ON route_certificates (not_after)
+    WHERE expiry_warned_at IS NULL;
+
+-- A listen port has at most one default certificate for non-SNI clients.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_route_certificates_default_port
+    ON route_certificates (listen_port)
+    WHERE default_for_non_sni;
+
+COMMENT ON TABLE route_certificates IS 'Uploaded TLS certificates for tls_terminate routes (key in secret_material)';
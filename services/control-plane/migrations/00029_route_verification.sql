@@ -0,0 +1,28 @@
+-- Migration: 00029_route_verification
+-- Description: Hostname ownership verification for routes
+-- See: docs/specs/networking/ingress-l4.md (Hostname ownership verification)
+
+-- Routes that existed before verification are grandfathered as verified.
+ALTER TABLE routes_view
+    ADD COLUMN IF NOT EXISTS verification_status TEXT NOT NULL DEFAULT 'verified',
+    ADD COLUMN IF NOT EXISTS verification_token TEXT,
+    ADD COLUMN IF NOT EXISTS verification_method TEXT,
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS verification_error TEXT;
+
+-- Verifier scan: routes still waiting on DNS.
+CREATE INDEX IF NOT EXISTS idx_routes_view_verification_pending
+    ON routes_view (created_at)
+    WHERE verification_status = 'pending' AND NOT is_deleted;
+
+COMMENT ON COLUMN routes_view.verification_status IS 'pending | verified | failed; only verified routes are served by the edge';
+
+-- Verifier bookkeeping for pending routes. Operational state, not a
+-- projection: rows are dropped once the route is verified, fails, or is deleted.
+CREATE TABLE IF NOT EXISTS route_verification_checks (
+    route_id TEXT PRIMARY KEY,
+    attempts INT NOT NULL DEFAULT 0,
+    last_checked_at TIMESTAMPTZ,
+    last_error TEXT,
+    next_check_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,27 @@
+-- Migration: 00030_managed_hostnames
+-- Description: Platform-managed env hostnames and their published DNS records
+-- See: docs/specs/networking/ingress-l4.md (Managed platform hostnames)
+
+ALTER TABLE envs_view
+    ADD COLUMN IF NOT EXISTS managed_hostname TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_envs_view_managed_hostname
+    ON envs_view (managed_hostname)
+    WHERE managed_hostname IS NOT NULL AND NOT is_deleted;
+
+COMMENT ON COLUMN envs_view.managed_hostname IS '<env>-<app>-<org>.apps.<platform-domain>, allocated at env creation; NULL when managed hostnames are off';
+
+-- What the DNS publisher last wrote to the provider. Operational state, not a
+-- projection: the publisher diffs live envs against it on every pass.
+CREATE TABLE IF NOT EXISTS managed_dns_records (
+    hostname TEXT PRIMARY KEY,
+    env_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    targets TEXT[] NOT NULL DEFAULT '{}',
+    published_at TIMESTAMPTZ,
+    last_error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_managed_dns_records_env
+    ON managed_dns_records (env_id);
@@ -0,0 +1,8 @@
+-- Migration: 00031_internal_routes
+-- Description: Internal (overlay-only) routes
+-- See: docs/specs/networking/ingress-l4.md (Internal routes)
+
+ALTER TABLE routes_view
+    ADD COLUMN IF NOT EXISTS internal BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN routes_view.internal IS 'Served only by internal ingress listeners on the overlay network; resolvable only through node-local DNS';
@@ -0,0 +1,35 @@
+-- Migration: 00032_ingress_status
+-- Description: Per-ingress route programming reports and aggregated route liveness
+-- See: docs/specs/networking/ingress-l4.md (Ingress status reporting)
+
+-- Last report from each ingress. Operational state, not a projection:
+-- ingresses overwrite their own row on every report.
+CREATE TABLE IF NOT EXISTS ingress_nodes (
+    org_id TEXT NOT NULL,
+    ingress_id TEXT NOT NULL,
+    public_ips TEXT[] NOT NULL DEFAULT '{}',
+    route_count INT NOT NULL DEFAULT 0,
+    last_report_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (org_id, ingress_id)
+);
+
+-- Routes each ingress has programmed, replaced wholesale on every report.
+CREATE TABLE IF NOT EXISTS ingress_route_status (
+    org_id TEXT NOT NULL,
+    ingress_id TEXT NOT NULL,
+    route_id TEXT NOT NULL,
+    backend_count INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (org_id, ingress_id, route_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingress_route_status_route
+    ON ingress_route_status (route_id);
+
+-- Aggregated from the reports above; refreshed on every ingress report.
+ALTER TABLE routes_view
+    ADD COLUMN IF NOT EXISTS ingress_live_count INT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS ingress_total_count INT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS ingress_status_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN routes_view.ingress_live_count IS 'Reporting ingresses that have the route programmed with at least one backend';
+COMMENT ON COLUMN routes_view.ingress_total_count IS 'Ingresses that reported for the org within the staleness window';
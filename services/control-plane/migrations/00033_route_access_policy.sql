@@ -0,0 +1,8 @@
+-- Migration: 00033_route_access_policy
+-- Description: Per-route client CIDR and TLS fingerprint filtering
+-- See: docs/specs/networking/ingress-l4.md (Client access policies)
+
+ALTER TABLE routes_view
+    ADD COLUMN IF NOT EXISTS access_policy JSONB;
+
+COMMENT ON COLUMN routes_view.access_policy IS 'allow/deny lists of client CIDRs and TLS fingerprints (JA3 hash or JA4) enforced by the edge; NULL when unrestricted';
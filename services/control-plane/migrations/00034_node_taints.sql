@@ -0,0 +1,15 @@
+-- Migration: 00034_node_taints
+-- Description: Node taints and per-process-type tolerations / node selectors
+-- See: docs/specs/scheduler/placement.md (Node labels, taints and tolerations)
+
+ALTER TABLE nodes_view
+    ADD COLUMN IF NOT EXISTS taints JSONB NOT NULL DEFAULT '[]';
+
+COMMENT ON COLUMN nodes_view.taints IS 'Array of {key, value?, effect} taints; effect is NoSchedule or PreferNoSchedule';
+
+ALTER TABLE env_scale_view
+    ADD COLUMN IF NOT EXISTS node_selector JSONB NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS tolerations JSONB NOT NULL DEFAULT '[]';
+
+COMMENT ON COLUMN env_scale_view.node_selector IS 'Node labels an instance node must carry (key -> value)';
+COMMENT ON COLUMN env_scale_view.tolerations IS 'Array of {key, value?, effect?} tolerations matched against node taints';
@@ -0,0 +1,31 @@
+-- Migration: 00035_instance_drift
+-- Description: Node instance inventories and desired/actual drift tracking
+-- See: docs/specs/scheduler/reconciliation-loop.md (Drift detection)
+
+-- Latest instance inventory reported by each node's heartbeat. Operational
+-- state, not a projection: overwritten by every heartbeat that carries one.
+CREATE TABLE IF NOT EXISTS node_instance_reports (
+    node_id TEXT PRIMARY KEY,
+    instances JSONB NOT NULL DEFAULT '{}',
+    reported_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON COLUMN node_instance_reports.instances IS 'instance_id -> status as reported by the node agent';
+
+-- Open drift found by the drift detector, one row per (node, instance).
+-- Rows are removed once the node report and desired state agree again.
+CREATE TABLE IF NOT EXISTS instance_drift (
+    node_id TEXT NOT NULL,
+    instance_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('not_reported', 'not_desired')),
+    org_id TEXT,
+    env_id TEXT,
+    desired_state TEXT,
+    reported_status TEXT,
+    first_detected_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_detected_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (node_id, instance_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_instance_drift_kind
+    ON instance_drift (kind);
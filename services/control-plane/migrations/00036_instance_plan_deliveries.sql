@@ -0,0 +1,11 @@
+-- Migration: 00036_instance_plan_deliveries
+-- Description: First plan delivery per instance, for deploy and instance timelines
+-- See: docs/specs/api/http-api.md (Timelines)
+
+-- Operational state, not a projection: written when a node fetches a plan
+-- that contains the instance for the first time, never updated.
+CREATE TABLE IF NOT EXISTS instance_plan_deliveries (
+    instance_id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    delivered_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
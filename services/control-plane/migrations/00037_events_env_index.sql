@@ -0,0 +1,6 @@
+-- Migration: 00037_events_env_index
+-- Description: Index env-scoped event reads (env configuration history and diff)
+-- See: docs/specs/api/http-api.md (Environments)
+
+CREATE INDEX IF NOT EXISTS idx_events_env_id
+    ON events (env_id, event_id) WHERE env_id IS NOT NULL;
@@ -0,0 +1,23 @@
+-- Migration: 00038_volume_home_node
+-- Description: Track the node holding each local volume so the scheduler can pin
+-- instances that mount it
+-- See: docs/specs/storage/volumes.md (Locality constraints and scheduling)
+
+ALTER TABLE volumes_view
+    ADD COLUMN IF NOT EXISTS home_node_id TEXT;
+
+-- Backfill from the most recent instance of a group the volume is attached to.
+UPDATE volumes_view v
+SET home_node_id = (
+    SELECT i.node_id
+    FROM volume_attachments_view a
+    JOIN instances_desired_view i
+        ON i.env_id = a.env_id AND i.process_type = a.process_type
+    WHERE a.volume_id = v.volume_id AND NOT a.is_deleted
+    ORDER BY i.created_at DESC
+    LIMIT 1
+)
+WHERE v.home_node_id IS NULL AND NOT v.is_deleted;
+
+CREATE INDEX IF NOT EXISTS idx_volume_attachments_group
+    ON volume_attachments_view (env_id, process_type) WHERE NOT is_deleted;
@@ -0,0 +1,10 @@
+-- Migration: 00039_volume_resize
+-- Description: Track the outcome of volume grow requests
+-- See: docs/specs/storage/volumes.md (Resize)
+
+-- pending: requested, not yet confirmed by the home node
+-- completed: the home node grew the backing storage
+-- failed: the home node could not grow it (see resize_error)
+ALTER TABLE volumes_view
+    ADD COLUMN IF NOT EXISTS resize_status TEXT,
+    ADD COLUMN IF NOT EXISTS resize_error TEXT;
@@ -0,0 +1,22 @@
+-- Migration: 00040_volume_backup_policy
+-- Description: Per-volume scheduled snapshots with retention
+-- See: docs/specs/storage/volumes.md (Scheduled snapshots and retention)
+
+ALTER TABLE volumes_view
+    ADD COLUMN IF NOT EXISTS backup_schedule TEXT,
+    ADD COLUMN IF NOT EXISTS backup_retain_count INT,
+    ADD COLUMN IF NOT EXISTS backup_retain_max_age_secs BIGINT,
+    ADD COLUMN IF NOT EXISTS backup_policy_updated_at TIMESTAMPTZ,
+    -- Most recent scheduled snapshot (maintained by the snapshots projection)
+    ADD COLUMN IF NOT EXISTS backup_last_snapshot_id TEXT,
+    ADD COLUMN IF NOT EXISTS backup_last_run_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS backup_last_status TEXT;
+
+ALTER TABLE snapshots_view
+    ADD COLUMN IF NOT EXISTS scheduled BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_volumes_backup_schedule
+    ON volumes_view (volume_id) WHERE backup_schedule IS NOT NULL AND NOT is_deleted;
+
+CREATE INDEX IF NOT EXISTS idx_snapshots_scheduled
+    ON snapshots_view (volume_id, created_at) WHERE scheduled;
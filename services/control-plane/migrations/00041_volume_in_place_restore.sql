@@ -0,0 +1,22 @@
+-- Migration: 00041_volume_in_place_restore
+-- Description: Restore a snapshot over its own volume with progress reporting
+-- See: docs/specs/storage/volumes.md (In-place restore)
+
+ALTER TABLE restore_jobs_view
+    ADD COLUMN IF NOT EXISTS in_place BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS bytes_restored BIGINT,
+    ADD COLUMN IF NOT EXISTS bytes_total BIGINT;
+
+-- Most recent in-place restore of the volume (maintained by the restore_jobs
+-- projection). queued/running restores keep the volume's process types down.
+ALTER TABLE volumes_view
+    ADD COLUMN IF NOT EXISTS restore_id TEXT,
+    ADD COLUMN IF NOT EXISTS restore_snapshot_id TEXT,
+    ADD COLUMN IF NOT EXISTS restore_status TEXT,
+    ADD COLUMN IF NOT EXISTS restore_bytes_restored BIGINT,
+    ADD COLUMN IF NOT EXISTS restore_bytes_total BIGINT,
+    ADD COLUMN IF NOT EXISTS restore_error TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_restore_jobs_in_place_active
+    ON restore_jobs_view (source_volume_id)
+    WHERE in_place AND status IN ('queued', 'running');
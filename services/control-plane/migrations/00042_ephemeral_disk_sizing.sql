@@ -0,0 +1,8 @@
+-- Migration: 00042_ephemeral_disk_sizing
+-- Description: Per-process-type scratch disk size
+-- See: docs/specs/manifest/workload-spec.md (resources.disk)
+
+ALTER TABLE env_scale_view
+    ADD COLUMN IF NOT EXISTS ephemeral_disk_bytes BIGINT;
+
+COMMENT ON COLUMN env_scale_view.ephemeral_disk_bytes IS 'Scratch disk per instance in bytes; NULL means the 4 GiB default';
@@ -0,0 +1,10 @@
+-- Migration: 00043_rollout_limits
+-- Description: Per-process-type rolling deploy surge and unavailability limits
+-- See: docs/specs/scheduler/reconciliation-loop.md (rolling strategy)
+
+ALTER TABLE env_scale_view
+    ADD COLUMN IF NOT EXISTS max_surge TEXT,
+    ADD COLUMN IF NOT EXISTS max_unavailable TEXT;
+
+COMMENT ON COLUMN env_scale_view.max_surge IS 'Instances a rollout may start above desired, as a count or percentage (e.g. 25%); NULL means 1';
+COMMENT ON COLUMN env_scale_view.max_unavailable IS 'Instances a rollout may take below desired, as a count or percentage (e.g. 25%); NULL means 0';
@@ -0,0 +1,90 @@
+-- Migration: 00044_notifications
+-- Description: Org notification channels, rules, and per-channel delivery logs
+-- See: docs/specs/observability/notifications.md
+
+-- Where notifications go. Non-secret settings live in config; the Slack
+-- webhook URL, SMTP password, or webhook signing secret is envelope-encrypted
+-- in secret_material under the org's key, so key rotation jobs re-wrap it
+-- with the rest of the org's secrets.
+CREATE TABLE IF NOT EXISTS notification_channels (
+    channel_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('smtp', 'slack', 'webhook')),
+    config JSONB NOT NULL DEFAULT '{}',
+    secret_material_id TEXT REFERENCES secret_material (material_id),
+    created_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (org_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_channels_material
+    ON notification_channels (secret_material_id)
+    WHERE secret_material_id IS NOT NULL;
+
+-- Which events notify which channels. Templates are NULL for the trigger's
+-- default wording.
+CREATE TABLE IF NOT EXISTS notification_rules (
+    rule_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    trigger TEXT NOT NULL CHECK (trigger IN ('deploy_failed', 'instance_crash_looping', 'cert_expiring')),
+    channel_ids TEXT[] NOT NULL,
+    -- Optional scope; NULL matches every app / env in the org.
+    app_id TEXT,
+    env_id TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    subject_template TEXT,
+    body_template TEXT,
+    created_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (org_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_rules_org_trigger
+    ON notification_rules (org_id, trigger)
+    WHERE enabled;
+
+-- One row per (rule, channel, occurrence), rendered when the event is
+-- matched and retried by the notifier until delivered or out of attempts.
+-- The dedupe key names the occurrence (deploy, instance, or route
+-- certificate) so a repeated event does not notify twice.
+CREATE TABLE IF NOT EXISTS notification_deliveries (
+    delivery_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    rule_id TEXT NOT NULL,
+    event_id BIGINT NOT NULL,
+    trigger TEXT NOT NULL,
+    dedupe_key TEXT NOT NULL,
+    state TEXT NOT NULL CHECK (state IN ('pending', 'delivered', 'failed')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_error TEXT,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delivered_at TIMESTAMPTZ,
+    UNIQUE (rule_id, channel_id, dedupe_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_deliveries_due
+    ON notification_deliveries (next_attempt_at)
+    WHERE state = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_notification_deliveries_channel
+    ON notification_deliveries (channel_id, delivery_id DESC);
+
+-- Last event the notifier has matched against rules (a single row).
+CREATE TABLE IF NOT EXISTS notification_cursor (
+    singleton BOOLEAN PRIMARY KEY DEFAULT true CHECK (singleton),
+    last_event_id BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE notification_channels IS 'Org notification destinations: SMTP, Slack webhook, or generic webhook (secret in secret_material)';
+COMMENT ON TABLE notification_rules IS 'Org notification rules: trigger, scope, target channels, and message templates';
+COMMENT ON TABLE notification_deliveries IS 'Rendered notifications and their delivery state, per channel';
+COMMENT ON TABLE notification_cursor IS 'Event log position of the notifier';
@@ -0,0 +1,74 @@
+-- Migration: 00045_alerts
+-- Description: Org alert rules, open alerts, and the instance restart log
+-- See: docs/specs/observability/alert-rules.md
+
+-- Conditions evaluated by the alert evaluator. threshold and window_seconds
+-- are set only for the conditions that take them.
+CREATE TABLE IF NOT EXISTS alert_rules (
+    rule_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    condition TEXT NOT NULL CHECK (condition IN ('env_unhealthy', 'node_offline', 'projection_lag', 'instance_restarts')),
+    threshold BIGINT,
+    window_seconds INT,
+    -- How long the condition must hold before the alert fires.
+    for_seconds INT NOT NULL DEFAULT 0,
+    -- Optional scope for env conditions; NULL matches every app / env in the org.
+    app_id TEXT,
+    env_id TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (org_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_rules_enabled
+    ON alert_rules (org_id)
+    WHERE enabled;
+
+-- Open alerts: one row per (rule, target) while the condition holds. Rows
+-- start pending, turn firing after the rule's for_seconds, and are deleted
+-- when the condition clears.
+CREATE TABLE IF NOT EXISTS alerts (
+    rule_id TEXT NOT NULL REFERENCES alert_rules (rule_id) ON DELETE CASCADE,
+    -- env_id, node_id, or projection name, by condition.
+    target TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    app_id TEXT,
+    env_id TEXT,
+    state TEXT NOT NULL CHECK (state IN ('pending', 'firing')),
+    value BIGINT,
+    summary TEXT NOT NULL,
+    pending_since TIMESTAMPTZ NOT NULL DEFAULT now(),
+    fired_at TIMESTAMPTZ,
+    last_evaluated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (rule_id, target)
+);
+
+CREATE INDEX IF NOT EXISTS idx_alerts_org_state
+    ON alerts (org_id, state);
+
+-- Instance failures (instance.status_changed to failed), one per event,
+-- written by the instances projection. Counted by instance_restarts rules
+-- and pruned after a day.
+CREATE TABLE IF NOT EXISTS instance_restarts (
+    event_id BIGINT PRIMARY KEY,
+    instance_id TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    env_id TEXT NOT NULL,
+    reason_code TEXT,
+    restarted_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_instance_restarts_env
+    ON instance_restarts (env_id, restarted_at);
+
+-- Alerts feed notification rules.
+ALTER TABLE notification_rules DROP CONSTRAINT IF EXISTS notification_rules_trigger_check;
+ALTER TABLE notification_rules ADD CONSTRAINT notification_rules_trigger_check
+    CHECK (trigger IN ('deploy_failed', 'instance_crash_looping', 'cert_expiring', 'alert_fired', 'alert_resolved'));
+
+COMMENT ON TABLE alert_rules IS 'Org alert rules: condition, threshold, duration, and scope';
+COMMENT ON TABLE alerts IS 'Pending and firing alerts per (rule, target)';
+COMMENT ON TABLE instance_restarts IS 'Recent instance failures (from instance.status_changed events), for restart alerts';
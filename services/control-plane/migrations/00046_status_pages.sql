@@ -0,0 +1,46 @@
+-- Migration: 00046_status_pages
+-- Description: Public org status pages and the instance status history behind their uptime
+-- See: docs/specs/observability/status-pages.md
+
+-- One status page per org. The public token is an HMAC of the org ID under
+-- signing_key, so rotating the key revokes every issued link.
+CREATE TABLE IF NOT EXISTS status_pages (
+    org_id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    -- Envs shown on the page; empty shows every env in the org.
+    env_ids TEXT[] NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    signing_key BYTEA NOT NULL,
+    created_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Every instance status transition, written by the instances projection
+-- (event_id is NULL only for rows seeded below). Status pages compute
+-- uptime from it; the cleanup worker prunes rows past the longest uptime
+-- window, keeping each instance's last state before the cutoff while it
+-- is ready.
+CREATE TABLE IF NOT EXISTS instance_status_history (
+    history_id BIGSERIAL PRIMARY KEY,
+    event_id BIGINT UNIQUE,
+    instance_id TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    env_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    reported_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_instance_status_history_org
+    ON instance_status_history (org_id, reported_at);
+
+CREATE INDEX IF NOT EXISTS idx_instance_status_history_instance
+    ON instance_status_history (instance_id, reported_at);
+
+-- Start the history from the current status of every instance.
+INSERT INTO instance_status_history (instance_id, org_id, env_id, status, reported_at)
+SELECT instance_id, org_id, env_id, status, reported_at
+FROM instances_status_view;
+
+COMMENT ON TABLE status_pages IS 'Per-org public status page settings and token signing key';
+COMMENT ON TABLE instance_status_history IS 'Instance status transitions (from instance.status_changed events), for uptime';
@@ -0,0 +1,37 @@
+-- Migration: 00047_slo
+-- Description: Per-env availability objectives and daily availability rollups
+-- See: docs/specs/observability/slo.md
+
+-- An env's availability objective, in percent. Envs without a row use the
+-- default objective (99.9).
+CREATE TABLE IF NOT EXISTS env_slos (
+    env_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    app_id TEXT NOT NULL,
+    objective DOUBLE PRECISION NOT NULL,
+    updated_by TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- One row per env per UTC day, computed from instance_status_history by the
+-- cleanup worker once the day is over. Rollups outlive the history they are
+-- computed from, so SLO windows can reach past its retention.
+CREATE TABLE IF NOT EXISTS env_slo_daily (
+    env_id TEXT NOT NULL,
+    day DATE NOT NULL,
+    org_id TEXT NOT NULL,
+    -- Seconds of the day after the env's first recorded transition.
+    observed_seconds BIGINT NOT NULL,
+    -- Seconds during which every routed process type had a ready instance.
+    available_seconds BIGINT NOT NULL,
+    -- Instance-seconds spent ready and failed, summed over instances.
+    ready_instance_seconds BIGINT NOT NULL,
+    failed_instance_seconds BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (env_id, day)
+);
+
+CREATE INDEX IF NOT EXISTS idx_env_slo_daily_day ON env_slo_daily (day);
+
+COMMENT ON TABLE env_slos IS 'Per-env availability objectives (percent)';
+COMMENT ON TABLE env_slo_daily IS 'Daily env availability rollups, from instance_status_history';
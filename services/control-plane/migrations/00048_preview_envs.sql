@@ -0,0 +1,20 @@
+-- Migration: 00048_preview_envs
+-- Description: Ephemeral preview envs with an expiry, and release source metadata
+-- See: docs/specs/api/http-api.md (Preview environments)
+
+ALTER TABLE envs_view
+    ADD COLUMN IF NOT EXISTS preview BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+-- The cleanup worker requests teardown of envs past their expiry.
+CREATE INDEX IF NOT EXISTS idx_envs_expires_at
+    ON envs_view (expires_at)
+    WHERE expires_at IS NOT NULL AND deletion_requested_at IS NULL AND NOT is_deleted;
+
+COMMENT ON COLUMN envs_view.preview IS 'Ephemeral preview env (env.created preview)';
+COMMENT ON COLUMN envs_view.expires_at IS 'When a preview env is torn down by the cleanup worker; NULL for regular envs';
+
+ALTER TABLE releases_view
+    ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN releases_view.metadata IS 'Source metadata from release.created (git_ref)';
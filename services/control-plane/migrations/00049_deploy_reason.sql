@@ -0,0 +1,8 @@
+-- Migration: 00049_deploy_reason
+-- Description: Record why a deploy was made (deploy.created reason)
+-- See: docs/specs/state/event-types.md (deploy.created)
+
+ALTER TABLE deploys_view
+    ADD COLUMN IF NOT EXISTS reason TEXT;
+
+COMMENT ON COLUMN deploys_view.reason IS 'Operator-supplied change reason from deploy.created';
@@ -0,0 +1,28 @@
+-- Migration: 00050_env_config_vars
+-- Description: Non-secret per-env config vars delivered as workload environment variables
+-- See: docs/specs/api/http-api.md (Config vars), docs/specs/state/event-types.md (env.config_vars_set)
+
+-- Current config vars per env (env.config_vars_set).
+CREATE TABLE IF NOT EXISTS env_config_vars_view (
+    env_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    app_id TEXT NOT NULL,
+    vars JSONB NOT NULL DEFAULT '{}',
+    version INT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Every config vars version, so instances keep the set they were allocated with.
+CREATE TABLE IF NOT EXISTS env_config_var_versions (
+    env_id TEXT NOT NULL,
+    version INT NOT NULL,
+    vars JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (env_id, version)
+);
+
+ALTER TABLE instances_desired_view
+    ADD COLUMN IF NOT EXISTS config_vars_version INT;
+
+COMMENT ON COLUMN env_config_vars_view.version IS 'Config vars version; part of the instance spec hash';
+COMMENT ON COLUMN instances_desired_view.config_vars_version IS 'Config vars version the instance was allocated with (instance.allocated); NULL when the env has none';
@@ -0,0 +1,20 @@
+-- Migration: 00051_env_process_resources
+-- Description: Per-process-type cpu/memory/disk overrides, independent of releases
+-- See: docs/specs/api/http-api.md (Process resources), docs/specs/state/event-types.md (env.process_resources_set)
+
+-- Current resource overrides per (env, process type) (env.process_resources_set).
+-- NULL columns fall back to the release (cpu, memory) or the env scale (disk).
+CREATE TABLE IF NOT EXISTS env_process_resources_view (
+    env_id TEXT NOT NULL,
+    process_type TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    app_id TEXT NOT NULL,
+    cpu_request DOUBLE PRECISION,
+    memory_limit_bytes BIGINT,
+    ephemeral_disk_bytes BIGINT,
+    resource_version INT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (env_id, process_type)
+);
+
+COMMENT ON COLUMN env_process_resources_view.resource_version IS 'Override version for the process type; checked by If-Match on writes';
@@ -0,0 +1,8 @@
+-- Migration: 00052_secrets_reload
+-- Description: Per-process-type secrets reload mode (restart, or in-place delivery with an optional signal/hook)
+-- See: docs/specs/manifest/manifest-schema.md (secrets), docs/specs/scheduler/reconciliation-loop.md (in-place config updates)
+
+ALTER TABLE env_scale_view
+    ADD COLUMN IF NOT EXISTS secrets_reload JSONB;
+
+COMMENT ON COLUMN env_scale_view.secrets_reload IS 'How running instances pick up a new secrets version ({"mode": "restart" | "file" | "signal" | "exec", ...}); NULL means restart';
@@ -0,0 +1,26 @@
+-- Migration: 00053_identity_tokens
+-- Description: Signing keys for instance identity tokens
+-- See: docs/security/08-platform-pki.md (Instance identity tokens)
+
+-- Identity token signing keys (ECDSA P-256). Exactly one is active;
+-- rotation retires it and creates a new one. Retired keys stay in the JWKS
+-- until the tokens they signed have expired. The private key (PKCS#8) is
+-- envelope-encrypted with the secrets master key, like pki_authorities.
+CREATE TABLE IF NOT EXISTS identity_token_keys (
+    kid TEXT PRIMARY KEY,
+    public_jwk JSONB NOT NULL,
+    key_cipher TEXT NOT NULL,
+    key_nonce BYTEA NOT NULL,
+    key_ciphertext BYTEA NOT NULL,
+    master_key_id TEXT NOT NULL,
+    wrapped_data_key BYTEA NOT NULL,
+    wrapped_data_key_nonce BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    retired_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_identity_token_keys_active
+    ON identity_token_keys ((true))
+    WHERE retired_at IS NULL;
+
+COMMENT ON TABLE identity_token_keys IS 'Instance identity token signing keys; public halves are served as JWKS';
@@ -0,0 +1,38 @@
+-- Migration: 00054_event_integrity
+-- Description: Hash chain and signed checkpoints for the event log
+-- See: docs/specs/state/event-log.md (Integrity)
+
+-- event_hash: SHA-256 over the row and prev_hash, the event_hash of the
+-- previous event of the same aggregate. NULL on rows written before chaining.
+ALTER TABLE events ADD COLUMN IF NOT EXISTS prev_hash BYTEA;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS event_hash BYTEA;
+
+COMMENT ON COLUMN events.prev_hash IS 'event_hash of the previous event of the aggregate (NULL for the first chained event)';
+COMMENT ON COLUMN events.event_hash IS 'SHA-256 of the row chained to prev_hash (NULL before chaining)';
+
+-- Each checkpoint seals the events in (from_event_id, to_event_id] with a
+-- Merkle root over their hashes, chained to the previous checkpoint's root,
+-- and signed (JWS) with the identity token key named by kid.
+CREATE TABLE IF NOT EXISTS event_log_checkpoints (
+    checkpoint_id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    from_event_id BIGINT NOT NULL UNIQUE,
+    to_event_id BIGINT NOT NULL,
+    event_count INTEGER NOT NULL,
+    merkle_root BYTEA NOT NULL,
+    prev_root BYTEA,
+    kid TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (to_event_id > from_event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_log_checkpoints_to
+    ON event_log_checkpoints (to_event_id);
+
+CREATE TRIGGER event_log_checkpoints_immutable_trigger
+    BEFORE UPDATE OR DELETE ON event_log_checkpoints
+    FOR EACH ROW
+    EXECUTE FUNCTION prevent_event_mutation();
+
+COMMENT ON TABLE event_log_checkpoints IS 'Signed Merkle roots over ranges of the event log (append-only)';
+COMMENT ON COLUMN event_log_checkpoints.from_event_id IS 'Exclusive start: to_event_id of the previous checkpoint, or 0';
@@ -0,0 +1,49 @@
+-- Migration: 00055_trash
+-- Description: Restore window for deleted apps, envs, routes and volumes
+-- See: docs/specs/state/materialized-views.md (Trash)
+
+-- deleted_at is set by *.deleted and cleared by *.restored; a deleted row is
+-- restorable until the cleanup worker emits *.purged, which sets purged_at.
+ALTER TABLE apps_view
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS purged_at TIMESTAMPTZ;
+
+ALTER TABLE envs_view
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS purged_at TIMESTAMPTZ;
+
+ALTER TABLE routes_view
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS purged_at TIMESTAMPTZ;
+
+ALTER TABLE volumes_view
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS purged_at TIMESTAMPTZ;
+
+-- The last update of a row deleted before this migration is its deletion.
+UPDATE apps_view SET deleted_at = updated_at WHERE is_deleted AND deleted_at IS NULL;
+UPDATE envs_view SET deleted_at = updated_at WHERE is_deleted AND deleted_at IS NULL;
+UPDATE routes_view SET deleted_at = updated_at WHERE is_deleted AND deleted_at IS NULL;
+UPDATE volumes_view SET deleted_at = updated_at WHERE is_deleted AND deleted_at IS NULL;
+
+-- The trash listing and the purge pass both scan restorable rows by age.
+CREATE INDEX IF NOT EXISTS idx_apps_trash
+    ON apps_view (deleted_at) WHERE is_deleted AND purged_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_envs_trash
+    ON envs_view (deleted_at) WHERE is_deleted AND purged_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_routes_trash
+    ON routes_view (deleted_at) WHERE is_deleted AND purged_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_volumes_trash
+    ON volumes_view (deleted_at) WHERE is_deleted AND purged_at IS NULL;
+
+COMMENT ON COLUMN apps_view.deleted_at IS 'When the app was deleted (app.deleted); NULL while live';
+COMMENT ON COLUMN apps_view.purged_at IS 'When the app left the trash (app.purged); no longer restorable';
+COMMENT ON COLUMN envs_view.deleted_at IS 'When the env was deleted (env.deleted); NULL while live';
+COMMENT ON COLUMN envs_view.purged_at IS 'When the env left the trash (env.purged); no longer restorable';
+COMMENT ON COLUMN routes_view.deleted_at IS 'When the route was deleted (route.deleted); NULL while live';
+COMMENT ON COLUMN routes_view.purged_at IS 'When the route left the trash (route.purged); no longer restorable';
+COMMENT ON COLUMN volumes_view.deleted_at IS 'When the volume was deleted (volume.deleted); NULL while live';
+COMMENT ON COLUMN volumes_view.purged_at IS 'When the volume left the trash (volume.purged); no longer restorable';
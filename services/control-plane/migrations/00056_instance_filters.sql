@@ -0,0 +1,17 @@
+-- Migration: 00056_instance_filters
+-- Description: Indexes for filtering GET /v1/instances
+-- See: docs/specs/api/http-api.md (Instances (runtime view))
+
+-- org_id, env_id and node_id are already indexed (00004).
+CREATE INDEX IF NOT EXISTS idx_instances_desired_app_id
+    ON instances_desired_view (app_id);
+
+CREATE INDEX IF NOT EXISTS idx_instances_desired_env_process_type
+    ON instances_desired_view (env_id, process_type);
+
+CREATE INDEX IF NOT EXISTS idx_instances_desired_spec_hash
+    ON instances_desired_view (spec_hash);
+
+-- ?stale= scans by the age of the last status report.
+CREATE INDEX IF NOT EXISTS idx_instances_status_reported_at
+    ON instances_status_view (reported_at);
@@ -0,0 +1,23 @@
+-- Migration: 00057_node_watchdog
+-- Description: Heartbeat tracking and flap detection for the node watchdog
+-- See: docs/specs/scheduler/drain-evict-reschedule.md (Offline (detected))
+
+-- Set from node.capacity_updated, which every heartbeat emits.
+ALTER TABLE nodes_view
+    ADD COLUMN IF NOT EXISTS last_heartbeat_at TIMESTAMPTZ;
+
+-- Heartbeats dominate node updates, so updated_at is the best estimate.
+UPDATE nodes_view SET last_heartbeat_at = updated_at WHERE last_heartbeat_at IS NULL;
+
+COMMENT ON COLUMN nodes_view.last_heartbeat_at IS 'When the node last heartbeated (node.capacity_updated)';
+
+-- One row per time the watchdog marked a node offline; rows older than the
+-- flap window are pruned by the watchdog. Operational state, not a view.
+CREATE TABLE IF NOT EXISTS node_offline_marks (
+    node_id TEXT NOT NULL,
+    marked_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (node_id, marked_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_node_offline_marks_marked_at
+    ON node_offline_marks (marked_at);
@@ -0,0 +1,13 @@
+-- Migration: 00058_maintenance_mode
+-- Description: Control plane maintenance mode (read-only API)
+-- See: docs/ops/runbooks/control-plane-maintenance.md
+
+-- At most one row; maintenance is on while it exists. Operational state,
+-- not a view: written directly by PUT /v1/_admin/maintenance.
+CREATE TABLE IF NOT EXISTS control_plane_maintenance (
+    singleton BOOLEAN PRIMARY KEY DEFAULT true CHECK (singleton),
+    reason TEXT,
+    retry_after_secs INTEGER NOT NULL CHECK (retry_after_secs >= 0),
+    started_at TIMESTAMPTZ NOT NULL,
+    started_by TEXT NOT NULL
+);
@@ -0,0 +1,16 @@
+-- Migration: 00059_schema_compat
+-- Description: Oldest binary schema version that may run against this database
+-- See: docs/ops/08-schema-migrations.md
+
+-- Single row. Migrations are expand-only by default; a migration that breaks
+-- older binaries ends with
+--   UPDATE schema_compat SET min_compatible_version = <its version>;
+-- and binaries built before it refuse to start.
+CREATE TABLE IF NOT EXISTS schema_compat (
+    singleton BOOLEAN PRIMARY KEY DEFAULT true CHECK (singleton),
+    min_compatible_version BIGINT NOT NULL
+);
+
+INSERT INTO schema_compat (min_compatible_version)
+VALUES (0)
+ON CONFLICT (singleton) DO NOTHING;
@@ -0,0 +1,16 @@
+-- Migration: 00060_instance_console
+-- Description: Latest guest serial console tail per instance
+-- See: docs/specs/runtime/firecracker-boot.md (Serial console)
+
+-- Uploaded by node agents (PUT /v1/nodes/{node_id}/instances/{instance_id}/console),
+-- one row per instance replaced on every upload. Operational state, not a
+-- view: the console is diagnostic output and is not part of the event log.
+CREATE TABLE IF NOT EXISTS instance_console (
+    instance_id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    boot_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    truncated BOOLEAN NOT NULL DEFAULT false,
+    captured_at TIMESTAMPTZ NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
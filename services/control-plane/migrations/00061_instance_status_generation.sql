@@ -0,0 +1,11 @@
+-- Migration: 00061_instance_status_generation
+-- Description: Track the plan generation behind each instance status row
+-- See: docs/specs/runtime/firecracker-boot.md (Boot IDs)
+
+-- Together with boot_id, orders status reports: reports from an older
+-- generation or an earlier boot are not applied (see instance_status.rs).
+-- NULL for rows written before agents reported generations.
+ALTER TABLE instances_status_view
+    ADD COLUMN IF NOT EXISTS generation INT;
+
+COMMENT ON COLUMN instances_status_view.generation IS 'Plan generation of the boot that reported the current status';
@@ -0,0 +1,15 @@
+-- Migration: 00062_event_export_cursor
+-- Description: Event log position of the Kafka/NATS event exporter
+-- See: docs/specs/state/event-export.md
+
+-- Last event the exporter has published and the sink acknowledged (a single
+-- row, created when the exporter first runs).
+CREATE TABLE IF NOT EXISTS event_export_cursor (
+    singleton BOOLEAN PRIMARY KEY DEFAULT true CHECK (singleton),
+    last_event_id BIGINT NOT NULL,
+    -- Sink the cursor last advanced for (kafka or nats)
+    sink TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE event_export_cursor IS 'Event log position of the event exporter';
@@ -0,0 +1,15 @@
+-- Migration: 00063_event_export_gaps
+-- Description: Event IDs the event exporter passed before they were visible
+-- See: docs/specs/state/event-export.md
+
+-- Event IDs are assigned before commit, so an append can become visible
+-- after the exporter's cursor moved past its ID. IDs at or below the cursor
+-- that were missing when the cursor passed them are kept here and exported
+-- when their event appears; an ID whose append rolled back never appears and
+-- is dropped after a timeout.
+CREATE TABLE IF NOT EXISTS event_export_gaps (
+    event_id BIGINT PRIMARY KEY,
+    noticed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE event_export_gaps IS 'Event IDs below the export cursor not yet seen by the event exporter';
@@ -0,0 +1,12 @@
+-- Migration: 00064_instance_config_target
+-- Description: Last in-place config update target per instance
+-- See: docs/specs/scheduler/reconciliation-loop.md
+
+-- Set from every instance.config_updated, so the scheduler can tell an
+-- update it already recorded from one it still has to emit.
+ALTER TABLE instances_desired_view
+    ADD COLUMN IF NOT EXISTS config_target_secrets_version_id TEXT,
+    ADD COLUMN IF NOT EXISTS config_target_config_vars_version INT;
+
+COMMENT ON COLUMN instances_desired_view.config_target_secrets_version_id IS 'Secrets version of the latest instance.config_updated; NULL if none or absent';
+COMMENT ON COLUMN instances_desired_view.config_target_config_vars_version IS 'Config vars version of the latest instance.config_updated; NULL if none or absent';
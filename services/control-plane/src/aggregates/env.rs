@@ -0,0 +1,979 @@
+//! Environment aggregate.
+//!
+//! Guards env-scoped writes: scale changes, restarts, config var changes and
+//! process resource overrides are only accepted for live environments, and their inputs are normalized
+//! (sorted, unique process types) before the event is emitted.
+
+use std::collections::BTreeMap;
+
+use plfm_events::{
+    event_types, AggregateType, EnvConfigVarsSetPayload, EnvCreatedPayload,
+    EnvProcessResourcesSetPayload, EnvRestartRequestedPayload, EventSource, SecretsReload,
+    Toleration,
+};
+use plfm_id::{AppId, EnvId, OrgId};
+use plfm_reconcile::RolloutLimit;
+use plfm_secrets_format::Secrets;
+use serde::Deserialize;
+
+use super::{Aggregate, CommandError, Emitter};
+use crate::db::EventRow;
+
+/// Smallest scratch disk an instance may request (manifest `resources.disk` floor).
+pub const MIN_EPHEMERAL_DISK_BYTES: i64 = 1024 * 1024 * 1024;
+
+/// Largest scratch disk an instance may request.
+pub const MAX_EPHEMERAL_DISK_BYTES: i64 = 1024 * 1024 * 1024 * 1024;
+
+/// Scratch disk size for process types that do not set one.
+pub const DEFAULT_EPHEMERAL_DISK_BYTES: i64 = 4 * 1024 * 1024 * 1024;
+
+/// Most config vars an env may hold.
+pub const MAX_CONFIG_VARS: usize = 500;
+
+/// Largest combined size of config var keys and values, in bytes.
+pub const MAX_CONFIG_VARS_BYTES: usize = 256 * 1024;
+
+/// Largest vCPU request a process type may be overridden to (manifest cap).
+pub const MAX_CPU_REQUEST: f64 = 64.0;
+
+/// Smallest memory limit a process type may be overridden to (manifest floor).
+pub const MIN_MEMORY_LIMIT_BYTES: i64 = 64 * 1024 * 1024;
+
+/// Largest memory limit a process type may be overridden to.
+pub const MAX_MEMORY_LIMIT_BYTES: i64 = 256 * 1024 * 1024 * 1024;
+
+/// Where an environment is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnvLifecycle {
+    /// No `env.created` event yet.
+    #[default]
+    Missing,
+    Active,
+    /// Deletion was requested; teardown is in progress.
+    DeletionRequested,
+    Deleted,
+}
+
+/// Environment state rebuilt from its events.
+#[derive(Debug, Clone, Default)]
+pub struct EnvAggregate {
+    pub env_id: Option<EnvId>,
+    pub org_id: Option<OrgId>,
+    pub app_id: Option<AppId>,
+    pub name: Option<String>,
+    pub lifecycle: EnvLifecycle,
+    /// Desired replicas per process type.
+    pub scales: BTreeMap<String, i32>,
+    /// Current config vars.
+    pub config_vars: BTreeMap<String, String>,
+    /// Config vars version; 0 until the first `env.config_vars_set`.
+    pub config_vars_version: i32,
+    /// Resource overrides per process type, with their override version.
+    pub process_resources: BTreeMap<String, (ProcessResources, i32)>,
+}
+
+/// Per-process-type resource overrides; unset fields fall back to the
+/// release (cpu, memory) or the env scale (disk).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessResources {
+    pub cpu_request: Option<f64>,
+    pub memory_limit_bytes: Option<i64>,
+    pub ephemeral_disk_bytes: Option<i64>,
+}
+
+impl ProcessResources {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Commands accepted by [`EnvAggregate`].
+#[derive(Debug, Clone)]
+pub enum EnvCommand {
+    /// Set desired replicas for the listed process types, with an optional
+    /// caller-supplied reason recorded on the event.
+    SetScale {
+        processes: Vec<ProcessScaleSpec>,
+        reason: Option<String>,
+    },
+    /// Roll all instances of the listed process types.
+    RequestRestart { process_types: Vec<String> },
+    /// Replace the env's config vars; a no-op when they are unchanged.
+    SetConfigVars { vars: BTreeMap<String, String> },
+    /// Replace one process type's resource overrides; a no-op when they are
+    /// unchanged.
+    SetProcessResources {
+        process_type: String,
+        resources: ProcessResources,
+    },
+}
+
+/// Desired replicas and placement constraints for one process type.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessScaleSpec {
+    pub process_type: String,
+    pub desired: i32,
+    /// Replaces the stored node selector when set; kept as-is when `None`.
+    pub node_selector: Option<BTreeMap<String, String>>,
+    /// Replaces the stored tolerations when set; kept as-is when `None`.
+    pub tolerations: Option<Vec<Toleration>>,
+    /// Replaces the stored per-instance scratch disk size when set; kept as-is when `None`.
+    pub ephemeral_disk_bytes: Option<i64>,
+    /// Replaces the stored rollout surge limit when set; kept as-is when `None`.
+    pub max_surge: Option<RolloutLimit>,
+    /// Replaces the stored rollout unavailability limit when set; kept as-is when `None`.
+    pub max_unavailable: Option<RolloutLimit>,
+    /// Replaces the stored secrets reload mode when set; kept as-is when `None`.
+    pub secrets_reload: Option<SecretsReload>,
+}
+
+impl ProcessScaleSpec {
+    pub fn new(process_type: impl Into<String>, desired: i32) -> Self {
+        Self {
+            process_type: process_type.into(),
+            desired,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ScaleSet {
+    #[serde(default)]
+    scales: Vec<ScaleEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScaleEntry {
+    process_type: String,
+    desired: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnvRenamed {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl Aggregate for EnvAggregate {
+    const AGGREGATE_TYPE: AggregateType = AggregateType::Env;
+    type Command = EnvCommand;
+
+    fn apply(&mut self, event: &EventRow) {
+        match event.event_type.as_str() {
+            event_types::ENV_CREATED => {
+                if let Ok(payload) =
+                    serde_json::from_value::<EnvCreatedPayload>(event.payload.clone())
+                {
+                    self.env_id = Some(payload.env_id);
+                    self.org_id = Some(payload.org_id);
+                    self.app_id = Some(payload.app_id);
+                    self.name = Some(payload.name);
+                }
+                self.lifecycle = EnvLifecycle::Active;
+            }
+            event_types::ENV_UPDATED => {
+                if let Ok(EnvRenamed { name: Some(name) }) =
+                    serde_json::from_value(event.payload.clone())
+                {
+                    self.name = Some(name);
+                }
+            }
+            event_types::ENV_SCALE_SET => {
+                if let Ok(payload) = serde_json::from_value::<ScaleSet>(event.payload.clone()) {
+                    for entry in payload.scales {
+                        self.scales.insert(entry.process_type, entry.desired);
+                    }
+                }
+            }
+            event_types::ENV_CONFIG_VARS_SET => {
+                if let Ok(payload) =
+                    serde_json::from_value::<EnvConfigVarsSetPayload>(event.payload.clone())
+                {
+                    self.config_vars = payload.vars;
+                    self.config_vars_version = payload.version;
+                }
+            }
+            event_types::ENV_PROCESS_RESOURCES_SET => {
+                if let Ok(payload) =
+                    serde_json::from_value::<EnvProcessResourcesSetPayload>(event.payload.clone())
+                {
+                    let resources = ProcessResources {
+                        cpu_request: payload.cpu_request,
+                        memory_limit_bytes: payload.memory_limit_bytes,
+                        ephemeral_disk_bytes: payload.ephemeral_disk_bytes,
+                    };
+                    self.process_resources
+                        .insert(payload.process_type, (resources, payload.version));
+                }
+            }
+            event_types::ENV_DELETION_REQUESTED => {
+                self.lifecycle = EnvLifecycle::DeletionRequested;
+            }
+            event_types::ENV_DELETED => {
+                self.lifecycle = EnvLifecycle::Deleted;
+            }
+            event_types::ENV_RESTORED => {
+                self.lifecycle = EnvLifecycle::Active;
+            }
+            event_types::ENV_PURGED => {
+                self.lifecycle = EnvLifecycle::Deleted;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle<S: EventSource>(
+        &self,
+        command: EnvCommand,
+        emitter: &mut Emitter<'_, S>,
+    ) -> Result<(), CommandError> {
+        let (env_id, org_id, app_id) = self.require_active()?;
+
+        match command {
+            EnvCommand::SetScale { processes, reason } => {
+                let processes = validate_scale(processes)?;
+                let scales: Vec<serde_json::Value> = processes
+                    .iter()
+                    .map(|spec| {
+                        let mut entry = serde_json::json!({
+                            "process_type": spec.process_type,
+                            "desired": spec.desired
+                        });
+                        if let Some(node_selector) = &spec.node_selector {
+                            entry["node_selector"] = serde_json::json!(node_selector);
+                        }
+                        if let Some(tolerations) = &spec.tolerations {
+                            entry["tolerations"] = serde_json::json!(tolerations);
+                        }
+                        if let Some(ephemeral_disk_bytes) = spec.ephemeral_disk_bytes {
+                            entry["ephemeral_disk_bytes"] = serde_json::json!(ephemeral_disk_bytes);
+                        }
+                        if let Some(max_surge) = spec.max_surge {
+                            entry["max_surge"] = serde_json::json!(max_surge);
+                        }
+                        if let Some(max_unavailable) = spec.max_unavailable {
+                            entry["max_unavailable"] = serde_json::json!(max_unavailable);
+                        }
+                        if let Some(secrets_reload) = &spec.secrets_reload {
+                            entry["secrets_reload"] = serde_json::json!(secrets_reload);
+                        }
+                        entry
+                    })
+                    .collect();
+
+                let mut payload = serde_json::json!({
+                    "env_id": env_id.to_string(),
+                    "org_id": org_id.to_string(),
+                    "app_id": app_id.to_string(),
+                    "scales": scales
+                });
+                if let Some(reason) = reason {
+                    payload["reason"] = serde_json::json!(reason);
+                }
+
+                let event = emitter
+                    .event()
+                    .org_id(org_id)
+                    .app_id(app_id)
+                    .env_id(env_id)
+                    .json_payload(AggregateType::Env, event_types::ENV_SCALE_SET, payload);
+                emitter.emit(event)
+            }
+            EnvCommand::RequestRestart { process_types } => {
+                let process_types = validate_process_types(process_types)?;
+                let event = emitter
+                    .event()
+                    .org_id(org_id)
+                    .app_id(app_id)
+                    .env_id(env_id)
+                    .payload(&EnvRestartRequestedPayload {
+                        env_id,
+                        org_id,
+                        app_id,
+                        process_types,
+                    });
+                emitter.emit(event)
+            }
+            EnvCommand::SetConfigVars { vars } => {
+                validate_config_vars(&vars)?;
+                if vars == self.config_vars {
+                    return Ok(());
+                }
+                let event = emitter
+                    .event()
+                    .org_id(org_id)
+                    .app_id(app_id)
+                    .env_id(env_id)
+                    .payload(&EnvConfigVarsSetPayload {
+                        env_id,
+                        org_id,
+                        app_id,
+                        version: self.config_vars_version + 1,
+                        vars,
+                    });
+                emitter.emit(event)
+            }
+            EnvCommand::SetProcessResources {
+                process_type,
+                resources,
+            } => {
+                validate_process_resources(&process_type, &resources)?;
+                let (current, version) = self
+                    .process_resources
+                    .get(&process_type)
+                    .copied()
+                    .unwrap_or_default();
+                if resources == current {
+                    return Ok(());
+                }
+                let event = emitter
+                    .event()
+                    .org_id(org_id)
+                    .app_id(app_id)
+                    .env_id(env_id)
+                    .payload(&EnvProcessResourcesSetPayload {
+                        env_id,
+                        org_id,
+                        app_id,
+                        process_type,
+                        version: version + 1,
+                        cpu_request: resources.cpu_request,
+                        memory_limit_bytes: resources.memory_limit_bytes,
+                        ephemeral_disk_bytes: resources.ephemeral_disk_bytes,
+                    });
+                emitter.emit(event)
+            }
+        }
+    }
+}
+
+impl EnvAggregate {
+    /// Writes are only accepted while the env is live.
+    fn require_active(&self) -> Result<(EnvId, OrgId, AppId), CommandError> {
+        let ids = match (self.env_id, self.org_id, self.app_id) {
+            (Some(env_id), Some(org_id), Some(app_id)) => Some((env_id, org_id, app_id)),
+            _ => None,
+        };
+
+        match (self.lifecycle, ids) {
+            (EnvLifecycle::Active, Some(ids)) => Ok(ids),
+            (EnvLifecycle::DeletionRequested, Some((env_id, ..))) => Err(CommandError::conflict(
+                "env_deletion_pending",
+                format!("Environment {} is being deleted", env_id),
+            )),
+            (_, Some((env_id, ..))) => Err(CommandError::not_found(
+                "env_not_found",
+                format!("Environment {} not found", env_id),
+            )),
+            (_, None) => Err(CommandError::not_found(
+                "env_not_found",
+                "Environment not found",
+            )),
+        }
+    }
+}
+
+/// Non-empty, non-negative, unique process types; sorted by process type.
+fn validate_scale(
+    mut processes: Vec<ProcessScaleSpec>,
+) -> Result<Vec<ProcessScaleSpec>, CommandError> {
+    if processes.is_empty() {
+        return Err(CommandError::invalid(
+            "invalid_processes",
+            "processes cannot be empty",
+        ));
+    }
+
+    for spec in &processes {
+        if spec.process_type.trim().is_empty() {
+            return Err(CommandError::invalid(
+                "invalid_process_type",
+                "process_type cannot be empty",
+            ));
+        }
+        if spec.desired < 0 {
+            return Err(CommandError::invalid(
+                "invalid_desired",
+                "desired must be >= 0",
+            ));
+        }
+        let blank_selector = spec
+            .node_selector
+            .as_ref()
+            .is_some_and(|selector| selector.keys().any(|k| k.trim().is_empty()));
+        if blank_selector {
+            return Err(CommandError::invalid(
+                "invalid_node_selector",
+                "node_selector keys cannot be empty",
+            ));
+        }
+        let blank_toleration = spec
+            .tolerations
+            .as_ref()
+            .is_some_and(|tolerations| tolerations.iter().any(|t| t.key.trim().is_empty()));
+        if blank_toleration {
+            return Err(CommandError::invalid(
+                "invalid_toleration",
+                "toleration key cannot be empty",
+            ));
+        }
+        if spec.ephemeral_disk_bytes.is_some_and(|bytes| {
+            !(MIN_EPHEMERAL_DISK_BYTES..=MAX_EPHEMERAL_DISK_BYTES).contains(&bytes)
+        }) {
+            return Err(CommandError::invalid(
+                "invalid_ephemeral_disk_bytes",
+                "ephemeral_disk_bytes must be between 1 GiB and 1 TiB",
+            ));
+        }
+        if let Some(Err(message)) = spec.secrets_reload.as_ref().map(SecretsReload::validate) {
+            return Err(CommandError::invalid(
+                "invalid_secrets_reload",
+                format!("secrets_reload: {message}"),
+            ));
+        }
+    }
+
+    processes.sort_by(|a, b| a.process_type.cmp(&b.process_type));
+    if processes
+        .windows(2)
+        .any(|pair| pair[0].process_type == pair[1].process_type)
+    {
+        return Err(CommandError::invalid(
+            "duplicate_process_type",
+            "process_type values must be unique",
+        ));
+    }
+
+    Ok(processes)
+}
+
+/// Non-empty, non-blank process types; sorted and deduplicated.
+fn validate_process_types(mut process_types: Vec<String>) -> Result<Vec<String>, CommandError> {
+    if process_types.is_empty() {
+        return Err(CommandError::invalid(
+            "invalid_process_types",
+            "process_types cannot be empty",
+        ));
+    }
+    if process_types.iter().any(|p| p.trim().is_empty()) {
+        return Err(CommandError::invalid(
+            "invalid_process_type",
+            "process_type cannot be empty",
+        ));
+    }
+
+    process_types.sort();
+    process_types.dedup();
+    Ok(process_types)
+}
+
+/// Config vars use the same key and value rules as secrets, since both end
+/// up in the workload environment.
+fn validate_config_vars(vars: &BTreeMap<String, String>) -> Result<(), CommandError> {
+    if vars.len() > MAX_CONFIG_VARS {
+        return Err(CommandError::invalid(
+            "invalid_config_vars",
+            format!("at most {MAX_CONFIG_VARS} config vars are allowed"),
+        ));
+    }
+    Secrets::try_from_iter(vars.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+        .map_err(|e| CommandError::invalid("invalid_config_vars", e.to_string()))?;
+    if vars.values().any(|v| v.contains('\0')) {
+        return Err(CommandError::invalid(
+            "invalid_config_vars",
+            "config var values cannot contain NUL bytes",
+        ));
+    }
+    let total: usize = vars.iter().map(|(k, v)| k.len() + v.len()).sum();
+    if total > MAX_CONFIG_VARS_BYTES {
+        return Err(CommandError::invalid(
+            "invalid_config_vars",
+            format!("config vars cannot exceed {MAX_CONFIG_VARS_BYTES} bytes in total"),
+        ));
+    }
+    Ok(())
+}
+
+/// Overrides stay within the manifest resource bounds.
+fn validate_process_resources(
+    process_type: &str,
+    resources: &ProcessResources,
+) -> Result<(), CommandError> {
+    if process_type.trim().is_empty() {
+        return Err(CommandError::invalid(
+            "invalid_process_type",
+            "process_type cannot be empty",
+        ));
+    }
+    if resources
+        .cpu_request
+        .is_some_and(|cpu| !(cpu > 0.0 && cpu <= MAX_CPU_REQUEST))
+    {
+        return Err(CommandError::invalid(
+            "invalid_resources",
+            "cpu_request must be > 0 and <= 64",
+        ));
+    }
+    if resources
+        .memory_limit_bytes
+        .is_some_and(|bytes| !(MIN_MEMORY_LIMIT_BYTES..=MAX_MEMORY_LIMIT_BYTES).contains(&bytes))
+    {
+        return Err(CommandError::invalid(
+            "invalid_resources",
+            "memory_limit_bytes must be between 64 MiB and 256 GiB",
+        ));
+    }
+    if resources.ephemeral_disk_bytes.is_some_and(|bytes| {
+        !(MIN_EPHEMERAL_DISK_BYTES..=MAX_EPHEMERAL_DISK_BYTES).contains(&bytes)
+    }) {
+        return Err(CommandError::invalid(
+            "invalid_resources",
+            "ephemeral_disk_bytes must be between 1 GiB and 1 TiB",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::replay;
+    use super::super::tests::row;
+    use super::*;
+    use plfm_events::SystemSource;
+
+    fn created_env() -> (EnvAggregate, EnvId) {
+        let env_id = EnvId::new();
+        let (env, _) = replay::<EnvAggregate>(&[row(
+            1,
+            event_types::ENV_CREATED,
+            serde_json::json!({
+                "env_id": env_id.to_string(),
+                "org_id": OrgId::new().to_string(),
+                "app_id": AppId::new().to_string(),
+                "name": "staging"
+            }),
+        )]);
+        (env, env_id)
+    }
+
+    fn handle(env: &EnvAggregate, command: EnvCommand) -> Result<Vec<EventRow>, CommandError> {
+        let source = SystemSource::new("test");
+        let mut emitter = Emitter::new(&source, "env_1", 1);
+        env.handle(command, &mut emitter)?;
+        Ok(emitter
+            .into_events()
+            .into_iter()
+            .map(|e| row(e.aggregate_seq, &e.event_type, e.payload))
+            .collect())
+    }
+
+    fn scale(processes: &[(&str, i32)]) -> EnvCommand {
+        EnvCommand::SetScale {
+            processes: processes
+                .iter()
+                .map(|(p, d)| ProcessScaleSpec::new(*p, *d))
+                .collect(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_tracks_lifecycle_and_scales() {
+        let (mut env, env_id) = created_env();
+        assert_eq!(env.lifecycle, EnvLifecycle::Active);
+        assert_eq!(env.env_id, Some(env_id));
+        assert_eq!(env.name.as_deref(), Some("staging"));
+
+        env.apply(&row(
+            2,
+            event_types::ENV_SCALE_SET,
+            serde_json::json!({"scales": [{"process_type": "web", "desired": 3}]}),
+        ));
+        assert_eq!(env.scales.get("web"), Some(&3));
+
+        env.apply(&row(
+            3,
+            event_types::ENV_DELETION_REQUESTED,
+            serde_json::json!({}),
+        ));
+        assert_eq!(env.lifecycle, EnvLifecycle::DeletionRequested);
+        env.apply(&row(4, event_types::ENV_DELETED, serde_json::json!({})));
+        assert_eq!(env.lifecycle, EnvLifecycle::Deleted);
+    }
+
+    #[test]
+    fn test_set_scale_emits_sorted_scale_event() {
+        let (env, _) = created_env();
+        let events = handle(&env, scale(&[("worker", 1), ("web", 2)])).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, event_types::ENV_SCALE_SET);
+        assert_eq!(events[0].aggregate_seq, 2);
+        assert_eq!(events[0].payload["scales"][0]["process_type"], "web");
+        assert_eq!(events[0].payload["scales"][1]["process_type"], "worker");
+        assert!(events[0].payload.get("reason").is_none());
+    }
+
+    #[test]
+    fn test_set_scale_records_reason() {
+        let (env, _) = created_env();
+        let command = EnvCommand::SetScale {
+            processes: vec![ProcessScaleSpec::new("web", 6)],
+            reason: Some("Launch traffic".to_string()),
+        };
+        let events = handle(&env, command).unwrap();
+        assert_eq!(events[0].payload["reason"], "Launch traffic");
+    }
+
+    #[test]
+    fn test_set_scale_rejects_duplicates() {
+        let (env, _) = created_env();
+        let err = handle(&env, scale(&[("web", 1), ("web", 2)])).unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Invalid {
+                code: "duplicate_process_type",
+                ..
+            }
+        ));
+
+        let err = handle(&env, scale(&[("web", -1)])).unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Invalid {
+                code: "invalid_desired",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_set_scale_carries_placement_constraints() {
+        let (env, _) = created_env();
+        let mut gpu = ProcessScaleSpec::new("gpu", 1);
+        gpu.node_selector = Some(BTreeMap::from([("accel".to_string(), "a100".to_string())]));
+        gpu.tolerations = Some(vec![Toleration {
+            key: "gpu".to_string(),
+            value: None,
+            effect: None,
+        }]);
+        let command = EnvCommand::SetScale {
+            processes: vec![gpu, ProcessScaleSpec::new("web", 2)],
+            reason: None,
+        };
+
+        let events = handle(&env, command).unwrap();
+        let scales = &events[0].payload["scales"];
+        assert_eq!(scales[0]["node_selector"]["accel"], "a100");
+        assert_eq!(scales[0]["tolerations"][0]["key"], "gpu");
+        assert!(scales[1].get("node_selector").is_none());
+        assert!(scales[1].get("tolerations").is_none());
+
+        let mut blank = ProcessScaleSpec::new("web", 1);
+        blank.tolerations = Some(vec![Toleration {
+            key: " ".to_string(),
+            value: None,
+            effect: None,
+        }]);
+        let err = handle(
+            &env,
+            EnvCommand::SetScale {
+                processes: vec![blank],
+                reason: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Invalid {
+                code: "invalid_toleration",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_set_scale_validates_ephemeral_disk() {
+        let (env, _) = created_env();
+        let mut worker = ProcessScaleSpec::new("worker", 2);
+        worker.ephemeral_disk_bytes = Some(8 * 1024 * 1024 * 1024);
+        let events = handle(
+            &env,
+            EnvCommand::SetScale {
+                processes: vec![worker, ProcessScaleSpec::new("web", 1)],
+                reason: None,
+            },
+        )
+        .unwrap();
+        let scales = &events[0].payload["scales"];
+        assert!(scales[0].get("ephemeral_disk_bytes").is_none());
+        assert_eq!(scales[1]["ephemeral_disk_bytes"], 8i64 * 1024 * 1024 * 1024);
+
+        let mut tiny = ProcessScaleSpec::new("web", 1);
+        tiny.ephemeral_disk_bytes = Some(MIN_EPHEMERAL_DISK_BYTES - 1);
+        let err = handle(
+            &env,
+            EnvCommand::SetScale {
+                processes: vec![tiny],
+                reason: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Invalid {
+                code: "invalid_ephemeral_disk_bytes",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_set_scale_records_rollout_limits() {
+        let (env, _) = created_env();
+        let mut web = ProcessScaleSpec::new("web", 4);
+        web.max_surge = Some(RolloutLimit::Percent(25));
+        web.max_unavailable = Some(RolloutLimit::Count(1));
+        let events = handle(
+            &env,
+            EnvCommand::SetScale {
+                processes: vec![web, ProcessScaleSpec::new("worker", 1)],
+                reason: None,
+            },
+        )
+        .unwrap();
+        let scales = &events[0].payload["scales"];
+        assert_eq!(scales[0]["max_surge"], "25%");
+        assert_eq!(scales[0]["max_unavailable"], 1);
+        assert!(scales[1].get("max_surge").is_none());
+        assert!(scales[1].get("max_unavailable").is_none());
+    }
+
+    #[test]
+    fn test_set_scale_records_and_validates_secrets_reload() {
+        let (env, _) = created_env();
+        let mut web = ProcessScaleSpec::new("web", 2);
+        web.secrets_reload = Some(SecretsReload::Signal {
+            signal: "SIGUSR1".to_string(),
+        });
+        let events = handle(
+            &env,
+            EnvCommand::SetScale {
+                processes: vec![web.clone()],
+                reason: None,
+            },
+        )
+        .unwrap();
+        let scale = &events[0].payload["scales"][0];
+        assert_eq!(scale["secrets_reload"]["mode"], "signal");
+        assert_eq!(scale["secrets_reload"]["signal"], "SIGUSR1");
+
+        web.secrets_reload = Some(SecretsReload::Exec {
+            command: Vec::new(),
+            timeout_seconds: None,
+        });
+        let err = handle(
+            &env,
+            EnvCommand::SetScale {
+                processes: vec![web],
+                reason: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Invalid {
+                code: "invalid_secrets_reload",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_cannot_write_to_deleting_or_deleted_env() {
+        let (mut env, _) = created_env();
+        env.apply(&row(
+            2,
+            event_types::ENV_DELETION_REQUESTED,
+            serde_json::json!({}),
+        ));
+        let err = handle(&env, scale(&[("web", 1)])).unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Conflict {
+                code: "env_deletion_pending",
+                ..
+            }
+        ));
+
+        env.apply(&row(3, event_types::ENV_DELETED, serde_json::json!({})));
+        let err = handle(&env, scale(&[("web", 1)])).unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::NotFound {
+                code: "env_not_found",
+                ..
+            }
+        ));
+
+        env.apply(&row(4, event_types::ENV_RESTORED, serde_json::json!({})));
+        assert_eq!(env.lifecycle, EnvLifecycle::Active);
+        assert!(handle(&env, scale(&[("web", 1)])).is_ok());
+
+        let err = handle(&EnvAggregate::default(), scale(&[("web", 1)])).unwrap_err();
+        assert!(matches!(err, CommandError::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_restart_dedups_process_types() {
+        let (env, _) = created_env();
+        let events = handle(
+            &env,
+            EnvCommand::RequestRestart {
+                process_types: vec!["worker".into(), "web".into(), "web".into()],
+            },
+        )
+        .unwrap();
+        assert_eq!(events[0].event_type, event_types::ENV_RESTART_REQUESTED);
+        assert_eq!(
+            events[0].payload["process_types"],
+            serde_json::json!(["web", "worker"])
+        );
+
+        let err = handle(
+            &env,
+            EnvCommand::RequestRestart {
+                process_types: Vec::new(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::Invalid {
+                code: "invalid_process_types",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_set_config_vars_versions_and_skips_noop() {
+        let (mut env, _) = created_env();
+        let vars = BTreeMap::from([("LOG_LEVEL".to_string(), "debug".to_string())]);
+
+        let events = handle(&env, EnvCommand::SetConfigVars { vars: vars.clone() }).unwrap();
+        assert_eq!(events[0].event_type, event_types::ENV_CONFIG_VARS_SET);
+        assert_eq!(events[0].payload["version"], 1);
+        assert_eq!(events[0].payload["vars"]["LOG_LEVEL"], "debug");
+
+        env.apply(&row(
+            2,
+            event_types::ENV_CONFIG_VARS_SET,
+            events[0].payload.clone(),
+        ));
+        assert_eq!(env.config_vars_version, 1);
+        let events = handle(&env, EnvCommand::SetConfigVars { vars }).unwrap();
+        assert!(events.is_empty());
+
+        let events = handle(
+            &env,
+            EnvCommand::SetConfigVars {
+                vars: BTreeMap::new(),
+            },
+        )
+        .unwrap();
+        assert_eq!(events[0].payload["version"], 2);
+    }
+
+    #[test]
+    fn test_set_config_vars_rejects_invalid_keys() {
+        let (env, _) = created_env();
+        for key in ["1ABC", "HAS-DASH", ""] {
+            let err = handle(
+                &env,
+                EnvCommand::SetConfigVars {
+                    vars: BTreeMap::from([(key.to_string(), "x".to_string())]),
+                },
+            )
+            .unwrap_err();
+            assert!(matches!(
+                err,
+                CommandError::Invalid {
+                    code: "invalid_config_vars",
+                    ..
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_set_process_resources_versions_per_process_type() {
+        let (mut env, _) = created_env();
+        let resources = ProcessResources {
+            cpu_request: Some(2.0),
+            memory_limit_bytes: Some(1 << 30),
+            ephemeral_disk_bytes: None,
+        };
+        let set = |process_type: &str, resources| EnvCommand::SetProcessResources {
+            process_type: process_type.to_string(),
+            resources,
+        };
+
+        let events = handle(&env, set("web", resources)).unwrap();
+        assert_eq!(events[0].event_type, event_types::ENV_PROCESS_RESOURCES_SET);
+        assert_eq!(events[0].payload["version"], 1);
+        assert_eq!(events[0].payload["cpu_request"], 2.0);
+        assert!(events[0].payload.get("ephemeral_disk_bytes").is_none());
+
+        env.apply(&row(
+            2,
+            event_types::ENV_PROCESS_RESOURCES_SET,
+            events[0].payload.clone(),
+        ));
+        assert!(handle(&env, set("web", resources)).unwrap().is_empty());
+        let events = handle(&env, set("worker", resources)).unwrap();
+        assert_eq!(events[0].payload["version"], 1);
+        let events = handle(&env, set("web", ProcessResources::default())).unwrap();
+        assert_eq!(events[0].payload["version"], 2);
+    }
+
+    #[test]
+    fn test_set_process_resources_validates_bounds() {
+        let (env, _) = created_env();
+        for resources in [
+            ProcessResources {
+                cpu_request: Some(0.0),
+                ..Default::default()
+            },
+            ProcessResources {
+                cpu_request: Some(65.0),
+                ..Default::default()
+            },
+            ProcessResources {
+                memory_limit_bytes: Some(1024),
+                ..Default::default()
+            },
+            ProcessResources {
+                ephemeral_disk_bytes: Some(1024),
+                ..Default::default()
+            },
+        ] {
+            let err = handle(
+                &env,
+                EnvCommand::SetProcessResources {
+                    process_type: "web".to_string(),
+                    resources,
+                },
+            )
+            .unwrap_err();
+            assert!(matches!(
+                err,
+                CommandError::Invalid {
+                    code: "invalid_resources",
+                    ..
+                }
+            ));
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Aggregate command layer.
+//!
+//! Business rules that guard a write ("can't scale a deleted env") live on
+//! the aggregate rather than in each HTTP handler that can perform the write.
+//! A command is executed by:
+//!
+//! 1. Replaying the aggregate's events into its state ([`Aggregate::apply`]).
+//! 2. Validating the command against that state ([`Aggregate::handle`]),
+//!    which emits zero or more events.
+//! 3. Appending the emitted events at the next aggregate sequence numbers.
+//!
+//! A concurrent write to the same aggregate makes step 3 fail with a
+//! sequence conflict, so invariants are always checked against the state the
+//! events are appended on top of.
+
+pub mod env;
+
+use plfm_events::{AggregateType, EventError, EventSource, NewEvent, NewEventBuilder};
+use plfm_id::EventId;
+
+use crate::api::error::ApiError;
+use crate::db::{DbError, EventRow, EventStore};
+
+pub use env::{EnvAggregate, EnvCommand, EnvLifecycle, ProcessResources, ProcessScaleSpec};
+
+/// An event-sourced aggregate that validates commands.
+pub trait Aggregate: Default + Send {
+    /// Aggregate type the events are stored under.
+    const AGGREGATE_TYPE: AggregateType;
+
+    /// Commands the aggregate accepts.
+    type Command: Send;
+
+    /// Fold a stored event into the state.
+    fn apply(&mut self, event: &EventRow);
+
+    /// Validate `command` against the current state and emit the resulting
+    /// events.
+    fn handle<S: EventSource>(
+        &self,
+        command: Self::Command,
+        emitter: &mut Emitter<'_, S>,
+    ) -> Result<(), CommandError>;
+}
+
+/// Collects the events a command emits, assigning aggregate sequence
+/// numbers in order.
+pub struct Emitter<'a, S: EventSource> {
+    source: &'a S,
+    aggregate_id: String,
+    version: i32,
+    events: Vec<NewEvent>,
+}
+
+impl<'a, S: EventSource> Emitter<'a, S> {
+    fn new(source: &'a S, aggregate_id: &str, version: i32) -> Self {
+        Self {
+            source,
+            aggregate_id: aggregate_id.to_string(),
+            version,
+            events: Vec::new(),
+        }
+    }
+
+    /// Start the next event for this aggregate. The actor, request, and
+    /// correlation fields come from the command's source.
+    pub fn event(&self) -> NewEventBuilder {
+        NewEvent::builder(self.source)
+            .aggregate_id(self.aggregate_id.clone())
+            .aggregate_seq(self.version + self.events.len() as i32 + 1)
+    }
+
+    /// Record an event built from [`Emitter::event`].
+    pub fn emit(&mut self, event: NewEventBuilder) -> Result<(), CommandError> {
+        self.events.push(event.build()?);
+        Ok(())
+    }
+
+    fn into_events(self) -> Vec<NewEvent> {
+        self.events
+    }
+}
+
+/// Why a command was rejected or could not be applied.
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    /// The command itself is malformed.
+    #[error("{message}")]
+    Invalid { code: &'static str, message: String },
+
+    /// The aggregate does not exist.
+    #[error("{message}")]
+    NotFound { code: &'static str, message: String },
+
+    /// The command is not allowed in the aggregate's current state.
+    #[error("{message}")]
+    Conflict { code: &'static str, message: String },
+
+    /// Another write to the aggregate landed first.
+    #[error("concurrent update to {aggregate_type} {aggregate_id}")]
+    Concurrent {
+        aggregate_type: String,
+        aggregate_id: String,
+    },
+
+    #[error("event store error: {0}")]
+    Store(#[from] DbError),
+
+    #[error("event error: {0}")]
+    Event(#[from] EventError),
+}
+
+impl CommandError {
+    pub fn invalid(code: &'static str, message: impl Into<String>) -> Self {
+        CommandError::Invalid {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        CommandError::NotFound {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        CommandError::Conflict {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<CommandError> for ApiError {
+    fn from(err: CommandError) -> Self {
+        match err {
+            CommandError::Invalid { code, message } => ApiError::bad_request(code, message),
+            CommandError::NotFound { code, message } => ApiError::not_found(code, message),
+            CommandError::Conflict { code, message } => ApiError::conflict(code, message),
+            CommandError::Concurrent { aggregate_type, .. } => ApiError::conflict(
+                "version_conflict",
+                format!("Concurrent {aggregate_type} update detected; retry"),
+            ),
+            CommandError::Store(_) | CommandError::Event(_) => {
+                ApiError::internal("internal_error", "Failed to apply command")
+            }
+        }
+    }
+}
+
+/// Replay an aggregate's events into its state. Returns the state and the
+/// last aggregate sequence number (0 when the aggregate has no events).
+pub async fn load<A: Aggregate>(
+    store: &EventStore,
+    aggregate_id: &str,
+) -> Result<(A, i32), CommandError> {
+    let events = store
+        .query_by_aggregate(&A::AGGREGATE_TYPE, aggregate_id)
+        .await?;
+    Ok(replay(&events))
+}
+
+fn replay<A: Aggregate>(events: &[EventRow]) -> (A, i32) {
+    let mut aggregate = A::default();
+    let mut version = 0;
+    for event in events {
+        aggregate.apply(event);
+        version = version.max(event.aggregate_seq);
+    }
+    (aggregate, version)
+}
+
+/// Load the aggregate, run `command` against it, and append the emitted
+/// events. Returns the IDs of the appended events (empty when the command
+/// was a no-op).
+pub async fn execute<A: Aggregate, S: EventSource>(
+    store: &EventStore,
+    source: &S,
+    aggregate_id: &str,
+    command: A::Command,
+) -> Result<Vec<EventId>, CommandError> {
+    let (aggregate, version) = load::<A>(store, aggregate_id).await?;
+
+    let mut emitter = Emitter::new(source, aggregate_id, version);
+    aggregate.handle(command, &mut emitter)?;
+    let events = emitter.into_events();
+    if events.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    store
+        .append_batch(events.into_iter().map(Into::into).collect())
+        .await
+        .map_err(|e| match e {
+            DbError::SequenceConflict { .. } => CommandError::Concurrent {
+                aggregate_type: A::AGGREGATE_TYPE.to_string(),
+                aggregate_id: aggregate_id.to_string(),
+            },
+            other => CommandError::Store(other),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plfm_events::SystemSource;
+
+    #[derive(Default)]
+    struct Counter {
+        total: i64,
+    }
+
+    impl Aggregate for Counter {
+        const AGGREGATE_TYPE: AggregateType = AggregateType::Org;
+        type Command = i64;
+
+        fn apply(&mut self, event: &EventRow) {
+            self.total += event.payload["by"].as_i64().unwrap_or(0);
+        }
+
+        fn handle<S: EventSource>(
+            &self,
+            by: i64,
+            emitter: &mut Emitter<'_, S>,
+        ) -> Result<(), CommandError> {
+            if self.total + by < 0 {
+                return Err(CommandError::conflict(
+                    "negative",
+                    "total cannot go negative",
+                ));
+            }
+            for _ in 0..2 {
+                let event = emitter.event().json_payload(
+                    AggregateType::Org,
+                    "org.updated",
+                    serde_json::json!({ "by": by }),
+                );
+                emitter.emit(event)?;
+            }
+            Ok(())
+        }
+    }
+
+    pub(super) fn row(seq: i32, event_type: &str, payload: serde_json::Value) -> EventRow {
+        EventRow {
+            event_id: seq as i64,
+            occurred_at: chrono::Utc::now(),
+            aggregate_type: "org".to_string(),
+            aggregate_id: "org_1".to_string(),
+            aggregate_seq: seq,
+            event_type: event_type.to_string(),
+            event_version: 1,
+            actor_type: "system".to_string(),
+            actor_id: "test".to_string(),
+            org_id: None,
+            request_id: "req_1".to_string(),
+            idempotency_key: None,
+            app_id: None,
+            env_id: None,
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_tracks_state_and_version() {
+        let (counter, version) = replay::<Counter>(&[
+            row(1, "org.updated", serde_json::json!({"by": 2})),
+            row(2, "org.updated", serde_json::json!({"by": 3})),
+        ]);
+        assert_eq!(counter.total, 5);
+        assert_eq!(version, 2);
+    }
+
+    #[test]
+    fn test_emitter_assigns_sequential_seqs() {
+        let source = SystemSource::new("test");
+        let mut emitter = Emitter::new(&source, "org_1", 4);
+        Counter { total: 1 }.handle(1, &mut emitter).unwrap();
+
+        let seqs: Vec<i32> = emitter
+            .into_events()
+            .iter()
+            .map(|e| e.aggregate_seq)
+            .collect();
+        assert_eq!(seqs, vec![5, 6]);
+    }
+
+    #[test]
+    fn test_invariant_violation_maps_to_conflict() {
+        let source = SystemSource::new("test");
+        let mut emitter = Emitter::new(&source, "org_1", 0);
+        let err = Counter { total: 1 }.handle(-5, &mut emitter).unwrap_err();
+        let api: ApiError = err.into();
+        assert_eq!(api.status, axum::http::StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,673 @@
+//! The alert evaluator.
+//!
+//! Each pass, on the elected leader only, every enabled rule's condition is
+//! checked against status tables and the rule's `alerts` rows are brought
+//! in line:
+//! - a target the condition newly holds on gets a `pending` alert
+//! - a pending alert whose condition has held for `for_seconds` turns
+//!   `firing` and emits `alert.fired`
+//! - an alert whose condition no longer holds is dropped; a firing one
+//!   emits `alert.resolved` first
+//!
+//! Restart counts come from `instance_restarts`, which the instances
+//! projection fills from `instance.status_changed` events; rows older than
+//! the longest window are pruned every pass. Burn rates are measured from
+//! `instance_status_history` (see [`crate::slo`]).
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{AggregateType, AlertFiredPayload, AlertResolvedPayload, NewEvent, SystemSource};
+use plfm_id::{AppId, EnvId, OrgId};
+use sqlx::PgPool;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use super::{AlertError, AlertRule, Condition, MAX_DURATION_SECS, RULE_COLUMNS};
+use crate::db::{EventStore, ProjectionStore};
+use crate::leader::{LeaderElection, LeaderRole};
+use crate::slo::{self, availability};
+
+/// Actor ID for events written by the alert evaluator.
+pub const ALERT_EVALUATOR_ACTOR_ID: &str = "alert-evaluator";
+
+// =============================================================================
+// Observations
+// =============================================================================
+
+/// A target a rule's condition currently holds on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    /// env_id, node_id, or projection name.
+    pub target: String,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    pub value: Option<i64>,
+    pub summary: String,
+}
+
+/// Why an env is unhealthy, or `None` when it is healthy.
+///
+/// An env is unhealthy when any of its instances failed or fewer instances
+/// are ready than its desired replicas.
+pub fn env_unhealthy_summary(
+    app_name: &str,
+    env_name: &str,
+    desired: i64,
+    ready: i64,
+    failed: i64,
+) -> Option<String> {
+    if failed == 0 && ready >= desired {
+        return None;
+    }
+    let mut summary = format!("{app_name}/{env_name}: {ready} of {desired} instances ready");
+    if failed > 0 {
+        summary.push_str(&format!(", {failed} failed"));
+    }
+    Some(summary)
+}
+
+/// The targets of projection lag above `threshold`.
+pub fn lagging_projections(lag: &[(String, i64)], threshold: i64) -> Vec<Observation> {
+    lag.iter()
+        .filter(|(_, lag)| *lag > threshold)
+        .map(|(name, lag)| Observation {
+            target: name.clone(),
+            app_id: None,
+            env_id: None,
+            value: Some(*lag),
+            summary: format!("Projection {name} is {lag} events behind (threshold {threshold})"),
+        })
+        .collect()
+}
+
+/// The summary of an env burning its error budget at `burn_rate`, or `None`
+/// below `threshold`.
+pub fn slo_burn_summary(
+    app_name: &str,
+    env_name: &str,
+    burn_rate: f64,
+    threshold: i64,
+    objective: f64,
+    window_seconds: i32,
+) -> Option<String> {
+    if burn_rate < threshold as f64 {
+        return None;
+    }
+    Some(format!(
+        "{app_name}/{env_name}: error budget burning at {burn_rate}x over the last \
+         {window_seconds}s (objective {objective}%, threshold {threshold}x)"
+    ))
+}
+
+/// Desired, ready, and failed instance counts of one env.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct EnvHealth {
+    pub env_id: String,
+    pub app_id: String,
+    pub app_name: String,
+    pub env_name: String,
+    pub desired: i64,
+    pub ready: i64,
+    pub failed: i64,
+}
+
+/// Health of the org's live envs, optionally limited to one app or env.
+///
+/// Only instances desired `running` count, so instances being drained by a
+/// deploy do not mark the env failed.
+pub async fn env_health(
+    pool: &PgPool,
+    org_id: &str,
+    app_id: Option<&str>,
+    env_id: Option<&str>,
+) -> Result<Vec<EnvHealth>, sqlx::Error> {
+    sqlx::query_as::<_, EnvHealth>(
+        r#"
+        SELECT e.env_id, e.app_id, a.name AS app_name, e.name AS env_name,
+            COALESCE((
+                SELECT SUM(s.desired_replicas) FROM env_scale_view s
+                WHERE s.env_id = e.env_id
+            ), 0)::BIGINT AS desired,
+            (
+                SELECT COUNT(*) FROM instances_desired_view d
+                JOIN instances_status_view st ON st.instance_id = d.instance_id
+                WHERE d.env_id = e.env_id AND d.desired_state = 'running'
+                  AND st.status = 'ready'
+            ) AS ready,
+            (
+                SELECT COUNT(*) FROM instances_desired_view d
+                JOIN instances_status_view st ON st.instance_id = d.instance_id
+                WHERE d.env_id = e.env_id AND d.desired_state = 'running'
+                  AND st.status = 'failed'
+            ) AS failed
+        FROM envs_view e
+        JOIN apps_view a ON a.app_id = e.app_id
+        WHERE e.org_id = $1 AND NOT e.is_deleted AND NOT a.is_deleted
+          AND ($2::TEXT IS NULL OR e.app_id = $2)
+          AND ($3::TEXT IS NULL OR e.env_id = $3)
+        ORDER BY a.name, e.name
+        "#,
+    )
+    .bind(org_id)
+    .bind(app_id)
+    .bind(env_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[derive(sqlx::FromRow)]
+struct EnvRestartsRow {
+    env_id: String,
+    app_id: String,
+    app_name: String,
+    env_name: String,
+    restarts: i64,
+}
+
+/// Where `rule`'s condition holds now. Projection lag is read once per pass
+/// by the caller and passed in.
+async fn observe(
+    pool: &PgPool,
+    rule: &AlertRule,
+    projection_lag: &[(String, i64)],
+) -> Result<Vec<Observation>, AlertError> {
+    let spec = &rule.spec;
+    let observations = match spec.condition {
+        Condition::EnvUnhealthy => env_health(
+            pool,
+            &rule.org_id,
+            spec.app_id.as_deref(),
+            spec.env_id.as_deref(),
+        )
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            let summary = env_unhealthy_summary(
+                &row.app_name,
+                &row.env_name,
+                row.desired,
+                row.ready,
+                row.failed,
+            )?;
+            Some(Observation {
+                target: row.env_id.clone(),
+                app_id: Some(row.app_id),
+                env_id: Some(row.env_id),
+                value: Some(row.ready),
+                summary,
+            })
+        })
+        .collect(),
+        Condition::NodeOffline => sqlx::query_as::<_, (String, DateTime<Utc>)>(
+            "SELECT node_id, updated_at FROM nodes_view WHERE state = 'offline' ORDER BY node_id",
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(node_id, since)| Observation {
+            summary: format!("Node {node_id} is offline (since {})", since.to_rfc3339()),
+            target: node_id,
+            app_id: None,
+            env_id: None,
+            value: None,
+        })
+        .collect(),
+        Condition::ProjectionLag { threshold } => lagging_projections(projection_lag, threshold),
+        Condition::InstanceRestarts {
+            threshold,
+            window_seconds,
+        } => sqlx::query_as::<_, EnvRestartsRow>(
+            r#"
+            SELECT r.env_id, e.app_id, a.name AS app_name, e.name AS env_name,
+                   COUNT(*) AS restarts
+            FROM instance_restarts r
+            JOIN envs_view e ON e.env_id = r.env_id
+            JOIN apps_view a ON a.app_id = e.app_id
+            WHERE r.org_id = $1
+              AND r.restarted_at >= now() - make_interval(secs => $2)
+              AND NOT e.is_deleted
+              AND ($3::TEXT IS NULL OR e.app_id = $3)
+              AND ($4::TEXT IS NULL OR e.env_id = $4)
+            GROUP BY r.env_id, e.app_id, a.name, e.name
+            HAVING COUNT(*) >= $5
+            "#,
+        )
+        .bind(&rule.org_id)
+        .bind(window_seconds as f64)
+        .bind(&spec.app_id)
+        .bind(&spec.env_id)
+        .bind(threshold)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Observation {
+            summary: format!(
+                "{}/{}: {} instance failures in the last {}s (threshold {threshold})",
+                row.app_name, row.env_name, row.restarts, window_seconds
+            ),
+            target: row.env_id.clone(),
+            app_id: Some(row.app_id),
+            env_id: Some(row.env_id),
+            value: Some(row.restarts),
+        })
+        .collect(),
+        Condition::SloBurnRate {
+            threshold,
+            window_seconds,
+        } => {
+            let objectives = slo::objectives(pool, &rule.org_id).await?;
+            let envs = env_health(
+                pool,
+                &rule.org_id,
+                spec.app_id.as_deref(),
+                spec.env_id.as_deref(),
+            )
+            .await?;
+            let now = Utc::now();
+            let since = now - Duration::seconds(window_seconds.into());
+
+            let mut observations = Vec::new();
+            for env in envs {
+                let objective = objectives
+                    .get(&env.env_id)
+                    .copied()
+                    .unwrap_or(slo::DEFAULT_OBJECTIVE);
+                let measure = slo::measure_env(pool, &env.env_id, since, now).await?;
+                let Some(burn_rate) = availability::budget(&measure, objective).burn_rate else {
+                    continue;
+                };
+                let Some(summary) = slo_burn_summary(
+                    &env.app_name,
+                    &env.env_name,
+                    burn_rate,
+                    threshold,
+                    objective,
+                    window_seconds,
+                ) else {
+                    continue;
+                };
+                observations.push(Observation {
+                    target: env.env_id.clone(),
+                    app_id: Some(env.app_id),
+                    env_id: Some(env.env_id),
+                    value: Some(burn_rate as i64),
+                    summary,
+                });
+            }
+            observations
+        }
+    };
+    Ok(observations)
+}
+
+// =============================================================================
+// Reconciliation
+// =============================================================================
+
+/// Whether a pending alert has held long enough to fire.
+pub fn is_due(pending_since: DateTime<Utc>, for_seconds: i32, now: DateTime<Utc>) -> bool {
+    now - pending_since >= Duration::seconds(for_seconds.into())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PassSummary {
+    pub rules_evaluated: usize,
+    pub fired: usize,
+    pub resolved: usize,
+}
+
+/// Bring `rule`'s alerts in line with `observed`, emitting events for
+/// alerts that fire or resolve. Returns (fired, resolved).
+async fn reconcile_rule(
+    pool: &PgPool,
+    store: &EventStore,
+    source: &SystemSource,
+    rule: &AlertRule,
+    observed: &[Observation],
+) -> Result<(usize, usize), AlertError> {
+    let mut fired = 0;
+    for observation in observed {
+        let (state, pending_since): (String, DateTime<Utc>) = sqlx::query_as(
+            r#"
+            INSERT INTO alerts
+                (rule_id, target, org_id, app_id, env_id, state, value, summary,
+                 pending_since, last_evaluated_at)
+            VALUES ($1, $2, $3, $4, $5, 'pending', $6, $7, now(), now())
+            ON CONFLICT (rule_id, target) DO UPDATE SET
+                app_id = EXCLUDED.app_id,
+                env_id = EXCLUDED.env_id,
+                value = EXCLUDED.value,
+                summary = EXCLUDED.summary,
+                last_evaluated_at = EXCLUDED.last_evaluated_at
+            RETURNING state, pending_since
+            "#,
+        )
+        .bind(&rule.rule_id)
+        .bind(&observation.target)
+        .bind(&rule.org_id)
+        .bind(&observation.app_id)
+        .bind(&observation.env_id)
+        .bind(observation.value)
+        .bind(&observation.summary)
+        .fetch_one(pool)
+        .await?;
+
+        if state != "pending" || !is_due(pending_since, rule.spec.for_seconds, Utc::now()) {
+            continue;
+        }
+        let marked = sqlx::query(
+            r#"
+            UPDATE alerts SET state = 'firing', fired_at = now()
+            WHERE rule_id = $1 AND target = $2 AND state = 'pending'
+            "#,
+        )
+        .bind(&rule.rule_id)
+        .bind(&observation.target)
+        .execute(pool)
+        .await?;
+        if marked.rows_affected() == 0 {
+            continue;
+        }
+        record_fired(store, source, rule, observation, pending_since).await?;
+        info!(
+            rule_id = %rule.rule_id,
+            condition = rule.spec.condition.as_str(),
+            target = %observation.target,
+            "Alert fired"
+        );
+        fired += 1;
+    }
+
+    let holding: Vec<String> = observed.iter().map(|o| o.target.clone()).collect();
+    let cleared = sqlx::query_as::<
+        _,
+        (
+            String,
+            String,
+            Option<String>,
+            Option<String>,
+            Option<DateTime<Utc>>,
+        ),
+    >(
+        r#"
+        DELETE FROM alerts
+        WHERE rule_id = $1 AND NOT (target = ANY($2))
+        RETURNING target, state, app_id, env_id, fired_at
+        "#,
+    )
+    .bind(&rule.rule_id)
+    .bind(&holding)
+    .fetch_all(pool)
+    .await?;
+
+    let mut resolved = 0;
+    for (target, state, app_id, env_id, fired_at) in cleared {
+        if state != "firing" {
+            continue;
+        }
+        let fired_at = fired_at.unwrap_or_else(Utc::now);
+        record_resolved(store, source, rule, &target, app_id, env_id, fired_at).await?;
+        info!(
+            rule_id = %rule.rule_id,
+            condition = rule.spec.condition.as_str(),
+            target = %target,
+            "Alert resolved"
+        );
+        resolved += 1;
+    }
+
+    Ok((fired, resolved))
+}
+
+fn parse_scope(app_id: Option<&str>, env_id: Option<&str>) -> (Option<AppId>, Option<EnvId>) {
+    (
+        app_id.and_then(|id| id.parse().ok()),
+        env_id.and_then(|id| id.parse().ok()),
+    )
+}
+
+async fn next_seq(store: &EventStore, rule_id: &str) -> Result<i32, AlertError> {
+    Ok(store
+        .get_latest_aggregate_seq(&AggregateType::AlertRule, rule_id)
+        .await?
+        .unwrap_or(0)
+        + 1)
+}
+
+/// Emit `alert.fired`.
+async fn record_fired(
+    store: &EventStore,
+    source: &SystemSource,
+    rule: &AlertRule,
+    observation: &Observation,
+    pending_since: DateTime<Utc>,
+) -> Result<(), AlertError> {
+    let Ok(org_id) = rule.org_id.parse::<OrgId>() else {
+        warn!(rule_id = %rule.rule_id, "Skipping alert event for rule with invalid org ID");
+        return Ok(());
+    };
+    let (app_id, env_id) =
+        parse_scope(observation.app_id.as_deref(), observation.env_id.as_deref());
+    let mut builder = NewEvent::builder(source)
+        .aggregate_id(rule.rule_id.clone())
+        .aggregate_seq(next_seq(store, &rule.rule_id).await?)
+        .org_id(org_id)
+        .payload(&AlertFiredPayload {
+            rule_id: rule.rule_id.clone(),
+            org_id,
+            rule_name: rule.spec.name.clone(),
+            condition: rule.spec.condition.as_str().to_string(),
+            target: observation.target.clone(),
+            app_id,
+            env_id,
+            value: observation.value,
+            threshold: rule.spec.condition.threshold(),
+            summary: observation.summary.clone(),
+            pending_since: pending_since.to_rfc3339(),
+        });
+    if let Some(app_id) = app_id {
+        builder = builder.app_id(app_id);
+    }
+    if let Some(env_id) = env_id {
+        builder = builder.env_id(env_id);
+    }
+    store.append(builder.build()?.into()).await?;
+    Ok(())
+}
+
+/// Emit `alert.resolved`.
+async fn record_resolved(
+    store: &EventStore,
+    source: &SystemSource,
+    rule: &AlertRule,
+    target: &str,
+    app_id: Option<String>,
+    env_id: Option<String>,
+    fired_at: DateTime<Utc>,
+) -> Result<(), AlertError> {
+    let Ok(org_id) = rule.org_id.parse::<OrgId>() else {
+        warn!(rule_id = %rule.rule_id, "Skipping alert event for rule with invalid org ID");
+        return Ok(());
+    };
+    let (app_id, env_id) = parse_scope(app_id.as_deref(), env_id.as_deref());
+    let mut builder = NewEvent::builder(source)
+        .aggregate_id(rule.rule_id.clone())
+        .aggregate_seq(next_seq(store, &rule.rule_id).await?)
+        .org_id(org_id)
+        .payload(&AlertResolvedPayload {
+            rule_id: rule.rule_id.clone(),
+            org_id,
+            rule_name: rule.spec.name.clone(),
+            condition: rule.spec.condition.as_str().to_string(),
+            target: target.to_string(),
+            app_id,
+            env_id,
+            fired_at: fired_at.to_rfc3339(),
+        });
+    if let Some(app_id) = app_id {
+        builder = builder.app_id(app_id);
+    }
+    if let Some(env_id) = env_id {
+        builder = builder.env_id(env_id);
+    }
+    store.append(builder.build()?.into()).await?;
+    Ok(())
+}
+
+// =============================================================================
+// Pass
+// =============================================================================
+
+/// Evaluate every enabled rule.
+pub async fn run_pass(pool: &PgPool) -> Result<PassSummary, AlertError> {
+    let query = format!("SELECT {RULE_COLUMNS} FROM alert_rules WHERE enabled ORDER BY rule_id");
+    let rules = sqlx::query_as::<_, AlertRule>(&query)
+        .fetch_all(pool)
+        .await?;
+
+    let projection_lag = if rules
+        .iter()
+        .any(|r| matches!(r.spec.condition, Condition::ProjectionLag { .. }))
+    {
+        ProjectionStore::new(pool.clone()).calculate_lag().await?
+    } else {
+        Vec::new()
+    };
+
+    let store = EventStore::new(pool.clone());
+    let source = SystemSource::new(ALERT_EVALUATOR_ACTOR_ID);
+    let mut summary = PassSummary::default();
+
+    for rule in &rules {
+        let observed = observe(pool, rule, &projection_lag).await?;
+        let (fired, resolved) = reconcile_rule(pool, &store, &source, rule, &observed).await?;
+        summary.rules_evaluated += 1;
+        summary.fired += fired;
+        summary.resolved += resolved;
+    }
+
+    sqlx::query(
+        "DELETE FROM instance_restarts WHERE restarted_at < now() - make_interval(secs => $1)",
+    )
+    .bind(f64::from(MAX_DURATION_SECS))
+    .execute(pool)
+    .await?;
+
+    Ok(summary)
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker evaluating alert rules, on the elected leader only.
+pub struct AlertEvaluatorWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    election: LeaderElection,
+}
+
+impl AlertEvaluatorWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::Alerts, interval * 3),
+            pool,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            "Starting alert evaluator worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    match run_pass(&self.pool).await {
+                        Ok(summary) if summary.fired > 0 || summary.resolved > 0 => info!(
+                            rules = summary.rules_evaluated,
+                            fired = summary.fired,
+                            resolved = summary.resolved,
+                            "Alert evaluation pass complete"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Alert evaluation pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Alert evaluator worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_unhealthy_summary() {
+        assert_eq!(env_unhealthy_summary("web", "prod", 3, 3, 0), None);
+        assert_eq!(env_unhealthy_summary("web", "prod", 0, 0, 0), None);
+        assert_eq!(
+            env_unhealthy_summary("web", "prod", 3, 1, 0).as_deref(),
+            Some("web/prod: 1 of 3 instances ready")
+        );
+        assert_eq!(
+            env_unhealthy_summary("web", "prod", 2, 2, 1).as_deref(),
+            Some("web/prod: 2 of 2 instances ready, 1 failed")
+        );
+    }
+
+    #[test]
+    fn test_slo_burn_summary() {
+        assert_eq!(slo_burn_summary("web", "prod", 13.9, 14, 99.9, 3600), None);
+        assert_eq!(
+            slo_burn_summary("web", "prod", 20.5, 14, 99.9, 3600).as_deref(),
+            Some(
+                "web/prod: error budget burning at 20.5x over the last 3600s \
+                 (objective 99.9%, threshold 14x)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_lagging_projections() {
+        let lag = vec![
+            ("instances".to_string(), 5000),
+            ("apps".to_string(), 100),
+            ("envs".to_string(), 0),
+        ];
+        let observed = lagging_projections(&lag, 100);
+        assert_eq!(observed.len(), 1);
+        assert_eq!(observed[0].target, "instances");
+        assert_eq!(observed[0].value, Some(5000));
+        assert_eq!(
+            observed[0].summary,
+            "Projection instances is 5000 events behind (threshold 100)"
+        );
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        assert!(is_due(now, 0, now));
+        assert!(!is_due(now - Duration::seconds(299), 300, now));
+        assert!(is_due(now - Duration::seconds(300), 300, now));
+    }
+}
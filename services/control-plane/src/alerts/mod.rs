@@ -0,0 +1,509 @@
+//! Org alert rules and open alerts.
+//!
+//! A rule watches one condition, evaluated from status tables by the alert
+//! evaluator (leader only, see [`evaluator`]):
+//! - `env_unhealthy`: an env has failed instances or fewer ready instances
+//!   than desired replicas
+//! - `node_offline`: a node is in state `offline`
+//! - `projection_lag`: a projection is more than `threshold` events behind
+//! - `instance_restarts`: an env's instances failed at least `threshold`
+//!   times within `window_seconds`
+//! - `slo_burn_rate`: an env is spending its error budget at least
+//!   `threshold` times faster than its objective allows, measured over
+//!   `window_seconds` (see [`crate::slo`])
+//!
+//! Each target (env, node, or projection) the condition holds on gets a
+//! row in `alerts`. It starts `pending` and turns `firing` once the
+//! condition has held for the rule's `for_seconds`, emitting `alert.fired`;
+//! when the condition clears, a firing alert emits `alert.resolved` and the
+//! row is dropped. Notification rules with the `alert_fired` and
+//! `alert_resolved` triggers deliver these events to channels.
+//!
+//! `node_offline` and `projection_lag` describe the platform rather than
+//! the org's apps, so only operators may create rules for them.
+//!
+//! See: docs/specs/observability/alert-rules.md
+
+pub mod evaluator;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+
+use crate::db::DbError;
+
+/// Upper bound on `for_seconds` and `window_seconds`.
+pub const MAX_DURATION_SECS: i32 = 86_400;
+
+/// Lower bound on `window_seconds` for restart counts.
+pub const MIN_WINDOW_SECS: i32 = 60;
+
+const DEFAULT_RESTART_WINDOW_SECS: i32 = 900;
+
+const DEFAULT_BURN_WINDOW_SECS: i32 = 3600;
+
+#[derive(Debug, Error)]
+pub enum AlertError {
+    #[error("name is already in use in this org")]
+    NameTaken,
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn name_taken(error: sqlx::Error) -> AlertError {
+    match &error {
+        sqlx::Error::Database(db) if db.is_unique_violation() => AlertError::NameTaken,
+        _ => AlertError::Database(error),
+    }
+}
+
+// =============================================================================
+// Conditions
+// =============================================================================
+
+fn default_restart_window() -> i32 {
+    DEFAULT_RESTART_WINDOW_SECS
+}
+
+fn default_burn_window() -> i32 {
+    DEFAULT_BURN_WINDOW_SECS
+}
+
+/// What a rule watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Condition {
+    EnvUnhealthy,
+    NodeOffline,
+    ProjectionLag {
+        /// Events behind the head of the log.
+        threshold: i64,
+    },
+    InstanceRestarts {
+        /// Failures within the window.
+        threshold: i64,
+        #[serde(default = "default_restart_window")]
+        window_seconds: i32,
+    },
+    SloBurnRate {
+        /// Burn rate multiple: 1 spends exactly the budget.
+        threshold: i64,
+        #[serde(default = "default_burn_window")]
+        window_seconds: i32,
+    },
+}
+
+impl Condition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Condition::EnvUnhealthy => "env_unhealthy",
+            Condition::NodeOffline => "node_offline",
+            Condition::ProjectionLag { .. } => "projection_lag",
+            Condition::InstanceRestarts { .. } => "instance_restarts",
+            Condition::SloBurnRate { .. } => "slo_burn_rate",
+        }
+    }
+
+    pub fn threshold(self) -> Option<i64> {
+        match self {
+            Condition::ProjectionLag { threshold }
+            | Condition::InstanceRestarts { threshold, .. }
+            | Condition::SloBurnRate { threshold, .. } => Some(threshold),
+            Condition::EnvUnhealthy | Condition::NodeOffline => None,
+        }
+    }
+
+    pub fn window_seconds(self) -> Option<i32> {
+        match self {
+            Condition::InstanceRestarts { window_seconds, .. }
+            | Condition::SloBurnRate { window_seconds, .. } => Some(window_seconds),
+            _ => None,
+        }
+    }
+
+    /// Rebuild a condition from its stored columns.
+    pub fn from_columns(
+        condition: &str,
+        threshold: Option<i64>,
+        window_seconds: Option<i32>,
+    ) -> Option<Self> {
+        match condition {
+            "env_unhealthy" => Some(Condition::EnvUnhealthy),
+            "node_offline" => Some(Condition::NodeOffline),
+            "projection_lag" => Some(Condition::ProjectionLag {
+                threshold: threshold?,
+            }),
+            "instance_restarts" => Some(Condition::InstanceRestarts {
+                threshold: threshold?,
+                window_seconds: window_seconds.unwrap_or(DEFAULT_RESTART_WINDOW_SECS),
+            }),
+            "slo_burn_rate" => Some(Condition::SloBurnRate {
+                threshold: threshold?,
+                window_seconds: window_seconds.unwrap_or(DEFAULT_BURN_WINDOW_SECS),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether the condition is about platform state (operators only).
+    pub fn is_platform(self) -> bool {
+        matches!(
+            self,
+            Condition::NodeOffline | Condition::ProjectionLag { .. }
+        )
+    }
+
+    /// Check thresholds and windows.
+    pub fn validate(self) -> Result<(), String> {
+        match self {
+            Condition::EnvUnhealthy | Condition::NodeOffline => Ok(()),
+            Condition::ProjectionLag { threshold } if threshold < 1 => {
+                Err("projection_lag threshold must be at least 1 event".to_string())
+            }
+            Condition::ProjectionLag { .. } => Ok(()),
+            Condition::InstanceRestarts { threshold, .. } if threshold < 1 => {
+                Err("instance_restarts threshold must be at least 1".to_string())
+            }
+            Condition::InstanceRestarts { window_seconds, .. }
+                if !(MIN_WINDOW_SECS..=MAX_DURATION_SECS).contains(&window_seconds) =>
+            {
+                Err(format!(
+                    "window_seconds must be between {MIN_WINDOW_SECS} and {MAX_DURATION_SECS}"
+                ))
+            }
+            Condition::InstanceRestarts { .. } => Ok(()),
+            Condition::SloBurnRate { threshold, .. } if threshold < 1 => {
+                Err("slo_burn_rate threshold must be at least 1".to_string())
+            }
+            Condition::SloBurnRate { window_seconds, .. }
+                if !(MIN_WINDOW_SECS..=MAX_DURATION_SECS).contains(&window_seconds) =>
+            {
+                Err(format!(
+                    "window_seconds must be between {MIN_WINDOW_SECS} and {MAX_DURATION_SECS}"
+                ))
+            }
+            Condition::SloBurnRate { .. } => Ok(()),
+        }
+    }
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// The settable fields of a rule.
+#[derive(Debug, Clone)]
+pub struct RuleSpec {
+    pub name: String,
+    pub condition: Condition,
+    /// How long the condition must hold before the alert fires.
+    pub for_seconds: i32,
+    /// Scope for env conditions; `None` matches every app / env in the org.
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub rule_id: String,
+    pub org_id: String,
+    pub spec: RuleSpec,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const RULE_COLUMNS: &str = "rule_id, org_id, name, condition, threshold, window_seconds, \
+     for_seconds, app_id, env_id, enabled, created_by, created_at, updated_at";
+
+impl<'r> FromRow<'r, PgRow> for AlertRule {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let kind: String = row.try_get("condition")?;
+        let condition = Condition::from_columns(
+            &kind,
+            row.try_get("threshold")?,
+            row.try_get("window_seconds")?,
+        )
+        .ok_or_else(|| sqlx::Error::Decode(format!("invalid alert condition {kind:?}").into()))?;
+        Ok(Self {
+            rule_id: row.try_get("rule_id")?,
+            org_id: row.try_get("org_id")?,
+            spec: RuleSpec {
+                name: row.try_get("name")?,
+                condition,
+                for_seconds: row.try_get("for_seconds")?,
+                app_id: row.try_get("app_id")?,
+                env_id: row.try_get("env_id")?,
+                enabled: row.try_get("enabled")?,
+            },
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+pub async fn create_rule(
+    pool: &PgPool,
+    org_id: &str,
+    spec: &RuleSpec,
+    created_by: &str,
+) -> Result<AlertRule, AlertError> {
+    let query = format!(
+        r#"
+        INSERT INTO alert_rules
+            (rule_id, org_id, name, condition, threshold, window_seconds, for_seconds,
+             app_id, env_id, enabled, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING {RULE_COLUMNS}
+        "#
+    );
+    sqlx::query_as::<_, AlertRule>(&query)
+        .bind(format!("alr_{}", plfm_id::Ulid::new()))
+        .bind(org_id)
+        .bind(&spec.name)
+        .bind(spec.condition.as_str())
+        .bind(spec.condition.threshold())
+        .bind(spec.condition.window_seconds())
+        .bind(spec.for_seconds)
+        .bind(&spec.app_id)
+        .bind(&spec.env_id)
+        .bind(spec.enabled)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await
+        .map_err(name_taken)
+}
+
+/// Replace a rule. Its open alerts are dropped without notifying, so the
+/// changed rule is evaluated from scratch.
+pub async fn update_rule(
+    pool: &PgPool,
+    org_id: &str,
+    rule_id: &str,
+    spec: &RuleSpec,
+) -> Result<Option<AlertRule>, AlertError> {
+    let query = format!(
+        r#"
+        UPDATE alert_rules
+        SET name = $3, condition = $4, threshold = $5, window_seconds = $6, for_seconds = $7,
+            app_id = $8, env_id = $9, enabled = $10, updated_at = now()
+        WHERE org_id = $1 AND rule_id = $2
+        RETURNING {RULE_COLUMNS}
+        "#
+    );
+    let mut tx = pool.begin().await?;
+    let rule = sqlx::query_as::<_, AlertRule>(&query)
+        .bind(org_id)
+        .bind(rule_id)
+        .bind(&spec.name)
+        .bind(spec.condition.as_str())
+        .bind(spec.condition.threshold())
+        .bind(spec.condition.window_seconds())
+        .bind(spec.for_seconds)
+        .bind(&spec.app_id)
+        .bind(&spec.env_id)
+        .bind(spec.enabled)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(name_taken)?;
+    if rule.is_some() {
+        sqlx::query("DELETE FROM alerts WHERE rule_id = $1")
+            .bind(rule_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(rule)
+}
+
+/// Delete a rule with its open alerts (without notifying).
+pub async fn delete_rule(pool: &PgPool, org_id: &str, rule_id: &str) -> Result<bool, sqlx::Error> {
+    let deleted = sqlx::query("DELETE FROM alert_rules WHERE org_id = $1 AND rule_id = $2")
+        .bind(org_id)
+        .bind(rule_id)
+        .execute(pool)
+        .await?
+        .rows_affected()
+        > 0;
+    Ok(deleted)
+}
+
+pub async fn list_rules(pool: &PgPool, org_id: &str) -> Result<Vec<AlertRule>, sqlx::Error> {
+    let query = format!("SELECT {RULE_COLUMNS} FROM alert_rules WHERE org_id = $1 ORDER BY name");
+    sqlx::query_as::<_, AlertRule>(&query)
+        .bind(org_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_rule(
+    pool: &PgPool,
+    org_id: &str,
+    rule_id: &str,
+) -> Result<Option<AlertRule>, sqlx::Error> {
+    let query =
+        format!("SELECT {RULE_COLUMNS} FROM alert_rules WHERE org_id = $1 AND rule_id = $2");
+    sqlx::query_as::<_, AlertRule>(&query)
+        .bind(org_id)
+        .bind(rule_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// =============================================================================
+// Alerts
+// =============================================================================
+
+/// A pending or firing alert.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub condition: String,
+    pub target: String,
+    pub org_id: String,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    /// `pending` or `firing`.
+    pub state: String,
+    pub value: Option<i64>,
+    pub summary: String,
+    pub pending_since: DateTime<Utc>,
+    pub fired_at: Option<DateTime<Utc>>,
+    pub last_evaluated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for Alert {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            rule_id: row.try_get("rule_id")?,
+            rule_name: row.try_get("rule_name")?,
+            condition: row.try_get("condition")?,
+            target: row.try_get("target")?,
+            org_id: row.try_get("org_id")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            state: row.try_get("state")?,
+            value: row.try_get("value")?,
+            summary: row.try_get("summary")?,
+            pending_since: row.try_get("pending_since")?,
+            fired_at: row.try_get("fired_at")?,
+            last_evaluated_at: row.try_get("last_evaluated_at")?,
+        })
+    }
+}
+
+/// The org's open alerts, oldest first, optionally only those in `state`.
+pub async fn list_alerts(
+    pool: &PgPool,
+    org_id: &str,
+    state: Option<&str>,
+) -> Result<Vec<Alert>, sqlx::Error> {
+    sqlx::query_as::<_, Alert>(
+        r#"
+        SELECT a.rule_id, r.name AS rule_name, r.condition, a.target, a.org_id, a.app_id,
+               a.env_id, a.state, a.value, a.summary, a.pending_since, a.fired_at,
+               a.last_evaluated_at
+        FROM alerts a
+        JOIN alert_rules r ON r.rule_id = a.rule_id
+        WHERE a.org_id = $1 AND ($2::TEXT IS NULL OR a.state = $2)
+        ORDER BY a.pending_since, a.rule_id, a.target
+        "#,
+    )
+    .bind(org_id)
+    .bind(state)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_condition_serde() {
+        let condition: Condition =
+            serde_json::from_value(json!({"type": "instance_restarts", "threshold": 5})).unwrap();
+        assert_eq!(
+            condition,
+            Condition::InstanceRestarts {
+                threshold: 5,
+                window_seconds: 900
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(Condition::EnvUnhealthy).unwrap(),
+            json!({"type": "env_unhealthy"})
+        );
+        assert!(serde_json::from_value::<Condition>(json!({"type": "projection_lag"})).is_err());
+        assert!(serde_json::from_value::<Condition>(
+            json!({"type": "projection_lag", "threshold": 1, "window_seconds": 60})
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_condition_columns_round_trip() {
+        for condition in [
+            Condition::EnvUnhealthy,
+            Condition::NodeOffline,
+            Condition::ProjectionLag { threshold: 1000 },
+            Condition::InstanceRestarts {
+                threshold: 3,
+                window_seconds: 600,
+            },
+            Condition::SloBurnRate {
+                threshold: 14,
+                window_seconds: 3600,
+            },
+        ] {
+            assert_eq!(
+                Condition::from_columns(
+                    condition.as_str(),
+                    condition.threshold(),
+                    condition.window_seconds()
+                ),
+                Some(condition)
+            );
+        }
+        assert_eq!(Condition::from_columns("projection_lag", None, None), None);
+        assert_eq!(Condition::from_columns("cpu_high", Some(1), None), None);
+    }
+
+    #[test]
+    fn test_condition_validate() {
+        assert_eq!(Condition::EnvUnhealthy.validate(), Ok(()));
+        assert!(Condition::ProjectionLag { threshold: 0 }
+            .validate()
+            .is_err());
+        assert!(Condition::InstanceRestarts {
+            threshold: 3,
+            window_seconds: 30
+        }
+        .validate()
+        .is_err());
+        assert!(Condition::InstanceRestarts {
+            threshold: 0,
+            window_seconds: 600
+        }
+        .validate()
+        .is_err());
+        assert!(Condition::SloBurnRate {
+            threshold: 2,
+            window_seconds: 86_401
+        }
+        .validate()
+        .is_err());
+        assert!(Condition::NodeOffline.is_platform());
+        assert!(!Condition::EnvUnhealthy.is_platform());
+    }
+}
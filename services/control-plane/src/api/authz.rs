@@ -1,6 +1,8 @@
 //! Authorization helpers (v1).
 //!
-//! v1 uses org-scoped membership for tenant isolation.
+//! v1 uses org-scoped membership for tenant isolation. Platform operations
+//! (node enrollment) are limited to operators listed in
+//! `PLFM_OPERATOR_EMAILS`; in dev mode every authenticated user is one.
 
 use plfm_events::MemberRole;
 use plfm_id::OrgId;
@@ -39,6 +41,36 @@ pub fn require_authenticated(ctx: &RequestContext) -> Result<(), ApiError> {
     Ok(())
 }
 
+/// Require a platform operator: a user whose email is listed in
+/// `PLFM_OPERATOR_EMAILS` (comma-separated), or any authenticated user in
+/// dev mode.
+pub fn require_operator(ctx: &RequestContext) -> Result<(), ApiError> {
+    require_authenticated(ctx)?;
+
+    let dev_mode = std::env::var("GHOST_DEV")
+        .map(|v| v == "1" || v.to_lowercase() == "true")
+        .unwrap_or(false);
+    if dev_mode {
+        return Ok(());
+    }
+
+    let operators = std::env::var("PLFM_OPERATOR_EMAILS").unwrap_or_default();
+    let is_operator = ctx.actor_email.as_deref().is_some_and(|email| {
+        operators
+            .split(',')
+            .map(str::trim)
+            .any(|operator| !operator.is_empty() && operator.eq_ignore_ascii_case(email))
+    });
+    if !is_operator {
+        return Err(ApiError::forbidden(
+            "forbidden",
+            "Platform operator access required for this operation",
+        )
+        .with_request_id(ctx.request_id.clone()));
+    }
+    Ok(())
+}
+
 pub async fn require_org_member(
     state: &AppState,
     org_id: &OrgId,
@@ -0,0 +1,276 @@
+//! Read-your-writes consistency tokens.
+//!
+//! Every write response carries an `X-Consistency-Token` header naming the
+//! last event the request appended. A later request that sends the token
+//! back (as the same header, or as `?min_event_id=`) is held by
+//! [`read_your_writes`] until every projection has applied the events up to
+//! that point, so it observes its own writes without the write itself having
+//! to block on the projection worker.
+//!
+//! Tokens are opaque to clients; `min_event_id` also accepts a raw event ID.
+//!
+//! See: docs/specs/api/http-api.md
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::projections::ProjectionRegistry;
+use crate::state::AppState;
+
+pub const CONSISTENCY_TOKEN_HEADER: &str = "X-Consistency-Token";
+pub const MIN_EVENT_ID_PARAM: &str = "min_event_id";
+
+const TOKEN_PREFIX: &str = "ct1_";
+
+/// A position in the event log a reader must observe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConsistencyToken(i64);
+
+impl ConsistencyToken {
+    pub fn new(event_id: i64) -> Option<Self> {
+        (event_id > 0).then_some(Self(event_id))
+    }
+
+    pub fn event_id(self) -> i64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ConsistencyToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:x}", TOKEN_PREFIX, self.0)
+    }
+}
+
+impl FromStr for ConsistencyToken {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let event_id = match s.strip_prefix(TOKEN_PREFIX) {
+            Some(hex) => i64::from_str_radix(hex, 16).map_err(|_| ())?,
+            None => s.parse::<i64>().map_err(|_| ())?,
+        };
+        Self::new(event_id).ok_or(())
+    }
+}
+
+/// The highest event ID written during a request.
+///
+/// Shared between the middleware and the request's [`RequestContext`].
+#[derive(Debug, Clone, Default)]
+pub struct WriteWatermark(Arc<AtomicI64>);
+
+impl WriteWatermark {
+    pub fn record(&self, event_id: i64) {
+        self.0.fetch_max(event_id, Ordering::Relaxed);
+    }
+
+    pub fn token(&self) -> Option<ConsistencyToken> {
+        ConsistencyToken::new(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// Record a write without waiting for projections. Use when the response is
+/// built from the request rather than read back from a view.
+pub fn record_write(ctx: &RequestContext, event_id: i64) {
+    ctx.write_watermark.record(event_id);
+}
+
+/// Record a write and wait for `projection_name` to apply it. Use when the
+/// response is read back from that projection's view.
+pub async fn wait_for_write(
+    state: &AppState,
+    ctx: &RequestContext,
+    projection_name: &str,
+    event_id: i64,
+) -> Result<(), ApiError> {
+    record_write(ctx, event_id);
+
+    state
+        .db()
+        .projection_store()
+        .wait_for_checkpoint(
+            projection_name,
+            event_id,
+            crate::api::projection_wait_timeout(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                error = %e,
+                request_id = %ctx.request_id,
+                projection = projection_name,
+                "Projection wait failed"
+            );
+            projection_timeout(&ctx.request_id)
+        })
+}
+
+/// `(projection_name, event_type)` for every registered projection.
+fn coverage() -> &'static [(&'static str, &'static str)] {
+    static COVERAGE: OnceLock<Vec<(&'static str, &'static str)>> = OnceLock::new();
+    COVERAGE.get_or_init(|| ProjectionRegistry::new().coverage())
+}
+
+fn projection_timeout(request_id: &str) -> ApiError {
+    ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
+        .with_retry_after_seconds(1)
+        .with_request_id(request_id.to_string())
+}
+
+/// The token the client asked to observe, from the header or the query.
+fn requested_token(request: &Request) -> Result<Option<ConsistencyToken>, ApiError> {
+    let from_query = request.uri().query().and_then(|query| {
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == MIN_EVENT_ID_PARAM).then_some(value)
+        })
+    });
+    let from_header = request
+        .headers()
+        .get(CONSISTENCY_TOKEN_HEADER)
+        .map(|v| v.to_str().unwrap_or_default());
+
+    let mut token = None;
+    for raw in [from_query, from_header].into_iter().flatten() {
+        let parsed = raw.parse::<ConsistencyToken>().map_err(|_| {
+            ApiError::bad_request(
+                "invalid_consistency_token",
+                format!("Invalid consistency token '{raw}'"),
+            )
+        })?;
+        token = token.max(Some(parsed));
+    }
+    Ok(token)
+}
+
+/// Hold requests that carry a consistency token until projections have
+/// caught up to it, and return the request's write watermark as a token.
+pub async fn read_your_writes(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_default();
+
+    let requested = match requested_token(&request) {
+        Ok(token) => token,
+        Err(err) => return err.with_request_id(request_id).into_response(),
+    };
+
+    if let Some(token) = requested {
+        if let Err(e) = state
+            .db()
+            .projection_store()
+            .wait_until_caught_up(
+                token.event_id(),
+                coverage(),
+                crate::api::projection_wait_timeout(),
+            )
+            .await
+        {
+            tracing::warn!(
+                error = %e,
+                request_id = %request_id,
+                min_event_id = token.event_id(),
+                "Consistency token wait failed"
+            );
+            return projection_timeout(&request_id).into_response();
+        }
+    }
+
+    let watermark = WriteWatermark::default();
+    request.extensions_mut().insert(watermark.clone());
+
+    let mut response = next.run(request).await;
+
+    // Echo the larger of the requested and written positions so clients can
+    // thread a single token through a session.
+    if let Some(token) = watermark.token().max(requested) {
+        if let Ok(value) = HeaderValue::from_str(&token.to_string()) {
+            response
+                .headers_mut()
+                .insert(HeaderName::from_static("x-consistency-token"), value);
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_round_trip() {
+        let token = ConsistencyToken::new(4096).unwrap();
+        assert_eq!(token.to_string(), "ct1_1000");
+        assert_eq!("ct1_1000".parse::<ConsistencyToken>(), Ok(token));
+    }
+
+    #[test]
+    fn test_token_accepts_raw_event_id() {
+        assert_eq!(
+            "42".parse::<ConsistencyToken>()
+                .map(ConsistencyToken::event_id),
+            Ok(42)
+        );
+        assert!("0".parse::<ConsistencyToken>().is_err());
+        assert!("-3".parse::<ConsistencyToken>().is_err());
+        assert!("ct1_zz".parse::<ConsistencyToken>().is_err());
+        assert!("".parse::<ConsistencyToken>().is_err());
+    }
+
+    #[test]
+    fn test_watermark_keeps_highest_write() {
+        let watermark = WriteWatermark::default();
+        assert_eq!(watermark.token(), None);
+
+        let shared = watermark.clone();
+        shared.record(7);
+        shared.record(3);
+        assert_eq!(watermark.token().map(ConsistencyToken::event_id), Some(7));
+    }
+
+    #[test]
+    fn test_requested_token_prefers_highest() {
+        let request = Request::builder()
+            .uri("/v1/orgs?limit=10&min_event_id=5")
+            .header(CONSISTENCY_TOKEN_HEADER, "ct1_9")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let token = requested_token(&request).unwrap().unwrap();
+        assert_eq!(token.event_id(), 9);
+
+        let request = Request::builder()
+            .uri("/v1/orgs?min_event_id=nope")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        assert!(requested_token(&request).is_err());
+    }
+
+    #[test]
+    fn test_coverage_includes_every_projection() {
+        let names: std::collections::HashSet<_> = coverage().iter().map(|(n, _)| *n).collect();
+        assert_eq!(
+            names.len(),
+            ProjectionRegistry::new().projection_names().len()
+        );
+    }
+}
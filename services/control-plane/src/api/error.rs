@@ -1,10 +1,25 @@
+//! API error responses (RFC 7807 problem details).
+//!
+//! Every error carries a stable `code` from the shared catalog in
+//! `api/errors/catalog.json` (exposed as `plfm_proto::errors`). The catalog
+//! supplies the `domain` and the default `retryable` flag; handlers only pick
+//! the code, status, and message.
+
 use axum::{
-    http::{header::CONTENT_TYPE, HeaderValue, StatusCode},
+    http::{
+        header::{CONTENT_TYPE, RETRY_AFTER},
+        HeaderValue, StatusCode,
+    },
     response::{IntoResponse, Response},
     Json,
 };
+pub use plfm_api_types::FieldError;
+use plfm_proto::errors;
 use serde::Serialize;
 
+/// Domain reported for codes missing from the catalog.
+const UNKNOWN_DOMAIN: &str = "unknown";
+
 #[derive(Debug, Serialize)]
 pub struct ProblemDetails {
     #[serde(rename = "type")]
@@ -15,6 +30,7 @@ pub struct ProblemDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instance: Option<String>,
     pub code: String,
+    pub domain: String,
     pub request_id: String,
     pub retryable: bool,
     pub retry_after_seconds: u32,
@@ -22,12 +38,6 @@ pub struct ProblemDetails {
     pub details: Option<Vec<FieldError>>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct FieldError {
-    pub field: String,
-    pub message: String,
-}
-
 impl ProblemDetails {
     fn new(status: StatusCode, code: impl Into<String>, detail: impl Into<String>) -> Self {
         let code = code.into();
@@ -35,15 +45,23 @@ impl ProblemDetails {
             .canonical_reason()
             .unwrap_or("Unknown Error")
             .to_string();
+        let spec = errors::lookup(&code);
+        if spec.is_none() {
+            tracing::debug!(code = %code, "API error code is not in the error catalog");
+        }
         Self {
             r#type: format!("https://plfm.dev/problems/{code}"),
             title,
             status: status.as_u16(),
             detail: detail.into(),
             instance: None,
+            domain: spec
+                .map(|spec| spec.domain)
+                .unwrap_or(UNKNOWN_DOMAIN)
+                .to_string(),
             code,
             request_id: "unknown".to_string(),
-            retryable: false,
+            retryable: spec.is_some_and(|spec| spec.retryable),
             retry_after_seconds: 0,
             details: None,
         }
@@ -104,6 +122,18 @@ impl ApiError {
         Self { status, problem }
     }
 
+    pub fn precondition_failed(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let status = StatusCode::PRECONDITION_FAILED;
+        let problem = Box::new(ProblemDetails::new(status, code, message));
+        Self { status, problem }
+    }
+
+    pub fn precondition_required(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let status = StatusCode::PRECONDITION_REQUIRED;
+        let problem = Box::new(ProblemDetails::new(status, code, message));
+        Self { status, problem }
+    }
+
     pub fn gateway_timeout(code: impl Into<String>, message: impl Into<String>) -> Self {
         let status = StatusCode::GATEWAY_TIMEOUT;
         let problem = Box::new(ProblemDetails::new(status, code, message));
@@ -122,6 +152,18 @@ impl ApiError {
         Self { status, problem }
     }
 
+    pub fn payload_too_large(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let status = StatusCode::PAYLOAD_TOO_LARGE;
+        let problem = Box::new(ProblemDetails::new(status, code, message));
+        Self { status, problem }
+    }
+
+    pub fn unsupported_media_type(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let status = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+        let problem = Box::new(ProblemDetails::new(status, code, message));
+        Self { status, problem }
+    }
+
     pub fn too_many_requests(code: impl Into<String>, message: impl Into<String>) -> Self {
         let status = StatusCode::TOO_MANY_REQUESTS;
         let mut problem = Box::new(ProblemDetails::new(status, code, message));
@@ -129,11 +171,19 @@ impl ApiError {
         Self { status, problem }
     }
 
+    pub fn service_unavailable(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let status = StatusCode::SERVICE_UNAVAILABLE;
+        let mut problem = Box::new(ProblemDetails::new(status, code, message));
+        problem.set_retryable(true);
+        Self { status, problem }
+    }
+
     pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
         self.problem.set_request_id(request_id);
         self
     }
 
+    /// Attach field-level violations (validation errors).
     pub fn with_details(mut self, details: Vec<FieldError>) -> Self {
         self.problem.set_details(details);
         self
@@ -147,11 +197,122 @@ impl ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        let retry_after = self.problem.retry_after_seconds;
         let mut response = (self.status, Json(self.problem)).into_response();
         response.headers_mut().insert(
             CONTENT_TYPE,
             HeaderValue::from_static("application/problem+json"),
         );
+        if retry_after > 0 {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_catalog_supplies_domain_and_retryable() {
+        let err = ApiError::conflict("version_conflict", "Concurrent modification");
+        assert_eq!(err.problem.domain, "concurrency");
+        assert!(err.problem.retryable);
+
+        let err = ApiError::not_found("app_not_found", "App not found");
+        assert_eq!(err.problem.domain, "apps");
+        assert!(!err.problem.retryable);
+    }
+
+    #[test]
+    fn test_uncatalogued_code_uses_unknown_domain() {
+        let err = ApiError::bad_request("not_a_real_code", "nope");
+        assert_eq!(err.problem.domain, UNKNOWN_DOMAIN);
+        assert!(!err.problem.retryable);
+    }
+
+    #[test]
+    fn test_problem_serialization_with_details() {
+        let err = ApiError::bad_request("invalid_labels", "bad label")
+            .with_request_id("req_123")
+            .with_details(vec![FieldError::new("labels.bad key", "invalid key")]);
+        let json = serde_json::to_value(&*err.problem).unwrap();
+        assert_eq!(json["code"], "invalid_labels");
+        assert_eq!(json["domain"], "request");
+        assert_eq!(json["request_id"], "req_123");
+        assert_eq!(json["details"][0]["field"], "labels.bad key");
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let response = ApiError::service_unavailable("maintenance_mode", "read-only")
+            .with_retry_after_seconds(60)
+            .into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[RETRY_AFTER], "60");
+
+        let response = ApiError::bad_request("invalid_request", "nope").into_response();
+        assert!(response.headers().get(RETRY_AFTER).is_none());
+    }
+
+    /// Extract literal codes from `ApiError::<ctor>("code", ...)` calls.
+    fn literal_codes(source: &str) -> Vec<String> {
+        let mut codes = Vec::new();
+        let mut rest = source;
+        while let Some(pos) = rest.find("ApiError::") {
+            rest = &rest[pos + "ApiError::".len()..];
+            let Some(open) = rest.find('(') else { break };
+            if !rest[..open]
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c == '_')
+            {
+                continue;
+            }
+            let args = rest[open + 1..].trim_start();
+            if let Some(literal) = args.strip_prefix('"') {
+                if let Some(end) = literal.find('"') {
+                    codes.push(literal[..end].to_string());
+                }
+            }
+        }
+        codes
+    }
+
+    fn collect_codes(dir: &Path, codes: &mut Vec<(String, String)>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_codes(&path, codes);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for code in literal_codes(&source) {
+                    codes.push((path.display().to_string(), code));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_api_error_codes_are_catalogued() {
+        let mut codes = Vec::new();
+        collect_codes(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut codes,
+        );
+        assert!(!codes.is_empty());
+
+        let missing: Vec<_> = codes
+            .iter()
+            .filter(|(file, code)| !file.ends_with("error.rs") && errors::lookup(code).is_none())
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "codes missing from api/errors/catalog.json: {missing:?}"
+        );
+    }
+}
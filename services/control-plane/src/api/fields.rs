@@ -0,0 +1,237 @@
+//! Sparse list responses (`?fields=` and `?include=`).
+//!
+//! List endpoints with heavy items accept `fields`, a comma-separated list of
+//! item fields to return, and `include`, a comma-separated list of related
+//! resources to embed (e.g. `include=attachments` on volumes). The item key
+//! (usually `id`) is always returned.
+//!
+//! Without either parameter items are returned in full, with their default
+//! relations. Once `fields` or `include` is given, only the named relations
+//! are embedded. Handlers use [`Selection::wants`] to skip work for fields
+//! and relations that will not be returned, and [`Selection::respond`] to
+//! prune the serialized items.
+
+use std::collections::BTreeSet;
+
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiError;
+
+/// Response shaping query parameters, extracted next to an endpoint's own query.
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldsQuery {
+    /// Comma-separated item fields to return.
+    pub fields: Option<String>,
+    /// Comma-separated relations to embed.
+    pub include: Option<String>,
+}
+
+/// The fields and relations of a list endpoint's items.
+pub struct Shape {
+    /// Field that is always returned.
+    pub key: &'static str,
+    /// Fields `fields=` may name.
+    pub fields: &'static [&'static str],
+    /// Relations `include=` may name, each with whether it is embedded when
+    /// neither `fields` nor `include` is given.
+    pub relations: &'static [(&'static str, bool)],
+}
+
+impl Shape {
+    /// Validate the shaping parameters of a request.
+    pub fn select(
+        &'static self,
+        query: FieldsQuery,
+        request_id: &str,
+    ) -> Result<Selection, ApiError> {
+        let fields = parse_list(query.fields.as_deref());
+        let include = parse_list(query.include.as_deref());
+
+        if let Some(unknown) = fields
+            .iter()
+            .flatten()
+            .find(|field| *field != self.key && !self.fields.contains(&field.as_str()))
+        {
+            return Err(ApiError::bad_request(
+                "invalid_fields",
+                format!(
+                    "Unknown field '{unknown}'; fields must be among: {}, {}",
+                    self.key,
+                    self.fields.join(", ")
+                ),
+            )
+            .with_request_id(request_id.to_string()));
+        }
+
+        if let Some(unknown) = include
+            .iter()
+            .flatten()
+            .find(|relation| !self.is_relation(relation))
+        {
+            let names: Vec<&str> = self.relations.iter().map(|(name, _)| *name).collect();
+            let message = if names.is_empty() {
+                format!("Unknown relation '{unknown}'; this list has no relations")
+            } else {
+                format!(
+                    "Unknown relation '{unknown}'; include must be among: {}",
+                    names.join(", ")
+                )
+            };
+            return Err(ApiError::bad_request("invalid_include", message)
+                .with_request_id(request_id.to_string()));
+        }
+
+        Ok(Selection {
+            shape: self,
+            fields,
+            include,
+        })
+    }
+
+    fn is_relation(&self, name: &str) -> bool {
+        self.relations.iter().any(|(relation, _)| *relation == name)
+    }
+}
+
+/// Parse a comma-separated list; blank lists count as absent.
+fn parse_list(raw: Option<&str>) -> Option<BTreeSet<String>> {
+    let items: BTreeSet<String> = raw?
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!items.is_empty()).then_some(items)
+}
+
+/// A validated `fields`/`include` selection.
+pub struct Selection {
+    shape: &'static Shape,
+    fields: Option<BTreeSet<String>>,
+    include: Option<BTreeSet<String>>,
+}
+
+impl Selection {
+    /// Whether items will carry `name`, a field or a relation.
+    pub fn wants(&self, name: &str) -> bool {
+        if name == self.shape.key {
+            return true;
+        }
+        if let Some(&(_, default)) = self.shape.relations.iter().find(|(r, _)| *r == name) {
+            return match (&self.include, &self.fields) {
+                (Some(include), _) => include.contains(name),
+                (None, Some(_)) => false,
+                (None, None) => default,
+            };
+        }
+        self.fields
+            .as_ref()
+            .is_none_or(|fields| fields.contains(name))
+    }
+
+    fn is_sparse(&self) -> bool {
+        self.fields.is_some() || self.include.is_some()
+    }
+
+    /// Serialize a list response, pruning each of its `items` to the selection.
+    pub fn respond<T: Serialize>(&self, body: T) -> Response {
+        if !self.is_sparse() {
+            return Json(body).into_response();
+        }
+
+        let mut value = serde_json::to_value(body).unwrap_or_default();
+        if let Some(items) = value
+            .get_mut("items")
+            .and_then(|items| items.as_array_mut())
+        {
+            for item in items {
+                if let Some(item) = item.as_object_mut() {
+                    item.retain(|name, _| self.wants(name));
+                }
+            }
+        }
+        Json(value).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SHAPE: Shape = Shape {
+        key: "id",
+        fields: &["name", "size_bytes"],
+        relations: &[("attachments", true)],
+    };
+
+    fn select(fields: Option<&str>, include: Option<&str>) -> Result<Selection, ApiError> {
+        SHAPE.select(
+            FieldsQuery {
+                fields: fields.map(str::to_string),
+                include: include.map(str::to_string),
+            },
+            "req",
+        )
+    }
+
+    async fn body(selection: &Selection) -> serde_json::Value {
+        let response = selection.respond(serde_json::json!({
+            "items": [{"id": "vol_1", "name": "data", "size_bytes": 1, "attachments": []}],
+            "next_cursor": null,
+        }));
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_wants() {
+        let full = select(None, None).unwrap();
+        assert!(full.wants("name"));
+        assert!(full.wants("attachments"));
+
+        let sparse = select(Some("name, id"), None).unwrap();
+        assert!(sparse.wants("id"));
+        assert!(sparse.wants("name"));
+        assert!(!sparse.wants("size_bytes"));
+        assert!(!sparse.wants("attachments"));
+
+        let with_relation = select(Some("name"), Some("attachments")).unwrap();
+        assert!(with_relation.wants("attachments"));
+
+        let blank = select(Some(""), Some(" ")).unwrap();
+        assert!(blank.wants("attachments"));
+        assert!(blank.wants("size_bytes"));
+    }
+
+    #[test]
+    fn test_unknown_names() {
+        let err = select(Some("name,secret"), None).err().unwrap();
+        assert_eq!(err.problem.code, "invalid_fields");
+
+        let err = select(None, Some("snapshots")).err().unwrap();
+        assert_eq!(err.problem.code, "invalid_include");
+    }
+
+    #[tokio::test]
+    async fn test_respond() {
+        let full = body(&select(None, None).unwrap()).await;
+        assert_eq!(full["items"][0]["size_bytes"], 1);
+
+        let sparse = body(&select(Some("name"), None).unwrap()).await;
+        assert_eq!(
+            sparse["items"][0],
+            serde_json::json!({"id": "vol_1", "name": "data"})
+        );
+        assert!(sparse["next_cursor"].is_null());
+
+        let include = body(&select(None, Some("attachments")).unwrap()).await;
+        assert_eq!(include["items"][0]["attachments"], serde_json::json!([]));
+        assert_eq!(include["items"][0]["name"], "data");
+    }
+}
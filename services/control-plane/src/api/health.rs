@@ -2,11 +2,14 @@
 //!
 //! These endpoints are used by load balancers and orchestration systems
 //! to determine if the service is healthy and ready to receive traffic.
+//! `/readyz` reports each readiness check (see [`crate::readiness`]).
 
 use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
 use chrono::Utc;
 use serde::Serialize;
 
+use crate::api::maintenance::{self, Maintenance};
+use crate::readiness::{self, Check, CheckResult, ReadinessConfig};
 use crate::state::AppState;
 
 /// Health check response.
@@ -25,32 +28,13 @@ pub struct HealthResponse {
     /// Current timestamp (ISO 8601).
     pub timestamp: String,
 
-    /// Detailed component health (optional).
+    /// Per-check readiness results (readyz only).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub components: Option<ComponentHealth>,
-}
-
-/// Component health details.
-#[derive(Debug, Serialize)]
-#[cfg_attr(test, derive(serde::Deserialize))]
-pub struct ComponentHealth {
-    /// Database connection status.
-    pub database: ComponentStatus,
-
-    /// Event log status.
-    pub event_log: ComponentStatus,
-}
+    pub checks: Option<Vec<CheckResult>>,
 
-/// Individual component status.
-#[derive(Debug, Serialize)]
-#[cfg_attr(test, derive(serde::Deserialize))]
-pub struct ComponentStatus {
-    /// Status: "ok", "degraded", or "unavailable".
-    pub status: String,
-
-    /// Optional message with details.
+    /// Active maintenance window; writes are rejected while it is set.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub message: Option<String>,
+    pub maintenance: Option<Maintenance>,
 }
 
 /// Create health check routes.
@@ -64,49 +48,36 @@ pub fn routes() -> Router<AppState> {
 /// Basic health check - is the service running?
 ///
 /// This is a simple liveness probe that returns 200 if the server is up.
-/// It does not check dependencies.
+/// It does not check dependencies, so it reports the last maintenance mode
+/// this replica read rather than querying it.
 async fn healthz() -> impl IntoResponse {
     Json(HealthResponse {
         status: "ok".to_string(),
         service: "control-plane".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         timestamp: Utc::now().to_rfc3339(),
-        components: None,
+        checks: None,
+        maintenance: maintenance::cached(),
     })
 }
 
 /// Readiness check - is the service ready to receive traffic?
 ///
-/// This checks that all critical dependencies are available.
-/// Returns 503 if the service is not ready.
+/// This runs every readiness check: database reachable, migrations
+/// applied, event store writable, and projection lag under threshold.
+/// Returns 503 if any fails. Maintenance mode does not make the service
+/// unready: reads and node agent traffic are still served.
 async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
-    // Check database connectivity
-    let db_result = state.db().health_check().await;
-    let db_ok = db_result.is_ok();
-    let db_message = db_result.err().map(|e| e.to_string());
-
-    // Event log uses the same database, so if DB is ok, event log is ok
-    let event_log_ok = db_ok;
-
-    let components = ComponentHealth {
-        database: ComponentStatus {
-            status: if db_ok { "ok" } else { "unavailable" }.to_string(),
-            message: db_message.clone(),
-        },
-        event_log: ComponentStatus {
-            status: if event_log_ok { "ok" } else { "unavailable" }.to_string(),
-            message: if event_log_ok { None } else { db_message },
-        },
-    };
-
-    let all_ok = db_ok && event_log_ok;
+    let checks = readiness::run_checks(state.db(), &ReadinessConfig::from_env(), Check::ALL).await;
+    let all_ok = checks.iter().all(CheckResult::is_ok);
 
     let response = HealthResponse {
         status: if all_ok { "ok" } else { "degraded" }.to_string(),
         service: "control-plane".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         timestamp: Utc::now().to_rfc3339(),
-        components: Some(components),
+        checks: Some(checks),
+        maintenance: maintenance::current(state.db().pool()).await,
     };
 
     if all_ok {
@@ -1,56 +1,128 @@
 //! Idempotency helpers for retry-safe write endpoints.
+//!
+//! Handlers call [`check`] before doing work and [`store`] afterwards. The
+//! [`serialize_in_flight`] middleware additionally makes concurrent requests
+//! with the same `Idempotency-Key` wait for each other, so a racing retry
+//! replays the stored response instead of appending events twice.
 
-use axum::http::StatusCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 
 use crate::api::error::ApiError;
+use crate::api::request_context::{AUTHORIZATION_HEADER, IDEMPOTENCY_KEY_HEADER};
 use crate::db::{IdempotencyCheck, StoreIdempotencyRecord};
 use crate::state::AppState;
 
 pub const IDEMPOTENCY_SCOPE_GLOBAL: &str = "_global";
 
-fn canonicalize_json(value: &mut serde_json::Value) {
-    match value {
-        serde_json::Value::Object(map) => {
-            let mut keys: Vec<String> = map.keys().cloned().collect();
-            keys.sort();
-
-            let mut ordered = serde_json::Map::new();
-            for key in keys {
-                if let Some(mut value) = map.remove(&key) {
-                    canonicalize_json(&mut value);
-                    ordered.insert(key, value);
-                }
-            }
+/// Minimum record TTL required by the API spec.
+const MIN_TTL: Duration = Duration::from_secs(24 * 3600);
+const DEFAULT_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+/// How long a lock survives a holder that never releases it.
+const LOCK_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_LOCK_WAIT: Duration = Duration::from_secs(10);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long idempotency records stay replayable (`PLFM_IDEMPOTENCY_TTL_HOURS`,
+/// default 7 days, minimum 24 hours).
+pub fn idempotency_ttl() -> Duration {
+    std::env::var("PLFM_IDEMPOTENCY_TTL_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|hours| Duration::from_secs(hours * 3600))
+        .unwrap_or(DEFAULT_TTL)
+        .max(MIN_TTL)
+}
+
+/// How long a request waits for a concurrent request with the same key
+/// (`PLFM_IDEMPOTENCY_LOCK_WAIT_SECS`, default 10s).
+fn lock_wait_timeout() -> Duration {
+    std::env::var("PLFM_IDEMPOTENCY_LOCK_WAIT_SECS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_LOCK_WAIT)
+}
+
+/// Process-wide idempotency counters.
+#[derive(Debug, Default)]
+pub struct IdempotencyMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    conflicts: AtomicU64,
+    lock_waits: AtomicU64,
+    lock_timeouts: AtomicU64,
+}
+
+/// Point-in-time copy of [`IdempotencyMetrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct IdempotencyMetricsSnapshot {
+    pub hits: u64,
+    pub misses: u64,
+    pub conflicts: u64,
+    pub lock_waits: u64,
+    pub lock_timeouts: u64,
+    /// hits / (hits + misses + conflicts), or 0 when there were no lookups.
+    pub hit_rate: f64,
+    /// conflicts / (hits + misses + conflicts), or 0 when there were no lookups.
+    pub conflict_rate: f64,
+}
 
-            *map = ordered;
+impl IdempotencyMetrics {
+    const fn new() -> Self {
+        Self {
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            conflicts: AtomicU64::new(0),
+            lock_waits: AtomicU64::new(0),
+            lock_timeouts: AtomicU64::new(0),
         }
-        serde_json::Value::Array(items) => {
-            for item in items {
-                canonicalize_json(item);
+    }
+
+    pub fn snapshot(&self) -> IdempotencyMetricsSnapshot {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let conflicts = self.conflicts.load(Ordering::Relaxed);
+        let lookups = hits + misses + conflicts;
+        let rate = |n: u64| {
+            if lookups == 0 {
+                0.0
+            } else {
+                n as f64 / lookups as f64
             }
+        };
+        IdempotencyMetricsSnapshot {
+            hits,
+            misses,
+            conflicts,
+            lock_waits: self.lock_waits.load(Ordering::Relaxed),
+            lock_timeouts: self.lock_timeouts.load(Ordering::Relaxed),
+            hit_rate: rate(hits),
+            conflict_rate: rate(conflicts),
         }
-        _ => {}
     }
 }
 
+/// Counters for this process (exposed via `GET /v1/_debug/idempotency/stats`).
+pub static METRICS: IdempotencyMetrics = IdempotencyMetrics::new();
+
 pub fn request_hash(endpoint_name: &str, request: &impl Serialize) -> Result<String, ApiError> {
-    let mut value = serde_json::to_value(request).map_err(|e| {
+    let canonical = plfm_canonical_json::serialize(request).map_err(|e| {
         ApiError::internal(
             "internal_error",
             format!("Failed to serialize request body: {}", e),
         )
     })?;
 
-    canonicalize_json(&mut value);
-    let canonical = serde_json::to_string(&value).map_err(|e| {
-        ApiError::internal(
-            "internal_error",
-            format!("Failed to serialize canonical request body: {}", e),
-        )
-    })?;
-
     let mut hasher = Sha256::new();
     hasher.update(endpoint_name.as_bytes());
     hasher.update(b"\n");
@@ -88,17 +160,29 @@ pub async fn check(
         })?;
 
     match check {
-        IdempotencyCheck::NotFound => Ok(None),
+        IdempotencyCheck::NotFound => {
+            METRICS.misses.fetch_add(1, Ordering::Relaxed);
+            Ok(None)
+        }
         IdempotencyCheck::Found(record) => {
+            METRICS.hits.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!(
+                request_id = %request_id,
+                endpoint_name = %endpoint_name,
+                "Replaying idempotent response"
+            );
             let status =
                 StatusCode::from_u16(record.response_status_code as u16).unwrap_or(StatusCode::OK);
             Ok(Some((status, record.response_body)))
         }
-        IdempotencyCheck::Conflict => Err(ApiError::conflict(
-            "idempotency_key_conflict",
-            "Idempotency-Key was already used with a different request",
-        )
-        .with_request_id(request_id.to_string())),
+        IdempotencyCheck::Conflict => {
+            METRICS.conflicts.fetch_add(1, Ordering::Relaxed);
+            Err(ApiError::conflict(
+                "idempotency_conflict",
+                "Idempotency-Key was already used with a different request",
+            )
+            .with_request_id(request_id.to_string()))
+        }
     }
 }
 
@@ -117,6 +201,7 @@ pub async fn store(
             request_hash: params.request_hash.to_string(),
             response_status_code: params.status.as_u16() as i32,
             response_body: params.body,
+            ttl: idempotency_ttl(),
         })
         .await
         .map_err(|e| {
@@ -139,3 +224,155 @@ pub struct StoreIdempotencyParams<'a> {
     pub status: StatusCode,
     pub body: Option<serde_json::Value>,
 }
+
+/// Lock key for a request: same credentials, method, path, and key.
+fn lock_key(authorization: &str, method: &Method, path: &str, idempotency_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [authorization, method.as_str(), path, idempotency_key] {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Middleware that serializes concurrent writes sharing an `Idempotency-Key`.
+///
+/// The first request takes a lock for the duration of the handler; a
+/// concurrent duplicate waits for it to finish and then runs normally, which
+/// replays the stored response. Waiting longer than the lock wait timeout
+/// returns 409 `idempotency_in_flight` (retryable).
+pub async fn serialize_in_flight(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_write = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let idempotency_key = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok());
+    let Some(idempotency_key) = idempotency_key.filter(|_| is_write) else {
+        return next.run(request).await;
+    };
+
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let key = lock_key(
+        authorization,
+        request.method(),
+        request.uri().path(),
+        idempotency_key,
+    );
+    let holder = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| plfm_id::RequestId::new().to_string());
+
+    let store = state.db().idempotency_store();
+    let deadline = tokio::time::Instant::now() + lock_wait_timeout();
+    let mut waited = false;
+    loop {
+        match store.try_lock(&key, &holder, LOCK_TTL).await {
+            Ok(true) => break,
+            Ok(false) => {}
+            Err(e) => {
+                // Locking is best-effort: fall back to the record check alone.
+                tracing::warn!(error = %e, request_id = %holder, "Failed to take idempotency lock");
+                return next.run(request).await;
+            }
+        }
+
+        if !waited {
+            waited = true;
+            METRICS.lock_waits.fetch_add(1, Ordering::Relaxed);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            METRICS.lock_timeouts.fetch_add(1, Ordering::Relaxed);
+            return ApiError::conflict(
+                "idempotency_in_flight",
+                "A request with this Idempotency-Key is still being processed",
+            )
+            .with_retry_after_seconds(1)
+            .with_request_id(holder)
+            .into_response();
+        }
+        tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+    }
+
+    let response = next.run(request).await;
+
+    if let Err(e) = store.unlock(&key, &holder).await {
+        tracing::warn!(error = %e, request_id = %holder, "Failed to release idempotency lock");
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_hash_ignores_key_order() {
+        let a = request_hash(
+            "create_app",
+            &serde_json::json!({"a": 1, "b": {"c": 2, "d": 3}}),
+        )
+        .unwrap();
+        let b = request_hash(
+            "create_app",
+            &serde_json::json!({"b": {"d": 3, "c": 2}, "a": 1}),
+        )
+        .unwrap();
+        assert_eq!(a, b);
+        let c = request_hash(
+            "create_env",
+            &serde_json::json!({"a": 1, "b": {"c": 2, "d": 3}}),
+        )
+        .unwrap();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_lock_key_scopes() {
+        let base = lock_key("Bearer a", &Method::POST, "/v1/orgs/o/apps", "key-1234");
+        assert_eq!(
+            base,
+            lock_key("Bearer a", &Method::POST, "/v1/orgs/o/apps", "key-1234")
+        );
+        assert_ne!(
+            base,
+            lock_key("Bearer b", &Method::POST, "/v1/orgs/o/apps", "key-1234")
+        );
+        assert_ne!(
+            base,
+            lock_key("Bearer a", &Method::POST, "/v1/orgs/o/apps", "key-5678")
+        );
+    }
+
+    #[test]
+    fn test_ttl_has_floor() {
+        assert!(idempotency_ttl() >= MIN_TTL);
+    }
+
+    #[test]
+    fn test_metrics_snapshot_rates() {
+        let metrics = IdempotencyMetrics::new();
+        assert_eq!(metrics.snapshot().hit_rate, 0.0);
+
+        metrics.hits.fetch_add(3, Ordering::Relaxed);
+        metrics.misses.fetch_add(1, Ordering::Relaxed);
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.hits, 3);
+        assert_eq!(snapshot.hit_rate, 0.75);
+        assert_eq!(snapshot.conflict_rate, 0.0);
+    }
+}
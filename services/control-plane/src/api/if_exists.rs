@@ -0,0 +1,88 @@
+//! Create-or-return (`?if_exists=`).
+//!
+//! Create endpoints of named resources (apps and envs by name, routes by
+//! hostname, volumes by name) accept `if_exists`:
+//! - `fail` (default): a live resource with the same name is a conflict.
+//!   Volume names are not unique, so volumes are created regardless.
+//! - `return`: the existing resource is returned (200, with its ETag) when
+//!   its settings match the request; it is still a conflict when they differ
+//!   (or, for volumes, when several share the name).
+//!
+//! With `return`, repeating a create whose outcome is unknown (a timeout, a
+//! crashed client) converges on one resource without an idempotency key,
+//! which is how declarative clients such as Terraform providers create.
+//!
+//! See: docs/specs/api/http-api.md
+
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+
+/// The `if_exists` query parameter, extracted next to an endpoint's body.
+#[derive(Debug, Default, Deserialize)]
+pub struct IfExistsQuery {
+    pub if_exists: Option<String>,
+}
+
+/// What a create does when the name is taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IfExists {
+    #[default]
+    Fail,
+    Return,
+}
+
+impl IfExistsQuery {
+    pub fn mode(&self, request_id: &str) -> Result<IfExists, ApiError> {
+        match self.if_exists.as_deref() {
+            None | Some("fail") => Ok(IfExists::Fail),
+            Some("return") => Ok(IfExists::Return),
+            Some(other) => Err(ApiError::bad_request(
+                "invalid_if_exists",
+                format!("Invalid if_exists '{other}'; expected 'fail' or 'return'"),
+            )
+            .with_request_id(request_id.to_string())),
+        }
+    }
+}
+
+impl IfExists {
+    /// Decide whether an existing resource with the requested name may be
+    /// returned in place of creating one. `matches` is whether its settings
+    /// equal the request's; `conflict` is the endpoint's name-taken error.
+    pub fn reuse(self, matches: bool, conflict: impl FnOnce() -> ApiError) -> Result<(), ApiError> {
+        if self == IfExists::Return && matches {
+            Ok(())
+        } else {
+            Err(conflict())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(value: Option<&str>) -> IfExistsQuery {
+        IfExistsQuery {
+            if_exists: value.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_mode() {
+        assert_eq!(query(None).mode("req").unwrap(), IfExists::Fail);
+        assert_eq!(query(Some("fail")).mode("req").unwrap(), IfExists::Fail);
+        assert_eq!(query(Some("return")).mode("req").unwrap(), IfExists::Return);
+        assert!(query(Some("update")).mode("req").is_err());
+    }
+
+    #[test]
+    fn test_reuse_requires_return_and_matching_settings() {
+        let conflict = || ApiError::conflict("app_name_exists", "taken");
+
+        assert!(IfExists::Return.reuse(true, conflict).is_ok());
+        assert!(IfExists::Return.reuse(false, conflict).is_err());
+        assert!(IfExists::Fail.reuse(true, conflict).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Resource labels and label selectors.
+//!
+//! Apps, envs, routes, and volumes carry arbitrary key/value labels that are
+//! set via `PATCH .../labels` and filtered on list endpoints with
+//! `?label_selector=`. Selectors are a comma-separated conjunction of terms:
+//! - `key=value` (or `key==value`): label present with that value
+//! - `key!=value`: label absent or set to a different value
+//! - `key`: label present
+//! - `!key`: label absent
+//!
+//! Parsed selectors are passed to Postgres as a single JSON document and
+//! evaluated by the `labels_match(labels, selector)` SQL function.
+
+use std::collections::BTreeMap;
+
+pub use plfm_api_types::Labels;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::{ApiError, FieldError};
+
+/// Maximum number of labels on a single resource.
+pub const MAX_LABELS: usize = 64;
+
+const MAX_KEY_LEN: usize = 128;
+const MAX_VALUE_LEN: usize = 63;
+const MAX_SELECTOR_TERMS: usize = 20;
+
+/// Request body for `PATCH .../labels`.
+///
+/// Merge semantics: keys with a string value are set, keys with `null` are
+/// removed, and keys not mentioned are left unchanged.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PatchLabelsRequest {
+    pub labels: BTreeMap<String, Option<String>>,
+}
+
+/// Response for `PATCH .../labels`.
+#[derive(Debug, Serialize)]
+pub struct LabelsResponse {
+    pub labels: Labels,
+    pub resource_version: i32,
+}
+
+/// Decode a `labels` JSONB column; anything but a string map decodes as empty.
+pub fn from_json(value: serde_json::Value) -> Labels {
+    serde_json::from_value(value).unwrap_or_default()
+}
+
+fn is_alnum(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+}
+
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(format!(
+            "label key '{key}' must be 1-{MAX_KEY_LEN} characters"
+        ));
+    }
+    let valid_chars = key
+        .chars()
+        .all(|c| is_alnum(c) || matches!(c, '-' | '_' | '.' | '/'));
+    let starts_ends_alnum =
+        key.chars().next().is_some_and(is_alnum) && key.chars().last().is_some_and(is_alnum);
+    if !valid_chars || !starts_ends_alnum {
+        return Err(format!(
+            "label key '{key}' must use [A-Za-z0-9._/-] and start and end with an alphanumeric character"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_value(key: &str, value: &str) -> Result<(), String> {
+    if value.len() > MAX_VALUE_LEN {
+        return Err(format!(
+            "label '{key}' value must be at most {MAX_VALUE_LEN} characters"
+        ));
+    }
+    if value.is_empty() {
+        return Ok(());
+    }
+    let valid_chars = value
+        .chars()
+        .all(|c| is_alnum(c) || matches!(c, '-' | '_' | '.'));
+    let starts_ends_alnum =
+        value.chars().next().is_some_and(is_alnum) && value.chars().last().is_some_and(is_alnum);
+    if !valid_chars || !starts_ends_alnum {
+        return Err(format!(
+            "label '{key}' value must use [A-Za-z0-9._-] and start and end with an alphanumeric character"
+        ));
+    }
+    Ok(())
+}
+
+/// Apply a label patch to the current labels, validating the result.
+pub fn apply_patch(
+    current: &Labels,
+    patch: &BTreeMap<String, Option<String>>,
+    request_id: &str,
+) -> Result<Labels, ApiError> {
+    let invalid = |message: String| {
+        ApiError::bad_request("invalid_labels", message).with_request_id(request_id.to_string())
+    };
+    let invalid_field = |key: &str, message: String| {
+        invalid(message.clone())
+            .with_details(vec![FieldError::new(format!("labels.{key}"), message)])
+    };
+
+    if patch.is_empty() {
+        return Err(invalid("labels patch cannot be empty".to_string()));
+    }
+
+    let mut labels = current.clone();
+    for (key, value) in patch {
+        validate_key(key).map_err(|message| invalid_field(key, message))?;
+        match value {
+            Some(value) => {
+                validate_value(key, value).map_err(|message| invalid_field(key, message))?;
+                labels.insert(key.clone(), value.clone());
+            }
+            None => {
+                labels.remove(key);
+            }
+        }
+    }
+
+    if labels.len() > MAX_LABELS {
+        return Err(invalid(format!(
+            "a resource can have at most {MAX_LABELS} labels"
+        )));
+    }
+
+    Ok(labels)
+}
+
+/// A parsed label selector.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LabelSelector {
+    /// Labels that must be present with the given value.
+    pub eq: Labels,
+    /// Labels that must not have the given value.
+    pub neq: Labels,
+    /// Keys that must be present.
+    pub exists: Vec<String>,
+    /// Keys that must be absent.
+    pub not_exists: Vec<String>,
+}
+
+impl LabelSelector {
+    /// Parse a selector string (see module docs for the syntax).
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut selector = LabelSelector::default();
+        let terms: Vec<&str> = raw.split(',').map(str::trim).collect();
+        if terms.iter().all(|t| t.is_empty()) {
+            return Err("label selector cannot be empty".to_string());
+        }
+        if terms.len() > MAX_SELECTOR_TERMS {
+            return Err(format!(
+                "label selector can have at most {MAX_SELECTOR_TERMS} terms"
+            ));
+        }
+
+        for term in terms {
+            if term.is_empty() {
+                return Err("label selector contains an empty term".to_string());
+            }
+            if let Some((key, value)) = term.split_once("!=") {
+                let (key, value) = (key.trim(), value.trim());
+                validate_key(key)?;
+                validate_value(key, value)?;
+                selector.neq.insert(key.to_string(), value.to_string());
+            } else if let Some((key, value)) = term.split_once('=') {
+                let value = value.strip_prefix('=').unwrap_or(value);
+                let (key, value) = (key.trim(), value.trim());
+                validate_key(key)?;
+                validate_value(key, value)?;
+                if let Some(existing) = selector.eq.get(key) {
+                    if existing != value {
+                        return Err(format!(
+                            "label selector requires '{key}' to equal both '{existing}' and '{value}'"
+                        ));
+                    }
+                }
+                selector.eq.insert(key.to_string(), value.to_string());
+            } else if let Some(key) = term.strip_prefix('!') {
+                let key = key.trim();
+                validate_key(key)?;
+                selector.not_exists.push(key.to_string());
+            } else {
+                validate_key(term)?;
+                selector.exists.push(term.to_string());
+            }
+        }
+
+        Ok(selector)
+    }
+
+    /// Parse an optional `label_selector` query parameter into the JSON
+    /// document bound to `labels_match`.
+    pub fn from_query(
+        raw: Option<&str>,
+        request_id: &str,
+    ) -> Result<Option<serde_json::Value>, ApiError> {
+        let Some(raw) = raw else {
+            return Ok(None);
+        };
+        let selector = LabelSelector::parse(raw).map_err(|message| {
+            ApiError::bad_request("invalid_label_selector", message)
+                .with_request_id(request_id.to_string())
+        })?;
+        Ok(Some(selector.to_json()))
+    }
+
+    /// JSON document understood by the `labels_match` SQL function.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "eq": self.eq,
+            "neq": self
+                .neq
+                .iter()
+                .map(|(k, v)| serde_json::json!({ k: v }))
+                .collect::<Vec<_>>(),
+            "exists": self.exists,
+            "not_exists": self.not_exists,
+        })
+    }
+
+    /// Evaluate the selector in memory (mirrors `labels_match`).
+    pub fn matches(&self, labels: &Labels) -> bool {
+        self.eq.iter().all(|(k, v)| labels.get(k) == Some(v))
+            && self.neq.iter().all(|(k, v)| labels.get(k) != Some(v))
+            && self.exists.iter().all(|k| labels.contains_key(k))
+            && self.not_exists.iter().all(|k| !labels.contains_key(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_selector_terms() {
+        let selector =
+            LabelSelector::parse("team=payments, tier!=frontend,owner,!legacy,env==prod").unwrap();
+        assert_eq!(
+            selector.eq,
+            labels(&[("team", "payments"), ("env", "prod")])
+        );
+        assert_eq!(selector.neq, labels(&[("tier", "frontend")]));
+        assert_eq!(selector.exists, vec!["owner".to_string()]);
+        assert_eq!(selector.not_exists, vec!["legacy".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_selector_rejects_invalid() {
+        assert!(LabelSelector::parse("").is_err());
+        assert!(LabelSelector::parse("team=payments,").is_err());
+        assert!(LabelSelector::parse("=payments").is_err());
+        assert!(LabelSelector::parse("team=pay ments").is_err());
+        assert!(LabelSelector::parse("team=a,team=b").is_err());
+        assert!(LabelSelector::parse("-team").is_err());
+    }
+
+    #[test]
+    fn test_selector_matches() {
+        let selector = LabelSelector::parse("team=payments,tier!=frontend,!legacy").unwrap();
+        assert!(selector.matches(&labels(&[("team", "payments")])));
+        assert!(selector.matches(&labels(&[("team", "payments"), ("tier", "backend")])));
+        assert!(!selector.matches(&labels(&[("team", "payments"), ("tier", "frontend")])));
+        assert!(!selector.matches(&labels(&[("team", "payments"), ("legacy", "")])));
+        assert!(!selector.matches(&labels(&[("team", "search")])));
+    }
+
+    #[test]
+    fn test_selector_json_shape() {
+        let selector = LabelSelector::parse("team=payments,tier!=frontend,owner").unwrap();
+        assert_eq!(
+            selector.to_json(),
+            serde_json::json!({
+                "eq": {"team": "payments"},
+                "neq": [{"tier": "frontend"}],
+                "exists": ["owner"],
+                "not_exists": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_patch_merges_and_removes() {
+        let current = labels(&[("team", "payments"), ("legacy", "true")]);
+        let mut patch = BTreeMap::new();
+        patch.insert("tier".to_string(), Some("backend".to_string()));
+        patch.insert("legacy".to_string(), None);
+
+        let updated = apply_patch(&current, &patch, "req_test").unwrap();
+        assert_eq!(
+            updated,
+            labels(&[("team", "payments"), ("tier", "backend")])
+        );
+    }
+
+    #[test]
+    fn test_apply_patch_validates() {
+        let current = Labels::new();
+        assert!(apply_patch(&current, &BTreeMap::new(), "req_test").is_err());
+
+        let mut patch = BTreeMap::new();
+        patch.insert("bad key".to_string(), Some("x".to_string()));
+        let err = apply_patch(&current, &patch, "req_test").unwrap_err();
+        let details = err.problem.details.expect("field violations");
+        assert_eq!(details[0].field, "labels.bad key");
+
+        let patch: BTreeMap<String, Option<String>> = (0..=MAX_LABELS)
+            .map(|i| (format!("k{i}"), Some("v".to_string())))
+            .collect();
+        assert!(apply_patch(&current, &patch, "req_test").is_err());
+    }
+
+    #[test]
+    fn test_from_json_tolerates_non_maps() {
+        assert_eq!(
+            from_json(serde_json::json!({"team": "payments"})),
+            labels(&[("team", "payments")])
+        );
+        assert!(from_json(serde_json::json!(null)).is_empty());
+    }
+}
@@ -0,0 +1,339 @@
+//! Request body limits.
+//!
+//! Every request body is capped by the size limit of its route class and,
+//! for JSON bodies, by a nesting depth limit, before any handler runs.
+//! Oversized bodies are rejected with `413 payload_too_large` (from
+//! `Content-Length` when present, without reading the body), and overly
+//! nested JSON with `400 json_too_deep`.
+//!
+//! Node log batches may be compressed (`Content-Encoding: gzip` or `zstd`).
+//! The class limit applies to the compressed body; the decoded body has its
+//! own limit so a small body cannot expand without bound. Other routes reject
+//! compressed bodies with `415 unsupported_content_encoding`.
+//!
+//! Handlers then deserialize and validate with
+//! [`ValidJson`](crate::api::validation::ValidJson).
+//!
+//! See: docs/specs/api/http-api.md
+
+use std::io::{self, Read};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::error::ApiError;
+
+/// Maximum JSON nesting depth (objects and arrays) of a request body.
+pub const MAX_JSON_DEPTH: usize = 32;
+
+/// Routes grouped by how much body they legitimately need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyClass {
+    /// Ordinary resource writes.
+    Default,
+    /// Releases, deploys, and batch requests carrying workload manifests.
+    Manifest,
+    /// Secret bundles (1 MiB canonical, plus JSON overhead).
+    Secrets,
+    /// Node agent log batches.
+    LogIngest,
+}
+
+impl BodyClass {
+    pub fn for_path(path: &str) -> Self {
+        let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        match segments.as_slice() {
+            ["", "v1", "nodes", _, "logs"] => BodyClass::LogIngest,
+            [.., "secrets"] => BodyClass::Secrets,
+            [.., "releases" | "deploys" | "batch"] => BodyClass::Manifest,
+            _ => BodyClass::Default,
+        }
+    }
+
+    /// Maximum body size in bytes.
+    pub fn max_bytes(self) -> usize {
+        match self {
+            BodyClass::Default => 256 * 1024,
+            BodyClass::Manifest => 1024 * 1024,
+            BodyClass::Secrets => 2 * 1024 * 1024,
+            BodyClass::LogIngest => 4 * 1024 * 1024,
+        }
+    }
+
+    /// Maximum decoded size of a compressed body, or `None` if the class
+    /// does not accept compressed bodies.
+    pub fn max_decoded_bytes(self) -> Option<usize> {
+        match self {
+            BodyClass::LogIngest => Some(16 * 1024 * 1024),
+            _ => None,
+        }
+    }
+}
+
+/// Compression of a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Zstd,
+}
+
+/// Why a compressed body was not decoded.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The decoded body exceeds the limit.
+    TooLarge,
+    /// The body is not valid for its encoding.
+    Invalid(io::Error),
+}
+
+impl ContentEncoding {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Decompress `bytes`, reading at most `limit` decoded bytes.
+    pub fn decode(self, bytes: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(bytes)),
+            Self::Zstd => {
+                Box::new(zstd::stream::read::Decoder::new(bytes).map_err(DecodeError::Invalid)?)
+            }
+        };
+        let mut decoded = Vec::new();
+        decoder
+            .take(limit as u64 + 1)
+            .read_to_end(&mut decoded)
+            .map_err(DecodeError::Invalid)?;
+        if decoded.len() > limit {
+            return Err(DecodeError::TooLarge);
+        }
+        Ok(decoded)
+    }
+}
+
+/// Nesting depth of a JSON document, stopping once `limit` is exceeded.
+///
+/// Counts brackets outside string literals; does not validate the JSON.
+pub fn json_depth_exceeds(bytes: &[u8], limit: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &b in bytes {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > limit {
+                    return true;
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
+}
+
+fn too_large(class: BodyClass, request_id: &str) -> Response {
+    ApiError::payload_too_large(
+        "payload_too_large",
+        format!(
+            "Request body exceeds the {} byte limit for this endpoint",
+            class.max_bytes()
+        ),
+    )
+    .with_request_id(request_id.to_string())
+    .into_response()
+}
+
+/// Enforce the body size and JSON depth limits.
+pub async fn limit_request_body(request: Request, next: Next) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return next.run(request).await;
+    }
+
+    let class = BodyClass::for_path(request.uri().path());
+    let limit = class.max_bytes();
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared_length.is_some_and(|len| len > limit) {
+        return too_large(class, &request_id);
+    }
+
+    let encoding = match request
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|v| v.to_str().unwrap_or_default().trim())
+    {
+        None | Some("" | "identity") => None,
+        Some(value) => match ContentEncoding::parse(value).zip(class.max_decoded_bytes()) {
+            Some(accepted) => Some(accepted),
+            None => {
+                return ApiError::unsupported_media_type(
+                    "unsupported_content_encoding",
+                    format!("Content-Encoding '{value}' is not accepted by this endpoint"),
+                )
+                .with_request_id(request_id)
+                .into_response();
+            }
+        },
+    };
+
+    let is_json = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json") || ct.contains("+json"));
+
+    let (mut parts, body) = request.into_parts();
+    let Ok(mut bytes) = axum::body::to_bytes(body, limit).await else {
+        return too_large(class, &request_id);
+    };
+
+    if let Some((encoding, max_decoded)) = encoding {
+        bytes = match encoding.decode(&bytes, max_decoded) {
+            Ok(decoded) => decoded.into(),
+            Err(DecodeError::TooLarge) => {
+                return ApiError::payload_too_large(
+                    "payload_too_large",
+                    format!("Decoded request body exceeds the {max_decoded} byte limit for this endpoint"),
+                )
+                .with_request_id(request_id)
+                .into_response();
+            }
+            Err(DecodeError::Invalid(e)) => {
+                return ApiError::bad_request(
+                    "invalid_content_encoding",
+                    format!("Failed to decompress request body: {e}"),
+                )
+                .with_request_id(request_id)
+                .into_response();
+            }
+        };
+        parts.headers.remove(header::CONTENT_ENCODING);
+        parts
+            .headers
+            .insert(header::CONTENT_LENGTH, HeaderValue::from(bytes.len()));
+    }
+
+    if is_json && json_depth_exceeds(&bytes, MAX_JSON_DEPTH) {
+        return ApiError::bad_request(
+            "json_too_deep",
+            format!("JSON request body is nested more than {MAX_JSON_DEPTH} levels deep"),
+        )
+        .with_request_id(request_id)
+        .into_response();
+    }
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_class_for_path() {
+        assert_eq!(
+            BodyClass::for_path("/v1/nodes/node_1/logs"),
+            BodyClass::LogIngest
+        );
+        assert_eq!(
+            BodyClass::for_path("/v1/orgs/org_1/apps/app_1/releases"),
+            BodyClass::Manifest
+        );
+        assert_eq!(
+            BodyClass::for_path("/v1/orgs/org_1/apps/app_1/envs/env_1/deploys"),
+            BodyClass::Manifest
+        );
+        assert_eq!(
+            BodyClass::for_path("/v1/orgs/org_1/batch"),
+            BodyClass::Manifest
+        );
+        assert_eq!(
+            BodyClass::for_path("/v1/orgs/org_1/apps/app_1/envs/env_1/secrets"),
+            BodyClass::Secrets
+        );
+        assert_eq!(BodyClass::for_path("/v1/orgs"), BodyClass::Default);
+        // Org-scoped log reads are not node log ingest.
+        assert_eq!(
+            BodyClass::for_path("/v1/orgs/org_1/apps/app_1/envs/env_1/logs"),
+            BodyClass::Default
+        );
+    }
+
+    #[test]
+    fn test_decode_compressed_body() {
+        use std::io::Write;
+
+        let body = br#"{"entries":[]}"#.repeat(100);
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gzip.write_all(&body).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let zstd = zstd::stream::encode_all(body.as_slice(), 3).unwrap();
+
+        assert_eq!(ContentEncoding::parse("GZIP"), Some(ContentEncoding::Gzip));
+        assert_eq!(ContentEncoding::parse("br"), None);
+        assert_eq!(ContentEncoding::Gzip.decode(&gzip, 4096).unwrap(), body);
+        assert_eq!(ContentEncoding::Zstd.decode(&zstd, 4096).unwrap(), body);
+
+        assert!(matches!(
+            ContentEncoding::Gzip.decode(&gzip, body.len() - 1),
+            Err(DecodeError::TooLarge)
+        ));
+        assert!(matches!(
+            ContentEncoding::Zstd.decode(&body, 4096),
+            Err(DecodeError::Invalid(_))
+        ));
+
+        assert_eq!(
+            BodyClass::LogIngest.max_decoded_bytes(),
+            Some(16 * 1024 * 1024)
+        );
+        assert_eq!(BodyClass::Default.max_decoded_bytes(), None);
+    }
+
+    #[test]
+    fn test_json_depth() {
+        assert!(!json_depth_exceeds(br#"{"a":{"b":[1,2,{"c":3}]}}"#, 4));
+        assert!(json_depth_exceeds(br#"{"a":{"b":[1,2,{"c":3}]}}"#, 3));
+        // Brackets inside strings do not count.
+        assert!(!json_depth_exceeds(br#"{"a":"[[[[{{{{\"]]]"}"#, 1));
+
+        let deep = "[".repeat(MAX_JSON_DEPTH + 1) + &"]".repeat(MAX_JSON_DEPTH + 1);
+        assert!(json_depth_exceeds(deep.as_bytes(), MAX_JSON_DEPTH));
+    }
+}
@@ -0,0 +1,289 @@
+//! Control plane maintenance mode.
+//!
+//! While maintenance mode is on, the API is read-only: writes are rejected
+//! with `503 maintenance_mode` and a `Retry-After` header, while reads and
+//! data-plane calls (node agent heartbeats, plans, and reports, and ingress
+//! status reports) keep working. Operators toggle it with
+//! `PUT /v1/_admin/maintenance` before database maintenance.
+//!
+//! The mode is stored in `control_plane_maintenance` so every replica
+//! honors it. Replicas cache it for [`CACHE_TTL`]; when the database cannot
+//! be read (the point of maintenance) the last known mode stays in force.
+//!
+//! See: docs/ops/runbooks/control-plane-maintenance.md
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::api::error::ApiError;
+use crate::projections::ProjectionRegistry;
+use crate::state::AppState;
+
+/// How long a replica trusts its cached mode before re-reading it.
+pub const CACHE_TTL: Duration = Duration::from_secs(2);
+
+/// Default `Retry-After` for rejected writes.
+pub const DEFAULT_RETRY_AFTER_SECS: u32 = 60;
+
+/// An active maintenance window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct Maintenance {
+    /// Why the API is read-only, shown to rejected clients.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Seconds clients are told to wait before retrying a write.
+    pub retry_after_secs: u32,
+    pub started_at: DateTime<Utc>,
+    /// Operator who turned maintenance on.
+    pub started_by: String,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Maintenance {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            reason: row.try_get("reason")?,
+            retry_after_secs: row.try_get::<i32, _>("retry_after_secs")?.max(0) as u32,
+            started_at: row.try_get("started_at")?,
+            started_by: row.try_get("started_by")?,
+        })
+    }
+}
+
+/// Last mode read by this replica, and when.
+static CACHE: Mutex<Option<(Instant, Option<Maintenance>)>> = Mutex::new(None);
+
+/// Writes this replica is currently serving.
+static IN_FLIGHT_WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes this replica is currently serving.
+pub fn in_flight_writes() -> usize {
+    IN_FLIGHT_WRITES.load(Ordering::Relaxed)
+}
+
+fn set_cached(mode: Option<Maintenance>) {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), mode));
+}
+
+/// The last mode this replica read, without touching the database.
+pub fn cached() -> Option<Maintenance> {
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|(_, mode)| mode.clone())
+}
+
+/// The active maintenance window, if any, as known to this replica.
+pub async fn current(pool: &PgPool) -> Option<Maintenance> {
+    let cached = CACHE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some((read_at, mode)) = &cached {
+        if read_at.elapsed() < CACHE_TTL {
+            return mode.clone();
+        }
+    }
+
+    match load(pool).await {
+        Ok(mode) => {
+            set_cached(mode.clone());
+            mode
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read maintenance mode; keeping last known");
+            cached.and_then(|(_, mode)| mode)
+        }
+    }
+}
+
+async fn load(pool: &PgPool) -> Result<Option<Maintenance>, sqlx::Error> {
+    sqlx::query_as::<_, Maintenance>(
+        "SELECT reason, retry_after_secs, started_at, started_by FROM control_plane_maintenance",
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Turn maintenance on (replacing any active window) or off.
+pub async fn set(pool: &PgPool, mode: Option<Maintenance>) -> Result<(), sqlx::Error> {
+    match &mode {
+        Some(mode) => {
+            sqlx::query(
+                r#"
+                INSERT INTO control_plane_maintenance
+                    (singleton, reason, retry_after_secs, started_at, started_by)
+                VALUES (true, $1, $2, $3, $4)
+                ON CONFLICT (singleton) DO UPDATE SET
+                    reason = EXCLUDED.reason,
+                    retry_after_secs = EXCLUDED.retry_after_secs,
+                    started_at = EXCLUDED.started_at,
+                    started_by = EXCLUDED.started_by
+                "#,
+            )
+            .bind(&mode.reason)
+            .bind(mode.retry_after_secs as i32)
+            .bind(mode.started_at)
+            .bind(&mode.started_by)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM control_plane_maintenance")
+                .execute(pool)
+                .await?;
+        }
+    }
+    set_cached(mode);
+    Ok(())
+}
+
+/// Wait for writes already under way to land: give other replicas
+/// [`CACHE_TTL`] to notice the mode, wait for this replica's in-flight
+/// writes, then for every projection to apply the events written so far.
+///
+/// Returns whether everything drained within `timeout`.
+pub async fn drain(state: &AppState, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    tokio::time::sleep(CACHE_TTL.min(timeout)).await;
+
+    while in_flight_writes() > 0 {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+
+    let projection_store = state.db().projection_store();
+    let result = async {
+        let max_event_id = projection_store.max_event_id().await?;
+        projection_store
+            .wait_until_caught_up(
+                max_event_id,
+                &ProjectionRegistry::new().coverage(),
+                deadline.saturating_duration_since(Instant::now()),
+            )
+            .await
+    }
+    .await;
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(error = %e, "Maintenance drain did not complete");
+            false
+        }
+    }
+}
+
+/// Writes that keep working during maintenance: the maintenance toggle and
+/// calls from node agents and ingresses, which keep workloads running.
+fn is_exempt(method: &Method, path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["", "v1", "_admin", "maintenance"] => true,
+        ["", "v1", "nodes", node_id, _, ..] => *node_id != "enroll",
+        ["", "v1", "instances", _, "status"] => true,
+        ["", "v1", "orgs", _, "ingresses", _] => *method == Method::PUT,
+        _ => false,
+    }
+}
+
+fn is_write(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+/// Decrements the in-flight write count when the request finishes.
+struct InFlightWrite;
+
+impl InFlightWrite {
+    fn start() -> Self {
+        IN_FLIGHT_WRITES.fetch_add(1, Ordering::Relaxed);
+        Self
+    }
+}
+
+impl Drop for InFlightWrite {
+    fn drop(&mut self) {
+        IN_FLIGHT_WRITES.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Reject writes while maintenance mode is on.
+pub async fn reject_writes(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_write(request.method()) || is_exempt(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    if let Some(mode) = current(state.db().pool()).await {
+        let request_id = request
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let detail = match &mode.reason {
+            Some(reason) => format!("The control plane is in maintenance mode: {reason}"),
+            None => "The control plane is in maintenance mode".to_string(),
+        };
+        return ApiError::service_unavailable("maintenance_mode", detail)
+            .with_retry_after_seconds(mode.retry_after_secs)
+            .with_request_id(request_id)
+            .into_response();
+    }
+
+    let _in_flight = InFlightWrite::start();
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_exempt() {
+        assert!(is_exempt(&Method::PUT, "/v1/_admin/maintenance"));
+        assert!(is_exempt(&Method::POST, "/v1/nodes/node_1/heartbeat"));
+        assert!(is_exempt(&Method::POST, "/v1/nodes/node_1/logs"));
+        assert!(is_exempt(
+            &Method::POST,
+            "/v1/nodes/node_1/instances/inst_1/status"
+        ));
+        assert!(is_exempt(&Method::POST, "/v1/instances/inst_1/status"));
+        assert!(is_exempt(&Method::PUT, "/v1/orgs/org_1/ingresses/ing_1"));
+
+        assert!(!is_exempt(&Method::POST, "/v1/nodes/enroll"));
+        assert!(!is_exempt(&Method::POST, "/v1/orgs"));
+        assert!(!is_exempt(&Method::POST, "/v1/orgs/org_1/apps"));
+        assert!(!is_exempt(
+            &Method::DELETE,
+            "/v1/orgs/org_1/ingresses/ing_1"
+        ));
+    }
+
+    #[test]
+    fn test_in_flight_write_guard() {
+        let before = in_flight_writes();
+        {
+            let _guard = InFlightWrite::start();
+            assert_eq!(in_flight_writes(), before + 1);
+        }
+        assert_eq!(in_flight_writes(), before);
+    }
+}
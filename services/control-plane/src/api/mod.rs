@@ -1,12 +1,21 @@
 //! HTTP API handlers and routing.
 
 pub mod authz;
+pub mod consistency;
 pub mod error;
+pub mod fields;
 mod health;
 pub mod idempotency;
+pub mod if_exists;
+pub mod labels;
+pub mod limits;
+pub mod maintenance;
+pub mod pagination;
+pub mod preconditions;
 pub mod request_context;
 pub mod tokens;
 mod v1;
+pub mod validation;
 
 use std::time::Duration;
 
@@ -56,7 +65,17 @@ pub fn create_router(state: AppState) -> Router {
             Method::PATCH,
             Method::DELETE,
         ])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::IF_MATCH,
+            header::HeaderName::from_static("x-consistency-token"),
+        ])
+        .expose_headers([
+            header::ETAG,
+            header::RETRY_AFTER,
+            header::HeaderName::from_static("x-consistency-token"),
+        ])
         .allow_origin(Any);
 
     let request_id_header = header::HeaderName::from_static("x-request-id");
@@ -69,6 +88,21 @@ pub fn create_router(state: AppState) -> Router {
         // API v1 routes
         .nest("/v1", v1::routes())
         // Middleware
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            idempotency::serialize_in_flight,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            consistency::read_your_writes,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            maintenance::reject_writes,
+        ))
+        .layer(axum::middleware::from_fn(limits::limit_request_body))
+        // Replaced by the per-class limits above.
+        .layer(axum::extract::DefaultBodyLimit::disable())
         .layer(TraceLayer::new_for_http())
         .layer(propagate_request_id)
         .layer(set_request_id)
@@ -0,0 +1,463 @@
+//! Cursor pagination for list endpoints.
+//!
+//! Paginated lists accept `limit`, `cursor`, `order_by`, `desc`, and
+//! `include_total` (see [`PageQuery`]). Rows are ordered by the requested
+//! sort key with the resource ID as a tiebreaker, so keyset pagination stays
+//! stable while rows are added or removed.
+//!
+//! `next_cursor` is opaque to clients: URL-safe base64 of the last item's
+//! sort key and ID plus the ordering it was issued for. A cursor is only
+//! valid with that ordering; `order_by` and `desc` may be omitted when
+//! passing one. Plain IDs, which lists used to return as cursors, are still
+//! accepted for the default ordering.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use crate::api::error::ApiError;
+
+/// Page size when `limit` is omitted.
+pub const DEFAULT_LIMIT: i64 = 50;
+
+/// Largest accepted `limit`.
+pub const MAX_LIMIT: i64 = 200;
+
+/// `include_total` stops counting after this many rows.
+pub const MAX_TOTAL: i64 = 10_000;
+
+/// Pagination query parameters, extracted next to an endpoint's own query.
+#[derive(Debug, Default, Deserialize)]
+pub struct PageQuery {
+    /// Max number of items to return.
+    pub limit: Option<i64>,
+    /// Opaque cursor from a previous page's `next_cursor`.
+    pub cursor: Option<String>,
+    /// Sort key (defaults to the list's first sort key, usually `id`).
+    pub order_by: Option<String>,
+    /// Sort descending.
+    pub desc: Option<bool>,
+    /// Also count the matching items (up to [`MAX_TOTAL`]).
+    pub include_total: Option<bool>,
+}
+
+/// Number of items matching a list's filters, returned for `include_total=true`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PageTotal {
+    /// Matching items, at most [`MAX_TOTAL`].
+    pub count: i64,
+    /// More than [`MAX_TOTAL`] items match.
+    pub capped: bool,
+}
+
+impl PageTotal {
+    /// Total from a count taken with `LIMIT MAX_TOTAL + 1`.
+    pub fn from_count(count: i64) -> Self {
+        Self {
+            count: count.min(MAX_TOTAL),
+            capped: count > MAX_TOTAL,
+        }
+    }
+}
+
+/// A column a list can be ordered by. The column must be `NOT NULL`.
+pub struct SortKey<T> {
+    /// Name accepted by `order_by`.
+    pub name: &'static str,
+    /// SQL column.
+    pub column: &'static str,
+    /// SQL type the cursor value is cast back to.
+    pub sql_type: &'static str,
+    /// The row's value, as it is stored in a cursor.
+    pub value: fn(&T) -> String,
+}
+
+/// The orderings a list endpoint supports.
+pub struct Sorting<T: 'static> {
+    /// Unique ID column, the tiebreaker for every sort key.
+    pub id_column: &'static str,
+    /// The row's ID.
+    pub id: fn(&T) -> String,
+    /// Accepted sort keys; the first is the default.
+    pub keys: &'static [SortKey<T>],
+}
+
+/// Cursor contents before encoding.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Cursor {
+    #[serde(rename = "o")]
+    order_by: String,
+    #[serde(rename = "d", default)]
+    desc: bool,
+    #[serde(rename = "k")]
+    key: String,
+    #[serde(rename = "i")]
+    id: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    fn decode(raw: &str) -> Option<Self> {
+        let json = URL_SAFE_NO_PAD.decode(raw).ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+}
+
+/// Format a timestamp sort key so it casts back to the same instant.
+pub fn timestamp(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+impl<T> Sorting<T> {
+    /// Validate the pagination parameters of a request.
+    pub fn page(&'static self, query: PageQuery, request_id: &str) -> Result<Page<T>, ApiError> {
+        let invalid_cursor = |message: &str| {
+            ApiError::bad_request("invalid_cursor", message).with_request_id(request_id.to_string())
+        };
+
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(|raw| (raw, Cursor::decode(raw)));
+
+        let order_by = query
+            .order_by
+            .as_deref()
+            .or_else(|| cursor.as_ref()?.1.as_ref().map(|c| c.order_by.as_str()));
+        let key = match order_by {
+            None => &self.keys[0],
+            Some(name) => self.key(name).ok_or_else(|| {
+                let names: Vec<&str> = self.keys.iter().map(|key| key.name).collect();
+                ApiError::bad_request(
+                    "invalid_order_by",
+                    format!("order_by must be one of: {}", names.join(", ")),
+                )
+                .with_request_id(request_id.to_string())
+            })?,
+        };
+        let desc = query
+            .desc
+            .or_else(|| cursor.as_ref()?.1.as_ref().map(|c| c.desc))
+            .unwrap_or(false);
+
+        let after = match cursor {
+            None => None,
+            Some((_, Some(cursor))) => {
+                if cursor.order_by != key.name || cursor.desc != desc {
+                    return Err(invalid_cursor(
+                        "Cursor was issued for a different order_by or desc",
+                    ));
+                }
+                Some((cursor.key, cursor.id))
+            }
+            // A bare ID from before cursors were opaque.
+            Some((raw, None)) => {
+                if key.column != self.id_column || desc {
+                    return Err(invalid_cursor("Invalid cursor format"));
+                }
+                Some((raw.to_string(), raw.to_string()))
+            }
+        };
+
+        Ok(Page {
+            limit,
+            sorting: self,
+            key,
+            desc,
+            after,
+            include_total: query.include_total.unwrap_or(false),
+        })
+    }
+
+    fn key(&self, name: &str) -> Option<&SortKey<T>> {
+        self.keys.iter().find(|key| key.name == name)
+    }
+}
+
+/// A validated page request.
+pub struct Page<T: 'static> {
+    /// Max number of items to return.
+    pub limit: i64,
+    sorting: &'static Sorting<T>,
+    key: &'static SortKey<T>,
+    desc: bool,
+    after: Option<(String, String)>,
+    include_total: bool,
+}
+
+impl<T> Page<T> {
+    /// Whether the page is sorted descending.
+    pub fn desc(&self) -> bool {
+        self.desc
+    }
+
+    /// ID of the last item of the previous page, if any.
+    pub fn after_id(&self) -> Option<&str> {
+        self.after.as_ref().map(|(_, id)| id.as_str())
+    }
+
+    /// Rows to fetch: one more than `limit`, to tell whether a next page exists.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+
+    /// Whether the request asked for a total.
+    pub fn include_total(&self) -> bool {
+        self.include_total
+    }
+
+    /// Append ` AND <position after the cursor>` to a `WHERE` clause.
+    pub fn push_after(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        let Some((key, id)) = &self.after else {
+            return;
+        };
+        let op = if self.desc { "<" } else { ">" };
+        let id_column = self.sorting.id_column;
+
+        if self.key.column == id_column {
+            builder.push(format!(" AND {id_column} {op} "));
+            builder.push_bind(id.clone());
+        } else {
+            builder.push(format!(
+                " AND ({}, {id_column}) {op} (CAST(",
+                self.key.column
+            ));
+            builder.push_bind(key.clone());
+            builder.push(format!(" AS {}), ", self.key.sql_type));
+            builder.push_bind(id.clone());
+            builder.push(")");
+        }
+    }
+
+    /// Append the `ORDER BY` and `LIMIT` clauses.
+    pub fn push_order(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        let dir = if self.desc { "DESC" } else { "ASC" };
+        let id_column = self.sorting.id_column;
+
+        if self.key.column == id_column {
+            builder.push(format!(" ORDER BY {id_column} {dir}"));
+        } else {
+            builder.push(format!(
+                " ORDER BY {} {dir}, {id_column} {dir}",
+                self.key.column
+            ));
+        }
+        builder.push(" LIMIT ");
+        builder.push_bind(self.fetch_limit());
+    }
+
+    /// Trim rows fetched with [`Self::fetch_limit`] to the page and compute
+    /// `next_cursor`.
+    pub fn finish(&self, mut rows: Vec<T>) -> (Vec<T>, Option<String>) {
+        if rows.len() as i64 <= self.limit {
+            return (rows, None);
+        }
+        rows.truncate(self.limit as usize);
+        let next_cursor = rows.last().map(|row| {
+            Cursor {
+                order_by: self.key.name.to_string(),
+                desc: self.desc,
+                key: (self.key.value)(row),
+                id: (self.sorting.id)(row),
+            }
+            .encode()
+        });
+        (rows, next_cursor)
+    }
+
+    /// Count rows of `from` matching `filters` (a `WHERE` clause without the
+    /// cursor), if the request asked for a total.
+    pub async fn total(
+        &self,
+        pool: &PgPool,
+        from: &str,
+        filters: impl Fn(&mut QueryBuilder<'_, Postgres>),
+    ) -> Result<Option<PageTotal>, sqlx::Error> {
+        if !self.include_total {
+            return Ok(None);
+        }
+
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM (SELECT 1 FROM ");
+        builder.push(from);
+        builder.push(" WHERE ");
+        filters(&mut builder);
+        builder.push(" LIMIT ");
+        builder.push_bind(MAX_TOTAL + 1);
+        builder.push(") capped");
+
+        let count: i64 = builder.build_query_scalar().fetch_one(pool).await?;
+        Ok(Some(PageTotal::from_count(count)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Row {
+        id: &'static str,
+        name: &'static str,
+    }
+
+    static SORTING: Sorting<Row> = Sorting {
+        id_column: "row_id",
+        id: |row| row.id.to_string(),
+        keys: &[
+            SortKey {
+                name: "id",
+                column: "row_id",
+                sql_type: "TEXT",
+                value: |row| row.id.to_string(),
+            },
+            SortKey {
+                name: "name",
+                column: "name",
+                sql_type: "TEXT",
+                value: |row| row.name.to_string(),
+            },
+        ],
+    };
+
+    fn query(order_by: Option<&str>, desc: Option<bool>, cursor: Option<&str>) -> PageQuery {
+        PageQuery {
+            limit: Some(2),
+            cursor: cursor.map(str::to_string),
+            order_by: order_by.map(str::to_string),
+            desc,
+            include_total: None,
+        }
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                id: "r1",
+                name: "c",
+            },
+            Row {
+                id: "r2",
+                name: "b",
+            },
+            Row {
+                id: "r3",
+                name: "a",
+            },
+        ]
+    }
+
+    fn sql(page: &Page<Row>) -> String {
+        let mut builder = QueryBuilder::new("SELECT * FROM rows WHERE TRUE");
+        page.push_after(&mut builder);
+        page.push_order(&mut builder);
+        builder.sql().to_string()
+    }
+
+    #[test]
+    fn test_default_page() {
+        let page = SORTING.page(PageQuery::default(), "req").unwrap();
+        assert_eq!(page.limit, DEFAULT_LIMIT);
+        assert_eq!(
+            sql(&page),
+            "SELECT * FROM rows WHERE TRUE ORDER BY row_id ASC LIMIT $1"
+        );
+
+        let page = SORTING
+            .page(
+                PageQuery {
+                    limit: Some(10_000),
+                    ..Default::default()
+                },
+                "req",
+            )
+            .unwrap();
+        assert_eq!(page.limit, MAX_LIMIT);
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let page = SORTING
+            .page(query(Some("name"), Some(true), None), "req")
+            .unwrap();
+        let (items, next_cursor) = page.finish(rows());
+        assert_eq!(items.len(), 2);
+        let next_cursor = next_cursor.unwrap();
+        assert!(next_cursor
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        // order_by and desc come from the cursor when omitted.
+        let next = SORTING
+            .page(query(None, None, Some(&next_cursor)), "req")
+            .unwrap();
+        assert!(next.desc());
+        assert_eq!(next.after_id(), Some("r2"));
+        assert_eq!(
+            sql(&next),
+            "SELECT * FROM rows WHERE TRUE AND (name, row_id) < (CAST($1 AS TEXT), $2) \
+             ORDER BY name DESC, row_id DESC LIMIT $3"
+        );
+
+        let err = SORTING
+            .page(query(Some("name"), Some(false), Some(&next_cursor)), "req")
+            .err()
+            .unwrap();
+        assert_eq!(err.problem.code, "invalid_cursor");
+    }
+
+    #[test]
+    fn test_last_page_has_no_cursor() {
+        let page = SORTING.page(query(None, None, None), "req").unwrap();
+        let (items, next_cursor) = page.finish(rows().into_iter().take(2).collect());
+        assert_eq!(items.len(), 2);
+        assert_eq!(next_cursor, None);
+    }
+
+    #[test]
+    fn test_legacy_cursor() {
+        let page = SORTING.page(query(None, None, Some("r1")), "req").unwrap();
+        assert_eq!(page.after_id(), Some("r1"));
+        assert_eq!(
+            sql(&page),
+            "SELECT * FROM rows WHERE TRUE AND row_id > $1 ORDER BY row_id ASC LIMIT $2"
+        );
+
+        let err = SORTING
+            .page(query(Some("name"), None, Some("r1")), "req")
+            .err()
+            .unwrap();
+        assert_eq!(err.problem.code, "invalid_cursor");
+    }
+
+    #[test]
+    fn test_invalid_order_by() {
+        let err = SORTING
+            .page(query(Some("size"), None, None), "req")
+            .err()
+            .unwrap();
+        assert_eq!(err.problem.code, "invalid_order_by");
+    }
+
+    #[test]
+    fn test_total_from_count() {
+        assert_eq!(
+            PageTotal::from_count(3),
+            PageTotal {
+                count: 3,
+                capped: false
+            }
+        );
+        assert_eq!(
+            PageTotal::from_count(MAX_TOTAL + 1),
+            PageTotal {
+                count: MAX_TOTAL,
+                capped: true
+            }
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Optimistic concurrency via `ETag` / `If-Match`.
+//!
+//! Mutable resources expose their `resource_version` as a strong entity tag
+//! (`ETag: "3"`). Writes accept `If-Match` as the standard alternative to the
+//! legacy `expected_version` body field:
+//! - `If-Match` mismatch => 412 Precondition Failed
+//! - `expected_version` mismatch => 409 Conflict (unchanged v1 behavior)
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+
+use crate::api::error::ApiError;
+
+/// Parsed `If-Match` header value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatch {
+    /// `If-Match: *` (any current representation).
+    Any,
+    /// The strong entity tags of the header; matches when any equals the
+    /// current version (weak tags are dropped, so this may be empty).
+    Versions(Vec<i32>),
+}
+
+impl IfMatch {
+    fn matches(&self, current_version: i32) -> bool {
+        match self {
+            IfMatch::Any => true,
+            IfMatch::Versions(versions) => versions.contains(&current_version),
+        }
+    }
+}
+
+/// Write preconditions extracted from request headers.
+#[derive(Debug, Clone, Default)]
+pub struct Preconditions {
+    pub if_match: Option<IfMatch>,
+}
+
+/// Format a resource version as a strong entity tag.
+pub fn etag(resource_version: i32) -> HeaderValue {
+    // A quoted decimal integer is always a valid header value.
+    HeaderValue::from_str(&format!("\"{resource_version}\"")).expect("valid etag header value")
+}
+
+/// Attach an `ETag` header for `resource_version` to a response.
+pub fn with_etag(resource_version: i32, body: impl IntoResponse) -> Response {
+    let mut response = body.into_response();
+    response
+        .headers_mut()
+        .insert(header::ETAG, etag(resource_version));
+    response
+}
+
+/// Parse an `If-Match` header value.
+///
+/// Accepts `*` or a comma-separated list of entity tags. `If-Match` uses the
+/// strong comparison (RFC 7232 §3.1), so weak tags (`W/"3"`) are well-formed
+/// but never match: a list of only weak tags fails with 412.
+pub fn parse_if_match(raw: &str) -> Option<IfMatch> {
+    let raw = raw.trim();
+    if raw == "*" {
+        return Some(IfMatch::Any);
+    }
+
+    let mut versions = Vec::new();
+    for tag in raw.split(',') {
+        let tag = tag.trim();
+        let (weak, tag) = match tag.strip_prefix("W/") {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let inner = tag.strip_prefix('"')?.strip_suffix('"')?;
+        let version = inner.parse::<i32>().ok().filter(|v| *v >= 0)?;
+        if !weak {
+            versions.push(version);
+        }
+    }
+
+    Some(IfMatch::Versions(versions))
+}
+
+impl Preconditions {
+    /// Validate write preconditions against the resource's current version.
+    ///
+    /// `expected_version` is the legacy body field. When `required` is set, the
+    /// request must carry `If-Match` or `expected_version` (428 otherwise).
+    pub fn check(
+        &self,
+        expected_version: Option<i32>,
+        current_version: i32,
+        required: bool,
+        request_id: &str,
+    ) -> Result<(), ApiError> {
+        if required && self.if_match.is_none() && expected_version.is_none() {
+            return Err(ApiError::precondition_required(
+                "precondition_required",
+                "If-Match header or expected_version is required",
+            )
+            .with_request_id(request_id.to_string()));
+        }
+
+        if let Some(if_match) = self.if_match.as_ref() {
+            if !if_match.matches(current_version) {
+                return Err(ApiError::precondition_failed(
+                    "precondition_failed",
+                    format!("If-Match does not match current ETag \"{current_version}\""),
+                )
+                .with_request_id(request_id.to_string()));
+            }
+        }
+
+        if let Some(expected) = expected_version {
+            if expected != current_version {
+                return Err(ApiError::conflict(
+                    "version_conflict",
+                    format!(
+                        "Resource version mismatch: expected {}, current {}",
+                        expected, current_version
+                    ),
+                )
+                .with_request_id(request_id.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate write preconditions for a resource that does not exist yet.
+    ///
+    /// Only `If-Match: *` style checks are meaningful here; any `If-Match`
+    /// fails because there is no current representation.
+    pub fn check_absent(&self, request_id: &str) -> Result<(), ApiError> {
+        if self.if_match.is_some() {
+            return Err(ApiError::precondition_failed(
+                "precondition_failed",
+                "If-Match was supplied but the resource does not exist",
+            )
+            .with_request_id(request_id.to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Preconditions {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(Self::default());
+        };
+
+        let request_id = parts
+            .headers
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let if_match = value
+            .to_str()
+            .ok()
+            .and_then(parse_if_match)
+            .ok_or_else(|| {
+                ApiError::bad_request(
+                    "invalid_if_match",
+                    "If-Match must be '*' or a list of quoted resource versions",
+                )
+                .with_request_id(request_id)
+            })?;
+
+        Ok(Self {
+            if_match: Some(if_match),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_if_match() {
+        assert_eq!(parse_if_match("*"), Some(IfMatch::Any));
+        assert_eq!(parse_if_match("\"3\""), Some(IfMatch::Versions(vec![3])));
+        assert_eq!(
+            parse_if_match("W/\"3\", \"4\""),
+            Some(IfMatch::Versions(vec![4]))
+        );
+        assert_eq!(parse_if_match("W/\"abc\""), None);
+        assert_eq!(parse_if_match("3"), None);
+        assert_eq!(parse_if_match("\"abc\""), None);
+        assert_eq!(parse_if_match("\"-1\""), None);
+        assert_eq!(parse_if_match(""), None);
+    }
+
+    #[test]
+    fn test_check_if_match_mismatch_is_412() {
+        let pre = Preconditions {
+            if_match: Some(IfMatch::Versions(vec![2])),
+        };
+        let err = pre.check(None, 3, true, "req").unwrap_err();
+        assert_eq!(err.status.as_u16(), 412);
+        assert!(pre.check(None, 2, true, "req").is_ok());
+    }
+
+    #[test]
+    fn test_weak_if_match_never_matches() {
+        let pre = Preconditions {
+            if_match: parse_if_match("W/\"3\""),
+        };
+        let err = pre.check(None, 3, true, "req").unwrap_err();
+        assert_eq!(err.status.as_u16(), 412);
+    }
+
+    #[test]
+    fn test_check_expected_version_mismatch_is_409() {
+        let pre = Preconditions::default();
+        let err = pre.check(Some(1), 2, true, "req").unwrap_err();
+        assert_eq!(err.status.as_u16(), 409);
+    }
+
+    #[test]
+    fn test_check_required() {
+        let pre = Preconditions::default();
+        let err = pre.check(None, 2, true, "req").unwrap_err();
+        assert_eq!(err.status.as_u16(), 428);
+        assert!(pre.check(None, 2, false, "req").is_ok());
+    }
+}
@@ -3,10 +3,11 @@
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
 use axum::http::HeaderMap;
-use plfm_events::ActorType;
+use plfm_events::{ActorType, EventSource};
 use plfm_id::RequestId;
 use sha2::{Digest, Sha256};
 
+use crate::api::consistency::WriteWatermark;
 use crate::api::error::ApiError;
 use crate::api::tokens;
 use crate::state::AppState;
@@ -22,6 +23,27 @@ pub struct RequestContext {
     pub actor_id: String,
     pub actor_email: Option<String>,
     pub scopes: Vec<String>,
+    /// Highest event ID written by this request; returned to the client as a
+    /// consistency token.
+    pub write_watermark: WriteWatermark,
+}
+
+impl EventSource for RequestContext {
+    fn actor_type(&self) -> ActorType {
+        self.actor_type
+    }
+
+    fn actor_id(&self) -> &str {
+        &self.actor_id
+    }
+
+    fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
 }
 
 fn header_string(headers: &HeaderMap, name: &str) -> Option<String> {
@@ -174,6 +196,11 @@ impl FromRequestParts<AppState> for RequestContext {
             actor_id,
             actor_email,
             scopes,
+            write_watermark: parts
+                .extensions
+                .get::<WriteWatermark>()
+                .cloned()
+                .unwrap_or_default(),
         })
     }
 }
@@ -9,6 +9,7 @@
 //! - Access token: `trc_at_<32 random bytes base64>`
 //! - Refresh token: `trc_rt_<32 random bytes base64>`
 //! - Device code: `trc_dc_<32 random bytes base64>`
+//! - Node bootstrap token: `trc_nb_<32 random bytes base64>`
 //!
 //! All tokens are stored hashed (SHA-256) in the database.
 
@@ -28,6 +29,7 @@ use crate::api::error::ApiError;
 pub const ACCESS_TOKEN_PREFIX: &str = "trc_at_";
 pub const REFRESH_TOKEN_PREFIX: &str = "trc_rt_";
 pub const DEVICE_CODE_PREFIX: &str = "trc_dc_";
+pub const NODE_BOOTSTRAP_TOKEN_PREFIX: &str = "trc_nb_";
 
 /// Default token lifetimes per spec.
 pub const ACCESS_TOKEN_LIFETIME_MINUTES: i64 = 15;
@@ -58,6 +60,11 @@ pub fn generate_device_code() -> String {
     generate_token_with_prefix(DEVICE_CODE_PREFIX)
 }
 
+/// Generate a new node bootstrap token.
+pub fn generate_node_bootstrap_token() -> String {
+    generate_token_with_prefix(NODE_BOOTSTRAP_TOKEN_PREFIX)
+}
+
 /// Generate a user-friendly user code for device flow (e.g., "ABCD-1234").
 /// Format: 4 uppercase letters + hyphen + 4 digits = 9 characters.
 pub fn generate_user_code() -> String {
@@ -619,6 +626,13 @@ mod tests {
         assert!(code.len() > DEVICE_CODE_PREFIX.len() + 40);
     }
 
+    #[test]
+    fn test_node_bootstrap_token_format() {
+        let token = generate_node_bootstrap_token();
+        assert!(token.starts_with(NODE_BOOTSTRAP_TOKEN_PREFIX));
+        assert!(token.len() > NODE_BOOTSTRAP_TOKEN_PREFIX.len() + 40);
+    }
+
     #[test]
     fn test_user_code_format() {
         let code = generate_user_code();
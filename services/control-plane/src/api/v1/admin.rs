@@ -0,0 +1,654 @@
+//! Operator admin endpoints.
+//!
+//! Projection health: per-projection checkpoint, lag, apply rate, last
+//! error, and owning replica, plus pause/resume/rebuild controls for the
+//! projection worker. Leadership: which replica runs each singleton worker.
+//! Backups: WAL archiving status of the control-plane database.
+//! Maintenance: read-only mode for database maintenance. All of them require
+//! a platform operator.
+//!
+//! See: docs/specs/state/materialized-views.md,
+//! docs/ops/07-backup-restore-runbook.md,
+//! docs/ops/runbooks/control-plane-maintenance.md
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::maintenance::{self, Maintenance};
+use crate::api::request_context::RequestContext;
+use crate::db::PROJECTION_LEASE_PREFIX;
+use crate::leader::{self, LeadershipSnapshot, LEADER_LEASE_PREFIX};
+use crate::projections::{worker, ProjectionRegistry};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/projections", get(list_projections))
+        .route("/leaders", get(list_leaders))
+        .route("/backups", get(get_backup_status))
+        .route("/maintenance", get(get_maintenance).put(set_maintenance))
+        .route(
+            "/projections/{projection_name}/pause",
+            post(pause_projection),
+        )
+        .route(
+            "/projections/{projection_name}/resume",
+            post(resume_projection),
+        )
+        .route(
+            "/projections/{projection_name}/rebuild",
+            post(rebuild_projection),
+        )
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectionErrorInfo {
+    message: String,
+    event_id: Option<i64>,
+    at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectionHealth {
+    projection_name: String,
+    last_applied_event_id: i64,
+    updated_at: Option<DateTime<Utc>>,
+    /// max_event_id - last_applied_event_id.
+    lag: i64,
+    /// Events this projection handles that it has not applied yet.
+    pending_events: i64,
+    paused: bool,
+    /// Apply rate over the last minute, as seen by this control-plane process.
+    events_per_second: f64,
+    last_error: Option<ProjectionErrorInfo>,
+    /// Replica currently holding the projection's lease, if any.
+    owner: Option<ProjectionOwner>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectionOwner {
+    holder: String,
+    since: DateTime<Utc>,
+    leased_until: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectionHealthResponse {
+    max_event_id: i64,
+    items: Vec<ProjectionHealth>,
+}
+
+#[derive(Debug, Serialize)]
+struct PauseResponse {
+    ok: bool,
+    projection_name: String,
+    paused: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RebuildResponse {
+    ok: bool,
+    projection_name: String,
+    /// The checkpoint the projection replays from once resumed.
+    last_applied_event_id: i64,
+}
+
+async fn list_projections(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+    let projection_store = state.db().projection_store();
+    let registry = ProjectionRegistry::new();
+
+    let internal = |e: crate::db::DbError| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to load projection health");
+        ApiError::internal("internal_error", "Failed to list projections")
+            .with_request_id(request_id.clone())
+    };
+
+    let max_event_id = projection_store.max_event_id().await.map_err(internal)?;
+    let rows = projection_store.list_status().await.map_err(internal)?;
+    let mut owners: HashMap<String, ProjectionOwner> = state
+        .db()
+        .lease_store()
+        .list_active(PROJECTION_LEASE_PREFIX)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .filter_map(|lease| {
+            let name = lease.lease_name.strip_prefix(PROJECTION_LEASE_PREFIX)?;
+            Some((
+                name.to_string(),
+                ProjectionOwner {
+                    holder: lease.holder,
+                    since: lease.acquired_at,
+                    leased_until: lease.leased_until,
+                },
+            ))
+        })
+        .collect();
+    let pending: HashMap<String, i64> = projection_store
+        .pending_counts(&registry.coverage())
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .collect();
+
+    let mut rows_by_name: HashMap<String, _> = rows
+        .into_iter()
+        .map(|row| (row.projection_name.clone(), row))
+        .collect();
+
+    let mut names = registry.projection_names();
+    names.sort_unstable();
+    let items = names
+        .into_iter()
+        .map(|name| {
+            let row = rows_by_name.remove(name);
+            let last_applied_event_id = row.as_ref().map_or(0, |r| r.last_applied_event_id);
+            ProjectionHealth {
+                projection_name: name.to_string(),
+                last_applied_event_id,
+                updated_at: row.as_ref().map(|r| r.updated_at),
+                lag: (max_event_id - last_applied_event_id).max(0),
+                pending_events: pending.get(name).copied().unwrap_or(0),
+                paused: row.as_ref().is_some_and(|r| r.paused),
+                events_per_second: worker::METRICS.events_per_second(name),
+                owner: owners.remove(name),
+                last_error: row.and_then(|r| {
+                    r.last_error.map(|message| ProjectionErrorInfo {
+                        message,
+                        event_id: r.last_error_event_id,
+                        at: r.last_error_at,
+                    })
+                }),
+            }
+        })
+        .collect();
+
+    Ok(Json(ProjectionHealthResponse {
+        max_event_id,
+        items,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+struct LeaderInfo {
+    role: String,
+    holder: String,
+    since: DateTime<Utc>,
+    leased_until: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct LeadersResponse {
+    /// The replica serving this request.
+    replica_id: &'static str,
+    /// Current leader of each role, across replicas.
+    items: Vec<LeaderInfo>,
+    /// This replica's own view of each role.
+    local: Vec<LeadershipSnapshot>,
+}
+
+async fn list_leaders(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let leases = state
+        .db()
+        .lease_store()
+        .list_active(LEADER_LEASE_PREFIX)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to list leaders");
+            ApiError::internal("internal_error", "Failed to list leaders")
+                .with_request_id(request_id.clone())
+        })?;
+
+    let items = leases
+        .into_iter()
+        .filter_map(|lease| {
+            Some(LeaderInfo {
+                role: lease
+                    .lease_name
+                    .strip_prefix(LEADER_LEASE_PREFIX)?
+                    .to_string(),
+                holder: lease.holder,
+                since: lease.acquired_at,
+                leased_until: lease.leased_until,
+            })
+        })
+        .collect();
+
+    Ok(Json(LeadersResponse {
+        replica_id: leader::replica_id(),
+        items,
+        local: leader::METRICS.snapshot(),
+    }))
+}
+
+/// Default `max_age_secs`: ten `archive_timeout`s at the recommended 60s.
+const DEFAULT_WAL_ARCHIVE_MAX_AGE_SECS: i64 = 600;
+
+#[derive(Debug, Deserialize)]
+struct BackupStatusQuery {
+    /// Report `stale` when no WAL segment was archived for this long.
+    max_age_secs: Option<i64>,
+}
+
+/// Health of continuous WAL archiving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum WalArchiveState {
+    /// `archive_mode` is off; point-in-time restore is impossible.
+    Disabled,
+    Ok,
+    /// The most recent archive attempt failed.
+    Failing,
+    /// Nothing archived within `max_age_secs`.
+    Stale,
+}
+
+#[derive(Debug, Serialize)]
+struct BackupStatusResponse {
+    state: WalArchiveState,
+    archive_mode: String,
+    archived_count: i64,
+    last_archived_wal: Option<String>,
+    last_archived_at: Option<DateTime<Utc>>,
+    failed_count: i64,
+    last_failed_wal: Option<String>,
+    last_failed_at: Option<DateTime<Utc>>,
+    max_age_secs: i64,
+}
+
+/// Classify WAL archiving from `pg_stat_archiver`.
+///
+/// An idle database with `archive_timeout` set still switches segments, so
+/// a long gap means the archiver is stuck even without recorded failures.
+fn wal_archive_state(
+    archive_mode: &str,
+    last_archived_at: Option<DateTime<Utc>>,
+    last_failed_at: Option<DateTime<Utc>>,
+    max_age: chrono::Duration,
+    now: DateTime<Utc>,
+) -> WalArchiveState {
+    if archive_mode == "off" {
+        return WalArchiveState::Disabled;
+    }
+    match (last_archived_at, last_failed_at) {
+        (archived, Some(failed)) if archived.is_none_or(|a| failed > a) => WalArchiveState::Failing,
+        (Some(archived), _) if now - archived <= max_age => WalArchiveState::Ok,
+        _ => WalArchiveState::Stale,
+    }
+}
+
+/// Continuous WAL archiving, as seen by the database.
+///
+/// Base backups live only in object storage; list them with
+/// `plfm-admin backup list`.
+///
+/// GET /v1/_admin/backups
+async fn get_backup_status(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<BackupStatusQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let max_age_secs = query
+        .max_age_secs
+        .unwrap_or(DEFAULT_WAL_ARCHIVE_MAX_AGE_SECS);
+    if max_age_secs <= 0 {
+        return Err(
+            ApiError::bad_request("invalid_request", "max_age_secs must be positive")
+                .with_request_id(request_id),
+        );
+    }
+
+    let row = sqlx::query(
+        r#"
+        SELECT current_setting('archive_mode') AS archive_mode,
+               archived_count, last_archived_wal, last_archived_time,
+               failed_count, last_failed_wal, last_failed_time
+        FROM pg_stat_archiver
+        "#,
+    )
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to read archiver status");
+        ApiError::internal("internal_error", "Failed to read backup status")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let decode = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to decode archiver status");
+        ApiError::internal("internal_error", "Failed to read backup status")
+            .with_request_id(request_id.clone())
+    };
+    let archive_mode: String = row.try_get("archive_mode").map_err(decode)?;
+    let last_archived_at: Option<DateTime<Utc>> =
+        row.try_get("last_archived_time").map_err(decode)?;
+    let last_failed_at: Option<DateTime<Utc>> = row.try_get("last_failed_time").map_err(decode)?;
+
+    Ok(Json(BackupStatusResponse {
+        state: wal_archive_state(
+            &archive_mode,
+            last_archived_at,
+            last_failed_at,
+            chrono::Duration::seconds(max_age_secs),
+            Utc::now(),
+        ),
+        archive_mode,
+        archived_count: row.try_get("archived_count").map_err(decode)?,
+        last_archived_wal: row.try_get("last_archived_wal").map_err(decode)?,
+        last_archived_at,
+        failed_count: row.try_get("failed_count").map_err(decode)?,
+        last_failed_wal: row.try_get("last_failed_wal").map_err(decode)?,
+        last_failed_at,
+        max_age_secs,
+    }))
+}
+
+/// Default `drain_timeout_secs` when enabling maintenance.
+const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// Longest `drain_timeout_secs` accepted; the request waits for the drain.
+const MAX_DRAIN_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Debug, Deserialize)]
+struct SetMaintenanceRequest {
+    enabled: bool,
+    reason: Option<String>,
+    /// `Retry-After` for rejected writes.
+    retry_after_secs: Option<u32>,
+    /// How long to wait for in-flight writes and projections to drain.
+    drain_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct MaintenanceResponse {
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maintenance: Option<Maintenance>,
+    /// Writes in flight on the replica serving this request.
+    in_flight_writes: usize,
+    /// Set when enabling: whether in-flight writes and projection work
+    /// finished within the drain timeout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    drained: Option<bool>,
+}
+
+/// GET /v1/_admin/maintenance
+async fn get_maintenance(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let mode = maintenance::current(state.db().pool()).await;
+
+    Ok(Json(MaintenanceResponse {
+        enabled: mode.is_some(),
+        maintenance: mode,
+        in_flight_writes: maintenance::in_flight_writes(),
+        drained: None,
+    }))
+}
+
+/// Turn read-only maintenance mode on or off.
+///
+/// Enabling waits (up to `drain_timeout_secs`) for in-flight writes and
+/// projection work to finish, so the database is quiet when this returns
+/// with `drained: true`. Node agents and ingresses keep reporting.
+///
+/// PUT /v1/_admin/maintenance
+async fn set_maintenance(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Json(req): Json<SetMaintenanceRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let drain_timeout_secs = req.drain_timeout_secs.unwrap_or(DEFAULT_DRAIN_TIMEOUT_SECS);
+    if drain_timeout_secs > MAX_DRAIN_TIMEOUT_SECS {
+        return Err(ApiError::bad_request(
+            "invalid_request",
+            format!("drain_timeout_secs must be at most {MAX_DRAIN_TIMEOUT_SECS}"),
+        )
+        .with_request_id(request_id));
+    }
+
+    let mode = req.enabled.then(|| Maintenance {
+        reason: req.reason.filter(|reason| !reason.trim().is_empty()),
+        retry_after_secs: req
+            .retry_after_secs
+            .unwrap_or(maintenance::DEFAULT_RETRY_AFTER_SECS),
+        started_at: Utc::now(),
+        started_by: ctx
+            .actor_email
+            .clone()
+            .unwrap_or_else(|| ctx.actor_id.clone()),
+    });
+
+    maintenance::set(state.db().pool(), mode.clone())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to set maintenance mode");
+            ApiError::internal("internal_error", "Failed to set maintenance mode")
+                .with_request_id(request_id.clone())
+        })?;
+
+    tracing::info!(
+        request_id = %request_id,
+        actor_id = %ctx.actor_id,
+        enabled = req.enabled,
+        "Maintenance mode changed"
+    );
+
+    let drained = if req.enabled {
+        let drained = maintenance::drain(&state, Duration::from_secs(drain_timeout_secs)).await;
+        tracing::info!(request_id = %request_id, drained, "Maintenance drain finished");
+        Some(drained)
+    } else {
+        None
+    };
+
+    Ok(Json(MaintenanceResponse {
+        enabled: req.enabled,
+        maintenance: mode,
+        in_flight_writes: maintenance::in_flight_writes(),
+        drained,
+    }))
+}
+
+async fn pause_projection(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(projection_name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    set_paused(&state, &ctx, projection_name, true).await
+}
+
+async fn resume_projection(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(projection_name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    set_paused(&state, &ctx, projection_name, false).await
+}
+
+async fn set_paused(
+    state: &AppState,
+    ctx: &RequestContext,
+    projection_name: String,
+    paused: bool,
+) -> Result<Json<PauseResponse>, ApiError> {
+    authz::require_operator(ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    require_projection(&projection_name, &request_id)?;
+
+    state
+        .db()
+        .projection_store()
+        .set_paused(&projection_name, paused)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                error = %e,
+                request_id = %request_id,
+                projection_name = %projection_name,
+                paused,
+                "Failed to update projection pause state"
+            );
+            ApiError::internal("internal_error", "Failed to update projection")
+                .with_request_id(request_id.clone())
+        })?;
+
+    tracing::info!(
+        request_id = %request_id,
+        actor_id = %ctx.actor_id,
+        projection_name = %projection_name,
+        paused,
+        "Projection pause state changed"
+    );
+
+    Ok(Json(PauseResponse {
+        ok: true,
+        projection_name,
+        paused,
+    }))
+}
+
+/// Rewind a paused projection so it replays the whole log once resumed.
+///
+/// Replay upserts over the projection's existing rows; tables shared with
+/// other projections are not truncated. Pausing first guarantees no worker
+/// is mid-batch when the checkpoint moves.
+///
+/// POST /v1/_admin/projections/{projection_name}/rebuild
+async fn rebuild_projection(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(projection_name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    require_projection(&projection_name, &request_id)?;
+
+    let rewound = state
+        .db()
+        .projection_store()
+        .rewind_paused(&projection_name)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                error = %e,
+                request_id = %request_id,
+                projection_name = %projection_name,
+                "Failed to rewind projection"
+            );
+            ApiError::internal("internal_error", "Failed to rebuild projection")
+                .with_request_id(request_id.clone())
+        })?;
+    if !rewound {
+        return Err(ApiError::conflict(
+            "projection_not_paused",
+            format!(
+                "Projection '{}' must be paused before it is rebuilt",
+                projection_name
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    tracing::info!(
+        request_id = %request_id,
+        actor_id = %ctx.actor_id,
+        projection_name = %projection_name,
+        "Projection rewound for rebuild"
+    );
+
+    Ok(Json(RebuildResponse {
+        ok: true,
+        projection_name,
+        last_applied_event_id: 0,
+    }))
+}
+
+fn require_projection(projection_name: &str, request_id: &str) -> Result<(), ApiError> {
+    if ProjectionRegistry::new()
+        .projection_names()
+        .contains(&projection_name)
+    {
+        return Ok(());
+    }
+    Err(ApiError::not_found(
+        "projection_not_found",
+        format!("Projection '{}' not found", projection_name),
+    )
+    .with_request_id(request_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_wal_archive_state() {
+        let now = Utc::now();
+        let max_age = Duration::minutes(10);
+        let state =
+            |mode, archived, failed| wal_archive_state(mode, archived, failed, max_age, now);
+
+        assert_eq!(state("off", Some(now), None), WalArchiveState::Disabled);
+        assert_eq!(
+            state("on", Some(now - Duration::minutes(1)), None),
+            WalArchiveState::Ok
+        );
+        assert_eq!(
+            state("on", Some(now - Duration::hours(1)), None),
+            WalArchiveState::Stale
+        );
+        assert_eq!(state("always", None, None), WalArchiveState::Stale);
+        assert_eq!(
+            state(
+                "on",
+                Some(now - Duration::minutes(2)),
+                Some(now - Duration::minutes(1))
+            ),
+            WalArchiveState::Failing
+        );
+        // A failure that a later archive recovered from is fine.
+        assert_eq!(
+            state(
+                "on",
+                Some(now - Duration::minutes(1)),
+                Some(now - Duration::minutes(2))
+            ),
+            WalArchiveState::Ok
+        );
+        assert_eq!(state("on", None, Some(now)), WalArchiveState::Failing);
+    }
+}
@@ -0,0 +1,554 @@
+//! Alert rule and open alert endpoints.
+//!
+//! - `/v1/orgs/{org_id}/alerts`: pending and firing alerts
+//! - `/v1/orgs/{org_id}/alerts/rules`: conditions evaluated by the alert
+//!   evaluator, with an optional app/env scope for env conditions
+//!
+//! Any member can read; creating, changing, and deleting rules requires the
+//! admin role. Rules on platform conditions (`node_offline`,
+//! `projection_lag`) can only be created or changed by operators.
+//!
+//! See: docs/specs/observability/alert-rules.md
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_id::{AppId, EnvId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::{self, Alert, AlertError, AlertRule, Condition, RuleSpec, MAX_DURATION_SECS};
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::api::validation::{ValidJson, Validate, Validator};
+use crate::state::AppState;
+
+const MAX_NAME_LEN: usize = 63;
+
+/// Alert routes, nested under /v1/orgs/{org_id}/alerts.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_alerts))
+        .route("/rules", get(list_rules).post(create_rule))
+        .route(
+            "/rules/{rule_id}",
+            get(get_rule).put(update_rule).delete(delete_rule),
+        )
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Body of rule create and replace.
+#[derive(Debug, Deserialize)]
+struct RuleRequest {
+    name: String,
+    condition: Condition,
+    /// Seconds the condition must hold before the alert fires.
+    #[serde(default)]
+    for_seconds: i32,
+    #[serde(default)]
+    app_id: Option<String>,
+    #[serde(default)]
+    env_id: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+impl Validate for RuleRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Rule", MAX_NAME_LEN);
+        if let Err(message) = self.condition.validate() {
+            v.check(false, "invalid_alert_rule", "condition", &message);
+        }
+        v.check(
+            (0..=MAX_DURATION_SECS).contains(&self.for_seconds),
+            "invalid_alert_rule",
+            "for_seconds",
+            &format!("for_seconds must be between 0 and {MAX_DURATION_SECS}"),
+        );
+        let scoped = self.app_id.is_some() || self.env_id.is_some();
+        v.check(
+            !(scoped && self.condition.is_platform()),
+            "invalid_alert_rule",
+            "condition",
+            "Platform conditions cannot be scoped to an app or environment",
+        );
+        if let Some(app_id) = &self.app_id {
+            v.check(
+                app_id.parse::<AppId>().is_ok(),
+                "invalid_alert_rule",
+                "app_id",
+                "Invalid app ID format",
+            );
+        }
+        if let Some(env_id) = &self.env_id {
+            v.check(
+                env_id.parse::<EnvId>().is_ok(),
+                "invalid_alert_rule",
+                "env_id",
+                "Invalid environment ID format",
+            );
+        }
+    }
+}
+
+impl RuleRequest {
+    fn into_spec(self) -> RuleSpec {
+        RuleSpec {
+            name: self.name,
+            condition: self.condition,
+            for_seconds: self.for_seconds,
+            app_id: self.app_id,
+            env_id: self.env_id,
+            enabled: self.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RuleResponse {
+    id: String,
+    org_id: String,
+    name: String,
+    condition: Condition,
+    for_seconds: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env_id: Option<String>,
+    enabled: bool,
+    created_by: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<AlertRule> for RuleResponse {
+    fn from(rule: AlertRule) -> Self {
+        Self {
+            id: rule.rule_id,
+            org_id: rule.org_id,
+            name: rule.spec.name,
+            condition: rule.spec.condition,
+            for_seconds: rule.spec.for_seconds,
+            app_id: rule.spec.app_id,
+            env_id: rule.spec.env_id,
+            enabled: rule.spec.enabled,
+            created_by: rule.created_by,
+            created_at: rule.created_at,
+            updated_at: rule.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListRulesResponse {
+    items: Vec<RuleResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListAlertsQuery {
+    /// `pending` or `firing`.
+    state: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AlertResponse {
+    rule_id: String,
+    rule_name: String,
+    condition: String,
+    target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env_id: Option<String>,
+    state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<i64>,
+    summary: String,
+    pending_since: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fired_at: Option<DateTime<Utc>>,
+    last_evaluated_at: DateTime<Utc>,
+}
+
+impl From<Alert> for AlertResponse {
+    fn from(alert: Alert) -> Self {
+        Self {
+            rule_id: alert.rule_id,
+            rule_name: alert.rule_name,
+            condition: alert.condition,
+            target: alert.target,
+            app_id: alert.app_id,
+            env_id: alert.env_id,
+            state: alert.state,
+            value: alert.value,
+            summary: alert.summary,
+            pending_since: alert.pending_since,
+            fired_at: alert.fired_at,
+            last_evaluated_at: alert.last_evaluated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListAlertsResponse {
+    items: Vec<AlertResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeleteResponse {
+    ok: bool,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn parse_org_id(org_id: &str, request_id: &str) -> Result<OrgId, ApiError> {
+    org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.to_string())
+    })
+}
+
+fn alert_error(error: AlertError, request_id: &str) -> ApiError {
+    match error {
+        AlertError::NameTaken => ApiError::conflict(
+            "alert_rule_name_exists",
+            "An alert rule with this name already exists",
+        )
+        .with_request_id(request_id.to_string()),
+        other => {
+            tracing::error!(error = %other, request_id = %request_id, "Alert request failed");
+            ApiError::internal("internal_error", "Alert request failed")
+                .with_request_id(request_id.to_string())
+        }
+    }
+}
+
+fn rule_not_found(request_id: &str) -> ApiError {
+    ApiError::not_found("alert_rule_not_found", "Alert rule not found")
+        .with_request_id(request_id.to_string())
+}
+
+/// Check that a rule's scope belongs to the org.
+async fn check_rule_scope(
+    state: &AppState,
+    org_id: &OrgId,
+    spec: &RuleSpec,
+    request_id: &str,
+) -> Result<(), ApiError> {
+    let pool = state.db().pool();
+    let org = org_id.to_string();
+    let internal = |e: sqlx::Error| alert_error(e.into(), request_id);
+    let invalid = |message: String| {
+        ApiError::bad_request("invalid_alert_rule", message).with_request_id(request_id.to_string())
+    };
+
+    if let Some(env_id) = &spec.env_id {
+        let env_app: Option<String> = sqlx::query_scalar(
+            "SELECT app_id FROM envs_view WHERE env_id = $1 AND org_id = $2 AND NOT is_deleted",
+        )
+        .bind(env_id)
+        .bind(&org)
+        .fetch_optional(pool)
+        .await
+        .map_err(internal)?;
+        match env_app {
+            None => {
+                return Err(invalid(format!(
+                    "Environment {env_id} not found in this org"
+                )))
+            }
+            Some(env_app) if spec.app_id.as_ref().is_some_and(|app| *app != env_app) => {
+                return Err(invalid(format!(
+                    "Environment {env_id} does not belong to app {}",
+                    spec.app_id.as_deref().unwrap_or_default()
+                )))
+            }
+            Some(_) => {}
+        }
+    } else if let Some(app_id) = &spec.app_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM apps_view WHERE app_id = $1 AND org_id = $2 AND NOT is_deleted)",
+        )
+        .bind(app_id)
+        .bind(&org)
+        .fetch_one(pool)
+        .await
+        .map_err(internal)?;
+        if !exists {
+            return Err(invalid(format!("App {app_id} not found in this org")));
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/alerts
+async fn list_alerts(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    Query(query): Query<ListAlertsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    if let Some(alert_state) = &query.state {
+        if !["pending", "firing"].contains(&alert_state.as_str()) {
+            return Err(ApiError::bad_request(
+                "invalid_request",
+                "state must be pending or firing",
+            )
+            .with_request_id(request_id));
+        }
+    }
+
+    let items = alerts::list_alerts(
+        state.db().pool(),
+        &org_id.to_string(),
+        query.state.as_deref(),
+    )
+    .await
+    .map_err(|e| alert_error(e.into(), &request_id))?;
+
+    Ok(Json(ListAlertsResponse {
+        items: items.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /v1/orgs/{org_id}/alerts/rules
+async fn list_rules(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let rules = alerts::list_rules(state.db().pool(), &org_id.to_string())
+        .await
+        .map_err(|e| alert_error(e.into(), &request_id))?;
+
+    Ok(Json(ListRulesResponse {
+        items: rules.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /v1/orgs/{org_id}/alerts/rules
+async fn create_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    ValidJson(req): ValidJson<RuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+    if req.condition.is_platform() {
+        authz::require_operator(&ctx)?;
+    }
+
+    let spec = req.into_spec();
+    check_rule_scope(&state, &org_id, &spec, &request_id).await?;
+    let rule = alerts::create_rule(state.db().pool(), &org_id.to_string(), &spec, &ctx.actor_id)
+        .await
+        .map_err(|e| alert_error(e, &request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        rule_id = %rule.rule_id,
+        condition = rule.spec.condition.as_str(),
+        actor_id = %ctx.actor_id,
+        "Alert rule created"
+    );
+
+    Ok((StatusCode::CREATED, Json(RuleResponse::from(rule))))
+}
+
+/// GET /v1/orgs/{org_id}/alerts/rules/{rule_id}
+async fn get_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, rule_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let rule = alerts::get_rule(state.db().pool(), &org_id.to_string(), &rule_id)
+        .await
+        .map_err(|e| alert_error(e.into(), &request_id))?
+        .ok_or_else(|| rule_not_found(&request_id))?;
+
+    Ok(Json(RuleResponse::from(rule)))
+}
+
+/// Replace a rule. Its open alerts are dropped and re-evaluated.
+///
+/// PUT /v1/orgs/{org_id}/alerts/rules/{rule_id}
+async fn update_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, rule_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<RuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let pool = state.db().pool();
+    let existing = alerts::get_rule(pool, &org_id.to_string(), &rule_id)
+        .await
+        .map_err(|e| alert_error(e.into(), &request_id))?
+        .ok_or_else(|| rule_not_found(&request_id))?;
+    if existing.spec.condition.is_platform() || req.condition.is_platform() {
+        authz::require_operator(&ctx)?;
+    }
+
+    let spec = req.into_spec();
+    check_rule_scope(&state, &org_id, &spec, &request_id).await?;
+    let rule = alerts::update_rule(pool, &org_id.to_string(), &rule_id, &spec)
+        .await
+        .map_err(|e| alert_error(e, &request_id))?
+        .ok_or_else(|| rule_not_found(&request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        rule_id = %rule_id,
+        enabled = rule.spec.enabled,
+        actor_id = %ctx.actor_id,
+        "Alert rule updated"
+    );
+
+    Ok(Json(RuleResponse::from(rule)))
+}
+
+/// Delete a rule. Its open alerts are dropped without resolving.
+///
+/// DELETE /v1/orgs/{org_id}/alerts/rules/{rule_id}
+async fn delete_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, rule_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let deleted = alerts::delete_rule(state.db().pool(), &org_id.to_string(), &rule_id)
+        .await
+        .map_err(|e| alert_error(e.into(), &request_id))?;
+    if !deleted {
+        return Err(rule_not_found(&request_id));
+    }
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        rule_id = %rule_id,
+        actor_id = %ctx.actor_id,
+        "Alert rule deleted"
+    );
+
+    Ok(Json(DeleteResponse { ok: true }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::validation::validate;
+
+    fn rule_request(body: serde_json::Value) -> RuleRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_rule_request_validation() {
+        let valid = rule_request(json!({
+            "name": "prod-down",
+            "condition": {"type": "env_unhealthy"},
+            "for_seconds": 300,
+            "env_id": EnvId::new().to_string(),
+        }));
+        assert!(validate(&valid).is_ok());
+        let spec = valid.into_spec();
+        assert!(spec.enabled);
+        assert_eq!(spec.condition, Condition::EnvUnhealthy);
+
+        let err = validate(&rule_request(json!({
+            "name": "restarts",
+            "condition": {"type": "instance_restarts", "threshold": 0},
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_alert_rule");
+
+        let err = validate(&rule_request(json!({
+            "name": "lag",
+            "condition": {"type": "projection_lag", "threshold": 1000},
+            "app_id": AppId::new().to_string(),
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_alert_rule");
+
+        let err = validate(&rule_request(json!({
+            "name": "prod-down",
+            "condition": {"type": "env_unhealthy"},
+            "for_seconds": 90000,
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_alert_rule");
+    }
+
+    #[test]
+    fn test_rule_response_shape() {
+        let rule = AlertRule {
+            rule_id: "alr_1".to_string(),
+            org_id: "org_1".to_string(),
+            spec: RuleSpec {
+                name: "restarts".to_string(),
+                condition: Condition::InstanceRestarts {
+                    threshold: 5,
+                    window_seconds: 600,
+                },
+                for_seconds: 0,
+                app_id: None,
+                env_id: None,
+                enabled: true,
+            },
+            created_by: "ops@example.com".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let body = serde_json::to_value(RuleResponse::from(rule)).unwrap();
+        assert_eq!(
+            body["condition"],
+            json!({"type": "instance_restarts", "threshold": 5, "window_seconds": 600})
+        );
+        assert!(body.get("env_id").is_none());
+    }
+}
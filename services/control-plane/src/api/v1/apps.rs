@@ -10,16 +10,31 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use plfm_events::{event_types, AggregateType};
+use plfm_api_types::apps::{
+    AppDeletionResponse, AppResponse, CreateAppRequest, ListAppsResponse, UpdateAppRequest,
+};
+use plfm_events::{
+    AggregateType, AppCreatedPayload, AppDeletionRequestedPayload, AppUpdatedPayload, NewEvent,
+};
 use plfm_id::{AppId, OrgId};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
+use crate::api::if_exists::IfExistsQuery;
+use crate::api::labels::{self, LabelSelector, PatchLabelsRequest};
+use crate::api::preconditions::{self, Preconditions};
 use crate::api::request_context::RequestContext;
-use crate::db::AppendEvent;
+use crate::api::validation::ValidJson;
+use crate::cleanup::teardown;
+use crate::db::DbError;
 use crate::state::AppState;
+use crate::trash::{RestoreScope, TrashKind};
+
+use super::labels::LabelTarget;
+use super::trash::{self, RestoreResponse};
 
 /// Create app routes.
 ///
@@ -28,74 +43,25 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", post(create_app))
         .route("/", get(list_apps))
+        .route("/by-name/{name}", get(get_app_by_name))
         .route("/{app_id}", patch(update_app))
         .route("/{app_id}", delete(delete_app))
         .route("/{app_id}", get(get_app))
+        .route("/{app_id}/deletion", get(get_app_deletion))
+        .route("/{app_id}/restore", post(restore_app))
+        .route("/{app_id}/labels", patch(patch_app_labels))
 }
 
 // =============================================================================
 // Request/Response Types
 // =============================================================================
 
-/// Request to create a new application.
-#[derive(Debug, Deserialize, Serialize)]
-pub struct CreateAppRequest {
-    /// Application name (unique within org).
-    pub name: String,
-
-    /// Optional description.
+/// Query parameters for deleting an application.
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteAppQuery {
+    /// Also delete volumes that are attached only to the app's environments.
     #[serde(default)]
-    pub description: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateAppRequest {
-    #[serde(default)]
-    pub name: Option<String>,
-    #[serde(default)]
-    pub description: Option<String>,
-    pub expected_version: i32,
-}
-
-#[derive(Debug, Serialize)]
-pub struct DeleteResponse {
-    pub ok: bool,
-}
-
-/// Response for a single application.
-#[derive(Debug, Serialize)]
-pub struct AppResponse {
-    /// Application ID.
-    pub id: String,
-
-    /// Organization ID.
-    pub org_id: String,
-
-    /// Application name.
-    pub name: String,
-
-    /// Optional description.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
-
-    /// Resource version for optimistic concurrency.
-    pub resource_version: i32,
-
-    /// When the app was created.
-    pub created_at: DateTime<Utc>,
-
-    /// When the app was last updated.
-    pub updated_at: DateTime<Utc>,
-}
-
-/// Response for listing applications.
-#[derive(Debug, Serialize)]
-pub struct ListAppsResponse {
-    /// List of applications.
-    pub items: Vec<AppResponse>,
-
-    /// Next cursor (null if no more results).
-    pub next_cursor: Option<String>,
+    pub delete_volumes: bool,
 }
 
 /// Query parameters for listing applications.
@@ -105,6 +71,8 @@ pub struct ListAppsQuery {
     pub limit: Option<i64>,
     /// Cursor (exclusive). Interpreted as an app_id.
     pub cursor: Option<String>,
+    /// Label selector (e.g. `team=payments,tier!=frontend`).
+    pub label_selector: Option<String>,
 }
 
 // =============================================================================
@@ -114,15 +82,18 @@ pub struct ListAppsQuery {
 /// Create a new application.
 ///
 /// POST /v1/orgs/{org_id}/apps
+///
+/// With `?if_exists=return`, an existing app with the same name and
+/// description is returned instead of a conflict.
 async fn create_app(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path(org_id): Path<String>,
-    Json(req): Json<CreateAppRequest>,
+    Query(if_exists): Query<IfExistsQuery>,
+    ValidJson(req): ValidJson<CreateAppRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
     let actor_id = ctx.actor_id.clone();
     let endpoint_name = "apps.create";
 
@@ -131,26 +102,11 @@ async fn create_app(
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
             .with_request_id(request_id.clone())
     })?;
+    let if_exists = if_exists.mode(&request_id)?;
 
     let role = authz::require_org_member(&state, &org_id, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
 
-    // Validate name
-    if req.name.is_empty() {
-        return Err(
-            ApiError::bad_request("invalid_name", "Application name cannot be empty")
-                .with_request_id(request_id.clone()),
-        );
-    }
-
-    if req.name.len() > 100 {
-        return Err(ApiError::bad_request(
-            "invalid_name",
-            "Application name cannot exceed 100 characters",
-        )
-        .with_request_id(request_id.clone()));
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -199,83 +155,65 @@ async fn create_app(
     }
 
     // Check for duplicate name within org
-    let name_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM apps_view WHERE org_id = $1 AND name = $2 AND NOT is_deleted)",
-    )
-    .bind(org_id.to_string())
-    .bind(&req.name)
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Failed to check app name uniqueness");
-        ApiError::internal("internal_error", "Failed to verify application name")
-            .with_request_id(request_id.clone())
-    })?;
+    let existing = load_app_by_name(&state, &org_id, &req.name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check app name uniqueness");
+            ApiError::internal("internal_error", "Failed to verify application name")
+                .with_request_id(request_id.clone())
+        })?;
 
-    if name_exists {
-        return Err(ApiError::conflict(
-            "app_name_exists",
-            format!(
-                "Application '{}' already exists in this organization",
-                req.name
-            ),
-        )
-        .with_request_id(request_id.clone()));
+    if let Some(existing) = existing {
+        if_exists.reuse(existing.description == req.description, || {
+            ApiError::conflict(
+                "app_name_exists",
+                format!(
+                    "Application '{}' already exists in this organization",
+                    req.name
+                ),
+            )
+            .with_request_id(request_id.clone())
+        })?;
+        return Ok(preconditions::with_etag(
+            existing.resource_version,
+            (StatusCode::OK, Json(AppResponse::from(existing))),
+        ));
     }
 
     let app_id = AppId::new();
 
     // Create the event
-    let event = AppendEvent {
-        aggregate_type: AggregateType::App,
-        aggregate_id: app_id.to_string(),
-        aggregate_seq: 1,
-        event_type: "app.created".to_string(),
-        event_version: 1,
-        actor_type,
-        actor_id: actor_id.clone(),
-        org_id: Some(org_id),
-        request_id: request_id.clone(),
-        idempotency_key: idempotency_key.clone(),
-        app_id: Some(app_id),
-        env_id: None,
-        correlation_id: None,
-        causation_id: None,
-        payload: serde_json::json!({
-            "app_id": app_id.to_string(),
-            "org_id": org_id.to_string(),
-            "name": req.name,
-            "description": req.description
-        }),
-        ..Default::default()
-    };
+    let event = NewEvent::builder(&ctx)
+        .aggregate_id(app_id.to_string())
+        .aggregate_seq(1)
+        .org_id(org_id)
+        .app_id(app_id)
+        .payload(&AppCreatedPayload {
+            app_id,
+            org_id,
+            name: req.name.clone(),
+            description: req.description.clone(),
+        })
+        .build()
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to build app event");
+            ApiError::internal("internal_error", "Failed to create application")
+                .with_request_id(request_id.clone())
+        })?;
 
     // Append the event
     let event_store = state.db().event_store();
-    let event_id = event_store.append(event).await.map_err(|e| {
+    let event_id = event_store.append(event.into()).await.map_err(|e| {
         tracing::error!(error = %e, request_id = %request_id, "Failed to create app");
         ApiError::internal("internal_error", "Failed to create application")
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "apps",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "apps", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, AppRow>(
         r#"
-        SELECT app_id, org_id, name, description, resource_version, created_at, updated_at
+        SELECT app_id, org_id, name, description, labels, resource_version, created_at, updated_at
         FROM apps_view
         WHERE app_id = $1 AND NOT is_deleted
         "#,
@@ -294,6 +232,7 @@ async fn create_app(
     })?;
 
     let response = AppResponse::from(row);
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -318,18 +257,26 @@ async fn create_app(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
 }
 
+/// Update an application.
+///
+/// PATCH /v1/orgs/{org_id}/apps/{app_id}
+///
+/// An update that changes nothing returns the app as is, without a new
+/// version.
 async fn update_app(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id)): Path<(String, String)>,
-    Json(req): Json<UpdateAppRequest>,
+    ValidJson(req): ValidJson<UpdateAppRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
     let actor_id = ctx.actor_id.clone();
     let endpoint_name = "apps.update";
 
@@ -353,29 +300,6 @@ async fn update_app(
         .with_request_id(request_id.clone()));
     }
 
-    if req.name.is_none() && req.description.is_none() {
-        return Err(
-            ApiError::bad_request("invalid_update", "No updatable fields provided")
-                .with_request_id(request_id.clone()),
-        );
-    }
-
-    if let Some(name) = req.name.as_ref() {
-        if name.is_empty() {
-            return Err(
-                ApiError::bad_request("invalid_name", "Application name cannot be empty")
-                    .with_request_id(request_id.clone()),
-            );
-        }
-        if name.len() > 100 {
-            return Err(ApiError::bad_request(
-                "invalid_name",
-                "Application name cannot exceed 100 characters",
-            )
-            .with_request_id(request_id.clone()));
-        }
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -411,7 +335,7 @@ async fn update_app(
 
     let current = sqlx::query_as::<_, AppRow>(
         r#"
-        SELECT app_id, org_id, name, description, resource_version, created_at, updated_at
+        SELECT app_id, org_id, name, description, labels, resource_version, created_at, updated_at
         FROM apps_view
         WHERE app_id = $1 AND org_id = $2 AND NOT is_deleted
         "#,
@@ -437,6 +361,13 @@ async fn update_app(
         );
     }
 
+    if app_update_is_noop(&current, &req) {
+        return Ok(preconditions::with_etag(
+            current.resource_version,
+            (StatusCode::OK, Json(AppResponse::from(current))),
+        ));
+    }
+
     if let Some(name) = req.name.as_ref() {
         if name != &current.name {
             let name_exists = sqlx::query_scalar::<_, bool>(
@@ -464,56 +395,40 @@ async fn update_app(
     }
 
     let next_version = current.resource_version + 1;
-    let payload = serde_json::json!({
-        "app_id": app_id.to_string(),
-        "org_id": org_id.to_string(),
-        "name": req.name,
-        "description": req.description
-    });
-
-    let event = AppendEvent {
-        aggregate_type: AggregateType::App,
-        aggregate_id: app_id.to_string(),
-        aggregate_seq: next_version,
-        event_type: event_types::APP_UPDATED.to_string(),
-        event_version: 1,
-        actor_type,
-        actor_id: actor_id.clone(),
-        org_id: Some(org_id),
-        request_id: request_id.clone(),
-        idempotency_key: idempotency_key.clone(),
-        app_id: Some(app_id),
-        env_id: None,
-        correlation_id: None,
-        causation_id: None,
-        payload,
-        ..Default::default()
-    };
-
-    let event_id = state.db().event_store().append(event).await.map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to update app");
-        ApiError::internal("internal_error", "Failed to update application")
-            .with_request_id(request_id.clone())
-    })?;
+    let event = NewEvent::builder(&ctx)
+        .aggregate_id(app_id.to_string())
+        .aggregate_seq(next_version)
+        .org_id(org_id)
+        .app_id(app_id)
+        .payload(&AppUpdatedPayload {
+            app_id,
+            org_id,
+            name: req.name.clone(),
+            description: req.description.clone(),
+        })
+        .build()
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to build app event");
+            ApiError::internal("internal_error", "Failed to update application")
+                .with_request_id(request_id.clone())
+        })?;
 
-    state
+    let event_id = state
         .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "apps",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
+        .event_store()
+        .append(event.into())
         .await
         .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
+            tracing::error!(error = %e, request_id = %request_id, "Failed to update app");
+            ApiError::internal("internal_error", "Failed to update application")
                 .with_request_id(request_id.clone())
         })?;
 
+    consistency::wait_for_write(&state, &ctx, "apps", event_id.value()).await?;
+
     let row = sqlx::query_as::<_, AppRow>(
         r#"
-        SELECT app_id, org_id, name, description, resource_version, created_at, updated_at
+        SELECT app_id, org_id, name, description, labels, resource_version, created_at, updated_at
         FROM apps_view
         WHERE app_id = $1 AND org_id = $2 AND NOT is_deleted
         "#,
@@ -533,6 +448,7 @@ async fn update_app(
     })?;
 
     let response = AppResponse::from(row);
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -557,17 +473,36 @@ async fn update_app(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
+}
+
+/// Whether `req` leaves every field of the app as it is.
+fn app_update_is_noop(current: &AppRow, req: &UpdateAppRequest) -> bool {
+    req.name.as_ref().is_none_or(|name| *name == current.name)
+        && req
+            .description
+            .as_ref()
+            .is_none_or(|description| current.description.as_ref() == Some(description))
 }
 
+/// Request deletion of an application.
+///
+/// DELETE /v1/orgs/{org_id}/apps/{app_id}
+///
+/// Emits `app.deletion_requested` and returns 202 with the teardown status.
+/// The cleanup worker requests deletion of every env of the app and emits
+/// `app.deleted` once they are all torn down.
 async fn delete_app(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id)): Path<(String, String)>,
+    Query(query): Query<DeleteAppQuery>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
     let actor_id = ctx.actor_id.clone();
     let endpoint_name = "apps.delete";
 
@@ -589,7 +524,8 @@ async fn delete_app(
         .map(|key| {
             let hash_input = serde_json::json!({
                 "org_id": org_scope.clone(),
-                "app_id": app_id.to_string()
+                "app_id": app_id.to_string(),
+                "delete_volumes": query.delete_volumes
             });
             idempotency::request_hash(endpoint_name, &hash_input)
                 .map(|hash| (key.to_string(), hash))
@@ -615,102 +551,61 @@ async fn delete_app(
         }
     }
 
-    let row = sqlx::query_as::<_, AppDeleteRow>(
-        r#"
-        SELECT resource_version, is_deleted
-        FROM apps_view
-        WHERE app_id = $1 AND org_id = $2
-        "#,
-    )
-    .bind(app_id.to_string())
-    .bind(org_id.to_string())
-    .fetch_optional(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, app_id = %app_id, "Failed to load app");
-        ApiError::internal("internal_error", "Failed to delete application")
-            .with_request_id(request_id.clone())
-    })?;
+    let row = load_app_delete_row(&state, &request_id, &org_id, &app_id).await?;
 
-    let Some(row) = row else {
-        return Err(ApiError::not_found(
-            "app_not_found",
-            format!("Application {} not found", app_id),
-        )
-        .with_request_id(request_id.clone()));
-    };
-
-    let response = DeleteResponse { ok: true };
-    if row.is_deleted {
-        if let Some((key, hash)) = request_hash {
-            let body = serde_json::to_value(&response).map_err(|e| {
-                tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+    if !row.is_deleted && row.deletion_requested_at.is_none() {
+        let current_seq = state
+            .db()
+            .event_store()
+            .get_latest_aggregate_seq(&AggregateType::App, &app_id.to_string())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, app_id = %app_id, "Failed to get aggregate sequence");
+                ApiError::internal("internal_error", "Failed to delete application")
+                    .with_request_id(request_id.clone())
+            })?
+            .unwrap_or(0);
+
+        let event = NewEvent::builder(&ctx)
+            .aggregate_id(app_id.to_string())
+            .aggregate_seq(current_seq + 1)
+            .org_id(org_id)
+            .app_id(app_id)
+            .payload(&AppDeletionRequestedPayload {
+                app_id,
+                org_id,
+                delete_volumes: query.delete_volumes,
+            })
+            .build()
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, "Failed to build app deletion event");
                 ApiError::internal("internal_error", "Failed to delete application")
                     .with_request_id(request_id.clone())
             })?;
 
-            let _ = idempotency::store(
-                &state,
-                idempotency::StoreIdempotencyParams {
-                    org_scope: &org_scope,
-                    actor_id: &actor_id,
-                    endpoint_name,
-                    idempotency_key: &key,
-                    request_hash: &hash,
-                    status: StatusCode::OK,
-                    body: Some(body),
-                },
-                &request_id,
-            )
-            .await;
-        }
+        let event_id = state.db().event_store().append(event.into()).await.map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to request app deletion");
+            match e {
+                DbError::SequenceConflict { .. } => ApiError::conflict(
+                    "version_conflict",
+                    "Concurrent application update detected; retry",
+                ),
+                _ => ApiError::internal("internal_error", "Failed to delete application"),
+            }
+            .with_request_id(request_id.clone())
+        })?;
 
-        return Ok((StatusCode::OK, Json(response)).into_response());
+        consistency::wait_for_write(&state, &ctx, "apps", event_id.value()).await?;
     }
 
-    let next_version = row.resource_version + 1;
-    let payload = serde_json::json!({});
-
-    let event = AppendEvent {
-        aggregate_type: AggregateType::App,
-        aggregate_id: app_id.to_string(),
-        aggregate_seq: next_version,
-        event_type: event_types::APP_DELETED.to_string(),
-        event_version: 1,
-        actor_type,
-        actor_id: actor_id.clone(),
-        org_id: Some(org_id),
-        request_id: request_id.clone(),
-        idempotency_key: idempotency_key.clone(),
-        app_id: Some(app_id),
-        env_id: None,
-        correlation_id: None,
-        causation_id: None,
-        payload,
-        ..Default::default()
+    let row = load_app_delete_row(&state, &request_id, &org_id, &app_id).await?;
+    let response = load_app_deletion(&state, &request_id, &app_id, &row).await?;
+    let status = if row.is_deleted {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
     };
 
-    let event_id = state.db().event_store().append(event).await.map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to delete app");
-        ApiError::internal("internal_error", "Failed to delete application")
-            .with_request_id(request_id.clone())
-    })?;
-
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "apps",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
-
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
             tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
@@ -726,7 +621,7 @@ async fn delete_app(
                 endpoint_name,
                 idempotency_key: &key,
                 request_hash: &hash,
-                status: StatusCode::OK,
+                status,
                 body: Some(body),
             },
             &request_id,
@@ -734,7 +629,164 @@ async fn delete_app(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok((status, Json(response)).into_response())
+}
+
+/// Restore a deleted application from the trash, along with the
+/// environments its deletion took with it.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/restore
+async fn restore_app(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id)): Path<(String, String)>,
+) -> Result<Json<RestoreResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let scope = RestoreScope {
+        org_id: &org_id,
+        app_id: None,
+        env_id: None,
+    };
+    trash::restore(&state, &ctx, scope, TrashKind::App, &app_id.to_string()).await
+}
+
+/// Get the teardown status of an application deletion.
+///
+/// GET /v1/orgs/{org_id}/apps/{app_id}/deletion
+async fn get_app_deletion(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id)): Path<(String, String)>,
+) -> Result<Json<AppDeletionResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let row = load_app_delete_row(&state, &request_id, &org_id, &app_id).await?;
+    if !row.is_deleted && row.deletion_requested_at.is_none() {
+        return Err(ApiError::not_found(
+            "deletion_not_found",
+            format!("Deletion has not been requested for application {}", app_id),
+        )
+        .with_request_id(request_id));
+    }
+
+    let response = load_app_deletion(&state, &request_id, &app_id, &row).await?;
+    Ok(Json(response))
+}
+
+async fn load_app_delete_row(
+    state: &AppState,
+    request_id: &str,
+    org_id: &OrgId,
+    app_id: &AppId,
+) -> Result<AppDeleteRow, ApiError> {
+    let row = sqlx::query_as::<_, AppDeleteRow>(
+        r#"
+        SELECT is_deleted, deletion_requested_at, delete_volumes
+        FROM apps_view
+        WHERE app_id = $1 AND org_id = $2
+        "#,
+    )
+    .bind(app_id.to_string())
+    .bind(org_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, app_id = %app_id, "Failed to load app");
+        ApiError::internal("internal_error", "Failed to load application deletion")
+            .with_request_id(request_id.to_string())
+    })?;
+
+    row.ok_or_else(|| {
+        ApiError::not_found("app_not_found", format!("Application {} not found", app_id))
+            .with_request_id(request_id.to_string())
+    })
+}
+
+async fn load_app_deletion(
+    state: &AppState,
+    request_id: &str,
+    app_id: &AppId,
+    row: &AppDeleteRow,
+) -> Result<AppDeletionResponse, ApiError> {
+    let envs_remaining = if row.is_deleted {
+        0
+    } else {
+        teardown::count_remaining_envs(state.db().pool(), &app_id.to_string())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, app_id = %app_id, "Failed to count remaining envs");
+                ApiError::internal("internal_error", "Failed to load application deletion")
+                    .with_request_id(request_id.to_string())
+            })?
+    };
+
+    Ok(AppDeletionResponse {
+        app_id: app_id.to_string(),
+        status: if row.is_deleted {
+            "completed".to_string()
+        } else {
+            "in_progress".to_string()
+        },
+        delete_volumes: row.delete_volumes,
+        requested_at: row.deletion_requested_at,
+        envs_remaining,
+    })
+}
+
+/// Update labels on an application.
+///
+/// PATCH /v1/orgs/{org_id}/apps/{app_id}/labels
+async fn patch_app_labels(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id)): Path<(String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<PatchLabelsRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    super::labels::patch_labels(
+        &state,
+        &ctx,
+        org_id,
+        LabelTarget::App { app_id },
+        &preconditions,
+        req,
+    )
+    .await
 }
 
 /// List applications in an organization.
@@ -767,14 +819,16 @@ async fn list_apps(
         }
         None => None,
     };
+    let selector = LabelSelector::from_query(query.label_selector.as_deref(), &request_id)?;
 
     // Query the apps_view table (stable ordering by app_id)
     let rows = sqlx::query_as::<_, AppRow>(
         r#"
-        SELECT app_id, org_id, name, description, resource_version, created_at, updated_at
+        SELECT app_id, org_id, name, description, labels, resource_version, created_at, updated_at
         FROM apps_view
         WHERE org_id = $1 AND NOT is_deleted
           AND ($2::TEXT IS NULL OR app_id > $2)
+          AND labels_match(labels, $4::JSONB)
         ORDER BY app_id ASC
         LIMIT $3
         "#,
@@ -782,6 +836,7 @@ async fn list_apps(
     .bind(org_id.to_string())
     .bind(cursor.as_deref())
     .bind(limit)
+    .bind(selector)
     .fetch_all(state.db().pool())
     .await
     .map_err(|e| {
@@ -827,7 +882,7 @@ async fn get_app(
     // Query the apps_view table
     let row = sqlx::query_as::<_, AppRow>(
         r#"
-        SELECT app_id, org_id, name, description, resource_version, created_at, updated_at
+        SELECT app_id, org_id, name, description, labels, resource_version, created_at, updated_at
         FROM apps_view
         WHERE app_id = $1 AND org_id = $2 AND NOT is_deleted
         "#,
@@ -849,7 +904,10 @@ async fn get_app(
     })?;
 
     match row {
-        Some(row) => Ok(Json(AppResponse::from(row))),
+        Some(row) => Ok(preconditions::with_etag(
+            row.resource_version,
+            Json(AppResponse::from(row)),
+        )),
         None => Err(ApiError::not_found(
             "app_not_found",
             format!("Application {} not found", app_id),
@@ -858,6 +916,60 @@ async fn get_app(
     }
 }
 
+/// Get a single application by name, e.g. to import it by name.
+///
+/// GET /v1/orgs/{org_id}/apps/by-name/{name}
+async fn get_app_by_name(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, name)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let row = load_app_by_name(&state, &org_id, &name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to get app");
+            ApiError::internal("internal_error", "Failed to get application")
+                .with_request_id(request_id.clone())
+        })?
+        .ok_or_else(|| {
+            ApiError::not_found("app_not_found", format!("Application '{}' not found", name))
+                .with_request_id(request_id.clone())
+        })?;
+
+    Ok(preconditions::with_etag(
+        row.resource_version,
+        Json(AppResponse::from(row)),
+    ))
+}
+
+/// The live app named `name` in the org. Names are unique among live apps.
+async fn load_app_by_name(
+    state: &AppState,
+    org_id: &OrgId,
+    name: &str,
+) -> Result<Option<AppRow>, sqlx::Error> {
+    sqlx::query_as::<_, AppRow>(
+        r#"
+        SELECT app_id, org_id, name, description, labels, resource_version, created_at, updated_at
+        FROM apps_view
+        WHERE org_id = $1 AND name = $2 AND NOT is_deleted
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(name)
+    .fetch_optional(state.db().pool())
+    .await
+}
+
 // =============================================================================
 // Database Row Types
 // =============================================================================
@@ -868,14 +980,16 @@ struct AppRow {
     org_id: String,
     name: String,
     description: Option<String>,
+    labels: serde_json::Value,
     resource_version: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
 
 struct AppDeleteRow {
-    resource_version: i32,
     is_deleted: bool,
+    deletion_requested_at: Option<DateTime<Utc>>,
+    delete_volumes: bool,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AppRow {
@@ -886,6 +1000,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AppRow {
             org_id: row.try_get("org_id")?,
             name: row.try_get("name")?,
             description: row.try_get("description")?,
+            labels: row.try_get("labels")?,
             resource_version: row.try_get("resource_version")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
@@ -897,8 +1012,9 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AppDeleteRow {
     fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
         use sqlx::Row;
         Ok(Self {
-            resource_version: row.try_get("resource_version")?,
             is_deleted: row.try_get("is_deleted")?,
+            deletion_requested_at: row.try_get("deletion_requested_at")?,
+            delete_volumes: row.try_get("delete_volumes")?,
         })
     }
 }
@@ -910,47 +1026,10 @@ impl From<AppRow> for AppResponse {
             org_id: row.org_id,
             name: row.name,
             description: row.description,
+            labels: labels::from_json(row.labels),
             resource_version: row.resource_version,
             created_at: row.created_at,
             updated_at: row.updated_at,
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_create_app_request_deserialization() {
-        let json = r#"{"name": "my-app", "description": "A test app"}"#;
-        let req: CreateAppRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(req.name, "my-app");
-        assert_eq!(req.description, Some("A test app".to_string()));
-    }
-
-    #[test]
-    fn test_create_app_request_without_description() {
-        let json = r#"{"name": "my-app"}"#;
-        let req: CreateAppRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(req.name, "my-app");
-        assert_eq!(req.description, None);
-    }
-
-    #[test]
-    fn test_app_response_serialization() {
-        let response = AppResponse {
-            id: "app_123".to_string(),
-            org_id: "org_456".to_string(),
-            name: "Test App".to_string(),
-            description: Some("A test".to_string()),
-            resource_version: 1,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
-
-        let json = serde_json::to_string(&response).unwrap();
-        assert!(json.contains("\"id\":\"app_123\""));
-        assert!(json.contains("\"org_id\":\"org_456\""));
-    }
-}
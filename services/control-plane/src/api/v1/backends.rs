@@ -0,0 +1,298 @@
+//! Ingress backend sync.
+//!
+//! `GET /v1/orgs/{org_id}/backends` serves the org's routable instances (ready,
+//! not stopping, with an overlay address) to edges in two modes:
+//! - full (no `since`): a snapshot paged by instance ID (`after`)
+//! - delta (`since=<cursor>`): the instances touched by `instance.*` events
+//!   after the cursor, each either re-listed with its current address or
+//!   reported as removed
+//!
+//! Cursors are event IDs, bounded by the instances projection checkpoint so a
+//! delta never reports an instance the views have not caught up on. The
+//! cursor from the first page of a full snapshot is the one to resume deltas
+//! from; replaying changes is idempotent.
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+use std::collections::BTreeSet;
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use plfm_id::{InstanceId, OrgId};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+/// Bumped on incompatible changes to the sync response.
+pub const BACKEND_SYNC_VERSION: u32 = 1;
+
+const DEFAULT_LIMIT: i64 = 1000;
+const MAX_LIMIT: i64 = 5000;
+
+/// Instances are routable once they report ready and are not being stopped.
+const ROUTABLE: &str = r#"
+    d.desired_state NOT IN ('stopped', 'draining')
+    AND s.status = 'ready'
+    AND d.overlay_ipv6 IS NOT NULL
+"#;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(sync_backends))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackendSyncQuery {
+    /// Cursor from a previous response; omitted for a full snapshot.
+    pub since: Option<i64>,
+    /// Full snapshot paging: last instance ID of the previous page.
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendSyncMode {
+    Full,
+    Delta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackendInstance {
+    pub instance_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub process_type: String,
+    pub overlay_ipv6: String,
+}
+
+impl<'r> FromRow<'r, PgRow> for BackendInstance {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            instance_id: row.try_get("instance_id")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            process_type: row.try_get("process_type")?,
+            overlay_ipv6: row.try_get("overlay_ipv6")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackendSyncResponse {
+    pub version: u32,
+    pub mode: BackendSyncMode,
+    /// Pass back as `since` to receive the changes after this response.
+    pub cursor: i64,
+    /// Routable instances (added or changed, in delta mode).
+    pub items: Vec<BackendInstance>,
+    /// Instances that are no longer routable (delta mode only).
+    pub removed: Vec<String>,
+    /// More pages (full) or more changes (delta) are available now.
+    pub has_more: bool,
+    /// Full snapshot paging: pass as `after` for the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_after: Option<String>,
+}
+
+/// GET /v1/orgs/{org_id}/backends
+async fn sync_backends(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    Query(query): Query<BackendSyncQuery>,
+) -> Result<Json<BackendSyncResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    if query.since.is_some_and(|since| since < 0) {
+        return Err(
+            ApiError::bad_request("invalid_cursor", "since must be >= 0")
+                .with_request_id(request_id),
+        );
+    }
+    if let Some(after) = query.after.as_deref() {
+        let _: InstanceId = after.parse().map_err(|_| {
+            ApiError::bad_request("invalid_cursor", "Invalid cursor format")
+                .with_request_id(request_id.clone())
+        })?;
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let db_error = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Backend sync query failed");
+        ApiError::internal("internal_error", "Failed to sync backends")
+            .with_request_id(request_id.clone())
+    };
+
+    let checkpoint = state
+        .db()
+        .projection_store()
+        .get_checkpoint("instances")
+        .await
+        .map(|c| c.last_applied_event_id)
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to read instances checkpoint");
+            ApiError::internal("internal_error", "Failed to sync backends")
+                .with_request_id(request_id.clone())
+        })?;
+
+    let Some(since) = query.since else {
+        let items = sqlx::query_as::<_, BackendInstance>(&format!(
+            r#"
+            SELECT d.instance_id, d.app_id, d.env_id, d.process_type,
+                   d.overlay_ipv6::TEXT AS overlay_ipv6
+            FROM instances_desired_view d
+            JOIN instances_status_view s ON d.instance_id = s.instance_id
+            WHERE d.org_id = $1
+              AND ($2::TEXT IS NULL OR d.instance_id > $2)
+              AND {ROUTABLE}
+            ORDER BY d.instance_id ASC
+            LIMIT $3
+            "#
+        ))
+        .bind(org_id.to_string())
+        .bind(query.after.as_deref())
+        .bind(limit)
+        .fetch_all(state.db().pool())
+        .await
+        .map_err(db_error)?;
+
+        let has_more = items.len() == limit as usize;
+        let next_after = has_more
+            .then(|| items.last().map(|i| i.instance_id.clone()))
+            .flatten();
+        return Ok(Json(BackendSyncResponse {
+            version: BACKEND_SYNC_VERSION,
+            mode: BackendSyncMode::Full,
+            cursor: checkpoint,
+            items,
+            removed: Vec::new(),
+            has_more,
+            next_after,
+        }));
+    };
+
+    // Changes the views have applied since the cursor, oldest first.
+    let changes = sqlx::query_as::<_, (i64, String)>(
+        r#"
+        SELECT event_id, aggregate_id
+        FROM events
+        WHERE org_id = $1
+          AND aggregate_type = 'instance'
+          AND event_id > $2
+          AND event_id <= $3
+        ORDER BY event_id ASC
+        LIMIT $4
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(since)
+    .bind(checkpoint)
+    .bind(limit)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(db_error)?;
+
+    let has_more = changes.len() == limit as usize;
+    let cursor = match changes.last() {
+        Some((event_id, _)) if has_more => *event_id,
+        _ => checkpoint.max(since),
+    };
+    let touched: Vec<String> = changes
+        .into_iter()
+        .map(|(_, id)| id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let items = if touched.is_empty() {
+        Vec::new()
+    } else {
+        sqlx::query_as::<_, BackendInstance>(&format!(
+            r#"
+            SELECT d.instance_id, d.app_id, d.env_id, d.process_type,
+                   d.overlay_ipv6::TEXT AS overlay_ipv6
+            FROM instances_desired_view d
+            JOIN instances_status_view s ON d.instance_id = s.instance_id
+            WHERE d.org_id = $1
+              AND d.instance_id = ANY($2)
+              AND {ROUTABLE}
+            ORDER BY d.instance_id ASC
+            "#
+        ))
+        .bind(org_id.to_string())
+        .bind(&touched)
+        .fetch_all(state.db().pool())
+        .await
+        .map_err(db_error)?
+    };
+
+    let removed = removed_instances(&touched, &items);
+
+    Ok(Json(BackendSyncResponse {
+        version: BACKEND_SYNC_VERSION,
+        mode: BackendSyncMode::Delta,
+        cursor,
+        items,
+        removed,
+        has_more,
+        next_after: None,
+    }))
+}
+
+/// Touched instances that are not routable any more.
+fn removed_instances(touched: &[String], routable: &[BackendInstance]) -> Vec<String> {
+    let routable: BTreeSet<&str> = routable.iter().map(|i| i.instance_id.as_str()).collect();
+    touched
+        .iter()
+        .filter(|id| !routable.contains(id.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_removed_instances() {
+        let touched = vec!["inst_a".to_string(), "inst_b".to_string()];
+        let routable = vec![BackendInstance {
+            instance_id: "inst_a".to_string(),
+            app_id: "app_1".to_string(),
+            env_id: "env_1".to_string(),
+            process_type: "web".to_string(),
+            overlay_ipv6: "fd00::1".to_string(),
+        }];
+        assert_eq!(removed_instances(&touched, &routable), vec!["inst_b"]);
+    }
+
+    #[test]
+    fn test_sync_response_shape() {
+        let json = serde_json::to_value(BackendSyncResponse {
+            version: BACKEND_SYNC_VERSION,
+            mode: BackendSyncMode::Delta,
+            cursor: 42,
+            items: Vec::new(),
+            removed: vec!["inst_b".to_string()],
+            has_more: false,
+            next_after: None,
+        })
+        .unwrap();
+        assert_eq!(json["mode"], "delta");
+        assert_eq!(json["cursor"], 42);
+        assert!(json.get("next_after").is_none());
+    }
+}
@@ -0,0 +1,455 @@
+//! Org-level batch operations.
+//!
+//! `POST /v1/orgs/{org_id}/batch` applies many env-scoped operations (scale,
+//! restart) in one request. Each operation is executed as a command against
+//! its env aggregate (see `crate::aggregates::env`), so it is atomic for that
+//! aggregate; one failing operation does not roll back the others.
+//! Per-operation outcomes are reported in order.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use plfm_id::{AppId, EnvId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::aggregates::{self, CommandError, EnvAggregate, EnvCommand};
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::idempotency;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+use super::deploys::normalize_change_reason;
+use super::envs::{self, ProcessScale};
+
+/// Upper bound on operations per batch request.
+const MAX_BATCH_OPERATIONS: usize = 100;
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+/// Request to apply a batch of operations.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+/// A single env-scoped operation.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    /// Set desired replica counts (same semantics as `PUT .../scale`).
+    Scale {
+        app_id: String,
+        env_id: String,
+        processes: Vec<ProcessScale>,
+        #[serde(default)]
+        expected_version: Option<i32>,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    /// Roll all instances of the given process types.
+    Restart {
+        app_id: String,
+        env_id: String,
+        process_types: Vec<String>,
+    },
+}
+
+impl BatchOperation {
+    fn op_name(&self) -> &'static str {
+        match self {
+            BatchOperation::Scale { .. } => "scale",
+            BatchOperation::Restart { .. } => "restart",
+        }
+    }
+
+    fn env_ref(&self) -> (&str, &str) {
+        match self {
+            BatchOperation::Scale { app_id, env_id, .. }
+            | BatchOperation::Restart { app_id, env_id, .. } => (app_id, env_id),
+        }
+    }
+}
+
+/// Outcome of a single operation.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOperationStatus {
+    Succeeded,
+    Failed,
+}
+
+/// Error detail for a failed operation.
+#[derive(Debug, Serialize)]
+pub struct BatchOperationError {
+    /// HTTP status the operation would have returned on its own.
+    pub status: u16,
+    pub code: String,
+    pub message: String,
+}
+
+/// Result for a single operation, in request order.
+#[derive(Debug, Serialize)]
+pub struct BatchOperationResult {
+    pub index: usize,
+    pub op: &'static str,
+    pub app_id: String,
+    pub env_id: String,
+    pub status: BatchOperationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchOperationError>,
+}
+
+/// Response for a batch request.
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchOperationResult>,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Apply a batch of operations.
+///
+/// POST /v1/orgs/{org_id}/batch
+pub async fn execute_batch(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    Json(req): Json<BatchRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_id = ctx.actor_id.clone();
+    let endpoint_name = "orgs.batch";
+
+    let org_id_typed: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id_typed, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    if req.operations.is_empty() {
+        return Err(
+            ApiError::bad_request("invalid_operations", "operations cannot be empty")
+                .with_request_id(request_id),
+        );
+    }
+    if req.operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(ApiError::bad_request(
+            "too_many_operations",
+            format!("at most {MAX_BATCH_OPERATIONS} operations are allowed per batch"),
+        )
+        .with_request_id(request_id));
+    }
+
+    let org_scope = org_id_typed.to_string();
+    let request_hash = idempotency_key
+        .as_deref()
+        .map(|key| {
+            idempotency::request_hash(endpoint_name, &req).map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            &state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let mut results = Vec::with_capacity(req.operations.len());
+    let mut last_event_id: Option<i64> = None;
+
+    for (index, op) in req.operations.into_iter().enumerate() {
+        let (app_id, env_id) = op.env_ref();
+        let (app_id, env_id) = (app_id.to_string(), env_id.to_string());
+        let op_name = op.op_name();
+
+        let outcome = apply_operation(&state, &ctx, &org_id_typed, op).await;
+        let result = match outcome {
+            Ok(event_id) => {
+                last_event_id = Some(last_event_id.map_or(event_id, |id| id.max(event_id)));
+                BatchOperationResult {
+                    index,
+                    op: op_name,
+                    app_id,
+                    env_id,
+                    status: BatchOperationStatus::Succeeded,
+                    event_id: Some(event_id),
+                    error: None,
+                }
+            }
+            Err(err) => BatchOperationResult {
+                index,
+                op: op_name,
+                app_id,
+                env_id,
+                status: BatchOperationStatus::Failed,
+                event_id: None,
+                error: Some(BatchOperationError {
+                    status: err.status.as_u16(),
+                    code: err.problem.code,
+                    message: err.problem.detail,
+                }),
+            },
+        };
+        results.push(result);
+    }
+
+    if let Some(event_id) = last_event_id {
+        consistency::record_write(&ctx, event_id);
+    }
+
+    let succeeded = results
+        .iter()
+        .filter(|r| matches!(r.status, BatchOperationStatus::Succeeded))
+        .count();
+    let response = BatchResponse {
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    };
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to apply batch")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            &state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+// =============================================================================
+// Operations
+// =============================================================================
+
+/// Validate and append a single operation; returns the appended event_id.
+async fn apply_operation(
+    state: &AppState,
+    ctx: &RequestContext,
+    org_id: &OrgId,
+    op: BatchOperation,
+) -> Result<i64, ApiError> {
+    let request_id = ctx.request_id.as_str();
+    let (app_id, env_id) = op.env_ref();
+
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+
+    let command = match op {
+        BatchOperation::Scale {
+            processes,
+            expected_version,
+            reason,
+            ..
+        } => {
+            if expected_version.is_some_and(|v| v < 0) {
+                return Err(ApiError::bad_request(
+                    "invalid_expected_version",
+                    "expected_version must be >= 0",
+                )
+                .with_request_id(request_id.to_string()));
+            }
+
+            let current =
+                envs::load_scale_state(state, request_id, org_id, &app_id, &env_id).await?;
+            if let Some(expected) = expected_version {
+                if expected != current.resource_version {
+                    return Err(ApiError::conflict(
+                        "version_conflict",
+                        format!(
+                            "Resource version mismatch: expected {}, current {}",
+                            expected, current.resource_version
+                        ),
+                    )
+                    .with_request_id(request_id.to_string()));
+                }
+            }
+
+            let reason = normalize_change_reason(reason.as_deref()).map_err(|message| {
+                ApiError::bad_request("invalid_reason", message)
+                    .with_request_id(request_id.to_string())
+            })?;
+
+            EnvCommand::SetScale {
+                processes: processes.iter().map(ProcessScale::to_spec).collect(),
+                reason,
+            }
+        }
+        BatchOperation::Restart { process_types, .. } => {
+            ensure_process_types_deployed(
+                state,
+                request_id,
+                org_id,
+                &app_id,
+                &env_id,
+                &process_types,
+            )
+            .await?;
+
+            EnvCommand::RequestRestart { process_types }
+        }
+    };
+
+    let event_ids = aggregates::execute::<EnvAggregate, _>(
+        &state.db().event_store(),
+        ctx,
+        &env_id.to_string(),
+        command,
+    )
+    .await
+    .map_err(|e| {
+        if matches!(e, CommandError::Store(_) | CommandError::Event(_)) {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to apply batch operation");
+        }
+        ApiError::from(e).with_request_id(request_id.to_string())
+    })?;
+
+    event_ids.last().map(|id| id.value()).ok_or_else(|| {
+        ApiError::internal("internal_error", "Failed to apply operation")
+            .with_request_id(request_id.to_string())
+    })
+}
+
+/// Restarts only make sense for process types the scheduler is running.
+async fn ensure_process_types_deployed(
+    state: &AppState,
+    request_id: &str,
+    org_id: &OrgId,
+    app_id: &AppId,
+    env_id: &EnvId,
+    process_types: &[String],
+) -> Result<(), ApiError> {
+    let env_exists: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM envs_view
+            WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
+        )
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to load env");
+        ApiError::internal("internal_error", "Failed to apply operation")
+            .with_request_id(request_id.to_string())
+    })?;
+
+    if !env_exists {
+        return Err(ApiError::not_found(
+            "env_not_found",
+            format!("Environment {} not found", env_id),
+        )
+        .with_request_id(request_id.to_string()));
+    }
+
+    let deployed: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT process_type
+        FROM env_desired_releases_view
+        WHERE env_id = $1 AND process_type = ANY($2::TEXT[])
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(process_types)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to load desired releases");
+        ApiError::internal("internal_error", "Failed to apply operation")
+            .with_request_id(request_id.to_string())
+    })?;
+
+    let missing: Vec<&str> = process_types
+        .iter()
+        .filter(|p| !p.trim().is_empty() && !deployed.contains(p))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(ApiError::conflict(
+            "process_type_not_deployed",
+            format!(
+                "Process types have no desired release in this environment: {}",
+                missing.join(", ")
+            ),
+        )
+        .with_request_id(request_id.to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_request_deserialization() {
+        let json = r#"{
+            "operations": [
+                {"op": "scale", "app_id": "app_1", "env_id": "env_1",
+                 "processes": [{"process_type": "web", "desired": 3}]},
+                {"op": "restart", "app_id": "app_1", "env_id": "env_2",
+                 "process_types": ["worker"]}
+            ]
+        }"#;
+        let req: BatchRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.operations.len(), 2);
+        assert_eq!(req.operations[0].op_name(), "scale");
+        assert_eq!(req.operations[1].op_name(), "restart");
+        assert_eq!(req.operations[1].env_ref(), ("app_1", "env_2"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Guest serial console endpoints.
+//!
+//! - PUT /v1/nodes/{node_id}/instances/{instance_id}/console (node agents)
+//! - GET /v1/instances/{instance_id}/console
+//!
+//! Node agents capture each instance's serial console and upload its tail
+//! while the VM runs and once more when it exits, so kernel panics and
+//! early-boot failures stay readable after the VM is gone. Only the latest
+//! upload per instance is kept.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_events::ActorType;
+use plfm_id::{InstanceId, NodeId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+use super::node_enrollment::verify_node_request;
+
+/// Largest console tail stored per instance; longer uploads keep their end.
+const MAX_CONSOLE_BYTES: usize = 64 * 1024;
+
+/// Routes merged into `/v1/instances`.
+pub fn instance_routes() -> Router<AppState> {
+    Router::new().route("/{instance_id}/console", get(get_console))
+}
+
+/// Routes merged into `/v1/nodes`.
+pub fn node_routes() -> Router<AppState> {
+    Router::new().route(
+        "/{node_id}/instances/{instance_id}/console",
+        put(upload_console),
+    )
+}
+
+/// Console tail uploaded by a node agent.
+#[derive(Debug, Deserialize)]
+pub struct UploadConsoleRequest {
+    /// Boot the output belongs to (the file may also hold earlier boots).
+    pub boot_id: String,
+    pub content: String,
+    /// Whether the agent cut off older output.
+    #[serde(default)]
+    pub truncated: bool,
+    /// When the console was last written.
+    pub captured_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadConsoleResponse {
+    /// False when a newer capture is already stored.
+    pub accepted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsoleQuery {
+    /// Return at most this many bytes from the end (default and max 64 KiB).
+    pub tail_bytes: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsoleResponse {
+    pub instance_id: String,
+    pub node_id: String,
+    pub boot_id: String,
+    pub content: String,
+    /// Whether older output exists beyond `content`.
+    pub truncated: bool,
+    pub captured_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ConsoleRow {
+    node_id: String,
+    boot_id: String,
+    content: String,
+    truncated: bool,
+    captured_at: DateTime<Utc>,
+}
+
+/// PUT /v1/nodes/{node_id}/instances/{instance_id}/console
+async fn upload_console(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path((node_id, instance_id)): Path<(String, String)>,
+    Json(req): Json<UploadConsoleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    if ctx.actor_type != ActorType::System {
+        return Err(ApiError::forbidden(
+            "forbidden",
+            "This endpoint is only available to system actors",
+        )
+        .with_request_id(request_id));
+    }
+
+    let _node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    let _instance_id_typed: InstanceId = instance_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_instance_id", "Invalid instance ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let internal = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, instance_id = %instance_id, "Failed to store instance console");
+        ApiError::internal("internal_error", "Failed to store console")
+            .with_request_id(request_id.clone())
+    };
+
+    let assigned: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM instances_desired_view WHERE instance_id = $1 AND node_id = $2)",
+    )
+    .bind(&instance_id)
+    .bind(&node_id)
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(internal)?;
+    if !assigned {
+        return Err(
+            ApiError::not_found("instance_not_found", "Instance not found on this node")
+                .with_request_id(request_id.clone()),
+        );
+    }
+
+    let (content, cut) = tail(&req.content, MAX_CONSOLE_BYTES);
+    let result = sqlx::query(
+        r#"
+        INSERT INTO instance_console (instance_id, node_id, boot_id, content, truncated, captured_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (instance_id) DO UPDATE SET
+            node_id = EXCLUDED.node_id,
+            boot_id = EXCLUDED.boot_id,
+            content = EXCLUDED.content,
+            truncated = EXCLUDED.truncated,
+            captured_at = EXCLUDED.captured_at,
+            uploaded_at = now()
+        WHERE instance_console.captured_at <= EXCLUDED.captured_at
+        "#,
+    )
+    .bind(&instance_id)
+    .bind(&node_id)
+    .bind(&req.boot_id)
+    .bind(content)
+    .bind(req.truncated || cut)
+    .bind(req.captured_at)
+    .execute(state.db().pool())
+    .await
+    .map_err(internal)?;
+
+    Ok(Json(UploadConsoleResponse {
+        accepted: result.rows_affected() > 0,
+    }))
+}
+
+/// GET /v1/instances/{instance_id}/console
+async fn get_console(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(instance_id): Path<String>,
+    Query(query): Query<ConsoleQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let _instance_id: InstanceId = instance_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_instance_id", "Invalid instance ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let internal = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, instance_id = %instance_id, "Failed to load instance console");
+        ApiError::internal("internal_error", "Failed to load console")
+            .with_request_id(request_id.clone())
+    };
+
+    let org_id: String =
+        sqlx::query_scalar("SELECT org_id FROM instances_desired_view WHERE instance_id = $1")
+            .bind(&instance_id)
+            .fetch_optional(state.db().pool())
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "instance_not_found",
+                    format!("Instance {} not found", instance_id),
+                )
+                .with_request_id(request_id.clone())
+            })?;
+
+    // Node agents and operators see every instance; users only their org's.
+    if ctx.actor_type != ActorType::System {
+        let org_id: OrgId = org_id.parse().map_err(|_| {
+            ApiError::internal("internal_error", "Invalid org_id in instances_desired_view")
+                .with_request_id(request_id.clone())
+        })?;
+        authz::require_org_member(&state, &org_id, &ctx).await?;
+    }
+
+    let row = sqlx::query_as::<_, ConsoleRow>(
+        r#"
+        SELECT node_id, boot_id, content, truncated, captured_at
+        FROM instance_console
+        WHERE instance_id = $1
+        "#,
+    )
+    .bind(&instance_id)
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(internal)?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "console_not_found",
+            format!("No console output captured for instance {}", instance_id),
+        )
+        .with_request_id(request_id.clone())
+    })?;
+
+    let tail_bytes = query
+        .tail_bytes
+        .unwrap_or(MAX_CONSOLE_BYTES)
+        .clamp(1, MAX_CONSOLE_BYTES);
+    let (content, cut) = tail(&row.content, tail_bytes);
+
+    Ok(Json(ConsoleResponse {
+        content: content.to_string(),
+        truncated: row.truncated || cut,
+        instance_id,
+        node_id: row.node_id,
+        boot_id: row.boot_id,
+        captured_at: row.captured_at,
+    }))
+}
+
+/// The last `max_bytes` of `content`, starting at a line boundary when one
+/// is available, and whether anything was cut.
+fn tail(content: &str, max_bytes: usize) -> (&str, bool) {
+    if content.len() <= max_bytes {
+        return (content, false);
+    }
+    let mut start = content.len() - max_bytes;
+    while !content.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &content[start..];
+    match tail.find('\n') {
+        Some(newline) if newline + 1 < tail.len() => (&tail[newline + 1..], true),
+        _ => (tail, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail() {
+        assert_eq!(tail("boot\npanic\n", 64), ("boot\npanic\n", false));
+        assert_eq!(tail("boot\nkernel\npanic\n", 10), ("panic\n", true));
+        // No full line fits: keep the partial one.
+        assert_eq!(tail("boot\nkernel panic\n", 8), ("l panic\n", true));
+        // Never split a character.
+        assert_eq!(tail("ab\u{e9}", 1), ("", true));
+        assert_eq!(tail("ab\u{e9}", 2), ("\u{e9}", true));
+    }
+}
@@ -15,6 +15,7 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 use crate::api::error::ApiError;
+use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
 use crate::state::AppState;
 
@@ -26,6 +27,7 @@ pub fn routes() -> Router<AppState> {
             post(reset_projection),
         )
         .route("/idempotency/cleanup", post(cleanup_idempotency))
+        .route("/idempotency/stats", get(idempotency_stats))
 }
 
 #[derive(Debug, Serialize)]
@@ -137,3 +139,10 @@ async fn cleanup_idempotency(
         Json(serde_json::json!({ "ok": true, "rows_deleted": rows_deleted })),
     ))
 }
+
+async fn idempotency_stats(_ctx: RequestContext) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "ttl_seconds": idempotency::idempotency_ttl().as_secs(),
+        "counters": idempotency::METRICS.snapshot(),
+    }))
+}
@@ -1,6 +1,7 @@
 //! Deploy API endpoints.
 //!
-//! Provides operations for creating and querying deploys.
+//! Provides operations for creating and querying deploys, and for controlling
+//! an in-progress rollout (pause, resume, promote).
 //! A deploy promotes a release to an environment.
 
 use axum::{
@@ -11,13 +12,15 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use plfm_events::AggregateType;
+use plfm_events::{event_types, AggregateType, ReleaseMetadata};
 use plfm_id::{AppId, DeployId, EnvId, OrgId, ReleaseId};
 use serde::{Deserialize, Serialize};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
+use crate::api::preconditions::{self, Preconditions};
 use crate::api::request_context::RequestContext;
 use crate::db::AppendEvent;
 use crate::state::AppState;
@@ -30,8 +33,14 @@ pub fn routes() -> Router<AppState> {
         .route("/", post(create_deploy))
         .route("/", get(list_deploys))
         .route("/{deploy_id}", get(get_deploy))
+        .route("/{deploy_id}/pause", post(pause_deploy))
+        .route("/{deploy_id}/resume", post(resume_deploy))
+        .route("/{deploy_id}/promote", post(promote_deploy))
 }
 
+/// Longest change reason accepted on deploys, rollbacks and scale changes.
+pub(super) const MAX_CHANGE_REASON_LEN: usize = 1000;
+
 // =============================================================================
 // Request/Response Types
 // =============================================================================
@@ -49,6 +58,10 @@ pub struct CreateDeployRequest {
     /// Deploy strategy (v1 only supports rolling).
     #[serde(default)]
     pub strategy: DeployStrategy,
+
+    /// Why the deploy is being made (changelog entry, ticket, incident).
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 /// Deploy strategy (v1).
@@ -69,6 +82,10 @@ impl Default for DeployStrategy {
 pub struct RollbackRequest {
     /// Release ID to roll back to.
     pub release_id: String,
+
+    /// Why the rollback is being made.
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 /// Response for a single deploy.
@@ -102,6 +119,17 @@ pub struct DeployResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 
+    /// Aggregated failure reason codes when the deploy failed or was halted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_reason: Option<String>,
+
+    /// Why the deploy was made, as given by the caller.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// Whether the rollout was promoted to 100% (skips rolling steps).
+    pub promoted: bool,
+
     /// Resource version for optimistic concurrency.
     pub resource_version: i32,
 
@@ -174,6 +202,10 @@ async fn create_deploy(
             .with_request_id(request_id.clone())
     })?;
 
+    let reason = normalize_change_reason(req.reason.as_deref()).map_err(|message| {
+        ApiError::bad_request("invalid_reason", message).with_request_id(request_id.clone())
+    })?;
+
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -231,27 +263,8 @@ async fn create_deploy(
     }
 
     // Validate release exists and belongs to app
-    let release_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM releases_view WHERE release_id = $1 AND org_id = $2 AND app_id = $3)",
-    )
-    .bind(release_id.to_string())
-    .bind(org_id.to_string())
-    .bind(app_id.to_string())
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to check release existence");
-        ApiError::internal("internal_error", "Failed to verify release")
-            .with_request_id(request_id.clone())
-    })?;
-
-    if !release_exists {
-        return Err(ApiError::not_found(
-            "release_not_found",
-            format!("Release {} not found in application {}", release_id, app_id),
-        )
-        .with_request_id(request_id.clone()));
-    }
+    let release_metadata =
+        load_release_metadata(&state, &request_id, &org_id, &app_id, &release_id).await?;
 
     let deploy_id = DeployId::new();
     let kind = "deploy";
@@ -283,6 +296,8 @@ async fn create_deploy(
             "process_types": process_types,
             "strategy": req.strategy,
             "initiated_at": Utc::now().to_rfc3339(),
+            "release_metadata": release_metadata,
+            "reason": reason,
         }),
         ..Default::default()
     };
@@ -295,25 +310,12 @@ async fn create_deploy(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "deploys",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "deploys", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, DeployRow>(
         r#"
         SELECT deploy_id, org_id, app_id, env_id, kind, release_id, process_types,
-               status, message, resource_version, created_at, updated_at
+               status, message, failed_reason, reason, promoted, resource_version, created_at, updated_at
         FROM deploys_view
         WHERE deploy_id = $1 AND org_id = $2 AND app_id = $3 AND env_id = $4
         "#,
@@ -401,6 +403,10 @@ pub async fn create_rollback(
             .with_request_id(request_id.clone())
     })?;
 
+    let reason = normalize_change_reason(req.reason.as_deref()).map_err(|message| {
+        ApiError::bad_request("invalid_reason", message).with_request_id(request_id.clone())
+    })?;
+
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -458,27 +464,8 @@ pub async fn create_rollback(
     }
 
     // Validate release exists and belongs to app
-    let release_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM releases_view WHERE release_id = $1 AND org_id = $2 AND app_id = $3)",
-    )
-    .bind(release_id.to_string())
-    .bind(org_id.to_string())
-    .bind(app_id.to_string())
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to check release existence");
-        ApiError::internal("internal_error", "Failed to verify release")
-            .with_request_id(request_id.clone())
-    })?;
-
-    if !release_exists {
-        return Err(ApiError::not_found(
-            "release_not_found",
-            format!("Release {} not found in application {}", release_id, app_id),
-        )
-        .with_request_id(request_id.clone()));
-    }
+    let release_metadata =
+        load_release_metadata(&state, &request_id, &org_id, &app_id, &release_id).await?;
 
     let deploy_id = DeployId::new();
     let process_types = vec!["web".to_string()];
@@ -508,6 +495,8 @@ pub async fn create_rollback(
             "process_types": process_types,
             "strategy": DeployStrategy::Rolling,
             "initiated_at": Utc::now().to_rfc3339(),
+            "release_metadata": release_metadata,
+            "reason": reason,
         }),
         ..Default::default()
     };
@@ -519,25 +508,12 @@ pub async fn create_rollback(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "deploys",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "deploys", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, DeployRow>(
         r#"
         SELECT deploy_id, org_id, app_id, env_id, kind, release_id, process_types,
-               status, message, resource_version, created_at, updated_at
+               status, message, failed_reason, reason, promoted, resource_version, created_at, updated_at
         FROM deploys_view
         WHERE deploy_id = $1 AND org_id = $2 AND app_id = $3 AND env_id = $4
         "#,
@@ -631,7 +607,7 @@ async fn list_deploys(
     let rows = sqlx::query_as::<_, DeployRow>(
         r#"
         SELECT deploy_id, org_id, app_id, env_id, kind, release_id, process_types,
-               status, message, resource_version, created_at, updated_at
+               status, message, failed_reason, reason, promoted, resource_version, created_at, updated_at
         FROM deploys_view
         WHERE org_id = $1 AND app_id = $2 AND env_id = $3
           AND ($4::TEXT IS NULL OR deploy_id > $4)
@@ -699,7 +675,7 @@ async fn get_deploy(
     let row = sqlx::query_as::<_, DeployRow>(
         r#"
         SELECT deploy_id, org_id, app_id, env_id, kind, release_id, process_types,
-               status, message, resource_version, created_at, updated_at
+               status, message, failed_reason, reason, promoted, resource_version, created_at, updated_at
         FROM deploys_view
         WHERE org_id = $1 AND app_id = $2 AND env_id = $3 AND deploy_id = $4
         "#,
@@ -726,6 +702,391 @@ async fn get_deploy(
     }
 }
 
+/// Rollout control action applied to an existing deploy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RolloutAction {
+    Pause,
+    Resume,
+    Promote,
+}
+
+impl RolloutAction {
+    fn endpoint_name(self) -> &'static str {
+        match self {
+            RolloutAction::Pause => "deploys.pause",
+            RolloutAction::Resume => "deploys.resume",
+            RolloutAction::Promote => "deploys.promote",
+        }
+    }
+}
+
+/// Event emitted by a rollout action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RolloutEvent {
+    /// `deploy.status_changed` with (status, message).
+    Status(&'static str, &'static str),
+    /// `deploy.promoted`.
+    Promoted,
+}
+
+/// Events a rollout action emits for a deploy in `status`.
+///
+/// Returns `Ok(None)` when the action is a no-op for the current state, and
+/// `Err(())` when the deploy is terminal. A deploy halted by its failure budget
+/// is terminal; recover by rolling back or creating a new deploy.
+fn rollout_transition(
+    action: RolloutAction,
+    status: &str,
+    promoted: bool,
+) -> Result<Option<Vec<RolloutEvent>>, ()> {
+    if matches!(status, "succeeded" | "failed" | "halted") {
+        return Err(());
+    }
+
+    let paused = status == "paused";
+    let events = match action {
+        RolloutAction::Pause if paused => None,
+        RolloutAction::Pause => Some(vec![RolloutEvent::Status("paused", "Rollout paused")]),
+        RolloutAction::Resume if paused => {
+            Some(vec![RolloutEvent::Status("rolling", "Rollout resumed")])
+        }
+        RolloutAction::Resume => None,
+        RolloutAction::Promote if promoted && !paused => None,
+        RolloutAction::Promote => {
+            let mut events = Vec::new();
+            if paused {
+                events.push(RolloutEvent::Status("rolling", "Rollout resumed"));
+            }
+            if !promoted {
+                events.push(RolloutEvent::Promoted);
+            }
+            Some(events)
+        }
+    };
+    Ok(events)
+}
+
+/// Pause an in-progress rollout.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys/{deploy_id}/pause
+async fn pause_deploy(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
+) -> Result<Response, ApiError> {
+    control_rollout(state, ctx, path, preconditions, RolloutAction::Pause).await
+}
+
+/// Resume a paused rollout.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys/{deploy_id}/resume
+async fn resume_deploy(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
+) -> Result<Response, ApiError> {
+    control_rollout(state, ctx, path, preconditions, RolloutAction::Resume).await
+}
+
+/// Promote a rollout to 100% (resuming it if paused).
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys/{deploy_id}/promote
+async fn promote_deploy(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
+) -> Result<Response, ApiError> {
+    control_rollout(state, ctx, path, preconditions, RolloutAction::Promote).await
+}
+
+async fn control_rollout(
+    state: AppState,
+    ctx: RequestContext,
+    (org_id, app_id, env_id, deploy_id): (String, String, String, String),
+    preconditions: Preconditions,
+    action: RolloutAction,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_type = ctx.actor_type;
+    let actor_id = ctx.actor_id.clone();
+    let endpoint_name = action.endpoint_name();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let deploy_id: DeployId = deploy_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_deploy_id", "Invalid deploy ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let org_scope = org_id.to_string();
+    let request_hash = idempotency_key
+        .as_deref()
+        .map(|key| {
+            let hash_input = serde_json::json!({
+                "app_id": app_id.to_string(),
+                "env_id": env_id.to_string(),
+                "deploy_id": deploy_id.to_string(),
+            });
+            idempotency::request_hash(endpoint_name, &hash_input)
+                .map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            &state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let current = load_deploy(&state, &request_id, &org_id, &app_id, &env_id, &deploy_id).await?;
+    preconditions.check(None, current.resource_version, false, &request_id)?;
+
+    let transitions =
+        rollout_transition(action, &current.status, current.promoted).map_err(|_| {
+            ApiError::conflict(
+                "invalid_deploy_state",
+                format!(
+                    "Deploy {} is {} and can no longer be changed",
+                    deploy_id, current.status
+                ),
+            )
+            .with_request_id(request_id.clone())
+        })?;
+
+    let row = match transitions {
+        Some(transitions) if !transitions.is_empty() => {
+            let event_store = state.db().event_store();
+            let current_seq = event_store
+                .get_latest_aggregate_seq(&AggregateType::Deploy, &deploy_id.to_string())
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, request_id = %request_id, "Failed to get aggregate sequence");
+                    ApiError::internal("internal_error", "Failed to update deploy")
+                        .with_request_id(request_id.clone())
+                })?
+                .unwrap_or(0);
+
+            let now = Utc::now().to_rfc3339();
+            let events = transitions
+                .into_iter()
+                .enumerate()
+                .map(|(offset, transition)| {
+                    let (event_type, payload) = match transition {
+                        RolloutEvent::Promoted => (
+                            event_types::DEPLOY_PROMOTED,
+                            serde_json::json!({
+                                "deploy_id": deploy_id.to_string(),
+                                "org_id": org_id.to_string(),
+                                "env_id": env_id.to_string(),
+                            }),
+                        ),
+                        RolloutEvent::Status(status, message) => (
+                            event_types::DEPLOY_STATUS_CHANGED,
+                            serde_json::json!({
+                                "deploy_id": deploy_id.to_string(),
+                                "org_id": org_id.to_string(),
+                                "env_id": env_id.to_string(),
+                                "status": status,
+                                "message": message,
+                                "updated_at": &now,
+                            }),
+                        ),
+                    };
+                    AppendEvent {
+                        aggregate_type: AggregateType::Deploy,
+                        aggregate_id: deploy_id.to_string(),
+                        aggregate_seq: current_seq + 1 + offset as i32,
+                        event_type: event_type.to_string(),
+                        event_version: 1,
+                        actor_type,
+                        actor_id: actor_id.clone(),
+                        org_id: Some(org_id),
+                        request_id: request_id.clone(),
+                        idempotency_key: idempotency_key.clone(),
+                        app_id: Some(app_id),
+                        env_id: Some(env_id),
+                        correlation_id: None,
+                        causation_id: None,
+                        payload,
+                        ..Default::default()
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let event_ids = event_store.append_batch(events).await.map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, "Failed to update deploy");
+                match e {
+                    crate::db::DbError::SequenceConflict { .. } => ApiError::conflict(
+                        "version_conflict",
+                        "Concurrent deploy update detected; retry",
+                    ),
+                    _ => ApiError::internal("internal_error", "Failed to update deploy"),
+                }
+                .with_request_id(request_id.clone())
+            })?;
+
+            if let Some(event_id) = event_ids.last() {
+                consistency::wait_for_write(&state, &ctx, "deploys", event_id.value()).await?;
+            }
+
+            load_deploy(&state, &request_id, &org_id, &app_id, &env_id, &deploy_id).await?
+        }
+        _ => current,
+    };
+
+    let response = DeployResponse::from(row);
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to update deploy")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            &state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok(preconditions::with_etag(
+        response.resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
+}
+
+/// Trim a caller-supplied change reason; blank reasons are `None`.
+///
+/// Reasons are free text (newlines and tabs allowed) of at most
+/// [`MAX_CHANGE_REASON_LEN`] characters.
+pub(super) fn normalize_change_reason(reason: Option<&str>) -> Result<Option<String>, String> {
+    let Some(reason) = reason.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(None);
+    };
+    if reason.chars().count() > MAX_CHANGE_REASON_LEN {
+        return Err(format!(
+            "reason must be at most {MAX_CHANGE_REASON_LEN} characters"
+        ));
+    }
+    if reason
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return Err("reason must not contain control characters".to_string());
+    }
+    Ok(Some(reason.to_string()))
+}
+
+/// Load the metadata of a release of the app; `404 release_not_found` if the
+/// release does not exist. Empty metadata is `None`.
+async fn load_release_metadata(
+    state: &AppState,
+    request_id: &str,
+    org_id: &OrgId,
+    app_id: &AppId,
+    release_id: &ReleaseId,
+) -> Result<Option<ReleaseMetadata>, ApiError> {
+    let metadata: serde_json::Value = sqlx::query_scalar(
+        "SELECT metadata FROM releases_view WHERE release_id = $1 AND org_id = $2 AND app_id = $3",
+    )
+    .bind(release_id.to_string())
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to check release existence");
+        ApiError::internal("internal_error", "Failed to verify release")
+            .with_request_id(request_id.to_string())
+    })?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "release_not_found",
+            format!("Release {} not found in application {}", release_id, app_id),
+        )
+        .with_request_id(request_id.to_string())
+    })?;
+
+    let metadata: ReleaseMetadata = serde_json::from_value(metadata).unwrap_or_default();
+    Ok((!metadata.is_empty()).then_some(metadata))
+}
+
+async fn load_deploy(
+    state: &AppState,
+    request_id: &str,
+    org_id: &OrgId,
+    app_id: &AppId,
+    env_id: &EnvId,
+    deploy_id: &DeployId,
+) -> Result<DeployRow, ApiError> {
+    sqlx::query_as::<_, DeployRow>(
+        r#"
+        SELECT deploy_id, org_id, app_id, env_id, kind, release_id, process_types,
+               status, message, failed_reason, reason, promoted, resource_version, created_at, updated_at
+        FROM deploys_view
+        WHERE org_id = $1 AND app_id = $2 AND env_id = $3 AND deploy_id = $4
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .bind(env_id.to_string())
+    .bind(deploy_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, deploy_id = %deploy_id, "Failed to get deploy");
+        ApiError::internal("internal_error", "Failed to get deploy")
+            .with_request_id(request_id.to_string())
+    })?
+    .ok_or_else(|| {
+        ApiError::not_found("deploy_not_found", format!("Deploy {} not found", deploy_id))
+            .with_request_id(request_id.to_string())
+    })
+}
+
 // =============================================================================
 // Database Row Types
 // =============================================================================
@@ -741,6 +1102,9 @@ struct DeployRow {
     process_types: serde_json::Value,
     status: String,
     message: Option<String>,
+    failed_reason: Option<String>,
+    reason: Option<String>,
+    promoted: bool,
     resource_version: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
@@ -759,6 +1123,9 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for DeployRow {
             process_types: row.try_get("process_types")?,
             status: row.try_get("status")?,
             message: row.try_get("message")?,
+            failed_reason: row.try_get("failed_reason")?,
+            reason: row.try_get("reason")?,
+            promoted: row.try_get("promoted")?,
             resource_version: row.try_get("resource_version")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
@@ -781,6 +1148,9 @@ impl From<DeployRow> for DeployResponse {
             process_types,
             status: row.status,
             message: row.message,
+            failed_reason: row.failed_reason,
+            reason: row.reason,
+            promoted: row.promoted,
             resource_version: row.resource_version,
             created_at: row.created_at,
             updated_at: row.updated_at,
@@ -828,6 +1198,9 @@ mod tests {
             process_types: vec!["web".to_string()],
             status: "queued".to_string(),
             message: None,
+            failed_reason: None,
+            reason: Some("Fix checkout timeout".to_string()),
+            promoted: false,
             resource_version: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -836,5 +1209,58 @@ mod tests {
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"id\":\"dep_123\""));
         assert!(json.contains("\"status\":\"queued\""));
+        assert!(json.contains("\"reason\":\"Fix checkout timeout\""));
+    }
+
+    #[test]
+    fn test_normalize_change_reason() {
+        assert_eq!(normalize_change_reason(None), Ok(None));
+        assert_eq!(normalize_change_reason(Some("  ")), Ok(None));
+        assert_eq!(
+            normalize_change_reason(Some(" Bump timeout\n\nSee INC-42 ")),
+            Ok(Some("Bump timeout\n\nSee INC-42".to_string()))
+        );
+        assert!(normalize_change_reason(Some("bell\u{7}")).is_err());
+        let long = "x".repeat(MAX_CHANGE_REASON_LEN + 1);
+        assert!(normalize_change_reason(Some(&long)).is_err());
+        let max = "é".repeat(MAX_CHANGE_REASON_LEN);
+        assert!(normalize_change_reason(Some(&max)).is_ok());
+    }
+
+    #[test]
+    fn test_rollout_transitions() {
+        assert_eq!(
+            rollout_transition(RolloutAction::Pause, "rolling", false),
+            Ok(Some(vec![RolloutEvent::Status("paused", "Rollout paused")]))
+        );
+        assert_eq!(
+            rollout_transition(RolloutAction::Pause, "paused", false),
+            Ok(None)
+        );
+        assert_eq!(
+            rollout_transition(RolloutAction::Resume, "paused", false),
+            Ok(Some(vec![RolloutEvent::Status(
+                "rolling",
+                "Rollout resumed"
+            )]))
+        );
+        assert_eq!(
+            rollout_transition(RolloutAction::Resume, "rolling", false),
+            Ok(None)
+        );
+        assert_eq!(
+            rollout_transition(RolloutAction::Promote, "paused", false),
+            Ok(Some(vec![
+                RolloutEvent::Status("rolling", "Rollout resumed"),
+                RolloutEvent::Promoted
+            ]))
+        );
+        assert_eq!(
+            rollout_transition(RolloutAction::Promote, "rolling", true),
+            Ok(None)
+        );
+        assert!(rollout_transition(RolloutAction::Pause, "succeeded", false).is_err());
+        assert!(rollout_transition(RolloutAction::Promote, "failed", false).is_err());
+        assert!(rollout_transition(RolloutAction::Resume, "halted", false).is_err());
     }
 }
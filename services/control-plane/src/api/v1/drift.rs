@@ -0,0 +1,143 @@
+//! Drift report endpoint.
+//!
+//! GET /v1/_admin/drift lists instances whose desired state disagrees with
+//! their node's reported inventory, as last found by the drift detector,
+//! plus the age of each node's inventory.
+//!
+//! See: docs/specs/scheduler/reconciliation-loop.md
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+pub fn admin_routes() -> Router<AppState> {
+    Router::new().route("/drift", get(drift_report))
+}
+
+#[derive(Debug, Deserialize)]
+struct DriftQuery {
+    node_id: Option<String>,
+    kind: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DriftItem {
+    instance_id: String,
+    node_id: String,
+    kind: String,
+    org_id: Option<String>,
+    env_id: Option<String>,
+    desired_state: Option<String>,
+    reported_status: Option<String>,
+    first_detected_at: DateTime<Utc>,
+    last_detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct NodeInventory {
+    node_id: String,
+    reported_at: DateTime<Utc>,
+    reported_instances: i32,
+}
+
+impl<'r> FromRow<'r, PgRow> for DriftItem {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            instance_id: row.try_get("instance_id")?,
+            node_id: row.try_get("node_id")?,
+            kind: row.try_get("kind")?,
+            org_id: row.try_get("org_id")?,
+            env_id: row.try_get("env_id")?,
+            desired_state: row.try_get("desired_state")?,
+            reported_status: row.try_get("reported_status")?,
+            first_detected_at: row.try_get("first_detected_at")?,
+            last_detected_at: row.try_get("last_detected_at")?,
+        })
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for NodeInventory {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            node_id: row.try_get("node_id")?,
+            reported_at: row.try_get("reported_at")?,
+            reported_instances: row.try_get("reported_instances")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DriftReportResponse {
+    items: Vec<DriftItem>,
+    /// Latest inventory per node; nodes missing here are not checked.
+    nodes: Vec<NodeInventory>,
+}
+
+/// GET /v1/_admin/drift
+async fn drift_report(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<DriftQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    if let Some(kind) = query.kind.as_deref() {
+        if !matches!(kind, "not_reported" | "not_desired") {
+            return Err(ApiError::bad_request(
+                "invalid_kind",
+                "kind must be not_reported or not_desired",
+            )
+            .with_request_id(request_id));
+        }
+    }
+
+    let internal = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to load drift report");
+        ApiError::internal("internal_error", "Failed to load drift report")
+            .with_request_id(request_id.clone())
+    };
+
+    let items = sqlx::query_as::<_, DriftItem>(
+        r#"
+        SELECT instance_id, node_id, kind, org_id, env_id, desired_state, reported_status,
+               first_detected_at, last_detected_at
+        FROM instance_drift
+        WHERE ($1::TEXT IS NULL OR node_id = $1)
+          AND ($2::TEXT IS NULL OR kind = $2)
+        ORDER BY first_detected_at, node_id, instance_id
+        "#,
+    )
+    .bind(&query.node_id)
+    .bind(&query.kind)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(internal)?;
+
+    let nodes = sqlx::query_as::<_, NodeInventory>(
+        r#"
+        SELECT node_id, reported_at,
+               (SELECT COUNT(*) FROM jsonb_object_keys(instances))::INT AS reported_instances
+        FROM node_instance_reports
+        WHERE ($1::TEXT IS NULL OR node_id = $1)
+        ORDER BY node_id
+        "#,
+    )
+    .bind(&query.node_id)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(internal)?;
+
+    Ok(Json(DriftReportResponse { items, nodes }))
+}
@@ -0,0 +1,689 @@
+//! Env configuration history endpoints.
+//!
+//! Reconstructs an env's configuration (desired release, scale, secrets
+//! version, config vars, process resources, routes) from its events:
+//! - GET .../envs/{env_id}/config/history lists configuration changes
+//! - GET .../envs/{env_id}/config/diff compares the configuration at two
+//!   points in time
+//!
+//! Configuration is flattened to `key -> value` pairs (`release_id`,
+//! `scale.<process_type>`, `secrets.version_id`, `config_vars.<key>`,
+//! `resources.<process_type>.<field>`, `routes.<route_id>.<field>`), so a
+//! diff is a plain key comparison.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_events::event_types;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::db::EventRow;
+use crate::state::AppState;
+
+use super::scope::{resolve_env, EnvPath, EnvState};
+
+/// Upper bound on events folded for a diff.
+const DIFF_MAX_EVENTS: i64 = 10_000;
+
+/// Create env config routes.
+///
+/// Nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/history", get(get_history))
+        .route("/diff", get(get_diff))
+}
+
+/// Kinds of configuration change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ChangeKind {
+    Release,
+    Scale,
+    Secrets,
+    ConfigVars,
+    Resources,
+    Route,
+}
+
+impl ChangeKind {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "release" => Some(Self::Release),
+            "scale" => Some(Self::Scale),
+            "secrets" => Some(Self::Secrets),
+            "config_vars" => Some(Self::ConfigVars),
+            "resources" => Some(Self::Resources),
+            "route" => Some(Self::Route),
+            _ => None,
+        }
+    }
+
+    fn event_types(self) -> &'static [&'static str] {
+        match self {
+            Self::Release => &[
+                event_types::DEPLOY_CREATED,
+                event_types::ENV_DESIRED_RELEASE_SET,
+            ],
+            Self::Scale => &[event_types::ENV_SCALE_SET],
+            Self::Secrets => &[event_types::SECRET_BUNDLE_VERSION_SET],
+            Self::ConfigVars => &[event_types::ENV_CONFIG_VARS_SET],
+            Self::Resources => &[event_types::ENV_PROCESS_RESOURCES_SET],
+            Self::Route => &[
+                event_types::ROUTE_CREATED,
+                event_types::ROUTE_UPDATED,
+                event_types::ROUTE_DELETED,
+            ],
+        }
+    }
+
+    fn of_event(event_type: &str) -> Option<Self> {
+        [
+            Self::Release,
+            Self::Scale,
+            Self::Secrets,
+            Self::ConfigVars,
+            Self::Resources,
+            Self::Route,
+        ]
+        .into_iter()
+        .find(|kind| kind.event_types().contains(&event_type))
+    }
+}
+
+fn all_event_types() -> Vec<&'static str> {
+    [
+        ChangeKind::Release,
+        ChangeKind::Scale,
+        ChangeKind::Secrets,
+        ChangeKind::ConfigVars,
+        ChangeKind::Resources,
+        ChangeKind::Route,
+    ]
+    .into_iter()
+    .flat_map(|kind| kind.event_types().iter().copied())
+    .collect()
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    /// Return changes with event_id > after_event_id.
+    after_event_id: Option<i64>,
+    limit: Option<i64>,
+    /// Only changes of this kind (release, scale, secrets, config_vars,
+    /// resources, route).
+    kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiffQuery {
+    /// RFC 3339.
+    from: DateTime<Utc>,
+    /// RFC 3339; defaults to now.
+    to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryItem {
+    event_id: i64,
+    occurred_at: DateTime<Utc>,
+    kind: ChangeKind,
+    event_type: String,
+    actor_type: String,
+    actor_id: String,
+    summary: String,
+    /// Why the change was made, when the caller gave a reason (deploys and
+    /// scale changes).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// Configuration keys set by this change (empty for route deletion).
+    changes: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryResponse {
+    items: Vec<HistoryItem>,
+    next_cursor: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ConfigChange {
+    key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffResponse {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    changes: Vec<ConfigChange>,
+}
+
+// =============================================================================
+// Configuration folding
+// =============================================================================
+
+/// Flattened env configuration.
+type ConfigSnapshot = BTreeMap<String, serde_json::Value>;
+
+/// Configuration keys an event sets.
+fn event_changes(event: &EventRow) -> BTreeMap<String, serde_json::Value> {
+    let payload = &event.payload;
+    let mut changes = BTreeMap::new();
+    match event.event_type.as_str() {
+        event_types::DEPLOY_CREATED | event_types::ENV_DESIRED_RELEASE_SET => {
+            if let Some(release_id) = payload.get("release_id") {
+                changes.insert("release_id".to_string(), release_id.clone());
+            }
+        }
+        event_types::ENV_SCALE_SET => {
+            let scales = payload.get("scales").and_then(|s| s.as_array());
+            for entry in scales.into_iter().flatten() {
+                let (Some(process_type), Some(desired)) = (
+                    entry.get("process_type").and_then(|p| p.as_str()),
+                    entry.get("desired"),
+                ) else {
+                    continue;
+                };
+                changes.insert(format!("scale.{process_type}"), desired.clone());
+            }
+        }
+        event_types::SECRET_BUNDLE_VERSION_SET => {
+            if let Some(version_id) = payload.get("version_id") {
+                changes.insert("secrets.version_id".to_string(), version_id.clone());
+            }
+        }
+        event_types::ENV_CONFIG_VARS_SET => {
+            let vars = payload.get("vars").and_then(|v| v.as_object());
+            for (key, value) in vars.into_iter().flatten() {
+                changes.insert(format!("config_vars.{key}"), value.clone());
+            }
+        }
+        event_types::ENV_PROCESS_RESOURCES_SET => {
+            let Some(process_type) = payload.get("process_type").and_then(|p| p.as_str()) else {
+                return changes;
+            };
+            for field in ["cpu_request", "memory_limit_bytes", "ephemeral_disk_bytes"] {
+                if let Some(value) = payload.get(field).filter(|v| !v.is_null()) {
+                    changes.insert(format!("resources.{process_type}.{field}"), value.clone());
+                }
+            }
+        }
+        event_types::ROUTE_CREATED | event_types::ROUTE_UPDATED => {
+            let Some(route_id) = payload.get("route_id").and_then(|r| r.as_str()) else {
+                return changes;
+            };
+            for field in [
+                "hostname",
+                "listen_port",
+                "backend_process_type",
+                "backend_port",
+                "proxy_protocol",
+                "ipv4_required",
+                "internal",
+            ] {
+                if let Some(value) = payload.get(field).filter(|v| !v.is_null()) {
+                    changes.insert(format!("routes.{route_id}.{field}"), value.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+    changes
+}
+
+/// Apply an event to a snapshot. Route deletion drops every key of the route.
+fn apply(snapshot: &mut ConfigSnapshot, event: &EventRow) {
+    if event.event_type == event_types::ROUTE_DELETED {
+        if let Some(route_id) = event.payload.get("route_id").and_then(|r| r.as_str()) {
+            let prefix = format!("routes.{route_id}.");
+            snapshot.retain(|key, _| !key.starts_with(&prefix));
+        }
+        return;
+    }
+    if event.event_type == event_types::ENV_CONFIG_VARS_SET {
+        // The event carries the full set, so removed keys drop out.
+        snapshot.retain(|key, _| !key.starts_with("config_vars."));
+    }
+    if event.event_type == event_types::ENV_PROCESS_RESOURCES_SET {
+        // Cleared overrides are absent from the payload.
+        if let Some(process_type) = event.payload.get("process_type").and_then(|p| p.as_str()) {
+            let prefix = format!("resources.{process_type}.");
+            snapshot.retain(|key, _| !key.starts_with(&prefix));
+        }
+    }
+    snapshot.extend(event_changes(event));
+}
+
+/// Configuration after every event that occurred at or before `at`.
+fn snapshot_at(events: &[EventRow], at: DateTime<Utc>) -> ConfigSnapshot {
+    let mut snapshot = ConfigSnapshot::new();
+    for event in events.iter().filter(|e| e.occurred_at <= at) {
+        apply(&mut snapshot, event);
+    }
+    snapshot
+}
+
+fn diff(from: &ConfigSnapshot, to: &ConfigSnapshot) -> Vec<ConfigChange> {
+    let mut keys: Vec<&String> = from.keys().chain(to.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| from.get(*key) != to.get(*key))
+        .map(|key| ConfigChange {
+            key: key.clone(),
+            from: from.get(key).cloned(),
+            to: to.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// The caller-supplied reason recorded on a change event.
+fn change_reason(event: &EventRow) -> Option<String> {
+    event
+        .payload
+        .get("reason")
+        .and_then(|r| r.as_str())
+        .map(str::to_string)
+}
+
+fn summarize(event: &EventRow, changes: &BTreeMap<String, serde_json::Value>) -> String {
+    let payload = &event.payload;
+    let text = |key: &str| {
+        payload
+            .get(key)
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| v.to_string())
+            })
+            .unwrap_or_default()
+    };
+    match event.event_type.as_str() {
+        event_types::DEPLOY_CREATED => {
+            format!(
+                "deploy {} of release {}",
+                text("deploy_id"),
+                text("release_id")
+            )
+        }
+        event_types::ENV_DESIRED_RELEASE_SET => format!("desired release {}", text("release_id")),
+        event_types::ENV_SCALE_SET => {
+            let scales: Vec<String> = changes
+                .iter()
+                .map(|(key, value)| format!("{}={}", key.trim_start_matches("scale."), value))
+                .collect();
+            format!("scale {}", scales.join(", "))
+        }
+        event_types::SECRET_BUNDLE_VERSION_SET => {
+            format!("secrets version {}", text("version_id"))
+        }
+        event_types::ENV_CONFIG_VARS_SET => {
+            format!("config vars version {}", text("version"))
+        }
+        event_types::ENV_PROCESS_RESOURCES_SET => {
+            let fields: Vec<String> = changes
+                .iter()
+                .map(|(key, value)| format!("{}={}", key.rsplit('.').next().unwrap_or(key), value))
+                .collect();
+            if fields.is_empty() {
+                format!("resources {} reset", text("process_type"))
+            } else {
+                format!("resources {} {}", text("process_type"), fields.join(", "))
+            }
+        }
+        event_types::ROUTE_CREATED => format!(
+            "route created {}:{} -> {}:{}",
+            text("hostname"),
+            text("listen_port"),
+            text("backend_process_type"),
+            text("backend_port")
+        ),
+        event_types::ROUTE_UPDATED => format!("route updated {}", text("route_id")),
+        event_types::ROUTE_DELETED => format!("route deleted {}", text("hostname")),
+        other => other.to_string(),
+    }
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config/history
+async fn get_history(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    // Deleted envs keep their history.
+    let (_, _, env_id, _) = resolve_env(&state, &ctx, path, EnvState::Any).await?;
+
+    let types = match query.kind.as_deref() {
+        Some(kind) => ChangeKind::parse(kind)
+            .ok_or_else(|| {
+                ApiError::bad_request(
+                    "invalid_kind",
+                    "kind must be one of release, scale, secrets, config_vars, resources, route",
+                )
+                .with_request_id(request_id.clone())
+            })?
+            .event_types()
+            .to_vec(),
+        None => all_event_types(),
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let events = state
+        .db()
+        .event_store()
+        .query_by_env(
+            &env_id,
+            &types,
+            query.after_event_id.unwrap_or(0),
+            None,
+            limit,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to load env config history");
+            ApiError::internal("internal_error", "Failed to load env configuration history")
+                .with_request_id(request_id.clone())
+        })?;
+
+    let next_cursor = if events.len() == limit as usize {
+        events.last().map(|e| e.event_id)
+    } else {
+        None
+    };
+    let items = events
+        .iter()
+        .filter_map(|event| {
+            let kind = ChangeKind::of_event(&event.event_type)?;
+            let changes = event_changes(event);
+            Some(HistoryItem {
+                event_id: event.event_id,
+                occurred_at: event.occurred_at,
+                kind,
+                event_type: event.event_type.clone(),
+                actor_type: event.actor_type.clone(),
+                actor_id: event.actor_id.clone(),
+                summary: summarize(event, &changes),
+                reason: change_reason(event),
+                changes,
+            })
+        })
+        .collect();
+
+    Ok(Json(HistoryResponse { items, next_cursor }))
+}
+
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config/diff
+async fn get_diff(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+    Query(query): Query<DiffQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    // Deleted envs keep their history.
+    let (_, _, env_id, _) = resolve_env(&state, &ctx, path, EnvState::Any).await?;
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    if query.from > to {
+        return Err(
+            ApiError::bad_request("invalid_range", "from must not be after to")
+                .with_request_id(request_id),
+        );
+    }
+
+    let events = state
+        .db()
+        .event_store()
+        .query_by_env(&env_id, &all_event_types(), 0, Some(to), DIFF_MAX_EVENTS)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to load env config events");
+            ApiError::internal("internal_error", "Failed to diff env configuration")
+                .with_request_id(request_id.clone())
+        })?;
+
+    let before = snapshot_at(&events, query.from);
+    let after = snapshot_at(&events, to);
+
+    Ok(Json(DiffResponse {
+        from: query.from,
+        to,
+        changes: diff(&before, &after),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use serde_json::json;
+
+    use super::*;
+
+    fn event(
+        event_id: i64,
+        event_type: &str,
+        at: DateTime<Utc>,
+        payload: serde_json::Value,
+    ) -> EventRow {
+        EventRow {
+            event_id,
+            occurred_at: at,
+            aggregate_type: "env".to_string(),
+            aggregate_id: "env_1".to_string(),
+            aggregate_seq: event_id as i32,
+            event_type: event_type.to_string(),
+            event_version: 1,
+            actor_type: "user".to_string(),
+            actor_id: "user_1".to_string(),
+            org_id: Some("org_1".to_string()),
+            request_id: "req_1".to_string(),
+            idempotency_key: None,
+            app_id: None,
+            env_id: Some("env_1".to_string()),
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_and_diff() {
+        let t0 = Utc::now();
+        let events = vec![
+            event(
+                1,
+                event_types::DEPLOY_CREATED,
+                t0,
+                json!({"deploy_id": "dep_1", "release_id": "rel_1"}),
+            ),
+            event(
+                2,
+                event_types::ENV_SCALE_SET,
+                t0,
+                json!({"scales": [{"process_type": "web", "desired": 2}]}),
+            ),
+            event(
+                3,
+                event_types::ROUTE_CREATED,
+                t0,
+                json!({"route_id": "rt_1", "hostname": "a.example.com", "listen_port": 443, "backend_process_type": "web", "backend_port": 8080}),
+            ),
+            event(
+                4,
+                event_types::DEPLOY_CREATED,
+                t0 + Duration::minutes(5),
+                json!({"deploy_id": "dep_2", "release_id": "rel_2"}),
+            ),
+            event(
+                5,
+                event_types::ENV_SCALE_SET,
+                t0 + Duration::minutes(6),
+                json!({"scales": [{"process_type": "web", "desired": 4}, {"process_type": "worker", "desired": 1}]}),
+            ),
+            event(
+                6,
+                event_types::ROUTE_DELETED,
+                t0 + Duration::minutes(7),
+                json!({"route_id": "rt_1", "hostname": "a.example.com"}),
+            ),
+        ];
+
+        let before = snapshot_at(&events, t0 + Duration::minutes(1));
+        assert_eq!(before.get("release_id"), Some(&json!("rel_1")));
+        assert_eq!(
+            before.get("routes.rt_1.hostname"),
+            Some(&json!("a.example.com"))
+        );
+
+        let after = snapshot_at(&events, t0 + Duration::minutes(10));
+        assert!(!after.keys().any(|k| k.starts_with("routes.")));
+
+        let changes = diff(&before, &after);
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "release_id",
+                "routes.rt_1.backend_port",
+                "routes.rt_1.backend_process_type",
+                "routes.rt_1.hostname",
+                "routes.rt_1.listen_port",
+                "scale.web",
+                "scale.worker",
+            ]
+        );
+        assert_eq!(
+            changes[5],
+            ConfigChange {
+                key: "scale.web".to_string(),
+                from: Some(json!(2)),
+                to: Some(json!(4)),
+            }
+        );
+        assert_eq!(changes[6].from, None);
+    }
+
+    #[test]
+    fn test_summaries_and_kinds() {
+        let t0 = Utc::now();
+        let scale = event(
+            1,
+            event_types::ENV_SCALE_SET,
+            t0,
+            json!({"scales": [{"process_type": "web", "desired": 3}]}),
+        );
+        assert_eq!(summarize(&scale, &event_changes(&scale)), "scale web=3");
+        assert_eq!(change_reason(&scale), None);
+        let deploy = event(
+            2,
+            event_types::DEPLOY_CREATED,
+            t0,
+            json!({"deploy_id": "dep_1", "release_id": "rel_1", "reason": "Ship INC-7 fix"}),
+        );
+        assert_eq!(change_reason(&deploy).as_deref(), Some("Ship INC-7 fix"));
+        assert_eq!(
+            ChangeKind::of_event(event_types::ROUTE_UPDATED),
+            Some(ChangeKind::Route)
+        );
+        assert_eq!(ChangeKind::of_event(event_types::APP_CREATED), None);
+        assert_eq!(ChangeKind::parse("secrets"), Some(ChangeKind::Secrets));
+        assert_eq!(ChangeKind::parse("labels"), None);
+    }
+
+    #[test]
+    fn test_config_vars_snapshot_drops_removed_keys() {
+        let t0 = Utc::now();
+        let events = vec![
+            event(
+                1,
+                event_types::ENV_CONFIG_VARS_SET,
+                t0,
+                json!({"version": 1, "vars": {"LOG_LEVEL": "info", "REGION": "eu"}}),
+            ),
+            event(
+                2,
+                event_types::ENV_CONFIG_VARS_SET,
+                t0 + Duration::minutes(1),
+                json!({"version": 2, "vars": {"LOG_LEVEL": "debug"}}),
+            ),
+        ];
+
+        let before = snapshot_at(&events, t0);
+        let after = snapshot_at(&events, t0 + Duration::minutes(1));
+        let changes = diff(&before, &after);
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["config_vars.LOG_LEVEL", "config_vars.REGION"]);
+        assert_eq!(changes[1].to, None);
+        assert_eq!(
+            summarize(&events[1], &event_changes(&events[1])),
+            "config vars version 2"
+        );
+        assert_eq!(
+            ChangeKind::of_event(event_types::ENV_CONFIG_VARS_SET),
+            Some(ChangeKind::ConfigVars)
+        );
+    }
+
+    #[test]
+    fn test_process_resources_snapshot_drops_cleared_overrides() {
+        let t0 = Utc::now();
+        let events = vec![
+            event(
+                1,
+                event_types::ENV_PROCESS_RESOURCES_SET,
+                t0,
+                json!({"process_type": "web", "version": 1, "cpu_request": 2.0, "memory_limit_bytes": 1073741824}),
+            ),
+            event(
+                2,
+                event_types::ENV_PROCESS_RESOURCES_SET,
+                t0 + Duration::minutes(1),
+                json!({"process_type": "web", "version": 2, "cpu_request": 4.0}),
+            ),
+        ];
+
+        let before = snapshot_at(&events, t0);
+        let after = snapshot_at(&events, t0 + Duration::minutes(1));
+        let changes = diff(&before, &after);
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "resources.web.cpu_request",
+                "resources.web.memory_limit_bytes"
+            ]
+        );
+        assert_eq!(changes[1].to, None);
+        assert_eq!(
+            summarize(&events[1], &event_changes(&events[1])),
+            "resources web cpu_request=4.0"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Env config var endpoints.
+//!
+//! Config vars are non-secret key/values, stored in plaintext on
+//! `env.config_vars_set` and handed to instances as environment variables.
+//! Secrets keep going through the encrypted secrets path.
+//!
+//! - `GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars`
+//! - `PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars`:
+//!   replace the full set
+//! - `PATCH /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars`:
+//!   merge; a `null` value removes the key
+//!
+//! Every change bumps the version, which is part of the instance spec hash,
+//! so the env's instances are rolled onto the new set, unless their process
+//! type reloads secrets in place; those get the new set in place.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_id::EnvId;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregates::{self, CommandError, EnvAggregate, EnvCommand};
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::idempotency;
+use crate::api::preconditions::{self, Preconditions};
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+use super::scope::{self, resolve_env, EnvPath, EnvState};
+
+/// Message of 500s from this module.
+const FAILURE: &str = "Config vars request failed";
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/",
+        get(get_config_vars)
+            .put(put_config_vars)
+            .patch(patch_config_vars),
+    )
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct PutConfigVarsRequest {
+    /// Full set of config vars.
+    vars: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PatchConfigVarsRequest {
+    /// Vars to set; a `null` value removes the key.
+    vars: BTreeMap<String, Option<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct ConfigVarsResponse {
+    env_id: String,
+    vars: BTreeMap<String, String>,
+    /// Config vars version; 0 until vars are first set.
+    resource_version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ConfigVarsRow {
+    vars: serde_json::Value,
+    version: i32,
+    updated_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Current config vars; empty at version 0 when none were ever set.
+pub(super) async fn load_config_vars(
+    state: &AppState,
+    env_id: &EnvId,
+) -> Result<ConfigVarsResponse, sqlx::Error> {
+    let row: Option<ConfigVarsRow> = sqlx::query_as(
+        r#"
+        SELECT vars, version, updated_at
+        FROM env_config_vars_view
+        WHERE env_id = $1
+        "#,
+    )
+    .bind(env_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await?;
+
+    Ok(match row {
+        Some(row) => ConfigVarsResponse {
+            env_id: env_id.to_string(),
+            vars: serde_json::from_value(row.vars).unwrap_or_default(),
+            resource_version: row.version,
+            updated_at: Some(row.updated_at),
+        },
+        None => ConfigVarsResponse {
+            env_id: env_id.to_string(),
+            vars: BTreeMap::new(),
+            resource_version: 0,
+            updated_at: None,
+        },
+    })
+}
+
+/// Apply a merge patch; `None` removes the key.
+fn merge_config_vars(
+    mut current: BTreeMap<String, String>,
+    patch: BTreeMap<String, Option<String>>,
+) -> BTreeMap<String, String> {
+    for (key, value) in patch {
+        match value {
+            Some(value) => {
+                current.insert(key, value);
+            }
+            None => {
+                current.remove(&key);
+            }
+        }
+    }
+    current
+}
+
+/// Shared write path for PUT and PATCH: checks preconditions against the
+/// current version, emits `env.config_vars_set` when the set changes, and
+/// waits for the projection.
+async fn write_config_vars(
+    state: &AppState,
+    ctx: &RequestContext,
+    path: EnvPath,
+    preconditions: &Preconditions,
+    endpoint_name: &'static str,
+    request_vars: serde_json::Value,
+    update: impl FnOnce(BTreeMap<String, String>) -> BTreeMap<String, String>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let actor_id = ctx.actor_id.clone();
+    let (org_id, app_id, env_id, role) = resolve_env(state, ctx, path, EnvState::Live).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let org_scope = org_id.to_string();
+    let hash_input = serde_json::json!({
+        "org_id": org_id,
+        "app_id": app_id,
+        "env_id": env_id,
+        "vars": request_vars,
+    });
+    let request_hash = ctx
+        .idempotency_key
+        .as_deref()
+        .map(|key| {
+            idempotency::request_hash(endpoint_name, &hash_input)
+                .map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let current = load_config_vars(state, &env_id)
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+    preconditions.check(None, current.resource_version, false, &request_id)?;
+
+    let command = EnvCommand::SetConfigVars {
+        vars: update(current.vars),
+    };
+    let event_ids = aggregates::execute::<EnvAggregate, _>(
+        &state.db().event_store(),
+        ctx,
+        &env_id.to_string(),
+        command,
+    )
+    .await
+    .map_err(|e| {
+        if matches!(e, CommandError::Store(_) | CommandError::Event(_)) {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to set config vars");
+        }
+        ApiError::from(e).with_request_id(request_id.clone())
+    })?;
+    if let Some(event_id) = event_ids.last() {
+        consistency::wait_for_write(state, ctx, "env_config", event_id.value()).await?;
+    }
+
+    let updated = load_config_vars(state, &env_id)
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&updated).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to set config vars")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok(preconditions::with_etag(
+        updated.resource_version,
+        (StatusCode::OK, Json(updated)),
+    ))
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars
+async fn get_config_vars(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let (_, _, env_id, _) = resolve_env(&state, &ctx, path, EnvState::Live).await?;
+
+    let current = load_config_vars(&state, &env_id)
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+
+    Ok(preconditions::with_etag(
+        current.resource_version,
+        Json(current),
+    ))
+}
+
+/// PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars
+async fn put_config_vars(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+    preconditions: Preconditions,
+    Json(req): Json<PutConfigVarsRequest>,
+) -> Result<Response, ApiError> {
+    let request_vars = serde_json::json!(req.vars);
+    write_config_vars(
+        &state,
+        &ctx,
+        path,
+        &preconditions,
+        "envs.config_vars.put",
+        request_vars,
+        |_| req.vars,
+    )
+    .await
+}
+
+/// PATCH /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars
+async fn patch_config_vars(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+    preconditions: Preconditions,
+    Json(req): Json<PatchConfigVarsRequest>,
+) -> Result<Response, ApiError> {
+    let request_vars = serde_json::json!(req.vars);
+    write_config_vars(
+        &state,
+        &ctx,
+        path,
+        &preconditions,
+        "envs.config_vars.patch",
+        request_vars,
+        |current| merge_config_vars(current, req.vars),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_config_vars() {
+        let current = BTreeMap::from([
+            ("LOG_LEVEL".to_string(), "info".to_string()),
+            ("REGION".to_string(), "eu".to_string()),
+        ]);
+        let patch = BTreeMap::from([
+            ("LOG_LEVEL".to_string(), Some("debug".to_string())),
+            ("REGION".to_string(), None),
+            ("MISSING".to_string(), None),
+            ("FEATURE_X".to_string(), Some("on".to_string())),
+        ]);
+        let merged = merge_config_vars(current, patch);
+        assert_eq!(
+            merged,
+            BTreeMap::from([
+                ("FEATURE_X".to_string(), "on".to_string()),
+                ("LOG_LEVEL".to_string(), "debug".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_config_vars_response_omits_unset_timestamp() {
+        let response = ConfigVarsResponse {
+            env_id: "env_123".to_string(),
+            vars: BTreeMap::new(),
+            resource_version: 0,
+            updated_at: None,
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["resource_version"], 0);
+        assert!(json.get("updated_at").is_none());
+    }
+}
@@ -15,6 +15,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::api::authz;
 use crate::api::error::ApiError;
+use crate::api::fields::{FieldsQuery, Shape};
 use crate::api::request_context::RequestContext;
 use crate::state::AppState;
 
@@ -59,6 +60,23 @@ pub struct InstanceResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// Fields of listed instances.
+static INSTANCE_SHAPE: Shape = Shape {
+    key: "id",
+    fields: &[
+        "env_id",
+        "process_type",
+        "node_id",
+        "generation",
+        "status",
+        "last_transition_at",
+        "failure_reason",
+        "overlay_ipv6",
+        "created_at",
+    ],
+    relations: &[],
+};
+
 #[derive(Debug, Serialize)]
 pub struct ListInstancesResponse {
     pub items: Vec<InstanceResponse>,
@@ -74,8 +92,10 @@ async fn list_instances(
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
     Query(query): Query<ListInstancesQuery>,
+    Query(fields): Query<FieldsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
+    let selection = INSTANCE_SHAPE.select(fields, &request_id)?;
 
     let org_id_typed: OrgId = org_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
@@ -183,7 +203,7 @@ async fn list_instances(
         None
     };
 
-    Ok(Json(ListInstancesResponse { items, next_cursor }))
+    Ok(selection.respond(ListInstancesResponse { items, next_cursor }))
 }
 
 async fn get_instance(
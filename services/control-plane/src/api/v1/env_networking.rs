@@ -11,6 +11,7 @@ use plfm_id::{AppId, EnvId, OrgId, Ulid};
 use serde::Serialize;
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
@@ -362,20 +363,7 @@ async fn enable_ipv4(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "env_networking",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::record_write(&ctx, event_id.value());
 
     let response = Ipv4EnabledResponse {
         env_id: env_id.to_string(),
@@ -586,20 +574,7 @@ async fn disable_ipv4(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "env_networking",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::record_write(&ctx, event_id.value());
 
     let response = Ipv4DisabledResponse {
         env_id: env_id.to_string(),
@@ -0,0 +1,248 @@
+//! Placement preview API endpoint.
+//!
+//! Runs scheduler placement in dry-run mode for an env:
+//! POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/placement:preview
+//!
+//! Nothing is allocated and no events are emitted.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+use plfm_id::{AppId, EnvId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::scheduler::{PlacementPreview, PreviewOverride, SchedulerReconciler};
+use crate::state::AppState;
+
+use super::envs::ProcessScale;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", post(preview_placement))
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PlacementPreviewRequest {
+    /// Scale to preview; process types not listed keep the env's scale.
+    #[serde(default)]
+    pub processes: Vec<ProcessScale>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlacementPreviewResponse {
+    pub env_id: String,
+    /// True when every new instance found a node.
+    pub feasible: bool,
+    pub processes: Vec<ProcessPlacementPreview>,
+    pub nodes: Vec<NodePlacementPreview>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcessPlacementPreview {
+    pub process_type: String,
+    pub release_id: String,
+    pub desired: i32,
+    pub running: i32,
+    pub new_instances: i32,
+    pub memory_bytes: i64,
+    pub cpu_cores: i32,
+    /// Node for each new instance that could be placed.
+    pub placements: Vec<String>,
+    pub unplaced: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unplaced_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodePlacementPreview {
+    pub node_id: String,
+    pub allocatable_memory_bytes: i64,
+    pub allocatable_cpu_cores: i32,
+    /// Capacity left after the previewed instances are placed.
+    pub available_memory_bytes: i64,
+    pub available_cpu_cores: i32,
+    pub planned_instances: i32,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Preview where an env's instances would be placed.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/placement:preview
+async fn preview_placement(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+    body: Option<Json<PlacementPreviewRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+
+    let org_id_typed: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let app_id_typed: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let env_id_typed: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id_typed, &ctx).await?;
+
+    let mut overrides = BTreeMap::new();
+    for process in &req.processes {
+        if process.process_type.trim().is_empty() {
+            return Err(ApiError::bad_request(
+                "invalid_process_type",
+                "process_type cannot be empty",
+            )
+            .with_request_id(request_id));
+        }
+        if process.desired < 0 {
+            return Err(
+                ApiError::bad_request("invalid_desired", "desired must be >= 0")
+                    .with_request_id(request_id),
+            );
+        }
+        overrides.insert(
+            process.process_type.clone(),
+            PreviewOverride {
+                desired: process.desired,
+                node_selector: process.node_selector.clone(),
+                tolerations: process.tolerations.clone(),
+            },
+        );
+    }
+
+    let env_exists: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM envs_view
+            WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
+        )
+        "#,
+    )
+    .bind(env_id_typed.to_string())
+    .bind(org_id_typed.to_string())
+    .bind(app_id_typed.to_string())
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to load env");
+        ApiError::internal("internal_error", "Failed to preview placement")
+            .with_request_id(request_id.clone())
+    })?;
+    if !env_exists {
+        return Err(ApiError::not_found(
+            "env_not_found",
+            format!("Environment {} not found", env_id_typed),
+        )
+        .with_request_id(request_id));
+    }
+
+    let preview = SchedulerReconciler::new(state.db().pool().clone())
+        .preview_placement(&env_id_typed, &overrides)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                error = %e,
+                request_id = %request_id,
+                env_id = %env_id_typed,
+                "Failed to preview placement"
+            );
+            ApiError::internal("internal_error", "Failed to preview placement")
+                .with_request_id(request_id.clone())
+        })?;
+
+    if let Some(unknown) = overrides
+        .keys()
+        .find(|p| !preview.groups.iter().any(|g| &g.process_type == *p))
+    {
+        return Err(ApiError::bad_request(
+            "unknown_process_type",
+            format!(
+                "Process type '{}' has no desired release in this environment",
+                unknown
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    Ok(Json(preview_response(&env_id_typed, preview)))
+}
+
+fn preview_response(env_id: &EnvId, preview: PlacementPreview) -> PlacementPreviewResponse {
+    let PlacementPreview { groups, plan } = preview;
+    let feasible = plan.feasible();
+
+    let processes = groups
+        .into_iter()
+        .map(|group| {
+            let placements = plan
+                .placements
+                .iter()
+                .filter(|p| p.process_type == group.process_type)
+                .map(|p| p.node_id.clone())
+                .collect();
+            let unplaced = plan
+                .unplaced
+                .iter()
+                .find(|u| u.process_type == group.process_type);
+            ProcessPlacementPreview {
+                process_type: group.process_type,
+                release_id: group.release_id,
+                desired: group.desired_replicas,
+                running: group.running,
+                new_instances: group.new_instances,
+                memory_bytes: group.memory_bytes,
+                cpu_cores: group.cpu_cores,
+                placements,
+                unplaced: unplaced.map(|u| u.count).unwrap_or(0),
+                unplaced_reason: unplaced.map(|u| u.reason.as_str().to_string()),
+            }
+        })
+        .collect();
+
+    let nodes = plan
+        .nodes
+        .into_iter()
+        .map(|node| NodePlacementPreview {
+            planned_instances: plan
+                .placements
+                .iter()
+                .filter(|p| p.node_id == node.node_id)
+                .count() as i32,
+            node_id: node.node_id,
+            allocatable_memory_bytes: node.allocatable_memory_bytes,
+            allocatable_cpu_cores: node.allocatable_cpu_cores,
+            available_memory_bytes: node.available_memory_bytes,
+            available_cpu_cores: node.available_cpu_cores,
+        })
+        .collect();
+
+    PlacementPreviewResponse {
+        env_id: env_id.to_string(),
+        feasible,
+        processes,
+        nodes,
+    }
+}
@@ -0,0 +1,416 @@
+//! Process resource override endpoints.
+//!
+//! Overrides cpu/memory/disk for one process type of an env without cutting
+//! a new release. Unset fields fall back to the release (cpu, memory) or the
+//! env scale (disk).
+//!
+//! - `GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{process_type}/resources`
+//! - `PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{process_type}/resources`:
+//!   replace the overrides; requires `If-Match` or `expected_version`
+//!
+//! Overrides are part of the instance spec hash, so a change rolls the
+//! process type's instances.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_id::EnvId;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregates::env::DEFAULT_EPHEMERAL_DISK_BYTES;
+use crate::aggregates::{self, CommandError, EnvAggregate, EnvCommand, ProcessResources};
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::idempotency;
+use crate::api::preconditions::{self, Preconditions};
+use crate::api::request_context::RequestContext;
+use crate::db::quotas::{check_quota, QuotaDimension};
+use crate::state::AppState;
+
+use super::scope::{self, resolve_env, EnvState};
+
+/// Message of 500s from this module.
+const FAILURE: &str = "Process resources request failed";
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/{process_type}/resources",
+        get(get_process_resources).put(put_process_resources),
+    )
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PutProcessResourcesRequest {
+    /// vCPU request; omitted or `null` uses the release value.
+    #[serde(default)]
+    cpu_request: Option<f64>,
+    /// Memory limit in bytes; omitted or `null` uses the release value.
+    #[serde(default)]
+    memory_limit_bytes: Option<i64>,
+    /// Scratch disk in bytes; omitted or `null` uses the env scale value.
+    #[serde(default)]
+    ephemeral_disk_bytes: Option<i64>,
+    /// Legacy alternative to `If-Match`.
+    #[serde(default)]
+    expected_version: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProcessResourcesResponse {
+    env_id: String,
+    process_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_request: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_limit_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ephemeral_disk_bytes: Option<i64>,
+    /// Override version; 0 until overrides are first set.
+    resource_version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ProcessResourcesRow {
+    cpu_request: Option<f64>,
+    memory_limit_bytes: Option<i64>,
+    ephemeral_disk_bytes: Option<i64>,
+    resource_version: i32,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ScaleRow {
+    desired_replicas: i32,
+    ephemeral_disk_bytes: Option<i64>,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Current overrides; empty at version 0 when none were ever set.
+async fn load_process_resources(
+    state: &AppState,
+    env_id: &EnvId,
+    process_type: &str,
+) -> Result<ProcessResourcesResponse, sqlx::Error> {
+    let row: Option<ProcessResourcesRow> = sqlx::query_as(
+        r#"
+        SELECT cpu_request, memory_limit_bytes, ephemeral_disk_bytes, resource_version, updated_at
+        FROM env_process_resources_view
+        WHERE env_id = $1 AND process_type = $2
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(process_type)
+    .fetch_optional(state.db().pool())
+    .await?;
+
+    let mut response = ProcessResourcesResponse {
+        env_id: env_id.to_string(),
+        process_type: process_type.to_string(),
+        cpu_request: None,
+        memory_limit_bytes: None,
+        ephemeral_disk_bytes: None,
+        resource_version: 0,
+        updated_at: None,
+    };
+    if let Some(row) = row {
+        response.cpu_request = row.cpu_request;
+        response.memory_limit_bytes = row.memory_limit_bytes;
+        response.ephemeral_disk_bytes = row.ephemeral_disk_bytes;
+        response.resource_version = row.resource_version;
+        response.updated_at = Some(row.updated_at);
+    }
+    Ok(response)
+}
+
+/// Additional scratch disk the env would reserve: `desired * disk`, where the
+/// disk is the override, else the scale size, else the default.
+fn disk_quota_delta(scale: Option<&ScaleRow>, current: Option<i64>, requested: Option<i64>) -> i64 {
+    let Some(scale) = scale else {
+        return 0;
+    };
+    let effective = |bytes: Option<i64>| {
+        bytes
+            .or(scale.ephemeral_disk_bytes)
+            .unwrap_or(DEFAULT_EPHEMERAL_DISK_BYTES)
+    };
+    i64::from(scale.desired_replicas.max(0)) * (effective(requested) - effective(current))
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{process_type}/resources
+async fn get_process_resources(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, process_type)): Path<(String, String, String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let (_, _, env_id, _) =
+        resolve_env(&state, &ctx, (org_id, app_id, env_id), EnvState::Live).await?;
+
+    let current = load_process_resources(&state, &env_id, &process_type)
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+
+    Ok(preconditions::with_etag(
+        current.resource_version,
+        Json(current),
+    ))
+}
+
+/// PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{process_type}/resources
+async fn put_process_resources(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, process_type)): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<PutProcessResourcesRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let actor_id = ctx.actor_id.clone();
+    let (org_id, app_id, env_id, role) =
+        resolve_env(&state, &ctx, (org_id, app_id, env_id), EnvState::Live).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let endpoint_name = "envs.process_resources.put";
+    let org_scope = org_id.to_string();
+    let hash_input = serde_json::json!({
+        "org_id": org_id,
+        "app_id": app_id,
+        "env_id": env_id,
+        "process_type": process_type,
+        "request": req,
+    });
+    let request_hash = ctx
+        .idempotency_key
+        .as_deref()
+        .map(|key| {
+            idempotency::request_hash(endpoint_name, &hash_input)
+                .map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            &state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let deployed: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM env_desired_releases_view
+            WHERE env_id = $1 AND process_type = $2
+        )
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(&process_type)
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+    if !deployed {
+        return Err(ApiError::conflict(
+            "process_type_not_deployed",
+            format!(
+                "Process type '{}' has no desired release in this environment",
+                process_type
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    let current = load_process_resources(&state, &env_id, &process_type)
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+    preconditions.check(
+        req.expected_version,
+        current.resource_version,
+        true,
+        &request_id,
+    )?;
+
+    let scale: Option<ScaleRow> = sqlx::query_as(
+        r#"
+        SELECT desired_replicas, ephemeral_disk_bytes
+        FROM env_scale_view
+        WHERE env_id = $1 AND process_type = $2
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(&process_type)
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+    let disk_delta = disk_quota_delta(
+        scale.as_ref(),
+        current.ephemeral_disk_bytes,
+        req.ephemeral_disk_bytes,
+    );
+    if disk_delta > 0 {
+        if let Some(exceeded) = check_quota(
+            state.db().pool(),
+            &org_id,
+            QuotaDimension::MaxTotalEphemeralDiskBytes,
+            disk_delta,
+        )
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?
+        {
+            return Err(ApiError::conflict(
+                "quota_exceeded",
+                format!(
+                    "Quota exceeded for {}: limit={}, current={}, requested={}",
+                    exceeded.dimension,
+                    exceeded.limit,
+                    exceeded.current_usage,
+                    exceeded.requested_delta
+                ),
+            )
+            .with_request_id(request_id));
+        }
+    }
+
+    let command = EnvCommand::SetProcessResources {
+        process_type: process_type.clone(),
+        resources: ProcessResources {
+            cpu_request: req.cpu_request,
+            memory_limit_bytes: req.memory_limit_bytes,
+            ephemeral_disk_bytes: req.ephemeral_disk_bytes,
+        },
+    };
+    let event_ids = aggregates::execute::<EnvAggregate, _>(
+        &state.db().event_store(),
+        &ctx,
+        &env_id.to_string(),
+        command,
+    )
+    .await
+    .map_err(|e| {
+        if matches!(e, CommandError::Store(_) | CommandError::Event(_)) {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to set process resources");
+        }
+        ApiError::from(e).with_request_id(request_id.clone())
+    })?;
+    if let Some(event_id) = event_ids.last() {
+        consistency::wait_for_write(&state, &ctx, "env_config", event_id.value()).await?;
+    }
+
+    let updated = load_process_resources(&state, &env_id, &process_type)
+        .await
+        .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&updated).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to set process resources")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            &state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok(preconditions::with_etag(
+        updated.resource_version,
+        (StatusCode::OK, Json(updated)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: i64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_disk_quota_delta() {
+        let scale = ScaleRow {
+            desired_replicas: 3,
+            ephemeral_disk_bytes: Some(8 * GIB),
+        };
+        // Override replaces the scale size.
+        assert_eq!(
+            disk_quota_delta(Some(&scale), None, Some(10 * GIB)),
+            6 * GIB
+        );
+        // Clearing an override falls back to the scale size.
+        assert_eq!(
+            disk_quota_delta(Some(&scale), Some(10 * GIB), None),
+            -6 * GIB
+        );
+        assert_eq!(disk_quota_delta(None, None, Some(10 * GIB)), 0);
+
+        let default_sized = ScaleRow {
+            desired_replicas: 2,
+            ephemeral_disk_bytes: None,
+        };
+        assert_eq!(
+            disk_quota_delta(
+                Some(&default_sized),
+                None,
+                Some(DEFAULT_EPHEMERAL_DISK_BYTES + GIB)
+            ),
+            2 * GIB
+        );
+    }
+
+    #[test]
+    fn test_process_resources_response_omits_unset_fields() {
+        let response = ProcessResourcesResponse {
+            env_id: "env_123".to_string(),
+            process_type: "web".to_string(),
+            cpu_request: Some(2.0),
+            memory_limit_bytes: None,
+            ephemeral_disk_bytes: None,
+            resource_version: 1,
+            updated_at: None,
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["cpu_request"], 2.0);
+        assert!(json.get("memory_limit_bytes").is_none());
+        assert!(json.get("updated_at").is_none());
+    }
+}
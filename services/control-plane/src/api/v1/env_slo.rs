@@ -0,0 +1,165 @@
+//! Env availability objective (SLO) endpoints.
+//!
+//! - `GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/slo?window=30d`:
+//!   availability over the window's UTC days, the error budget left, and
+//!   burn rates
+//! - `PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/slo`: set the
+//!   env's objective
+//!
+//! See: docs/specs/observability/slo.md
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::api::validation::{ValidJson, Validate, Validator};
+use crate::slo::{self, DEFAULT_WINDOW_DAYS, MAX_OBJECTIVE, MAX_WINDOW_DAYS, MIN_OBJECTIVE};
+use crate::state::AppState;
+
+use super::scope::{self, resolve_env, EnvPath, EnvState};
+
+/// Message of 500s from this module.
+const FAILURE: &str = "SLO request failed";
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(get_slo).put(put_slo))
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct SloQuery {
+    /// Window in days, e.g. `30d` (1d-90d).
+    window: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SloObjectiveRequest {
+    /// Availability objective, in percent.
+    objective: f64,
+}
+
+impl Validate for SloObjectiveRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            (MIN_OBJECTIVE..=MAX_OBJECTIVE).contains(&self.objective),
+            "invalid_slo",
+            "objective",
+            &format!("objective must be between {MIN_OBJECTIVE} and {MAX_OBJECTIVE} percent"),
+        );
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SloObjectiveResponse {
+    env_id: String,
+    objective: f64,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Parse a `{days}d` window.
+fn parse_window(window: Option<&str>) -> Option<i64> {
+    let Some(window) = window else {
+        return Some(DEFAULT_WINDOW_DAYS);
+    };
+    let days: i64 = window.strip_suffix('d')?.parse().ok()?;
+    (1..=MAX_WINDOW_DAYS).contains(&days).then_some(days)
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/slo
+async fn get_slo(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+    Query(query): Query<SloQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let window_days = parse_window(query.window.as_deref()).ok_or_else(|| {
+        ApiError::bad_request(
+            "invalid_slo",
+            format!("window must be between 1d and {MAX_WINDOW_DAYS}d"),
+        )
+        .with_request_id(request_id.clone())
+    })?;
+    let (_, _, env_id, _) = resolve_env(&state, &ctx, path, EnvState::Live).await?;
+
+    let report = slo::report(
+        state.db().pool(),
+        &env_id.to_string(),
+        window_days,
+        Utc::now(),
+    )
+    .await
+    .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+
+    Ok(Json(report))
+}
+
+/// PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/slo
+async fn put_slo(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(path): Path<EnvPath>,
+    ValidJson(req): ValidJson<SloObjectiveRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let (org_id, app_id, env_id, role) = resolve_env(&state, &ctx, path, EnvState::Live).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    slo::set_objective(
+        state.db().pool(),
+        &org_id.to_string(),
+        &app_id.to_string(),
+        &env_id.to_string(),
+        req.objective,
+        &ctx.actor_id,
+    )
+    .await
+    .map_err(|e| scope::internal(e, &request_id, FAILURE))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        env_id = %env_id,
+        objective = req.objective,
+        actor_id = %ctx.actor_id,
+        "Env SLO objective set"
+    );
+
+    Ok(Json(SloObjectiveResponse {
+        env_id: env_id.to_string(),
+        objective: req.objective,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(parse_window(None), Some(30));
+        assert_eq!(parse_window(Some("7d")), Some(7));
+        assert_eq!(parse_window(Some("90d")), Some(90));
+        assert_eq!(parse_window(Some("91d")), None);
+        assert_eq!(parse_window(Some("0d")), None);
+        assert_eq!(parse_window(Some("24h")), None);
+        assert_eq!(parse_window(Some("30")), None);
+    }
+}
@@ -9,17 +9,38 @@ use axum::{
     routing::{delete, get, patch, post, put},
     Json, Router,
 };
-use chrono::{DateTime, Utc};
-use plfm_events::{event_types, AggregateType};
-use plfm_id::{AppId, EnvId, OrgId};
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{
+    event_types, AggregateType, EnvDeletionRequestedPayload, RouteCreatedPayload,
+    RouteProxyProtocol, SecretsReload, Toleration,
+};
+use plfm_id::{AppId, EnvId, OrgId, RouteId};
+use plfm_reconcile::RolloutLimit;
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 
+use crate::aggregates::env::DEFAULT_EPHEMERAL_DISK_BYTES;
+use crate::aggregates::{self, CommandError, EnvAggregate, EnvCommand, ProcessScaleSpec};
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
+use crate::api::if_exists::IfExistsQuery;
+use crate::api::labels::{self, LabelSelector, Labels, PatchLabelsRequest};
+use crate::api::pagination::{self, PageQuery, PageTotal, SortKey, Sorting};
+use crate::api::preconditions::{self, Preconditions};
 use crate::api::request_context::RequestContext;
-use crate::db::AppendEvent;
+use crate::api::validation::{ValidJson, Validate, Validator};
+use crate::cleanup::teardown::{self, EnvTeardownProgress, EnvTeardownStep};
+use crate::db::quotas::{check_quota, QuotaDimension};
+use crate::db::{AppendEvent, DbError};
+use crate::managed_dns::{self, ManagedRouteTemplate};
 use crate::state::AppState;
+use crate::trash::{RestoreScope, TrashKind};
+
+use super::deploys::normalize_change_reason;
+use super::labels::LabelTarget;
+use super::trash::{self, RestoreResponse};
 
 /// Create env routes.
 ///
@@ -28,9 +49,13 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", post(create_env))
         .route("/", get(list_envs))
+        .route("/by-name/{name}", get(get_env_by_name))
         .route("/{env_id}", patch(update_env))
         .route("/{env_id}", delete(delete_env))
         .route("/{env_id}", get(get_env))
+        .route("/{env_id}/deletion", get(get_env_deletion))
+        .route("/{env_id}/restore", post(restore_env))
+        .route("/{env_id}/labels", patch(patch_env_labels))
 }
 
 /// Create env status routes.
@@ -55,23 +80,120 @@ pub fn scale_routes() -> Router<AppState> {
 // Request/Response Types
 // =============================================================================
 
+/// Lifetime of a preview env when the request does not set one.
+pub const DEFAULT_PREVIEW_TTL_SECS: i64 = 72 * 3600;
+
+/// Shortest preview env lifetime.
+pub const MIN_PREVIEW_TTL_SECS: i64 = 3600;
+
+/// Longest preview env lifetime.
+pub const MAX_PREVIEW_TTL_SECS: i64 = 30 * 24 * 3600;
+
 /// Request to create a new environment.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateEnvRequest {
     /// Environment name (unique within app, e.g., "production", "staging").
     pub name: String,
+
+    /// Ephemeral preview env, torn down by the cleanup worker once its TTL
+    /// elapses.
+    #[serde(default)]
+    pub preview: bool,
+
+    /// Preview lifetime in seconds (default 72 hours).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct UpdateEnvRequest {
     #[serde(default)]
     pub name: Option<String>,
-    pub expected_version: i32,
+    /// Legacy alternative to `If-Match`.
+    #[serde(default)]
+    pub expected_version: Option<i32>,
+}
+
+impl Validate for CreateEnvRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Environment", 50);
+        v.check(
+            self.name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'),
+            "invalid_name",
+            "name",
+            "Environment name must contain only lowercase letters, numbers, and hyphens",
+        );
+        if let Some(ttl) = self.ttl_seconds {
+            v.check(
+                self.preview,
+                "invalid_preview",
+                "ttl_seconds",
+                "ttl_seconds can only be set on preview environments",
+            );
+            v.check(
+                (MIN_PREVIEW_TTL_SECS..=MAX_PREVIEW_TTL_SECS).contains(&ttl),
+                "invalid_preview",
+                "ttl_seconds",
+                &format!(
+                    "ttl_seconds must be between {MIN_PREVIEW_TTL_SECS} and {MAX_PREVIEW_TTL_SECS}"
+                ),
+            );
+        }
+    }
+}
+
+impl Validate for UpdateEnvRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.name("name", name, "Environment", 100);
+        }
+    }
+}
+
+/// Query parameters for deleting an environment.
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteEnvQuery {
+    /// Also delete volumes that are attached only to this environment.
+    #[serde(default)]
+    pub delete_volumes: bool,
 }
 
+/// Teardown status of an environment deletion.
 #[derive(Debug, Serialize)]
-pub struct DeleteResponse {
-    pub ok: bool,
+pub struct EnvDeletionResponse {
+    /// Environment ID.
+    pub env_id: String,
+
+    /// Overall status (in_progress, completed).
+    pub status: String,
+
+    /// Whether volumes are deleted as part of the teardown.
+    pub delete_volumes: bool,
+
+    /// When deletion was requested.
+    pub requested_at: Option<DateTime<Utc>>,
+
+    /// Step currently being worked on (absent once completed).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_step: Option<EnvTeardownStep>,
+
+    /// All teardown steps in execution order.
+    pub steps: Vec<DeletionStepResponse>,
+}
+
+/// Status of a single teardown step.
+#[derive(Debug, Serialize)]
+pub struct DeletionStepResponse {
+    /// Step name.
+    pub name: EnvTeardownStep,
+
+    /// Step status (pending, in_progress, completed).
+    pub status: String,
+
+    /// Items still outstanding for the step.
+    pub remaining: i64,
 }
 
 /// Response for a single environment.
@@ -89,6 +211,20 @@ pub struct EnvResponse {
     /// Environment name.
     pub name: String,
 
+    /// Platform-managed hostname, when managed hostnames are enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed_hostname: Option<String>,
+
+    /// Whether this is an ephemeral preview env.
+    pub preview: bool,
+
+    /// When a preview env is torn down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// User-assigned labels.
+    pub labels: Labels,
+
     /// Resource version for optimistic concurrency.
     pub resource_version: i32,
 
@@ -107,21 +243,81 @@ pub struct ListEnvsResponse {
 
     /// Next cursor (null if no more results).
     pub next_cursor: Option<String>,
+
+    /// Matching environments, with `include_total=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
 }
 
-/// Query parameters for listing environments.
+/// Query parameters for listing environments. Pagination is in [`PageQuery`].
 #[derive(Debug, Deserialize)]
 pub struct ListEnvsQuery {
-    /// Max number of items to return.
-    pub limit: Option<i64>,
-    /// Cursor (exclusive). Interpreted as an env_id.
-    pub cursor: Option<String>,
+    /// Label selector (e.g. `team=payments,tier!=frontend`).
+    pub label_selector: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ProcessScale {
     pub process_type: String,
     pub desired: i32,
+    /// Node labels an instance's node must carry. Omit to keep the current selector.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_selector: Option<Labels>,
+    /// Node taints the process type tolerates. Omit to keep the current tolerations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerations: Option<Vec<Toleration>>,
+    /// Scratch disk per instance in bytes. Omit to keep the current size
+    /// (4 GiB when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_bytes: Option<i64>,
+    /// Instances a rollout may start above desired: a count or `"25%"`.
+    /// Omit to keep the current limit (1 when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_surge: Option<RolloutLimit>,
+    /// Instances a rollout may take below desired: a count or `"25%"`.
+    /// Omit to keep the current limit (0 when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_unavailable: Option<RolloutLimit>,
+    /// How running instances pick up a new secrets version. Omit to keep
+    /// the current mode (`restart` when never set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets_reload: Option<SecretsReload>,
+}
+
+impl ProcessScale {
+    pub(super) fn to_spec(&self) -> ProcessScaleSpec {
+        ProcessScaleSpec {
+            process_type: self.process_type.clone(),
+            desired: self.desired,
+            node_selector: self.node_selector.clone(),
+            tolerations: self.tolerations.clone(),
+            ephemeral_disk_bytes: self.ephemeral_disk_bytes,
+            max_surge: self.max_surge,
+            max_unavailable: self.max_unavailable,
+            secrets_reload: self.secrets_reload.clone(),
+        }
+    }
+}
+
+/// Change in the org's reserved scratch disk if `requested` replaces `current`.
+///
+/// Reserved disk is `desired * ephemeral_disk_bytes` per process type; process
+/// types that do not set a size keep their current one.
+fn ephemeral_disk_delta(current: &[ProcessScale], requested: &[ProcessScale]) -> i64 {
+    requested
+        .iter()
+        .map(|process| {
+            let existing = current
+                .iter()
+                .find(|c| c.process_type == process.process_type);
+            let existing_disk = existing
+                .and_then(|c| c.ephemeral_disk_bytes)
+                .unwrap_or(DEFAULT_EPHEMERAL_DISK_BYTES);
+            let reserved = existing.map_or(0, |c| i64::from(c.desired) * existing_disk);
+            let disk = process.ephemeral_disk_bytes.unwrap_or(existing_disk);
+            i64::from(process.desired.max(0)) * disk - reserved
+        })
+        .sum()
 }
 
 #[derive(Debug, Serialize)]
@@ -135,7 +331,12 @@ pub struct ScaleState {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ScaleUpdateRequest {
     pub processes: Vec<ProcessScale>,
-    pub expected_version: i32,
+    /// Legacy alternative to `If-Match`.
+    #[serde(default)]
+    pub expected_version: Option<i32>,
+    /// Why the scale is being changed; recorded on the `env.scale_set` event.
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 /// Response for environment status (desired vs current state).
@@ -178,10 +379,26 @@ pub struct EnvStatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_error: Option<String>,
 
+    /// Most recent events of the environment, newest first.
+    pub recent_events: Vec<EnvStatusEvent>,
+
     /// Overall status (healthy, degraded, failed).
     pub status: String,
 }
 
+/// Event summary included in the environment status.
+#[derive(Debug, Serialize)]
+pub struct EnvStatusEvent {
+    pub event_id: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+}
+
+/// Number of events included in the environment status.
+const STATUS_RECENT_EVENTS: i64 = 10;
+
 /// Instance count summary.
 #[derive(Debug, Serialize)]
 pub struct InstanceCounts {
@@ -224,7 +441,7 @@ pub struct RouteStatus {
 // Handlers
 // =============================================================================
 
-async fn load_scale_state(
+pub(super) async fn load_scale_state(
     state: &AppState,
     request_id: &str,
     org_id: &OrgId,
@@ -260,7 +477,9 @@ async fn load_scale_state(
 
     let rows = sqlx::query_as::<_, ScaleRow>(
         r#"
-        SELECT process_type, desired_replicas, resource_version, updated_at
+        SELECT process_type, desired_replicas, node_selector, tolerations,
+               ephemeral_disk_bytes, max_surge, max_unavailable,
+               secrets_reload, resource_version, updated_at
         FROM env_scale_view
         WHERE env_id = $1 AND org_id = $2 AND app_id = $3
         ORDER BY process_type ASC
@@ -288,9 +507,20 @@ async fn load_scale_state(
     for row in rows {
         resource_version = resource_version.max(row.resource_version);
         updated_at = updated_at.max(row.updated_at);
+        let node_selector = labels::from_json(row.node_selector);
+        let tolerations: Vec<Toleration> =
+            serde_json::from_value(row.tolerations).unwrap_or_default();
         processes.push(ProcessScale {
             process_type: row.process_type,
             desired: row.desired_replicas,
+            node_selector: (!node_selector.is_empty()).then_some(node_selector),
+            tolerations: (!tolerations.is_empty()).then_some(tolerations),
+            ephemeral_disk_bytes: row.ephemeral_disk_bytes,
+            max_surge: row.max_surge.and_then(|limit| limit.parse().ok()),
+            max_unavailable: row.max_unavailable.and_then(|limit| limit.parse().ok()),
+            secrets_reload: row
+                .secrets_reload
+                .and_then(|reload| serde_json::from_value(reload).ok()),
         });
     }
 
@@ -305,11 +535,15 @@ async fn load_scale_state(
 /// Create a new environment.
 ///
 /// POST /v1/orgs/{org_id}/apps/{app_id}/envs
+///
+/// With `?if_exists=return`, an existing env with the same name and the same
+/// `preview` flag is returned instead of a conflict.
 async fn create_env(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id)): Path<(String, String)>,
-    Json(req): Json<CreateEnvRequest>,
+    Query(if_exists): Query<IfExistsQuery>,
+    ValidJson(req): ValidJson<CreateEnvRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -322,6 +556,7 @@ async fn create_env(
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
             .with_request_id(request_id.clone())
     })?;
+    let if_exists = if_exists.mode(&request_id)?;
 
     // Validate app_id format
     let app_id: AppId = app_id.parse().map_err(|_| {
@@ -366,35 +601,6 @@ async fn create_env(
         .with_request_id(request_id.clone()));
     }
 
-    // Validate name
-    if req.name.is_empty() {
-        return Err(
-            ApiError::bad_request("invalid_name", "Environment name cannot be empty")
-                .with_request_id(request_id.clone()),
-        );
-    }
-
-    if req.name.len() > 50 {
-        return Err(ApiError::bad_request(
-            "invalid_name",
-            "Environment name cannot exceed 50 characters",
-        )
-        .with_request_id(request_id.clone()));
-    }
-
-    // Validate name format (lowercase alphanumeric and hyphens)
-    if !req
-        .name
-        .chars()
-        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
-    {
-        return Err(ApiError::bad_request(
-            "invalid_name",
-            "Environment name must contain only lowercase letters, numbers, and hyphens",
-        )
-        .with_request_id(request_id.clone()));
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -428,32 +634,54 @@ async fn create_env(
     }
 
     // Check for duplicate name within app
-    let name_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM envs_view WHERE app_id = $1 AND name = $2 AND NOT is_deleted)",
-    )
-    .bind(app_id.to_string())
-    .bind(&req.name)
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Failed to check env name uniqueness");
-        ApiError::internal("internal_error", "Failed to verify environment name")
-            .with_request_id(request_id.clone())
-    })?;
+    let existing = load_env_by_name(&state, &org_id, &app_id, &req.name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check env name uniqueness");
+            ApiError::internal("internal_error", "Failed to verify environment name")
+                .with_request_id(request_id.clone())
+        })?;
 
-    if name_exists {
-        return Err(ApiError::conflict(
-            "env_name_exists",
-            format!(
-                "Environment '{}' already exists in this application",
-                req.name
-            ),
-        )
-        .with_request_id(request_id.clone()));
+    if let Some(existing) = existing {
+        // The TTL is relative to creation, so only the preview flag is compared.
+        if_exists.reuse(existing.preview == req.preview, || {
+            ApiError::conflict(
+                "env_name_exists",
+                format!(
+                    "Environment '{}' already exists in this application",
+                    req.name
+                ),
+            )
+            .with_request_id(request_id.clone())
+        })?;
+        let response = EnvResponse::from(existing);
+        return Ok(preconditions::with_etag(
+            response.resource_version,
+            (StatusCode::OK, Json(response)),
+        ));
     }
 
     let env_id = EnvId::new();
 
+    let managed = managed_route(&state, env_id, &req.name, app_id)
+        .await
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+    let mut env_payload = serde_json::json!({
+        "env_id": env_id.to_string(),
+        "org_id": org_id.to_string(),
+        "app_id": app_id.to_string(),
+        "name": req.name
+    });
+    if let Some((hostname, _)) = managed.as_ref() {
+        env_payload["managed_hostname"] = serde_json::json!(hostname);
+    }
+    if req.preview {
+        let ttl = req.ttl_seconds.unwrap_or(DEFAULT_PREVIEW_TTL_SECS);
+        let expires_at = Utc::now() + Duration::seconds(ttl);
+        env_payload["preview"] = serde_json::json!(true);
+        env_payload["expires_at"] = serde_json::json!(expires_at.to_rfc3339());
+    }
+
     // Create the event
     let event = AppendEvent {
         aggregate_type: AggregateType::Env,
@@ -470,41 +698,73 @@ async fn create_env(
         env_id: Some(env_id),
         correlation_id: None,
         causation_id: None,
-        payload: serde_json::json!({
-            "env_id": env_id.to_string(),
-            "org_id": org_id.to_string(),
-            "app_id": app_id.to_string(),
-            "name": req.name
-        }),
+        payload: env_payload,
         ..Default::default()
     };
 
-    // Append the event
+    let mut events = vec![event];
+    if let Some((hostname, template)) = managed {
+        let route_id = RouteId::new();
+        let payload = RouteCreatedPayload {
+            route_id,
+            org_id,
+            app_id,
+            env_id,
+            hostname,
+            listen_port: template.listen_port,
+            protocol_hint: template.protocol_hint,
+            backend_process_type: template.backend_process_type,
+            backend_port: template.backend_port,
+            proxy_protocol: RouteProxyProtocol::Off,
+            backend_expects_proxy_protocol: false,
+            ipv4_required: false,
+            env_ipv4_address: None,
+            // The platform owns the domain; nothing to verify.
+            verification_token: None,
+            internal: false,
+            access_policy: None,
+        };
+        let payload = serde_json::to_value(&payload).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize route payload");
+            ApiError::internal("internal_error", "Failed to create environment")
+                .with_request_id(request_id.clone())
+        })?;
+        events.push(AppendEvent {
+            aggregate_type: AggregateType::Route,
+            aggregate_id: route_id.to_string(),
+            aggregate_seq: 1,
+            event_type: event_types::ROUTE_CREATED.to_string(),
+            event_version: 1,
+            actor_type,
+            actor_id: actor_id.clone(),
+            org_id: Some(org_id),
+            request_id: request_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+            app_id: Some(app_id),
+            env_id: Some(env_id),
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            ..Default::default()
+        });
+    }
+
+    // Append the env (and its managed route) atomically
     let event_store = state.db().event_store();
-    let event_id = event_store.append(event).await.map_err(|e| {
+    let event_ids = event_store.append_batch(events).await.map_err(|e| {
         tracing::error!(error = %e, request_id = %request_id, "Failed to create env");
         ApiError::internal("internal_error", "Failed to create environment")
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "envs",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "envs", event_ids[0].value()).await?;
+    if let Some(route_event_id) = event_ids.get(1) {
+        consistency::wait_for_write(&state, &ctx, "routes", route_event_id.value()).await?;
+    }
 
     let row = sqlx::query_as::<_, EnvRow>(
         r#"
-        SELECT env_id, app_id, org_id, name, resource_version, created_at, updated_at
+        SELECT env_id, app_id, org_id, name, managed_hostname, preview, expires_at, labels, resource_version, created_at, updated_at
         FROM envs_view
         WHERE env_id = $1 AND NOT is_deleted
         "#,
@@ -523,6 +783,7 @@ async fn create_env(
     })?;
 
     let response = EnvResponse::from(row);
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -547,14 +808,24 @@ async fn create_env(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
 }
 
+/// Update an environment.
+///
+/// PATCH /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}
+///
+/// An update that changes nothing returns the env as is, without a new
+/// version.
 async fn update_env(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
-    Json(req): Json<UpdateEnvRequest>,
+    preconditions: Preconditions,
+    ValidJson(req): ValidJson<UpdateEnvRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -578,7 +849,7 @@ async fn update_env(
     let role = authz::require_org_member(&state, &org_id, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
 
-    if req.expected_version < 0 {
+    if req.expected_version.is_some_and(|v| v < 0) {
         return Err(ApiError::bad_request(
             "invalid_expected_version",
             "expected_version must be >= 0",
@@ -586,29 +857,6 @@ async fn update_env(
         .with_request_id(request_id.clone()));
     }
 
-    if req.name.is_none() {
-        return Err(
-            ApiError::bad_request("invalid_update", "No updatable fields provided")
-                .with_request_id(request_id.clone()),
-        );
-    }
-
-    if let Some(name) = req.name.as_ref() {
-        if name.is_empty() {
-            return Err(
-                ApiError::bad_request("invalid_name", "Environment name cannot be empty")
-                    .with_request_id(request_id.clone()),
-            );
-        }
-        if name.len() > 100 {
-            return Err(ApiError::bad_request(
-                "invalid_name",
-                "Environment name cannot exceed 100 characters",
-            )
-            .with_request_id(request_id.clone()));
-        }
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -645,7 +893,7 @@ async fn update_env(
 
     let current = sqlx::query_as::<_, EnvRow>(
         r#"
-        SELECT env_id, org_id, app_id, name, resource_version, created_at, updated_at
+        SELECT env_id, org_id, app_id, name, managed_hostname, preview, expires_at, labels, resource_version, created_at, updated_at
         FROM envs_view
         WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
         "#,
@@ -665,11 +913,19 @@ async fn update_env(
             .with_request_id(request_id.clone())
     })?;
 
-    if req.expected_version != current.resource_version {
-        return Err(
-            ApiError::conflict("version_conflict", "Resource version mismatch")
-                .with_request_id(request_id.clone()),
-        );
+    preconditions.check(
+        req.expected_version,
+        current.resource_version,
+        true,
+        &request_id,
+    )?;
+
+    if env_update_is_noop(&current, &req) {
+        let response = EnvResponse::from(current);
+        return Ok(preconditions::with_etag(
+            response.resource_version,
+            (StatusCode::OK, Json(response)),
+        ));
     }
 
     if let Some(name) = req.name.as_ref() {
@@ -732,24 +988,11 @@ async fn update_env(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "envs",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "envs", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, EnvRow>(
         r#"
-        SELECT env_id, org_id, app_id, name, resource_version, created_at, updated_at
+        SELECT env_id, org_id, app_id, name, managed_hostname, preview, expires_at, labels, resource_version, created_at, updated_at
         FROM envs_view
         WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
         "#,
@@ -770,6 +1013,7 @@ async fn update_env(
     })?;
 
     let response = EnvResponse::from(row);
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -794,13 +1038,25 @@ async fn update_env(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
 }
 
+/// Request deletion of an environment.
+///
+/// DELETE /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}
+///
+/// Emits `env.deletion_requested` and returns 202 with the teardown status;
+/// the cleanup worker drives the teardown and emits `env.deleted`. Repeating
+/// the request while teardown is in progress returns the current status.
 async fn delete_env(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+    Query(query): Query<DeleteEnvQuery>,
+    preconditions: Preconditions,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -831,7 +1087,8 @@ async fn delete_env(
             let hash_input = serde_json::json!({
                 "org_id": org_scope.clone(),
                 "app_id": app_id.to_string(),
-                "env_id": env_id.to_string()
+                "env_id": env_id.to_string(),
+                "delete_volumes": query.delete_volumes
             });
             idempotency::request_hash(endpoint_name, &hash_input)
                 .map(|hash| (key.to_string(), hash))
@@ -857,9 +1114,187 @@ async fn delete_env(
         }
     }
 
+    let row = load_env_delete_row(&state, &request_id, &org_id, &app_id, &env_id).await?;
+
+    if !row.is_deleted && row.deletion_requested_at.is_none() {
+        preconditions.check(None, row.resource_version, false, &request_id)?;
+
+        let current_seq = state
+            .db()
+            .event_store()
+            .get_latest_aggregate_seq(&AggregateType::Env, &env_id.to_string())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to get aggregate sequence");
+                ApiError::internal("internal_error", "Failed to delete environment")
+                    .with_request_id(request_id.clone())
+            })?
+            .unwrap_or(0);
+
+        let payload = EnvDeletionRequestedPayload {
+            env_id,
+            org_id,
+            app_id,
+            delete_volumes: query.delete_volumes,
+        };
+        let payload = serde_json::to_value(&payload).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize env deletion payload");
+            ApiError::internal("internal_error", "Failed to delete environment")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let event = AppendEvent {
+            aggregate_type: AggregateType::Env,
+            aggregate_id: env_id.to_string(),
+            aggregate_seq: current_seq + 1,
+            event_type: event_types::ENV_DELETION_REQUESTED.to_string(),
+            event_version: 1,
+            actor_type,
+            actor_id: actor_id.clone(),
+            org_id: Some(org_id),
+            request_id: request_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+            app_id: Some(app_id),
+            env_id: Some(env_id),
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            ..Default::default()
+        };
+
+        let event_id = state.db().event_store().append(event).await.map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to request env deletion");
+            match e {
+                DbError::SequenceConflict { .. } => ApiError::conflict(
+                    "version_conflict",
+                    "Concurrent environment update detected; retry",
+                ),
+                _ => ApiError::internal("internal_error", "Failed to delete environment"),
+            }
+            .with_request_id(request_id.clone())
+        })?;
+
+        consistency::wait_for_write(&state, &ctx, "envs", event_id.value()).await?;
+    }
+
+    let row = load_env_delete_row(&state, &request_id, &org_id, &app_id, &env_id).await?;
+    let response = load_env_deletion(&state, &request_id, &env_id, &row).await?;
+    let status = if row.is_deleted {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
+    };
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to delete environment")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            &state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok((status, Json(response)).into_response())
+}
+
+/// Restore a deleted environment from the trash, scaled to zero, along with
+/// the routes and volumes its deletion took with it.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/restore
+async fn restore_env(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+) -> Result<Json<RestoreResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let app_id = app_id.to_string();
+    let scope = RestoreScope {
+        org_id: &org_id,
+        app_id: Some(&app_id),
+        env_id: None,
+    };
+    trash::restore(&state, &ctx, scope, TrashKind::Env, &env_id.to_string()).await
+}
+
+/// Get the teardown status of an environment deletion.
+///
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deletion
+async fn get_env_deletion(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+) -> Result<Json<EnvDeletionResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let row = load_env_delete_row(&state, &request_id, &org_id, &app_id, &env_id).await?;
+    if !row.is_deleted && row.deletion_requested_at.is_none() {
+        return Err(ApiError::not_found(
+            "deletion_not_found",
+            format!("Deletion has not been requested for environment {}", env_id),
+        )
+        .with_request_id(request_id));
+    }
+
+    let response = load_env_deletion(&state, &request_id, &env_id, &row).await?;
+    Ok(Json(response))
+}
+
+async fn load_env_delete_row(
+    state: &AppState,
+    request_id: &str,
+    org_id: &OrgId,
+    app_id: &AppId,
+    env_id: &EnvId,
+) -> Result<EnvDeleteRow, ApiError> {
     let row = sqlx::query_as::<_, EnvDeleteRow>(
         r#"
-        SELECT resource_version, is_deleted
+        SELECT resource_version, is_deleted, deletion_requested_at, delete_volumes
         FROM envs_view
         WHERE env_id = $1 AND org_id = $2 AND app_id = $3
         "#,
@@ -871,113 +1306,123 @@ async fn delete_env(
     .await
     .map_err(|e| {
         tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to load env");
-        ApiError::internal("internal_error", "Failed to delete environment")
-            .with_request_id(request_id.clone())
+        ApiError::internal("internal_error", "Failed to load environment deletion")
+            .with_request_id(request_id.to_string())
     })?;
 
-    let Some(row) = row else {
-        return Err(ApiError::not_found(
-            "env_not_found",
-            format!("Environment {} not found", env_id),
-        )
-        .with_request_id(request_id.clone()));
-    };
-
-    let response = DeleteResponse { ok: true };
-    if row.is_deleted {
-        if let Some((key, hash)) = request_hash {
-            let body = serde_json::to_value(&response).map_err(|e| {
-                tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
-                ApiError::internal("internal_error", "Failed to delete environment")
-                    .with_request_id(request_id.clone())
-            })?;
-
-            let _ = idempotency::store(
-                &state,
-                idempotency::StoreIdempotencyParams {
-                    org_scope: &org_scope,
-                    actor_id: &actor_id,
-                    endpoint_name,
-                    idempotency_key: &key,
-                    request_hash: &hash,
-                    status: StatusCode::OK,
-                    body: Some(body),
-                },
-                &request_id,
-            )
-            .await;
-        }
-
-        return Ok((StatusCode::OK, Json(response)).into_response());
-    }
-
-    let next_version = row.resource_version + 1;
-    let payload = serde_json::json!({});
-
-    let event = AppendEvent {
-        aggregate_type: AggregateType::Env,
-        aggregate_id: env_id.to_string(),
-        aggregate_seq: next_version,
-        event_type: event_types::ENV_DELETED.to_string(),
-        event_version: 1,
-        actor_type,
-        actor_id: actor_id.clone(),
-        org_id: Some(org_id),
-        request_id: request_id.clone(),
-        idempotency_key: idempotency_key.clone(),
-        app_id: Some(app_id),
-        env_id: Some(env_id),
-        correlation_id: None,
-        causation_id: None,
-        payload,
-        ..Default::default()
-    };
-
-    let event_id = state.db().event_store().append(event).await.map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to delete env");
-        ApiError::internal("internal_error", "Failed to delete environment")
-            .with_request_id(request_id.clone())
-    })?;
+    row.ok_or_else(|| {
+        ApiError::not_found("env_not_found", format!("Environment {} not found", env_id))
+            .with_request_id(request_id.to_string())
+    })
+}
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "envs",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
+async fn load_env_deletion(
+    state: &AppState,
+    request_id: &str,
+    env_id: &EnvId,
+    row: &EnvDeleteRow,
+) -> Result<EnvDeletionResponse, ApiError> {
+    let progress = if row.is_deleted {
+        None
+    } else {
+        let progress = teardown::load_env_progress(
+            state.db().pool(),
+            &env_id.to_string(),
+            row.delete_volumes,
         )
         .await
         .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
+            tracing::error!(error = %e, request_id = %request_id, env_id = %env_id, "Failed to load teardown progress");
+            ApiError::internal("internal_error", "Failed to load environment deletion")
+                .with_request_id(request_id.to_string())
         })?;
+        Some(progress)
+    };
 
-    if let Some((key, hash)) = request_hash {
-        let body = serde_json::to_value(&response).map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
-            ApiError::internal("internal_error", "Failed to delete environment")
-                .with_request_id(request_id.clone())
-        })?;
+    Ok(env_deletion_response(
+        env_id.to_string(),
+        row.deletion_requested_at,
+        row.delete_volumes,
+        progress.as_ref(),
+    ))
+}
 
-        let _ = idempotency::store(
-            &state,
-            idempotency::StoreIdempotencyParams {
-                org_scope: &org_scope,
-                actor_id: &actor_id,
-                endpoint_name,
-                idempotency_key: &key,
-                request_hash: &hash,
-                status: StatusCode::OK,
-                body: Some(body),
-            },
-            &request_id,
-        )
-        .await;
+/// Build the deletion status; `progress` is `None` once the env is deleted.
+fn env_deletion_response(
+    env_id: String,
+    requested_at: Option<DateTime<Utc>>,
+    delete_volumes: bool,
+    progress: Option<&EnvTeardownProgress>,
+) -> EnvDeletionResponse {
+    let current_step = progress.map(EnvTeardownProgress::next_step);
+    let steps = EnvTeardownStep::ALL
+        .into_iter()
+        .map(|step| {
+            let (status, remaining) = match (progress, current_step) {
+                (Some(progress), Some(current)) if step == current => {
+                    ("in_progress", progress.remaining(step))
+                }
+                (Some(progress), Some(current)) if step > current => {
+                    ("pending", progress.remaining(step))
+                }
+                _ => ("completed", 0),
+            };
+            DeletionStepResponse {
+                name: step,
+                status: status.to_string(),
+                remaining,
+            }
+        })
+        .collect();
+
+    EnvDeletionResponse {
+        env_id,
+        status: if progress.is_some() {
+            "in_progress".to_string()
+        } else {
+            "completed".to_string()
+        },
+        delete_volumes,
+        requested_at,
+        current_step,
+        steps,
     }
+}
+
+/// Update labels on an environment.
+///
+/// PATCH /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/labels
+async fn patch_env_labels(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<PatchLabelsRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    super::labels::patch_labels(
+        &state,
+        &ctx,
+        org_id,
+        LabelTarget::Env { app_id, env_id },
+        &preconditions,
+        req,
+    )
+    .await
 }
 
 /// List environments in an application.
@@ -988,6 +1433,7 @@ async fn list_envs(
     ctx: RequestContext,
     Path((org_id, app_id)): Path<(String, String)>,
     Query(query): Query<ListEnvsQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
 
@@ -1004,49 +1450,50 @@ async fn list_envs(
 
     let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
 
-    let limit: i64 = query.limit.unwrap_or(50).clamp(1, 200);
-    let cursor = match query.cursor.as_deref() {
-        Some(raw) => {
-            let _: EnvId = raw.parse().map_err(|_| {
-                ApiError::bad_request("invalid_cursor", "Invalid cursor format")
-                    .with_request_id(request_id.clone())
-            })?;
-            Some(raw.to_string())
-        }
-        None => None,
+    let page = ENV_SORTING.page(page, &request_id)?;
+    let selector = LabelSelector::from_query(query.label_selector.as_deref(), &request_id)?;
+    let filters = |builder: &mut QueryBuilder<'_, Postgres>| {
+        builder.push("org_id = ");
+        builder.push_bind(org_id.to_string());
+        builder.push(" AND app_id = ");
+        builder.push_bind(app_id.to_string());
+        builder.push(" AND NOT is_deleted AND labels_match(labels, ");
+        builder.push_bind(selector.clone());
+        builder.push("::JSONB)");
     };
 
-    // Query the envs_view table (stable ordering by env_id)
-    let rows = sqlx::query_as::<_, EnvRow>(
-        r#"
-        SELECT env_id, app_id, org_id, name, resource_version, created_at, updated_at
-        FROM envs_view
-        WHERE org_id = $1 AND app_id = $2 AND NOT is_deleted
-          AND ($3::TEXT IS NULL OR env_id > $3)
-        ORDER BY env_id ASC
-        LIMIT $4
-        "#,
-    )
-    .bind(org_id.to_string())
-    .bind(app_id.to_string())
-    .bind(cursor.as_deref())
-    .bind(limit)
-    .fetch_all(state.db().pool())
-    .await
-    .map_err(|e| {
+    let mut builder = QueryBuilder::new(
+        "SELECT env_id, app_id, org_id, name, managed_hostname, preview, expires_at, labels, \
+                resource_version, created_at, updated_at \
+         FROM envs_view WHERE ",
+    );
+    filters(&mut builder);
+    page.push_after(&mut builder);
+    page.push_order(&mut builder);
+
+    let list_failed = |e: sqlx::Error| {
         tracing::error!(error = %e, request_id = %request_id, "Failed to list envs");
         ApiError::internal("internal_error", "Failed to list environments")
             .with_request_id(request_id.clone())
-    })?;
+    };
+    let rows = builder
+        .build_query_as::<EnvRow>()
+        .fetch_all(state.db().pool())
+        .await
+        .map_err(list_failed)?;
+    let total = page
+        .total(state.db().pool(), "envs_view", filters)
+        .await
+        .map_err(list_failed)?;
 
+    let (rows, next_cursor) = page.finish(rows);
     let items: Vec<EnvResponse> = rows.into_iter().map(EnvResponse::from).collect();
-    let next_cursor = if items.len() == limit as usize {
-        items.last().map(|e| e.id.clone())
-    } else {
-        None
-    };
 
-    Ok(Json(ListEnvsResponse { items, next_cursor }))
+    Ok(Json(ListEnvsResponse {
+        items,
+        next_cursor,
+        total,
+    }))
 }
 
 /// Get desired scale for an environment.
@@ -1076,15 +1523,18 @@ async fn get_scale(
 
     let _role = authz::require_org_member(&state, &org_id_typed, &ctx).await?;
 
-    Ok(Json(
-        load_scale_state(
-            &state,
-            &request_id,
-            &org_id_typed,
-            &app_id_typed,
-            &env_id_typed,
-        )
-        .await?,
+    let scale = load_scale_state(
+        &state,
+        &request_id,
+        &org_id_typed,
+        &app_id_typed,
+        &env_id_typed,
+    )
+    .await?;
+
+    Ok(preconditions::with_etag(
+        scale.resource_version,
+        Json(scale),
     ))
 }
 
@@ -1095,11 +1545,11 @@ async fn update_scale(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
-    Json(mut req): Json<ScaleUpdateRequest>,
+    preconditions: Preconditions,
+    Json(req): Json<ScaleUpdateRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
     let actor_id = ctx.actor_id.clone();
     let endpoint_name = "envs.set_scale";
 
@@ -1121,7 +1571,7 @@ async fn update_scale(
     let role = authz::require_org_member(&state, &org_id_typed, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
 
-    if req.expected_version < 0 {
+    if req.expected_version.is_some_and(|v| v < 0) {
         return Err(ApiError::bad_request(
             "invalid_expected_version",
             "expected_version must be >= 0",
@@ -1129,42 +1579,9 @@ async fn update_scale(
         .with_request_id(request_id));
     }
 
-    if req.processes.is_empty() {
-        return Err(
-            ApiError::bad_request("invalid_processes", "processes cannot be empty")
-                .with_request_id(request_id),
-        );
-    }
-
-    for process in &req.processes {
-        if process.process_type.trim().is_empty() {
-            return Err(ApiError::bad_request(
-                "invalid_process_type",
-                "process_type cannot be empty",
-            )
-            .with_request_id(request_id.clone()));
-        }
-        if process.desired < 0 {
-            return Err(
-                ApiError::bad_request("invalid_desired", "desired must be >= 0")
-                    .with_request_id(request_id.clone()),
-            );
-        }
-    }
-
-    req.processes
-        .sort_by(|a, b| a.process_type.cmp(&b.process_type));
-    for pair in req.processes.windows(2) {
-        if let [a, b] = pair {
-            if a.process_type == b.process_type {
-                return Err(ApiError::bad_request(
-                    "duplicate_process_type",
-                    "process_type values must be unique",
-                )
-                .with_request_id(request_id));
-            }
-        }
-    }
+    let reason = normalize_change_reason(req.reason.as_deref()).map_err(|message| {
+        ApiError::bad_request("invalid_reason", message).with_request_id(request_id.clone())
+    })?;
 
     let org_scope = org_id_typed.to_string();
     let request_hash = idempotency_key
@@ -1202,79 +1619,64 @@ async fn update_scale(
     )
     .await?;
 
-    if req.expected_version != current.resource_version {
-        return Err(
-            ApiError::conflict("version_conflict", "Resource version mismatch")
-                .with_request_id(request_id.clone()),
-        );
-    }
+    preconditions.check(
+        req.expected_version,
+        current.resource_version,
+        true,
+        &request_id,
+    )?;
 
-    let event_store = state.db().event_store();
-    let current_seq = event_store
-        .get_latest_aggregate_seq(&AggregateType::Env, &env_id_typed.to_string())
+    let disk_delta = ephemeral_disk_delta(&current.processes, &req.processes);
+    if disk_delta > 0 {
+        if let Some(exceeded) = check_quota(
+            state.db().pool(),
+            &org_id_typed,
+            QuotaDimension::MaxTotalEphemeralDiskBytes,
+            disk_delta,
+        )
         .await
         .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Failed to get aggregate sequence");
+            tracing::error!(error = %e, request_id = %request_id, "Failed to check quota");
             ApiError::internal("internal_error", "Failed to set scale")
                 .with_request_id(request_id.clone())
-        })?
-        .unwrap_or(0);
-
-    let scales: Vec<serde_json::Value> = req
-        .processes
-        .iter()
-        .map(|p| {
-            serde_json::json!({
-                "process_type": &p.process_type,
-                "desired": p.desired
-            })
-        })
-        .collect();
+        })? {
+            return Err(ApiError::conflict(
+                "quota_exceeded",
+                format!(
+                    "Quota exceeded for {}: limit={}, current={}, requested={}",
+                    exceeded.dimension,
+                    exceeded.limit,
+                    exceeded.current_usage,
+                    exceeded.requested_delta
+                ),
+            )
+            .with_request_id(request_id));
+        }
+    }
 
-    let event = AppendEvent {
-        aggregate_type: AggregateType::Env,
-        aggregate_id: env_id_typed.to_string(),
-        aggregate_seq: current_seq + 1,
-        event_type: "env.scale_set".to_string(),
-        event_version: 1,
-        actor_type,
-        actor_id: actor_id.clone(),
-        org_id: Some(org_id_typed),
-        request_id: request_id.clone(),
-        idempotency_key: idempotency_key.clone(),
-        app_id: Some(app_id_typed),
-        env_id: Some(env_id_typed),
-        correlation_id: None,
-        causation_id: None,
-        payload: serde_json::json!({
-            "env_id": env_id,
-            "org_id": org_id,
-            "app_id": app_id,
-            "scales": scales
-        }),
-        ..Default::default()
+    let command = EnvCommand::SetScale {
+        processes: req.processes.iter().map(ProcessScale::to_spec).collect(),
+        reason,
     };
-
-    let event_id = event_store.append(event).await.map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to set scale");
+    let event_ids = aggregates::execute::<EnvAggregate, _>(
+        &state.db().event_store(),
+        &ctx,
+        &env_id_typed.to_string(),
+        command,
+    )
+    .await
+    .map_err(|e| {
+        if matches!(e, CommandError::Store(_) | CommandError::Event(_)) {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to set scale");
+        }
+        ApiError::from(e).with_request_id(request_id.clone())
+    })?;
+    let event_id = event_ids.last().copied().ok_or_else(|| {
         ApiError::internal("internal_error", "Failed to set scale")
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "env_config",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "env_config", event_id.value()).await?;
 
     let updated = load_scale_state(
         &state,
@@ -1308,7 +1710,10 @@ async fn update_scale(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(updated)).into_response())
+    Ok(preconditions::with_etag(
+        updated.resource_version,
+        (StatusCode::OK, Json(updated)),
+    ))
 }
 
 /// Get a single environment by ID.
@@ -1342,7 +1747,7 @@ async fn get_env(
     // Query the envs_view table
     let row = sqlx::query_as::<_, EnvRow>(
         r#"
-        SELECT env_id, app_id, org_id, name, resource_version, created_at, updated_at
+        SELECT env_id, app_id, org_id, name, managed_hostname, preview, expires_at, labels, resource_version, created_at, updated_at
         FROM envs_view
         WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
         "#,
@@ -1364,7 +1769,13 @@ async fn get_env(
     })?;
 
     match row {
-        Some(row) => Ok(Json(EnvResponse::from(row))),
+        Some(row) => {
+            let response = EnvResponse::from(row);
+            Ok(preconditions::with_etag(
+                response.resource_version,
+                Json(response),
+            ))
+        }
         None => Err(ApiError::not_found(
             "env_not_found",
             format!("Environment {} not found", env_id),
@@ -1373,6 +1784,67 @@ async fn get_env(
     }
 }
 
+/// Get a single environment by name, e.g. to import it by name.
+///
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/by-name/{name}
+async fn get_env_by_name(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, name)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let row = load_env_by_name(&state, &org_id, &app_id, &name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, app_id = %app_id, "Failed to get env");
+            ApiError::internal("internal_error", "Failed to get environment")
+                .with_request_id(request_id.clone())
+        })?
+        .ok_or_else(|| {
+            ApiError::not_found("env_not_found", format!("Environment '{}' not found", name))
+                .with_request_id(request_id.clone())
+        })?;
+
+    let response = EnvResponse::from(row);
+    Ok(preconditions::with_etag(
+        response.resource_version,
+        Json(response),
+    ))
+}
+
+/// The live env named `name` in the app. Names are unique among live envs.
+async fn load_env_by_name(
+    state: &AppState,
+    org_id: &OrgId,
+    app_id: &AppId,
+    name: &str,
+) -> Result<Option<EnvRow>, sqlx::Error> {
+    sqlx::query_as::<_, EnvRow>(
+        r#"
+        SELECT env_id, app_id, org_id, name, managed_hostname, preview, expires_at, labels, resource_version, created_at, updated_at
+        FROM envs_view
+        WHERE org_id = $1 AND app_id = $2 AND name = $3 AND NOT is_deleted
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .bind(name)
+    .fetch_optional(state.db().pool())
+    .await
+}
+
 /// Get environment status (desired vs current state).
 ///
 /// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/status
@@ -1606,6 +2078,35 @@ async fn get_status(
             .with_request_id(request_id.clone())
     })?;
 
+    // 9. Recent events (newest first)
+    let recent_events = sqlx::query_as::<_, EnvStatusEventRow>(
+        r#"
+        SELECT event_id, occurred_at, event_type, aggregate_type, aggregate_id
+        FROM events
+        WHERE env_id = $1
+        ORDER BY event_id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(STATUS_RECENT_EVENTS)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to get recent events");
+        ApiError::internal("internal_error", "Failed to get environment status")
+            .with_request_id(request_id.clone())
+    })?
+    .into_iter()
+    .map(|row| EnvStatusEvent {
+        event_id: row.event_id,
+        occurred_at: row.occurred_at,
+        event_type: row.event_type,
+        aggregate_type: row.aggregate_type,
+        aggregate_id: row.aggregate_id,
+    })
+    .collect();
+
     // Calculate release_synced
     let release_synced = match (&current_release, &desired_release) {
         (Some(c), Some(d)) => c == d,
@@ -1640,12 +2141,46 @@ async fn get_status(
         routes,
         last_reconcile_at: last_reconcile,
         last_error,
+        recent_events,
         status: overall_status.to_string(),
     };
 
     Ok(Json(response))
 }
 
+/// Allocate the managed hostname for a new env and the route to create for
+/// it. `None` when managed hostnames are off, or misconfigured (logged; the
+/// env is still created).
+async fn managed_route(
+    state: &AppState,
+    env_id: EnvId,
+    env_name: &str,
+    app_id: AppId,
+) -> Result<Option<(String, ManagedRouteTemplate)>, ApiError> {
+    if managed_dns::platform_domain().is_none() {
+        return Ok(None);
+    }
+    let template = match ManagedRouteTemplate::from_env() {
+        Ok(template) => template,
+        Err(e) => {
+            tracing::error!(error = %e, "Skipping managed hostname for new env");
+            return Ok(None);
+        }
+    };
+    let hostname = managed_dns::allocate(
+        state.db().pool(),
+        &env_id.to_string(),
+        env_name,
+        &app_id.to_string(),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to allocate managed hostname");
+        ApiError::internal("internal_error", "Failed to create environment")
+    })?;
+    Ok(hostname.map(|hostname| (hostname, template)))
+}
+
 // =============================================================================
 // Database Row Types
 // =============================================================================
@@ -1667,20 +2202,64 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AppInfoRow {
     }
 }
 
+/// Orderings accepted when listing environments.
+static ENV_SORTING: Sorting<EnvRow> = Sorting {
+    id_column: "env_id",
+    id: |row| row.env_id.clone(),
+    keys: &[
+        SortKey {
+            name: "id",
+            column: "env_id",
+            sql_type: "TEXT",
+            value: |row| row.env_id.clone(),
+        },
+        SortKey {
+            name: "name",
+            column: "name",
+            sql_type: "TEXT",
+            value: |row| row.name.clone(),
+        },
+        SortKey {
+            name: "created_at",
+            column: "created_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.created_at),
+        },
+        SortKey {
+            name: "updated_at",
+            column: "updated_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.updated_at),
+        },
+    ],
+};
+
 /// Row from envs_view table.
 struct EnvRow {
     env_id: String,
     app_id: String,
     org_id: String,
     name: String,
+    managed_hostname: Option<String>,
+    preview: bool,
+    expires_at: Option<DateTime<Utc>>,
+    labels: serde_json::Value,
     resource_version: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
 
+/// Whether an update leaves the env as it is (absent fields are unchanged),
+/// so it is answered without a new version.
+fn env_update_is_noop(current: &EnvRow, req: &UpdateEnvRequest) -> bool {
+    req.name.as_ref().is_none_or(|name| *name == current.name)
+}
+
 struct EnvDeleteRow {
     resource_version: i32,
     is_deleted: bool,
+    deletion_requested_at: Option<DateTime<Utc>>,
+    delete_volumes: bool,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for EnvRow {
@@ -1691,6 +2270,10 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for EnvRow {
             app_id: row.try_get("app_id")?,
             org_id: row.try_get("org_id")?,
             name: row.try_get("name")?,
+            managed_hostname: row.try_get("managed_hostname")?,
+            preview: row.try_get("preview")?,
+            expires_at: row.try_get("expires_at")?,
+            labels: row.try_get("labels")?,
             resource_version: row.try_get("resource_version")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
@@ -1704,6 +2287,8 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for EnvDeleteRow {
         Ok(Self {
             resource_version: row.try_get("resource_version")?,
             is_deleted: row.try_get("is_deleted")?,
+            deletion_requested_at: row.try_get("deletion_requested_at")?,
+            delete_volumes: row.try_get("delete_volumes")?,
         })
     }
 }
@@ -1715,6 +2300,10 @@ impl From<EnvRow> for EnvResponse {
             app_id: row.app_id,
             org_id: row.org_id,
             name: row.name,
+            managed_hostname: row.managed_hostname,
+            preview: row.preview,
+            expires_at: row.expires_at,
+            labels: labels::from_json(row.labels),
             resource_version: row.resource_version,
             created_at: row.created_at,
             updated_at: row.updated_at,
@@ -1725,6 +2314,12 @@ impl From<EnvRow> for EnvResponse {
 struct ScaleRow {
     process_type: String,
     desired_replicas: i32,
+    node_selector: serde_json::Value,
+    tolerations: serde_json::Value,
+    ephemeral_disk_bytes: Option<i64>,
+    max_surge: Option<String>,
+    max_unavailable: Option<String>,
+    secrets_reload: Option<serde_json::Value>,
     resource_version: i32,
     updated_at: DateTime<Utc>,
 }
@@ -1735,6 +2330,12 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ScaleRow {
         Ok(Self {
             process_type: row.try_get("process_type")?,
             desired_replicas: row.try_get("desired_replicas")?,
+            node_selector: row.try_get("node_selector")?,
+            tolerations: row.try_get("tolerations")?,
+            ephemeral_disk_bytes: row.try_get("ephemeral_disk_bytes")?,
+            max_surge: row.try_get("max_surge")?,
+            max_unavailable: row.try_get("max_unavailable")?,
+            secrets_reload: row.try_get("secrets_reload")?,
             resource_version: row.try_get("resource_version")?,
             updated_at: row.try_get("updated_at")?,
         })
@@ -1800,15 +2401,131 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for RouteInfoRow {
     }
 }
 
+/// Row for a recent env event.
+struct EnvStatusEventRow {
+    event_id: i64,
+    occurred_at: DateTime<Utc>,
+    event_type: String,
+    aggregate_type: String,
+    aggregate_id: String,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for EnvStatusEventRow {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            event_id: row.try_get("event_id")?,
+            occurred_at: row.try_get("occurred_at")?,
+            event_type: row.try_get("event_type")?,
+            aggregate_type: row.try_get("aggregate_type")?,
+            aggregate_id: row.try_get("aggregate_id")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn env_row(name: &str) -> EnvRow {
+        EnvRow {
+            env_id: "env_1".to_string(),
+            app_id: "app_1".to_string(),
+            org_id: "org_1".to_string(),
+            name: name.to_string(),
+            managed_hostname: None,
+            preview: false,
+            expires_at: None,
+            labels: serde_json::json!({}),
+            resource_version: 3,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_env_update_is_noop() {
+        let current = env_row("staging");
+        let update = |body: &str| serde_json::from_str::<UpdateEnvRequest>(body).unwrap();
+
+        assert!(env_update_is_noop(&current, &update("{}")));
+        assert!(env_update_is_noop(
+            &current,
+            &update(r#"{"expected_version": 3}"#)
+        ));
+        assert!(env_update_is_noop(
+            &current,
+            &update(r#"{"name": "staging"}"#)
+        ));
+        assert!(!env_update_is_noop(
+            &current,
+            &update(r#"{"name": "prod"}"#)
+        ));
+    }
+
     #[test]
     fn test_create_env_request_deserialization() {
         let json = r#"{"name": "production"}"#;
         let req: CreateEnvRequest = serde_json::from_str(json).unwrap();
         assert_eq!(req.name, "production");
+        assert!(!req.preview);
+        assert_eq!(req.ttl_seconds, None);
+    }
+
+    #[test]
+    fn test_create_env_request_preview_validation() {
+        use crate::api::validation::validate;
+
+        let request = |body: &str| serde_json::from_str::<CreateEnvRequest>(body).unwrap();
+        assert!(validate(&request(r#"{"name": "pr-42", "preview": true}"#)).is_ok());
+        assert!(validate(&request(
+            r#"{"name": "pr-42", "preview": true, "ttl_seconds": 3600}"#
+        ))
+        .is_ok());
+
+        for body in [
+            r#"{"name": "staging", "ttl_seconds": 3600}"#,
+            r#"{"name": "pr-42", "preview": true, "ttl_seconds": 60}"#,
+            r#"{"name": "pr-42", "preview": true, "ttl_seconds": 31536000}"#,
+        ] {
+            let err = validate(&request(body)).unwrap_err();
+            assert_eq!(err.problem.code, "invalid_preview");
+        }
+    }
+
+    #[test]
+    fn test_ephemeral_disk_delta() {
+        const GIB: i64 = 1024 * 1024 * 1024;
+        let process = |process_type: &str, desired: i32, disk: Option<i64>| ProcessScale {
+            process_type: process_type.to_string(),
+            desired,
+            node_selector: None,
+            tolerations: None,
+            ephemeral_disk_bytes: disk,
+            max_surge: None,
+            max_unavailable: None,
+            secrets_reload: None,
+        };
+        let current = vec![
+            process("web", 2, None),
+            process("worker", 1, Some(10 * GIB)),
+        ];
+
+        // Scaling web up reserves another default-sized disk.
+        assert_eq!(
+            ephemeral_disk_delta(&current, &[process("web", 3, None)]),
+            DEFAULT_EPHEMERAL_DISK_BYTES
+        );
+        // Resizing keeps the replica count and swaps the per-instance size.
+        assert_eq!(
+            ephemeral_disk_delta(&current, &[process("worker", 1, Some(2 * GIB))]),
+            -8 * GIB
+        );
+        // New process types start from nothing.
+        assert_eq!(
+            ephemeral_disk_delta(&current, &[process("cron", 2, Some(GIB))]),
+            2 * GIB
+        );
     }
 
     #[test]
@@ -1818,6 +2535,10 @@ mod tests {
             app_id: "app_456".to_string(),
             org_id: "org_789".to_string(),
             name: "staging".to_string(),
+            managed_hostname: None,
+            preview: false,
+            expires_at: None,
+            labels: Labels::new(),
             resource_version: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -1827,6 +2548,10 @@ mod tests {
         assert!(json.contains("\"id\":\"env_123\""));
         assert!(json.contains("\"app_id\":\"app_456\""));
         assert!(json.contains("\"name\":\"staging\""));
+        assert!(json.contains("\"labels\":{}"));
+        assert!(!json.contains("managed_hostname"));
+        assert!(json.contains("\"preview\":false"));
+        assert!(!json.contains("expires_at"));
     }
 
     #[test]
@@ -1855,6 +2580,13 @@ mod tests {
             }],
             last_reconcile_at: Some(Utc::now()),
             last_error: None,
+            recent_events: vec![EnvStatusEvent {
+                event_id: 42,
+                occurred_at: Utc::now(),
+                event_type: "deploy.status_changed".to_string(),
+                aggregate_type: "deploy".to_string(),
+                aggregate_id: "dep_123".to_string(),
+            }],
             status: "healthy".to_string(),
         };
 
@@ -1866,6 +2598,7 @@ mod tests {
         assert!(json.contains("\"desired\":3"));
         assert!(json.contains("\"ready\":3"));
         assert!(json.contains("\"hostname\":\"myapp.example.com\""));
+        assert!(json.contains("\"event_type\":\"deploy.status_changed\""));
         // last_error should be omitted when None
         assert!(!json.contains("\"last_error\""));
     }
@@ -1891,6 +2624,7 @@ mod tests {
             routes: vec![],
             last_reconcile_at: None,
             last_error: None,
+            recent_events: vec![],
             status: "degraded".to_string(),
         };
 
@@ -1898,4 +2632,50 @@ mod tests {
         assert!(json.contains("\"release_synced\":false"));
         assert!(json.contains("\"status\":\"degraded\""));
     }
+
+    #[test]
+    fn test_env_deletion_response_in_progress() {
+        let progress = EnvTeardownProgress {
+            active_instances: 2,
+            unarchived_bundles: vec!["sb_1".to_string()],
+            ..Default::default()
+        };
+        let response = env_deletion_response(
+            "env_123".to_string(),
+            Some(Utc::now()),
+            false,
+            Some(&progress),
+        );
+
+        assert_eq!(response.status, "in_progress");
+        assert_eq!(response.current_step, Some(EnvTeardownStep::DrainInstances));
+        let statuses: Vec<(&str, i64)> = response
+            .steps
+            .iter()
+            .map(|s| (s.status.as_str(), s.remaining))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("completed", 0),
+                ("completed", 0),
+                ("in_progress", 2),
+                ("pending", 0),
+                ("pending", 0),
+                ("pending", 1),
+                ("pending", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_env_deletion_response_completed() {
+        let response = env_deletion_response("env_123".to_string(), Some(Utc::now()), true, None);
+        assert_eq!(response.status, "completed");
+        assert!(response.steps.iter().all(|s| s.status == "completed"));
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(!json.contains("\"current_step\""));
+        assert!(json.contains("\"name\":\"archive_secrets\""));
+    }
 }
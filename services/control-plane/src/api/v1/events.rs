@@ -1,6 +1,8 @@
 //! Events API endpoints.
 //!
-//! Provides org-scoped event querying for debugging and introspection.
+//! Provides org-scoped event querying for debugging and introspection, and
+//! a cross-org query for operators (`GET /v1/_admin/events`) and event log
+//! integrity verification (`GET /v1/_admin/events/verify`).
 
 use std::{collections::VecDeque, convert::Infallible, sync::OnceLock, time::Duration};
 
@@ -9,7 +11,8 @@ use axum::{
     extract::{Path, Query, State},
     http::{header::CONTENT_TYPE, HeaderValue},
     response::{IntoResponse, Response},
-    Json,
+    routing::get,
+    Json, Router,
 };
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
@@ -26,23 +29,34 @@ const STREAM_BATCH_LIMIT: i64 = 200;
 const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 use crate::api::error::ApiError;
+use crate::api::fields::{FieldsQuery, Shape};
+use crate::api::pagination::{Page, PageQuery, PageTotal, SortKey, Sorting, MAX_TOTAL};
 use crate::api::request_context::RequestContext;
-use crate::db::EventRow;
+use crate::db::{DbError, EventFilter, EventRow};
+use crate::event_integrity;
 use crate::state::AppState;
 
-/// Query parameters for listing events.
+/// Query parameters for listing events. Pagination is in [`PageQuery`].
 #[derive(Debug, Deserialize)]
 pub struct ListEventsQuery {
     /// Return events with event_id > after_event_id.
     pub after_event_id: Option<i64>,
-    /// Max number of events to return.
-    pub limit: Option<i64>,
-    /// Filter by exact event type.
+    /// Filter by event type (exact, or a prefix ending in `*`).
     pub event_type: Option<String>,
     /// Filter by app_id.
     pub app_id: Option<String>,
     /// Filter by env_id.
     pub env_id: Option<String>,
+    /// Filter by aggregate type (e.g. `deploy`).
+    pub aggregate_type: Option<String>,
+    /// Filter by aggregate ID.
+    pub aggregate_id: Option<String>,
+    /// Filter by actor type (`user`, `service_principal`, `system`).
+    pub actor_type: Option<String>,
+    /// Filter by actor ID.
+    pub actor_id: Option<String>,
+    /// Only events at or after this RFC 3339 timestamp.
+    pub since: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,9 +66,30 @@ pub struct StreamEventsQuery {
     pub event_type: Option<String>,
     pub app_id: Option<String>,
     pub env_id: Option<String>,
+    pub aggregate_type: Option<String>,
+    pub aggregate_id: Option<String>,
+    pub actor_type: Option<String>,
+    pub actor_id: Option<String>,
+    pub since: Option<String>,
     pub poll_ms: Option<u64>,
 }
 
+/// Query parameters for the operator event query.
+#[derive(Debug, Deserialize)]
+struct AdminEventsQuery {
+    /// Only events of this organization.
+    org_id: Option<String>,
+    after_event_id: Option<i64>,
+    event_type: Option<String>,
+    app_id: Option<String>,
+    env_id: Option<String>,
+    aggregate_type: Option<String>,
+    aggregate_id: Option<String>,
+    actor_type: Option<String>,
+    actor_id: Option<String>,
+    since: Option<String>,
+}
+
 /// Response event shape (subset + payload).
 #[derive(Debug, Serialize)]
 pub struct EventResponse {
@@ -86,7 +121,34 @@ pub struct EventResponse {
 #[derive(Debug, Serialize)]
 pub struct EventsResponse {
     pub items: Vec<EventResponse>,
+    /// Highest event_id returned (or `after_event_id`), for tailing.
     pub next_after_event_id: i64,
+    pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
+}
+
+/// An event in the operator query, which spans organizations.
+#[derive(Debug, Serialize)]
+struct AdminEventResponse {
+    org_id: Option<String>,
+    #[serde(flatten)]
+    event: EventResponse,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminEventsResponse {
+    items: Vec<AdminEventResponse>,
+    next_after_event_id: i64,
+    next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<PageTotal>,
+}
+
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/events", get(list_admin_events))
+        .route("/events/verify", get(verify_events))
 }
 
 #[derive(Debug, Serialize)]
@@ -99,6 +161,8 @@ struct EventStreamLine {
     pub aggregate_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aggregate_id: Option<String>,
+    pub actor_type: String,
+    pub actor_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -110,10 +174,7 @@ struct EventStreamLine {
 struct EventStreamState {
     state: AppState,
     org_id: OrgId,
-    org_id_str: String,
-    event_type: Option<String>,
-    app_id: Option<String>,
-    env_id: Option<String>,
+    filter: EventFilter,
     limit: i64,
     poll_interval: Duration,
     last_id: i64,
@@ -128,8 +189,11 @@ pub async fn list_events(
     ctx: RequestContext,
     Path(org_id): Path<String>,
     Query(query): Query<ListEventsQuery>,
+    Query(page): Query<PageQuery>,
+    Query(fields): Query<FieldsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
+    let selection = EVENT_SHAPE.select(fields, &request_id)?;
 
     let org_id: OrgId = org_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
@@ -139,80 +203,254 @@ pub async fn list_events(
     let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
 
     let after_event_id = query.after_event_id.unwrap_or(0).max(0);
-    let limit: i32 = query.limit.unwrap_or(50).clamp(1, 200) as i32;
+    let page = EVENT_SORTING.page(page, &request_id)?;
+
+    let filter = EventFilter {
+        event_type: query.event_type,
+        aggregate_type: query.aggregate_type,
+        aggregate_id: query.aggregate_id,
+        actor_type: query.actor_type,
+        actor_id: query.actor_id,
+        app_id: query.app_id,
+        env_id: query.env_id,
+        since: parse_since(query.since.as_deref(), &request_id)?,
+    };
+
+    let (rows, next_cursor, total) = query_page(
+        &state,
+        Some(&org_id),
+        &filter,
+        after_event_id,
+        &page,
+        &request_id,
+    )
+    .await?;
+
+    let with_payload = selection.wants("payload");
+    let items: Vec<EventResponse> = rows
+        .into_iter()
+        .map(|row| event_response(row, with_payload))
+        .collect();
+    let next_after_event_id = items
+        .iter()
+        .map(|e| e.event_id)
+        .max()
+        .unwrap_or(after_event_id);
+
+    Ok(selection.respond(EventsResponse {
+        items,
+        next_after_event_id,
+        next_cursor,
+        total,
+    }))
+}
+
+/// Query events across organizations (operators only).
+///
+/// GET /v1/_admin/events
+async fn list_admin_events(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<AdminEventsQuery>,
+    Query(page): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let org_id: Option<OrgId> = query
+        .org_id
+        .as_deref()
+        .map(|org_id| {
+            org_id.parse().map_err(|_| {
+                ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+                    .with_request_id(request_id.clone())
+            })
+        })
+        .transpose()?;
+
+    let after_event_id = query.after_event_id.unwrap_or(0).max(0);
+    let page = EVENT_SORTING.page(page, &request_id)?;
+
+    let filter = EventFilter {
+        event_type: query.event_type,
+        aggregate_type: query.aggregate_type,
+        aggregate_id: query.aggregate_id,
+        actor_type: query.actor_type,
+        actor_id: query.actor_id,
+        app_id: query.app_id,
+        env_id: query.env_id,
+        since: parse_since(query.since.as_deref(), &request_id)?,
+    };
+
+    let (rows, next_cursor, total) = query_page(
+        &state,
+        org_id.as_ref(),
+        &filter,
+        after_event_id,
+        &page,
+        &request_id,
+    )
+    .await?;
+
+    let items: Vec<AdminEventResponse> = rows
+        .into_iter()
+        .map(|row| AdminEventResponse {
+            org_id: row.org_id.clone(),
+            event: event_response(row, true),
+        })
+        .collect();
+    let next_after_event_id = items
+        .iter()
+        .map(|e| e.event.event_id)
+        .max()
+        .unwrap_or(after_event_id);
+
+    Ok(Json(AdminEventsResponse {
+        items,
+        next_after_event_id,
+        next_cursor,
+        total,
+    }))
+}
+
+/// Fields of listed events.
+static EVENT_SHAPE: Shape = Shape {
+    key: "event_id",
+    fields: &[
+        "occurred_at",
+        "event_type",
+        "event_version",
+        "actor_type",
+        "aggregate_type",
+        "aggregate_id",
+        "aggregate_seq",
+        "actor_id",
+        "request_id",
+        "idempotency_key",
+        "correlation_id",
+        "causation_id",
+        "payload",
+    ],
+    relations: &[],
+};
+
+/// Events are only ordered by event_id.
+static EVENT_SORTING: Sorting<EventRow> = Sorting {
+    id_column: "event_id",
+    id: |row| row.event_id.to_string(),
+    keys: &[SortKey {
+        name: "event_id",
+        column: "event_id",
+        sql_type: "BIGINT",
+        value: |row| row.event_id.to_string(),
+    }],
+};
+
+/// Fetch one page of events, its `next_cursor`, and the total if requested.
+async fn query_page(
+    state: &AppState,
+    org_id: Option<&OrgId>,
+    filter: &EventFilter,
+    after_event_id: i64,
+    page: &Page<EventRow>,
+    request_id: &str,
+) -> Result<(Vec<EventRow>, Option<String>, Option<PageTotal>), ApiError> {
+    let cursor = page
+        .after_id()
+        .map(|id| {
+            id.parse::<i64>().map_err(|_| {
+                ApiError::bad_request("invalid_cursor", "Invalid cursor format")
+                    .with_request_id(request_id.to_string())
+            })
+        })
+        .transpose()?;
+    let query_failed = |e: DbError| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to query events");
+        ApiError::internal("internal_error", "Failed to query events")
+            .with_request_id(request_id.to_string())
+    };
 
     let event_store = state.db().event_store();
-    let org_id_str = org_id.to_string();
-    let mut rows = if let Some(event_type) = query.event_type.as_deref() {
-        let fetch_limit = limit.saturating_mul(10).clamp(1, 2000);
-        event_store
-            .query_by_type_after_cursor(event_type, after_event_id, fetch_limit)
+    let rows = event_store
+        .query_filtered_page(
+            org_id,
+            filter,
+            after_event_id,
+            cursor,
+            page.desc(),
+            page.fetch_limit(),
+        )
+        .await
+        .map_err(query_failed)?;
+    let total = if page.include_total() {
+        let count = event_store
+            .count_filtered(org_id, filter, after_event_id, MAX_TOTAL)
             .await
-            .map_err(|e| {
-                tracing::error!(
-                    error = %e,
-                    request_id = %request_id,
-                    org_id = %org_id,
-                    event_type = %event_type,
-                    "Failed to query events"
-                );
-                ApiError::internal("internal_error", "Failed to query events")
-                    .with_request_id(request_id.clone())
-            })?
-            .into_iter()
-            .filter(|row| row.org_id.as_deref() == Some(org_id_str.as_str()))
-            .take(limit as usize)
-            .collect::<Vec<_>>()
+            .map_err(query_failed)?;
+        Some(PageTotal::from_count(count))
     } else {
-        event_store
-            .query_by_org_after_cursor(&org_id, after_event_id, limit)
-            .await
-            .map_err(|e| {
-                tracing::error!(
-                    error = %e,
-                    request_id = %request_id,
-                    org_id = %org_id,
-                    "Failed to query events"
-                );
-                ApiError::internal("internal_error", "Failed to query events")
-                    .with_request_id(request_id.clone())
-            })?
+        None
     };
 
-    if let Some(app_id) = query.app_id.as_deref() {
-        rows.retain(|row| row.app_id.as_deref() == Some(app_id));
-    }
-    if let Some(env_id) = query.env_id.as_deref() {
-        rows.retain(|row| row.env_id.as_deref() == Some(env_id));
-    }
+    let (rows, next_cursor) = page.finish(rows);
+    Ok((rows, next_cursor, total))
+}
 
-    let mut items = Vec::with_capacity(rows.len());
-    for row in rows {
-        let payload = event_payload_json(&row);
-        items.push(EventResponse {
-            event_id: row.event_id,
-            occurred_at: row.occurred_at,
-            event_type: row.event_type,
-            event_version: row.event_version,
-            actor_type: row.actor_type,
-            aggregate_type: Some(row.aggregate_type),
-            aggregate_id: Some(row.aggregate_id),
-            aggregate_seq: Some(row.aggregate_seq),
-            actor_id: Some(row.actor_id),
-            request_id: row.request_id,
-            idempotency_key: row.idempotency_key,
-            correlation_id: row.correlation_id,
-            causation_id: row.causation_id,
-            payload,
-        });
-    }
+#[derive(Debug, Deserialize)]
+struct VerifyEventsQuery {
+    /// Verify events with event_id > after_event_id.
+    after_event_id: Option<i64>,
+    /// Approximate max number of events to verify.
+    limit: Option<i64>,
+}
 
-    let next_after_event_id = items.last().map(|e| e.event_id).unwrap_or(after_event_id);
+/// Verify hashes, chains, and checkpoints of one page of the event log
+/// (operators only).
+///
+/// GET /v1/_admin/events/verify
+async fn verify_events(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<VerifyEventsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
 
-    Ok(Json(EventsResponse {
-        items,
-        next_after_event_id,
-    }))
+    let after_event_id = query.after_event_id.unwrap_or(0).max(0);
+    let limit = query.limit.unwrap_or(10_000).clamp(1, 100_000);
+
+    let report = event_integrity::verify_page(state.db().pool(), after_event_id, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to verify events");
+            ApiError::internal("internal_error", "Failed to verify events")
+                .with_request_id(request_id.clone())
+        })?;
+    Ok(Json(report))
+}
+
+fn event_response(row: EventRow, with_payload: bool) -> EventResponse {
+    let payload = if with_payload {
+        event_payload_json(&row)
+    } else {
+        None
+    };
+    EventResponse {
+        event_id: row.event_id,
+        occurred_at: row.occurred_at,
+        event_type: row.event_type,
+        event_version: row.event_version,
+        actor_type: row.actor_type,
+        aggregate_type: Some(row.aggregate_type),
+        aggregate_id: Some(row.aggregate_id),
+        aggregate_seq: Some(row.aggregate_seq),
+        actor_id: Some(row.actor_id),
+        request_id: row.request_id,
+        idempotency_key: row.idempotency_key,
+        correlation_id: row.correlation_id,
+        causation_id: row.causation_id,
+        payload,
+    }
 }
 
 pub async fn stream_events(
@@ -241,13 +479,21 @@ pub async fn stream_events(
         .max(100);
     let poll_interval = Duration::from_millis(poll_ms);
 
+    let filter = EventFilter {
+        event_type: query.event_type,
+        aggregate_type: query.aggregate_type,
+        aggregate_id: query.aggregate_id,
+        actor_type: query.actor_type,
+        actor_id: query.actor_id,
+        app_id: query.app_id,
+        env_id: query.env_id,
+        since: parse_since(query.since.as_deref(), &request_id)?,
+    };
+
     let stream_state = EventStreamState {
         state: state.clone(),
         org_id,
-        org_id_str: org_id.to_string(),
-        event_type: query.event_type.clone(),
-        app_id: query.app_id.clone(),
-        env_id: query.env_id.clone(),
+        filter,
         limit,
         poll_interval,
         last_id: after_event_id,
@@ -266,6 +512,8 @@ pub async fn stream_events(
                         event_type: row.event_type,
                         aggregate_type: Some(row.aggregate_type),
                         aggregate_id: Some(row.aggregate_id),
+                        actor_type: row.actor_type,
+                        actor_id: row.actor_id,
                         app_id: row.app_id,
                         env_id: row.env_id,
                         payload,
@@ -283,47 +531,21 @@ pub async fn stream_events(
                     return Some((Ok::<Bytes, Infallible>(payload), st));
                 }
 
-                let event_store = st.state.db().event_store();
-                let rows = if let Some(event_type) = st.event_type.as_deref() {
-                    let fetch_limit = (st.limit.saturating_mul(10)).clamp(1, 2000) as i32;
-                    event_store
-                        .query_by_type_after_cursor(event_type, st.last_id, fetch_limit)
-                        .await
-                } else {
-                    event_store
-                        .query_by_org_after_cursor(&st.org_id, st.last_id, st.limit as i32)
-                        .await
-                };
+                let rows = st
+                    .state
+                    .db()
+                    .event_store()
+                    .query_by_org_filtered(&st.org_id, &st.filter, st.last_id, st.limit as i32)
+                    .await;
 
                 match rows {
                     Ok(rows) => {
-                        if rows.is_empty() {
+                        let Some(last) = rows.last() else {
                             sleep(st.poll_interval).await;
                             continue;
-                        }
-
-                        if let Some(last) = rows.last() {
-                            st.last_id = last.event_id;
-                        }
-
-                        let mut filtered = rows;
-                        if st.event_type.is_some() {
-                            filtered.retain(|row| {
-                                row.org_id.as_deref() == Some(st.org_id_str.as_str())
-                            });
-                        }
-                        if let Some(app_id) = st.app_id.as_deref() {
-                            filtered.retain(|row| row.app_id.as_deref() == Some(app_id));
-                        }
-                        if let Some(env_id) = st.env_id.as_deref() {
-                            filtered.retain(|row| row.env_id.as_deref() == Some(env_id));
-                        }
-
-                        if filtered.is_empty() {
-                            continue;
-                        }
-
-                        st.buffer = VecDeque::from(filtered);
+                        };
+                        st.last_id = last.event_id;
+                        st.buffer = VecDeque::from(rows);
                     }
                     Err(e) => {
                         tracing::error!(error = %e, request_id = %request_id, "Failed to stream events");
@@ -343,6 +565,23 @@ pub async fn stream_events(
     Ok(response)
 }
 
+/// Parse the `since` query parameter (RFC 3339).
+fn parse_since(since: Option<&str>, request_id: &str) -> Result<Option<DateTime<Utc>>, ApiError> {
+    since
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| {
+                    ApiError::bad_request(
+                        "invalid_since",
+                        "since must be an RFC 3339 timestamp (e.g. 2024-01-02T03:04:05Z)",
+                    )
+                    .with_request_id(request_id.to_string())
+                })
+        })
+        .transpose()
+}
+
 fn event_payload_json(row: &EventRow) -> Option<serde_json::Value> {
     if let (Some(type_url), Some(payload_bytes)) = (
         row.payload_type_url.as_deref(),
@@ -451,6 +690,16 @@ mod tests {
         assert_eq!(snake_to_lower_camel(""), "");
     }
 
+    #[test]
+    fn parse_since_accepts_rfc3339_only() {
+        assert_eq!(parse_since(None, "req").unwrap(), None);
+        let since = parse_since(Some("2024-01-02T03:04:05+01:00"), "req")
+            .unwrap()
+            .unwrap();
+        assert_eq!(since.to_rfc3339(), "2024-01-02T02:04:05+00:00");
+        assert!(parse_since(Some("1h"), "req").is_err());
+    }
+
     #[test]
     fn to_proto_json_maps_nested_keys() {
         let value = serde_json::json!({
@@ -16,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
@@ -253,20 +254,7 @@ async fn create_exec_grant(
     )
     .await?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "exec_sessions",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "exec_sessions", event_id.value()).await?;
 
     let response = ExecGrantResponse {
         session_id: exec_session_id.to_string(),
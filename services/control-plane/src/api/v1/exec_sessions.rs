@@ -22,14 +22,17 @@ use plfm_events::{
     event_types, ActorType, AggregateType, ExecSessionConnectedPayload, ExecSessionEndedPayload,
 };
 use plfm_id::{ExecSessionId, InstanceId, OrgId, RequestId};
+use plfm_proto::agent::v1::ExecTunnelOpen;
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tracing::{error, warn};
 
 use crate::api::error::ApiError;
 use crate::api::tokens;
 use crate::db::AppendEvent;
+use crate::grpc::{ExecTunnelReader, ExecTunnelWriter};
 use crate::state::AppState;
 
 const FRAME_INIT: u8 = 0x20;
@@ -277,8 +280,16 @@ async fn connect_exec_session(
     })?;
 
     let placement = load_instance_placement(&state, &instance_id, &request_id).await?;
-    let node_addr = load_node_address(&state, &placement.node_id, &request_id).await?;
-    let agent_socket = resolve_exec_agent_socket(&node_addr, &request_id)?;
+    // Prefer the agent's gRPC tunnel; agents without one still accept direct
+    // connections on their exec port.
+    let agent = if state.exec_tunnels().is_connected(&placement.node_id) {
+        AgentTransport::Tunnel {
+            node_id: placement.node_id,
+        }
+    } else {
+        let node_addr = load_node_address(&state, &placement.node_id, &request_id).await?;
+        AgentTransport::Direct(resolve_exec_agent_socket(&node_addr, &request_id)?)
+    };
 
     let command: Vec<String> = serde_json::from_value(session.requested_command).map_err(|e| {
         tracing::error!(error = ?e, request_id = %request_id, "Invalid exec command payload");
@@ -304,7 +315,7 @@ async fn connect_exec_session(
             exec_session_id_typed,
             org_id,
             instance_id,
-            agent_socket,
+            agent,
             init,
         )
     }))
@@ -316,13 +327,13 @@ async fn handle_exec_socket(
     exec_session_id: ExecSessionId,
     org_id: OrgId,
     instance_id: InstanceId,
-    agent_socket: SocketAddr,
+    agent: AgentTransport,
     init: ExecConnectInit,
 ) {
-    let mut agent_stream = match TcpStream::connect(agent_socket).await {
-        Ok(stream) => stream,
+    let (mut agent_reader, mut agent_writer) = match connect_agent(&state, agent, init).await {
+        Ok(halves) => halves,
         Err(e) => {
-            error!(error = ?e, exec_session_id = %exec_session_id, "Failed to connect to node agent");
+            error!(error = ?e, exec_session_id = %exec_session_id, "Failed to start exec session on node agent");
             emit_exec_end(
                 &state,
                 &exec_session_id,
@@ -336,43 +347,11 @@ async fn handle_exec_socket(
         }
     };
 
-    let init_payload = match serde_json::to_vec(&init) {
-        Ok(payload) => payload,
-        Err(e) => {
-            error!(error = ?e, exec_session_id = %exec_session_id, "Failed to serialize exec init");
-            emit_exec_end(
-                &state,
-                &exec_session_id,
-                &org_id,
-                &instance_id,
-                None,
-                "connect_timeout",
-            )
-            .await;
-            return;
-        }
-    };
-
-    if let Err(e) = write_framed(&mut agent_stream, FRAME_INIT, &init_payload).await {
-        error!(error = ?e, exec_session_id = %exec_session_id, "Failed to send exec init to node agent");
-        emit_exec_end(
-            &state,
-            &exec_session_id,
-            &org_id,
-            &instance_id,
-            None,
-            "connect_timeout",
-        )
-        .await;
-        return;
-    }
-
     if let Err(e) = emit_exec_connected(&state, &exec_session_id, &org_id, &instance_id).await {
         error!(error = ?e, exec_session_id = %exec_session_id, "Failed to emit exec_session.connected");
     }
 
     let (mut client_sender, mut client_receiver) = client_socket.split();
-    let (mut agent_reader, mut agent_writer) = agent_stream.into_split();
 
     let end_state = Arc::new(tokio::sync::Mutex::new(None::<ExecEndState>));
     let end_emitted = Arc::new(AtomicBool::new(false));
@@ -386,7 +365,7 @@ async fn handle_exec_socket(
 
     let to_client = tokio::spawn(async move {
         loop {
-            match read_framed(&mut agent_reader).await {
+            match agent_reader.read_frame().await {
                 Ok(Some(frame)) => {
                     if frame.is_empty() {
                         continue;
@@ -456,7 +435,7 @@ async fn handle_exec_socket(
                     }
                     let frame_type = bytes[0];
                     let payload = &bytes[1..];
-                    if let Err(e) = write_framed(&mut agent_writer, frame_type, payload).await {
+                    if let Err(e) = agent_writer.write_frame(frame_type, payload).await {
                         warn!(error = ?e, exec_session_id = %exec_session_id_client, "Failed to send exec frame to node agent");
                         break;
                     }
@@ -498,6 +477,96 @@ async fn handle_exec_socket(
     let _ = tokio::join!(to_client, to_agent);
 }
 
+/// How the exec stream reaches the node agent.
+enum AgentTransport {
+    /// Over the agent's `ExecTunnel` gRPC stream.
+    Tunnel { node_id: String },
+    /// Direct TCP connection to the agent's exec port.
+    Direct(SocketAddr),
+}
+
+enum AgentReader {
+    Tunnel(ExecTunnelReader),
+    Direct(OwnedReadHalf),
+}
+
+impl AgentReader {
+    async fn read_frame(&mut self) -> Result<Option<Vec<u8>>, ApiError> {
+        match self {
+            Self::Tunnel(reader) => Ok(reader.recv().await),
+            Self::Direct(reader) => read_framed(reader).await,
+        }
+    }
+}
+
+enum AgentWriter {
+    Tunnel(ExecTunnelWriter),
+    Direct(OwnedWriteHalf),
+}
+
+impl AgentWriter {
+    async fn write_frame(&mut self, frame_type: u8, payload: &[u8]) -> Result<(), ApiError> {
+        match self {
+            Self::Tunnel(writer) => {
+                let mut frame = Vec::with_capacity(1 + payload.len());
+                frame.push(frame_type);
+                frame.extend_from_slice(payload);
+                writer.send(frame).await.map_err(|e| {
+                    ApiError::internal("exec_proxy_failed", format!("failed to write frame: {e}"))
+                })
+            }
+            Self::Direct(writer) => write_framed(writer, frame_type, payload).await,
+        }
+    }
+}
+
+/// Start the exec session on the node agent.
+async fn connect_agent(
+    state: &AppState,
+    agent: AgentTransport,
+    init: ExecConnectInit,
+) -> Result<(AgentReader, AgentWriter), ApiError> {
+    match agent {
+        AgentTransport::Tunnel { node_id } => {
+            let open = ExecTunnelOpen {
+                instance_id: init.instance_id,
+                command: init.command,
+                tty: init.tty,
+                cols: u32::from(init.cols),
+                rows: u32::from(init.rows),
+                env: init.env.into_iter().collect(),
+                stdin: init.stdin,
+            };
+            let session = state
+                .exec_tunnels()
+                .open_session(&node_id, &init.session_id, open)
+                .await
+                .ok_or_else(|| {
+                    ApiError::internal("exec_proxy_failed", "node agent exec tunnel closed")
+                })?;
+            let (reader, writer) = session.into_split();
+            Ok((AgentReader::Tunnel(reader), AgentWriter::Tunnel(writer)))
+        }
+        AgentTransport::Direct(socket) => {
+            let mut stream = TcpStream::connect(socket).await.map_err(|e| {
+                ApiError::internal(
+                    "exec_proxy_failed",
+                    format!("failed to connect to node agent: {e}"),
+                )
+            })?;
+            let init_payload = serde_json::to_vec(&init).map_err(|e| {
+                ApiError::internal(
+                    "exec_proxy_failed",
+                    format!("failed to serialize exec init: {e}"),
+                )
+            })?;
+            write_framed(&mut stream, FRAME_INIT, &init_payload).await?;
+            let (reader, writer) = stream.into_split();
+            Ok((AgentReader::Direct(reader), AgentWriter::Direct(writer)))
+        }
+    }
+}
+
 fn header_request_id(headers: &HeaderMap) -> String {
     headers
         .get("x-request-id")
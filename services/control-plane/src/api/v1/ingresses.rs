@@ -0,0 +1,328 @@
+//! Ingress status reporting.
+//!
+//! Every ingress periodically reports the routes it has programmed (with the
+//! number of backends behind each) and the public IPs it serves on:
+//! - `PUT /v1/orgs/{org_id}/ingresses/{ingress_id}`: replace this ingress's report
+//! - `GET /v1/orgs/{org_id}/ingresses`: ingresses that reported recently
+//!
+//! Reports are operational state, not events. Each report re-aggregates the
+//! org's routes into `routes_view` (`ingress_live_count` of
+//! `ingress_total_count`), counting only ingresses heard from within
+//! [`STALE_AFTER_SECS`], so a route shows as live on N of M ingresses.
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_id::{OrgId, RouteId};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+/// Ingresses silent for longer than this no longer count towards route status.
+pub const STALE_AFTER_SECS: i64 = 90;
+
+/// Interval ingresses are asked to report at.
+const REPORT_INTERVAL_SECS: i64 = 30;
+
+const MAX_INGRESS_ID_LEN: usize = 128;
+const MAX_REPORTED_ROUTES: usize = 10_000;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_ingresses))
+        .route("/{ingress_id}", put(report_status))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IngressStatusReport {
+    /// Public IPs the ingress serves on.
+    #[serde(default)]
+    pub public_ips: Vec<String>,
+    /// Routes currently programmed on the ingress.
+    #[serde(default)]
+    pub routes: Vec<ProgrammedRoute>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProgrammedRoute {
+    pub route_id: String,
+    pub backend_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngressStatusReportResponse {
+    pub ingress_id: String,
+    /// Routes this report counts as live (programmed with at least one backend).
+    pub live_route_count: i32,
+    pub next_report_secs: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngressResponse {
+    pub ingress_id: String,
+    pub public_ips: Vec<String>,
+    pub route_count: i32,
+    pub last_report_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for IngressResponse {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            ingress_id: row.try_get("ingress_id")?,
+            public_ips: row.try_get("public_ips")?,
+            route_count: row.try_get("route_count")?,
+            last_report_at: row.try_get("last_report_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListIngressesResponse {
+    pub items: Vec<IngressResponse>,
+}
+
+/// PUT /v1/orgs/{org_id}/ingresses/{ingress_id}
+async fn report_status(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, ingress_id)): Path<(String, String)>,
+    Json(req): Json<IngressStatusReport>,
+) -> Result<Json<IngressStatusReportResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    validate_report(&ingress_id, &req).map_err(|message| {
+        ApiError::bad_request("invalid_ingress_report", message).with_request_id(request_id.clone())
+    })?;
+
+    let db_error = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, ingress_id = %ingress_id, "Failed to record ingress status");
+        ApiError::internal("internal_error", "Failed to record ingress status")
+            .with_request_id(request_id.clone())
+    };
+
+    let route_ids: Vec<String> = req.routes.iter().map(|r| r.route_id.clone()).collect();
+    let backend_counts: Vec<i32> = req.routes.iter().map(|r| r.backend_count).collect();
+    let live_route_count = backend_counts.iter().filter(|&&n| n > 0).count() as i32;
+
+    let mut tx = state.db().pool().begin().await.map_err(db_error)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO ingress_nodes (org_id, ingress_id, public_ips, route_count, last_report_at)
+        VALUES ($1, $2, $3, $4, now())
+        ON CONFLICT (org_id, ingress_id) DO UPDATE SET
+            public_ips = EXCLUDED.public_ips,
+            route_count = EXCLUDED.route_count,
+            last_report_at = EXCLUDED.last_report_at
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(&ingress_id)
+    .bind(&req.public_ips)
+    .bind(req.routes.len() as i32)
+    .execute(&mut *tx)
+    .await
+    .map_err(db_error)?;
+
+    sqlx::query("DELETE FROM ingress_route_status WHERE org_id = $1 AND ingress_id = $2")
+        .bind(org_id.to_string())
+        .bind(&ingress_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO ingress_route_status (org_id, ingress_id, route_id, backend_count)
+        SELECT $1, $2, r.route_id, r.backend_count
+        FROM UNNEST($3::TEXT[], $4::INT[]) AS r(route_id, backend_count)
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(&ingress_id)
+    .bind(&route_ids)
+    .bind(&backend_counts)
+    .execute(&mut *tx)
+    .await
+    .map_err(db_error)?;
+
+    // Re-aggregate every route in the org: this report may have added or
+    // dropped routes, and other ingresses may have gone stale since the last
+    // report.
+    sqlx::query(
+        r#"
+        WITH fresh AS (
+            SELECT ingress_id
+            FROM ingress_nodes
+            WHERE org_id = $1
+              AND last_report_at > now() - make_interval(secs => $2)
+        ),
+        live AS (
+            SELECT s.route_id, COUNT(*)::INT AS live_count
+            FROM ingress_route_status s
+            JOIN fresh f ON f.ingress_id = s.ingress_id
+            WHERE s.org_id = $1 AND s.backend_count > 0
+            GROUP BY s.route_id
+        )
+        UPDATE routes_view r
+        SET ingress_live_count = COALESCE(
+                (SELECT l.live_count FROM live l WHERE l.route_id = r.route_id),
+                0
+            ),
+            ingress_total_count = (SELECT COUNT(*)::INT FROM fresh),
+            ingress_status_at = now()
+        WHERE r.org_id = $1
+          AND NOT r.is_deleted
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(STALE_AFTER_SECS as f64)
+    .execute(&mut *tx)
+    .await
+    .map_err(db_error)?;
+
+    tx.commit().await.map_err(db_error)?;
+
+    tracing::debug!(
+        org_id = %org_id,
+        ingress_id = %ingress_id,
+        route_count = route_ids.len(),
+        live_route_count,
+        "Ingress status recorded"
+    );
+
+    Ok(Json(IngressStatusReportResponse {
+        ingress_id,
+        live_route_count,
+        next_report_secs: REPORT_INTERVAL_SECS,
+    }))
+}
+
+/// GET /v1/orgs/{org_id}/ingresses
+async fn list_ingresses(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<Json<ListIngressesResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let items = sqlx::query_as::<_, IngressResponse>(
+        r#"
+        SELECT ingress_id, public_ips, route_count, last_report_at
+        FROM ingress_nodes
+        WHERE org_id = $1
+          AND last_report_at > now() - make_interval(secs => $2)
+        ORDER BY ingress_id ASC
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(STALE_AFTER_SECS as f64)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to list ingresses");
+        ApiError::internal("internal_error", "Failed to list ingresses")
+            .with_request_id(request_id.clone())
+    })?;
+
+    Ok(Json(ListIngressesResponse { items }))
+}
+
+fn validate_report(ingress_id: &str, report: &IngressStatusReport) -> Result<(), String> {
+    if ingress_id.is_empty()
+        || ingress_id.len() > MAX_INGRESS_ID_LEN
+        || !ingress_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!(
+            "ingress_id must be 1-{MAX_INGRESS_ID_LEN} characters of [A-Za-z0-9._-]"
+        ));
+    }
+    if report.routes.len() > MAX_REPORTED_ROUTES {
+        return Err(format!(
+            "at most {MAX_REPORTED_ROUTES} routes may be reported"
+        ));
+    }
+    if let Some(ip) = report
+        .public_ips
+        .iter()
+        .find(|ip| ip.parse::<IpAddr>().is_err())
+    {
+        return Err(format!("invalid public IP: {ip}"));
+    }
+
+    let mut seen = BTreeSet::new();
+    for route in &report.routes {
+        if route.route_id.parse::<RouteId>().is_err() {
+            return Err(format!("invalid route_id: {}", route.route_id));
+        }
+        if route.backend_count < 0 {
+            return Err("backend_count must be >= 0".to_string());
+        }
+        if !seen.insert(route.route_id.as_str()) {
+            return Err(format!("duplicate route_id: {}", route.route_id));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(routes: Vec<(&str, i32)>) -> IngressStatusReport {
+        IngressStatusReport {
+            public_ips: vec!["2001:db8::1".to_string(), "203.0.113.7".to_string()],
+            routes: routes
+                .into_iter()
+                .map(|(route_id, backend_count)| ProgrammedRoute {
+                    route_id: route_id.to_string(),
+                    backend_count,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_report() {
+        let a = RouteId::new().to_string();
+        let b = RouteId::new().to_string();
+
+        assert!(validate_report("edge-1.fra", &report(vec![(&a, 2), (&b, 0)])).is_ok());
+        assert!(validate_report("", &report(vec![])).is_err());
+        assert!(validate_report("edge/1", &report(vec![])).is_err());
+        assert!(validate_report("edge-1", &report(vec![("not-a-route", 1)])).is_err());
+        assert!(validate_report("edge-1", &report(vec![(&a, -1)])).is_err());
+        assert!(validate_report("edge-1", &report(vec![(&a, 1), (&a, 2)])).is_err());
+
+        let mut bad_ip = report(vec![]);
+        bad_ip.public_ips.push("not-an-ip".to_string());
+        assert!(validate_report("edge-1", &bad_ip).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 //! Instance API endpoints.
 //!
 //! Provides endpoints for instance status reporting and querying.
-//! These are primarily used by node-agents to report status.
+//! These are primarily used by node-agents to report status, and by
+//! operators answering "what's running where" across orgs.
 
 use axum::{
     extract::{Path, Query, State},
@@ -15,10 +16,20 @@ use plfm_events::{ActorType, AggregateType};
 use serde::{Deserialize, Serialize};
 
 use crate::api::error::ApiError;
+use crate::api::fields::{FieldsQuery, Shape};
 use crate::api::request_context::RequestContext;
 use crate::db::AppendEvent;
 use crate::state::AppState;
 
+/// Default `stale_minutes` for `?stale=`.
+const DEFAULT_STALE_MINUTES: i32 = 5;
+
+/// Upper bound for `stale_minutes` (one day).
+const MAX_STALE_MINUTES: i32 = 1440;
+
+/// Statuses a node-agent can report.
+const VALID_STATUSES: [&str; 5] = ["booting", "ready", "draining", "stopped", "failed"];
+
 /// Create instance routes.
 ///
 /// Instance status is reported by node-agents.
@@ -90,10 +101,18 @@ pub struct InstanceResponse {
     /// Release ID.
     pub release_id: String,
 
+    /// Hash of the instance spec; instances with equal hashes run the same
+    /// workload.
+    pub spec_hash: String,
+
     /// Overlay IPv6 address (for ingress routing).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub overlay_ipv6: Option<String>,
 
+    /// When the node-agent last reported status (absent if never).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reported_at: Option<DateTime<Utc>>,
+
     /// When the instance was created.
     pub created_at: DateTime<Utc>,
 
@@ -111,6 +130,27 @@ pub struct ListInstancesResponse {
     pub next_cursor: Option<String>,
 }
 
+/// Fields of listed instances.
+static INSTANCE_SHAPE: Shape = Shape {
+    key: "id",
+    fields: &[
+        "org_id",
+        "app_id",
+        "env_id",
+        "process_type",
+        "node_id",
+        "desired_state",
+        "status",
+        "release_id",
+        "spec_hash",
+        "overlay_ipv6",
+        "reported_at",
+        "created_at",
+        "updated_at",
+    ],
+    relations: &[],
+};
+
 /// Query parameters for listing instances.
 #[derive(Debug, Deserialize)]
 pub struct ListInstancesQuery {
@@ -118,23 +158,39 @@ pub struct ListInstancesQuery {
     pub limit: Option<i64>,
     /// Cursor (exclusive). Interpreted as an instance_id.
     pub cursor: Option<String>,
+    /// Filter by org_id.
+    pub org_id: Option<String>,
+    /// Filter by app_id.
+    pub app_id: Option<String>,
     /// Filter by env_id.
     pub env_id: Option<String>,
+    /// Filter by process type.
+    pub process_type: Option<String>,
     /// Filter by node_id.
     pub node_id: Option<String>,
+    /// Filter by last reported status.
+    pub status: Option<String>,
+    /// Filter by spec hash.
+    pub spec_hash: Option<String>,
+    /// `true` for instances with no status report in `stale_minutes`
+    /// (including never reported), `false` for the rest.
+    pub stale: Option<bool>,
+    /// Staleness threshold in minutes (default 5).
+    pub stale_minutes: Option<i32>,
 }
 
 // =============================================================================
 // Handlers
 // =============================================================================
 
-/// List all instances (optionally filtered by env or node).
+/// List all non-stopped instances, optionally filtered.
 ///
 /// GET /v1/instances
 async fn list_instances(
     State(state): State<AppState>,
     ctx: RequestContext,
     Query(query): Query<ListInstancesQuery>,
+    Query(fields): Query<FieldsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
 
@@ -146,30 +202,67 @@ async fn list_instances(
         .with_request_id(request_id));
     }
 
+    let selection = INSTANCE_SHAPE.select(fields, &request_id)?;
+
+    if let Some(status) = query.status.as_deref() {
+        if !VALID_STATUSES.contains(&status) {
+            return Err(ApiError::bad_request(
+                "invalid_status",
+                format!("Status must be one of: {}", VALID_STATUSES.join(", ")),
+            )
+            .with_request_id(request_id));
+        }
+    }
+
+    let stale_minutes = query.stale_minutes.unwrap_or(DEFAULT_STALE_MINUTES);
+    if !(1..=MAX_STALE_MINUTES).contains(&stale_minutes) {
+        return Err(ApiError::bad_request(
+            "invalid_query",
+            format!("stale_minutes must be between 1 and {MAX_STALE_MINUTES}"),
+        )
+        .with_request_id(request_id));
+    }
+
     let limit: i64 = query.limit.unwrap_or(50).clamp(1, 200);
     let cursor = query.cursor;
 
-    // Query instances from the desired view, joined with status view
+    // Query instances from the desired view, joined with status view. An
+    // instance that never reported status counts as stale.
     let rows = sqlx::query_as::<_, InstanceRow>(
         r#"
         SELECT
             d.instance_id, d.org_id, d.app_id, d.env_id, d.process_type,
-            d.node_id, d.desired_state, d.release_id, d.overlay_ipv6,
+            d.node_id, d.desired_state, d.release_id, d.spec_hash,
+            d.overlay_ipv6::TEXT AS overlay_ipv6,
             d.created_at, d.updated_at,
-            s.status
+            s.status, s.reported_at
         FROM instances_desired_view d
         LEFT JOIN instances_status_view s ON d.instance_id = s.instance_id
         WHERE d.desired_state != 'stopped'
           AND ($1::text IS NULL OR d.instance_id > $1)
-          AND ($2::text IS NULL OR d.env_id = $2)
-          AND ($3::text IS NULL OR d.node_id = $3)
+          AND ($2::text IS NULL OR d.org_id = $2)
+          AND ($3::text IS NULL OR d.app_id = $3)
+          AND ($4::text IS NULL OR d.env_id = $4)
+          AND ($5::text IS NULL OR d.process_type = $5)
+          AND ($6::text IS NULL OR d.node_id = $6)
+          AND ($7::text IS NULL OR s.status = $7)
+          AND ($8::text IS NULL OR d.spec_hash = $8)
+          AND ($9::boolean IS NULL
+               OR COALESCE(s.reported_at < now() - make_interval(mins => $10), true) = $9)
         ORDER BY d.instance_id ASC
-        LIMIT $4
+        LIMIT $11
         "#,
     )
     .bind(cursor.as_deref())
+    .bind(query.org_id.as_deref())
+    .bind(query.app_id.as_deref())
     .bind(query.env_id.as_deref())
+    .bind(query.process_type.as_deref())
     .bind(query.node_id.as_deref())
+    .bind(query.status.as_deref())
+    .bind(query.spec_hash.as_deref())
+    .bind(query.stale)
+    .bind(stale_minutes)
     .bind(limit)
     .fetch_all(state.db().pool())
     .await
@@ -186,7 +279,7 @@ async fn list_instances(
         None
     };
 
-    Ok(Json(ListInstancesResponse { items, next_cursor }))
+    Ok(selection.respond(ListInstancesResponse { items, next_cursor }))
 }
 
 /// Get a single instance by ID.
@@ -211,9 +304,10 @@ async fn get_instance(
         r#"
         SELECT
             d.instance_id, d.org_id, d.app_id, d.env_id, d.process_type,
-            d.node_id, d.desired_state, d.release_id, d.overlay_ipv6,
+            d.node_id, d.desired_state, d.release_id, d.spec_hash,
+            d.overlay_ipv6::TEXT AS overlay_ipv6,
             d.created_at, d.updated_at,
-            s.status
+            s.status, s.reported_at
         FROM instances_desired_view d
         LEFT JOIN instances_status_view s ON d.instance_id = s.instance_id
         WHERE d.instance_id = $1
@@ -258,11 +352,10 @@ async fn report_status(
     }
 
     // Validate status
-    let valid_statuses = ["booting", "ready", "draining", "stopped", "failed"];
-    if !valid_statuses.contains(&req.status.as_str()) {
+    if !VALID_STATUSES.contains(&req.status.as_str()) {
         return Err(ApiError::bad_request(
             "invalid_status",
-            format!("Status must be one of: {:?}", valid_statuses),
+            format!("Status must be one of: {:?}", VALID_STATUSES),
         )
         .with_request_id(request_id.clone()));
     }
@@ -390,10 +483,12 @@ struct InstanceRow {
     node_id: String,
     desired_state: String,
     release_id: String,
+    spec_hash: String,
     overlay_ipv6: Option<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     status: Option<String>,
+    reported_at: Option<DateTime<Utc>>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for InstanceRow {
@@ -408,10 +503,12 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for InstanceRow {
             node_id: row.try_get("node_id")?,
             desired_state: row.try_get("desired_state")?,
             release_id: row.try_get("release_id")?,
+            spec_hash: row.try_get("spec_hash")?,
             overlay_ipv6: row.try_get("overlay_ipv6")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
             status: row.try_get("status")?,
+            reported_at: row.try_get("reported_at")?,
         })
     }
 }
@@ -428,7 +525,9 @@ impl From<InstanceRow> for InstanceResponse {
             desired_state: row.desired_state,
             status: row.status,
             release_id: row.release_id,
+            spec_hash: row.spec_hash,
             overlay_ipv6: row.overlay_ipv6,
+            reported_at: row.reported_at,
             created_at: row.created_at,
             updated_at: row.updated_at,
         }
@@ -466,6 +565,24 @@ mod tests {
         assert_eq!(req.boot_id, Some("boot_123".to_string()));
     }
 
+    #[test]
+    fn test_list_instances_query_deserialization() {
+        let uri: axum::http::Uri = concat!(
+            "/v1/instances?org_id=org_1&process_type=web&status=ready",
+            "&spec_hash=abc&stale=true&stale_minutes=10"
+        )
+        .parse()
+        .unwrap();
+        let Query(query) = Query::<ListInstancesQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(query.org_id.as_deref(), Some("org_1"));
+        assert_eq!(query.process_type.as_deref(), Some("web"));
+        assert_eq!(query.status.as_deref(), Some("ready"));
+        assert_eq!(query.spec_hash.as_deref(), Some("abc"));
+        assert_eq!(query.stale, Some(true));
+        assert_eq!(query.stale_minutes, Some(10));
+        assert_eq!(query.app_id, None);
+    }
+
     #[test]
     fn test_report_status_response_serialization() {
         let response = ReportStatusResponse { accepted: true };
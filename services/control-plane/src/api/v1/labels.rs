@@ -0,0 +1,429 @@
+//! Label update endpoints shared by apps, envs, routes, and volumes.
+//!
+//! Each resource exposes `PATCH .../labels`; the resource modules parse their
+//! path IDs and delegate to [`patch_labels`], which applies the merge patch,
+//! emits `<resource>.labels_updated` with the full resulting label set, and
+//! waits for the owning projection.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use plfm_events::{
+    event_types, AggregateType, AppLabelsUpdatedPayload, EnvLabelsUpdatedPayload,
+    RouteLabelsUpdatedPayload, VolumeLabelsUpdatedPayload,
+};
+use plfm_id::{AppId, EnvId, OrgId, RouteId, VolumeId};
+
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::idempotency;
+use crate::api::labels::{self, Labels, LabelsResponse, PatchLabelsRequest};
+use crate::api::preconditions::{self, Preconditions};
+use crate::api::request_context::RequestContext;
+use crate::db::{AppendEvent, DbError};
+use crate::state::AppState;
+
+/// Resource whose labels are being updated.
+pub(super) enum LabelTarget {
+    App {
+        app_id: AppId,
+    },
+    Env {
+        app_id: AppId,
+        env_id: EnvId,
+    },
+    Route {
+        app_id: AppId,
+        env_id: EnvId,
+        route_id: RouteId,
+    },
+    Volume {
+        volume_id: VolumeId,
+    },
+}
+
+impl LabelTarget {
+    fn endpoint_name(&self) -> &'static str {
+        match self {
+            LabelTarget::App { .. } => "apps.labels",
+            LabelTarget::Env { .. } => "envs.labels",
+            LabelTarget::Route { .. } => "routes.labels",
+            LabelTarget::Volume { .. } => "volumes.labels",
+        }
+    }
+
+    fn projection(&self) -> &'static str {
+        match self {
+            LabelTarget::App { .. } => "apps",
+            LabelTarget::Env { .. } => "envs",
+            LabelTarget::Route { .. } => "routes",
+            LabelTarget::Volume { .. } => "volumes",
+        }
+    }
+
+    fn aggregate(&self) -> (AggregateType, String) {
+        match self {
+            LabelTarget::App { app_id } => (AggregateType::App, app_id.to_string()),
+            LabelTarget::Env { env_id, .. } => (AggregateType::Env, env_id.to_string()),
+            LabelTarget::Route { route_id, .. } => (AggregateType::Route, route_id.to_string()),
+            LabelTarget::Volume { volume_id } => (AggregateType::Volume, volume_id.to_string()),
+        }
+    }
+
+    fn event_type(&self) -> &'static str {
+        match self {
+            LabelTarget::App { .. } => event_types::APP_LABELS_UPDATED,
+            LabelTarget::Env { .. } => event_types::ENV_LABELS_UPDATED,
+            LabelTarget::Route { .. } => event_types::ROUTE_LABELS_UPDATED,
+            LabelTarget::Volume { .. } => event_types::VOLUME_LABELS_UPDATED,
+        }
+    }
+
+    fn app_id(&self) -> Option<AppId> {
+        match self {
+            LabelTarget::App { app_id }
+            | LabelTarget::Env { app_id, .. }
+            | LabelTarget::Route { app_id, .. } => Some(*app_id),
+            LabelTarget::Volume { .. } => None,
+        }
+    }
+
+    fn env_id(&self) -> Option<EnvId> {
+        match self {
+            LabelTarget::Env { env_id, .. } | LabelTarget::Route { env_id, .. } => Some(*env_id),
+            LabelTarget::App { .. } | LabelTarget::Volume { .. } => None,
+        }
+    }
+
+    fn not_found(&self, request_id: &str) -> ApiError {
+        let (code, message) = match self {
+            LabelTarget::App { app_id } => {
+                ("app_not_found", format!("Application {} not found", app_id))
+            }
+            LabelTarget::Env { env_id, .. } => {
+                ("env_not_found", format!("Environment {} not found", env_id))
+            }
+            LabelTarget::Route { route_id, .. } => {
+                ("route_not_found", format!("Route {} not found", route_id))
+            }
+            LabelTarget::Volume { volume_id } => (
+                "volume_not_found",
+                format!("Volume {} not found", volume_id),
+            ),
+        };
+        ApiError::not_found(code, message).with_request_id(request_id.to_string())
+    }
+
+    fn hash_input(&self, org_id: &OrgId, req: &PatchLabelsRequest) -> serde_json::Value {
+        let (_, id) = self.aggregate();
+        serde_json::json!({
+            "org_id": org_id.to_string(),
+            "app_id": self.app_id().map(|id| id.to_string()),
+            "env_id": self.env_id().map(|id| id.to_string()),
+            "resource_id": id,
+            "body": req
+        })
+    }
+
+    fn payload(
+        &self,
+        org_id: OrgId,
+        labels: Labels,
+    ) -> Result<serde_json::Value, serde_json::Error> {
+        match self {
+            LabelTarget::App { app_id } => serde_json::to_value(AppLabelsUpdatedPayload {
+                app_id: *app_id,
+                org_id,
+                labels,
+            }),
+            LabelTarget::Env { app_id, env_id } => serde_json::to_value(EnvLabelsUpdatedPayload {
+                env_id: *env_id,
+                org_id,
+                app_id: *app_id,
+                labels,
+            }),
+            LabelTarget::Route {
+                env_id, route_id, ..
+            } => serde_json::to_value(RouteLabelsUpdatedPayload {
+                route_id: *route_id,
+                org_id,
+                env_id: *env_id,
+                labels,
+            }),
+            LabelTarget::Volume { volume_id } => serde_json::to_value(VolumeLabelsUpdatedPayload {
+                volume_id: *volume_id,
+                org_id,
+                labels,
+            }),
+        }
+    }
+
+    /// Load the current labels and resource version of the target.
+    async fn load(
+        &self,
+        state: &AppState,
+        org_id: &OrgId,
+    ) -> Result<Option<(serde_json::Value, i32)>, sqlx::Error> {
+        let pool = state.db().pool();
+        match self {
+            LabelTarget::App { app_id } => {
+                sqlx::query_as::<_, (serde_json::Value, i32)>(
+                    r#"
+                    SELECT labels, resource_version
+                    FROM apps_view
+                    WHERE app_id = $1 AND org_id = $2 AND NOT is_deleted
+                    "#,
+                )
+                .bind(app_id.to_string())
+                .bind(org_id.to_string())
+                .fetch_optional(pool)
+                .await
+            }
+            LabelTarget::Env { app_id, env_id } => {
+                sqlx::query_as::<_, (serde_json::Value, i32)>(
+                    r#"
+                    SELECT labels, resource_version
+                    FROM envs_view
+                    WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
+                    "#,
+                )
+                .bind(env_id.to_string())
+                .bind(org_id.to_string())
+                .bind(app_id.to_string())
+                .fetch_optional(pool)
+                .await
+            }
+            LabelTarget::Route {
+                app_id,
+                env_id,
+                route_id,
+            } => {
+                sqlx::query_as::<_, (serde_json::Value, i32)>(
+                    r#"
+                    SELECT labels, resource_version
+                    FROM routes_view
+                    WHERE route_id = $1 AND org_id = $2 AND app_id = $3 AND env_id = $4
+                      AND NOT is_deleted
+                    "#,
+                )
+                .bind(route_id.to_string())
+                .bind(org_id.to_string())
+                .bind(app_id.to_string())
+                .bind(env_id.to_string())
+                .fetch_optional(pool)
+                .await
+            }
+            LabelTarget::Volume { volume_id } => {
+                sqlx::query_as::<_, (serde_json::Value, i32)>(
+                    r#"
+                    SELECT labels, resource_version
+                    FROM volumes_view
+                    WHERE volume_id = $1 AND org_id = $2 AND NOT is_deleted
+                    "#,
+                )
+                .bind(volume_id.to_string())
+                .bind(org_id.to_string())
+                .fetch_optional(pool)
+                .await
+            }
+        }
+    }
+}
+
+/// Apply a label merge patch to a resource.
+///
+/// `If-Match` is optional; when present it must match the resource's current
+/// ETag.
+pub(super) async fn patch_labels(
+    state: &AppState,
+    ctx: &RequestContext,
+    org_id: OrgId,
+    target: LabelTarget,
+    preconditions: &Preconditions,
+    req: PatchLabelsRequest,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_id = ctx.actor_id.clone();
+    let endpoint_name = target.endpoint_name();
+
+    let role = authz::require_org_member(state, &org_id, ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let org_scope = org_id.to_string();
+    let request_hash = idempotency_key
+        .as_deref()
+        .map(|key| {
+            idempotency::request_hash(endpoint_name, &target.hash_input(&org_id, &req))
+                .map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let (current_labels, current_version) = target
+        .load(state, &org_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, endpoint = endpoint_name, "Failed to load labels");
+            ApiError::internal("internal_error", "Failed to update labels")
+                .with_request_id(request_id.clone())
+        })?
+        .ok_or_else(|| target.not_found(&request_id))?;
+
+    preconditions.check(None, current_version, false, &request_id)?;
+
+    let current_labels = labels::from_json(current_labels);
+    let updated = labels::apply_patch(&current_labels, &req.labels, &request_id)?;
+
+    let (aggregate_type, aggregate_id) = target.aggregate();
+    let resource_version = if updated == current_labels {
+        current_version
+    } else {
+        let current_seq = state
+            .db()
+            .event_store()
+            .get_latest_aggregate_seq(&aggregate_type, &aggregate_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, aggregate_id = %aggregate_id, "Failed to get aggregate sequence");
+                ApiError::internal("internal_error", "Failed to update labels")
+                    .with_request_id(request_id.clone())
+            })?
+            .unwrap_or(0);
+
+        let payload = target.payload(org_id, updated.clone()).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize labels payload");
+            ApiError::internal("internal_error", "Failed to update labels")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let event = AppendEvent {
+            aggregate_type,
+            aggregate_id: aggregate_id.clone(),
+            aggregate_seq: current_seq + 1,
+            event_type: target.event_type().to_string(),
+            event_version: 1,
+            actor_type: ctx.actor_type,
+            actor_id: actor_id.clone(),
+            org_id: Some(org_id),
+            request_id: request_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+            app_id: target.app_id(),
+            env_id: target.env_id(),
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            ..Default::default()
+        };
+
+        let event_id = state.db().event_store().append(event).await.map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to update labels");
+            match e {
+                DbError::SequenceConflict { .. } => {
+                    ApiError::conflict("version_conflict", "Concurrent update detected; retry")
+                }
+                _ => ApiError::internal("internal_error", "Failed to update labels"),
+            }
+            .with_request_id(request_id.clone())
+        })?;
+
+        consistency::wait_for_write(state, ctx, target.projection(), event_id.value()).await?;
+
+        current_version + 1
+    };
+
+    let response = LabelsResponse {
+        labels: updated,
+        resource_version,
+    };
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to update labels")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_target_routing() {
+        let app_id = AppId::new();
+        let env_id = EnvId::new();
+        let target = LabelTarget::Env { app_id, env_id };
+        assert_eq!(target.endpoint_name(), "envs.labels");
+        assert_eq!(target.projection(), "envs");
+        assert_eq!(target.event_type(), event_types::ENV_LABELS_UPDATED);
+        assert_eq!(target.aggregate(), (AggregateType::Env, env_id.to_string()));
+        assert_eq!(target.app_id(), Some(app_id));
+
+        let target = LabelTarget::Volume {
+            volume_id: VolumeId::new(),
+        };
+        assert_eq!(target.projection(), "volumes");
+        assert_eq!(target.app_id(), None);
+        assert_eq!(target.env_id(), None);
+    }
+
+    #[test]
+    fn test_label_payload_carries_full_set() {
+        let org_id = OrgId::new();
+        let route_id = RouteId::new();
+        let target = LabelTarget::Route {
+            app_id: AppId::new(),
+            env_id: EnvId::new(),
+            route_id,
+        };
+        let mut labels = Labels::new();
+        labels.insert("team".to_string(), "payments".to_string());
+
+        let payload = target.payload(org_id, labels).unwrap();
+        assert_eq!(payload["route_id"], route_id.to_string());
+        assert_eq!(payload["labels"]["team"], "payments");
+    }
+}
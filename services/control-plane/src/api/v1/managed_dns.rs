@@ -0,0 +1,89 @@
+//! Managed hostname DNS records (operator only).
+//!
+//! - `/v1/_admin/managed-dns/records`: the AAAA records live envs should have,
+//!   in external-dns endpoint form, for the `external-dns` provider (an
+//!   external-dns webhook provider or other agent publishes them)
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+use std::collections::BTreeMap;
+
+use axum::{extract::State, routing::get, Json, Router};
+use serde::Serialize;
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::managed_dns;
+use crate::state::AppState;
+
+/// Merged into /v1/_admin.
+pub fn admin_routes() -> Router<AppState> {
+    Router::new().route("/managed-dns/records", get(list_records))
+}
+
+/// One record set, shaped like an external-dns `Endpoint`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DnsEndpoint {
+    dns_name: String,
+    targets: Vec<String>,
+    record_type: &'static str,
+    #[serde(rename = "recordTTL")]
+    record_ttl: u32,
+    labels: BTreeMap<String, String>,
+}
+
+/// GET /v1/_admin/managed-dns/records
+///
+/// Empty when managed hostnames are off.
+async fn list_records(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<Json<Vec<DnsEndpoint>>, ApiError> {
+    let request_id = ctx.request_id.clone();
+    authz::require_operator(&ctx)?;
+
+    if managed_dns::platform_domain().is_none() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let targets = managed_dns::ingress_targets().map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Managed DNS targets are not configured");
+        ApiError::internal("internal_error", "Managed DNS is not configured")
+            .with_request_id(request_id.clone())
+    })?;
+    let targets: Vec<String> = targets.iter().map(ToString::to_string).collect();
+    let ttl = managed_dns::record_ttl();
+
+    let rows = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT managed_hostname, env_id
+        FROM envs_view
+        WHERE managed_hostname IS NOT NULL
+          AND NOT is_deleted
+          AND deletion_requested_at IS NULL
+        ORDER BY managed_hostname
+        "#,
+    )
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to list managed hostnames");
+        ApiError::internal("internal_error", "Failed to list managed DNS records")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let endpoints = rows
+        .into_iter()
+        .map(|(hostname, env_id)| DnsEndpoint {
+            dns_name: hostname,
+            targets: targets.clone(),
+            record_type: "AAAA",
+            record_ttl: ttl,
+            labels: BTreeMap::from([("plfm-env-id".to_string(), env_id)]),
+        })
+        .collect();
+
+    Ok(Json(endpoints))
+}
@@ -18,6 +18,7 @@ use plfm_id::{MemberId, OrgId};
 use serde::{Deserialize, Serialize};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
@@ -255,20 +256,7 @@ async fn create_member(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "members",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "members", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, MemberRow>(
         r#"
@@ -473,20 +461,7 @@ async fn update_member(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "members",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "members", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, MemberRow>(
         r#"
@@ -681,20 +656,7 @@ async fn delete_member(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "members",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::record_write(&ctx, event_id.value());
 
     let response = DeleteResponse { ok: true };
 
@@ -1,26 +1,53 @@
 //! API v1 routes.
 
+mod admin;
+mod alerts;
 mod apps;
 mod auth;
+mod backends;
+mod batch;
+mod console;
 mod debug;
 mod deploys;
+mod drift;
+mod env_config;
+mod env_config_vars;
 mod env_instances;
 mod env_networking;
+mod env_placement;
+mod env_process_resources;
+mod env_slo;
 mod envs;
 mod events;
 mod exec;
 mod exec_sessions;
+mod ingresses;
 mod instances;
+mod labels;
 mod logs;
+mod managed_dns;
 mod members;
+mod node_enrollment;
+mod node_labels;
 mod nodes;
+mod notifications;
 mod orgs;
+mod pki;
 mod projects;
+mod quotas;
 mod releases;
+mod route_certificates;
 mod routes;
+mod scope;
+mod search;
+mod secret_keys;
 mod secrets;
+mod status_page;
+mod timelines;
+mod trash;
 mod volume_attachments;
 mod volumes;
+mod workload;
 
 use axum::Router;
 
@@ -33,6 +60,16 @@ pub fn routes() -> Router<AppState> {
         .nest("/orgs", orgs::routes())
         .nest("/orgs/{org_id}/members", members::routes())
         .nest("/orgs/{org_id}/projects", projects::routes())
+        // Ingress backend sync: /v1/orgs/{org_id}/backends
+        .nest("/orgs/{org_id}/backends", backends::routes())
+        .nest("/orgs/{org_id}/ingresses", ingresses::routes())
+        .nest("/orgs/{org_id}/notifications", notifications::routes())
+        .nest("/orgs/{org_id}/alerts", alerts::routes())
+        .nest("/orgs/{org_id}/status", status_page::routes())
+        .route(
+            "/orgs/{org_id}/batch",
+            axum::routing::post(batch::execute_batch),
+        )
         .route(
             "/orgs/{org_id}/events",
             axum::routing::get(events::list_events),
@@ -41,6 +78,11 @@ pub fn routes() -> Router<AppState> {
             "/orgs/{org_id}/events/stream",
             axum::routing::get(events::stream_events),
         )
+        .route("/orgs/{org_id}/search", axum::routing::get(search::search))
+        .route(
+            "/orgs/{org_id}/trash",
+            axum::routing::get(trash::list_trash),
+        )
         .route(
             "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/logs",
             axum::routing::get(logs::query_logs),
@@ -63,7 +105,7 @@ pub fn routes() -> Router<AppState> {
         // Deploys are nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys
         .nest(
             "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys",
-            deploys::routes(),
+            deploys::routes().merge(timelines::deploy_routes()),
         )
         // Instances are nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/instances
         .nest(
@@ -73,7 +115,7 @@ pub fn routes() -> Router<AppState> {
         // Routes are nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes
         .nest(
             "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes",
-            routes::routes(),
+            routes::routes().merge(route_certificates::routes()),
         )
         // Volume attachments are nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/volume-attachments
         .nest(
@@ -95,17 +137,69 @@ pub fn routes() -> Router<AppState> {
             "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/status",
             envs::status_routes(),
         )
+        // Config history is nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config
+        .nest(
+            "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config",
+            env_config::routes(),
+        )
+        // Config vars are nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars
+        .nest(
+            "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/config-vars",
+            env_config_vars::routes(),
+        )
+        // Process resources are nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes/{process_type}/resources
+        .nest(
+            "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/processes",
+            env_process_resources::routes(),
+        )
+        // Placement preview is nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/placement:preview
+        .nest(
+            "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/placement:preview",
+            env_placement::routes(),
+        )
+        // SLO is nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/slo
+        .nest(
+            "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/slo",
+            env_slo::routes(),
+        )
         // Networking is nested under envs: /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/networking
         .nest(
             "/orgs/{org_id}/apps/{app_id}/envs/{env_id}/networking",
             env_networking::routes(),
         )
         // Nodes are infrastructure resources: /v1/nodes
-        .nest("/nodes", nodes::routes())
+        .nest(
+            "/nodes",
+            nodes::routes()
+                .merge(pki::node_routes())
+                .merge(node_labels::node_routes())
+                .merge(console::node_routes()),
+        )
+        .nest("/pki", pki::routes())
+        // Workload self-service, authenticated by instance identity tokens: /v1/workload
+        .nest("/workload", workload::routes())
         // Instances are VM instances: /v1/instances
-        .nest("/instances", instances::routes())
+        .nest(
+            "/instances",
+            instances::routes()
+                .merge(timelines::instance_routes())
+                .merge(console::instance_routes()),
+        )
         // Volumes are org-scoped resources: /v1/orgs/{org_id}/volumes
         .nest("/orgs/{org_id}/volumes", volumes::routes())
         // Development/debug endpoints: /v1/_debug/*
         .nest("/_debug", debug::routes())
+        // Operator admin endpoints: /v1/_admin/*
+        .nest(
+            "/_admin",
+            admin::routes()
+                .merge(node_enrollment::admin_routes())
+                .merge(pki::admin_routes())
+                .merge(secret_keys::admin_routes())
+                .merge(route_certificates::admin_routes())
+                .merge(managed_dns::admin_routes())
+                .merge(drift::admin_routes())
+                .merge(events::admin_routes())
+                .merge(quotas::admin_routes()),
+        )
 }
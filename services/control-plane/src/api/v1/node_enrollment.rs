@@ -0,0 +1,479 @@
+//! Node enrollment administration.
+//!
+//! Operator endpoints under `/v1/_admin` for minting, listing, and revoking
+//! single-use node bootstrap tokens and for approving or denying nodes that
+//! enrolled into the approval queue, plus the error mapping and agent
+//! identity check shared with the node API.
+//!
+//! See: docs/specs/api/http-api.md
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{
+    AggregateType, EventPayload, NewEvent, NodeEnrollmentApprovedPayload,
+    NodeEnrollmentDeniedPayload,
+};
+use plfm_id::NodeId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::db::DbError;
+use crate::enrollment::{
+    self, BootstrapToken, EnrollmentError, DEFAULT_BOOTSTRAP_TOKEN_TTL_HOURS,
+    MAX_BOOTSTRAP_TOKEN_TTL_HOURS, STATE_PENDING_APPROVAL,
+};
+use crate::state::AppState;
+
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/node-bootstrap-tokens", post(create_bootstrap_token))
+        .route("/node-bootstrap-tokens", get(list_bootstrap_tokens))
+        .route(
+            "/node-bootstrap-tokens/{token_id}",
+            delete(revoke_bootstrap_token),
+        )
+        .route("/nodes/{node_id}/approve", post(approve_node))
+        .route("/nodes/{node_id}/deny", post(deny_node))
+}
+
+/// Map an enrollment error to an API error.
+pub(super) fn enrollment_error(error: EnrollmentError, request_id: &str) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        EnrollmentError::TokenRequired => {
+            ApiError::unauthorized("bootstrap_token_required", message)
+        }
+        EnrollmentError::InvalidToken => ApiError::unauthorized("invalid_bootstrap_token", message),
+        EnrollmentError::NodeNotFound(node_id) => {
+            ApiError::not_found("node_not_found", format!("Node {} not found", node_id))
+        }
+        EnrollmentError::PendingApproval(_) => {
+            ApiError::forbidden("node_pending_approval", message)
+        }
+        EnrollmentError::Denied(_) => ApiError::forbidden("node_enrollment_denied", message),
+        EnrollmentError::IdentityRequired => {
+            ApiError::unauthorized("node_identity_required", message)
+        }
+        EnrollmentError::IdentityMismatch(_) | EnrollmentError::SubjectMismatch => {
+            ApiError::forbidden("node_identity_mismatch", message)
+        }
+        EnrollmentError::Database(e) => {
+            tracing::error!(error = %e, request_id = %request_id, "Node enrollment query failed");
+            ApiError::internal("internal_error", "Failed to verify node")
+        }
+    };
+    api_error.with_request_id(request_id.to_string())
+}
+
+/// Reject agent requests from nodes that are not admitted or that present a
+/// client certificate subject other than the one they enrolled with.
+pub(super) async fn verify_node_request(
+    state: &AppState,
+    headers: &HeaderMap,
+    node_id: &str,
+    request_id: &str,
+) -> Result<(), ApiError> {
+    let subject = headers
+        .get(enrollment::client_cert_subject_header().as_str())
+        .and_then(|v| v.to_str().ok());
+    enrollment::verify_node(state.db().pool(), node_id, subject)
+        .await
+        .map_err(|e| enrollment_error(e, request_id))
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateBootstrapTokenRequest {
+    /// Free-form note (e.g. the host being provisioned).
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Send the node enrolled with this token to the approval queue.
+    #[serde(default)]
+    pub requires_approval: bool,
+    /// Token lifetime; defaults to 24 hours.
+    #[serde(default)]
+    pub ttl_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BootstrapTokenResponse {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub requires_approval: bool,
+    /// `active`, `used`, `revoked`, or `expired`.
+    pub status: &'static str,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_by_node_id: Option<String>,
+}
+
+impl From<BootstrapToken> for BootstrapTokenResponse {
+    fn from(token: BootstrapToken) -> Self {
+        let status = if token.used_at.is_some() {
+            "used"
+        } else if token.revoked_at.is_some() {
+            "revoked"
+        } else if token.expires_at <= Utc::now() {
+            "expired"
+        } else {
+            "active"
+        };
+        Self {
+            id: token.token_id,
+            description: token.description,
+            requires_approval: token.requires_approval,
+            status,
+            created_by: token.created_by,
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+            used_at: token.used_at,
+            used_by_node_id: token.used_by_node_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateBootstrapTokenResponse {
+    /// The bootstrap token. Returned only here; store it securely.
+    pub token: String,
+    #[serde(flatten)]
+    pub record: BootstrapTokenResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListBootstrapTokensResponse {
+    pub items: Vec<BootstrapTokenResponse>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DenyNodeRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnrollmentDecisionResponse {
+    pub node_id: String,
+    pub state: &'static str,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Mint a single-use node bootstrap token.
+///
+/// POST /v1/_admin/node-bootstrap-tokens
+async fn create_bootstrap_token(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Json(req): Json<CreateBootstrapTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let ttl_hours = req.ttl_hours.unwrap_or(DEFAULT_BOOTSTRAP_TOKEN_TTL_HOURS);
+    if !(1..=MAX_BOOTSTRAP_TOKEN_TTL_HOURS).contains(&ttl_hours) {
+        return Err(ApiError::bad_request(
+            "invalid_bootstrap_token_ttl",
+            format!("ttl_hours must be between 1 and {MAX_BOOTSTRAP_TOKEN_TTL_HOURS}"),
+        )
+        .with_request_id(request_id));
+    }
+
+    let (token, record) = enrollment::create_bootstrap_token(
+        state.db().pool(),
+        req.description.as_deref(),
+        req.requires_approval,
+        Duration::hours(ttl_hours),
+        &ctx.actor_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to create bootstrap token");
+        ApiError::internal("internal_error", "Failed to create bootstrap token")
+            .with_request_id(request_id.clone())
+    })?;
+
+    tracing::info!(
+        token_id = %record.token_id,
+        requires_approval = record.requires_approval,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Node bootstrap token created"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateBootstrapTokenResponse {
+            token,
+            record: record.into(),
+        }),
+    ))
+}
+
+/// List bootstrap tokens (without the tokens themselves).
+///
+/// GET /v1/_admin/node-bootstrap-tokens
+async fn list_bootstrap_tokens(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let tokens = enrollment::list_bootstrap_tokens(state.db().pool())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to list bootstrap tokens");
+            ApiError::internal("internal_error", "Failed to list bootstrap tokens")
+                .with_request_id(request_id.clone())
+        })?;
+
+    Ok(Json(ListBootstrapTokensResponse {
+        items: tokens.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Revoke an unused bootstrap token.
+///
+/// DELETE /v1/_admin/node-bootstrap-tokens/{token_id}
+async fn revoke_bootstrap_token(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(token_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let revoked = enrollment::revoke_bootstrap_token(state.db().pool(), &token_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to revoke bootstrap token");
+            ApiError::internal("internal_error", "Failed to revoke bootstrap token")
+                .with_request_id(request_id.clone())
+        })?;
+
+    if !revoked {
+        return Err(ApiError::not_found(
+            "bootstrap_token_not_found",
+            format!("No unused bootstrap token {token_id}"),
+        )
+        .with_request_id(request_id));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Admit a node waiting in the approval queue.
+///
+/// POST /v1/_admin/nodes/{node_id}/approve
+async fn approve_node(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(node_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let node_id = parse_node_id(&node_id, &ctx.request_id)?;
+    decide_enrollment(
+        &state,
+        &ctx,
+        node_id,
+        &NodeEnrollmentApprovedPayload { node_id },
+    )
+    .await?;
+    Ok(Json(EnrollmentDecisionResponse {
+        node_id: node_id.to_string(),
+        state: "active",
+    }))
+}
+
+/// Reject a node waiting in the approval queue.
+///
+/// POST /v1/_admin/nodes/{node_id}/deny
+async fn deny_node(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(node_id): Path<String>,
+    body: Option<Json<DenyNodeRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let node_id = parse_node_id(&node_id, &ctx.request_id)?;
+    let reason = body.and_then(|Json(req)| req.reason);
+    decide_enrollment(
+        &state,
+        &ctx,
+        node_id,
+        &NodeEnrollmentDeniedPayload { node_id, reason },
+    )
+    .await?;
+    Ok(Json(EnrollmentDecisionResponse {
+        node_id: node_id.to_string(),
+        state: enrollment::STATE_DENIED,
+    }))
+}
+
+fn parse_node_id(node_id: &str, request_id: &str) -> Result<NodeId, ApiError> {
+    node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.to_string())
+    })
+}
+
+async fn decide_enrollment<P: EventPayload>(
+    state: &AppState,
+    ctx: &RequestContext,
+    node_id: NodeId,
+    payload: &P,
+) -> Result<(), ApiError> {
+    authz::require_operator(ctx)?;
+    let request_id = ctx.request_id.clone();
+    let internal = |e: &dyn std::fmt::Display| {
+        tracing::error!(error = %e, request_id = %request_id, node_id = %node_id, "Failed to record enrollment decision");
+        ApiError::internal("internal_error", "Failed to record enrollment decision")
+            .with_request_id(request_id.clone())
+    };
+
+    let current_state =
+        sqlx::query_scalar::<_, String>("SELECT state FROM nodes_view WHERE node_id = $1")
+            .bind(node_id.to_string())
+            .fetch_optional(state.db().pool())
+            .await
+            .map_err(|e| internal(&e))?;
+
+    match current_state.as_deref() {
+        None => {
+            return Err(ApiError::not_found(
+                "node_not_found",
+                format!("Node {} not found", node_id),
+            )
+            .with_request_id(request_id))
+        }
+        Some(STATE_PENDING_APPROVAL) => {}
+        Some(other) => {
+            return Err(ApiError::conflict(
+                "node_not_pending",
+                format!("Node {node_id} is {other}, not awaiting approval"),
+            )
+            .with_request_id(request_id))
+        }
+    }
+
+    let current_seq = state
+        .db()
+        .event_store()
+        .get_latest_aggregate_seq(&AggregateType::Node, &node_id.to_string())
+        .await
+        .map_err(|e| internal(&e))?
+        .unwrap_or(0);
+
+    let event = NewEvent::builder(ctx)
+        .aggregate_id(node_id.to_string())
+        .aggregate_seq(current_seq + 1)
+        .payload(payload)
+        .build()
+        .map_err(|e| internal(&e))?;
+
+    let event_id = state
+        .db()
+        .event_store()
+        .append(event.into())
+        .await
+        .map_err(|e| match e {
+            DbError::SequenceConflict { .. } => {
+                ApiError::conflict("version_conflict", "Concurrent node update detected; retry")
+                    .with_request_id(request_id.clone())
+            }
+            other => internal(&other),
+        })?;
+
+    tracing::info!(
+        node_id = %node_id,
+        event_type = %P::EVENT_TYPE,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Node enrollment decision recorded"
+    );
+
+    consistency::wait_for_write(state, ctx, "nodes", event_id.value()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(used: bool, revoked: bool, expires_in: Duration) -> BootstrapToken {
+        let now = Utc::now();
+        BootstrapToken {
+            token_id: "nbt_1".to_string(),
+            description: None,
+            requires_approval: false,
+            created_by: "usr_1".to_string(),
+            created_at: now,
+            expires_at: now + expires_in,
+            used_at: used.then_some(now),
+            used_by_node_id: used.then(|| "node_1".to_string()),
+            revoked_at: revoked.then_some(now),
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_token_status() {
+        let hour = Duration::hours(1);
+        assert_eq!(
+            BootstrapTokenResponse::from(token(false, false, hour)).status,
+            "active"
+        );
+        assert_eq!(
+            BootstrapTokenResponse::from(token(true, false, hour)).status,
+            "used"
+        );
+        assert_eq!(
+            BootstrapTokenResponse::from(token(false, true, hour)).status,
+            "revoked"
+        );
+        assert_eq!(
+            BootstrapTokenResponse::from(token(false, false, -hour)).status,
+            "expired"
+        );
+    }
+
+    #[test]
+    fn test_enrollment_error_mapping() {
+        let err = enrollment_error(EnrollmentError::TokenRequired, "req_1");
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.problem.code, "bootstrap_token_required");
+
+        let err = enrollment_error(
+            EnrollmentError::IdentityMismatch("node_1".to_string()),
+            "req_1",
+        );
+        assert_eq!(err.status, StatusCode::FORBIDDEN);
+        assert_eq!(err.problem.code, "node_identity_mismatch");
+
+        let err = enrollment_error(EnrollmentError::SubjectMismatch, "req_1");
+        assert_eq!(err.status, StatusCode::FORBIDDEN);
+        assert_eq!(err.problem.code, "node_identity_mismatch");
+
+        let err = enrollment_error(
+            EnrollmentError::PendingApproval("node_1".to_string()),
+            "req_1",
+        );
+        assert_eq!(err.problem.code, "node_pending_approval");
+    }
+}
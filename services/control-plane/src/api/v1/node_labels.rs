@@ -0,0 +1,292 @@
+//! Node label and taint management.
+//!
+//! Operator endpoints under `/v1/nodes`:
+//! - PATCH /v1/nodes/{node_id}/labels - merge-patch node labels
+//! - PUT /v1/nodes/{node_id}/taints - replace node taints
+//!
+//! Labels are matched by env scale `node_selector`s and taints repel
+//! instances without a matching toleration (see docs/specs/scheduler/placement.md).
+
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Response,
+    routing::{patch, put},
+    Json, Router,
+};
+use plfm_events::{
+    AggregateType, EventPayload, NewEvent, NodeLabelsUpdatedPayload, NodeTaint,
+    NodeTaintsUpdatedPayload,
+};
+use plfm_id::NodeId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::labels::{self, LabelsResponse, PatchLabelsRequest};
+use crate::api::preconditions::{self, Preconditions};
+use crate::api::request_context::RequestContext;
+use crate::db::DbError;
+use crate::state::AppState;
+
+/// Maximum number of taints on a single node.
+const MAX_TAINTS: usize = 32;
+
+pub fn node_routes() -> Router<AppState> {
+    Router::new()
+        .route("/{node_id}/labels", patch(patch_node_labels))
+        .route("/{node_id}/taints", put(put_node_taints))
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+/// Request body for `PUT /v1/nodes/{node_id}/taints`.
+#[derive(Debug, Deserialize)]
+pub struct PutTaintsRequest {
+    /// Full taint set; an empty list removes all taints.
+    pub taints: Vec<NodeTaint>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaintsResponse {
+    pub node_id: String,
+    pub taints: Vec<NodeTaint>,
+    pub resource_version: i32,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Merge-patch a node's labels.
+///
+/// PATCH /v1/nodes/{node_id}/labels
+async fn patch_node_labels(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(node_id): Path<String>,
+    preconditions: Preconditions,
+    Json(req): Json<PatchLabelsRequest>,
+) -> Result<Response, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let node_id = parse_node_id(&node_id, &request_id)?;
+
+    let (current_labels, _, current_version) = load_node(&state, &node_id, &request_id).await?;
+    preconditions.check(None, current_version, false, &request_id)?;
+
+    let current_labels = labels::from_json(current_labels);
+    let updated = labels::apply_patch(&current_labels, &req.labels, &request_id)?;
+
+    let resource_version = if updated == current_labels {
+        current_version
+    } else {
+        let payload = NodeLabelsUpdatedPayload {
+            node_id,
+            labels: updated.clone(),
+        };
+        append_node_event(&state, &ctx, node_id, &payload).await?;
+        current_version + 1
+    };
+
+    Ok(preconditions::with_etag(
+        resource_version,
+        (
+            StatusCode::OK,
+            Json(LabelsResponse {
+                labels: updated,
+                resource_version,
+            }),
+        ),
+    ))
+}
+
+/// Replace a node's taints.
+///
+/// PUT /v1/nodes/{node_id}/taints
+async fn put_node_taints(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(node_id): Path<String>,
+    preconditions: Preconditions,
+    Json(req): Json<PutTaintsRequest>,
+) -> Result<Response, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let node_id = parse_node_id(&node_id, &request_id)?;
+
+    validate_taints(&req.taints).map_err(|msg| {
+        ApiError::bad_request("invalid_taint", msg).with_request_id(request_id.clone())
+    })?;
+
+    let (_, current_taints, current_version) = load_node(&state, &node_id, &request_id).await?;
+    preconditions.check(None, current_version, false, &request_id)?;
+
+    let current_taints: Vec<NodeTaint> = serde_json::from_value(current_taints).unwrap_or_default();
+
+    let resource_version = if req.taints == current_taints {
+        current_version
+    } else {
+        let payload = NodeTaintsUpdatedPayload {
+            node_id,
+            taints: req.taints.clone(),
+        };
+        append_node_event(&state, &ctx, node_id, &payload).await?;
+        current_version + 1
+    };
+
+    Ok(preconditions::with_etag(
+        resource_version,
+        (
+            StatusCode::OK,
+            Json(TaintsResponse {
+                node_id: node_id.to_string(),
+                taints: req.taints,
+                resource_version,
+            }),
+        ),
+    ))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn parse_node_id(raw: &str, request_id: &str) -> Result<NodeId, ApiError> {
+    raw.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.to_string())
+    })
+}
+
+/// Validate a taint set: keys must be non-blank and each (key, effect) pair
+/// may appear at most once.
+fn validate_taints(taints: &[NodeTaint]) -> Result<(), String> {
+    if taints.len() > MAX_TAINTS {
+        return Err(format!("a node may carry at most {MAX_TAINTS} taints"));
+    }
+    let mut seen = HashSet::new();
+    for taint in taints {
+        if taint.key.trim().is_empty() {
+            return Err("taint key cannot be empty".to_string());
+        }
+        if !seen.insert((taint.key.as_str(), taint.effect)) {
+            return Err(format!(
+                "duplicate taint '{}' with effect {:?}",
+                taint.key, taint.effect
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Load a node's labels, taints and resource version.
+async fn load_node(
+    state: &AppState,
+    node_id: &NodeId,
+    request_id: &str,
+) -> Result<(serde_json::Value, serde_json::Value, i32), ApiError> {
+    sqlx::query_as::<_, (serde_json::Value, serde_json::Value, i32)>(
+        "SELECT labels, taints, resource_version FROM nodes_view WHERE node_id = $1",
+    )
+    .bind(node_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, node_id = %node_id, "Failed to load node");
+        ApiError::internal("internal_error", "Failed to update node")
+            .with_request_id(request_id.to_string())
+    })?
+    .ok_or_else(|| {
+        ApiError::not_found("node_not_found", format!("Node {} not found", node_id))
+            .with_request_id(request_id.to_string())
+    })
+}
+
+/// Append a node event and wait for the nodes projection to apply it.
+async fn append_node_event<P: EventPayload>(
+    state: &AppState,
+    ctx: &RequestContext,
+    node_id: NodeId,
+    payload: &P,
+) -> Result<(), ApiError> {
+    let request_id = ctx.request_id.clone();
+    let internal = |e: &dyn std::fmt::Display| {
+        tracing::error!(error = %e, request_id = %request_id, node_id = %node_id, "Failed to update node");
+        ApiError::internal("internal_error", "Failed to update node")
+            .with_request_id(request_id.clone())
+    };
+
+    let current_seq = state
+        .db()
+        .event_store()
+        .get_latest_aggregate_seq(&AggregateType::Node, &node_id.to_string())
+        .await
+        .map_err(|e| internal(&e))?
+        .unwrap_or(0);
+
+    let event = NewEvent::builder(ctx)
+        .aggregate_id(node_id.to_string())
+        .aggregate_seq(current_seq + 1)
+        .payload(payload)
+        .build()
+        .map_err(|e| internal(&e))?;
+
+    let event_id = state
+        .db()
+        .event_store()
+        .append(event.into())
+        .await
+        .map_err(|e| match e {
+            DbError::SequenceConflict { .. } => {
+                ApiError::conflict("version_conflict", "Concurrent node update detected; retry")
+                    .with_request_id(request_id.clone())
+            }
+            other => internal(&other),
+        })?;
+
+    tracing::info!(
+        node_id = %node_id,
+        event_type = %P::EVENT_TYPE,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Node scheduling metadata updated"
+    );
+
+    consistency::wait_for_write(state, ctx, "nodes", event_id.value()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plfm_events::TaintEffect;
+
+    fn taint(key: &str, effect: TaintEffect) -> NodeTaint {
+        NodeTaint {
+            key: key.to_string(),
+            value: None,
+            effect,
+        }
+    }
+
+    #[test]
+    fn test_validate_taints() {
+        assert!(validate_taints(&[]).is_ok());
+        assert!(validate_taints(&[
+            taint("dedicated", TaintEffect::NoSchedule),
+            taint("dedicated", TaintEffect::PreferNoSchedule),
+        ])
+        .is_ok());
+        assert!(validate_taints(&[taint(" ", TaintEffect::NoSchedule)]).is_err());
+        assert!(validate_taints(&[
+            taint("gpu", TaintEffect::NoSchedule),
+            taint("gpu", TaintEffect::NoSchedule),
+        ])
+        .is_err());
+    }
+}
@@ -5,24 +5,39 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use plfm_events::{ActorType, AggregateType, NodeState};
-use plfm_id::{AppId, AssignmentId, EnvId, InstanceId, NodeId, OrgId, SecretVersionId, Ulid};
+use plfm_events::{
+    event_types, ActorType, AggregateType, InstanceFailureReason, JobStatus, NodeState,
+    RestoreJobStatusChangedPayload, SecretsReload, SnapshotStatusChangedPayload,
+    VolumeResizeCompletedPayload, VolumeResizeFailedPayload,
+};
+use plfm_id::{
+    AppId, AssignmentId, EnvId, InstanceId, NodeId, OrgId, RestoreJobId, SecretVersionId,
+    SnapshotId, Ulid, VolumeId,
+};
 use serde::{Deserialize, Serialize};
-use sqlx::QueryBuilder;
+use sqlx::{Postgres, QueryBuilder};
 use std::collections::HashMap;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 use crate::api::error::ApiError;
+use crate::api::pagination::{self, PageQuery, PageTotal, SortKey, Sorting};
 use crate::api::request_context::RequestContext;
 use crate::db::AppendEvent;
+use crate::drift;
+use crate::enrollment::{self, EnrollmentMode};
+use crate::instance_status::{self, BootKey};
+use crate::node_watchdog;
 use crate::secrets as secrets_crypto;
 use crate::state::AppState;
+use crate::timeline;
+
+use super::node_enrollment::{enrollment_error, verify_node_request};
 
 const MAX_LOG_ENTRIES: usize = 500;
 const MAX_LOG_LINE_BYTES: usize = 16 * 1024;
@@ -49,6 +64,18 @@ pub fn routes() -> Router<AppState> {
             "/{node_id}/instances/{instance_id}/status",
             post(report_instance_status),
         )
+        .route(
+            "/{node_id}/volumes/{volume_id}/resize",
+            post(report_volume_resize),
+        )
+        .route(
+            "/{node_id}/restores/{restore_id}",
+            post(report_volume_restore),
+        )
+        .route(
+            "/{node_id}/snapshots/{snapshot_id}",
+            post(report_volume_snapshot),
+        )
 }
 
 // =============================================================================
@@ -90,6 +117,10 @@ pub struct EnrollNodeRequest {
     /// Labels for scheduling (region, zone, etc.).
     #[serde(default)]
     pub labels: serde_json::Value,
+
+    /// Single-use bootstrap token minted by an operator.
+    #[serde(default)]
+    pub bootstrap_token: Option<String>,
 }
 
 /// Response for a single node.
@@ -122,6 +153,9 @@ pub struct NodeResponse {
     /// Labels for scheduling.
     pub labels: serde_json::Value,
 
+    /// Scheduling taints (`{key, value?, effect}`).
+    pub taints: serde_json::Value,
+
     /// Allocatable resources.
     pub allocatable: serde_json::Value,
 
@@ -137,6 +171,10 @@ pub struct NodeResponse {
 
     /// When the node was last updated.
     pub updated_at: DateTime<Utc>,
+
+    /// When the node last heartbeated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
 }
 
 /// Response for listing nodes.
@@ -147,15 +185,17 @@ pub struct ListNodesResponse {
 
     /// Next cursor (null if no more results).
     pub next_cursor: Option<String>,
+
+    /// Matching nodes, with `include_total=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
 }
 
-/// Query parameters for listing nodes.
+/// Query parameters for listing nodes. Pagination is in [`PageQuery`].
 #[derive(Debug, Deserialize)]
 pub struct ListNodesQuery {
-    /// Max number of items to return.
-    pub limit: Option<i64>,
-    /// Cursor (exclusive). Interpreted as a node_id.
-    pub cursor: Option<String>,
+    /// Only nodes in this state (e.g. `pending_approval`).
+    pub state: Option<String>,
 }
 
 /// Request for node heartbeat.
@@ -173,9 +213,18 @@ pub struct HeartbeatRequest {
     /// Number of running instances.
     pub instance_count: i32,
 
-    /// Instance statuses (instance_id -> status).
+    /// Instance statuses (instance_id -> status), the agent's full
+    /// inventory. Absent from agents that do not report one.
     #[serde(default)]
     pub instance_statuses: serde_json::Value,
+
+    /// Bytes allocated on the host by instance scratch disks on this node.
+    #[serde(default)]
+    pub ephemeral_disk_used_bytes: Option<i64>,
+
+    /// Free bytes left on the filesystem holding scratch disks.
+    #[serde(default)]
+    pub ephemeral_disk_available_bytes: Option<i64>,
 }
 
 /// Response for heartbeat.
@@ -197,6 +246,27 @@ pub struct NodePlanResponse {
     pub created_at: DateTime<Utc>,
     pub cursor_event_id: i64,
     pub instances: Vec<DesiredInstanceAssignment>,
+    /// In-place restores of volumes homed on this node that have not finished.
+    pub volume_restores: Vec<VolumeRestoreAssignment>,
+    /// Snapshots of volumes homed on this node that have not been taken yet.
+    pub volume_snapshots: Vec<VolumeSnapshotAssignment>,
+}
+
+/// A snapshot to take of a volume on this node.
+#[derive(Debug, Serialize)]
+pub struct VolumeSnapshotAssignment {
+    pub snapshot_id: String,
+    pub volume_id: String,
+}
+
+/// A snapshot to write over a volume on this node.
+#[derive(Debug, Serialize)]
+pub struct VolumeRestoreAssignment {
+    pub restore_id: String,
+    pub volume_id: String,
+    pub snapshot_id: String,
+    /// Current size of the volume; the restored volume keeps it.
+    pub size_bytes: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -290,6 +360,9 @@ pub struct WorkloadMount {
     pub filesystem: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub device_hint: Option<String>,
+    /// Desired volume size; the agent grows the backing storage to match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -304,6 +377,9 @@ pub struct WorkloadSecrets {
     pub uid: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gid: Option<i32>,
+    /// How the guest applies a new version in place; absent means restart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reload: Option<SecretsReload>,
 }
 
 /// Secret material response for node agent delivery.
@@ -325,6 +401,10 @@ pub struct ReportInstanceStatusRequest {
     #[serde(default)]
     pub boot_id: Option<String>,
 
+    /// Plan generation the reporting boot runs.
+    #[serde(default)]
+    pub generation: Option<i32>,
+
     /// Optional error message.
     #[serde(default)]
     pub error_message: Option<String>,
@@ -332,6 +412,10 @@ pub struct ReportInstanceStatusRequest {
     /// Optional exit code.
     #[serde(default)]
     pub exit_code: Option<i32>,
+
+    /// Classified failure reason, set by the agent when status is failed.
+    #[serde(default)]
+    pub reason_code: Option<InstanceFailureReason>,
 }
 
 /// Response for instance status reports.
@@ -340,6 +424,79 @@ pub struct ReportInstanceStatusResponse {
     pub accepted: bool,
 }
 
+/// Outcome of a volume resize performed by the node agent.
+#[derive(Debug, Deserialize)]
+pub struct ReportVolumeResizeRequest {
+    /// Size the agent attempted to grow the volume to.
+    pub size_bytes: i64,
+
+    /// Failure classification (e.g. `insufficient_space`); absent on success.
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// Human-readable failure detail.
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// Response for volume resize reports.
+#[derive(Debug, Serialize)]
+pub struct ReportVolumeResizeResponse {
+    pub accepted: bool,
+}
+
+/// Progress or outcome of an in-place restore, reported by the home node.
+#[derive(Debug, Deserialize)]
+pub struct ReportVolumeRestoreRequest {
+    /// `running`, `succeeded`, or `failed`.
+    pub status: String,
+
+    #[serde(default)]
+    pub bytes_restored: Option<i64>,
+
+    #[serde(default)]
+    pub bytes_total: Option<i64>,
+
+    /// Failure classification (e.g. `snapshot_unavailable`); set when failed.
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// Human-readable failure detail.
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// Response for volume restore reports.
+#[derive(Debug, Serialize)]
+pub struct ReportVolumeRestoreResponse {
+    pub accepted: bool,
+}
+
+/// Progress or outcome of a volume snapshot, reported by the home node.
+#[derive(Debug, Deserialize)]
+pub struct ReportVolumeSnapshotRequest {
+    /// `running`, `succeeded`, or `failed`.
+    pub status: String,
+
+    /// Bytes held by the snapshot; set when succeeded.
+    #[serde(default)]
+    pub size_bytes: Option<i64>,
+
+    /// Failure classification (e.g. `volume_missing`); set when failed.
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// Human-readable failure detail.
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// Response for volume snapshot reports.
+#[derive(Debug, Serialize)]
+pub struct ReportVolumeSnapshotResponse {
+    pub accepted: bool,
+}
+
 /// Workload log ingestion request (from node agents).
 #[derive(Debug, Deserialize)]
 pub struct WorkloadLogIngestRequest {
@@ -373,6 +530,7 @@ pub struct WorkloadLogIngestResponse {
 async fn enroll_node(
     State(state): State<AppState>,
     ctx: RequestContext,
+    headers: HeaderMap,
     Json(req): Json<EnrollNodeRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id;
@@ -446,7 +604,23 @@ async fn enroll_node(
         .with_request_id(request_id.clone()));
     }
 
+    // Pin only the subject the node's certificate actually carries.
+    let presented_subject = headers
+        .get(enrollment::client_cert_subject_header().as_str())
+        .and_then(|v| v.to_str().ok());
+    enrollment::check_enrollment_subject(&req.agent_mtls_subject, presented_subject)
+        .map_err(|e| enrollment_error(e, &request_id))?;
+
     let node_id = NodeId::new();
+    let admission = enrollment::admit(
+        state.db().pool(),
+        EnrollmentMode::from_env(),
+        req.bootstrap_token.as_deref(),
+        &node_id,
+    )
+    .await
+    .map_err(|e| enrollment_error(e, &request_id))?;
+
     let overlay_ipv6 = allocate_node_ipv6(state.db().pool(), &node_id, &request_id).await?;
 
     // Build allocatable resources
@@ -455,12 +629,27 @@ async fn enroll_node(
         "memory_bytes": req.memory_bytes,
     });
 
+    // Nodes awaiting approval are recorded but not schedulable.
+    let (event_type, node_state, status) = if admission.requires_approval {
+        (
+            plfm_events::event_types::NODE_ENROLLMENT_REQUESTED,
+            enrollment::STATE_PENDING_APPROVAL,
+            StatusCode::ACCEPTED,
+        )
+    } else {
+        (
+            plfm_events::event_types::NODE_ENROLLED,
+            "active",
+            StatusCode::CREATED,
+        )
+    };
+
     // Create the event
     let event = AppendEvent {
         aggregate_type: AggregateType::Node,
         aggregate_id: node_id.to_string(),
         aggregate_seq: 1,
-        event_type: "node.enrolled".to_string(),
+        event_type: event_type.to_string(),
         event_version: 1,
         actor_type: ActorType::ServicePrincipal, // Node agents are service principals
         actor_id: node_id.to_string(),
@@ -485,6 +674,7 @@ async fn enroll_node(
             "mtu": req.mtu,
             "labels": req.labels,
             "allocatable": allocatable,
+            "bootstrap_token_id": admission.bootstrap_token_id,
         }),
         ..Default::default()
     };
@@ -500,13 +690,14 @@ async fn enroll_node(
     let now = Utc::now();
     let response = NodeResponse {
         id: node_id.to_string(),
-        state: "active".to_string(),
+        state: node_state.to_string(),
         wireguard_public_key: req.wireguard_public_key,
         agent_mtls_subject: req.agent_mtls_subject,
         public_ipv6: Some(req.public_ipv6.to_string()),
         public_ipv4: req.public_ipv4.map(|ip| ip.to_string()),
         overlay_ipv6: Some(overlay_ipv6.clone()),
         labels: req.labels,
+        taints: serde_json::json!([]),
         allocatable,
         mtu: req.mtu,
         resource_version: 1,
@@ -518,11 +709,12 @@ async fn enroll_node(
         node_id = %node_id,
         hostname = %req.hostname,
         region = %req.region,
+        state = %node_state,
         request_id = %request_id,
         "Node enrolled"
     );
 
-    Ok((StatusCode::CREATED, Json(response)))
+    Ok((status, Json(response)))
 }
 
 async fn allocate_node_ipv6(
@@ -633,44 +825,55 @@ async fn list_nodes(
     State(state): State<AppState>,
     ctx: RequestContext,
     Query(query): Query<ListNodesQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id;
 
-    let limit: i64 = query.limit.unwrap_or(50).clamp(1, 200);
-    let cursor = query.cursor;
+    let page = NODE_SORTING.page(page, &request_id)?;
+    let filters = |builder: &mut QueryBuilder<'_, Postgres>| {
+        builder.push("TRUE");
+        if let Some(node_state) = query.state.as_ref() {
+            builder.push(" AND state = ");
+            builder.push_bind(node_state.clone());
+        }
+    };
 
-    let rows = sqlx::query_as::<_, NodeRow>(
-        r#"
-        SELECT node_id, state, wireguard_public_key, agent_mtls_subject,
-               host(public_ipv6)::TEXT as public_ipv6,
-               host(public_ipv4)::TEXT as public_ipv4,
-               host(overlay_ipv6)::TEXT as overlay_ipv6,
-               labels, allocatable, mtu,
-               resource_version, created_at, updated_at
-        FROM nodes_view
-        WHERE ($1::text IS NULL OR node_id > $1)
-        ORDER BY node_id ASC
-        LIMIT $2
-        "#,
-    )
-    .bind(cursor.as_deref())
-    .bind(limit)
-    .fetch_all(state.db().pool())
-    .await
-    .map_err(|e| {
+    let mut builder = QueryBuilder::new(
+        "SELECT node_id, state, wireguard_public_key, agent_mtls_subject, \
+                host(public_ipv6)::TEXT as public_ipv6, \
+                host(public_ipv4)::TEXT as public_ipv4, \
+                host(overlay_ipv6)::TEXT as overlay_ipv6, \
+                labels, taints, allocatable, mtu, \
+                resource_version, created_at, updated_at, last_heartbeat_at \
+         FROM nodes_view WHERE ",
+    );
+    filters(&mut builder);
+    page.push_after(&mut builder);
+    page.push_order(&mut builder);
+
+    let list_failed = |e: sqlx::Error| {
         tracing::error!(error = %e, request_id = %request_id, "Failed to list nodes");
         ApiError::internal("internal_error", "Failed to list nodes")
             .with_request_id(request_id.clone())
-    })?;
+    };
+    let rows = builder
+        .build_query_as::<NodeRow>()
+        .fetch_all(state.db().pool())
+        .await
+        .map_err(list_failed)?;
+    let total = page
+        .total(state.db().pool(), "nodes_view", filters)
+        .await
+        .map_err(list_failed)?;
 
+    let (rows, next_cursor) = page.finish(rows);
     let items: Vec<NodeResponse> = rows.into_iter().map(NodeResponse::from).collect();
-    let next_cursor = if items.len() == limit as usize {
-        items.last().map(|item| item.id.clone())
-    } else {
-        None
-    };
 
-    Ok(Json(ListNodesResponse { items, next_cursor }))
+    Ok(Json(ListNodesResponse {
+        items,
+        next_cursor,
+        total,
+    }))
 }
 
 /// Get a single node by ID.
@@ -695,8 +898,8 @@ async fn get_node(
                host(public_ipv6)::TEXT as public_ipv6,
                host(public_ipv4)::TEXT as public_ipv4,
                host(overlay_ipv6)::TEXT as overlay_ipv6,
-               labels, allocatable, mtu,
-               resource_version, created_at, updated_at
+               labels, taints, allocatable, mtu,
+               resource_version, created_at, updated_at, last_heartbeat_at
         FROM nodes_view
         WHERE node_id = $1
         "#,
@@ -725,6 +928,7 @@ async fn get_node(
 async fn heartbeat(
     State(state): State<AppState>,
     ctx: RequestContext,
+    headers: HeaderMap,
     Path(node_id): Path<String>,
     Json(req): Json<HeartbeatRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -736,6 +940,8 @@ async fn heartbeat(
             .with_request_id(request_id.clone())
     })?;
 
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
     // Check node exists and get current state
     let current_state =
         sqlx::query_scalar::<_, String>("SELECT state FROM nodes_view WHERE node_id = $1")
@@ -798,6 +1004,8 @@ async fn heartbeat(
             "available_memory_bytes": req.available_memory_bytes,
             "instance_count": req.instance_count,
             "instance_statuses_entries": instance_statuses_entries,
+            "ephemeral_disk_used_bytes": req.ephemeral_disk_used_bytes,
+            "ephemeral_disk_available_bytes": req.ephemeral_disk_available_bytes,
         }),
         ..Default::default()
     };
@@ -810,8 +1018,28 @@ async fn heartbeat(
         NodeState::Degraded => "degraded",
         NodeState::Offline => "offline",
     };
+    let transition = node_watchdog::heartbeat_state(
+        state.db().pool(),
+        &node_id,
+        &current_state,
+        new_state_str,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to check node flapping");
+        ApiError::internal("internal_error", "Failed to process heartbeat")
+            .with_request_id(request_id.clone())
+    })?;
 
-    if current_state != new_state_str {
+    if let Some(transition) = transition {
+        let mut payload = serde_json::json!({
+            "node_id": node_id_typed.to_string(),
+            "old_state": current_state,
+            "new_state": transition.new_state,
+        });
+        if let Some(reason) = transition.reason {
+            payload["reason"] = serde_json::json!(reason);
+        }
         let state_event = AppendEvent {
             aggregate_type: AggregateType::Node,
             aggregate_id: node_id.clone(),
@@ -827,11 +1055,7 @@ async fn heartbeat(
             env_id: None,
             correlation_id: None,
             causation_id: None,
-            payload: serde_json::json!({
-                "node_id": node_id_typed.to_string(),
-                "old_state": current_state,
-                "new_state": new_state_str,
-            }),
+            payload,
             ..Default::default()
         };
 
@@ -845,7 +1069,8 @@ async fn heartbeat(
         tracing::info!(
             node_id = %node_id,
             old_state = %current_state,
-            new_state = %new_state_str,
+            new_state = %transition.new_state,
+            reason = ?transition.reason,
             request_id = %request_id,
             "Node state changed"
         );
@@ -857,6 +1082,12 @@ async fn heartbeat(
         })?;
     }
 
+    if let Some(inventory) = drift::inventory_from_json(&req.instance_statuses) {
+        if let Err(e) = drift::record_inventory(state.db().pool(), &node_id, &inventory).await {
+            tracing::warn!(error = %e, node_id = %node_id, "Failed to record instance inventory");
+        }
+    }
+
     Ok(Json(HeartbeatResponse {
         accepted: true,
         next_heartbeat_secs: 30, // 30 second heartbeat interval
@@ -871,6 +1102,7 @@ async fn heartbeat(
 async fn get_plan(
     State(state): State<AppState>,
     ctx: RequestContext,
+    headers: HeaderMap,
     Path(node_id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id;
@@ -881,6 +1113,8 @@ async fn get_plan(
             .with_request_id(request_id.clone())
     })?;
 
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
     let node_info = sqlx::query_as::<_, NodePlanNodeRow>(
         "SELECT labels, mtu FROM nodes_view WHERE node_id = $1",
     )
@@ -923,11 +1157,17 @@ async fn get_plan(
                r.manifest_hash as manifest_hash,
                r.command as command,
                i.secrets_version_id,
+               cv.vars as config_vars,
+               s.secrets_reload,
                host(i.overlay_ipv6)::TEXT as overlay_ipv6,
                i.resources_snapshot,
                i.spec_hash
         FROM instances_desired_view i
         JOIN releases_view r ON i.release_id = r.release_id
+        LEFT JOIN env_config_var_versions cv
+          ON cv.env_id = i.env_id AND cv.version = i.config_vars_version
+        LEFT JOIN env_scale_view s
+          ON s.env_id = i.env_id AND s.process_type = i.process_type
         WHERE i.node_id = $1
         ORDER BY i.created_at
         "#,
@@ -948,6 +1188,69 @@ async fn get_plan(
             .with_request_id(request_id.clone())
     })?;
 
+    let instance_ids: Vec<String> = instances.iter().map(|i| i.instance_id.clone()).collect();
+    if let Err(e) = timeline::record_plan_delivery(state.db().pool(), &node_id, &instance_ids).await
+    {
+        tracing::warn!(error = %e, node_id = %node_id, "Failed to record plan delivery");
+    }
+
+    let volume_restores = sqlx::query_as::<_, (String, String, String, i64)>(
+        r#"
+        SELECT r.restore_id, r.source_volume_id, r.snapshot_id, v.size_bytes
+        FROM restore_jobs_view r
+        JOIN volumes_view v ON v.volume_id = r.source_volume_id
+        WHERE r.in_place
+          AND r.status IN ('queued', 'running')
+          AND v.home_node_id = $1
+          AND NOT v.is_deleted
+        ORDER BY r.created_at
+        "#,
+    )
+    .bind(&node_id)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, node_id = %node_id, "Failed to load volume restores");
+        ApiError::internal("internal_error", "Failed to get plan")
+            .with_request_id(request_id.clone())
+    })?
+    .into_iter()
+    .map(
+        |(restore_id, volume_id, snapshot_id, size_bytes)| VolumeRestoreAssignment {
+            restore_id,
+            volume_id,
+            snapshot_id,
+            size_bytes,
+        },
+    )
+    .collect();
+
+    let volume_snapshots = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT s.snapshot_id, s.volume_id
+        FROM snapshots_view s
+        JOIN volumes_view v ON v.volume_id = s.volume_id
+        WHERE s.status IN ('queued', 'running')
+          AND v.home_node_id = $1
+          AND NOT v.is_deleted
+        ORDER BY s.created_at
+        "#,
+    )
+    .bind(&node_id)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, node_id = %node_id, "Failed to load volume snapshots");
+        ApiError::internal("internal_error", "Failed to get plan")
+            .with_request_id(request_id.clone())
+    })?
+    .into_iter()
+    .map(|(snapshot_id, volume_id)| VolumeSnapshotAssignment {
+        snapshot_id,
+        volume_id,
+    })
+    .collect();
+
     let volume_mounts = load_volume_mounts(&state, &request_id, &instances).await?;
     let arch_hint = label_value(&node_info.labels, "arch");
     let instance_assignments: Vec<DesiredInstanceAssignment> = instances
@@ -962,6 +1265,8 @@ async fn get_plan(
         created_at: Utc::now(),
         cursor_event_id,
         instances: instance_assignments,
+        volume_restores,
+        volume_snapshots,
     }))
 }
 
@@ -971,6 +1276,7 @@ async fn get_plan(
 async fn get_secret_material(
     State(state): State<AppState>,
     ctx: RequestContext,
+    headers: HeaderMap,
     Path((node_id, version_id)): Path<(String, String)>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
@@ -988,6 +1294,8 @@ async fn get_secret_material(
             .with_request_id(request_id.clone())
     })?;
 
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
     let version_id_typed: SecretVersionId = version_id.parse().map_err(|_| {
         ApiError::bad_request(
             "invalid_secret_version_id",
@@ -996,25 +1304,6 @@ async fn get_secret_material(
         .with_request_id(request_id.clone())
     })?;
 
-    let node_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM nodes_view WHERE node_id = $1)",
-    )
-    .bind(&node_id)
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to check node existence");
-        ApiError::internal("internal_error", "Failed to load secrets")
-            .with_request_id(request_id.clone())
-    })?;
-
-    if !node_exists {
-        return Err(
-            ApiError::not_found("node_not_found", format!("Node {} not found", node_id))
-                .with_request_id(request_id.clone()),
-        );
-    }
-
     let row = sqlx::query_as::<_, SecretMaterialRow>(
         r#"
         SELECT sv.version_id,
@@ -1078,6 +1367,7 @@ async fn get_secret_material(
         &row.wrapped_data_key_nonce,
         aad.as_bytes(),
     )
+    .await
     .map_err(|e| {
         tracing::error!(error = %e, request_id = %request_id, "Failed to decrypt secrets");
         ApiError::internal("secrets_decrypt_failed", "Failed to decrypt secrets")
@@ -1106,6 +1396,7 @@ async fn get_secret_material(
 async fn ingest_logs(
     State(state): State<AppState>,
     ctx: RequestContext,
+    headers: HeaderMap,
     Path(node_id): Path<String>,
     Json(req): Json<WorkloadLogIngestRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -1119,29 +1410,12 @@ async fn ingest_logs(
         .with_request_id(request_id));
     }
 
-    let node_id_typed: NodeId = node_id.parse().map_err(|_| {
+    let _node_id_typed: NodeId = node_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_node_id", "Invalid node ID format")
             .with_request_id(request_id.clone())
     })?;
 
-    let node_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM nodes_view WHERE node_id = $1)",
-    )
-    .bind(node_id_typed.to_string())
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to check node existence");
-        ApiError::internal("internal_error", "Failed to ingest logs")
-            .with_request_id(request_id.clone())
-    })?;
-
-    if !node_exists {
-        return Err(
-            ApiError::not_found("node_not_found", format!("Node {} not found", node_id))
-                .with_request_id(request_id.clone()),
-        );
-    }
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
 
     if req.entries.is_empty() {
         return Ok(Json(WorkloadLogIngestResponse {
@@ -1253,9 +1527,14 @@ async fn ingest_logs(
 /// Report instance status for an instance assigned to this node.
 ///
 /// POST /v1/nodes/{node_id}/instances/{instance_id}/status
+///
+/// Reports from an older generation or an earlier boot than the current
+/// status are stale (see [`instance_status`]); they are acknowledged with
+/// `accepted: false` and no event.
 async fn report_instance_status(
     State(state): State<AppState>,
     ctx: RequestContext,
+    headers: HeaderMap,
     Path((node_id, instance_id)): Path<(String, String)>,
     Json(req): Json<ReportInstanceStatusRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -1274,6 +1553,8 @@ async fn report_instance_status(
             .with_request_id(request_id.clone())
     })?;
 
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
     let instance_id_typed: InstanceId = instance_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_instance_id", "Invalid instance ID format")
             .with_request_id(request_id.clone())
@@ -1288,19 +1569,6 @@ async fn report_instance_status(
         .with_request_id(request_id.clone()));
     }
 
-    let _current_status = sqlx::query_scalar::<_, Option<String>>(
-        "SELECT status FROM instances_status_view WHERE instance_id = $1",
-    )
-    .bind(instance_id_typed.to_string())
-    .fetch_optional(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Failed to get current status");
-        ApiError::internal("internal_error", "Failed to process status")
-            .with_request_id(request_id.clone())
-    })?
-    .flatten();
-
     let instance_info = sqlx::query_as::<_, InstanceInfoRow>(
         r#"
         SELECT org_id, app_id, env_id
@@ -1329,6 +1597,29 @@ async fn report_instance_status(
         }
     };
 
+    let report_key = BootKey::new(req.generation, req.boot_id.clone());
+    let current_key = instance_status::current_key(state.db().pool(), &instance_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get current status");
+            ApiError::internal("internal_error", "Failed to process status")
+                .with_request_id(request_id.clone())
+        })?;
+    if let Some(current_key) = current_key.filter(|current| report_key.is_stale(current)) {
+        tracing::info!(
+            instance_id = %instance_id,
+            boot_id = ?report_key.boot_id,
+            generation = ?report_key.generation,
+            current_boot_id = ?current_key.boot_id,
+            current_generation = ?current_key.generation,
+            "Ignoring stale instance status report"
+        );
+        return Ok((
+            StatusCode::OK,
+            Json(ReportInstanceStatusResponse { accepted: false }),
+        ));
+    }
+
     let event_store = state.db().event_store();
     let current_seq = event_store
         .get_latest_aggregate_seq(&AggregateType::Instance, &instance_id_typed.to_string())
@@ -1353,6 +1644,12 @@ async fn report_instance_status(
             .with_request_id(request_id.clone())
     })?;
 
+    let reason_code = match (req.status.as_str(), req.reason_code) {
+        ("failed", Some(reason)) => serde_json::json!(reason),
+        ("failed", None) => serde_json::json!(req.error_message.as_ref().map(|_| "unspecified")),
+        _ => serde_json::Value::Null,
+    };
+
     let event = AppendEvent {
         aggregate_type: AggregateType::Instance,
         aggregate_id: instance_id_typed.to_string(),
@@ -1373,8 +1670,9 @@ async fn report_instance_status(
             "node_id": node_id_typed.to_string(),
             "status": req.status,
             "boot_id": req.boot_id,
+            "generation": req.generation,
             "exit_code": req.exit_code,
-            "reason_code": if req.status == "failed" { req.error_message.as_ref().map(|_| "unspecified") } else { None },
+            "reason_code": reason_code,
             "reason_detail": req.error_message,
             "reported_at": chrono::Utc::now().to_rfc3339(),
         }),
@@ -1393,10 +1691,510 @@ async fn report_instance_status(
     ))
 }
 
+/// Report the outcome of growing a volume on its home node.
+///
+/// POST /v1/nodes/{node_id}/volumes/{volume_id}/resize
+///
+/// Reports are only recorded while a resize is outstanding (`pending`, or
+/// `failed` and being retried). Reports for another size are stale because a
+/// newer resize superseded them; both cases are acknowledged with
+/// `accepted: false` and no event.
+async fn report_volume_resize(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path((node_id, volume_id)): Path<(String, String)>,
+    Json(req): Json<ReportVolumeResizeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    if ctx.actor_type != ActorType::System {
+        return Err(ApiError::forbidden(
+            "forbidden",
+            "This endpoint is only available to system actors",
+        )
+        .with_request_id(request_id));
+    }
+
+    let node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    let volume_id_typed: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let volume = sqlx::query_as::<_, (String, i64, Option<String>, Option<String>)>(
+        r#"
+        SELECT org_id, size_bytes, home_node_id, resize_status
+        FROM volumes_view
+        WHERE volume_id = $1 AND NOT is_deleted
+        "#,
+    )
+    .bind(volume_id_typed.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to load volume");
+        ApiError::internal("internal_error", "Failed to process resize report")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let Some((org_id, size_bytes, home_node_id, resize_status)) = volume else {
+        return Err(
+            ApiError::not_found("volume_not_found", "Volume not found").with_request_id(request_id)
+        );
+    };
+
+    if home_node_id.as_deref() != Some(node_id_typed.to_string().as_str()) {
+        return Err(ApiError::conflict(
+            "volume_home_node_conflict",
+            "Volume is not homed on this node",
+        )
+        .with_request_id(request_id));
+    }
+
+    let outstanding = matches!(resize_status.as_deref(), Some("pending" | "failed"));
+    if req.size_bytes != size_bytes || !outstanding {
+        return Ok((
+            StatusCode::OK,
+            Json(ReportVolumeResizeResponse { accepted: false }),
+        ));
+    }
+
+    let org_id = org_id.parse::<OrgId>().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid org_id in volumes_view")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let event_store = state.db().event_store();
+    let current_seq = event_store
+        .get_latest_aggregate_seq(&AggregateType::Volume, &volume_id_typed.to_string())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get aggregate sequence");
+            ApiError::internal("internal_error", "Failed to process resize report")
+                .with_request_id(request_id.clone())
+        })?
+        .unwrap_or(0);
+
+    let (event_type, payload) = match req.reason {
+        None => (
+            event_types::VOLUME_RESIZE_COMPLETED,
+            serde_json::to_value(VolumeResizeCompletedPayload {
+                volume_id: volume_id_typed,
+                org_id,
+                node_id: node_id_typed,
+                size_bytes,
+            }),
+        ),
+        Some(reason) => (
+            event_types::VOLUME_RESIZE_FAILED,
+            serde_json::to_value(VolumeResizeFailedPayload {
+                volume_id: volume_id_typed,
+                org_id,
+                node_id: node_id_typed,
+                size_bytes,
+                message: req
+                    .error_message
+                    .unwrap_or_else(|| format!("resize failed: {reason}")),
+                reason,
+            }),
+        ),
+    };
+    let payload = payload.map_err(|e| {
+        tracing::error!(error = %e, "Failed to serialize resize payload");
+        ApiError::internal("internal_error", "Failed to process resize report")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let event = AppendEvent {
+        aggregate_type: AggregateType::Volume,
+        aggregate_id: volume_id_typed.to_string(),
+        aggregate_seq: current_seq + 1,
+        event_type: event_type.to_string(),
+        event_version: 1,
+        actor_type: ActorType::ServicePrincipal, // Node agent
+        actor_id: node_id_typed.to_string(),
+        org_id: Some(org_id),
+        request_id: request_id.clone(),
+        idempotency_key: None,
+        app_id: None,
+        env_id: None,
+        correlation_id: None,
+        causation_id: None,
+        payload,
+        ..Default::default()
+    };
+
+    event_store.append(event).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to record resize outcome");
+        ApiError::internal("internal_error", "Failed to record resize outcome")
+            .with_request_id(request_id.clone())
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReportVolumeResizeResponse { accepted: true }),
+    ))
+}
+
+/// Record progress or the outcome of an in-place restore.
+///
+/// POST /v1/nodes/{node_id}/restores/{restore_id}
+///
+/// Only the home node of the target volume may report, and only while the
+/// restore is queued or running; reports for a finished restore are
+/// acknowledged with `accepted: false` and no event.
+async fn report_volume_restore(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path((node_id, restore_id)): Path<(String, String)>,
+    Json(req): Json<ReportVolumeRestoreRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    if ctx.actor_type != ActorType::System {
+        return Err(ApiError::forbidden(
+            "forbidden",
+            "This endpoint is only available to system actors",
+        )
+        .with_request_id(request_id));
+    }
+
+    let node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    let restore_id_typed: RestoreJobId = restore_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_restore_id", "Invalid restore ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let status = match req.status.as_str() {
+        "running" => JobStatus::Running,
+        "succeeded" => JobStatus::Succeeded,
+        "failed" => JobStatus::Failed,
+        other => {
+            return Err(ApiError::bad_request(
+                "invalid_restore_status",
+                format!("Invalid restore status '{other}'; expected running, succeeded, or failed"),
+            )
+            .with_request_id(request_id));
+        }
+    };
+
+    let restore = sqlx::query_as::<_, (String, String, Option<String>)>(
+        r#"
+        SELECT r.org_id, r.status, v.home_node_id
+        FROM restore_jobs_view r
+        LEFT JOIN volumes_view v ON v.volume_id = r.source_volume_id
+        WHERE r.restore_id = $1 AND r.in_place
+        "#,
+    )
+    .bind(restore_id_typed.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to load restore job");
+        ApiError::internal("internal_error", "Failed to process restore report")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let Some((org_id, current_status, home_node_id)) = restore else {
+        return Err(
+            ApiError::not_found("restore_not_found", "Restore not found")
+                .with_request_id(request_id),
+        );
+    };
+
+    if home_node_id.as_deref() != Some(node_id_typed.to_string().as_str()) {
+        return Err(ApiError::conflict(
+            "volume_home_node_conflict",
+            "Volume is not homed on this node",
+        )
+        .with_request_id(request_id));
+    }
+
+    if !matches!(current_status.as_str(), "queued" | "running") {
+        return Ok((
+            StatusCode::OK,
+            Json(ReportVolumeRestoreResponse { accepted: false }),
+        ));
+    }
+
+    let org_id = org_id.parse::<OrgId>().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid org_id in restore_jobs_view")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let failed_reason = match status {
+        JobStatus::Failed => Some(match (req.reason, req.error_message) {
+            (Some(reason), Some(message)) => format!("{reason}: {message}"),
+            (Some(reason), None) => reason,
+            (None, Some(message)) => message,
+            (None, None) => "restore failed".to_string(),
+        }),
+        _ => None,
+    };
+
+    let payload = serde_json::to_value(RestoreJobStatusChangedPayload {
+        restore_id: restore_id_typed,
+        org_id,
+        status,
+        new_volume_id: None,
+        failed_reason,
+        bytes_restored: req.bytes_restored,
+        bytes_total: req.bytes_total,
+    })
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to serialize restore payload");
+        ApiError::internal("internal_error", "Failed to process restore report")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let event_store = state.db().event_store();
+    let current_seq = event_store
+        .get_latest_aggregate_seq(&AggregateType::RestoreJob, &restore_id_typed.to_string())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get aggregate sequence");
+            ApiError::internal("internal_error", "Failed to process restore report")
+                .with_request_id(request_id.clone())
+        })?
+        .unwrap_or(0);
+
+    let event = AppendEvent {
+        aggregate_type: AggregateType::RestoreJob,
+        aggregate_id: restore_id_typed.to_string(),
+        aggregate_seq: current_seq + 1,
+        event_type: event_types::RESTORE_JOB_STATUS_CHANGED.to_string(),
+        event_version: 1,
+        actor_type: ActorType::ServicePrincipal, // Node agent
+        actor_id: node_id_typed.to_string(),
+        org_id: Some(org_id),
+        request_id: request_id.clone(),
+        idempotency_key: None,
+        app_id: None,
+        env_id: None,
+        correlation_id: None,
+        causation_id: None,
+        payload,
+        ..Default::default()
+    };
+
+    event_store.append(event).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to record restore progress");
+        ApiError::internal("internal_error", "Failed to record restore progress")
+            .with_request_id(request_id.clone())
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReportVolumeRestoreResponse { accepted: true }),
+    ))
+}
+
+/// Record progress or the outcome of a volume snapshot taken by the volume's
+/// home node.
+///
+/// POST /v1/nodes/{node_id}/snapshots/{snapshot_id}
+async fn report_volume_snapshot(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path((node_id, snapshot_id)): Path<(String, String)>,
+    Json(req): Json<ReportVolumeSnapshotRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    if ctx.actor_type != ActorType::System {
+        return Err(ApiError::forbidden(
+            "forbidden",
+            "This endpoint is only available to system actors",
+        )
+        .with_request_id(request_id));
+    }
+
+    let node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    let snapshot_id_typed: SnapshotId = snapshot_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_snapshot_id", "Invalid snapshot ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let status = match req.status.as_str() {
+        "running" => JobStatus::Running,
+        "succeeded" => JobStatus::Succeeded,
+        "failed" => JobStatus::Failed,
+        other => {
+            return Err(ApiError::bad_request(
+                "invalid_snapshot_status",
+                format!(
+                    "Invalid snapshot status '{other}'; expected running, succeeded, or failed"
+                ),
+            )
+            .with_request_id(request_id));
+        }
+    };
+
+    let snapshot = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        r#"
+        SELECT s.org_id, s.volume_id, s.status, v.home_node_id
+        FROM snapshots_view s
+        LEFT JOIN volumes_view v ON v.volume_id = s.volume_id
+        WHERE s.snapshot_id = $1
+        "#,
+    )
+    .bind(snapshot_id_typed.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to load snapshot");
+        ApiError::internal("internal_error", "Failed to process snapshot report")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let Some((org_id, volume_id, current_status, home_node_id)) = snapshot else {
+        return Err(
+            ApiError::not_found("snapshot_not_found", "Snapshot not found")
+                .with_request_id(request_id),
+        );
+    };
+
+    if home_node_id.as_deref() != Some(node_id_typed.to_string().as_str()) {
+        return Err(ApiError::conflict(
+            "volume_home_node_conflict",
+            "Volume is not homed on this node",
+        )
+        .with_request_id(request_id));
+    }
+
+    if !matches!(current_status.as_str(), "queued" | "running") {
+        return Ok((
+            StatusCode::OK,
+            Json(ReportVolumeSnapshotResponse { accepted: false }),
+        ));
+    }
+
+    let org_id = org_id.parse::<OrgId>().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid org_id in snapshots_view")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id = volume_id.parse::<VolumeId>().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid volume_id in snapshots_view")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let failed_reason = match status {
+        JobStatus::Failed => Some(match (req.reason, req.error_message) {
+            (Some(reason), Some(message)) => format!("{reason}: {message}"),
+            (Some(reason), None) => reason,
+            (None, Some(message)) => message,
+            (None, None) => "snapshot failed".to_string(),
+        }),
+        _ => None,
+    };
+
+    let payload = serde_json::to_value(SnapshotStatusChangedPayload {
+        snapshot_id: snapshot_id_typed,
+        org_id,
+        volume_id,
+        status,
+        size_bytes: req.size_bytes.filter(|_| status == JobStatus::Succeeded),
+        failed_reason,
+    })
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to serialize snapshot payload");
+        ApiError::internal("internal_error", "Failed to process snapshot report")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let event_store = state.db().event_store();
+    let current_seq = event_store
+        .get_latest_aggregate_seq(&AggregateType::Snapshot, &snapshot_id_typed.to_string())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get aggregate sequence");
+            ApiError::internal("internal_error", "Failed to process snapshot report")
+                .with_request_id(request_id.clone())
+        })?
+        .unwrap_or(0);
+
+    let event = AppendEvent {
+        aggregate_type: AggregateType::Snapshot,
+        aggregate_id: snapshot_id_typed.to_string(),
+        aggregate_seq: current_seq + 1,
+        event_type: event_types::SNAPSHOT_STATUS_CHANGED.to_string(),
+        event_version: 1,
+        actor_type: ActorType::ServicePrincipal, // Node agent
+        actor_id: node_id_typed.to_string(),
+        org_id: Some(org_id),
+        request_id: request_id.clone(),
+        idempotency_key: None,
+        app_id: None,
+        env_id: None,
+        correlation_id: None,
+        causation_id: None,
+        payload,
+        ..Default::default()
+    };
+
+    event_store.append(event).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to record snapshot status");
+        ApiError::internal("internal_error", "Failed to record snapshot status")
+            .with_request_id(request_id.clone())
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReportVolumeSnapshotResponse { accepted: true }),
+    ))
+}
+
 // =============================================================================
 // Database Row Types
 // =============================================================================
 
+/// Orderings accepted by `GET /v1/nodes`.
+static NODE_SORTING: Sorting<NodeRow> = Sorting {
+    id_column: "node_id",
+    id: |row| row.node_id.clone(),
+    keys: &[
+        SortKey {
+            name: "id",
+            column: "node_id",
+            sql_type: "TEXT",
+            value: |row| row.node_id.clone(),
+        },
+        SortKey {
+            name: "created_at",
+            column: "created_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.created_at),
+        },
+        SortKey {
+            name: "updated_at",
+            column: "updated_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.updated_at),
+        },
+    ],
+};
+
 /// Row from nodes_view table.
 struct NodeRow {
     node_id: String,
@@ -1407,11 +2205,13 @@ struct NodeRow {
     public_ipv4: Option<String>,
     overlay_ipv6: Option<String>,
     labels: serde_json::Value,
+    taints: serde_json::Value,
     allocatable: serde_json::Value,
     mtu: Option<i32>,
     resource_version: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    last_heartbeat_at: Option<DateTime<Utc>>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for NodeRow {
@@ -1428,11 +2228,13 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for NodeRow {
             public_ipv4: row.try_get("public_ipv4")?,
             overlay_ipv6: row.try_get("overlay_ipv6")?,
             labels: row.try_get("labels")?,
+            taints: row.try_get("taints")?,
             allocatable: row.try_get("allocatable")?,
             mtu: row.try_get("mtu")?,
             resource_version: row.try_get("resource_version")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
+            last_heartbeat_at: row.try_get("last_heartbeat_at")?,
         })
     }
 }
@@ -1448,11 +2250,13 @@ impl From<NodeRow> for NodeResponse {
             public_ipv4: row.public_ipv4,
             overlay_ipv6: row.overlay_ipv6,
             labels: row.labels,
+            taints: row.taints,
             allocatable: row.allocatable,
             mtu: row.mtu,
             resource_version: row.resource_version,
             created_at: row.created_at,
             updated_at: row.updated_at,
+            last_heartbeat_at: row.last_heartbeat_at,
         }
     }
 }
@@ -1489,6 +2293,10 @@ struct InstancePlanRow {
     manifest_hash: String,
     command: serde_json::Value,
     secrets_version_id: Option<String>,
+    /// Config vars the instance was allocated with.
+    config_vars: Option<serde_json::Value>,
+    /// Secrets reload mode of the process type; NULL means restart.
+    secrets_reload: Option<serde_json::Value>,
     overlay_ipv6: Option<String>,
     resources_snapshot: serde_json::Value,
     spec_hash: String,
@@ -1513,6 +2321,8 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for InstancePlanRow {
             manifest_hash: row.try_get("manifest_hash")?,
             command: row.try_get("command")?,
             secrets_version_id: row.try_get("secrets_version_id")?,
+            config_vars: row.try_get("config_vars")?,
+            secrets_reload: row.try_get("secrets_reload")?,
             overlay_ipv6: row.try_get("overlay_ipv6")?,
             resources_snapshot: row.try_get("resources_snapshot")?,
             spec_hash: row.try_get("spec_hash")?,
@@ -1665,6 +2475,10 @@ fn workload_spec_from_row(
             mode: None,
             uid: None,
             gid: None,
+            reload: row
+                .secrets_reload
+                .clone()
+                .and_then(|reload| serde_json::from_value(reload).ok()),
         });
     let overlay_ipv6 = row
         .overlay_ipv6
@@ -1691,7 +2505,7 @@ fn workload_spec_from_row(
         manifest_hash: row.manifest_hash.clone(),
         command,
         workdir: None,
-        env_vars: None,
+        env_vars: config_vars_from_row(row),
         resources,
         network,
         mounts,
@@ -1700,6 +2514,14 @@ fn workload_spec_from_row(
     }
 }
 
+/// Config vars become the workload environment; secrets stay in the secrets file.
+fn config_vars_from_row(row: &InstancePlanRow) -> Option<HashMap<String, String>> {
+    row.config_vars
+        .clone()
+        .and_then(|vars| serde_json::from_value::<HashMap<String, String>>(vars).ok())
+        .filter(|vars| !vars.is_empty())
+}
+
 fn workload_image_from_row(row: &InstancePlanRow, arch_hint: Option<&str>) -> WorkloadImage {
     let entries = resolved_digest_entries(&row.resolved_digests);
     let resolved = select_resolved_digest(&entries, arch_hint);
@@ -1806,12 +2628,13 @@ async fn load_volume_mounts(
 
     let rows = sqlx::query_as::<_, VolumeMountRow>(
         r#"
-        SELECT env_id, process_type, volume_id, mount_path, read_only
-        FROM volume_attachments_view
-        WHERE env_id = ANY($1::TEXT[])
-          AND process_type = ANY($2::TEXT[])
-          AND NOT is_deleted
-        ORDER BY env_id ASC, process_type ASC, volume_id ASC
+        SELECT a.env_id, a.process_type, a.volume_id, a.mount_path, a.read_only, v.size_bytes
+        FROM volume_attachments_view a
+        LEFT JOIN volumes_view v ON v.volume_id = a.volume_id
+        WHERE a.env_id = ANY($1::TEXT[])
+          AND a.process_type = ANY($2::TEXT[])
+          AND NOT a.is_deleted
+        ORDER BY a.env_id ASC, a.process_type ASC, a.volume_id ASC
         "#,
     )
     .bind(env_ids)
@@ -1835,6 +2658,7 @@ async fn load_volume_mounts(
                 read_only: row.read_only,
                 filesystem: "ext4".to_string(),
                 device_hint: None,
+                size_bytes: row.size_bytes,
             });
     }
 
@@ -1882,6 +2706,7 @@ struct VolumeMountRow {
     volume_id: String,
     mount_path: String,
     read_only: bool,
+    size_bytes: Option<i64>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeMountRow {
@@ -1893,6 +2718,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeMountRow {
             volume_id: row.try_get("volume_id")?,
             mount_path: row.try_get("mount_path")?,
             read_only: row.try_get("read_only")?,
+            size_bytes: row.try_get("size_bytes")?,
         })
     }
 }
@@ -1960,6 +2786,7 @@ mod tests {
         assert_eq!(req.state, NodeState::Active);
         assert_eq!(req.available_cpu_cores, 6);
         assert_eq!(req.instance_count, 4);
+        assert!(req.ephemeral_disk_used_bytes.is_none());
     }
 
     #[test]
@@ -1973,6 +2800,7 @@ mod tests {
             public_ipv4: None,
             overlay_ipv6: Some("fd00::1".to_string()),
             labels: serde_json::json!({"region": "us-west-2"}),
+            taints: serde_json::json!([]),
             allocatable: serde_json::json!({"cpu_cores": 8}),
             mtu: Some(1500),
             resource_version: 1,
@@ -0,0 +1,908 @@
+//! Notification channel, rule, and delivery log endpoints.
+//!
+//! - `/v1/orgs/{org_id}/notifications/channels`: SMTP, Slack, and webhook
+//!   destinations
+//! - `/v1/orgs/{org_id}/notifications/channels/{channel_id}/deliveries`: the
+//!   channel's delivery log, newest first
+//! - `/v1/orgs/{org_id}/notifications/rules`: which triggers notify which
+//!   channels, with optional app/env scope and message templates
+//!
+//! Any member can read; creating, changing, and deleting channels and rules
+//! requires the admin role. Channel secrets are write-only.
+//!
+//! See: docs/specs/observability/notifications.md
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_id::{AppId, EnvId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::api::validation::{ValidJson, Validate, Validator};
+use crate::notifications::{
+    self, template, Channel, ChannelConfig, ChannelKind, Delivery, NotificationError, Rule,
+    RuleSpec, Trigger,
+};
+use crate::state::AppState;
+
+const MAX_NAME_LEN: usize = 63;
+const MAX_RULE_CHANNELS: usize = 10;
+const MAX_TEMPLATE_LEN: usize = 4096;
+
+/// Notification routes, nested under /v1/orgs/{org_id}/notifications.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/channels", get(list_channels).post(create_channel))
+        .route(
+            "/channels/{channel_id}",
+            get(get_channel).put(update_channel).delete(delete_channel),
+        )
+        .route("/channels/{channel_id}/deliveries", get(list_deliveries))
+        .route("/rules", get(list_rules).post(create_rule))
+        .route(
+            "/rules/{rule_id}",
+            get(get_rule).put(update_rule).delete(delete_rule),
+        )
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct CreateChannelRequest {
+    name: String,
+    kind: ChannelKind,
+    /// Non-secret settings for `kind` (empty for Slack).
+    #[serde(default)]
+    config: serde_json::Value,
+    /// Slack webhook URL, SMTP password, or webhook signing secret.
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+impl Validate for CreateChannelRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Channel", MAX_NAME_LEN);
+        if let Err(message) = parse_config(self.kind, &self.config) {
+            v.check(false, "invalid_notification_channel", "config", &message);
+        }
+        match &self.secret {
+            Some(secret) => {
+                if let Err(message) = notifications::validate_secret(self.kind, secret) {
+                    v.check(false, "invalid_notification_channel", "secret", &message);
+                }
+            }
+            None => v.check(
+                !self.kind.requires_secret(),
+                "invalid_notification_channel",
+                "secret",
+                "Slack channels need the incoming webhook URL as secret",
+            ),
+        }
+    }
+}
+
+/// Replaces a channel's name and config. The kind cannot change; the secret
+/// is kept unless a new one is given.
+#[derive(Debug, Deserialize)]
+struct UpdateChannelRequest {
+    name: String,
+    #[serde(default)]
+    config: serde_json::Value,
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+impl Validate for UpdateChannelRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Channel", MAX_NAME_LEN);
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelResponse {
+    id: String,
+    org_id: String,
+    name: String,
+    kind: ChannelKind,
+    config: serde_json::Value,
+    /// Whether a secret is stored (it is never returned).
+    has_secret: bool,
+    created_by: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<Channel> for ChannelResponse {
+    fn from(channel: Channel) -> Self {
+        Self {
+            id: channel.channel_id,
+            org_id: channel.org_id,
+            name: channel.name,
+            kind: channel.config.kind(),
+            config: channel.config.to_json(),
+            has_secret: channel.has_secret,
+            created_by: channel.created_by,
+            created_at: channel.created_at,
+            updated_at: channel.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListChannelsResponse {
+    items: Vec<ChannelResponse>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Body of rule create and replace.
+#[derive(Debug, Deserialize)]
+struct RuleRequest {
+    name: String,
+    trigger: Trigger,
+    channel_ids: Vec<String>,
+    #[serde(default)]
+    app_id: Option<String>,
+    #[serde(default)]
+    env_id: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    /// Omit for the trigger's default subject.
+    #[serde(default)]
+    subject_template: Option<String>,
+    /// Omit for the trigger's default body.
+    #[serde(default)]
+    body_template: Option<String>,
+}
+
+impl Validate for RuleRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.name("name", &self.name, "Rule", MAX_NAME_LEN);
+        v.check(
+            !self.channel_ids.is_empty() && self.channel_ids.len() <= MAX_RULE_CHANNELS,
+            "invalid_notification_rule",
+            "channel_ids",
+            &format!("A rule needs 1 to {MAX_RULE_CHANNELS} channels"),
+        );
+        if let Some(app_id) = &self.app_id {
+            v.check(
+                app_id.parse::<AppId>().is_ok(),
+                "invalid_notification_rule",
+                "app_id",
+                "Invalid app ID format",
+            );
+        }
+        if let Some(env_id) = &self.env_id {
+            v.check(
+                env_id.parse::<EnvId>().is_ok(),
+                "invalid_notification_rule",
+                "env_id",
+                "Invalid environment ID format",
+            );
+        }
+
+        let variables = self.trigger.variables();
+        for (field, value) in [
+            ("subject_template", &self.subject_template),
+            ("body_template", &self.body_template),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            let result = if value.len() > MAX_TEMPLATE_LEN {
+                Err(format!("Template cannot exceed {MAX_TEMPLATE_LEN} bytes"))
+            } else {
+                template::validate(value, &variables).map_err(|e| {
+                    format!(
+                        "Invalid template: {e} (available: {})",
+                        variables.join(", ")
+                    )
+                })
+            };
+            if let Err(message) = result {
+                v.check(false, "invalid_notification_template", field, &message);
+            }
+        }
+    }
+}
+
+impl RuleRequest {
+    fn into_spec(self) -> RuleSpec {
+        let mut channel_ids = Vec::with_capacity(self.channel_ids.len());
+        for channel_id in self.channel_ids {
+            if !channel_ids.contains(&channel_id) {
+                channel_ids.push(channel_id);
+            }
+        }
+        RuleSpec {
+            name: self.name,
+            trigger: self.trigger,
+            channel_ids,
+            app_id: self.app_id,
+            env_id: self.env_id,
+            enabled: self.enabled,
+            subject_template: self.subject_template,
+            body_template: self.body_template,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RuleResponse {
+    id: String,
+    org_id: String,
+    name: String,
+    trigger: Trigger,
+    channel_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env_id: Option<String>,
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject_template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body_template: Option<String>,
+    created_by: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<Rule> for RuleResponse {
+    fn from(rule: Rule) -> Self {
+        Self {
+            id: rule.rule_id,
+            org_id: rule.org_id,
+            name: rule.spec.name,
+            trigger: rule.spec.trigger,
+            channel_ids: rule.spec.channel_ids,
+            app_id: rule.spec.app_id,
+            env_id: rule.spec.env_id,
+            enabled: rule.spec.enabled,
+            subject_template: rule.spec.subject_template,
+            body_template: rule.spec.body_template,
+            created_by: rule.created_by,
+            created_at: rule.created_at,
+            updated_at: rule.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListRulesResponse {
+    items: Vec<RuleResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListDeliveriesQuery {
+    /// `pending`, `delivered`, or `failed`.
+    state: Option<String>,
+    limit: Option<i64>,
+    /// Cursor (exclusive): a delivery ID from the previous page.
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeliveryResponse {
+    id: String,
+    channel_id: String,
+    rule_id: String,
+    event_id: i64,
+    trigger: String,
+    state: String,
+    attempts: i32,
+    /// When the next attempt is due (pending deliveries only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<String>,
+    subject: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<Delivery> for DeliveryResponse {
+    fn from(delivery: Delivery) -> Self {
+        Self {
+            next_attempt_at: (delivery.state == "pending").then_some(delivery.next_attempt_at),
+            id: delivery.delivery_id,
+            channel_id: delivery.channel_id,
+            rule_id: delivery.rule_id,
+            event_id: delivery.event_id,
+            trigger: delivery.trigger,
+            state: delivery.state,
+            attempts: delivery.attempts,
+            last_error: delivery.last_error,
+            subject: delivery.subject,
+            body: delivery.body,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListDeliveriesResponse {
+    items: Vec<DeliveryResponse>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeleteResponse {
+    ok: bool,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Parse and check a channel's config for `kind`.
+fn parse_config(kind: ChannelKind, config: &serde_json::Value) -> Result<ChannelConfig, String> {
+    let config = ChannelConfig::parse(kind, config)?;
+    config.validate()?;
+    Ok(config)
+}
+
+fn parse_org_id(org_id: &str, request_id: &str) -> Result<OrgId, ApiError> {
+    org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.to_string())
+    })
+}
+
+fn notification_error(error: NotificationError, request_id: &str) -> ApiError {
+    tracing::error!(error = %error, request_id = %request_id, "Notification request failed");
+    ApiError::internal("internal_error", "Notification request failed")
+        .with_request_id(request_id.to_string())
+}
+
+fn channel_error(error: NotificationError, request_id: &str) -> ApiError {
+    match error {
+        NotificationError::NameTaken => ApiError::conflict(
+            "notification_channel_name_exists",
+            "A notification channel with this name already exists",
+        )
+        .with_request_id(request_id.to_string()),
+        other => notification_error(other, request_id),
+    }
+}
+
+fn rule_error(error: NotificationError, request_id: &str) -> ApiError {
+    match error {
+        NotificationError::NameTaken => ApiError::conflict(
+            "notification_rule_name_exists",
+            "A notification rule with this name already exists",
+        )
+        .with_request_id(request_id.to_string()),
+        other => notification_error(other, request_id),
+    }
+}
+
+fn channel_not_found(request_id: &str) -> ApiError {
+    ApiError::not_found(
+        "notification_channel_not_found",
+        "Notification channel not found",
+    )
+    .with_request_id(request_id.to_string())
+}
+
+fn rule_not_found(request_id: &str) -> ApiError {
+    ApiError::not_found("notification_rule_not_found", "Notification rule not found")
+        .with_request_id(request_id.to_string())
+}
+
+/// Check that a rule's channels and scope belong to the org.
+async fn check_rule_refs(
+    state: &AppState,
+    org_id: &OrgId,
+    spec: &RuleSpec,
+    request_id: &str,
+) -> Result<(), ApiError> {
+    let pool = state.db().pool();
+    let org = org_id.to_string();
+    let internal = |e: sqlx::Error| notification_error(e.into(), request_id);
+    let invalid = |message: String| {
+        ApiError::bad_request("invalid_notification_rule", message)
+            .with_request_id(request_id.to_string())
+    };
+
+    let existing = notifications::existing_channel_ids(pool, &org, &spec.channel_ids)
+        .await
+        .map_err(internal)?;
+    let unknown: Vec<&str> = spec
+        .channel_ids
+        .iter()
+        .filter(|id| !existing.contains(*id))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(invalid(format!(
+            "Unknown notification channels: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    if let Some(env_id) = &spec.env_id {
+        let env_app: Option<String> = sqlx::query_scalar(
+            "SELECT app_id FROM envs_view WHERE env_id = $1 AND org_id = $2 AND NOT is_deleted",
+        )
+        .bind(env_id)
+        .bind(&org)
+        .fetch_optional(pool)
+        .await
+        .map_err(internal)?;
+        match env_app {
+            None => {
+                return Err(invalid(format!(
+                    "Environment {env_id} not found in this org"
+                )))
+            }
+            Some(env_app) if spec.app_id.as_ref().is_some_and(|app| *app != env_app) => {
+                return Err(invalid(format!(
+                    "Environment {env_id} does not belong to app {}",
+                    spec.app_id.as_deref().unwrap_or_default()
+                )))
+            }
+            Some(_) => {}
+        }
+    } else if let Some(app_id) = &spec.app_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM apps_view WHERE app_id = $1 AND org_id = $2 AND NOT is_deleted)",
+        )
+        .bind(app_id)
+        .bind(&org)
+        .fetch_one(pool)
+        .await
+        .map_err(internal)?;
+        if !exists {
+            return Err(invalid(format!("App {app_id} not found in this org")));
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Channel Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/notifications/channels
+async fn list_channels(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let channels = notifications::list_channels(state.db().pool(), &org_id.to_string())
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?;
+
+    Ok(Json(ListChannelsResponse {
+        items: channels.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /v1/orgs/{org_id}/notifications/channels
+async fn create_channel(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    ValidJson(req): ValidJson<CreateChannelRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let config = parse_config(req.kind, &req.config).map_err(|message| {
+        ApiError::bad_request("invalid_notification_channel", message)
+            .with_request_id(request_id.clone())
+    })?;
+    let channel = notifications::create_channel(
+        state.db().pool(),
+        &org_id.to_string(),
+        &req.name,
+        &config,
+        req.secret.as_deref(),
+        &ctx.actor_id,
+    )
+    .await
+    .map_err(|e| channel_error(e, &request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        channel_id = %channel.channel_id,
+        kind = channel.config.kind().as_str(),
+        actor_id = %ctx.actor_id,
+        "Notification channel created"
+    );
+
+    Ok((StatusCode::CREATED, Json(ChannelResponse::from(channel))))
+}
+
+/// GET /v1/orgs/{org_id}/notifications/channels/{channel_id}
+async fn get_channel(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, channel_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let channel = notifications::get_channel(state.db().pool(), &org_id.to_string(), &channel_id)
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?
+        .ok_or_else(|| channel_not_found(&request_id))?;
+
+    Ok(Json(ChannelResponse::from(channel)))
+}
+
+/// PUT /v1/orgs/{org_id}/notifications/channels/{channel_id}
+async fn update_channel(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, channel_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<UpdateChannelRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+    let pool = state.db().pool();
+
+    let current = notifications::get_channel(pool, &org_id.to_string(), &channel_id)
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?
+        .ok_or_else(|| channel_not_found(&request_id))?;
+    let kind = current.config.kind();
+    let invalid = |message: String| {
+        ApiError::bad_request("invalid_notification_channel", message)
+            .with_request_id(request_id.clone())
+    };
+    let config = parse_config(kind, &req.config).map_err(invalid)?;
+    if let Some(secret) = &req.secret {
+        notifications::validate_secret(kind, secret).map_err(invalid)?;
+    }
+
+    let channel = notifications::update_channel(
+        pool,
+        &org_id.to_string(),
+        &channel_id,
+        &req.name,
+        &config,
+        req.secret.as_deref(),
+    )
+    .await
+    .map_err(|e| channel_error(e, &request_id))?
+    .ok_or_else(|| channel_not_found(&request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        channel_id = %channel_id,
+        secret_replaced = req.secret.is_some(),
+        actor_id = %ctx.actor_id,
+        "Notification channel updated"
+    );
+
+    Ok(Json(ChannelResponse::from(channel)))
+}
+
+/// Delete a channel, its secret, and its delivery log. Rules stop
+/// notifying it.
+///
+/// DELETE /v1/orgs/{org_id}/notifications/channels/{channel_id}
+async fn delete_channel(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, channel_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let deleted =
+        notifications::delete_channel(state.db().pool(), &org_id.to_string(), &channel_id)
+            .await
+            .map_err(|e| notification_error(e.into(), &request_id))?;
+    if !deleted {
+        return Err(channel_not_found(&request_id));
+    }
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        channel_id = %channel_id,
+        actor_id = %ctx.actor_id,
+        "Notification channel deleted"
+    );
+
+    Ok(Json(DeleteResponse { ok: true }))
+}
+
+/// GET /v1/orgs/{org_id}/notifications/channels/{channel_id}/deliveries
+async fn list_deliveries(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, channel_id)): Path<(String, String)>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+    let pool = state.db().pool();
+
+    if let Some(delivery_state) = &query.state {
+        if !["pending", "delivered", "failed"].contains(&delivery_state.as_str()) {
+            return Err(ApiError::bad_request(
+                "invalid_request",
+                "state must be pending, delivered, or failed",
+            )
+            .with_request_id(request_id));
+        }
+    }
+
+    notifications::get_channel(pool, &org_id.to_string(), &channel_id)
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?
+        .ok_or_else(|| channel_not_found(&request_id))?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let deliveries = notifications::list_deliveries(
+        pool,
+        &channel_id,
+        query.state.as_deref(),
+        query.cursor.as_deref(),
+        limit,
+    )
+    .await
+    .map_err(|e| notification_error(e.into(), &request_id))?;
+
+    let items: Vec<DeliveryResponse> = deliveries.into_iter().map(Into::into).collect();
+    let next_cursor = if items.len() == limit as usize {
+        items.last().map(|d| d.id.clone())
+    } else {
+        None
+    };
+
+    Ok(Json(ListDeliveriesResponse { items, next_cursor }))
+}
+
+// =============================================================================
+// Rule Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/notifications/rules
+async fn list_rules(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let rules = notifications::list_rules(state.db().pool(), &org_id.to_string())
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?;
+
+    Ok(Json(ListRulesResponse {
+        items: rules.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /v1/orgs/{org_id}/notifications/rules
+async fn create_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    ValidJson(req): ValidJson<RuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let spec = req.into_spec();
+    check_rule_refs(&state, &org_id, &spec, &request_id).await?;
+    let rule =
+        notifications::create_rule(state.db().pool(), &org_id.to_string(), &spec, &ctx.actor_id)
+            .await
+            .map_err(|e| rule_error(e, &request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        rule_id = %rule.rule_id,
+        trigger = rule.spec.trigger.as_str(),
+        actor_id = %ctx.actor_id,
+        "Notification rule created"
+    );
+
+    Ok((StatusCode::CREATED, Json(RuleResponse::from(rule))))
+}
+
+/// GET /v1/orgs/{org_id}/notifications/rules/{rule_id}
+async fn get_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, rule_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let rule = notifications::get_rule(state.db().pool(), &org_id.to_string(), &rule_id)
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?
+        .ok_or_else(|| rule_not_found(&request_id))?;
+
+    Ok(Json(RuleResponse::from(rule)))
+}
+
+/// PUT /v1/orgs/{org_id}/notifications/rules/{rule_id}
+async fn update_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, rule_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<RuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let spec = req.into_spec();
+    check_rule_refs(&state, &org_id, &spec, &request_id).await?;
+    let rule = notifications::update_rule(state.db().pool(), &org_id.to_string(), &rule_id, &spec)
+        .await
+        .map_err(|e| rule_error(e, &request_id))?
+        .ok_or_else(|| rule_not_found(&request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        rule_id = %rule_id,
+        enabled = rule.spec.enabled,
+        actor_id = %ctx.actor_id,
+        "Notification rule updated"
+    );
+
+    Ok(Json(RuleResponse::from(rule)))
+}
+
+/// Delete a rule. Its undelivered notifications are dropped.
+///
+/// DELETE /v1/orgs/{org_id}/notifications/rules/{rule_id}
+async fn delete_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, rule_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let deleted = notifications::delete_rule(state.db().pool(), &org_id.to_string(), &rule_id)
+        .await
+        .map_err(|e| notification_error(e.into(), &request_id))?;
+    if !deleted {
+        return Err(rule_not_found(&request_id));
+    }
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        rule_id = %rule_id,
+        actor_id = %ctx.actor_id,
+        "Notification rule deleted"
+    );
+
+    Ok(Json(DeleteResponse { ok: true }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::validation::validate;
+
+    fn rule_request(body: serde_json::Value) -> RuleRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_rule_request_validation() {
+        let valid = rule_request(json!({
+            "name": "oncall",
+            "trigger": "cert_expiring",
+            "channel_ids": ["nch_1", "nch_1"],
+            "subject_template": "{hostname}: {days_remaining} days left",
+        }));
+        assert!(validate(&valid).is_ok());
+        let spec = valid.into_spec();
+        assert!(spec.enabled);
+        assert_eq!(spec.channel_ids, vec!["nch_1"]);
+
+        let err = validate(&rule_request(json!({
+            "name": "oncall",
+            "trigger": "cert_expiring",
+            "channel_ids": ["nch_1"],
+            "body_template": "Deploy {deploy_id} failed",
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_notification_template");
+
+        let err = validate(&rule_request(json!({
+            "name": "oncall",
+            "trigger": "deploy_failed",
+            "channel_ids": [],
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_notification_rule");
+    }
+
+    #[test]
+    fn test_create_channel_validation() {
+        let request = |body: serde_json::Value| -> CreateChannelRequest {
+            serde_json::from_value(body).unwrap()
+        };
+        assert!(validate(&request(json!({
+            "name": "ops-slack",
+            "kind": "slack",
+            "secret": "https://hooks.slack.com/services/T/B/x",
+        })))
+        .is_ok());
+
+        let err = validate(&request(json!({"name": "ops-slack", "kind": "slack"}))).unwrap_err();
+        assert_eq!(err.problem.code, "invalid_notification_channel");
+
+        let err = validate(&request(json!({
+            "name": "ops-mail",
+            "kind": "smtp",
+            "config": {"host": "smtp.example.com", "from": "alerts@example.com", "to": []},
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_notification_channel");
+    }
+
+    #[test]
+    fn test_channel_response_omits_secret() {
+        let channel = Channel {
+            channel_id: "nch_1".to_string(),
+            org_id: "org_1".to_string(),
+            name: "ops-slack".to_string(),
+            config: ChannelConfig::Slack,
+            has_secret: true,
+            created_by: "ops@example.com".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let json = serde_json::to_value(ChannelResponse::from(channel)).unwrap();
+        assert_eq!(json["kind"], "slack");
+        assert_eq!(json["has_secret"], true);
+        assert_eq!(json["config"], json!({}));
+        assert!(json.get("secret").is_none());
+    }
+}
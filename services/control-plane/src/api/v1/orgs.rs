@@ -10,14 +10,16 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
+use plfm_api_types::orgs::{CreateOrgRequest, ListOrgsResponse, OrgResponse, UpdateOrgRequest};
 use plfm_events::{event_types, AggregateType, MemberRole, OrgMemberAddedPayload};
 use plfm_id::{MemberId, OrgId};
-use serde::{Deserialize, Serialize};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
+use crate::api::validation::ValidJson;
 use crate::db::AppendEvent;
 use crate::state::AppState;
 
@@ -30,53 +32,6 @@ pub fn routes() -> Router<AppState> {
         .route("/{org_id}", get(get_org))
 }
 
-// =============================================================================
-// Request/Response Types
-// =============================================================================
-
-/// Request to create a new organization.
-#[derive(Debug, Deserialize, Serialize)]
-pub struct CreateOrgRequest {
-    /// Organization name.
-    pub name: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateOrgRequest {
-    #[serde(default)]
-    pub name: Option<String>,
-    pub expected_version: i32,
-}
-
-/// Response for a single organization.
-#[derive(Debug, Serialize)]
-pub struct OrgResponse {
-    /// Organization ID.
-    pub id: String,
-
-    /// Organization name.
-    pub name: String,
-
-    /// Resource version for optimistic concurrency.
-    pub resource_version: i32,
-
-    /// When the org was created.
-    pub created_at: DateTime<Utc>,
-
-    /// When the org was last updated.
-    pub updated_at: DateTime<Utc>,
-}
-
-/// Response for listing organizations.
-#[derive(Debug, Serialize)]
-pub struct ListOrgsResponse {
-    /// List of organizations.
-    pub items: Vec<OrgResponse>,
-
-    /// Total count (for pagination).
-    pub total: i64,
-}
-
 // =============================================================================
 // Handlers
 // =============================================================================
@@ -87,7 +42,7 @@ pub struct ListOrgsResponse {
 async fn create_org(
     State(state): State<AppState>,
     ctx: RequestContext,
-    Json(req): Json<CreateOrgRequest>,
+    ValidJson(req): ValidJson<CreateOrgRequest>,
 ) -> Result<Response, ApiError> {
     authz::require_authenticated(&ctx)?;
 
@@ -104,22 +59,6 @@ async fn create_org(
     };
     let endpoint_name = "orgs.create";
 
-    // Validate name
-    if req.name.is_empty() {
-        return Err(
-            ApiError::bad_request("invalid_name", "Organization name cannot be empty")
-                .with_request_id(request_id),
-        );
-    }
-
-    if req.name.len() > 100 {
-        return Err(ApiError::bad_request(
-            "invalid_name",
-            "Organization name cannot exceed 100 characters",
-        )
-        .with_request_id(request_id));
-    }
-
     let request_hash = idempotency_key
         .as_deref()
         .map(|key| {
@@ -224,35 +163,9 @@ async fn create_org(
         }
     };
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "orgs",
-            org_event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "orgs", org_event_id.value()).await?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "members",
-            member_event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "members", member_event_id.value()).await?;
 
     let row = sqlx::query_as::<_, OrgRow>(
         r#"
@@ -312,7 +225,7 @@ async fn update_org(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path(org_id): Path<String>,
-    Json(req): Json<UpdateOrgRequest>,
+    ValidJson(req): ValidJson<UpdateOrgRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -343,22 +256,6 @@ async fn update_org(
         );
     }
 
-    if let Some(name) = req.name.as_ref() {
-        if name.is_empty() {
-            return Err(
-                ApiError::bad_request("invalid_name", "Organization name cannot be empty")
-                    .with_request_id(request_id.clone()),
-            );
-        }
-        if name.len() > 100 {
-            return Err(ApiError::bad_request(
-                "invalid_name",
-                "Organization name cannot exceed 100 characters",
-            )
-            .with_request_id(request_id.clone()));
-        }
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -449,20 +346,7 @@ async fn update_org(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "orgs",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "orgs", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, OrgRow>(
         r#"
@@ -656,29 +540,3 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for OrgRow {
         })
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_create_org_request_deserialization() {
-        let json = r#"{"name": "Acme Corp"}"#;
-        let req: CreateOrgRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(req.name, "Acme Corp");
-    }
-
-    #[test]
-    fn test_org_response_serialization() {
-        let response = OrgResponse {
-            id: "org_123".to_string(),
-            name: "Test Org".to_string(),
-            resource_version: 1,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
-
-        let json = serde_json::to_string(&response).unwrap();
-        assert!(json.contains("\"id\":\"org_123\""));
-    }
-}
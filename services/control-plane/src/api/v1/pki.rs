@@ -0,0 +1,667 @@
+//! Platform CA endpoints.
+//!
+//! - `/v1/pki/*`: trust bundle, CRLs, and identity token JWKS (public)
+//! - `/v1/nodes/{node_id}/certificate` and
+//!   `/v1/nodes/{node_id}/instances/{instance_id}/certificate`: node agents
+//!   obtain and renew certificates for themselves and their instances
+//! - `/v1/nodes/{node_id}/instances/{instance_id}/identity-token`: node
+//!   agents obtain and renew identity tokens for their instances
+//! - `/v1/_admin/pki/*`: operator issuance (ingress), listing, revocation,
+//!   and CA and token key rotation
+//!
+//! See: docs/security/08-platform-pki.md
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use plfm_id::{InstanceId, NodeId};
+use plfm_pki::{Identity, IdentityKind, IssuedCertificate, PkiError};
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::identity_tokens;
+use crate::pki::{self, CaError, CertificateRecord};
+use crate::state::AppState;
+
+use super::node_enrollment::verify_node_request;
+
+/// Public trust material: /v1/pki
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/trust-bundle", get(get_trust_bundle))
+        .route("/crl", get(get_crl))
+        .route("/jwks", get(get_jwks))
+}
+
+/// Node agent issuance, merged into /v1/nodes.
+pub fn node_routes() -> Router<AppState> {
+    Router::new()
+        .route("/{node_id}/certificate", post(issue_node_certificate))
+        .route(
+            "/{node_id}/instances/{instance_id}/certificate",
+            post(issue_instance_certificate),
+        )
+        .route(
+            "/{node_id}/instances/{instance_id}/identity-token",
+            post(issue_instance_identity_token),
+        )
+}
+
+/// Operator endpoints, merged into /v1/_admin.
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/pki/certificates", post(issue_certificate))
+        .route("/pki/certificates", get(list_certificates))
+        .route(
+            "/pki/certificates/{serial}/revoke",
+            post(revoke_certificate),
+        )
+        .route("/pki/rotate", post(rotate_ca))
+        .route("/pki/token-keys/rotate", post(rotate_token_key))
+}
+
+fn ca_error(error: CaError, request_id: &str) -> ApiError {
+    let api_error = match error {
+        CaError::Pki(PkiError::InvalidCsr(msg)) => {
+            ApiError::bad_request("invalid_csr", format!("Invalid CSR: {msg}"))
+        }
+        CaError::Pki(e @ PkiError::InvalidTtl { .. }) => {
+            ApiError::bad_request("invalid_certificate_ttl", e.to_string())
+        }
+        CaError::Pki(PkiError::InvalidIdentity(msg)) => {
+            ApiError::bad_request("invalid_certificate_identity", msg)
+        }
+        CaError::Database(e) => {
+            tracing::error!(error = %e, request_id = %request_id, "PKI query failed");
+            ApiError::internal("internal_error", "Certificate authority request failed")
+        }
+        other => {
+            tracing::error!(error = %other, request_id = %request_id, "Certificate authority unavailable");
+            ApiError::internal("ca_unavailable", "Certificate authority is unavailable")
+        }
+    };
+    api_error.with_request_id(request_id.to_string())
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+/// Certificate request from a node agent.
+#[derive(Debug, Deserialize)]
+pub struct CertificateRequest {
+    /// PEM-encoded PKCS#10 CSR. Only its public key is used.
+    pub csr_pem: String,
+    /// Requested lifetime; defaults per identity kind.
+    #[serde(default)]
+    pub ttl_seconds: Option<i64>,
+}
+
+/// Operator certificate request for any identity kind.
+#[derive(Debug, Deserialize)]
+pub struct IssueCertificateRequest {
+    /// `node`, `ingress`, or `instance`.
+    pub kind: String,
+    /// Subject ID (e.g. the ingress instance name).
+    pub id: String,
+    #[serde(default)]
+    pub common_name: Option<String>,
+    #[serde(default)]
+    pub dns_names: Vec<String>,
+    pub csr_pem: String,
+    #[serde(default)]
+    pub ttl_seconds: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificateResponse {
+    pub serial: String,
+    pub certificate_pem: String,
+    /// Certificate followed by the issuing CA.
+    pub chain_pem: String,
+    /// All CAs to trust (see /v1/pki/trust-bundle).
+    pub trust_bundle_pem: String,
+    pub fingerprint_sha256: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    /// Renew after this point (two thirds of the lifetime).
+    pub renew_after: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrustAnchorResponse {
+    pub ca_id: String,
+    pub certificate_pem: String,
+    pub not_after: DateTime<Utc>,
+    /// Whether this CA signs new certificates.
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrustBundleResponse {
+    pub trust_domain: String,
+    /// Concatenated CA certificates.
+    pub bundle_pem: String,
+    pub authorities: Vec<TrustAnchorResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListCertificatesQuery {
+    pub kind: Option<String>,
+    pub subject_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificateRecordResponse {
+    pub serial: String,
+    pub ca_id: String,
+    pub kind: String,
+    pub subject_id: String,
+    pub common_name: String,
+    pub fingerprint_sha256: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub issued_by: String,
+    pub issued_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revocation_reason: Option<String>,
+}
+
+impl From<CertificateRecord> for CertificateRecordResponse {
+    fn from(record: CertificateRecord) -> Self {
+        Self {
+            serial: record.serial,
+            ca_id: record.ca_id,
+            kind: record.kind,
+            subject_id: record.subject_id,
+            common_name: record.common_name,
+            fingerprint_sha256: record.fingerprint_sha256,
+            not_before: record.not_before,
+            not_after: record.not_after,
+            issued_by: record.issued_by,
+            issued_at: record.issued_at,
+            revoked_at: record.revoked_at,
+            revocation_reason: record.revocation_reason,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListCertificatesResponse {
+    pub items: Vec<CertificateRecordResponse>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RevokeCertificateRequest {
+    /// `key_compromise` (default) or `superseded`.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateCaResponse {
+    pub ca_id: String,
+}
+
+/// Identity token signing keys (RFC 7517 JWK Set).
+#[derive(Debug, Serialize)]
+pub struct JwksResponse {
+    pub keys: Vec<plfm_pki::Jwk>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdentityTokenResponse {
+    pub token: String,
+    pub token_type: &'static str,
+    pub expires_at: DateTime<Utc>,
+    /// Renew after this point (two thirds of the lifetime).
+    pub renew_after: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateTokenKeyResponse {
+    pub kid: String,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/pki/trust-bundle
+async fn get_trust_bundle(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    let anchors = pki::trust_bundle(state.db().pool())
+        .await
+        .map_err(|e| ca_error(e, &ctx.request_id))?;
+
+    Ok(Json(TrustBundleResponse {
+        trust_domain: pki::trust_domain(),
+        bundle_pem: anchors.iter().map(|a| a.cert_pem.as_str()).collect(),
+        authorities: anchors
+            .into_iter()
+            .map(|a| TrustAnchorResponse {
+                ca_id: a.ca_id,
+                certificate_pem: a.cert_pem,
+                not_after: a.not_after,
+                active: a.active,
+            })
+            .collect(),
+    }))
+}
+
+/// GET /v1/pki/crl
+///
+/// PEM CRLs, one per trusted CA, valid for [`pki::CRL_VALIDITY`].
+async fn get_crl(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    let pem = pki::crl_bundle(state.db().pool())
+        .await
+        .map_err(|e| ca_error(e, &ctx.request_id))?;
+    Ok(([(CONTENT_TYPE, "application/x-pem-file")], pem))
+}
+
+/// GET /v1/pki/jwks
+///
+/// Public keys for verifying instance identity tokens.
+async fn get_jwks(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    let keys = identity_tokens::jwks(state.db().pool())
+        .await
+        .map_err(|e| ca_error(e, &ctx.request_id))?;
+    Ok(Json(JwksResponse { keys }))
+}
+
+fn requested_ttl(ttl_seconds: Option<i64>) -> Option<Duration> {
+    ttl_seconds.map(Duration::seconds)
+}
+
+async fn certificate_response(
+    state: &AppState,
+    issued: IssuedCertificate,
+    request_id: &str,
+) -> Result<CertificateResponse, ApiError> {
+    let anchors = pki::trust_bundle(state.db().pool())
+        .await
+        .map_err(|e| ca_error(e, request_id))?;
+    let lifetime = issued.not_after - issued.not_before;
+    Ok(CertificateResponse {
+        serial: issued.serial,
+        certificate_pem: issued.cert_pem,
+        chain_pem: issued.chain_pem,
+        trust_bundle_pem: anchors.iter().map(|a| a.cert_pem.as_str()).collect(),
+        fingerprint_sha256: issued.fingerprint_sha256,
+        not_before: issued.not_before,
+        not_after: issued.not_after,
+        renew_after: issued.not_before + lifetime * 2 / 3,
+    })
+}
+
+/// Issue or renew the node agent's own certificate.
+///
+/// POST /v1/nodes/{node_id}/certificate
+async fn issue_node_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path(node_id): Path<String>,
+    Json(req): Json<CertificateRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id;
+
+    let _node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    let subject = sqlx::query_scalar::<_, String>(
+        "SELECT agent_mtls_subject FROM nodes_view WHERE node_id = $1",
+    )
+    .bind(&node_id)
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| ca_error(e.into(), &request_id))?;
+
+    let identity = Identity::new(IdentityKind::Node, node_id.clone())
+        .with_common_name(pki::node_common_name(&subject, &node_id));
+    let issued = pki::issue(
+        state.db().pool(),
+        &identity,
+        &req.csr_pem,
+        requested_ttl(req.ttl_seconds),
+        &node_id,
+    )
+    .await
+    .map_err(|e| ca_error(e, &request_id))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(certificate_response(&state, issued, &request_id).await?),
+    ))
+}
+
+/// Issue or renew the identity certificate of an instance on this node.
+///
+/// POST /v1/nodes/{node_id}/instances/{instance_id}/certificate
+async fn issue_instance_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path((node_id, instance_id)): Path<(String, String)>,
+    Json(req): Json<CertificateRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id;
+
+    let _node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let _instance_id_typed: InstanceId = instance_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_instance_id", "Invalid instance ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    // Nodes may only obtain identities for instances placed on them.
+    let placed = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM instances_desired_view
+            WHERE instance_id = $1 AND node_id = $2 AND desired_state <> 'stopped'
+        )
+        "#,
+    )
+    .bind(&instance_id)
+    .bind(&node_id)
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| ca_error(e.into(), &request_id))?;
+
+    if !placed {
+        return Err(ApiError::not_found(
+            "instance_not_found",
+            format!("Instance {instance_id} is not placed on node {node_id}"),
+        )
+        .with_request_id(request_id));
+    }
+
+    let identity = Identity::new(IdentityKind::Instance, instance_id.clone());
+    let issued = pki::issue(
+        state.db().pool(),
+        &identity,
+        &req.csr_pem,
+        requested_ttl(req.ttl_seconds),
+        &node_id,
+    )
+    .await
+    .map_err(|e| ca_error(e, &request_id))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(certificate_response(&state, issued, &request_id).await?),
+    ))
+}
+
+/// Issue or renew the identity token of an instance on this node.
+///
+/// POST /v1/nodes/{node_id}/instances/{instance_id}/identity-token
+async fn issue_instance_identity_token(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Path((node_id, instance_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id;
+
+    let _node_id_typed: NodeId = node_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_node_id", "Invalid node ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let _instance_id_typed: InstanceId = instance_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_instance_id", "Invalid instance ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    verify_node_request(&state, &headers, &node_id, &request_id).await?;
+
+    // Nodes may only obtain tokens for instances placed on them.
+    let Some(issued) =
+        identity_tokens::issue_for_instance(state.db().pool(), &instance_id, &node_id)
+            .await
+            .map_err(|e| ca_error(e, &request_id))?
+    else {
+        return Err(ApiError::not_found(
+            "instance_not_found",
+            format!("Instance {instance_id} is not placed on node {node_id}"),
+        )
+        .with_request_id(request_id));
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        Json(IdentityTokenResponse {
+            token: issued.token,
+            token_type: "Bearer",
+            expires_at: issued.expires_at,
+            renew_after: issued.renew_after,
+        }),
+    ))
+}
+
+/// Issue a certificate for any identity (typically ingress).
+///
+/// POST /v1/_admin/pki/certificates
+async fn issue_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Json(req): Json<IssueCertificateRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let kind = IdentityKind::parse(&req.kind).ok_or_else(|| {
+        ApiError::bad_request(
+            "invalid_identity_kind",
+            "kind must be one of: node, ingress, instance",
+        )
+        .with_request_id(request_id.clone())
+    })?;
+
+    let mut identity = Identity::new(kind, req.id).with_dns_names(req.dns_names);
+    if let Some(common_name) = req.common_name {
+        identity = identity.with_common_name(common_name);
+    }
+    let issued = pki::issue(
+        state.db().pool(),
+        &identity,
+        &req.csr_pem,
+        requested_ttl(req.ttl_seconds),
+        &ctx.actor_id,
+    )
+    .await
+    .map_err(|e| ca_error(e, &request_id))?;
+
+    tracing::info!(
+        serial = %issued.serial,
+        kind = %kind,
+        subject_id = %identity.id,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Operator issued certificate"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(certificate_response(&state, issued, &request_id).await?),
+    ))
+}
+
+/// GET /v1/_admin/pki/certificates
+async fn list_certificates(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<ListCertificatesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let kind = match query.kind.as_deref() {
+        Some(kind) => Some(IdentityKind::parse(kind).ok_or_else(|| {
+            ApiError::bad_request(
+                "invalid_identity_kind",
+                "kind must be one of: node, ingress, instance",
+            )
+            .with_request_id(request_id.clone())
+        })?),
+        None => None,
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let records =
+        pki::list_certificates(state.db().pool(), kind, query.subject_id.as_deref(), limit)
+            .await
+            .map_err(|e| ca_error(e, &request_id))?;
+
+    Ok(Json(ListCertificatesResponse {
+        items: records.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /v1/_admin/pki/certificates/{serial}/revoke
+async fn revoke_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(serial): Path<String>,
+    body: Option<Json<RevokeCertificateRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let key_compromise = match body.and_then(|Json(req)| req.reason).as_deref() {
+        None | Some("key_compromise") => true,
+        Some("superseded") => false,
+        Some(_) => {
+            return Err(ApiError::bad_request(
+                "invalid_request",
+                "reason must be one of: key_compromise, superseded",
+            )
+            .with_request_id(request_id))
+        }
+    };
+
+    let revoked = pki::revoke(state.db().pool(), &serial, key_compromise)
+        .await
+        .map_err(|e| ca_error(e, &request_id))?;
+    if !revoked {
+        return Err(ApiError::not_found(
+            "certificate_not_found",
+            format!("No unexpired, unrevoked certificate with serial {serial}"),
+        )
+        .with_request_id(request_id));
+    }
+
+    tracing::info!(
+        serial = %serial,
+        key_compromise,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Certificate revoked"
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Retire the active CA and start signing with a new one.
+///
+/// POST /v1/_admin/pki/rotate
+async fn rotate_ca(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let ca_id = pki::rotate(state.db().pool())
+        .await
+        .map_err(|e| ca_error(e, &request_id))?;
+
+    tracing::info!(
+        ca_id = %ca_id,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Platform CA rotated"
+    );
+    Ok(Json(RotateCaResponse { ca_id }))
+}
+
+/// Retire the active identity token signing key and start signing with a
+/// new one.
+///
+/// POST /v1/_admin/pki/token-keys/rotate
+async fn rotate_token_key(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    let kid = identity_tokens::rotate(state.db().pool())
+        .await
+        .map_err(|e| ca_error(e, &request_id))?;
+
+    tracing::info!(
+        kid = %kid,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Identity token signing key rotated"
+    );
+    Ok(Json(RotateTokenKeyResponse { kid }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ca_error_mapping() {
+        let err = ca_error(
+            CaError::Pki(PkiError::InvalidCsr("bad".to_string())),
+            "req_1",
+        );
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert_eq!(err.problem.code, "invalid_csr");
+
+        let err = ca_error(
+            CaError::Pki(PkiError::InvalidTtl {
+                requested_secs: 1,
+                max_secs: 60,
+            }),
+            "req_1",
+        );
+        assert_eq!(err.problem.code, "invalid_certificate_ttl");
+
+        let err = ca_error(
+            CaError::Pki(PkiError::InvalidPem("corrupt".to_string())),
+            "req_1",
+        );
+        assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.problem.code, "ca_unavailable");
+    }
+}
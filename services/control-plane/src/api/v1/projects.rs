@@ -10,14 +10,19 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
+use plfm_api_types::projects::{
+    CreateProjectRequest, ListProjectsResponse, ProjectResponse, UpdateProjectRequest,
+};
 use plfm_events::AggregateType;
 use plfm_id::{OrgId, ProjectId};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
+use crate::api::validation::ValidJson;
 use crate::db::AppendEvent;
 use crate::state::AppState;
 
@@ -36,52 +41,6 @@ pub fn routes() -> Router<AppState> {
 // Request/Response Types
 // =============================================================================
 
-/// Request to create a new project.
-#[derive(Debug, Deserialize, Serialize)]
-pub struct CreateProjectRequest {
-    /// Project name (unique within org).
-    pub name: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateProjectRequest {
-    #[serde(default)]
-    pub name: Option<String>,
-    pub expected_version: i32,
-}
-
-/// Response for a single project.
-#[derive(Debug, Serialize)]
-pub struct ProjectResponse {
-    /// Project ID.
-    pub id: String,
-
-    /// Organization ID.
-    pub org_id: String,
-
-    /// Project name.
-    pub name: String,
-
-    /// Resource version for optimistic concurrency.
-    pub resource_version: i32,
-
-    /// When the project was created.
-    pub created_at: DateTime<Utc>,
-
-    /// When the project was last updated.
-    pub updated_at: DateTime<Utc>,
-}
-
-/// Response for listing projects.
-#[derive(Debug, Serialize)]
-pub struct ListProjectsResponse {
-    /// List of projects.
-    pub items: Vec<ProjectResponse>,
-
-    /// Next cursor (or null when there are no more items).
-    pub next_cursor: Option<String>,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct ListProjectsQuery {
     pub limit: Option<i64>,
@@ -99,7 +58,7 @@ async fn create_project(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path(org_id): Path<String>,
-    Json(req): Json<CreateProjectRequest>,
+    ValidJson(req): ValidJson<CreateProjectRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -116,22 +75,6 @@ async fn create_project(
     let role = authz::require_org_member(&state, &org_id, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
 
-    // Validate name
-    if req.name.is_empty() {
-        return Err(
-            ApiError::bad_request("invalid_name", "Project name cannot be empty")
-                .with_request_id(request_id),
-        );
-    }
-
-    if req.name.len() > 100 {
-        return Err(ApiError::bad_request(
-            "invalid_name",
-            "Project name cannot exceed 100 characters",
-        )
-        .with_request_id(request_id));
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -234,20 +177,7 @@ async fn create_project(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "projects",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "projects", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, ProjectRow>(
         r#"
@@ -302,7 +232,7 @@ async fn update_project(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, project_id)): Path<(String, String)>,
-    Json(req): Json<UpdateProjectRequest>,
+    ValidJson(req): ValidJson<UpdateProjectRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -337,22 +267,6 @@ async fn update_project(
         );
     }
 
-    if let Some(name) = req.name.as_ref() {
-        if name.is_empty() {
-            return Err(
-                ApiError::bad_request("invalid_name", "Project name cannot be empty")
-                    .with_request_id(request_id.clone()),
-            );
-        }
-        if name.len() > 100 {
-            return Err(ApiError::bad_request(
-                "invalid_name",
-                "Project name cannot exceed 100 characters",
-            )
-            .with_request_id(request_id.clone()));
-        }
-    }
-
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -472,20 +386,7 @@ async fn update_project(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "projects",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "projects", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, ProjectRow>(
         r#"
@@ -671,32 +572,3 @@ impl From<ProjectRow> for ProjectResponse {
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_create_project_request_deserialization() {
-        let json = r#"{"name":"my-project"}"#;
-        let req: CreateProjectRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(req.name, "my-project");
-    }
-
-    #[test]
-    fn test_project_response_serialization() {
-        let response = ProjectResponse {
-            id: "prj_01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
-            org_id: "org_01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
-            name: "my-project".to_string(),
-            resource_version: 1,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
-
-        let json = serde_json::to_string(&response).unwrap();
-        assert!(json.contains("\"id\""));
-        assert!(json.contains("\"org_id\""));
-        assert!(json.contains("\"name\""));
-    }
-}
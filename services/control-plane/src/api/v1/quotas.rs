@@ -0,0 +1,241 @@
+//! Operator endpoints for per-org quota overrides.
+//!
+//! - `GET /v1/_admin/orgs/{org_id}/quotas`: effective limit, default,
+//!   override, and current usage for every dimension
+//! - `PUT /v1/_admin/orgs/{org_id}/quotas/{dimension}`: set an override
+//! - `DELETE /v1/_admin/orgs/{org_id}/quotas/{dimension}`: clear it
+//!
+//! Overrides live in `org_quotas` and take effect on the next quota check.
+//!
+//! See: docs/specs/api/http-api.md
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{get, put},
+    Json, Router,
+};
+use plfm_id::OrgId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::api::validation::{ValidJson, Validate, Validator};
+use crate::db::quotas::{self, QuotaDimension};
+use crate::state::AppState;
+
+/// Operator endpoints, merged into /v1/_admin.
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orgs/{org_id}/quotas", get(list_quotas))
+        .route(
+            "/orgs/{org_id}/quotas/{dimension}",
+            put(set_quota).delete(clear_quota),
+        )
+}
+
+#[derive(Debug, Serialize)]
+struct QuotaResponse {
+    dimension: &'static str,
+    /// The limit quota checks enforce: the override if set, else the default.
+    limit: i64,
+    default_limit: i64,
+    override_limit: Option<i64>,
+    usage: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct QuotasResponse {
+    org_id: String,
+    items: Vec<QuotaResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetQuotaRequest {
+    limit: i64,
+}
+
+impl Validate for SetQuotaRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            self.limit >= 0,
+            "invalid_quota_limit",
+            "limit",
+            "Quota limit must not be negative",
+        );
+    }
+}
+
+/// GET /v1/_admin/orgs/{org_id}/quotas
+async fn list_quotas(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let org_id = require_org(&state, &org_id, &request_id).await?;
+    let pool = state.db().pool();
+
+    let internal = |e: sqlx::Error| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to load quotas");
+        ApiError::internal("internal_error", "Failed to load quotas")
+            .with_request_id(request_id.clone())
+    };
+
+    let overrides: HashMap<String, i64> = quotas::list_overrides(pool, &org_id)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .collect();
+
+    let mut items = Vec::with_capacity(QuotaDimension::ALL.len());
+    for dimension in QuotaDimension::ALL {
+        let override_limit = overrides.get(dimension.as_str()).copied();
+        items.push(QuotaResponse {
+            dimension: dimension.as_str(),
+            limit: override_limit.unwrap_or_else(|| dimension.default_limit()),
+            default_limit: dimension.default_limit(),
+            override_limit,
+            usage: quotas::get_current_usage(pool, &org_id, dimension)
+                .await
+                .map_err(internal)?,
+        });
+    }
+
+    Ok(Json(QuotasResponse {
+        org_id: org_id.to_string(),
+        items,
+    }))
+}
+
+/// PUT /v1/_admin/orgs/{org_id}/quotas/{dimension}
+async fn set_quota(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, dimension)): Path<(String, String)>,
+    ValidJson(req): ValidJson<SetQuotaRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let org_id = require_org(&state, &org_id, &request_id).await?;
+    let dimension = parse_dimension(&dimension, &request_id)?;
+
+    quotas::set_override(state.db().pool(), &org_id, dimension, req.limit)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to set quota");
+            ApiError::internal("internal_error", "Failed to set quota")
+                .with_request_id(request_id.clone())
+        })?;
+
+    tracing::info!(
+        request_id = %request_id,
+        actor_id = %ctx.actor_id,
+        org_id = %org_id,
+        dimension = dimension.as_str(),
+        limit = req.limit,
+        "Quota override set"
+    );
+
+    Ok(Json(QuotaResponse {
+        dimension: dimension.as_str(),
+        limit: req.limit,
+        default_limit: dimension.default_limit(),
+        override_limit: Some(req.limit),
+        usage: current_usage(&state, &org_id, dimension, &request_id).await?,
+    }))
+}
+
+/// DELETE /v1/_admin/orgs/{org_id}/quotas/{dimension}
+async fn clear_quota(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, dimension)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let org_id = require_org(&state, &org_id, &request_id).await?;
+    let dimension = parse_dimension(&dimension, &request_id)?;
+
+    let cleared = quotas::clear_override(state.db().pool(), &org_id, dimension)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to clear quota");
+            ApiError::internal("internal_error", "Failed to clear quota")
+                .with_request_id(request_id.clone())
+        })?;
+
+    if cleared {
+        tracing::info!(
+            request_id = %request_id,
+            actor_id = %ctx.actor_id,
+            org_id = %org_id,
+            dimension = dimension.as_str(),
+            "Quota override cleared"
+        );
+    }
+
+    Ok(Json(QuotaResponse {
+        dimension: dimension.as_str(),
+        limit: dimension.default_limit(),
+        default_limit: dimension.default_limit(),
+        override_limit: None,
+        usage: current_usage(&state, &org_id, dimension, &request_id).await?,
+    }))
+}
+
+/// Parse `org_id` and check the org exists.
+async fn require_org(state: &AppState, org_id: &str, request_id: &str) -> Result<OrgId, ApiError> {
+    let parsed: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM orgs_view WHERE org_id = $1)")
+        .bind(org_id)
+        .fetch_one(state.db().pool())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to load org");
+            ApiError::internal("internal_error", "Failed to load organization")
+                .with_request_id(request_id.to_string())
+        })?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "org_not_found",
+            format!("Organization {} not found", org_id),
+        )
+        .with_request_id(request_id.to_string()));
+    }
+
+    Ok(parsed)
+}
+
+fn parse_dimension(dimension: &str, request_id: &str) -> Result<QuotaDimension, ApiError> {
+    QuotaDimension::parse(dimension).ok_or_else(|| {
+        ApiError::not_found(
+            "quota_dimension_not_found",
+            format!("Unknown quota dimension '{}'", dimension),
+        )
+        .with_request_id(request_id.to_string())
+    })
+}
+
+async fn current_usage(
+    state: &AppState,
+    org_id: &OrgId,
+    dimension: QuotaDimension,
+    request_id: &str,
+) -> Result<i64, ApiError> {
+    quotas::get_current_usage(state.db().pool(), org_id, dimension)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to load quota usage");
+            ApiError::internal("internal_error", "Failed to load quota usage")
+                .with_request_id(request_id.to_string())
+        })
+}
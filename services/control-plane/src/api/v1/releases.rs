@@ -11,13 +11,16 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use plfm_events::AggregateType;
+use plfm_events::{AggregateType, ReleaseMetadata};
 use plfm_id::{AppId, OrgId, ReleaseId};
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
+use crate::api::pagination::{self, PageQuery, PageTotal, SortKey, Sorting};
 use crate::api::request_context::RequestContext;
 use crate::db::AppendEvent;
 use crate::state::AppState;
@@ -54,8 +57,19 @@ pub struct CreateReleaseRequest {
 
     /// Entrypoint command (array of strings).
     pub command: Vec<String>,
+
+    /// Source and build provenance (git ref/commit/branch, CI run, SBOM,
+    /// builder).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ReleaseMetadata>,
 }
 
+/// Longest git ref, branch, or builder identity accepted in release metadata.
+const MAX_METADATA_NAME_LEN: usize = 255;
+
+/// Longest CI run URL accepted in release metadata.
+const MAX_CI_RUN_URL_LEN: usize = 2048;
+
 fn default_manifest_version() -> i32 {
     1
 }
@@ -87,6 +101,9 @@ pub struct ReleaseResponse {
     /// Entrypoint command.
     pub command: Vec<String>,
 
+    /// Source metadata recorded at creation.
+    pub metadata: ReleaseMetadata,
+
     /// Resource version for optimistic concurrency.
     pub resource_version: i32,
 
@@ -102,15 +119,103 @@ pub struct ListReleasesResponse {
 
     /// Next cursor (null if no more results).
     pub next_cursor: Option<String>,
+
+    /// Matching releases, with `include_total=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
 }
 
-/// Query parameters for listing releases.
+/// Query parameters for listing releases. Pagination is in [`PageQuery`].
 #[derive(Debug, Deserialize)]
 pub struct ListReleasesQuery {
-    /// Max number of items to return.
-    pub limit: Option<i64>,
-    /// Cursor (exclusive). Interpreted as a release_id.
-    pub cursor: Option<String>,
+    /// Only releases built from this git ref.
+    pub git_ref: Option<String>,
+    /// Only releases built from this git commit.
+    pub git_commit: Option<String>,
+}
+
+/// A git ref, branch, or builder identity: printable, no whitespace.
+fn valid_metadata_name(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_METADATA_NAME_LEN
+        && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// A full or abbreviated git commit SHA (SHA-1 or SHA-256).
+fn valid_git_commit(commit: &str) -> bool {
+    (7..=64).contains(&commit.len())
+        && commit
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+fn valid_sha256_digest(digest: &str) -> bool {
+    digest.strip_prefix("sha256:").is_some_and(|hex| {
+        hex.len() == 64
+            && hex
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    })
+}
+
+fn valid_ci_run_url(url: &str) -> bool {
+    url.len() <= MAX_CI_RUN_URL_LEN
+        && (url.starts_with("https://") || url.starts_with("http://"))
+        && !url.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// The first invalid field of `metadata`, as a message.
+fn metadata_error(metadata: &ReleaseMetadata) -> Option<String> {
+    let name_error = |field: &str| {
+        format!("{field} must be 1-{MAX_METADATA_NAME_LEN} characters without whitespace or control characters")
+    };
+    let checks = [
+        (
+            metadata
+                .git_ref
+                .as_deref()
+                .is_some_and(|v| !valid_metadata_name(v)),
+            name_error("git_ref"),
+        ),
+        (
+            metadata
+                .git_commit
+                .as_deref()
+                .is_some_and(|v| !valid_git_commit(v)),
+            "git_commit must be 7-64 lowercase hex characters".to_string(),
+        ),
+        (
+            metadata
+                .git_branch
+                .as_deref()
+                .is_some_and(|v| !valid_metadata_name(v)),
+            name_error("git_branch"),
+        ),
+        (
+            metadata
+                .ci_run_url
+                .as_deref()
+                .is_some_and(|v| !valid_ci_run_url(v)),
+            format!("ci_run_url must be an http(s) URL of at most {MAX_CI_RUN_URL_LEN} characters"),
+        ),
+        (
+            metadata
+                .sbom_digest
+                .as_deref()
+                .is_some_and(|v| !valid_sha256_digest(v)),
+            "sbom_digest must be sha256:<64 lowercase hex characters>".to_string(),
+        ),
+        (
+            metadata
+                .builder
+                .as_deref()
+                .is_some_and(|v| !valid_metadata_name(v)),
+            name_error("builder"),
+        ),
+    ];
+    checks
+        .into_iter()
+        .find_map(|(invalid, message)| invalid.then_some(message))
 }
 
 // =============================================================================
@@ -178,6 +283,11 @@ async fn create_release(
         .with_request_id(request_id.clone()));
     }
 
+    if let Some(message) = req.metadata.as_ref().and_then(metadata_error) {
+        return Err(ApiError::bad_request("invalid_release_metadata", message)
+            .with_request_id(request_id.clone()));
+    }
+
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -258,7 +368,8 @@ async fn create_release(
             "image_digest": req.image_digest,
             "manifest_schema_version": req.manifest_schema_version,
             "manifest_hash": req.manifest_hash,
-            "command": req.command
+            "command": req.command,
+            "metadata": req.metadata
         }),
         ..Default::default()
     };
@@ -271,25 +382,13 @@ async fn create_release(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "releases",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "releases", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, ReleaseRow>(
         r#"
         SELECT release_id, org_id, app_id, image_ref, index_or_manifest_digest,
-               manifest_schema_version, manifest_hash, command, resource_version, created_at
+               manifest_schema_version, manifest_hash, command, metadata, resource_version,
+               created_at
         FROM releases_view
         WHERE release_id = $1 AND org_id = $2 AND app_id = $3
         "#,
@@ -345,6 +444,7 @@ async fn list_releases(
     ctx: RequestContext,
     Path((org_id_raw, app_id_raw)): Path<(String, String)>,
     Query(query): Query<ListReleasesQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
 
@@ -362,49 +462,55 @@ async fn list_releases(
 
     let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
 
-    let limit: i64 = query.limit.unwrap_or(50).clamp(1, 200);
-    let cursor = match query.cursor.as_deref() {
-        Some(raw) => {
-            let _: ReleaseId = raw.parse().map_err(|_| {
-                ApiError::bad_request("invalid_cursor", "Invalid cursor format")
-                    .with_request_id(request_id.clone())
-            })?;
-            Some(raw.to_string())
+    let page = RELEASE_SORTING.page(page, &request_id)?;
+    let filters = |builder: &mut QueryBuilder<'_, Postgres>| {
+        builder.push("org_id = ");
+        builder.push_bind(org_id_raw.clone());
+        builder.push(" AND app_id = ");
+        builder.push_bind(app_id_raw.clone());
+        if let Some(git_ref) = query.git_ref.as_ref() {
+            builder.push(" AND metadata->>'git_ref' = ");
+            builder.push_bind(git_ref.clone());
+        }
+        if let Some(git_commit) = query.git_commit.as_ref() {
+            builder.push(" AND metadata->>'git_commit' = ");
+            builder.push_bind(git_commit.clone());
         }
-        None => None,
     };
 
-    let rows = sqlx::query_as::<_, ReleaseRow>(
-        r#"
-        SELECT release_id, org_id, app_id, image_ref, index_or_manifest_digest,
-               manifest_schema_version, manifest_hash, command, resource_version, created_at
-        FROM releases_view
-        WHERE org_id = $1 AND app_id = $2
-          AND ($3::TEXT IS NULL OR release_id > $3)
-        ORDER BY release_id ASC
-        LIMIT $4
-        "#,
-    )
-    .bind(&org_id_raw)
-    .bind(&app_id_raw)
-    .bind(cursor.as_deref())
-    .bind(limit)
-    .fetch_all(state.db().pool())
-    .await
-    .map_err(|e| {
+    let mut builder = QueryBuilder::new(
+        "SELECT release_id, org_id, app_id, image_ref, index_or_manifest_digest, \
+                manifest_schema_version, manifest_hash, command, metadata, resource_version, \
+                created_at \
+         FROM releases_view WHERE ",
+    );
+    filters(&mut builder);
+    page.push_after(&mut builder);
+    page.push_order(&mut builder);
+
+    let list_failed = |e: sqlx::Error| {
         tracing::error!(error = %e, request_id = %request_id, "Failed to list releases");
         ApiError::internal("internal_error", "Failed to list releases")
             .with_request_id(request_id.clone())
-    })?;
+    };
+    let rows = builder
+        .build_query_as::<ReleaseRow>()
+        .fetch_all(state.db().pool())
+        .await
+        .map_err(list_failed)?;
+    let total = page
+        .total(state.db().pool(), "releases_view", filters)
+        .await
+        .map_err(list_failed)?;
 
+    let (rows, next_cursor) = page.finish(rows);
     let items: Vec<ReleaseResponse> = rows.into_iter().map(ReleaseResponse::from).collect();
-    let next_cursor = if items.len() == limit as usize {
-        items.last().map(|r| r.id.clone())
-    } else {
-        None
-    };
 
-    Ok(Json(ListReleasesResponse { items, next_cursor }))
+    Ok(Json(ListReleasesResponse {
+        items,
+        next_cursor,
+        total,
+    }))
 }
 
 /// Get a single release by ID.
@@ -438,7 +544,8 @@ async fn get_release(
     let row = sqlx::query_as::<_, ReleaseRow>(
         r#"
         SELECT release_id, org_id, app_id, image_ref, index_or_manifest_digest,
-               manifest_schema_version, manifest_hash, command, resource_version, created_at
+               manifest_schema_version, manifest_hash, command, metadata, resource_version,
+               created_at
         FROM releases_view
         WHERE org_id = $1 AND app_id = $2 AND release_id = $3
         "#,
@@ -468,6 +575,26 @@ async fn get_release(
 // Database Row Types
 // =============================================================================
 
+/// Orderings accepted when listing releases.
+static RELEASE_SORTING: Sorting<ReleaseRow> = Sorting {
+    id_column: "release_id",
+    id: |row| row.release_id.clone(),
+    keys: &[
+        SortKey {
+            name: "id",
+            column: "release_id",
+            sql_type: "TEXT",
+            value: |row| row.release_id.clone(),
+        },
+        SortKey {
+            name: "created_at",
+            column: "created_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.created_at),
+        },
+    ],
+};
+
 struct ReleaseRow {
     release_id: String,
     org_id: String,
@@ -477,6 +604,7 @@ struct ReleaseRow {
     manifest_schema_version: i32,
     manifest_hash: String,
     command: serde_json::Value,
+    metadata: serde_json::Value,
     resource_version: i32,
     created_at: DateTime<Utc>,
 }
@@ -493,6 +621,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ReleaseRow {
             manifest_schema_version: row.try_get("manifest_schema_version")?,
             manifest_hash: row.try_get("manifest_hash")?,
             command: row.try_get("command")?,
+            metadata: row.try_get("metadata")?,
             resource_version: row.try_get("resource_version")?,
             created_at: row.try_get("created_at")?,
         })
@@ -502,6 +631,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ReleaseRow {
 impl From<ReleaseRow> for ReleaseResponse {
     fn from(row: ReleaseRow) -> Self {
         let command: Vec<String> = serde_json::from_value(row.command).unwrap_or_default();
+        let metadata: ReleaseMetadata = serde_json::from_value(row.metadata).unwrap_or_default();
         Self {
             id: row.release_id,
             org_id: row.org_id,
@@ -511,6 +641,7 @@ impl From<ReleaseRow> for ReleaseResponse {
             manifest_schema_version: row.manifest_schema_version,
             manifest_hash: row.manifest_hash,
             command,
+            metadata,
             resource_version: row.resource_version,
             created_at: row.created_at,
         }
@@ -535,6 +666,52 @@ mod tests {
         assert_eq!(req.manifest_schema_version, 1);
         assert_eq!(req.manifest_hash, "def456");
         assert_eq!(req.command, vec!["./start", "--port", "8080"]);
+        assert!(req.metadata.is_none());
+    }
+
+    #[test]
+    fn test_valid_metadata_name() {
+        assert!(valid_metadata_name("main"));
+        assert!(valid_metadata_name("refs/pull/42/head"));
+        assert!(!valid_metadata_name(""));
+        assert!(!valid_metadata_name("feature branch"));
+        assert!(!valid_metadata_name(&"a".repeat(MAX_METADATA_NAME_LEN + 1)));
+    }
+
+    #[test]
+    fn test_metadata_error() {
+        let metadata = ReleaseMetadata {
+            git_ref: Some("refs/heads/main".to_string()),
+            git_commit: Some("3f2a9c1".to_string()),
+            git_branch: Some("main".to_string()),
+            ci_run_url: Some("https://ci.example.com/runs/42".to_string()),
+            sbom_digest: Some(format!("sha256:{}", "ab".repeat(32))),
+            builder: Some("github-actions".to_string()),
+        };
+        assert_eq!(metadata_error(&metadata), None);
+        assert_eq!(metadata_error(&ReleaseMetadata::default()), None);
+
+        let invalid = [
+            ReleaseMetadata {
+                git_commit: Some("3F2A9C1".to_string()),
+                ..metadata.clone()
+            },
+            ReleaseMetadata {
+                ci_run_url: Some("ftp://ci.example.com".to_string()),
+                ..metadata.clone()
+            },
+            ReleaseMetadata {
+                sbom_digest: Some("sha256:abc".to_string()),
+                ..metadata.clone()
+            },
+            ReleaseMetadata {
+                builder: Some(String::new()),
+                ..metadata.clone()
+            },
+        ];
+        for metadata in invalid {
+            assert!(metadata_error(&metadata).is_some(), "{metadata:?}");
+        }
     }
 
     #[test]
@@ -548,6 +725,10 @@ mod tests {
             manifest_schema_version: 1,
             manifest_hash: "def456".to_string(),
             command: vec!["./start".to_string()],
+            metadata: ReleaseMetadata {
+                git_ref: Some("refs/pull/42/head".to_string()),
+                ..Default::default()
+            },
             resource_version: 1,
             created_at: Utc::now(),
         };
@@ -556,5 +737,6 @@ mod tests {
         assert!(json.contains("\"id\":\"rel_123\""));
         assert!(json.contains("\"image_ref\":\"registry.example.com/app:v1.0\""));
         assert!(json.contains("\"command\":[\"./start\"]"));
+        assert!(json.contains("\"metadata\":{\"git_ref\":\"refs/pull/42/head\"}"));
     }
 }
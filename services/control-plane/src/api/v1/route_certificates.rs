@@ -0,0 +1,518 @@
+//! Route TLS certificate endpoints.
+//!
+//! - `/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/certificate`:
+//!   upload, inspect, and remove the certificate a `tls_terminate` route is
+//!   served with (merged into the routes router)
+//! - `/v1/_admin/route-certificates`: certificates with decrypted keys, for
+//!   the ingress (operator only)
+//!
+//! Private keys are write-only for org members: no org-scoped endpoint
+//! returns them.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::CACHE_CONTROL, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_events::{
+    AggregateType, EventPayload, NewEvent, RouteCertRemovedPayload, RouteCertSetPayload,
+    RouteProtocolHint,
+};
+use plfm_id::{AppId, EnvId, OrgId, RouteId};
+use plfm_pki::PkiError;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::route_certs::{self, RouteCertError, RouteCertificate, RouteTarget};
+use crate::state::AppState;
+
+use super::routes::{load_route_from_events, RouteState};
+
+/// Per-route certificate, merged into the routes router.
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/{route_id}/certificate",
+        put(put_certificate)
+            .get(get_certificate)
+            .delete(delete_certificate),
+    )
+}
+
+/// Ingress delivery, merged into /v1/_admin.
+pub fn admin_routes() -> Router<AppState> {
+    Router::new().route("/route-certificates", get(list_certificate_material))
+}
+
+fn route_cert_error(error: RouteCertError, request_id: &str) -> ApiError {
+    let api_error = match error {
+        RouteCertError::Pki(PkiError::KeyMismatch) => ApiError::bad_request(
+            "certificate_key_mismatch",
+            "Private key does not match the certificate",
+        ),
+        RouteCertError::Pki(e) => {
+            ApiError::bad_request("invalid_certificate", format!("Invalid certificate: {e}"))
+        }
+        e @ RouteCertError::HostnameMismatch(_) => {
+            ApiError::bad_request("certificate_hostname_mismatch", e.to_string())
+        }
+        e @ RouteCertError::NotValidNow { .. } => {
+            ApiError::bad_request("certificate_not_valid", e.to_string())
+        }
+        e @ RouteCertError::DefaultTaken(_) => {
+            ApiError::conflict("default_certificate_taken", e.to_string())
+        }
+        other => {
+            tracing::error!(error = %other, request_id = %request_id, "Route certificate request failed");
+            ApiError::internal("internal_error", "Route certificate request failed")
+        }
+    };
+    api_error.with_request_id(request_id.to_string())
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct PutCertificateRequest {
+    /// Leaf certificate followed by intermediates, PEM.
+    pub certificate_pem: String,
+    /// Private key for the leaf, PEM (PKCS#8, PKCS#1, or SEC1).
+    pub private_key_pem: String,
+    /// Serve this certificate to clients that send no SNI on the route's
+    /// listen port.
+    #[serde(default)]
+    pub default_for_non_sni: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteCertificateResponse {
+    pub route_id: String,
+    pub hostname: String,
+    pub certificate_pem: String,
+    pub fingerprint_sha256: String,
+    pub dns_names: Vec<String>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub default_for_non_sni: bool,
+    /// Whether the certificate is inside the expiry warning window.
+    pub expiring: bool,
+    pub uploaded_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<RouteCertificate> for RouteCertificateResponse {
+    fn from(cert: RouteCertificate) -> Self {
+        Self {
+            expiring: cert.not_after <= Utc::now() + route_certs::expiry_warning_window(),
+            route_id: cert.route_id,
+            hostname: cert.hostname,
+            certificate_pem: cert.chain_pem,
+            fingerprint_sha256: cert.fingerprint_sha256,
+            dns_names: cert.dns_names,
+            not_before: cert.not_before,
+            not_after: cert.not_after,
+            default_for_non_sni: cert.default_for_non_sni,
+            uploaded_by: cert.uploaded_by,
+            created_at: cert.created_at,
+            updated_at: cert.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteCertificateResponse {
+    pub ok: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CertificateMaterialQuery {
+    pub org_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificateMaterialItem {
+    pub route_id: String,
+    pub org_id: String,
+    pub env_id: String,
+    pub hostname: String,
+    pub listen_port: i32,
+    pub certificate_pem: String,
+    pub private_key_pem: String,
+    pub fingerprint_sha256: String,
+    pub not_after: DateTime<Utc>,
+    pub default_for_non_sni: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificateMaterialResponse {
+    pub items: Vec<CertificateMaterialItem>,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Upload or replace a route's certificate.
+///
+/// PUT /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/certificate
+async fn put_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+    Json(req): Json<PutCertificateRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let (org_id, app_id, env_id, route_id) =
+        parse_ids(&org_id, &app_id, &env_id, &route_id, &request_id)?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let route = load_route(&state, org_id, app_id, env_id, &route_id, &request_id).await?;
+    if route.protocol_hint != RouteProtocolHint::TlsTerminate {
+        return Err(ApiError::conflict(
+            "route_not_tls_terminate",
+            "Certificates can only be uploaded for tls_terminate routes",
+        )
+        .with_request_id(request_id));
+    }
+
+    let cert = route_certs::validate(
+        &route.hostname,
+        &req.certificate_pem,
+        &req.private_key_pem,
+        Utc::now(),
+    )
+    .map_err(|e| route_cert_error(e, &request_id))?;
+
+    let target = RouteTarget {
+        route_id: route_id.to_string(),
+        org_id: org_id.to_string(),
+        app_id: app_id.to_string(),
+        env_id: env_id.to_string(),
+        hostname: route.hostname.clone(),
+        listen_port: route.listen_port,
+    };
+    let stored = route_certs::store(
+        state.db().pool(),
+        &target,
+        &cert,
+        req.default_for_non_sni,
+        &ctx.actor_id,
+    )
+    .await
+    .map_err(|e| route_cert_error(e, &request_id))?;
+
+    append_cert_event(
+        &state,
+        &ctx,
+        &route,
+        &RouteCertSetPayload {
+            route_id,
+            org_id,
+            env_id,
+            hostname: stored.hostname.clone(),
+            fingerprint_sha256: stored.fingerprint_sha256.clone(),
+            not_after: stored.not_after.to_rfc3339(),
+            default_for_non_sni: stored.default_for_non_sni,
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        request_id = %request_id,
+        route_id = %route_id,
+        fingerprint_sha256 = %stored.fingerprint_sha256,
+        not_after = %stored.not_after,
+        actor_id = %ctx.actor_id,
+        "Route certificate uploaded"
+    );
+
+    Ok((StatusCode::OK, Json(RouteCertificateResponse::from(stored))).into_response())
+}
+
+/// Get a route's certificate (never the key).
+///
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/certificate
+async fn get_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let (org_id, app_id, env_id, route_id) =
+        parse_ids(&org_id, &app_id, &env_id, &route_id, &request_id)?;
+
+    authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let cert = route_certs::get(state.db().pool(), &route_id.to_string())
+        .await
+        .map_err(|e| route_cert_error(e.into(), &request_id))?
+        .filter(|c| {
+            c.org_id == org_id.to_string()
+                && c.app_id == app_id.to_string()
+                && c.env_id == env_id.to_string()
+        })
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "route_certificate_not_found",
+                "Route has no uploaded certificate",
+            )
+            .with_request_id(request_id.clone())
+        })?;
+
+    Ok((StatusCode::OK, Json(RouteCertificateResponse::from(cert))).into_response())
+}
+
+/// Remove a route's certificate and its key.
+///
+/// DELETE /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/certificate
+async fn delete_certificate(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let (org_id, app_id, env_id, route_id) =
+        parse_ids(&org_id, &app_id, &env_id, &route_id, &request_id)?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let route = load_route(&state, org_id, app_id, env_id, &route_id, &request_id).await?;
+
+    let removed = route_certs::remove(state.db().pool(), &route_id.to_string())
+        .await
+        .map_err(|e| route_cert_error(e.into(), &request_id))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "route_certificate_not_found",
+                "Route has no uploaded certificate",
+            )
+            .with_request_id(request_id.clone())
+        })?;
+
+    append_cert_event(
+        &state,
+        &ctx,
+        &route,
+        &RouteCertRemovedPayload {
+            route_id,
+            org_id,
+            env_id,
+            hostname: removed.hostname,
+            fingerprint_sha256: removed.fingerprint_sha256,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(DeleteCertificateResponse { ok: true })).into_response())
+}
+
+/// Certificates with decrypted keys for the ingress.
+///
+/// GET /v1/_admin/route-certificates?org_id=
+async fn list_certificate_material(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<CertificateMaterialQuery>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    authz::require_operator(&ctx)?;
+
+    if let Some(org_id) = query.org_id.as_deref() {
+        org_id.parse::<OrgId>().map_err(|_| {
+            ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+                .with_request_id(request_id.clone())
+        })?;
+    }
+
+    let material = route_certs::list_material(state.db().pool(), query.org_id.as_deref())
+        .await
+        .map_err(|e| route_cert_error(e, &request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        actor_id = %ctx.actor_id,
+        org_id = ?query.org_id,
+        count = material.len(),
+        "Route certificate keys read"
+    );
+
+    let items = material
+        .into_iter()
+        .map(|m| CertificateMaterialItem {
+            route_id: m.certificate.route_id,
+            org_id: m.certificate.org_id,
+            env_id: m.certificate.env_id,
+            hostname: m.certificate.hostname,
+            listen_port: m.certificate.listen_port,
+            certificate_pem: m.certificate.chain_pem,
+            private_key_pem: m.key_pem,
+            fingerprint_sha256: m.certificate.fingerprint_sha256,
+            not_after: m.certificate.not_after,
+            default_for_non_sni: m.certificate.default_for_non_sni,
+        })
+        .collect();
+
+    let mut response =
+        (StatusCode::OK, Json(CertificateMaterialResponse { items })).into_response();
+    response
+        .headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+/// Remove the certificate of a route being deleted. Best effort: the route
+/// is already gone, and the ingress drops termination with the route.
+pub(super) async fn remove_with_route(state: &AppState, ctx: &RequestContext, route: &RouteState) {
+    let removed = match route_certs::remove(state.db().pool(), &route.route_id.to_string()).await {
+        Ok(Some(removed)) => removed,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                request_id = %ctx.request_id,
+                route_id = %route.route_id,
+                "Failed to remove certificate of deleted route"
+            );
+            return;
+        }
+    };
+
+    let payload = RouteCertRemovedPayload {
+        route_id: route.route_id,
+        org_id: route.org_id,
+        env_id: route.env_id,
+        hostname: removed.hostname,
+        fingerprint_sha256: removed.fingerprint_sha256,
+    };
+    if let Err(e) = append_cert_event(state, ctx, route, &payload).await {
+        tracing::warn!(
+            error = ?e,
+            request_id = %ctx.request_id,
+            route_id = %route.route_id,
+            "Failed to record certificate removal of deleted route"
+        );
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn parse_ids(
+    org_id: &str,
+    app_id: &str,
+    env_id: &str,
+    route_id: &str,
+    request_id: &str,
+) -> Result<(OrgId, AppId, EnvId, RouteId), ApiError> {
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    let route_id: RouteId = route_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_route_id", "Invalid route ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    Ok((org_id, app_id, env_id, route_id))
+}
+
+async fn load_route(
+    state: &AppState,
+    org_id: OrgId,
+    app_id: AppId,
+    env_id: EnvId,
+    route_id: &RouteId,
+    request_id: &str,
+) -> Result<RouteState, ApiError> {
+    let event_store = state.db().event_store();
+    load_route_from_events(&event_store, route_id, request_id)
+        .await?
+        .filter(|r| !r.is_deleted && r.org_id == org_id && r.app_id == app_id && r.env_id == env_id)
+        .ok_or_else(|| {
+            ApiError::not_found("route_not_found", "Route not found")
+                .with_request_id(request_id.to_string())
+        })
+}
+
+async fn append_cert_event<P: EventPayload>(
+    state: &AppState,
+    ctx: &RequestContext,
+    route: &RouteState,
+    payload: &P,
+) -> Result<(), ApiError> {
+    let request_id = &ctx.request_id;
+    let event_store = state.db().event_store();
+    let aggregate_id = route.route_id.to_string();
+    let current_seq = event_store
+        .get_latest_aggregate_seq(&AggregateType::RouteCertificate, &aggregate_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to get aggregate sequence");
+            ApiError::internal("internal_error", "Failed to record certificate change")
+                .with_request_id(request_id.clone())
+        })?
+        .unwrap_or(0);
+
+    let event = NewEvent::builder(ctx)
+        .aggregate_id(aggregate_id)
+        .aggregate_seq(current_seq + 1)
+        .org_id(route.org_id)
+        .app_id(route.app_id)
+        .env_id(route.env_id)
+        .payload(payload)
+        .build()
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to build route certificate event");
+            ApiError::internal("internal_error", "Failed to record certificate change")
+                .with_request_id(request_id.clone())
+        })?;
+
+    let event_id = event_store.append(event.into()).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to append route certificate event");
+        ApiError::internal("internal_error", "Failed to record certificate change")
+            .with_request_id(request_id.clone())
+    })?;
+    consistency::record_write(ctx, event_id.value());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_cert_error_mapping() {
+        let err = route_cert_error(RouteCertError::Pki(PkiError::KeyMismatch), "req_1");
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert_eq!(err.problem.code, "certificate_key_mismatch");
+
+        let err = route_cert_error(
+            RouteCertError::HostnameMismatch("api.example.com".to_string()),
+            "req_1",
+        );
+        assert_eq!(err.problem.code, "certificate_hostname_mismatch");
+
+        let err = route_cert_error(RouteCertError::DefaultTaken(443), "req_1");
+        assert_eq!(err.status, StatusCode::CONFLICT);
+        assert_eq!(err.problem.code, "default_certificate_taken");
+    }
+}
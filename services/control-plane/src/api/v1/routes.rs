@@ -11,18 +11,31 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use plfm_events::{
-    event_types, AggregateType, RouteCreatedPayload, RouteDeletedPayload, RouteProtocolHint,
-    RouteProxyProtocol, RouteUpdatedPayload,
+    event_types, AggregateType, RouteAccessPolicy, RouteCreatedPayload, RouteDeletedPayload,
+    RouteLabelsUpdatedPayload, RouteProtocolHint, RouteProxyProtocol, RouteUpdatedPayload,
+    RouteVerificationFailedPayload, RouteVerificationMethod, RouteVerificationSucceededPayload,
 };
 use plfm_id::{AppId, EnvId, OrgId, RouteId};
 use serde::{Deserialize, Serialize};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
+use crate::api::if_exists::{IfExists, IfExistsQuery};
+use crate::api::labels::{self, LabelSelector, Labels, PatchLabelsRequest};
+use crate::api::preconditions::{self, Preconditions};
 use crate::api::request_context::RequestContext;
 use crate::db::{AppendEvent, EventRow};
+use crate::internal_routes;
+use crate::managed_dns;
+use crate::route_access;
+use crate::route_verification;
 use crate::state::AppState;
+use crate::trash::{RestoreScope, TrashKind};
+
+use super::labels::LabelTarget;
+use super::trash::{self, RestoreResponse};
 
 /// Create route routes.
 ///
@@ -32,9 +45,13 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_routes))
         .route("/", post(create_route))
+        .route("/by-hostname/{hostname}", get(get_route_by_hostname))
         .route("/{route_id}", get(get_route))
         .route("/{route_id}", patch(update_route))
         .route("/{route_id}", delete(delete_route))
+        .route("/{route_id}/labels", patch(patch_route_labels))
+        .route("/{route_id}/verify", post(verify_route))
+        .route("/{route_id}/restore", post(restore_route))
 }
 
 // =============================================================================
@@ -45,6 +62,7 @@ pub fn routes() -> Router<AppState> {
 pub struct ListRoutesQuery {
     pub limit: Option<i64>,
     pub cursor: Option<String>,
+    pub label_selector: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -59,11 +77,77 @@ pub struct RouteResponse {
     pub proxy_protocol: RouteProxyProtocol,
     #[serde(default)]
     pub ipv4_required: bool,
+    /// Served only on the overlay network.
+    pub internal: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+    pub verification: RouteVerificationResponse,
+    pub ingress: RouteIngressStatusResponse,
+    pub labels: Labels,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub resource_version: i32,
 }
 
+/// Hostname verification state. Only `verified` routes are served.
+#[derive(Debug, Serialize)]
+pub struct RouteVerificationResponse {
+    /// `pending`, `verified`, or `failed`.
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<DateTime<Utc>>,
+    /// TXT record proving ownership; shown until verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txt_record_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txt_record_value: Option<String>,
+    /// Alternative CNAME target, when the CNAME method is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cname_target: Option<String>,
+    /// Why verification failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How many ingresses have the route programmed with backends, out of those
+/// reporting for the org.
+#[derive(Debug, Serialize)]
+pub struct RouteIngressStatusResponse {
+    pub live: i32,
+    pub total: i32,
+    /// When the counts were last refreshed; absent until an ingress reports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RouteVerificationResponse {
+    fn new(
+        hostname: &str,
+        status: &str,
+        token: Option<&str>,
+        method: Option<String>,
+        verified_at: Option<DateTime<Utc>>,
+        error: Option<String>,
+    ) -> Self {
+        // Instructions are only useful while the route is unverified.
+        let token = token.filter(|_| status != "verified");
+        Self {
+            status: status.to_string(),
+            method,
+            verified_at,
+            txt_record_name: token.map(|_| route_verification::txt_record_name(hostname)),
+            txt_record_value: token.map(route_verification::txt_record_value),
+            cname_target: token.and_then(|t| {
+                route_verification::cname_suffix()
+                    .map(|suffix| route_verification::cname_target(t, &suffix))
+            }),
+            error,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ListRoutesResponse {
     pub items: Vec<RouteResponse>,
@@ -83,11 +167,19 @@ pub struct CreateRouteRequest {
     pub backend_expects_proxy_protocol: bool,
     #[serde(default)]
     pub ipv4_required: bool,
+    /// Serve only on the overlay network, under the internal hostname suffix.
+    #[serde(default)]
+    pub internal: bool,
+    /// Client CIDR and TLS fingerprint filtering.
+    #[serde(default)]
+    pub access_policy: Option<RouteAccessPolicy>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct UpdateRouteRequest {
-    pub expected_version: i32,
+    /// Legacy alternative to `If-Match`.
+    #[serde(default)]
+    pub expected_version: Option<i32>,
     #[serde(default)]
     pub backend_process_type: Option<String>,
     #[serde(default)]
@@ -98,6 +190,9 @@ pub struct UpdateRouteRequest {
     pub backend_expects_proxy_protocol: Option<bool>,
     #[serde(default)]
     pub ipv4_required: Option<bool>,
+    /// Replaces the whole policy; an empty policy clears it.
+    #[serde(default)]
+    pub access_policy: Option<RouteAccessPolicy>,
 }
 
 #[derive(Debug, Serialize)]
@@ -137,6 +232,7 @@ async fn list_routes(
 
     let limit = query.limit.unwrap_or(50).clamp(1, 200);
     let cursor = query.cursor.as_deref();
+    let selector = LabelSelector::from_query(query.label_selector.as_deref(), &request_id)?;
 
     let rows = sqlx::query_as::<_, RouteRow>(
         r#"
@@ -150,6 +246,17 @@ async fn list_routes(
             backend_port,
             proxy_protocol,
             ipv4_required,
+            internal,
+            access_policy,
+            verification_status,
+            verification_token,
+            verification_method,
+            verified_at,
+            verification_error,
+            ingress_live_count,
+            ingress_total_count,
+            ingress_status_at,
+            labels,
             resource_version,
             created_at,
             updated_at
@@ -159,6 +266,7 @@ async fn list_routes(
           AND env_id = $3
           AND NOT is_deleted
           AND ($4::TEXT IS NULL OR route_id > $4)
+          AND labels_match(labels, $6::JSONB)
         ORDER BY route_id ASC
         LIMIT $5
         "#,
@@ -168,6 +276,7 @@ async fn list_routes(
     .bind(env_id.to_string())
     .bind(cursor)
     .bind(limit)
+    .bind(selector)
     .fetch_all(state.db().pool())
     .await
     .map_err(|e| {
@@ -192,13 +301,61 @@ async fn list_routes(
     Ok(Json(ListRoutesResponse { items, next_cursor }))
 }
 
+/// Update labels on a route.
+///
+/// PATCH /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/labels
+async fn patch_route_labels(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<PatchLabelsRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let route_id: RouteId = route_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_route_id", "Invalid route ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    super::labels::patch_labels(
+        &state,
+        &ctx,
+        org_id,
+        LabelTarget::Route {
+            app_id,
+            env_id,
+            route_id,
+        },
+        &preconditions,
+        req,
+    )
+    .await
+}
+
 /// Create a route.
 ///
 /// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes
+///
+/// With `?if_exists=return`, an existing route of this env with the same
+/// hostname and settings is returned instead of a conflict.
 async fn create_route(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+    Query(if_exists): Query<IfExistsQuery>,
     Json(req): Json<CreateRouteRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
@@ -211,6 +368,7 @@ async fn create_route(
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
             .with_request_id(request_id.clone())
     })?;
+    let if_exists = if_exists.mode(&request_id)?;
     let app_id: AppId = app_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_app_id", "Invalid application ID format")
             .with_request_id(request_id.clone())
@@ -243,6 +401,38 @@ async fn create_route(
         .with_request_id(request_id.clone()));
     }
 
+    // Internal and public routes never share a namespace.
+    let internal_suffix = internal_routes::suffix();
+    let internal_hostname = internal_routes::is_internal_hostname(&req.hostname, &internal_suffix);
+    if req.internal && !internal_hostname {
+        return Err(ApiError::bad_request(
+            "invalid_internal_route",
+            format!("Internal route hostnames must end in .{internal_suffix}"),
+        )
+        .with_request_id(request_id.clone()));
+    }
+    if req.internal && req.ipv4_required {
+        return Err(ApiError::bad_request(
+            "invalid_internal_route",
+            "Internal routes are served on the IPv6 overlay and cannot require IPv4",
+        )
+        .with_request_id(request_id.clone()));
+    }
+
+    let access_policy = req
+        .access_policy
+        .as_ref()
+        .map(|p| validate_access_policy(p, req.protocol_hint, &request_id))
+        .transpose()?
+        .filter(|p| !p.is_empty());
+    if !req.internal && internal_hostname {
+        return Err(ApiError::bad_request(
+            "hostname_reserved",
+            format!("Hostnames under .{internal_suffix} are reserved for internal routes"),
+        )
+        .with_request_id(request_id.clone()));
+    }
+
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -311,23 +501,89 @@ async fn create_route(
         .with_request_id(request_id.clone()));
     }
 
+    // Managed hostnames are allocated by the platform; an env may only bind
+    // its own (e.g. to re-create a deleted managed route).
+    if let Some(domain) = managed_dns::platform_domain() {
+        if managed_dns::is_reserved(&req.hostname, &domain) {
+            let own: Option<String> = sqlx::query_scalar(
+                "SELECT managed_hostname FROM envs_view WHERE env_id = $1 AND NOT is_deleted",
+            )
+            .bind(env_id.to_string())
+            .fetch_optional(state.db().pool())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, "Failed to load managed hostname");
+                ApiError::internal("internal_error", "Failed to create route")
+                    .with_request_id(request_id.clone())
+            })?
+            .flatten();
+            if own.as_deref() != Some(req.hostname.as_str()) {
+                return Err(ApiError::bad_request(
+                    "hostname_reserved",
+                    format!(
+                        "Hostnames under {} are managed by the platform",
+                        managed_dns::apps_domain(&domain)
+                    ),
+                )
+                .with_request_id(request_id.clone()));
+            }
+        }
+    }
+
+    // A route of another env holding the hostname is a conflict either way.
+    if if_exists == IfExists::Return {
+        let existing = load_route_by_hostname(&state, &org_id, &app_id, &env_id, &req.hostname)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, "Failed to load route");
+                ApiError::internal("internal_error", "Failed to verify hostname uniqueness")
+                    .with_request_id(request_id.clone())
+            })?;
+        if let Some(existing) = existing {
+            let existing = RouteResponse::from(existing);
+            if_exists.reuse(
+                route_matches(&existing, &req, access_policy.as_ref()),
+                || {
+                    ApiError::conflict(
+                        "hostname_in_use",
+                        format!(
+                            "Hostname '{}' is already routed with different settings",
+                            req.hostname
+                        ),
+                    )
+                    .with_request_id(request_id.clone())
+                },
+            )?;
+            return Ok(preconditions::with_etag(
+                existing.resource_version,
+                (StatusCode::OK, Json(existing)),
+            ));
+        }
+    }
+
     // Enforce global hostname uniqueness by policy (view + event-log fallback for projection lag).
     let hostname_exists = sqlx::query_scalar::<_, bool>(
         r#"
         SELECT
-          EXISTS (SELECT 1 FROM routes_view WHERE hostname = $1 AND NOT is_deleted)
+          EXISTS (
+            SELECT 1 FROM routes_view
+            WHERE hostname = $1 AND NOT is_deleted AND verification_status <> 'failed'
+          )
           OR EXISTS (
             SELECT 1
             FROM events e
             WHERE e.event_type = 'route.created'
               AND e.payload->>'hostname' = $1
-              AND NOT EXISTS (
-                SELECT 1
+              AND COALESCE((
+                SELECT d.event_type
                 FROM events d
                 WHERE d.aggregate_type = e.aggregate_type
                   AND d.aggregate_id = e.aggregate_id
-                  AND d.event_type = 'route.deleted'
-              )
+                  AND d.event_type IN
+                      ('route.deleted', 'route.restored', 'route.verification_failed')
+                ORDER BY d.aggregate_seq DESC
+                LIMIT 1
+              ), 'route.restored') = 'route.restored'
           )
         "#,
     )
@@ -381,6 +637,11 @@ async fn create_route(
         backend_expects_proxy_protocol: req.backend_expects_proxy_protocol,
         ipv4_required: req.ipv4_required,
         env_ipv4_address,
+        // Internal names never reach public DNS; there is nothing to verify.
+        verification_token: (!req.internal && route_verification::verification_required())
+            .then(route_verification::new_token),
+        internal: req.internal,
+        access_policy,
     };
 
     let payload = serde_json::to_value(&payload).map_err(|e| {
@@ -423,20 +684,7 @@ async fn create_route(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "routes",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "routes", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, RouteRow>(
         r#"
@@ -450,6 +698,17 @@ async fn create_route(
             backend_port,
             proxy_protocol,
             ipv4_required,
+            internal,
+            access_policy,
+            verification_status,
+            verification_token,
+            verification_method,
+            verified_at,
+            verification_error,
+            ingress_live_count,
+            ingress_total_count,
+            ingress_status_at,
+            labels,
             resource_version,
             created_at,
             updated_at
@@ -478,6 +737,7 @@ async fn create_route(
     })?;
 
     let response = RouteResponse::from(row);
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -502,7 +762,27 @@ async fn create_route(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
+}
+
+/// Whether an existing route has the settings a create request asks for.
+/// `access_policy` is the request's validated policy.
+fn route_matches(
+    existing: &RouteResponse,
+    req: &CreateRouteRequest,
+    access_policy: Option<&RouteAccessPolicy>,
+) -> bool {
+    existing.listen_port == req.listen_port
+        && existing.protocol_hint == req.protocol_hint
+        && existing.backend_process_type == req.backend_process_type
+        && existing.backend_port == req.backend_port
+        && existing.proxy_protocol == req.proxy_protocol
+        && existing.ipv4_required == req.ipv4_required
+        && existing.internal == req.internal
+        && existing.access_policy.as_ref().filter(|p| !p.is_empty()) == access_policy
 }
 
 /// Get route.
@@ -546,6 +826,17 @@ async fn get_route(
             backend_port,
             proxy_protocol,
             ipv4_required,
+            internal,
+            access_policy,
+            verification_status,
+            verification_token,
+            verification_method,
+            verified_at,
+            verification_error,
+            ingress_live_count,
+            ingress_total_count,
+            ingress_status_at,
+            labels,
             resource_version,
             created_at,
             updated_at
@@ -575,7 +866,11 @@ async fn get_route(
     })?;
 
     if let Some(row) = row {
-        return Ok(Json(RouteResponse::from(row)));
+        let response = RouteResponse::from(row);
+        return Ok(preconditions::with_etag(
+            response.resource_version,
+            Json(response),
+        ));
     }
 
     // Fallback: reconstruct from event log for projection lag.
@@ -594,16 +889,119 @@ async fn get_route(
             .with_request_id(request_id.clone()));
     }
 
-    Ok(Json(route.to_response()))
+    let response = route.to_response();
+    Ok(preconditions::with_etag(
+        response.resource_version,
+        Json(response),
+    ))
+}
+
+/// Get a route of the env by hostname, e.g. to import it by hostname.
+///
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/by-hostname/{hostname}
+async fn get_route_by_hostname(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, hostname)): Path<(String, String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let row = load_route_by_hostname(&state, &org_id, &app_id, &env_id, &hostname)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, hostname = %hostname, "Failed to get route");
+            ApiError::internal("internal_error", "Failed to get route")
+                .with_request_id(request_id.clone())
+        })?
+        .ok_or_else(|| {
+            ApiError::not_found("route_not_found", "Route not found")
+                .with_request_id(request_id.clone())
+        })?;
+
+    let response = RouteResponse::from(row);
+    Ok(preconditions::with_etag(
+        response.resource_version,
+        Json(response),
+    ))
+}
+
+/// The live route of the env serving `hostname`. Hostnames are unique among
+/// live routes.
+async fn load_route_by_hostname(
+    state: &AppState,
+    org_id: &OrgId,
+    app_id: &AppId,
+    env_id: &EnvId,
+    hostname: &str,
+) -> Result<Option<RouteRow>, sqlx::Error> {
+    sqlx::query_as::<_, RouteRow>(
+        r#"
+        SELECT
+            route_id,
+            env_id,
+            hostname,
+            listen_port,
+            protocol_hint,
+            backend_process_type,
+            backend_port,
+            proxy_protocol,
+            ipv4_required,
+            internal,
+            access_policy,
+            verification_status,
+            verification_token,
+            verification_method,
+            verified_at,
+            verification_error,
+            ingress_live_count,
+            ingress_total_count,
+            ingress_status_at,
+            labels,
+            resource_version,
+            created_at,
+            updated_at
+        FROM routes_view
+        WHERE hostname = $1
+          AND org_id = $2
+          AND app_id = $3
+          AND env_id = $4
+          AND NOT is_deleted
+        "#,
+    )
+    .bind(hostname)
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .bind(env_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
 }
 
 /// Update route.
 ///
 /// PATCH /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}
+///
+/// An update that changes nothing returns the route as is, without a new
+/// version.
 async fn update_route(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
     Json(req): Json<UpdateRouteRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
@@ -632,7 +1030,7 @@ async fn update_route(
     let role = authz::require_org_member(&state, &org_id, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
 
-    if req.expected_version < 0 {
+    if req.expected_version.is_some_and(|v| v < 0) {
         return Err(ApiError::bad_request(
             "invalid_expected_version",
             "expected_version must be >= 0",
@@ -640,18 +1038,6 @@ async fn update_route(
         .with_request_id(request_id.clone()));
     }
 
-    if req.backend_process_type.is_none()
-        && req.backend_port.is_none()
-        && req.proxy_protocol.is_none()
-        && req.backend_expects_proxy_protocol.is_none()
-        && req.ipv4_required.is_none()
-    {
-        return Err(
-            ApiError::bad_request("invalid_update", "No updatable fields provided")
-                .with_request_id(request_id.clone()),
-        );
-    }
-
     if let Some(port) = req.backend_port {
         validate_port(port, "backend_port", &request_id)?;
     }
@@ -705,18 +1091,28 @@ async fn update_route(
             .with_request_id(request_id.clone()));
     }
 
-    if current.resource_version != req.expected_version {
-        return Err(ApiError::conflict(
-            "version_conflict",
-            format!(
-                "Route version conflict: expected {}, current {}",
-                req.expected_version, current.resource_version
-            ),
+    preconditions.check(
+        req.expected_version,
+        current.resource_version,
+        true,
+        &request_id,
+    )?;
+
+    let next_version = current.resource_version + 1;
+
+    if current.internal && req.ipv4_required == Some(true) {
+        return Err(ApiError::bad_request(
+            "invalid_internal_route",
+            "Internal routes are served on the IPv6 overlay and cannot require IPv4",
         )
         .with_request_id(request_id.clone()));
     }
 
-    let next_version = current.resource_version + 1;
+    let access_policy = req
+        .access_policy
+        .as_ref()
+        .map(|p| validate_access_policy(p, current.protocol_hint, &request_id))
+        .transpose()?;
 
     // Validate proxy protocol invariants (v1).
     let desired_proxy_protocol = req.proxy_protocol.unwrap_or(current.proxy_protocol);
@@ -744,6 +1140,21 @@ async fn update_route(
         .with_request_id(request_id.clone()));
     }
 
+    if route_update_is_noop(&current, &req, access_policy.as_ref()) {
+        return get_route(
+            State(state),
+            ctx,
+            Path((
+                org_id.to_string(),
+                app_id.to_string(),
+                env_id.to_string(),
+                route_id.to_string(),
+            )),
+        )
+        .await
+        .map(IntoResponse::into_response);
+    }
+
     let payload = RouteUpdatedPayload {
         route_id,
         org_id,
@@ -754,6 +1165,7 @@ async fn update_route(
         backend_expects_proxy_protocol: req.backend_expects_proxy_protocol,
         ipv4_required: req.ipv4_required,
         env_ipv4_address: None,
+        access_policy,
     };
 
     let payload = serde_json::to_value(&payload).map_err(|e| {
@@ -792,20 +1204,7 @@ async fn update_route(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "routes",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "routes", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, RouteRow>(
         r#"
@@ -819,6 +1218,17 @@ async fn update_route(
             backend_port,
             proxy_protocol,
             ipv4_required,
+            internal,
+            access_policy,
+            verification_status,
+            verification_token,
+            verification_method,
+            verified_at,
+            verification_error,
+            ingress_live_count,
+            ingress_total_count,
+            ingress_status_at,
+            labels,
             resource_version,
             created_at,
             updated_at
@@ -847,6 +1257,7 @@ async fn update_route(
     })?;
 
     let response = RouteResponse::from(row);
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -871,7 +1282,68 @@ async fn update_route(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
+}
+
+/// Whether `req` leaves every setting of the route as it is.
+/// `access_policy` is the request's validated policy.
+fn route_update_is_noop(
+    current: &RouteState,
+    req: &UpdateRouteRequest,
+    access_policy: Option<&RouteAccessPolicy>,
+) -> bool {
+    let current_policy = current.access_policy.as_ref().filter(|p| !p.is_empty());
+    req.backend_process_type
+        .as_ref()
+        .is_none_or(|v| *v == current.backend_process_type)
+        && req.backend_port.is_none_or(|v| v == current.backend_port)
+        && req
+            .proxy_protocol
+            .is_none_or(|v| v == current.proxy_protocol)
+        && req.ipv4_required.is_none_or(|v| v == current.ipv4_required)
+        && access_policy.is_none_or(|p| Some(p).filter(|p| !p.is_empty()) == current_policy)
+}
+
+/// Restore a deleted route from the trash. Its env must be live.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/restore
+async fn restore_route(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+) -> Result<Json<RestoreResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let route_id: RouteId = route_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_route_id", "Invalid route ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let (app_id, env_id) = (app_id.to_string(), env_id.to_string());
+    let scope = RestoreScope {
+        org_id: &org_id,
+        app_id: Some(&app_id),
+        env_id: Some(&env_id),
+    };
+    trash::restore(&state, &ctx, scope, TrashKind::Route, &route_id.to_string()).await
 }
 
 /// Delete route (idempotent for already-deleted routes).
@@ -881,6 +1353,7 @@ async fn delete_route(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+    preconditions: Preconditions,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
     let idempotency_key = ctx.idempotency_key.clone();
@@ -984,6 +1457,8 @@ async fn delete_route(
         return Ok((StatusCode::OK, Json(response)).into_response());
     }
 
+    preconditions.check(None, current.resource_version, false, &request_id)?;
+
     let next_version = current.resource_version + 1;
     let payload = RouteDeletedPayload {
         route_id,
@@ -1028,20 +1503,10 @@ async fn delete_route(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "routes",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::record_write(&ctx, event_id.value());
+
+    // An uploaded certificate goes with its route.
+    super::route_certificates::remove_with_route(&state, &ctx, &current).await;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -1069,6 +1534,148 @@ async fn delete_route(
     Ok((StatusCode::OK, Json(response)).into_response())
 }
 
+/// Re-check hostname ownership now.
+///
+/// POST /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/routes/{route_id}/verify
+///
+/// Verified routes are returned as-is. Otherwise the DNS check runs inline;
+/// a pass records `route.verification_succeeded`, a miss returns 409 with the
+/// reason. This is the only way back for a route whose verification failed.
+async fn verify_route(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, route_id)): Path<(String, String, String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let route_id: RouteId = route_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_route_id", "Invalid route ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let event_store = state.db().event_store();
+    let Some(current) = load_route_from_events(&event_store, &route_id, &request_id).await? else {
+        return Err(ApiError::not_found("route_not_found", "Route not found")
+            .with_request_id(request_id.clone()));
+    };
+
+    if current.is_deleted
+        || current.org_id != org_id
+        || current.app_id != app_id
+        || current.env_id != env_id
+    {
+        return Err(ApiError::not_found("route_not_found", "Route not found")
+            .with_request_id(request_id.clone()));
+    }
+
+    let token = match current.verification_token.as_deref() {
+        Some(token) if current.verification_status != "verified" => token,
+        _ => return Ok((StatusCode::OK, Json(current.to_response())).into_response()),
+    };
+
+    // A failed route released its hostname; it only comes back if nobody
+    // else has claimed it since.
+    if current.verification_status == "failed" {
+        let taken = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM routes_view
+                WHERE hostname = $1
+                  AND route_id <> $2
+                  AND NOT is_deleted
+                  AND verification_status <> 'failed'
+            )
+            "#,
+        )
+        .bind(&current.hostname)
+        .bind(route_id.to_string())
+        .fetch_one(state.db().pool())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to check hostname uniqueness");
+            ApiError::internal("internal_error", "Failed to verify route")
+                .with_request_id(request_id.clone())
+        })?;
+
+        if taken {
+            return Err(ApiError::conflict(
+                "hostname_in_use",
+                format!("Hostname '{}' is already in use", current.hostname),
+            )
+            .with_request_id(request_id.clone()));
+        }
+    }
+
+    let dns = route_verification::SystemDns::from_system_conf().map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "No DNS resolver for route verification");
+        ApiError::internal("internal_error", "Failed to verify route")
+            .with_request_id(request_id.clone())
+    })?;
+    let suffix = route_verification::cname_suffix();
+    let method = route_verification::check(&dns, &current.hostname, token, suffix.as_deref())
+        .await
+        .map_err(|reason| {
+            ApiError::conflict(
+                "hostname_not_verified",
+                format!("Hostname verification failed: {reason}"),
+            )
+            .with_request_id(request_id.clone())
+        })?;
+
+    let event_id = route_verification::record_success(
+        &event_store,
+        &ctx,
+        route_id,
+        org_id,
+        env_id,
+        &current.hostname,
+        method,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(
+            error = %e,
+            request_id = %request_id,
+            route_id = %route_id,
+            "Failed to record route verification"
+        );
+        ApiError::internal("internal_error", "Failed to verify route")
+            .with_request_id(request_id.clone())
+    })?;
+
+    consistency::wait_for_write(&state, &ctx, "routes", event_id.value()).await?;
+
+    tracing::info!(
+        request_id = %request_id,
+        route_id = %route_id,
+        hostname = %current.hostname,
+        method = verification_method_label(method),
+        "Route hostname verified"
+    );
+
+    let Some(route) = load_route_from_events(&event_store, &route_id, &request_id).await? else {
+        return Err(ApiError::not_found("route_not_found", "Route not found")
+            .with_request_id(request_id.clone()));
+    };
+
+    Ok((StatusCode::OK, Json(route.to_response())).into_response())
+}
+
 // =============================================================================
 // Helpers
 // =============================================================================
@@ -1083,6 +1690,17 @@ struct RouteRow {
     backend_port: i32,
     proxy_protocol: bool,
     ipv4_required: bool,
+    internal: bool,
+    access_policy: Option<serde_json::Value>,
+    verification_status: String,
+    verification_token: Option<String>,
+    verification_method: Option<String>,
+    verified_at: Option<DateTime<Utc>>,
+    verification_error: Option<String>,
+    ingress_live_count: i32,
+    ingress_total_count: i32,
+    ingress_status_at: Option<DateTime<Utc>>,
+    labels: serde_json::Value,
     resource_version: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
@@ -1101,6 +1719,17 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for RouteRow {
             backend_port: row.try_get("backend_port")?,
             proxy_protocol: row.try_get("proxy_protocol")?,
             ipv4_required: row.try_get("ipv4_required")?,
+            internal: row.try_get("internal")?,
+            access_policy: row.try_get("access_policy")?,
+            verification_status: row.try_get("verification_status")?,
+            verification_token: row.try_get("verification_token")?,
+            verification_method: row.try_get("verification_method")?,
+            verified_at: row.try_get("verified_at")?,
+            verification_error: row.try_get("verification_error")?,
+            ingress_live_count: row.try_get("ingress_live_count")?,
+            ingress_total_count: row.try_get("ingress_total_count")?,
+            ingress_status_at: row.try_get("ingress_status_at")?,
+            labels: row.try_get("labels")?,
             resource_version: row.try_get("resource_version")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
@@ -1112,9 +1741,19 @@ impl From<RouteRow> for RouteResponse {
     fn from(row: RouteRow) -> Self {
         let protocol_hint = match row.protocol_hint.as_deref() {
             Some("tls_passthrough") => RouteProtocolHint::TlsPassthrough,
+            Some("tls_terminate") => RouteProtocolHint::TlsTerminate,
             _ => RouteProtocolHint::TcpRaw,
         };
 
+        let verification = RouteVerificationResponse::new(
+            &row.hostname,
+            &row.verification_status,
+            row.verification_token.as_deref(),
+            row.verification_method,
+            row.verified_at,
+            row.verification_error,
+        );
+
         Self {
             id: row.route_id,
             env_id: row.env_id,
@@ -1129,6 +1768,17 @@ impl From<RouteRow> for RouteResponse {
                 RouteProxyProtocol::Off
             },
             ipv4_required: row.ipv4_required,
+            internal: row.internal,
+            access_policy: row
+                .access_policy
+                .and_then(|v| serde_json::from_value(v).ok()),
+            verification,
+            ingress: RouteIngressStatusResponse {
+                live: row.ingress_live_count,
+                total: row.ingress_total_count,
+                updated_at: row.ingress_status_at,
+            },
+            labels: labels::from_json(row.labels),
             created_at: row.created_at,
             updated_at: row.updated_at,
             resource_version: row.resource_version,
@@ -1136,22 +1786,30 @@ impl From<RouteRow> for RouteResponse {
     }
 }
 
-struct RouteState {
-    route_id: RouteId,
-    org_id: OrgId,
-    app_id: AppId,
-    env_id: EnvId,
-    hostname: String,
-    listen_port: i32,
-    protocol_hint: RouteProtocolHint,
-    backend_process_type: String,
-    backend_port: i32,
-    proxy_protocol: RouteProxyProtocol,
-    ipv4_required: bool,
-    created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
-    resource_version: i32,
-    is_deleted: bool,
+pub(super) struct RouteState {
+    pub(super) route_id: RouteId,
+    pub(super) org_id: OrgId,
+    pub(super) app_id: AppId,
+    pub(super) env_id: EnvId,
+    pub(super) hostname: String,
+    pub(super) listen_port: i32,
+    pub(super) protocol_hint: RouteProtocolHint,
+    pub(super) backend_process_type: String,
+    pub(super) backend_port: i32,
+    pub(super) proxy_protocol: RouteProxyProtocol,
+    pub(super) ipv4_required: bool,
+    pub(super) internal: bool,
+    pub(super) access_policy: Option<RouteAccessPolicy>,
+    pub(super) verification_status: &'static str,
+    pub(super) verification_token: Option<String>,
+    pub(super) verification_method: Option<RouteVerificationMethod>,
+    pub(super) verified_at: Option<DateTime<Utc>>,
+    pub(super) verification_error: Option<String>,
+    pub(super) labels: Labels,
+    pub(super) created_at: DateTime<Utc>,
+    pub(super) updated_at: DateTime<Utc>,
+    pub(super) resource_version: i32,
+    pub(super) is_deleted: bool,
 }
 
 impl RouteState {
@@ -1166,6 +1824,24 @@ impl RouteState {
             backend_port: self.backend_port,
             proxy_protocol: self.proxy_protocol,
             ipv4_required: self.ipv4_required,
+            internal: self.internal,
+            access_policy: self.access_policy.clone(),
+            verification: RouteVerificationResponse::new(
+                &self.hostname,
+                self.verification_status,
+                self.verification_token.as_deref(),
+                self.verification_method
+                    .map(|m| verification_method_label(m).to_string()),
+                self.verified_at,
+                self.verification_error.clone(),
+            ),
+            // Ingress reports are not events; the view fills these in.
+            ingress: RouteIngressStatusResponse {
+                live: 0,
+                total: 0,
+                updated_at: None,
+            },
+            labels: self.labels.clone(),
             created_at: self.created_at,
             updated_at: self.updated_at,
             resource_version: self.resource_version,
@@ -1173,7 +1849,7 @@ impl RouteState {
     }
 }
 
-async fn load_route_from_events(
+pub(super) async fn load_route_from_events(
     store: &crate::db::EventStore,
     route_id: &RouteId,
     request_id: &str,
@@ -1218,6 +1894,11 @@ fn fold_route_events(
                             .with_request_id(request_id.to_string())
                     })?;
 
+                let verification_status = if payload.verification_token.is_some() {
+                    "pending"
+                } else {
+                    "verified"
+                };
                 state = Some(RouteState {
                     route_id: payload.route_id,
                     org_id: payload.org_id,
@@ -1230,6 +1911,17 @@ fn fold_route_events(
                     backend_port: payload.backend_port,
                     proxy_protocol: payload.proxy_protocol,
                     ipv4_required: payload.ipv4_required,
+                    internal: payload.internal,
+                    access_policy: payload.access_policy.filter(|p| !p.is_empty()),
+                    verified_at: payload
+                        .verification_token
+                        .is_none()
+                        .then_some(event.occurred_at),
+                    verification_status,
+                    verification_token: payload.verification_token,
+                    verification_method: None,
+                    verification_error: None,
+                    labels: Labels::new(),
                     created_at: event.occurred_at,
                     updated_at: event.occurred_at,
                     resource_version: event.aggregate_seq,
@@ -1266,10 +1958,75 @@ fn fold_route_events(
                 if let Some(v) = payload.ipv4_required {
                     s.ipv4_required = v;
                 }
+                if let Some(v) = payload.access_policy {
+                    s.access_policy = (!v.is_empty()).then_some(v);
+                }
 
                 s.updated_at = event.occurred_at;
                 s.resource_version = event.aggregate_seq;
             }
+            "route.labels_updated" => {
+                let payload: RouteLabelsUpdatedPayload =
+                    serde_json::from_value(event.payload.clone()).map_err(|e| {
+                        tracing::error!(
+                            error = %e,
+                            request_id = %request_id,
+                            route_id = %route_id,
+                            "Invalid route.labels_updated payload"
+                        );
+                        ApiError::internal("internal_error", "Invalid route event payload")
+                            .with_request_id(request_id.to_string())
+                    })?;
+
+                let Some(s) = state.as_mut() else { continue };
+                if payload.org_id != s.org_id || payload.env_id != s.env_id {
+                    continue;
+                }
+
+                s.labels = payload.labels;
+                s.updated_at = event.occurred_at;
+                s.resource_version = event.aggregate_seq;
+            }
+            "route.verification_succeeded" => {
+                let payload: RouteVerificationSucceededPayload =
+                    serde_json::from_value(event.payload.clone()).map_err(|e| {
+                        tracing::error!(
+                            error = %e,
+                            request_id = %request_id,
+                            route_id = %route_id,
+                            "Invalid route.verification_succeeded payload"
+                        );
+                        ApiError::internal("internal_error", "Invalid route event payload")
+                            .with_request_id(request_id.to_string())
+                    })?;
+
+                let Some(s) = state.as_mut() else { continue };
+                s.verification_status = "verified";
+                s.verification_method = Some(payload.method);
+                s.verified_at = Some(event.occurred_at);
+                s.verification_error = None;
+                s.updated_at = event.occurred_at;
+                s.resource_version = event.aggregate_seq;
+            }
+            "route.verification_failed" => {
+                let payload: RouteVerificationFailedPayload =
+                    serde_json::from_value(event.payload.clone()).map_err(|e| {
+                        tracing::error!(
+                            error = %e,
+                            request_id = %request_id,
+                            route_id = %route_id,
+                            "Invalid route.verification_failed payload"
+                        );
+                        ApiError::internal("internal_error", "Invalid route event payload")
+                            .with_request_id(request_id.to_string())
+                    })?;
+
+                let Some(s) = state.as_mut() else { continue };
+                s.verification_status = "failed";
+                s.verification_error = Some(payload.reason);
+                s.updated_at = event.occurred_at;
+                s.resource_version = event.aggregate_seq;
+            }
             "route.deleted" => {
                 let payload: RouteDeletedPayload = serde_json::from_value(event.payload.clone())
                     .map_err(|e| {
@@ -1292,6 +2049,12 @@ fn fold_route_events(
                 s.updated_at = event.occurred_at;
                 s.resource_version = event.aggregate_seq;
             }
+            "route.restored" => {
+                let Some(s) = state.as_mut() else { continue };
+                s.is_deleted = false;
+                s.updated_at = event.occurred_at;
+                s.resource_version = event.aggregate_seq;
+            }
             _ => {}
         }
     }
@@ -1299,6 +2062,13 @@ fn fold_route_events(
     Ok(state)
 }
 
+fn verification_method_label(method: RouteVerificationMethod) -> &'static str {
+    match method {
+        RouteVerificationMethod::Txt => "txt",
+        RouteVerificationMethod::Cname => "cname",
+    }
+}
+
 fn validate_hostname(hostname: &str, request_id: &str) -> Result<(), ApiError> {
     if hostname.trim().is_empty() {
         return Err(
@@ -1337,3 +2107,14 @@ fn validate_port(port: i32, field: &str, request_id: &str) -> Result<(), ApiErro
 
     Ok(())
 }
+
+fn validate_access_policy(
+    policy: &RouteAccessPolicy,
+    protocol_hint: RouteProtocolHint,
+    request_id: &str,
+) -> Result<RouteAccessPolicy, ApiError> {
+    route_access::normalize(policy, protocol_hint).map_err(|message| {
+        ApiError::bad_request("invalid_access_policy", message)
+            .with_request_id(request_id.to_string())
+    })
+}
@@ -0,0 +1,93 @@
+//! Path resolution shared by env-scoped endpoints
+//! (`/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/...`).
+//!
+//! Handlers extract the path as an [`EnvPath`] and call [`resolve_env`],
+//! which parses the IDs, checks org membership and checks that the env
+//! exists under the app.
+
+use std::fmt::Display;
+
+use plfm_events::MemberRole;
+use plfm_id::{AppId, EnvId, OrgId};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+/// Raw `(org_id, app_id, env_id)` path segments.
+pub(super) type EnvPath = (String, String, String);
+
+/// Which envs resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum EnvState {
+    /// Only envs that are not deleted.
+    Live,
+    /// Deleted envs too, for endpoints that serve history.
+    Any,
+}
+
+/// Parse the path IDs, check membership, and check the env exists.
+pub(super) async fn resolve_env(
+    state: &AppState,
+    ctx: &RequestContext,
+    path: EnvPath,
+    env_state: EnvState,
+) -> Result<(OrgId, AppId, EnvId, MemberRole), ApiError> {
+    let request_id = ctx.request_id.as_str();
+    let (org_id, app_id, env_id) = parse_env_path(path, request_id)?;
+
+    let role = authz::require_org_member(state, &org_id, ctx).await?;
+
+    let env_exists: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM envs_view
+            WHERE env_id = $1 AND org_id = $2 AND app_id = $3
+              AND ($4 OR NOT is_deleted)
+        )
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .bind(env_state == EnvState::Any)
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| internal(e, request_id, "Failed to load environment"))?;
+    if !env_exists {
+        return Err(ApiError::not_found(
+            "env_not_found",
+            format!("Environment {} not found", env_id),
+        )
+        .with_request_id(request_id.to_string()));
+    }
+
+    Ok((org_id, app_id, env_id, role))
+}
+
+fn parse_env_path(
+    (org_id, app_id, env_id): EnvPath,
+    request_id: &str,
+) -> Result<(OrgId, AppId, EnvId), ApiError> {
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.to_string())
+    })?;
+    Ok((org_id, app_id, env_id))
+}
+
+/// Log an unexpected failure and turn it into a 500 with `message`.
+pub(super) fn internal(error: impl Display, request_id: &str, message: &str) -> ApiError {
+    tracing::error!(error = %error, request_id = %request_id, "{message}");
+    ApiError::internal("internal_error", message.to_string())
+        .with_request_id(request_id.to_string())
+}
@@ -0,0 +1,313 @@
+//! Org-wide search API endpoint.
+//!
+//! Searches apps, envs, releases, routes, volumes, and instances by ID or
+//! name prefix, backed by search_index_view.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use plfm_id::{AnyResourceId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+const MAX_QUERY_LEN: usize = 100;
+
+/// Query parameters for searching.
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    /// Case-insensitive ID or name prefix.
+    pub q: Option<String>,
+    /// Comma-separated resource types to restrict results to.
+    pub types: Option<String>,
+    /// Max number of results to return.
+    pub limit: Option<i64>,
+}
+
+/// Resource types covered by search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchResourceType {
+    App,
+    Env,
+    Release,
+    Route,
+    Volume,
+    Instance,
+}
+
+impl SearchResourceType {
+    fn as_str(self) -> &'static str {
+        match self {
+            SearchResourceType::App => "app",
+            SearchResourceType::Env => "env",
+            SearchResourceType::Release => "release",
+            SearchResourceType::Route => "route",
+            SearchResourceType::Volume => "volume",
+            SearchResourceType::Instance => "instance",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "app" => Some(SearchResourceType::App),
+            "env" => Some(SearchResourceType::Env),
+            "release" => Some(SearchResourceType::Release),
+            "route" => Some(SearchResourceType::Route),
+            "volume" => Some(SearchResourceType::Volume),
+            "instance" => Some(SearchResourceType::Instance),
+            _ => None,
+        }
+    }
+}
+
+/// Which field of the resource matched the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMatch {
+    Id,
+    Name,
+}
+
+/// A single search hit.
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    #[serde(rename = "type")]
+    pub resource_type: SearchResourceType,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    pub matched: SearchMatch,
+}
+
+/// Response for searching.
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub items: Vec<SearchResult>,
+}
+
+/// Search resources in an org by ID or name prefix.
+///
+/// Results are ordered by relevance: exact ID, exact name, ID prefix, then
+/// name prefix; ties prefer shorter names.
+///
+/// GET /v1/orgs/{org_id}/search?q=
+pub async fn search(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let q = query.q.as_deref().map(str::trim).unwrap_or_default();
+    if q.is_empty() || q.chars().count() > MAX_QUERY_LEN {
+        return Err(ApiError::bad_request(
+            "invalid_query",
+            format!("q must be 1-{MAX_QUERY_LEN} characters"),
+        )
+        .with_request_id(request_id));
+    }
+
+    let types = parse_types(query.types.as_deref()).map_err(|message| {
+        ApiError::bad_request("invalid_types", message).with_request_id(request_id.clone())
+    })?;
+    let types = types.or_else(|| id_type(q).map(|t| vec![t]));
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let type_filter: Option<Vec<String>> =
+        types.map(|types| types.iter().map(|t| t.as_str().to_string()).collect());
+    let pattern = format!("{}%", escape_like(&q.to_lowercase()));
+
+    let rows = sqlx::query_as::<
+        _,
+        (
+            String,
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            i32,
+        ),
+    >(
+        r#"
+        SELECT resource_type, resource_id, name, app_id, env_id, rank
+        FROM (
+            SELECT resource_type, resource_id, name, app_id, env_id,
+                   CASE
+                       WHEN lower(resource_id) = $2 THEN 0
+                       WHEN lower(name) = $2 THEN 1
+                       WHEN lower(resource_id) LIKE $3 ESCAPE '\' THEN 2
+                       ELSE 3
+                   END AS rank
+            FROM search_index_view
+            WHERE org_id = $1
+              AND (lower(resource_id) LIKE $3 ESCAPE '\' OR lower(name) LIKE $3 ESCAPE '\')
+              AND ($4::TEXT[] IS NULL OR resource_type = ANY($4))
+        ) hits
+        ORDER BY rank, length(name) NULLS LAST, resource_type, resource_id
+        LIMIT $5
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(q.to_lowercase())
+    .bind(&pattern)
+    .bind(type_filter)
+    .bind(limit)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(
+            error = %e,
+            request_id = %request_id,
+            org_id = %org_id,
+            "Failed to search resources"
+        );
+        ApiError::internal("internal_error", "Failed to search resources")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let items = rows
+        .into_iter()
+        .filter_map(|(resource_type, id, name, app_id, env_id, rank)| {
+            Some(SearchResult {
+                resource_type: SearchResourceType::parse(&resource_type)?,
+                id,
+                name,
+                app_id,
+                env_id,
+                matched: if rank == 0 || rank == 2 {
+                    SearchMatch::Id
+                } else {
+                    SearchMatch::Name
+                },
+            })
+        })
+        .collect();
+
+    Ok(Json(SearchResponse { items }))
+}
+
+/// Parse the comma-separated `types` filter; `None` means all types.
+fn parse_types(raw: Option<&str>) -> Result<Option<Vec<SearchResourceType>>, String> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    let mut types = Vec::new();
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parsed = SearchResourceType::parse(part).ok_or_else(|| {
+            format!(
+                "unknown resource type '{part}' (expected app, env, release, route, volume, or instance)"
+            )
+        })?;
+        if !types.contains(&parsed) {
+            types.push(parsed);
+        }
+    }
+
+    if types.is_empty() {
+        return Err("types cannot be empty".to_string());
+    }
+    Ok(Some(types))
+}
+
+/// The searchable type of a query that is a complete resource ID.
+///
+/// Such a query only matches that type's index rows, so search can skip the
+/// others when no `types` filter was given.
+fn id_type(q: &str) -> Option<SearchResourceType> {
+    let id = AnyResourceId::parse(q).ok()?;
+    SearchResourceType::parse(id.kind())
+}
+
+/// Escape LIKE wildcards so user input only ever matches literally.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("web"), "web");
+        assert_eq!(escape_like("app_01%"), "app\\_01\\%");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+    }
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(parse_types(None).unwrap(), None);
+        assert_eq!(
+            parse_types(Some("app, route,app")).unwrap(),
+            Some(vec![SearchResourceType::App, SearchResourceType::Route])
+        );
+        assert!(parse_types(Some("app,deploy")).is_err());
+        assert!(parse_types(Some(" , ")).is_err());
+    }
+
+    #[test]
+    fn test_id_type() {
+        let ulid = "01HV4Z2WQXKJNM8GPQY6VBKC3D";
+        assert_eq!(
+            id_type(&format!("rt_{ulid}")),
+            Some(SearchResourceType::Route)
+        );
+        assert_eq!(
+            id_type(&format!("inst_{}", ulid.to_lowercase())),
+            Some(SearchResourceType::Instance)
+        );
+        assert_eq!(id_type(&format!("dep_{ulid}")), None);
+        assert_eq!(id_type("app_01HV"), None);
+        assert_eq!(id_type("web"), None);
+    }
+
+    #[test]
+    fn test_search_result_serialization() {
+        let result = SearchResult {
+            resource_type: SearchResourceType::Route,
+            id: "rt_123".to_string(),
+            name: Some("api.example.com".to_string()),
+            app_id: Some("app_123".to_string()),
+            env_id: None,
+            matched: SearchMatch::Name,
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "type": "route",
+                "id": "rt_123",
+                "name": "api.example.com",
+                "app_id": "app_123",
+                "matched": "name"
+            })
+        );
+    }
+}
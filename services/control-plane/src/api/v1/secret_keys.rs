@@ -0,0 +1,395 @@
+//! Operator endpoints for secrets key encryption keys.
+//!
+//! - `GET /v1/_admin/secrets/keys`: configured provider, current key IDs, and
+//!   how many rows each stored key ID still wraps
+//! - `POST /v1/_admin/secrets/rewrap`: re-wrap one batch of data keys under
+//!   the current KEK, resumable by cursor
+//! - `/v1/_admin/secrets/rotations`: start, inspect, and cancel background
+//!   key rotation jobs that re-wrap everything in batches
+//!
+//! See: docs/security/03-secret-handling.md
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use plfm_id::OrgId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::secrets::jobs::{self, JobError, KeyRotationJob};
+use crate::secrets::kms::Provider;
+use crate::secrets::rotation::{self, RewrapReport, RotationError};
+use crate::secrets::{self as secrets_crypto, KeyScope};
+use crate::state::AppState;
+
+const DEFAULT_REWRAP_LIMIT: i64 = 100;
+const MAX_REWRAP_LIMIT: i64 = 1000;
+
+/// Operator endpoints, merged into /v1/_admin.
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/secrets/keys", get(get_keys))
+        .route("/secrets/rewrap", post(rewrap))
+        .route(
+            "/secrets/rotations",
+            post(start_rotation).get(list_rotations),
+        )
+        .route("/secrets/rotations/{job_id}", get(get_rotation))
+        .route("/secrets/rotations/{job_id}/cancel", post(cancel_rotation))
+}
+
+#[derive(Debug, Serialize)]
+struct KeyUsageResponse {
+    master_key_id: String,
+    secret_material: i64,
+    pki_authorities: i64,
+    /// Whether new platform material is wrapped under this key.
+    current: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct KeysResponse {
+    provider: &'static str,
+    current_platform_key_id: String,
+    items: Vec<KeyUsageResponse>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RewrapRequest {
+    /// Only re-wrap this org's secret material.
+    #[serde(default)]
+    org_id: Option<String>,
+    /// Resume after this material ID (`next_cursor` of the previous call).
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct RewrapResponse {
+    scanned: u64,
+    rewrapped: u64,
+    failed: u64,
+    /// Pass back as `cursor` to continue; absent when done.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+    /// Platform CA and identity token keys re-wrapped (first batch of a full
+    /// pass only).
+    pki_authorities_rewrapped: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StartRotationRequest {
+    /// Only re-wrap this org's secret material.
+    #[serde(default)]
+    org_id: Option<String>,
+    /// Rows per batch (default 100, max 1000).
+    #[serde(default)]
+    batch_size: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListRotationsQuery {
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct RotationJobResponse {
+    job_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    org_id: Option<String>,
+    /// `running`, `completed`, `failed`, or `cancelled`.
+    state: String,
+    target_key_id: String,
+    batch_size: i32,
+    /// Rows in scope when the job started.
+    total: i64,
+    scanned: i64,
+    rewrapped: i64,
+    failed: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<String>,
+    requested_by: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<KeyRotationJob> for RotationJobResponse {
+    fn from(job: KeyRotationJob) -> Self {
+        Self {
+            job_id: job.job_id,
+            org_id: job.org_id,
+            state: job.state,
+            target_key_id: job.target_key_id,
+            batch_size: job.batch_size,
+            total: job.total,
+            scanned: job.scanned,
+            rewrapped: job.rewrapped,
+            failed: job.failed,
+            last_error: job.last_error,
+            requested_by: job.requested_by,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListRotationsResponse {
+    items: Vec<RotationJobResponse>,
+}
+
+fn job_error(error: JobError, request_id: &str) -> ApiError {
+    match error {
+        JobError::AlreadyRunning => ApiError::conflict(
+            "key_rotation_in_progress",
+            "A key rotation job is already running",
+        ),
+        JobError::Crypto(e) => {
+            tracing::error!(error = %e, request_id = %request_id, "Key rotation unavailable");
+            ApiError::internal(
+                "secrets_rewrap_failed",
+                "Secrets key encryption keys are unavailable",
+            )
+        }
+        other => {
+            tracing::error!(error = %other, request_id = %request_id, "Key rotation job query failed");
+            ApiError::internal("internal_error", "Failed to manage key rotation job")
+        }
+    }
+    .with_request_id(request_id.to_string())
+}
+
+fn rotation_error(error: RotationError, request_id: &str) -> ApiError {
+    tracing::error!(error = %error, request_id = %request_id, "Secrets key operation failed");
+    match error {
+        RotationError::Crypto(_) => ApiError::internal(
+            "secrets_rewrap_failed",
+            "Secrets key encryption keys are unavailable",
+        ),
+        RotationError::Database(_) => {
+            ApiError::internal("internal_error", "Failed to re-wrap secrets")
+        }
+    }
+    .with_request_id(request_id.to_string())
+}
+
+/// GET /v1/_admin/secrets/keys
+async fn get_keys(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let provider = Provider::configured().map_err(|e| rotation_error(e.into(), &request_id))?;
+    let current = secrets_crypto::current_key_id(KeyScope::Platform)
+        .map_err(|e| rotation_error(e.into(), &request_id))?;
+    let usage = rotation::key_usage(state.db().pool())
+        .await
+        .map_err(|e| rotation_error(e.into(), &request_id))?;
+
+    Ok(Json(KeysResponse {
+        provider: provider.as_str(),
+        items: usage
+            .into_iter()
+            .map(|u| KeyUsageResponse {
+                current: u.master_key_id == current,
+                master_key_id: u.master_key_id,
+                secret_material: u.secret_material,
+                pki_authorities: u.pki_authorities,
+            })
+            .collect(),
+        current_platform_key_id: current,
+    }))
+}
+
+/// POST /v1/_admin/secrets/rewrap
+async fn rewrap(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    body: Option<Json<RewrapRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+
+    if let Some(org_id) = &req.org_id {
+        org_id.parse::<OrgId>().map_err(|_| {
+            ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+                .with_request_id(request_id.clone())
+        })?;
+    }
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_REWRAP_LIMIT)
+        .clamp(1, MAX_REWRAP_LIMIT);
+
+    // CA and token keys are platform-scoped; re-wrap them at the start of a full pass.
+    let pki_authorities_rewrapped = if req.org_id.is_none() && req.cursor.is_none() {
+        rotation::rewrap_pki_authorities(state.db().pool())
+            .await
+            .map_err(|e| rotation_error(e, &request_id))?
+            .rewrapped
+    } else {
+        0
+    };
+
+    let RewrapReport {
+        scanned,
+        rewrapped,
+        failed,
+        next_cursor,
+    } = rotation::rewrap_secret_material(
+        state.db().pool(),
+        req.org_id.as_deref(),
+        req.cursor.as_deref(),
+        limit,
+    )
+    .await
+    .map_err(|e| rotation_error(e, &request_id))?;
+
+    tracing::info!(
+        org_id = ?req.org_id,
+        scanned,
+        rewrapped,
+        failed,
+        pki_authorities_rewrapped,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Re-wrapped secrets data keys"
+    );
+
+    Ok(Json(RewrapResponse {
+        scanned,
+        rewrapped,
+        failed,
+        next_cursor,
+        pki_authorities_rewrapped,
+    }))
+}
+
+/// Start a background job that re-wraps all data keys under the current KEK.
+///
+/// POST /v1/_admin/secrets/rotations
+async fn start_rotation(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    body: Option<Json<StartRotationRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+
+    let org_id = req
+        .org_id
+        .as_deref()
+        .map(|id| {
+            id.parse::<OrgId>().map_err(|_| {
+                ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+                    .with_request_id(request_id.clone())
+            })
+        })
+        .transpose()?;
+
+    let job = jobs::start(
+        state.db().pool(),
+        &ctx,
+        org_id,
+        req.batch_size.unwrap_or(jobs::DEFAULT_BATCH_SIZE),
+    )
+    .await
+    .map_err(|e| job_error(e, &request_id))?;
+
+    tracing::info!(
+        job_id = %job.job_id,
+        org_id = ?job.org_id,
+        total = job.total,
+        actor_id = %ctx.actor_id,
+        request_id = %request_id,
+        "Key rotation job started"
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(RotationJobResponse::from(job))))
+}
+
+/// GET /v1/_admin/secrets/rotations
+async fn list_rotations(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Query(query): Query<ListRotationsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let jobs = jobs::list(state.db().pool(), query.limit.unwrap_or(20).clamp(1, 100))
+        .await
+        .map_err(|e| job_error(e.into(), &request_id))?;
+
+    Ok(Json(ListRotationsResponse {
+        items: jobs.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /v1/_admin/secrets/rotations/{job_id}
+async fn get_rotation(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(job_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id;
+
+    let job = jobs::get(state.db().pool(), &job_id)
+        .await
+        .map_err(|e| job_error(e.into(), &request_id))?
+        .ok_or_else(|| {
+            ApiError::not_found("key_rotation_not_found", "Key rotation job not found")
+                .with_request_id(request_id.clone())
+        })?;
+
+    Ok(Json(RotationJobResponse::from(job)))
+}
+
+/// Stop a running job. Re-wrapped rows stay re-wrapped.
+///
+/// POST /v1/_admin/secrets/rotations/{job_id}/cancel
+async fn cancel_rotation(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(job_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authz::require_operator(&ctx)?;
+    let request_id = ctx.request_id.clone();
+
+    if let Some(job) = jobs::cancel(state.db().pool(), &ctx, &job_id)
+        .await
+        .map_err(|e| job_error(e, &request_id))?
+    {
+        return Ok(Json(RotationJobResponse::from(job)));
+    }
+
+    let exists = jobs::get(state.db().pool(), &job_id)
+        .await
+        .map_err(|e| job_error(e.into(), &request_id))?
+        .is_some();
+    Err(if exists {
+        ApiError::conflict(
+            "key_rotation_not_running",
+            "Key rotation job has already finished",
+        )
+    } else {
+        ApiError::not_found("key_rotation_not_found", "Key rotation job not found")
+    }
+    .with_request_id(request_id))
+}
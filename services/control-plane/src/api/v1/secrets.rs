@@ -19,8 +19,10 @@ use plfm_secrets_format::Secrets;
 use sha2::{Digest, Sha256};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
+use crate::api::preconditions::{self, Preconditions};
 use crate::api::request_context::RequestContext;
 use crate::db::AppendEvent;
 use crate::secrets as secrets_crypto;
@@ -44,6 +46,8 @@ pub struct SecretsMetadataResponse {
     pub env_id: String,
     pub bundle_id: String,
     pub current_version_id: String,
+    /// Resource version for optimistic concurrency.
+    pub resource_version: i32,
     pub updated_at: DateTime<Utc>,
 }
 
@@ -130,7 +134,7 @@ async fn get_secrets_metadata(
 
     let row = sqlx::query_as::<_, SecretBundleRow>(
         r#"
-        SELECT bundle_id, current_version_id, updated_at
+        SELECT bundle_id, current_version_id, resource_version, updated_at
         FROM secret_bundles_view
         WHERE org_id = $1 AND app_id = $2 AND env_id = $3
         "#,
@@ -169,12 +173,16 @@ async fn get_secrets_metadata(
         .with_request_id(request_id));
     };
 
-    Ok(Json(SecretsMetadataResponse {
-        env_id: env_id_typed.to_string(),
-        bundle_id: row.bundle_id,
-        current_version_id,
-        updated_at: row.updated_at,
-    }))
+    Ok(preconditions::with_etag(
+        row.resource_version,
+        Json(SecretsMetadataResponse {
+            env_id: env_id_typed.to_string(),
+            bundle_id: row.bundle_id,
+            current_version_id,
+            resource_version: row.resource_version,
+            updated_at: row.updated_at,
+        }),
+    ))
 }
 
 /// Set secrets for an environment (creates a new version).
@@ -184,6 +192,7 @@ async fn put_secrets(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
+    preconditions: Preconditions,
     Json(req): Json<PutSecretsRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
@@ -284,7 +293,7 @@ async fn put_secrets(
 
     let existing = sqlx::query_as::<_, SecretBundleExistingRow>(
         r#"
-        SELECT bundle_id
+        SELECT bundle_id, resource_version
         FROM secret_bundles_view
         WHERE org_id = $1 AND app_id = $2 AND env_id = $3
         "#,
@@ -310,6 +319,13 @@ async fn put_secrets(
     let now = Utc::now();
     let version_id = SecretVersionId::new();
 
+    match existing.as_ref() {
+        Some(existing) => {
+            preconditions.check(None, existing.resource_version, false, &request_id)?
+        }
+        None => preconditions.check_absent(&request_id)?,
+    }
+
     let (bundle_id, event_ids) = if let Some(existing) = existing {
         let bundle_id: SecretBundleId = existing.bundle_id.parse().map_err(|_| {
             ApiError::internal("internal_error", "Corrupt secret bundle state")
@@ -505,24 +521,11 @@ async fn put_secrets(
         .copied()
         .ok_or_else(|| ApiError::internal("internal_error", "Failed to set secrets"))?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "secret_bundles",
-            last_event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "secret_bundles", last_event_id.value()).await?;
 
     let updated = sqlx::query_as::<_, SecretBundleRow>(
         r#"
-        SELECT bundle_id, current_version_id, updated_at
+        SELECT bundle_id, current_version_id, resource_version, updated_at
         FROM secret_bundles_view
         WHERE org_id = $1 AND app_id = $2 AND env_id = $3
         "#,
@@ -557,6 +560,7 @@ async fn put_secrets(
         env_id: env_id_typed.to_string(),
         bundle_id: bundle_id.to_string(),
         current_version_id,
+        resource_version: updated.resource_version,
         updated_at: updated.updated_at,
     };
 
@@ -583,7 +587,10 @@ async fn put_secrets(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response_body)).into_response())
+    Ok(preconditions::with_etag(
+        updated.resource_version,
+        (StatusCode::OK, Json(response_body)),
+    ))
 }
 
 // =============================================================================
@@ -681,7 +688,14 @@ async fn store_secret_material(
     request_id: &str,
 ) -> Result<(), ApiError> {
     let aad = secrets_aad(org_id, env_id, bundle_id, version_id, data_hash);
-    let encrypted = secrets_crypto::encrypt(plaintext, aad.as_bytes()).map_err(|e| {
+    let org_id_str = org_id.to_string();
+    let encrypted = secrets_crypto::encrypt(
+        secrets_crypto::KeyScope::Org(&org_id_str),
+        plaintext,
+        aad.as_bytes(),
+    )
+    .await
+    .map_err(|e| {
         tracing::error!(
             error = %e,
             request_id = %request_id,
@@ -765,6 +779,7 @@ async fn store_secret_material(
 struct SecretBundleRow {
     bundle_id: String,
     current_version_id: Option<String>,
+    resource_version: i32,
     updated_at: DateTime<Utc>,
 }
 
@@ -774,6 +789,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for SecretBundleRow {
         Ok(Self {
             bundle_id: row.try_get("bundle_id")?,
             current_version_id: row.try_get("current_version_id")?,
+            resource_version: row.try_get("resource_version")?,
             updated_at: row.try_get("updated_at")?,
         })
     }
@@ -782,6 +798,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for SecretBundleRow {
 #[derive(Debug)]
 struct SecretBundleExistingRow {
     bundle_id: String,
+    resource_version: i32,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for SecretBundleExistingRow {
@@ -789,6 +806,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for SecretBundleExistingRow {
         use sqlx::Row;
         Ok(Self {
             bundle_id: row.try_get("bundle_id")?,
+            resource_version: row.try_get("resource_version")?,
         })
     }
 }
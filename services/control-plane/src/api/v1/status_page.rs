@@ -0,0 +1,366 @@
+//! Org status page endpoints.
+//!
+//! - `/v1/orgs/{org_id}/status/page`: page settings and its public token
+//!   (admin role)
+//! - `/v1/orgs/{org_id}/status/public?token=...`: the public summary, as
+//!   JSON or (`format=html`) a rendered page. It needs no credentials, only
+//!   the page's current token; a missing page, a disabled page, and a wrong
+//!   token are all `404 status_page_not_found`, so the endpoint does not
+//!   reveal which orgs have a page.
+//!
+//! See: docs/specs/observability/status-pages.md
+
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use plfm_id::{EnvId, OrgId};
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::api::validation::{ValidJson, Validate, Validator};
+use crate::state::AppState;
+use crate::status_page::{self, PageSpec, StatusPage, StatusPageError};
+
+const MAX_TITLE_LEN: usize = 100;
+const MAX_ENVS: usize = 100;
+
+/// Public responses may be cached briefly by browsers and CDNs.
+const PUBLIC_CACHE_CONTROL: &str = "public, max-age=30";
+
+/// Status page routes, nested under /v1/orgs/{org_id}/status.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/public", get(public_status))
+        .route("/page", get(get_page).put(put_page).delete(delete_page))
+        .route("/page/token", post(rotate_token))
+}
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct PageRequest {
+    title: String,
+    /// Envs to show; empty or omitted shows every env in the org.
+    #[serde(default)]
+    env_ids: Vec<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+impl Validate for PageRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            !self.title.trim().is_empty() && self.title.len() <= MAX_TITLE_LEN,
+            "invalid_status_page",
+            "title",
+            &format!("title must be 1-{MAX_TITLE_LEN} characters"),
+        );
+        v.check(
+            self.env_ids.len() <= MAX_ENVS,
+            "invalid_status_page",
+            "env_ids",
+            &format!("at most {MAX_ENVS} environments can be shown"),
+        );
+        v.check(
+            self.env_ids.iter().all(|id| id.parse::<EnvId>().is_ok()),
+            "invalid_status_page",
+            "env_ids",
+            "Invalid environment ID format",
+        );
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PageResponse {
+    org_id: String,
+    title: String,
+    env_ids: Vec<String>,
+    enabled: bool,
+    /// Current public token; rotate it to revoke shared links.
+    token: String,
+    /// Path of the public summary, token included.
+    public_path: String,
+    created_by: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<StatusPage> for PageResponse {
+    fn from(page: StatusPage) -> Self {
+        let token = page.token();
+        Self {
+            public_path: format!("/v1/orgs/{}/status/public?token={token}", page.org_id),
+            token,
+            org_id: page.org_id,
+            title: page.title,
+            env_ids: page.env_ids,
+            enabled: page.enabled,
+            created_by: page.created_by,
+            created_at: page.created_at,
+            updated_at: page.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicQuery {
+    #[serde(default)]
+    token: String,
+    /// `json` (default) or `html`.
+    format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeleteResponse {
+    ok: bool,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn parse_org_id(org_id: &str, request_id: &str) -> Result<OrgId, ApiError> {
+    org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.to_string())
+    })
+}
+
+fn internal(error: impl Into<StatusPageError>, request_id: &str) -> ApiError {
+    let error = error.into();
+    tracing::error!(error = %error, request_id = %request_id, "Status page request failed");
+    ApiError::internal("internal_error", "Status page request failed")
+        .with_request_id(request_id.to_string())
+}
+
+fn page_not_found(request_id: &str) -> ApiError {
+    ApiError::not_found("status_page_not_found", "Status page not found")
+        .with_request_id(request_id.to_string())
+}
+
+/// Check that every env shown belongs to the org.
+async fn check_envs(
+    state: &AppState,
+    org_id: &OrgId,
+    env_ids: &[String],
+    request_id: &str,
+) -> Result<(), ApiError> {
+    if env_ids.is_empty() {
+        return Ok(());
+    }
+    let known: Vec<String> = sqlx::query_scalar(
+        "SELECT env_id FROM envs_view WHERE org_id = $1 AND env_id = ANY($2) AND NOT is_deleted",
+    )
+    .bind(org_id.to_string())
+    .bind(env_ids)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| internal(e, request_id))?;
+
+    match env_ids.iter().find(|id| !known.contains(id)) {
+        Some(missing) => Err(ApiError::bad_request(
+            "invalid_status_page",
+            format!("Environment {missing} not found in this org"),
+        )
+        .with_request_id(request_id.to_string())),
+        None => Ok(()),
+    }
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// GET /v1/orgs/{org_id}/status/public
+async fn public_status(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    Query(query): Query<PublicQuery>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let html = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("html") => true,
+        Some(_) => {
+            return Err(
+                ApiError::bad_request("invalid_request", "format must be json or html")
+                    .with_request_id(request_id),
+            )
+        }
+    };
+    let org_id: OrgId = org_id.parse().map_err(|_| page_not_found(&request_id))?;
+
+    let pool = state.db().pool();
+    let page = status_page::get_page(pool, &org_id.to_string())
+        .await
+        .map_err(|e| internal(e, &request_id))?
+        .filter(|page| page.enabled && page.verify_token(&query.token))
+        .ok_or_else(|| page_not_found(&request_id))?;
+
+    let status = status_page::public_status(pool, &page)
+        .await
+        .map_err(|e| internal(e, &request_id))?;
+
+    let cache = [(header::CACHE_CONTROL, PUBLIC_CACHE_CONTROL)];
+    Ok(if html {
+        (cache, Html(status_page::html::render(&status))).into_response()
+    } else {
+        (cache, Json(status)).into_response()
+    })
+}
+
+/// GET /v1/orgs/{org_id}/status/page
+async fn get_page(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let page = status_page::get_page(state.db().pool(), &org_id.to_string())
+        .await
+        .map_err(|e| internal(e, &request_id))?
+        .ok_or_else(|| page_not_found(&request_id))?;
+
+    Ok(Json(PageResponse::from(page)))
+}
+
+/// Create or replace the page. Its token is kept across updates.
+///
+/// PUT /v1/orgs/{org_id}/status/page
+async fn put_page(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    ValidJson(req): ValidJson<PageRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    check_envs(&state, &org_id, &req.env_ids, &request_id).await?;
+    let spec = PageSpec {
+        title: req.title.trim().to_string(),
+        env_ids: req.env_ids,
+        enabled: req.enabled,
+    };
+    let page = status_page::put_page(state.db().pool(), &org_id.to_string(), &spec, &ctx.actor_id)
+        .await
+        .map_err(|e| internal(e, &request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        enabled = page.enabled,
+        actor_id = %ctx.actor_id,
+        "Status page updated"
+    );
+
+    Ok(Json(PageResponse::from(page)))
+}
+
+/// Issue a new token, revoking the current one.
+///
+/// POST /v1/orgs/{org_id}/status/page/token
+async fn rotate_token(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let page = status_page::rotate_token(state.db().pool(), &org_id.to_string())
+        .await
+        .map_err(|e| internal(e, &request_id))?
+        .ok_or_else(|| page_not_found(&request_id))?;
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        actor_id = %ctx.actor_id,
+        "Status page token rotated"
+    );
+
+    Ok(Json(PageResponse::from(page)))
+}
+
+/// DELETE /v1/orgs/{org_id}/status/page
+async fn delete_page(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let org_id = parse_org_id(&org_id, &request_id)?;
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_admin(role, &request_id)?;
+
+    let deleted = status_page::delete_page(state.db().pool(), &org_id.to_string())
+        .await
+        .map_err(|e| internal(e, &request_id))?;
+    if !deleted {
+        return Err(page_not_found(&request_id));
+    }
+
+    tracing::info!(
+        request_id = %request_id,
+        org_id = %org_id,
+        actor_id = %ctx.actor_id,
+        "Status page deleted"
+    );
+
+    Ok(Json(DeleteResponse { ok: true }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::validation::validate;
+
+    fn page_request(body: serde_json::Value) -> PageRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_page_request_validation() {
+        let valid = page_request(json!({
+            "title": "Acme status",
+            "env_ids": [EnvId::new().to_string()],
+        }));
+        assert!(validate(&valid).is_ok());
+        assert!(valid.enabled);
+
+        let err = validate(&page_request(json!({"title": "  "}))).unwrap_err();
+        assert_eq!(err.problem.code, "invalid_status_page");
+
+        let err = validate(&page_request(json!({
+            "title": "Acme status",
+            "env_ids": ["prod"],
+        })))
+        .unwrap_err();
+        assert_eq!(err.problem.code, "invalid_status_page");
+    }
+}
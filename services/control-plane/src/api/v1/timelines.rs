@@ -0,0 +1,268 @@
+//! Deploy and instance timeline endpoints.
+//!
+//! - GET /v1/instances/{instance_id}/timeline
+//! - GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys/{deploy_id}/timeline
+//!
+//! Both return ordered phases with the time between them; see
+//! [`crate::timeline`] for how they are assembled.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use plfm_events::{ActorType, AggregateType};
+use plfm_id::{AppId, DeployId, EnvId, InstanceId, OrgId};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::api::authz;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+use crate::timeline::{self, PhaseDurations, PhaseTimes, TimelineEntry};
+
+/// Routes merged into `/v1/instances`.
+pub fn instance_routes() -> Router<AppState> {
+    Router::new().route("/{instance_id}/timeline", get(instance_timeline))
+}
+
+/// Routes merged into `/v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys`.
+pub fn deploy_routes() -> Router<AppState> {
+    Router::new().route("/{deploy_id}/timeline", get(deploy_timeline))
+}
+
+#[derive(Debug, Serialize)]
+struct InstanceTimelineResponse {
+    instance_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deploy_id: Option<String>,
+    node_id: String,
+    entries: Vec<TimelineEntry>,
+    durations: PhaseDurations,
+}
+
+#[derive(Debug, Serialize)]
+struct DeployTimelineResponse {
+    deploy_id: String,
+    /// Deploy events merged with milestones derived from its instances.
+    entries: Vec<TimelineEntry>,
+    instances: Vec<DeployInstanceSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeployInstanceSummary {
+    instance_id: String,
+    process_type: String,
+    node_id: String,
+    phases: PhaseTimes,
+    durations: PhaseDurations,
+}
+
+#[derive(Debug)]
+struct InstanceRow {
+    instance_id: String,
+    org_id: String,
+    deploy_id: Option<String>,
+    process_type: String,
+    node_id: String,
+}
+
+impl<'r> FromRow<'r, PgRow> for InstanceRow {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            instance_id: row.try_get("instance_id")?,
+            org_id: row.try_get("org_id")?,
+            deploy_id: row.try_get("deploy_id")?,
+            process_type: row.try_get("process_type")?,
+            node_id: row.try_get("node_id")?,
+        })
+    }
+}
+
+/// GET /v1/instances/{instance_id}/timeline
+async fn instance_timeline(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(instance_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let _instance_id: InstanceId = instance_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_instance_id", "Invalid instance ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let internal = |e: String| {
+        tracing::error!(error = %e, request_id = %request_id, instance_id = %instance_id, "Failed to load instance timeline");
+        ApiError::internal("internal_error", "Failed to load instance timeline")
+            .with_request_id(request_id.clone())
+    };
+
+    let row = sqlx::query_as::<_, InstanceRow>(
+        r#"
+        SELECT instance_id, org_id, deploy_id, process_type, node_id
+        FROM instances_desired_view
+        WHERE instance_id = $1
+        "#,
+    )
+    .bind(&instance_id)
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| internal(e.to_string()))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "instance_not_found",
+            format!("Instance {} not found", instance_id),
+        )
+        .with_request_id(request_id.clone())
+    })?;
+
+    // Node agents and operators see every instance; users only their org's.
+    if ctx.actor_type != ActorType::System {
+        let org_id: OrgId = row.org_id.parse().map_err(|_| {
+            ApiError::internal("internal_error", "Invalid org_id in instances_desired_view")
+                .with_request_id(request_id.clone())
+        })?;
+        authz::require_org_member(&state, &org_id, &ctx).await?;
+    }
+
+    let events = state
+        .db()
+        .event_store()
+        .query_by_aggregate(&AggregateType::Instance, &instance_id)
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+    let delivered = timeline::load_plan_deliveries(state.db().pool(), &[instance_id.clone()])
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+
+    let entries = timeline::instance_timeline(&events, delivered.get(&instance_id).copied());
+    let durations = PhaseTimes::from_entries(&entries).durations();
+
+    Ok(Json(InstanceTimelineResponse {
+        instance_id: row.instance_id,
+        deploy_id: row.deploy_id,
+        node_id: row.node_id,
+        entries,
+        durations,
+    }))
+}
+
+/// GET /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/deploys/{deploy_id}/timeline
+async fn deploy_timeline(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, deploy_id)): Path<(String, String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id_typed: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let _app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let _env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let _deploy_id: DeployId = deploy_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_deploy_id", "Invalid deploy ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id_typed, &ctx).await?;
+
+    let internal = |e: String| {
+        tracing::error!(error = %e, request_id = %request_id, deploy_id = %deploy_id, "Failed to load deploy timeline");
+        ApiError::internal("internal_error", "Failed to load deploy timeline")
+            .with_request_id(request_id.clone())
+    };
+
+    let exists: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT deploy_id
+        FROM deploys_view
+        WHERE org_id = $1 AND app_id = $2 AND env_id = $3 AND deploy_id = $4
+        "#,
+    )
+    .bind(&org_id)
+    .bind(&app_id)
+    .bind(&env_id)
+    .bind(&deploy_id)
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| internal(e.to_string()))?;
+    if exists.is_none() {
+        return Err(ApiError::not_found(
+            "deploy_not_found",
+            format!("Deploy {} not found", deploy_id),
+        )
+        .with_request_id(request_id.clone()));
+    }
+
+    let instances = sqlx::query_as::<_, InstanceRow>(
+        r#"
+        SELECT instance_id, org_id, deploy_id, process_type, node_id
+        FROM instances_desired_view
+        WHERE deploy_id = $1
+        ORDER BY created_at, instance_id
+        "#,
+    )
+    .bind(&deploy_id)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| internal(e.to_string()))?;
+
+    let event_store = state.db().event_store();
+    let deploy_events = event_store
+        .query_by_aggregate(&AggregateType::Deploy, &deploy_id)
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+
+    let instance_ids: Vec<String> = instances.iter().map(|i| i.instance_id.clone()).collect();
+    let instance_events = event_store
+        .query_by_aggregates(&AggregateType::Instance, &instance_ids)
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+    let delivered = timeline::load_plan_deliveries(state.db().pool(), &instance_ids)
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+
+    let summaries: Vec<DeployInstanceSummary> = instances
+        .into_iter()
+        .map(|row| {
+            let events: Vec<_> = instance_events
+                .iter()
+                .filter(|e| e.aggregate_id == row.instance_id)
+                .cloned()
+                .collect();
+            let entries =
+                timeline::instance_timeline(&events, delivered.get(&row.instance_id).copied());
+            let phases = PhaseTimes::from_entries(&entries);
+            DeployInstanceSummary {
+                instance_id: row.instance_id,
+                process_type: row.process_type,
+                node_id: row.node_id,
+                durations: phases.durations(),
+                phases,
+            }
+        })
+        .collect();
+
+    let phase_times: Vec<PhaseTimes> = summaries.iter().map(|s| s.phases.clone()).collect();
+    let entries = timeline::merge(
+        timeline::deploy_timeline(&deploy_events),
+        timeline::instance_milestones(&phase_times),
+    );
+
+    Ok(Json(DeployTimelineResponse {
+        deploy_id,
+        entries,
+        instances: summaries,
+    }))
+}
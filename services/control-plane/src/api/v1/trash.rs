@@ -0,0 +1,102 @@
+//! Trash API endpoints.
+//!
+//! Lists an org's deleted resources that can still be restored. The restore
+//! endpoints themselves live with their resources (`.../restore`, and
+//! `.../undelete` for volumes) and share [`restore`].
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use plfm_id::OrgId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::authz;
+use crate::api::consistency;
+use crate::api::error::ApiError;
+use crate::api::request_context::RequestContext;
+use crate::state::AppState;
+use crate::trash::{self, RestoreScope, TrashItem, TrashKind};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+/// Query parameters for listing the trash.
+#[derive(Debug, Deserialize)]
+pub struct ListTrashQuery {
+    /// Max number of items to return.
+    pub limit: Option<i64>,
+}
+
+/// Response for listing the trash.
+#[derive(Debug, Serialize)]
+pub struct ListTrashResponse {
+    pub items: Vec<TrashItem>,
+}
+
+/// Response for restoring a resource.
+#[derive(Debug, Serialize)]
+pub struct RestoreResponse {
+    /// Everything the restore brought back, the requested resource first.
+    /// Empty when the resource was not deleted.
+    pub restored: Vec<TrashItem>,
+}
+
+/// List restorable resources, most recently deleted first.
+///
+/// GET /v1/orgs/{org_id}/trash
+pub async fn list_trash(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path(org_id): Path<String>,
+    Query(query): Query<ListTrashQuery>,
+) -> Result<Json<ListTrashResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let items = trash::list(state.db().pool(), &org_id, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to list trash");
+            ApiError::internal("internal_error", "Failed to list trash")
+                .with_request_id(request_id.clone())
+        })?;
+
+    Ok(Json(ListTrashResponse { items }))
+}
+
+/// Restore a deleted resource and wait until the restore is visible.
+/// Callers check org write access first.
+pub(super) async fn restore(
+    state: &AppState,
+    ctx: &RequestContext,
+    scope: RestoreScope<'_>,
+    kind: TrashKind,
+    id: &str,
+) -> Result<Json<RestoreResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let restored = trash::restore(state.db().pool(), ctx, scope, kind, id)
+        .await
+        .map_err(|e| {
+            tracing::warn!(error = %e, request_id = %request_id, kind = kind.as_str(), id = %id, "Restore failed");
+            ApiError::from(e).with_request_id(request_id.clone())
+        })?;
+
+    if let Some(event_id) = restored.last_event_id {
+        for projection in restored.projections() {
+            consistency::wait_for_write(state, ctx, projection, event_id.value()).await?;
+        }
+    }
+
+    Ok(Json(RestoreResponse {
+        restored: restored.items,
+    }))
+}
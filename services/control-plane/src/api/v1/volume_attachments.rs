@@ -1,6 +1,9 @@
 //! Volume attachment API endpoints.
 //!
 //! Attachments bind an org-owned volume to an env/process type at a mount path.
+//! They can be managed from the env (`.../envs/{env_id}/volume-attachments`)
+//! or from the volume (`.../volumes/{volume_id}/attach` and `/detach`); both
+//! share the same validation and emit `volume.attached` / `volume.detached`.
 
 use axum::{
     extract::{Path, State},
@@ -10,13 +13,12 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use plfm_events::{
-    event_types, AggregateType, VolumeAttachmentCreatedPayload, VolumeAttachmentDeletedPayload,
-};
+use plfm_events::{event_types, AggregateType, VolumeAttachedPayload, VolumeDetachedPayload};
 use plfm_id::{AppId, EnvId, OrgId, VolumeAttachmentId, VolumeId};
 use serde::{Deserialize, Serialize};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
 use crate::api::idempotency;
 use crate::api::request_context::RequestContext;
@@ -45,6 +47,30 @@ pub struct CreateVolumeAttachmentRequest {
     pub read_only: bool,
 }
 
+/// Body for `POST /v1/orgs/{org_id}/volumes/{volume_id}/attach`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AttachVolumeRequest {
+    pub env_id: String,
+    pub process_type: String,
+    pub mount_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Body for `POST /v1/orgs/{org_id}/volumes/{volume_id}/detach`.
+///
+/// All fields are optional filters; they are only needed when the volume has
+/// more than one active attachment.
+#[derive(Debug, Default, Deserialize)]
+pub struct DetachVolumeRequest {
+    #[serde(default)]
+    pub attachment_id: Option<String>,
+    #[serde(default)]
+    pub env_id: Option<String>,
+    #[serde(default)]
+    pub process_type: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct VolumeAttachmentResponse {
     pub id: String,
@@ -73,13 +99,9 @@ async fn create_attachment(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path((org_id, app_id, env_id)): Path<(String, String, String)>,
-    Json(mut req): Json<CreateVolumeAttachmentRequest>,
+    Json(req): Json<CreateVolumeAttachmentRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
-    let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
-    let actor_id = ctx.actor_id.clone();
-    let endpoint_name = "volume_attachments.create";
 
     let org_id: OrgId = org_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
@@ -97,22 +119,324 @@ async fn create_attachment(
     let role = authz::require_org_member(&state, &org_id, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
 
-    req.process_type = req.process_type.trim().to_string();
-    if req.process_type.is_empty() {
+    let volume_id: VolumeId = req.volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    // Validate env exists (scoped to org/app).
+    let env_exists = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM envs_view
+            WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
+        )
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(org_id.to_string())
+    .bind(app_id.to_string())
+    .fetch_one(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, app_id = %app_id, env_id = %env_id, "Failed to check env existence");
+        ApiError::internal("internal_error", "Failed to create volume attachment")
+            .with_request_id(request_id.clone())
+    })?;
+
+    if !env_exists {
+        return Err(ApiError::not_found(
+            "env_not_found",
+            format!("Environment {} not found", env_id),
+        )
+        .with_request_id(request_id.clone()));
+    }
+
+    attach(
+        &state,
+        &ctx,
+        "volume_attachments.create",
+        AttachTarget {
+            org_id,
+            app_id,
+            env_id,
+            volume_id,
+            process_type: req.process_type,
+            mount_path: req.mount_path,
+            read_only: req.read_only,
+        },
+    )
+    .await
+}
+
+/// Attach a volume to an env/process type, addressed by volume.
+///
+/// POST /v1/orgs/{org_id}/volumes/{volume_id}/attach
+pub(super) async fn attach_volume(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+    Json(req): Json<AttachVolumeRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let env_id: EnvId = req.env_id.trim().parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    // The env determines the app; attachments are always app-scoped.
+    let app_id = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT app_id FROM envs_view
+        WHERE env_id = $1 AND org_id = $2 AND NOT is_deleted
+        "#,
+    )
+    .bind(env_id.to_string())
+    .bind(org_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, env_id = %env_id, "Failed to load env");
+        ApiError::internal("internal_error", "Failed to attach volume")
+            .with_request_id(request_id.clone())
+    })?
+    .ok_or_else(|| {
+        ApiError::not_found("env_not_found", format!("Environment {} not found", env_id))
+            .with_request_id(request_id.clone())
+    })?;
+
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::internal("internal_error", "Corrupt environment state")
+            .with_request_id(request_id.clone())
+    })?;
+
+    attach(
+        &state,
+        &ctx,
+        "volumes.attach",
+        AttachTarget {
+            org_id,
+            app_id,
+            env_id,
+            volume_id,
+            process_type: req.process_type,
+            mount_path: req.mount_path,
+            read_only: req.read_only,
+        },
+    )
+    .await
+}
+
+/// Detach a volume attachment (idempotent for already-deleted attachments).
+///
+/// DELETE /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/volume-attachments/{attachment_id}
+async fn delete_attachment(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, app_id, env_id, attachment_id)): Path<(String, String, String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let app_id: AppId = app_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let env_id: EnvId = env_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let attachment_id: VolumeAttachmentId = attachment_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_attachment_id", "Invalid attachment ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let row = sqlx::query_as::<_, AttachmentDeleteRow>(
+        r#"
+        SELECT attachment_id, app_id, env_id, volume_id, process_type, mount_path, is_deleted
+        FROM volume_attachments_view
+        WHERE org_id = $1 AND attachment_id = $2
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(attachment_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, attachment_id = %attachment_id, "Failed to load attachment");
+        ApiError::internal("internal_error", "Failed to delete attachment")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let Some(row) = row else {
         return Err(
-            ApiError::bad_request("invalid_process_type", "process_type cannot be empty")
+            ApiError::not_found("attachment_not_found", "Attachment not found")
+                .with_request_id(request_id),
+        );
+    };
+
+    if row.app_id != app_id.to_string() || row.env_id != env_id.to_string() {
+        return Err(
+            ApiError::not_found("attachment_not_found", "Attachment not found")
                 .with_request_id(request_id),
         );
     }
 
-    req.mount_path = req.mount_path.trim().to_string();
-    validate_mount_path(&req.mount_path, &request_id)?;
+    if row.is_deleted {
+        return Ok((StatusCode::OK, Json(DeleteResponse { ok: true })).into_response());
+    }
 
-    let volume_id: VolumeId = req.volume_id.parse().map_err(|_| {
+    detach(&state, &ctx, &org_id, row).await
+}
+
+/// Detach a volume, addressed by volume.
+///
+/// POST /v1/orgs/{org_id}/volumes/{volume_id}/detach
+///
+/// Detaches the volume's only active attachment. When there are several, the
+/// body must narrow them down to one (`attachment_id`, or `env_id` and
+/// `process_type`).
+pub(super) async fn detach_volume(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+    body: Option<Json<DetachVolumeRequest>>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
         ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
             .with_request_id(request_id.clone())
     })?;
 
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let rows = sqlx::query_as::<_, AttachmentDeleteRow>(
+        r#"
+        SELECT attachment_id, app_id, env_id, volume_id, process_type, mount_path, is_deleted
+        FROM volume_attachments_view
+        WHERE org_id = $1 AND volume_id = $2 AND NOT is_deleted
+        ORDER BY created_at ASC, attachment_id ASC
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(volume_id.to_string())
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, volume_id = %volume_id, "Failed to load volume attachments");
+        ApiError::internal("internal_error", "Failed to detach volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let mut matching: Vec<AttachmentDeleteRow> = rows
+        .into_iter()
+        .filter(|row| {
+            req.attachment_id
+                .as_deref()
+                .is_none_or(|id| row.attachment_id == id.trim())
+                && req
+                    .env_id
+                    .as_deref()
+                    .is_none_or(|id| row.env_id == id.trim())
+                && req
+                    .process_type
+                    .as_deref()
+                    .is_none_or(|pt| row.process_type == pt.trim())
+        })
+        .collect();
+
+    if matching.len() > 1 {
+        return Err(ApiError::conflict(
+            "ambiguous_attachment",
+            format!(
+                "Volume {} has {} matching attachments; specify attachment_id",
+                volume_id,
+                matching.len()
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    let Some(row) = matching.pop() else {
+        return Err(ApiError::not_found(
+            "attachment_not_found",
+            format!("Volume {} has no matching active attachment", volume_id),
+        )
+        .with_request_id(request_id));
+    };
+
+    detach(&state, &ctx, &org_id, row).await
+}
+
+// =============================================================================
+// Shared attach/detach
+// =============================================================================
+
+/// A validated-for-format attach request; `attach` checks it against state.
+struct AttachTarget {
+    org_id: OrgId,
+    app_id: AppId,
+    env_id: EnvId,
+    volume_id: VolumeId,
+    process_type: String,
+    mount_path: String,
+    read_only: bool,
+}
+
+/// Validate and record an attachment. The caller has already authorized the
+/// request and confirmed the env belongs to `target.org_id`/`target.app_id`.
+async fn attach(
+    state: &AppState,
+    ctx: &RequestContext,
+    endpoint_name: &str,
+    mut target: AttachTarget,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_type = ctx.actor_type;
+    let actor_id = ctx.actor_id.clone();
+    let org_id = target.org_id;
+    let app_id = target.app_id;
+    let env_id = target.env_id;
+    let volume_id = target.volume_id;
+
+    target.process_type = target.process_type.trim().to_string();
+    if target.process_type.is_empty() {
+        return Err(
+            ApiError::bad_request("invalid_process_type", "process_type cannot be empty")
+                .with_request_id(request_id),
+        );
+    }
+
+    target.mount_path = target.mount_path.trim().to_string();
+    validate_mount_path(&target.mount_path, &request_id)?;
+
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -120,7 +444,10 @@ async fn create_attachment(
             let hash_input = serde_json::json!({
                 "app_id": app_id.to_string(),
                 "env_id": env_id.to_string(),
-                "body": &req
+                "volume_id": volume_id.to_string(),
+                "process_type": &target.process_type,
+                "mount_path": &target.mount_path,
+                "read_only": target.read_only,
             });
             idempotency::request_hash(endpoint_name, &hash_input)
                 .map(|hash| (key.to_string(), hash))
@@ -130,7 +457,7 @@ async fn create_attachment(
 
     if let Some((key, hash)) = request_hash.as_ref() {
         if let Some((status, body)) = idempotency::check(
-            &state,
+            state,
             &org_scope,
             &actor_id,
             endpoint_name,
@@ -146,46 +473,16 @@ async fn create_attachment(
         }
     }
 
-    // Validate env exists (scoped to org/app).
-    let env_exists = sqlx::query_scalar::<_, bool>(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM envs_view
-            WHERE env_id = $1 AND org_id = $2 AND app_id = $3 AND NOT is_deleted
-        )
-        "#,
-    )
-    .bind(env_id.to_string())
-    .bind(org_id.to_string())
-    .bind(app_id.to_string())
-    .fetch_one(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, app_id = %app_id, env_id = %env_id, "Failed to check env existence");
-        ApiError::internal("internal_error", "Failed to create volume attachment")
-            .with_request_id(request_id.clone())
-    })?;
-
-    if !env_exists {
-        return Err(ApiError::not_found(
-            "env_not_found",
-            format!("Environment {} not found", env_id),
-        )
-        .with_request_id(request_id.clone()));
-    }
-
     // Validate volume exists and is owned by org.
-    let volume_exists = sqlx::query_scalar::<_, bool>(
+    let home_node_id = sqlx::query_scalar::<_, Option<String>>(
         r#"
-        SELECT EXISTS(
-            SELECT 1 FROM volumes_view
-            WHERE org_id = $1 AND volume_id = $2 AND NOT is_deleted
-        )
+        SELECT home_node_id FROM volumes_view
+        WHERE org_id = $1 AND volume_id = $2 AND NOT is_deleted
         "#,
     )
     .bind(org_id.to_string())
     .bind(volume_id.to_string())
-    .fetch_one(state.db().pool())
+    .fetch_optional(state.db().pool())
     .await
     .map_err(|e| {
         tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, volume_id = %volume_id, "Failed to check volume existence");
@@ -193,10 +490,10 @@ async fn create_attachment(
             .with_request_id(request_id.clone())
     })?;
 
-    if !volume_exists {
+    let Some(home_node_id) = home_node_id else {
         return Err(ApiError::not_found("volume_not_found", "Volume not found")
             .with_request_id(request_id.clone()));
-    }
+    };
 
     // Enforce uniqueness of (env_id, process_type, mount_path).
     let attachment_exists = sqlx::query_scalar::<_, bool>(
@@ -213,8 +510,8 @@ async fn create_attachment(
     )
     .bind(org_id.to_string())
     .bind(env_id.to_string())
-    .bind(&req.process_type)
-    .bind(&req.mount_path)
+    .bind(&target.process_type)
+    .bind(&target.mount_path)
     .fetch_one(state.db().pool())
     .await
     .map_err(|e| {
@@ -231,16 +528,86 @@ async fn create_attachment(
         .with_request_id(request_id.clone()));
     }
 
+    // Volumes are single-attach in v1 (exclusive writer).
+    let in_use_by = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT attachment_id FROM volume_attachments_view
+        WHERE org_id = $1 AND volume_id = $2 AND NOT is_deleted
+        LIMIT 1
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(volume_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, volume_id = %volume_id, "Failed to check volume exclusivity");
+        ApiError::internal("internal_error", "Failed to create volume attachment")
+            .with_request_id(request_id.clone())
+    })?;
+
+    if let Some(existing) = in_use_by {
+        return Err(ApiError::conflict(
+            "volume_in_use",
+            format!(
+                "Volume {} is already attached ({}); detach it first",
+                volume_id, existing
+            ),
+        )
+        .with_request_id(request_id.clone()));
+    }
+
+    // Local volumes pin instances to their home node, so every volume on a
+    // process type must live on the same node.
+    if let Some(home_node_id) = home_node_id.as_deref() {
+        let conflicting_node = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT v.home_node_id
+            FROM volume_attachments_view a
+            JOIN volumes_view v ON v.volume_id = a.volume_id
+            WHERE a.org_id = $1
+              AND a.env_id = $2
+              AND a.process_type = $3
+              AND NOT a.is_deleted
+              AND v.home_node_id IS NOT NULL
+              AND v.home_node_id <> $4
+            LIMIT 1
+            "#,
+        )
+        .bind(org_id.to_string())
+        .bind(env_id.to_string())
+        .bind(&target.process_type)
+        .bind(home_node_id)
+        .fetch_optional(state.db().pool())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, env_id = %env_id, "Failed to check volume locality");
+            ApiError::internal("internal_error", "Failed to create volume attachment")
+                .with_request_id(request_id.clone())
+        })?;
+
+        if let Some(other_node) = conflicting_node {
+            return Err(ApiError::conflict(
+                "volume_home_node_conflict",
+                format!(
+                    "Volume {} lives on node {}, but process type '{}' already mounts volumes on node {}",
+                    volume_id, home_node_id, target.process_type, other_node
+                ),
+            )
+            .with_request_id(request_id.clone()));
+        }
+    }
+
     let attachment_id = VolumeAttachmentId::new();
-    let payload = VolumeAttachmentCreatedPayload {
+    let payload = VolumeAttachedPayload {
         attachment_id,
         org_id,
         volume_id,
         app_id,
         env_id,
-        process_type: req.process_type.clone(),
-        mount_path: req.mount_path.clone(),
-        read_only: req.read_only,
+        process_type: target.process_type.clone(),
+        mount_path: target.mount_path.clone(),
+        read_only: target.read_only,
     };
 
     let payload = serde_json::to_value(&payload).map_err(|e| {
@@ -253,7 +620,7 @@ async fn create_attachment(
         aggregate_type: AggregateType::VolumeAttachment,
         aggregate_id: attachment_id.to_string(),
         aggregate_seq: 1,
-        event_type: event_types::VOLUME_ATTACHMENT_CREATED.to_string(),
+        event_type: event_types::VOLUME_ATTACHED.to_string(),
         event_version: 1,
         actor_type,
         actor_id: actor_id.clone(),
@@ -274,20 +641,7 @@ async fn create_attachment(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "volume_attachments",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(state, ctx, "volume_attachments", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, AttachmentRow>(
         r#"
@@ -316,7 +670,7 @@ async fn create_attachment(
         })?;
 
         let _ = idempotency::store(
-            &state,
+            state,
             idempotency::StoreIdempotencyParams {
                 org_scope: &org_scope,
                 actor_id: &actor_id,
@@ -334,74 +688,22 @@ async fn create_attachment(
     Ok((StatusCode::OK, Json(response)).into_response())
 }
 
-/// Detach a volume attachment (idempotent for already-deleted attachments).
-///
-/// DELETE /v1/orgs/{org_id}/apps/{app_id}/envs/{env_id}/volume-attachments/{attachment_id}
-async fn delete_attachment(
-    State(state): State<AppState>,
-    ctx: RequestContext,
-    Path((org_id, app_id, env_id, attachment_id)): Path<(String, String, String, String)>,
+/// Record the removal of an active attachment.
+async fn detach(
+    state: &AppState,
+    ctx: &RequestContext,
+    org_id: &OrgId,
+    row: AttachmentDeleteRow,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
-    let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
-    let actor_id = ctx.actor_id.clone();
-
-    let org_id: OrgId = org_id.parse().map_err(|_| {
-        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
-            .with_request_id(request_id.clone())
-    })?;
-    let app_id: AppId = app_id.parse().map_err(|_| {
-        ApiError::bad_request("invalid_app_id", "Invalid application ID format")
-            .with_request_id(request_id.clone())
-    })?;
-    let env_id: EnvId = env_id.parse().map_err(|_| {
-        ApiError::bad_request("invalid_env_id", "Invalid environment ID format")
-            .with_request_id(request_id.clone())
-    })?;
-    let attachment_id: VolumeAttachmentId = attachment_id.parse().map_err(|_| {
-        ApiError::bad_request("invalid_attachment_id", "Invalid attachment ID format")
-            .with_request_id(request_id.clone())
-    })?;
-
-    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
-    authz::require_org_write(role, &request_id)?;
-
-    let row = sqlx::query_as::<_, AttachmentDeleteRow>(
-        r#"
-        SELECT attachment_id, app_id, env_id, volume_id, process_type, is_deleted
-        FROM volume_attachments_view
-        WHERE org_id = $1 AND attachment_id = $2
-        "#,
-    )
-    .bind(org_id.to_string())
-    .bind(attachment_id.to_string())
-    .fetch_optional(state.db().pool())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, attachment_id = %attachment_id, "Failed to load attachment");
-        ApiError::internal("internal_error", "Failed to delete attachment")
+    let corrupt = || {
+        ApiError::internal("internal_error", "Corrupt attachment state")
             .with_request_id(request_id.clone())
-    })?;
-
-    let Some(row) = row else {
-        return Err(
-            ApiError::not_found("attachment_not_found", "Attachment not found")
-                .with_request_id(request_id),
-        );
     };
-
-    if row.app_id != app_id.to_string() || row.env_id != env_id.to_string() {
-        return Err(
-            ApiError::not_found("attachment_not_found", "Attachment not found")
-                .with_request_id(request_id),
-        );
-    }
-
-    let response = DeleteResponse { ok: true };
-    if row.is_deleted {
-        return Ok((StatusCode::OK, Json(response)).into_response());
-    }
+    let attachment_id: VolumeAttachmentId = row.attachment_id.parse().map_err(|_| corrupt())?;
+    let app_id: AppId = row.app_id.parse().map_err(|_| corrupt())?;
+    let env_id: EnvId = row.env_id.parse().map_err(|_| corrupt())?;
+    let volume_id: VolumeId = row.volume_id.parse().map_err(|_| corrupt())?;
 
     let current_seq = state
         .db()
@@ -415,15 +717,14 @@ async fn delete_attachment(
         })?
         .unwrap_or(0);
 
-    let payload = VolumeAttachmentDeletedPayload {
+    let payload = VolumeDetachedPayload {
         attachment_id,
-        org_id,
-        volume_id: row.volume_id.parse().map_err(|_| {
-            ApiError::internal("internal_error", "Corrupt attachment state")
-                .with_request_id(request_id.clone())
-        })?,
+        org_id: *org_id,
+        volume_id,
+        app_id,
         env_id,
-        process_type: row.process_type.clone(),
+        process_type: row.process_type,
+        mount_path: row.mount_path,
     };
 
     let payload = serde_json::to_value(&payload).map_err(|e| {
@@ -436,13 +737,13 @@ async fn delete_attachment(
         aggregate_type: AggregateType::VolumeAttachment,
         aggregate_id: attachment_id.to_string(),
         aggregate_seq: current_seq + 1,
-        event_type: event_types::VOLUME_ATTACHMENT_DELETED.to_string(),
+        event_type: event_types::VOLUME_DETACHED.to_string(),
         event_version: 1,
-        actor_type,
-        actor_id,
-        org_id: Some(org_id),
+        actor_type: ctx.actor_type,
+        actor_id: ctx.actor_id.clone(),
+        org_id: Some(*org_id),
         request_id: request_id.clone(),
-        idempotency_key,
+        idempotency_key: ctx.idempotency_key.clone(),
         app_id: Some(app_id),
         env_id: Some(env_id),
         correlation_id: None,
@@ -457,22 +758,9 @@ async fn delete_attachment(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "volume_attachments",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::record_write(ctx, event_id.value());
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok((StatusCode::OK, Json(DeleteResponse { ok: true })).into_response())
 }
 
 // =============================================================================
@@ -563,12 +851,12 @@ impl From<AttachmentRow> for VolumeAttachmentResponse {
 
 #[derive(Debug)]
 struct AttachmentDeleteRow {
-    #[allow(dead_code)]
     attachment_id: String,
     app_id: String,
     env_id: String,
     volume_id: String,
     process_type: String,
+    mount_path: String,
     is_deleted: bool,
 }
 
@@ -581,6 +869,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AttachmentDeleteRow {
             env_id: row.try_get("env_id")?,
             volume_id: row.try_get("volume_id")?,
             process_type: row.try_get("process_type")?,
+            mount_path: row.try_get("mount_path")?,
             is_deleted: row.try_get("is_deleted")?,
         })
     }
@@ -6,24 +6,36 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
 use plfm_events::{
     event_types, AggregateType, JobStatus, RestoreJobCreatedPayload,
-    RestoreJobStatusChangedPayload, SnapshotCreatedPayload, VolumeCreatedPayload,
-    VolumeDeletedPayload,
+    RestoreJobStatusChangedPayload, SnapshotCreatedPayload, VolumeBackupPolicySetPayload,
+    VolumeCreatedPayload, VolumeDeletedPayload, VolumeResizedPayload,
 };
 use plfm_id::{OrgId, RestoreJobId, SnapshotId, VolumeId};
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 
 use crate::api::authz;
+use crate::api::consistency;
 use crate::api::error::ApiError;
+use crate::api::fields::{FieldsQuery, Shape};
 use crate::api::idempotency;
+use crate::api::if_exists::{IfExists, IfExistsQuery};
+use crate::api::labels::{self, LabelSelector, Labels, PatchLabelsRequest};
+use crate::api::pagination::{self, PageQuery, PageTotal, SortKey, Sorting};
+use crate::api::preconditions::{self, Preconditions};
 use crate::api::request_context::RequestContext;
+use crate::backups::{self, CronSchedule};
 use crate::db::AppendEvent;
 use crate::state::AppState;
+use crate::trash::{RestoreScope, TrashKind};
+
+use super::labels::LabelTarget;
+use super::trash::{self, RestoreResponse};
 
 /// Volume routes.
 ///
@@ -32,21 +44,34 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_volumes))
         .route("/", post(create_volume))
+        .route("/by-name/{name}", get(get_volume_by_name))
         .route("/{volume_id}", get(get_volume))
         .route("/{volume_id}", delete(delete_volume))
+        .route("/{volume_id}/labels", patch(patch_volume_labels))
+        .route("/{volume_id}/resize", post(resize_volume))
+        .route("/{volume_id}/backup-policy", put(set_backup_policy))
+        .route(
+            "/{volume_id}/attach",
+            post(super::volume_attachments::attach_volume),
+        )
+        .route(
+            "/{volume_id}/detach",
+            post(super::volume_attachments::detach_volume),
+        )
         .route("/{volume_id}/snapshots", post(create_snapshot))
         .route("/{volume_id}/snapshots", get(list_snapshots))
         .route("/{volume_id}/restore", post(restore_volume))
+        .route("/{volume_id}/undelete", post(undelete_volume))
 }
 
 // =============================================================================
 // Request/Response Types (OpenAPI parity)
 // =============================================================================
 
+/// Pagination is in [`PageQuery`].
 #[derive(Debug, Deserialize)]
 pub struct ListVolumesQuery {
-    pub limit: Option<i64>,
-    pub cursor: Option<String>,
+    pub label_selector: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -80,6 +105,25 @@ pub struct VolumeResponse {
     pub name: Option<String>,
     pub size_bytes: i64,
     pub filesystem: String,
+    pub labels: Labels,
+    /// Resource version for optimistic concurrency.
+    pub resource_version: i32,
+    /// Node holding the volume's data; set when an instance first mounts it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home_node_id: Option<String>,
+    /// State of the most recent resize: `pending`, `completed`, or `failed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize_status: Option<String>,
+    /// Why the most recent resize failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize_error: Option<String>,
+    /// Scheduled snapshot policy; absent when scheduled snapshots are off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_policy: Option<BackupPolicyResponse>,
+    /// Most recent in-place restore; absent if the volume was never restored
+    /// in place.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restore: Option<VolumeRestoreResponse>,
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<DateTime<Utc>>,
@@ -87,10 +131,45 @@ pub struct VolumeResponse {
     pub attachments: Vec<VolumeAttachmentResponse>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct BackupPolicyResponse {
+    /// Five-field cron expression, evaluated in UTC.
+    pub schedule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_max_age_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// When the most recent scheduled snapshot was queued.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Status of the most recent scheduled snapshot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_status: Option<String>,
+}
+
+/// Progress of an in-place restore.
+#[derive(Debug, Serialize)]
+pub struct VolumeRestoreResponse {
+    pub id: String,
+    pub snapshot_id: String,
+    /// `queued`, `running`, `succeeded`, or `failed`.
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_restored: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_total: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_reason: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ListVolumesResponse {
     pub items: Vec<VolumeResponse>,
     pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<PageTotal>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -112,6 +191,25 @@ fn default_backup_enabled() -> bool {
     true
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ResizeVolumeRequest {
+    /// New size; must not be smaller than the current size.
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetBackupPolicyRequest {
+    /// Five-field cron expression (UTC); `null` turns scheduled snapshots off.
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Keep at most this many successful scheduled snapshots.
+    #[serde(default)]
+    pub retain_count: Option<i32>,
+    /// Prune scheduled snapshots older than this.
+    #[serde(default)]
+    pub retain_max_age_secs: Option<i64>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SnapshotResponse {
     pub id: String,
@@ -137,6 +235,10 @@ pub struct CreateSnapshotRequest {
 pub struct RestoreVolumeRequest {
     pub snapshot_id: String,
     pub new_volume_name: Option<String>,
+    /// Overwrite this volume instead of creating a new one. Requires every
+    /// process type mounting it to be scaled to zero.
+    #[serde(default)]
+    pub in_place: bool,
 }
 
 // =============================================================================
@@ -151,6 +253,8 @@ async fn list_volumes(
     ctx: RequestContext,
     Path(org_id): Path<String>,
     Query(query): Query<ListVolumesQuery>,
+    Query(page): Query<PageQuery>,
+    Query(fields): Query<FieldsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = ctx.request_id.clone();
 
@@ -161,42 +265,52 @@ async fn list_volumes(
 
     let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
 
-    let limit = query.limit.unwrap_or(50).clamp(1, 200);
-    let cursor = query.cursor.as_deref();
+    let page = VOLUME_SORTING.page(page, &request_id)?;
+    let selection = VOLUME_SHAPE.select(fields, &request_id)?;
+    let selector = LabelSelector::from_query(query.label_selector.as_deref(), &request_id)?;
+    let filters = |builder: &mut QueryBuilder<'_, Postgres>| {
+        builder.push("org_id = ");
+        builder.push_bind(org_id.to_string());
+        builder.push(" AND NOT is_deleted AND labels_match(labels, ");
+        builder.push_bind(selector.clone());
+        builder.push("::JSONB)");
+    };
 
-    let rows = sqlx::query_as::<_, VolumeRow>(
-        r#"
-        SELECT
-            volume_id,
-            org_id,
-            name,
-            size_bytes,
-            filesystem,
-            backup_enabled,
-            created_at,
-            updated_at
-        FROM volumes_view
-        WHERE org_id = $1
-          AND NOT is_deleted
-          AND ($2::TEXT IS NULL OR volume_id > $2)
-        ORDER BY volume_id ASC
-        LIMIT $3
-        "#,
-    )
-    .bind(org_id.to_string())
-    .bind(cursor)
-    .bind(limit)
-    .fetch_all(state.db().pool())
-    .await
-    .map_err(|e| {
+    let mut builder = QueryBuilder::new(
+        "SELECT volume_id, org_id, name, size_bytes, filesystem, backup_enabled, labels, \
+                resource_version, home_node_id, resize_status, resize_error, \
+                backup_schedule, backup_retain_count, backup_retain_max_age_secs, \
+                backup_policy_updated_at, backup_last_run_at, backup_last_status, \
+                restore_id, restore_snapshot_id, restore_status, restore_bytes_restored, \
+                restore_bytes_total, restore_error, created_at, updated_at \
+         FROM volumes_view WHERE ",
+    );
+    filters(&mut builder);
+    page.push_after(&mut builder);
+    page.push_order(&mut builder);
+
+    let list_failed = |e: sqlx::Error| {
         tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to list volumes");
         ApiError::internal("internal_error", "Failed to list volumes")
             .with_request_id(request_id.clone())
-    })?;
-
-    let volume_ids: Vec<String> = rows.iter().map(|r| r.volume_id.clone()).collect();
-    let mut attachments =
-        load_attachments_for_volumes(&state, &request_id, &org_id, &volume_ids).await?;
+    };
+    let rows = builder
+        .build_query_as::<VolumeRow>()
+        .fetch_all(state.db().pool())
+        .await
+        .map_err(list_failed)?;
+    let total = page
+        .total(state.db().pool(), "volumes_view", filters)
+        .await
+        .map_err(list_failed)?;
+    let (rows, next_cursor) = page.finish(rows);
+
+    let mut attachments = if selection.wants("attachments") {
+        let volume_ids: Vec<String> = rows.iter().map(|r| r.volume_id.clone()).collect();
+        load_attachments_for_volumes(&state, &request_id, &org_id, &volume_ids).await?
+    } else {
+        HashMap::new()
+    };
 
     let mut items: Vec<VolumeResponse> = Vec::with_capacity(rows.len());
     for row in rows {
@@ -208,27 +322,38 @@ async fn list_volumes(
             name: row.name.clone(),
             size_bytes: row.size_bytes,
             filesystem: row.filesystem.clone(),
+            backup_policy: backup_policy_response(&row),
+            restore: restore_response(&row),
+            labels: labels::from_json(row.labels),
+            resource_version: row.resource_version,
+            home_node_id: row.home_node_id.clone(),
+            resize_status: row.resize_status.clone(),
+            resize_error: row.resize_error.clone(),
             created_at: row.created_at,
             updated_at: Some(row.updated_at),
             attachments: attachments_for_volume,
         });
     }
 
-    let next_cursor = items
-        .last()
-        .filter(|_| items.len() as i64 == limit)
-        .map(|v| v.id.clone());
-
-    Ok(Json(ListVolumesResponse { items, next_cursor }))
+    Ok(selection.respond(ListVolumesResponse {
+        items,
+        next_cursor,
+        total,
+    }))
 }
 
 /// Create volume.
 ///
 /// POST /v1/orgs/{org_id}/volumes
+///
+/// Volume names are not unique, so a create always creates a volume unless
+/// `?if_exists=return` is given: then an existing volume with the same name,
+/// size, filesystem and backup setting is returned instead.
 async fn create_volume(
     State(state): State<AppState>,
     ctx: RequestContext,
     Path(org_id): Path<String>,
+    Query(if_exists): Query<IfExistsQuery>,
     Json(mut req): Json<CreateVolumeRequest>,
 ) -> Result<Response, ApiError> {
     let request_id = ctx.request_id.clone();
@@ -241,6 +366,7 @@ async fn create_volume(
         ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
             .with_request_id(request_id.clone())
     })?;
+    let if_exists = if_exists.mode(&request_id)?;
 
     let role = authz::require_org_member(&state, &org_id, &ctx).await?;
     authz::require_org_write(role, &request_id)?;
@@ -270,6 +396,14 @@ async fn create_volume(
         );
     }
 
+    if if_exists == IfExists::Return && req.name.is_none() {
+        return Err(ApiError::bad_request(
+            "invalid_name",
+            "name is required with if_exists=return",
+        )
+        .with_request_id(request_id));
+    }
+
     let org_scope = org_id.to_string();
     let request_hash = idempotency_key
         .as_deref()
@@ -297,6 +431,24 @@ async fn create_volume(
         }
     }
 
+    if let (IfExists::Return, Some(name)) = (if_exists, req.name.as_deref()) {
+        if let Some(existing) = find_volume_by_name(&state, &org_id, name, &request_id).await? {
+            let matches = existing.size_bytes == req.size_bytes
+                && existing.filesystem == req.filesystem
+                && existing.backup_enabled == req.backup_enabled;
+            if_exists.reuse(matches, || {
+                ApiError::conflict(
+                    "volume_name_exists",
+                    format!("Volume '{name}' already exists with different settings"),
+                )
+                .with_request_id(request_id.clone())
+            })?;
+            return get_volume(State(state), ctx, Path((org_scope, existing.volume_id)))
+                .await
+                .map(IntoResponse::into_response);
+        }
+    }
+
     let volume_id = VolumeId::new();
     let payload = VolumeCreatedPayload {
         volume_id,
@@ -338,20 +490,7 @@ async fn create_volume(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "volumes",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "volumes", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, VolumeRow>(
         r#"
@@ -362,6 +501,23 @@ async fn create_volume(
             size_bytes,
             filesystem,
             backup_enabled,
+            labels,
+            resource_version,
+            home_node_id,
+            resize_status,
+            resize_error,
+            backup_schedule,
+            backup_retain_count,
+            backup_retain_max_age_secs,
+            backup_policy_updated_at,
+            backup_last_run_at,
+            backup_last_status,
+            restore_id,
+            restore_snapshot_id,
+            restore_status,
+            restore_bytes_restored,
+            restore_bytes_total,
+            restore_error,
             created_at,
             updated_at
         FROM volumes_view
@@ -384,10 +540,18 @@ async fn create_volume(
         name: row.name.clone(),
         size_bytes: row.size_bytes,
         filesystem: row.filesystem.clone(),
+        backup_policy: backup_policy_response(&row),
+        restore: restore_response(&row),
+        labels: labels::from_json(row.labels),
+        resource_version: row.resource_version,
+        home_node_id: row.home_node_id.clone(),
+        resize_status: row.resize_status.clone(),
+        resize_error: row.resize_error.clone(),
         created_at: row.created_at,
         updated_at: Some(row.updated_at),
         attachments: Vec::new(),
     };
+    let resource_version = response.resource_version;
 
     if let Some((key, hash)) = request_hash {
         let body = serde_json::to_value(&response).map_err(|e| {
@@ -412,7 +576,98 @@ async fn create_volume(
         .await;
     }
 
-    Ok((StatusCode::OK, Json(response)).into_response())
+    Ok(preconditions::with_etag(
+        resource_version,
+        (StatusCode::OK, Json(response)),
+    ))
+}
+
+/// The settings of a live volume found by name.
+struct VolumeByName {
+    volume_id: String,
+    size_bytes: i64,
+    filesystem: String,
+    backup_enabled: bool,
+}
+
+/// The live volume named `name` in the org. Names are not unique; several
+/// matches are a conflict rather than a guess.
+async fn find_volume_by_name(
+    state: &AppState,
+    org_id: &OrgId,
+    name: &str,
+    request_id: &str,
+) -> Result<Option<VolumeByName>, ApiError> {
+    let rows = sqlx::query_as::<_, (String, i64, String, bool)>(
+        r#"
+        SELECT volume_id, size_bytes, filesystem, backup_enabled
+        FROM volumes_view
+        WHERE org_id = $1 AND name = $2 AND NOT is_deleted
+        ORDER BY volume_id
+        LIMIT 2
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(name)
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, "Failed to look up volume by name");
+        ApiError::internal("internal_error", "Failed to load volume")
+            .with_request_id(request_id.to_string())
+    })?;
+
+    if rows.len() > 1 {
+        return Err(ApiError::conflict(
+            "volume_name_ambiguous",
+            format!("Several volumes are named '{name}'"),
+        )
+        .with_request_id(request_id.to_string()));
+    }
+
+    Ok(rows
+        .into_iter()
+        .next()
+        .map(
+            |(volume_id, size_bytes, filesystem, backup_enabled)| VolumeByName {
+                volume_id,
+                size_bytes,
+                filesystem,
+                backup_enabled,
+            },
+        ))
+}
+
+/// Update labels on a volume.
+///
+/// PATCH /v1/orgs/{org_id}/volumes/{volume_id}/labels
+async fn patch_volume_labels(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<PatchLabelsRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    super::labels::patch_labels(
+        &state,
+        &ctx,
+        org_id,
+        LabelTarget::Volume { volume_id },
+        &preconditions,
+        req,
+    )
+    .await
 }
 
 /// Get volume.
@@ -445,6 +700,23 @@ async fn get_volume(
             size_bytes,
             filesystem,
             backup_enabled,
+            labels,
+            resource_version,
+            home_node_id,
+            resize_status,
+            resize_error,
+            backup_schedule,
+            backup_retain_count,
+            backup_retain_max_age_secs,
+            backup_policy_updated_at,
+            backup_last_run_at,
+            backup_last_status,
+            restore_id,
+            restore_snapshot_id,
+            restore_status,
+            restore_bytes_restored,
+            restore_bytes_total,
+            restore_error,
             created_at,
             updated_at
         FROM volumes_view
@@ -477,48 +749,692 @@ async fn get_volume(
     .await?;
     let attachments = attachments.remove(&volume_id_str).unwrap_or_default();
 
-    Ok(Json(VolumeResponse {
-        id: row.volume_id.clone(),
-        org_id: row.org_id.clone(),
-        name: row.name.clone(),
-        size_bytes: row.size_bytes,
-        filesystem: row.filesystem.clone(),
-        created_at: row.created_at,
-        updated_at: Some(row.updated_at),
-        attachments,
-    }))
+    Ok(preconditions::with_etag(
+        row.resource_version,
+        Json(VolumeResponse {
+            id: row.volume_id.clone(),
+            org_id: row.org_id.clone(),
+            name: row.name.clone(),
+            size_bytes: row.size_bytes,
+            filesystem: row.filesystem.clone(),
+            backup_policy: backup_policy_response(&row),
+            restore: restore_response(&row),
+            labels: labels::from_json(row.labels),
+            resource_version: row.resource_version,
+            home_node_id: row.home_node_id.clone(),
+            resize_status: row.resize_status.clone(),
+            resize_error: row.resize_error.clone(),
+            created_at: row.created_at,
+            updated_at: Some(row.updated_at),
+            attachments,
+        }),
+    ))
+}
+
+/// Get volume by name, e.g. to import it by name.
+///
+/// GET /v1/orgs/{org_id}/volumes/by-name/{name}
+async fn get_volume_by_name(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, name)): Path<(String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let _role = authz::require_org_member(&state, &org_id, &ctx).await?;
+
+    let Some(volume) = find_volume_by_name(&state, &org_id, &name, &request_id).await? else {
+        return Err(
+            ApiError::not_found("volume_not_found", format!("Volume '{name}' not found"))
+                .with_request_id(request_id),
+        );
+    };
+
+    get_volume(
+        State(state),
+        ctx,
+        Path((org_id.to_string(), volume.volume_id)),
+    )
+    .await
+    .map(IntoResponse::into_response)
+}
+
+/// Delete volume (idempotent for already-deleted volumes).
+///
+/// DELETE /v1/orgs/{org_id}/volumes/{volume_id}
+async fn delete_volume(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+    preconditions: Preconditions,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_type = ctx.actor_type;
+    let actor_id = ctx.actor_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let row = sqlx::query_as::<_, VolumeDeleteRow>(
+        r#"
+        SELECT volume_id, org_id, resource_version, is_deleted, restore_status
+        FROM volumes_view
+        WHERE org_id = $1 AND volume_id = $2
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(volume_id.to_string())
+    .fetch_optional(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, volume_id = %volume_id, "Failed to load volume");
+        ApiError::internal("internal_error", "Failed to delete volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let Some(row) = row else {
+        return Err(
+            ApiError::not_found("volume_not_found", "Volume not found").with_request_id(request_id)
+        );
+    };
+
+    let response = DeleteResponse { ok: true };
+    if row.is_deleted {
+        return Ok((StatusCode::OK, Json(response)).into_response());
+    }
+
+    preconditions.check(None, row.resource_version, false, &request_id)?;
+
+    if restore_active(row.restore_status.as_deref()) {
+        return Err(ApiError::conflict(
+            "volume_restore_in_progress",
+            "Volume is being restored from a snapshot; wait for the restore to finish",
+        )
+        .with_request_id(request_id));
+    }
+
+    let current_seq = state
+        .db()
+        .event_store()
+        .get_latest_aggregate_seq(&AggregateType::Volume, &volume_id.to_string())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to get aggregate sequence");
+            ApiError::internal("internal_error", "Failed to delete volume")
+                .with_request_id(request_id.clone())
+        })?
+        .unwrap_or(0);
+
+    let payload = VolumeDeletedPayload { volume_id, org_id };
+    let payload = serde_json::to_value(&payload).map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to serialize volume delete payload");
+        ApiError::internal("internal_error", "Failed to delete volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let event = AppendEvent {
+        aggregate_type: AggregateType::Volume,
+        aggregate_id: volume_id.to_string(),
+        aggregate_seq: current_seq + 1,
+        event_type: event_types::VOLUME_DELETED.to_string(),
+        event_version: 1,
+        actor_type,
+        actor_id,
+        org_id: Some(org_id),
+        request_id: request_id.clone(),
+        idempotency_key,
+        app_id: None,
+        env_id: None,
+        correlation_id: None,
+        causation_id: None,
+        payload,
+        ..Default::default()
+    };
+
+    let event_id = state.db().event_store().append(event).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to delete volume");
+        ApiError::internal("internal_error", "Failed to delete volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    consistency::record_write(&ctx, event_id.value());
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+/// Restore a deleted volume from the trash, unattached.
+///
+/// POST /v1/orgs/{org_id}/volumes/{volume_id}/undelete
+///
+/// Named `undelete` because `restore` restores a volume from a snapshot.
+async fn undelete_volume(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+) -> Result<Json<RestoreResponse>, ApiError> {
+    let request_id = ctx.request_id.clone();
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let scope = RestoreScope {
+        org_id: &org_id,
+        app_id: None,
+        env_id: None,
+    };
+    trash::restore(
+        &state,
+        &ctx,
+        scope,
+        TrashKind::Volume,
+        &volume_id.to_string(),
+    )
+    .await
+}
+
+/// Grow a volume.
+///
+/// POST /v1/orgs/{org_id}/volumes/{volume_id}/resize
+///
+/// Volumes only grow. The new size is recorded immediately with
+/// `resize_status = pending`; the home node extends the backing storage and
+/// the filesystem online, then reports completion or failure.
+async fn resize_volume(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<ResizeVolumeRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_type = ctx.actor_type;
+    let actor_id = ctx.actor_id.clone();
+    let endpoint_name = "volumes.resize";
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let org_scope = org_id.to_string();
+    let request_hash = idempotency_key
+        .as_deref()
+        .map(|key| {
+            idempotency::request_hash(
+                endpoint_name,
+                &serde_json::json!({ "volume_id": volume_id.to_string(), "request": &req }),
+            )
+            .map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            &state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let row = load_volume_row(&state, &request_id, &org_id, &volume_id, "resize").await?;
+    let Some(row) = row else {
+        return Err(
+            ApiError::not_found("volume_not_found", "Volume not found").with_request_id(request_id)
+        );
+    };
+
+    preconditions.check(None, row.resource_version, false, &request_id)?;
+
+    if req.size_bytes < row.size_bytes {
+        return Err(ApiError::bad_request(
+            "volume_shrink_not_supported",
+            format!(
+                "Volumes can only grow: size_bytes must be >= the current size ({})",
+                row.size_bytes
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    // Resizing to the current size is a no-op rather than a new resize.
+    let row = if req.size_bytes == row.size_bytes {
+        row
+    } else {
+        let current_seq = state
+            .db()
+            .event_store()
+            .get_latest_aggregate_seq(&AggregateType::Volume, &volume_id.to_string())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to get aggregate sequence");
+                ApiError::internal("internal_error", "Failed to resize volume")
+                    .with_request_id(request_id.clone())
+            })?
+            .unwrap_or(0);
+
+        let payload = VolumeResizedPayload {
+            volume_id,
+            org_id,
+            previous_size_bytes: row.size_bytes,
+            size_bytes: req.size_bytes,
+        };
+        let payload = serde_json::to_value(&payload).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize volume resize payload");
+            ApiError::internal("internal_error", "Failed to resize volume")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let event = AppendEvent {
+            aggregate_type: AggregateType::Volume,
+            aggregate_id: volume_id.to_string(),
+            aggregate_seq: current_seq + 1,
+            event_type: event_types::VOLUME_RESIZED.to_string(),
+            event_version: 1,
+            actor_type,
+            actor_id: actor_id.clone(),
+            org_id: Some(org_id),
+            request_id: request_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+            app_id: None,
+            env_id: None,
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            ..Default::default()
+        };
+
+        let event_id = state.db().event_store().append(event).await.map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to resize volume");
+            ApiError::internal("internal_error", "Failed to resize volume")
+                .with_request_id(request_id.clone())
+        })?;
+
+        consistency::wait_for_write(&state, &ctx, "volumes", event_id.value()).await?;
+
+        load_volume_row(&state, &request_id, &org_id, &volume_id, "resize")
+            .await?
+            .ok_or_else(|| {
+                ApiError::not_found("volume_not_found", "Volume not found")
+                    .with_request_id(request_id.clone())
+            })?
+    };
+
+    let response = VolumeResponse {
+        id: row.volume_id.clone(),
+        org_id: row.org_id.clone(),
+        name: row.name.clone(),
+        size_bytes: row.size_bytes,
+        filesystem: row.filesystem.clone(),
+        backup_policy: backup_policy_response(&row),
+        restore: restore_response(&row),
+        labels: labels::from_json(row.labels),
+        resource_version: row.resource_version,
+        home_node_id: row.home_node_id.clone(),
+        resize_status: row.resize_status.clone(),
+        resize_error: row.resize_error.clone(),
+        created_at: row.created_at,
+        updated_at: Some(row.updated_at),
+        attachments: Vec::new(),
+    };
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to resize volume")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            &state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+/// Set or clear a volume's scheduled snapshot policy.
+///
+/// PUT /v1/orgs/{org_id}/volumes/{volume_id}/backup-policy
+async fn set_backup_policy(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Path((org_id, volume_id)): Path<(String, String)>,
+    preconditions: Preconditions,
+    Json(req): Json<SetBackupPolicyRequest>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let idempotency_key = ctx.idempotency_key.clone();
+    let actor_type = ctx.actor_type;
+    let actor_id = ctx.actor_id.clone();
+    let endpoint_name = "volumes.set_backup_policy";
+
+    let org_id: OrgId = org_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
+        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
+    authz::require_org_write(role, &request_id)?;
+
+    let schedule = req
+        .schedule
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            CronSchedule::parse(s).map(|_| s.to_string()).map_err(|e| {
+                ApiError::bad_request("invalid_backup_schedule", e.to_string())
+                    .with_request_id(request_id.clone())
+            })
+        })
+        .transpose()?;
+    if schedule.is_none() && (req.retain_count.is_some() || req.retain_max_age_secs.is_some()) {
+        return Err(ApiError::bad_request(
+            "invalid_backup_retention",
+            "Retention requires a schedule",
+        )
+        .with_request_id(request_id));
+    }
+    if req.retain_count.is_some_and(|count| count < 1) {
+        return Err(ApiError::bad_request(
+            "invalid_backup_retention",
+            "retain_count must be at least 1",
+        )
+        .with_request_id(request_id));
+    }
+    if req.retain_max_age_secs.is_some_and(|secs| secs < 3600) {
+        return Err(ApiError::bad_request(
+            "invalid_backup_retention",
+            "retain_max_age_secs must be at least 3600 (one hour)",
+        )
+        .with_request_id(request_id));
+    }
+
+    let org_scope = org_id.to_string();
+    let request_hash = idempotency_key
+        .as_deref()
+        .map(|key| {
+            idempotency::request_hash(
+                endpoint_name,
+                &serde_json::json!({ "volume_id": volume_id.to_string(), "request": &req }),
+            )
+            .map(|hash| (key.to_string(), hash))
+        })
+        .transpose()
+        .map_err(|e| e.with_request_id(request_id.clone()))?;
+
+    if let Some((key, hash)) = request_hash.as_ref() {
+        if let Some((status, body)) = idempotency::check(
+            &state,
+            &org_scope,
+            &actor_id,
+            endpoint_name,
+            key,
+            hash,
+            &request_id,
+        )
+        .await?
+        {
+            return Ok(
+                (status, Json(body.unwrap_or_else(|| serde_json::json!({})))).into_response(),
+            );
+        }
+    }
+
+    let action = "set backup policy for";
+    let row = load_volume_row(&state, &request_id, &org_id, &volume_id, action).await?;
+    let Some(row) = row else {
+        return Err(
+            ApiError::not_found("volume_not_found", "Volume not found").with_request_id(request_id)
+        );
+    };
+
+    preconditions.check(None, row.resource_version, false, &request_id)?;
+
+    // Re-applying the current policy is a no-op, so it does not reset the
+    // schedule's anchor.
+    let unchanged = row.backup_schedule == schedule
+        && row.backup_retain_count == req.retain_count
+        && row.backup_retain_max_age_secs == req.retain_max_age_secs;
+    let row = if unchanged {
+        row
+    } else {
+        let current_seq = state
+            .db()
+            .event_store()
+            .get_latest_aggregate_seq(&AggregateType::Volume, &volume_id.to_string())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to get aggregate sequence");
+                ApiError::internal("internal_error", "Failed to set backup policy")
+                    .with_request_id(request_id.clone())
+            })?
+            .unwrap_or(0);
+
+        let payload = VolumeBackupPolicySetPayload {
+            volume_id,
+            org_id,
+            schedule,
+            retain_count: req.retain_count,
+            retain_max_age_secs: req.retain_max_age_secs,
+        };
+        let payload = serde_json::to_value(&payload).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize backup policy payload");
+            ApiError::internal("internal_error", "Failed to set backup policy")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let event = AppendEvent {
+            aggregate_type: AggregateType::Volume,
+            aggregate_id: volume_id.to_string(),
+            aggregate_seq: current_seq + 1,
+            event_type: event_types::VOLUME_BACKUP_POLICY_SET.to_string(),
+            event_version: 1,
+            actor_type,
+            actor_id: actor_id.clone(),
+            org_id: Some(org_id),
+            request_id: request_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+            app_id: None,
+            env_id: None,
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            ..Default::default()
+        };
+
+        let event_id = state.db().event_store().append(event).await.map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to set backup policy");
+            ApiError::internal("internal_error", "Failed to set backup policy")
+                .with_request_id(request_id.clone())
+        })?;
+
+        consistency::wait_for_write(&state, &ctx, "volumes", event_id.value()).await?;
+
+        load_volume_row(&state, &request_id, &org_id, &volume_id, action)
+            .await?
+            .ok_or_else(|| {
+                ApiError::not_found("volume_not_found", "Volume not found")
+                    .with_request_id(request_id.clone())
+            })?
+    };
+
+    let response = VolumeResponse {
+        id: row.volume_id.clone(),
+        org_id: row.org_id.clone(),
+        name: row.name.clone(),
+        size_bytes: row.size_bytes,
+        filesystem: row.filesystem.clone(),
+        backup_policy: backup_policy_response(&row),
+        restore: restore_response(&row),
+        labels: labels::from_json(row.labels),
+        resource_version: row.resource_version,
+        home_node_id: row.home_node_id.clone(),
+        resize_status: row.resize_status.clone(),
+        resize_error: row.resize_error.clone(),
+        created_at: row.created_at,
+        updated_at: Some(row.updated_at),
+        attachments: Vec::new(),
+    };
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to set backup policy")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            &state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_scope,
+                actor_id: &actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::OK,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok((StatusCode::OK, Json(response)).into_response())
 }
 
-/// Delete volume (idempotent for already-deleted volumes).
-///
-/// DELETE /v1/orgs/{org_id}/volumes/{volume_id}
-async fn delete_volume(
-    State(state): State<AppState>,
-    ctx: RequestContext,
-    Path((org_id, volume_id)): Path<(String, String)>,
-) -> Result<Response, ApiError> {
-    let request_id = ctx.request_id.clone();
-    let idempotency_key = ctx.idempotency_key.clone();
-    let actor_type = ctx.actor_type;
-    let actor_id = ctx.actor_id.clone();
+/// The volume's backup policy with its next and last runs.
+fn backup_policy_response(row: &VolumeRow) -> Option<BackupPolicyResponse> {
+    let schedule = row.backup_schedule.clone()?;
+    let next_run_at = CronSchedule::parse(&schedule)
+        .ok()
+        .zip(row.backup_policy_updated_at)
+        .and_then(|(cron, updated_at)| {
+            backups::next_run(&cron, updated_at, row.backup_last_run_at)
+        });
+    Some(BackupPolicyResponse {
+        schedule,
+        retain_count: row.backup_retain_count,
+        retain_max_age_secs: row.backup_retain_max_age_secs,
+        next_run_at,
+        last_run_at: row.backup_last_run_at,
+        last_run_status: row.backup_last_status.clone(),
+    })
+}
 
-    let org_id: OrgId = org_id.parse().map_err(|_| {
-        ApiError::bad_request("invalid_org_id", "Invalid organization ID format")
-            .with_request_id(request_id.clone())
-    })?;
-    let volume_id: VolumeId = volume_id.parse().map_err(|_| {
-        ApiError::bad_request("invalid_volume_id", "Invalid volume ID format")
-            .with_request_id(request_id.clone())
-    })?;
+/// The volume's most recent in-place restore.
+fn restore_response(row: &VolumeRow) -> Option<VolumeRestoreResponse> {
+    Some(VolumeRestoreResponse {
+        id: row.restore_id.clone()?,
+        snapshot_id: row.restore_snapshot_id.clone().unwrap_or_default(),
+        status: row.restore_status.clone().unwrap_or_default(),
+        bytes_restored: row.restore_bytes_restored,
+        bytes_total: row.restore_bytes_total,
+        failed_reason: row.restore_error.clone(),
+    })
+}
 
-    let role = authz::require_org_member(&state, &org_id, &ctx).await?;
-    authz::require_org_write(role, &request_id)?;
+/// Whether an in-place restore in this state still owns the volume.
+fn restore_active(status: Option<&str>) -> bool {
+    matches!(status, Some("queued" | "running"))
+}
 
-    let row = sqlx::query_as::<_, VolumeDeleteRow>(
+/// Load a live volume row; `action` names the operation in error messages.
+async fn load_volume_row(
+    state: &AppState,
+    request_id: &str,
+    org_id: &OrgId,
+    volume_id: &VolumeId,
+    action: &str,
+) -> Result<Option<VolumeRow>, ApiError> {
+    sqlx::query_as::<_, VolumeRow>(
         r#"
-        SELECT volume_id, org_id, is_deleted
+        SELECT
+            volume_id,
+            org_id,
+            name,
+            size_bytes,
+            filesystem,
+            backup_enabled,
+            labels,
+            resource_version,
+            home_node_id,
+            resize_status,
+            resize_error,
+            backup_schedule,
+            backup_retain_count,
+            backup_retain_max_age_secs,
+            backup_policy_updated_at,
+            backup_last_run_at,
+            backup_last_status,
+            restore_id,
+            restore_snapshot_id,
+            restore_status,
+            restore_bytes_restored,
+            restore_bytes_total,
+            restore_error,
+            created_at,
+            updated_at
         FROM volumes_view
-        WHERE org_id = $1 AND volume_id = $2
+        WHERE org_id = $1 AND volume_id = $2 AND NOT is_deleted
         "#,
     )
     .bind(org_id.to_string())
@@ -527,81 +1443,9 @@ async fn delete_volume(
     .await
     .map_err(|e| {
         tracing::error!(error = %e, request_id = %request_id, org_id = %org_id, volume_id = %volume_id, "Failed to load volume");
-        ApiError::internal("internal_error", "Failed to delete volume")
-            .with_request_id(request_id.clone())
-    })?;
-
-    let Some(row) = row else {
-        return Err(
-            ApiError::not_found("volume_not_found", "Volume not found").with_request_id(request_id)
-        );
-    };
-
-    let response = DeleteResponse { ok: true };
-    if row.is_deleted {
-        return Ok((StatusCode::OK, Json(response)).into_response());
-    }
-
-    let current_seq = state
-        .db()
-        .event_store()
-        .get_latest_aggregate_seq(&AggregateType::Volume, &volume_id.to_string())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to get aggregate sequence");
-            ApiError::internal("internal_error", "Failed to delete volume")
-                .with_request_id(request_id.clone())
-        })?
-        .unwrap_or(0);
-
-    let payload = VolumeDeletedPayload { volume_id, org_id };
-    let payload = serde_json::to_value(&payload).map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, "Failed to serialize volume delete payload");
-        ApiError::internal("internal_error", "Failed to delete volume")
-            .with_request_id(request_id.clone())
-    })?;
-
-    let event = AppendEvent {
-        aggregate_type: AggregateType::Volume,
-        aggregate_id: volume_id.to_string(),
-        aggregate_seq: current_seq + 1,
-        event_type: event_types::VOLUME_DELETED.to_string(),
-        event_version: 1,
-        actor_type,
-        actor_id,
-        org_id: Some(org_id),
-        request_id: request_id.clone(),
-        idempotency_key,
-        app_id: None,
-        env_id: None,
-        correlation_id: None,
-        causation_id: None,
-        payload,
-        ..Default::default()
-    };
-
-    let event_id = state.db().event_store().append(event).await.map_err(|e| {
-        tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to delete volume");
-        ApiError::internal("internal_error", "Failed to delete volume")
-            .with_request_id(request_id.clone())
-    })?;
-
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "volumes",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
-
-    Ok((StatusCode::OK, Json(response)).into_response())
+        ApiError::internal("internal_error", format!("Failed to {action} volume"))
+            .with_request_id(request_id.to_string())
+    })
 }
 
 /// Create snapshot for a volume.
@@ -699,6 +1543,7 @@ async fn create_snapshot(
         volume_id,
         status: JobStatus::Queued,
         note,
+        scheduled: false,
     };
 
     let payload = serde_json::to_value(&payload).map_err(|e| {
@@ -732,20 +1577,7 @@ async fn create_snapshot(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "snapshots",
-            event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "snapshots", event_id.value()).await?;
 
     let row = sqlx::query_as::<_, SnapshotRow>(
         r#"
@@ -874,9 +1706,13 @@ async fn list_snapshots(
     Ok(Json(ListSnapshotsResponse { items, next_cursor }))
 }
 
-/// Restore volume from snapshot (creates a new volume).
+/// Restore volume from snapshot.
 ///
 /// POST /v1/orgs/{org_id}/volumes/{volume_id}/restore
+///
+/// By default the snapshot is restored into a new volume. With `in_place`
+/// the volume itself is overwritten by its home node; see
+/// [`restore_in_place`].
 async fn restore_volume(
     State(state): State<AppState>,
     ctx: RequestContext,
@@ -942,6 +1778,23 @@ async fn restore_volume(
             size_bytes,
             filesystem,
             backup_enabled,
+            labels,
+            resource_version,
+            home_node_id,
+            resize_status,
+            resize_error,
+            backup_schedule,
+            backup_retain_count,
+            backup_retain_max_age_secs,
+            backup_policy_updated_at,
+            backup_last_run_at,
+            backup_last_status,
+            restore_id,
+            restore_snapshot_id,
+            restore_status,
+            restore_bytes_restored,
+            restore_bytes_total,
+            restore_error,
             created_at,
             updated_at
         FROM volumes_view
@@ -996,6 +1849,17 @@ async fn restore_volume(
         );
     }
 
+    if req.in_place {
+        if req.new_volume_name.is_some() {
+            return Err(ApiError::bad_request(
+                "invalid_new_volume_name",
+                "new_volume_name cannot be combined with in_place",
+            )
+            .with_request_id(request_id));
+        }
+        return restore_in_place(&state, &ctx, source, snapshot, request_hash).await;
+    }
+
     let new_volume_id = VolumeId::new();
     let restore_id = RestoreJobId::new();
     let new_name = req.new_volume_name.as_ref().map(|s| s.trim().to_string());
@@ -1120,20 +1984,7 @@ async fn restore_volume(
             .with_request_id(request_id.clone())
     })?;
 
-    state
-        .db()
-        .projection_store()
-        .wait_for_checkpoint(
-            "volumes",
-            volume_event_id.value(),
-            crate::api::projection_wait_timeout(),
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Projection wait failed");
-            ApiError::gateway_timeout("projection_timeout", "Request timed out waiting for state")
-                .with_request_id(request_id.clone())
-        })?;
+    consistency::wait_for_write(&state, &ctx, "volumes", volume_event_id.value()).await?;
 
     let row = sqlx::query_as::<_, VolumeRow>(
         r#"
@@ -1144,6 +1995,23 @@ async fn restore_volume(
             size_bytes,
             filesystem,
             backup_enabled,
+            labels,
+            resource_version,
+            home_node_id,
+            resize_status,
+            resize_error,
+            backup_schedule,
+            backup_retain_count,
+            backup_retain_max_age_secs,
+            backup_policy_updated_at,
+            backup_last_run_at,
+            backup_last_status,
+            restore_id,
+            restore_snapshot_id,
+            restore_status,
+            restore_bytes_restored,
+            restore_bytes_total,
+            restore_error,
             created_at,
             updated_at
         FROM volumes_view
@@ -1166,6 +2034,13 @@ async fn restore_volume(
         name: row.name.clone(),
         size_bytes: row.size_bytes,
         filesystem: row.filesystem.clone(),
+        backup_policy: backup_policy_response(&row),
+        restore: restore_response(&row),
+        labels: labels::from_json(row.labels),
+        resource_version: row.resource_version,
+        home_node_id: row.home_node_id.clone(),
+        resize_status: row.resize_status.clone(),
+        resize_error: row.resize_error.clone(),
         created_at: row.created_at,
         updated_at: Some(row.updated_at),
         attachments: Vec::new(),
@@ -1197,6 +2072,205 @@ async fn restore_volume(
     Ok((StatusCode::OK, Json(response)).into_response())
 }
 
+/// Overwrite a volume with one of its snapshots.
+///
+/// Refused while any process type mounting the volume still has instances:
+/// the env must be scaled down (or the volume detached) first. The restore is
+/// queued for the volume's home node, which reports progress as it writes;
+/// until it finishes the scheduler keeps those process types at zero.
+/// Responds `202 Accepted` with the volume and its `restore` progress.
+async fn restore_in_place(
+    state: &AppState,
+    ctx: &RequestContext,
+    volume: VolumeRow,
+    snapshot: SnapshotRow,
+    request_hash: Option<(String, String)>,
+) -> Result<Response, ApiError> {
+    let request_id = ctx.request_id.clone();
+    let endpoint_name = "volumes.restore";
+
+    let org_id: OrgId = volume.org_id.parse().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid org_id in volumes_view")
+            .with_request_id(request_id.clone())
+    })?;
+    let volume_id: VolumeId = volume.volume_id.parse().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid volume_id in volumes_view")
+            .with_request_id(request_id.clone())
+    })?;
+    let snapshot_id: SnapshotId = snapshot.snapshot_id.parse().map_err(|_| {
+        ApiError::internal("internal_error", "Invalid snapshot_id in snapshots_view")
+            .with_request_id(request_id.clone())
+    })?;
+
+    if restore_active(volume.restore_status.as_deref()) {
+        return Err(ApiError::conflict(
+            "volume_restore_in_progress",
+            "Volume is already being restored from a snapshot",
+        )
+        .with_request_id(request_id));
+    }
+
+    if snapshot.status != "succeeded" {
+        return Err(ApiError::conflict(
+            "snapshot_not_ready",
+            format!(
+                "Snapshot {} is {}; only succeeded snapshots can be restored in place",
+                snapshot.snapshot_id, snapshot.status
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    if volume.home_node_id.is_none() {
+        return Err(ApiError::conflict(
+            "volume_not_placed",
+            "Volume has no home node yet, so there is no data to overwrite",
+        )
+        .with_request_id(request_id));
+    }
+
+    // A group is active while any of its instances is wanted or still up.
+    let active = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT DISTINCT a.env_id, a.process_type
+        FROM volume_attachments_view a
+        JOIN instances_desired_view i
+            ON i.env_id = a.env_id AND i.process_type = a.process_type
+        LEFT JOIN instances_status_view s ON s.instance_id = i.instance_id
+        WHERE a.volume_id = $1
+          AND NOT a.is_deleted
+          AND (i.desired_state <> 'stopped'
+               OR s.status IN ('booting', 'ready', 'draining'))
+        ORDER BY a.env_id, a.process_type
+        "#,
+    )
+    .bind(volume_id.to_string())
+    .fetch_all(state.db().pool())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, volume_id = %volume_id, "Failed to load active attachments");
+        ApiError::internal("internal_error", "Failed to restore volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    if !active.is_empty() {
+        let groups: Vec<String> = active
+            .iter()
+            .map(|(env_id, process_type)| format!("{env_id}/{process_type}"))
+            .collect();
+        return Err(ApiError::conflict(
+            "volume_attachment_active",
+            format!(
+                "Volume is mounted by running instances of {}; scale them to 0 or detach the volume first",
+                groups.join(", ")
+            ),
+        )
+        .with_request_id(request_id));
+    }
+
+    let restore_id = RestoreJobId::new();
+    let payload = RestoreJobCreatedPayload {
+        restore_id,
+        org_id,
+        snapshot_id,
+        source_volume_id: volume_id,
+        new_volume_name: None,
+        status: JobStatus::Queued,
+        in_place: true,
+    };
+    let payload = serde_json::to_value(&payload).map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to serialize restore payload");
+        ApiError::internal("internal_error", "Failed to restore volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let event = AppendEvent {
+        aggregate_type: AggregateType::RestoreJob,
+        aggregate_id: restore_id.to_string(),
+        aggregate_seq: 1,
+        event_type: event_types::RESTORE_JOB_CREATED.to_string(),
+        event_version: 1,
+        actor_type: ctx.actor_type,
+        actor_id: ctx.actor_id.clone(),
+        org_id: Some(org_id),
+        request_id: request_id.clone(),
+        idempotency_key: ctx.idempotency_key.clone(),
+        app_id: None,
+        env_id: None,
+        correlation_id: None,
+        causation_id: None,
+        payload,
+        ..Default::default()
+    };
+
+    let event_id = state.db().event_store().append(event).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, restore_id = %restore_id, "Failed to append restore event");
+        ApiError::internal("internal_error", "Failed to restore volume")
+            .with_request_id(request_id.clone())
+    })?;
+
+    consistency::wait_for_write(state, ctx, "restore_jobs", event_id.value()).await?;
+
+    let Some(row) = load_volume_row(state, &request_id, &org_id, &volume_id, "restore").await?
+    else {
+        return Err(
+            ApiError::not_found("volume_not_found", "Volume not found").with_request_id(request_id)
+        );
+    };
+
+    let volume_id_str = volume_id.to_string();
+    let mut attachments = load_attachments_for_volumes(
+        state,
+        &request_id,
+        &org_id,
+        std::slice::from_ref(&volume_id_str),
+    )
+    .await?;
+
+    let response = VolumeResponse {
+        id: row.volume_id.clone(),
+        org_id: row.org_id.clone(),
+        name: row.name.clone(),
+        size_bytes: row.size_bytes,
+        filesystem: row.filesystem.clone(),
+        backup_policy: backup_policy_response(&row),
+        restore: restore_response(&row),
+        labels: labels::from_json(row.labels),
+        resource_version: row.resource_version,
+        home_node_id: row.home_node_id.clone(),
+        resize_status: row.resize_status.clone(),
+        resize_error: row.resize_error.clone(),
+        created_at: row.created_at,
+        updated_at: Some(row.updated_at),
+        attachments: attachments.remove(&volume_id_str).unwrap_or_default(),
+    };
+
+    if let Some((key, hash)) = request_hash {
+        let body = serde_json::to_value(&response).map_err(|e| {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to serialize response");
+            ApiError::internal("internal_error", "Failed to restore volume")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let _ = idempotency::store(
+            state,
+            idempotency::StoreIdempotencyParams {
+                org_scope: &org_id.to_string(),
+                actor_id: &ctx.actor_id,
+                endpoint_name,
+                idempotency_key: &key,
+                request_hash: &hash,
+                status: StatusCode::ACCEPTED,
+                body: Some(body),
+            },
+            &request_id,
+        )
+        .await;
+    }
+
+    Ok((StatusCode::ACCEPTED, Json(response)).into_response())
+}
+
 // =============================================================================
 // Helpers
 // =============================================================================
@@ -1254,6 +2328,59 @@ async fn load_attachments_for_volumes(
 // DB Row Types
 // =============================================================================
 
+/// Fields and relations of listed volumes.
+static VOLUME_SHAPE: Shape = Shape {
+    key: "id",
+    fields: &[
+        "org_id",
+        "name",
+        "size_bytes",
+        "filesystem",
+        "labels",
+        "resource_version",
+        "home_node_id",
+        "resize_status",
+        "resize_error",
+        "backup_policy",
+        "restore",
+        "created_at",
+        "updated_at",
+    ],
+    relations: &[("attachments", true)],
+};
+
+/// Orderings accepted when listing volumes.
+static VOLUME_SORTING: Sorting<VolumeRow> = Sorting {
+    id_column: "volume_id",
+    id: |row| row.volume_id.clone(),
+    keys: &[
+        SortKey {
+            name: "id",
+            column: "volume_id",
+            sql_type: "TEXT",
+            value: |row| row.volume_id.clone(),
+        },
+        SortKey {
+            name: "size_bytes",
+            column: "size_bytes",
+            sql_type: "BIGINT",
+            value: |row| row.size_bytes.to_string(),
+        },
+        SortKey {
+            name: "created_at",
+            column: "created_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.created_at),
+        },
+        SortKey {
+            name: "updated_at",
+            column: "updated_at",
+            sql_type: "TIMESTAMPTZ",
+            value: |row| pagination::timestamp(&row.updated_at),
+        },
+    ],
+};
+
 #[derive(Debug)]
 struct VolumeRow {
     volume_id: String,
@@ -1262,6 +2389,23 @@ struct VolumeRow {
     size_bytes: i64,
     filesystem: String,
     backup_enabled: bool,
+    labels: serde_json::Value,
+    resource_version: i32,
+    home_node_id: Option<String>,
+    resize_status: Option<String>,
+    resize_error: Option<String>,
+    backup_schedule: Option<String>,
+    backup_retain_count: Option<i32>,
+    backup_retain_max_age_secs: Option<i64>,
+    backup_policy_updated_at: Option<DateTime<Utc>>,
+    backup_last_run_at: Option<DateTime<Utc>>,
+    backup_last_status: Option<String>,
+    restore_id: Option<String>,
+    restore_snapshot_id: Option<String>,
+    restore_status: Option<String>,
+    restore_bytes_restored: Option<i64>,
+    restore_bytes_total: Option<i64>,
+    restore_error: Option<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -1276,6 +2420,23 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeRow {
             size_bytes: row.try_get("size_bytes")?,
             filesystem: row.try_get("filesystem")?,
             backup_enabled: row.try_get("backup_enabled")?,
+            labels: row.try_get("labels")?,
+            resource_version: row.try_get("resource_version")?,
+            home_node_id: row.try_get("home_node_id")?,
+            resize_status: row.try_get("resize_status")?,
+            resize_error: row.try_get("resize_error")?,
+            backup_schedule: row.try_get("backup_schedule")?,
+            backup_retain_count: row.try_get("backup_retain_count")?,
+            backup_retain_max_age_secs: row.try_get("backup_retain_max_age_secs")?,
+            backup_policy_updated_at: row.try_get("backup_policy_updated_at")?,
+            backup_last_run_at: row.try_get("backup_last_run_at")?,
+            backup_last_status: row.try_get("backup_last_status")?,
+            restore_id: row.try_get("restore_id")?,
+            restore_snapshot_id: row.try_get("restore_snapshot_id")?,
+            restore_status: row.try_get("restore_status")?,
+            restore_bytes_restored: row.try_get("restore_bytes_restored")?,
+            restore_bytes_total: row.try_get("restore_bytes_total")?,
+            restore_error: row.try_get("restore_error")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
         })
@@ -1288,7 +2449,9 @@ struct VolumeDeleteRow {
     volume_id: String,
     #[allow(dead_code)]
     org_id: String,
+    resource_version: i32,
     is_deleted: bool,
+    restore_status: Option<String>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeDeleteRow {
@@ -1297,7 +2460,9 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeDeleteRow {
         Ok(Self {
             volume_id: row.try_get("volume_id")?,
             org_id: row.try_get("org_id")?,
+            resource_version: row.try_get("resource_version")?,
             is_deleted: row.try_get("is_deleted")?,
+            restore_status: row.try_get("restore_status")?,
         })
     }
 }
@@ -0,0 +1,133 @@
+//! Workload self-service endpoints.
+//!
+//! Called from inside instances, authenticated by the instance identity
+//! token guest-init writes to `PLFM_IDENTITY_TOKEN_FILE`, not by a user or
+//! service principal access token.
+//!
+//! - `GET /v1/workload/identity`: the verified token claims
+//! - `GET /v1/workload/config-vars`: config vars of the caller's env
+//!
+//! See: docs/security/08-platform-pki.md (Instance identity tokens)
+
+use axum::{
+    extract::{FromRequestParts, State},
+    http::request::Parts,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use plfm_id::{EnvId, RequestId};
+use plfm_pki::{IdentityClaims, PkiError};
+
+use crate::api::error::ApiError;
+use crate::api::request_context::AUTHORIZATION_HEADER;
+use crate::identity_tokens;
+use crate::pki::CaError;
+use crate::state::AppState;
+
+use super::env_config_vars::load_config_vars;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/identity", get(get_identity))
+        .route("/config-vars", get(get_config_vars))
+}
+
+/// Caller authenticated by an instance identity token.
+#[derive(Debug, Clone)]
+pub struct WorkloadIdentity {
+    pub request_id: String,
+    pub claims: IdentityClaims,
+}
+
+impl FromRequestParts<AppState> for WorkloadIdentity {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .headers
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| RequestId::new().to_string());
+
+        let token = parts
+            .headers
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| {
+                ApiError::unauthorized(
+                    "invalid_identity_token",
+                    "Authorization must be a Bearer instance identity token",
+                )
+                .with_request_id(request_id.clone())
+            })?;
+
+        let claims = identity_tokens::verify(state.db().pool(), token)
+            .await
+            .map_err(|e| identity_error(e, &request_id))?;
+        Ok(Self { request_id, claims })
+    }
+}
+
+fn identity_error(error: CaError, request_id: &str) -> ApiError {
+    let api_error = match error {
+        CaError::Pki(e @ PkiError::InvalidToken(_)) => {
+            ApiError::unauthorized("invalid_identity_token", e.to_string())
+        }
+        other => {
+            tracing::error!(error = %other, request_id = %request_id, "Identity token verification failed");
+            ApiError::internal("internal_error", "Identity token verification failed")
+        }
+    };
+    api_error.with_request_id(request_id.to_string())
+}
+
+/// GET /v1/workload/identity
+async fn get_identity(identity: WorkloadIdentity) -> impl IntoResponse {
+    Json(identity.claims)
+}
+
+/// GET /v1/workload/config-vars
+async fn get_config_vars(
+    State(state): State<AppState>,
+    identity: WorkloadIdentity,
+) -> Result<impl IntoResponse, ApiError> {
+    let request_id = identity.request_id;
+    let env_id: EnvId = identity.claims.env_id.parse().map_err(|_| {
+        ApiError::unauthorized("invalid_identity_token", "Token names an invalid env")
+            .with_request_id(request_id.clone())
+    })?;
+
+    let response = load_config_vars(&state, &env_id).await.map_err(|e| {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to load config vars");
+        ApiError::internal("internal_error", "Failed to load config vars")
+            .with_request_id(request_id.clone())
+    })?;
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_identity_error_mapping() {
+        let err = identity_error(
+            CaError::Pki(PkiError::InvalidToken("expired".to_string())),
+            "req_1",
+        );
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.problem.code, "invalid_identity_token");
+
+        let err = identity_error(CaError::Database(sqlx::Error::PoolClosed), "req_1");
+        assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,187 @@
+//! Request payload validation.
+//!
+//! [`ValidJson`] replaces `axum::Json` for request bodies: it requires a JSON
+//! content type, maps deserialization failures to `400 invalid_json` with the
+//! offending field, and runs the payload's [`Validate`] impl so every
+//! violation is reported in `details` rather than only the first.
+//!
+//! Size and nesting limits are enforced earlier by
+//! [`limits::limit_request_body`](crate::api::limits::limit_request_body).
+//!
+//! See: docs/specs/api/http-api.md
+
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::header,
+};
+use serde::de::DeserializeOwned;
+
+pub use plfm_api_types::{Validate, Validator};
+
+use crate::api::error::{ApiError, FieldError};
+
+/// Validate `payload`, returning the combined error for any violations.
+///
+/// The problem code and message are those of the first violation so clients
+/// matching on specific codes (e.g. `invalid_name`) keep working; `details`
+/// lists all of them.
+pub fn validate<T: Validate>(payload: &T) -> Result<(), ApiError> {
+    let mut v = Validator::new();
+    payload.validate(&mut v);
+    let violations = v.into_violations();
+    let Some((code, first)) = violations.first() else {
+        return Ok(());
+    };
+    let error = ApiError::bad_request(*code, first.message.clone());
+    let details = violations.into_iter().map(|(_, detail)| detail).collect();
+    Err(error.with_details(details))
+}
+
+/// JSON request body, deserialized and validated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidJson<T>(pub T);
+
+fn is_json_content_type(value: &str) -> bool {
+    let Some(essence) = value.split(';').next() else {
+        return false;
+    };
+    let essence = essence.trim().to_ascii_lowercase();
+    essence == "application/json"
+        || (essence.starts_with("application/") && essence.ends_with("+json"))
+}
+
+/// Best-effort field name from a serde_json error message.
+fn field_of(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    message
+        .split('`')
+        .nth(1)
+        .filter(|_| message.starts_with("missing field") || message.starts_with("unknown field"))
+        .unwrap_or("body")
+        .to_string()
+}
+
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let request_id = req
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok());
+        if !content_type.is_some_and(is_json_content_type) {
+            return Err(ApiError::unsupported_media_type(
+                "unsupported_media_type",
+                "Request body must be JSON (Content-Type: application/json)",
+            )
+            .with_request_id(request_id));
+        }
+
+        let bytes = Bytes::from_request(req, state).await.map_err(|_| {
+            ApiError::bad_request("invalid_json", "Failed to read request body")
+                .with_request_id(request_id.clone())
+        })?;
+
+        let payload: T = serde_json::from_slice(&bytes).map_err(|e| {
+            ApiError::bad_request("invalid_json", format!("Invalid JSON request body: {e}"))
+                .with_details(vec![FieldError::new(field_of(&e), e.to_string())])
+                .with_request_id(request_id.clone())
+        })?;
+
+        validate(&payload).map_err(|e| e.with_request_id(request_id))?;
+        Ok(ValidJson(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Payload {
+        name: String,
+        replicas: i32,
+    }
+
+    impl Validate for Payload {
+        fn validate(&self, v: &mut Validator) {
+            v.name("name", &self.name, "Widget", 10);
+            v.check(
+                self.replicas >= 0,
+                "invalid_replicas",
+                "replicas",
+                "replicas must be >= 0",
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_collects_all_violations() {
+        let payload = Payload {
+            name: String::new(),
+            replicas: -1,
+        };
+        let err = validate(&payload).unwrap_err();
+        assert_eq!(err.problem.code, "invalid_name");
+        assert_eq!(err.problem.detail, "Widget name cannot be empty");
+
+        let details = err.problem.details.as_ref().unwrap();
+        let fields: Vec<_> = details.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, ["name", "replicas"]);
+    }
+
+    #[test]
+    fn test_validate_name_length() {
+        let payload = Payload {
+            name: "x".repeat(11),
+            replicas: 1,
+        };
+        let err = validate(&payload).unwrap_err();
+        assert_eq!(
+            err.problem.detail,
+            "Widget name cannot exceed 10 characters"
+        );
+
+        let payload = Payload {
+            name: "ok".to_string(),
+            replicas: 1,
+        };
+        assert!(validate(&payload).is_ok());
+    }
+
+    #[test]
+    fn test_json_content_type() {
+        assert!(is_json_content_type("application/json"));
+        assert!(is_json_content_type("application/json; charset=utf-8"));
+        assert!(is_json_content_type("application/merge-patch+json"));
+        assert!(!is_json_content_type("text/plain"));
+        assert!(!is_json_content_type("application/x-www-form-urlencoded"));
+    }
+
+    #[test]
+    fn test_field_of_serde_error() {
+        let err = serde_json::from_str::<Payload>(r#"{"replicas": 1}"#).unwrap_err();
+        assert_eq!(field_of(&err), "name");
+
+        let err =
+            serde_json::from_str::<Payload>(r#"{"name": "a", "replicas": 1, "x": 2}"#).unwrap_err();
+        assert_eq!(field_of(&err), "x");
+
+        let err = serde_json::from_str::<Payload>(r#"{"name": 5"#).unwrap_err();
+        assert_eq!(field_of(&err), "body");
+    }
+}
@@ -0,0 +1,651 @@
+//! Scheduled volume snapshots and retention.
+//!
+//! A volume's backup policy (`volume.backup_policy_set`) holds a five-field
+//! cron schedule, evaluated in UTC, and optional retention limits: a count of
+//! successful scheduled snapshots to keep and a maximum age. Every minute the
+//! backup scheduler (leader only) queues a snapshot (`snapshot.created` with
+//! `scheduled: true`) for each volume whose next run is due, then prunes
+//! scheduled snapshots that fall outside retention (`snapshot.deleted`).
+//!
+//! Runs missed while no replica led are not replayed: a late volume gets one
+//! snapshot and its schedule continues from there. Manual snapshots are never
+//! pruned.
+//!
+//! See: docs/specs/storage/volumes.md
+
+use std::collections::HashMap;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+use plfm_events::{
+    AggregateType, JobStatus, NewEvent, SnapshotCreatedPayload, SnapshotDeletedPayload,
+    SystemSource,
+};
+use plfm_id::{OrgId, SnapshotId, VolumeId};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use crate::db::{DbError, EventStore};
+use crate::leader::{LeaderElection, LeaderRole};
+
+/// Actor ID for events written by the backup scheduler.
+pub const BACKUP_SCHEDULER_ACTOR_ID: &str = "backup-scheduler";
+
+/// Note attached to scheduled snapshots.
+const SCHEDULED_NOTE: &str = "scheduled backup";
+
+/// How far ahead to look for the next run before giving up (e.g. `0 0 30 2 *`).
+const MAX_LOOKAHEAD_DAYS: i64 = 4 * 366;
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+// =============================================================================
+// Schedules
+// =============================================================================
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid cron expression: {0}")]
+pub struct InvalidSchedule(String);
+
+/// A parsed five-field cron expression (`minute hour day-of-month month
+/// day-of-week`).
+///
+/// Fields accept `*`, numbers, ranges (`1-5`), steps (`*/15`, `0-30/10`),
+/// and comma-separated lists. Day-of-week is 0-7 with both 0 and 7 meaning
+/// Sunday. As in cron, when both day fields are restricted a day matching
+/// either one fires. `@hourly`, `@daily`, `@weekly`, and `@monthly` are
+/// accepted as shorthands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, InvalidSchedule> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            return Err(InvalidSchedule(format!(
+                "expected 5 fields, got {}",
+                fields.len()
+            )));
+        };
+
+        let mut days_of_week = parse_field(dow, 0, 7)?;
+        // 7 is an alias for Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(dom, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            any_day_of_month: dom == "*",
+            any_day_of_week: dow == "*",
+        })
+    }
+
+    /// The first matching minute strictly after `after`, if any within a few
+    /// years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let limit = after + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        while t <= limit {
+            if !has(self.months, t.month()) {
+                t = start_of_next_month(t)?;
+            } else if !self.day_matches(t) {
+                t = start_of_day(t) + Duration::days(1);
+            } else if !has(self.hours, t.hour()) {
+                t = t.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let dom = has(self.days_of_month, t.day());
+        let dow = has(self.days_of_week, t.weekday().num_days_from_sunday());
+        if self.any_day_of_month || self.any_day_of_week {
+            dom && dow
+        } else {
+            dom || dow
+        }
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn start_of_day(t: DateTime<Utc>) -> DateTime<Utc> {
+    t.date_naive()
+        .and_hms_opt(0, 0, 0)
+        .map(|d| d.and_utc())
+        .unwrap_or(t)
+}
+
+fn start_of_next_month(t: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let (year, month) = if t.month() == 12 {
+        (t.year() + 1, 1)
+    } else {
+        (t.year(), t.month() + 1)
+    };
+    Some(
+        chrono::NaiveDate::from_ymd_opt(year, month, 1)?
+            .and_hms_opt(0, 0, 0)?
+            .and_utc(),
+    )
+}
+
+/// Parse one cron field into a bit set of the values in `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, InvalidSchedule> {
+    let invalid = || InvalidSchedule(format!("bad field '{field}' (allowed {min}-{max})"));
+    let number = |s: &str| -> Result<u32, InvalidSchedule> {
+        s.parse::<u32>()
+            .ok()
+            .filter(|n| (min..=max).contains(n))
+            .ok_or_else(invalid)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (number(a)?, number(b)?),
+                // `5/15` means every 15 starting at 5.
+                None if part.contains('/') => (number(range)?, max),
+                None => {
+                    let n = number(range)?;
+                    (n, n)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+/// When a volume's next scheduled snapshot is due: the first run after both
+/// the policy change and the last scheduled snapshot.
+pub fn next_run(
+    schedule: &CronSchedule,
+    policy_updated_at: DateTime<Utc>,
+    last_run_at: Option<DateTime<Utc>>,
+) -> Option<DateTime<Utc>> {
+    let anchor = last_run_at.map_or(policy_updated_at, |last| last.max(policy_updated_at));
+    schedule.next_after(anchor)
+}
+
+// =============================================================================
+// Retention
+// =============================================================================
+
+/// A scheduled snapshot considered for pruning.
+#[derive(Debug, Clone)]
+pub struct ScheduledSnapshot {
+    pub snapshot_id: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for ScheduledSnapshot {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            snapshot_id: row.try_get("snapshot_id")?,
+            status: row.try_get("status")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// Snapshots that fall outside retention.
+///
+/// Queued and running snapshots are never pruned, and neither is the newest
+/// successful one (as in the backups spec). Beyond that, the newest
+/// `retain_count` successful snapshots are kept, failed ones are pruned once
+/// a newer snapshot succeeded, and anything older than `retain_max_age` goes.
+pub fn expired(
+    snapshots: &[ScheduledSnapshot],
+    retain_count: Option<i32>,
+    retain_max_age: Option<Duration>,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut newest_first: Vec<&ScheduledSnapshot> = snapshots.iter().collect();
+    newest_first.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+
+    let mut kept = 0i32;
+    let mut expired = Vec::new();
+    for snapshot in newest_first {
+        let too_old = retain_max_age.is_some_and(|age| snapshot.created_at + age < now);
+        let prune = match snapshot.status.as_str() {
+            "queued" | "running" => false,
+            "succeeded" => {
+                kept += 1;
+                kept > 1 && (too_old || retain_count.is_some_and(|count| kept > count))
+            }
+            _ => too_old || kept > 0,
+        };
+        if prune {
+            expired.push(snapshot.snapshot_id.clone());
+        }
+    }
+    expired
+}
+
+// =============================================================================
+// Pass
+// =============================================================================
+
+/// A volume with a backup policy.
+#[derive(Debug, Clone)]
+struct PolicyRow {
+    volume_id: String,
+    org_id: String,
+    schedule: String,
+    retain_count: Option<i32>,
+    retain_max_age_secs: Option<i64>,
+    policy_updated_at: DateTime<Utc>,
+    last_run_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> FromRow<'r, PgRow> for PolicyRow {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            volume_id: row.try_get("volume_id")?,
+            org_id: row.try_get("org_id")?,
+            schedule: row.try_get("backup_schedule")?,
+            retain_count: row.try_get("backup_retain_count")?,
+            retain_max_age_secs: row.try_get("backup_retain_max_age_secs")?,
+            policy_updated_at: row.try_get("backup_policy_updated_at")?,
+            last_run_at: row.try_get("backup_last_run_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PassSummary {
+    pub volumes_checked: usize,
+    pub snapshots_queued: usize,
+    pub snapshots_pruned: usize,
+}
+
+/// Queue due snapshots and prune expired ones.
+///
+/// `recent_runs` remembers the runs this replica queued, so a lagging
+/// projection does not cause a run to be queued twice.
+pub async fn run_pass(
+    pool: &PgPool,
+    recent_runs: &mut HashMap<String, DateTime<Utc>>,
+) -> Result<PassSummary, BackupError> {
+    let policies = sqlx::query_as::<_, PolicyRow>(
+        r#"
+        SELECT volume_id, org_id, backup_schedule, backup_retain_count,
+               backup_retain_max_age_secs, backup_policy_updated_at, backup_last_run_at
+        FROM volumes_view
+        WHERE backup_schedule IS NOT NULL
+          AND backup_policy_updated_at IS NOT NULL
+          AND NOT is_deleted
+        ORDER BY volume_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let store = EventStore::new(pool.clone());
+    let source = SystemSource::new(BACKUP_SCHEDULER_ACTOR_ID);
+    let now = Utc::now();
+    let mut summary = PassSummary::default();
+
+    recent_runs.retain(|volume_id, _| policies.iter().any(|p| &p.volume_id == volume_id));
+
+    for policy in &policies {
+        let (Ok(volume_id), Ok(org_id)) = (
+            policy.volume_id.parse::<VolumeId>(),
+            policy.org_id.parse::<OrgId>(),
+        ) else {
+            warn!(volume_id = %policy.volume_id, "Skipping backup policy for invalid IDs");
+            continue;
+        };
+        let schedule = match CronSchedule::parse(&policy.schedule) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!(volume_id = %policy.volume_id, error = %e, "Skipping invalid backup schedule");
+                continue;
+            }
+        };
+        summary.volumes_checked += 1;
+
+        let last_run_at = match (policy.last_run_at, recent_runs.get(&policy.volume_id)) {
+            (Some(a), Some(&b)) => Some(a.max(b)),
+            (a, b) => a.or(b.copied()),
+        };
+        let due = next_run(&schedule, policy.policy_updated_at, last_run_at)
+            .is_some_and(|next| next <= now);
+        if due {
+            queue_snapshot(&store, &source, org_id, volume_id).await?;
+            recent_runs.insert(policy.volume_id.clone(), now);
+            info!(volume_id = %policy.volume_id, "Queued scheduled snapshot");
+            summary.snapshots_queued += 1;
+        }
+
+        if policy.retain_count.is_none() && policy.retain_max_age_secs.is_none() {
+            continue;
+        }
+        let snapshots = sqlx::query_as::<_, ScheduledSnapshot>(
+            r#"
+            SELECT snapshot_id, status, created_at
+            FROM snapshots_view
+            WHERE volume_id = $1 AND scheduled
+            "#,
+        )
+        .bind(&policy.volume_id)
+        .fetch_all(pool)
+        .await?;
+
+        let max_age = policy.retain_max_age_secs.map(Duration::seconds);
+        for snapshot_id in expired(&snapshots, policy.retain_count, max_age, now) {
+            prune_snapshot(&store, &source, org_id, volume_id, &snapshot_id).await?;
+            info!(volume_id = %policy.volume_id, snapshot_id = %snapshot_id, "Pruned expired snapshot");
+            summary.snapshots_pruned += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Emit `snapshot.created` for a scheduled snapshot.
+async fn queue_snapshot(
+    store: &EventStore,
+    source: &SystemSource,
+    org_id: OrgId,
+    volume_id: VolumeId,
+) -> Result<(), BackupError> {
+    let snapshot_id = SnapshotId::new();
+    let event = NewEvent::builder(source)
+        .aggregate_id(snapshot_id.to_string())
+        .aggregate_seq(1)
+        .org_id(org_id)
+        .payload(&SnapshotCreatedPayload {
+            snapshot_id,
+            org_id,
+            volume_id,
+            status: JobStatus::Queued,
+            note: Some(SCHEDULED_NOTE.to_string()),
+            scheduled: true,
+        })
+        .build()?;
+    store.append(event.into()).await?;
+    Ok(())
+}
+
+/// Emit `snapshot.deleted` for a snapshot outside retention.
+async fn prune_snapshot(
+    store: &EventStore,
+    source: &SystemSource,
+    org_id: OrgId,
+    volume_id: VolumeId,
+    snapshot_id: &str,
+) -> Result<(), BackupError> {
+    let Ok(snapshot_id_typed) = snapshot_id.parse::<SnapshotId>() else {
+        warn!(snapshot_id = %snapshot_id, "Skipping prune of invalid snapshot ID");
+        return Ok(());
+    };
+    let seq = store
+        .get_latest_aggregate_seq(&AggregateType::Snapshot, snapshot_id)
+        .await?
+        .unwrap_or(0)
+        + 1;
+    let event = NewEvent::builder(source)
+        .aggregate_id(snapshot_id.to_string())
+        .aggregate_seq(seq)
+        .org_id(org_id)
+        .payload(&SnapshotDeletedPayload {
+            snapshot_id: snapshot_id_typed,
+            org_id,
+            volume_id,
+            reason: "retention".to_string(),
+        })
+        .build()?;
+    store.append(event.into()).await?;
+    Ok(())
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker running backup passes, on the elected leader only.
+pub struct BackupSchedulerWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    election: LeaderElection,
+}
+
+impl BackupSchedulerWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::BackupScheduler, interval * 3),
+            pool,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            "Starting backup scheduler worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut recent_runs = HashMap::new();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        recent_runs.clear();
+                        continue;
+                    }
+                    match run_pass(&self.pool, &mut recent_runs).await {
+                        Ok(summary) if summary.snapshots_queued > 0 || summary.snapshots_pruned > 0 => info!(
+                            volumes = summary.volumes_checked,
+                            queued = summary.snapshots_queued,
+                            pruned = summary.snapshots_pruned,
+                            "Backup pass complete"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Backup pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Backup scheduler worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn snapshot(id: &str, status: &str, created_at: &str) -> ScheduledSnapshot {
+        ScheduledSnapshot {
+            snapshot_id: id.to_string(),
+            status: status.to_string(),
+            created_at: at(created_at),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_expressions() {
+        assert!(CronSchedule::parse("0 3 * * *").is_ok());
+        assert!(CronSchedule::parse("*/15 * * * 1-5").is_ok());
+        assert!(CronSchedule::parse("@daily").is_ok());
+        assert!(CronSchedule::parse("0 3 * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("0 3 * * mon").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let daily = CronSchedule::parse("0 3 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(at("2026-01-01T02:59:30Z")),
+            Some(at("2026-01-01T03:00:00Z"))
+        );
+        // Strictly after: a run at exactly 03:00 moves to the next day.
+        assert_eq!(
+            daily.next_after(at("2026-01-01T03:00:00Z")),
+            Some(at("2026-01-02T03:00:00Z"))
+        );
+
+        let quarter_hourly = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            quarter_hourly.next_after(at("2026-01-01T10:16:00Z")),
+            Some(at("2026-01-01T10:30:00Z"))
+        );
+
+        // Sunday (7) at midnight; 2026-01-04 is a Sunday.
+        let weekly = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(
+            weekly.next_after(at("2026-01-01T00:00:00Z")),
+            Some(at("2026-01-04T00:00:00Z"))
+        );
+
+        let new_year = CronSchedule::parse("0 0 1 1 *").unwrap();
+        assert_eq!(
+            new_year.next_after(at("2026-03-15T12:00:00Z")),
+            Some(at("2027-01-01T00:00:00Z"))
+        );
+
+        let never = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(never.next_after(at("2026-01-01T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_restricted_day_fields_match_either() {
+        // The 13th of the month or any Friday; 2026-02-06 is a Friday.
+        let schedule = CronSchedule::parse("0 0 13 * 5").unwrap();
+        assert_eq!(
+            schedule.next_after(at("2026-02-01T00:00:00Z")),
+            Some(at("2026-02-06T00:00:00Z"))
+        );
+        assert_eq!(
+            schedule.next_after(at("2026-02-12T00:00:00Z")),
+            Some(at("2026-02-13T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_next_run_anchors_on_latest_of_policy_and_last_run() {
+        let hourly = CronSchedule::parse("@hourly").unwrap();
+        let policy = at("2026-01-01T10:30:00Z");
+        assert_eq!(
+            next_run(&hourly, policy, None),
+            Some(at("2026-01-01T11:00:00Z"))
+        );
+        assert_eq!(
+            next_run(&hourly, policy, Some(at("2026-01-01T11:00:05Z"))),
+            Some(at("2026-01-01T12:00:00Z"))
+        );
+        // A run from before the policy changed does not count.
+        assert_eq!(
+            next_run(&hourly, policy, Some(at("2026-01-01T09:00:00Z"))),
+            Some(at("2026-01-01T11:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_expired_by_count() {
+        let snapshots = vec![
+            snapshot("snap_1", "succeeded", "2026-01-01T03:00:00Z"),
+            snapshot("snap_2", "failed", "2026-01-02T03:00:00Z"),
+            snapshot("snap_3", "succeeded", "2026-01-03T03:00:00Z"),
+            snapshot("snap_4", "succeeded", "2026-01-04T03:00:00Z"),
+            snapshot("snap_5", "failed", "2026-01-05T03:00:00Z"),
+            snapshot("snap_6", "queued", "2026-01-06T03:00:00Z"),
+        ];
+        let now = at("2026-01-06T03:00:00Z");
+
+        let mut pruned = expired(&snapshots, Some(2), None, now);
+        pruned.sort();
+        // The newest failure stays until something newer succeeds.
+        assert_eq!(pruned, vec!["snap_1", "snap_2"]);
+    }
+
+    #[test]
+    fn test_expired_by_age() {
+        let snapshots = vec![
+            snapshot("snap_old", "succeeded", "2026-01-01T03:00:00Z"),
+            snapshot("snap_new", "succeeded", "2026-01-09T03:00:00Z"),
+            snapshot("snap_running", "running", "2026-01-01T03:00:00Z"),
+        ];
+        let now = at("2026-01-10T03:00:00Z");
+
+        assert_eq!(
+            expired(&snapshots, None, Some(Duration::days(7)), now),
+            vec!["snap_old"]
+        );
+        assert!(expired(&snapshots, None, None, now).is_empty());
+
+        // The newest successful snapshot survives however old it is.
+        let only_old = vec![snapshot("snap_old", "succeeded", "2026-01-01T03:00:00Z")];
+        assert!(expired(&only_old, Some(1), Some(Duration::days(7)), now).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod teardown;
 mod worker;
 
 pub use worker::{CleanupWorker, CleanupWorkerConfig};
@@ -0,0 +1,779 @@
+//! Cascading teardown for app and env deletion.
+//!
+//! Deleting an env is a multi-step saga driven by the cleanup worker:
+//! scale to zero, remove routes, wait for instances to drain, detach (and
+//! optionally delete) volumes, archive secrets, then emit `env.deleted`.
+//! Deleting an app requests deletion of each of its envs and emits
+//! `app.deleted` once they are all gone. Preview envs past their
+//! `expires_at` get the same teardown, with volume deletion, requested on
+//! their behalf.
+//!
+//! Progress is derived from the materialized views on every pass rather
+//! than stored separately, so each step is idempotent and the saga resumes
+//! after a restart without extra bookkeeping. The same progress is served
+//! by the deletion status endpoints.
+
+use plfm_events::{event_types, ActorType, AggregateType};
+use plfm_id::RequestId;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{debug, info, warn};
+
+use crate::db::{AppendEvent, DbError, EventStore, ProjectionStore};
+
+/// Actor recorded on events emitted by the teardown saga.
+const TEARDOWN_ACTOR_ID: &str = "cleanup";
+
+/// Steps of an env teardown, in execution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvTeardownStep {
+    ScaleDown,
+    RemoveRoutes,
+    DrainInstances,
+    DetachVolumes,
+    DeleteVolumes,
+    ArchiveSecrets,
+    Finalize,
+}
+
+impl EnvTeardownStep {
+    /// All steps in execution order.
+    pub const ALL: [EnvTeardownStep; 7] = [
+        EnvTeardownStep::ScaleDown,
+        EnvTeardownStep::RemoveRoutes,
+        EnvTeardownStep::DrainInstances,
+        EnvTeardownStep::DetachVolumes,
+        EnvTeardownStep::DeleteVolumes,
+        EnvTeardownStep::ArchiveSecrets,
+        EnvTeardownStep::Finalize,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnvTeardownStep::ScaleDown => "scale_down",
+            EnvTeardownStep::RemoveRoutes => "remove_routes",
+            EnvTeardownStep::DrainInstances => "drain_instances",
+            EnvTeardownStep::DetachVolumes => "detach_volumes",
+            EnvTeardownStep::DeleteVolumes => "delete_volumes",
+            EnvTeardownStep::ArchiveSecrets => "archive_secrets",
+            EnvTeardownStep::Finalize => "finalize",
+        }
+    }
+}
+
+/// A live route owned by an env being torn down.
+#[derive(Debug, Clone)]
+pub struct TeardownRoute {
+    pub route_id: String,
+    pub hostname: String,
+}
+
+/// A live volume attachment owned by an env being torn down.
+#[derive(Debug, Clone)]
+pub struct TeardownAttachment {
+    pub attachment_id: String,
+    pub volume_id: String,
+    pub process_type: String,
+    pub mount_path: String,
+}
+
+/// Remaining work for an env teardown, loaded from the views.
+#[derive(Debug, Clone, Default)]
+pub struct EnvTeardownProgress {
+    /// Process types whose desired replica count is still above zero.
+    pub scaled_process_types: Vec<String>,
+    pub routes: Vec<TeardownRoute>,
+    /// Instances not yet stopped.
+    pub active_instances: i64,
+    pub attachments: Vec<TeardownAttachment>,
+    /// Volumes that were attached to the env and have no other live
+    /// attachments. Only populated when volume deletion was requested.
+    pub volumes: Vec<String>,
+    pub unarchived_bundles: Vec<String>,
+}
+
+impl EnvTeardownProgress {
+    /// Number of items still outstanding for a step.
+    pub fn remaining(&self, step: EnvTeardownStep) -> i64 {
+        match step {
+            EnvTeardownStep::ScaleDown => self.scaled_process_types.len() as i64,
+            EnvTeardownStep::RemoveRoutes => self.routes.len() as i64,
+            EnvTeardownStep::DrainInstances => self.active_instances,
+            EnvTeardownStep::DetachVolumes => self.attachments.len() as i64,
+            EnvTeardownStep::DeleteVolumes => self.volumes.len() as i64,
+            EnvTeardownStep::ArchiveSecrets => self.unarchived_bundles.len() as i64,
+            EnvTeardownStep::Finalize => 1,
+        }
+    }
+
+    /// The first step that still has outstanding work.
+    ///
+    /// Steps are strictly ordered: volumes are only detached once instances
+    /// have drained, even if there are no routes to remove in between.
+    pub fn next_step(&self) -> EnvTeardownStep {
+        EnvTeardownStep::ALL
+            .into_iter()
+            .find(|step| self.remaining(*step) > 0)
+            .unwrap_or(EnvTeardownStep::Finalize)
+    }
+}
+
+/// Load the remaining teardown work for an env.
+pub async fn load_env_progress(
+    pool: &PgPool,
+    env_id: &str,
+    delete_volumes: bool,
+) -> Result<EnvTeardownProgress, sqlx::Error> {
+    // Groups without a scale row default to one replica in the scheduler, so
+    // every process type with a desired release needs an explicit zero.
+    let scaled_process_types: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT r.process_type
+        FROM env_desired_releases_view r
+        LEFT JOIN env_scale_view s
+            ON r.env_id = s.env_id AND r.process_type = s.process_type
+        WHERE r.env_id = $1 AND COALESCE(s.desired_replicas, 1) > 0
+        UNION
+        SELECT process_type
+        FROM env_scale_view
+        WHERE env_id = $1 AND desired_replicas > 0
+        ORDER BY 1
+        "#,
+    )
+    .bind(env_id)
+    .fetch_all(pool)
+    .await?;
+
+    let routes = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT route_id, hostname
+        FROM routes_view
+        WHERE env_id = $1 AND NOT is_deleted
+        ORDER BY route_id
+        "#,
+    )
+    .bind(env_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(route_id, hostname)| TeardownRoute { route_id, hostname })
+    .collect();
+
+    let active_instances: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM instances_desired_view d
+        LEFT JOIN instances_status_view st ON d.instance_id = st.instance_id
+        WHERE d.env_id = $1
+          AND (d.desired_state <> 'stopped'
+               OR (st.status IS NOT NULL AND st.status NOT IN ('stopped', 'failed')))
+        "#,
+    )
+    .bind(env_id)
+    .fetch_one(pool)
+    .await?;
+
+    let attachments = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"
+        SELECT attachment_id, volume_id, process_type, mount_path
+        FROM volume_attachments_view
+        WHERE env_id = $1 AND NOT is_deleted
+        ORDER BY attachment_id
+        "#,
+    )
+    .bind(env_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(
+        |(attachment_id, volume_id, process_type, mount_path)| TeardownAttachment {
+            attachment_id,
+            volume_id,
+            process_type,
+            mount_path,
+        },
+    )
+    .collect();
+
+    let volumes = if delete_volumes {
+        sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT v.volume_id
+            FROM volume_attachments_view a
+            JOIN volumes_view v ON a.volume_id = v.volume_id
+            WHERE a.env_id = $1
+              AND NOT v.is_deleted
+              AND NOT EXISTS (
+                  SELECT 1 FROM volume_attachments_view other
+                  WHERE other.volume_id = v.volume_id AND NOT other.is_deleted
+              )
+            ORDER BY v.volume_id
+            "#,
+        )
+        .bind(env_id)
+        .fetch_all(pool)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let unarchived_bundles: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT bundle_id
+        FROM secret_bundles_view
+        WHERE env_id = $1 AND archived_at IS NULL
+        ORDER BY bundle_id
+        "#,
+    )
+    .bind(env_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(EnvTeardownProgress {
+        scaled_process_types,
+        routes,
+        active_instances,
+        attachments,
+        volumes,
+        unarchived_bundles,
+    })
+}
+
+/// Number of live envs of an app that still need to be torn down.
+pub async fn count_remaining_envs(pool: &PgPool, app_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM envs_view
+        WHERE app_id = $1 AND NOT is_deleted
+        "#,
+    )
+    .bind(app_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[derive(Debug)]
+struct PendingEnvRow {
+    env_id: String,
+    org_id: String,
+    app_id: String,
+    delete_volumes: bool,
+}
+
+#[derive(Debug)]
+struct PendingAppRow {
+    app_id: String,
+    org_id: String,
+    delete_volumes: bool,
+}
+
+/// Drives pending app and env teardowns one step per pass.
+pub(super) struct TeardownDriver {
+    pool: PgPool,
+    event_store: EventStore,
+    projection_store: ProjectionStore,
+    projection_wait: std::time::Duration,
+}
+
+impl TeardownDriver {
+    pub(super) fn new(pool: PgPool, projection_wait: std::time::Duration) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            projection_store: ProjectionStore::new(pool.clone()),
+            pool,
+            projection_wait,
+        }
+    }
+
+    /// Advance every pending teardown by one step.
+    ///
+    /// Expired preview envs and apps are processed first so that the envs
+    /// they request deletion of are picked up in the same pass.
+    pub(super) async fn run_pass(&self) -> Result<u64, DbError> {
+        let mut advanced = 0u64;
+
+        match self.expire_preview_envs().await {
+            Ok(count) => advanced += count,
+            Err(e) => warn!(error = %e, "Failed to expire preview envs"),
+        }
+
+        let apps: Vec<PendingAppRow> = sqlx::query_as::<_, (String, String, bool)>(
+            r#"
+            SELECT app_id, org_id, delete_volumes
+            FROM apps_view
+            WHERE deletion_requested_at IS NOT NULL AND NOT is_deleted
+            ORDER BY deletion_requested_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?
+        .into_iter()
+        .map(|(app_id, org_id, delete_volumes)| PendingAppRow {
+            app_id,
+            org_id,
+            delete_volumes,
+        })
+        .collect();
+
+        for app in apps {
+            match self.advance_app(&app).await {
+                Ok(true) => advanced += 1,
+                Ok(false) => {}
+                Err(e) => warn!(error = %e, app_id = %app.app_id, "App teardown step failed"),
+            }
+        }
+
+        let envs: Vec<PendingEnvRow> = sqlx::query_as::<_, (String, String, String, bool)>(
+            r#"
+            SELECT env_id, org_id, app_id, delete_volumes
+            FROM envs_view
+            WHERE deletion_requested_at IS NOT NULL AND NOT is_deleted
+            ORDER BY deletion_requested_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?
+        .into_iter()
+        .map(|(env_id, org_id, app_id, delete_volumes)| PendingEnvRow {
+            env_id,
+            org_id,
+            app_id,
+            delete_volumes,
+        })
+        .collect();
+
+        for env in envs {
+            match self.advance_env(&env).await {
+                Ok(true) => advanced += 1,
+                Ok(false) => {}
+                Err(e) => warn!(error = %e, env_id = %env.env_id, "Env teardown step failed"),
+            }
+        }
+
+        Ok(advanced)
+    }
+
+    /// Request deletion of preview envs whose TTL has elapsed.
+    async fn expire_preview_envs(&self) -> Result<u64, DbError> {
+        let expired: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT env_id, org_id, app_id
+            FROM envs_view
+            WHERE expires_at <= now() AND deletion_requested_at IS NULL AND NOT is_deleted
+            ORDER BY expires_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let mut events = Vec::with_capacity(expired.len());
+        for (env_id, org_id, app_id) in &expired {
+            let seq = self.next_seq(AggregateType::Env, env_id).await?;
+            events.push(self.event(
+                AggregateType::Env,
+                env_id,
+                seq,
+                event_types::ENV_DELETION_REQUESTED,
+                org_id,
+                Some(app_id.as_str()),
+                Some(env_id.as_str()),
+                serde_json::json!({
+                    "env_id": env_id,
+                    "org_id": org_id,
+                    "app_id": app_id,
+                    "delete_volumes": true
+                }),
+            ));
+        }
+        info!(
+            envs = expired.len(),
+            "Requesting teardown of expired preview envs"
+        );
+        self.append_and_wait(events, "envs").await?;
+        Ok(expired.len() as u64)
+    }
+
+    async fn advance_app(&self, app: &PendingAppRow) -> Result<bool, DbError> {
+        let pending_envs: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT env_id
+            FROM envs_view
+            WHERE app_id = $1 AND NOT is_deleted AND deletion_requested_at IS NULL
+            ORDER BY env_id
+            "#,
+        )
+        .bind(&app.app_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        if !pending_envs.is_empty() {
+            let mut events = Vec::with_capacity(pending_envs.len());
+            for env_id in &pending_envs {
+                let seq = self.next_seq(AggregateType::Env, env_id).await?;
+                events.push(self.event(
+                    AggregateType::Env,
+                    env_id,
+                    seq,
+                    event_types::ENV_DELETION_REQUESTED,
+                    &app.org_id,
+                    Some(&app.app_id),
+                    Some(env_id),
+                    serde_json::json!({
+                        "env_id": env_id,
+                        "org_id": app.org_id,
+                        "app_id": app.app_id,
+                        "delete_volumes": app.delete_volumes
+                    }),
+                ));
+            }
+            info!(app_id = %app.app_id, envs = pending_envs.len(), "Requesting env teardown for app deletion");
+            self.append_and_wait(events, "envs").await?;
+            return Ok(true);
+        }
+
+        let remaining = count_remaining_envs(&self.pool, &app.app_id)
+            .await
+            .map_err(DbError::Query)?;
+        if remaining > 0 {
+            return Ok(false);
+        }
+
+        let seq = self.next_seq(AggregateType::App, &app.app_id).await?;
+        let event = self.event(
+            AggregateType::App,
+            &app.app_id,
+            seq,
+            event_types::APP_DELETED,
+            &app.org_id,
+            Some(&app.app_id),
+            None,
+            serde_json::json!({ "app_id": app.app_id }),
+        );
+        info!(app_id = %app.app_id, "App teardown complete");
+        self.append_and_wait(vec![event], "apps").await?;
+        Ok(true)
+    }
+
+    async fn advance_env(&self, env: &PendingEnvRow) -> Result<bool, DbError> {
+        let progress = load_env_progress(&self.pool, &env.env_id, env.delete_volumes)
+            .await
+            .map_err(DbError::Query)?;
+        let step = progress.next_step();
+        debug!(env_id = %env.env_id, step = step.as_str(), "Advancing env teardown");
+
+        let (events, projection) = match step {
+            EnvTeardownStep::ScaleDown => {
+                let scales: Vec<serde_json::Value> = progress
+                    .scaled_process_types
+                    .iter()
+                    .map(|process_type| {
+                        serde_json::json!({ "process_type": process_type, "desired": 0 })
+                    })
+                    .collect();
+                let seq = self.next_seq(AggregateType::Env, &env.env_id).await?;
+                let event = self.env_event(
+                    env,
+                    AggregateType::Env,
+                    &env.env_id,
+                    seq,
+                    event_types::ENV_SCALE_SET,
+                    serde_json::json!({
+                        "env_id": env.env_id,
+                        "org_id": env.org_id,
+                        "app_id": env.app_id,
+                        "scales": scales
+                    }),
+                );
+                (vec![event], "env_config")
+            }
+            EnvTeardownStep::RemoveRoutes => {
+                let mut events = Vec::with_capacity(progress.routes.len());
+                for route in &progress.routes {
+                    let seq = self.next_seq(AggregateType::Route, &route.route_id).await?;
+                    events.push(self.env_event(
+                        env,
+                        AggregateType::Route,
+                        &route.route_id,
+                        seq,
+                        event_types::ROUTE_DELETED,
+                        serde_json::json!({
+                            "route_id": route.route_id,
+                            "org_id": env.org_id,
+                            "env_id": env.env_id,
+                            "hostname": route.hostname
+                        }),
+                    ));
+                }
+                (events, "routes")
+            }
+            EnvTeardownStep::DrainInstances => {
+                // The scheduler drains instances once the env is scaled to
+                // zero; nothing to emit until they have stopped.
+                return Ok(false);
+            }
+            EnvTeardownStep::DetachVolumes => {
+                let mut events = Vec::with_capacity(progress.attachments.len());
+                for attachment in &progress.attachments {
+                    let seq = self
+                        .next_seq(AggregateType::VolumeAttachment, &attachment.attachment_id)
+                        .await?;
+                    events.push(self.env_event(
+                        env,
+                        AggregateType::VolumeAttachment,
+                        &attachment.attachment_id,
+                        seq,
+                        event_types::VOLUME_DETACHED,
+                        serde_json::json!({
+                            "attachment_id": attachment.attachment_id,
+                            "org_id": env.org_id,
+                            "volume_id": attachment.volume_id,
+                            "app_id": env.app_id,
+                            "env_id": env.env_id,
+                            "process_type": attachment.process_type,
+                            "mount_path": attachment.mount_path
+                        }),
+                    ));
+                }
+                (events, "volume_attachments")
+            }
+            EnvTeardownStep::DeleteVolumes => {
+                let mut events = Vec::with_capacity(progress.volumes.len());
+                for volume_id in &progress.volumes {
+                    let seq = self.next_seq(AggregateType::Volume, volume_id).await?;
+                    events.push(self.event(
+                        AggregateType::Volume,
+                        volume_id,
+                        seq,
+                        event_types::VOLUME_DELETED,
+                        &env.org_id,
+                        None,
+                        None,
+                        serde_json::json!({
+                            "volume_id": volume_id,
+                            "org_id": env.org_id
+                        }),
+                    ));
+                }
+                (events, "volumes")
+            }
+            EnvTeardownStep::ArchiveSecrets => {
+                let mut events = Vec::with_capacity(progress.unarchived_bundles.len());
+                for bundle_id in &progress.unarchived_bundles {
+                    let seq = self
+                        .next_seq(AggregateType::SecretBundle, bundle_id)
+                        .await?;
+                    events.push(self.env_event(
+                        env,
+                        AggregateType::SecretBundle,
+                        bundle_id,
+                        seq,
+                        event_types::SECRET_BUNDLE_ARCHIVED,
+                        serde_json::json!({
+                            "bundle_id": bundle_id,
+                            "org_id": env.org_id,
+                            "env_id": env.env_id
+                        }),
+                    ));
+                }
+                (events, "secret_bundles")
+            }
+            EnvTeardownStep::Finalize => {
+                let seq = self.next_seq(AggregateType::Env, &env.env_id).await?;
+                let event = self.env_event(
+                    env,
+                    AggregateType::Env,
+                    &env.env_id,
+                    seq,
+                    event_types::ENV_DELETED,
+                    serde_json::json!({ "env_id": env.env_id }),
+                );
+                info!(env_id = %env.env_id, "Env teardown complete");
+                (vec![event], "envs")
+            }
+        };
+
+        self.append_and_wait(events, projection).await?;
+        Ok(true)
+    }
+
+    async fn next_seq(
+        &self,
+        aggregate_type: AggregateType,
+        aggregate_id: &str,
+    ) -> Result<i32, DbError> {
+        Ok(self
+            .event_store
+            .get_latest_aggregate_seq(&aggregate_type, aggregate_id)
+            .await?
+            .unwrap_or(0)
+            + 1)
+    }
+
+    fn env_event(
+        &self,
+        env: &PendingEnvRow,
+        aggregate_type: AggregateType,
+        aggregate_id: &str,
+        aggregate_seq: i32,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> AppendEvent {
+        self.event(
+            aggregate_type,
+            aggregate_id,
+            aggregate_seq,
+            event_type,
+            &env.org_id,
+            Some(&env.app_id),
+            Some(&env.env_id),
+            payload,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn event(
+        &self,
+        aggregate_type: AggregateType,
+        aggregate_id: &str,
+        aggregate_seq: i32,
+        event_type: &str,
+        org_id: &str,
+        app_id: Option<&str>,
+        env_id: Option<&str>,
+        payload: serde_json::Value,
+    ) -> AppendEvent {
+        AppendEvent {
+            aggregate_type,
+            aggregate_id: aggregate_id.to_string(),
+            aggregate_seq,
+            event_type: event_type.to_string(),
+            event_version: 1,
+            actor_type: ActorType::System,
+            actor_id: TEARDOWN_ACTOR_ID.to_string(),
+            org_id: org_id.parse().ok(),
+            request_id: RequestId::new().to_string(),
+            idempotency_key: None,
+            app_id: app_id.and_then(|id| id.parse().ok()),
+            env_id: env_id.and_then(|id| id.parse().ok()),
+            correlation_id: env_id.or(app_id).map(|id| format!("teardown:{id}")),
+            causation_id: None,
+            payload,
+            ..Default::default()
+        }
+    }
+
+    /// Append a step's events and wait for them to be projected, so the
+    /// next pass does not re-emit work that is already in flight.
+    async fn append_and_wait(
+        &self,
+        events: Vec<AppendEvent>,
+        projection: &str,
+    ) -> Result<(), DbError> {
+        let ids = self.event_store.append_batch(events).await?;
+        if let Some(last) = ids.last() {
+            if let Err(e) = self
+                .projection_store
+                .wait_for_checkpoint(projection, last.value(), self.projection_wait)
+                .await
+            {
+                warn!(error = %e, projection = projection, "Teardown projection wait timed out");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress() -> EnvTeardownProgress {
+        EnvTeardownProgress {
+            scaled_process_types: vec!["web".to_string()],
+            routes: vec![TeardownRoute {
+                route_id: "rt_1".to_string(),
+                hostname: "app.example.com".to_string(),
+            }],
+            active_instances: 2,
+            attachments: vec![TeardownAttachment {
+                attachment_id: "vat_1".to_string(),
+                volume_id: "vol_1".to_string(),
+                process_type: "web".to_string(),
+                mount_path: "/data".to_string(),
+            }],
+            volumes: Vec::new(),
+            unarchived_bundles: vec!["sb_1".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_steps_run_in_order() {
+        let mut p = progress();
+        assert_eq!(p.next_step(), EnvTeardownStep::ScaleDown);
+
+        p.scaled_process_types.clear();
+        assert_eq!(p.next_step(), EnvTeardownStep::RemoveRoutes);
+
+        p.routes.clear();
+        assert_eq!(p.next_step(), EnvTeardownStep::DrainInstances);
+
+        p.active_instances = 0;
+        assert_eq!(p.next_step(), EnvTeardownStep::DetachVolumes);
+
+        p.attachments.clear();
+        p.volumes.push("vol_1".to_string());
+        assert_eq!(p.next_step(), EnvTeardownStep::DeleteVolumes);
+
+        p.volumes.clear();
+        assert_eq!(p.next_step(), EnvTeardownStep::ArchiveSecrets);
+
+        p.unarchived_bundles.clear();
+        assert_eq!(p.next_step(), EnvTeardownStep::Finalize);
+    }
+
+    #[test]
+    fn test_volumes_wait_for_drain() {
+        let mut p = progress();
+        p.scaled_process_types.clear();
+        p.routes.clear();
+        assert_eq!(p.next_step(), EnvTeardownStep::DrainInstances);
+        assert_eq!(p.remaining(EnvTeardownStep::DetachVolumes), 1);
+    }
+
+    #[test]
+    fn test_empty_env_finalizes() {
+        let p = EnvTeardownProgress::default();
+        assert_eq!(p.next_step(), EnvTeardownStep::Finalize);
+    }
+
+    #[test]
+    fn test_step_names() {
+        let names: Vec<&str> = EnvTeardownStep::ALL.iter().map(|s| s.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "scale_down",
+                "remove_routes",
+                "drain_instances",
+                "detach_volumes",
+                "delete_volumes",
+                "archive_secrets",
+                "finalize"
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(EnvTeardownStep::ScaleDown).unwrap(),
+            serde_json::json!("scale_down")
+        );
+    }
+}
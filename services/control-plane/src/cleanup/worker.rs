@@ -4,12 +4,29 @@ use sqlx::PgPool;
 use tokio::sync::watch;
 use tracing::{error, info, instrument, warn};
 
+use super::teardown::TeardownDriver;
+use crate::leader::{LeaderElection, LeaderRole};
+use crate::route_certs;
+use crate::secrets;
+use crate::slo;
+use crate::status_page;
+use crate::trash;
+
 #[derive(Debug, Clone)]
 pub struct CleanupWorkerConfig {
     pub interval: Duration,
     pub workload_log_retention_days: i32,
     pub ipv4_cooldown_grace_days: i32,
-    pub idempotency_retention_days: i32,
+    /// How often pending app/env teardowns are advanced.
+    pub teardown_interval: Duration,
+    /// How long a teardown step waits for its events to be projected.
+    pub teardown_projection_wait: Duration,
+    /// How often the running key rotation job advances by one batch.
+    pub key_rotation_interval: Duration,
+    /// How often route certificates are checked for upcoming expiry.
+    pub route_cert_expiry_interval: Duration,
+    /// How often finished days are rolled up into env SLO rollups.
+    pub slo_rollup_interval: Duration,
 }
 
 impl Default for CleanupWorkerConfig {
@@ -18,7 +35,11 @@ impl Default for CleanupWorkerConfig {
             interval: Duration::from_secs(3600),
             workload_log_retention_days: 7,
             ipv4_cooldown_grace_days: 1,
-            idempotency_retention_days: 7,
+            teardown_interval: Duration::from_secs(5),
+            teardown_projection_wait: Duration::from_secs(10),
+            key_rotation_interval: Duration::from_secs(2),
+            route_cert_expiry_interval: Duration::from_secs(900),
+            slo_rollup_interval: Duration::from_secs(900),
         }
     }
 }
@@ -26,11 +47,24 @@ impl Default for CleanupWorkerConfig {
 pub struct CleanupWorker {
     pool: PgPool,
     config: CleanupWorkerConfig,
+    teardown: TeardownDriver,
+    election: LeaderElection,
 }
 
 impl CleanupWorker {
     pub fn new(pool: PgPool, config: CleanupWorkerConfig) -> Self {
-        Self { pool, config }
+        let teardown = TeardownDriver::new(pool.clone(), config.teardown_projection_wait);
+        let election = LeaderElection::new(
+            pool.clone(),
+            LeaderRole::Cleanup,
+            config.teardown_interval * 3,
+        );
+        Self {
+            pool,
+            config,
+            teardown,
+            election,
+        }
     }
 
     #[instrument(skip(self, shutdown))]
@@ -38,20 +72,53 @@ impl CleanupWorker {
         info!(
             interval_secs = self.config.interval.as_secs(),
             workload_log_retention_days = self.config.workload_log_retention_days,
+            teardown_interval_secs = self.config.teardown_interval.as_secs(),
             "Starting cleanup worker"
         );
 
         let mut interval = tokio::time::interval(self.config.interval);
         interval.tick().await;
+        let mut teardown_interval = tokio::time::interval(self.config.teardown_interval);
+        teardown_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut key_rotation_interval = tokio::time::interval(self.config.key_rotation_interval);
+        key_rotation_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut route_cert_expiry_interval =
+            tokio::time::interval(self.config.route_cert_expiry_interval);
+        route_cert_expiry_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut slo_rollup_interval = tokio::time::interval(self.config.slo_rollup_interval);
+        slo_rollup_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         loop {
             tokio::select! {
                 _ = interval.tick() => {
-                    self.run_cleanup().await;
+                    if self.election.ensure_leader().await {
+                        self.run_cleanup().await;
+                    }
+                }
+                _ = teardown_interval.tick() => {
+                    if self.election.ensure_leader().await {
+                        self.run_teardown().await;
+                    }
+                }
+                _ = key_rotation_interval.tick() => {
+                    if self.election.ensure_leader().await {
+                        self.run_key_rotation().await;
+                    }
+                }
+                _ = route_cert_expiry_interval.tick() => {
+                    if self.election.ensure_leader().await {
+                        self.run_route_cert_expiry().await;
+                    }
+                }
+                _ = slo_rollup_interval.tick() => {
+                    if self.election.ensure_leader().await {
+                        self.run_slo_rollup().await;
+                    }
                 }
                 _ = shutdown.changed() => {
                     if *shutdown.borrow() {
                         info!("Cleanup worker shutting down");
+                        self.election.resign().await;
                         break;
                     }
                 }
@@ -98,11 +165,92 @@ impl CleanupWorker {
             }
         }
 
+        match status_page::prune_history(&self.pool).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!(deleted = count, "Pruned old instance status history");
+                }
+                total_deleted += count;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to prune instance status history");
+            }
+        }
+
+        match slo::prune_rollups(&self.pool).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!(deleted = count, "Pruned old env SLO rollups");
+                }
+                total_deleted += count;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to prune env SLO rollups");
+            }
+        }
+
+        match trash::purge_expired(&self.pool).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!(purged = count, "Purged expired resources from the trash");
+                }
+                total_deleted += count;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to purge the trash");
+            }
+        }
+
         if total_deleted > 0 {
             info!(total_deleted = total_deleted, "Cleanup pass complete");
         }
     }
 
+    async fn run_teardown(&self) {
+        match self.teardown.run_pass().await {
+            Ok(count) => {
+                if count > 0 {
+                    info!(advanced = count, "Advanced pending teardowns");
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to advance pending teardowns");
+            }
+        }
+    }
+
+    async fn run_key_rotation(&self) {
+        if let Err(e) = secrets::jobs::run_pass(&self.pool).await {
+            error!(error = %e, "Failed to advance key rotation job");
+        }
+    }
+
+    async fn run_route_cert_expiry(&self) {
+        match route_certs::warn_expiring(&self.pool).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!(warned = count, "Emitted route certificate expiry warnings");
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to check route certificate expiry");
+            }
+        }
+    }
+
+    async fn run_slo_rollup(&self) {
+        match slo::rollup(&self.pool, chrono::Utc::now()).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!(days = count, "Rolled up env SLO days");
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to roll up env SLO days");
+            }
+        }
+    }
+
     async fn cleanup_workload_logs(&self) -> Result<u64, sqlx::Error> {
         let result = sqlx::query(
             r#"
@@ -132,18 +280,17 @@ impl CleanupWorker {
         Ok(result.rows_affected())
     }
 
+    /// Records carry their own `expires_at` (see `PLFM_IDEMPOTENCY_TTL_HOURS`);
+    /// lapsed in-flight locks are dropped in the same pass.
     async fn cleanup_idempotency_records(&self) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query(
-            r#"
-            DELETE FROM idempotency_records
-            WHERE created_at < now() - make_interval(days => $1)
-            "#,
-        )
-        .bind(self.config.idempotency_retention_days)
-        .execute(&self.pool)
-        .await?;
+        let records = sqlx::query("DELETE FROM idempotency_records WHERE expires_at <= now()")
+            .execute(&self.pool)
+            .await?;
+        let locks = sqlx::query("DELETE FROM idempotency_locks WHERE locked_until <= now()")
+            .execute(&self.pool)
+            .await?;
 
-        Ok(result.rows_affected())
+        Ok(records.rows_affected() + locks.rows_affected())
     }
 }
 
@@ -156,5 +303,6 @@ mod tests {
         let config = CleanupWorkerConfig::default();
         assert_eq!(config.workload_log_retention_days, 7);
         assert_eq!(config.interval.as_secs(), 3600);
+        assert!(config.teardown_interval < config.interval);
     }
 }
@@ -0,0 +1,320 @@
+//! Storage backends.
+//!
+//! The event log, projection checkpoints, and idempotency records are reached
+//! through the [`EventLog`], [`CheckpointLog`], and [`IdempotencyLog`] traits
+//! so they can live in Postgres (the default) or, with the `sqlite` feature,
+//! in a single SQLite file for single-node and development deployments.
+//!
+//! The backend is selected by the `DATABASE_URL` scheme (see
+//! [`DatabaseKind::from_url`]). Materialized views and the API handlers that
+//! read them still require Postgres.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use plfm_events::AggregateType;
+use plfm_id::EventId;
+
+use super::{
+    AppendEvent, Database, DbConfig, DbError, EventRow, EventStore, IdempotencyCheck,
+    IdempotencyStore, ProjectionCheckpoint, ProjectionStore, StoreIdempotencyRecord,
+};
+
+#[cfg(feature = "sqlite")]
+use super::sqlite::SqliteStore;
+
+/// Database engine named by a connection URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseKind {
+    Postgres,
+    Sqlite,
+}
+
+impl DatabaseKind {
+    /// `postgres://` / `postgresql://` select Postgres; `sqlite:` selects
+    /// SQLite (`sqlite://path/to/plfm.db`, `sqlite::memory:`).
+    pub fn from_url(url: &str) -> Result<Self, DbError> {
+        let scheme = url.split_once(':').map(|(scheme, _)| scheme).unwrap_or("");
+        match scheme.to_ascii_lowercase().as_str() {
+            "postgres" | "postgresql" => Ok(DatabaseKind::Postgres),
+            "sqlite" => Ok(DatabaseKind::Sqlite),
+            _ => Err(DbError::UnsupportedBackend(format!(
+                "unrecognized DATABASE_URL scheme '{scheme}' (expected postgres:// or sqlite:)"
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DatabaseKind::Postgres => "postgres",
+            DatabaseKind::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// Append-only event log.
+#[async_trait]
+pub trait EventLog: Send + Sync {
+    /// Append one event; `DbError::SequenceConflict` if its aggregate_seq is
+    /// taken.
+    async fn append(&self, event: AppendEvent) -> Result<EventId, DbError>;
+
+    /// Append events atomically.
+    async fn append_batch(&self, events: Vec<AppendEvent>) -> Result<Vec<EventId>, DbError>;
+
+    /// Events after `after_event_id`, ascending.
+    async fn query_after_cursor(
+        &self,
+        after_event_id: i64,
+        limit: i32,
+    ) -> Result<Vec<EventRow>, DbError>;
+
+    /// Events of one aggregate, ascending by aggregate_seq.
+    async fn query_by_aggregate(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventRow>, DbError>;
+
+    async fn get_latest_aggregate_seq(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_id: &str,
+    ) -> Result<Option<i32>, DbError>;
+
+    /// Highest event_id, or 0 for an empty log.
+    async fn get_max_event_id(&self) -> Result<i64, DbError>;
+}
+
+/// Durable projection checkpoints.
+#[async_trait]
+pub trait CheckpointLog: Send + Sync {
+    async fn get_checkpoint(&self, projection_name: &str) -> Result<ProjectionCheckpoint, DbError>;
+
+    async fn ensure_checkpoint(&self, projection_name: &str) -> Result<(), DbError>;
+
+    async fn update_checkpoint(
+        &self,
+        projection_name: &str,
+        last_applied_event_id: i64,
+    ) -> Result<(), DbError>;
+
+    async fn list_checkpoints(&self) -> Result<Vec<ProjectionCheckpoint>, DbError>;
+
+    async fn reset_checkpoint(&self, projection_name: &str) -> Result<(), DbError>;
+}
+
+/// Stored command responses for idempotent retries.
+#[async_trait]
+pub trait IdempotencyLog: Send + Sync {
+    async fn check(
+        &self,
+        org_id: &str,
+        actor_id: &str,
+        endpoint_name: &str,
+        idempotency_key: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyCheck, DbError>;
+
+    async fn store(&self, record: StoreIdempotencyRecord) -> Result<(), DbError>;
+
+    async fn cleanup_expired(&self, max_age_hours: i32) -> Result<u64, DbError>;
+}
+
+#[async_trait]
+impl EventLog for EventStore {
+    async fn append(&self, event: AppendEvent) -> Result<EventId, DbError> {
+        EventStore::append(self, event).await
+    }
+
+    async fn append_batch(&self, events: Vec<AppendEvent>) -> Result<Vec<EventId>, DbError> {
+        EventStore::append_batch(self, events).await
+    }
+
+    async fn query_after_cursor(
+        &self,
+        after_event_id: i64,
+        limit: i32,
+    ) -> Result<Vec<EventRow>, DbError> {
+        EventStore::query_after_cursor(self, after_event_id, limit).await
+    }
+
+    async fn query_by_aggregate(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventRow>, DbError> {
+        EventStore::query_by_aggregate(self, aggregate_type, aggregate_id).await
+    }
+
+    async fn get_latest_aggregate_seq(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_id: &str,
+    ) -> Result<Option<i32>, DbError> {
+        EventStore::get_latest_aggregate_seq(self, aggregate_type, aggregate_id).await
+    }
+
+    async fn get_max_event_id(&self) -> Result<i64, DbError> {
+        EventStore::get_max_event_id(self).await
+    }
+}
+
+#[async_trait]
+impl CheckpointLog for ProjectionStore {
+    async fn get_checkpoint(&self, projection_name: &str) -> Result<ProjectionCheckpoint, DbError> {
+        ProjectionStore::get_checkpoint(self, projection_name).await
+    }
+
+    async fn ensure_checkpoint(&self, projection_name: &str) -> Result<(), DbError> {
+        ProjectionStore::ensure_checkpoint(self, projection_name).await
+    }
+
+    async fn update_checkpoint(
+        &self,
+        projection_name: &str,
+        last_applied_event_id: i64,
+    ) -> Result<(), DbError> {
+        ProjectionStore::update_checkpoint(self, projection_name, last_applied_event_id).await
+    }
+
+    async fn list_checkpoints(&self) -> Result<Vec<ProjectionCheckpoint>, DbError> {
+        ProjectionStore::list_checkpoints(self).await
+    }
+
+    async fn reset_checkpoint(&self, projection_name: &str) -> Result<(), DbError> {
+        ProjectionStore::reset_checkpoint(self, projection_name).await
+    }
+}
+
+#[async_trait]
+impl IdempotencyLog for IdempotencyStore {
+    async fn check(
+        &self,
+        org_id: &str,
+        actor_id: &str,
+        endpoint_name: &str,
+        idempotency_key: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyCheck, DbError> {
+        IdempotencyStore::check(
+            self,
+            org_id,
+            actor_id,
+            endpoint_name,
+            idempotency_key,
+            request_hash,
+        )
+        .await
+    }
+
+    async fn store(&self, record: StoreIdempotencyRecord) -> Result<(), DbError> {
+        IdempotencyStore::store(self, record).await
+    }
+
+    async fn cleanup_expired(&self, max_age_hours: i32) -> Result<u64, DbError> {
+        IdempotencyStore::cleanup_expired(self, max_age_hours).await
+    }
+}
+
+/// Storage selected by `DATABASE_URL`.
+#[derive(Clone)]
+pub enum Storage {
+    Postgres(Database),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteStore),
+}
+
+impl Storage {
+    /// Connect to the backend named by `config.database_url`.
+    pub async fn connect(config: &DbConfig) -> Result<Self, DbError> {
+        match DatabaseKind::from_url(&config.database_url)? {
+            DatabaseKind::Postgres => Ok(Storage::Postgres(Database::connect(config).await?)),
+            #[cfg(feature = "sqlite")]
+            DatabaseKind::Sqlite => Ok(Storage::Sqlite(SqliteStore::connect(config).await?)),
+            #[cfg(not(feature = "sqlite"))]
+            DatabaseKind::Sqlite => Err(DbError::UnsupportedBackend(
+                "sqlite DATABASE_URL requires building the control plane with the `sqlite` feature"
+                    .to_string(),
+            )),
+        }
+    }
+
+    pub fn kind(&self) -> DatabaseKind {
+        match self {
+            Storage::Postgres(_) => DatabaseKind::Postgres,
+            #[cfg(feature = "sqlite")]
+            Storage::Sqlite(_) => DatabaseKind::Sqlite,
+        }
+    }
+
+    /// Create or upgrade the schema.
+    pub async fn run_migrations(&self) -> Result<(), DbError> {
+        match self {
+            Storage::Postgres(db) => db.run_migrations().await,
+            #[cfg(feature = "sqlite")]
+            Storage::Sqlite(store) => store.run_migrations().await,
+        }
+    }
+
+    /// The Postgres database, for the parts of the control plane (views,
+    /// API handlers, workers) that have no other backend yet.
+    pub fn postgres(&self) -> Option<&Database> {
+        match self {
+            Storage::Postgres(db) => Some(db),
+            #[cfg(feature = "sqlite")]
+            Storage::Sqlite(_) => None,
+        }
+    }
+
+    pub fn event_log(&self) -> Arc<dyn EventLog> {
+        match self {
+            Storage::Postgres(db) => Arc::new(db.event_store()),
+            #[cfg(feature = "sqlite")]
+            Storage::Sqlite(store) => Arc::new(store.clone()),
+        }
+    }
+
+    pub fn checkpoints(&self) -> Arc<dyn CheckpointLog> {
+        match self {
+            Storage::Postgres(db) => Arc::new(db.projection_store()),
+            #[cfg(feature = "sqlite")]
+            Storage::Sqlite(store) => Arc::new(store.clone()),
+        }
+    }
+
+    pub fn idempotency(&self) -> Arc<dyn IdempotencyLog> {
+        match self {
+            Storage::Postgres(db) => Arc::new(db.idempotency_store()),
+            #[cfg(feature = "sqlite")]
+            Storage::Sqlite(store) => Arc::new(store.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_kind_from_url() {
+        assert_eq!(
+            DatabaseKind::from_url("postgres://localhost/plfm").unwrap(),
+            DatabaseKind::Postgres
+        );
+        assert_eq!(
+            DatabaseKind::from_url("postgresql://u:p@db:5432/plfm").unwrap(),
+            DatabaseKind::Postgres
+        );
+        assert_eq!(
+            DatabaseKind::from_url("sqlite://./plfm.db").unwrap(),
+            DatabaseKind::Sqlite
+        );
+        assert_eq!(
+            DatabaseKind::from_url("sqlite::memory:").unwrap(),
+            DatabaseKind::Sqlite
+        );
+        assert!(DatabaseKind::from_url("mysql://localhost/plfm").is_err());
+        assert!(DatabaseKind::from_url("plfm.db").is_err());
+    }
+}
@@ -17,9 +17,9 @@ pub enum DbError {
     #[error("migration failed: {0}")]
     Migration(#[source] sqlx::migrate::MigrateError),
 
-    /// Migration directory not found in the current environment.
-    #[error("migration directory not found; tried {tried}. Last error: {last_error}. Run from repo root or services/control-plane.")]
-    MigrationDirNotFound { tried: String, last_error: String },
+    /// `DATABASE_URL` names a backend this build cannot use.
+    #[error("unsupported database backend: {0}")]
+    UnsupportedBackend(String),
 
     /// Aggregate sequence conflict (optimistic concurrency).
     #[error("aggregate sequence conflict: expected {expected}, got {actual}")]
@@ -0,0 +1,311 @@
+//! Tamper evidence for the event log.
+//!
+//! Each event stores `event_hash`, a SHA-256 over every column a reader can
+//! see (except `event_id`) plus `prev_hash`: the `event_hash` of the previous
+//! event of the same aggregate. Editing a row breaks its own hash; deleting
+//! or inserting one breaks the chain of its aggregate. Deleting the newest
+//! events of an aggregate leaves no broken link, which is what the signed
+//! log checkpoints (Merkle roots over event ranges) are for; see
+//! `crate::event_integrity`.
+//!
+//! Rows written before chaining have no hashes and start no chain.
+//!
+//! See: docs/specs/state/event-log.md (Integrity)
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{postgres::PgRow, Postgres, Row, Transaction};
+
+use super::event_store::AppendEvent;
+use super::DbError;
+
+/// Domain separator of event hash input, versioned with the encoding.
+const EVENT_HASH_DOMAIN: &[u8] = b"plfm.event.v1";
+
+/// Everything an event hash covers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HashedEvent {
+    pub occurred_at: DateTime<Utc>,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub aggregate_seq: i32,
+    pub event_type: String,
+    pub event_version: i32,
+    pub actor_type: String,
+    pub actor_id: String,
+    pub org_id: Option<String>,
+    pub request_id: String,
+    pub idempotency_key: Option<String>,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    pub correlation_id: Option<String>,
+    pub causation_id: Option<i64>,
+    /// The stored JSON payload; `None` for protobuf-only rows.
+    pub payload: Option<serde_json::Value>,
+    pub payload_type_url: Option<String>,
+    pub payload_bytes: Option<Vec<u8>>,
+    pub payload_schema_version: Option<i32>,
+    pub traceparent: Option<String>,
+    pub tags: Option<serde_json::Value>,
+}
+
+impl HashedEvent {
+    /// The row about to be written for `event`.
+    pub fn from_append(
+        event: &AppendEvent,
+        occurred_at: DateTime<Utc>,
+        json_payload: Option<&serde_json::Value>,
+    ) -> Self {
+        Self {
+            occurred_at,
+            aggregate_type: event.aggregate_type.to_string(),
+            aggregate_id: event.aggregate_id.clone(),
+            aggregate_seq: event.aggregate_seq,
+            event_type: event.event_type.clone(),
+            event_version: event.event_version,
+            actor_type: event.actor_type.to_string(),
+            actor_id: event.actor_id.clone(),
+            org_id: event.org_id.as_ref().map(|id| id.to_string()),
+            request_id: event.request_id.clone(),
+            idempotency_key: event.idempotency_key.clone(),
+            app_id: event.app_id.as_ref().map(|id| id.to_string()),
+            env_id: event.env_id.as_ref().map(|id| id.to_string()),
+            correlation_id: event.correlation_id.clone(),
+            causation_id: event.causation_id.map(|id| id.value()),
+            payload: json_payload.cloned(),
+            payload_type_url: event.payload_type_url.clone(),
+            payload_bytes: event.payload_bytes.clone(),
+            payload_schema_version: event.payload_schema_version,
+            traceparent: event.traceparent.clone(),
+            tags: event.tags.clone(),
+        }
+    }
+
+    /// SHA-256 of this event chained to `prev_hash`.
+    ///
+    /// Fields are length-prefixed so no two rows encode alike; JSON columns
+    /// are canonicalized (RFC 8785) because JSONB does not keep the bytes
+    /// that were written.
+    pub fn hash(&self, prev_hash: Option<&[u8]>) -> Vec<u8> {
+        let json = |value: &Option<serde_json::Value>| {
+            value
+                .as_ref()
+                .map(|v| plfm_canonical_json::to_string(v).into_bytes())
+        };
+        let text = |value: &Option<String>| value.as_ref().map(|v| v.as_bytes().to_vec());
+        let number = |value: Option<i64>| value.map(|v| v.to_string().into_bytes());
+
+        let fields: [Option<Vec<u8>>; 22] = [
+            prev_hash.map(<[u8]>::to_vec),
+            Some(
+                self.occurred_at
+                    .to_rfc3339_opts(SecondsFormat::Micros, true)
+                    .into_bytes(),
+            ),
+            Some(self.aggregate_type.as_bytes().to_vec()),
+            Some(self.aggregate_id.as_bytes().to_vec()),
+            number(Some(self.aggregate_seq.into())),
+            Some(self.event_type.as_bytes().to_vec()),
+            number(Some(self.event_version.into())),
+            Some(self.actor_type.as_bytes().to_vec()),
+            Some(self.actor_id.as_bytes().to_vec()),
+            text(&self.org_id),
+            Some(self.request_id.as_bytes().to_vec()),
+            text(&self.idempotency_key),
+            text(&self.app_id),
+            text(&self.env_id),
+            text(&self.correlation_id),
+            number(self.causation_id),
+            json(&self.payload),
+            text(&self.payload_type_url),
+            self.payload_bytes.clone(),
+            number(self.payload_schema_version.map(i64::from)),
+            text(&self.traceparent),
+            json(&self.tags),
+        ];
+
+        let mut hasher = Sha256::new();
+        hasher.update(EVENT_HASH_DOMAIN);
+        for field in fields {
+            match field {
+                None => hasher.update([0u8]),
+                Some(bytes) => {
+                    hasher.update([1u8]);
+                    hasher.update((bytes.len() as u64).to_be_bytes());
+                    hasher.update(bytes);
+                }
+            }
+        }
+        hasher.finalize().to_vec()
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for HashedEvent {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            occurred_at: row.try_get("occurred_at")?,
+            aggregate_type: row.try_get("aggregate_type")?,
+            aggregate_id: row.try_get("aggregate_id")?,
+            aggregate_seq: row.try_get("aggregate_seq")?,
+            event_type: row.try_get("event_type")?,
+            event_version: row.try_get("event_version")?,
+            actor_type: row.try_get("actor_type")?,
+            actor_id: row.try_get("actor_id")?,
+            org_id: row.try_get("org_id")?,
+            request_id: row.try_get("request_id")?,
+            idempotency_key: row.try_get("idempotency_key")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            correlation_id: row.try_get("correlation_id")?,
+            causation_id: row.try_get("causation_id")?,
+            payload: row.try_get("payload")?,
+            payload_type_url: row.try_get("payload_type_url")?,
+            payload_bytes: row.try_get("payload_bytes")?,
+            payload_schema_version: row.try_get("payload_schema_version")?,
+            traceparent: row.try_get("traceparent")?,
+            tags: row.try_get("tags")?,
+        })
+    }
+}
+
+/// Columns [`HashedEvent`] reads.
+pub const HASHED_EVENT_COLUMNS: &str = "occurred_at, aggregate_type, aggregate_id, \
+    aggregate_seq, event_type, event_version, actor_type, actor_id, org_id, request_id, \
+    idempotency_key, app_id, env_id, correlation_id, causation_id, payload, \
+    payload_type_url, payload_bytes, payload_schema_version, traceparent, tags";
+
+/// The timestamp an appended event is stored and hashed with. Postgres keeps
+/// microseconds, so finer digits would not survive the round trip.
+pub fn append_timestamp() -> DateTime<Utc> {
+    let now = Utc::now();
+    DateTime::from_timestamp_micros(now.timestamp_micros()).unwrap_or(now)
+}
+
+/// `event_hash` of the aggregate's latest event before `aggregate_seq`, as
+/// seen by `tx` (so earlier events of the same batch count).
+pub async fn prev_hash(
+    tx: &mut Transaction<'_, Postgres>,
+    event: &AppendEvent,
+) -> Result<Option<Vec<u8>>, DbError> {
+    let prev: Option<Option<Vec<u8>>> = sqlx::query_scalar(
+        r#"
+        SELECT event_hash
+        FROM events
+        WHERE aggregate_type = $1 AND aggregate_id = $2 AND aggregate_seq < $3
+        ORDER BY aggregate_seq DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(event.aggregate_type.to_string())
+    .bind(&event.aggregate_id)
+    .bind(event.aggregate_seq)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(DbError::Query)?;
+    Ok(prev.flatten())
+}
+
+/// Merkle root over event hashes in log order (RFC 6962 leaf and node
+/// prefixes; an odd node is carried up unchanged).
+pub fn merkle_root<I, T>(leaves: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut level: Vec<Vec<u8>> = leaves
+        .into_iter()
+        .map(|leaf| {
+            let mut hasher = Sha256::new();
+            hasher.update([0u8]);
+            hasher.update(leaf.as_ref());
+            hasher.finalize().to_vec()
+        })
+        .collect();
+    if level.is_empty() {
+        return Sha256::digest([]).to_vec();
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([1u8]);
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().to_vec()
+                }
+                [single] => single.clone(),
+                _ => unreachable!("chunks of two"),
+            })
+            .collect();
+    }
+    level.swap_remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event() -> HashedEvent {
+        HashedEvent {
+            occurred_at: "2026-10-16T09:30:00.123456Z".parse().unwrap(),
+            aggregate_type: "app".to_string(),
+            aggregate_id: "app_1".to_string(),
+            aggregate_seq: 2,
+            event_type: "app.updated".to_string(),
+            event_version: 1,
+            actor_type: "user".to_string(),
+            actor_id: "usr_1".to_string(),
+            org_id: Some("org_1".to_string()),
+            request_id: "req_1".to_string(),
+            payload: Some(json!({ "name": "web", "labels": { "b": 1, "a": 2 } })),
+            payload_bytes: Some(vec![1, 2, 3]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hash_covers_content_and_chain() {
+        let base = event();
+        let hash = base.hash(None);
+        assert_eq!(hash.len(), 32);
+        assert_eq!(hash, event().hash(None));
+        assert_ne!(hash, base.hash(Some(&[0u8; 32])));
+
+        let mut edited = event();
+        edited.actor_id = "usr_2".to_string();
+        assert_ne!(edited.hash(None), hash);
+
+        let mut edited = event();
+        edited.payload = Some(json!({ "name": "worker", "labels": { "b": 1, "a": 2 } }));
+        assert_ne!(edited.hash(None), hash);
+
+        // Absent and empty differ.
+        let mut edited = event();
+        edited.correlation_id = Some(String::new());
+        assert_ne!(edited.hash(None), hash);
+
+        // JSONB reorders keys; the hash does not care.
+        let mut reordered = event();
+        reordered.payload = Some(json!({ "labels": { "a": 2, "b": 1 }, "name": "web" }));
+        assert_eq!(reordered.hash(None), hash);
+    }
+
+    #[test]
+    fn test_merkle_root() {
+        let leaves: Vec<Vec<u8>> = (0u8..5).map(|i| vec![i; 32]).collect();
+        let root = merkle_root(&leaves);
+        assert_eq!(root, merkle_root(&leaves));
+        assert_ne!(root, merkle_root(&leaves[..4]));
+
+        let mut swapped = leaves.clone();
+        swapped.swap(1, 2);
+        assert_ne!(root, merkle_root(&swapped));
+
+        assert_eq!(merkle_root(Vec::<Vec<u8>>::new()).len(), 32);
+        // A single leaf is still hashed, so a root never equals an event hash.
+        assert_ne!(merkle_root([&leaves[0]]), leaves[0]);
+    }
+}
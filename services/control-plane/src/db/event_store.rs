@@ -5,20 +5,30 @@
 //! - Query events by cursor (for projections)
 //! - Query events by aggregate (for loading aggregate state)
 //! - Query events by org (for tenant-scoped reads)
+//!
+//! Payloads are always encoded to protobuf (`payload_bytes`). The JSON
+//! `payload` column is written too unless protobuf-only encoding is enabled
+//! (see [`PayloadEncoding`]); rows without JSON are decoded from
+//! `payload_bytes` on read, so callers always see `EventRow::payload`.
 
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
 use chrono::{DateTime, Utc};
-use plfm_events::{event_types, ActorType, AggregateType};
+use plfm_events::{event_types, ActorType, AggregateType, CausalEvent, NewEvent};
 use plfm_id::{AppId, EnvId, EventId, OrgId};
+use plfm_proto::common::v1::{ActorType as ProtoActorType, AggregateType as ProtoAggregateType};
+use plfm_proto::convert::timestamp_from_datetime;
+use plfm_proto::events::v1::EventEnvelope;
 use plfm_proto::FILE_DESCRIPTOR_SET;
 use prost_012::Message;
 use prost_reflect::{
     DescriptorPool, DeserializeOptions, DynamicMessage, EnumDescriptor, FieldDescriptor, Kind,
-    MessageDescriptor,
+    MessageDescriptor, SerializeOptions,
 };
-use sqlx::{postgres::PgPool, postgres::PgRow, Row};
+use sqlx::{postgres::PgPool, postgres::PgRow, Postgres, Row, Transaction};
 
+use super::event_chain::{self, HashedEvent};
 use super::DbError;
 
 /// A row from the events table.
@@ -50,7 +60,7 @@ pub struct EventRow {
 
 impl<'r> sqlx::FromRow<'r, PgRow> for EventRow {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
-        Ok(Self {
+        let mut event = Self {
             event_id: row.try_get("event_id")?,
             occurred_at: row.try_get("occurred_at")?,
             aggregate_type: row.try_get("aggregate_type")?,
@@ -67,16 +77,161 @@ impl<'r> sqlx::FromRow<'r, PgRow> for EventRow {
             env_id: row.try_get("env_id")?,
             correlation_id: row.try_get("correlation_id")?,
             causation_id: row.try_get("causation_id")?,
-            payload: row.try_get("payload")?,
+            payload: serde_json::Value::Null,
             payload_type_url: row.try_get("payload_type_url").ok(),
             payload_bytes: row.try_get("payload_bytes").ok(),
             payload_schema_version: row.try_get("payload_schema_version").ok(),
             traceparent: row.try_get("traceparent").ok(),
             tags: row.try_get("tags").ok(),
+        };
+
+        // Dual read: protobuf-only rows carry no JSON payload.
+        let payload: Option<serde_json::Value> = row.try_get("payload")?;
+        event.payload = match payload {
+            Some(payload) => payload,
+            None => match (
+                event.payload_type_url.as_deref(),
+                event.payload_bytes.as_deref(),
+            ) {
+                (Some(type_url), Some(bytes)) => decode_payload_json(type_url, bytes)
+                    .map_err(|e| sqlx::Error::Decode(e.to_string().into()))?,
+                _ => serde_json::Value::Null,
+            },
+        };
+
+        Ok(event)
+    }
+}
+
+/// Parse a snake_case enum column back into its domain enum.
+fn parse_column<T: serde::de::DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+impl EventRow {
+    /// Build the protobuf wire envelope for this event.
+    ///
+    /// Rows written before protobuf payloads existed are encoded from their
+    /// JSON payload on the fly.
+    pub fn to_envelope(&self) -> Result<EventEnvelope, DbError> {
+        let (payload_type_url, payload) =
+            match (self.payload_type_url.as_ref(), self.payload_bytes.as_ref()) {
+                (Some(type_url), Some(bytes)) => (type_url.clone(), bytes.clone()),
+                _ => {
+                    let type_url =
+                        payload_type_url_for_event(&self.event_type).ok_or_else(|| {
+                            DbError::InvalidPayload(format!(
+                                "missing payload type url for event_type {}",
+                                self.event_type
+                            ))
+                        })?;
+                    (
+                        type_url.to_string(),
+                        encode_payload_bytes(type_url, &self.payload)?,
+                    )
+                }
+            };
+
+        let tags: HashMap<String, String> = self
+            .tags
+            .as_ref()
+            .and_then(|tags| tags.as_object())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(EventEnvelope {
+            event_id: self.event_id.to_string(),
+            sequence: self.event_id as u64,
+            observed_at: Some(timestamp_from_datetime(self.occurred_at)),
+            org_id: self.org_id.clone().unwrap_or_default(),
+            project_id: String::new(),
+            app_id: self.app_id.clone().unwrap_or_default(),
+            env_id: self.env_id.clone().unwrap_or_default(),
+            aggregate_type: parse_column::<AggregateType>(&self.aggregate_type)
+                .map(ProtoAggregateType::from)
+                .unwrap_or(ProtoAggregateType::Unspecified) as i32,
+            aggregate_id: self.aggregate_id.clone(),
+            aggregate_seq: self.aggregate_seq.max(0) as u32,
+            event_type: self.event_type.clone(),
+            schema_version: self.event_version.max(0) as u32,
+            payload_type_url,
+            payload,
+            traceparent: self.traceparent.clone().unwrap_or_default(),
+            tags,
+            actor_type: parse_column::<ActorType>(&self.actor_type)
+                .map(ProtoActorType::from)
+                .unwrap_or(ProtoActorType::Unspecified) as i32,
+            actor_id: self.actor_id.clone(),
+            request_id: self.request_id.clone(),
+            idempotency_key: self.idempotency_key.clone().unwrap_or_default(),
+            correlation_id: self.correlation_id.clone().unwrap_or_default(),
+            causation_id: self
+                .causation_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
         })
     }
+
+    /// Protobuf-encoded [`EventEnvelope`] for this event.
+    pub fn encode_envelope(&self) -> Result<Vec<u8>, DbError> {
+        Ok(prost::Message::encode_to_vec(&self.to_envelope()?))
+    }
 }
 
+impl CausalEvent for EventRow {
+    fn event_id(&self) -> EventId {
+        EventId::new(self.event_id)
+    }
+
+    fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    fn request_id(&self) -> &str {
+        &self.request_id
+    }
+}
+
+/// How event payloads are written to the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadEncoding {
+    /// JSON `payload` plus protobuf `payload_bytes` (default).
+    #[default]
+    Json,
+    /// Protobuf-only for high-volume event types whose payload round-trips
+    /// losslessly; everything else is still written in both forms.
+    Protobuf,
+}
+
+impl PayloadEncoding {
+    /// Read `PLFM_EVENT_PAYLOAD_ENCODING` (`json` or `protobuf`).
+    pub fn from_env() -> Self {
+        static ENCODING: OnceLock<PayloadEncoding> = OnceLock::new();
+        *ENCODING.get_or_init(|| {
+            match std::env::var("PLFM_EVENT_PAYLOAD_ENCODING")
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "protobuf" | "proto" => PayloadEncoding::Protobuf,
+                _ => PayloadEncoding::Json,
+            }
+        })
+    }
+}
+
+/// Channel the `events_appended_notify` trigger signals on commit of an
+/// append (see migration 00023).
+pub const EVENTS_NOTIFY_CHANNEL: &str = "plfm_events";
+
+/// Event types eligible for protobuf-only storage. These dominate log volume;
+/// nothing queries their JSON payload in SQL.
+const PROTOBUF_ONLY_EVENT_PREFIXES: &[&str] = &["instance.", "node."];
+
 /// Input for appending a new event.
 #[derive(Debug, Clone, Default)]
 pub struct AppendEvent {
@@ -107,16 +262,83 @@ pub struct AppendEvent {
     pub tags: Option<serde_json::Value>,
 }
 
+impl From<NewEvent> for AppendEvent {
+    fn from(event: NewEvent) -> Self {
+        Self {
+            aggregate_type: event.aggregate_type,
+            aggregate_id: event.aggregate_id,
+            aggregate_seq: event.aggregate_seq,
+            event_type: event.event_type,
+            event_version: event.event_version,
+            actor_type: event.actor_type,
+            actor_id: event.actor_id,
+            org_id: event.org_id,
+            request_id: event.request_id,
+            idempotency_key: event.idempotency_key,
+            app_id: event.app_id,
+            env_id: event.env_id,
+            correlation_id: event.correlation_id,
+            causation_id: event.causation_id,
+            payload: event.payload,
+            ..Default::default()
+        }
+    }
+}
+
+/// Optional filters for org-scoped event queries (the events API).
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Exact event type, or a prefix when it ends in `*` (e.g. `deploy.*`).
+    pub event_type: Option<String>,
+    pub aggregate_type: Option<String>,
+    pub aggregate_id: Option<String>,
+    pub actor_type: Option<String>,
+    pub actor_id: Option<String>,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    /// Only events that occurred at or after this time.
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl EventFilter {
+    /// Split `event_type` into an exact match and a `LIKE` prefix pattern.
+    fn event_type_match(&self) -> (Option<String>, Option<String>) {
+        match self.event_type.as_deref() {
+            None => (None, None),
+            Some(t) => match t.strip_suffix('*') {
+                Some(prefix) => {
+                    let escaped = prefix
+                        .replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_");
+                    (None, Some(format!("{escaped}%")))
+                }
+                None => (Some(t.to_string()), None),
+            },
+        }
+    }
+}
+
 /// Event store for managing the append-only event log.
 #[derive(Clone)]
 pub struct EventStore {
     pool: PgPool,
+    encoding: PayloadEncoding,
 }
 
 impl EventStore {
-    /// Create a new event store.
+    /// Create a new event store using the configured payload encoding.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            encoding: PayloadEncoding::from_env(),
+        }
+    }
+
+    /// Override the payload encoding.
+    pub fn with_encoding(mut self, encoding: PayloadEncoding) -> Self {
+        self.encoding = encoding;
+        self
     }
 
     /// Append a single event to the log.
@@ -130,71 +352,11 @@ impl EventStore {
     pub async fn append(&self, event: AppendEvent) -> Result<EventId, DbError> {
         let mut event = event;
         populate_protobuf_payload(&mut event)?;
-        let result = sqlx::query(
-            r#"
-            INSERT INTO events (
-                aggregate_type,
-                aggregate_id,
-                aggregate_seq,
-                event_type,
-                event_version,
-                actor_type,
-                actor_id,
-                org_id,
-                request_id,
-                idempotency_key,
-                app_id,
-                env_id,
-                correlation_id,
-                causation_id,
-                payload,
-                payload_type_url,
-                payload_bytes,
-                payload_schema_version,
-                traceparent,
-                tags
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
-            RETURNING event_id
-            "#,
-        )
-        .bind(event.aggregate_type.to_string())
-        .bind(&event.aggregate_id)
-        .bind(event.aggregate_seq)
-        .bind(&event.event_type)
-        .bind(event.event_version)
-        .bind(event.actor_type.to_string())
-        .bind(&event.actor_id)
-        .bind(event.org_id.as_ref().map(|id| id.to_string()))
-        .bind(&event.request_id)
-        .bind(&event.idempotency_key)
-        .bind(event.app_id.as_ref().map(|id| id.to_string()))
-        .bind(event.env_id.as_ref().map(|id| id.to_string()))
-        .bind(&event.correlation_id)
-        .bind(event.causation_id.map(|id| id.value()))
-        .bind(&event.payload)
-        .bind(&event.payload_type_url)
-        .bind(&event.payload_bytes)
-        .bind(event.payload_schema_version)
-        .bind(&event.traceparent)
-        .bind(&event.tags)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(|e| {
-            if let sqlx::Error::Database(ref db_err) = e {
-                if db_err.code().as_deref() == Some("23505") {
-                    return DbError::SequenceConflict {
-                        aggregate_id: event.aggregate_id.clone(),
-                        expected: event.aggregate_seq,
-                        actual: event.aggregate_seq,
-                    };
-                }
-            }
-            DbError::Query(e)
-        })?;
 
-        let event_id: i64 = result.get("event_id");
-        Ok(EventId::new(event_id))
+        let mut tx = self.pool.begin().await.map_err(DbError::Query)?;
+        let event_id = insert_event(&mut tx, &event, self.encoding).await?;
+        tx.commit().await.map_err(DbError::Query)?;
+        Ok(event_id)
     }
 
     /// Append multiple events atomically.
@@ -213,73 +375,8 @@ impl EventStore {
 
         let mut tx = self.pool.begin().await.map_err(DbError::Query)?;
         let mut event_ids = Vec::with_capacity(events.len());
-
-        for event in events {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO events (
-                    aggregate_type,
-                    aggregate_id,
-                    aggregate_seq,
-                    event_type,
-                    event_version,
-                    actor_type,
-                    actor_id,
-                    org_id,
-                    request_id,
-                    idempotency_key,
-                    app_id,
-                    env_id,
-                    correlation_id,
-                    causation_id,
-                    payload,
-                    payload_type_url,
-                    payload_bytes,
-                    payload_schema_version,
-                    traceparent,
-                    tags
-                )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
-                RETURNING event_id
-                "#,
-            )
-            .bind(event.aggregate_type.to_string())
-            .bind(&event.aggregate_id)
-            .bind(event.aggregate_seq)
-            .bind(&event.event_type)
-            .bind(event.event_version)
-            .bind(event.actor_type.to_string())
-            .bind(&event.actor_id)
-            .bind(event.org_id.as_ref().map(|id| id.to_string()))
-            .bind(&event.request_id)
-            .bind(&event.idempotency_key)
-            .bind(event.app_id.as_ref().map(|id| id.to_string()))
-            .bind(event.env_id.as_ref().map(|id| id.to_string()))
-            .bind(&event.correlation_id)
-            .bind(event.causation_id.map(|id| id.value()))
-            .bind(&event.payload)
-            .bind(&event.payload_type_url)
-            .bind(&event.payload_bytes)
-            .bind(event.payload_schema_version)
-            .bind(&event.traceparent)
-            .bind(&event.tags)
-            .fetch_one(&mut *tx)
-            .await
-            .map_err(|e| {
-                if let sqlx::Error::Database(ref db_err) = e {
-                    if db_err.code().as_deref() == Some("23505") {
-                        return DbError::SequenceConflict {
-                            aggregate_id: event.aggregate_id.clone(),
-                            expected: event.aggregate_seq,
-                            actual: event.aggregate_seq,
-                        };
-                    }
-                }
-                DbError::Query(e)
-            })?;
-
-            let event_id: i64 = result.get("event_id");
-            event_ids.push(EventId::new(event_id));
+        for event in &events {
+            event_ids.push(insert_event(&mut tx, event, self.encoding).await?);
         }
 
         tx.commit().await.map_err(DbError::Query)?;
@@ -382,6 +479,57 @@ impl EventStore {
         Ok(rows)
     }
 
+    /// Query events for several aggregates of one type.
+    ///
+    /// Returns events in ascending event_id order.
+    pub async fn query_by_aggregates(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_ids: &[String],
+    ) -> Result<Vec<EventRow>, DbError> {
+        if aggregate_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query_as::<_, EventRow>(
+            r#"
+            SELECT
+                event_id,
+                occurred_at,
+                aggregate_type,
+                aggregate_id,
+                aggregate_seq,
+                event_type,
+                event_version,
+                actor_type,
+                actor_id,
+                org_id,
+                request_id,
+                idempotency_key,
+                app_id,
+                env_id,
+                correlation_id,
+                causation_id,
+                payload,
+                payload_type_url,
+                payload_bytes,
+                payload_schema_version,
+                traceparent,
+                tags
+            FROM events
+            WHERE aggregate_type = $1 AND aggregate_id = ANY($2)
+            ORDER BY event_id ASC
+            "#,
+        )
+        .bind(aggregate_type.to_string())
+        .bind(aggregate_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
     /// Get the latest aggregate sequence number.
     ///
     /// Returns None if no events exist for the aggregate.
@@ -456,6 +604,220 @@ impl EventStore {
         Ok(rows)
     }
 
+    /// Query an organization's events after a cursor, applying `filter` in SQL.
+    ///
+    /// Returns events in ascending event_id order.
+    pub async fn query_by_org_filtered(
+        &self,
+        org_id: &OrgId,
+        filter: &EventFilter,
+        after_event_id: i64,
+        limit: i32,
+    ) -> Result<Vec<EventRow>, DbError> {
+        self.query_filtered(Some(org_id), filter, after_event_id, limit)
+            .await
+    }
+
+    /// Query events after a cursor across every organization (or just
+    /// `org_id`), applying `filter` in SQL. Used by operator tooling.
+    ///
+    /// Returns events in ascending event_id order.
+    pub async fn query_filtered(
+        &self,
+        org_id: Option<&OrgId>,
+        filter: &EventFilter,
+        after_event_id: i64,
+        limit: i32,
+    ) -> Result<Vec<EventRow>, DbError> {
+        self.query_filtered_page(org_id, filter, after_event_id, None, false, limit.into())
+            .await
+    }
+
+    /// Query one page of events with event_id > `after_event_id` matching
+    /// `filter`, ordered by event_id (descending when `desc`). `cursor` is the
+    /// last event_id of the previous page.
+    pub async fn query_filtered_page(
+        &self,
+        org_id: Option<&OrgId>,
+        filter: &EventFilter,
+        after_event_id: i64,
+        cursor: Option<i64>,
+        desc: bool,
+        limit: i64,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let (event_type, event_type_prefix) = filter.event_type_match();
+        let (op, dir) = if desc { ("<", "DESC") } else { (">", "ASC") };
+        let sql = format!(
+            r#"
+            SELECT
+                event_id,
+                occurred_at,
+                aggregate_type,
+                aggregate_id,
+                aggregate_seq,
+                event_type,
+                event_version,
+                actor_type,
+                actor_id,
+                org_id,
+                request_id,
+                idempotency_key,
+                app_id,
+                env_id,
+                correlation_id,
+                causation_id,
+                payload,
+                payload_type_url,
+                payload_bytes,
+                payload_schema_version,
+                traceparent,
+                tags
+            FROM events
+            WHERE ($1::TEXT IS NULL OR org_id = $1)
+              AND event_id > $2
+              AND ($3::TEXT IS NULL OR event_type = $3)
+              AND ($4::TEXT IS NULL OR event_type LIKE $4)
+              AND ($5::TEXT IS NULL OR aggregate_type = $5)
+              AND ($6::TEXT IS NULL OR aggregate_id = $6)
+              AND ($7::TEXT IS NULL OR actor_type = $7)
+              AND ($8::TEXT IS NULL OR actor_id = $8)
+              AND ($9::TEXT IS NULL OR app_id = $9)
+              AND ($10::TEXT IS NULL OR env_id = $10)
+              AND ($11::TIMESTAMPTZ IS NULL OR occurred_at >= $11)
+              AND ($13::BIGINT IS NULL OR event_id {op} $13)
+            ORDER BY event_id {dir}
+            LIMIT $12
+            "#
+        );
+        let rows = sqlx::query_as::<_, EventRow>(&sql)
+            .bind(org_id.map(ToString::to_string))
+            .bind(after_event_id)
+            .bind(event_type)
+            .bind(event_type_prefix)
+            .bind(filter.aggregate_type.as_deref())
+            .bind(filter.aggregate_id.as_deref())
+            .bind(filter.actor_type.as_deref())
+            .bind(filter.actor_id.as_deref())
+            .bind(filter.app_id.as_deref())
+            .bind(filter.env_id.as_deref())
+            .bind(filter.since)
+            .bind(limit)
+            .bind(cursor)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
+    /// Count events with event_id > `after_event_id` matching `filter`,
+    /// stopping at `cap + 1`.
+    pub async fn count_filtered(
+        &self,
+        org_id: Option<&OrgId>,
+        filter: &EventFilter,
+        after_event_id: i64,
+        cap: i64,
+    ) -> Result<i64, DbError> {
+        let (event_type, event_type_prefix) = filter.event_type_match();
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM (
+                SELECT 1
+                FROM events
+                WHERE ($1::TEXT IS NULL OR org_id = $1)
+                  AND event_id > $2
+                  AND ($3::TEXT IS NULL OR event_type = $3)
+                  AND ($4::TEXT IS NULL OR event_type LIKE $4)
+                  AND ($5::TEXT IS NULL OR aggregate_type = $5)
+                  AND ($6::TEXT IS NULL OR aggregate_id = $6)
+                  AND ($7::TEXT IS NULL OR actor_type = $7)
+                  AND ($8::TEXT IS NULL OR actor_id = $8)
+                  AND ($9::TEXT IS NULL OR app_id = $9)
+                  AND ($10::TEXT IS NULL OR env_id = $10)
+                  AND ($11::TIMESTAMPTZ IS NULL OR occurred_at >= $11)
+                LIMIT $12
+            ) capped
+            "#,
+        )
+        .bind(org_id.map(ToString::to_string))
+        .bind(after_event_id)
+        .bind(event_type)
+        .bind(event_type_prefix)
+        .bind(filter.aggregate_type.as_deref())
+        .bind(filter.aggregate_id.as_deref())
+        .bind(filter.actor_type.as_deref())
+        .bind(filter.actor_id.as_deref())
+        .bind(filter.app_id.as_deref())
+        .bind(filter.env_id.as_deref())
+        .bind(filter.since)
+        .bind(cap + 1)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(count)
+    }
+
+    /// Query an env's events of the given types, optionally only those that
+    /// occurred at or before `until`.
+    ///
+    /// Returns events in ascending event_id order.
+    pub async fn query_by_env(
+        &self,
+        env_id: &EnvId,
+        event_types: &[&str],
+        after_event_id: i64,
+        until: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let event_types: Vec<String> = event_types.iter().map(|t| t.to_string()).collect();
+        let rows = sqlx::query_as::<_, EventRow>(
+            r#"
+            SELECT
+                event_id,
+                occurred_at,
+                aggregate_type,
+                aggregate_id,
+                aggregate_seq,
+                event_type,
+                event_version,
+                actor_type,
+                actor_id,
+                org_id,
+                request_id,
+                idempotency_key,
+                app_id,
+                env_id,
+                correlation_id,
+                causation_id,
+                payload,
+                payload_type_url,
+                payload_bytes,
+                payload_schema_version,
+                traceparent,
+                tags
+            FROM events
+            WHERE env_id = $1
+              AND event_type = ANY($2)
+              AND event_id > $3
+              AND ($4::TIMESTAMPTZ IS NULL OR occurred_at <= $4)
+            ORDER BY event_id ASC
+            LIMIT $5
+            "#,
+        )
+        .bind(env_id.to_string())
+        .bind(&event_types)
+        .bind(after_event_id)
+        .bind(until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
     /// Query events by type after a cursor.
     ///
     /// Used for type-filtered streaming.
@@ -506,6 +868,103 @@ impl EventStore {
         Ok(rows)
     }
 
+    /// Query events of any of `event_types` after a cursor.
+    ///
+    /// Returns events in ascending event_id order.
+    pub async fn query_by_types_after_cursor(
+        &self,
+        event_types: &[&str],
+        after_event_id: i64,
+        limit: i32,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let event_types: Vec<String> = event_types.iter().map(|t| t.to_string()).collect();
+        let rows = sqlx::query_as::<_, EventRow>(
+            r#"
+            SELECT
+                event_id,
+                occurred_at,
+                aggregate_type,
+                aggregate_id,
+                aggregate_seq,
+                event_type,
+                event_version,
+                actor_type,
+                actor_id,
+                org_id,
+                request_id,
+                idempotency_key,
+                app_id,
+                env_id,
+                correlation_id,
+                causation_id,
+                payload,
+                payload_type_url,
+                payload_bytes,
+                payload_schema_version,
+                traceparent,
+                tags
+            FROM events
+            WHERE event_type = ANY($1) AND event_id > $2
+            ORDER BY event_id ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(&event_types)
+        .bind(after_event_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
+    /// Query the events with the given IDs; IDs with no event are skipped.
+    ///
+    /// Returns events in ascending event_id order.
+    pub async fn query_by_ids(&self, event_ids: &[i64]) -> Result<Vec<EventRow>, DbError> {
+        if event_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query_as::<_, EventRow>(
+            r#"
+            SELECT
+                event_id,
+                occurred_at,
+                aggregate_type,
+                aggregate_id,
+                aggregate_seq,
+                event_type,
+                event_version,
+                actor_type,
+                actor_id,
+                org_id,
+                request_id,
+                idempotency_key,
+                app_id,
+                env_id,
+                correlation_id,
+                causation_id,
+                payload,
+                payload_type_url,
+                payload_bytes,
+                payload_schema_version,
+                traceparent,
+                tags
+            FROM events
+            WHERE event_id = ANY($1)
+            ORDER BY event_id ASC
+            "#,
+        )
+        .bind(event_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
     /// Get the current max event_id.
     ///
     /// Returns 0 if no events exist.
@@ -519,7 +978,92 @@ impl EventStore {
     }
 }
 
-fn populate_protobuf_payload(event: &mut AppendEvent) -> Result<(), DbError> {
+/// Insert one event, chained to the previous event of its aggregate (see
+/// [`super::event_chain`]).
+async fn insert_event(
+    tx: &mut Transaction<'_, Postgres>,
+    event: &AppendEvent,
+    encoding: PayloadEncoding,
+) -> Result<EventId, DbError> {
+    let json_payload = stored_json_payload(event, encoding);
+    let occurred_at = event_chain::append_timestamp();
+    let prev_hash = event_chain::prev_hash(tx, event).await?;
+    let event_hash =
+        HashedEvent::from_append(event, occurred_at, json_payload).hash(prev_hash.as_deref());
+
+    let event_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO events (
+            occurred_at,
+            aggregate_type,
+            aggregate_id,
+            aggregate_seq,
+            event_type,
+            event_version,
+            actor_type,
+            actor_id,
+            org_id,
+            request_id,
+            idempotency_key,
+            app_id,
+            env_id,
+            correlation_id,
+            causation_id,
+            payload,
+            payload_type_url,
+            payload_bytes,
+            payload_schema_version,
+            traceparent,
+            tags,
+            prev_hash,
+            event_hash
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
+        RETURNING event_id
+        "#,
+    )
+    .bind(occurred_at)
+    .bind(event.aggregate_type.to_string())
+    .bind(&event.aggregate_id)
+    .bind(event.aggregate_seq)
+    .bind(&event.event_type)
+    .bind(event.event_version)
+    .bind(event.actor_type.to_string())
+    .bind(&event.actor_id)
+    .bind(event.org_id.as_ref().map(|id| id.to_string()))
+    .bind(&event.request_id)
+    .bind(&event.idempotency_key)
+    .bind(event.app_id.as_ref().map(|id| id.to_string()))
+    .bind(event.env_id.as_ref().map(|id| id.to_string()))
+    .bind(&event.correlation_id)
+    .bind(event.causation_id.map(|id| id.value()))
+    .bind(json_payload)
+    .bind(&event.payload_type_url)
+    .bind(&event.payload_bytes)
+    .bind(event.payload_schema_version)
+    .bind(&event.traceparent)
+    .bind(&event.tags)
+    .bind(prev_hash)
+    .bind(event_hash)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| {
+        if let sqlx::Error::Database(ref db_err) = e {
+            if db_err.code().as_deref() == Some("23505") {
+                return DbError::SequenceConflict {
+                    aggregate_id: event.aggregate_id.clone(),
+                    expected: event.aggregate_seq,
+                    actual: event.aggregate_seq,
+                };
+            }
+        }
+        DbError::Query(e)
+    })?;
+
+    Ok(EventId::new(event_id))
+}
+
+pub(super) fn populate_protobuf_payload(event: &mut AppendEvent) -> Result<(), DbError> {
     if event.payload_bytes.is_some() && event.payload_type_url.is_some() {
         return Ok(());
     }
@@ -546,6 +1090,147 @@ fn populate_protobuf_payload(event: &mut AppendEvent) -> Result<(), DbError> {
     Ok(())
 }
 
+/// JSON payload to store for an event, or `None` when protobuf-only storage
+/// applies: the encoding is `Protobuf`, the event type is eligible, and the
+/// protobuf bytes decode back to exactly the same JSON.
+pub(super) fn stored_json_payload(
+    event: &AppendEvent,
+    encoding: PayloadEncoding,
+) -> Option<&serde_json::Value> {
+    if encoding == PayloadEncoding::Json
+        || !PROTOBUF_ONLY_EVENT_PREFIXES
+            .iter()
+            .any(|prefix| event.event_type.starts_with(prefix))
+    {
+        return Some(&event.payload);
+    }
+
+    let (Some(type_url), Some(bytes)) = (&event.payload_type_url, &event.payload_bytes) else {
+        return Some(&event.payload);
+    };
+    match decode_payload_json(type_url, bytes) {
+        Ok(decoded) if decoded == strip_nulls(&event.payload) => None,
+        Ok(_) => {
+            tracing::debug!(
+                event_type = %event.event_type,
+                "Payload does not round-trip through protobuf; storing JSON too"
+            );
+            Some(&event.payload)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, event_type = %event.event_type, "Failed to verify protobuf payload");
+            Some(&event.payload)
+        }
+    }
+}
+
+fn strip_nulls(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), strip_nulls(v)))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(strip_nulls).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// Decode protobuf payload bytes into the JSON shape the payload structs use
+/// (snake_case field names, numeric 64-bit integers, lower-case enum values).
+pub fn decode_payload_json(type_url: &str, bytes: &[u8]) -> Result<serde_json::Value, DbError> {
+    let pool = descriptor_pool()?;
+    let message_name = type_url.rsplit('/').next().unwrap_or(type_url);
+    let descriptor = pool
+        .get_message_by_name(message_name)
+        .ok_or_else(|| DbError::InvalidPayload(format!("unknown payload type {message_name}")))?;
+
+    let message = DynamicMessage::decode(descriptor.clone(), bytes)
+        .map_err(|e| DbError::InvalidPayload(format!("payload decode failed: {e}")))?;
+    let options = SerializeOptions::new()
+        .use_proto_field_name(true)
+        .stringify_64_bit_integers(false);
+    let value = message
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|e| DbError::InvalidPayload(format!("payload decode failed: {e}")))?;
+
+    Ok(restore_payload_json(&descriptor, value))
+}
+
+/// Undo the protobuf JSON mapping where it differs from the payload structs:
+/// enum values lose their type prefix and are lower-cased, and unspecified
+/// enum values are dropped.
+fn restore_payload_json(
+    descriptor: &MessageDescriptor,
+    value: serde_json::Value,
+) -> serde_json::Value {
+    let serde_json::Value::Object(obj) = value else {
+        return value;
+    };
+
+    let mut out = serde_json::Map::new();
+    for (key, value) in obj {
+        let Some(field) = descriptor.get_field_by_name(&key) else {
+            out.insert(key, value);
+            continue;
+        };
+        let kind = if field.is_map() {
+            match field.kind() {
+                Kind::Message(entry) => entry
+                    .get_field_by_name("value")
+                    .map(|value_field| value_field.kind())
+                    .unwrap_or(Kind::String),
+                other => other,
+            }
+        } else {
+            field.kind()
+        };
+
+        let restored = match value {
+            serde_json::Value::Array(items) if field.is_list() => serde_json::Value::Array(
+                items
+                    .into_iter()
+                    .filter_map(|item| restore_value_by_kind(&kind, item))
+                    .collect(),
+            ),
+            serde_json::Value::Object(entries) if field.is_map() => serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .filter_map(|(k, v)| restore_value_by_kind(&kind, v).map(|v| (k, v)))
+                    .collect(),
+            ),
+            value => match restore_value_by_kind(&kind, value) {
+                Some(value) => value,
+                None => continue,
+            },
+        };
+        out.insert(key, restored);
+    }
+
+    serde_json::Value::Object(out)
+}
+
+fn restore_value_by_kind(kind: &Kind, value: serde_json::Value) -> Option<serde_json::Value> {
+    match kind {
+        Kind::Enum(enum_desc) => {
+            let name = value.as_str()?;
+            let prefix = format!("{}_", normalize_enum_candidate(enum_desc.name()));
+            let short = name.strip_prefix(&prefix).unwrap_or(name);
+            if short == "UNSPECIFIED" {
+                return None;
+            }
+            Some(serde_json::Value::String(short.to_ascii_lowercase()))
+        }
+        Kind::Message(message_desc) if !is_well_known_string_message(message_desc) => {
+            Some(restore_payload_json(message_desc, value))
+        }
+        _ => Some(value),
+    }
+}
+
 fn payload_type_url_for_event(event_type: &str) -> Option<&'static str> {
     match event_type {
         event_types::ORG_CREATED => Some("type.googleapis.com/plfm.events.v1.OrgCreatedPayload"),
@@ -582,20 +1267,45 @@ fn payload_type_url_for_event(event_type: &str) -> Option<&'static str> {
         }
         event_types::APP_CREATED => Some("type.googleapis.com/plfm.events.v1.AppCreatedPayload"),
         event_types::APP_UPDATED => Some("type.googleapis.com/plfm.events.v1.AppUpdatedPayload"),
+        event_types::APP_LABELS_UPDATED => {
+            Some("type.googleapis.com/plfm.events.v1.AppLabelsUpdatedPayload")
+        }
+        event_types::APP_DELETION_REQUESTED => {
+            Some("type.googleapis.com/plfm.events.v1.AppDeletionRequestedPayload")
+        }
         event_types::APP_DELETED => Some("type.googleapis.com/plfm.events.v1.AppDeletedPayload"),
+        event_types::APP_RESTORED => Some("type.googleapis.com/plfm.events.v1.AppRestoredPayload"),
+        event_types::APP_PURGED => Some("type.googleapis.com/plfm.events.v1.AppPurgedPayload"),
         event_types::ENV_CREATED => Some("type.googleapis.com/plfm.events.v1.EnvCreatedPayload"),
         event_types::ENV_UPDATED => Some("type.googleapis.com/plfm.events.v1.EnvUpdatedPayload"),
+        event_types::ENV_LABELS_UPDATED => {
+            Some("type.googleapis.com/plfm.events.v1.EnvLabelsUpdatedPayload")
+        }
+        event_types::ENV_DELETION_REQUESTED => {
+            Some("type.googleapis.com/plfm.events.v1.EnvDeletionRequestedPayload")
+        }
         event_types::ENV_DELETED => Some("type.googleapis.com/plfm.events.v1.EnvDeletedPayload"),
+        event_types::ENV_RESTORED => Some("type.googleapis.com/plfm.events.v1.EnvRestoredPayload"),
+        event_types::ENV_PURGED => Some("type.googleapis.com/plfm.events.v1.EnvPurgedPayload"),
         event_types::ENV_SCALE_SET => Some("type.googleapis.com/plfm.events.v1.EnvScaleSetPayload"),
         event_types::ENV_DESIRED_RELEASE_SET => {
             Some("type.googleapis.com/plfm.events.v1.EnvDesiredReleaseSetPayload")
         }
+        event_types::ENV_RESTART_REQUESTED => {
+            Some("type.googleapis.com/plfm.events.v1.EnvRestartRequestedPayload")
+        }
         event_types::ENV_IPV4_ADDON_ENABLED => {
             Some("type.googleapis.com/plfm.events.v1.EnvIpv4AddonEnabledPayload")
         }
         event_types::ENV_IPV4_ADDON_DISABLED => {
             Some("type.googleapis.com/plfm.events.v1.EnvIpv4AddonDisabledPayload")
         }
+        event_types::ENV_CONFIG_VARS_SET => {
+            Some("type.googleapis.com/plfm.events.v1.EnvConfigVarsSetPayload")
+        }
+        event_types::ENV_PROCESS_RESOURCES_SET => {
+            Some("type.googleapis.com/plfm.events.v1.EnvProcessResourcesSetPayload")
+        }
         event_types::RELEASE_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.ReleaseCreatedPayload")
         }
@@ -605,39 +1315,106 @@ fn payload_type_url_for_event(event_type: &str) -> Option<&'static str> {
         event_types::DEPLOY_STATUS_CHANGED => {
             Some("type.googleapis.com/plfm.events.v1.DeployStatusChangedPayload")
         }
+        event_types::DEPLOY_PROMOTED => {
+            Some("type.googleapis.com/plfm.events.v1.DeployPromotedPayload")
+        }
+        event_types::DEPLOY_ROLLOUT_STUCK => {
+            Some("type.googleapis.com/plfm.events.v1.DeployRolloutStuckPayload")
+        }
         event_types::ROUTE_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.RouteCreatedPayload")
         }
         event_types::ROUTE_UPDATED => {
             Some("type.googleapis.com/plfm.events.v1.RouteUpdatedPayload")
         }
+        event_types::ROUTE_LABELS_UPDATED => {
+            Some("type.googleapis.com/plfm.events.v1.RouteLabelsUpdatedPayload")
+        }
         event_types::ROUTE_DELETED => {
             Some("type.googleapis.com/plfm.events.v1.RouteDeletedPayload")
         }
+        event_types::ROUTE_RESTORED => {
+            Some("type.googleapis.com/plfm.events.v1.RouteRestoredPayload")
+        }
+        event_types::ROUTE_PURGED => Some("type.googleapis.com/plfm.events.v1.RoutePurgedPayload"),
+        event_types::ROUTE_CERT_SET => {
+            Some("type.googleapis.com/plfm.events.v1.RouteCertSetPayload")
+        }
+        event_types::ROUTE_CERT_REMOVED => {
+            Some("type.googleapis.com/plfm.events.v1.RouteCertRemovedPayload")
+        }
+        event_types::ROUTE_CERT_EXPIRING => {
+            Some("type.googleapis.com/plfm.events.v1.RouteCertExpiringPayload")
+        }
+        event_types::ROUTE_VERIFICATION_SUCCEEDED => {
+            Some("type.googleapis.com/plfm.events.v1.RouteVerificationSucceededPayload")
+        }
+        event_types::ROUTE_VERIFICATION_FAILED => {
+            Some("type.googleapis.com/plfm.events.v1.RouteVerificationFailedPayload")
+        }
         event_types::SECRET_BUNDLE_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.SecretBundleCreatedPayload")
         }
         event_types::SECRET_BUNDLE_VERSION_SET => {
             Some("type.googleapis.com/plfm.events.v1.SecretBundleVersionSetPayload")
         }
+        event_types::SECRET_BUNDLE_ARCHIVED => {
+            Some("type.googleapis.com/plfm.events.v1.SecretBundleArchivedPayload")
+        }
+        event_types::KEY_ROTATION_STARTED => {
+            Some("type.googleapis.com/plfm.events.v1.KeyRotationStartedPayload")
+        }
+        event_types::KEY_ROTATION_COMPLETED => {
+            Some("type.googleapis.com/plfm.events.v1.KeyRotationCompletedPayload")
+        }
         event_types::VOLUME_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.VolumeCreatedPayload")
         }
+        event_types::VOLUME_LABELS_UPDATED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeLabelsUpdatedPayload")
+        }
         event_types::VOLUME_DELETED => {
             Some("type.googleapis.com/plfm.events.v1.VolumeDeletedPayload")
         }
+        event_types::VOLUME_RESTORED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeRestoredPayload")
+        }
+        event_types::VOLUME_PURGED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumePurgedPayload")
+        }
+        event_types::VOLUME_RESIZED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeResizedPayload")
+        }
+        event_types::VOLUME_RESIZE_COMPLETED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeResizeCompletedPayload")
+        }
+        event_types::VOLUME_RESIZE_FAILED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeResizeFailedPayload")
+        }
+        event_types::VOLUME_BACKUP_POLICY_SET => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeBackupPolicySetPayload")
+        }
         event_types::VOLUME_ATTACHMENT_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.VolumeAttachmentCreatedPayload")
         }
         event_types::VOLUME_ATTACHMENT_DELETED => {
             Some("type.googleapis.com/plfm.events.v1.VolumeAttachmentDeletedPayload")
         }
+        event_types::VOLUME_ATTACHED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeAttachedPayload")
+        }
+        event_types::VOLUME_DETACHED => {
+            Some("type.googleapis.com/plfm.events.v1.VolumeDetachedPayload")
+        }
         event_types::SNAPSHOT_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.SnapshotCreatedPayload")
         }
         event_types::SNAPSHOT_STATUS_CHANGED => {
             Some("type.googleapis.com/plfm.events.v1.SnapshotStatusChangedPayload")
         }
+        event_types::SNAPSHOT_DELETED => {
+            Some("type.googleapis.com/plfm.events.v1.SnapshotDeletedPayload")
+        }
         event_types::RESTORE_JOB_CREATED => {
             Some("type.googleapis.com/plfm.events.v1.RestoreJobCreatedPayload")
         }
@@ -653,6 +1430,9 @@ fn payload_type_url_for_event(event_type: &str) -> Option<&'static str> {
         event_types::INSTANCE_STATUS_CHANGED => {
             Some("type.googleapis.com/plfm.events.v1.InstanceStatusChangedPayload")
         }
+        event_types::INSTANCE_CONFIG_UPDATED => {
+            Some("type.googleapis.com/plfm.events.v1.InstanceConfigUpdatedPayload")
+        }
         event_types::NODE_ENROLLED => {
             Some("type.googleapis.com/plfm.events.v1.NodeEnrolledPayload")
         }
@@ -662,6 +1442,15 @@ fn payload_type_url_for_event(event_type: &str) -> Option<&'static str> {
         event_types::NODE_CAPACITY_UPDATED => {
             Some("type.googleapis.com/plfm.events.v1.NodeCapacityUpdatedPayload")
         }
+        event_types::NODE_ENROLLMENT_REQUESTED => {
+            Some("type.googleapis.com/plfm.events.v1.NodeEnrollmentRequestedPayload")
+        }
+        event_types::NODE_ENROLLMENT_APPROVED => {
+            Some("type.googleapis.com/plfm.events.v1.NodeEnrollmentApprovedPayload")
+        }
+        event_types::NODE_ENROLLMENT_DENIED => {
+            Some("type.googleapis.com/plfm.events.v1.NodeEnrollmentDeniedPayload")
+        }
         event_types::EXEC_SESSION_GRANTED => {
             Some("type.googleapis.com/plfm.events.v1.ExecSessionGrantedPayload")
         }
@@ -671,6 +1460,10 @@ fn payload_type_url_for_event(event_type: &str) -> Option<&'static str> {
         event_types::EXEC_SESSION_ENDED => {
             Some("type.googleapis.com/plfm.events.v1.ExecSessionEndedPayload")
         }
+        event_types::ALERT_FIRED => Some("type.googleapis.com/plfm.events.v1.AlertFiredPayload"),
+        event_types::ALERT_RESOLVED => {
+            Some("type.googleapis.com/plfm.events.v1.AlertResolvedPayload")
+        }
         _ => None,
     }
 }
@@ -1044,4 +1837,142 @@ mod tests {
         assert_eq!(decoded.org_id, "org_123");
         assert_eq!(decoded.name, "Acme");
     }
+
+    fn instance_event(payload: serde_json::Value) -> AppendEvent {
+        let mut event = AppendEvent {
+            aggregate_type: AggregateType::Instance,
+            aggregate_id: "inst_123".to_string(),
+            aggregate_seq: 2,
+            event_type: event_types::INSTANCE_DESIRED_STATE_CHANGED.to_string(),
+            event_version: 1,
+            actor_type: ActorType::System,
+            actor_id: "scheduler".to_string(),
+            org_id: None,
+            request_id: "req_789".to_string(),
+            idempotency_key: None,
+            app_id: None,
+            env_id: None,
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: None,
+        };
+        populate_protobuf_payload(&mut event).expect("payload bytes");
+        event
+    }
+
+    #[test]
+    fn test_decode_payload_json_restores_enum_values() {
+        let payload = serde_json::json!({
+            "instance_id": "inst_123",
+            "org_id": "org_123",
+            "env_id": "env_123",
+            "desired_state": "draining",
+            "drain_grace_seconds": 30
+        });
+        let event = instance_event(payload.clone());
+
+        let decoded = decode_payload_json(
+            event.payload_type_url.as_deref().unwrap(),
+            event.payload_bytes.as_deref().unwrap(),
+        )
+        .expect("decode");
+        assert_eq!(decoded, payload);
+    }
+
+    #[test]
+    fn test_stored_json_payload_respects_encoding() {
+        let event = instance_event(serde_json::json!({
+            "instance_id": "inst_123",
+            "org_id": "org_123",
+            "env_id": "env_123",
+            "desired_state": "stopped",
+            "reason": null
+        }));
+
+        assert!(stored_json_payload(&event, PayloadEncoding::Json).is_some());
+        assert!(stored_json_payload(&event, PayloadEncoding::Protobuf).is_none());
+    }
+
+    #[test]
+    fn test_stored_json_payload_keeps_json_for_lossy_or_ineligible_events() {
+        // Extra fields are not in the protobuf schema and would be lost.
+        let lossy = instance_event(serde_json::json!({
+            "instance_id": "inst_123",
+            "org_id": "org_123",
+            "env_id": "env_123",
+            "desired_state": "running",
+            "note": "not in schema"
+        }));
+        assert!(stored_json_payload(&lossy, PayloadEncoding::Protobuf).is_some());
+
+        let mut route = instance_event(serde_json::json!({}));
+        route.event_type = event_types::ROUTE_CREATED.to_string();
+        assert!(stored_json_payload(&route, PayloadEncoding::Protobuf).is_some());
+    }
+
+    #[test]
+    fn test_event_row_to_envelope() {
+        let event = instance_event(serde_json::json!({
+            "instance_id": "inst_123",
+            "org_id": "org_123",
+            "env_id": "env_123",
+            "desired_state": "running"
+        }));
+        let row = EventRow {
+            event_id: 42,
+            occurred_at: Utc::now(),
+            aggregate_type: "instance".to_string(),
+            aggregate_id: "inst_123".to_string(),
+            aggregate_seq: 2,
+            event_type: event.event_type.clone(),
+            event_version: 1,
+            actor_type: "system".to_string(),
+            actor_id: "scheduler".to_string(),
+            org_id: Some("org_123".to_string()),
+            request_id: "req_789".to_string(),
+            idempotency_key: None,
+            app_id: None,
+            env_id: Some("env_123".to_string()),
+            correlation_id: Some("corr_1".to_string()),
+            causation_id: Some(41),
+            payload: event.payload.clone(),
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: Some(serde_json::json!({"region": "eu"})),
+        };
+
+        let bytes = row.encode_envelope().expect("encode");
+        let envelope = EventEnvelope::decode(bytes.as_slice()).expect("decode");
+        assert_eq!(envelope.sequence, 42);
+        assert_eq!(envelope.aggregate_seq, 2);
+        assert_eq!(envelope.aggregate_type, ProtoAggregateType::Instance as i32);
+        assert_eq!(envelope.actor_type, ProtoActorType::System as i32);
+        assert_eq!(envelope.causation_id, "41");
+        assert_eq!(envelope.tags.get("region").map(String::as_str), Some("eu"));
+        assert_eq!(envelope.payload, event.payload_bytes.unwrap());
+    }
+
+    #[test]
+    fn event_filter_splits_exact_and_prefix_types() {
+        let filter = |t: &str| EventFilter {
+            event_type: Some(t.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(EventFilter::default().event_type_match(), (None, None));
+        assert_eq!(
+            filter("deploy.created").event_type_match(),
+            (Some("deploy.created".to_string()), None)
+        );
+        assert_eq!(
+            filter("env.desired_*").event_type_match(),
+            (None, Some("env.desired\\_%".to_string()))
+        );
+    }
 }
@@ -3,6 +3,12 @@
 //! Idempotency records store command responses for deduplication.
 //! If a command is retried with the same idempotency key, we return the stored response.
 //! If the key is reused with a different request, we return 409 Conflict.
+//!
+//! Records expire after a configurable TTL (`expires_at`); expired records are
+//! treated as absent. Short-lived locks in `idempotency_locks` serialize
+//! concurrent requests that share a key.
+
+use std::time::Duration;
 
 use sqlx::{postgres::PgPool, postgres::PgRow, Row};
 
@@ -53,6 +59,8 @@ pub struct StoreIdempotencyRecord {
     pub request_hash: String,
     pub response_status_code: i32,
     pub response_body: Option<serde_json::Value>,
+    /// How long the record is replayable.
+    pub ttl: Duration,
 }
 
 impl IdempotencyStore {
@@ -86,6 +94,7 @@ impl IdempotencyStore {
               AND actor_id = $2
               AND endpoint_name = $3
               AND idempotency_key = $4
+              AND expires_at > now()
             "#,
         )
         .bind(org_id)
@@ -110,7 +119,8 @@ impl IdempotencyStore {
 
     /// Store a new idempotency record.
     ///
-    /// This should be called after successfully processing a request.
+    /// This should be called after successfully processing a request. An
+    /// expired record under the same key is replaced.
     pub async fn store(&self, record: StoreIdempotencyRecord) -> Result<(), DbError> {
         sqlx::query(
             r#"
@@ -121,11 +131,19 @@ impl IdempotencyStore {
                 idempotency_key,
                 request_hash,
                 response_status_code,
-                response_body
+                response_body,
+                created_at,
+                expires_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, now(), now() + make_interval(secs => $8))
             ON CONFLICT (org_id, actor_id, endpoint_name, idempotency_key)
-            DO NOTHING
+            DO UPDATE SET
+                request_hash = EXCLUDED.request_hash,
+                response_status_code = EXCLUDED.response_status_code,
+                response_body = EXCLUDED.response_body,
+                created_at = EXCLUDED.created_at,
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_records.expires_at <= now()
             "#,
         )
         .bind(record.org_id)
@@ -135,6 +153,7 @@ impl IdempotencyStore {
         .bind(record.request_hash)
         .bind(record.response_status_code)
         .bind(record.response_body)
+        .bind(record.ttl.as_secs_f64())
         .execute(&self.pool)
         .await
         .map_err(DbError::Query)?;
@@ -142,6 +161,50 @@ impl IdempotencyStore {
         Ok(())
     }
 
+    /// Try to take the in-flight lock for `lock_key`.
+    ///
+    /// Returns `true` if the lock was acquired, either fresh or by taking over
+    /// a lock whose holder let it lapse (e.g. crashed mid-request).
+    pub async fn try_lock(
+        &self,
+        lock_key: &str,
+        holder: &str,
+        lock_ttl: Duration,
+    ) -> Result<bool, DbError> {
+        let acquired = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO idempotency_locks (lock_key, holder, locked_until)
+            VALUES ($1, $2, now() + make_interval(secs => $3))
+            ON CONFLICT (lock_key) DO UPDATE SET
+                holder = EXCLUDED.holder,
+                locked_until = EXCLUDED.locked_until,
+                created_at = now()
+            WHERE idempotency_locks.locked_until <= now()
+            RETURNING lock_key
+            "#,
+        )
+        .bind(lock_key)
+        .bind(holder)
+        .bind(lock_ttl.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(acquired.is_some())
+    }
+
+    /// Release an in-flight lock held by `holder`.
+    pub async fn unlock(&self, lock_key: &str, holder: &str) -> Result<(), DbError> {
+        sqlx::query("DELETE FROM idempotency_locks WHERE lock_key = $1 AND holder = $2")
+            .bind(lock_key)
+            .bind(holder)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
     /// Delete expired idempotency records.
     ///
     /// Records older than the specified duration are deleted.
@@ -0,0 +1,123 @@
+//! Leases on background work shared by control-plane replicas.
+//!
+//! A lease gives one holder exclusive ownership of a named unit of work until
+//! `leased_until`. Holders renew well before expiry; a lease whose holder
+//! stopped renewing can be taken over by anyone.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgPool, postgres::PgRow, Row};
+
+use super::DbError;
+
+/// A lease row.
+#[derive(Debug, Clone)]
+pub struct Lease {
+    pub lease_name: String,
+    pub holder: String,
+    pub leased_until: DateTime<Utc>,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for Lease {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            lease_name: row.try_get("lease_name")?,
+            holder: row.try_get("holder")?,
+            leased_until: row.try_get("leased_until")?,
+            acquired_at: row.try_get("acquired_at")?,
+        })
+    }
+}
+
+/// Store for worker leases.
+#[derive(Clone)]
+pub struct LeaseStore {
+    pool: PgPool,
+}
+
+impl LeaseStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Acquire or renew each of `lease_names` for `holder`.
+    ///
+    /// Returns the names `holder` now holds: leases it already held (renewed),
+    /// free leases, and leases whose previous holder let them lapse.
+    pub async fn acquire(
+        &self,
+        lease_names: &[String],
+        holder: &str,
+        ttl: Duration,
+    ) -> Result<Vec<String>, DbError> {
+        let held = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO worker_leases (lease_name, holder, leased_until, acquired_at)
+            SELECT name, $2, now() + make_interval(secs => $3), now()
+            FROM unnest($1::TEXT[]) AS name
+            ON CONFLICT (lease_name) DO UPDATE SET
+                holder = EXCLUDED.holder,
+                leased_until = EXCLUDED.leased_until,
+                acquired_at = CASE
+                    WHEN worker_leases.holder = EXCLUDED.holder THEN worker_leases.acquired_at
+                    ELSE now()
+                END
+            WHERE worker_leases.holder = EXCLUDED.holder
+               OR worker_leases.leased_until <= now()
+            RETURNING lease_name
+            "#,
+        )
+        .bind(lease_names)
+        .bind(holder)
+        .bind(ttl.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(held)
+    }
+
+    /// Release every lease held by `holder` so other replicas can take over
+    /// without waiting for expiry.
+    pub async fn release_all(&self, holder: &str) -> Result<(), DbError> {
+        sqlx::query("DELETE FROM worker_leases WHERE holder = $1")
+            .bind(holder)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    /// Release one lease if `holder` holds it.
+    pub async fn release(&self, lease_name: &str, holder: &str) -> Result<(), DbError> {
+        sqlx::query("DELETE FROM worker_leases WHERE lease_name = $1 AND holder = $2")
+            .bind(lease_name)
+            .bind(holder)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    /// Unexpired leases whose name starts with `prefix`.
+    pub async fn list_active(&self, prefix: &str) -> Result<Vec<Lease>, DbError> {
+        let leases = sqlx::query_as::<_, Lease>(
+            r#"
+            SELECT lease_name, holder, leased_until, acquired_at
+            FROM worker_leases
+            WHERE starts_with(lease_name, $1) AND leased_until > now()
+            ORDER BY lease_name
+            "#,
+        )
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(leases)
+    }
+}
@@ -5,23 +5,43 @@
 //! - Event store operations (append, query)
 //! - Projection checkpoint management
 //! - Idempotency record storage
+//! - Worker leases shared across replicas
 //!
-//! The database layer uses SQLx with Postgres.
+//! The database layer uses SQLx with Postgres. The event log, checkpoints,
+//! and idempotency records can also run on SQLite (`sqlite` feature) for
+//! single-node deployments; see [`backend`].
 
+mod backend;
 mod error;
+mod event_chain;
 mod event_store;
 mod idempotency;
+mod leases;
 mod projections;
 pub mod quotas;
+pub mod schema;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use backend::{CheckpointLog, DatabaseKind, EventLog, IdempotencyLog, Storage};
 
 pub use error::DbError;
-pub use event_store::{AppendEvent, EventRow, EventStore};
+pub use event_chain::{merkle_root, HashedEvent, HASHED_EVENT_COLUMNS};
+pub use event_store::{
+    AppendEvent, EventFilter, EventRow, EventStore, PayloadEncoding, EVENTS_NOTIFY_CHANNEL,
+};
 #[allow(unused_imports)]
 pub use idempotency::{
     IdempotencyCheck, IdempotencyRecord, IdempotencyStore, StoreIdempotencyRecord,
 };
+pub use leases::{Lease, LeaseStore};
 #[allow(unused_imports)]
-pub use projections::{ProjectionCheckpoint, ProjectionStore};
+pub use projections::{
+    projection_lease_name, ProjectionCheckpoint, ProjectionStatusRow, ProjectionStore,
+    PROJECTION_LEASE_PREFIX,
+};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
 
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use std::time::Duration;
@@ -131,46 +151,26 @@ impl Database {
         Ok(())
     }
 
-    /// Run pending migrations.
+    /// Apply pending migrations embedded in the binary (see [`schema`]).
     ///
-    /// Note: In production, migrations should be run via a separate migration tool
-    /// or as part of deployment. This method uses runtime migration loading.
+    /// Dev mode runs this at startup; production runs `control-plane migrate`.
     pub async fn run_migrations(&self) -> Result<(), DbError> {
-        info!("Running database migrations");
-
-        let candidates = vec![
-            std::path::PathBuf::from("./migrations"),
-            std::path::PathBuf::from("services/control-plane/migrations"),
-            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("migrations"),
-        ];
-        let mut last_error: Option<sqlx::migrate::MigrateError> = None;
-
-        for dir in &candidates {
-            match sqlx::migrate::Migrator::new(dir.clone()).await {
-                Ok(migrator) => {
-                    info!(migrations_dir = %dir.display(), "Loaded migrations");
-                    migrator.run(&self.pool).await.map_err(DbError::Migration)?;
-                    info!("Database migrations complete");
-                    return Ok(());
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                }
-            }
-        }
+        info!(
+            schema_version = schema::schema_version(),
+            "Running database migrations"
+        );
+        schema::migrator()
+            .await?
+            .run(&self.pool)
+            .await
+            .map_err(DbError::Migration)?;
+        info!("Database migrations complete");
+        Ok(())
+    }
 
-        let tried = candidates
-            .iter()
-            .map(|dir| dir.display().to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        Err(DbError::MigrationDirNotFound {
-            tried,
-            last_error: last_error
-                .map(|e| e.to_string())
-                .unwrap_or_else(|| "unknown error".to_string()),
-        })
+    /// Compare the database schema with the one this binary was built for.
+    pub async fn schema_status(&self) -> Result<schema::SchemaStatus, DbError> {
+        schema::status(&self.pool).await
     }
 
     /// Get an event store handle.
@@ -183,6 +183,11 @@ impl Database {
         ProjectionStore::new(self.pool.clone())
     }
 
+    /// Get a worker lease store handle.
+    pub fn lease_store(&self) -> LeaseStore {
+        LeaseStore::new(self.pool.clone())
+    }
+
     /// Get an idempotency store handle.
     pub fn idempotency_store(&self) -> IdempotencyStore {
         IdempotencyStore::new(self.pool.clone())
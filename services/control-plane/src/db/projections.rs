@@ -9,6 +9,14 @@ use tokio::time::{sleep, Duration, Instant};
 
 use super::DbError;
 
+/// Prefix of the worker lease that grants ownership of a projection.
+pub const PROJECTION_LEASE_PREFIX: &str = "projection:";
+
+/// Name of the worker lease for `projection_name`.
+pub fn projection_lease_name(projection_name: &str) -> String {
+    format!("{PROJECTION_LEASE_PREFIX}{projection_name}")
+}
+
 /// A projection checkpoint record.
 #[derive(Debug, Clone)]
 pub struct ProjectionCheckpoint {
@@ -27,6 +35,32 @@ impl<'r> sqlx::FromRow<'r, PgRow> for ProjectionCheckpoint {
     }
 }
 
+/// Checkpoint plus operator-facing state, for the admin API.
+#[derive(Debug, Clone)]
+pub struct ProjectionStatusRow {
+    pub projection_name: String,
+    pub last_applied_event_id: i64,
+    pub updated_at: DateTime<Utc>,
+    pub paused: bool,
+    pub last_error: Option<String>,
+    pub last_error_event_id: Option<i64>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for ProjectionStatusRow {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            projection_name: row.try_get("projection_name")?,
+            last_applied_event_id: row.try_get("last_applied_event_id")?,
+            updated_at: row.try_get("updated_at")?,
+            paused: row.try_get("paused")?,
+            last_error: row.try_get("last_error")?,
+            last_error_event_id: row.try_get("last_error_event_id")?,
+            last_error_at: row.try_get("last_error_at")?,
+        })
+    }
+}
+
 /// Store for managing projection checkpoints.
 #[derive(Clone)]
 pub struct ProjectionStore {
@@ -104,27 +138,41 @@ impl ProjectionStore {
 
     /// Update checkpoint atomically with view updates.
     ///
-    /// This is used when applying events within a transaction.
+    /// This is used when applying events within a transaction. With
+    /// `lease_holder`, the update only happens while that holder owns the
+    /// projection's lease and the checkpoint moves forward; `false` means
+    /// another replica owns the projection now and the transaction must be
+    /// rolled back.
     pub async fn update_checkpoint_in_tx(
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         projection_name: &str,
         last_applied_event_id: i64,
-    ) -> Result<(), DbError> {
-        sqlx::query(
+        lease_holder: Option<&str>,
+    ) -> Result<bool, DbError> {
+        let updated = sqlx::query_scalar::<_, String>(
             r#"
             INSERT INTO projection_checkpoints (projection_name, last_applied_event_id, updated_at)
             VALUES ($1, $2, now())
             ON CONFLICT (projection_name)
             DO UPDATE SET last_applied_event_id = EXCLUDED.last_applied_event_id, updated_at = now()
+            WHERE $3::TEXT IS NULL
+               OR (projection_checkpoints.last_applied_event_id < EXCLUDED.last_applied_event_id
+                   AND EXISTS (
+                       SELECT 1 FROM worker_leases l
+                       WHERE l.lease_name = $4 AND l.holder = $3 AND l.leased_until > now()
+                   ))
+            RETURNING projection_name
             "#,
         )
         .bind(projection_name)
         .bind(last_applied_event_id)
-        .execute(&mut **tx)
+        .bind(lease_holder)
+        .bind(projection_lease_name(projection_name))
+        .fetch_optional(&mut **tx)
         .await
         .map_err(DbError::Query)?;
 
-        Ok(())
+        Ok(updated.is_some())
     }
 
     /// Get all projection checkpoints.
@@ -162,6 +210,146 @@ impl ProjectionStore {
         Ok(())
     }
 
+    /// Rewind a paused projection to replay the log from the start, clearing
+    /// its last error. Returns `false` (and changes nothing) if the
+    /// projection is not paused, so a running worker never sees the rewind.
+    pub async fn rewind_paused(&self, projection_name: &str) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE projection_checkpoints
+            SET last_applied_event_id = 0, updated_at = now(),
+                last_error = NULL, last_error_event_id = NULL, last_error_at = NULL
+            WHERE projection_name = $1 AND paused
+            "#,
+        )
+        .bind(projection_name)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get all projection checkpoints with pause and error state.
+    pub async fn list_status(&self) -> Result<Vec<ProjectionStatusRow>, DbError> {
+        let rows = sqlx::query_as::<_, ProjectionStatusRow>(
+            r#"
+            SELECT projection_name, last_applied_event_id, updated_at,
+                   paused, last_error, last_error_event_id, last_error_at
+            FROM projection_checkpoints
+            ORDER BY projection_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
+    /// Names of projections an operator has paused.
+    pub async fn paused_projections(&self) -> Result<Vec<String>, DbError> {
+        let names: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT projection_name FROM projection_checkpoints WHERE paused
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(names.into_iter().map(|(name,)| name).collect())
+    }
+
+    /// Pause or resume a projection. Wakes the projection worker so a resumed
+    /// projection starts catching up immediately.
+    pub async fn set_paused(&self, projection_name: &str, paused: bool) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            INSERT INTO projection_checkpoints (projection_name, last_applied_event_id, updated_at, paused)
+            VALUES ($1, 0, now(), $2)
+            ON CONFLICT (projection_name) DO UPDATE SET paused = EXCLUDED.paused
+            "#,
+        )
+        .bind(projection_name)
+        .bind(paused)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        sqlx::query("SELECT pg_notify($1, '')")
+            .bind(super::EVENTS_NOTIFY_CHANNEL)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    /// Record a failure to apply `event_id`. Written outside the failed
+    /// transaction so it survives the rollback.
+    pub async fn record_error(
+        &self,
+        projection_name: &str,
+        event_id: i64,
+        error: &str,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            UPDATE projection_checkpoints
+            SET last_error = $2, last_error_event_id = $3, last_error_at = now()
+            WHERE projection_name = $1
+            "#,
+        )
+        .bind(projection_name)
+        .bind(error)
+        .bind(event_id)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    /// Count, per projection, the events it handles that it has not applied
+    /// yet. `coverage` lists `(projection_name, event_type)` pairs.
+    pub async fn pending_counts(
+        &self,
+        coverage: &[(&str, &str)],
+    ) -> Result<Vec<(String, i64)>, DbError> {
+        let names: Vec<&str> = coverage.iter().map(|(name, _)| *name).collect();
+        let event_types: Vec<&str> = coverage.iter().map(|(_, ty)| *ty).collect();
+
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT h.projection_name, COUNT(e.event_id)
+            FROM unnest($1::TEXT[], $2::TEXT[]) AS h(projection_name, event_type)
+            LEFT JOIN projection_checkpoints c ON c.projection_name = h.projection_name
+            JOIN events e
+              ON e.event_type = h.event_type
+             AND e.event_id > COALESCE(c.last_applied_event_id, 0)
+            GROUP BY h.projection_name
+            "#,
+        )
+        .bind(&names)
+        .bind(&event_types)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(rows)
+    }
+
+    /// Highest event ID in the log (0 when empty).
+    pub async fn max_event_id(&self) -> Result<i64, DbError> {
+        let (max,): (i64,) = sqlx::query_as("SELECT COALESCE(MAX(event_id), 0) FROM events")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(max)
+    }
+
     /// Calculate lag for all projections.
     ///
     /// Returns projection name and lag (max_event_id - last_applied_event_id).
@@ -229,6 +417,75 @@ impl ProjectionStore {
             sleep(Duration::from_millis(25)).await;
         }
     }
+
+    /// Find a projection that has not yet applied every event it handles up
+    /// to and including `min_event_id`.
+    ///
+    /// `coverage` lists `(projection_name, event_type)` pairs. A projection
+    /// only advances its checkpoint when it applies an event, so comparing the
+    /// checkpoint against `min_event_id` directly would wait forever on views
+    /// that never see the written event type; only events the projection
+    /// handles are considered. Paused projections are skipped.
+    pub async fn find_lagging(
+        &self,
+        min_event_id: i64,
+        coverage: &[(&str, &str)],
+    ) -> Result<Option<(String, i64)>, DbError> {
+        let names: Vec<&str> = coverage.iter().map(|(name, _)| *name).collect();
+        let event_types: Vec<&str> = coverage.iter().map(|(_, ty)| *ty).collect();
+
+        let row: Option<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT h.projection_name, COALESCE(c.last_applied_event_id, 0)
+            FROM unnest($2::TEXT[], $3::TEXT[]) AS h(projection_name, event_type)
+            LEFT JOIN projection_checkpoints c ON c.projection_name = h.projection_name
+            WHERE NOT COALESCE(c.paused, false)
+              AND EXISTS (
+                SELECT 1 FROM events e
+                WHERE e.event_type = h.event_type
+                  AND e.event_id > COALESCE(c.last_applied_event_id, 0)
+                  AND e.event_id <= $1
+            )
+            LIMIT 1
+            "#,
+        )
+        .bind(min_event_id)
+        .bind(&names)
+        .bind(&event_types)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(row)
+    }
+
+    /// Wait until every projection in `coverage` has applied the events it
+    /// handles up to `min_event_id`.
+    pub async fn wait_until_caught_up(
+        &self,
+        min_event_id: i64,
+        coverage: &[(&str, &str)],
+        timeout: Duration,
+    ) -> Result<(), DbError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let Some((projection_name, actual)) = self.find_lagging(min_event_id, coverage).await?
+            else {
+                return Ok(());
+            };
+
+            if Instant::now() >= deadline {
+                return Err(DbError::ProjectionTimeout {
+                    projection_name,
+                    expected: min_event_id,
+                    actual,
+                });
+            }
+
+            sleep(Duration::from_millis(25)).await;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -13,9 +13,28 @@ pub enum QuotaDimension {
     MaxVolumes,
     MaxTotalVolumeBytes,
     MaxVolumeAttachments,
+    MaxTotalEphemeralDiskBytes,
 }
 
 impl QuotaDimension {
+    pub const ALL: [Self; 10] = [
+        Self::MaxInstances,
+        Self::MaxTotalMemoryBytes,
+        Self::MaxEnvs,
+        Self::MaxApps,
+        Self::MaxRoutes,
+        Self::MaxIpv4Allocations,
+        Self::MaxVolumes,
+        Self::MaxTotalVolumeBytes,
+        Self::MaxVolumeAttachments,
+        Self::MaxTotalEphemeralDiskBytes,
+    ];
+
+    /// The dimension named `name` (as returned by `as_str`).
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.as_str() == name)
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::MaxInstances => "max_instances",
@@ -27,6 +46,7 @@ impl QuotaDimension {
             Self::MaxVolumes => "max_volumes",
             Self::MaxTotalVolumeBytes => "max_total_volume_bytes",
             Self::MaxVolumeAttachments => "max_volume_attachments",
+            Self::MaxTotalEphemeralDiskBytes => "max_total_ephemeral_disk_bytes",
         }
     }
 
@@ -41,6 +61,7 @@ impl QuotaDimension {
             Self::MaxVolumes => 20,
             Self::MaxTotalVolumeBytes => 500 * 1024 * 1024 * 1024,
             Self::MaxVolumeAttachments => 50,
+            Self::MaxTotalEphemeralDiskBytes => 200 * 1024 * 1024 * 1024,
         }
     }
 }
@@ -69,6 +90,57 @@ pub async fn get_effective_limit(
     Ok(override_limit.unwrap_or_else(|| dimension.default_limit()))
 }
 
+/// Operator overrides for an org, by dimension name.
+pub async fn list_overrides(
+    pool: &PgPool,
+    org_id: &OrgId,
+) -> Result<Vec<(String, i64)>, sqlx::Error> {
+    sqlx::query_as("SELECT dimension, limit_value FROM org_quotas WHERE org_id = $1")
+        .bind(org_id.to_string())
+        .fetch_all(pool)
+        .await
+}
+
+/// Set an operator override, replacing the default limit.
+pub async fn set_override(
+    pool: &PgPool,
+    org_id: &OrgId,
+    dimension: QuotaDimension,
+    limit: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO org_quotas (org_id, dimension, limit_value, updated_at)
+        VALUES ($1, $2, $3, now())
+        ON CONFLICT (org_id, dimension)
+        DO UPDATE SET limit_value = EXCLUDED.limit_value, updated_at = now()
+        "#,
+    )
+    .bind(org_id.to_string())
+    .bind(dimension.as_str())
+    .bind(limit)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Remove an operator override, restoring the default limit. Returns
+/// whether there was one.
+pub async fn clear_override(
+    pool: &PgPool,
+    org_id: &OrgId,
+    dimension: QuotaDimension,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM org_quotas WHERE org_id = $1 AND dimension = $2")
+        .bind(org_id.to_string())
+        .bind(dimension.as_str())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn get_current_usage(
     pool: &PgPool,
     org_id: &OrgId,
@@ -107,6 +179,18 @@ pub async fn get_current_usage(
             "SELECT COUNT(*)::BIGINT FROM volume_attachments_view 
              WHERE org_id = $1 AND NOT is_deleted"
         }
+        // Reserved by the env scale, with process resource overrides taking
+        // precedence; unset sizes count at the 4 GiB default.
+        QuotaDimension::MaxTotalEphemeralDiskBytes => {
+            "SELECT COALESCE(SUM(s.desired_replicas::BIGINT
+                                 * COALESCE(pr.ephemeral_disk_bytes, s.ephemeral_disk_bytes,
+                                            4294967296)), 0)::BIGINT
+             FROM env_scale_view s
+             JOIN envs_view e ON e.env_id = s.env_id
+             LEFT JOIN env_process_resources_view pr
+                 ON pr.env_id = s.env_id AND pr.process_type = s.process_type
+             WHERE s.org_id = $1 AND NOT e.is_deleted"
+        }
     };
 
     let usage: i64 = sqlx::query_scalar(query)
@@ -149,6 +233,18 @@ mod tests {
             QuotaDimension::MaxIpv4Allocations.as_str(),
             "max_ipv4_allocations"
         );
+        assert_eq!(
+            QuotaDimension::MaxTotalEphemeralDiskBytes.as_str(),
+            "max_total_ephemeral_disk_bytes"
+        );
+    }
+
+    #[test]
+    fn test_dimension_parse_round_trips() {
+        for dimension in QuotaDimension::ALL {
+            assert_eq!(QuotaDimension::parse(dimension.as_str()), Some(dimension));
+        }
+        assert_eq!(QuotaDimension::parse("max_widgets"), None);
     }
 
     #[test]
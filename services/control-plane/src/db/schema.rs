@@ -0,0 +1,313 @@
+//! Schema migrations and the startup schema version handshake.
+//!
+//! Migrations are embedded in the binary at build time (see `build.rs`), so
+//! `control-plane migrate` applies exactly the schema the binary was built
+//! against. The binary's schema version is its newest migration.
+//!
+//! Migrations are expand-only by default: a binary keeps running against a
+//! database migrated by a newer binary. A migration that breaks older
+//! binaries (dropping or renaming what they use) raises
+//! `schema_compat.min_compatible_version` to its own version; binaries with
+//! an older schema version then refuse to start.
+//!
+//! See: docs/ops/08-schema-migrations.md
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use futures_core::future::BoxFuture;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::{Migration, MigrationSource, MigrationType, Migrator};
+use sqlx::PgPool;
+
+use super::DbError;
+
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+/// The migrations embedded in this binary, as a sqlx migration source.
+#[derive(Debug)]
+struct Embedded;
+
+impl MigrationSource<'static> for Embedded {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async { Ok(embedded()) })
+    }
+}
+
+fn embedded() -> Vec<Migration> {
+    EMBEDDED_MIGRATIONS
+        .iter()
+        .map(|&(version, description, sql)| {
+            Migration::new(
+                version,
+                Cow::Borrowed(description),
+                MigrationType::Simple,
+                Cow::Borrowed(sql),
+                sql.starts_with("-- no-transaction"),
+            )
+        })
+        .collect()
+}
+
+/// A migrator for the embedded migrations.
+pub async fn migrator() -> Result<Migrator, DbError> {
+    Migrator::new(Embedded).await.map_err(DbError::Migration)
+}
+
+/// The schema version this binary was built against.
+pub fn schema_version() -> i64 {
+    EMBEDDED_MIGRATIONS
+        .last()
+        .map_or(0, |&(version, _, _)| version)
+}
+
+/// A row of `_sqlx_migrations`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub success: bool,
+    pub checksum: Vec<u8>,
+}
+
+/// How the database schema relates to this binary's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaStatus {
+    /// Exactly this binary's migrations are applied.
+    Current,
+    /// Newer migrations are applied, none of which break this binary.
+    Newer { versions: Vec<i64> },
+    /// Some of this binary's migrations are not applied yet.
+    Behind { pending: Vec<i64> },
+    /// A migration applied by a newer binary breaks this one.
+    Incompatible { min_compatible_version: i64 },
+    /// A migration failed partway and must be repaired by hand.
+    Failed { version: i64 },
+    /// An applied migration differs from the one this binary ships.
+    Modified { version: i64 },
+}
+
+impl SchemaStatus {
+    /// Whether this binary can serve against the schema.
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, SchemaStatus::Current | SchemaStatus::Newer { .. })
+    }
+}
+
+impl fmt::Display for SchemaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = schema_version();
+        match self {
+            SchemaStatus::Current => write!(f, "schema is at version {version}"),
+            SchemaStatus::Newer { versions } => write!(
+                f,
+                "schema has {} migration(s) newer than this binary (version {version}), all compatible",
+                versions.len()
+            ),
+            SchemaStatus::Behind { pending } => write!(
+                f,
+                "{} migration(s) not applied, starting at {}; run `control-plane migrate`",
+                pending.len(),
+                pending.first().copied().unwrap_or_default()
+            ),
+            SchemaStatus::Incompatible {
+                min_compatible_version,
+            } => write!(
+                f,
+                "schema requires a binary at schema version {min_compatible_version} or later; this binary is at {version}"
+            ),
+            SchemaStatus::Failed { version } => write!(
+                f,
+                "migration {version} failed; repair the schema and delete its _sqlx_migrations row before retrying"
+            ),
+            SchemaStatus::Modified { version } => write!(
+                f,
+                "migration {version} was changed after it was applied"
+            ),
+        }
+    }
+}
+
+/// Compare applied migrations with the ones a binary ships, given as
+/// `(version, checksum)`.
+fn evaluate(
+    known: &[(i64, &[u8])],
+    applied: &[AppliedMigration],
+    min_compatible_version: i64,
+) -> SchemaStatus {
+    if let Some(failed) = applied.iter().find(|migration| !migration.success) {
+        return SchemaStatus::Failed {
+            version: failed.version,
+        };
+    }
+
+    let applied_checksums: HashMap<i64, &[u8]> = applied
+        .iter()
+        .map(|migration| (migration.version, migration.checksum.as_slice()))
+        .collect();
+    if let Some(&(version, _)) = known.iter().find(|(version, checksum)| {
+        applied_checksums
+            .get(version)
+            .is_some_and(|applied| applied != checksum)
+    }) {
+        return SchemaStatus::Modified { version };
+    }
+
+    let binary_version = known.last().map_or(0, |&(version, _)| version);
+    if min_compatible_version > binary_version {
+        return SchemaStatus::Incompatible {
+            min_compatible_version,
+        };
+    }
+
+    let pending: Vec<i64> = known
+        .iter()
+        .map(|&(version, _)| version)
+        .filter(|version| !applied_checksums.contains_key(version))
+        .collect();
+    if !pending.is_empty() {
+        return SchemaStatus::Behind { pending };
+    }
+
+    let newer: Vec<i64> = applied
+        .iter()
+        .map(|migration| migration.version)
+        .filter(|version| !known.iter().any(|(known, _)| known == version))
+        .collect();
+    if !newer.is_empty() {
+        return SchemaStatus::Newer { versions: newer };
+    }
+    SchemaStatus::Current
+}
+
+async fn table_exists(pool: &PgPool, table: &str) -> Result<bool, DbError> {
+    sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .bind(table)
+        .fetch_one(pool)
+        .await
+        .map_err(DbError::Query)
+}
+
+/// Migrations recorded in the database; empty before the first migration.
+pub async fn applied_migrations(pool: &PgPool) -> Result<Vec<AppliedMigration>, DbError> {
+    if !table_exists(pool, "_sqlx_migrations").await? {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, success, checksum FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::Query)
+}
+
+/// The oldest binary schema version allowed to run against this database.
+async fn min_compatible_version(pool: &PgPool) -> Result<i64, DbError> {
+    // Created by migration 59; older databases have no breaking migrations.
+    if !table_exists(pool, "schema_compat").await? {
+        return Ok(0);
+    }
+    let version: Option<i64> =
+        sqlx::query_scalar("SELECT min_compatible_version FROM schema_compat")
+            .fetch_optional(pool)
+            .await
+            .map_err(DbError::Query)?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Compare the database schema with this binary's.
+pub async fn status(pool: &PgPool) -> Result<SchemaStatus, DbError> {
+    let applied = applied_migrations(pool).await?;
+    let min_compatible_version = min_compatible_version(pool).await?;
+    let known = embedded();
+    let known: Vec<(i64, &[u8])> = known
+        .iter()
+        .map(|migration| (migration.version, migration.checksum.as_ref()))
+        .collect();
+    Ok(evaluate(&known, &applied, min_compatible_version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(versions: &[i64]) -> Vec<AppliedMigration> {
+        versions
+            .iter()
+            .map(|&version| AppliedMigration {
+                version,
+                success: true,
+                checksum: vec![version as u8],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let known: Vec<(i64, &[u8])> = vec![(1, &[1]), (2, &[2]), (3, &[3])];
+
+        assert_eq!(
+            evaluate(&known, &applied(&[1, 2, 3]), 0),
+            SchemaStatus::Current
+        );
+        assert_eq!(
+            evaluate(&known, &applied(&[1]), 0),
+            SchemaStatus::Behind {
+                pending: vec![2, 3]
+            }
+        );
+        assert_eq!(
+            evaluate(&known, &applied(&[]), 0),
+            SchemaStatus::Behind {
+                pending: vec![1, 2, 3]
+            }
+        );
+        assert_eq!(
+            evaluate(&known, &applied(&[1, 2, 3, 4]), 3),
+            SchemaStatus::Newer { versions: vec![4] }
+        );
+        assert_eq!(
+            evaluate(&known, &applied(&[1, 2, 3, 4]), 4),
+            SchemaStatus::Incompatible {
+                min_compatible_version: 4
+            }
+        );
+
+        let mut failed = applied(&[1, 2]);
+        failed[1].success = false;
+        assert_eq!(
+            evaluate(&known, &failed, 0),
+            SchemaStatus::Failed { version: 2 }
+        );
+
+        let mut modified = applied(&[1, 2, 3]);
+        modified[0].checksum = vec![9];
+        assert_eq!(
+            evaluate(&known, &modified, 0),
+            SchemaStatus::Modified { version: 1 }
+        );
+    }
+
+    #[test]
+    fn test_embedded_migrations() {
+        let migrations = embedded();
+        assert!(!migrations.is_empty());
+        assert!(migrations
+            .windows(2)
+            .all(|pair| pair[0].version < pair[1].version));
+        assert_eq!(migrations[0].description, "create events table");
+        assert_eq!(schema_version(), migrations.last().unwrap().version);
+    }
+
+    #[test]
+    fn test_status_compatibility() {
+        assert!(SchemaStatus::Current.is_compatible());
+        assert!(SchemaStatus::Newer { versions: vec![9] }.is_compatible());
+        assert!(!SchemaStatus::Behind { pending: vec![1] }.is_compatible());
+        assert!(!SchemaStatus::Incompatible {
+            min_compatible_version: 9
+        }
+        .is_compatible());
+    }
+}
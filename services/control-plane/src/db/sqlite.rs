@@ -0,0 +1,700 @@
+//! SQLite storage for single-node and development deployments.
+//!
+//! Implements the event log, projection checkpoints, and idempotency records
+//! on one SQLite file (or `sqlite::memory:`), selected with a `sqlite:`
+//! `DATABASE_URL`. Rows use the same columns and payload encoding as the
+//! Postgres tables so events round-trip between the backends unchanged.
+//!
+//! SQLite has a single writer; appends serialize on the database lock rather
+//! than on row locks, which is ample for one node.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use plfm_events::AggregateType;
+use plfm_id::EventId;
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteRow,
+};
+use sqlx::{Row, Sqlite, Transaction};
+use tracing::info;
+
+use super::backend::{CheckpointLog, EventLog, IdempotencyLog};
+use super::event_store::{
+    decode_payload_json, populate_protobuf_payload, stored_json_payload, PayloadEncoding,
+};
+use super::{
+    AppendEvent, DbConfig, DbError, EventRow, IdempotencyCheck, IdempotencyRecord,
+    ProjectionCheckpoint, StoreIdempotencyRecord,
+};
+
+const SCHEMA: &str = include_str!("sqlite_schema.sql");
+
+const EVENT_COLUMNS: &str = "event_id, occurred_at, aggregate_type, aggregate_id, \
+    aggregate_seq, event_type, event_version, actor_type, actor_id, org_id, request_id, \
+    idempotency_key, app_id, env_id, correlation_id, causation_id, payload, \
+    payload_type_url, payload_bytes, payload_schema_version, traceparent, tags";
+
+/// SQLite-backed event log, checkpoints, and idempotency records.
+#[derive(Clone)]
+pub struct SqliteStore {
+    pool: SqlitePool,
+    encoding: PayloadEncoding,
+}
+
+impl SqliteStore {
+    /// Open (creating if missing) the database named by `config.database_url`.
+    pub async fn connect(config: &DbConfig) -> Result<Self, DbError> {
+        let options = SqliteConnectOptions::from_str(&config.database_url)
+            .map_err(DbError::Connect)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(Duration::from_secs(5))
+            .foreign_keys(true);
+
+        // Every connection to an in-memory database is a separate database.
+        let max_connections = if config.database_url.contains(":memory:") {
+            1
+        } else {
+            config.max_connections
+        };
+
+        info!(max_connections, "Opening SQLite database");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .min_connections(config.min_connections.min(max_connections))
+            .acquire_timeout(config.acquire_timeout)
+            .idle_timeout(Some(config.idle_timeout))
+            .max_lifetime(None)
+            .connect_with(options)
+            .await
+            .map_err(DbError::Connect)?;
+
+        Ok(Self {
+            pool,
+            encoding: PayloadEncoding::from_env(),
+        })
+    }
+
+    /// Override the payload encoding.
+    pub fn with_encoding(mut self, encoding: PayloadEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Get a reference to the underlying connection pool.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// Create the schema if it does not exist.
+    pub async fn run_migrations(&self) -> Result<(), DbError> {
+        sqlx::raw_sql(SCHEMA)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+        info!("SQLite schema ready");
+        Ok(())
+    }
+
+    async fn insert_event(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        event: &AppendEvent,
+    ) -> Result<EventId, DbError> {
+        let json_payload = stored_json_payload(event, self.encoding)
+            .map(serde_json::to_string)
+            .transpose()?;
+        let tags = event.tags.as_ref().map(serde_json::to_string).transpose()?;
+
+        let event_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO events (
+                occurred_at,
+                aggregate_type,
+                aggregate_id,
+                aggregate_seq,
+                event_type,
+                event_version,
+                actor_type,
+                actor_id,
+                org_id,
+                request_id,
+                idempotency_key,
+                app_id,
+                env_id,
+                correlation_id,
+                causation_id,
+                payload,
+                payload_type_url,
+                payload_bytes,
+                payload_schema_version,
+                traceparent,
+                tags
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING event_id
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(event.aggregate_type.to_string())
+        .bind(&event.aggregate_id)
+        .bind(event.aggregate_seq)
+        .bind(&event.event_type)
+        .bind(event.event_version)
+        .bind(event.actor_type.to_string())
+        .bind(&event.actor_id)
+        .bind(event.org_id.as_ref().map(|id| id.to_string()))
+        .bind(&event.request_id)
+        .bind(&event.idempotency_key)
+        .bind(event.app_id.as_ref().map(|id| id.to_string()))
+        .bind(event.env_id.as_ref().map(|id| id.to_string()))
+        .bind(&event.correlation_id)
+        .bind(event.causation_id.map(|id| id.value()))
+        .bind(json_payload)
+        .bind(&event.payload_type_url)
+        .bind(&event.payload_bytes)
+        .bind(event.payload_schema_version)
+        .bind(&event.traceparent)
+        .bind(tags)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                DbError::SequenceConflict {
+                    aggregate_id: event.aggregate_id.clone(),
+                    expected: event.aggregate_seq,
+                    actual: event.aggregate_seq,
+                }
+            }
+            e => DbError::Query(e),
+        })?;
+
+        Ok(EventId::new(event_id))
+    }
+}
+
+/// Decode an events row; JSON columns are stored as text.
+fn event_row(row: &SqliteRow) -> Result<EventRow, DbError> {
+    let get_json = |column: &str| -> Result<Option<serde_json::Value>, DbError> {
+        let text: Option<String> = row.try_get(column).map_err(DbError::Query)?;
+        Ok(text.as_deref().map(serde_json::from_str).transpose()?)
+    };
+
+    let mut event = EventRow {
+        event_id: row.try_get("event_id").map_err(DbError::Query)?,
+        occurred_at: row.try_get("occurred_at").map_err(DbError::Query)?,
+        aggregate_type: row.try_get("aggregate_type").map_err(DbError::Query)?,
+        aggregate_id: row.try_get("aggregate_id").map_err(DbError::Query)?,
+        aggregate_seq: row.try_get("aggregate_seq").map_err(DbError::Query)?,
+        event_type: row.try_get("event_type").map_err(DbError::Query)?,
+        event_version: row.try_get("event_version").map_err(DbError::Query)?,
+        actor_type: row.try_get("actor_type").map_err(DbError::Query)?,
+        actor_id: row.try_get("actor_id").map_err(DbError::Query)?,
+        org_id: row.try_get("org_id").map_err(DbError::Query)?,
+        request_id: row.try_get("request_id").map_err(DbError::Query)?,
+        idempotency_key: row.try_get("idempotency_key").map_err(DbError::Query)?,
+        app_id: row.try_get("app_id").map_err(DbError::Query)?,
+        env_id: row.try_get("env_id").map_err(DbError::Query)?,
+        correlation_id: row.try_get("correlation_id").map_err(DbError::Query)?,
+        causation_id: row.try_get("causation_id").map_err(DbError::Query)?,
+        payload: serde_json::Value::Null,
+        payload_type_url: row.try_get("payload_type_url").map_err(DbError::Query)?,
+        payload_bytes: row.try_get("payload_bytes").map_err(DbError::Query)?,
+        payload_schema_version: row
+            .try_get("payload_schema_version")
+            .map_err(DbError::Query)?,
+        traceparent: row.try_get("traceparent").map_err(DbError::Query)?,
+        tags: get_json("tags")?,
+    };
+
+    // Dual read, as in Postgres: protobuf-only rows carry no JSON payload.
+    event.payload = match get_json("payload")? {
+        Some(payload) => payload,
+        None => match (
+            event.payload_type_url.as_deref(),
+            event.payload_bytes.as_deref(),
+        ) {
+            (Some(type_url), Some(bytes)) => decode_payload_json(type_url, bytes)?,
+            _ => serde_json::Value::Null,
+        },
+    };
+
+    Ok(event)
+}
+
+fn checkpoint_row(row: &SqliteRow) -> Result<ProjectionCheckpoint, DbError> {
+    Ok(ProjectionCheckpoint {
+        projection_name: row.try_get("projection_name").map_err(DbError::Query)?,
+        last_applied_event_id: row
+            .try_get("last_applied_event_id")
+            .map_err(DbError::Query)?,
+        updated_at: row.try_get("updated_at").map_err(DbError::Query)?,
+    })
+}
+
+fn unix_millis(at: DateTime<Utc>) -> i64 {
+    at.timestamp_millis()
+}
+
+#[async_trait]
+impl EventLog for SqliteStore {
+    async fn append(&self, event: AppendEvent) -> Result<EventId, DbError> {
+        let mut event = event;
+        populate_protobuf_payload(&mut event)?;
+
+        let mut tx = self.pool.begin().await.map_err(DbError::Query)?;
+        let event_id = self.insert_event(&mut tx, &event).await?;
+        tx.commit().await.map_err(DbError::Query)?;
+        Ok(event_id)
+    }
+
+    async fn append_batch(&self, events: Vec<AppendEvent>) -> Result<Vec<EventId>, DbError> {
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut events = events;
+        for event in &mut events {
+            populate_protobuf_payload(event)?;
+        }
+
+        let mut tx = self.pool.begin().await.map_err(DbError::Query)?;
+        let mut event_ids = Vec::with_capacity(events.len());
+        for event in &events {
+            event_ids.push(self.insert_event(&mut tx, event).await?);
+        }
+        tx.commit().await.map_err(DbError::Query)?;
+        Ok(event_ids)
+    }
+
+    async fn query_after_cursor(
+        &self,
+        after_event_id: i64,
+        limit: i32,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {EVENT_COLUMNS} FROM events WHERE event_id > ? ORDER BY event_id ASC LIMIT ?"
+        ))
+        .bind(after_event_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        rows.iter().map(event_row).collect()
+    }
+
+    async fn query_by_aggregate(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {EVENT_COLUMNS} FROM events \
+             WHERE aggregate_type = ? AND aggregate_id = ? ORDER BY aggregate_seq ASC"
+        ))
+        .bind(aggregate_type.to_string())
+        .bind(aggregate_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        rows.iter().map(event_row).collect()
+    }
+
+    async fn get_latest_aggregate_seq(
+        &self,
+        aggregate_type: &AggregateType,
+        aggregate_id: &str,
+    ) -> Result<Option<i32>, DbError> {
+        sqlx::query_scalar(
+            "SELECT MAX(aggregate_seq) FROM events WHERE aggregate_type = ? AND aggregate_id = ?",
+        )
+        .bind(aggregate_type.to_string())
+        .bind(aggregate_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Query)
+    }
+
+    async fn get_max_event_id(&self) -> Result<i64, DbError> {
+        sqlx::query_scalar("SELECT COALESCE(MAX(event_id), 0) FROM events")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DbError::Query)
+    }
+}
+
+#[async_trait]
+impl CheckpointLog for SqliteStore {
+    async fn get_checkpoint(&self, projection_name: &str) -> Result<ProjectionCheckpoint, DbError> {
+        let row = sqlx::query(
+            r#"
+            SELECT projection_name, last_applied_event_id, updated_at
+            FROM projection_checkpoints
+            WHERE projection_name = ?
+            "#,
+        )
+        .bind(projection_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Query)?
+        .ok_or_else(|| DbError::ProjectionNotFound(projection_name.to_string()))?;
+
+        checkpoint_row(&row)
+    }
+
+    async fn ensure_checkpoint(&self, projection_name: &str) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            INSERT INTO projection_checkpoints (projection_name, last_applied_event_id, updated_at)
+            VALUES (?, 0, ?)
+            ON CONFLICT (projection_name) DO NOTHING
+            "#,
+        )
+        .bind(projection_name)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    async fn update_checkpoint(
+        &self,
+        projection_name: &str,
+        last_applied_event_id: i64,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            INSERT INTO projection_checkpoints (projection_name, last_applied_event_id, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (projection_name)
+            DO UPDATE SET
+                last_applied_event_id = excluded.last_applied_event_id,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(projection_name)
+        .bind(last_applied_event_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    async fn list_checkpoints(&self) -> Result<Vec<ProjectionCheckpoint>, DbError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT projection_name, last_applied_event_id, updated_at
+            FROM projection_checkpoints
+            ORDER BY projection_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        rows.iter().map(checkpoint_row).collect()
+    }
+
+    async fn reset_checkpoint(&self, projection_name: &str) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            UPDATE projection_checkpoints
+            SET last_applied_event_id = 0, updated_at = ?
+            WHERE projection_name = ?
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(projection_name)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IdempotencyLog for SqliteStore {
+    async fn check(
+        &self,
+        org_id: &str,
+        actor_id: &str,
+        endpoint_name: &str,
+        idempotency_key: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyCheck, DbError> {
+        let row = sqlx::query(
+            r#"
+            SELECT request_hash, response_status_code, response_body
+            FROM idempotency_records
+            WHERE org_id = ?
+              AND actor_id = ?
+              AND endpoint_name = ?
+              AND idempotency_key = ?
+              AND expires_at_ms > ?
+            "#,
+        )
+        .bind(org_id)
+        .bind(actor_id)
+        .bind(endpoint_name)
+        .bind(idempotency_key)
+        .bind(unix_millis(Utc::now()))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        let Some(row) = row else {
+            return Ok(IdempotencyCheck::NotFound);
+        };
+
+        let stored_hash: String = row.try_get("request_hash").map_err(DbError::Query)?;
+        if stored_hash != request_hash {
+            return Ok(IdempotencyCheck::Conflict);
+        }
+
+        let body: Option<String> = row.try_get("response_body").map_err(DbError::Query)?;
+        Ok(IdempotencyCheck::Found(IdempotencyRecord {
+            request_hash: stored_hash,
+            response_status_code: row
+                .try_get("response_status_code")
+                .map_err(DbError::Query)?,
+            response_body: body.as_deref().map(serde_json::from_str).transpose()?,
+        }))
+    }
+
+    async fn store(&self, record: StoreIdempotencyRecord) -> Result<(), DbError> {
+        let now = Utc::now();
+        let expires_at =
+            now + chrono::Duration::from_std(record.ttl).unwrap_or_else(|_| chrono::Duration::MAX);
+        let body = record
+            .response_body
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO idempotency_records (
+                org_id,
+                actor_id,
+                endpoint_name,
+                idempotency_key,
+                request_hash,
+                response_status_code,
+                response_body,
+                created_at_ms,
+                expires_at_ms
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (org_id, actor_id, endpoint_name, idempotency_key)
+            DO UPDATE SET
+                request_hash = excluded.request_hash,
+                response_status_code = excluded.response_status_code,
+                response_body = excluded.response_body,
+                created_at_ms = excluded.created_at_ms,
+                expires_at_ms = excluded.expires_at_ms
+            WHERE idempotency_records.expires_at_ms <= excluded.created_at_ms
+            "#,
+        )
+        .bind(record.org_id)
+        .bind(record.actor_id)
+        .bind(record.endpoint_name)
+        .bind(record.idempotency_key)
+        .bind(record.request_hash)
+        .bind(record.response_status_code)
+        .bind(body)
+        .bind(unix_millis(now))
+        .bind(unix_millis(expires_at))
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Query)?;
+
+        Ok(())
+    }
+
+    async fn cleanup_expired(&self, max_age_hours: i32) -> Result<u64, DbError> {
+        let cutoff = Utc::now() - chrono::Duration::hours(i64::from(max_age_hours));
+        let result = sqlx::query("DELETE FROM idempotency_records WHERE created_at_ms < ?")
+            .bind(unix_millis(cutoff))
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plfm_events::ActorType;
+
+    async fn memory_store() -> SqliteStore {
+        let config = DbConfig {
+            database_url: "sqlite::memory:".to_string(),
+            ..Default::default()
+        };
+        let store = SqliteStore::connect(&config).await.unwrap();
+        store.run_migrations().await.unwrap();
+        // Idempotent.
+        store.run_migrations().await.unwrap();
+        store
+    }
+
+    fn org_event(aggregate_id: &str, seq: i32) -> AppendEvent {
+        AppendEvent {
+            aggregate_type: AggregateType::Org,
+            aggregate_id: aggregate_id.to_string(),
+            aggregate_seq: seq,
+            event_type: "org.created".to_string(),
+            event_version: 1,
+            actor_type: ActorType::User,
+            actor_id: "user_1".to_string(),
+            request_id: "req_1".to_string(),
+            payload: serde_json::json!({ "org_id": aggregate_id, "name": "acme" }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_and_query() {
+        let store = memory_store().await;
+
+        let first = store.append(org_event("org_a", 1)).await.unwrap();
+        let batch = store
+            .append_batch(vec![org_event("org_b", 1), org_event("org_c", 1)])
+            .await
+            .unwrap();
+        assert!(batch[0].value() > first.value());
+        assert_eq!(store.get_max_event_id().await.unwrap(), batch[1].value());
+
+        let events = store.query_after_cursor(first.value(), 10).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].aggregate_id, "org_b");
+        assert_eq!(events[0].payload["name"], "acme");
+        assert!(events[0].payload_bytes.is_some());
+
+        assert_eq!(
+            store
+                .get_latest_aggregate_seq(&AggregateType::Org, "org_a")
+                .await
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            store
+                .get_latest_aggregate_seq(&AggregateType::Org, "org_z")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_conflict() {
+        let store = memory_store().await;
+        store.append(org_event("org_a", 1)).await.unwrap();
+
+        let err = store.append(org_event("org_a", 1)).await.unwrap_err();
+        assert!(matches!(err, DbError::SequenceConflict { .. }));
+
+        // A conflicting batch writes nothing.
+        let err = store
+            .append_batch(vec![org_event("org_b", 1), org_event("org_a", 1)])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DbError::SequenceConflict { .. }));
+        assert!(store
+            .query_by_aggregate(&AggregateType::Org, "org_b")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints() {
+        let store = memory_store().await;
+
+        assert!(matches!(
+            store.get_checkpoint("orgs").await,
+            Err(DbError::ProjectionNotFound(_))
+        ));
+        store.ensure_checkpoint("orgs").await.unwrap();
+        store.update_checkpoint("orgs", 42).await.unwrap();
+        store.ensure_checkpoint("orgs").await.unwrap();
+        assert_eq!(
+            store
+                .get_checkpoint("orgs")
+                .await
+                .unwrap()
+                .last_applied_event_id,
+            42
+        );
+
+        store.reset_checkpoint("orgs").await.unwrap();
+        let checkpoints = store.list_checkpoints().await.unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].last_applied_event_id, 0);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_records() {
+        let store = memory_store().await;
+        let record = |hash: &str, ttl: Duration| StoreIdempotencyRecord {
+            org_id: "org_a".to_string(),
+            actor_id: "user_1".to_string(),
+            endpoint_name: "apps.create".to_string(),
+            idempotency_key: "key_1".to_string(),
+            request_hash: hash.to_string(),
+            response_status_code: 201,
+            response_body: Some(serde_json::json!({ "id": "app_1" })),
+            ttl,
+        };
+
+        store
+            .store(record("hash_1", Duration::from_secs(3600)))
+            .await
+            .unwrap();
+        match store
+            .check("org_a", "user_1", "apps.create", "key_1", "hash_1")
+            .await
+            .unwrap()
+        {
+            IdempotencyCheck::Found(found) => {
+                assert_eq!(found.response_status_code, 201);
+                assert_eq!(found.response_body.unwrap()["id"], "app_1");
+            }
+            other => panic!("expected Found, got {other:?}"),
+        }
+        assert!(matches!(
+            store
+                .check("org_a", "user_1", "apps.create", "key_1", "hash_2")
+                .await
+                .unwrap(),
+            IdempotencyCheck::Conflict
+        ));
+
+        // A live record is not overwritten.
+        store
+            .store(record("hash_2", Duration::from_secs(3600)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            store
+                .check("org_a", "user_1", "apps.create", "key_1", "hash_1")
+                .await
+                .unwrap(),
+            IdempotencyCheck::Found(_)
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+-- SQLite schema for single-node and development deployments.
+-- Mirrors the Postgres events, projection_checkpoints, and
+-- idempotency_records tables (migrations 00001, 00022).
+--
+-- Timestamps on events and checkpoints are RFC 3339 text written by the
+-- control plane; idempotency timestamps are Unix milliseconds so expiry
+-- compares numerically. Idempotent: applied on every start.
+
+CREATE TABLE IF NOT EXISTS events (
+    event_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    occurred_at TEXT NOT NULL,
+    aggregate_type TEXT NOT NULL,
+    aggregate_id TEXT NOT NULL,
+    aggregate_seq INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    event_version INTEGER NOT NULL DEFAULT 1,
+    actor_type TEXT NOT NULL,
+    actor_id TEXT NOT NULL,
+    org_id TEXT,
+    request_id TEXT NOT NULL,
+    idempotency_key TEXT,
+    app_id TEXT,
+    env_id TEXT,
+    correlation_id TEXT,
+    causation_id INTEGER,
+    payload TEXT,
+    payload_type_url TEXT,
+    payload_bytes BLOB,
+    payload_schema_version INTEGER,
+    traceparent TEXT,
+    tags TEXT,
+    UNIQUE (aggregate_type, aggregate_id, aggregate_seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_org_id ON events (org_id, event_id);
+CREATE INDEX IF NOT EXISTS idx_events_event_type ON events (event_type, event_id);
+
+CREATE TABLE IF NOT EXISTS projection_checkpoints (
+    projection_name TEXT PRIMARY KEY,
+    last_applied_event_id INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    paused INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    last_error_event_id INTEGER,
+    last_error_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS idempotency_records (
+    org_id TEXT NOT NULL,
+    actor_id TEXT NOT NULL,
+    endpoint_name TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    response_status_code INTEGER NOT NULL,
+    response_body TEXT,
+    created_at_ms INTEGER NOT NULL,
+    expires_at_ms INTEGER NOT NULL,
+    PRIMARY KEY (org_id, actor_id, endpoint_name, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_records_created_at
+    ON idempotency_records (created_at_ms);
@@ -0,0 +1,574 @@
+//! Anti-entropy: drift between desired and node-reported instances.
+//!
+//! Node heartbeats carry the agent's instance inventory (instance_id ->
+//! status), stored per node in `node_instance_reports`. The drift detector
+//! periodically compares each fresh inventory with `instances_desired_view`
+//! and flags two kinds of drift:
+//! - `not_reported`: desired `running` on the node, but missing from its
+//!   inventory
+//! - `not_desired`: live on the node, but assigned elsewhere, unknown, or
+//!   desired `stopped`
+//!
+//! Desired changes younger than the grace period are ignored, so ordinary
+//! convergence is not reported as drift. Open drift is kept in
+//! `instance_drift`; `instance.drift_detected` is emitted when an instance
+//! enters drift (or changes kind), and the row is dropped once the node and
+//! desired state agree again.
+//!
+//! Configuration:
+//! - `PLFM_DRIFT_GRACE_SECS`: grace period for desired changes (default 120)
+//! - `PLFM_DRIFT_REPORT_MAX_AGE_SECS`: inventories older than this are not
+//!   checked (default 90)
+//!
+//! See: docs/specs/scheduler/reconciliation-loop.md
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{
+    AggregateType, InstanceDriftDetectedPayload, InstanceDriftKind, NewEvent, SystemSource,
+};
+use plfm_id::{AppId, EnvId, InstanceId, NodeId, OrgId};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use crate::db::{DbError, EventStore};
+use crate::leader::{LeaderElection, LeaderRole};
+
+/// Actor ID for events written by the drift detector.
+pub const DRIFT_DETECTOR_ACTOR_ID: &str = "drift-detector";
+
+const DEFAULT_GRACE_SECS: i64 = 120;
+const DEFAULT_REPORT_MAX_AGE_SECS: i64 = 90;
+
+/// Reported statuses that mean a VM is (or may still be) running.
+const LIVE_STATUSES: &[&str] = &["booting", "ready", "draining"];
+
+#[derive(Debug, Error)]
+pub enum DriftError {
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// How long a desired change may take to show up in node inventories.
+pub fn grace_period() -> Duration {
+    let secs = std::env::var("PLFM_DRIFT_GRACE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_GRACE_SECS);
+    Duration::seconds(secs)
+}
+
+/// Inventories older than this are too stale to compare.
+pub fn report_max_age() -> Duration {
+    let secs = std::env::var("PLFM_DRIFT_REPORT_MAX_AGE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_REPORT_MAX_AGE_SECS);
+    Duration::seconds(secs)
+}
+
+// =============================================================================
+// Inventories
+// =============================================================================
+
+/// Decode the `instance_statuses` object of an HTTP heartbeat.
+///
+/// Returns `None` when the agent sent no inventory (older agents), so the
+/// node is not checked rather than treated as running nothing.
+pub fn inventory_from_json(value: &serde_json::Value) -> Option<BTreeMap<String, String>> {
+    let entries = value.as_object()?;
+    Some(
+        entries
+            .iter()
+            .filter_map(|(id, status)| Some((id.clone(), status.as_str()?.to_string())))
+            .collect(),
+    )
+}
+
+/// Store the latest instance inventory reported by a node.
+pub async fn record_inventory(
+    pool: &PgPool,
+    node_id: &str,
+    instances: &BTreeMap<String, String>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO node_instance_reports (node_id, instances, reported_at)
+        VALUES ($1, $2, now())
+        ON CONFLICT (node_id) DO UPDATE SET
+            instances = EXCLUDED.instances,
+            reported_at = EXCLUDED.reported_at
+        "#,
+    )
+    .bind(node_id)
+    .bind(serde_json::json!(instances))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// =============================================================================
+// Detection
+// =============================================================================
+
+/// A desired instance relevant to one node's inventory.
+#[derive(Debug, Clone)]
+pub struct DesiredInstance {
+    pub instance_id: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub node_id: String,
+    pub desired_state: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for DesiredInstance {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            instance_id: row.try_get("instance_id")?,
+            org_id: row.try_get("org_id")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            node_id: row.try_get("node_id")?,
+            desired_state: row.try_get("desired_state")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// One instance whose desired and reported state disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub instance_id: String,
+    pub kind: InstanceDriftKind,
+    /// Desired state, when the instance is desired on this node.
+    pub desired_state: Option<String>,
+    pub reported_status: Option<String>,
+    pub org_id: Option<String>,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+}
+
+/// Compare a node's inventory, taken at `reported_at`, with desired state.
+///
+/// `desired` holds the instances desired on `node_id` plus any reported
+/// instances desired elsewhere. Desired changes made less than `grace`
+/// before the inventory was taken are skipped.
+pub fn detect(
+    node_id: &str,
+    desired: &[DesiredInstance],
+    reported: &BTreeMap<String, String>,
+    reported_at: DateTime<Utc>,
+    grace: Duration,
+) -> Vec<Drift> {
+    let settled = |d: &DesiredInstance| d.updated_at + grace <= reported_at;
+    let by_id: HashMap<&str, &DesiredInstance> = desired
+        .iter()
+        .map(|d| (d.instance_id.as_str(), d))
+        .collect();
+    let mut drift = Vec::new();
+
+    for d in desired {
+        if d.node_id == node_id
+            && d.desired_state == "running"
+            && settled(d)
+            && !reported.contains_key(&d.instance_id)
+        {
+            drift.push(Drift {
+                instance_id: d.instance_id.clone(),
+                kind: InstanceDriftKind::NotReported,
+                desired_state: Some(d.desired_state.clone()),
+                reported_status: None,
+                org_id: Some(d.org_id.clone()),
+                app_id: Some(d.app_id.clone()),
+                env_id: Some(d.env_id.clone()),
+            });
+        }
+    }
+
+    for (instance_id, status) in reported {
+        if !LIVE_STATUSES.contains(&status.as_str()) {
+            continue;
+        }
+        let desired_here = by_id
+            .get(instance_id.as_str())
+            .filter(|d| d.node_id == node_id);
+        let not_desired = match desired_here {
+            // Unknown here: never allocated, or moved to another node.
+            None => by_id.get(instance_id.as_str()).is_none_or(|d| settled(*d)),
+            Some(d) => d.desired_state == "stopped" && settled(*d),
+        };
+        if not_desired {
+            let known = by_id.get(instance_id.as_str());
+            drift.push(Drift {
+                instance_id: instance_id.clone(),
+                kind: InstanceDriftKind::NotDesired,
+                desired_state: desired_here.map(|d| d.desired_state.clone()),
+                reported_status: Some(status.clone()),
+                org_id: known.map(|d| d.org_id.clone()),
+                app_id: known.map(|d| d.app_id.clone()),
+                env_id: known.map(|d| d.env_id.clone()),
+            });
+        }
+    }
+
+    drift
+}
+
+// =============================================================================
+// Pass
+// =============================================================================
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PassSummary {
+    pub nodes_checked: usize,
+    /// Instances that entered drift (or changed kind) this pass.
+    pub detected: usize,
+    /// Instances whose drift cleared this pass.
+    pub resolved: usize,
+}
+
+/// Check every node with a fresh inventory.
+pub async fn run_pass(pool: &PgPool) -> Result<PassSummary, DriftError> {
+    let reports = sqlx::query_as::<_, (String, serde_json::Value, DateTime<Utc>)>(
+        r#"
+        SELECT r.node_id, r.instances, r.reported_at
+        FROM node_instance_reports r
+        JOIN nodes_view n ON n.node_id = r.node_id
+        WHERE r.reported_at >= $1
+          AND n.state IN ('active', 'draining', 'degraded')
+        ORDER BY r.node_id
+        "#,
+    )
+    .bind(Utc::now() - report_max_age())
+    .fetch_all(pool)
+    .await?;
+
+    let grace = grace_period();
+    let store = EventStore::new(pool.clone());
+    let source = SystemSource::new(DRIFT_DETECTOR_ACTOR_ID);
+    let mut summary = PassSummary::default();
+
+    for (node_id, instances, reported_at) in reports {
+        let reported = inventory_from_json(&instances).unwrap_or_default();
+        let reported_ids: Vec<String> = reported.keys().cloned().collect();
+        let desired = sqlx::query_as::<_, DesiredInstance>(
+            r#"
+            SELECT instance_id, org_id, app_id, env_id, node_id, desired_state, updated_at
+            FROM instances_desired_view
+            WHERE node_id = $1 OR instance_id = ANY($2)
+            "#,
+        )
+        .bind(&node_id)
+        .bind(&reported_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let found = detect(&node_id, &desired, &reported, reported_at, grace);
+        let (detected, resolved) = reconcile_node(pool, &store, &source, &node_id, &found).await?;
+        summary.nodes_checked += 1;
+        summary.detected += detected;
+        summary.resolved += resolved;
+    }
+
+    Ok(summary)
+}
+
+/// Sync `instance_drift` for a node with this pass's findings, emitting an
+/// event for each instance that newly entered drift.
+async fn reconcile_node(
+    pool: &PgPool,
+    store: &EventStore,
+    source: &SystemSource,
+    node_id: &str,
+    found: &[Drift],
+) -> Result<(usize, usize), DriftError> {
+    let open: HashMap<String, String> = sqlx::query_as::<_, (String, String)>(
+        "SELECT instance_id, kind FROM instance_drift WHERE node_id = $1",
+    )
+    .bind(node_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut detected = 0;
+    for drift in found {
+        let is_new = open.get(&drift.instance_id).map(String::as_str) != Some(drift.kind.as_str());
+        sqlx::query(
+            r#"
+            INSERT INTO instance_drift
+                (node_id, instance_id, kind, org_id, env_id, desired_state, reported_status,
+                 first_detected_at, last_detected_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, now(), now())
+            ON CONFLICT (node_id, instance_id) DO UPDATE SET
+                kind = EXCLUDED.kind,
+                org_id = EXCLUDED.org_id,
+                env_id = EXCLUDED.env_id,
+                desired_state = EXCLUDED.desired_state,
+                reported_status = EXCLUDED.reported_status,
+                first_detected_at = CASE
+                    WHEN instance_drift.kind = EXCLUDED.kind THEN instance_drift.first_detected_at
+                    ELSE EXCLUDED.first_detected_at
+                END,
+                last_detected_at = EXCLUDED.last_detected_at
+            "#,
+        )
+        .bind(node_id)
+        .bind(&drift.instance_id)
+        .bind(drift.kind.as_str())
+        .bind(&drift.org_id)
+        .bind(&drift.env_id)
+        .bind(&drift.desired_state)
+        .bind(&drift.reported_status)
+        .execute(pool)
+        .await?;
+
+        if is_new {
+            record_drift(store, source, node_id, drift).await?;
+            info!(
+                node_id = %node_id,
+                instance_id = %drift.instance_id,
+                kind = drift.kind.as_str(),
+                "Instance drift detected"
+            );
+            detected += 1;
+        }
+    }
+
+    let still_open: Vec<String> = found.iter().map(|d| d.instance_id.clone()).collect();
+    let resolved = sqlx::query(
+        "DELETE FROM instance_drift WHERE node_id = $1 AND NOT (instance_id = ANY($2))",
+    )
+    .bind(node_id)
+    .bind(&still_open)
+    .execute(pool)
+    .await?
+    .rows_affected() as usize;
+
+    Ok((detected, resolved))
+}
+
+/// Emit `instance.drift_detected`.
+async fn record_drift(
+    store: &EventStore,
+    source: &SystemSource,
+    node_id: &str,
+    drift: &Drift,
+) -> Result<(), DriftError> {
+    let (Ok(instance_id), Ok(node_id_typed)) = (
+        drift.instance_id.parse::<InstanceId>(),
+        node_id.parse::<NodeId>(),
+    ) else {
+        warn!(node_id = %node_id, instance_id = %drift.instance_id, "Skipping drift event for invalid IDs");
+        return Ok(());
+    };
+    let org_id = drift
+        .org_id
+        .as_deref()
+        .and_then(|id| id.parse::<OrgId>().ok());
+    let app_id = drift
+        .app_id
+        .as_deref()
+        .and_then(|id| id.parse::<AppId>().ok());
+    let env_id = drift
+        .env_id
+        .as_deref()
+        .and_then(|id| id.parse::<EnvId>().ok());
+
+    let seq = store
+        .get_latest_aggregate_seq(&AggregateType::Instance, &drift.instance_id)
+        .await?
+        .unwrap_or(0)
+        + 1;
+    let mut builder = NewEvent::builder(source)
+        .aggregate_id(drift.instance_id.clone())
+        .aggregate_seq(seq)
+        .payload(&InstanceDriftDetectedPayload {
+            instance_id,
+            node_id: node_id_typed,
+            org_id,
+            env_id,
+            kind: drift.kind,
+            desired_state: drift.desired_state.clone(),
+            reported_status: drift.reported_status.clone(),
+            detected_at: Utc::now().to_rfc3339(),
+        });
+    if let Some(org_id) = org_id {
+        builder = builder.org_id(org_id);
+    }
+    if let Some(app_id) = app_id {
+        builder = builder.app_id(app_id);
+    }
+    if let Some(env_id) = env_id {
+        builder = builder.env_id(env_id);
+    }
+    store.append(builder.build()?.into()).await?;
+    Ok(())
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker running drift passes, on the elected leader only.
+pub struct DriftDetectorWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    election: LeaderElection,
+}
+
+impl DriftDetectorWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::DriftDetector, interval * 3),
+            pool,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            "Starting drift detector worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    match run_pass(&self.pool).await {
+                        Ok(summary) if summary.detected > 0 || summary.resolved > 0 => info!(
+                            nodes = summary.nodes_checked,
+                            detected = summary.detected,
+                            resolved = summary.resolved,
+                            "Drift pass complete"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Drift pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Drift detector worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desired(
+        id: &str,
+        node: &str,
+        state: &str,
+        age_secs: i64,
+        now: DateTime<Utc>,
+    ) -> DesiredInstance {
+        DesiredInstance {
+            instance_id: id.to_string(),
+            org_id: "org_1".to_string(),
+            app_id: "app_1".to_string(),
+            env_id: "env_1".to_string(),
+            node_id: node.to_string(),
+            desired_state: state.to_string(),
+            updated_at: now - Duration::seconds(age_secs),
+        }
+    }
+
+    fn inventory(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(id, status)| (id.to_string(), status.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_not_reported() {
+        let now = Utc::now();
+        let grace = Duration::seconds(120);
+        let rows = vec![
+            desired("inst_old", "node_a", "running", 600, now),
+            desired("inst_new", "node_a", "running", 10, now),
+            desired("inst_ok", "node_a", "running", 600, now),
+            desired("inst_stopped", "node_a", "stopped", 600, now),
+        ];
+        let reported = inventory(&[("inst_ok", "ready")]);
+
+        let drift = detect("node_a", &rows, &reported, now, grace);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].instance_id, "inst_old");
+        assert_eq!(drift[0].kind, InstanceDriftKind::NotReported);
+        assert_eq!(drift[0].org_id.as_deref(), Some("org_1"));
+    }
+
+    #[test]
+    fn test_detect_not_desired() {
+        let now = Utc::now();
+        let grace = Duration::seconds(120);
+        let rows = vec![
+            desired("inst_moved", "node_b", "running", 600, now),
+            desired("inst_moving", "node_b", "running", 10, now),
+            desired("inst_stop", "node_a", "stopped", 600, now),
+            desired("inst_drain", "node_a", "draining", 600, now),
+        ];
+        let reported = inventory(&[
+            ("inst_moved", "ready"),
+            ("inst_moving", "ready"),
+            ("inst_stop", "ready"),
+            ("inst_drain", "draining"),
+            ("inst_ghost", "booting"),
+            ("inst_dead", "failed"),
+        ]);
+
+        let drift = detect("node_a", &rows, &reported, now, grace);
+        let ids: Vec<&str> = drift.iter().map(|d| d.instance_id.as_str()).collect();
+        assert_eq!(ids, vec!["inst_ghost", "inst_moved", "inst_stop"]);
+        assert!(drift
+            .iter()
+            .all(|d| d.kind == InstanceDriftKind::NotDesired));
+
+        let ghost = drift
+            .iter()
+            .find(|d| d.instance_id == "inst_ghost")
+            .unwrap();
+        assert_eq!(ghost.org_id, None);
+        assert_eq!(ghost.reported_status.as_deref(), Some("booting"));
+        let moved = drift
+            .iter()
+            .find(|d| d.instance_id == "inst_moved")
+            .unwrap();
+        assert_eq!(moved.desired_state, None);
+        assert_eq!(moved.env_id.as_deref(), Some("env_1"));
+    }
+
+    #[test]
+    fn test_inventory_from_json() {
+        assert_eq!(inventory_from_json(&serde_json::Value::Null), None);
+        let parsed = inventory_from_json(&serde_json::json!({"inst_a": "ready", "inst_b": 3}));
+        assert_eq!(parsed, Some(inventory(&[("inst_a", "ready")])));
+    }
+}
@@ -0,0 +1,408 @@
+//! Node enrollment policy, bootstrap tokens, and agent identity checks.
+//!
+//! Enrollment is gated by `PLFM_NODE_ENROLLMENT_MODE`:
+//! - `open`: any caller may enroll (the default in dev mode)
+//! - `token`: a single-use bootstrap token minted by an operator is required
+//!   (the default otherwise)
+//! - `approval`: a token is required and every new node waits in
+//!   `pending_approval` until an operator approves or denies it
+//!
+//! A token presented in `open` mode is still verified and consumed, and a
+//! token minted with `requires_approval` sends its node to the approval queue
+//! regardless of mode.
+//!
+//! After enrollment, agent requests are tied to the node's enrolled mTLS
+//! subject. mTLS is terminated in front of the control plane, which forwards
+//! the verified client certificate subject in `PLFM_CLIENT_CERT_SUBJECT_HEADER`
+//! (default `x-client-cert-subject`; the proxy must strip any client-supplied
+//! value). The subject a node enrolls with must be the presented one, and
+//! later requests must present the pinned subject; with
+//! `PLFM_NODE_REQUIRE_CLIENT_CERT=true` a missing subject is rejected too.
+//!
+//! Shared by the HTTP and gRPC node APIs.
+//!
+//! See: docs/specs/api/http-api.md
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_id::{NodeId, Ulid};
+use sqlx::PgPool;
+use thiserror::Error;
+
+use crate::api::tokens;
+
+/// Default lifetime of a bootstrap token.
+pub const DEFAULT_BOOTSTRAP_TOKEN_TTL_HOURS: i64 = 24;
+
+/// Longest lifetime an operator may give a bootstrap token.
+pub const MAX_BOOTSTRAP_TOKEN_TTL_HOURS: i64 = 24 * 30;
+
+const DEFAULT_CLIENT_CERT_SUBJECT_HEADER: &str = "x-client-cert-subject";
+
+/// Node states that exist only before a node is admitted.
+pub const STATE_PENDING_APPROVAL: &str = "pending_approval";
+pub const STATE_DENIED: &str = "denied";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnrollmentMode {
+    Open,
+    Token,
+    Approval,
+}
+
+impl EnrollmentMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "open" => Some(EnrollmentMode::Open),
+            "token" => Some(EnrollmentMode::Token),
+            "approval" => Some(EnrollmentMode::Approval),
+            _ => None,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let default = if dev_mode_enabled() {
+            EnrollmentMode::Open
+        } else {
+            EnrollmentMode::Token
+        };
+        match std::env::var("PLFM_NODE_ENROLLMENT_MODE") {
+            Ok(value) => Self::parse(&value).unwrap_or_else(|| {
+                tracing::warn!(
+                    value = %value,
+                    "Unrecognized PLFM_NODE_ENROLLMENT_MODE; using the default"
+                );
+                default
+            }),
+            Err(_) => default,
+        }
+    }
+}
+
+fn dev_mode_enabled() -> bool {
+    std::env::var("GHOST_DEV")
+        .map(|v| v == "1" || v.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
+/// Header (HTTP) / metadata key (gRPC) carrying the verified client
+/// certificate subject.
+pub fn client_cert_subject_header() -> String {
+    std::env::var("PLFM_CLIENT_CERT_SUBJECT_HEADER")
+        .map(|v| v.trim().to_ascii_lowercase())
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_CLIENT_CERT_SUBJECT_HEADER.to_string())
+}
+
+fn client_cert_required() -> bool {
+    std::env::var("PLFM_NODE_REQUIRE_CLIENT_CERT")
+        .map(|v| v == "1" || v.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Error)]
+pub enum EnrollmentError {
+    #[error("a bootstrap token is required to enroll a node")]
+    TokenRequired,
+    #[error("bootstrap token is invalid, expired, revoked, or already used")]
+    InvalidToken,
+    #[error("node {0} not found")]
+    NodeNotFound(String),
+    #[error("node {0} is awaiting enrollment approval")]
+    PendingApproval(String),
+    #[error("enrollment of node {0} was denied")]
+    Denied(String),
+    #[error("a client certificate subject is required for node requests")]
+    IdentityRequired,
+    #[error("client certificate subject does not match the subject node {0} enrolled with")]
+    IdentityMismatch(String),
+    #[error("client certificate subject does not match agent_mtls_subject")]
+    SubjectMismatch,
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Outcome of admitting an enrollment request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnrollmentAdmission {
+    /// Bootstrap token consumed by this enrollment, if any.
+    pub bootstrap_token_id: Option<String>,
+    /// The node must wait for operator approval.
+    pub requires_approval: bool,
+}
+
+/// Check an enrollment against `mode`, consuming `bootstrap_token` if given.
+///
+/// The token is consumed even if the caller then fails to record the
+/// enrollment; the operator mints a new one.
+pub async fn admit(
+    pool: &PgPool,
+    mode: EnrollmentMode,
+    bootstrap_token: Option<&str>,
+    node_id: &NodeId,
+) -> Result<EnrollmentAdmission, EnrollmentError> {
+    let token = bootstrap_token.map(str::trim).filter(|t| !t.is_empty());
+    let Some(token) = token else {
+        return match mode {
+            EnrollmentMode::Open => Ok(EnrollmentAdmission {
+                bootstrap_token_id: None,
+                requires_approval: false,
+            }),
+            EnrollmentMode::Token | EnrollmentMode::Approval => Err(EnrollmentError::TokenRequired),
+        };
+    };
+
+    let consumed: Option<(String, bool)> = sqlx::query_as(
+        r#"
+        UPDATE node_bootstrap_tokens
+        SET used_at = now(), used_by_node_id = $2
+        WHERE token_hash = $1
+          AND used_at IS NULL
+          AND revoked_at IS NULL
+          AND expires_at > now()
+        RETURNING token_id, requires_approval
+        "#,
+    )
+    .bind(tokens::hash_token(token))
+    .bind(node_id.to_string())
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((token_id, token_requires_approval)) = consumed else {
+        return Err(EnrollmentError::InvalidToken);
+    };
+
+    Ok(EnrollmentAdmission {
+        bootstrap_token_id: Some(token_id),
+        requires_approval: token_requires_approval || mode == EnrollmentMode::Approval,
+    })
+}
+
+/// Check that the `agent_mtls_subject` a node asks to be pinned to is the
+/// client certificate subject it enrolls with.
+pub fn check_enrollment_subject(
+    agent_mtls_subject: &str,
+    presented_subject: Option<&str>,
+) -> Result<(), EnrollmentError> {
+    check_subject(
+        agent_mtls_subject,
+        presented_subject,
+        client_cert_required(),
+    )
+}
+
+fn check_subject(
+    agent_mtls_subject: &str,
+    presented_subject: Option<&str>,
+    require_subject: bool,
+) -> Result<(), EnrollmentError> {
+    match presented_subject.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(subject) if subject == agent_mtls_subject => Ok(()),
+        Some(_) => Err(EnrollmentError::SubjectMismatch),
+        None if require_subject => Err(EnrollmentError::IdentityRequired),
+        None => Ok(()),
+    }
+}
+
+/// Check that `node_id` is admitted and, when a client certificate subject
+/// is presented (or required), that it is the one the node enrolled with.
+pub async fn verify_node(
+    pool: &PgPool,
+    node_id: &str,
+    presented_subject: Option<&str>,
+) -> Result<(), EnrollmentError> {
+    let row: Option<(String, String)> =
+        sqlx::query_as("SELECT state, agent_mtls_subject FROM nodes_view WHERE node_id = $1")
+            .bind(node_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let Some((state, pinned_subject)) = row else {
+        return Err(EnrollmentError::NodeNotFound(node_id.to_string()));
+    };
+    check_node(
+        node_id,
+        &state,
+        &pinned_subject,
+        presented_subject,
+        client_cert_required(),
+    )
+}
+
+fn check_node(
+    node_id: &str,
+    state: &str,
+    pinned_subject: &str,
+    presented_subject: Option<&str>,
+    require_subject: bool,
+) -> Result<(), EnrollmentError> {
+    match state {
+        STATE_PENDING_APPROVAL => {
+            return Err(EnrollmentError::PendingApproval(node_id.to_string()))
+        }
+        STATE_DENIED => return Err(EnrollmentError::Denied(node_id.to_string())),
+        _ => {}
+    }
+
+    match presented_subject.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(subject) if subject == pinned_subject => Ok(()),
+        Some(_) => Err(EnrollmentError::IdentityMismatch(node_id.to_string())),
+        None if require_subject => Err(EnrollmentError::IdentityRequired),
+        None => Ok(()),
+    }
+}
+
+/// A bootstrap token as listed to operators (never includes the token).
+#[derive(Debug, Clone)]
+pub struct BootstrapToken {
+    pub token_id: String,
+    pub description: Option<String>,
+    pub requires_approval: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub used_by_node_id: Option<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for BootstrapToken {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            token_id: row.try_get("token_id")?,
+            description: row.try_get("description")?,
+            requires_approval: row.try_get("requires_approval")?,
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            expires_at: row.try_get("expires_at")?,
+            used_at: row.try_get("used_at")?,
+            used_by_node_id: row.try_get("used_by_node_id")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+}
+
+const BOOTSTRAP_TOKEN_COLUMNS: &str = "token_id, description, requires_approval, created_by, \
+     created_at, expires_at, used_at, used_by_node_id, revoked_at";
+
+/// Mint a bootstrap token, returning the token (shown once) and its record.
+pub async fn create_bootstrap_token(
+    pool: &PgPool,
+    description: Option<&str>,
+    requires_approval: bool,
+    ttl: Duration,
+    created_by: &str,
+) -> Result<(String, BootstrapToken), sqlx::Error> {
+    let token = tokens::generate_node_bootstrap_token();
+    let token_id = format!("nbt_{}", Ulid::new());
+    let record: BootstrapToken = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO node_bootstrap_tokens (
+            token_id, token_hash, description, requires_approval, created_by, expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5, now() + make_interval(secs => $6))
+        RETURNING {BOOTSTRAP_TOKEN_COLUMNS}
+        "#
+    ))
+    .bind(&token_id)
+    .bind(tokens::hash_token(&token))
+    .bind(description)
+    .bind(requires_approval)
+    .bind(created_by)
+    .bind(ttl.num_seconds() as f64)
+    .fetch_one(pool)
+    .await?;
+    Ok((token, record))
+}
+
+/// All bootstrap tokens, newest first.
+pub async fn list_bootstrap_tokens(pool: &PgPool) -> Result<Vec<BootstrapToken>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {BOOTSTRAP_TOKEN_COLUMNS} FROM node_bootstrap_tokens ORDER BY created_at DESC"
+    ))
+    .fetch_all(pool)
+    .await
+}
+
+/// Revoke an unused bootstrap token. Returns false if no such unused,
+/// unrevoked token exists.
+pub async fn revoke_bootstrap_token(pool: &PgPool, token_id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE node_bootstrap_tokens
+        SET revoked_at = now()
+        WHERE token_id = $1 AND used_at IS NULL AND revoked_at IS NULL
+        "#,
+    )
+    .bind(token_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrollment_mode_parse() {
+        assert_eq!(EnrollmentMode::parse("open"), Some(EnrollmentMode::Open));
+        assert_eq!(
+            EnrollmentMode::parse(" Token "),
+            Some(EnrollmentMode::Token)
+        );
+        assert_eq!(
+            EnrollmentMode::parse("APPROVAL"),
+            Some(EnrollmentMode::Approval)
+        );
+        assert_eq!(EnrollmentMode::parse("closed"), None);
+    }
+
+    #[test]
+    fn test_check_node_state() {
+        assert!(matches!(
+            check_node(
+                "node_1",
+                STATE_PENDING_APPROVAL,
+                "CN=a",
+                Some("CN=a"),
+                false
+            ),
+            Err(EnrollmentError::PendingApproval(_))
+        ));
+        assert!(matches!(
+            check_node("node_1", STATE_DENIED, "CN=a", None, false),
+            Err(EnrollmentError::Denied(_))
+        ));
+        assert!(check_node("node_1", "draining", "CN=a", None, false).is_ok());
+    }
+
+    #[test]
+    fn test_check_node_subject_pinning() {
+        assert!(check_node("node_1", "active", "CN=a", Some("CN=a"), true).is_ok());
+        assert!(matches!(
+            check_node("node_1", "active", "CN=a", Some("CN=b"), false),
+            Err(EnrollmentError::IdentityMismatch(_))
+        ));
+        assert!(check_node("node_1", "active", "CN=a", None, false).is_ok());
+        assert!(matches!(
+            check_node("node_1", "active", "CN=a", Some("  "), true),
+            Err(EnrollmentError::IdentityRequired)
+        ));
+    }
+
+    #[test]
+    fn test_check_enrollment_subject() {
+        assert!(check_subject("CN=a", Some("CN=a"), true).is_ok());
+        assert!(matches!(
+            check_subject("CN=a", Some("CN=b"), false),
+            Err(EnrollmentError::SubjectMismatch)
+        ));
+        assert!(check_subject("CN=a", None, false).is_ok());
+        assert!(matches!(
+            check_subject("CN=a", None, true),
+            Err(EnrollmentError::IdentityRequired)
+        ));
+    }
+}
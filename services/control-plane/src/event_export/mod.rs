@@ -0,0 +1,230 @@
+//! Event log export to Kafka or NATS.
+//!
+//! The exporter (leader only, see [`worker`]) follows the event log and
+//! publishes every event, envelope and JSON payload, to one configured sink
+//! so downstream data platforms can consume platform activity:
+//! - `kafka`: records on one topic through a Kafka REST Proxy, keyed by the
+//!   routing key so each org's events share a partition and stay in order
+//! - `nats`: JetStream messages on `<subject prefix>.<routing key>.<event
+//!   type>`, so consumers can subscribe to one org with
+//!   `<prefix>.<org_id>.>`
+//!
+//! The routing key is the event's org ID, or `platform` for events outside
+//! any org (node and platform administration events).
+//!
+//! Delivery is at least once: the cursor in `event_export_cursor` advances
+//! only after the sink acknowledged every event up to it, so a failed or
+//! interrupted pass republishes from the last checkpoint, and an event that
+//! commits after the cursor passed its ID is published late rather than
+//! skipped (see [`worker`]). Each message
+//! carries the event ID for consumers to deduplicate (NATS also sets
+//! `Nats-Msg-Id`, so JetStream drops duplicates within its window).
+//!
+//! Configuration:
+//! - `PLFM_EVENT_EXPORT_SINK`: `kafka` or `nats` (unset: export is off)
+//! - `PLFM_EVENT_EXPORT_START`: where a new cursor starts, `latest`
+//!   (default) or `earliest` to replay the whole log once
+//! - Kafka: `PLFM_EVENT_EXPORT_KAFKA_REST_URL`, `PLFM_EVENT_EXPORT_KAFKA_TOPIC`
+//!   (default `plfm.events`), `PLFM_EVENT_EXPORT_KAFKA_USERNAME`,
+//!   `PLFM_EVENT_EXPORT_KAFKA_PASSWORD`
+//! - NATS: `PLFM_EVENT_EXPORT_NATS_URL`, `PLFM_EVENT_EXPORT_NATS_SUBJECT`
+//!   (default `plfm.events`), `PLFM_EVENT_EXPORT_NATS_TOKEN` or
+//!   `PLFM_EVENT_EXPORT_NATS_CREDS_FILE`
+//!
+//! See: docs/specs/state/event-export.md
+
+pub mod sinks;
+pub mod worker;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::db::{DbError, EventRow};
+
+/// Routing key of events that belong to no org.
+pub const PLATFORM_ROUTING_KEY: &str = "platform";
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("event export configuration: {0}")]
+    Config(String),
+    #[error("{sink}: {message}")]
+    Sink { sink: &'static str, message: String },
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Where the cursor starts the first time the exporter runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartPosition {
+    /// The end of the log: only events appended from now on are exported.
+    #[default]
+    Latest,
+    /// The start of the log: every event is exported.
+    Earliest,
+}
+
+impl StartPosition {
+    pub fn parse(value: &str) -> Result<Self, ExportError> {
+        match value {
+            "latest" => Ok(Self::Latest),
+            "earliest" => Ok(Self::Earliest),
+            other => Err(ExportError::Config(format!(
+                "invalid PLFM_EVENT_EXPORT_START '{other}'"
+            ))),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, ExportError> {
+        env("PLFM_EVENT_EXPORT_START")
+            .map(|v| Self::parse(&v))
+            .unwrap_or(Ok(Self::Latest))
+    }
+}
+
+/// One event as published to a sink.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportMessage {
+    pub event_id: i64,
+    pub event_type: String,
+    /// The org ID, or [`PLATFORM_ROUTING_KEY`].
+    pub routing_key: String,
+    /// `{"envelope": {...}, "payload": {...}}`
+    pub body: Value,
+}
+
+impl ExportMessage {
+    pub fn from_event(event: &EventRow) -> Self {
+        let envelope = json!({
+            "event_id": event.event_id,
+            "occurred_at": event.occurred_at,
+            "event_type": event.event_type,
+            "event_version": event.event_version,
+            "org_id": event.org_id,
+            "app_id": event.app_id,
+            "env_id": event.env_id,
+            "aggregate_type": event.aggregate_type,
+            "aggregate_id": event.aggregate_id,
+            "aggregate_seq": event.aggregate_seq,
+            "actor_type": event.actor_type,
+            "actor_id": event.actor_id,
+            "request_id": event.request_id,
+            "correlation_id": event.correlation_id,
+            "causation_id": event.causation_id,
+            "traceparent": event.traceparent,
+        });
+        Self {
+            event_id: event.event_id,
+            event_type: event.event_type.clone(),
+            routing_key: event
+                .org_id
+                .clone()
+                .unwrap_or_else(|| PLATFORM_ROUTING_KEY.to_string()),
+            body: json!({
+                "envelope": envelope,
+                "payload": event.payload,
+            }),
+        }
+    }
+}
+
+/// A destination for exported events.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Stable sink name, used in logs and errors.
+    fn name(&self) -> &'static str;
+
+    /// Publish `messages` in order. Returns once the sink has acknowledged
+    /// all of them; on error, any of them may or may not have been stored.
+    async fn publish(&self, messages: &[ExportMessage]) -> Result<(), ExportError>;
+}
+
+/// The sink named by `PLFM_EVENT_EXPORT_SINK`, or `None` when export is off.
+pub async fn sink_from_env() -> Result<Option<Arc<dyn EventSink>>, ExportError> {
+    match env("PLFM_EVENT_EXPORT_SINK").as_deref() {
+        None => Ok(None),
+        Some("kafka") => Ok(Some(Arc::new(sinks::KafkaRest::from_env()?))),
+        Some("nats") => Ok(Some(Arc::new(sinks::Nats::from_env().await?))),
+        Some(other) => Err(ExportError::Config(format!(
+            "unknown PLFM_EVENT_EXPORT_SINK '{other}'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    pub(crate) fn event(event_id: i64, org_id: Option<&str>) -> EventRow {
+        EventRow {
+            event_id,
+            occurred_at: Utc::now(),
+            aggregate_type: "instance".to_string(),
+            aggregate_id: "inst_1".to_string(),
+            aggregate_seq: 3,
+            event_type: "instance.status_changed".to_string(),
+            event_version: 1,
+            actor_type: "system".to_string(),
+            actor_id: "node_1".to_string(),
+            org_id: org_id.map(str::to_string),
+            request_id: "req_1".to_string(),
+            idempotency_key: None,
+            app_id: Some("app_1".to_string()),
+            env_id: Some("env_1".to_string()),
+            correlation_id: None,
+            causation_id: Some(4),
+            payload: json!({"instance_id": "inst_1", "status": "ready"}),
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_message_from_event() {
+        let message = ExportMessage::from_event(&event(9, Some("org_1")));
+
+        assert_eq!(message.event_id, 9);
+        assert_eq!(message.routing_key, "org_1");
+        assert_eq!(message.body["envelope"]["event_id"], 9);
+        assert_eq!(message.body["envelope"]["aggregate_seq"], 3);
+        assert_eq!(message.body["envelope"]["causation_id"], 4);
+        assert_eq!(message.body["payload"]["status"], "ready");
+    }
+
+    #[test]
+    fn test_events_without_org_use_platform_key() {
+        let message = ExportMessage::from_event(&event(9, None));
+        assert_eq!(message.routing_key, PLATFORM_ROUTING_KEY);
+        assert!(message.body["envelope"]["org_id"].is_null());
+    }
+
+    #[test]
+    fn test_start_position() {
+        assert_eq!(
+            StartPosition::parse("latest").unwrap(),
+            StartPosition::Latest
+        );
+        assert_eq!(
+            StartPosition::parse("earliest").unwrap(),
+            StartPosition::Earliest
+        );
+        assert!(StartPosition::parse("beginning").is_err());
+    }
+}
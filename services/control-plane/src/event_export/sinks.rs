@@ -0,0 +1,279 @@
+//! Event export sinks.
+//!
+//! - `kafka`: produces through a Kafka REST Proxy (v2 API), one request per
+//!   batch. Every record in the response must carry an offset.
+//! - `nats`: publishes to JetStream and waits for the stream's ack of every
+//!   message. A stream must capture the subjects, or publishes fail with
+//!   "no responders".
+
+use std::time::Duration;
+
+use async_nats::jetstream;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{env, EventSink, ExportError, ExportMessage};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const DEFAULT_TOPIC: &str = "plfm.events";
+const DEFAULT_SUBJECT_PREFIX: &str = "plfm.events";
+
+fn require_env(name: &str) -> Result<String, ExportError> {
+    env(name).ok_or_else(|| ExportError::Config(format!("{name} is not set")))
+}
+
+fn sink_error(sink: &'static str, message: impl Into<String>) -> ExportError {
+    ExportError::Sink {
+        sink,
+        message: message.into(),
+    }
+}
+
+// =============================================================================
+// Kafka (REST Proxy)
+// =============================================================================
+
+const KAFKA: &str = "kafka";
+
+const KAFKA_CONTENT_TYPE: &str = "application/vnd.kafka.json.v2+json";
+const KAFKA_ACCEPT: &str = "application/vnd.kafka.v2+json";
+
+pub struct KafkaRest {
+    url: String,
+    credentials: Option<(String, String)>,
+    client: reqwest::Client,
+}
+
+impl KafkaRest {
+    pub fn from_env() -> Result<Self, ExportError> {
+        let base = require_env("PLFM_EVENT_EXPORT_KAFKA_REST_URL")?;
+        let topic =
+            env("PLFM_EVENT_EXPORT_KAFKA_TOPIC").unwrap_or_else(|| DEFAULT_TOPIC.to_string());
+        let credentials = match env("PLFM_EVENT_EXPORT_KAFKA_USERNAME") {
+            Some(username) => Some((username, require_env("PLFM_EVENT_EXPORT_KAFKA_PASSWORD")?)),
+            None => None,
+        };
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ExportError::Config(format!("kafka HTTP client: {e}")))?;
+        Ok(Self {
+            url: format!("{}/topics/{}", base.trim_end_matches('/'), topic),
+            credentials,
+            client,
+        })
+    }
+}
+
+/// Records of a produce request, keyed by routing key.
+fn kafka_records(messages: &[ExportMessage]) -> Value {
+    let records: Vec<Value> = messages
+        .iter()
+        .map(|m| json!({ "key": m.routing_key, "value": m.body }))
+        .collect();
+    json!({ "records": records })
+}
+
+#[derive(Debug, Deserialize)]
+struct ProduceResponse {
+    offsets: Vec<ProduceOffset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProduceOffset {
+    #[serde(default)]
+    error_code: Option<i64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Check that the proxy stored all `expected` records.
+fn check_produce_response(response: &ProduceResponse, expected: usize) -> Result<(), String> {
+    if let Some(failed) = response.offsets.iter().find(|o| o.error_code.is_some()) {
+        return Err(format!(
+            "record rejected (error_code {}): {}",
+            failed.error_code.unwrap_or_default(),
+            failed.error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+    if response.offsets.len() != expected {
+        return Err(format!(
+            "{} of {} records acknowledged",
+            response.offsets.len(),
+            expected
+        ));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl EventSink for KafkaRest {
+    fn name(&self) -> &'static str {
+        KAFKA
+    }
+
+    async fn publish(&self, messages: &[ExportMessage]) -> Result<(), ExportError> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_CONTENT_TYPE)
+            .header(reqwest::header::ACCEPT, KAFKA_ACCEPT)
+            .json(&kafka_records(messages));
+        if let Some((username, password)) = &self.credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| sink_error(KAFKA, e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(sink_error(
+                KAFKA,
+                format!("produce failed with {status}: {body}"),
+            ));
+        }
+        let response: ProduceResponse = response
+            .json()
+            .await
+            .map_err(|e| sink_error(KAFKA, format!("invalid produce response: {e}")))?;
+        check_produce_response(&response, messages.len()).map_err(|e| sink_error(KAFKA, e))
+    }
+}
+
+// =============================================================================
+// NATS (JetStream)
+// =============================================================================
+
+const NATS: &str = "nats";
+
+pub struct Nats {
+    subject_prefix: String,
+    jetstream: jetstream::Context,
+}
+
+impl Nats {
+    /// Connect to `PLFM_EVENT_EXPORT_NATS_URL`. The client reconnects on its
+    /// own after the initial connection.
+    pub async fn from_env() -> Result<Self, ExportError> {
+        let url = require_env("PLFM_EVENT_EXPORT_NATS_URL")?;
+        let subject_prefix = env("PLFM_EVENT_EXPORT_NATS_SUBJECT")
+            .unwrap_or_else(|| DEFAULT_SUBJECT_PREFIX.to_string());
+
+        let mut options = async_nats::ConnectOptions::new()
+            .name("plfm-control-plane-event-export")
+            .retry_on_initial_connect();
+        if let Some(path) = env("PLFM_EVENT_EXPORT_NATS_CREDS_FILE") {
+            options = options
+                .credentials_file(&path)
+                .await
+                .map_err(|e| ExportError::Config(format!("NATS credentials file {path}: {e}")))?;
+        } else if let Some(token) = env("PLFM_EVENT_EXPORT_NATS_TOKEN") {
+            options = options.token(token);
+        }
+        let client = async_nats::connect_with_options(url.as_str(), options)
+            .await
+            .map_err(|e| sink_error(NATS, format!("connect to {url}: {e}")))?;
+
+        Ok(Self {
+            subject_prefix,
+            jetstream: jetstream::new(client),
+        })
+    }
+}
+
+/// `<prefix>.<routing key>.<event type>`
+fn nats_subject(prefix: &str, message: &ExportMessage) -> String {
+    format!("{prefix}.{}.{}", message.routing_key, message.event_type)
+}
+
+#[async_trait]
+impl EventSink for Nats {
+    fn name(&self) -> &'static str {
+        NATS
+    }
+
+    async fn publish(&self, messages: &[ExportMessage]) -> Result<(), ExportError> {
+        // Send everything, then wait for the acks, instead of a round trip
+        // per message.
+        let mut acks = Vec::with_capacity(messages.len());
+        for message in messages {
+            let mut headers = async_nats::HeaderMap::new();
+            headers.insert(
+                async_nats::header::NATS_MESSAGE_ID,
+                message.event_id.to_string().as_str(),
+            );
+            let payload = serde_json::to_vec(&message.body)
+                .map_err(|e| sink_error(NATS, format!("encode event: {e}")))?;
+            let ack = self
+                .jetstream
+                .publish_with_headers(
+                    nats_subject(&self.subject_prefix, message),
+                    headers,
+                    payload.into(),
+                )
+                .await
+                .map_err(|e| sink_error(NATS, format!("publish: {e}")))?;
+            acks.push(ack);
+        }
+        for ack in acks {
+            ack.await
+                .map_err(|e| sink_error(NATS, format!("publish not acknowledged: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_export::tests::event;
+
+    #[test]
+    fn test_kafka_records_are_keyed_by_org() {
+        let messages = vec![
+            ExportMessage::from_event(&event(1, Some("org_1"))),
+            ExportMessage::from_event(&event(2, None)),
+        ];
+        let body = kafka_records(&messages);
+
+        assert_eq!(body["records"][0]["key"], "org_1");
+        assert_eq!(body["records"][0]["value"]["envelope"]["event_id"], 1);
+        assert_eq!(body["records"][1]["key"], "platform");
+    }
+
+    #[test]
+    fn test_check_produce_response() {
+        let response: ProduceResponse = serde_json::from_value(json!({
+            "offsets": [
+                {"partition": 0, "offset": 10, "error_code": null, "error": null},
+                {"partition": 1, "offset": 4, "error_code": null, "error": null},
+            ]
+        }))
+        .unwrap();
+        assert!(check_produce_response(&response, 2).is_ok());
+        assert!(check_produce_response(&response, 3).is_err());
+
+        let response: ProduceResponse = serde_json::from_value(json!({
+            "offsets": [
+                {"partition": 0, "offset": 10},
+                {"partition": null, "offset": null, "error_code": 50002, "error": "Kafka error"},
+            ]
+        }))
+        .unwrap();
+        let error = check_produce_response(&response, 2).unwrap_err();
+        assert!(error.contains("50002"), "{error}");
+    }
+
+    #[test]
+    fn test_nats_subject() {
+        let message = ExportMessage::from_event(&event(1, Some("org_1")));
+        assert_eq!(
+            nats_subject("plfm.events", &message),
+            "plfm.events.org_1.instance.status_changed"
+        );
+    }
+}
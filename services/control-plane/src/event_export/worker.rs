@@ -0,0 +1,228 @@
+//! The exporter: follows the event log and publishes it to the sink.
+//!
+//! Each pass, on the elected leader only, reads the events after
+//! `event_export_cursor`, publishes them in batches, and advances the cursor
+//! past each batch the sink acknowledged. A full batch is followed by the
+//! next one right away, so a backlog drains without waiting for the next
+//! tick. A failed batch is retried from the cursor on the next tick.
+//!
+//! Event IDs are assigned before commit, so an append may become visible
+//! after the cursor moved past its ID. IDs missing below the cursor are
+//! recorded in `event_export_gaps` when the cursor passes them and looked up
+//! again on every pass; an event that turns up is published then. A gap
+//! still empty after `GAP_TIMEOUT` is an append that rolled back and is
+//! dropped.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use super::{sink_from_env, EventSink, ExportError, ExportMessage, StartPosition};
+use crate::db::EventStore;
+use crate::leader::{LeaderElection, LeaderRole};
+
+/// Events read and published per batch.
+const EVENT_BATCH: i32 = 500;
+
+/// How long a missing event ID is looked for. Longer than any append
+/// transaction stays open.
+const GAP_TIMEOUT: Duration = Duration::from_secs(600);
+
+async fn load_cursor(pool: &PgPool, start: StartPosition) -> Result<i64, sqlx::Error> {
+    let initial = match start {
+        StartPosition::Latest => "SELECT true, COALESCE(MAX(event_id), 0) FROM events",
+        StartPosition::Earliest => "SELECT true, 0::BIGINT",
+    };
+    sqlx::query(&format!(
+        "INSERT INTO event_export_cursor (singleton, last_event_id) {initial} \
+         ON CONFLICT (singleton) DO NOTHING"
+    ))
+    .execute(pool)
+    .await?;
+    sqlx::query_scalar("SELECT last_event_id FROM event_export_cursor")
+        .fetch_one(pool)
+        .await
+}
+
+/// Publish the events that filled gaps and the next batch after the
+/// cursor, then advance the cursor past the batch. Returns the number of
+/// events exported.
+pub async fn export_batch(
+    pool: &PgPool,
+    sink: &dyn EventSink,
+    start: StartPosition,
+) -> Result<usize, ExportError> {
+    let cursor = load_cursor(pool, start).await?;
+    let store = EventStore::new(pool.clone());
+
+    sqlx::query(
+        "DELETE FROM event_export_gaps g \
+         WHERE g.noticed_at < now() - make_interval(secs => $1) \
+           AND NOT EXISTS (SELECT 1 FROM events e WHERE e.event_id = g.event_id)",
+    )
+    .bind(GAP_TIMEOUT.as_secs_f64())
+    .execute(pool)
+    .await?;
+    let gap_ids: Vec<i64> =
+        sqlx::query_scalar("SELECT event_id FROM event_export_gaps ORDER BY event_id")
+            .fetch_all(pool)
+            .await?;
+    let filled = store.query_by_ids(&gap_ids).await?;
+    let events = store.query_after_cursor(cursor, EVENT_BATCH).await?;
+    if filled.is_empty() && events.is_empty() {
+        return Ok(0);
+    }
+
+    let messages: Vec<ExportMessage> = filled
+        .iter()
+        .chain(&events)
+        .map(ExportMessage::from_event)
+        .collect();
+    sink.publish(&messages).await?;
+
+    let filled_ids: Vec<i64> = filled.iter().map(|e| e.event_id).collect();
+    let event_ids: Vec<i64> = events.iter().map(|e| e.event_id).collect();
+    let last_event_id = event_ids.last().copied().unwrap_or(cursor);
+
+    let mut tx = pool.begin().await?;
+    let advanced = sqlx::query(
+        "UPDATE event_export_cursor SET last_event_id = $1, sink = $2, updated_at = now() \
+         WHERE last_event_id = $3",
+    )
+    .bind(last_event_id)
+    .bind(sink.name())
+    .bind(cursor)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if advanced == 0 {
+        // The cursor was moved (reset for a replay) while the batch was in
+        // flight; the gaps are of the old position.
+        return Ok(messages.len());
+    }
+    sqlx::query("DELETE FROM event_export_gaps WHERE event_id = ANY($1)")
+        .bind(&filled_ids)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO event_export_gaps (event_id) SELECT unnest($1::BIGINT[]) \
+         ON CONFLICT (event_id) DO NOTHING",
+    )
+    .bind(missing_ids(cursor, &event_ids))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(messages.len())
+}
+
+/// IDs in `(cursor, last of event_ids]` that are not in `event_ids`
+/// (ascending).
+fn missing_ids(cursor: i64, event_ids: &[i64]) -> Vec<i64> {
+    let mut missing = Vec::new();
+    let mut expected = cursor + 1;
+    for &event_id in event_ids {
+        missing.extend(expected..event_id);
+        expected = event_id + 1;
+    }
+    missing
+}
+
+/// Background worker exporting the event log, on the elected leader only.
+/// Idle unless `PLFM_EVENT_EXPORT_SINK` is set.
+pub struct EventExportWorker {
+    pool: PgPool,
+    interval: Duration,
+    election: LeaderElection,
+}
+
+impl EventExportWorker {
+    pub fn new(pool: PgPool, interval: Duration) -> Self {
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::EventExporter, interval * 3),
+            pool,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        let (sink, start) = match (sink_from_env().await, StartPosition::from_env()) {
+            (Ok(Some(sink)), Ok(start)) => (sink, start),
+            (Ok(None), _) => return,
+            (Err(e), _) | (_, Err(e)) => {
+                error!(error = %e, "Event export is not configured; events will not be exported");
+                return;
+            }
+        };
+        info!(
+            interval_secs = self.interval.as_secs(),
+            sink = sink.name(),
+            "Starting event export worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    self.drain(&sink, start, &shutdown).await;
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Event export worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Export batches until the log is caught up, a batch fails, or the
+    /// worker is shutting down.
+    async fn drain(
+        &self,
+        sink: &Arc<dyn EventSink>,
+        start: StartPosition,
+        shutdown: &watch::Receiver<bool>,
+    ) {
+        let mut exported = 0;
+        while !*shutdown.borrow() {
+            match export_batch(&self.pool, sink.as_ref(), start).await {
+                Ok(count) => {
+                    exported += count;
+                    if count < EVENT_BATCH as usize {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, sink = sink.name(), "Event export failed; retrying from the last checkpoint");
+                    break;
+                }
+            }
+        }
+        if exported > 0 {
+            info!(exported, sink = sink.name(), "Exported events");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_ids() {
+        assert_eq!(missing_ids(10, &[11, 12, 13]), Vec::<i64>::new());
+        assert_eq!(missing_ids(10, &[12, 15]), vec![11, 13, 14]);
+        assert_eq!(missing_ids(10, &[]), Vec::<i64>::new());
+    }
+}
@@ -0,0 +1,707 @@
+//! Event log checkpoints and integrity verification.
+//!
+//! Per-aggregate hash chains (see [`crate::db::event_chain`]) catch edited,
+//! inserted, and deleted events except at the tail of an aggregate. The
+//! checkpointer (leader only) closes that hole: every few minutes it seals
+//! the events appended since the last checkpoint with a Merkle root over
+//! their hashes, chained to the previous checkpoint's root and signed as a
+//! JWS with the control plane's signing key (the identity token key, so the
+//! JWKS at `/v1/pki/jwks` verifies checkpoints while their key is current).
+//!
+//! Events younger than [`SETTLE_DELAY`] wait for the next pass, so a
+//! transaction that took an event_id and commits late is still covered.
+//!
+//! [`verify_page`] checks hashes, chain links, aggregate sequence gaps,
+//! checkpoint roots and signatures over a range of the log; operators run it
+//! with `plfm-admin events verify` (`GET /v1/_admin/events/verify`).
+//!
+//! See: docs/specs/state/event-log.md (Integrity)
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_pki::PkiError;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{error, info, instrument};
+
+use crate::db::{merkle_root, HashedEvent, HASHED_EVENT_COLUMNS};
+use crate::identity_tokens;
+use crate::leader::{LeaderElection, LeaderRole};
+use crate::pki::CaError;
+
+/// Events must be this old before a checkpoint covers them.
+pub const SETTLE_DELAY: Duration = Duration::seconds(60);
+
+/// Most events sealed by one checkpoint.
+const MAX_CHECKPOINT_EVENTS: i64 = 10_000;
+
+/// JWS `typ` of checkpoint signatures.
+const CHECKPOINT_JWS_TYPE: &str = "plfm-event-checkpoint+jwt";
+
+#[derive(Debug, Error)]
+pub enum IntegrityError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("signing key: {0}")]
+    Signing(#[from] CaError),
+}
+
+// =============================================================================
+// Rows
+// =============================================================================
+
+/// An event with its stored hashes and its aggregate predecessor.
+#[derive(Debug, Clone)]
+struct ChainRow {
+    event_id: i64,
+    prev_hash: Option<Vec<u8>>,
+    event_hash: Option<Vec<u8>>,
+    event: HashedEvent,
+    /// aggregate_seq and event_hash of the aggregate's previous event.
+    prev_seq: Option<i32>,
+    prev_event_hash: Option<Vec<u8>>,
+}
+
+impl<'r> FromRow<'r, PgRow> for ChainRow {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            event_id: row.try_get("event_id")?,
+            prev_hash: row.try_get("prev_hash")?,
+            event_hash: row.try_get("event_hash")?,
+            event: HashedEvent::from_row(row)?,
+            prev_seq: row.try_get("prev_seq")?,
+            prev_event_hash: row.try_get("prev_event_hash")?,
+        })
+    }
+}
+
+impl ChainRow {
+    /// Checkpoint leaf: the hash recomputed from the row as it is now.
+    fn leaf(&self) -> Vec<u8> {
+        self.event.hash(self.prev_hash.as_deref())
+    }
+}
+
+/// Events in `(after_event_id, to_event_id]`, oldest first, with the
+/// previous event of each one's aggregate.
+async fn load_rows(
+    pool: &PgPool,
+    after_event_id: i64,
+    to_event_id: i64,
+    limit: i64,
+) -> Result<Vec<ChainRow>, sqlx::Error> {
+    let query = format!(
+        r#"
+        SELECT event_id, prev_hash, event_hash, {HASHED_EVENT_COLUMNS},
+               p.prev_seq, p.prev_event_hash
+        FROM events e
+        LEFT JOIN LATERAL (
+            SELECT prev.aggregate_seq AS prev_seq, prev.event_hash AS prev_event_hash
+            FROM events prev
+            WHERE prev.aggregate_type = e.aggregate_type
+              AND prev.aggregate_id = e.aggregate_id
+              AND prev.aggregate_seq < e.aggregate_seq
+            ORDER BY prev.aggregate_seq DESC
+            LIMIT 1
+        ) p ON true
+        WHERE e.event_id > $1 AND e.event_id <= $2
+        ORDER BY e.event_id
+        LIMIT $3
+        "#
+    );
+    sqlx::query_as::<_, ChainRow>(&query)
+        .bind(after_event_id)
+        .bind(to_event_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// A sealed range of the log.
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct CheckpointRow {
+    checkpoint_id: i64,
+    from_event_id: i64,
+    to_event_id: i64,
+    event_count: i32,
+    merkle_root: Vec<u8>,
+    prev_root: Option<Vec<u8>>,
+    signature: String,
+}
+
+const CHECKPOINT_COLUMNS: &str =
+    "checkpoint_id, from_event_id, to_event_id, event_count, merkle_root, prev_root, signature";
+
+/// What a checkpoint signature attests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CheckpointStatement {
+    iss: String,
+    iat: i64,
+    /// Exclusive.
+    from_event_id: i64,
+    /// Inclusive.
+    to_event_id: i64,
+    event_count: i64,
+    merkle_root: String,
+    prev_root: Option<String>,
+}
+
+impl CheckpointStatement {
+    fn matches(&self, checkpoint: &CheckpointRow) -> bool {
+        self.from_event_id == checkpoint.from_event_id
+            && self.to_event_id == checkpoint.to_event_id
+            && self.event_count == i64::from(checkpoint.event_count)
+            && self.merkle_root == hex::encode(&checkpoint.merkle_root)
+            && self.prev_root == checkpoint.prev_root.as_ref().map(hex::encode)
+    }
+}
+
+// =============================================================================
+// Checkpoints
+// =============================================================================
+
+/// Rows of `rows` old enough to seal: everything before the first row that
+/// occurred after `cutoff`.
+fn settled(rows: &[ChainRow], cutoff: DateTime<Utc>) -> &[ChainRow] {
+    let end = rows
+        .iter()
+        .position(|row| row.event.occurred_at >= cutoff)
+        .unwrap_or(rows.len());
+    &rows[..end]
+}
+
+/// Seal settled events into checkpoints until caught up. Returns the
+/// number of checkpoints written.
+pub async fn run_checkpoint_pass(pool: &PgPool) -> Result<usize, IntegrityError> {
+    let mut written = 0;
+    loop {
+        let last: Option<(i64, Vec<u8>)> = sqlx::query_as(
+            "SELECT to_event_id, merkle_root FROM event_log_checkpoints ORDER BY to_event_id DESC LIMIT 1",
+        )
+        .fetch_optional(pool)
+        .await?;
+        let (from_event_id, prev_root) = match last {
+            Some((to, root)) => (to, Some(root)),
+            None => (0, None),
+        };
+
+        let rows = load_rows(pool, from_event_id, i64::MAX, MAX_CHECKPOINT_EVENTS).await?;
+        let now = Utc::now();
+        let sealed = settled(&rows, now - SETTLE_DELAY);
+        let Some(last_row) = sealed.last() else {
+            return Ok(written);
+        };
+
+        let root = merkle_root(sealed.iter().map(ChainRow::leaf));
+        let statement = CheckpointStatement {
+            iss: identity_tokens::issuer(),
+            iat: now.timestamp(),
+            from_event_id,
+            to_event_id: last_row.event_id,
+            event_count: sealed.len() as i64,
+            merkle_root: hex::encode(&root),
+            prev_root: prev_root.as_ref().map(hex::encode),
+        };
+        let key = identity_tokens::active_key(pool).await?;
+        let signature = key
+            .sign_jws(CHECKPOINT_JWS_TYPE, &statement)
+            .map_err(CaError::from)?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO event_log_checkpoints (
+                from_event_id, to_event_id, event_count, merkle_root, prev_root, kid, signature
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (from_event_id) DO NOTHING
+            "#,
+        )
+        .bind(from_event_id)
+        .bind(last_row.event_id)
+        .bind(sealed.len() as i32)
+        .bind(&root)
+        .bind(&prev_root)
+        .bind(key.kid())
+        .bind(&signature)
+        .execute(pool)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            // Another replica sealed this range; pick up after it.
+            continue;
+        }
+        written += 1;
+        info!(
+            from_event_id,
+            to_event_id = last_row.event_id,
+            events = sealed.len(),
+            "Event log checkpoint written"
+        );
+
+        if sealed.len() < rows.len() || (rows.len() as i64) < MAX_CHECKPOINT_EVENTS {
+            return Ok(written);
+        }
+    }
+}
+
+// =============================================================================
+// Verification
+// =============================================================================
+
+/// What verification found wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// The row no longer matches its `event_hash`: it was edited.
+    HashMismatch,
+    /// `prev_hash` is not the hash of the aggregate's previous event: an
+    /// event before it was edited, deleted, or inserted.
+    ChainBroken,
+    /// An unhashed event follows a hashed one in its aggregate.
+    HashMissing,
+    /// The aggregate's sequence skips numbers.
+    SeqGap,
+    /// The events in a checkpoint's range do not produce its root.
+    CheckpointMismatch,
+    /// A checkpoint's signature does not verify or does not match the row.
+    CheckpointSignatureInvalid,
+    /// A checkpoint does not continue from the previous one.
+    CheckpointChainBroken,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_id: Option<i64>,
+    pub detail: String,
+}
+
+impl Finding {
+    fn event(kind: FindingKind, row: &ChainRow, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            event_id: Some(row.event_id),
+            aggregate_type: Some(row.event.aggregate_type.clone()),
+            aggregate_id: Some(row.event.aggregate_id.clone()),
+            checkpoint_id: None,
+            detail: detail.into(),
+        }
+    }
+
+    fn checkpoint(
+        kind: FindingKind,
+        checkpoint: &CheckpointRow,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            event_id: None,
+            aggregate_type: None,
+            aggregate_id: None,
+            checkpoint_id: Some(checkpoint.checkpoint_id),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Result of verifying one page of the log.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub after_event_id: i64,
+    /// Where the next page starts; `None` once the end of the log is reached.
+    pub next_after_event_id: Option<i64>,
+    pub events_checked: usize,
+    /// Events written before chaining.
+    pub unchained_events: usize,
+    pub checkpoints_checked: usize,
+    /// Checked events not yet covered by a checkpoint.
+    pub uncheckpointed_events: usize,
+    pub findings: Vec<Finding>,
+}
+
+/// Hash, chain, and sequence checks of one event.
+fn check_row(row: &ChainRow) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let Some(event_hash) = row.event_hash.as_deref() else {
+        if row.prev_event_hash.is_some() {
+            findings.push(Finding::event(
+                FindingKind::HashMissing,
+                row,
+                "event has no hash but its predecessor does",
+            ));
+        }
+        return findings;
+    };
+
+    if row.event.hash(row.prev_hash.as_deref()) != event_hash {
+        findings.push(Finding::event(
+            FindingKind::HashMismatch,
+            row,
+            "row does not match its event_hash",
+        ));
+    }
+    if row.prev_hash != row.prev_event_hash {
+        findings.push(Finding::event(
+            FindingKind::ChainBroken,
+            row,
+            match row.prev_seq {
+                Some(seq) => format!("prev_hash does not match aggregate_seq {seq}"),
+                None => "prev_hash names an event that does not exist".to_string(),
+            },
+        ));
+    }
+    let expected_prev = row.event.aggregate_seq - 1;
+    match row.prev_seq {
+        Some(seq) if seq != expected_prev => findings.push(Finding::event(
+            FindingKind::SeqGap,
+            row,
+            format!("aggregate_seq {} follows {seq}", row.event.aggregate_seq),
+        )),
+        None if expected_prev > 0 => findings.push(Finding::event(
+            FindingKind::SeqGap,
+            row,
+            format!(
+                "aggregate_seq {} is the aggregate's first event",
+                row.event.aggregate_seq
+            ),
+        )),
+        _ => {}
+    }
+    findings
+}
+
+/// Root, count, and signature checks of one checkpoint over its rows.
+async fn check_checkpoint(
+    pool: &PgPool,
+    checkpoint: &CheckpointRow,
+    rows: &[ChainRow],
+    prev_root: Option<&[u8]>,
+) -> Result<Vec<Finding>, IntegrityError> {
+    let mut findings = Vec::new();
+    if rows.len() != checkpoint.event_count as usize {
+        findings.push(Finding::checkpoint(
+            FindingKind::CheckpointMismatch,
+            checkpoint,
+            format!(
+                "range holds {} events, checkpoint sealed {}",
+                rows.len(),
+                checkpoint.event_count
+            ),
+        ));
+    } else if merkle_root(rows.iter().map(ChainRow::leaf)) != checkpoint.merkle_root {
+        findings.push(Finding::checkpoint(
+            FindingKind::CheckpointMismatch,
+            checkpoint,
+            "events in range do not produce the sealed root",
+        ));
+    }
+    if checkpoint.prev_root.as_deref() != prev_root {
+        findings.push(Finding::checkpoint(
+            FindingKind::CheckpointChainBroken,
+            checkpoint,
+            "prev_root is not the previous checkpoint's root",
+        ));
+    }
+
+    let key = match plfm_pki::token_key_id(&checkpoint.signature) {
+        Ok(kid) => identity_tokens::key_by_kid(pool, &kid)
+            .await?
+            .ok_or_else(|| PkiError::InvalidToken(format!("unknown signing key {kid}"))),
+        Err(e) => Err(e),
+    };
+    let verified = key.and_then(|key| {
+        plfm_pki::verify_jws::<CheckpointStatement>(&checkpoint.signature, &[key.public_jwk()])
+            .map(|statement| statement.matches(checkpoint))
+    });
+    match verified {
+        Ok(true) => {}
+        Ok(false) => findings.push(Finding::checkpoint(
+            FindingKind::CheckpointSignatureInvalid,
+            checkpoint,
+            "signed statement does not match the checkpoint row",
+        )),
+        Err(e) => findings.push(Finding::checkpoint(
+            FindingKind::CheckpointSignatureInvalid,
+            checkpoint,
+            e.to_string(),
+        )),
+    }
+    Ok(findings)
+}
+
+/// Verify the log after `after_event_id`: whole checkpoints totalling up to
+/// `max_events` events (at least one), or, past the last checkpoint, up to
+/// `max_events` unsealed events.
+pub async fn verify_page(
+    pool: &PgPool,
+    after_event_id: i64,
+    max_events: i64,
+) -> Result<VerifyReport, IntegrityError> {
+    let mut report = VerifyReport {
+        after_event_id,
+        ..Default::default()
+    };
+
+    let candidates = sqlx::query_as::<_, CheckpointRow>(&format!(
+        "SELECT {CHECKPOINT_COLUMNS} FROM event_log_checkpoints \
+         WHERE to_event_id > $1 ORDER BY to_event_id LIMIT $2"
+    ))
+    .bind(after_event_id)
+    .bind(max_events.max(1))
+    .fetch_all(pool)
+    .await?;
+    let mut checkpoints = Vec::new();
+    let mut total = 0i64;
+    for checkpoint in candidates {
+        total += i64::from(checkpoint.event_count);
+        if !checkpoints.is_empty() && total > max_events {
+            break;
+        }
+        checkpoints.push(checkpoint);
+    }
+
+    let Some(last) = checkpoints.last() else {
+        let rows = load_rows(pool, after_event_id, i64::MAX, max_events).await?;
+        report.uncheckpointed_events = rows.len();
+        check_rows(&rows, &mut report);
+        report.next_after_event_id = match rows.last() {
+            Some(row) if rows.len() as i64 == max_events => Some(row.event_id),
+            _ => None,
+        };
+        return Ok(report);
+    };
+
+    // A page starting inside a checkpoint begins at that checkpoint instead.
+    let start = checkpoints[0].from_event_id.min(after_event_id);
+    let rows = load_rows(pool, start, last.to_event_id, i64::MAX).await?;
+    check_rows(&rows, &mut report);
+
+    let mut prev_root: Option<Vec<u8>> =
+        sqlx::query_scalar("SELECT merkle_root FROM event_log_checkpoints WHERE to_event_id = $1")
+            .bind(checkpoints[0].from_event_id)
+            .fetch_optional(pool)
+            .await?;
+    let mut prev_to = checkpoints[0].from_event_id;
+    for checkpoint in &checkpoints {
+        if checkpoint.from_event_id != prev_to {
+            report.findings.push(Finding::checkpoint(
+                FindingKind::CheckpointChainBroken,
+                checkpoint,
+                format!(
+                    "starts after event {}, not {prev_to}",
+                    checkpoint.from_event_id
+                ),
+            ));
+        }
+        let in_range: Vec<ChainRow> = rows
+            .iter()
+            .filter(|row| {
+                row.event_id > checkpoint.from_event_id && row.event_id <= checkpoint.to_event_id
+            })
+            .cloned()
+            .collect();
+        report
+            .findings
+            .extend(check_checkpoint(pool, checkpoint, &in_range, prev_root.as_deref()).await?);
+        report.checkpoints_checked += 1;
+        prev_root = Some(checkpoint.merkle_root.clone());
+        prev_to = checkpoint.to_event_id;
+    }
+
+    report.next_after_event_id = Some(last.to_event_id);
+    Ok(report)
+}
+
+fn check_rows(rows: &[ChainRow], report: &mut VerifyReport) {
+    for row in rows {
+        report.events_checked += 1;
+        if row.event_hash.is_none() {
+            report.unchained_events += 1;
+        }
+        report.findings.extend(check_row(row));
+    }
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker writing checkpoints, on the elected leader only.
+pub struct EventCheckpointWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    election: LeaderElection,
+}
+
+impl EventCheckpointWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        Self {
+            election: LeaderElection::new(
+                pool.clone(),
+                LeaderRole::EventCheckpointer,
+                interval * 3,
+            ),
+            pool,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            "Starting event checkpoint worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    if let Err(e) = run_checkpoint_pass(&self.pool).await {
+                        error!(error = %e, "Event checkpoint pass failed");
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Event checkpoint worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(event_id: i64, seq: i32, prev: Option<&ChainRow>) -> ChainRow {
+        let event = HashedEvent {
+            occurred_at: DateTime::from_timestamp(1_790_000_000 + event_id, 0).unwrap(),
+            aggregate_type: "app".to_string(),
+            aggregate_id: "app_1".to_string(),
+            aggregate_seq: seq,
+            event_type: "app.updated".to_string(),
+            event_version: 1,
+            actor_type: "user".to_string(),
+            actor_id: "usr_1".to_string(),
+            request_id: format!("req_{event_id}"),
+            payload: Some(serde_json::json!({ "n": event_id })),
+            ..Default::default()
+        };
+        let prev_hash = prev.and_then(|p| p.event_hash.clone());
+        ChainRow {
+            event_id,
+            event_hash: Some(event.hash(prev_hash.as_deref())),
+            prev_hash: prev_hash.clone(),
+            event,
+            prev_seq: prev.map(|p| p.event.aggregate_seq),
+            prev_event_hash: prev_hash,
+        }
+    }
+
+    fn kinds(row: &ChainRow) -> Vec<FindingKind> {
+        check_row(row).into_iter().map(|f| f.kind).collect()
+    }
+
+    #[test]
+    fn test_check_row_accepts_intact_chain() {
+        let first = row(1, 1, None);
+        let second = row(2, 2, Some(&first));
+        assert!(kinds(&first).is_empty());
+        assert!(kinds(&second).is_empty());
+    }
+
+    #[test]
+    fn test_check_row_detects_tampering() {
+        let first = row(1, 1, None);
+        let mut edited = row(2, 2, Some(&first));
+        edited.event.actor_id = "usr_evil".to_string();
+        assert_eq!(kinds(&edited), vec![FindingKind::HashMismatch]);
+
+        // The predecessor was rewritten (its stored hash changed).
+        let mut relinked = row(2, 2, Some(&first));
+        relinked.prev_event_hash = Some(vec![0; 32]);
+        assert_eq!(kinds(&relinked), vec![FindingKind::ChainBroken]);
+
+        // Event 2 was deleted: 3 now follows 1.
+        let second = row(2, 2, Some(&first));
+        let mut third = row(3, 3, Some(&second));
+        third.prev_seq = Some(1);
+        third.prev_event_hash = first.event_hash.clone();
+        assert_eq!(
+            kinds(&third),
+            vec![FindingKind::ChainBroken, FindingKind::SeqGap]
+        );
+
+        // The hash was stripped after chaining started.
+        let mut stripped = row(2, 2, Some(&first));
+        stripped.event_hash = None;
+        assert_eq!(kinds(&stripped), vec![FindingKind::HashMissing]);
+    }
+
+    #[test]
+    fn test_check_row_tolerates_legacy_rows() {
+        let mut legacy = row(1, 1, None);
+        legacy.event_hash = None;
+        assert!(kinds(&legacy).is_empty());
+
+        // The first chained event after legacy ones links to nothing.
+        let mut first_chained = row(2, 2, None);
+        first_chained.prev_seq = Some(1);
+        assert!(kinds(&first_chained).is_empty());
+    }
+
+    #[test]
+    fn test_settled_stops_at_first_recent_event() {
+        let first = row(1, 1, None);
+        let second = row(2, 2, Some(&first));
+        let third = row(3, 3, Some(&second));
+        let rows = vec![first, second.clone(), third];
+
+        assert_eq!(settled(&rows, second.event.occurred_at).len(), 1);
+        assert_eq!(settled(&rows, Utc::now()).len(), 3);
+        assert!(settled(&rows, DateTime::UNIX_EPOCH).is_empty());
+    }
+
+    #[test]
+    fn test_statement_matches_row() {
+        let checkpoint = CheckpointRow {
+            checkpoint_id: 1,
+            from_event_id: 0,
+            to_event_id: 42,
+            event_count: 40,
+            merkle_root: vec![0xab; 32],
+            prev_root: None,
+            signature: String::new(),
+        };
+        let mut statement = CheckpointStatement {
+            iss: "spiffe://plfm.internal".to_string(),
+            iat: 0,
+            from_event_id: 0,
+            to_event_id: 42,
+            event_count: 40,
+            merkle_root: hex::encode([0xab; 32]),
+            prev_root: None,
+        };
+        assert!(statement.matches(&checkpoint));
+        statement.event_count = 41;
+        assert!(!statement.matches(&checkpoint));
+    }
+}
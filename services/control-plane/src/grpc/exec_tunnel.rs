@@ -0,0 +1,357 @@
+//! Registry of exec tunnels opened by node agents.
+//!
+//! Each agent keeps one `ExecTunnel` stream open to the control plane. Exec
+//! sessions are multiplexed over it by session ID: the WebSocket handler
+//! opens a session here and exchanges exec frames with the agent through the
+//! tunnel, so no inbound connection to the node is needed.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use plfm_proto::agent::v1::{
+    exec_tunnel_server_message::Body, ExecTunnelClose, ExecTunnelOpen, ExecTunnelServerMessage,
+};
+use tokio::sync::mpsc;
+use tonic::Status;
+
+/// Messages buffered per direction before senders wait.
+const CHANNEL_CAPACITY: usize = 64;
+
+type Outbound = mpsc::Sender<Result<ExecTunnelServerMessage, Status>>;
+
+/// Exec tunnels keyed by node ID.
+#[derive(Clone, Default)]
+pub struct ExecTunnels {
+    inner: Arc<Mutex<Registry>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_generation: u64,
+    tunnels: HashMap<String, Tunnel>,
+}
+
+struct Tunnel {
+    /// Distinguishes a reconnected tunnel from the one it replaced.
+    generation: u64,
+    outbound: Outbound,
+    sessions: HashMap<String, mpsc::Sender<Vec<u8>>>,
+}
+
+impl ExecTunnels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tunnel for a node, replacing any previous one.
+    ///
+    /// Returns the registration (which routes agent frames to sessions and
+    /// unregisters the tunnel when dropped) and the receiver feeding the
+    /// agent's response stream. Sessions on a replaced tunnel end.
+    pub fn register(
+        &self,
+        node_id: &str,
+    ) -> (
+        TunnelRegistration,
+        mpsc::Receiver<Result<ExecTunnelServerMessage, Status>>,
+    ) {
+        let (outbound, outbound_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let mut registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        registry.next_generation += 1;
+        let generation = registry.next_generation;
+        registry.tunnels.insert(
+            node_id.to_string(),
+            Tunnel {
+                generation,
+                outbound,
+                sessions: HashMap::new(),
+            },
+        );
+
+        let registration = TunnelRegistration {
+            tunnels: self.clone(),
+            node_id: node_id.to_string(),
+            generation,
+        };
+        (registration, outbound_rx)
+    }
+
+    /// Whether the node currently has a tunnel open.
+    pub fn is_connected(&self, node_id: &str) -> bool {
+        let registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        registry.tunnels.contains_key(node_id)
+    }
+
+    /// Number of open tunnels.
+    pub fn len(&self) -> usize {
+        let registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        registry.tunnels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Start an exec session on the node's tunnel.
+    ///
+    /// Returns `None` when the node has no tunnel or it closed before the
+    /// open request could be sent.
+    pub async fn open_session(
+        &self,
+        node_id: &str,
+        session_id: &str,
+        open: ExecTunnelOpen,
+    ) -> Option<ExecTunnelSession> {
+        let (frames_tx, frames_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (generation, outbound) = {
+            let mut registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            let tunnel = registry.tunnels.get_mut(node_id)?;
+            tunnel.sessions.insert(session_id.to_string(), frames_tx);
+            (tunnel.generation, tunnel.outbound.clone())
+        };
+
+        let guard = Arc::new(SessionGuard {
+            tunnels: self.clone(),
+            node_id: node_id.to_string(),
+            session_id: session_id.to_string(),
+            generation,
+            outbound: outbound.clone(),
+        });
+
+        let message = server_message(session_id, Body::Open(open));
+        if outbound.send(Ok(message)).await.is_err() {
+            return None;
+        }
+
+        Some(ExecTunnelSession {
+            reader: ExecTunnelReader {
+                frames: frames_rx,
+                _guard: Arc::clone(&guard),
+            },
+            writer: ExecTunnelWriter { outbound, guard },
+        })
+    }
+
+    fn unregister(&self, node_id: &str, generation: u64) {
+        let mut registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if registry
+            .tunnels
+            .get(node_id)
+            .is_some_and(|tunnel| tunnel.generation == generation)
+        {
+            registry.tunnels.remove(node_id);
+        }
+    }
+
+    fn session_sender(
+        &self,
+        node_id: &str,
+        generation: u64,
+        session_id: &str,
+    ) -> Option<mpsc::Sender<Vec<u8>>> {
+        let registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        registry
+            .tunnels
+            .get(node_id)
+            .filter(|tunnel| tunnel.generation == generation)
+            .and_then(|tunnel| tunnel.sessions.get(session_id).cloned())
+    }
+
+    fn remove_session(&self, node_id: &str, generation: u64, session_id: &str) {
+        let mut registry = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(tunnel) = registry.tunnels.get_mut(node_id) {
+            if tunnel.generation == generation {
+                tunnel.sessions.remove(session_id);
+            }
+        }
+    }
+}
+
+/// A registered tunnel; unregisters itself when dropped.
+pub struct TunnelRegistration {
+    tunnels: ExecTunnels,
+    node_id: String,
+    generation: u64,
+}
+
+impl TunnelRegistration {
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Route a frame from the agent to its session.
+    ///
+    /// Frames for unknown or finished sessions are dropped.
+    pub async fn dispatch(&self, session_id: &str, frame: Vec<u8>) {
+        let Some(sender) = self
+            .tunnels
+            .session_sender(&self.node_id, self.generation, session_id)
+        else {
+            return;
+        };
+        let _ = sender.send(frame).await;
+    }
+}
+
+impl Drop for TunnelRegistration {
+    fn drop(&mut self) {
+        self.tunnels.unregister(&self.node_id, self.generation);
+    }
+}
+
+/// An exec session carried over a node's tunnel.
+pub struct ExecTunnelSession {
+    reader: ExecTunnelReader,
+    writer: ExecTunnelWriter,
+}
+
+impl ExecTunnelSession {
+    pub fn into_split(self) -> (ExecTunnelReader, ExecTunnelWriter) {
+        (self.reader, self.writer)
+    }
+}
+
+/// Receives exec frames sent by the agent.
+pub struct ExecTunnelReader {
+    frames: mpsc::Receiver<Vec<u8>>,
+    _guard: Arc<SessionGuard>,
+}
+
+impl ExecTunnelReader {
+    /// Next frame, or `None` once the tunnel has gone away.
+    pub async fn recv(&mut self) -> Option<Vec<u8>> {
+        self.frames.recv().await
+    }
+}
+
+/// Sends exec frames to the agent.
+pub struct ExecTunnelWriter {
+    outbound: Outbound,
+    guard: Arc<SessionGuard>,
+}
+
+impl ExecTunnelWriter {
+    /// Send a frame; fails once the tunnel has gone away.
+    pub async fn send(&mut self, frame: Vec<u8>) -> Result<(), TunnelClosed> {
+        let message = server_message(&self.guard.session_id, Body::Frame(frame));
+        self.outbound
+            .send(Ok(message))
+            .await
+            .map_err(|_| TunnelClosed)
+    }
+}
+
+/// The tunnel carrying a session closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("exec tunnel closed")]
+pub struct TunnelClosed;
+
+/// Removes the session and tells the agent to tear it down once both halves
+/// are dropped.
+struct SessionGuard {
+    tunnels: ExecTunnels,
+    node_id: String,
+    session_id: String,
+    generation: u64,
+    outbound: Outbound,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.tunnels
+            .remove_session(&self.node_id, self.generation, &self.session_id);
+        let close = Body::Close(ExecTunnelClose {
+            reason: "client_disconnect".to_string(),
+        });
+        let _ = self
+            .outbound
+            .try_send(Ok(server_message(&self.session_id, close)));
+    }
+}
+
+fn server_message(session_id: &str, body: Body) -> ExecTunnelServerMessage {
+    ExecTunnelServerMessage {
+        session_id: session_id.to_string(),
+        body: Some(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open() -> ExecTunnelOpen {
+        ExecTunnelOpen {
+            instance_id: "inst_1".to_string(),
+            command: vec!["sh".to_string()],
+            tty: true,
+            cols: 80,
+            rows: 24,
+            env: HashMap::new(),
+            stdin: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_round_trip() {
+        let tunnels = ExecTunnels::new();
+        let (registration, mut outbound) = tunnels.register("node_a");
+        assert!(tunnels.is_connected("node_a"));
+
+        let session = tunnels
+            .open_session("node_a", "exec_1", open())
+            .await
+            .unwrap();
+        let (mut reader, mut writer) = session.into_split();
+
+        let sent = outbound.recv().await.unwrap().unwrap();
+        assert_eq!(sent.session_id, "exec_1");
+        assert!(matches!(sent.body, Some(Body::Open(_))));
+
+        writer.send(vec![0x01, b'x']).await.unwrap();
+        let sent = outbound.recv().await.unwrap().unwrap();
+        assert_eq!(sent.body, Some(Body::Frame(vec![0x01, b'x'])));
+
+        registration.dispatch("exec_1", vec![0x02, b'y']).await;
+        assert_eq!(reader.recv().await, Some(vec![0x02, b'y']));
+
+        // Frames for other sessions are dropped.
+        registration.dispatch("exec_2", vec![0x02]).await;
+
+        drop(reader);
+        drop(writer);
+        let sent = outbound.recv().await.unwrap().unwrap();
+        assert!(matches!(sent.body, Some(Body::Close(_))));
+    }
+
+    #[tokio::test]
+    async fn test_tunnel_drop_ends_sessions() {
+        let tunnels = ExecTunnels::new();
+        let (registration, _outbound) = tunnels.register("node_a");
+        let session = tunnels
+            .open_session("node_a", "exec_1", open())
+            .await
+            .unwrap();
+        let (mut reader, _writer) = session.into_split();
+
+        drop(registration);
+        assert!(!tunnels.is_connected("node_a"));
+        assert_eq!(reader.recv().await, None);
+        assert!(tunnels
+            .open_session("node_a", "exec_2", open())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_keeps_new_tunnel() {
+        let tunnels = ExecTunnels::new();
+        let (old, _old_rx) = tunnels.register("node_a");
+        let (_new, _new_rx) = tunnels.register("node_a");
+
+        // The stale registration must not remove its replacement.
+        drop(old);
+        assert!(tunnels.is_connected("node_a"));
+        assert_eq!(tunnels.len(), 1);
+    }
+}
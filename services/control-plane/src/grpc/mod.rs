@@ -1,3 +1,8 @@
+mod exec_tunnel;
 mod node_agent;
 
+pub use exec_tunnel::{
+    ExecTunnelReader, ExecTunnelSession, ExecTunnelWriter, ExecTunnels, TunnelClosed,
+    TunnelRegistration,
+};
 pub use node_agent::NodeAgentService;
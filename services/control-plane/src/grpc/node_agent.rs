@@ -2,23 +2,32 @@ use std::collections::HashMap;
 use std::net::Ipv6Addr;
 
 use chrono::Utc;
-use plfm_events::{ActorType, AggregateType};
+use plfm_events::{ActorType, AggregateType, SecretsReload as SecretsReloadMode};
 use plfm_id::{AppId, AssignmentId, EnvId, InstanceId, NodeId, OrgId, SecretVersionId, Ulid};
 use plfm_proto::agent::v1::{
     node_agent_server::NodeAgent, DesiredInstanceAssignment, EnrollRequest, EnrollResponse,
-    GetPlanRequest, GetPlanResponse, GetSecretMaterialRequest, GetSecretMaterialResponse,
-    HeartbeatRequest, HeartbeatResponse, NodePlan, ReportInstanceStatusRequest,
-    ReportInstanceStatusResponse, SecretMaterial, SendWorkloadLogsRequest,
-    SendWorkloadLogsResponse, WorkloadImage, WorkloadMount, WorkloadNetwork, WorkloadResources,
-    WorkloadSecrets, WorkloadSpec,
+    ExecTunnelAgentMessage, ExecTunnelServerMessage, GetPlanRequest, GetPlanResponse,
+    GetSecretMaterialRequest, GetSecretMaterialResponse, HeartbeatRequest, HeartbeatResponse,
+    NodePlan, ReportInstanceStatusRequest, ReportInstanceStatusResponse, SecretMaterial,
+    SecretsReload, SendWorkloadLogsRequest, SendWorkloadLogsResponse, WorkloadImage, WorkloadMount,
+    WorkloadNetwork, WorkloadResources, WorkloadSecrets, WorkloadSpec,
+};
+use plfm_proto::convert::decode_enum;
+use plfm_proto::events::v1::{
+    InstanceDesiredState, InstanceFailureReason, InstanceStatus, NodeState,
 };
-use plfm_proto::events::v1::{InstanceDesiredState, InstanceStatus, NodeState};
 use sqlx::QueryBuilder;
-use tonic::{Request, Response, Status};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
 
 use crate::db::AppendEvent;
+use crate::drift;
+use crate::enrollment::{self, EnrollmentError, EnrollmentMode};
+use crate::instance_status::{self, BootKey};
+use crate::node_watchdog;
 use crate::secrets as secrets_crypto;
 use crate::state::AppState;
+use crate::timeline;
 
 const MAX_LOG_ENTRIES: usize = 500;
 const MAX_LOG_LINE_BYTES: usize = 16 * 1024;
@@ -38,15 +47,16 @@ impl NodeAgentService {
         Self { state }
     }
 
-    fn map_instance_status_from_proto(status: InstanceStatus) -> &'static str {
-        match status {
-            InstanceStatus::Booting => "booting",
-            InstanceStatus::Ready => "ready",
-            InstanceStatus::Draining => "draining",
-            InstanceStatus::Stopped => "stopped",
-            InstanceStatus::Failed => "failed",
-            InstanceStatus::Unspecified => "unknown",
-        }
+    /// Reject calls for nodes that are not admitted or whose forwarded
+    /// client certificate subject does not match the enrolled one.
+    async fn verify_node<T>(&self, request: &Request<T>, node_id: &str) -> Result<(), Status> {
+        let subject = request
+            .metadata()
+            .get(enrollment::client_cert_subject_header().as_str())
+            .and_then(|v| v.to_str().ok());
+        enrollment::verify_node(self.state.db().pool(), node_id, subject)
+            .await
+            .map_err(enrollment_status)
     }
 }
 
@@ -56,6 +66,11 @@ impl NodeAgent for NodeAgentService {
         &self,
         request: Request<EnrollRequest>,
     ) -> Result<Response<EnrollResponse>, Status> {
+        let presented_subject = request
+            .metadata()
+            .get(enrollment::client_cert_subject_header().as_str())
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
         let req = request.into_inner();
         let request_id = Ulid::new().to_string();
 
@@ -99,7 +114,28 @@ impl NodeAgent for NodeAgentService {
             ));
         }
 
+        // Pin only the subject the node's certificate actually carries.
+        enrollment::check_enrollment_subject(&req.agent_mtls_subject, presented_subject.as_deref())
+            .map_err(enrollment_status)?;
+
         let node_id = NodeId::new();
+        let admission = enrollment::admit(
+            self.state.db().pool(),
+            EnrollmentMode::from_env(),
+            req.bootstrap_token.as_deref(),
+            &node_id,
+        )
+        .await
+        .map_err(enrollment_status)?;
+        let (event_type, node_state) = if admission.requires_approval {
+            (
+                plfm_events::event_types::NODE_ENROLLMENT_REQUESTED,
+                NodeState::PendingApproval,
+            )
+        } else {
+            (plfm_events::event_types::NODE_ENROLLED, NodeState::Active)
+        };
+
         let overlay_ipv6 = allocate_node_ipv6(self.state.db().pool(), &node_id, &request_id)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
@@ -119,7 +155,7 @@ impl NodeAgent for NodeAgentService {
             aggregate_type: AggregateType::Node,
             aggregate_id: node_id.to_string(),
             aggregate_seq: 1,
-            event_type: "node.enrolled".to_string(),
+            event_type: event_type.to_string(),
             event_version: 1,
             actor_type: ActorType::ServicePrincipal,
             actor_id: node_id.to_string(),
@@ -144,6 +180,7 @@ impl NodeAgent for NodeAgentService {
                 "mtu": req.mtu,
                 "labels": labels,
                 "allocatable": allocatable,
+                "bootstrap_token_id": admission.bootstrap_token_id,
             }),
             ..Default::default()
         };
@@ -158,6 +195,7 @@ impl NodeAgent for NodeAgentService {
             node_id = %node_id,
             hostname = %req.hostname,
             region = %req.region,
+            state = node_state.as_str_name(),
             request_id = %request_id,
             "Node enrolled via gRPC"
         );
@@ -165,7 +203,7 @@ impl NodeAgent for NodeAgentService {
         Ok(Response::new(EnrollResponse {
             node_id: node_id.to_string(),
             overlay_ipv6,
-            state: NodeState::Active.into(),
+            state: node_state.into(),
             selected_api_version: "v1".to_string(),
             minimum_agent_version: Some("0.1.0".to_string()),
             rejection_reason: None,
@@ -185,17 +223,13 @@ impl NodeAgent for NodeAgentService {
             .ok_or_else(|| Status::invalid_argument("missing x-node-id header"))?
             .to_string();
 
+        self.verify_node(&request, &node_id).await?;
         let req = request.into_inner();
 
-        let node_state = NodeState::try_from(req.state).unwrap_or(NodeState::Active);
-        let node_state_str = match node_state {
-            NodeState::Active => "active",
-            NodeState::Draining => "draining",
-            NodeState::Disabled => "disabled",
-            NodeState::Degraded => "degraded",
-            NodeState::Offline => "offline",
-            NodeState::Unspecified => "active",
-        };
+        // Approval is decided by operators, never reported by agents.
+        let node_state_str = decode_enum::<NodeState, plfm_events::NodeState>(req.state)
+            .unwrap_or(plfm_events::NodeState::Active)
+            .as_str();
 
         let node_id_typed: NodeId = node_id
             .parse()
@@ -216,6 +250,18 @@ impl NodeAgent for NodeAgentService {
             None => return Err(Status::not_found(format!("node {} not found", node_id))),
         };
 
+        let transition = node_watchdog::heartbeat_state(
+            self.state.db().pool(),
+            &node_id,
+            &current_state,
+            node_state_str,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check node flapping");
+            Status::internal("failed to process heartbeat")
+        })?;
+
         let event_store = self.state.db().event_store();
         let current_seq = event_store
             .get_latest_aggregate_seq(&AggregateType::Node, &node_id)
@@ -250,7 +296,15 @@ impl NodeAgent for NodeAgentService {
             ..Default::default()
         };
 
-        if current_state != node_state_str {
+        if let Some(transition) = transition {
+            let mut payload = serde_json::json!({
+                "node_id": node_id_typed.to_string(),
+                "old_state": current_state,
+                "new_state": transition.new_state,
+            });
+            if let Some(reason) = transition.reason {
+                payload["reason"] = serde_json::json!(reason);
+            }
             let state_event = AppendEvent {
                 aggregate_type: AggregateType::Node,
                 aggregate_id: node_id.clone(),
@@ -266,11 +320,7 @@ impl NodeAgent for NodeAgentService {
                 env_id: None,
                 correlation_id: None,
                 causation_id: None,
-                payload: serde_json::json!({
-                    "node_id": node_id_typed.to_string(),
-                    "old_state": current_state,
-                    "new_state": node_state_str,
-                }),
+                payload,
                 ..Default::default()
             };
 
@@ -285,7 +335,8 @@ impl NodeAgent for NodeAgentService {
             tracing::info!(
                 node_id = %node_id,
                 old_state = %current_state,
-                new_state = %node_state_str,
+                new_state = %transition.new_state,
+                reason = ?transition.reason,
                 request_id = %request_id,
                 "Node state changed"
             );
@@ -299,6 +350,15 @@ impl NodeAgent for NodeAgentService {
                 })?;
         }
 
+        if let Some(inventory) = req.inventory {
+            let statuses = inventory.statuses.into_iter().collect();
+            if let Err(e) =
+                drift::record_inventory(self.state.db().pool(), &node_id, &statuses).await
+            {
+                tracing::warn!(error = %e, node_id = %node_id, "Failed to record instance inventory");
+            }
+        }
+
         Ok(Response::new(HeartbeatResponse {
             accepted: true,
             next_heartbeat_secs: 30,
@@ -309,6 +369,8 @@ impl NodeAgent for NodeAgentService {
         &self,
         request: Request<GetPlanRequest>,
     ) -> Result<Response<GetPlanResponse>, Status> {
+        self.verify_node(&request, &request.get_ref().node_id)
+            .await?;
         let req = request.into_inner();
         let request_id = Ulid::new().to_string();
 
@@ -352,11 +414,17 @@ impl NodeAgent for NodeAgentService {
                    r.manifest_hash as manifest_hash,
                    r.command as command,
                    i.secrets_version_id,
+                   cv.vars as config_vars,
+                   s.secrets_reload,
                    host(i.overlay_ipv6)::TEXT as overlay_ipv6,
                    i.resources_snapshot,
                    i.spec_hash
             FROM instances_desired_view i
             JOIN releases_view r ON i.release_id = r.release_id
+            LEFT JOIN env_config_var_versions cv
+              ON cv.env_id = i.env_id AND cv.version = i.config_vars_version
+            LEFT JOIN env_scale_view s
+              ON s.env_id = i.env_id AND s.process_type = i.process_type
             WHERE i.node_id = $1
             ORDER BY i.created_at
             "#,
@@ -375,6 +443,14 @@ impl NodeAgent for NodeAgentService {
             Status::internal("failed to get plan")
         })?;
 
+        let instance_ids: Vec<String> = instances.iter().map(|i| i.instance_id.clone()).collect();
+        if let Err(e) =
+            timeline::record_plan_delivery(self.state.db().pool(), &req.node_id, &instance_ids)
+                .await
+        {
+            tracing::warn!(error = %e, node_id = %req.node_id, "Failed to record plan delivery");
+        }
+
         let volume_mounts = load_volume_mounts(&self.state, &request_id, &instances)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
@@ -401,6 +477,8 @@ impl NodeAgent for NodeAgentService {
         &self,
         request: Request<ReportInstanceStatusRequest>,
     ) -> Result<Response<ReportInstanceStatusResponse>, Status> {
+        self.verify_node(&request, &request.get_ref().node_id)
+            .await?;
         let req = request.into_inner();
         let request_id = Ulid::new().to_string();
 
@@ -419,16 +497,12 @@ impl NodeAgent for NodeAgentService {
             .map_err(|_| Status::invalid_argument("invalid instance_id format"))?;
 
         let status =
-            InstanceStatus::try_from(status_report.status).unwrap_or(InstanceStatus::Unspecified);
-        let status_str = Self::map_instance_status_from_proto(status);
-
-        let valid_statuses = ["booting", "ready", "draining", "stopped", "failed"];
-        if !valid_statuses.contains(&status_str) {
-            return Err(Status::invalid_argument(format!(
-                "status must be one of: {:?}",
-                valid_statuses
-            )));
-        }
+            decode_enum::<InstanceStatus, plfm_events::InstanceStatus>(status_report.status)
+                .map_err(|_| {
+                    Status::invalid_argument(
+                        "status must be one of: booting, ready, draining, stopped, failed",
+                    )
+                })?;
 
         let instance_info = sqlx::query_as::<_, InstanceInfoRow>(
             r#"
@@ -453,6 +527,30 @@ impl NodeAgent for NodeAgentService {
             }
         };
 
+        // Stale reports are acknowledged without recording them; see
+        // `instance_status`.
+        let report_key = BootKey::new(status_report.generation, status_report.boot_id.clone());
+        let current_key =
+            instance_status::current_key(self.state.db().pool(), &status_report.instance_id)
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to get current status");
+                    Status::internal("failed to process status")
+                })?;
+        if let Some(current_key) = current_key.filter(|current| report_key.is_stale(current)) {
+            tracing::info!(
+                instance_id = %instance_id_typed,
+                boot_id = ?report_key.boot_id,
+                generation = ?report_key.generation,
+                current_boot_id = ?current_key.boot_id,
+                current_generation = ?current_key.generation,
+                "Ignoring stale instance status report"
+            );
+            return Ok(Response::new(ReportInstanceStatusResponse {
+                accepted: false,
+            }));
+        }
+
         let event_store = self.state.db().event_store();
         let current_seq = event_store
             .get_latest_aggregate_seq(&AggregateType::Instance, &instance_id_typed.to_string())
@@ -476,6 +574,19 @@ impl NodeAgent for NodeAgentService {
             .parse::<EnvId>()
             .map_err(|_| Status::internal("invalid env_id in instances_desired_view"))?;
 
+        let reason_code = if status == plfm_events::InstanceStatus::Failed {
+            status_report
+                .reason_code
+                .and_then(|code| {
+                    decode_enum::<InstanceFailureReason, plfm_events::InstanceFailureReason>(code)
+                        .ok()
+                })
+                .map(|reason| reason.as_str())
+                .or_else(|| status_report.error_message.as_ref().map(|_| "unspecified"))
+        } else {
+            None
+        };
+
         let event = AppendEvent {
             aggregate_type: AggregateType::Instance,
             aggregate_id: instance_id_typed.to_string(),
@@ -494,10 +605,11 @@ impl NodeAgent for NodeAgentService {
             payload: serde_json::json!({
                 "instance_id": instance_id_typed.to_string(),
                 "node_id": node_id_typed.to_string(),
-                "status": status_str,
+                "status": status.as_str(),
                 "boot_id": status_report.boot_id,
+                "generation": status_report.generation,
                 "exit_code": status_report.exit_code,
-                "reason_code": if status_str == "failed" { status_report.error_message.as_ref().map(|_| "unspecified") } else { None },
+                "reason_code": reason_code,
                 "reason_detail": status_report.error_message,
                 "reported_at": chrono::Utc::now().to_rfc3339(),
             }),
@@ -518,6 +630,8 @@ impl NodeAgent for NodeAgentService {
         &self,
         request: Request<GetSecretMaterialRequest>,
     ) -> Result<Response<GetSecretMaterialResponse>, Status> {
+        self.verify_node(&request, &request.get_ref().node_id)
+            .await?;
         let req = request.into_inner();
         let request_id = Ulid::new().to_string();
 
@@ -531,21 +645,6 @@ impl NodeAgent for NodeAgentService {
             .parse()
             .map_err(|_| Status::invalid_argument("invalid secret version_id format"))?;
 
-        let node_exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM nodes_view WHERE node_id = $1)",
-        )
-        .bind(&req.node_id)
-        .fetch_one(self.state.db().pool())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Failed to check node existence");
-            Status::internal("failed to load secrets")
-        })?;
-
-        if !node_exists {
-            return Err(Status::not_found(format!("node {} not found", req.node_id)));
-        }
-
         let row = sqlx::query_as::<_, SecretMaterialRow>(
             r#"
             SELECT sv.version_id,
@@ -602,6 +701,7 @@ impl NodeAgent for NodeAgentService {
             &row.wrapped_data_key_nonce,
             aad.as_bytes(),
         )
+        .await
         .map_err(|e| {
             tracing::error!(error = %e, request_id = %request_id, "Failed to decrypt secrets");
             Status::internal("failed to decrypt secrets")
@@ -624,29 +724,16 @@ impl NodeAgent for NodeAgentService {
         &self,
         request: Request<SendWorkloadLogsRequest>,
     ) -> Result<Response<SendWorkloadLogsResponse>, Status> {
+        self.verify_node(&request, &request.get_ref().node_id)
+            .await?;
         let req = request.into_inner();
         let request_id = Ulid::new().to_string();
 
-        let node_id_typed: NodeId = req
+        let _node_id_typed: NodeId = req
             .node_id
             .parse()
             .map_err(|_| Status::invalid_argument("invalid node_id format"))?;
 
-        let node_exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM nodes_view WHERE node_id = $1)",
-        )
-        .bind(node_id_typed.to_string())
-        .fetch_one(self.state.db().pool())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, request_id = %request_id, "Failed to check node existence");
-            Status::internal("failed to ingest logs")
-        })?;
-
-        if !node_exists {
-            return Err(Status::not_found(format!("node {} not found", req.node_id)));
-        }
-
         if req.entries.is_empty() {
             return Ok(Response::new(SendWorkloadLogsResponse {
                 accepted: 0,
@@ -752,6 +839,70 @@ impl NodeAgent for NodeAgentService {
             rejected,
         }))
     }
+
+    type ExecTunnelStream = ReceiverStream<Result<ExecTunnelServerMessage, Status>>;
+
+    async fn exec_tunnel(
+        &self,
+        request: Request<Streaming<ExecTunnelAgentMessage>>,
+    ) -> Result<Response<Self::ExecTunnelStream>, Status> {
+        let node_id = request
+            .metadata()
+            .get("x-node-id")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Status::invalid_argument("missing x-node-id header"))?
+            .to_string();
+
+        self.verify_node(&request, &node_id).await?;
+        let mut inbound = request.into_inner();
+
+        let tunnels = self.state.exec_tunnels();
+        let (registration, outbound) = tunnels.register(&node_id);
+        tracing::info!(node_id = %node_id, open_tunnels = tunnels.len(), "Exec tunnel opened");
+
+        tokio::spawn(async move {
+            loop {
+                match inbound.message().await {
+                    Ok(Some(message)) => {
+                        registration
+                            .dispatch(&message.session_id, message.frame)
+                            .await;
+                    }
+                    Ok(None) => break,
+                    Err(status) => {
+                        tracing::warn!(
+                            node_id = %registration.node_id(),
+                            error = %status,
+                            "Exec tunnel stream failed"
+                        );
+                        break;
+                    }
+                }
+            }
+            tracing::info!(node_id = %registration.node_id(), "Exec tunnel closed");
+        });
+
+        Ok(Response::new(ReceiverStream::new(outbound)))
+    }
+}
+
+fn enrollment_status(error: EnrollmentError) -> Status {
+    match error {
+        EnrollmentError::TokenRequired
+        | EnrollmentError::InvalidToken
+        | EnrollmentError::IdentityRequired => Status::unauthenticated(error.to_string()),
+        EnrollmentError::NodeNotFound(node_id) => {
+            Status::not_found(format!("node {} not found", node_id))
+        }
+        EnrollmentError::PendingApproval(_)
+        | EnrollmentError::Denied(_)
+        | EnrollmentError::IdentityMismatch(_)
+        | EnrollmentError::SubjectMismatch => Status::permission_denied(error.to_string()),
+        EnrollmentError::Database(e) => {
+            tracing::error!(error = %e, "Node enrollment query failed");
+            Status::internal("failed to verify node")
+        }
+    }
 }
 
 async fn allocate_node_ipv6(
@@ -864,6 +1015,10 @@ struct InstancePlanRow {
     manifest_hash: String,
     command: serde_json::Value,
     secrets_version_id: Option<String>,
+    /// Config vars the instance was allocated with.
+    config_vars: Option<serde_json::Value>,
+    /// Secrets reload mode of the process type; NULL means restart.
+    secrets_reload: Option<serde_json::Value>,
     overlay_ipv6: Option<String>,
     resources_snapshot: serde_json::Value,
     spec_hash: String,
@@ -888,6 +1043,8 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for InstancePlanRow {
             manifest_hash: row.try_get("manifest_hash")?,
             command: row.try_get("command")?,
             secrets_version_id: row.try_get("secrets_version_id")?,
+            config_vars: row.try_get("config_vars")?,
+            secrets_reload: row.try_get("secrets_reload")?,
             overlay_ipv6: row.try_get("overlay_ipv6")?,
             resources_snapshot: row.try_get("resources_snapshot")?,
             spec_hash: row.try_get("spec_hash")?,
@@ -992,6 +1149,7 @@ struct VolumeMountData {
     volume_id: String,
     mount_path: String,
     read_only: bool,
+    size_bytes: Option<i64>,
 }
 
 struct VolumeMountRow {
@@ -1000,6 +1158,7 @@ struct VolumeMountRow {
     volume_id: String,
     mount_path: String,
     read_only: bool,
+    size_bytes: Option<i64>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeMountRow {
@@ -1011,6 +1170,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeMountRow {
             volume_id: row.try_get("volume_id")?,
             mount_path: row.try_get("mount_path")?,
             read_only: row.try_get("read_only")?,
+            size_bytes: row.try_get("size_bytes")?,
         })
     }
 }
@@ -1038,12 +1198,13 @@ async fn load_volume_mounts(
 
     let rows = sqlx::query_as::<_, VolumeMountRow>(
         r#"
-        SELECT env_id, process_type, volume_id, mount_path, read_only
-        FROM volume_attachments_view
-        WHERE env_id = ANY($1::TEXT[])
-          AND process_type = ANY($2::TEXT[])
-          AND NOT is_deleted
-        ORDER BY env_id ASC, process_type ASC, volume_id ASC
+        SELECT a.env_id, a.process_type, a.volume_id, a.mount_path, a.read_only, v.size_bytes
+        FROM volume_attachments_view a
+        LEFT JOIN volumes_view v ON v.volume_id = a.volume_id
+        WHERE a.env_id = ANY($1::TEXT[])
+          AND a.process_type = ANY($2::TEXT[])
+          AND NOT a.is_deleted
+        ORDER BY a.env_id ASC, a.process_type ASC, a.volume_id ASC
         "#,
     )
     .bind(env_ids)
@@ -1064,6 +1225,7 @@ async fn load_volume_mounts(
                 volume_id: row.volume_id,
                 mount_path: row.mount_path,
                 read_only: row.read_only,
+                size_bytes: row.size_bytes,
             });
     }
 
@@ -1144,6 +1306,7 @@ fn workload_spec_from_row(
                     read_only: m.read_only,
                     filesystem: "ext4".to_string(),
                     device_hint: None,
+                    size_bytes: m.size_bytes,
                 })
                 .collect()
         })
@@ -1159,6 +1322,11 @@ fn workload_spec_from_row(
             mode: None,
             uid: None,
             gid: None,
+            reload: row
+                .secrets_reload
+                .clone()
+                .and_then(|reload| serde_json::from_value(reload).ok())
+                .map(|reload| secrets_reload_to_proto(&reload)),
         });
 
     let overlay_ipv6 = row
@@ -1173,7 +1341,12 @@ fn workload_spec_from_row(
         ports: vec![],
     };
 
-    let env_vars: HashMap<String, String> = HashMap::new();
+    // Config vars become the workload environment; secrets stay in the secrets file.
+    let env_vars: HashMap<String, String> = row
+        .config_vars
+        .clone()
+        .and_then(|vars| serde_json::from_value(vars).ok())
+        .unwrap_or_default();
 
     WorkloadSpec {
         spec_version: WORKLOAD_SPEC_VERSION.to_string(),
@@ -1197,6 +1370,33 @@ fn workload_spec_from_row(
     }
 }
 
+fn secrets_reload_to_proto(reload: &SecretsReloadMode) -> SecretsReload {
+    match reload {
+        SecretsReloadMode::Restart => SecretsReload {
+            mode: "restart".to_string(),
+            ..Default::default()
+        },
+        SecretsReloadMode::File => SecretsReload {
+            mode: "file".to_string(),
+            ..Default::default()
+        },
+        SecretsReloadMode::Signal { signal } => SecretsReload {
+            mode: "signal".to_string(),
+            signal: Some(signal.clone()),
+            ..Default::default()
+        },
+        SecretsReloadMode::Exec {
+            command,
+            timeout_seconds,
+        } => SecretsReload {
+            mode: "exec".to_string(),
+            command: command.clone(),
+            timeout_seconds: *timeout_seconds,
+            ..Default::default()
+        },
+    }
+}
+
 fn workload_image_from_row(row: &InstancePlanRow, arch_hint: Option<&str>) -> WorkloadImage {
     let entries = resolved_digest_entries(&row.resolved_digests);
     let resolved = select_resolved_digest(&entries, arch_hint);
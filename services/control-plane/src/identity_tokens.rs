@@ -0,0 +1,379 @@
+//! Instance identity tokens.
+//!
+//! Short-lived ES256 JWTs naming an instance and where it belongs (org, app,
+//! env, process type, node). Node agents obtain them for the instances they
+//! host and hand them to the guest, renewing before expiry. Workloads present
+//! them as bearer tokens to `/v1/workload/*` and to each other; anyone can
+//! verify them against the JWKS at `/v1/pki/jwks`.
+//!
+//! Configuration:
+//! - `PLFM_IDENTITY_TOKEN_TTL_SECS`: token lifetime (default 900, 60 to 3600)
+//!
+//! The signing key is created on first use. Rotation retires it and creates
+//! a new one; retired keys stay in the JWKS for the longest token lifetime.
+//!
+//! See: docs/security/08-platform-pki.md
+
+use std::sync::OnceLock;
+use std::time::{Duration as StdDuration, Instant};
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_id::Ulid;
+use plfm_pki::{Identity, IdentityClaims, IdentityKind, IdentityTokenKey, Jwk, PkiError};
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+
+use crate::pki::{trust_domain, CaError};
+use crate::secrets::{self as secrets_crypto, KeyScope};
+
+const DEFAULT_TOKEN_TTL_SECS: i64 = 900;
+
+/// Longest token lifetime. Retired keys stay in the JWKS this long.
+const MAX_TOKEN_TTL: Duration = Duration::hours(1);
+
+/// How long verification reuses the loaded keys before reloading them.
+const KEY_CACHE_TTL: StdDuration = StdDuration::from_secs(60);
+
+fn ttl_from(value: Option<&str>) -> Duration {
+    let secs = value
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .unwrap_or(DEFAULT_TOKEN_TTL_SECS)
+        .clamp(60, MAX_TOKEN_TTL.num_seconds());
+    Duration::seconds(secs)
+}
+
+pub fn token_ttl() -> Duration {
+    ttl_from(
+        std::env::var("PLFM_IDENTITY_TOKEN_TTL_SECS")
+            .ok()
+            .as_deref(),
+    )
+}
+
+/// `iss` of every token.
+pub fn issuer() -> String {
+    format!("spiffe://{}", trust_domain())
+}
+
+/// `aud` of every token: the trust domain.
+pub fn audience() -> String {
+    trust_domain()
+}
+
+fn key_aad(kid: &str) -> String {
+    format!("plfm-identity-token-key:{kid}")
+}
+
+/// A signed token for an instance.
+#[derive(Debug, Clone)]
+pub struct IssuedToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    /// Renew after this point (two thirds of the lifetime).
+    pub renew_after: DateTime<Utc>,
+}
+
+/// Where an instance belongs, as named in its token.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct InstanceSubject {
+    pub instance_id: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub process_type: String,
+    pub node_id: String,
+}
+
+fn claims_for(subject: &InstanceSubject, now: DateTime<Utc>, ttl: Duration) -> IdentityClaims {
+    let domain = trust_domain();
+    IdentityClaims {
+        iss: issuer(),
+        sub: Identity::new(IdentityKind::Instance, subject.instance_id.clone()).uri(&domain),
+        aud: domain,
+        iat: now.timestamp(),
+        nbf: now.timestamp(),
+        exp: (now + ttl).timestamp(),
+        jti: Ulid::new().to_string(),
+        org_id: subject.org_id.clone(),
+        app_id: subject.app_id.clone(),
+        env_id: subject.env_id.clone(),
+        process_type: subject.process_type.clone(),
+        instance_id: subject.instance_id.clone(),
+        node_id: subject.node_id.clone(),
+    }
+}
+
+struct TokenKeyRow {
+    kid: String,
+    key_nonce: Vec<u8>,
+    key_ciphertext: Vec<u8>,
+    master_key_id: String,
+    wrapped_data_key: Vec<u8>,
+    wrapped_data_key_nonce: Vec<u8>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for TokenKeyRow {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            kid: row.try_get("kid")?,
+            key_nonce: row.try_get("key_nonce")?,
+            key_ciphertext: row.try_get("key_ciphertext")?,
+            master_key_id: row.try_get("master_key_id")?,
+            wrapped_data_key: row.try_get("wrapped_data_key")?,
+            wrapped_data_key_nonce: row.try_get("wrapped_data_key_nonce")?,
+        })
+    }
+}
+
+const KEY_COLUMNS: &str =
+    "kid, key_nonce, key_ciphertext, master_key_id, wrapped_data_key, wrapped_data_key_nonce";
+
+async fn load_key(row: TokenKeyRow) -> Result<IdentityTokenKey, CaError> {
+    let pkcs8 = secrets_crypto::decrypt(
+        &row.master_key_id,
+        &row.key_nonce,
+        &row.key_ciphertext,
+        &row.wrapped_data_key,
+        &row.wrapped_data_key_nonce,
+        key_aad(&row.kid).as_bytes(),
+    )
+    .await?;
+    Ok(IdentityTokenKey::from_pkcs8(row.kid, &pkcs8)?)
+}
+
+/// Generate a signing key and store it as the active one. Returns `None` if
+/// another replica created an active key first.
+async fn insert_key(pool: &PgPool) -> Result<Option<IdentityTokenKey>, CaError> {
+    let key = IdentityTokenKey::generate(format!("itk_{}", Ulid::new()))?;
+    let encrypted = secrets_crypto::encrypt(
+        KeyScope::Platform,
+        key.pkcs8_der(),
+        key_aad(key.kid()).as_bytes(),
+    )
+    .await?;
+    let public_jwk = serde_json::to_value(key.public_jwk()).expect("JWK serializes");
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO identity_token_keys (
+            kid, public_jwk, key_cipher, key_nonce, key_ciphertext, master_key_id,
+            wrapped_data_key, wrapped_data_key_nonce
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(key.kid())
+    .bind(&public_jwk)
+    .bind(&encrypted.cipher)
+    .bind(&encrypted.nonce)
+    .bind(&encrypted.ciphertext)
+    .bind(&encrypted.master_key_id)
+    .bind(&encrypted.wrapped_data_key)
+    .bind(&encrypted.wrapped_data_key_nonce)
+    .execute(pool)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Ok(None);
+    }
+    tracing::info!(kid = %key.kid(), "Created identity token signing key");
+    Ok(Some(key))
+}
+
+/// The active signing key, creating it on first use.
+pub async fn active_key(pool: &PgPool) -> Result<IdentityTokenKey, CaError> {
+    let query = format!("SELECT {KEY_COLUMNS} FROM identity_token_keys WHERE retired_at IS NULL");
+    if let Some(row) = sqlx::query_as::<_, TokenKeyRow>(&query)
+        .fetch_optional(pool)
+        .await?
+    {
+        return load_key(row).await;
+    }
+
+    if let Some(created) = insert_key(pool).await? {
+        return Ok(created);
+    }
+    let row = sqlx::query_as::<_, TokenKeyRow>(&query)
+        .fetch_one(pool)
+        .await?;
+    load_key(row).await
+}
+
+/// A signing key by ID, retired or not. The key is decrypted rather than
+/// read from `public_jwk`, so a row edited in the database cannot pass off
+/// its own key as the control plane's.
+pub async fn key_by_kid(pool: &PgPool, kid: &str) -> Result<Option<IdentityTokenKey>, CaError> {
+    let query = format!("SELECT {KEY_COLUMNS} FROM identity_token_keys WHERE kid = $1");
+    match sqlx::query_as::<_, TokenKeyRow>(&query)
+        .bind(kid)
+        .fetch_optional(pool)
+        .await?
+    {
+        Some(row) => Ok(Some(load_key(row).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Public keys verifiers should accept: the active key plus keys retired
+/// less than the longest token lifetime ago.
+pub async fn jwks(pool: &PgPool) -> Result<Vec<Jwk>, CaError> {
+    // Make sure there is a key to publish before the first issuance.
+    active_key(pool).await?;
+
+    let rows: Vec<(serde_json::Value,)> = sqlx::query_as(
+        r#"
+        SELECT public_jwk
+        FROM identity_token_keys
+        WHERE retired_at IS NULL OR retired_at > now() - make_interval(secs => $1)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(MAX_TOKEN_TTL.num_seconds() as f64)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|(jwk,)| {
+            serde_json::from_value::<Jwk>(jwk)
+                .map_err(|e| CaError::from(PkiError::InvalidKey(format!("stored JWK: {e}"))))
+        })
+        .collect()
+}
+
+/// Retire the active signing key and create a new one. Returns the new
+/// key's ID.
+pub async fn rotate(pool: &PgPool) -> Result<String, CaError> {
+    sqlx::query("UPDATE identity_token_keys SET retired_at = now() WHERE retired_at IS NULL")
+        .execute(pool)
+        .await?;
+    let key = active_key(pool).await?;
+    tracing::info!(kid = %key.kid(), "Rotated identity token signing key");
+    Ok(key.kid().to_string())
+}
+
+/// Issue a token for an instance placed on `node_id` and not stopped.
+/// Returns `None` if there is no such instance.
+pub async fn issue_for_instance(
+    pool: &PgPool,
+    instance_id: &str,
+    node_id: &str,
+) -> Result<Option<IssuedToken>, CaError> {
+    let subject: Option<InstanceSubject> = sqlx::query_as(
+        r#"
+        SELECT instance_id, org_id, app_id, env_id, process_type, node_id
+        FROM instances_desired_view
+        WHERE instance_id = $1 AND node_id = $2 AND desired_state <> 'stopped'
+        "#,
+    )
+    .bind(instance_id)
+    .bind(node_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(subject) = subject else {
+        return Ok(None);
+    };
+
+    let key = active_key(pool).await?;
+    let now = Utc::now();
+    let ttl = token_ttl();
+    let token = key.sign(&claims_for(&subject, now, ttl))?;
+
+    tracing::debug!(
+        instance_id = %instance_id,
+        kid = %key.kid(),
+        "Issued instance identity token"
+    );
+    Ok(Some(IssuedToken {
+        token,
+        expires_at: now + ttl,
+        renew_after: now + ttl * 2 / 3,
+    }))
+}
+
+struct CachedKeys {
+    loaded_at: Instant,
+    keys: Vec<Jwk>,
+}
+
+fn key_cache() -> &'static RwLock<Option<CachedKeys>> {
+    static CACHE: OnceLock<RwLock<Option<CachedKeys>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Trusted keys, from the cache unless it is stale or lacks `kid` (a key
+/// created by another replica since the last load).
+async fn verification_keys(pool: &PgPool, kid: &str) -> Result<Vec<Jwk>, CaError> {
+    if let Some(cached) = key_cache().read().await.as_ref() {
+        if cached.loaded_at.elapsed() < KEY_CACHE_TTL && cached.keys.iter().any(|k| k.kid == kid) {
+            return Ok(cached.keys.clone());
+        }
+    }
+
+    let keys = jwks(pool).await?;
+    *key_cache().write().await = Some(CachedKeys {
+        loaded_at: Instant::now(),
+        keys: keys.clone(),
+    });
+    Ok(keys)
+}
+
+/// Verify a token presented by a workload. Tokens of instances that have
+/// since been stopped are rejected even before they expire.
+pub async fn verify(pool: &PgPool, token: &str) -> Result<IdentityClaims, CaError> {
+    let kid = plfm_pki::token_key_id(token)?;
+    let keys = verification_keys(pool, &kid).await?;
+    let claims = plfm_pki::verify_identity_token(token, &keys, &issuer(), &audience(), Utc::now())?;
+
+    let live: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM instances_desired_view
+            WHERE instance_id = $1 AND desired_state <> 'stopped'
+        )
+        "#,
+    )
+    .bind(&claims.instance_id)
+    .fetch_one(pool)
+    .await?;
+    if !live {
+        return Err(PkiError::InvalidToken("instance is stopped".to_string()).into());
+    }
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_from() {
+        assert_eq!(ttl_from(None), Duration::minutes(15));
+        assert_eq!(ttl_from(Some("300")), Duration::minutes(5));
+        assert_eq!(ttl_from(Some("5")), Duration::minutes(1));
+        assert_eq!(ttl_from(Some("86400")), Duration::hours(1));
+        assert_eq!(ttl_from(Some("soon")), Duration::minutes(15));
+    }
+
+    #[test]
+    fn test_claims_name_the_instance() {
+        let subject = InstanceSubject {
+            instance_id: "inst_1".to_string(),
+            org_id: "org_1".to_string(),
+            app_id: "app_1".to_string(),
+            env_id: "env_1".to_string(),
+            process_type: "web".to_string(),
+            node_id: "node_1".to_string(),
+        };
+        let now = Utc::now();
+        let claims = claims_for(&subject, now, Duration::minutes(15));
+
+        assert_eq!(claims.sub, "spiffe://plfm.internal/instance/inst_1");
+        assert_eq!(claims.iss, "spiffe://plfm.internal");
+        assert_eq!(claims.aud, "plfm.internal");
+        assert_eq!(claims.exp - claims.iat, 900);
+        assert_eq!(claims.env_id, "env_1");
+        assert_eq!(claims.process_type, "web");
+    }
+}
@@ -0,0 +1,114 @@
+//! Ordering of instance status reports.
+//!
+//! Node agents report every status change with the boot it came from
+//! (`boot_id`, fresh for each VM boot) and the plan generation that boot
+//! runs. Retries, agent restarts and reboots can deliver reports late or out
+//! of order, so a report is only applied if it is not older than the one
+//! behind the instance's `instances_status_view` row:
+//!
+//! 1. A lower generation is stale; a higher one is newer.
+//! 2. Within a generation, reports from different boots are ordered by boot
+//!    ID. Agents issue ULID-based boot IDs (`boot_<ulid>`), so a later boot
+//!    sorts after an earlier one.
+//! 3. Reports without a generation or boot ID, and boot IDs from older agents
+//!    that cannot be ordered, are applied as they arrive.
+//!
+//! The status endpoints acknowledge stale reports with `accepted: false` and
+//! record no event. The instances projection applies the same check, so
+//! reports that race past the endpoint check cannot regress the row either.
+
+use plfm_id::BootId;
+use sqlx::{Executor, Postgres};
+
+/// The generation and boot a status report (or status row) belongs to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct BootKey {
+    pub generation: Option<i32>,
+    pub boot_id: Option<String>,
+}
+
+impl BootKey {
+    pub fn new(generation: Option<i32>, boot_id: Option<String>) -> Self {
+        Self {
+            generation,
+            boot_id,
+        }
+    }
+
+    /// Whether a report for this key is older than `current`, the key of
+    /// the latest applied report.
+    pub fn is_stale(&self, current: &BootKey) -> bool {
+        if let (Some(report), Some(current)) = (self.generation, current.generation) {
+            if report != current {
+                return report < current;
+            }
+        }
+        match (self.boot_id.as_deref(), current.boot_id.as_deref()) {
+            (Some(report), Some(current)) if report != current => {
+                match (report.parse::<BootId>(), current.parse::<BootId>()) {
+                    (Ok(report), Ok(current)) => report < current,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Key of the latest applied status report for an instance, if any.
+pub async fn current_key<'e, E>(executor: E, instance_id: &str) -> sqlx::Result<Option<BootKey>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, BootKey>(
+        "SELECT generation, boot_id FROM instances_status_view WHERE instance_id = $1",
+    )
+    .bind(instance_id)
+    .fetch_optional(executor)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(generation: Option<i32>, boot_id: Option<&BootId>) -> BootKey {
+        BootKey::new(generation, boot_id.map(|id| id.to_string()))
+    }
+
+    #[test]
+    fn test_generation_orders_reports() {
+        let earlier = BootId::new();
+        let later = BootId::new();
+        let current = key(Some(3), Some(&earlier));
+
+        assert!(key(Some(2), Some(&later)).is_stale(&current));
+        assert!(!key(Some(4), Some(&earlier)).is_stale(&current));
+        assert!(!key(Some(3), Some(&earlier)).is_stale(&current));
+    }
+
+    #[test]
+    fn test_boot_id_orders_reports_within_generation() {
+        let earlier = BootId::from_ulid(plfm_id::Ulid::from_parts(1_000, 7));
+        let later = BootId::from_ulid(plfm_id::Ulid::from_parts(2_000, 1));
+
+        assert!(key(Some(1), Some(&earlier)).is_stale(&key(Some(1), Some(&later))));
+        assert!(!key(Some(1), Some(&later)).is_stale(&key(Some(1), Some(&earlier))));
+        // An unknown generation falls back to the boot ID.
+        assert!(key(None, Some(&earlier)).is_stale(&key(Some(1), Some(&later))));
+    }
+
+    #[test]
+    fn test_unordered_reports_are_applied() {
+        let boot = BootId::new();
+        let current = key(Some(1), Some(&boot));
+
+        assert!(!BootKey::default().is_stale(&current));
+        assert!(!key(Some(1), None).is_stale(&current));
+        assert!(!key(None, None).is_stale(&BootKey::default()));
+        // Boot IDs from agents that predate ULID boot IDs.
+        let legacy = BootKey::new(Some(1), Some("boot_0000000000000001".to_string()));
+        assert!(!legacy.is_stale(&current));
+        assert!(!current.is_stale(&legacy));
+    }
+}
@@ -0,0 +1,55 @@
+//! Internal (service-to-service) routes.
+//!
+//! A route created with `internal: true` is only served by ingress listeners
+//! bound to overlay addresses (`GHOST_INTERNAL_LISTENERS`), and its hostname
+//! only resolves through the node-local DNS responder every node agent runs
+//! on the overlay. Nothing about it is published to public DNS, so internal
+//! routes skip hostname verification.
+//!
+//! Internal hostnames live under a dedicated suffix so they can never collide
+//! with, or be confused for, public names: internal routes must use it and
+//! public routes may not.
+//!
+//! Configuration:
+//! - `PLFM_INTERNAL_ROUTE_SUFFIX`: hostname suffix for internal routes
+//!   (default `internal`); must match the node agents' resolver
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+/// Default suffix for internal route hostnames.
+pub const DEFAULT_SUFFIX: &str = "internal";
+
+/// Suffix internal route hostnames live under, without leading or trailing
+/// dots.
+pub fn suffix() -> String {
+    std::env::var("PLFM_INTERNAL_ROUTE_SUFFIX")
+        .ok()
+        .map(|v| v.trim().trim_matches('.').to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_SUFFIX.to_string())
+}
+
+/// Whether `hostname` is a name under `suffix` (the bare suffix itself is not).
+pub fn is_internal_hostname(hostname: &str, suffix: &str) -> bool {
+    hostname
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+        .strip_suffix(suffix)
+        .and_then(|head| head.strip_suffix('.'))
+        .is_some_and(|head| !head.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_internal_hostname() {
+        assert!(is_internal_hostname("api.billing.internal", "internal"));
+        assert!(is_internal_hostname("API.Billing.Internal.", "internal"));
+        assert!(is_internal_hostname("db.svc.corp", "svc.corp"));
+        assert!(!is_internal_hostname("internal", "internal"));
+        assert!(!is_internal_hostname("api.example.com", "internal"));
+        assert!(!is_internal_hostname("api.notinternal", "internal"));
+    }
+}
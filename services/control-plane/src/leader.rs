@@ -0,0 +1,317 @@
+//! Leader election for singleton background workers.
+//!
+//! These workers must run on exactly one control-plane replica at a time:
+//! - scheduler
+//! - cleanup
+//! - route verifier
+//! - managed DNS
+//! - drift detector
+//! - backup scheduler
+//! - notifier
+//! - alert evaluator
+//! - event checkpoint
+//! - node watchdog
+//! - event export
+//!
+//! Each role is guarded by a Postgres session-level advisory lock held on a
+//! dedicated connection: the replica whose session holds the lock is the
+//! leader, and the lock is released by Postgres as soon as that session ends
+//! (process exit, crash, or network loss).
+//!
+//! The leader re-checks its session before every pass and renews a row in
+//! `worker_leases` (`leader:<role>`) so operators can see which replica
+//! leads. The row is informational; the advisory lock is what excludes.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Connection, PgConnection, PgPool};
+use tracing::{info, warn};
+
+use crate::db::LeaseStore;
+
+/// Prefix of the informational worker lease naming a role's leader.
+pub const LEADER_LEASE_PREFIX: &str = "leader:";
+
+/// Identifies this control-plane process as a lease holder or leader.
+pub fn replica_id() -> &'static str {
+    static REPLICA_ID: OnceLock<String> = OnceLock::new();
+    REPLICA_ID.get_or_init(|| {
+        format!(
+            "{}-{}",
+            std::env::var("HOSTNAME").unwrap_or_else(|_| "control-plane".to_string()),
+            plfm_id::Ulid::new()
+        )
+    })
+}
+
+/// A singleton background role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LeaderRole {
+    Scheduler,
+    Cleanup,
+    RouteVerifier,
+    ManagedDns,
+    DriftDetector,
+    BackupScheduler,
+    Notifier,
+    Alerts,
+    EventCheckpointer,
+    NodeWatchdog,
+    EventExporter,
+}
+
+impl LeaderRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LeaderRole::Scheduler => "scheduler",
+            LeaderRole::Cleanup => "cleanup",
+            LeaderRole::RouteVerifier => "route_verifier",
+            LeaderRole::ManagedDns => "managed_dns",
+            LeaderRole::DriftDetector => "drift_detector",
+            LeaderRole::BackupScheduler => "backup_scheduler",
+            LeaderRole::Notifier => "notifier",
+            LeaderRole::Alerts => "alerts",
+            LeaderRole::EventCheckpointer => "event_checkpointer",
+            LeaderRole::NodeWatchdog => "node_watchdog",
+            LeaderRole::EventExporter => "event_exporter",
+        }
+    }
+
+    /// Advisory lock key. Fixed values so every replica agrees.
+    fn lock_key(self) -> i64 {
+        // "plfm" in the high bytes keeps clear of other advisory lock users.
+        const BASE: i64 = 0x706c_666d_0000_0000;
+        match self {
+            LeaderRole::Scheduler => BASE + 1,
+            LeaderRole::Cleanup => BASE + 2,
+            LeaderRole::RouteVerifier => BASE + 3,
+            LeaderRole::ManagedDns => BASE + 4,
+            LeaderRole::DriftDetector => BASE + 5,
+            LeaderRole::BackupScheduler => BASE + 6,
+            LeaderRole::Notifier => BASE + 7,
+            LeaderRole::Alerts => BASE + 8,
+            LeaderRole::EventCheckpointer => BASE + 9,
+            LeaderRole::NodeWatchdog => BASE + 10,
+            LeaderRole::EventExporter => BASE + 11,
+        }
+    }
+
+    fn lease_name(self) -> String {
+        format!("{LEADER_LEASE_PREFIX}{}", self.as_str())
+    }
+}
+
+/// This process's view of one role.
+#[derive(Debug, Clone, Serialize)]
+pub struct LeadershipSnapshot {
+    pub role: &'static str,
+    pub is_leader: bool,
+    pub leader_since: Option<DateTime<Utc>>,
+    /// Times this process gained or lost leadership.
+    pub transitions: u64,
+}
+
+/// Leadership state for this process, per role.
+#[derive(Debug)]
+pub struct LeaderMetrics {
+    roles: Mutex<BTreeMap<LeaderRole, (Option<DateTime<Utc>>, u64)>>,
+}
+
+/// Leadership of this process (exposed via `GET /v1/_admin/leaders`).
+pub static METRICS: LeaderMetrics = LeaderMetrics::new();
+
+impl LeaderMetrics {
+    const fn new() -> Self {
+        Self {
+            roles: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn set(&self, role: LeaderRole, is_leader: bool) {
+        let mut roles = self.roles.lock().unwrap_or_else(|e| e.into_inner());
+        let (since, transitions) = roles.entry(role).or_insert((None, 0));
+        if since.is_some() != is_leader {
+            *since = is_leader.then(Utc::now);
+            *transitions += 1;
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<LeadershipSnapshot> {
+        let roles = self.roles.lock().unwrap_or_else(|e| e.into_inner());
+        roles
+            .iter()
+            .map(|(role, (since, transitions))| LeadershipSnapshot {
+                role: role.as_str(),
+                is_leader: since.is_some(),
+                leader_since: *since,
+                transitions: *transitions,
+            })
+            .collect()
+    }
+}
+
+/// Leader election for one role.
+pub struct LeaderElection {
+    pool: PgPool,
+    role: LeaderRole,
+    lease_ttl: Duration,
+    /// The session holding the advisory lock, while leader.
+    session: tokio::sync::Mutex<Option<PgConnection>>,
+}
+
+impl LeaderElection {
+    /// `lease_ttl` bounds how stale the informational lease row can get; it
+    /// should comfortably exceed the interval between [`ensure_leader`]
+    /// calls.
+    ///
+    /// [`ensure_leader`]: LeaderElection::ensure_leader
+    pub fn new(pool: PgPool, role: LeaderRole, lease_ttl: Duration) -> Self {
+        Self {
+            pool,
+            role,
+            lease_ttl,
+            session: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Whether this replica leads the role, acquiring leadership if it is
+    /// free. Call before each pass of singleton work.
+    pub async fn ensure_leader(&self) -> bool {
+        let mut session = self.session.lock().await;
+
+        if let Some(conn) = session.as_mut() {
+            if let Err(e) = conn.ping().await {
+                // The session is gone, and with it the lock.
+                warn!(error = %e, role = self.role.as_str(), "Leader session lost");
+                *session = None;
+                METRICS.set(self.role, false);
+            }
+        }
+
+        if session.is_none() {
+            match self.try_acquire().await {
+                Ok(Some(conn)) => {
+                    info!(
+                        role = self.role.as_str(),
+                        replica = replica_id(),
+                        "Acquired leadership"
+                    );
+                    *session = Some(conn);
+                    METRICS.set(self.role, true);
+                }
+                Ok(None) => {
+                    METRICS.set(self.role, false);
+                    return false;
+                }
+                Err(e) => {
+                    warn!(error = %e, role = self.role.as_str(), "Leader election failed");
+                    METRICS.set(self.role, false);
+                    return false;
+                }
+            }
+        }
+
+        if let Err(e) = LeaseStore::new(self.pool.clone())
+            .acquire(&[self.role.lease_name()], replica_id(), self.lease_ttl)
+            .await
+        {
+            warn!(error = %e, role = self.role.as_str(), "Failed to renew leader lease");
+        }
+        true
+    }
+
+    /// Give up leadership (on shutdown) so another replica takes over at once.
+    pub async fn resign(&self) {
+        let mut session = self.session.lock().await;
+        let Some(mut conn) = session.take() else {
+            return;
+        };
+
+        if let Err(e) = sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(self.role.lock_key())
+            .execute(&mut conn)
+            .await
+        {
+            warn!(error = %e, role = self.role.as_str(), "Failed to release leader lock");
+        }
+        let _ = conn.close().await;
+
+        if let Err(e) = LeaseStore::new(self.pool.clone())
+            .release(&self.role.lease_name(), replica_id())
+            .await
+        {
+            warn!(error = %e, role = self.role.as_str(), "Failed to release leader lease");
+        }
+        METRICS.set(self.role, false);
+        info!(role = self.role.as_str(), "Resigned leadership");
+    }
+
+    /// Open a dedicated session and try to take the role's lock on it.
+    async fn try_acquire(&self) -> Result<Option<PgConnection>, sqlx::Error> {
+        // Detached from the pool: the lock must never ride along on a
+        // connection handed to some other query.
+        let mut conn = self.pool.acquire().await?.detach();
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(self.role.lock_key())
+            .fetch_one(&mut conn)
+            .await?;
+
+        if acquired {
+            Ok(Some(conn))
+        } else {
+            let _ = conn.close().await;
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_lock_keys_are_distinct() {
+        let roles = [
+            LeaderRole::Scheduler,
+            LeaderRole::Cleanup,
+            LeaderRole::RouteVerifier,
+            LeaderRole::ManagedDns,
+            LeaderRole::DriftDetector,
+            LeaderRole::BackupScheduler,
+            LeaderRole::Notifier,
+            LeaderRole::Alerts,
+            LeaderRole::EventCheckpointer,
+            LeaderRole::NodeWatchdog,
+            LeaderRole::EventExporter,
+        ];
+        let keys: HashSet<i64> = roles.iter().map(|role| role.lock_key()).collect();
+        assert_eq!(keys.len(), roles.len());
+        assert_eq!(LeaderRole::Scheduler.lease_name(), "leader:scheduler");
+    }
+
+    #[test]
+    fn test_metrics_count_transitions() {
+        let metrics = LeaderMetrics::new();
+        metrics.set(LeaderRole::Scheduler, false);
+        metrics.set(LeaderRole::Scheduler, true);
+        metrics.set(LeaderRole::Scheduler, true);
+        metrics.set(LeaderRole::Scheduler, false);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].role, "scheduler");
+        assert!(!snapshot[0].is_leader);
+        assert_eq!(snapshot[0].transitions, 2);
+    }
+
+    #[test]
+    fn test_replica_id_is_stable() {
+        assert_eq!(replica_id(), replica_id());
+    }
+}
@@ -3,12 +3,36 @@
 //! This crate primarily ships a `control-plane` binary, but we expose a small
 //! library surface to enable integration testing and reuse.
 
+pub mod aggregates;
+pub mod alerts;
 pub mod api;
+pub mod backups;
 pub mod cleanup;
 pub mod config;
 pub mod db;
+pub mod drift;
+pub mod enrollment;
+pub mod event_export;
+pub mod event_integrity;
 pub mod grpc;
+pub mod identity_tokens;
+pub mod instance_status;
+pub mod internal_routes;
+pub mod leader;
+pub mod managed_dns;
+pub mod node_watchdog;
+pub mod notifications;
+pub mod pki;
 pub mod projections;
+pub mod readiness;
+pub mod route_access;
+pub mod route_certs;
+pub mod route_verification;
 pub mod scheduler;
 pub mod secrets;
+pub mod server;
+pub mod slo;
 pub mod state;
+pub mod status_page;
+pub mod timeline;
+pub mod trash;
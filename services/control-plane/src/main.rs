@@ -1,24 +1,25 @@
+//! plfm-vt control plane binary.
+//!
+//! - `control-plane`: serve the API (refuses to start against an
+//!   incompatible schema outside dev mode)
+//! - `control-plane migrate`: apply pending migrations
+//! - `control-plane migrate status`: compare the schema with this binary's
+
 use anyhow::Result;
 use plfm_control_plane::{
-    api,
-    cleanup::{CleanupWorker, CleanupWorkerConfig},
     config,
-    db::Database,
-    grpc::NodeAgentService,
-    projections::{worker::WorkerConfig, ProjectionWorker},
-    scheduler::SchedulerWorker,
-    state::AppState,
+    db::{schema, Database, Storage},
+    server::{self, ServerAddrs},
 };
-use plfm_proto::agent::v1::NodeAgentServer;
 use tokio::sync::watch;
-use tonic::transport::Server as TonicServer;
-use tracing::{error, info, warn};
+use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load configuration
     let config = config::Config::from_env()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
     // Initialize tracing (prefer RUST_LOG, fallback to GHOST_LOG_LEVEL)
     tracing_subscriber::registry()
@@ -34,18 +35,36 @@ async fn main() -> Result<()> {
     );
 
     // Connect to database
-    let db = match Database::connect(&config.database).await {
-        Ok(db) => {
-            info!("Database connection established");
-            db
-        }
+    let db = match Storage::connect(&config.database).await {
+        Ok(storage) => match storage.postgres() {
+            Some(db) => {
+                info!("Database connection established");
+                db.clone()
+            }
+            None => {
+                // Views and API handlers still query Postgres directly.
+                error!(
+                    backend = storage.kind().as_str(),
+                    "The API server requires a Postgres DATABASE_URL; this backend only serves the event log, checkpoints, and idempotency records"
+                );
+                anyhow::bail!(
+                    "unsupported DATABASE_URL backend for the API server: {}",
+                    storage.kind().as_str()
+                );
+            }
+        },
         Err(e) => {
             error!(error = %e, "Failed to connect to database");
             return Err(e.into());
         }
     };
 
-    // Run migrations in dev mode
+    if args.first().map(String::as_str) == Some("migrate") {
+        return run_migrate_command(&db, &args[1..]).await;
+    }
+
+    // Dev mode migrates on startup; production runs `control-plane migrate`
+    // before rolling out a new binary.
     if config.dev_mode {
         info!("Running database migrations (dev mode)");
         if let Err(e) = db.run_migrations().await {
@@ -54,123 +73,64 @@ async fn main() -> Result<()> {
         }
     }
 
+    let status = db.schema_status().await?;
+    if !status.is_compatible() {
+        error!(
+            schema_version = schema::schema_version(),
+            status = %status,
+            "Database schema is incompatible with this binary"
+        );
+        anyhow::bail!("incompatible database schema: {status}");
+    }
+    info!(
+        schema_version = schema::schema_version(),
+        status = %status,
+        "Database schema is compatible"
+    );
+
     // Create shutdown channel for graceful shutdown
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
-
-    // Start projection worker in background
-    let projection_worker = ProjectionWorker::new(db.pool().clone(), WorkerConfig::default());
-    let projection_handle = tokio::spawn({
-        let shutdown_rx = shutdown_rx.clone();
-        async move {
-            if let Err(e) = projection_worker.run(shutdown_rx).await {
-                error!(error = %e, "Projection worker failed");
-            }
-        }
-    });
-
-    // Start scheduler worker in background
-    let scheduler_worker =
-        SchedulerWorker::new(db.pool().clone(), std::time::Duration::from_secs(5));
-    let scheduler_handle = tokio::spawn({
-        let shutdown_rx = shutdown_rx.clone();
-        async move {
-            scheduler_worker.run(shutdown_rx).await;
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = shutdown_tx.send(true);
         }
     });
 
-    // Start cleanup worker in background
-    let cleanup_worker = CleanupWorker::new(db.pool().clone(), CleanupWorkerConfig::default());
-    let cleanup_handle = tokio::spawn({
-        let shutdown_rx = shutdown_rx.clone();
-        async move {
-            cleanup_worker.run(shutdown_rx).await;
-        }
-    });
-
-    let state = AppState::new(db);
-
-    let app = api::create_router(state.clone());
-    let listener = tokio::net::TcpListener::bind(&config.listen_addr).await?;
-    info!(addr = %config.listen_addr, "Listening for HTTP connections");
-
-    let http_shutdown_rx = shutdown_rx.clone();
-    let server_handle = tokio::spawn(async move {
-        axum::serve(listener, app)
-            .with_graceful_shutdown(async move {
-                let mut shutdown_rx = http_shutdown_rx;
-                loop {
-                    if *shutdown_rx.borrow() {
-                        break;
-                    }
-                    if shutdown_rx.changed().await.is_err() {
-                        break;
-                    }
-                }
-                info!("HTTP server shutting down");
-            })
-            .await
-    });
-
-    let node_agent_service = NodeAgentService::new(state);
-    let grpc_addr = config.grpc_listen_addr;
-    info!(addr = %grpc_addr, "Listening for gRPC connections");
+    let addrs = ServerAddrs {
+        http: config.listen_addr,
+        grpc: config.grpc_listen_addr,
+    };
+    server::run(db, addrs, shutdown_rx).await?;
 
-    let grpc_shutdown_rx = shutdown_rx.clone();
-    let grpc_handle = tokio::spawn(async move {
-        TonicServer::builder()
-            .add_service(NodeAgentServer::new(node_agent_service))
-            .serve_with_shutdown(grpc_addr, async move {
-                let mut shutdown_rx = grpc_shutdown_rx;
-                loop {
-                    if *shutdown_rx.borrow() {
-                        break;
-                    }
-                    if shutdown_rx.changed().await.is_err() {
-                        break;
-                    }
-                }
-                info!("gRPC server shutting down");
-            })
-            .await
-    });
+    info!("Control plane shutdown complete");
+    Ok(())
+}
 
-    tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
-            info!("Received shutdown signal");
+/// `control-plane migrate` / `control-plane migrate status`.
+///
+/// Migrations take an advisory lock, so running this from several places
+/// at once is safe. `status` exits non-zero when this binary could not
+/// start against the schema.
+async fn run_migrate_command(db: &Database, args: &[String]) -> Result<()> {
+    let usage = "usage: control-plane migrate [status]";
+    match args.first().map(String::as_str) {
+        None => {
+            let before = db.schema_status().await?;
+            info!(status = %before, "Schema before migrating");
+            db.run_migrations().await?;
+            let after = db.schema_status().await?;
+            println!("{after}");
+            Ok(())
         }
-        result = server_handle => {
-            match result {
-                Ok(Ok(())) => info!("HTTP server exited normally"),
-                Ok(Err(e)) => error!(error = %e, "HTTP server error"),
-                Err(e) => error!(error = %e, "HTTP server task panicked"),
+        Some("status") => {
+            let status = db.schema_status().await?;
+            println!("binary schema version: {}", schema::schema_version());
+            println!("{status}");
+            if !status.is_compatible() {
+                anyhow::bail!("this binary cannot start against the schema");
             }
+            Ok(())
         }
-        result = grpc_handle => {
-            match result {
-                Ok(Ok(())) => info!("gRPC server exited normally"),
-                Ok(Err(e)) => error!(error = %e, "gRPC server error"),
-                Err(e) => error!(error = %e, "gRPC server task panicked"),
-            }
-        }
-    }
-
-    let _ = shutdown_tx.send(true);
-
-    info!("Waiting for workers to shut down...");
-    let shutdown_timeout = std::time::Duration::from_secs(10);
-
-    if let Err(e) = tokio::time::timeout(shutdown_timeout, projection_handle).await {
-        warn!(error = %e, "Projection worker did not shut down in time");
-    }
-
-    if let Err(e) = tokio::time::timeout(shutdown_timeout, scheduler_handle).await {
-        warn!(error = %e, "Scheduler worker did not shut down in time");
+        Some(_) => anyhow::bail!(usage),
     }
-
-    if let Err(e) = tokio::time::timeout(shutdown_timeout, cleanup_handle).await {
-        warn!(error = %e, "Cleanup worker did not shut down in time");
-    }
-
-    info!("Control plane shutdown complete");
-    Ok(())
 }
@@ -0,0 +1,584 @@
+//! Platform-managed env hostnames.
+//!
+//! With a platform domain configured, every new env is allocated
+//! `<env>-<app>-<org>.apps.<platform-domain>` at creation, and a route for
+//! that hostname is created alongside it (see `api::v1::envs`). The hostname
+//! is fixed for the life of the env; renaming the env or app does not move it.
+//!
+//! The DNS publisher worker keeps one AAAA record set per live env pointing at
+//! the ingress nodes, diffing live envs against `managed_dns_records` (what was
+//! last written to the provider) on every pass. Records for deleted envs, or
+//! envs being torn down, are removed.
+//!
+//! Configuration:
+//! - `PLFM_PLATFORM_DOMAIN`: enables managed hostnames (e.g. `plfm.example.net`)
+//! - `PLFM_MANAGED_DNS_PROVIDER`: `route53`, `cloudflare`, or `external-dns`
+//!   (see [`providers`])
+//! - `PLFM_MANAGED_DNS_TARGETS`: comma-separated IPv6 addresses of the ingress
+//!   nodes the AAAA records point at
+//! - `PLFM_MANAGED_DNS_TTL`: record TTL in seconds (default 60)
+//! - `PLFM_MANAGED_ROUTE_LISTEN_PORT` (default 443),
+//!   `PLFM_MANAGED_ROUTE_PROTOCOL` (default `tls_passthrough`),
+//!   `PLFM_MANAGED_ROUTE_PROCESS_TYPE` (default `web`),
+//!   `PLFM_MANAGED_ROUTE_BACKEND_PORT` (default 8443): the route created for
+//!   each managed hostname
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+pub mod providers;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::Ipv6Addr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use plfm_events::RouteProtocolHint;
+use sha2::{Digest, Sha256};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use crate::leader::{LeaderElection, LeaderRole};
+
+use self::providers::DnsProvider;
+
+/// Label between the env label and the platform domain.
+pub const APPS_LABEL: &str = "apps";
+
+const MAX_LABEL_LEN: usize = 63;
+const DEFAULT_TTL: u32 = 60;
+
+/// Hex characters of the env ID hash used to disambiguate labels.
+const SUFFIX_LEN: usize = 6;
+
+#[derive(Debug, Error)]
+pub enum ManagedDnsError {
+    #[error("managed DNS configuration: {0}")]
+    Config(String),
+    #[error("{provider}: {message}")]
+    Provider {
+        provider: &'static str,
+        message: String,
+    },
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Platform domain managed hostnames live under; managed hostnames are off
+/// without it.
+pub fn platform_domain() -> Option<String> {
+    env("PLFM_PLATFORM_DOMAIN").map(|v| v.trim_matches('.').to_ascii_lowercase())
+}
+
+/// `apps.<platform-domain>`.
+pub fn apps_domain(platform_domain: &str) -> String {
+    format!("{APPS_LABEL}.{platform_domain}")
+}
+
+/// Whether `hostname` is inside the managed namespace and so cannot be
+/// claimed by a tenant route.
+pub fn is_reserved(hostname: &str, platform_domain: &str) -> bool {
+    let hostname = hostname.trim_end_matches('.').to_ascii_lowercase();
+    let apps = apps_domain(platform_domain);
+    hostname == apps || hostname.ends_with(&format!(".{apps}"))
+}
+
+/// Lower-case a name into a DNS label fragment: runs of anything other than
+/// `[a-z0-9]` become a single `-`.
+fn slug(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars().map(|c| c.to_ascii_lowercase()) {
+        if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
+fn env_suffix(env_id: &str) -> String {
+    hex::encode(Sha256::digest(env_id.as_bytes()))[..SUFFIX_LEN].to_string()
+}
+
+fn with_suffix(label: &str, env_id: &str) -> String {
+    let keep = MAX_LABEL_LEN - SUFFIX_LEN - 1;
+    let head = label[..label.len().min(keep)].trim_end_matches('-');
+    let head = if head.is_empty() { "env" } else { head };
+    format!("{head}-{}", env_suffix(env_id))
+}
+
+/// The `<env>-<app>-<org>` label. Labels longer than 63 characters are
+/// truncated and suffixed with a hash of the env ID.
+pub fn hostname_label(env_name: &str, app_name: &str, org_name: &str, env_id: &str) -> String {
+    let label = [slug(env_name), slug(app_name), slug(org_name)]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        with_suffix(&label, env_id)
+    } else {
+        label
+    }
+}
+
+/// Allocate the managed hostname for a new env, or `None` when managed
+/// hostnames are off. If the natural name is taken (by another env or any
+/// route), the env ID hash is appended.
+pub async fn allocate(
+    pool: &PgPool,
+    env_id: &str,
+    env_name: &str,
+    app_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let Some(domain) = platform_domain() else {
+        return Ok(None);
+    };
+
+    let names: Option<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT a.name, o.name
+        FROM apps_view a
+        JOIN orgs_view o ON o.org_id = a.org_id
+        WHERE a.app_id = $1
+        "#,
+    )
+    .bind(app_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((app_name, org_name)) = names else {
+        return Ok(None);
+    };
+
+    let apps = apps_domain(&domain);
+    let label = hostname_label(env_name, &app_name, &org_name, env_id);
+    let hostname = format!("{label}.{apps}");
+    if !hostname_taken(pool, &hostname).await? {
+        return Ok(Some(hostname));
+    }
+    Ok(Some(format!("{}.{apps}", with_suffix(&label, env_id))))
+}
+
+async fn hostname_taken(pool: &PgPool, hostname: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT
+          EXISTS (SELECT 1 FROM envs_view WHERE managed_hostname = $1 AND NOT is_deleted)
+          OR EXISTS (SELECT 1 FROM routes_view WHERE hostname = $1 AND NOT is_deleted)
+        "#,
+    )
+    .bind(hostname)
+    .fetch_one(pool)
+    .await
+}
+
+/// The route created for each managed hostname.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedRouteTemplate {
+    pub listen_port: i32,
+    pub protocol_hint: RouteProtocolHint,
+    pub backend_process_type: String,
+    pub backend_port: i32,
+}
+
+impl ManagedRouteTemplate {
+    pub fn from_env() -> Result<Self, ManagedDnsError> {
+        let port = |name: &str, default: i32| match env(name) {
+            None => Ok(default),
+            Some(v) => v
+                .parse::<i32>()
+                .ok()
+                .filter(|p| (1..=65535).contains(p))
+                .ok_or_else(|| ManagedDnsError::Config(format!("invalid {name} '{v}'"))),
+        };
+        let protocol_hint = match env("PLFM_MANAGED_ROUTE_PROTOCOL").as_deref() {
+            None | Some("tls_passthrough") => RouteProtocolHint::TlsPassthrough,
+            Some("tls_terminate") => RouteProtocolHint::TlsTerminate,
+            Some("tcp_raw") => RouteProtocolHint::TcpRaw,
+            Some(other) => {
+                return Err(ManagedDnsError::Config(format!(
+                    "invalid PLFM_MANAGED_ROUTE_PROTOCOL '{other}'"
+                )))
+            }
+        };
+        Ok(Self {
+            listen_port: port("PLFM_MANAGED_ROUTE_LISTEN_PORT", 443)?,
+            protocol_hint,
+            backend_process_type: env("PLFM_MANAGED_ROUTE_PROCESS_TYPE")
+                .unwrap_or_else(|| "web".to_string()),
+            backend_port: port("PLFM_MANAGED_ROUTE_BACKEND_PORT", 8443)?,
+        })
+    }
+}
+
+/// Ingress addresses managed hostnames resolve to.
+pub fn ingress_targets() -> Result<Vec<Ipv6Addr>, ManagedDnsError> {
+    let raw = env("PLFM_MANAGED_DNS_TARGETS").ok_or_else(|| {
+        ManagedDnsError::Config("PLFM_MANAGED_DNS_TARGETS is not set".to_string())
+    })?;
+    let targets: BTreeSet<Ipv6Addr> = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<Ipv6Addr>().map_err(|_| {
+                ManagedDnsError::Config(format!(
+                    "invalid IPv6 address '{v}' in PLFM_MANAGED_DNS_TARGETS"
+                ))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    if targets.is_empty() {
+        return Err(ManagedDnsError::Config(
+            "PLFM_MANAGED_DNS_TARGETS is empty".to_string(),
+        ));
+    }
+    Ok(targets.into_iter().collect())
+}
+
+/// Record TTL in seconds.
+pub fn record_ttl() -> u32 {
+    env("PLFM_MANAGED_DNS_TTL")
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|ttl| *ttl > 0)
+        .unwrap_or(DEFAULT_TTL)
+}
+
+// =============================================================================
+// Publishing
+// =============================================================================
+
+/// A record set as last written to the provider.
+#[derive(Debug, Clone)]
+pub struct PublishedRecord {
+    pub hostname: String,
+    pub env_id: String,
+    pub provider: String,
+    pub targets: Vec<String>,
+    pub published: bool,
+}
+
+impl<'r> FromRow<'r, PgRow> for PublishedRecord {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let published_at: Option<chrono::DateTime<chrono::Utc>> = row.try_get("published_at")?;
+        Ok(Self {
+            hostname: row.try_get("hostname")?,
+            env_id: row.try_get("env_id")?,
+            provider: row.try_get("provider")?,
+            targets: row.try_get("targets")?,
+            published: published_at.is_some(),
+        })
+    }
+}
+
+/// A change the publisher must make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordChange {
+    Upsert {
+        hostname: String,
+        env_id: String,
+    },
+    Delete {
+        hostname: String,
+        targets: Vec<Ipv6Addr>,
+    },
+}
+
+/// Diff live managed hostnames (`hostname -> env_id`) against what was
+/// published. Records published through a different provider are rewritten.
+pub fn plan(
+    desired: &BTreeMap<String, String>,
+    published: &[PublishedRecord],
+    provider: &str,
+    targets: &[Ipv6Addr],
+) -> Vec<RecordChange> {
+    let want = sorted(&targets.iter().map(ToString::to_string).collect::<Vec<_>>());
+    let by_host: BTreeMap<&str, &PublishedRecord> =
+        published.iter().map(|r| (r.hostname.as_str(), r)).collect();
+
+    let mut changes = Vec::new();
+    for (hostname, env_id) in desired {
+        let current = by_host.get(hostname.as_str());
+        let up_to_date = current
+            .is_some_and(|r| r.published && r.provider == provider && sorted(&r.targets) == want);
+        if !up_to_date {
+            changes.push(RecordChange::Upsert {
+                hostname: hostname.clone(),
+                env_id: env_id.clone(),
+            });
+        }
+    }
+    for record in published {
+        if !desired.contains_key(&record.hostname) {
+            changes.push(RecordChange::Delete {
+                hostname: record.hostname.clone(),
+                targets: record
+                    .targets
+                    .iter()
+                    .filter_map(|t| t.parse().ok())
+                    .collect(),
+            });
+        }
+    }
+    changes
+}
+
+fn sorted(targets: &[String]) -> Vec<String> {
+    let set: BTreeSet<Ipv6Addr> = targets.iter().filter_map(|t| t.parse().ok()).collect();
+    set.into_iter().map(|t| t.to_string()).collect()
+}
+
+/// Publish or remove records until DNS matches the live envs. Returns how
+/// many record sets changed; provider failures are recorded per hostname and
+/// retried on the next pass.
+pub async fn run_pass(
+    pool: &PgPool,
+    provider: &dyn DnsProvider,
+    targets: &[Ipv6Addr],
+    ttl: u32,
+) -> Result<usize, ManagedDnsError> {
+    let desired: BTreeMap<String, String> = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT managed_hostname, env_id
+        FROM envs_view
+        WHERE managed_hostname IS NOT NULL
+          AND NOT is_deleted
+          AND deletion_requested_at IS NULL
+        "#,
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let published = sqlx::query_as::<_, PublishedRecord>(
+        "SELECT hostname, env_id, provider, targets, published_at FROM managed_dns_records",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let target_strings: Vec<String> = targets.iter().map(ToString::to_string).collect();
+    let mut changed = 0;
+
+    for change in plan(&desired, &published, provider.name(), targets) {
+        match change {
+            RecordChange::Upsert { hostname, env_id } => {
+                let result = provider.upsert_aaaa(&hostname, targets, ttl).await;
+                let error = result.as_ref().err().map(ToString::to_string);
+                sqlx::query(
+                    r#"
+                    INSERT INTO managed_dns_records
+                        (hostname, env_id, provider, targets, published_at, last_error, updated_at)
+                    VALUES ($1, $2, $3, $4, CASE WHEN $5::TEXT IS NULL THEN now() END, $5, now())
+                    ON CONFLICT (hostname) DO UPDATE SET
+                        env_id = EXCLUDED.env_id,
+                        provider = EXCLUDED.provider,
+                        targets = EXCLUDED.targets,
+                        published_at = COALESCE(EXCLUDED.published_at, managed_dns_records.published_at),
+                        last_error = EXCLUDED.last_error,
+                        updated_at = EXCLUDED.updated_at
+                    "#,
+                )
+                .bind(&hostname)
+                .bind(&env_id)
+                .bind(provider.name())
+                .bind(&target_strings)
+                .bind(&error)
+                .execute(pool)
+                .await?;
+                match error {
+                    None => {
+                        info!(hostname = %hostname, env_id = %env_id, "Published managed hostname");
+                        changed += 1;
+                    }
+                    Some(e) => {
+                        warn!(hostname = %hostname, error = %e, "Failed to publish managed hostname")
+                    }
+                }
+            }
+            RecordChange::Delete { hostname, targets } => {
+                match provider.delete_aaaa(&hostname, &targets, ttl).await {
+                    Ok(()) => {
+                        sqlx::query("DELETE FROM managed_dns_records WHERE hostname = $1")
+                            .bind(&hostname)
+                            .execute(pool)
+                            .await?;
+                        info!(hostname = %hostname, "Removed managed hostname");
+                        changed += 1;
+                    }
+                    Err(e) => {
+                        sqlx::query(
+                            "UPDATE managed_dns_records SET last_error = $2, updated_at = now() WHERE hostname = $1",
+                        )
+                        .bind(&hostname)
+                        .bind(e.to_string())
+                        .execute(pool)
+                        .await?;
+                        warn!(hostname = %hostname, error = %e, "Failed to remove managed hostname");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker publishing managed hostnames, on the elected leader only.
+pub struct ManagedDnsWorker {
+    pool: PgPool,
+    interval: Duration,
+    election: LeaderElection,
+    publisher: Option<(Arc<dyn DnsProvider>, Vec<Ipv6Addr>)>,
+}
+
+impl ManagedDnsWorker {
+    pub fn new(pool: PgPool, interval: Duration) -> Self {
+        let publisher = if platform_domain().is_none() {
+            None
+        } else {
+            match providers::from_env().and_then(|p| ingress_targets().map(|t| (p, t))) {
+                Ok(publisher) => Some(publisher),
+                Err(e) => {
+                    error!(error = %e, "Managed DNS is not configured; managed hostnames will not resolve");
+                    None
+                }
+            }
+        };
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::ManagedDns, interval * 3),
+            pool,
+            interval,
+            publisher,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        let Some((provider, targets)) = &self.publisher else {
+            return;
+        };
+        info!(
+            interval_secs = self.interval.as_secs(),
+            provider = provider.name(),
+            "Starting managed DNS worker"
+        );
+
+        let ttl = record_ttl();
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    match run_pass(&self.pool, provider.as_ref(), targets, ttl).await {
+                        Ok(count) if count > 0 => info!(changed = count, "Managed DNS pass complete"),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Managed DNS pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Managed DNS worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(hostname: &str, provider: &str, targets: &[&str]) -> PublishedRecord {
+        PublishedRecord {
+            hostname: hostname.to_string(),
+            env_id: "env_1".to_string(),
+            provider: provider.to_string(),
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+            published: true,
+        }
+    }
+
+    #[test]
+    fn test_hostname_label() {
+        assert_eq!(
+            hostname_label("Production", "my_api", "Acme Corp", "env_1"),
+            "production-my-api-acme-corp"
+        );
+
+        let long = "x".repeat(40);
+        let label = hostname_label(&long, &long, "acme", "env_1");
+        assert_eq!(label.len(), MAX_LABEL_LEN);
+        assert!(label.ends_with(&format!("-{}", env_suffix("env_1"))));
+        assert_ne!(label, hostname_label(&long, &long, "acme", "env_2"));
+    }
+
+    #[test]
+    fn test_is_reserved() {
+        assert!(is_reserved("prod-api-acme.apps.plfm.dev", "plfm.dev"));
+        assert!(is_reserved("APPS.plfm.dev.", "plfm.dev"));
+        assert!(!is_reserved("api.plfm.dev", "plfm.dev"));
+        assert!(!is_reserved("myapps.plfm.dev", "plfm.dev"));
+    }
+
+    #[test]
+    fn test_plan_publishes_new_and_changed_records() {
+        let targets: Vec<Ipv6Addr> = vec!["2001:db8::1".parse().unwrap()];
+        let desired = BTreeMap::from([
+            ("a.apps.plfm.dev".to_string(), "env_a".to_string()),
+            ("b.apps.plfm.dev".to_string(), "env_b".to_string()),
+            ("c.apps.plfm.dev".to_string(), "env_c".to_string()),
+        ]);
+        let published = vec![
+            record("a.apps.plfm.dev", "route53", &["2001:db8::1"]),
+            record("b.apps.plfm.dev", "route53", &["2001:db8::2"]),
+            record("gone.apps.plfm.dev", "route53", &["2001:db8::1"]),
+        ];
+
+        let changes = plan(&desired, &published, "route53", &targets);
+        assert_eq!(
+            changes,
+            vec![
+                RecordChange::Upsert {
+                    hostname: "b.apps.plfm.dev".to_string(),
+                    env_id: "env_b".to_string(),
+                },
+                RecordChange::Upsert {
+                    hostname: "c.apps.plfm.dev".to_string(),
+                    env_id: "env_c".to_string(),
+                },
+                RecordChange::Delete {
+                    hostname: "gone.apps.plfm.dev".to_string(),
+                    targets: targets.clone(),
+                },
+            ]
+        );
+
+        // A provider switch republishes everything.
+        let changes = plan(&desired, &published[..1], "cloudflare", &targets);
+        assert!(changes.contains(&RecordChange::Upsert {
+            hostname: "a.apps.plfm.dev".to_string(),
+            env_id: "env_a".to_string(),
+        }));
+    }
+}
@@ -0,0 +1,472 @@
+//! DNS providers for managed hostnames.
+//!
+//! - `route53`: `PLFM_DNS_ROUTE53_HOSTED_ZONE_ID`, `AWS_ACCESS_KEY_ID`,
+//!   `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`, `PLFM_DNS_ROUTE53_ENDPOINT`
+//! - `cloudflare`: `PLFM_DNS_CLOUDFLARE_ZONE_ID`,
+//!   `PLFM_DNS_CLOUDFLARE_API_TOKEN`, `PLFM_DNS_CLOUDFLARE_ENDPOINT`
+//! - `external-dns`: nothing is written; the desired records are served from
+//!   `GET /v1/_admin/managed-dns/records` in external-dns endpoint form for an
+//!   external-dns webhook provider (or any other sync agent) to publish
+
+use std::net::Ipv6Addr;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::{env, ManagedDnsError};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Writes AAAA record sets for managed hostnames.
+#[async_trait]
+pub trait DnsProvider: Send + Sync {
+    /// Stable provider name, stored with each published record.
+    fn name(&self) -> &'static str;
+
+    /// Make `hostname` resolve to exactly `targets`.
+    async fn upsert_aaaa(
+        &self,
+        hostname: &str,
+        targets: &[Ipv6Addr],
+        ttl: u32,
+    ) -> Result<(), ManagedDnsError>;
+
+    /// Remove the record set previously published for `hostname`. Removing a
+    /// record set that is already gone succeeds.
+    async fn delete_aaaa(
+        &self,
+        hostname: &str,
+        targets: &[Ipv6Addr],
+        ttl: u32,
+    ) -> Result<(), ManagedDnsError>;
+}
+
+/// The provider named by `PLFM_MANAGED_DNS_PROVIDER`.
+pub fn from_env() -> Result<Arc<dyn DnsProvider>, ManagedDnsError> {
+    match env("PLFM_MANAGED_DNS_PROVIDER").as_deref() {
+        Some("route53") => Ok(Arc::new(Route53::from_env()?)),
+        Some("cloudflare") => Ok(Arc::new(Cloudflare::from_env()?)),
+        Some("external-dns") => Ok(Arc::new(ExternalDns)),
+        Some(other) => Err(ManagedDnsError::Config(format!(
+            "unknown PLFM_MANAGED_DNS_PROVIDER '{other}'"
+        ))),
+        None => Err(ManagedDnsError::Config(
+            "PLFM_MANAGED_DNS_PROVIDER is not set".to_string(),
+        )),
+    }
+}
+
+fn require_env(name: &str) -> Result<String, ManagedDnsError> {
+    env(name).ok_or_else(|| ManagedDnsError::Config(format!("{name} is not set")))
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build DNS provider HTTP client")
+    })
+}
+
+fn provider_error(provider: &'static str, message: impl Into<String>) -> ManagedDnsError {
+    ManagedDnsError::Provider {
+        provider,
+        message: message.into(),
+    }
+}
+
+// =============================================================================
+// external-dns
+// =============================================================================
+
+/// Records are only tracked; an external agent publishes them.
+pub struct ExternalDns;
+
+#[async_trait]
+impl DnsProvider for ExternalDns {
+    fn name(&self) -> &'static str {
+        "external-dns"
+    }
+
+    async fn upsert_aaaa(&self, _: &str, _: &[Ipv6Addr], _: u32) -> Result<(), ManagedDnsError> {
+        Ok(())
+    }
+
+    async fn delete_aaaa(&self, _: &str, _: &[Ipv6Addr], _: u32) -> Result<(), ManagedDnsError> {
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Route 53
+// =============================================================================
+
+const ROUTE53: &str = "route53";
+
+pub struct Route53 {
+    zone_id: String,
+    endpoint: String,
+    credentials: AwsCredentials,
+}
+
+struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl Route53 {
+    fn from_env() -> Result<Self, ManagedDnsError> {
+        Ok(Self {
+            zone_id: require_env("PLFM_DNS_ROUTE53_HOSTED_ZONE_ID")?
+                .trim_start_matches("/hostedzone/")
+                .to_string(),
+            endpoint: env("PLFM_DNS_ROUTE53_ENDPOINT")
+                .unwrap_or_else(|| "https://route53.amazonaws.com".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            credentials: AwsCredentials {
+                access_key_id: require_env("AWS_ACCESS_KEY_ID")?,
+                secret_access_key: require_env("AWS_SECRET_ACCESS_KEY")?,
+                session_token: env("AWS_SESSION_TOKEN"),
+            },
+        })
+    }
+
+    async fn change(
+        &self,
+        action: &str,
+        hostname: &str,
+        targets: &[Ipv6Addr],
+        ttl: u32,
+    ) -> Result<(), ManagedDnsError> {
+        let path = format!("/2013-04-01/hostedzone/{}/rrset", self.zone_id);
+        let body = change_batch_xml(action, hostname, targets, ttl);
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, rest)| rest)
+            .to_string();
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sigv4_authorization(
+            &self.credentials,
+            "us-east-1",
+            ROUTE53,
+            &host,
+            &path,
+            &amz_date,
+            body.as_bytes(),
+        );
+
+        let mut request = http_client()
+            .post(format!("{}{path}", self.endpoint))
+            .header("content-type", "text/xml")
+            .header("x-amz-date", &amz_date)
+            .header("authorization", authorization)
+            .body(body);
+        if let Some(token) = &self.credentials.session_token {
+            request = request.header("x-amz-security-token", token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| provider_error(ROUTE53, e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text().await.unwrap_or_default();
+        // Deleting a record set that no longer exists is a no-op for us.
+        if action == "DELETE" && text.contains("not found") {
+            return Ok(());
+        }
+        Err(provider_error(
+            ROUTE53,
+            format!("{action} {hostname} returned {status}"),
+        ))
+    }
+}
+
+#[async_trait]
+impl DnsProvider for Route53 {
+    fn name(&self) -> &'static str {
+        ROUTE53
+    }
+
+    async fn upsert_aaaa(
+        &self,
+        hostname: &str,
+        targets: &[Ipv6Addr],
+        ttl: u32,
+    ) -> Result<(), ManagedDnsError> {
+        self.change("UPSERT", hostname, targets, ttl).await
+    }
+
+    async fn delete_aaaa(
+        &self,
+        hostname: &str,
+        targets: &[Ipv6Addr],
+        ttl: u32,
+    ) -> Result<(), ManagedDnsError> {
+        if targets.is_empty() {
+            return Ok(());
+        }
+        self.change("DELETE", hostname, targets, ttl).await
+    }
+}
+
+/// `ChangeResourceRecordSets` request body for one AAAA record set.
+fn change_batch_xml(action: &str, hostname: &str, targets: &[Ipv6Addr], ttl: u32) -> String {
+    let records: String = targets
+        .iter()
+        .map(|t| format!("<ResourceRecord><Value>{t}</Value></ResourceRecord>"))
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <ChangeResourceRecordSetsRequest xmlns=\"https://route53.amazonaws.com/doc/2013-04-01/\">\
+         <ChangeBatch><Changes><Change><Action>{action}</Action>\
+         <ResourceRecordSet><Name>{hostname}.</Name><Type>AAAA</Type><TTL>{ttl}</TTL>\
+         <ResourceRecords>{records}</ResourceRecords></ResourceRecordSet>\
+         </Change></Changes></ChangeBatch></ChangeResourceRecordSetsRequest>"
+    )
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// AWS Signature Version 4 `Authorization` header for a POST without a
+/// query string.
+fn sigv4_authorization(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    host: &str,
+    path: &str,
+    amz_date: &str,
+    payload: &[u8],
+) -> String {
+    let date = &amz_date[..8];
+    let mut headers = vec![
+        ("content-type", "text/xml"),
+        ("host", host),
+        ("x-amz-date", amz_date),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token));
+    }
+    headers.sort_by_key(|(name, _)| *name);
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "POST\n{path}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(payload))
+    );
+
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date,
+    );
+    let k_region = hmac(&k_date, region);
+    let k_service = hmac(&k_region, service);
+    let k_signing = hmac(&k_service, "aws4_request");
+    let signature = hex::encode(hmac(&k_signing, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    )
+}
+
+// =============================================================================
+// Cloudflare
+// =============================================================================
+
+const CLOUDFLARE: &str = "cloudflare";
+
+pub struct Cloudflare {
+    zone_id: String,
+    api_token: String,
+    endpoint: String,
+}
+
+impl Cloudflare {
+    fn from_env() -> Result<Self, ManagedDnsError> {
+        Ok(Self {
+            zone_id: require_env("PLFM_DNS_CLOUDFLARE_ZONE_ID")?,
+            api_token: require_env("PLFM_DNS_CLOUDFLARE_API_TOKEN")?,
+            endpoint: env("PLFM_DNS_CLOUDFLARE_ENDPOINT")
+                .unwrap_or_else(|| "https://api.cloudflare.com/client/v4".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        })
+    }
+
+    fn records_url(&self) -> String {
+        format!("{}/zones/{}/dns_records", self.endpoint, self.zone_id)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value, ManagedDnsError> {
+        let response = request
+            .bearer_auth(&self.api_token)
+            .send()
+            .await
+            .map_err(|e| provider_error(CLOUDFLARE, e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(provider_error(CLOUDFLARE, format!("returned {status}")));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| provider_error(CLOUDFLARE, format!("invalid response: {e}")))
+    }
+
+    /// Existing AAAA records for `hostname`, as `(record_id, address)`.
+    async fn list(&self, hostname: &str) -> Result<Vec<(String, String)>, ManagedDnsError> {
+        let body = self
+            .send(http_client().get(self.records_url()).query(&[
+                ("type", "AAAA"),
+                ("name", hostname),
+                ("per_page", "100"),
+            ]))
+            .await?;
+        Ok(body["result"]
+            .as_array()
+            .map(|records| {
+                records
+                    .iter()
+                    .filter_map(|r| {
+                        Some((
+                            r["id"].as_str()?.to_string(),
+                            r["content"].as_str()?.to_string(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn delete_record(&self, record_id: &str) -> Result<(), ManagedDnsError> {
+        self.send(http_client().delete(format!("{}/{record_id}", self.records_url())))
+            .await
+            .map(|_| ())
+    }
+}
+
+#[async_trait]
+impl DnsProvider for Cloudflare {
+    fn name(&self) -> &'static str {
+        CLOUDFLARE
+    }
+
+    async fn upsert_aaaa(
+        &self,
+        hostname: &str,
+        targets: &[Ipv6Addr],
+        ttl: u32,
+    ) -> Result<(), ManagedDnsError> {
+        let existing = self.list(hostname).await?;
+        let mut present = Vec::new();
+        for (id, content) in &existing {
+            match content.parse::<Ipv6Addr>() {
+                Ok(addr) if targets.contains(&addr) => present.push(addr),
+                _ => self.delete_record(id).await?,
+            }
+        }
+        for target in targets.iter().filter(|t| !present.contains(t)) {
+            self.send(http_client().post(self.records_url()).json(&json!({
+                "type": "AAAA",
+                "name": hostname,
+                "content": target.to_string(),
+                "ttl": ttl,
+                "proxied": false,
+            })))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_aaaa(
+        &self,
+        hostname: &str,
+        _targets: &[Ipv6Addr],
+        _ttl: u32,
+    ) -> Result<(), ManagedDnsError> {
+        for (id, _) in self.list(hostname).await? {
+            self.delete_record(&id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_batch_xml() {
+        let targets: Vec<Ipv6Addr> = vec![
+            "2001:db8::1".parse().unwrap(),
+            "2001:db8::2".parse().unwrap(),
+        ];
+        let xml = change_batch_xml("UPSERT", "prod-api-acme.apps.plfm.dev", &targets, 60);
+        assert!(xml.contains("<Action>UPSERT</Action>"));
+        assert!(xml.contains("<Name>prod-api-acme.apps.plfm.dev.</Name>"));
+        assert!(xml.contains("<Type>AAAA</Type><TTL>60</TTL>"));
+        assert!(xml.contains(
+            "<ResourceRecord><Value>2001:db8::1</Value></ResourceRecord>\
+             <ResourceRecord><Value>2001:db8::2</Value></ResourceRecord>"
+        ));
+    }
+
+    #[test]
+    fn test_sigv4_scope_and_signed_headers() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        let header = sigv4_authorization(
+            &credentials,
+            "us-east-1",
+            ROUTE53,
+            "route53.amazonaws.com",
+            "/2013-04-01/hostedzone/Z1/rrset",
+            "20150830T123600Z",
+            b"<xml/>",
+        );
+        assert!(header.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/route53/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, Signature="
+        ));
+        let signature = header.rsplit('=').next().unwrap();
+        assert_eq!(signature.len(), 64);
+    }
+}
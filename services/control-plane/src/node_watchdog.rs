@@ -0,0 +1,580 @@
+//! Node heartbeat watchdog.
+//!
+//! Agents heartbeat every 30 seconds; each heartbeat is projected into
+//! `nodes_view.last_heartbeat_at`. The watchdog periodically:
+//! - marks `active`, `draining`, and `degraded` nodes `offline` (emitting
+//!   `node.state_changed` with reason `heartbeat_timeout`) once their last
+//!   heartbeat is older than the offline threshold;
+//! - once a node has been silent for the threshold plus a grace period,
+//!   stops its stateless instances (`instance.desired_state_changed` to
+//!   `stopped`, reason `node_offline`) so the scheduler places replacements
+//!   on healthy nodes. Instances of groups that mount volumes stay put: their
+//!   volumes live on the node, so they need a restore to move.
+//!
+//! Flapping protection: each offline marking is recorded in
+//! `node_offline_marks`. A node marked offline at least the flap threshold
+//! times within the flap window is flapping; when its heartbeats resume it
+//! comes back `degraded` (not schedulable) instead of `active`, and stays
+//! there until its marks age out of the window.
+//!
+//! The watchdog pauses while the control plane is in maintenance mode:
+//! database work can make heartbeats fail, and that silence is not the
+//! nodes' fault.
+//!
+//! Configuration:
+//! - `PLFM_NODE_OFFLINE_AFTER_SECS`: heartbeat silence before a node is
+//!   marked offline (default 90, three missed heartbeats)
+//! - `PLFM_NODE_RESCHEDULE_GRACE_SECS`: further silence before its
+//!   instances are rescheduled (default 120)
+//! - `PLFM_NODE_FLAP_WINDOW_SECS`: window for counting offline markings
+//!   (default 900)
+//! - `PLFM_NODE_FLAP_THRESHOLD`: markings within the window that make a node
+//!   flapping (default 3)
+//!
+//! See: docs/specs/scheduler/drain-evict-reschedule.md
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{
+    AggregateType, InstanceDesiredState, InstanceDesiredStateChangedPayload, NewEvent, NodeState,
+    NodeStateChangedPayload, SystemSource,
+};
+use plfm_id::{AppId, EnvId, InstanceId, NodeId, OrgId};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::db::{DbError, EventStore};
+use crate::leader::{LeaderElection, LeaderRole};
+
+/// Actor ID for events written by the watchdog.
+pub const NODE_WATCHDOG_ACTOR_ID: &str = "node-watchdog";
+
+/// `node.state_changed` reason when heartbeats stop.
+pub const REASON_HEARTBEAT_TIMEOUT: &str = "heartbeat_timeout";
+
+/// `node.state_changed` reason when a flapping node is held `degraded`.
+pub const REASON_FLAPPING: &str = "flapping";
+
+/// `instance.desired_state_changed` reason for instances moved off a node.
+pub const REASON_NODE_OFFLINE: &str = "node_offline";
+
+const DEFAULT_OFFLINE_AFTER_SECS: i64 = 90;
+const DEFAULT_RESCHEDULE_GRACE_SECS: i64 = 120;
+const DEFAULT_FLAP_WINDOW_SECS: i64 = 900;
+const DEFAULT_FLAP_THRESHOLD: i64 = 3;
+
+/// Node states the watchdog marks offline when heartbeats stop.
+const WATCHED_STATES: &[&str] = &["active", "draining", "degraded"];
+
+#[derive(Debug, Error)]
+pub enum WatchdogError {
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn env_i64(name: &str, default: i64) -> i64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(default)
+}
+
+/// Watchdog thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchdogConfig {
+    pub offline_after: Duration,
+    pub reschedule_grace: Duration,
+    pub flap_window: Duration,
+    pub flap_threshold: i64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            offline_after: Duration::seconds(DEFAULT_OFFLINE_AFTER_SECS),
+            reschedule_grace: Duration::seconds(DEFAULT_RESCHEDULE_GRACE_SECS),
+            flap_window: Duration::seconds(DEFAULT_FLAP_WINDOW_SECS),
+            flap_threshold: DEFAULT_FLAP_THRESHOLD,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    pub fn from_env() -> Self {
+        Self {
+            offline_after: Duration::seconds(env_i64(
+                "PLFM_NODE_OFFLINE_AFTER_SECS",
+                DEFAULT_OFFLINE_AFTER_SECS,
+            )),
+            reschedule_grace: Duration::seconds(env_i64(
+                "PLFM_NODE_RESCHEDULE_GRACE_SECS",
+                DEFAULT_RESCHEDULE_GRACE_SECS,
+            )),
+            flap_window: Duration::seconds(env_i64(
+                "PLFM_NODE_FLAP_WINDOW_SECS",
+                DEFAULT_FLAP_WINDOW_SECS,
+            )),
+            flap_threshold: env_i64("PLFM_NODE_FLAP_THRESHOLD", DEFAULT_FLAP_THRESHOLD),
+        }
+    }
+}
+
+// =============================================================================
+// Heartbeats
+// =============================================================================
+
+/// A node state change triggered by a heartbeat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatTransition {
+    pub new_state: &'static str,
+    pub reason: Option<&'static str>,
+}
+
+/// Decide the state change for a heartbeat reporting `reported` on a node in
+/// `current`. A flapping node recovering to `active` is held `degraded`.
+pub fn heartbeat_transition(
+    current: &str,
+    reported: &'static str,
+    flapping: bool,
+) -> Option<HeartbeatTransition> {
+    let held = flapping && reported == NodeState::Active.as_str();
+    if held && current == "offline" {
+        return Some(HeartbeatTransition {
+            new_state: NodeState::Degraded.as_str(),
+            reason: Some(REASON_FLAPPING),
+        });
+    }
+    if current == reported || (held && current == "degraded") {
+        return None;
+    }
+    Some(HeartbeatTransition {
+        new_state: reported,
+        reason: None,
+    })
+}
+
+/// Whether a node was marked offline often enough recently to be flapping.
+pub async fn is_flapping(
+    pool: &PgPool,
+    node_id: &str,
+    config: &WatchdogConfig,
+) -> Result<bool, sqlx::Error> {
+    let marks = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM node_offline_marks WHERE node_id = $1 AND marked_at >= $2",
+    )
+    .bind(node_id)
+    .bind(Utc::now() - config.flap_window)
+    .fetch_one(pool)
+    .await?;
+    Ok(marks >= config.flap_threshold)
+}
+
+/// The state change for a heartbeat from `node_id`, checking for flapping
+/// only when it could matter.
+pub async fn heartbeat_state(
+    pool: &PgPool,
+    node_id: &str,
+    current: &str,
+    reported: &'static str,
+) -> Result<Option<HeartbeatTransition>, sqlx::Error> {
+    let flapping = if matches!(current, "offline" | "degraded") && current != reported {
+        is_flapping(pool, node_id, &WatchdogConfig::from_env()).await?
+    } else {
+        false
+    };
+    Ok(heartbeat_transition(current, reported, flapping))
+}
+
+// =============================================================================
+// Pass
+// =============================================================================
+
+/// Whether a node last heard from at `last_heartbeat_at` is silent for
+/// longer than `after` at `now`.
+pub fn is_silent(last_heartbeat_at: DateTime<Utc>, now: DateTime<Utc>, after: Duration) -> bool {
+    last_heartbeat_at + after < now
+}
+
+/// An instance on an offline node.
+#[derive(Debug, Clone)]
+struct StrandedInstance {
+    instance_id: String,
+    org_id: String,
+    app_id: String,
+    env_id: String,
+    desired_state: String,
+    /// Its group mounts volumes, which live on the offline node.
+    stateful: bool,
+}
+
+impl<'r> FromRow<'r, PgRow> for StrandedInstance {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            instance_id: row.try_get("instance_id")?,
+            org_id: row.try_get("org_id")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            desired_state: row.try_get("desired_state")?,
+            stateful: row.try_get("stateful")?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PassSummary {
+    /// Nodes marked offline this pass.
+    pub marked_offline: usize,
+    /// Instances stopped for rescheduling this pass.
+    pub rescheduled: usize,
+    /// Instances left on offline nodes because their volumes live there.
+    pub stateful_stranded: usize,
+}
+
+/// Mark silent nodes offline and reschedule off long-silent ones.
+pub async fn run_pass(
+    pool: &PgPool,
+    config: &WatchdogConfig,
+) -> Result<PassSummary, WatchdogError> {
+    let now = Utc::now();
+    let store = EventStore::new(pool.clone());
+    let source = SystemSource::new(NODE_WATCHDOG_ACTOR_ID);
+    let mut summary = PassSummary::default();
+
+    sqlx::query("DELETE FROM node_offline_marks WHERE marked_at < $1")
+        .bind(now - config.flap_window)
+        .execute(pool)
+        .await?;
+
+    // A mark newer than the last heartbeat means this silence was already
+    // marked; the state change may just not be projected yet.
+    let nodes = sqlx::query_as::<_, (String, String, DateTime<Utc>, bool)>(
+        r#"
+        SELECT n.node_id, n.state,
+               COALESCE(n.last_heartbeat_at, n.created_at) AS last_heartbeat_at,
+               EXISTS (
+                   SELECT 1 FROM node_offline_marks m
+                   WHERE m.node_id = n.node_id
+                     AND m.marked_at > COALESCE(n.last_heartbeat_at, n.created_at)
+               ) AS marked
+        FROM nodes_view n
+        WHERE n.state = ANY($1) OR n.state = 'offline'
+        ORDER BY n.node_id
+        "#,
+    )
+    .bind(WATCHED_STATES)
+    .fetch_all(pool)
+    .await?;
+
+    for (node_id, state, last_heartbeat_at, marked) in nodes {
+        if state != "offline" && !marked && is_silent(last_heartbeat_at, now, config.offline_after)
+        {
+            mark_offline(pool, &store, &source, &node_id, &state).await?;
+            warn!(
+                node_id = %node_id,
+                old_state = %state,
+                last_heartbeat_at = %last_heartbeat_at,
+                "Node heartbeats stopped; marked offline"
+            );
+            summary.marked_offline += 1;
+        }
+
+        if is_silent(
+            last_heartbeat_at,
+            now,
+            config.offline_after + config.reschedule_grace,
+        ) {
+            let (rescheduled, stranded) = reschedule_node(pool, &store, &source, &node_id).await?;
+            summary.rescheduled += rescheduled;
+            summary.stateful_stranded += stranded;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Emit `node.state_changed` to `offline` and record the marking.
+async fn mark_offline(
+    pool: &PgPool,
+    store: &EventStore,
+    source: &SystemSource,
+    node_id: &str,
+    state: &str,
+) -> Result<(), WatchdogError> {
+    let (Ok(node_id_typed), Some(old_state)) = (node_id.parse::<NodeId>(), parse_state(state))
+    else {
+        warn!(node_id = %node_id, state = %state, "Skipping offline marking for invalid node");
+        return Ok(());
+    };
+
+    let seq = store
+        .get_latest_aggregate_seq(&AggregateType::Node, node_id)
+        .await?
+        .unwrap_or(0)
+        + 1;
+    let event = NewEvent::builder(source)
+        .aggregate_id(node_id.to_string())
+        .aggregate_seq(seq)
+        .payload(&NodeStateChangedPayload {
+            node_id: node_id_typed,
+            old_state,
+            new_state: NodeState::Offline,
+            reason: Some(REASON_HEARTBEAT_TIMEOUT.to_string()),
+        })
+        .build()?;
+    store.append(event.into()).await?;
+
+    sqlx::query("INSERT INTO node_offline_marks (node_id, marked_at) VALUES ($1, now())")
+        .bind(node_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+fn parse_state(state: &str) -> Option<NodeState> {
+    [
+        NodeState::Active,
+        NodeState::Draining,
+        NodeState::Disabled,
+        NodeState::Degraded,
+        NodeState::Offline,
+    ]
+    .into_iter()
+    .find(|s| s.as_str() == state)
+}
+
+/// Stop the stateless instances still desired on an offline node. Returns
+/// (stopped, left in place because stateful).
+async fn reschedule_node(
+    pool: &PgPool,
+    store: &EventStore,
+    source: &SystemSource,
+    node_id: &str,
+) -> Result<(usize, usize), WatchdogError> {
+    let instances = sqlx::query_as::<_, StrandedInstance>(
+        r#"
+        SELECT d.instance_id, d.org_id, d.app_id, d.env_id, d.desired_state,
+               EXISTS (
+                   SELECT 1 FROM volume_attachments_view a
+                   WHERE a.env_id = d.env_id
+                     AND a.process_type = d.process_type
+                     AND NOT a.is_deleted
+               ) AS stateful
+        FROM instances_desired_view d
+        WHERE d.node_id = $1 AND d.desired_state != 'stopped'
+        ORDER BY d.instance_id
+        "#,
+    )
+    .bind(node_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut stopped = 0;
+    let mut stranded = 0;
+    for instance in instances {
+        if instance.stateful {
+            debug!(
+                node_id = %node_id,
+                instance_id = %instance.instance_id,
+                "Instance mounts volumes on the offline node; not rescheduling"
+            );
+            stranded += 1;
+            continue;
+        }
+        if stop_instance(store, source, &instance).await? {
+            info!(
+                node_id = %node_id,
+                instance_id = %instance.instance_id,
+                old_desired_state = %instance.desired_state,
+                "Stopped instance on offline node for rescheduling"
+            );
+            stopped += 1;
+        }
+    }
+    Ok((stopped, stranded))
+}
+
+/// Emit `instance.desired_state_changed` to `stopped`. Returns false when
+/// the instance's IDs do not parse.
+async fn stop_instance(
+    store: &EventStore,
+    source: &SystemSource,
+    instance: &StrandedInstance,
+) -> Result<bool, WatchdogError> {
+    let (Ok(instance_id), Ok(org_id), Ok(app_id), Ok(env_id)) = (
+        instance.instance_id.parse::<InstanceId>(),
+        instance.org_id.parse::<OrgId>(),
+        instance.app_id.parse::<AppId>(),
+        instance.env_id.parse::<EnvId>(),
+    ) else {
+        warn!(instance_id = %instance.instance_id, "Skipping reschedule for invalid IDs");
+        return Ok(false);
+    };
+
+    let seq = store
+        .get_latest_aggregate_seq(&AggregateType::Instance, &instance.instance_id)
+        .await?
+        .unwrap_or(0)
+        + 1;
+    let event = NewEvent::builder(source)
+        .aggregate_id(instance.instance_id.clone())
+        .aggregate_seq(seq)
+        .org_id(org_id)
+        .app_id(app_id)
+        .env_id(env_id)
+        .payload(&InstanceDesiredStateChangedPayload {
+            instance_id,
+            org_id,
+            env_id,
+            desired_state: InstanceDesiredState::Stopped,
+            drain_grace_seconds: None,
+            reason: Some(REASON_NODE_OFFLINE.to_string()),
+        })
+        .build()?;
+    store.append(event.into()).await?;
+    Ok(true)
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker running watchdog passes, on the elected leader only.
+pub struct NodeWatchdogWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    config: WatchdogConfig,
+    election: LeaderElection,
+}
+
+impl NodeWatchdogWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::NodeWatchdog, interval * 3),
+            pool,
+            interval,
+            config: WatchdogConfig::from_env(),
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            offline_after_secs = self.config.offline_after.num_seconds(),
+            reschedule_grace_secs = self.config.reschedule_grace.num_seconds(),
+            "Starting node watchdog worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    if crate::api::maintenance::current(&self.pool).await.is_some() {
+                        continue;
+                    }
+                    match run_pass(&self.pool, &self.config).await {
+                        Ok(summary) if summary.marked_offline > 0 || summary.rescheduled > 0 => info!(
+                            marked_offline = summary.marked_offline,
+                            rescheduled = summary.rescheduled,
+                            stateful_stranded = summary.stateful_stranded,
+                            "Node watchdog pass complete"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Node watchdog pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Node watchdog worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat_transition() {
+        // Unchanged state.
+        assert_eq!(heartbeat_transition("active", "active", false), None);
+
+        // Ordinary recovery.
+        assert_eq!(
+            heartbeat_transition("offline", "active", false),
+            Some(HeartbeatTransition {
+                new_state: "active",
+                reason: None
+            })
+        );
+
+        // Flapping recovery is held degraded, and stays held.
+        assert_eq!(
+            heartbeat_transition("offline", "active", true),
+            Some(HeartbeatTransition {
+                new_state: "degraded",
+                reason: Some(REASON_FLAPPING)
+            })
+        );
+        assert_eq!(heartbeat_transition("degraded", "active", true), None);
+
+        // Once the marks age out the node returns to active.
+        assert_eq!(
+            heartbeat_transition("degraded", "active", false),
+            Some(HeartbeatTransition {
+                new_state: "active",
+                reason: None
+            })
+        );
+
+        // Agent-reported states other than active pass through.
+        assert_eq!(
+            heartbeat_transition("offline", "draining", true),
+            Some(HeartbeatTransition {
+                new_state: "draining",
+                reason: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_silent() {
+        let now = Utc::now();
+        let after = Duration::seconds(90);
+        assert!(!is_silent(now - Duration::seconds(30), now, after));
+        assert!(!is_silent(now - Duration::seconds(90), now, after));
+        assert!(is_silent(now - Duration::seconds(91), now, after));
+    }
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(parse_state("draining"), Some(NodeState::Draining));
+        assert_eq!(parse_state("pending_approval"), None);
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = WatchdogConfig::default();
+        assert_eq!(config.offline_after, Duration::seconds(90));
+        assert_eq!(config.reschedule_grace, Duration::seconds(120));
+        assert_eq!(config.flap_threshold, 3);
+    }
+}
@@ -0,0 +1,217 @@
+//! Delivery transports.
+//!
+//! - Slack: `POST` `{"text": ...}` to the incoming webhook URL
+//! - webhook: `POST` a JSON document describing the notification; with a
+//!   signing secret, `X-Plfm-Signature: sha256=<hex>` carries the
+//!   HMAC-SHA256 of the request body
+//! - SMTP: a plain-text email to every recipient, over STARTTLS, implicit
+//!   TLS, or (for local relays) no TLS
+//!
+//! Requests time out after 10 seconds and HTTP redirects are not followed.
+//! Any non-2xx response is a failed attempt.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use sha2::Sha256;
+use thiserror::Error;
+
+use super::{parse_mailbox, ChannelConfig, SmtpConfig, SmtpTls, WebhookConfig};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response bodies are cut to this many characters in delivery errors.
+const MAX_ERROR_BODY_CHARS: usize = 200;
+
+/// Header carrying the webhook body signature.
+pub const SIGNATURE_HEADER: &str = "x-plfm-signature";
+
+/// Header carrying the delivery ID, stable across retries.
+pub const DELIVERY_HEADER: &str = "x-plfm-delivery";
+
+#[derive(Debug, Error)]
+pub enum SendError {
+    #[error("channel has no secret")]
+    MissingSecret,
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("endpoint returned HTTP {status}: {body}")]
+    Rejected { status: u16, body: String },
+    #[error("{0}")]
+    Address(String),
+    #[error("SMTP delivery failed: {0}")]
+    Smtp(String),
+}
+
+/// A rendered notification on its way to a channel.
+#[derive(Debug, Clone, Copy)]
+pub struct Notification<'a> {
+    pub delivery_id: &'a str,
+    pub org_id: &'a str,
+    pub trigger: &'a str,
+    pub event_id: i64,
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+/// Send `notification` through a channel with `config` and `secret`.
+pub async fn send(
+    config: &ChannelConfig,
+    secret: Option<&str>,
+    notification: &Notification<'_>,
+) -> Result<(), SendError> {
+    match config {
+        ChannelConfig::Slack => {
+            send_slack(secret.ok_or(SendError::MissingSecret)?, notification).await
+        }
+        ChannelConfig::Webhook(webhook) => send_webhook(webhook, secret, notification).await,
+        ChannelConfig::Smtp(smtp) => send_smtp(smtp, secret, notification).await,
+    }
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("failed to build notification HTTP client")
+    })
+}
+
+async fn check_response(response: reqwest::Response) -> Result<(), SendError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(SendError::Rejected {
+        status: status.as_u16(),
+        body: body.trim().chars().take(MAX_ERROR_BODY_CHARS).collect(),
+    })
+}
+
+async fn send_slack(url: &str, notification: &Notification<'_>) -> Result<(), SendError> {
+    let text = format!("*{}*\n{}", notification.subject, notification.body);
+    let response = http_client()
+        .post(url)
+        .json(&serde_json::json!({ "text": text }))
+        .send()
+        .await?;
+    check_response(response).await
+}
+
+/// The JSON document posted to webhook channels.
+pub fn webhook_body(notification: &Notification<'_>) -> serde_json::Value {
+    serde_json::json!({
+        "delivery_id": notification.delivery_id,
+        "org_id": notification.org_id,
+        "trigger": notification.trigger,
+        "event_id": notification.event_id,
+        "subject": notification.subject,
+        "body": notification.body,
+        "sent_at": Utc::now().to_rfc3339(),
+    })
+}
+
+/// `sha256=<hex HMAC-SHA256 of body>` for the signature header.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+async fn send_webhook(
+    config: &WebhookConfig,
+    secret: Option<&str>,
+    notification: &Notification<'_>,
+) -> Result<(), SendError> {
+    let body =
+        serde_json::to_vec(&webhook_body(notification)).expect("notification JSON serializes");
+    let mut request = http_client()
+        .post(&config.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(DELIVERY_HEADER, notification.delivery_id);
+    if let Some(secret) = secret {
+        request = request.header(SIGNATURE_HEADER, signature(secret, &body));
+    }
+    let response = request.body(body).send().await?;
+    check_response(response).await
+}
+
+async fn send_smtp(
+    config: &SmtpConfig,
+    password: Option<&str>,
+    notification: &Notification<'_>,
+) -> Result<(), SendError> {
+    let mut message = lettre::Message::builder()
+        .from(parse_mailbox(&config.from).map_err(SendError::Address)?)
+        .subject(notification.subject)
+        .header(ContentType::TEXT_PLAIN);
+    for to in &config.to {
+        message = message.to(parse_mailbox(to).map_err(SendError::Address)?);
+    }
+    let message = message
+        .body(notification.body.to_string())
+        .map_err(|e| SendError::Smtp(e.to_string()))?;
+
+    let builder = match config.tls {
+        SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+        SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            &config.host,
+        )),
+    }
+    .map_err(|e| SendError::Smtp(e.to_string()))?;
+    let mut builder = builder.port(config.port).timeout(Some(REQUEST_TIMEOUT));
+    if let (Some(username), Some(password)) = (&config.username, password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.to_string()));
+    }
+    let transport: AsyncSmtpTransport<Tokio1Executor> = builder.build();
+
+    transport
+        .send(message)
+        .await
+        .map_err(|e| SendError::Smtp(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2.
+        let mac = signature("Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            mac,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_webhook_body() {
+        let body = webhook_body(&Notification {
+            delivery_id: "ndl_1",
+            org_id: "org_1",
+            trigger: "deploy_failed",
+            event_id: 42,
+            subject: "Deploy failed",
+            body: "details",
+        });
+        assert_eq!(body["delivery_id"], "ndl_1");
+        assert_eq!(body["trigger"], "deploy_failed");
+        assert_eq!(body["event_id"], 42);
+        assert!(body["sent_at"].is_string());
+    }
+}
@@ -0,0 +1,1066 @@
+//! Org notifications: channels, rules, and delivery.
+//!
+//! An org configures channels (where messages go: an SMTP relay, a Slack
+//! incoming webhook, or a generic webhook) and rules (which events notify
+//! which channels). Rules fire on one of five triggers:
+//! - `deploy_failed`: `deploy.status_changed` to `failed`
+//! - `instance_crash_looping`: `instance.status_changed` with reason
+//!   `crash_loop_backoff`
+//! - `cert_expiring`: `route.cert_expiring`
+//! - `alert_fired` / `alert_resolved`: `alert.fired` / `alert.resolved`
+//!   from the org's alert rules (see [`crate::alerts`])
+//!
+//! The notifier (leader only, see [`worker`]) follows the event log, renders
+//! a message for every matching (rule, channel) pair into
+//! `notification_deliveries`, and sends due deliveries with exponential
+//! backoff. An occurrence (a deploy, an instance, a route certificate, an
+//! alert transition) notifies each channel of a rule at most once. The delivery rows are also
+//! the per-channel delivery log served by the API.
+//!
+//! Channel secrets (the Slack webhook URL, SMTP password, or webhook signing
+//! secret) are envelope-encrypted under the org's key in `secret_material`,
+//! so key rotation jobs cover them, and are never returned by the API.
+//!
+//! See: docs/specs/observability/notifications.md
+
+pub mod channels;
+pub mod template;
+pub mod worker;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Postgres, Row, Transaction};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::db::DbError;
+use crate::secrets::{self as secrets_crypto, EncryptedSecret, KeyScope, SecretsCryptoError};
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+/// Upper bound on SMTP recipients per channel.
+pub const MAX_SMTP_RECIPIENTS: usize = 20;
+
+/// Upper bound on channel secrets, in bytes.
+pub const MAX_SECRET_BYTES: usize = 4096;
+
+/// Variables every trigger offers to templates.
+const COMMON_VARIABLES: &[&str] = &[
+    "trigger",
+    "event_id",
+    "occurred_at",
+    "org_id",
+    "app_id",
+    "app_name",
+    "env_id",
+    "env_name",
+];
+
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    #[error("name is already in use in this org")]
+    NameTaken,
+    #[error("channel secret encryption failed: {0}")]
+    Crypto(#[from] SecretsCryptoError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn name_taken(error: sqlx::Error) -> NotificationError {
+    match &error {
+        sqlx::Error::Database(db) if db.is_unique_violation() => NotificationError::NameTaken,
+        _ => NotificationError::Database(error),
+    }
+}
+
+// =============================================================================
+// Triggers
+// =============================================================================
+
+/// The condition a rule notifies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    DeployFailed,
+    InstanceCrashLooping,
+    CertExpiring,
+    AlertFired,
+    AlertResolved,
+}
+
+impl Trigger {
+    pub const ALL: [Trigger; 5] = [
+        Trigger::DeployFailed,
+        Trigger::InstanceCrashLooping,
+        Trigger::CertExpiring,
+        Trigger::AlertFired,
+        Trigger::AlertResolved,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Trigger::DeployFailed => "deploy_failed",
+            Trigger::InstanceCrashLooping => "instance_crash_looping",
+            Trigger::CertExpiring => "cert_expiring",
+            Trigger::AlertFired => "alert_fired",
+            Trigger::AlertResolved => "alert_resolved",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+
+    /// The event type this trigger watches.
+    pub fn event_type(self) -> &'static str {
+        use plfm_events::event_types;
+        match self {
+            Trigger::DeployFailed => event_types::DEPLOY_STATUS_CHANGED,
+            Trigger::InstanceCrashLooping => event_types::INSTANCE_STATUS_CHANGED,
+            Trigger::CertExpiring => event_types::ROUTE_CERT_EXPIRING,
+            Trigger::AlertFired => event_types::ALERT_FIRED,
+            Trigger::AlertResolved => event_types::ALERT_RESOLVED,
+        }
+    }
+
+    /// Event payload fields offered to templates.
+    fn payload_variables(self) -> &'static [&'static str] {
+        match self {
+            Trigger::DeployFailed => &[
+                "deploy_id",
+                "status",
+                "message",
+                "failed_reason",
+                "updated_at",
+            ],
+            Trigger::InstanceCrashLooping => &[
+                "instance_id",
+                "node_id",
+                "status",
+                "exit_code",
+                "reason_code",
+                "reason_detail",
+                "reported_at",
+            ],
+            Trigger::CertExpiring => &[
+                "route_id",
+                "hostname",
+                "fingerprint_sha256",
+                "not_after",
+                "days_remaining",
+            ],
+            Trigger::AlertFired => &[
+                "rule_id",
+                "rule_name",
+                "condition",
+                "target",
+                "value",
+                "threshold",
+                "summary",
+                "pending_since",
+            ],
+            Trigger::AlertResolved => &["rule_id", "rule_name", "condition", "target", "fired_at"],
+        }
+    }
+
+    /// Variables looked up from projections when the event fires.
+    fn lookup_variables(self) -> &'static [&'static str] {
+        match self {
+            Trigger::DeployFailed => &["deploy_reason"],
+            _ => &[],
+        }
+    }
+
+    /// Every variable a template for this trigger may use.
+    pub fn variables(self) -> Vec<&'static str> {
+        COMMON_VARIABLES
+            .iter()
+            .chain(self.payload_variables())
+            .chain(self.lookup_variables())
+            .copied()
+            .collect()
+    }
+
+    pub fn default_subject(self) -> &'static str {
+        match self {
+            Trigger::DeployFailed => "Deploy {deploy_id} failed in {app_name}/{env_name}",
+            Trigger::InstanceCrashLooping => {
+                "Instance {instance_id} is crash-looping in {app_name}/{env_name}"
+            }
+            Trigger::CertExpiring => "Certificate for {hostname} expires in {days_remaining} days",
+            Trigger::AlertFired => "[FIRING] {rule_name}: {summary}",
+            Trigger::AlertResolved => "[RESOLVED] {rule_name} on {target}",
+        }
+    }
+
+    pub fn default_body(self) -> &'static str {
+        match self {
+            Trigger::DeployFailed => {
+                "Deploy {deploy_id} of {app_name} to {env_name} failed.\n\
+                 Reason: {failed_reason}\n\
+                 {message}"
+            }
+            Trigger::InstanceCrashLooping => {
+                "Instance {instance_id} of {app_name} ({env_name}) on node {node_id} \
+                 keeps exiting and is backing off before restarting.\n\
+                 Last exit code: {exit_code}\n\
+                 {reason_detail}"
+            }
+            Trigger::CertExpiring => {
+                "The TLS certificate for {hostname} ({app_name}/{env_name}) expires at \
+                 {not_after}.\nUpload a renewed certificate for route {route_id} to \
+                 keep serving it."
+            }
+            Trigger::AlertFired => {
+                "Alert rule {rule_name} is firing on {target}.\n\
+                 {summary}\n\
+                 Condition: {condition}, holding since {pending_since}"
+            }
+            Trigger::AlertResolved => {
+                "Alert rule {rule_name} on {target} has resolved.\n\
+                 Condition: {condition}, fired at {fired_at}"
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Channels
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelKind {
+    Smtp,
+    Slack,
+    Webhook,
+}
+
+impl ChannelKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChannelKind::Smtp => "smtp",
+            ChannelKind::Slack => "slack",
+            ChannelKind::Webhook => "webhook",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [ChannelKind::Smtp, ChannelKind::Slack, ChannelKind::Webhook]
+            .into_iter()
+            .find(|k| k.as_str() == value)
+    }
+
+    /// Whether the channel cannot deliver without a secret. The secret is
+    /// optional for SMTP (unauthenticated relays) and webhooks (unsigned).
+    pub fn requires_secret(self) -> bool {
+        self == ChannelKind::Slack
+    }
+}
+
+/// How an SMTP channel secures its connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS (port 587).
+    #[default]
+    Starttls,
+    /// Implicit TLS (port 465).
+    Tls,
+    /// No encryption; for local relays only.
+    None,
+}
+
+fn default_smtp_port() -> u16 {
+    DEFAULT_SMTP_PORT
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    /// Login user; the password is the channel secret.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+}
+
+/// Non-secret channel settings, by kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelConfig {
+    Smtp(SmtpConfig),
+    /// The webhook URL is a credential, so it is the channel secret.
+    Slack,
+    Webhook(WebhookConfig),
+}
+
+impl ChannelConfig {
+    pub fn kind(&self) -> ChannelKind {
+        match self {
+            ChannelConfig::Smtp(_) => ChannelKind::Smtp,
+            ChannelConfig::Slack => ChannelKind::Slack,
+            ChannelConfig::Webhook(_) => ChannelKind::Webhook,
+        }
+    }
+
+    /// Parse the `config` object of a channel of `kind`.
+    pub fn parse(kind: ChannelKind, value: &serde_json::Value) -> Result<Self, String> {
+        match kind {
+            ChannelKind::Smtp => serde_json::from_value(value.clone())
+                .map(ChannelConfig::Smtp)
+                .map_err(|e| format!("invalid smtp config: {e}")),
+            ChannelKind::Slack => match value {
+                serde_json::Value::Null => Ok(ChannelConfig::Slack),
+                serde_json::Value::Object(fields) if fields.is_empty() => Ok(ChannelConfig::Slack),
+                _ => Err(
+                    "slack channels take no config; the webhook URL is the channel secret"
+                        .to_string(),
+                ),
+            },
+            ChannelKind::Webhook => serde_json::from_value(value.clone())
+                .map(ChannelConfig::Webhook)
+                .map_err(|e| format!("invalid webhook config: {e}")),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ChannelConfig::Smtp(config) => serde_json::json!(config),
+            ChannelConfig::Slack => serde_json::json!({}),
+            ChannelConfig::Webhook(config) => serde_json::json!(config),
+        }
+    }
+
+    /// Check what deserialization does not: addresses, URLs, and ports.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ChannelConfig::Smtp(config) => {
+                if config.host.trim().is_empty() || config.host.contains(char::is_whitespace) {
+                    return Err("smtp host must be a hostname".to_string());
+                }
+                if config.port == 0 {
+                    return Err("smtp port must be between 1 and 65535".to_string());
+                }
+                if config.username.as_deref().is_some_and(str::is_empty) {
+                    return Err("smtp username must not be empty".to_string());
+                }
+                parse_mailbox(&config.from)?;
+                if config.to.is_empty() || config.to.len() > MAX_SMTP_RECIPIENTS {
+                    return Err(format!(
+                        "smtp channels need 1 to {MAX_SMTP_RECIPIENTS} recipients"
+                    ));
+                }
+                config
+                    .to
+                    .iter()
+                    .try_for_each(|to| parse_mailbox(to).map(drop))
+            }
+            ChannelConfig::Slack => Ok(()),
+            ChannelConfig::Webhook(config) => {
+                if is_http_url(&config.url, false) {
+                    Ok(())
+                } else {
+                    Err("webhook url must be an http or https URL".to_string())
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn parse_mailbox(address: &str) -> Result<lettre::message::Mailbox, String> {
+    address
+        .parse()
+        .map_err(|e| format!("invalid email address {address:?}: {e}"))
+}
+
+fn is_http_url(value: &str, https_only: bool) -> bool {
+    reqwest::Url::parse(value).is_ok_and(|url| {
+        url.host_str().is_some()
+            && (url.scheme() == "https" || (!https_only && url.scheme() == "http"))
+    })
+}
+
+/// Check a channel secret for a channel of `kind`.
+pub fn validate_secret(kind: ChannelKind, secret: &str) -> Result<(), String> {
+    if secret.is_empty() || secret.len() > MAX_SECRET_BYTES {
+        return Err(format!(
+            "secret must be between 1 and {MAX_SECRET_BYTES} bytes"
+        ));
+    }
+    if kind == ChannelKind::Slack && !is_http_url(secret, true) {
+        return Err("slack secret must be the https incoming webhook URL".to_string());
+    }
+    Ok(())
+}
+
+/// A notification channel. The secret itself is only read by the notifier.
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub channel_id: String,
+    pub org_id: String,
+    pub name: String,
+    pub config: ChannelConfig,
+    pub has_secret: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const CHANNEL_COLUMNS: &str =
+    "channel_id, org_id, name, kind, config, secret_material_id, created_by, created_at, updated_at";
+
+impl<'r> FromRow<'r, PgRow> for Channel {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let kind: String = row.try_get("kind")?;
+        let kind = ChannelKind::parse(&kind).ok_or_else(|| {
+            sqlx::Error::Decode(format!("unknown notification channel kind {kind:?}").into())
+        })?;
+        let config: serde_json::Value = row.try_get("config")?;
+        let config =
+            ChannelConfig::parse(kind, &config).map_err(|e| sqlx::Error::Decode(e.into()))?;
+        let material: Option<String> = row.try_get("secret_material_id")?;
+        Ok(Self {
+            channel_id: row.try_get("channel_id")?,
+            org_id: row.try_get("org_id")?,
+            name: row.try_get("name")?,
+            config,
+            has_secret: material.is_some(),
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+fn secret_aad(org_id: &str, channel_id: &str) -> String {
+    format!("plfm-notification-channel-v1|org:{org_id}|channel:{channel_id}")
+}
+
+async fn encrypt_secret(
+    org_id: &str,
+    channel_id: &str,
+    secret: &str,
+) -> Result<EncryptedSecret, SecretsCryptoError> {
+    let aad = secret_aad(org_id, channel_id);
+    secrets_crypto::encrypt(KeyScope::Org(org_id), secret.as_bytes(), aad.as_bytes()).await
+}
+
+/// Store an encrypted channel secret, returning its material ID.
+async fn insert_secret(
+    tx: &mut Transaction<'_, Postgres>,
+    encrypted: &EncryptedSecret,
+) -> Result<String, sqlx::Error> {
+    let material_id = format!("sm_{}", plfm_id::RequestId::new());
+    sqlx::query(
+        r#"
+        INSERT INTO secret_material (
+            material_id, cipher, nonce, ciphertext, master_key_id,
+            wrapped_data_key, wrapped_data_key_nonce, plaintext_size_bytes
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(&material_id)
+    .bind(&encrypted.cipher)
+    .bind(&encrypted.nonce)
+    .bind(&encrypted.ciphertext)
+    .bind(&encrypted.master_key_id)
+    .bind(&encrypted.wrapped_data_key)
+    .bind(&encrypted.wrapped_data_key_nonce)
+    .bind(encrypted.plaintext_size_bytes)
+    .execute(&mut **tx)
+    .await?;
+    Ok(material_id)
+}
+
+async fn delete_secret(
+    tx: &mut Transaction<'_, Postgres>,
+    material_id: Option<String>,
+) -> Result<(), sqlx::Error> {
+    if let Some(material_id) = material_id {
+        sqlx::query("DELETE FROM secret_material WHERE material_id = $1")
+            .bind(material_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn create_channel(
+    pool: &PgPool,
+    org_id: &str,
+    name: &str,
+    config: &ChannelConfig,
+    secret: Option<&str>,
+    created_by: &str,
+) -> Result<Channel, NotificationError> {
+    let channel_id = format!("nch_{}", plfm_id::Ulid::new());
+    let encrypted = match secret {
+        Some(secret) => Some(encrypt_secret(org_id, &channel_id, secret).await?),
+        None => None,
+    };
+
+    let mut tx = pool.begin().await?;
+    let material_id = match &encrypted {
+        Some(encrypted) => Some(insert_secret(&mut tx, encrypted).await?),
+        None => None,
+    };
+    let query = format!(
+        r#"
+        INSERT INTO notification_channels
+            (channel_id, org_id, name, kind, config, secret_material_id, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {CHANNEL_COLUMNS}
+        "#
+    );
+    let channel = sqlx::query_as::<_, Channel>(&query)
+        .bind(&channel_id)
+        .bind(org_id)
+        .bind(name)
+        .bind(config.kind().as_str())
+        .bind(config.to_json())
+        .bind(&material_id)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(name_taken)?;
+    tx.commit().await?;
+    Ok(channel)
+}
+
+/// Replace a channel's name and config, and its secret when `secret` is
+/// given (the previous material is deleted in the same transaction).
+pub async fn update_channel(
+    pool: &PgPool,
+    org_id: &str,
+    channel_id: &str,
+    name: &str,
+    config: &ChannelConfig,
+    secret: Option<&str>,
+) -> Result<Option<Channel>, NotificationError> {
+    let encrypted = match secret {
+        Some(secret) => Some(encrypt_secret(org_id, channel_id, secret).await?),
+        None => None,
+    };
+
+    let mut tx = pool.begin().await?;
+    let previous: Option<Option<String>> = sqlx::query_scalar(
+        r#"
+        SELECT secret_material_id FROM notification_channels
+        WHERE org_id = $1 AND channel_id = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(org_id)
+    .bind(channel_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(previous_material) = previous else {
+        return Ok(None);
+    };
+
+    let (material_id, replaced) = match &encrypted {
+        Some(encrypted) => (
+            Some(insert_secret(&mut tx, encrypted).await?),
+            previous_material,
+        ),
+        None => (previous_material, None),
+    };
+    let query = format!(
+        r#"
+        UPDATE notification_channels
+        SET name = $3, config = $4, secret_material_id = $5, updated_at = now()
+        WHERE org_id = $1 AND channel_id = $2
+        RETURNING {CHANNEL_COLUMNS}
+        "#
+    );
+    let channel = sqlx::query_as::<_, Channel>(&query)
+        .bind(org_id)
+        .bind(channel_id)
+        .bind(name)
+        .bind(config.to_json())
+        .bind(&material_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(name_taken)?;
+    delete_secret(&mut tx, replaced).await?;
+    tx.commit().await?;
+    Ok(Some(channel))
+}
+
+/// Delete a channel with its secret and delivery log, and drop it from the
+/// org's rules.
+pub async fn delete_channel(
+    pool: &PgPool,
+    org_id: &str,
+    channel_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let deleted: Option<Option<String>> = sqlx::query_scalar(
+        r#"
+        DELETE FROM notification_channels
+        WHERE org_id = $1 AND channel_id = $2
+        RETURNING secret_material_id
+        "#,
+    )
+    .bind(org_id)
+    .bind(channel_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(material_id) = deleted else {
+        return Ok(false);
+    };
+    delete_secret(&mut tx, material_id).await?;
+    sqlx::query("DELETE FROM notification_deliveries WHERE channel_id = $1")
+        .bind(channel_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        UPDATE notification_rules
+        SET channel_ids = array_remove(channel_ids, $2), updated_at = now()
+        WHERE org_id = $1 AND $2 = ANY(channel_ids)
+        "#,
+    )
+    .bind(org_id)
+    .bind(channel_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn list_channels(pool: &PgPool, org_id: &str) -> Result<Vec<Channel>, sqlx::Error> {
+    let query = format!(
+        "SELECT {CHANNEL_COLUMNS} FROM notification_channels WHERE org_id = $1 ORDER BY name"
+    );
+    sqlx::query_as::<_, Channel>(&query)
+        .bind(org_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_channel(
+    pool: &PgPool,
+    org_id: &str,
+    channel_id: &str,
+) -> Result<Option<Channel>, sqlx::Error> {
+    let query = format!(
+        "SELECT {CHANNEL_COLUMNS} FROM notification_channels WHERE org_id = $1 AND channel_id = $2"
+    );
+    sqlx::query_as::<_, Channel>(&query)
+        .bind(org_id)
+        .bind(channel_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Of `channel_ids`, those that are channels of `org_id`.
+pub async fn existing_channel_ids(
+    pool: &PgPool,
+    org_id: &str,
+    channel_ids: &[String],
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT channel_id FROM notification_channels WHERE org_id = $1 AND channel_id = ANY($2)",
+    )
+    .bind(org_id)
+    .bind(channel_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// A channel's decrypted secret, if it has one.
+pub async fn channel_secret(
+    pool: &PgPool,
+    channel: &Channel,
+) -> Result<Option<String>, NotificationError> {
+    let row = sqlx::query(
+        r#"
+        SELECT sm.cipher, sm.nonce, sm.ciphertext, sm.master_key_id,
+               sm.wrapped_data_key, sm.wrapped_data_key_nonce
+        FROM notification_channels nc
+        JOIN secret_material sm ON sm.material_id = nc.secret_material_id
+        WHERE nc.channel_id = $1
+        "#,
+    )
+    .bind(&channel.channel_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let cipher: String = row.try_get("cipher")?;
+    if cipher != secrets_crypto::CIPHER_NAME {
+        warn!(channel_id = %channel.channel_id, cipher = %cipher, "Unsupported cipher for notification channel secret");
+        return Err(SecretsCryptoError::DecryptFailed.into());
+    }
+    let master_key_id: String = row.try_get("master_key_id")?;
+    let nonce: Vec<u8> = row.try_get("nonce")?;
+    let ciphertext: Vec<u8> = row.try_get("ciphertext")?;
+    let wrapped: Vec<u8> = row.try_get("wrapped_data_key")?;
+    let wrapped_nonce: Vec<u8> = row.try_get("wrapped_data_key_nonce")?;
+
+    let aad = secret_aad(&channel.org_id, &channel.channel_id);
+    let secret = secrets_crypto::decrypt(
+        &master_key_id,
+        &nonce,
+        &ciphertext,
+        &wrapped,
+        &wrapped_nonce,
+        aad.as_bytes(),
+    )
+    .await?;
+    String::from_utf8(secret)
+        .map(Some)
+        .map_err(|_| SecretsCryptoError::DecryptFailed.into())
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// The settable fields of a rule.
+#[derive(Debug, Clone)]
+pub struct RuleSpec {
+    pub name: String,
+    pub trigger: Trigger,
+    pub channel_ids: Vec<String>,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    pub enabled: bool,
+    pub subject_template: Option<String>,
+    pub body_template: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub rule_id: String,
+    pub org_id: String,
+    pub spec: RuleSpec,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const RULE_COLUMNS: &str = "rule_id, org_id, name, trigger, channel_ids, app_id, env_id, enabled, \
+     subject_template, body_template, created_by, created_at, updated_at";
+
+impl<'r> FromRow<'r, PgRow> for Rule {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let trigger: String = row.try_get("trigger")?;
+        let trigger = Trigger::parse(&trigger).ok_or_else(|| {
+            sqlx::Error::Decode(format!("unknown notification trigger {trigger:?}").into())
+        })?;
+        Ok(Self {
+            rule_id: row.try_get("rule_id")?,
+            org_id: row.try_get("org_id")?,
+            spec: RuleSpec {
+                name: row.try_get("name")?,
+                trigger,
+                channel_ids: row.try_get("channel_ids")?,
+                app_id: row.try_get("app_id")?,
+                env_id: row.try_get("env_id")?,
+                enabled: row.try_get("enabled")?,
+                subject_template: row.try_get("subject_template")?,
+                body_template: row.try_get("body_template")?,
+            },
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+pub async fn create_rule(
+    pool: &PgPool,
+    org_id: &str,
+    spec: &RuleSpec,
+    created_by: &str,
+) -> Result<Rule, NotificationError> {
+    let query = format!(
+        r#"
+        INSERT INTO notification_rules
+            (rule_id, org_id, name, trigger, channel_ids, app_id, env_id, enabled,
+             subject_template, body_template, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING {RULE_COLUMNS}
+        "#
+    );
+    sqlx::query_as::<_, Rule>(&query)
+        .bind(format!("nrl_{}", plfm_id::Ulid::new()))
+        .bind(org_id)
+        .bind(&spec.name)
+        .bind(spec.trigger.as_str())
+        .bind(&spec.channel_ids)
+        .bind(&spec.app_id)
+        .bind(&spec.env_id)
+        .bind(spec.enabled)
+        .bind(&spec.subject_template)
+        .bind(&spec.body_template)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await
+        .map_err(name_taken)
+}
+
+pub async fn update_rule(
+    pool: &PgPool,
+    org_id: &str,
+    rule_id: &str,
+    spec: &RuleSpec,
+) -> Result<Option<Rule>, NotificationError> {
+    let query = format!(
+        r#"
+        UPDATE notification_rules
+        SET name = $3, trigger = $4, channel_ids = $5, app_id = $6, env_id = $7,
+            enabled = $8, subject_template = $9, body_template = $10, updated_at = now()
+        WHERE org_id = $1 AND rule_id = $2
+        RETURNING {RULE_COLUMNS}
+        "#
+    );
+    sqlx::query_as::<_, Rule>(&query)
+        .bind(org_id)
+        .bind(rule_id)
+        .bind(&spec.name)
+        .bind(spec.trigger.as_str())
+        .bind(&spec.channel_ids)
+        .bind(&spec.app_id)
+        .bind(&spec.env_id)
+        .bind(spec.enabled)
+        .bind(&spec.subject_template)
+        .bind(&spec.body_template)
+        .fetch_optional(pool)
+        .await
+        .map_err(name_taken)
+}
+
+/// Delete a rule and drop its undelivered notifications. Delivered and
+/// failed ones stay in the channel logs.
+pub async fn delete_rule(pool: &PgPool, org_id: &str, rule_id: &str) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let deleted = sqlx::query("DELETE FROM notification_rules WHERE org_id = $1 AND rule_id = $2")
+        .bind(org_id)
+        .bind(rule_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+        > 0;
+    if deleted {
+        sqlx::query("DELETE FROM notification_deliveries WHERE rule_id = $1 AND state = 'pending'")
+            .bind(rule_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+pub async fn list_rules(pool: &PgPool, org_id: &str) -> Result<Vec<Rule>, sqlx::Error> {
+    let query =
+        format!("SELECT {RULE_COLUMNS} FROM notification_rules WHERE org_id = $1 ORDER BY name");
+    sqlx::query_as::<_, Rule>(&query)
+        .bind(org_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_rule(
+    pool: &PgPool,
+    org_id: &str,
+    rule_id: &str,
+) -> Result<Option<Rule>, sqlx::Error> {
+    let query =
+        format!("SELECT {RULE_COLUMNS} FROM notification_rules WHERE org_id = $1 AND rule_id = $2");
+    sqlx::query_as::<_, Rule>(&query)
+        .bind(org_id)
+        .bind(rule_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// =============================================================================
+// Deliveries
+// =============================================================================
+
+/// One rendered notification for one channel, and how sending it went.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub delivery_id: String,
+    pub org_id: String,
+    pub channel_id: String,
+    pub rule_id: String,
+    pub event_id: i64,
+    pub trigger: String,
+    /// `pending`, `delivered`, or `failed`.
+    pub state: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+const DELIVERY_COLUMNS: &str =
+    "delivery_id, org_id, channel_id, rule_id, event_id, trigger, state, \
+     attempts, next_attempt_at, last_error, subject, body, created_at, delivered_at";
+
+impl<'r> FromRow<'r, PgRow> for Delivery {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            delivery_id: row.try_get("delivery_id")?,
+            org_id: row.try_get("org_id")?,
+            channel_id: row.try_get("channel_id")?,
+            rule_id: row.try_get("rule_id")?,
+            event_id: row.try_get("event_id")?,
+            trigger: row.try_get("trigger")?,
+            state: row.try_get("state")?,
+            attempts: row.try_get("attempts")?,
+            next_attempt_at: row.try_get("next_attempt_at")?,
+            last_error: row.try_get("last_error")?,
+            subject: row.try_get("subject")?,
+            body: row.try_get("body")?,
+            created_at: row.try_get("created_at")?,
+            delivered_at: row.try_get("delivered_at")?,
+        })
+    }
+}
+
+/// A channel's deliveries, newest first, starting after `cursor` (a
+/// delivery ID from the previous page).
+pub async fn list_deliveries(
+    pool: &PgPool,
+    channel_id: &str,
+    state: Option<&str>,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Delivery>, sqlx::Error> {
+    let query = format!(
+        r#"
+        SELECT {DELIVERY_COLUMNS} FROM notification_deliveries
+        WHERE channel_id = $1
+          AND ($2::TEXT IS NULL OR state = $2)
+          AND ($3::TEXT IS NULL OR delivery_id < $3)
+        ORDER BY delivery_id DESC
+        LIMIT $4
+        "#
+    );
+    sqlx::query_as::<_, Delivery>(&query)
+        .bind(channel_id)
+        .bind(state)
+        .bind(cursor)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn smtp(to: &[&str]) -> ChannelConfig {
+        ChannelConfig::Smtp(SmtpConfig {
+            host: "smtp.example.com".to_string(),
+            port: 587,
+            tls: SmtpTls::Starttls,
+            username: Some("alerts".to_string()),
+            from: "plfm <alerts@example.com>".to_string(),
+            to: to.iter().map(|t| t.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_channel_config_parse() {
+        let config = ChannelConfig::parse(
+            ChannelKind::Smtp,
+            &json!({"host": "smtp.example.com", "from": "alerts@example.com", "to": ["ops@example.com"]}),
+        )
+        .unwrap();
+        let ChannelConfig::Smtp(smtp) = &config else {
+            panic!("expected smtp config");
+        };
+        assert_eq!(smtp.port, 587);
+        assert_eq!(smtp.tls, SmtpTls::Starttls);
+        assert_eq!(
+            ChannelConfig::parse(ChannelKind::Smtp, &config.to_json()),
+            Ok(config)
+        );
+
+        assert_eq!(
+            ChannelConfig::parse(ChannelKind::Slack, &json!({})),
+            Ok(ChannelConfig::Slack)
+        );
+        assert!(ChannelConfig::parse(ChannelKind::Slack, &json!({"url": "x"})).is_err());
+        assert!(ChannelConfig::parse(
+            ChannelKind::Webhook,
+            &json!({"url": "https://hooks.example.com", "token": "x"})
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_channel_config_validate() {
+        assert_eq!(smtp(&["ops@example.com"]).validate(), Ok(()));
+        assert!(smtp(&[]).validate().is_err());
+        assert!(smtp(&["not an address"]).validate().is_err());
+
+        let webhook = |url: &str| {
+            ChannelConfig::Webhook(WebhookConfig {
+                url: url.to_string(),
+            })
+            .validate()
+        };
+        assert_eq!(webhook("http://receiver.internal:8080/hook"), Ok(()));
+        assert!(webhook("ftp://example.com").is_err());
+        assert!(webhook("example.com/hook").is_err());
+    }
+
+    #[test]
+    fn test_validate_secret() {
+        assert_eq!(
+            validate_secret(ChannelKind::Slack, "https://hooks.slack.com/services/T/B/x"),
+            Ok(())
+        );
+        assert!(
+            validate_secret(ChannelKind::Slack, "http://hooks.slack.com/services/T/B/x").is_err()
+        );
+        assert!(validate_secret(ChannelKind::Webhook, "").is_err());
+        assert_eq!(validate_secret(ChannelKind::Smtp, "hunter2"), Ok(()));
+    }
+
+    #[test]
+    fn test_default_templates_use_known_variables() {
+        for trigger in Trigger::ALL {
+            let variables = trigger.variables();
+            assert_eq!(
+                template::validate(trigger.default_subject(), &variables),
+                Ok(())
+            );
+            assert_eq!(
+                template::validate(trigger.default_body(), &variables),
+                Ok(())
+            );
+            assert_eq!(Trigger::parse(trigger.as_str()), Some(trigger));
+        }
+        assert!(Trigger::DeployFailed.variables().contains(&"deploy_reason"));
+        assert!(!Trigger::CertExpiring.variables().contains(&"deploy_reason"));
+    }
+}
@@ -0,0 +1,164 @@
+//! Message templates.
+//!
+//! A template is plain text with `{name}` placeholders, where names are
+//! lowercase letters, digits, and underscores. `{{` and `}}` stand for
+//! literal braces. Templates are checked against the trigger's variables
+//! when a rule is saved, so rendering never meets an unknown name; a
+//! variable without a value renders as the empty string.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+/// Variable values for one rendering.
+pub type Vars = BTreeMap<String, String>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("unclosed placeholder at byte {0} (write {{{{ for a literal brace)")]
+    Unclosed(usize),
+    #[error("invalid placeholder {{{0}}}: names use a-z, 0-9, and _")]
+    InvalidName(String),
+    #[error("unknown variable {{{0}}}")]
+    UnknownVariable(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part<'a> {
+    Text(&'a str),
+    Var(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, TemplateError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    let mut offset = 0;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        if i > 0 {
+            parts.push(Part::Text(&rest[..i]));
+        }
+        let brace = &rest[i..i + 1];
+        if rest[i + 1..].starts_with(brace) {
+            parts.push(Part::Text(brace));
+            rest = &rest[i + 2..];
+            offset += i + 2;
+            continue;
+        }
+        if brace == "}" {
+            // A lone closing brace is kept as text.
+            parts.push(Part::Text(brace));
+            rest = &rest[i + 1..];
+            offset += i + 1;
+            continue;
+        }
+        let Some(len) = rest[i + 1..].find('}') else {
+            return Err(TemplateError::Unclosed(offset + i));
+        };
+        let name = &rest[i + 1..i + 1 + len];
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(TemplateError::InvalidName(name.to_string()));
+        }
+        parts.push(Part::Var(name));
+        rest = &rest[i + len + 2..];
+        offset += i + len + 2;
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    Ok(parts)
+}
+
+/// Check that `template` parses and only uses `variables`.
+pub fn validate(template: &str, variables: &[&str]) -> Result<(), TemplateError> {
+    for part in parse(template)? {
+        if let Part::Var(name) = part {
+            if !variables.contains(&name) {
+                return Err(TemplateError::UnknownVariable(name.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Substitute `vars` into `template`.
+pub fn render(template: &str, vars: &Vars) -> Result<String, TemplateError> {
+    let mut out = String::with_capacity(template.len());
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Var(name) => out.push_str(vars.get(name).map(String::as_str).unwrap_or("")),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(entries: &[(&str, &str)]) -> Vars {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let vars = vars(&[("app_name", "web"), ("deploy_id", "dep_1")]);
+        assert_eq!(
+            render("Deploy {deploy_id} of {app_name} failed", &vars).unwrap(),
+            "Deploy dep_1 of web failed"
+        );
+        assert_eq!(render("{app_name}{app_name}", &vars).unwrap(), "webweb");
+        assert_eq!(
+            render("reason: {failed_reason}.", &vars).unwrap(),
+            "reason: ."
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_and_lone_closing_brace() {
+        let vars = vars(&[("hostname", "a.example.com")]);
+        assert_eq!(
+            render(r#"{{"host": "{hostname}"}}"#, &vars).unwrap(),
+            r#"{"host": "a.example.com"}"#
+        );
+        assert_eq!(render("smile :}", &vars).unwrap(), "smile :}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let vars = Vars::new();
+        assert_eq!(
+            render("Deploy {deploy_id", &vars),
+            Err(TemplateError::Unclosed(7))
+        );
+        assert_eq!(
+            render("{Deploy ID}", &vars),
+            Err(TemplateError::InvalidName("Deploy ID".to_string()))
+        );
+        assert_eq!(
+            render("{}", &vars),
+            Err(TemplateError::InvalidName(String::new()))
+        );
+    }
+
+    #[test]
+    fn test_validate_checks_variables() {
+        let known = ["hostname", "days_remaining"];
+        assert_eq!(
+            validate("{hostname} in {days_remaining} days", &known),
+            Ok(())
+        );
+        assert_eq!(
+            validate("{hostname} for {deploy_id}", &known),
+            Err(TemplateError::UnknownVariable("deploy_id".to_string()))
+        );
+    }
+}
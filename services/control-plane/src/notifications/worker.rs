@@ -0,0 +1,748 @@
+//! The notifier: rule matching, fan-out, and delivery.
+//!
+//! Each pass, on the elected leader only:
+//! 1. trigger events after `notification_cursor` are matched against the
+//!    org's enabled rules, and a rendered delivery is inserted per (rule,
+//!    channel); the cursor advances in the same transaction
+//! 2. due pending deliveries are sent; a failed attempt is retried after
+//!    30s, 1m, 2m, ... (capped at 1h) until the attempt limit, after which
+//!    the delivery is marked `failed`
+//!
+//! The cursor starts at the end of the event log the first time the notifier
+//! runs, so turning notifications on does not replay history.
+//!
+//! Configuration:
+//! - `PLFM_NOTIFICATION_MAX_ATTEMPTS`: attempts per delivery (default 6)
+
+use std::collections::HashMap;
+use std::time::Duration as StdDuration;
+
+use chrono::Duration;
+use plfm_events::event_types;
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use super::channels::{self, Notification};
+use super::template::{self, Vars};
+use super::{
+    channel_secret, get_channel, Channel, Delivery, NotificationError, Rule, Trigger,
+    DELIVERY_COLUMNS, RULE_COLUMNS,
+};
+use crate::db::{EventRow, EventStore};
+use crate::leader::{LeaderElection, LeaderRole};
+
+const DEFAULT_MAX_ATTEMPTS: i32 = 6;
+
+/// Events read per fan-out pass.
+const EVENT_BATCH: i32 = 500;
+
+/// Deliveries attempted per pass.
+const DELIVERY_BATCH: i64 = 50;
+
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 3600;
+
+pub fn max_attempts() -> i32 {
+    std::env::var("PLFM_NOTIFICATION_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+}
+
+/// Wait before the next attempt, after `attempts` failed ones.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    Duration::seconds((RETRY_BASE_SECS << exponent).min(RETRY_MAX_SECS))
+}
+
+// =============================================================================
+// Matching
+// =============================================================================
+
+/// An event that fires a trigger, with its template variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub event_id: i64,
+    pub trigger: Trigger,
+    pub org_id: String,
+    pub app_id: Option<String>,
+    pub env_id: Option<String>,
+    /// Names the occurrence; events with the same key notify once per rule
+    /// and channel.
+    pub dedupe_key: String,
+    pub vars: Vars,
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+impl TriggerEvent {
+    /// The trigger `event` fires, if any.
+    pub fn from_event(event: &EventRow) -> Option<Self> {
+        let payload = &event.payload;
+        let field = |name: &str| payload.get(name).and_then(Value::as_str);
+        let (trigger, dedupe_key) = match event.event_type.as_str() {
+            event_types::DEPLOY_STATUS_CHANGED if field("status") == Some("failed") => {
+                (Trigger::DeployFailed, field("deploy_id")?.to_string())
+            }
+            event_types::INSTANCE_STATUS_CHANGED
+                if field("reason_code") == Some("crash_loop_backoff") =>
+            {
+                (
+                    Trigger::InstanceCrashLooping,
+                    field("instance_id")?.to_string(),
+                )
+            }
+            event_types::ROUTE_CERT_EXPIRING => (
+                Trigger::CertExpiring,
+                format!("{}:{}", field("route_id")?, field("fingerprint_sha256")?),
+            ),
+            // Every alert transition is its own occurrence.
+            event_types::ALERT_FIRED => (Trigger::AlertFired, event.event_id.to_string()),
+            event_types::ALERT_RESOLVED => (Trigger::AlertResolved, event.event_id.to_string()),
+            _ => return None,
+        };
+        let org_id = event
+            .org_id
+            .clone()
+            .or_else(|| field("org_id").map(str::to_string))?;
+        let env_id = event
+            .env_id
+            .clone()
+            .or_else(|| field("env_id").map(str::to_string));
+
+        let mut vars: Vars = trigger
+            .payload_variables()
+            .iter()
+            .filter_map(|name| Some((name.to_string(), scalar(payload.get(*name)?)?)))
+            .collect();
+        vars.insert("trigger".to_string(), trigger.as_str().to_string());
+        vars.insert("event_id".to_string(), event.event_id.to_string());
+        vars.insert("occurred_at".to_string(), event.occurred_at.to_rfc3339());
+        vars.insert("org_id".to_string(), org_id.clone());
+
+        let mut trigger_event = Self {
+            event_id: event.event_id,
+            trigger,
+            org_id,
+            app_id: None,
+            env_id,
+            dedupe_key,
+            vars,
+        };
+        trigger_event.set_scope(event.app_id.clone(), None, None);
+        Some(trigger_event)
+    }
+
+    /// Record the app (and names) the event belongs to. Names fall back to
+    /// IDs when unknown.
+    fn set_scope(
+        &mut self,
+        app_id: Option<String>,
+        app_name: Option<String>,
+        env_name: Option<String>,
+    ) {
+        if app_id.is_some() {
+            self.app_id = app_id;
+        }
+        let app_id = self.app_id.clone().unwrap_or_default();
+        let env_id = self.env_id.clone().unwrap_or_default();
+        self.vars.insert(
+            "app_name".to_string(),
+            app_name.unwrap_or_else(|| app_id.clone()),
+        );
+        self.vars.insert(
+            "env_name".to_string(),
+            env_name.unwrap_or_else(|| env_id.clone()),
+        );
+        self.vars.insert("app_id".to_string(), app_id);
+        self.vars.insert("env_id".to_string(), env_id);
+    }
+}
+
+impl Rule {
+    /// Whether this rule notifies on `event`.
+    pub fn matches(&self, event: &TriggerEvent) -> bool {
+        let scoped =
+            |filter: &Option<String>, value: &Option<String>| filter.is_none() || filter == value;
+        self.spec.enabled
+            && self.spec.trigger == event.trigger
+            && self.org_id == event.org_id
+            && scoped(&self.spec.app_id, &event.app_id)
+            && scoped(&self.spec.env_id, &event.env_id)
+    }
+
+    /// Subject and body for `event`, from the rule's templates or the
+    /// trigger's defaults. Subjects are kept to one line.
+    pub fn render(&self, event: &TriggerEvent) -> (String, String) {
+        let trigger = self.spec.trigger;
+        let render = |custom: &Option<String>, default: &str| {
+            custom
+                .as_deref()
+                .and_then(|t| template::render(t, &event.vars).ok())
+                .or_else(|| template::render(default, &event.vars).ok())
+                .unwrap_or_default()
+        };
+        let subject = render(&self.spec.subject_template, trigger.default_subject());
+        let body = render(&self.spec.body_template, trigger.default_body());
+        let subject = subject.split_whitespace().collect::<Vec<_>>().join(" ");
+        (subject, body.trim().to_string())
+    }
+}
+
+// =============================================================================
+// Fan-out
+// =============================================================================
+
+/// Read the notifier's cursor, starting it at the end of the log if unset.
+async fn load_cursor(pool: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO notification_cursor (singleton, last_event_id)
+        SELECT true, COALESCE(MAX(event_id), 0) FROM events
+        ON CONFLICT (singleton) DO NOTHING
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query_scalar("SELECT last_event_id FROM notification_cursor")
+        .fetch_one(pool)
+        .await
+}
+
+async fn enabled_rules(
+    pool: &PgPool,
+    org_id: &str,
+    trigger: Trigger,
+) -> Result<Vec<Rule>, sqlx::Error> {
+    let query = format!(
+        "SELECT {RULE_COLUMNS} FROM notification_rules WHERE org_id = $1 AND trigger = $2 AND enabled"
+    );
+    sqlx::query_as::<_, Rule>(&query)
+        .bind(org_id)
+        .bind(trigger.as_str())
+        .fetch_all(pool)
+        .await
+}
+
+/// Fill in the event's app and the app and env names.
+async fn resolve_scope(pool: &PgPool, event: &mut TriggerEvent) -> Result<(), sqlx::Error> {
+    let Some(env_id) = event.env_id.clone() else {
+        return Ok(());
+    };
+    let row = sqlx::query_as::<_, (String, String, String)>(
+        r#"
+        SELECT a.app_id, a.name, e.name
+        FROM envs_view e
+        JOIN apps_view a ON a.app_id = e.app_id
+        WHERE e.env_id = $1
+        "#,
+    )
+    .bind(&env_id)
+    .fetch_optional(pool)
+    .await?;
+    if let Some((app_id, app_name, env_name)) = row {
+        event.set_scope(Some(app_id), Some(app_name), Some(env_name));
+    }
+    Ok(())
+}
+
+/// Fill in the reason the failed deploy was made, when it had one.
+async fn resolve_deploy_reason(pool: &PgPool, event: &mut TriggerEvent) -> Result<(), sqlx::Error> {
+    if event.trigger != Trigger::DeployFailed {
+        return Ok(());
+    }
+    let Some(deploy_id) = event.vars.get("deploy_id").cloned() else {
+        return Ok(());
+    };
+    let reason: Option<String> =
+        sqlx::query_scalar("SELECT reason FROM deploys_view WHERE deploy_id = $1")
+            .bind(&deploy_id)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+    if let Some(reason) = reason {
+        event.vars.insert("deploy_reason".to_string(), reason);
+    }
+    Ok(())
+}
+
+struct NewDelivery {
+    org_id: String,
+    channel_id: String,
+    rule_id: String,
+    event_id: i64,
+    trigger: Trigger,
+    dedupe_key: String,
+    subject: String,
+    body: String,
+}
+
+/// Match the next batch of trigger events against rules and queue their
+/// deliveries. Returns the number of deliveries queued.
+pub async fn fan_out(pool: &PgPool) -> Result<usize, NotificationError> {
+    let cursor = load_cursor(pool).await?;
+    let event_types: Vec<&str> = Trigger::ALL.iter().map(|t| t.event_type()).collect();
+    let events = EventStore::new(pool.clone())
+        .query_by_types_after_cursor(&event_types, cursor, EVENT_BATCH)
+        .await?;
+    let Some(last_event_id) = events.last().map(|e| e.event_id) else {
+        return Ok(0);
+    };
+
+    let mut deliveries = Vec::new();
+    for event in &events {
+        let Some(mut trigger_event) = TriggerEvent::from_event(event) else {
+            continue;
+        };
+        let rules = enabled_rules(pool, &trigger_event.org_id, trigger_event.trigger).await?;
+        if rules.is_empty() {
+            continue;
+        }
+        resolve_scope(pool, &mut trigger_event).await?;
+        resolve_deploy_reason(pool, &mut trigger_event).await?;
+
+        for rule in rules.iter().filter(|r| r.matches(&trigger_event)) {
+            let (subject, body) = rule.render(&trigger_event);
+            for channel_id in &rule.spec.channel_ids {
+                deliveries.push(NewDelivery {
+                    org_id: trigger_event.org_id.clone(),
+                    channel_id: channel_id.clone(),
+                    rule_id: rule.rule_id.clone(),
+                    event_id: trigger_event.event_id,
+                    trigger: trigger_event.trigger,
+                    dedupe_key: trigger_event.dedupe_key.clone(),
+                    subject: subject.clone(),
+                    body: body.clone(),
+                });
+            }
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut queued = 0;
+    for delivery in &deliveries {
+        queued += sqlx::query(
+            r#"
+            INSERT INTO notification_deliveries
+                (delivery_id, org_id, channel_id, rule_id, event_id, trigger, dedupe_key,
+                 state, subject, body)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending', $8, $9)
+            ON CONFLICT (rule_id, channel_id, dedupe_key) DO NOTHING
+            "#,
+        )
+        .bind(format!("ndl_{}", plfm_id::Ulid::new()))
+        .bind(&delivery.org_id)
+        .bind(&delivery.channel_id)
+        .bind(&delivery.rule_id)
+        .bind(delivery.event_id)
+        .bind(delivery.trigger.as_str())
+        .bind(&delivery.dedupe_key)
+        .bind(&delivery.subject)
+        .bind(&delivery.body)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as usize;
+    }
+    sqlx::query("UPDATE notification_cursor SET last_event_id = $1, updated_at = now()")
+        .bind(last_event_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(queued)
+}
+
+// =============================================================================
+// Delivery
+// =============================================================================
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeliverySummary {
+    pub delivered: usize,
+    /// Failed attempts that will be retried.
+    pub retrying: usize,
+    /// Deliveries that ran out of attempts.
+    pub failed: usize,
+}
+
+/// A channel and its decrypted secret (or why it could not be decrypted).
+struct Target {
+    channel: Channel,
+    secret: Result<Option<String>, String>,
+}
+
+/// Send the pending deliveries that are due.
+pub async fn deliver_due(pool: &PgPool) -> Result<DeliverySummary, NotificationError> {
+    let query = format!(
+        r#"
+        SELECT {DELIVERY_COLUMNS} FROM notification_deliveries
+        WHERE state = 'pending' AND next_attempt_at <= now()
+        ORDER BY next_attempt_at
+        LIMIT $1
+        "#
+    );
+    let due = sqlx::query_as::<_, Delivery>(&query)
+        .bind(DELIVERY_BATCH)
+        .fetch_all(pool)
+        .await?;
+
+    let max_attempts = max_attempts();
+    let mut targets: HashMap<String, Option<Target>> = HashMap::new();
+    let mut summary = DeliverySummary::default();
+
+    for delivery in due {
+        if !targets.contains_key(&delivery.channel_id) {
+            let target = match get_channel(pool, &delivery.org_id, &delivery.channel_id).await? {
+                Some(channel) => Some(Target {
+                    secret: channel_secret(pool, &channel)
+                        .await
+                        .map_err(|e| format!("channel secret unavailable: {e}")),
+                    channel,
+                }),
+                None => None,
+            };
+            targets.insert(delivery.channel_id.clone(), target);
+        }
+        let Some(target) = &targets[&delivery.channel_id] else {
+            // The channel was deleted after the delivery was queued.
+            sqlx::query("DELETE FROM notification_deliveries WHERE delivery_id = $1")
+                .bind(&delivery.delivery_id)
+                .execute(pool)
+                .await?;
+            continue;
+        };
+
+        let result = match &target.secret {
+            Ok(secret) => channels::send(
+                &target.channel.config,
+                secret.as_deref(),
+                &Notification {
+                    delivery_id: &delivery.delivery_id,
+                    org_id: &delivery.org_id,
+                    trigger: &delivery.trigger,
+                    event_id: delivery.event_id,
+                    subject: &delivery.subject,
+                    body: &delivery.body,
+                },
+            )
+            .await
+            .map_err(|e| e.to_string()),
+            Err(e) => Err(e.clone()),
+        };
+
+        let attempts = delivery.attempts + 1;
+        match result {
+            Ok(()) => {
+                sqlx::query(
+                    r#"
+                    UPDATE notification_deliveries
+                    SET state = 'delivered', attempts = $2, last_error = NULL, delivered_at = now()
+                    WHERE delivery_id = $1
+                    "#,
+                )
+                .bind(&delivery.delivery_id)
+                .bind(attempts)
+                .execute(pool)
+                .await?;
+                summary.delivered += 1;
+            }
+            Err(error) => {
+                let give_up = attempts >= max_attempts;
+                warn!(
+                    delivery_id = %delivery.delivery_id,
+                    channel_id = %delivery.channel_id,
+                    attempts,
+                    give_up,
+                    error = %error,
+                    "Notification delivery failed"
+                );
+                sqlx::query(
+                    r#"
+                    UPDATE notification_deliveries
+                    SET state = CASE WHEN $3 THEN 'failed' ELSE 'pending' END,
+                        attempts = $2,
+                        last_error = $4,
+                        next_attempt_at = now() + make_interval(secs => $5)
+                    WHERE delivery_id = $1
+                    "#,
+                )
+                .bind(&delivery.delivery_id)
+                .bind(attempts)
+                .bind(give_up)
+                .bind(&error)
+                .bind(retry_delay(attempts).num_seconds() as f64)
+                .execute(pool)
+                .await?;
+                if give_up {
+                    summary.failed += 1;
+                } else {
+                    summary.retrying += 1;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker matching and delivering notifications, on the elected
+/// leader only.
+pub struct NotifierWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    election: LeaderElection,
+}
+
+impl NotifierWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::Notifier, interval * 3),
+            pool,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            "Starting notifier worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    match fan_out(&self.pool).await {
+                        Ok(queued) if queued > 0 => info!(queued, "Queued notifications"),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Notification fan-out failed"),
+                    }
+                    match deliver_due(&self.pool).await {
+                        Ok(summary) if summary != DeliverySummary::default() => info!(
+                            delivered = summary.delivered,
+                            retrying = summary.retrying,
+                            failed = summary.failed,
+                            "Notification delivery pass complete"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Notification delivery pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Notifier worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::*;
+    use crate::notifications::RuleSpec;
+
+    fn event(event_type: &str, payload: Value) -> EventRow {
+        EventRow {
+            event_id: 7,
+            occurred_at: Utc::now(),
+            aggregate_type: "deploy".to_string(),
+            aggregate_id: "agg_1".to_string(),
+            aggregate_seq: 1,
+            event_type: event_type.to_string(),
+            event_version: 1,
+            actor_type: "system".to_string(),
+            actor_id: "test".to_string(),
+            org_id: Some("org_1".to_string()),
+            request_id: "req_1".to_string(),
+            idempotency_key: None,
+            app_id: None,
+            env_id: Some("env_1".to_string()),
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: None,
+        }
+    }
+
+    fn rule(trigger: Trigger, app_id: Option<&str>, env_id: Option<&str>) -> Rule {
+        Rule {
+            rule_id: "nrl_1".to_string(),
+            org_id: "org_1".to_string(),
+            spec: RuleSpec {
+                name: "oncall".to_string(),
+                trigger,
+                channel_ids: vec!["nch_1".to_string()],
+                app_id: app_id.map(str::to_string),
+                env_id: env_id.map(str::to_string),
+                enabled: true,
+                subject_template: None,
+                body_template: None,
+            },
+            created_by: "ops@example.com".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn deploy_failed() -> TriggerEvent {
+        let mut event = TriggerEvent::from_event(&event(
+            event_types::DEPLOY_STATUS_CHANGED,
+            json!({"deploy_id": "dep_1", "org_id": "org_1", "env_id": "env_1",
+                   "status": "failed", "failed_reason": "healthcheck_failed"}),
+        ))
+        .unwrap();
+        event.set_scope(
+            Some("app_1".to_string()),
+            Some("web".to_string()),
+            Some("prod".to_string()),
+        );
+        event
+    }
+
+    #[test]
+    fn test_from_event_triggers() {
+        let event_of = |event_type, payload| TriggerEvent::from_event(&event(event_type, payload));
+
+        let failed = deploy_failed();
+        assert_eq!(failed.trigger, Trigger::DeployFailed);
+        assert_eq!(failed.dedupe_key, "dep_1");
+        assert_eq!(failed.vars["failed_reason"], "healthcheck_failed");
+        assert_eq!(failed.vars["app_name"], "web");
+        assert_eq!(failed.vars["event_id"], "7");
+
+        assert_eq!(
+            event_of(
+                event_types::DEPLOY_STATUS_CHANGED,
+                json!({"deploy_id": "dep_1", "status": "succeeded"})
+            ),
+            None
+        );
+
+        let crash = event_of(
+            event_types::INSTANCE_STATUS_CHANGED,
+            json!({"instance_id": "inst_1", "node_id": "node_1", "status": "failed",
+                   "exit_code": 137, "reason_code": "crash_loop_backoff"}),
+        )
+        .unwrap();
+        assert_eq!(crash.trigger, Trigger::InstanceCrashLooping);
+        assert_eq!(crash.dedupe_key, "inst_1");
+        assert_eq!(crash.vars["exit_code"], "137");
+        // Unresolved names fall back to IDs.
+        assert_eq!(crash.vars["env_name"], "env_1");
+        assert_eq!(
+            event_of(
+                event_types::INSTANCE_STATUS_CHANGED,
+                json!({"instance_id": "inst_1", "status": "failed", "reason_code": "oom_killed"})
+            ),
+            None
+        );
+
+        let cert = event_of(
+            event_types::ROUTE_CERT_EXPIRING,
+            json!({"route_id": "rt_1", "hostname": "a.example.com",
+                   "fingerprint_sha256": "ab12", "days_remaining": 9}),
+        )
+        .unwrap();
+        assert_eq!(cert.trigger, Trigger::CertExpiring);
+        assert_eq!(cert.dedupe_key, "rt_1:ab12");
+        assert_eq!(cert.vars["days_remaining"], "9");
+
+        let alert = event_of(
+            event_types::ALERT_FIRED,
+            json!({"rule_id": "alr_1", "rule_name": "prod-down", "condition": "env_unhealthy",
+                   "target": "env_1", "value": 0, "summary": "web/prod: 0 of 2 instances ready"}),
+        )
+        .unwrap();
+        assert_eq!(alert.trigger, Trigger::AlertFired);
+        assert_eq!(alert.dedupe_key, "7");
+        assert_eq!(alert.vars["value"], "0");
+        assert_eq!(
+            rule(Trigger::AlertFired, None, Some("env_1"))
+                .render(&alert)
+                .0,
+            "[FIRING] prod-down: web/prod: 0 of 2 instances ready"
+        );
+
+        assert_eq!(event_of("app.created", json!({})), None);
+    }
+
+    #[test]
+    fn test_rule_matches() {
+        let event = deploy_failed();
+        assert!(rule(Trigger::DeployFailed, None, None).matches(&event));
+        assert!(rule(Trigger::DeployFailed, Some("app_1"), Some("env_1")).matches(&event));
+        assert!(!rule(Trigger::DeployFailed, Some("app_2"), None).matches(&event));
+        assert!(!rule(Trigger::DeployFailed, None, Some("env_2")).matches(&event));
+        assert!(!rule(Trigger::CertExpiring, None, None).matches(&event));
+
+        let mut disabled = rule(Trigger::DeployFailed, None, None);
+        disabled.spec.enabled = false;
+        assert!(!disabled.matches(&event));
+
+        let mut other_org = rule(Trigger::DeployFailed, None, None);
+        other_org.org_id = "org_2".to_string();
+        assert!(!other_org.matches(&event));
+    }
+
+    #[test]
+    fn test_rule_render() {
+        let event = deploy_failed();
+        let (subject, body) = rule(Trigger::DeployFailed, None, None).render(&event);
+        assert_eq!(subject, "Deploy dep_1 failed in web/prod");
+        assert_eq!(
+            body,
+            "Deploy dep_1 of web to prod failed.\nReason: healthcheck_failed"
+        );
+
+        let mut custom = rule(Trigger::DeployFailed, None, None);
+        custom.spec.subject_template = Some("[{env_name}]\n{app_name} deploy failed".to_string());
+        custom.spec.body_template = Some("See event {event_id}".to_string());
+        assert_eq!(
+            custom.render(&event),
+            (
+                "[prod] web deploy failed".to_string(),
+                "See event 7".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(5), Duration::seconds(480));
+        assert_eq!(retry_delay(10), Duration::seconds(3600));
+        assert_eq!(retry_delay(i32::MAX), Duration::seconds(3600));
+    }
+}
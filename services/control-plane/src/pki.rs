@@ -0,0 +1,456 @@
+//! Built-in certificate authority.
+//!
+//! Persists the platform CA (certificate plus envelope-encrypted key, see
+//! [`crate::secrets`]) and the certificates it issues, and builds the trust
+//! bundle and CRLs served to clients. The active CA is created on first use.
+//!
+//! Configuration:
+//! - `PLFM_PKI_TRUST_DOMAIN`: trust domain in identity URIs (default
+//!   `plfm.internal`)
+//! - `PLFM_PKI_CA_VALIDITY_DAYS`: lifetime of newly created CAs (default 365)
+//!
+//! Rotation retires the active CA and creates a new one. A retired CA stays
+//! in the trust bundle for the longest certificate lifetime after retirement
+//! (or until it expires), so certificates it issued keep verifying until
+//! they are renewed.
+//!
+//! See: docs/security/08-platform-pki.md
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_id::Ulid;
+use plfm_pki::{CertificateAuthority, Identity, IdentityKind, IssuedCertificate, PkiError};
+use sqlx::PgPool;
+use thiserror::Error;
+
+use crate::secrets::{self as secrets_crypto, KeyScope, SecretsCryptoError};
+
+const DEFAULT_CA_VALIDITY_DAYS: i64 = 365;
+
+/// How long a published CRL is valid; clients refetch before then.
+pub const CRL_VALIDITY: Duration = Duration::hours(1);
+
+/// Retired CAs stay trusted this long, the longest certificate lifetime. The
+/// active CA is also replaced this long before it expires.
+const RETIRED_CA_GRACE: Duration = Duration::days(7);
+
+#[derive(Debug, Error)]
+pub enum CaError {
+    #[error(transparent)]
+    Pki(#[from] PkiError),
+    #[error("CA key encryption failed: {0}")]
+    Crypto(#[from] SecretsCryptoError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub fn trust_domain() -> String {
+    std::env::var("PLFM_PKI_TRUST_DOMAIN")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| plfm_pki::DEFAULT_TRUST_DOMAIN.to_string())
+}
+
+fn ca_validity() -> Duration {
+    let days = std::env::var("PLFM_PKI_CA_VALIDITY_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|d| *d > 0)
+        .unwrap_or(DEFAULT_CA_VALIDITY_DAYS);
+    Duration::days(days)
+}
+
+fn key_aad(ca_id: &str) -> String {
+    format!("plfm-pki-ca:{ca_id}")
+}
+
+/// A CA certificate in the trust bundle.
+#[derive(Debug, Clone)]
+pub struct TrustAnchor {
+    pub ca_id: String,
+    pub cert_pem: String,
+    pub not_after: DateTime<Utc>,
+    pub active: bool,
+}
+
+/// Metadata of an issued certificate.
+#[derive(Debug, Clone)]
+pub struct CertificateRecord {
+    pub serial: String,
+    pub ca_id: String,
+    pub kind: String,
+    pub subject_id: String,
+    pub common_name: String,
+    pub fingerprint_sha256: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub issued_by: String,
+    pub issued_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revocation_reason: Option<String>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for CertificateRecord {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            serial: row.try_get("serial")?,
+            ca_id: row.try_get("ca_id")?,
+            kind: row.try_get("kind")?,
+            subject_id: row.try_get("subject_id")?,
+            common_name: row.try_get("common_name")?,
+            fingerprint_sha256: row.try_get("fingerprint_sha256")?,
+            not_before: row.try_get("not_before")?,
+            not_after: row.try_get("not_after")?,
+            issued_by: row.try_get("issued_by")?,
+            issued_at: row.try_get("issued_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+            revocation_reason: row.try_get("revocation_reason")?,
+        })
+    }
+}
+
+const CERTIFICATE_COLUMNS: &str = "serial, ca_id, kind, subject_id, common_name, \
+     fingerprint_sha256, not_before, not_after, issued_by, issued_at, revoked_at, \
+     revocation_reason";
+
+struct AuthorityRow {
+    ca_id: String,
+    cert_pem: String,
+    key_nonce: Vec<u8>,
+    key_ciphertext: Vec<u8>,
+    master_key_id: String,
+    wrapped_data_key: Vec<u8>,
+    wrapped_data_key_nonce: Vec<u8>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AuthorityRow {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            ca_id: row.try_get("ca_id")?,
+            cert_pem: row.try_get("cert_pem")?,
+            key_nonce: row.try_get("key_nonce")?,
+            key_ciphertext: row.try_get("key_ciphertext")?,
+            master_key_id: row.try_get("master_key_id")?,
+            wrapped_data_key: row.try_get("wrapped_data_key")?,
+            wrapped_data_key_nonce: row.try_get("wrapped_data_key_nonce")?,
+        })
+    }
+}
+
+const AUTHORITY_COLUMNS: &str = "ca_id, cert_pem, key_nonce, key_ciphertext, master_key_id, \
+     wrapped_data_key, wrapped_data_key_nonce";
+
+async fn load_authority(row: AuthorityRow) -> Result<(String, CertificateAuthority), CaError> {
+    let key_pem = secrets_crypto::decrypt(
+        &row.master_key_id,
+        &row.key_nonce,
+        &row.key_ciphertext,
+        &row.wrapped_data_key,
+        &row.wrapped_data_key_nonce,
+        key_aad(&row.ca_id).as_bytes(),
+    )
+    .await?;
+    let key_pem = String::from_utf8(key_pem)
+        .map_err(|_| PkiError::InvalidPem("CA key is not UTF-8".to_string()))?;
+    let ca = CertificateAuthority::from_pem(&row.cert_pem, &key_pem)?;
+    Ok((row.ca_id, ca))
+}
+
+/// Generate a CA and store it as the active one. Returns `None` if another
+/// replica created an active CA first.
+async fn insert_authority(
+    pool: &PgPool,
+) -> Result<Option<(String, CertificateAuthority)>, CaError> {
+    let ca_id = format!("ca_{}", Ulid::new());
+    let ca = CertificateAuthority::generate(
+        &format!("plfm-vt platform CA {}", trust_domain()),
+        ca_validity(),
+    )?;
+    let encrypted = secrets_crypto::encrypt(
+        KeyScope::Platform,
+        ca.key_pem().as_bytes(),
+        key_aad(&ca_id).as_bytes(),
+    )
+    .await?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO pki_authorities (
+            ca_id, cert_pem, key_cipher, key_nonce, key_ciphertext, master_key_id,
+            wrapped_data_key, wrapped_data_key_nonce, not_after
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&ca_id)
+    .bind(ca.cert_pem())
+    .bind(&encrypted.cipher)
+    .bind(&encrypted.nonce)
+    .bind(&encrypted.ciphertext)
+    .bind(&encrypted.master_key_id)
+    .bind(&encrypted.wrapped_data_key)
+    .bind(&encrypted.wrapped_data_key_nonce)
+    .bind(ca.not_after())
+    .execute(pool)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Ok(None);
+    }
+    tracing::info!(ca_id = %ca_id, not_after = %ca.not_after(), "Created platform CA");
+    Ok(Some((ca_id, ca)))
+}
+
+/// The active signing CA, creating it on first use and replacing it once it
+/// is too close to expiry to cover a full certificate lifetime.
+pub async fn active_authority(pool: &PgPool) -> Result<(String, CertificateAuthority), CaError> {
+    sqlx::query(
+        r#"
+        UPDATE pki_authorities SET retired_at = now()
+        WHERE retired_at IS NULL AND not_after <= now() + make_interval(secs => $1)
+        "#,
+    )
+    .bind(RETIRED_CA_GRACE.num_seconds() as f64)
+    .execute(pool)
+    .await?;
+
+    let query = format!("SELECT {AUTHORITY_COLUMNS} FROM pki_authorities WHERE retired_at IS NULL");
+    if let Some(row) = sqlx::query_as::<_, AuthorityRow>(&query)
+        .fetch_optional(pool)
+        .await?
+    {
+        return load_authority(row).await;
+    }
+
+    if let Some(created) = insert_authority(pool).await? {
+        return Ok(created);
+    }
+    let row = sqlx::query_as::<_, AuthorityRow>(&query)
+        .fetch_one(pool)
+        .await?;
+    load_authority(row).await
+}
+
+/// Issue a certificate for `identity` and record it.
+pub async fn issue(
+    pool: &PgPool,
+    identity: &Identity,
+    csr_pem: &str,
+    ttl: Option<Duration>,
+    issued_by: &str,
+) -> Result<IssuedCertificate, CaError> {
+    let ttl = identity.kind.ttl(ttl)?;
+    let (ca_id, ca) = active_authority(pool).await?;
+    let issued = ca.issue(csr_pem, identity, &trust_domain(), ttl)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO pki_certificates (
+            serial, ca_id, kind, subject_id, common_name, fingerprint_sha256,
+            not_before, not_after, issued_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(&issued.serial)
+    .bind(&ca_id)
+    .bind(identity.kind.as_str())
+    .bind(&identity.id)
+    .bind(&identity.common_name)
+    .bind(&issued.fingerprint_sha256)
+    .bind(issued.not_before)
+    .bind(issued.not_after)
+    .bind(issued_by)
+    .execute(pool)
+    .await?;
+
+    tracing::info!(
+        serial = %issued.serial,
+        kind = %identity.kind,
+        subject_id = %identity.id,
+        not_after = %issued.not_after,
+        "Issued certificate"
+    );
+    Ok(issued)
+}
+
+/// Revoke an unexpired certificate. Returns false if no such certificate
+/// exists or it is already revoked.
+pub async fn revoke(pool: &PgPool, serial: &str, key_compromise: bool) -> Result<bool, CaError> {
+    let reason = if key_compromise {
+        "key_compromise"
+    } else {
+        "superseded"
+    };
+    let result = sqlx::query(
+        r#"
+        UPDATE pki_certificates
+        SET revoked_at = now(), revocation_reason = $2
+        WHERE serial = $1 AND revoked_at IS NULL AND not_after > now()
+        "#,
+    )
+    .bind(serial)
+    .bind(reason)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Revoke every unexpired certificate issued to a subject (e.g. a node that
+/// is being decommissioned). Returns the number revoked.
+pub async fn revoke_subject(
+    pool: &PgPool,
+    kind: IdentityKind,
+    subject_id: &str,
+) -> Result<u64, CaError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE pki_certificates
+        SET revoked_at = now(), revocation_reason = 'key_compromise'
+        WHERE kind = $1 AND subject_id = $2 AND revoked_at IS NULL AND not_after > now()
+        "#,
+    )
+    .bind(kind.as_str())
+    .bind(subject_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn list_certificates(
+    pool: &PgPool,
+    kind: Option<IdentityKind>,
+    subject_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<CertificateRecord>, CaError> {
+    Ok(sqlx::query_as(&format!(
+        r#"
+        SELECT {CERTIFICATE_COLUMNS}
+        FROM pki_certificates
+        WHERE ($1::text IS NULL OR kind = $1)
+          AND ($2::text IS NULL OR subject_id = $2)
+        ORDER BY issued_at DESC
+        LIMIT $3
+        "#
+    ))
+    .bind(kind.map(IdentityKind::as_str))
+    .bind(subject_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// CA certificates clients should trust: the active CA plus recently
+/// retired, unexpired ones.
+pub async fn trust_bundle(pool: &PgPool) -> Result<Vec<TrustAnchor>, CaError> {
+    // Make sure there is something to trust before the first issuance.
+    active_authority(pool).await?;
+
+    let rows: Vec<(String, String, DateTime<Utc>, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        SELECT ca_id, cert_pem, not_after, retired_at
+        FROM pki_authorities
+        WHERE not_after > now()
+          AND (retired_at IS NULL OR retired_at > now() - make_interval(secs => $1))
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(RETIRED_CA_GRACE.num_seconds() as f64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(ca_id, cert_pem, not_after, retired_at)| TrustAnchor {
+            ca_id,
+            cert_pem,
+            not_after,
+            active: retired_at.is_none(),
+        })
+        .collect())
+}
+
+/// Signed CRLs (PEM, concatenated) for every CA in the trust bundle.
+pub async fn crl_bundle(pool: &PgPool) -> Result<String, CaError> {
+    let mut pem = String::new();
+    for anchor in trust_bundle(pool).await? {
+        let row = sqlx::query_as::<_, AuthorityRow>(&format!(
+            "SELECT {AUTHORITY_COLUMNS} FROM pki_authorities WHERE ca_id = $1"
+        ))
+        .bind(&anchor.ca_id)
+        .fetch_one(pool)
+        .await?;
+        let (ca_id, ca) = load_authority(row).await?;
+
+        let crl_number: i64 = sqlx::query_scalar(
+            "UPDATE pki_authorities SET crl_number = crl_number + 1 WHERE ca_id = $1 RETURNING crl_number",
+        )
+        .bind(&ca_id)
+        .fetch_one(pool)
+        .await?;
+
+        let revoked: Vec<(String, DateTime<Utc>, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT serial, revoked_at, revocation_reason
+            FROM pki_certificates
+            WHERE ca_id = $1 AND revoked_at IS NOT NULL AND not_after > now()
+            ORDER BY revoked_at
+            "#,
+        )
+        .bind(&ca_id)
+        .fetch_all(pool)
+        .await?;
+        let revoked: Vec<_> = revoked
+            .into_iter()
+            .map(|(serial, revoked_at, reason)| plfm_pki::Revocation {
+                serial,
+                revoked_at,
+                key_compromise: reason.as_deref() != Some("superseded"),
+            })
+            .collect();
+
+        pem.push_str(&ca.crl(&revoked, crl_number as u64, Utc::now() + CRL_VALIDITY)?);
+    }
+    Ok(pem)
+}
+
+/// Retire the active CA and create a new one. Returns the new CA's ID.
+pub async fn rotate(pool: &PgPool) -> Result<String, CaError> {
+    sqlx::query("UPDATE pki_authorities SET retired_at = now() WHERE retired_at IS NULL")
+        .execute(pool)
+        .await?;
+    let (ca_id, _) = active_authority(pool).await?;
+    tracing::info!(ca_id = %ca_id, "Rotated platform CA");
+    Ok(ca_id)
+}
+
+/// Common name for a node certificate: the CN of the node's enrolled mTLS
+/// subject (so subject pinning matches), or the node ID.
+pub fn node_common_name(agent_mtls_subject: &str, node_id: &str) -> String {
+    agent_mtls_subject
+        .split(',')
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix("CN="))
+        .filter(|cn| !cn.is_empty() && cn.len() <= 64)
+        .unwrap_or(node_id)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_common_name() {
+        assert_eq!(
+            node_common_name("CN=node-01.example.com", "node_1"),
+            "node-01.example.com"
+        );
+        assert_eq!(node_common_name("O=plfm, CN=node-02", "node_1"), "node-02");
+        assert_eq!(node_common_name("", "node_1"), "node_1");
+        assert_eq!(node_common_name("O=plfm", "node_1"), "node_1");
+    }
+}
@@ -1,6 +1,9 @@
 //! Applications projection handler.
 //!
-//! Handles app.created, app.updated, and app.deleted events, updating the apps_view table.
+//! Handles app.created, app.updated, app.labels_updated, app.deletion_requested,
+//! app.deleted, app.restored, and app.purged events, updating the apps_view table.
+
+use std::collections::BTreeMap;
 
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -36,6 +39,20 @@ struct AppUpdatedPayload {
     description: Option<String>,
 }
 
+/// Payload for app.labels_updated event.
+#[derive(Debug, Deserialize)]
+struct AppLabelsUpdatedPayload {
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+/// Payload for app.deletion_requested event.
+#[derive(Debug, Deserialize)]
+struct AppDeletionRequestedPayload {
+    #[serde(default)]
+    delete_volumes: bool,
+}
+
 #[async_trait]
 impl ProjectionHandler for AppsProjection {
     fn name(&self) -> &'static str {
@@ -43,7 +60,15 @@ impl ProjectionHandler for AppsProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["app.created", "app.updated", "app.deleted"]
+        &[
+            "app.created",
+            "app.updated",
+            "app.labels_updated",
+            "app.deletion_requested",
+            "app.deleted",
+            "app.restored",
+            "app.purged",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -55,7 +80,11 @@ impl ProjectionHandler for AppsProjection {
         match event.event_type.as_str() {
             "app.created" => self.handle_app_created(tx, event).await,
             "app.updated" => self.handle_app_updated(tx, event).await,
+            "app.labels_updated" => self.handle_app_labels_updated(tx, event).await,
+            "app.deletion_requested" => self.handle_app_deletion_requested(tx, event).await,
             "app.deleted" => self.handle_app_deleted(tx, event).await,
+            "app.restored" => self.handle_app_restored(tx, event).await,
+            "app.purged" => self.handle_app_purged(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -193,6 +222,78 @@ impl AppsProjection {
         Ok(())
     }
 
+    /// Handle app.labels_updated event.
+    ///
+    /// The payload carries the full label set, so the view is overwritten.
+    async fn handle_app_labels_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: AppLabelsUpdatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            app_id = %event.aggregate_id,
+            label_count = payload.labels.len(),
+            "Updating labels in apps_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE apps_view
+            SET labels = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE app_id = $1
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(serde_json::json!(payload.labels))
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle app.deletion_requested event.
+    ///
+    /// Marks the app as pending teardown; the cleanup worker emits
+    /// app.deleted once every env of the app has been torn down.
+    async fn handle_app_deletion_requested(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: AppDeletionRequestedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            app_id = %event.aggregate_id,
+            delete_volumes = payload.delete_volumes,
+            "Marking app deletion requested in apps_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE apps_view
+            SET deletion_requested_at = COALESCE(deletion_requested_at, $2),
+                delete_volumes = $3,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE app_id = $1 AND NOT is_deleted
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .bind(payload.delete_volumes)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     /// Handle app.deleted event.
     async fn handle_app_deleted(
         &self,
@@ -208,6 +309,7 @@ impl AppsProjection {
             r#"
             UPDATE apps_view
             SET is_deleted = true,
+                deleted_at = $2,
                 resource_version = resource_version + 1,
                 updated_at = $2
             WHERE app_id = $1
@@ -220,6 +322,68 @@ impl AppsProjection {
 
         Ok(())
     }
+
+    /// Handle app.restored event.
+    ///
+    /// Clears the deletion along with the teardown request that led to it,
+    /// so the cleanup worker does not pick the app up again.
+    async fn handle_app_restored(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(
+            app_id = %event.aggregate_id,
+            "Restoring app in apps_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE apps_view
+            SET is_deleted = false,
+                deleted_at = NULL,
+                deletion_requested_at = NULL,
+                delete_volumes = false,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE app_id = $1 AND purged_at IS NULL
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle app.purged event.
+    async fn handle_app_purged(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(
+            app_id = %event.aggregate_id,
+            "Purging app in apps_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE apps_view
+            SET purged_at = $2,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE app_id = $1 AND is_deleted
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -267,6 +431,33 @@ mod tests {
         let projection = AppsProjection;
         assert!(projection.event_types().contains(&"app.created"));
         assert!(projection.event_types().contains(&"app.updated"));
+        assert!(projection.event_types().contains(&"app.labels_updated"));
+        assert!(projection.event_types().contains(&"app.deletion_requested"));
         assert!(projection.event_types().contains(&"app.deleted"));
+        assert!(projection.event_types().contains(&"app.restored"));
+        assert!(projection.event_types().contains(&"app.purged"));
+    }
+
+    #[test]
+    fn test_app_labels_updated_payload_deserialization() {
+        let json =
+            r#"{"app_id": "app_test", "org_id": "org_test", "labels": {"team": "payments"}}"#;
+        let payload: AppLabelsUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.labels.get("team"), Some(&"payments".to_string()));
+
+        let json = r#"{"app_id": "app_test", "org_id": "org_test"}"#;
+        let payload: AppLabelsUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert!(payload.labels.is_empty());
+    }
+
+    #[test]
+    fn test_app_deletion_requested_payload_defaults() {
+        let payload: AppDeletionRequestedPayload =
+            serde_json::from_str(r#"{"app_id": "app_test", "org_id": "org_test"}"#).unwrap();
+        assert!(!payload.delete_volumes);
+
+        let payload: AppDeletionRequestedPayload =
+            serde_json::from_str(r#"{"app_id": "app_test", "delete_volumes": true}"#).unwrap();
+        assert!(payload.delete_volumes);
     }
 }
@@ -1,6 +1,7 @@
 //! Deploys projection handler.
 //!
-//! Handles deploy.created and deploy.status_changed events, updating the deploys_view table.
+//! Handles deploy.created, deploy.status_changed, deploy.promoted and
+//! deploy.rollout_stuck events, updating the deploys_view table.
 
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -26,6 +27,8 @@ struct DeployCreatedPayload {
     process_types: Vec<String>,
     strategy: String,
     initiated_at: String,
+    #[serde(default)]
+    reason: Option<String>,
 }
 
 /// Payload for deploy.status_changed event.
@@ -43,6 +46,17 @@ struct DeployStatusChangedPayload {
     updated_at: String,
 }
 
+/// Payload for deploy.rollout_stuck event.
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct DeployRolloutStuckPayload {
+    deploy_id: String,
+    process_type: String,
+    converging_secs: i64,
+    deadline_secs: i64,
+    message: String,
+}
+
 #[async_trait]
 impl ProjectionHandler for DeploysProjection {
     fn name(&self) -> &'static str {
@@ -50,7 +64,12 @@ impl ProjectionHandler for DeploysProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["deploy.created", "deploy.status_changed"]
+        &[
+            "deploy.created",
+            "deploy.status_changed",
+            "deploy.promoted",
+            "deploy.rollout_stuck",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -62,6 +81,8 @@ impl ProjectionHandler for DeploysProjection {
         match event.event_type.as_str() {
             "deploy.created" => self.handle_deploy_created(tx, event).await,
             "deploy.status_changed" => self.handle_deploy_status_changed(tx, event).await,
+            "deploy.promoted" => self.handle_deploy_promoted(tx, event).await,
+            "deploy.rollout_stuck" => self.handle_deploy_rollout_stuck(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -110,9 +131,9 @@ impl DeploysProjection {
             r#"
             INSERT INTO deploys_view (
                 deploy_id, org_id, app_id, env_id, kind, release_id, process_types,
-                status, message, failed_reason, resource_version, created_at, updated_at
+                status, message, failed_reason, reason, resource_version, created_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NULL, NULL, 1, $9, $9)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NULL, NULL, $10, 1, $9, $9)
             ON CONFLICT (deploy_id) DO UPDATE SET
                 status = EXCLUDED.status,
                 updated_at = EXCLUDED.updated_at
@@ -127,6 +148,7 @@ impl DeploysProjection {
         .bind(serde_json::to_value(&payload.process_types).unwrap_or_default())
         .bind("queued")
         .bind(event.occurred_at)
+        .bind(&payload.reason)
         .execute(&mut **tx)
         .await?;
 
@@ -228,6 +250,73 @@ impl DeploysProjection {
 
         Ok(())
     }
+
+    /// Handle deploy.promoted event.
+    ///
+    /// Marks the deploy as promoted so the scheduler replaces remaining old
+    /// instances without surge limits.
+    async fn handle_deploy_promoted(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(
+            deploy_id = %event.aggregate_id,
+            "Marking deploy as promoted in deploys_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE deploys_view
+            SET promoted = true,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE deploy_id = $1
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle deploy.rollout_stuck event.
+    ///
+    /// Surfaces the stuck process type in the deploy's message; the status is
+    /// left alone since the rollout may still converge.
+    async fn handle_deploy_rollout_stuck(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: DeployRolloutStuckPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            deploy_id = %event.aggregate_id,
+            process_type = %payload.process_type,
+            "Recording stuck rollout in deploys_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE deploys_view
+            SET message = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE deploy_id = $1
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(&payload.message)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -257,6 +346,28 @@ mod tests {
         assert_eq!(payload.process_types, vec!["web", "worker"]);
         assert_eq!(payload.strategy, "rolling");
         assert_eq!(payload.initiated_at, "2025-01-01T00:00:00Z");
+        assert_eq!(payload.reason, None);
+    }
+
+    #[test]
+    fn test_deploy_created_payload_with_reason() {
+        let json = r#"{
+            "deploy_id": "dep_123",
+            "org_id": "org_123",
+            "app_id": "app_123",
+            "env_id": "env_123",
+            "release_id": "rel_123",
+            "kind": "rollback",
+            "process_types": ["web"],
+            "strategy": "rolling",
+            "initiated_at": "2025-01-01T00:00:00Z",
+            "reason": "Revert checkout regression"
+        }"#;
+        let payload: DeployCreatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.reason.as_deref(),
+            Some("Revert checkout regression")
+        );
     }
 
     #[test]
@@ -290,5 +401,7 @@ mod tests {
         let projection = DeploysProjection;
         assert!(projection.event_types().contains(&"deploy.created"));
         assert!(projection.event_types().contains(&"deploy.status_changed"));
+        assert!(projection.event_types().contains(&"deploy.promoted"));
+        assert!(projection.event_types().contains(&"deploy.rollout_stuck"));
     }
 }
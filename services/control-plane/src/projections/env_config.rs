@@ -1,11 +1,14 @@
 //! Environment configuration projection handler.
 //!
-//! Handles env.desired_release_set and env.scale_set events,
-//! updating the env_desired_releases_view and env_scale_view tables.
+//! Handles env.desired_release_set, env.scale_set, env.restart_requested,
+//! env.config_vars_set and env.process_resources_set events, updating the
+//! env_desired_releases_view, env_scale_view, env_config_vars_view and
+//! env_process_resources_view tables.
 //!
 //! These views are critical inputs for the scheduler.
 
 use async_trait::async_trait;
+use plfm_reconcile::RolloutLimit;
 use serde::Deserialize;
 use tracing::{debug, instrument};
 
@@ -37,11 +40,60 @@ struct EnvScaleSetPayload {
     scales: Vec<ScaleEntry>,
 }
 
+/// Payload for env.restart_requested event.
+#[derive(Debug, Deserialize)]
+struct EnvRestartRequestedPayload {
+    env_id: String,
+    process_types: Vec<String>,
+}
+
+/// Payload for env.config_vars_set event.
+#[derive(Debug, Deserialize)]
+struct EnvConfigVarsSetPayload {
+    env_id: String,
+    org_id: String,
+    app_id: String,
+    version: i32,
+    #[serde(default)]
+    vars: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Payload for env.process_resources_set event.
+#[derive(Debug, Deserialize)]
+struct EnvProcessResourcesSetPayload {
+    env_id: String,
+    org_id: String,
+    app_id: String,
+    process_type: String,
+    version: i32,
+    #[serde(default)]
+    cpu_request: Option<f64>,
+    #[serde(default)]
+    memory_limit_bytes: Option<i64>,
+    #[serde(default)]
+    ephemeral_disk_bytes: Option<i64>,
+}
+
 /// Individual scale entry.
+///
+/// Absent `node_selector`/`tolerations`/`ephemeral_disk_bytes`/`max_surge`/
+/// `max_unavailable`/`secrets_reload` leave the stored values unchanged.
 #[derive(Debug, Deserialize)]
 struct ScaleEntry {
     process_type: String,
     desired: i32,
+    #[serde(default)]
+    node_selector: Option<serde_json::Value>,
+    #[serde(default)]
+    tolerations: Option<serde_json::Value>,
+    #[serde(default)]
+    ephemeral_disk_bytes: Option<i64>,
+    #[serde(default)]
+    max_surge: Option<RolloutLimit>,
+    #[serde(default)]
+    max_unavailable: Option<RolloutLimit>,
+    #[serde(default)]
+    secrets_reload: Option<serde_json::Value>,
 }
 
 #[async_trait]
@@ -51,7 +103,13 @@ impl ProjectionHandler for EnvConfigProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["env.desired_release_set", "env.scale_set"]
+        &[
+            "env.desired_release_set",
+            "env.scale_set",
+            "env.restart_requested",
+            "env.config_vars_set",
+            "env.process_resources_set",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -63,6 +121,9 @@ impl ProjectionHandler for EnvConfigProjection {
         match event.event_type.as_str() {
             "env.desired_release_set" => self.handle_desired_release_set(tx, event).await,
             "env.scale_set" => self.handle_scale_set(tx, event).await,
+            "env.restart_requested" => self.handle_restart_requested(tx, event).await,
+            "env.config_vars_set" => self.handle_config_vars_set(tx, event).await,
+            "env.process_resources_set" => self.handle_process_resources_set(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -173,13 +234,25 @@ impl EnvConfigProjection {
                 r#"
                 INSERT INTO env_scale_view (
                     env_id, process_type, org_id, app_id, desired_replicas,
+                    node_selector, tolerations, ephemeral_disk_bytes,
+                    max_surge, max_unavailable, secrets_reload,
                     resource_version, updated_at
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                VALUES (
+                    $1, $2, $3, $4, $5,
+                    COALESCE($8, '{}'::jsonb), COALESCE($9, '[]'::jsonb), $10,
+                    $11, $12, $13, $6, $7
+                )
                 ON CONFLICT (env_id, process_type) DO UPDATE SET
                     org_id = EXCLUDED.org_id,
                     app_id = EXCLUDED.app_id,
                     desired_replicas = EXCLUDED.desired_replicas,
+                    node_selector = COALESCE($8, env_scale_view.node_selector),
+                    tolerations = COALESCE($9, env_scale_view.tolerations),
+                    ephemeral_disk_bytes = COALESCE($10, env_scale_view.ephemeral_disk_bytes),
+                    max_surge = COALESCE($11, env_scale_view.max_surge),
+                    max_unavailable = COALESCE($12, env_scale_view.max_unavailable),
+                    secrets_reload = COALESCE($13, env_scale_view.secrets_reload),
                     resource_version = EXCLUDED.resource_version,
                     updated_at = EXCLUDED.updated_at
                 "#,
@@ -191,12 +264,162 @@ impl EnvConfigProjection {
             .bind(scale.desired)
             .bind(next_version)
             .bind(event.occurred_at)
+            .bind(scale.node_selector.as_ref())
+            .bind(scale.tolerations.as_ref())
+            .bind(scale.ephemeral_disk_bytes)
+            .bind(scale.max_surge.map(|limit| limit.to_string()))
+            .bind(scale.max_unavailable.map(|limit| limit.to_string()))
+            .bind(scale.secrets_reload.as_ref())
             .execute(&mut **tx)
             .await?;
         }
 
         Ok(())
     }
+
+    /// Handle env.restart_requested event.
+    ///
+    /// Bumps restart_generation for the named process types so the scheduler
+    /// computes a new spec hash and rolls their instances.
+    async fn handle_restart_requested(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: EnvRestartRequestedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            env_id = %payload.env_id,
+            process_types = ?payload.process_types,
+            "Restarting process types"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE env_desired_releases_view
+            SET restart_generation = restart_generation + 1,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE env_id = $1 AND process_type = ANY($2::TEXT[])
+            "#,
+        )
+        .bind(&payload.env_id)
+        .bind(&payload.process_types)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle env.config_vars_set event.
+    ///
+    /// Replaces the env's current config vars and records the version so
+    /// instances allocated with it keep resolving the same set.
+    async fn handle_config_vars_set(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: EnvConfigVarsSetPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            env_id = %payload.env_id,
+            version = payload.version,
+            var_count = payload.vars.len(),
+            "Setting config vars for environment"
+        );
+
+        let vars = serde_json::Value::Object(payload.vars);
+
+        sqlx::query(
+            r#"
+            INSERT INTO env_config_var_versions (env_id, version, vars, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (env_id, version) DO NOTHING
+            "#,
+        )
+        .bind(&payload.env_id)
+        .bind(payload.version)
+        .bind(&vars)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO env_config_vars_view (env_id, org_id, app_id, vars, version, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (env_id) DO UPDATE SET
+                vars = EXCLUDED.vars,
+                version = EXCLUDED.version,
+                updated_at = EXCLUDED.updated_at
+            WHERE env_config_vars_view.version < EXCLUDED.version
+            "#,
+        )
+        .bind(&payload.env_id)
+        .bind(&payload.org_id)
+        .bind(&payload.app_id)
+        .bind(&vars)
+        .bind(payload.version)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle env.process_resources_set event.
+    ///
+    /// Replaces the resource overrides of one process type; cleared fields
+    /// are stored as NULL so the release or scale values apply again.
+    async fn handle_process_resources_set(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: EnvProcessResourcesSetPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            env_id = %payload.env_id,
+            process_type = %payload.process_type,
+            version = payload.version,
+            "Setting process resources for environment"
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO env_process_resources_view (
+                env_id, process_type, org_id, app_id, cpu_request, memory_limit_bytes,
+                ephemeral_disk_bytes, resource_version, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (env_id, process_type) DO UPDATE SET
+                cpu_request = EXCLUDED.cpu_request,
+                memory_limit_bytes = EXCLUDED.memory_limit_bytes,
+                ephemeral_disk_bytes = EXCLUDED.ephemeral_disk_bytes,
+                resource_version = EXCLUDED.resource_version,
+                updated_at = EXCLUDED.updated_at
+            WHERE env_process_resources_view.resource_version < EXCLUDED.resource_version
+            "#,
+        )
+        .bind(&payload.env_id)
+        .bind(&payload.process_type)
+        .bind(&payload.org_id)
+        .bind(&payload.app_id)
+        .bind(payload.cpu_request)
+        .bind(payload.memory_limit_bytes)
+        .bind(payload.ephemeral_disk_bytes)
+        .bind(payload.version)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +459,35 @@ mod tests {
         assert_eq!(payload.scales.len(), 2);
         assert_eq!(payload.scales[0].process_type, "web");
         assert_eq!(payload.scales[0].desired, 3);
+        assert!(payload.scales[0].tolerations.is_none());
+        assert!(payload.scales[0].ephemeral_disk_bytes.is_none());
+    }
+
+    #[test]
+    fn test_env_scale_set_payload_with_placement() {
+        let json = r#"{
+            "env_id": "env_123",
+            "org_id": "org_456",
+            "app_id": "app_789",
+            "scales": [{
+                "process_type": "gpu",
+                "desired": 1,
+                "node_selector": {"accel": "a100"},
+                "tolerations": [{"key": "gpu", "effect": "NoSchedule"}],
+                "ephemeral_disk_bytes": 8589934592,
+                "max_surge": "25%",
+                "max_unavailable": 1,
+                "secrets_reload": {"mode": "signal", "signal": "SIGHUP"}
+            }]
+        }"#;
+        let payload: EnvScaleSetPayload = serde_json::from_str(json).unwrap();
+        let scale = &payload.scales[0];
+        assert_eq!(scale.node_selector.as_ref().unwrap()["accel"], "a100");
+        assert_eq!(scale.tolerations.as_ref().unwrap()[0]["key"], "gpu");
+        assert_eq!(scale.ephemeral_disk_bytes, Some(8589934592));
+        assert_eq!(scale.max_surge, Some(RolloutLimit::Percent(25)));
+        assert_eq!(scale.max_unavailable, Some(RolloutLimit::Count(1)));
+        assert_eq!(scale.secrets_reload.as_ref().unwrap()["mode"], "signal");
     }
 
     #[test]
@@ -250,5 +502,55 @@ mod tests {
         let types = projection.event_types();
         assert!(types.contains(&"env.desired_release_set"));
         assert!(types.contains(&"env.scale_set"));
+        assert!(types.contains(&"env.restart_requested"));
+        assert!(types.contains(&"env.config_vars_set"));
+        assert!(types.contains(&"env.process_resources_set"));
+    }
+
+    #[test]
+    fn test_env_config_vars_set_payload_deserialization() {
+        let json = r#"{
+            "env_id": "env_123",
+            "org_id": "org_456",
+            "app_id": "app_789",
+            "version": 3,
+            "vars": {"LOG_LEVEL": "debug", "FEATURE_X": "on"}
+        }"#;
+        let payload: EnvConfigVarsSetPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.version, 3);
+        assert_eq!(payload.vars.len(), 2);
+        assert_eq!(payload.vars["LOG_LEVEL"], "debug");
+    }
+
+    #[test]
+    fn test_env_process_resources_set_payload_deserialization() {
+        let json = r#"{
+            "env_id": "env_123",
+            "org_id": "org_456",
+            "app_id": "app_789",
+            "process_type": "web",
+            "version": 2,
+            "cpu_request": 2.5,
+            "memory_limit_bytes": 1073741824
+        }"#;
+        let payload: EnvProcessResourcesSetPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.process_type, "web");
+        assert_eq!(payload.version, 2);
+        assert_eq!(payload.cpu_request, Some(2.5));
+        assert_eq!(payload.memory_limit_bytes, Some(1073741824));
+        assert!(payload.ephemeral_disk_bytes.is_none());
+    }
+
+    #[test]
+    fn test_env_restart_requested_payload_deserialization() {
+        let json = r#"{
+            "env_id": "env_123",
+            "org_id": "org_456",
+            "app_id": "app_789",
+            "process_types": ["web", "worker"]
+        }"#;
+        let payload: EnvRestartRequestedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.env_id, "env_123");
+        assert_eq!(payload.process_types, vec!["web", "worker"]);
     }
 }
@@ -1,8 +1,12 @@
 //! Environments projection handler.
 //!
-//! Handles env.created, env.updated, and env.deleted events, updating the envs_view table.
+//! Handles env.created, env.updated, env.labels_updated, env.deletion_requested,
+//! env.deleted, env.restored, and env.purged events, updating the envs_view table.
+
+use std::collections::BTreeMap;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use tracing::{debug, instrument};
 
@@ -21,6 +25,12 @@ struct EnvCreatedPayload {
     org_id: String,
     app_id: String,
     name: String,
+    #[serde(default)]
+    managed_hostname: Option<String>,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
 }
 
 /// Payload for env.updated event.
@@ -34,6 +44,20 @@ struct EnvUpdatedPayload {
     name: Option<String>,
 }
 
+/// Payload for env.labels_updated event.
+#[derive(Debug, Deserialize)]
+struct EnvLabelsUpdatedPayload {
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+/// Payload for env.deletion_requested event.
+#[derive(Debug, Deserialize)]
+struct EnvDeletionRequestedPayload {
+    #[serde(default)]
+    delete_volumes: bool,
+}
+
 #[async_trait]
 impl ProjectionHandler for EnvsProjection {
     fn name(&self) -> &'static str {
@@ -41,7 +65,15 @@ impl ProjectionHandler for EnvsProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["env.created", "env.updated", "env.deleted"]
+        &[
+            "env.created",
+            "env.updated",
+            "env.labels_updated",
+            "env.deletion_requested",
+            "env.deleted",
+            "env.restored",
+            "env.purged",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -53,7 +85,11 @@ impl ProjectionHandler for EnvsProjection {
         match event.event_type.as_str() {
             "env.created" => self.handle_env_created(tx, event).await,
             "env.updated" => self.handle_env_updated(tx, event).await,
+            "env.labels_updated" => self.handle_env_labels_updated(tx, event).await,
+            "env.deletion_requested" => self.handle_env_deletion_requested(tx, event).await,
             "env.deleted" => self.handle_env_deleted(tx, event).await,
+            "env.restored" => self.handle_env_restored(tx, event).await,
+            "env.purged" => self.handle_env_purged(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -90,10 +126,13 @@ impl EnvsProjection {
 
         sqlx::query(
             r#"
-            INSERT INTO envs_view (env_id, org_id, app_id, name, resource_version, created_at, updated_at, is_deleted)
-            VALUES ($1, $2, $3, $4, 1, $5, $5, false)
+            INSERT INTO envs_view (env_id, org_id, app_id, name, managed_hostname, preview, expires_at, resource_version, created_at, updated_at, is_deleted)
+            VALUES ($1, $2, $3, $4, $6, $7, $8, 1, $5, $5, false)
             ON CONFLICT (env_id) DO UPDATE SET
                 name = EXCLUDED.name,
+                managed_hostname = EXCLUDED.managed_hostname,
+                preview = EXCLUDED.preview,
+                expires_at = EXCLUDED.expires_at,
                 is_deleted = false,
                 updated_at = EXCLUDED.updated_at
             "#,
@@ -103,6 +142,9 @@ impl EnvsProjection {
         .bind(app_id)
         .bind(&payload.name)
         .bind(event.occurred_at)
+        .bind(&payload.managed_hostname)
+        .bind(payload.preview)
+        .bind(payload.expires_at)
         .execute(&mut **tx)
         .await?;
 
@@ -157,6 +199,78 @@ impl EnvsProjection {
         Ok(())
     }
 
+    /// Handle env.labels_updated event.
+    ///
+    /// The payload carries the full label set, so the view is overwritten.
+    async fn handle_env_labels_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: EnvLabelsUpdatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            env_id = %event.aggregate_id,
+            label_count = payload.labels.len(),
+            "Updating labels in envs_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE envs_view
+            SET labels = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE env_id = $1
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(serde_json::json!(payload.labels))
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle env.deletion_requested event.
+    ///
+    /// Marks the env as pending teardown; the cleanup worker drives the
+    /// remaining steps and emits env.deleted when they are done.
+    async fn handle_env_deletion_requested(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: EnvDeletionRequestedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            env_id = %event.aggregate_id,
+            delete_volumes = payload.delete_volumes,
+            "Marking env deletion requested in envs_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE envs_view
+            SET deletion_requested_at = COALESCE(deletion_requested_at, $2),
+                delete_volumes = $3,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE env_id = $1 AND NOT is_deleted
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .bind(payload.delete_volumes)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     /// Handle env.deleted event.
     async fn handle_env_deleted(
         &self,
@@ -172,6 +286,7 @@ impl EnvsProjection {
             r#"
             UPDATE envs_view
             SET is_deleted = true,
+                deleted_at = $2,
                 resource_version = resource_version + 1,
                 updated_at = $2
             WHERE env_id = $1
@@ -184,6 +299,68 @@ impl EnvsProjection {
 
         Ok(())
     }
+
+    /// Handle env.restored event.
+    ///
+    /// Clears the deletion along with the teardown request that led to it,
+    /// so the cleanup worker does not pick the env up again.
+    async fn handle_env_restored(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(
+            env_id = %event.aggregate_id,
+            "Restoring env in envs_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE envs_view
+            SET is_deleted = false,
+                deleted_at = NULL,
+                deletion_requested_at = NULL,
+                delete_volumes = false,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE env_id = $1 AND purged_at IS NULL
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle env.purged event.
+    async fn handle_env_purged(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(
+            env_id = %event.aggregate_id,
+            "Purging env in envs_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE envs_view
+            SET purged_at = $2,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE env_id = $1 AND is_deleted
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -198,6 +375,30 @@ mod tests {
         assert_eq!(payload.org_id, "org_test");
         assert_eq!(payload.app_id, "app_test");
         assert_eq!(payload.name, "production");
+        assert_eq!(payload.managed_hostname, None);
+        assert!(!payload.preview);
+        assert_eq!(payload.expires_at, None);
+    }
+
+    #[test]
+    fn test_env_created_payload_preview() {
+        let json = r#"{"env_id": "env_test", "org_id": "org_test", "app_id": "app_test", "name": "pr-42", "preview": true, "expires_at": "2026-01-04T12:00:00Z"}"#;
+        let payload: EnvCreatedPayload = serde_json::from_str(json).unwrap();
+        assert!(payload.preview);
+        assert_eq!(
+            payload.expires_at.map(|at| at.to_rfc3339()).as_deref(),
+            Some("2026-01-04T12:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_env_created_payload_with_managed_hostname() {
+        let json = r#"{"env_id": "env_test", "org_id": "org_test", "app_id": "app_test", "name": "production", "managed_hostname": "production-api-acme.apps.example.net"}"#;
+        let payload: EnvCreatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.managed_hostname.as_deref(),
+            Some("production-api-acme.apps.example.net")
+        );
     }
 
     #[test]
@@ -231,6 +432,28 @@ mod tests {
         let projection = EnvsProjection;
         assert!(projection.event_types().contains(&"env.created"));
         assert!(projection.event_types().contains(&"env.updated"));
+        assert!(projection.event_types().contains(&"env.labels_updated"));
+        assert!(projection.event_types().contains(&"env.deletion_requested"));
         assert!(projection.event_types().contains(&"env.deleted"));
+        assert!(projection.event_types().contains(&"env.restored"));
+        assert!(projection.event_types().contains(&"env.purged"));
+    }
+
+    #[test]
+    fn test_env_labels_updated_payload_deserialization() {
+        let json = r#"{"env_id": "env_test", "org_id": "org_test", "app_id": "app_test", "labels": {"tier": "prod"}}"#;
+        let payload: EnvLabelsUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.labels.get("tier"), Some(&"prod".to_string()));
+    }
+
+    #[test]
+    fn test_env_deletion_requested_payload_deserialization() {
+        let json = r#"{"env_id": "env_test", "org_id": "org_test", "app_id": "app_test", "delete_volumes": true}"#;
+        let payload: EnvDeletionRequestedPayload = serde_json::from_str(json).unwrap();
+        assert!(payload.delete_volumes);
+
+        let json = r#"{"env_id": "env_test", "org_id": "org_test", "app_id": "app_test"}"#;
+        let payload: EnvDeletionRequestedPayload = serde_json::from_str(json).unwrap();
+        assert!(!payload.delete_volumes);
     }
 }
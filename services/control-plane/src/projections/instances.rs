@@ -1,13 +1,14 @@
 //! Instances projection handler for scheduler output.
 //!
-//! Handles instance.allocated, instance.desired_state_changed events,
-//! updating the instances_desired_view table.
+//! Handles instance.allocated, instance.desired_state_changed and
+//! instance.config_updated events, updating the instances_desired_view table.
 
 use async_trait::async_trait;
 use serde::Deserialize;
 use tracing::{debug, instrument};
 
 use crate::db::EventRow;
+use crate::instance_status::{self, BootKey};
 use crate::projections::{ProjectionError, ProjectionHandler, ProjectionResult};
 
 /// Projection handler for instances.
@@ -22,6 +23,8 @@ struct InstanceAllocatedPayload {
     release_id: String,
     #[serde(default)]
     secrets_version_id: Option<String>,
+    #[serde(default)]
+    config_vars_version: Option<i32>,
     overlay_ipv6: String,
     #[serde(default)]
     resources_snapshot: serde_json::Value,
@@ -43,6 +46,18 @@ struct InstanceDesiredStateChangedPayload {
     reason: Option<String>,
 }
 
+/// Payload for instance.config_updated event.
+#[derive(Debug, Deserialize)]
+struct InstanceConfigUpdatedPayload {
+    instance_id: String,
+    #[serde(default)]
+    secrets_version_id: Option<String>,
+    #[serde(default)]
+    config_vars_version: Option<i32>,
+    #[serde(default)]
+    spec_hash: Option<String>,
+}
+
 /// Payload for instance.status_changed event.
 #[derive(Debug, Deserialize)]
 struct InstanceStatusChangedPayload {
@@ -53,6 +68,8 @@ struct InstanceStatusChangedPayload {
     #[serde(default)]
     boot_id: Option<String>,
     #[serde(default)]
+    generation: Option<i32>,
+    #[serde(default)]
     #[allow(dead_code)]
     microvm_id: Option<String>,
     #[serde(default)]
@@ -76,6 +93,7 @@ impl ProjectionHandler for InstancesProjection {
         &[
             "instance.allocated",
             "instance.desired_state_changed",
+            "instance.config_updated",
             "instance.status_changed",
         ]
     }
@@ -91,6 +109,7 @@ impl ProjectionHandler for InstancesProjection {
             "instance.desired_state_changed" => {
                 self.handle_instance_desired_state_changed(tx, event).await
             }
+            "instance.config_updated" => self.handle_instance_config_updated(tx, event).await,
             "instance.status_changed" => self.handle_instance_status_changed(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
@@ -143,13 +162,13 @@ impl InstancesProjection {
                 instance_id, org_id, app_id, env_id, process_type, node_id,
                 desired_state, release_id, deploy_id, secrets_version_id, overlay_ipv6,
                 resources_snapshot, spec_hash, generation, resource_version,
-                created_at, updated_at
+                created_at, updated_at, config_vars_version
             )
             VALUES (
                 $1, $2, $3, $4, $5, $6,
                 'running', $7, $8, $9, $10::INET,
                 $11, $12, 1, 1,
-                $13, $13
+                $13, $13, $14
             )
             ON CONFLICT (instance_id) DO UPDATE SET
                 desired_state = 'running',
@@ -157,6 +176,7 @@ impl InstancesProjection {
                 release_id = EXCLUDED.release_id,
                 deploy_id = EXCLUDED.deploy_id,
                 secrets_version_id = EXCLUDED.secrets_version_id,
+                config_vars_version = EXCLUDED.config_vars_version,
                 resources_snapshot = EXCLUDED.resources_snapshot,
                 spec_hash = EXCLUDED.spec_hash,
                 resource_version = instances_desired_view.resource_version + 1,
@@ -176,6 +196,7 @@ impl InstancesProjection {
         .bind(&resources_snapshot)
         .bind(&payload.spec_hash)
         .bind(event.occurred_at)
+        .bind(payload.config_vars_version)
         .execute(&mut **tx)
         .await?;
 
@@ -216,6 +237,68 @@ impl InstancesProjection {
         Ok(())
     }
 
+    /// Handle instance.config_updated event.
+    ///
+    /// Records the update's target, then bumps the config generation so the
+    /// node plan carries the new config for in-place delivery. The spec hash
+    /// moves with the config vars version. An absent field is left as it is.
+    /// A replayed or duplicate update changes nothing but the target.
+    async fn handle_instance_config_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: InstanceConfigUpdatedPayload =
+            serde_json::from_value(event.payload.clone())
+                .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            instance_id = %payload.instance_id,
+            secrets_version_id = ?payload.secrets_version_id,
+            config_vars_version = ?payload.config_vars_version,
+            "Updating instance config in instances_desired_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE instances_desired_view
+            SET config_target_secrets_version_id = $2,
+                config_target_config_vars_version = $3
+            WHERE instance_id = $1
+            "#,
+        )
+        .bind(&payload.instance_id)
+        .bind(payload.secrets_version_id.as_deref())
+        .bind(payload.config_vars_version)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE instances_desired_view
+            SET secrets_version_id = COALESCE($2, secrets_version_id),
+                config_vars_version = COALESCE($4, config_vars_version),
+                spec_hash = COALESCE($5, spec_hash),
+                generation = generation + 1,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE instance_id = $1
+              AND desired_state = 'running'
+              AND (secrets_version_id IS DISTINCT FROM COALESCE($2, secrets_version_id)
+                   OR config_vars_version IS DISTINCT FROM COALESCE($4, config_vars_version))
+            "#,
+        )
+        .bind(&payload.instance_id)
+        .bind(payload.secrets_version_id.as_deref())
+        .bind(event.occurred_at)
+        .bind(payload.config_vars_version)
+        .bind(payload.spec_hash.as_deref())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     /// Handle instance.status_changed event.
     ///
     /// Updates the instances_status_view table with the current status
@@ -241,6 +324,22 @@ impl InstancesProjection {
             )
         })?;
 
+        // Reports that raced past the endpoint's staleness check must not
+        // regress the row (or count as failures of the current boot).
+        let report_key = BootKey::new(payload.generation, payload.boot_id.clone());
+        if let Some(current_key) = instance_status::current_key(&mut **tx, &payload.instance_id)
+            .await?
+            .filter(|current| report_key.is_stale(current))
+        {
+            debug!(
+                instance_id = %payload.instance_id,
+                boot_id = ?report_key.boot_id,
+                current_boot_id = ?current_key.boot_id,
+                "Skipping stale instance status"
+            );
+            return Ok(());
+        }
+
         debug!(
             instance_id = %payload.instance_id,
             status = %payload.status,
@@ -261,17 +360,18 @@ impl InstancesProjection {
             r#"
             INSERT INTO instances_status_view (
                 instance_id, org_id, env_id, node_id, status,
-                boot_id, exit_code, reason_code, reason_detail,
+                boot_id, generation, exit_code, reason_code, reason_detail,
                 reported_at,
                 resource_version, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 1, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 1, $11)
             ON CONFLICT (instance_id) DO UPDATE SET
                 org_id = EXCLUDED.org_id,
                 env_id = EXCLUDED.env_id,
                 node_id = EXCLUDED.node_id,
                 status = EXCLUDED.status,
                 boot_id = COALESCE(EXCLUDED.boot_id, instances_status_view.boot_id),
+                generation = COALESCE(EXCLUDED.generation, instances_status_view.generation),
                 exit_code = EXCLUDED.exit_code,
                 reason_code = EXCLUDED.reason_code,
                 reason_detail = EXCLUDED.reason_detail,
@@ -286,6 +386,7 @@ impl InstancesProjection {
         .bind(&node_id)
         .bind(&payload.status)
         .bind(payload.boot_id.as_deref())
+        .bind(payload.generation)
         .bind(payload.exit_code)
         .bind(payload.reason_code.as_deref())
         .bind(payload.reason_detail.as_deref())
@@ -293,6 +394,44 @@ impl InstancesProjection {
         .execute(&mut **tx)
         .await?;
 
+        // Failures are counted by instance_restarts alert rules.
+        if payload.status == "failed" {
+            sqlx::query(
+                r#"
+                INSERT INTO instance_restarts
+                    (event_id, instance_id, org_id, env_id, reason_code, restarted_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (event_id) DO NOTHING
+                "#,
+            )
+            .bind(event.event_id)
+            .bind(&payload.instance_id)
+            .bind(org_id)
+            .bind(env_id.to_string())
+            .bind(payload.reason_code.as_deref())
+            .bind(event.occurred_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        // Status pages compute uptime from the transition history.
+        sqlx::query(
+            r#"
+            INSERT INTO instance_status_history
+                (event_id, instance_id, org_id, env_id, status, reported_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (event_id) DO NOTHING
+            "#,
+        )
+        .bind(event.event_id)
+        .bind(&payload.instance_id)
+        .bind(org_id)
+        .bind(env_id.to_string())
+        .bind(&payload.status)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
         Ok(())
     }
 }
@@ -331,6 +470,32 @@ mod tests {
         assert_eq!(payload.drain_grace_seconds, Some(10));
     }
 
+    #[test]
+    fn test_instance_config_updated_payload_deserialization() {
+        let json = r#"{
+            "instance_id": "inst_123",
+            "org_id": "org_1",
+            "env_id": "env_1",
+            "secrets_version_id": "sv_2",
+            "reason": "secrets_version_changed"
+        }"#;
+        let payload: InstanceConfigUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.instance_id, "inst_123");
+        assert_eq!(payload.secrets_version_id.as_deref(), Some("sv_2"));
+        assert_eq!(payload.config_vars_version, None);
+
+        let json = r#"{
+            "instance_id": "inst_123",
+            "secrets_version_id": "sv_2",
+            "config_vars_version": 4,
+            "spec_hash": "abc123",
+            "reason": "config_vars_version_changed"
+        }"#;
+        let payload: InstanceConfigUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.config_vars_version, Some(4));
+        assert_eq!(payload.spec_hash.as_deref(), Some("abc123"));
+    }
+
     #[test]
     fn test_instances_projection_name() {
         let projection = InstancesProjection;
@@ -343,6 +508,7 @@ mod tests {
         let types = projection.event_types();
         assert!(types.contains(&"instance.allocated"));
         assert!(types.contains(&"instance.desired_state_changed"));
+        assert!(types.contains(&"instance.config_updated"));
         assert!(types.contains(&"instance.status_changed"));
     }
 
@@ -353,12 +519,14 @@ mod tests {
             "node_id": "node_789",
             "status": "ready",
             "boot_id": "boot_456",
+            "generation": 2,
             "reported_at": "2025-12-21T00:00:00Z"
         }"#;
         let payload: InstanceStatusChangedPayload = serde_json::from_str(json).unwrap();
         assert_eq!(payload.instance_id, "inst_123");
         assert_eq!(payload.status, "ready");
         assert_eq!(payload.boot_id, Some("boot_456".to_string()));
+        assert_eq!(payload.generation, Some(2));
         assert_eq!(payload.node_id, Some("node_789".to_string()));
     }
 
@@ -23,6 +23,7 @@ mod projects;
 mod releases;
 mod restore_jobs;
 mod routes;
+mod search;
 mod secret_bundles;
 mod snapshots;
 mod volume_attachments;
@@ -100,6 +101,9 @@ impl ProjectionRegistry {
                 Box::new(snapshots::SnapshotsProjection),
                 Box::new(restore_jobs::RestoreJobsProjection),
                 Box::new(exec_sessions::ExecSessionsProjection),
+                // Registered last: it shares event types with the views above,
+                // and handler_for returns the first match.
+                Box::new(search::SearchProjection),
             ],
         }
     }
@@ -119,6 +123,15 @@ impl ProjectionRegistry {
         &self.handlers
     }
 
+    /// `(projection_name, event_type)` for every event type each projection
+    /// handles.
+    pub fn coverage(&self) -> Vec<(&'static str, &'static str)> {
+        self.handlers
+            .iter()
+            .flat_map(|h| h.event_types().iter().map(move |ty| (h.name(), *ty)))
+            .collect()
+    }
+
     /// Get all unique projection names.
     pub fn projection_names(&self) -> Vec<&'static str> {
         self.handlers.iter().map(|h| h.name()).collect()
@@ -157,12 +170,23 @@ mod tests {
     fn test_registry_finds_app_handler() {
         let registry = ProjectionRegistry::new();
         assert!(registry.handler_for("app.created").is_some());
+        assert!(registry.handler_for("app.labels_updated").is_some());
+        assert!(registry.handler_for("app.deletion_requested").is_some());
     }
 
     #[test]
     fn test_registry_finds_env_handler() {
         let registry = ProjectionRegistry::new();
         assert!(registry.handler_for("env.created").is_some());
+        assert!(registry.handler_for("env.labels_updated").is_some());
+        assert!(registry.handler_for("env.deletion_requested").is_some());
+    }
+
+    #[test]
+    fn test_registry_finds_label_handlers() {
+        let registry = ProjectionRegistry::new();
+        assert!(registry.handler_for("route.labels_updated").is_some());
+        assert!(registry.handler_for("volume.labels_updated").is_some());
     }
 
     #[test]
@@ -182,6 +206,8 @@ mod tests {
         let registry = ProjectionRegistry::new();
         assert!(registry.handler_for("deploy.created").is_some());
         assert!(registry.handler_for("deploy.status_changed").is_some());
+        assert!(registry.handler_for("deploy.promoted").is_some());
+        assert!(registry.handler_for("deploy.rollout_stuck").is_some());
     }
 
     #[test]
@@ -206,6 +232,9 @@ mod tests {
         let registry = ProjectionRegistry::new();
         assert!(registry.handler_for("env.desired_release_set").is_some());
         assert!(registry.handler_for("env.scale_set").is_some());
+        assert!(registry.handler_for("env.restart_requested").is_some());
+        assert!(registry.handler_for("env.config_vars_set").is_some());
+        assert!(registry.handler_for("env.process_resources_set").is_some());
     }
 
     #[test]
@@ -214,4 +243,11 @@ mod tests {
         assert!(registry.handler_for("env.ipv4_addon_enabled").is_some());
         assert!(registry.handler_for("env.ipv4_addon_disabled").is_some());
     }
+
+    #[test]
+    fn test_registry_includes_search_projection() {
+        let registry = ProjectionRegistry::new();
+        assert!(registry.projection_names().contains(&"search"));
+        assert_eq!(registry.handler_for("app.created").unwrap().name(), "apps");
+    }
 }
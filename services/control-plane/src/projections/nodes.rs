@@ -1,20 +1,22 @@
 //! Nodes projection handler.
 //!
-//! Handles node.enrolled, node.state_changed, and node.capacity_updated events,
-//! updating the nodes_view table.
+//! Handles node.enrolled, node.state_changed, node.capacity_updated,
+//! node.labels_updated, node.taints_updated, and the node.enrollment_*
+//! approval events, updating the nodes_view table.
 
 use async_trait::async_trait;
 use serde::Deserialize;
 use tracing::{debug, instrument};
 
 use crate::db::EventRow;
+use crate::enrollment::{STATE_DENIED, STATE_PENDING_APPROVAL};
 
 use super::{ProjectionError, ProjectionHandler, ProjectionResult};
 
 /// Projection handler for nodes.
 pub struct NodesProjection;
 
-/// Payload for node.enrolled event.
+/// Payload for node.enrolled and node.enrollment_requested events.
 #[derive(Debug, Deserialize)]
 struct NodeEnrolledPayload {
     node_id: String,
@@ -49,6 +51,28 @@ struct NodeStateChangedPayload {
     reason: Option<String>,
 }
 
+/// Payload for node.enrollment_approved and node.enrollment_denied events.
+#[derive(Debug, Deserialize)]
+struct NodeEnrollmentDecisionPayload {
+    node_id: String,
+}
+
+/// Payload for node.labels_updated event.
+#[derive(Debug, Deserialize)]
+struct NodeLabelsUpdatedPayload {
+    node_id: String,
+    #[serde(default)]
+    labels: serde_json::Value,
+}
+
+/// Payload for node.taints_updated event.
+#[derive(Debug, Deserialize)]
+struct NodeTaintsUpdatedPayload {
+    node_id: String,
+    #[serde(default)]
+    taints: serde_json::Value,
+}
+
 /// Payload for node.capacity_updated event.
 #[derive(Debug, Deserialize)]
 struct NodeCapacityUpdatedPayload {
@@ -56,6 +80,11 @@ struct NodeCapacityUpdatedPayload {
     available_cpu_cores: i32,
     available_memory_bytes: i64,
     instance_count: i32,
+    /// Absent from agents that do not report scratch disk usage.
+    #[serde(default)]
+    ephemeral_disk_used_bytes: Option<i64>,
+    #[serde(default)]
+    ephemeral_disk_available_bytes: Option<i64>,
 }
 
 #[async_trait]
@@ -69,6 +98,11 @@ impl ProjectionHandler for NodesProjection {
             "node.enrolled",
             "node.state_changed",
             "node.capacity_updated",
+            "node.labels_updated",
+            "node.taints_updated",
+            "node.enrollment_requested",
+            "node.enrollment_approved",
+            "node.enrollment_denied",
         ]
     }
 
@@ -79,9 +113,22 @@ impl ProjectionHandler for NodesProjection {
         event: &EventRow,
     ) -> ProjectionResult<()> {
         match event.event_type.as_str() {
-            "node.enrolled" => self.handle_node_enrolled(tx, event).await,
+            "node.enrolled" => self.handle_node_enrolled(tx, event, "active").await,
+            "node.enrollment_requested" => {
+                self.handle_node_enrolled(tx, event, STATE_PENDING_APPROVAL)
+                    .await
+            }
+            "node.enrollment_approved" => {
+                self.handle_enrollment_decision(tx, event, "active").await
+            }
+            "node.enrollment_denied" => {
+                self.handle_enrollment_decision(tx, event, STATE_DENIED)
+                    .await
+            }
             "node.state_changed" => self.handle_node_state_changed(tx, event).await,
             "node.capacity_updated" => self.handle_node_capacity_updated(tx, event).await,
+            "node.labels_updated" => self.handle_node_labels_updated(tx, event).await,
+            "node.taints_updated" => self.handle_node_taints_updated(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -91,11 +138,13 @@ impl ProjectionHandler for NodesProjection {
 }
 
 impl NodesProjection {
-    /// Handle node.enrolled event.
+    /// Handle node.enrolled (`state` active) and node.enrollment_requested
+    /// (`state` pending_approval) events.
     async fn handle_node_enrolled(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         event: &EventRow,
+        state: &str,
     ) -> ProjectionResult<()> {
         let payload: NodeEnrolledPayload = serde_json::from_value(event.payload.clone())
             .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
@@ -134,12 +183,12 @@ impl NodesProjection {
                 resource_version, created_at, updated_at
             )
             VALUES (
-                $1, 'active', $2, $3,
+                $1, $11, $2, $3,
                 $4::INET, $5::INET, $6::INET, $7, $8, $9,
                 1, $10, $10
             )
             ON CONFLICT (node_id) DO UPDATE SET
-                state = 'active',
+                state = EXCLUDED.state,
                 wireguard_public_key = EXCLUDED.wireguard_public_key,
                 agent_mtls_subject = EXCLUDED.agent_mtls_subject,
                 public_ipv6 = EXCLUDED.public_ipv6,
@@ -162,6 +211,45 @@ impl NodesProjection {
         .bind(&allocatable)
         .bind(payload.mtu)
         .bind(event.occurred_at)
+        .bind(state)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle node.enrollment_approved (`state` active) and
+    /// node.enrollment_denied (`state` denied) events.
+    ///
+    /// Only a node still pending approval changes state.
+    async fn handle_enrollment_decision(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+        state: &str,
+    ) -> ProjectionResult<()> {
+        let payload: NodeEnrollmentDecisionPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            node_id = %payload.node_id,
+            new_state = %state,
+            "Applying enrollment decision to nodes_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE nodes_view
+            SET state = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE node_id = $1 AND state = $4
+            "#,
+        )
+        .bind(&payload.node_id)
+        .bind(state)
+        .bind(event.occurred_at)
+        .bind(STATE_PENDING_APPROVAL)
         .execute(&mut **tx)
         .await?;
 
@@ -201,6 +289,80 @@ impl NodesProjection {
         Ok(())
     }
 
+    /// Handle node.labels_updated event.
+    ///
+    /// The payload carries the full label set, which replaces the stored one.
+    async fn handle_node_labels_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: NodeLabelsUpdatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(node_id = %payload.node_id, "Updating node labels in nodes_view");
+
+        let labels = if payload.labels.is_null() {
+            serde_json::json!({})
+        } else {
+            payload.labels
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE nodes_view
+            SET labels = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE node_id = $1
+            "#,
+        )
+        .bind(&payload.node_id)
+        .bind(&labels)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle node.taints_updated event.
+    ///
+    /// The payload carries the full taint set, which replaces the stored one.
+    async fn handle_node_taints_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: NodeTaintsUpdatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(node_id = %payload.node_id, "Updating node taints in nodes_view");
+
+        let taints = if payload.taints.is_null() {
+            serde_json::json!([])
+        } else {
+            payload.taints
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE nodes_view
+            SET taints = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE node_id = $1
+            "#,
+        )
+        .bind(&payload.node_id)
+        .bind(&taints)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     /// Handle node.capacity_updated event.
     ///
     /// Updates the allocatable field with current available resources.
@@ -220,17 +382,25 @@ impl NodesProjection {
             "Updating node capacity in nodes_view"
         );
 
-        // Update allocatable with current available resources
-        let allocatable = serde_json::json!({
+        // Update allocatable with current available resources. Every
+        // heartbeat emits this event, so it also records the heartbeat time.
+        let mut allocatable = serde_json::json!({
             "available_cpu_cores": payload.available_cpu_cores,
             "available_memory_bytes": payload.available_memory_bytes,
             "instance_count": payload.instance_count,
         });
+        if let Some(used) = payload.ephemeral_disk_used_bytes {
+            allocatable["ephemeral_disk_used_bytes"] = serde_json::json!(used);
+        }
+        if let Some(available) = payload.ephemeral_disk_available_bytes {
+            allocatable["ephemeral_disk_available_bytes"] = serde_json::json!(available);
+        }
 
         sqlx::query(
             r#"
             UPDATE nodes_view
             SET allocatable = allocatable || $2::jsonb,
+                last_heartbeat_at = $3,
                 resource_version = resource_version + 1,
                 updated_at = $3
             WHERE node_id = $1
@@ -314,6 +484,33 @@ mod tests {
         assert_eq!(payload.node_id, "node_123");
         assert_eq!(payload.available_cpu_cores, 6);
         assert_eq!(payload.instance_count, 4);
+        assert!(payload.ephemeral_disk_used_bytes.is_none());
+    }
+
+    #[test]
+    fn test_node_capacity_updated_payload_with_disk_usage() {
+        let json = r#"{
+            "node_id": "node_123",
+            "available_cpu_cores": 6,
+            "available_memory_bytes": 12884901888,
+            "instance_count": 2,
+            "ephemeral_disk_used_bytes": 8589934592,
+            "ephemeral_disk_available_bytes": null
+        }"#;
+        let payload: NodeCapacityUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.ephemeral_disk_used_bytes, Some(8589934592));
+        assert!(payload.ephemeral_disk_available_bytes.is_none());
+    }
+
+    #[test]
+    fn test_node_taints_updated_payload_deserialization() {
+        let json = r#"{
+            "node_id": "node_123",
+            "taints": [{"key": "gpu", "value": "a100", "effect": "NoSchedule"}]
+        }"#;
+        let payload: NodeTaintsUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.node_id, "node_123");
+        assert_eq!(payload.taints[0]["effect"], "NoSchedule");
     }
 
     #[test]
@@ -329,5 +526,17 @@ mod tests {
         assert!(types.contains(&"node.enrolled"));
         assert!(types.contains(&"node.state_changed"));
         assert!(types.contains(&"node.capacity_updated"));
+        assert!(types.contains(&"node.labels_updated"));
+        assert!(types.contains(&"node.taints_updated"));
+        assert!(types.contains(&"node.enrollment_requested"));
+        assert!(types.contains(&"node.enrollment_approved"));
+        assert!(types.contains(&"node.enrollment_denied"));
+    }
+
+    #[test]
+    fn test_node_enrollment_decision_payload_deserialization() {
+        let json = r#"{"node_id": "node_123", "reason": "unknown hardware"}"#;
+        let payload: NodeEnrollmentDecisionPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.node_id, "node_123");
     }
 }
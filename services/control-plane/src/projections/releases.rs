@@ -21,6 +21,8 @@ struct ReleaseCreatedPayload {
     manifest_schema_version: i32,
     manifest_hash: String,
     command: Vec<String>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
 }
 
 #[async_trait]
@@ -80,9 +82,9 @@ impl ReleasesProjection {
             INSERT INTO releases_view (
                 release_id, org_id, app_id, image_ref, index_or_manifest_digest,
                 resolved_digests, manifest_schema_version, manifest_hash, command,
-                resource_version, created_at
+                metadata, resource_version, created_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 1, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $11, 1, $10)
             ON CONFLICT (release_id) DO NOTHING
             "#,
         )
@@ -96,6 +98,7 @@ impl ReleasesProjection {
         .bind(&payload.manifest_hash)
         .bind(serde_json::json!(&payload.command))
         .bind(event.occurred_at)
+        .bind(payload.metadata.unwrap_or_else(|| serde_json::json!({})))
         .execute(&mut **tx)
         .await?;
 
@@ -122,6 +125,24 @@ mod tests {
         assert_eq!(payload.manifest_schema_version, 1);
         assert_eq!(payload.manifest_hash, "def456");
         assert_eq!(payload.command, vec!["./start", "--port", "8080"]);
+        assert!(payload.metadata.is_none());
+    }
+
+    #[test]
+    fn test_release_created_payload_with_metadata() {
+        let json = r#"{
+            "image_ref": "registry.example.com/app:pr-42",
+            "image_digest": "sha256:abc123",
+            "manifest_schema_version": 1,
+            "manifest_hash": "def456",
+            "command": [],
+            "metadata": {"git_ref": "refs/pull/42/head"}
+        }"#;
+        let payload: ReleaseCreatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.metadata,
+            Some(serde_json::json!({"git_ref": "refs/pull/42/head"}))
+        );
     }
 
     #[test]
@@ -1,6 +1,7 @@
 //! Restore jobs projection handler.
 //!
 //! Handles restore_job.created and restore_job.status_changed events, updating restore_jobs_view.
+//! In-place restores are also mirrored onto the target volume's row in volumes_view.
 
 use async_trait::async_trait;
 use plfm_events::{JobStatus, RestoreJobCreatedPayload, RestoreJobStatusChangedPayload};
@@ -66,6 +67,7 @@ impl RestoreJobsProjection {
             source_volume_id = %payload.source_volume_id,
             org_id = %payload.org_id,
             status = %status,
+            in_place = payload.in_place,
             "Inserting restore job into restore_jobs_view"
         );
 
@@ -79,11 +81,12 @@ impl RestoreJobsProjection {
                 status,
                 new_volume_id,
                 failed_reason,
+                in_place,
                 resource_version,
                 created_at,
                 updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, NULL, NULL, 1, $6, $6)
+            VALUES ($1, $2, $3, $4, $5, NULL, NULL, $7, 1, $6, $6)
             ON CONFLICT (restore_id) DO UPDATE SET
                 status = EXCLUDED.status,
                 updated_at = EXCLUDED.updated_at
@@ -95,9 +98,34 @@ impl RestoreJobsProjection {
         .bind(payload.source_volume_id.to_string())
         .bind(status)
         .bind(event.occurred_at)
+        .bind(payload.in_place)
         .execute(&mut **tx)
         .await?;
 
+        if payload.in_place {
+            sqlx::query(
+                r#"
+                UPDATE volumes_view
+                SET restore_id = $2,
+                    restore_snapshot_id = $3,
+                    restore_status = $4,
+                    restore_bytes_restored = NULL,
+                    restore_bytes_total = NULL,
+                    restore_error = NULL,
+                    resource_version = resource_version + 1,
+                    updated_at = $5
+                WHERE volume_id = $1
+                "#,
+            )
+            .bind(payload.source_volume_id.to_string())
+            .bind(payload.restore_id.to_string())
+            .bind(payload.snapshot_id.to_string())
+            .bind(status)
+            .bind(event.occurred_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -124,6 +152,8 @@ impl RestoreJobsProjection {
             SET status = $3,
                 new_volume_id = $4,
                 failed_reason = $5,
+                bytes_restored = COALESCE($7, bytes_restored),
+                bytes_total = COALESCE($8, bytes_total),
                 resource_version = resource_version + 1,
                 updated_at = $6
             WHERE restore_id = $1 AND org_id = $2
@@ -135,6 +165,30 @@ impl RestoreJobsProjection {
         .bind(payload.new_volume_id.as_ref().map(|id| id.to_string()))
         .bind(payload.failed_reason.as_deref())
         .bind(event.occurred_at)
+        .bind(payload.bytes_restored)
+        .bind(payload.bytes_total)
+        .execute(&mut **tx)
+        .await?;
+
+        // Only matches while this is still the volume's latest in-place restore.
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET restore_status = $2,
+                restore_bytes_restored = COALESCE($3, restore_bytes_restored),
+                restore_bytes_total = COALESCE($4, restore_bytes_total),
+                restore_error = $5,
+                resource_version = resource_version + 1,
+                updated_at = $6
+            WHERE restore_id = $1
+            "#,
+        )
+        .bind(payload.restore_id.to_string())
+        .bind(status)
+        .bind(payload.bytes_restored)
+        .bind(payload.bytes_total)
+        .bind(payload.failed_reason.as_deref())
+        .bind(event.occurred_at)
         .execute(&mut **tx)
         .await?;
 
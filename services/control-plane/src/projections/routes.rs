@@ -1,12 +1,15 @@
 //! Routes projection handler.
 //!
-//! Handles route.created, route.updated, and route.deleted events,
+//! Handles route.created, route.updated, route.labels_updated, route.deleted,
+//! route.restored, route.purged, and the route.verification_* events,
 //! updating the routes_view table.
 
 use async_trait::async_trait;
 use plfm_events::{
-    RouteCreatedPayload, RouteDeletedPayload, RouteProtocolHint, RouteProxyProtocol,
-    RouteUpdatedPayload,
+    RouteAccessPolicy, RouteCreatedPayload, RouteDeletedPayload, RouteLabelsUpdatedPayload,
+    RouteProtocolHint, RouteProxyProtocol, RoutePurgedPayload, RouteRestoredPayload,
+    RouteUpdatedPayload, RouteVerificationFailedPayload, RouteVerificationMethod,
+    RouteVerificationSucceededPayload,
 };
 use tracing::{debug, instrument};
 
@@ -24,7 +27,16 @@ impl ProjectionHandler for RoutesProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["route.created", "route.updated", "route.deleted"]
+        &[
+            "route.created",
+            "route.updated",
+            "route.labels_updated",
+            "route.deleted",
+            "route.restored",
+            "route.purged",
+            "route.verification_succeeded",
+            "route.verification_failed",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -36,7 +48,14 @@ impl ProjectionHandler for RoutesProjection {
         match event.event_type.as_str() {
             "route.created" => self.handle_route_created(tx, event).await,
             "route.updated" => self.handle_route_updated(tx, event).await,
+            "route.labels_updated" => self.handle_route_labels_updated(tx, event).await,
             "route.deleted" => self.handle_route_deleted(tx, event).await,
+            "route.restored" => self.handle_route_restored(tx, event).await,
+            "route.purged" => self.handle_route_purged(tx, event).await,
+            "route.verification_succeeded" => {
+                self.handle_route_verification_succeeded(tx, event).await
+            }
+            "route.verification_failed" => self.handle_route_verification_failed(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -45,6 +64,13 @@ impl ProjectionHandler for RoutesProjection {
     }
 }
 
+/// Stored form of a route access policy: NULL when it restricts nothing.
+fn access_policy_json(policy: Option<&RouteAccessPolicy>) -> Option<serde_json::Value> {
+    policy
+        .filter(|p| !p.is_empty())
+        .map(|p| serde_json::json!(p))
+}
+
 impl RoutesProjection {
     async fn handle_route_created(
         &self,
@@ -58,6 +84,13 @@ impl RoutesProjection {
         let protocol_hint = match payload.protocol_hint {
             RouteProtocolHint::TlsPassthrough => "tls_passthrough",
             RouteProtocolHint::TcpRaw => "tcp_raw",
+            RouteProtocolHint::TlsTerminate => "tls_terminate",
+        };
+        // Routes created without a token (verification disabled) are active at once.
+        let verification_status = if payload.verification_token.is_some() {
+            "pending"
+        } else {
+            "verified"
         };
 
         debug!(
@@ -81,12 +114,22 @@ impl RoutesProjection {
                 backend_port,
                 proxy_protocol,
                 ipv4_required,
+                verification_status,
+                verification_token,
+                verified_at,
+                internal,
+                access_policy,
                 resource_version,
                 created_at,
                 updated_at,
                 is_deleted
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 1, $12, $12, false)
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $13::TEXT, $14,
+                CASE WHEN $13::TEXT = 'verified' THEN $12 END,
+                $15, $16,
+                1, $12, $12, false
+            )
             ON CONFLICT (route_id) DO UPDATE SET
                 hostname = EXCLUDED.hostname,
                 listen_port = EXCLUDED.listen_port,
@@ -95,6 +138,11 @@ impl RoutesProjection {
                 backend_port = EXCLUDED.backend_port,
                 proxy_protocol = EXCLUDED.proxy_protocol,
                 ipv4_required = EXCLUDED.ipv4_required,
+                verification_status = EXCLUDED.verification_status,
+                verification_token = EXCLUDED.verification_token,
+                verified_at = EXCLUDED.verified_at,
+                internal = EXCLUDED.internal,
+                access_policy = EXCLUDED.access_policy,
                 is_deleted = false,
                 updated_at = EXCLUDED.updated_at
             "#,
@@ -111,6 +159,10 @@ impl RoutesProjection {
         .bind(proxy_protocol)
         .bind(payload.ipv4_required)
         .bind(event.occurred_at)
+        .bind(verification_status)
+        .bind(payload.verification_token.as_deref())
+        .bind(payload.internal)
+        .bind(access_policy_json(payload.access_policy.as_ref()))
         .execute(&mut **tx)
         .await?;
 
@@ -138,6 +190,7 @@ impl RoutesProjection {
                 backend_port = COALESCE($3, backend_port),
                 proxy_protocol = COALESCE($4, proxy_protocol),
                 ipv4_required = COALESCE($5, ipv4_required),
+                access_policy = CASE WHEN $7 THEN $8 ELSE access_policy END,
                 resource_version = resource_version + 1,
                 updated_at = $6
             WHERE route_id = $1 AND NOT is_deleted
@@ -149,6 +202,109 @@ impl RoutesProjection {
         .bind(proxy_protocol)
         .bind(payload.ipv4_required)
         .bind(event.occurred_at)
+        .bind(payload.access_policy.is_some())
+        .bind(access_policy_json(payload.access_policy.as_ref()))
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle route.labels_updated event.
+    ///
+    /// The payload carries the full label set, so the view is overwritten.
+    async fn handle_route_labels_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: RouteLabelsUpdatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            route_id = %payload.route_id,
+            label_count = payload.labels.len(),
+            "Updating labels in routes_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE routes_view
+            SET labels = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE route_id = $1
+            "#,
+        )
+        .bind(payload.route_id.to_string())
+        .bind(serde_json::json!(payload.labels))
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_route_verification_succeeded(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: RouteVerificationSucceededPayload =
+            serde_json::from_value(event.payload.clone())
+                .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(route_id = %payload.route_id, "Marking route verified in routes_view");
+
+        let method = match payload.method {
+            RouteVerificationMethod::Txt => "txt",
+            RouteVerificationMethod::Cname => "cname",
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE routes_view
+            SET verification_status = 'verified',
+                verification_method = $2,
+                verified_at = $3,
+                verification_error = NULL,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE route_id = $1
+            "#,
+        )
+        .bind(payload.route_id.to_string())
+        .bind(method)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_route_verification_failed(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: RouteVerificationFailedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(route_id = %payload.route_id, "Marking route verification failed in routes_view");
+
+        sqlx::query(
+            r#"
+            UPDATE routes_view
+            SET verification_status = 'failed',
+                verification_error = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE route_id = $1
+            "#,
+        )
+        .bind(payload.route_id.to_string())
+        .bind(&payload.reason)
+        .bind(event.occurred_at)
         .execute(&mut **tx)
         .await?;
 
@@ -169,6 +325,7 @@ impl RoutesProjection {
             r#"
             UPDATE routes_view
             SET is_deleted = true,
+                deleted_at = $2,
                 resource_version = resource_version + 1,
                 updated_at = $2
             WHERE route_id = $1
@@ -181,6 +338,61 @@ impl RoutesProjection {
 
         Ok(())
     }
+
+    async fn handle_route_restored(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: RouteRestoredPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(route_id = %payload.route_id, "Restoring route in routes_view");
+
+        sqlx::query(
+            r#"
+            UPDATE routes_view
+            SET is_deleted = false,
+                deleted_at = NULL,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE route_id = $1 AND purged_at IS NULL
+            "#,
+        )
+        .bind(payload.route_id.to_string())
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_route_purged(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: RoutePurgedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(route_id = %payload.route_id, "Purging route in routes_view");
+
+        sqlx::query(
+            r#"
+            UPDATE routes_view
+            SET purged_at = $2,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE route_id = $1 AND is_deleted
+            "#,
+        )
+        .bind(payload.route_id.to_string())
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -207,5 +419,92 @@ mod tests {
         let payload: RouteCreatedPayload = serde_json::from_str(json).unwrap();
         assert_eq!(payload.hostname, "example.com");
         assert!(matches!(payload.proxy_protocol, RouteProxyProtocol::Off));
+        assert!(!payload.internal);
+        assert!(payload.access_policy.is_none());
+    }
+
+    #[test]
+    fn access_policy_json_drops_empty_policies() {
+        assert!(access_policy_json(None).is_none());
+        assert!(access_policy_json(Some(&RouteAccessPolicy::default())).is_none());
+
+        let policy = RouteAccessPolicy {
+            deny_cidrs: vec!["203.0.113.0/24".to_string()],
+            ..Default::default()
+        };
+        let json = access_policy_json(Some(&policy)).unwrap();
+        assert_eq!(json["deny_cidrs"][0], "203.0.113.0/24");
+        assert!(json.get("allow_cidrs").is_none());
+    }
+
+    #[test]
+    fn route_labels_updated_payload_roundtrip() {
+        let json = r#"{
+            "route_id": "rt_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "org_id": "org_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "env_id": "env_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "labels": {"team": "payments"}
+        }"#;
+
+        let payload: RouteLabelsUpdatedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.labels.get("team").map(String::as_str),
+            Some("payments")
+        );
+        assert!(RoutesProjection
+            .event_types()
+            .contains(&"route.labels_updated"));
+    }
+
+    #[test]
+    fn route_verification_payloads_roundtrip() {
+        let json = r#"{
+            "route_id": "rt_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "org_id": "org_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "env_id": "env_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "hostname": "example.com",
+            "method": "cname"
+        }"#;
+        let payload: RouteVerificationSucceededPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.method, RouteVerificationMethod::Cname);
+
+        let created = r#"{
+            "route_id": "rt_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "org_id": "org_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "app_id": "app_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "env_id": "env_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "hostname": "example.com",
+            "listen_port": 443,
+            "protocol_hint": "tls_passthrough",
+            "backend_process_type": "web",
+            "backend_port": 8080,
+            "proxy_protocol": "off",
+            "backend_expects_proxy_protocol": false,
+            "ipv4_required": false
+        }"#;
+        let payload: RouteCreatedPayload = serde_json::from_str(created).unwrap();
+        assert!(payload.verification_token.is_none());
+        assert!(RoutesProjection
+            .event_types()
+            .contains(&"route.verification_failed"));
+    }
+
+    #[test]
+    fn route_restored_payload_roundtrip() {
+        let json = r#"{
+            "route_id": "rt_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "org_id": "org_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "env_id": "env_01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "hostname": "example.com"
+        }"#;
+        let payload: RouteRestoredPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.hostname, "example.com");
+        let payload: RoutePurgedPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.route_id.to_string(),
+            "rt_01ARZ3NDEKTSV4RRFFQ69G5FAV"
+        );
+        assert!(RoutesProjection.event_types().contains(&"route.restored"));
+        assert!(RoutesProjection.event_types().contains(&"route.purged"));
     }
 }
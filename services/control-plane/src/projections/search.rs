@@ -0,0 +1,487 @@
+//! Search index projection handler.
+//!
+//! Maintains search_index_view, a flat (resource_type, resource_id, name) index
+//! over apps, envs, releases, routes, volumes, and instances that backs
+//! `GET /v1/orgs/{org_id}/search`. Deleting an app or env also drops the
+//! resources indexed beneath it; restoring one re-indexes it from its view.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use crate::db::EventRow;
+
+use super::{ProjectionError, ProjectionHandler, ProjectionResult};
+
+/// Projection handler for the search index.
+pub struct SearchProjection;
+
+/// Subset of payload fields the index cares about, shared by every
+/// consumed event type.
+#[derive(Debug, Default, Deserialize)]
+struct SearchPayload {
+    #[serde(default)]
+    org_id: Option<String>,
+    #[serde(default)]
+    app_id: Option<String>,
+    #[serde(default)]
+    env_id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    hostname: Option<String>,
+    #[serde(default)]
+    image_ref: Option<String>,
+    #[serde(default)]
+    process_type: Option<String>,
+    #[serde(default)]
+    desired_state: Option<String>,
+}
+
+/// How an event changes the index.
+#[derive(Debug, PartialEq, Eq)]
+enum SearchChange {
+    /// Insert or replace the entry for the resource.
+    Upsert {
+        resource_type: &'static str,
+        name: Option<String>,
+    },
+    /// Rename an existing entry.
+    Rename {
+        resource_type: &'static str,
+        name: String,
+    },
+    /// Remove the entry (and, for apps and envs, everything beneath it).
+    Remove { resource_type: &'static str },
+    /// Re-index a restored resource (and, for apps, its releases).
+    Restore { resource_type: &'static str },
+    /// Nothing to do.
+    Skip,
+}
+
+impl SearchChange {
+    fn from_event(event_type: &str, payload: &SearchPayload) -> Self {
+        match event_type {
+            "app.created" => SearchChange::Upsert {
+                resource_type: "app",
+                name: payload.name.clone(),
+            },
+            "env.created" => SearchChange::Upsert {
+                resource_type: "env",
+                name: payload.name.clone(),
+            },
+            "release.created" => SearchChange::Upsert {
+                resource_type: "release",
+                name: payload.image_ref.clone(),
+            },
+            "route.created" => SearchChange::Upsert {
+                resource_type: "route",
+                name: payload.hostname.clone(),
+            },
+            "volume.created" => SearchChange::Upsert {
+                resource_type: "volume",
+                name: payload.name.clone(),
+            },
+            "instance.allocated" => SearchChange::Upsert {
+                resource_type: "instance",
+                name: payload.process_type.clone(),
+            },
+            "app.updated" | "env.updated" => match payload.name.clone() {
+                Some(name) => SearchChange::Rename {
+                    resource_type: if event_type == "app.updated" {
+                        "app"
+                    } else {
+                        "env"
+                    },
+                    name,
+                },
+                None => SearchChange::Skip,
+            },
+            "app.deleted" => SearchChange::Remove {
+                resource_type: "app",
+            },
+            "env.deleted" => SearchChange::Remove {
+                resource_type: "env",
+            },
+            "route.deleted" => SearchChange::Remove {
+                resource_type: "route",
+            },
+            "volume.deleted" => SearchChange::Remove {
+                resource_type: "volume",
+            },
+            "app.restored" => SearchChange::Restore {
+                resource_type: "app",
+            },
+            "env.restored" => SearchChange::Restore {
+                resource_type: "env",
+            },
+            "route.restored" => SearchChange::Restore {
+                resource_type: "route",
+            },
+            "volume.restored" => SearchChange::Restore {
+                resource_type: "volume",
+            },
+            "instance.desired_state_changed" => {
+                if payload.desired_state.as_deref() == Some("stopped") {
+                    SearchChange::Remove {
+                        resource_type: "instance",
+                    }
+                } else {
+                    SearchChange::Skip
+                }
+            }
+            _ => SearchChange::Skip,
+        }
+    }
+}
+
+#[async_trait]
+impl ProjectionHandler for SearchProjection {
+    fn name(&self) -> &'static str {
+        "search"
+    }
+
+    fn event_types(&self) -> &'static [&'static str] {
+        &[
+            "app.created",
+            "app.updated",
+            "app.deleted",
+            "app.restored",
+            "env.created",
+            "env.updated",
+            "env.deleted",
+            "env.restored",
+            "release.created",
+            "route.created",
+            "route.deleted",
+            "route.restored",
+            "volume.created",
+            "volume.deleted",
+            "volume.restored",
+            "instance.allocated",
+            "instance.desired_state_changed",
+        ]
+    }
+
+    #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
+    async fn apply(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: SearchPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        match SearchChange::from_event(&event.event_type, &payload) {
+            SearchChange::Upsert {
+                resource_type,
+                name,
+            } => self.upsert(tx, event, &payload, resource_type, name).await,
+            SearchChange::Rename {
+                resource_type,
+                name,
+            } => self.rename(tx, event, resource_type, &name).await,
+            SearchChange::Remove { resource_type } => self.remove(tx, event, resource_type).await,
+            SearchChange::Restore { resource_type } => self.restore(tx, event, resource_type).await,
+            SearchChange::Skip => Ok(()),
+        }
+    }
+}
+
+impl SearchProjection {
+    async fn upsert(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+        payload: &SearchPayload,
+        resource_type: &'static str,
+        name: Option<String>,
+    ) -> ProjectionResult<()> {
+        let Some(org_id) = event.org_id.clone().or_else(|| payload.org_id.clone()) else {
+            debug!(
+                resource_type,
+                resource_id = %event.aggregate_id,
+                "Skipping search index entry without org_id"
+            );
+            return Ok(());
+        };
+        let app_id = match resource_type {
+            "app" => Some(event.aggregate_id.clone()),
+            _ => event.app_id.clone().or_else(|| payload.app_id.clone()),
+        };
+        let env_id = match resource_type {
+            "env" => Some(event.aggregate_id.clone()),
+            "app" | "release" | "volume" => None,
+            _ => event.env_id.clone().or_else(|| payload.env_id.clone()),
+        };
+
+        debug!(
+            resource_type,
+            resource_id = %event.aggregate_id,
+            org_id = %org_id,
+            "Indexing resource in search_index_view"
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO search_index_view (
+                resource_type, resource_id, org_id, app_id, env_id, name, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (resource_type, resource_id) DO UPDATE SET
+                org_id = EXCLUDED.org_id,
+                app_id = EXCLUDED.app_id,
+                env_id = EXCLUDED.env_id,
+                name = EXCLUDED.name,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(resource_type)
+        .bind(&event.aggregate_id)
+        .bind(&org_id)
+        .bind(app_id)
+        .bind(env_id)
+        .bind(name)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn rename(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+        resource_type: &'static str,
+        name: &str,
+    ) -> ProjectionResult<()> {
+        debug!(
+            resource_type,
+            resource_id = %event.aggregate_id,
+            "Renaming resource in search_index_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE search_index_view
+            SET name = $3,
+                updated_at = $4
+            WHERE resource_type = $1 AND resource_id = $2
+            "#,
+        )
+        .bind(resource_type)
+        .bind(&event.aggregate_id)
+        .bind(name)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+        resource_type: &'static str,
+    ) -> ProjectionResult<()> {
+        debug!(
+            resource_type,
+            resource_id = %event.aggregate_id,
+            "Removing resource from search_index_view"
+        );
+
+        // App and env deletions cascade to the resources scoped beneath them.
+        sqlx::query(
+            r#"
+            DELETE FROM search_index_view
+            WHERE (resource_type = $1 AND resource_id = $2)
+               OR ($1 = 'app' AND app_id = $2)
+               OR ($1 = 'env' AND env_id = $2)
+            "#,
+        )
+        .bind(resource_type)
+        .bind(&event.aggregate_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Names do not change while a resource is deleted, so the view row is
+    /// current whether or not its own projection has caught up yet.
+    async fn restore(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+        resource_type: &'static str,
+    ) -> ProjectionResult<()> {
+        let source = match resource_type {
+            "app" => {
+                r#"
+                SELECT 'app', app_id, org_id, app_id, NULL, name FROM apps_view WHERE app_id = $1
+                UNION ALL
+                SELECT 'release', release_id, org_id, app_id, NULL, image_ref
+                FROM releases_view WHERE app_id = $1
+                "#
+            }
+            "env" => {
+                "SELECT 'env', env_id, org_id, app_id, env_id, name FROM envs_view WHERE env_id = $1"
+            }
+            "route" => {
+                r#"
+                SELECT 'route', route_id, org_id, app_id, env_id, hostname
+                FROM routes_view WHERE route_id = $1
+                "#
+            }
+            "volume" => {
+                r#"
+                SELECT 'volume', volume_id, org_id, NULL, NULL, name
+                FROM volumes_view WHERE volume_id = $1
+                "#
+            }
+            _ => return Ok(()),
+        };
+
+        debug!(
+            resource_type,
+            resource_id = %event.aggregate_id,
+            "Re-indexing restored resource in search_index_view"
+        );
+
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO search_index_view (
+                resource_type, resource_id, org_id, app_id, env_id, name, updated_at
+            )
+            SELECT src.*, $2::timestamptz FROM ({source}) AS src
+            ON CONFLICT (resource_type, resource_id) DO UPDATE SET
+                org_id = EXCLUDED.org_id,
+                app_id = EXCLUDED.app_id,
+                env_id = EXCLUDED.env_id,
+                name = EXCLUDED.name,
+                updated_at = EXCLUDED.updated_at
+            "#
+        ))
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(json: &str) -> SearchPayload {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_search_projection_name() {
+        assert_eq!(SearchProjection.name(), "search");
+    }
+
+    #[test]
+    fn test_created_events_index_display_name() {
+        let change = SearchChange::from_event(
+            "route.created",
+            &payload(r#"{"hostname": "api.example.com"}"#),
+        );
+        assert_eq!(
+            change,
+            SearchChange::Upsert {
+                resource_type: "route",
+                name: Some("api.example.com".to_string()),
+            }
+        );
+
+        let change = SearchChange::from_event(
+            "release.created",
+            &payload(r#"{"image_ref": "registry.example.com/app:v1"}"#),
+        );
+        assert_eq!(
+            change,
+            SearchChange::Upsert {
+                resource_type: "release",
+                name: Some("registry.example.com/app:v1".to_string()),
+            }
+        );
+
+        let change =
+            SearchChange::from_event("instance.allocated", &payload(r#"{"process_type": "web"}"#));
+        assert_eq!(
+            change,
+            SearchChange::Upsert {
+                resource_type: "instance",
+                name: Some("web".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_updates_without_name_are_skipped() {
+        assert_eq!(
+            SearchChange::from_event("app.updated", &payload(r#"{"description": "x"}"#)),
+            SearchChange::Skip
+        );
+        assert_eq!(
+            SearchChange::from_event("env.updated", &payload(r#"{"name": "staging"}"#)),
+            SearchChange::Rename {
+                resource_type: "env",
+                name: "staging".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_stopped_instances_are_removed() {
+        assert_eq!(
+            SearchChange::from_event(
+                "instance.desired_state_changed",
+                &payload(r#"{"desired_state": "stopped"}"#)
+            ),
+            SearchChange::Remove {
+                resource_type: "instance"
+            }
+        );
+        assert_eq!(
+            SearchChange::from_event(
+                "instance.desired_state_changed",
+                &payload(r#"{"desired_state": "draining"}"#)
+            ),
+            SearchChange::Skip
+        );
+    }
+
+    #[test]
+    fn test_restored_events_reindex() {
+        assert_eq!(
+            SearchChange::from_event("app.restored", &payload(r#"{"app_id": "app_1"}"#)),
+            SearchChange::Restore {
+                resource_type: "app"
+            }
+        );
+        assert_eq!(
+            SearchChange::from_event("route.restored", &payload(r#"{"hostname": "a.example"}"#)),
+            SearchChange::Restore {
+                resource_type: "route"
+            }
+        );
+    }
+
+    #[test]
+    fn test_every_consumed_event_is_handled() {
+        for event_type in SearchProjection.event_types() {
+            let change = SearchChange::from_event(
+                event_type,
+                &payload(r#"{"name": "n", "desired_state": "stopped"}"#),
+            );
+            assert_ne!(change, SearchChange::Skip, "{event_type} is not handled");
+        }
+    }
+}
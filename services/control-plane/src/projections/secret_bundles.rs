@@ -1,7 +1,8 @@
 //! Secret bundle projection handler.
 //!
-//! Handles secret_bundle.created and secret_bundle.version_set events,
-//! updating the secret_bundles_view table.
+//! Handles secret_bundle.created, secret_bundle.version_set and
+//! secret_bundle.archived events, updating the secret_bundles_view table.
+//! env.restored unarchives the bundles that the env's teardown archived.
 
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -40,7 +41,12 @@ impl ProjectionHandler for SecretBundlesProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["secret_bundle.created", "secret_bundle.version_set"]
+        &[
+            "secret_bundle.created",
+            "secret_bundle.version_set",
+            "secret_bundle.archived",
+            "env.restored",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -52,6 +58,8 @@ impl ProjectionHandler for SecretBundlesProjection {
         match event.event_type.as_str() {
             "secret_bundle.created" => self.handle_created(tx, event).await,
             "secret_bundle.version_set" => self.handle_version_set(tx, event).await,
+            "secret_bundle.archived" => self.handle_archived(tx, event).await,
+            "env.restored" => self.handle_env_restored(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -203,6 +211,54 @@ impl SecretBundlesProjection {
 
         Ok(())
     }
+
+    async fn handle_archived(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(bundle_id = %event.aggregate_id, "Archiving secret bundle");
+
+        sqlx::query(
+            r#"
+            UPDATE secret_bundles_view
+            SET archived_at = COALESCE(archived_at, $2),
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE bundle_id = $1
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_env_restored(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        debug!(env_id = %event.aggregate_id, "Unarchiving secret bundles of restored env");
+
+        sqlx::query(
+            r#"
+            UPDATE secret_bundles_view
+            SET archived_at = NULL,
+                resource_version = resource_version + 1,
+                updated_at = $2
+            WHERE env_id = $1 AND archived_at IS NOT NULL
+            "#,
+        )
+        .bind(&event.aggregate_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,13 @@
 //! Snapshots projection handler.
 //!
-//! Handles snapshot.created and snapshot.status_changed events, updating snapshots_view.
+//! Handles snapshot.created, snapshot.status_changed, and snapshot.deleted
+//! events, updating snapshots_view. Scheduled snapshots are also recorded as
+//! the volume's last backup run in volumes_view.
 
 use async_trait::async_trait;
-use plfm_events::{JobStatus, SnapshotCreatedPayload, SnapshotStatusChangedPayload};
+use plfm_events::{
+    JobStatus, SnapshotCreatedPayload, SnapshotDeletedPayload, SnapshotStatusChangedPayload,
+};
 use tracing::{debug, instrument};
 
 use crate::db::EventRow;
@@ -20,7 +24,11 @@ impl ProjectionHandler for SnapshotsProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["snapshot.created", "snapshot.status_changed"]
+        &[
+            "snapshot.created",
+            "snapshot.status_changed",
+            "snapshot.deleted",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -32,6 +40,7 @@ impl ProjectionHandler for SnapshotsProjection {
         match event.event_type.as_str() {
             "snapshot.created" => self.handle_created(tx, event).await,
             "snapshot.status_changed" => self.handle_status_changed(tx, event).await,
+            "snapshot.deleted" => self.handle_deleted(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -78,14 +87,16 @@ impl SnapshotsProjection {
                 size_bytes,
                 note,
                 failed_reason,
+                scheduled,
                 resource_version,
                 created_at,
                 updated_at
             )
-            VALUES ($1, $2, $3, $4, NULL, $5, NULL, 1, $6, $6)
+            VALUES ($1, $2, $3, $4, NULL, $5, NULL, $7, 1, $6, $6)
             ON CONFLICT (snapshot_id) DO UPDATE SET
                 status = EXCLUDED.status,
                 note = EXCLUDED.note,
+                scheduled = EXCLUDED.scheduled,
                 updated_at = EXCLUDED.updated_at
             "#,
         )
@@ -95,9 +106,28 @@ impl SnapshotsProjection {
         .bind(status)
         .bind(payload.note.as_deref())
         .bind(event.occurred_at)
+        .bind(payload.scheduled)
         .execute(&mut **tx)
         .await?;
 
+        if payload.scheduled {
+            sqlx::query(
+                r#"
+                UPDATE volumes_view
+                SET backup_last_snapshot_id = $2,
+                    backup_last_run_at = $3,
+                    backup_last_status = $4
+                WHERE volume_id = $1
+                "#,
+            )
+            .bind(payload.volume_id.to_string())
+            .bind(payload.snapshot_id.to_string())
+            .bind(event.occurred_at)
+            .bind(status)
+            .execute(&mut **tx)
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -141,6 +171,49 @@ impl SnapshotsProjection {
         .execute(&mut **tx)
         .await?;
 
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET backup_last_status = $3
+            WHERE volume_id = $1 AND backup_last_snapshot_id = $2
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.snapshot_id.to_string())
+        .bind(status)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_deleted(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: SnapshotDeletedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            snapshot_id = %payload.snapshot_id,
+            volume_id = %payload.volume_id,
+            reason = %payload.reason,
+            "Removing snapshot from snapshots_view"
+        );
+
+        sqlx::query(
+            r#"
+            DELETE FROM snapshots_view
+            WHERE snapshot_id = $1 AND org_id = $2 AND volume_id = $3
+            "#,
+        )
+        .bind(payload.snapshot_id.to_string())
+        .bind(payload.org_id.to_string())
+        .bind(payload.volume_id.to_string())
+        .execute(&mut **tx)
+        .await?;
+
         Ok(())
     }
 }
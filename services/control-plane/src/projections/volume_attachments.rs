@@ -1,10 +1,11 @@
 //! Volume attachments projection handler.
 //!
-//! Handles volume_attachment.created and volume_attachment.deleted events,
-//! updating the volume_attachments_view table.
+//! Handles volume.attached and volume.detached events (and their legacy names
+//! volume_attachment.created and volume_attachment.deleted), updating the
+//! volume_attachments_view table.
 
 use async_trait::async_trait;
-use plfm_events::{VolumeAttachmentCreatedPayload, VolumeAttachmentDeletedPayload};
+use plfm_events::{VolumeAttachedPayload, VolumeAttachmentDeletedPayload};
 use tracing::{debug, instrument};
 
 use crate::db::EventRow;
@@ -21,7 +22,12 @@ impl ProjectionHandler for VolumeAttachmentsProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["volume_attachment.created", "volume_attachment.deleted"]
+        &[
+            "volume.attached",
+            "volume.detached",
+            "volume_attachment.created",
+            "volume_attachment.deleted",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -31,8 +37,8 @@ impl ProjectionHandler for VolumeAttachmentsProjection {
         event: &EventRow,
     ) -> ProjectionResult<()> {
         match event.event_type.as_str() {
-            "volume_attachment.created" => self.handle_created(tx, event).await,
-            "volume_attachment.deleted" => self.handle_deleted(tx, event).await,
+            "volume.attached" | "volume_attachment.created" => self.handle_created(tx, event).await,
+            "volume.detached" | "volume_attachment.deleted" => self.handle_deleted(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -47,7 +53,8 @@ impl VolumeAttachmentsProjection {
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         event: &EventRow,
     ) -> ProjectionResult<()> {
-        let payload: VolumeAttachmentCreatedPayload = serde_json::from_value(event.payload.clone())
+        // Both event names share the payload shape.
+        let payload: VolumeAttachedPayload = serde_json::from_value(event.payload.clone())
             .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
 
         debug!(
@@ -109,6 +116,7 @@ impl VolumeAttachmentsProjection {
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         event: &EventRow,
     ) -> ProjectionResult<()> {
+        // The legacy payload's fields are a subset of volume.detached's.
         let payload: VolumeAttachmentDeletedPayload = serde_json::from_value(event.payload.clone())
             .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
 
@@ -1,9 +1,17 @@
 //! Volumes projection handler.
 //!
-//! Handles volume.created and volume.deleted events, updating the volumes_view table.
+//! Handles volume.created, volume.labels_updated, volume.deleted,
+//! volume.restored, volume.purged, volume.backup_policy_set, and the volume.resized / resize_completed /
+//! resize_failed events, updating the volumes_view table. instance.allocated sets the home node of volumes
+//! attached to the instance's process type that do not have one yet.
 
 use async_trait::async_trait;
-use plfm_events::{VolumeCreatedPayload, VolumeDeletedPayload};
+use plfm_events::{
+    VolumeBackupPolicySetPayload, VolumeCreatedPayload, VolumeDeletedPayload,
+    VolumeLabelsUpdatedPayload, VolumePurgedPayload, VolumeResizeCompletedPayload,
+    VolumeResizeFailedPayload, VolumeResizedPayload, VolumeRestoredPayload,
+};
+use serde::Deserialize;
 use tracing::{debug, instrument};
 
 use crate::db::EventRow;
@@ -13,6 +21,13 @@ use super::{ProjectionError, ProjectionHandler, ProjectionResult};
 /// Projection handler for volumes.
 pub struct VolumesProjection;
 
+/// The fields of instance.allocated this projection needs.
+#[derive(Debug, Deserialize)]
+struct InstanceAllocatedPayload {
+    node_id: String,
+    process_type: String,
+}
+
 #[async_trait]
 impl ProjectionHandler for VolumesProjection {
     fn name(&self) -> &'static str {
@@ -20,7 +35,18 @@ impl ProjectionHandler for VolumesProjection {
     }
 
     fn event_types(&self) -> &'static [&'static str] {
-        &["volume.created", "volume.deleted"]
+        &[
+            "volume.created",
+            "volume.labels_updated",
+            "volume.deleted",
+            "volume.restored",
+            "volume.purged",
+            "volume.resized",
+            "volume.resize_completed",
+            "volume.resize_failed",
+            "volume.backup_policy_set",
+            "instance.allocated",
+        ]
     }
 
     #[instrument(skip(self, tx, event), fields(event_id = event.event_id, event_type = %event.event_type))]
@@ -31,7 +57,15 @@ impl ProjectionHandler for VolumesProjection {
     ) -> ProjectionResult<()> {
         match event.event_type.as_str() {
             "volume.created" => self.handle_created(tx, event).await,
+            "volume.labels_updated" => self.handle_labels_updated(tx, event).await,
             "volume.deleted" => self.handle_deleted(tx, event).await,
+            "volume.restored" => self.handle_restored(tx, event).await,
+            "volume.purged" => self.handle_purged(tx, event).await,
+            "volume.resized" => self.handle_resized(tx, event).await,
+            "volume.resize_completed" => self.handle_resize_completed(tx, event).await,
+            "volume.resize_failed" => self.handle_resize_failed(tx, event).await,
+            "volume.backup_policy_set" => self.handle_backup_policy_set(tx, event).await,
+            "instance.allocated" => self.handle_instance_allocated(tx, event).await,
             _ => {
                 debug!(event_type = %event.event_type, "Ignoring unknown event type");
                 Ok(())
@@ -95,6 +129,38 @@ impl VolumesProjection {
         Ok(())
     }
 
+    async fn handle_labels_updated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumeLabelsUpdatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            label_count = payload.labels.len(),
+            "Updating labels in volumes_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET labels = $2,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE volume_id = $1
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(serde_json::json!(payload.labels))
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     async fn handle_deleted(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -113,6 +179,7 @@ impl VolumesProjection {
             r#"
             UPDATE volumes_view
             SET is_deleted = true,
+                deleted_at = $3,
                 resource_version = resource_version + 1,
                 updated_at = $3
             WHERE volume_id = $1 AND org_id = $2
@@ -126,4 +193,260 @@ impl VolumesProjection {
 
         Ok(())
     }
+
+    async fn handle_restored(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumeRestoredPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            org_id = %payload.org_id,
+            "Restoring volume in volumes_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET is_deleted = false,
+                deleted_at = NULL,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE volume_id = $1 AND org_id = $2 AND purged_at IS NULL
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.org_id.to_string())
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_purged(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumePurgedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            org_id = %payload.org_id,
+            "Purging volume in volumes_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET purged_at = $3,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE volume_id = $1 AND org_id = $2 AND is_deleted
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.org_id.to_string())
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_resized(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumeResizedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            previous_size_bytes = payload.previous_size_bytes,
+            size_bytes = payload.size_bytes,
+            "Growing volume in volumes_view"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET size_bytes = GREATEST(size_bytes, $2),
+                resize_status = 'pending',
+                resize_error = NULL,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE volume_id = $1
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.size_bytes)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_resize_completed(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumeResizeCompletedPayload =
+            serde_json::from_value(event.payload.clone())
+                .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            node_id = %payload.node_id,
+            size_bytes = payload.size_bytes,
+            "Marking volume resize completed"
+        );
+
+        // A report for an older, smaller request does not settle a newer one.
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET resize_status = 'completed',
+                resize_error = NULL,
+                resource_version = resource_version + 1,
+                updated_at = $3
+            WHERE volume_id = $1 AND size_bytes <= $2
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.size_bytes)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_resize_failed(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumeResizeFailedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            node_id = %payload.node_id,
+            size_bytes = payload.size_bytes,
+            reason = %payload.reason,
+            "Marking volume resize failed"
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET resize_status = 'failed',
+                resize_error = $3,
+                resource_version = resource_version + 1,
+                updated_at = $4
+            WHERE volume_id = $1 AND size_bytes = $2
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.size_bytes)
+        .bind(&payload.message)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_backup_policy_set(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: VolumeBackupPolicySetPayload =
+            serde_json::from_value(event.payload.clone())
+                .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+
+        debug!(
+            volume_id = %payload.volume_id,
+            schedule = ?payload.schedule,
+            "Setting volume backup policy"
+        );
+
+        // The policy timestamp anchors the first scheduled run.
+        sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET backup_schedule = $2,
+                backup_retain_count = $3,
+                backup_retain_max_age_secs = $4,
+                backup_policy_updated_at = $5,
+                resource_version = resource_version + 1,
+                updated_at = $5
+            WHERE volume_id = $1
+            "#,
+        )
+        .bind(payload.volume_id.to_string())
+        .bind(payload.schedule.as_deref())
+        .bind(payload.retain_count)
+        .bind(payload.retain_max_age_secs)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_instance_allocated(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        event: &EventRow,
+    ) -> ProjectionResult<()> {
+        let payload: InstanceAllocatedPayload = serde_json::from_value(event.payload.clone())
+            .map_err(|e| ProjectionError::InvalidPayload(e.to_string()))?;
+        let Some(env_id) = event.env_id.as_deref() else {
+            return Ok(());
+        };
+
+        // Only the first placement decides: the data stays on that node.
+        let result = sqlx::query(
+            r#"
+            UPDATE volumes_view
+            SET home_node_id = $3,
+                updated_at = $4
+            WHERE home_node_id IS NULL
+              AND NOT is_deleted
+              AND volume_id IN (
+                  SELECT volume_id FROM volume_attachments_view
+                  WHERE env_id = $1 AND process_type = $2 AND NOT is_deleted
+              )
+            "#,
+        )
+        .bind(env_id)
+        .bind(&payload.process_type)
+        .bind(&payload.node_id)
+        .bind(event.occurred_at)
+        .execute(&mut **tx)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            debug!(
+                env_id = %env_id,
+                process_type = %payload.process_type,
+                node_id = %payload.node_id,
+                volumes = result.rows_affected(),
+                "Set home node of attached volumes"
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -5,19 +5,35 @@
 //! 1. Query events after the minimum checkpoint across all projections
 //! 2. For each event, dispatch to the appropriate handler
 //! 3. Update checkpoints atomically with view updates
-//! 4. Sleep if no new events, then repeat
+//! 4. Wait for a NOTIFY from an append (or the poll interval) if no new
+//!    events, then repeat
 //!
 //! The worker handles restarts gracefully by resuming from persisted checkpoints.
-
-use std::collections::HashMap;
-use std::time::Duration;
-
+//!
+//! Several control-plane replicas can run the worker at once. Each projection
+//! is owned through a worker lease (`projection:<name>`): a replica only
+//! applies events to projections it holds a lease for, renews its leases
+//! every third of the TTL, and takes over leases another replica let lapse.
+//! The checkpoint update checks the lease inside the apply transaction, so a
+//! replica that lost its lease mid-batch rolls back instead of applying twice.
+//! Projections an operator has paused are skipped (their checkpoints stay put)
+//! until resumed. A resumed projection reloads its persisted checkpoint, so
+//! one rewound while paused (`POST /v1/_admin/projections/{name}/rebuild`)
+//! replays from the start of the log.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sqlx::postgres::PgListener;
 use sqlx::PgPool;
 use tokio::sync::watch;
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
 use tracing::{debug, error, info, instrument, warn};
 
-use crate::db::{EventStore, ProjectionStore};
+use crate::db::{
+    projection_lease_name, EventStore, LeaseStore, ProjectionStore, EVENTS_NOTIFY_CHANNEL,
+};
 
 use super::{ProjectionError, ProjectionRegistry, ProjectionResult};
 
@@ -30,8 +46,24 @@ pub struct WorkerConfig {
     /// How long to sleep when no events are available.
     pub poll_interval: Duration,
 
+    /// Whether to LISTEN for append notifications instead of relying on
+    /// polling alone.
+    pub listen: bool,
+
+    /// Fallback poll interval while a listener is connected. Covers missed
+    /// notifications (e.g. across a listener reconnect).
+    pub listen_poll_interval: Duration,
+
+    /// How long to wait after a notification before fetching, so a burst of
+    /// appends is applied as one batch.
+    pub notify_debounce: Duration,
+
     /// How often to log progress (in events processed).
     pub log_interval: u64,
+
+    /// How long a projection lease lasts without renewal. A replica that dies
+    /// gives up its projections after at most this long.
+    pub lease_ttl: Duration,
 }
 
 impl Default for WorkerConfig {
@@ -39,8 +71,85 @@ impl Default for WorkerConfig {
         Self {
             batch_size: 100,
             poll_interval: Duration::from_millis(100),
+            listen: std::env::var("PLFM_PROJECTION_LISTEN")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            listen_poll_interval: Duration::from_secs(1),
+            notify_debounce: Duration::from_millis(5),
             log_interval: 1000,
+            lease_ttl: std::env::var("PLFM_PROJECTION_LEASE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(Duration::from_secs(15)),
+        }
+    }
+}
+
+/// Window over which [`ProjectionMetrics`] averages the apply rate.
+const RATE_WINDOW_SECS: u64 = 60;
+
+/// Per-projection apply counts for this process, bucketed by second.
+#[derive(Debug)]
+pub struct ProjectionMetrics {
+    applied: Mutex<BTreeMap<&'static str, VecDeque<(u64, u64)>>>,
+}
+
+/// Apply rates for this process (exposed via `GET /v1/_admin/projections`).
+pub static METRICS: ProjectionMetrics = ProjectionMetrics::new();
+
+impl ProjectionMetrics {
+    const fn new() -> Self {
+        Self {
+            applied: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn now_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
+    /// Count one event applied by `projection_name`.
+    pub fn record_applied(&self, projection_name: &'static str) {
+        self.record_at(projection_name, Self::now_secs());
+    }
+
+    /// Events per second applied by `projection_name` over the last minute.
+    pub fn events_per_second(&self, projection_name: &str) -> f64 {
+        self.rate_at(projection_name, Self::now_secs())
+    }
+
+    fn record_at(&self, projection_name: &'static str, now: u64) {
+        let mut applied = self.applied.lock().unwrap_or_else(|e| e.into_inner());
+        let buckets = applied.entry(projection_name).or_default();
+        match buckets.back_mut() {
+            Some((second, count)) if *second == now => *count += 1,
+            _ => buckets.push_back((now, 1)),
         }
+        while buckets
+            .front()
+            .is_some_and(|(second, _)| *second + RATE_WINDOW_SECS <= now)
+        {
+            buckets.pop_front();
+        }
+    }
+
+    fn rate_at(&self, projection_name: &str, now: u64) -> f64 {
+        let applied = self.applied.lock().unwrap_or_else(|e| e.into_inner());
+        let total: u64 = applied
+            .get(projection_name)
+            .map(|buckets| {
+                buckets
+                    .iter()
+                    .filter(|(second, _)| *second + RATE_WINDOW_SECS > now)
+                    .map(|(_, count)| count)
+                    .sum()
+            })
+            .unwrap_or(0);
+        total as f64 / RATE_WINDOW_SECS as f64
     }
 }
 
@@ -49,6 +158,9 @@ pub struct ProjectionWorker {
     pool: PgPool,
     event_store: EventStore,
     projection_store: ProjectionStore,
+    lease_store: LeaseStore,
+    /// Identifies this replica as a lease holder.
+    holder: String,
     registry: ProjectionRegistry,
     config: WorkerConfig,
 }
@@ -59,6 +171,8 @@ impl ProjectionWorker {
         Self {
             event_store: EventStore::new(pool.clone()),
             projection_store: ProjectionStore::new(pool.clone()),
+            lease_store: LeaseStore::new(pool.clone()),
+            holder: crate::leader::replica_id().to_string(),
             pool,
             registry: ProjectionRegistry::new(),
             config,
@@ -78,13 +192,19 @@ impl ProjectionWorker {
 
         // Load initial checkpoints
         let mut checkpoints = self.load_checkpoints().await?;
-        let min_checkpoint = self.min_checkpoint(&checkpoints);
+        let min_checkpoint = checkpoints.values().copied().min().unwrap_or(0);
         info!(
             min_checkpoint = min_checkpoint,
             projections = checkpoints.len(),
             "Loaded projection checkpoints"
         );
 
+        let mut listener = self.listen().await;
+
+        let mut owned: HashSet<String> = HashSet::new();
+        let mut paused: HashSet<String> = HashSet::new();
+        let mut next_renewal = Instant::now();
+
         let mut events_processed: u64 = 0;
         let mut last_log_count: u64 = 0;
 
@@ -98,25 +218,49 @@ impl ProjectionWorker {
                 break;
             }
 
-            // Calculate minimum checkpoint to query from
-            let min_checkpoint = self.min_checkpoint(&checkpoints);
+            if Instant::now() >= next_renewal {
+                self.renew_leases(&mut owned, &mut checkpoints).await;
+                next_renewal = Instant::now() + self.config.lease_ttl / 3;
+            }
 
-            // Fetch next batch of events
-            let events = self
-                .event_store
-                .query_after_cursor(min_checkpoint, self.config.batch_size)
-                .await?;
+            let mut now_paused: HashSet<String> = self
+                .projection_store
+                .paused_projections()
+                .await?
+                .into_iter()
+                .collect();
+            self.reload_resumed(&paused, &mut now_paused, &mut checkpoints)
+                .await;
+            paused = now_paused;
+
+            // Projections this replica must not touch: paused ones, and ones
+            // another replica owns.
+            let mut skipped = paused.clone();
+            for name in self.registry.projection_names() {
+                if !owned.contains(name) {
+                    skipped.insert(name.to_string());
+                }
+            }
+
+            // Calculate minimum checkpoint to query from. Skipped projections
+            // are left out so they don't hold the others back.
+            let min_checkpoint = self.min_checkpoint(&checkpoints, &skipped);
+
+            // Fetch next batch of events (nothing to do if every projection is skipped)
+            let events = match min_checkpoint {
+                Some(min_checkpoint) => {
+                    self.event_store
+                        .query_after_cursor(min_checkpoint, self.config.batch_size)
+                        .await?
+                }
+                None => Vec::new(),
+            };
 
             if events.is_empty() {
                 // No events, wait and retry
-                tokio::select! {
-                    _ = shutdown.changed() => {
-                        if *shutdown.borrow() {
-                            info!("Shutdown signal received during poll wait");
-                            break;
-                        }
-                    }
-                    _ = sleep(self.config.poll_interval) => {}
+                if self.wait_for_events(&mut listener, &mut shutdown).await {
+                    info!("Shutdown signal received during poll wait");
+                    break;
                 }
                 continue;
             }
@@ -142,12 +286,17 @@ impl ProjectionWorker {
                         let checkpoint = checkpoints.get(h.name()).copied().unwrap_or(0);
                         checkpoint < event.event_id
                             && h.event_types().contains(&event.event_type.as_str())
+                            && !skipped.contains(h.name())
                     })
                     .collect();
 
                 if projections_needing_event.is_empty() {
                     // No projection needs this event, update all checkpoints past it
+                    // (except skipped ones, which must still see it later)
                     for handler in self.registry.handlers() {
+                        if skipped.contains(handler.name()) {
+                            continue;
+                        }
                         let checkpoint = checkpoints.entry(handler.name().to_string()).or_insert(0);
                         if *checkpoint < event.event_id {
                             *checkpoint = event.event_id;
@@ -158,6 +307,8 @@ impl ProjectionWorker {
 
                 // Process in a transaction
                 let mut tx = self.pool.begin().await?;
+                let mut applied = Vec::new();
+                let mut lost_lease = None;
 
                 for handler in projections_needing_event {
                     let current_checkpoint = checkpoints.get(handler.name()).copied().unwrap_or(0);
@@ -168,23 +319,29 @@ impl ProjectionWorker {
                     match handler.apply(&mut tx, &event).await {
                         Ok(()) => {
                             // Update checkpoint
-                            if let Err(err) = ProjectionStore::update_checkpoint_in_tx(
+                            match ProjectionStore::update_checkpoint_in_tx(
                                 &mut tx,
                                 handler.name(),
                                 event.event_id,
+                                Some(&self.holder),
                             )
                             .await
                             {
-                                error!(
-                                    error = %err,
-                                    event_id = event.event_id,
-                                    projection = handler.name(),
-                                    "Failed to update projection checkpoint"
-                                );
-                                return Err(ProjectionError::Database(err));
+                                Ok(true) => applied.push(handler.name()),
+                                Ok(false) => {
+                                    lost_lease = Some(handler.name());
+                                    break;
+                                }
+                                Err(err) => {
+                                    error!(
+                                        error = %err,
+                                        event_id = event.event_id,
+                                        projection = handler.name(),
+                                        "Failed to update projection checkpoint"
+                                    );
+                                    return Err(ProjectionError::Database(err));
+                                }
                             }
-
-                            checkpoints.insert(handler.name().to_string(), event.event_id);
                         }
                         Err(e) => {
                             error!(
@@ -194,14 +351,43 @@ impl ProjectionWorker {
                                 projection = handler.name(),
                                 "Failed to apply event, rolling back"
                             );
-                            // Rollback happens automatically when tx is dropped
+                            drop(tx);
+                            if let Err(err) = self
+                                .projection_store
+                                .record_error(handler.name(), event.event_id, &e.to_string())
+                                .await
+                            {
+                                warn!(
+                                    error = %err,
+                                    projection = handler.name(),
+                                    "Failed to record projection error"
+                                );
+                            }
                             return Err(e);
                         }
                     }
                 }
 
+                if let Some(name) = lost_lease {
+                    // Another replica owns this projection now; roll back the
+                    // whole event and refetch with the new ownership.
+                    warn!(
+                        projection = name,
+                        event_id = event.event_id,
+                        "Projection lease lost, rolling back"
+                    );
+                    drop(tx);
+                    owned.remove(name);
+                    next_renewal = Instant::now();
+                    break;
+                }
+
                 tx.commit().await?;
                 events_processed += 1;
+                for name in applied {
+                    checkpoints.insert(name.to_string(), event.event_id);
+                    METRICS.record_applied(name);
+                }
 
                 // Log progress periodically
                 if events_processed - last_log_count >= self.config.log_interval {
@@ -215,6 +401,10 @@ impl ProjectionWorker {
             }
         }
 
+        if let Err(e) = self.lease_store.release_all(&self.holder).await {
+            warn!(error = %e, "Failed to release projection leases");
+        }
+
         info!(
             events_processed = events_processed,
             "Projection worker stopped"
@@ -222,6 +412,162 @@ impl ProjectionWorker {
         Ok(())
     }
 
+    /// Acquire or renew leases on every projection. Projections newly taken
+    /// over resume from their persisted checkpoint, since another replica
+    /// may have advanced them.
+    async fn renew_leases(
+        &self,
+        owned: &mut HashSet<String>,
+        checkpoints: &mut HashMap<String, i64>,
+    ) {
+        let names: Vec<String> = self
+            .registry
+            .projection_names()
+            .into_iter()
+            .map(projection_lease_name)
+            .collect();
+
+        let held = match self
+            .lease_store
+            .acquire(&names, &self.holder, self.config.lease_ttl)
+            .await
+        {
+            Ok(held) => held,
+            Err(e) => {
+                // Without a renewal the leases may lapse; stop applying and
+                // retry on the next pass. The checkpoint fence covers any
+                // transaction already in flight.
+                warn!(error = %e, "Failed to renew projection leases");
+                owned.clear();
+                return;
+            }
+        };
+
+        let mut held: HashSet<String> = held
+            .iter()
+            .filter_map(|lease| lease.strip_prefix(crate::db::PROJECTION_LEASE_PREFIX))
+            .map(str::to_string)
+            .collect();
+
+        let mut unloaded = Vec::new();
+        for name in held.difference(owned) {
+            if let Err(e) = self.projection_store.ensure_checkpoint(name).await {
+                warn!(error = %e, projection = %name, "Failed to ensure projection checkpoint");
+            }
+            let checkpoint = match self.projection_store.get_checkpoint(name).await {
+                Ok(cp) => cp.last_applied_event_id,
+                Err(e) => {
+                    warn!(error = %e, projection = %name, "Failed to load projection checkpoint");
+                    unloaded.push(name.clone());
+                    continue;
+                }
+            };
+            checkpoints.insert(name.clone(), checkpoint);
+            info!(projection = %name, checkpoint, holder = %self.holder, "Acquired projection lease");
+        }
+        // Keep the lease but don't apply until the checkpoint is known.
+        for name in unloaded {
+            held.remove(&name);
+        }
+        for name in owned.difference(&held) {
+            warn!(projection = %name, holder = %self.holder, "Projection lease lost");
+        }
+
+        *owned = held;
+    }
+
+    /// Subscribe to append notifications. Returns `None` (polling only) when
+    /// disabled or the listener cannot connect.
+    async fn listen(&self) -> Option<PgListener> {
+        if !self.config.listen {
+            return None;
+        }
+
+        let mut listener = match PgListener::connect_with(&self.pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!(error = %e, "Failed to connect event listener, falling back to polling");
+                return None;
+            }
+        };
+        if let Err(e) = listener.listen(EVENTS_NOTIFY_CHANNEL).await {
+            warn!(error = %e, "Failed to LISTEN for events, falling back to polling");
+            return None;
+        }
+
+        info!(
+            channel = EVENTS_NOTIFY_CHANNEL,
+            "Listening for event notifications"
+        );
+        Some(listener)
+    }
+
+    /// Wait until new events may be available: a notification, the poll
+    /// interval, or shutdown. Returns `true` on shutdown.
+    async fn wait_for_events(
+        &self,
+        listener: &mut Option<PgListener>,
+        shutdown: &mut watch::Receiver<bool>,
+    ) -> bool {
+        let Some(listener) = listener.as_mut() else {
+            return tokio::select! {
+                _ = shutdown.changed() => *shutdown.borrow(),
+                _ = sleep(self.config.poll_interval) => false,
+            };
+        };
+
+        tokio::select! {
+            _ = shutdown.changed() => *shutdown.borrow(),
+            _ = sleep(self.config.listen_poll_interval) => false,
+            notification = listener.recv() => {
+                match notification {
+                    Ok(_) => {
+                        // Let a burst of appends land, then drain the
+                        // notifications it produced so they cost one fetch.
+                        sleep(self.config.notify_debounce).await;
+                        while listener.next_buffered().is_some() {}
+                    }
+                    Err(e) => {
+                        // The listener reconnects on the next recv; poll
+                        // in the meantime.
+                        warn!(error = %e, "Event listener error");
+                        sleep(self.config.poll_interval).await;
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    /// Reload the persisted checkpoint of projections resumed since the last
+    /// pass; it may have been rewound while they were paused. A projection
+    /// whose checkpoint cannot be read is kept in `now_paused` so it is
+    /// skipped and retried on the next pass.
+    async fn reload_resumed(
+        &self,
+        paused: &HashSet<String>,
+        now_paused: &mut HashSet<String>,
+        checkpoints: &mut HashMap<String, i64>,
+    ) {
+        let resumed: Vec<String> = paused.difference(now_paused).cloned().collect();
+        for name in resumed {
+            match self.projection_store.get_checkpoint(name).await {
+                Ok(cp) => {
+                    checkpoints.insert(name.clone(), cp.last_applied_event_id);
+                    info!(
+                        projection = %name,
+                        checkpoint = cp.last_applied_event_id,
+                        "Projection resumed"
+                    );
+                }
+                Err(e) => {
+                    warn!(error = %e, projection = %name, "Failed to reload resumed projection checkpoint");
+                    now_paused.insert(name);
+                }
+            }
+        }
+    }
+
     /// Load checkpoints for all projections.
     async fn load_checkpoints(&self) -> ProjectionResult<HashMap<String, i64>> {
         let mut checkpoints = HashMap::new();
@@ -248,9 +594,20 @@ impl ProjectionWorker {
         Ok(checkpoints)
     }
 
-    /// Get the minimum checkpoint across all projections.
-    fn min_checkpoint(&self, checkpoints: &HashMap<String, i64>) -> i64 {
-        checkpoints.values().copied().min().unwrap_or(0)
+    /// Get the minimum checkpoint across all unpaused projections, or `None`
+    /// when every projection is paused.
+    fn min_checkpoint(
+        &self,
+        checkpoints: &HashMap<String, i64>,
+        paused: &HashSet<String>,
+    ) -> Option<i64> {
+        let active = self
+            .registry
+            .projection_names()
+            .into_iter()
+            .filter(|name| !paused.contains(*name))
+            .map(|name| checkpoints.get(name).copied().unwrap_or(0));
+        active.min()
     }
 }
 
@@ -258,10 +615,25 @@ impl ProjectionWorker {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_metrics_rate_uses_last_minute() {
+        let metrics = ProjectionMetrics::new();
+        for _ in 0..30 {
+            metrics.record_at("apps", 1_000);
+        }
+        metrics.record_at("apps", 1_030);
+        assert_eq!(metrics.rate_at("apps", 1_030), 31.0 / 60.0);
+        assert_eq!(metrics.rate_at("apps", 1_060), 1.0 / 60.0);
+        assert_eq!(metrics.rate_at("apps", 1_100), 0.0);
+        assert_eq!(metrics.rate_at("envs", 1_030), 0.0);
+    }
+
     #[test]
     fn test_worker_config_defaults() {
         let config = WorkerConfig::default();
         assert_eq!(config.batch_size, 100);
         assert_eq!(config.poll_interval, Duration::from_millis(100));
+        assert!(config.listen_poll_interval > config.poll_interval);
+        assert!(config.notify_debounce < config.poll_interval);
     }
 }
@@ -0,0 +1,272 @@
+//! Readiness checks.
+//!
+//! `/readyz` runs every [`Check`] and reports each result; the server runs
+//! [`Check::STARTUP`] before it starts workers and binds its listeners, so a
+//! replica never takes traffic against an unreachable or unmigrated
+//! database.
+//!
+//! Projection lag is not a startup check: projections catch up while the
+//! replica serves, and a stuck projection should not stop the API from
+//! coming up. It only takes the replica out of `/readyz`.
+//!
+//! Configuration:
+//! - `PLFM_READY_MAX_PROJECTION_LAG`: pending events a projection may have
+//!   before the replica is unready (default 1000)
+//! - `PLFM_READY_STARTUP_TIMEOUT_SECS`: how long startup waits for the
+//!   startup checks before giving up (default 120)
+
+use std::time::{Duration, Instant};
+
+use futures_util::future::join_all;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::db::schema::SchemaStatus;
+use crate::db::Database;
+use crate::projections::ProjectionRegistry;
+
+/// Longest a single check may take before it counts as failed.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Delay between startup check attempts.
+const STARTUP_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Readiness thresholds.
+#[derive(Debug, Clone)]
+pub struct ReadinessConfig {
+    pub max_projection_lag: i64,
+    pub startup_timeout: Duration,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            max_projection_lag: 1000,
+            startup_timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+impl ReadinessConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_projection_lag: std::env::var("PLFM_READY_MAX_PROJECTION_LAG")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_projection_lag),
+            startup_timeout: std::env::var("PLFM_READY_STARTUP_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.startup_timeout),
+        }
+    }
+}
+
+/// A dependency the control plane needs to serve traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Postgres answers queries.
+    Database,
+    /// The schema is compatible with this binary (see [`crate::db::schema`]).
+    Migrations,
+    /// The event log accepts writes (not a read-only replica or session).
+    EventStore,
+    /// No running projection is too far behind the event log.
+    ProjectionLag,
+}
+
+impl Check {
+    pub const ALL: &'static [Check] = &[
+        Check::Database,
+        Check::Migrations,
+        Check::EventStore,
+        Check::ProjectionLag,
+    ];
+
+    /// Checks that gate startup.
+    pub const STARTUP: &'static [Check] = &[Check::Database, Check::Migrations, Check::EventStore];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::Database => "database",
+            Check::Migrations => "migrations",
+            Check::EventStore => "event_store",
+            Check::ProjectionLag => "projection_lag",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Fail,
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub duration_ms: u64,
+    /// Why the check failed, or a note on a passing check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl CheckResult {
+    pub fn is_ok(&self) -> bool {
+        self.status == CheckStatus::Ok
+    }
+}
+
+/// Run `checks` concurrently, each bounded by [`CHECK_TIMEOUT`].
+pub async fn run_checks(
+    db: &Database,
+    config: &ReadinessConfig,
+    checks: &[Check],
+) -> Vec<CheckResult> {
+    join_all(checks.iter().map(|&check| async move {
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(CHECK_TIMEOUT, run_check(db, config, check))
+            .await
+            .unwrap_or_else(|_| Err(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())));
+        let (status, message) = match outcome {
+            Ok(note) => (CheckStatus::Ok, note),
+            Err(message) => (CheckStatus::Fail, Some(message)),
+        };
+        CheckResult {
+            name: check.name().to_string(),
+            status,
+            duration_ms: started.elapsed().as_millis() as u64,
+            message,
+        }
+    }))
+    .await
+}
+
+/// Run the startup checks until they pass or `config.startup_timeout`
+/// elapses; on timeout, returns the checks still failing.
+pub async fn wait_until_ready(
+    db: &Database,
+    config: &ReadinessConfig,
+) -> Result<(), Vec<CheckResult>> {
+    let deadline = Instant::now() + config.startup_timeout;
+    loop {
+        let failing: Vec<CheckResult> = run_checks(db, config, Check::STARTUP)
+            .await
+            .into_iter()
+            .filter(|result| !result.is_ok())
+            .collect();
+        if failing.is_empty() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(failing);
+        }
+        for result in &failing {
+            warn!(
+                check = %result.name,
+                message = result.message.as_deref().unwrap_or_default(),
+                "Waiting for readiness"
+            );
+        }
+        tokio::time::sleep(STARTUP_RETRY_INTERVAL).await;
+    }
+}
+
+/// `Ok` with an optional note, or `Err` with why the check failed.
+type Outcome = Result<Option<String>, String>;
+
+async fn run_check(db: &Database, config: &ReadinessConfig, check: Check) -> Outcome {
+    match check {
+        Check::Database => db
+            .health_check()
+            .await
+            .map(|()| None)
+            .map_err(|e| e.to_string()),
+        Check::Migrations => {
+            let status = db.schema_status().await.map_err(|e| e.to_string())?;
+            match status {
+                SchemaStatus::Current => Ok(None),
+                status if status.is_compatible() => Ok(Some(status.to_string())),
+                status => Err(status.to_string()),
+            }
+        }
+        Check::EventStore => {
+            let (in_recovery, read_only, can_insert): (bool, bool, bool) = sqlx::query_as(
+                r#"
+                SELECT pg_is_in_recovery(),
+                       current_setting('transaction_read_only') = 'on',
+                       has_table_privilege('events', 'INSERT')
+                "#,
+            )
+            .fetch_one(db.pool())
+            .await
+            .map_err(|e| e.to_string())?;
+            if in_recovery {
+                Err("connected to a read-only replica (in recovery)".to_string())
+            } else if read_only {
+                Err("database sessions are read-only".to_string())
+            } else if !can_insert {
+                Err("missing INSERT privilege on events".to_string())
+            } else {
+                Ok(None)
+            }
+        }
+        Check::ProjectionLag => {
+            let store = db.projection_store();
+            let paused = store
+                .paused_projections()
+                .await
+                .map_err(|e| e.to_string())?;
+            let pending = store
+                .pending_counts(&ProjectionRegistry::new().coverage())
+                .await
+                .map_err(|e| e.to_string())?;
+            projection_lag_outcome(&pending, &paused, config.max_projection_lag)
+        }
+    }
+}
+
+/// Fail when a running projection has more than `max_lag` pending events.
+fn projection_lag_outcome(pending: &[(String, i64)], paused: &[String], max_lag: i64) -> Outcome {
+    let worst = pending
+        .iter()
+        .filter(|(name, _)| !paused.contains(name))
+        .max_by_key(|(_, count)| *count);
+
+    match worst {
+        Some((name, count)) if *count > max_lag => Err(format!(
+            "projection '{name}' is {count} events behind (max {max_lag})"
+        )),
+        _ if !paused.is_empty() => Ok(Some(format!("paused: {}", paused.join(", ")))),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_lag_outcome() {
+        let pending = vec![("apps".to_string(), 10), ("instances".to_string(), 5000)];
+
+        assert_eq!(
+            projection_lag_outcome(&pending, &[], 1000),
+            Err("projection 'instances' is 5000 events behind (max 1000)".to_string())
+        );
+        assert_eq!(projection_lag_outcome(&pending, &[], 10_000), Ok(None));
+
+        // Paused projections are expected to fall behind.
+        assert_eq!(
+            projection_lag_outcome(&pending, &["instances".to_string()], 1000),
+            Ok(Some("paused: instances".to_string()))
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Route access policies.
+//!
+//! A route may carry allow/deny lists of client CIDRs and TLS client
+//! fingerprints. The edge enforces them per connection, before a backend is
+//! picked: deny entries win, and a non-empty allow list admits only its
+//! entries. This is basic L4 abuse mitigation, not a WAF.
+//!
+//! Fingerprints are either JA3 hashes (32 hex digits) or JA4 strings
+//! (`t13d1516h2_8daaf6152771_e5627efa2ab1`). Only TLS routes carry a
+//! ClientHello to fingerprint, so `tcp_raw` routes may only filter by CIDR.
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+use std::net::IpAddr;
+
+use plfm_events::{RouteAccessPolicy, RouteProtocolHint};
+
+/// Maximum entries in each allow/deny list.
+pub const MAX_ENTRIES: usize = 256;
+
+/// Validate a policy and return it normalized (trimmed, lowercased,
+/// deduplicated), or the reason it is invalid.
+pub fn normalize(
+    policy: &RouteAccessPolicy,
+    protocol_hint: RouteProtocolHint,
+) -> Result<RouteAccessPolicy, String> {
+    let normalized = RouteAccessPolicy {
+        allow_cidrs: normalize_list(&policy.allow_cidrs, "allow_cidrs", normalize_cidr)?,
+        deny_cidrs: normalize_list(&policy.deny_cidrs, "deny_cidrs", normalize_cidr)?,
+        allow_fingerprints: normalize_list(
+            &policy.allow_fingerprints,
+            "allow_fingerprints",
+            normalize_fingerprint,
+        )?,
+        deny_fingerprints: normalize_list(
+            &policy.deny_fingerprints,
+            "deny_fingerprints",
+            normalize_fingerprint,
+        )?,
+    };
+
+    let has_fingerprints =
+        !normalized.allow_fingerprints.is_empty() || !normalized.deny_fingerprints.is_empty();
+    if has_fingerprints && protocol_hint == RouteProtocolHint::TcpRaw {
+        return Err("TLS fingerprint rules require a TLS route (not tcp_raw)".to_string());
+    }
+
+    Ok(normalized)
+}
+
+fn normalize_list(
+    entries: &[String],
+    field: &str,
+    normalize_entry: fn(&str) -> Option<String>,
+) -> Result<Vec<String>, String> {
+    if entries.len() > MAX_ENTRIES {
+        return Err(format!("{field} has more than {MAX_ENTRIES} entries"));
+    }
+    let mut out = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let normalized =
+            normalize_entry(entry).ok_or_else(|| format!("invalid {field} entry: {entry}"))?;
+        if !out.contains(&normalized) {
+            out.push(normalized);
+        }
+    }
+    Ok(out)
+}
+
+/// `addr/prefix`, or a bare address (a single host).
+fn normalize_cidr(entry: &str) -> Option<String> {
+    let entry = entry.trim();
+    let (addr, prefix) = match entry.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (entry, None),
+    };
+    let addr: IpAddr = addr.parse().ok()?;
+    let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix: u8 = match prefix {
+        Some(p) => p.parse().ok()?,
+        None => max_prefix,
+    };
+    (prefix <= max_prefix).then(|| format!("{addr}/{prefix}"))
+}
+
+/// A JA3 hash or a JA4 fingerprint, lowercased.
+fn normalize_fingerprint(entry: &str) -> Option<String> {
+    let fp = entry.trim().to_ascii_lowercase();
+    (is_ja3_hash(&fp) || is_ja4(&fp)).then_some(fp)
+}
+
+fn is_ja3_hash(s: &str) -> bool {
+    s.len() == 32 && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// `<a>_<b>_<c>`: a 10-character prefix (protocol, version, SNI, counts,
+/// ALPN) and two 12-digit hex hashes.
+fn is_ja4(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('_').collect();
+    let [a, b, c] = parts.as_slice() else {
+        return false;
+    };
+    let hash = |h: &str| h.len() == 12 && h.chars().all(|c| c.is_ascii_hexdigit());
+    a.len() == 10
+        && matches!(a.as_bytes()[0], b't' | b'q' | b'd')
+        && a.chars().all(|c| c.is_ascii_alphanumeric())
+        && hash(b)
+        && hash(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_policy() {
+        let policy = RouteAccessPolicy {
+            allow_cidrs: vec![" 2001:DB8::/32 ".to_string(), "203.0.113.7".to_string()],
+            deny_cidrs: vec!["10.0.0.0/8".to_string(), "10.0.0.0/8".to_string()],
+            allow_fingerprints: Vec::new(),
+            deny_fingerprints: vec![
+                "E7D705A3286E19EA42F587B344EE6865".to_string(),
+                "t13d1516h2_8daaf6152771_e5627efa2ab1".to_string(),
+            ],
+        };
+        let normalized = normalize(&policy, RouteProtocolHint::TlsPassthrough).unwrap();
+        assert_eq!(
+            normalized.allow_cidrs,
+            vec!["2001:db8::/32", "203.0.113.7/32"]
+        );
+        assert_eq!(normalized.deny_cidrs, vec!["10.0.0.0/8"]);
+        assert_eq!(
+            normalized.deny_fingerprints[0],
+            "e7d705a3286e19ea42f587b344ee6865"
+        );
+    }
+
+    #[test]
+    fn test_normalize_rejects_invalid_entries() {
+        let bad_cidr = RouteAccessPolicy {
+            deny_cidrs: vec!["10.0.0.0/33".to_string()],
+            ..Default::default()
+        };
+        assert!(normalize(&bad_cidr, RouteProtocolHint::TcpRaw).is_err());
+
+        let bad_fp = RouteAccessPolicy {
+            deny_fingerprints: vec!["not-a-fingerprint".to_string()],
+            ..Default::default()
+        };
+        assert!(normalize(&bad_fp, RouteProtocolHint::TlsPassthrough).is_err());
+
+        let fp_on_raw = RouteAccessPolicy {
+            deny_fingerprints: vec!["e7d705a3286e19ea42f587b344ee6865".to_string()],
+            ..Default::default()
+        };
+        assert!(normalize(&fp_on_raw, RouteProtocolHint::TcpRaw).is_err());
+    }
+}
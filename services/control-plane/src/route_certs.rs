@@ -0,0 +1,499 @@
+//! Uploaded TLS certificates for `tls_terminate` routes.
+//!
+//! An org uploads a certificate chain and private key per route. The chain
+//! is stored in `route_certificates`; the key is envelope-encrypted under the
+//! org's key in `secret_material` (see [`crate::secrets`]), so it is covered
+//! by key rotation jobs like any other secret. Only the ingress, through an
+//! operator-only endpoint, ever reads the key back.
+//!
+//! Expiry is tracked per certificate: once it enters the warning window the
+//! cleanup worker emits `route.cert_expiring` exactly once. Re-uploading
+//! resets the warning.
+//!
+//! Configuration:
+//! - `PLFM_ROUTE_CERT_EXPIRY_WARNING_DAYS`: warning window (default 14)
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{NewEvent, RouteCertExpiringPayload, SystemSource};
+use plfm_id::{EnvId, OrgId, RouteId};
+use plfm_pki::{PkiError, ServerCertificate};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::db::{DbError, EventStore};
+use crate::secrets::{self as secrets_crypto, KeyScope, SecretsCryptoError};
+
+/// Actor ID for events written by the expiry scan.
+pub const ROUTE_CERT_ACTOR_ID: &str = "route-certificates";
+
+const DEFAULT_EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// Name of the index enforcing one default certificate per listen port.
+const DEFAULT_PORT_INDEX: &str = "idx_route_certificates_default_port";
+
+#[derive(Debug, Error)]
+pub enum RouteCertError {
+    #[error(transparent)]
+    Pki(#[from] PkiError),
+    #[error("certificate does not cover hostname {0}")]
+    HostnameMismatch(String),
+    #[error("certificate is not valid now (valid from {not_before} to {not_after})")]
+    NotValidNow {
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    },
+    #[error("listen port {0} already has a default certificate for non-SNI clients")]
+    DefaultTaken(i32),
+    #[error("certificate key encryption failed: {0}")]
+    Crypto(#[from] SecretsCryptoError),
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub fn expiry_warning_window() -> Duration {
+    let days = std::env::var("PLFM_ROUTE_CERT_EXPIRY_WARNING_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|d| *d > 0)
+        .unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+    Duration::days(days)
+}
+
+fn key_aad(org_id: &str, route_id: &str, fingerprint: &str) -> String {
+    format!("plfm-route-cert-v1|org:{org_id}|route:{route_id}|fingerprint:{fingerprint}")
+}
+
+/// The route a certificate is uploaded for.
+#[derive(Debug, Clone)]
+pub struct RouteTarget {
+    pub route_id: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub hostname: String,
+    pub listen_port: i32,
+}
+
+/// Stored certificate metadata (never the key).
+#[derive(Debug, Clone)]
+pub struct RouteCertificate {
+    pub route_id: String,
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+    pub hostname: String,
+    pub listen_port: i32,
+    pub chain_pem: String,
+    pub fingerprint_sha256: String,
+    pub dns_names: Vec<String>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub default_for_non_sni: bool,
+    pub expiry_warned_at: Option<DateTime<Utc>>,
+    pub uploaded_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for RouteCertificate {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            route_id: row.try_get("route_id")?,
+            org_id: row.try_get("org_id")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            hostname: row.try_get("hostname")?,
+            listen_port: row.try_get("listen_port")?,
+            chain_pem: row.try_get("chain_pem")?,
+            fingerprint_sha256: row.try_get("fingerprint_sha256")?,
+            dns_names: row.try_get("dns_names")?,
+            not_before: row.try_get("not_before")?,
+            not_after: row.try_get("not_after")?,
+            default_for_non_sni: row.try_get("default_for_non_sni")?,
+            expiry_warned_at: row.try_get("expiry_warned_at")?,
+            uploaded_by: row.try_get("uploaded_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+const CERT_COLUMNS: &str = "route_id, org_id, app_id, env_id, hostname, listen_port, chain_pem, \
+     fingerprint_sha256, dns_names, not_before, not_after, default_for_non_sni, \
+     expiry_warned_at, uploaded_by, created_at, updated_at";
+
+/// A certificate with its decrypted key, for delivery to the ingress.
+pub struct CertificateMaterial {
+    pub certificate: RouteCertificate,
+    pub key_pem: String,
+}
+
+/// Parse and check an uploaded bundle against the route's hostname.
+pub fn validate(
+    hostname: &str,
+    chain_pem: &str,
+    key_pem: &str,
+    now: DateTime<Utc>,
+) -> Result<ServerCertificate, RouteCertError> {
+    let cert = ServerCertificate::parse(chain_pem, key_pem)?;
+    if !cert.covers(hostname) {
+        return Err(RouteCertError::HostnameMismatch(hostname.to_string()));
+    }
+    if !cert.is_valid_at(now) {
+        return Err(RouteCertError::NotValidNow {
+            not_before: cert.not_before,
+            not_after: cert.not_after,
+        });
+    }
+    Ok(cert)
+}
+
+/// Store (or replace) the certificate for a route.
+///
+/// The previous key material, if any, is deleted in the same transaction.
+pub async fn store(
+    pool: &PgPool,
+    route: &RouteTarget,
+    cert: &ServerCertificate,
+    default_for_non_sni: bool,
+    uploaded_by: &str,
+) -> Result<RouteCertificate, RouteCertError> {
+    let aad = key_aad(&route.org_id, &route.route_id, &cert.fingerprint_sha256);
+    let encrypted = secrets_crypto::encrypt(
+        KeyScope::Org(&route.org_id),
+        cert.key_pem.as_bytes(),
+        aad.as_bytes(),
+    )
+    .await?;
+    let material_id = format!("sm_{}", plfm_id::RequestId::new());
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO secret_material (
+            material_id, cipher, nonce, ciphertext, master_key_id,
+            wrapped_data_key, wrapped_data_key_nonce, plaintext_size_bytes
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(&material_id)
+    .bind(&encrypted.cipher)
+    .bind(&encrypted.nonce)
+    .bind(&encrypted.ciphertext)
+    .bind(&encrypted.master_key_id)
+    .bind(&encrypted.wrapped_data_key)
+    .bind(&encrypted.wrapped_data_key_nonce)
+    .bind(encrypted.plaintext_size_bytes)
+    .execute(&mut *tx)
+    .await?;
+
+    let previous_material: Option<String> = sqlx::query_scalar(
+        "SELECT key_material_id FROM route_certificates WHERE route_id = $1 FOR UPDATE",
+    )
+    .bind(&route.route_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let query = format!(
+        r#"
+        INSERT INTO route_certificates (
+            route_id, org_id, app_id, env_id, hostname, listen_port, chain_pem,
+            key_material_id, fingerprint_sha256, dns_names, not_before, not_after,
+            default_for_non_sni, uploaded_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        ON CONFLICT (route_id) DO UPDATE SET
+            hostname = EXCLUDED.hostname,
+            listen_port = EXCLUDED.listen_port,
+            chain_pem = EXCLUDED.chain_pem,
+            key_material_id = EXCLUDED.key_material_id,
+            fingerprint_sha256 = EXCLUDED.fingerprint_sha256,
+            dns_names = EXCLUDED.dns_names,
+            not_before = EXCLUDED.not_before,
+            not_after = EXCLUDED.not_after,
+            default_for_non_sni = EXCLUDED.default_for_non_sni,
+            expiry_warned_at = NULL,
+            uploaded_by = EXCLUDED.uploaded_by,
+            updated_at = now()
+        RETURNING {CERT_COLUMNS}
+        "#
+    );
+    let stored = sqlx::query_as::<_, RouteCertificate>(&query)
+        .bind(&route.route_id)
+        .bind(&route.org_id)
+        .bind(&route.app_id)
+        .bind(&route.env_id)
+        .bind(&route.hostname)
+        .bind(route.listen_port)
+        .bind(&cert.chain_pem)
+        .bind(&material_id)
+        .bind(&cert.fingerprint_sha256)
+        .bind(&cert.dns_names)
+        .bind(cert.not_before)
+        .bind(cert.not_after)
+        .bind(default_for_non_sni)
+        .bind(uploaded_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.constraint() == Some(DEFAULT_PORT_INDEX) => {
+                RouteCertError::DefaultTaken(route.listen_port)
+            }
+            _ => RouteCertError::Database(e),
+        })?;
+
+    if let Some(previous) = previous_material {
+        sqlx::query("DELETE FROM secret_material WHERE material_id = $1")
+            .bind(previous)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(stored)
+}
+
+pub async fn get(pool: &PgPool, route_id: &str) -> Result<Option<RouteCertificate>, sqlx::Error> {
+    let query = format!("SELECT {CERT_COLUMNS} FROM route_certificates WHERE route_id = $1");
+    sqlx::query_as::<_, RouteCertificate>(&query)
+        .bind(route_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Delete a route's certificate and its key material.
+pub async fn remove(
+    pool: &PgPool,
+    route_id: &str,
+) -> Result<Option<RouteCertificate>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let query = format!(
+        "DELETE FROM route_certificates WHERE route_id = $1 RETURNING {CERT_COLUMNS}, key_material_id"
+    );
+    let row = sqlx::query(&query)
+        .bind(route_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let removed = RouteCertificate::from_row(&row)?;
+    let material_id: String = row.try_get("key_material_id")?;
+    sqlx::query("DELETE FROM secret_material WHERE material_id = $1")
+        .bind(material_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Some(removed))
+}
+
+/// Certificates with decrypted keys, optionally for one org.
+///
+/// A certificate whose key cannot be decrypted is skipped (and logged) so
+/// one bad row does not take every other route's termination down.
+pub async fn list_material(
+    pool: &PgPool,
+    org_id: Option<&str>,
+) -> Result<Vec<CertificateMaterial>, RouteCertError> {
+    let query = format!(
+        r#"
+        SELECT {cols}, sm.cipher, sm.nonce, sm.ciphertext, sm.master_key_id,
+               sm.wrapped_data_key, sm.wrapped_data_key_nonce
+        FROM route_certificates rc
+        JOIN secret_material sm ON sm.material_id = rc.key_material_id
+        WHERE ($1::TEXT IS NULL OR rc.org_id = $1)
+        ORDER BY rc.route_id
+        "#,
+        cols = CERT_COLUMNS
+            .split(", ")
+            .map(|c| format!("rc.{}", c.trim()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let rows = sqlx::query(&query).bind(org_id).fetch_all(pool).await?;
+
+    let mut material = Vec::with_capacity(rows.len());
+    for row in rows {
+        let certificate = RouteCertificate::from_row(&row)?;
+        let cipher: String = row.try_get("cipher")?;
+        if cipher != secrets_crypto::CIPHER_NAME {
+            warn!(route_id = %certificate.route_id, cipher = %cipher, "Unsupported cipher for route certificate key");
+            continue;
+        }
+        let master_key_id: String = row.try_get("master_key_id")?;
+        let nonce: Vec<u8> = row.try_get("nonce")?;
+        let ciphertext: Vec<u8> = row.try_get("ciphertext")?;
+        let wrapped: Vec<u8> = row.try_get("wrapped_data_key")?;
+        let wrapped_nonce: Vec<u8> = row.try_get("wrapped_data_key_nonce")?;
+
+        let aad = key_aad(
+            &certificate.org_id,
+            &certificate.route_id,
+            &certificate.fingerprint_sha256,
+        );
+        let key = match secrets_crypto::decrypt(
+            &master_key_id,
+            &nonce,
+            &ciphertext,
+            &wrapped,
+            &wrapped_nonce,
+            aad.as_bytes(),
+        )
+        .await
+        {
+            Ok(key) => key,
+            Err(e) => {
+                warn!(error = %e, route_id = %certificate.route_id, "Failed to decrypt route certificate key");
+                continue;
+            }
+        };
+        let Ok(key_pem) = String::from_utf8(key) else {
+            warn!(route_id = %certificate.route_id, "Route certificate key is not valid UTF-8");
+            continue;
+        };
+        material.push(CertificateMaterial {
+            certificate,
+            key_pem,
+        });
+    }
+    Ok(material)
+}
+
+/// Emit `route.cert_expiring` for certificates entering the warning window.
+///
+/// Each certificate is warned about once; the row is marked before the
+/// event is appended, guarded on the fingerprint so a concurrent re-upload
+/// is not marked by mistake.
+pub async fn warn_expiring(pool: &PgPool) -> Result<usize, RouteCertError> {
+    let now = Utc::now();
+    let query = format!(
+        r#"
+        SELECT {CERT_COLUMNS} FROM route_certificates
+        WHERE expiry_warned_at IS NULL AND not_after <= $1
+        ORDER BY not_after
+        LIMIT 100
+        "#
+    );
+    let expiring = sqlx::query_as::<_, RouteCertificate>(&query)
+        .bind(now + expiry_warning_window())
+        .fetch_all(pool)
+        .await?;
+
+    let source = SystemSource::new(ROUTE_CERT_ACTOR_ID);
+    let store = EventStore::new(pool.clone());
+    let mut warned = 0;
+    for cert in expiring {
+        let marked = sqlx::query(
+            r#"
+            UPDATE route_certificates SET expiry_warned_at = now()
+            WHERE route_id = $1 AND fingerprint_sha256 = $2 AND expiry_warned_at IS NULL
+            "#,
+        )
+        .bind(&cert.route_id)
+        .bind(&cert.fingerprint_sha256)
+        .execute(pool)
+        .await?;
+        if marked.rows_affected() == 0 {
+            continue;
+        }
+
+        let (Ok(route_id), Ok(org_id), Ok(env_id)) = (
+            cert.route_id.parse::<RouteId>(),
+            cert.org_id.parse::<OrgId>(),
+            cert.env_id.parse::<EnvId>(),
+        ) else {
+            warn!(route_id = %cert.route_id, "Skipping expiry warning for certificate with invalid IDs");
+            continue;
+        };
+        let seq = store
+            .get_latest_aggregate_seq(
+                &plfm_events::AggregateType::RouteCertificate,
+                &cert.route_id,
+            )
+            .await?
+            .unwrap_or(0)
+            + 1;
+        let days_remaining = (cert.not_after - now).num_days().max(0);
+        let event = NewEvent::builder(&source)
+            .aggregate_id(cert.route_id.clone())
+            .aggregate_seq(seq)
+            .org_id(org_id)
+            .env_id(env_id)
+            .payload(&RouteCertExpiringPayload {
+                route_id,
+                org_id,
+                env_id,
+                hostname: cert.hostname.clone(),
+                fingerprint_sha256: cert.fingerprint_sha256.clone(),
+                not_after: cert.not_after.to_rfc3339(),
+                days_remaining,
+            })
+            .build()?;
+        store.append(event.into()).await?;
+
+        info!(
+            route_id = %cert.route_id,
+            hostname = %cert.hostname,
+            not_after = %cert.not_after,
+            days_remaining,
+            "Route certificate expiring"
+        );
+        warned += 1;
+    }
+    Ok(warned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertificateParams, KeyPair};
+
+    fn bundle(names: &[&str]) -> (String, String) {
+        let key = KeyPair::generate().unwrap();
+        let params =
+            CertificateParams::new(names.iter().map(|n| n.to_string()).collect::<Vec<_>>())
+                .unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+
+    #[test]
+    fn test_validate_checks_hostname() {
+        let (cert, key) = bundle(&["*.example.com"]);
+        assert!(validate("api.example.com", &cert, &key, Utc::now()).is_ok());
+        assert!(matches!(
+            validate("example.org", &cert, &key, Utc::now()).unwrap_err(),
+            RouteCertError::HostnameMismatch(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_checks_validity() {
+        let (cert, key) = bundle(&["api.example.com"]);
+        let far_future = Utc::now() + Duration::days(365 * 5000);
+        assert!(matches!(
+            validate("api.example.com", &cert, &key, far_future).unwrap_err(),
+            RouteCertError::NotValidNow { .. }
+        ));
+    }
+
+    #[test]
+    fn test_key_aad_binds_route_and_fingerprint() {
+        assert_ne!(
+            key_aad("org_1", "route_1", "aa"),
+            key_aad("org_1", "route_2", "aa")
+        );
+        assert_ne!(
+            key_aad("org_1", "route_1", "aa"),
+            key_aad("org_1", "route_1", "bb")
+        );
+    }
+}
@@ -0,0 +1,601 @@
+//! Hostname ownership verification for routes.
+//!
+//! A new route is created `pending` with a random token and is not served by
+//! the edge until the org proves it controls the hostname, by either:
+//! - a TXT record at `_plfm-challenge.<hostname>` with value
+//!   `plfm-verify=<token>`, or
+//! - a CNAME from the hostname to `<token>.<suffix>`, when a CNAME suffix is
+//!   configured.
+//!
+//! The verifier worker re-checks pending routes with backoff and emits
+//! `route.verification_succeeded` on the first passing check. If the
+//! verification window passes first it emits `route.verification_failed`;
+//! failed routes release their hostname and are only re-checked on demand
+//! (`POST .../routes/{route_id}/verify`).
+//!
+//! Configuration:
+//! - `PLFM_ROUTE_VERIFICATION`: `required` (default) or `disabled`
+//! - `PLFM_ROUTE_VERIFICATION_CNAME_SUFFIX`: enables the CNAME method
+//! - `PLFM_ROUTE_VERIFICATION_WINDOW_HOURS`: verification window (default 72)
+//!
+//! See: docs/specs/networking/ingress-l4.md
+
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::proto::rr::{RData, RecordType};
+use hickory_resolver::TokioAsyncResolver;
+use plfm_events::{
+    AggregateType, EventSource, NewEvent, RouteVerificationFailedPayload, RouteVerificationMethod,
+    RouteVerificationSucceededPayload, SystemSource,
+};
+use plfm_id::{EnvId, EventId, OrgId, RouteId};
+use rand::RngCore;
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+use crate::db::{DbError, EventStore};
+use crate::leader::{LeaderElection, LeaderRole};
+
+/// Actor ID for events written by the verifier worker.
+pub const ROUTE_VERIFIER_ACTOR_ID: &str = "route-verifier";
+
+/// Label prepended to the hostname for the TXT challenge.
+pub const TXT_CHALLENGE_LABEL: &str = "_plfm-challenge";
+
+const DEFAULT_WINDOW_HOURS: i64 = 72;
+
+/// Routes checked per verifier pass.
+const PASS_BATCH: i64 = 50;
+
+/// Re-check backoff: 30s doubling per attempt, capped at 1h.
+const BACKOFF_BASE_SECS: i64 = 30;
+const BACKOFF_MAX_SECS: i64 = 3600;
+
+#[derive(Debug, Error)]
+pub enum RouteVerificationError {
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Whether new routes must verify their hostname before being served.
+pub fn verification_required() -> bool {
+    !matches!(
+        std::env::var("PLFM_ROUTE_VERIFICATION")
+            .ok()
+            .as_deref()
+            .map(str::trim),
+        Some("disabled")
+    )
+}
+
+/// Suffix for per-route CNAME targets; the CNAME method is off without it.
+pub fn cname_suffix() -> Option<String> {
+    std::env::var("PLFM_ROUTE_VERIFICATION_CNAME_SUFFIX")
+        .ok()
+        .map(|v| v.trim().trim_matches('.').to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+/// How long a route may stay pending before verification fails.
+pub fn verification_window() -> Duration {
+    let hours = std::env::var("PLFM_ROUTE_VERIFICATION_WINDOW_HOURS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(DEFAULT_WINDOW_HOURS);
+    Duration::hours(hours)
+}
+
+/// New verification token: 128 random bits, hex (valid as a DNS label).
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn txt_record_name(hostname: &str) -> String {
+    format!("{TXT_CHALLENGE_LABEL}.{}", normalize(hostname))
+}
+
+pub fn txt_record_value(token: &str) -> String {
+    format!("plfm-verify={token}")
+}
+
+pub fn cname_target(token: &str, suffix: &str) -> String {
+    format!("{token}.{suffix}")
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn backoff(attempts: i32) -> Duration {
+    let shift = attempts.clamp(0, 16) as u32;
+    Duration::seconds((BACKOFF_BASE_SECS << shift).min(BACKOFF_MAX_SECS))
+}
+
+// =============================================================================
+// DNS
+// =============================================================================
+
+/// DNS queries used by verification. Missing records are `Ok(vec![])`.
+#[async_trait]
+pub trait DnsLookup: Send + Sync {
+    async fn txt(&self, name: &str) -> Result<Vec<String>, String>;
+    async fn cname(&self, name: &str) -> Result<Vec<String>, String>;
+}
+
+/// Resolver using the host's DNS configuration, without caching so a
+/// freshly published record is seen on the next check.
+pub struct SystemDns {
+    resolver: TokioAsyncResolver,
+}
+
+impl SystemDns {
+    pub fn from_system_conf() -> Result<Self, String> {
+        let (config, mut opts) = hickory_resolver::system_conf::read_system_conf()
+            .map_err(|e| format!("failed to read resolver config: {e}"))?;
+        opts.cache_size = 0;
+        Ok(Self {
+            resolver: TokioAsyncResolver::tokio(config, opts),
+        })
+    }
+}
+
+fn fqdn(name: &str) -> String {
+    format!("{}.", normalize(name))
+}
+
+#[async_trait]
+impl DnsLookup for SystemDns {
+    async fn txt(&self, name: &str) -> Result<Vec<String>, String> {
+        match self.resolver.txt_lookup(fqdn(name)).await {
+            Ok(lookup) => Ok(lookup
+                .iter()
+                .map(|txt| {
+                    txt.txt_data()
+                        .iter()
+                        .map(|part| String::from_utf8_lossy(part))
+                        .collect::<String>()
+                })
+                .collect()),
+            Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(Vec::new()),
+            Err(e) => Err(format!("TXT lookup for {name} failed: {e}")),
+        }
+    }
+
+    async fn cname(&self, name: &str) -> Result<Vec<String>, String> {
+        match self.resolver.lookup(fqdn(name), RecordType::CNAME).await {
+            Ok(lookup) => Ok(lookup
+                .record_iter()
+                .filter_map(|record| match record.data() {
+                    Some(RData::CNAME(target)) => Some(normalize(&target.0.to_utf8())),
+                    _ => None,
+                })
+                .collect()),
+            Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(Vec::new()),
+            Err(e) => Err(format!("CNAME lookup for {name} failed: {e}")),
+        }
+    }
+}
+
+/// Check whether DNS proves ownership of `hostname` for `token`.
+///
+/// Returns the method that passed, or a reason suitable for showing to the
+/// user.
+pub async fn check(
+    dns: &dyn DnsLookup,
+    hostname: &str,
+    token: &str,
+    cname_suffix: Option<&str>,
+) -> Result<RouteVerificationMethod, String> {
+    let txt_name = txt_record_name(hostname);
+    let expected_txt = txt_record_value(token);
+    let mut reasons = Vec::new();
+
+    match dns.txt(&txt_name).await {
+        Ok(values) if values.iter().any(|v| v.trim() == expected_txt) => {
+            return Ok(RouteVerificationMethod::Txt)
+        }
+        Ok(values) if values.is_empty() => reasons.push(format!("no TXT record at {txt_name}")),
+        Ok(_) => reasons.push(format!(
+            "TXT record at {txt_name} does not contain {expected_txt}"
+        )),
+        Err(e) => reasons.push(e),
+    }
+
+    if let Some(suffix) = cname_suffix {
+        let expected = cname_target(token, suffix);
+        match dns.cname(hostname).await {
+            Ok(targets) if targets.iter().any(|t| *t == expected) => {
+                return Ok(RouteVerificationMethod::Cname)
+            }
+            Ok(targets) if targets.is_empty() => {
+                reasons.push(format!("{} has no CNAME record", normalize(hostname)))
+            }
+            Ok(targets) => reasons.push(format!(
+                "{} is a CNAME to {}, expected {expected}",
+                normalize(hostname),
+                targets.join(", ")
+            )),
+            Err(e) => reasons.push(e),
+        }
+    }
+
+    Err(reasons.join("; "))
+}
+
+// =============================================================================
+// Events
+// =============================================================================
+
+/// A route awaiting verification, as read from `routes_view`.
+#[derive(Debug, Clone)]
+pub struct PendingRoute {
+    pub route_id: String,
+    pub org_id: String,
+    pub env_id: String,
+    pub hostname: String,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+    pub attempts: i32,
+}
+
+impl<'r> FromRow<'r, PgRow> for PendingRoute {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            route_id: row.try_get("route_id")?,
+            org_id: row.try_get("org_id")?,
+            env_id: row.try_get("env_id")?,
+            hostname: row.try_get("hostname")?,
+            token: row.try_get("verification_token")?,
+            created_at: row.try_get("created_at")?,
+            attempts: row.try_get("attempts")?,
+        })
+    }
+}
+
+fn parse_ids(route: &PendingRoute) -> Option<(RouteId, OrgId, EnvId)> {
+    Some((
+        route.route_id.parse().ok()?,
+        route.org_id.parse().ok()?,
+        route.env_id.parse().ok()?,
+    ))
+}
+
+/// Emit `route.verification_succeeded` for a route.
+pub async fn record_success(
+    store: &EventStore,
+    source: &impl EventSource,
+    route_id: RouteId,
+    org_id: OrgId,
+    env_id: EnvId,
+    hostname: &str,
+    method: RouteVerificationMethod,
+) -> Result<EventId, RouteVerificationError> {
+    let aggregate_id = route_id.to_string();
+    let seq = store
+        .get_latest_aggregate_seq(&AggregateType::Route, &aggregate_id)
+        .await?
+        .unwrap_or(0)
+        + 1;
+    let event = NewEvent::builder(source)
+        .aggregate_id(aggregate_id)
+        .aggregate_seq(seq)
+        .org_id(org_id)
+        .env_id(env_id)
+        .payload(&RouteVerificationSucceededPayload {
+            route_id,
+            org_id,
+            env_id,
+            hostname: hostname.to_string(),
+            method,
+        })
+        .build()?;
+    Ok(store.append(event.into()).await?)
+}
+
+async fn record_failure(
+    store: &EventStore,
+    source: &SystemSource,
+    route: &PendingRoute,
+    reason: &str,
+    attempts: i32,
+) -> Result<(), RouteVerificationError> {
+    let Some((route_id, org_id, env_id)) = parse_ids(route) else {
+        warn!(route_id = %route.route_id, "Skipping verification failure for route with invalid IDs");
+        return Ok(());
+    };
+    let seq = store
+        .get_latest_aggregate_seq(&AggregateType::Route, &route.route_id)
+        .await?
+        .unwrap_or(0)
+        + 1;
+    let event = NewEvent::builder(source)
+        .aggregate_id(route.route_id.clone())
+        .aggregate_seq(seq)
+        .org_id(org_id)
+        .env_id(env_id)
+        .payload(&RouteVerificationFailedPayload {
+            route_id,
+            org_id,
+            env_id,
+            hostname: route.hostname.clone(),
+            reason: reason.to_string(),
+            attempts,
+        })
+        .build()?;
+    store.append(event.into()).await?;
+    Ok(())
+}
+
+/// Check every pending route whose next check is due. Returns how many
+/// routes changed state (verified or failed).
+pub async fn run_pass(pool: &PgPool, dns: &dyn DnsLookup) -> Result<usize, RouteVerificationError> {
+    // Bookkeeping for routes that are no longer pending.
+    sqlx::query(
+        r#"
+        DELETE FROM route_verification_checks c
+        WHERE NOT EXISTS (
+            SELECT 1 FROM routes_view r
+            WHERE r.route_id = c.route_id
+              AND r.verification_status = 'pending'
+              AND NOT r.is_deleted
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let due = sqlx::query_as::<_, PendingRoute>(
+        r#"
+        SELECT r.route_id, r.org_id, r.env_id, r.hostname, r.verification_token, r.created_at,
+               COALESCE(c.attempts, 0) AS attempts
+        FROM routes_view r
+        LEFT JOIN route_verification_checks c ON c.route_id = r.route_id
+        WHERE r.verification_status = 'pending'
+          AND NOT r.is_deleted
+          AND r.verification_token IS NOT NULL
+          AND (c.next_check_at IS NULL OR c.next_check_at <= now())
+        ORDER BY COALESCE(c.next_check_at, r.created_at)
+        LIMIT $1
+        "#,
+    )
+    .bind(PASS_BATCH)
+    .fetch_all(pool)
+    .await?;
+
+    let source = SystemSource::new(ROUTE_VERIFIER_ACTOR_ID);
+    let store = EventStore::new(pool.clone());
+    let suffix = cname_suffix();
+    let window = verification_window();
+    let mut changed = 0;
+
+    for route in due {
+        let attempts = route.attempts + 1;
+        match check(dns, &route.hostname, &route.token, suffix.as_deref()).await {
+            Ok(method) => {
+                let Some((route_id, org_id, env_id)) = parse_ids(&route) else {
+                    warn!(route_id = %route.route_id, "Skipping verification for route with invalid IDs");
+                    continue;
+                };
+                record_success(
+                    &store,
+                    &source,
+                    route_id,
+                    org_id,
+                    env_id,
+                    &route.hostname,
+                    method,
+                )
+                .await?;
+                sqlx::query("DELETE FROM route_verification_checks WHERE route_id = $1")
+                    .bind(&route.route_id)
+                    .execute(pool)
+                    .await?;
+                info!(
+                    route_id = %route.route_id,
+                    hostname = %route.hostname,
+                    method = ?method,
+                    attempts,
+                    "Route hostname verified"
+                );
+                changed += 1;
+            }
+            Err(reason) if Utc::now() >= route.created_at + window => {
+                record_failure(&store, &source, &route, &reason, attempts).await?;
+                sqlx::query("DELETE FROM route_verification_checks WHERE route_id = $1")
+                    .bind(&route.route_id)
+                    .execute(pool)
+                    .await?;
+                info!(
+                    route_id = %route.route_id,
+                    hostname = %route.hostname,
+                    attempts,
+                    reason = %reason,
+                    "Route hostname verification failed"
+                );
+                changed += 1;
+            }
+            Err(reason) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO route_verification_checks
+                        (route_id, attempts, last_checked_at, last_error, next_check_at)
+                    VALUES ($1, $2, now(), $3, $4)
+                    ON CONFLICT (route_id) DO UPDATE SET
+                        attempts = EXCLUDED.attempts,
+                        last_checked_at = EXCLUDED.last_checked_at,
+                        last_error = EXCLUDED.last_error,
+                        next_check_at = EXCLUDED.next_check_at
+                    "#,
+                )
+                .bind(&route.route_id)
+                .bind(attempts)
+                .bind(&reason)
+                .bind(Utc::now() + backoff(attempts))
+                .execute(pool)
+                .await?;
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Last failed check for a pending route, if any.
+pub async fn last_error(pool: &PgPool, route_id: &str) -> Result<Option<String>, sqlx::Error> {
+    let row: Option<Option<String>> =
+        sqlx::query_scalar("SELECT last_error FROM route_verification_checks WHERE route_id = $1")
+            .bind(route_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.flatten())
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Background worker checking pending routes, on the elected leader only.
+pub struct RouteVerifierWorker {
+    pool: PgPool,
+    interval: StdDuration,
+    election: LeaderElection,
+    dns: Option<Arc<dyn DnsLookup>>,
+}
+
+impl RouteVerifierWorker {
+    pub fn new(pool: PgPool, interval: StdDuration) -> Self {
+        let dns = match SystemDns::from_system_conf() {
+            Ok(dns) => Some(Arc::new(dns) as Arc<dyn DnsLookup>),
+            Err(e) => {
+                error!(error = %e, "Route verifier has no DNS resolver; routes will stay pending");
+                None
+            }
+        };
+        Self {
+            election: LeaderElection::new(pool.clone(), LeaderRole::RouteVerifier, interval * 3),
+            pool,
+            interval,
+            dns,
+        }
+    }
+
+    #[instrument(skip(self, shutdown))]
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            interval_secs = self.interval.as_secs(),
+            "Starting route verifier worker"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let Some(dns) = self.dns.as_deref() else { continue };
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
+                    match run_pass(&self.pool, dns).await {
+                        Ok(count) if count > 0 => info!(changed = count, "Route verification pass complete"),
+                        Ok(_) => {}
+                        Err(e) => error!(error = %e, "Route verification pass failed"),
+                    }
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Route verifier worker shutting down");
+                        self.election.resign().await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct FakeDns {
+        txt: HashMap<String, Vec<String>>,
+        cname: HashMap<String, Vec<String>>,
+    }
+
+    #[async_trait]
+    impl DnsLookup for FakeDns {
+        async fn txt(&self, name: &str) -> Result<Vec<String>, String> {
+            Ok(self.txt.get(name).cloned().unwrap_or_default())
+        }
+
+        async fn cname(&self, name: &str) -> Result<Vec<String>, String> {
+            Ok(self.cname.get(name).cloned().unwrap_or_default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_txt_record() {
+        let mut dns = FakeDns::default();
+        dns.txt.insert(
+            "_plfm-challenge.api.example.com".to_string(),
+            vec!["v=spf1 -all".to_string(), "plfm-verify=abc123".to_string()],
+        );
+
+        let method = check(&dns, "API.example.com.", "abc123", None).await;
+        assert_eq!(method, Ok(RouteVerificationMethod::Txt));
+
+        let err = check(&dns, "api.example.com", "other", None)
+            .await
+            .unwrap_err();
+        assert!(err.contains("does not contain plfm-verify=other"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_check_cname_requires_suffix() {
+        let mut dns = FakeDns::default();
+        dns.cname.insert(
+            "api.example.com".to_string(),
+            vec!["abc123.verify.plfm.test".to_string()],
+        );
+
+        let err = check(&dns, "api.example.com", "abc123", None)
+            .await
+            .unwrap_err();
+        assert!(err.contains("no TXT record"), "{err}");
+
+        let method = check(&dns, "api.example.com", "abc123", Some("verify.plfm.test")).await;
+        assert_eq!(method, Ok(RouteVerificationMethod::Cname));
+
+        let err = check(&dns, "api.example.com", "zzz", Some("verify.plfm.test"))
+            .await
+            .unwrap_err();
+        assert!(err.contains("expected zzz.verify.plfm.test"), "{err}");
+    }
+
+    #[test]
+    fn test_token_and_backoff() {
+        let token = new_token();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, new_token());
+
+        assert_eq!(backoff(1), Duration::seconds(60));
+        assert_eq!(backoff(20), Duration::seconds(BACKOFF_MAX_SECS));
+    }
+}
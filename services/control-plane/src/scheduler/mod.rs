@@ -9,9 +9,10 @@
 //! See: docs/specs/scheduler/reconciliation-loop.md
 //! See: docs/specs/scheduler/placement.md
 
+mod placement;
 mod reconciler;
 mod worker;
 
 #[allow(unused_imports)]
-pub use reconciler::SchedulerReconciler;
+pub use reconciler::{PlacementPreview, PreviewOverride, SchedulerReconciler};
 pub use worker::SchedulerWorker;
@@ -0,0 +1,442 @@
+//! Node selection for instance placement.
+//!
+//! Shared by the reconciler (one placement at a time) and the placement
+//! preview API, which plans a batch of placements against current capacity
+//! without emitting events.
+//!
+//! Hard filters: node state, capacity, node selector and `NoSchedule` taints.
+//! Ranking: fewest untolerated `PreferNoSchedule` taints, then most available
+//! memory, then CPU, then node_id.
+//!
+//! See: docs/specs/scheduler/placement.md
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use plfm_events::{NodeTaint, TaintEffect, Toleration};
+
+/// Node capacity for placement decisions.
+#[derive(Debug, Clone)]
+pub struct NodeCapacity {
+    pub node_id: String,
+    pub state: String,
+    pub allocatable_memory_bytes: i64,
+    pub allocatable_cpu_cores: i32,
+    pub available_memory_bytes: i64,
+    pub available_cpu_cores: i32,
+    pub instance_count: i32,
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<NodeTaint>,
+}
+
+/// Node selector and tolerations of a process type.
+#[derive(Debug, Clone, Default)]
+pub struct PlacementConstraints {
+    pub node_selector: BTreeMap<String, String>,
+    pub tolerations: Vec<Toleration>,
+    /// Home node of the process type's local volumes; when set, that is the
+    /// only node the process type may run on.
+    pub pinned_node_id: Option<String>,
+}
+
+impl PlacementConstraints {
+    fn tolerates(&self, taint: &NodeTaint) -> bool {
+        self.tolerations.iter().any(|t| t.tolerates(taint))
+    }
+
+    /// Whether the node is the pinned node (if any), passes the selector and
+    /// has no untolerated `NoSchedule` taint.
+    fn admits(&self, node: &NodeCapacity) -> bool {
+        if self
+            .pinned_node_id
+            .as_ref()
+            .is_some_and(|pinned| *pinned != node.node_id)
+        {
+            return false;
+        }
+        let selected = self
+            .node_selector
+            .iter()
+            .all(|(key, value)| node.labels.get(key) == Some(value));
+        selected
+            && node
+                .taints
+                .iter()
+                .filter(|t| t.effect == TaintEffect::NoSchedule)
+                .all(|t| self.tolerates(t))
+    }
+
+    fn untolerated_soft_taints(&self, node: &NodeCapacity) -> usize {
+        node.taints
+            .iter()
+            .filter(|t| t.effect == TaintEffect::PreferNoSchedule && !self.tolerates(t))
+            .count()
+    }
+}
+
+impl NodeCapacity {
+    fn fits(&self, memory_bytes: i64, cpu_cores: i32) -> bool {
+        self.state == "active"
+            && self.available_memory_bytes >= memory_bytes
+            && self.available_cpu_cores >= cpu_cores
+    }
+
+    /// Preference order: most available memory, then CPU, then node_id.
+    fn rank(&self, other: &Self) -> Ordering {
+        other
+            .available_memory_bytes
+            .cmp(&self.available_memory_bytes)
+            .then(other.available_cpu_cores.cmp(&self.available_cpu_cores))
+            .then(self.node_id.cmp(&other.node_id))
+    }
+}
+
+/// Pick the best eligible node for an instance, if any.
+pub fn select_node<'a>(
+    nodes: &'a [NodeCapacity],
+    memory_bytes: i64,
+    cpu_cores: i32,
+    constraints: &PlacementConstraints,
+) -> Option<&'a NodeCapacity> {
+    nodes
+        .iter()
+        .filter(|n| n.fits(memory_bytes, cpu_cores) && constraints.admits(n))
+        .min_by(|a, b| {
+            constraints
+                .untolerated_soft_taints(a)
+                .cmp(&constraints.untolerated_soft_taints(b))
+                .then(a.rank(b))
+        })
+}
+
+/// Instances of one process type that need a node.
+#[derive(Debug, Clone)]
+pub struct PlacementDemand {
+    pub process_type: String,
+    pub count: i32,
+    pub memory_bytes: i64,
+    pub cpu_cores: i32,
+    pub constraints: PlacementConstraints,
+}
+
+/// Why a demand could not be (fully) placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfeasibleReason {
+    NoNodesActive,
+    /// No active node passes the node selector and `NoSchedule` taints.
+    NoMatchingNodes,
+    /// The process type mounts local volumes whose home node is not active.
+    VolumeLocalityNoNode,
+    NoCapacityMemory,
+    NoCapacityCpu,
+}
+
+impl InfeasibleReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoNodesActive => "no_nodes_active",
+            Self::NoMatchingNodes => "no_matching_nodes",
+            Self::VolumeLocalityNoNode => "volume_locality_no_node",
+            Self::NoCapacityMemory => "no_capacity_memory",
+            Self::NoCapacityCpu => "no_capacity_cpu",
+        }
+    }
+}
+
+/// One planned instance placement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedPlacement {
+    pub process_type: String,
+    pub node_id: String,
+}
+
+/// Instances of a demand left without a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnplacedDemand {
+    pub process_type: String,
+    pub count: i32,
+    pub reason: InfeasibleReason,
+}
+
+/// Result of planning a batch of placements.
+#[derive(Debug, Clone)]
+pub struct PlacementPlan {
+    pub placements: Vec<PlannedPlacement>,
+    pub unplaced: Vec<UnplacedDemand>,
+    /// Node capacity after the planned placements are reserved.
+    pub nodes: Vec<NodeCapacity>,
+}
+
+impl PlacementPlan {
+    pub fn feasible(&self) -> bool {
+        self.unplaced.is_empty()
+    }
+}
+
+/// Plan placements for `demands` in order, reserving capacity on each chosen
+/// node so later instances see what earlier ones consumed.
+pub fn plan_placements(mut nodes: Vec<NodeCapacity>, demands: &[PlacementDemand]) -> PlacementPlan {
+    let mut placements = Vec::new();
+    let mut unplaced = Vec::new();
+
+    for demand in demands {
+        for placed in 0..demand.count.max(0) {
+            let chosen = select_node(
+                &nodes,
+                demand.memory_bytes,
+                demand.cpu_cores,
+                &demand.constraints,
+            )
+            .map(|n| n.node_id.clone());
+            let Some(node_id) = chosen else {
+                unplaced.push(UnplacedDemand {
+                    process_type: demand.process_type.clone(),
+                    count: demand.count - placed,
+                    reason: infeasible_reason(&nodes, demand),
+                });
+                break;
+            };
+
+            if let Some(node) = nodes.iter_mut().find(|n| n.node_id == node_id) {
+                node.available_memory_bytes -= demand.memory_bytes;
+                node.available_cpu_cores -= demand.cpu_cores;
+                node.instance_count += 1;
+            }
+            placements.push(PlannedPlacement {
+                process_type: demand.process_type.clone(),
+                node_id,
+            });
+        }
+    }
+
+    PlacementPlan {
+        placements,
+        unplaced,
+        nodes,
+    }
+}
+
+fn infeasible_reason(nodes: &[NodeCapacity], demand: &PlacementDemand) -> InfeasibleReason {
+    let active: Vec<&NodeCapacity> = nodes.iter().filter(|n| n.state == "active").collect();
+    let matching: Vec<&NodeCapacity> = active
+        .iter()
+        .copied()
+        .filter(|n| demand.constraints.admits(n))
+        .collect();
+    if active.is_empty() {
+        InfeasibleReason::NoNodesActive
+    } else if demand
+        .constraints
+        .pinned_node_id
+        .as_ref()
+        .is_some_and(|pinned| !active.iter().any(|n| n.node_id == *pinned))
+    {
+        InfeasibleReason::VolumeLocalityNoNode
+    } else if matching.is_empty() {
+        InfeasibleReason::NoMatchingNodes
+    } else if matching
+        .iter()
+        .all(|n| n.available_memory_bytes < demand.memory_bytes)
+    {
+        InfeasibleReason::NoCapacityMemory
+    } else {
+        InfeasibleReason::NoCapacityCpu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: i64 = 1024 * 1024 * 1024;
+
+    fn node(id: &str, memory_gib: i64, cpu: i32) -> NodeCapacity {
+        NodeCapacity {
+            node_id: id.to_string(),
+            state: "active".to_string(),
+            allocatable_memory_bytes: memory_gib * GIB,
+            allocatable_cpu_cores: cpu,
+            available_memory_bytes: memory_gib * GIB,
+            available_cpu_cores: cpu,
+            instance_count: 0,
+            labels: BTreeMap::new(),
+            taints: Vec::new(),
+        }
+    }
+
+    fn taint(key: &str, effect: TaintEffect) -> NodeTaint {
+        NodeTaint {
+            key: key.to_string(),
+            value: None,
+            effect,
+        }
+    }
+
+    fn toleration(key: &str) -> Toleration {
+        Toleration {
+            key: key.to_string(),
+            value: None,
+            effect: None,
+        }
+    }
+
+    fn demand(process_type: &str, count: i32) -> PlacementDemand {
+        PlacementDemand {
+            process_type: process_type.to_string(),
+            count,
+            memory_bytes: GIB,
+            cpu_cores: 1,
+            constraints: PlacementConstraints::default(),
+        }
+    }
+
+    #[test]
+    fn test_select_node_prefers_capacity_then_id() {
+        let none = PlacementConstraints::default();
+        let nodes = vec![
+            node("node_b", 4, 4),
+            node("node_a", 4, 4),
+            node("node_c", 2, 8),
+        ];
+        assert_eq!(
+            select_node(&nodes, GIB, 1, &none).unwrap().node_id,
+            "node_a"
+        );
+
+        let mut draining = node("node_d", 16, 16);
+        draining.state = "draining".to_string();
+        let nodes = vec![draining, node("node_a", 1, 1)];
+        assert_eq!(
+            select_node(&nodes, GIB, 1, &none).unwrap().node_id,
+            "node_a"
+        );
+        assert!(select_node(&nodes, 2 * GIB, 1, &none).is_none());
+    }
+
+    #[test]
+    fn test_plan_placements_reserves_capacity() {
+        let nodes = vec![node("node_a", 3, 4), node("node_b", 2, 4)];
+        let plan = plan_placements(nodes, &[demand("web", 4)]);
+
+        assert!(plan.feasible());
+        let targets: Vec<&str> = plan.placements.iter().map(|p| p.node_id.as_str()).collect();
+        assert_eq!(targets, vec!["node_a", "node_b", "node_a", "node_b"]);
+        assert!(plan.nodes.iter().all(|n| n.available_memory_bytes >= 0));
+    }
+
+    #[test]
+    fn test_plan_placements_reports_infeasible() {
+        let plan = plan_placements(
+            vec![node("node_a", 2, 1)],
+            &[demand("web", 3), demand("worker", 1)],
+        );
+        assert!(!plan.feasible());
+        assert_eq!(plan.placements.len(), 1);
+        assert_eq!(
+            plan.unplaced,
+            vec![
+                UnplacedDemand {
+                    process_type: "web".to_string(),
+                    count: 2,
+                    reason: InfeasibleReason::NoCapacityCpu,
+                },
+                UnplacedDemand {
+                    process_type: "worker".to_string(),
+                    count: 1,
+                    reason: InfeasibleReason::NoCapacityCpu,
+                },
+            ]
+        );
+
+        let plan = plan_placements(vec![node("node_a", 1, 8)], &[demand("web", 2)]);
+        assert_eq!(plan.unplaced[0].reason, InfeasibleReason::NoCapacityMemory);
+
+        let plan = plan_placements(Vec::new(), &[demand("web", 1)]);
+        assert_eq!(plan.unplaced[0].reason, InfeasibleReason::NoNodesActive);
+    }
+
+    #[test]
+    fn test_select_node_honors_taints_and_selector() {
+        let none = PlacementConstraints::default();
+        let mut gpu = node("node_gpu", 64, 32);
+        gpu.labels.insert("accel".to_string(), "a100".to_string());
+        gpu.taints.push(taint("gpu", TaintEffect::NoSchedule));
+        let mut spot = node("node_spot", 32, 16);
+        spot.taints
+            .push(taint("spot", TaintEffect::PreferNoSchedule));
+        let plain = node("node_plain", 8, 4);
+        let nodes = vec![gpu, spot, plain];
+
+        // Untolerated NoSchedule excludes the GPU node; PreferNoSchedule
+        // ranks the spot node behind the smaller untainted node.
+        assert_eq!(
+            select_node(&nodes, GIB, 1, &none).unwrap().node_id,
+            "node_plain"
+        );
+
+        let spot_ok = PlacementConstraints {
+            tolerations: vec![toleration("spot")],
+            ..Default::default()
+        };
+        assert_eq!(
+            select_node(&nodes, GIB, 1, &spot_ok).unwrap().node_id,
+            "node_spot"
+        );
+
+        let gpu_only = PlacementConstraints {
+            node_selector: BTreeMap::from([("accel".to_string(), "a100".to_string())]),
+            tolerations: vec![toleration("gpu")],
+            ..Default::default()
+        };
+        assert_eq!(
+            select_node(&nodes, GIB, 1, &gpu_only).unwrap().node_id,
+            "node_gpu"
+        );
+
+        let selector_only = PlacementConstraints {
+            node_selector: gpu_only.node_selector.clone(),
+            ..Default::default()
+        };
+        assert!(select_node(&nodes, GIB, 1, &selector_only).is_none());
+
+        let plan = plan_placements(
+            nodes,
+            &[PlacementDemand {
+                constraints: selector_only,
+                ..demand("gpu", 1)
+            }],
+        );
+        assert_eq!(plan.unplaced[0].reason, InfeasibleReason::NoMatchingNodes);
+    }
+
+    #[test]
+    fn pinned_process_types_only_run_on_their_volume_node() {
+        let nodes = vec![node("node_big", 64, 32), node("node_small", 8, 4)];
+        let pinned = PlacementConstraints {
+            pinned_node_id: Some("node_small".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            select_node(&nodes, GIB, 1, &pinned).unwrap().node_id,
+            "node_small"
+        );
+
+        let gone = PlacementConstraints {
+            pinned_node_id: Some("node_gone".to_string()),
+            ..Default::default()
+        };
+        assert!(select_node(&nodes, GIB, 1, &gone).is_none());
+
+        let plan = plan_placements(
+            nodes,
+            &[PlacementDemand {
+                constraints: gone,
+                ..demand("db", 1)
+            }],
+        );
+        assert_eq!(
+            plan.unplaced[0].reason,
+            InfeasibleReason::VolumeLocalityNoNode
+        );
+    }
+}
@@ -1,22 +1,64 @@
 //! Scheduler reconciler for instance allocation.
 //!
 //! The reconciler is responsible for:
-//! - Reading desired state from env_desired_releases_view and env_scale_view
+//! - Reading desired state from env_desired_releases_view and env_scale_view,
+//!   with per-process resource overrides from env_process_resources_view
 //! - Computing what instances should exist
 //! - Allocating instances to nodes based on capacity
 //! - Emitting instance.allocated and instance.desired_state_changed events
+//! - Pacing rollouts (max surge/unavailable per process type) and honoring
+//!   paused/promoted deploys
+//! - Halting rollouts whose new instances exceed the deploy failure budget
+//! - Reporting rollouts that stay unconverged past the convergence deadline
+//!   (`deploy.rollout_stuck`)
+//!
+//! Configuration:
+//! - `PLFM_DEPLOY_FAILURE_BUDGET`: failed instances tolerated per deploy
+//!   before its rollout is halted (default 3, `0` disables the budget)
+//! - `PLFM_CONVERGENCE_DEADLINE_SECS`: seconds a rolling process type may
+//!   stay unconverged before it is reported stuck (default 900, `0` disables)
 //!
 //! See: docs/specs/scheduler/reconciliation-loop.md
 
-use plfm_events::{ActorType, AggregateType};
+use plfm_events::{ActorType, AggregateType, SecretsReload, Toleration};
 use plfm_id::{AppId, EnvId, InstanceId, OrgId, ReleaseId, RequestId};
+use plfm_reconcile::{
+    select_for_drain, ConvergenceCheck, ConvergenceStatus, ConvergenceTracker, DrainPriority,
+    RollingStrategy, DEFAULT_CONVERGENCE_DEADLINE,
+};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use std::collections::{BTreeMap, HashSet};
 use std::net::Ipv6Addr;
+use std::sync::Mutex;
+use std::time::Duration;
 use tracing::{debug, info, instrument, warn};
 
+use crate::aggregates::env::{ProcessResources, DEFAULT_EPHEMERAL_DISK_BYTES};
 use crate::db::{AppendEvent, EventStore};
 
+use super::placement::{self, NodeCapacity, PlacementConstraints, PlacementDemand, PlacementPlan};
+
+/// Failed instances tolerated per deploy when `PLFM_DEPLOY_FAILURE_BUDGET` is unset.
+const DEFAULT_DEPLOY_FAILURE_BUDGET: u32 = 3;
+
+/// Failed instances tolerated per deploy before its rollout is halted.
+pub fn deploy_failure_budget() -> u32 {
+    std::env::var("PLFM_DEPLOY_FAILURE_BUDGET")
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(DEFAULT_DEPLOY_FAILURE_BUDGET)
+}
+
+/// Time a rolling process type may stay unconverged before it is reported stuck.
+pub fn convergence_deadline() -> Duration {
+    std::env::var("PLFM_CONVERGENCE_DEADLINE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_CONVERGENCE_DEADLINE)
+}
+
 /// Result type for scheduler operations.
 pub type SchedulerResult<T> = Result<T, SchedulerError>;
 
@@ -47,7 +89,117 @@ pub struct GroupDesiredState {
     pub deploy_id: Option<String>,
     pub desired_replicas: i32,
     pub spec_hash: String,
+    /// Hash the group had when the secrets version was part of the spec, so
+    /// instances allocated before in-place config updates still match.
+    pub legacy_spec_hash: String,
+    /// Not part of `spec_hash`; see `secrets_reload`.
     pub secrets_version_id: Option<String>,
+    /// How a new secrets version reaches instances: replacement (`restart`)
+    /// or in-place delivery.
+    pub secrets_reload: SecretsReload,
+    /// Current config vars version of the env, if any are set. Part of
+    /// `spec_hash`; in-place reload modes deliver it like the secrets.
+    pub config_vars_version: Option<i32>,
+    /// Spec hash inputs kept to rehash with an instance's config vars version.
+    pub volume_hash: String,
+    pub restart_generation: i32,
+    /// Desired deploy is paused; replacement of old instances is frozen.
+    pub rollout_paused: bool,
+    /// Desired deploy was promoted; old instances are replaced without surge limits.
+    pub rollout_promoted: bool,
+    /// Desired deploy is queued or rolling, so its failure budget and the
+    /// convergence deadline apply.
+    pub rollout_active: bool,
+    /// Desired deploy exceeded its failure budget; no new instances are created.
+    pub rollout_halted: bool,
+    /// Node selector and tolerations from the env scale.
+    pub constraints: PlacementConstraints,
+    /// Scratch disk per instance: the resource override, else the env scale.
+    pub ephemeral_disk_bytes: i64,
+    /// Resource overrides for the process type; unset cpu/memory fall back
+    /// to the release.
+    pub resources: ProcessResources,
+    /// Rollout surge/unavailability limits from the env scale.
+    pub strategy: RollingStrategy,
+}
+
+impl GroupDesiredState {
+    /// Whether an instance runs this group's spec (replacement not required).
+    /// In `restart` reload mode a different secrets or config vars version
+    /// requires replacement; in-place modes also accept the spec as hashed
+    /// with the instance's own config vars version.
+    fn matches_spec(&self, instance: &InstanceState) -> bool {
+        if !self.secrets_reload.in_place() {
+            return (instance.spec_hash == self.spec_hash
+                || instance.spec_hash == self.legacy_spec_hash)
+                && !self.secrets_changed(instance);
+        }
+        instance.spec_hash == self.spec_hash
+            || instance.spec_hash == self.legacy_spec_hash
+            || instance.spec_hash == self.spec_hash_with(instance.config_vars_version)
+    }
+
+    /// Whether a matching instance needs the group's config delivered in
+    /// place. An update the instance already has as its target is not
+    /// repeated.
+    fn needs_config_update(&self, instance: &InstanceState) -> bool {
+        instance.desired_state == "running"
+            && self.secrets_reload.in_place()
+            && (self.secrets_changed(instance) || self.config_vars_changed(instance))
+            && instance.config_target.as_ref() != Some(&self.config_target(instance))
+    }
+
+    /// Config an in-place update moves the instance to. Versions that did
+    /// not change stay as the instance has them.
+    fn config_target(&self, instance: &InstanceState) -> ConfigTarget {
+        ConfigTarget {
+            secrets_version_id: if self.secrets_changed(instance) {
+                self.secrets_version_id.clone()
+            } else {
+                instance.secrets_version_id.clone()
+            },
+            config_vars_version: if self.config_vars_changed(instance) {
+                self.config_vars_version
+            } else {
+                instance.config_vars_version
+            },
+        }
+    }
+
+    /// Whether the group has a newer secrets version than the instance.
+    /// Removing every secret leaves instances with their last version.
+    fn secrets_changed(&self, instance: &InstanceState) -> bool {
+        self.secrets_version_id.is_some() && instance.secrets_version_id != self.secrets_version_id
+    }
+
+    /// Whether the group has a newer config vars version than the instance.
+    fn config_vars_changed(&self, instance: &InstanceState) -> bool {
+        self.config_vars_version.is_some()
+            && instance.config_vars_version != self.config_vars_version
+    }
+
+    /// The group's spec hash with another config vars version.
+    fn spec_hash_with(&self, config_vars_version: Option<i32>) -> String {
+        compute_spec_hash(
+            &self.release_id,
+            &self.process_type,
+            &self.volume_hash,
+            self.ephemeral_disk_bytes,
+            self.restart_generation,
+            config_vars_version,
+            &self.resources,
+        )
+    }
+
+    /// Convergence tracking key; a new deploy starts a new episode.
+    fn convergence_key(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.env_id,
+            self.process_type,
+            self.deploy_id.as_deref().unwrap_or("")
+        )
+    }
 }
 
 /// Current instance state.
@@ -58,31 +210,53 @@ pub struct InstanceState {
     pub node_id: String,
     pub desired_state: String,
     pub spec_hash: String,
+    pub secrets_version_id: Option<String>,
+    pub config_vars_version: Option<i32>,
+    /// Target of the instance's latest `instance.config_updated`, if any.
+    pub config_target: Option<ConfigTarget>,
+    /// Last reported status (None until the agent reports).
+    pub status: Option<String>,
     #[allow(dead_code)]
     pub release_id: String,
 }
 
-/// Node capacity for placement decisions.
-#[derive(Debug, Clone)]
-pub struct NodeCapacity {
-    pub node_id: String,
-    pub state: String,
-    pub allocatable_memory_bytes: i64,
-    pub allocatable_cpu_cores: i32,
-    pub available_memory_bytes: i64,
-    pub available_cpu_cores: i32,
-    pub instance_count: i32,
+impl InstanceState {
+    fn is_ready(&self) -> bool {
+        self.status.as_deref() == Some("ready")
+    }
+
+    fn drain_priority(&self) -> DrainPriority {
+        match self.status.as_deref() {
+            Some("failed") => DrainPriority::Failed,
+            Some("ready") => DrainPriority::Oldest,
+            _ => DrainPriority::NotReady,
+        }
+    }
+}
+
+/// Config versions delivered to a running instance in place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigTarget {
+    pub secrets_version_id: Option<String>,
+    pub config_vars_version: Option<i32>,
 }
 
 /// The scheduler reconciler.
 pub struct SchedulerReconciler {
     pool: PgPool,
+    failure_budget: u32,
+    /// Time each rolling group has spent unconverged, keyed by [`GroupDesiredState::convergence_key`].
+    convergence: Mutex<ConvergenceTracker>,
 }
 
 impl SchedulerReconciler {
     /// Create a new scheduler reconciler.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            failure_budget: deploy_failure_budget(),
+            convergence: Mutex::new(ConvergenceTracker::new(convergence_deadline())),
+        }
     }
 
     /// Run a single reconciliation pass for all groups.
@@ -94,12 +268,38 @@ impl SchedulerReconciler {
         let groups = self.get_all_groups().await?;
         debug!(group_count = groups.len(), "Found groups to reconcile");
 
-        for group in groups {
+        // Deploys halted earlier in this pass; their other process types are
+        // held without re-checking (the deploys projection may lag).
+        let mut halted_deploys = HashSet::new();
+
+        // Groups that no longer exist (or whose deploy changed) stop being tracked.
+        let group_keys: HashSet<String> = groups.iter().map(|g| g.convergence_key()).collect();
+        self.convergence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|key| group_keys.contains(key));
+
+        for mut group in groups {
+            if group
+                .deploy_id
+                .as_ref()
+                .is_some_and(|deploy_id| halted_deploys.contains(deploy_id))
+            {
+                group.rollout_halted = true;
+            }
             match self.reconcile_group(&group).await {
                 Ok(group_stats) => {
                     stats.groups_processed += 1;
                     stats.instances_allocated += group_stats.instances_allocated;
                     stats.instances_drained += group_stats.instances_drained;
+                    stats.instances_config_updated += group_stats.instances_config_updated;
+                    if let Some(deploy_id) = group_stats.halted_deploy {
+                        stats.deploys_halted += 1;
+                        halted_deploys.insert(deploy_id);
+                    }
+                    if group_stats.rollout_stuck {
+                        stats.rollouts_stuck += 1;
+                    }
                 }
                 Err(e) => {
                     warn!(
@@ -118,6 +318,9 @@ impl SchedulerReconciler {
             groups_failed = stats.groups_failed,
             instances_allocated = stats.instances_allocated,
             instances_drained = stats.instances_drained,
+            instances_config_updated = stats.instances_config_updated,
+            deploys_halted = stats.deploys_halted,
+            rollouts_stuck = stats.rollouts_stuck,
             "Reconciliation pass complete"
         );
 
@@ -136,13 +339,31 @@ impl SchedulerReconciler {
                 r.process_type,
                 r.release_id,
                 r.deploy_id,
+                r.restart_generation,
                 COALESCE(s.desired_replicas, 1) as desired_replicas,
-                sb.current_version_id as secrets_version_id
+                sb.current_version_id as secrets_version_id,
+                cv.version as config_vars_version,
+                d.status as deploy_status,
+                COALESCE(d.promoted, false) as deploy_promoted,
+                COALESCE(s.node_selector, '{}'::jsonb) as node_selector,
+                COALESCE(s.tolerations, '[]'::jsonb) as tolerations,
+                COALESCE(pr.ephemeral_disk_bytes, s.ephemeral_disk_bytes) as ephemeral_disk_bytes,
+                pr.cpu_request,
+                pr.memory_limit_bytes,
+                s.max_surge,
+                s.max_unavailable,
+                s.secrets_reload
             FROM env_desired_releases_view r
             LEFT JOIN env_scale_view s
                 ON r.env_id = s.env_id AND r.process_type = s.process_type
+            LEFT JOIN env_process_resources_view pr
+                ON r.env_id = pr.env_id AND r.process_type = pr.process_type
             LEFT JOIN secret_bundles_view sb
-                ON r.env_id = sb.env_id
+                ON r.env_id = sb.env_id AND sb.archived_at IS NULL
+            LEFT JOIN env_config_vars_view cv
+                ON r.env_id = cv.env_id
+            LEFT JOIN deploys_view d
+                ON r.deploy_id = d.deploy_id
             "#,
         )
         .fetch_all(&self.pool)
@@ -166,11 +387,43 @@ impl SchedulerReconciler {
             } else {
                 row.desired_replicas
             };
+            let desired_replicas =
+                if has_volumes && self.volume_restoring(&env_id, &row.process_type).await? {
+                    debug!(
+                        env_id = %env_id,
+                        process_type = %row.process_type,
+                        "Holding group at 0 replicas while an attached volume is restored in place"
+                    );
+                    0
+                } else {
+                    desired_replicas
+                };
+            let ephemeral_disk_bytes = row
+                .ephemeral_disk_bytes
+                .unwrap_or(DEFAULT_EPHEMERAL_DISK_BYTES);
+            let resources = ProcessResources {
+                cpu_request: row.cpu_request,
+                memory_limit_bytes: row.memory_limit_bytes,
+                ephemeral_disk_bytes: row.ephemeral_disk_bytes,
+            };
             let spec_hash = compute_spec_hash(
+                &release_id,
+                &row.process_type,
+                &volume_hash,
+                ephemeral_disk_bytes,
+                row.restart_generation,
+                row.config_vars_version,
+                &resources,
+            );
+            let legacy_spec_hash = compute_legacy_spec_hash(
                 &release_id,
                 &row.process_type,
                 row.secrets_version_id.as_deref(),
                 &volume_hash,
+                ephemeral_disk_bytes,
+                row.restart_generation,
+                row.config_vars_version,
+                &resources,
             );
             groups.push(GroupDesiredState {
                 org_id: row.org_id.parse().unwrap_or_else(|_| OrgId::new()),
@@ -181,7 +434,33 @@ impl SchedulerReconciler {
                 deploy_id: row.deploy_id,
                 desired_replicas,
                 spec_hash,
+                legacy_spec_hash,
                 secrets_version_id: row.secrets_version_id,
+                secrets_reload: row
+                    .secrets_reload
+                    .and_then(|reload| serde_json::from_value(reload).ok())
+                    .unwrap_or_default(),
+                config_vars_version: row.config_vars_version,
+                volume_hash,
+                restart_generation: row.restart_generation,
+                rollout_paused: row.deploy_status.as_deref() == Some("paused"),
+                rollout_promoted: row.deploy_promoted,
+                rollout_active: matches!(row.deploy_status.as_deref(), Some("queued" | "rolling")),
+                rollout_halted: row.deploy_status.as_deref() == Some("halted"),
+                constraints: PlacementConstraints {
+                    pinned_node_id: if has_volumes {
+                        self.volume_home_node(&env_id, &row.process_type).await?
+                    } else {
+                        None
+                    },
+                    ..placement_constraints(row.node_selector, row.tolerations)
+                },
+                ephemeral_disk_bytes,
+                resources,
+                strategy: rolling_strategy(
+                    row.max_surge.as_deref(),
+                    row.max_unavailable.as_deref(),
+                ),
             });
         }
 
@@ -199,11 +478,11 @@ impl SchedulerReconciler {
         // Partition instances
         let matching: Vec<_> = current_instances
             .iter()
-            .filter(|i| i.desired_state != "stopped" && i.spec_hash == group.spec_hash)
+            .filter(|i| i.desired_state != "stopped" && group.matches_spec(i))
             .collect();
         let old: Vec<_> = current_instances
             .iter()
-            .filter(|i| i.desired_state != "stopped" && i.spec_hash != group.spec_hash)
+            .filter(|i| i.desired_state != "stopped" && !group.matches_spec(i))
             .collect();
         let running_count = matching.len() + old.len();
 
@@ -212,80 +491,353 @@ impl SchedulerReconciler {
             matching = matching.len(),
             old = old.len(),
             total_running = running_count,
+            paused = group.rollout_paused,
+            promoted = group.rollout_promoted,
+            halted = group.rollout_halted,
             "Group instance state"
         );
 
-        // Scale up: need more matching instances
-        let matching_count = matching.len() as i32;
-        if matching_count < group.desired_replicas {
-            let to_create = group.desired_replicas - matching_count;
-            for _ in 0..to_create {
-                match self.allocate_instance(group).await {
-                    Ok(instance_id) => {
-                        info!(
-                            instance_id = %instance_id,
-                            env_id = %group.env_id,
-                            process_type = %group.process_type,
-                            "Allocated new instance"
-                        );
-                        stats.instances_allocated += 1;
-                    }
-                    Err(e) => {
-                        warn!(error = %e, "Failed to allocate instance");
-                        // Don't fail the whole group, continue with what we have
-                    }
-                }
-            }
-        }
-
-        // Drain old instances (ones with wrong spec_hash)
-        for instance in &old {
-            match self.drain_instance(instance).await {
-                Ok(_) => {
+        // Non-disruptive changes reach instances that keep running; this
+        // is independent of rollout pacing, so it happens even when held.
+        for instance in matching.iter().filter(|i| group.needs_config_update(i)) {
+            match self.update_instance_config(group, instance).await {
+                Ok(()) => {
                     info!(
                         instance_id = %instance.instance_id,
-                        "Draining old instance"
+                        secrets_version_id = ?group.secrets_version_id,
+                        config_vars_version = ?group.config_vars_version,
+                        "Delivering config update in place"
                     );
-                    stats.instances_drained += 1;
+                    stats.instances_config_updated += 1;
                 }
                 Err(e) => {
                     warn!(
                         instance_id = %instance.instance_id,
                         error = %e,
-                        "Failed to drain instance"
+                        "Failed to record config update"
                     );
                 }
             }
         }
 
+        if group.rollout_halted {
+            debug!("Rollout halted by failure budget; holding group");
+            return Ok(stats);
+        }
+
+        // Failure budget: only while the new spec is still converging.
+        let converging = !old.is_empty()
+            || matching.iter().filter(|i| i.is_ready()).count() < group.desired_replicas as usize;
+
+        // Convergence deadline: report rollouts that stop making progress.
+        let check = self
+            .convergence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .observe(
+                &group.convergence_key(),
+                rollout_convergence(group, converging),
+            );
+        if check.newly_diverged {
+            if let Some(deploy_id) = &group.deploy_id {
+                self.report_stuck_rollout(group, deploy_id, &check).await?;
+                stats.rollout_stuck = true;
+            }
+        }
+
+        if converging && group.rollout_active && self.failure_budget > 0 {
+            if let Some(deploy_id) = &group.deploy_id {
+                let failures = self.deploy_failures(deploy_id).await?;
+                if let Some(halt) = evaluate_failure_budget(&failures, self.failure_budget) {
+                    self.halt_deploy(group, deploy_id, &halt).await?;
+                    stats.halted_deploy = Some(deploy_id.clone());
+                    return Ok(stats);
+                }
+            }
+        }
+
+        // Rollout in progress: pace replacement unless the deploy was promoted.
+        if !old.is_empty() && !group.rollout_promoted {
+            if group.rollout_paused {
+                debug!("Rollout paused; holding group");
+                return Ok(stats);
+            }
+            self.rolling_step(group, &matching, &old, &mut stats).await;
+            return Ok(stats);
+        }
+
+        // Scale up: need more matching instances
+        let matching_count = matching.len() as i32;
+        if matching_count < group.desired_replicas {
+            let to_create = group.desired_replicas - matching_count;
+            self.allocate_instances(group, to_create as u32, &mut stats)
+                .await;
+        }
+
+        // Drain old instances (ones with wrong spec_hash)
+        self.drain_instances(old, "Draining old instance", &mut stats)
+            .await;
+
         // Scale down: too many matching instances
         if matching_count > group.desired_replicas {
             let to_drain = (matching_count - group.desired_replicas) as usize;
             // Drain oldest instances first (by instance_id which is ULID-based)
-            let mut to_drain_instances: Vec<_> = matching.iter().collect();
+            let mut to_drain_instances: Vec<_> = matching.clone();
             to_drain_instances.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+            to_drain_instances.truncate(to_drain);
 
-            for instance in to_drain_instances.into_iter().take(to_drain) {
-                match self.drain_instance(instance).await {
-                    Ok(_) => {
-                        info!(
-                            instance_id = %instance.instance_id,
-                            "Draining excess instance (scale down)"
-                        );
-                        stats.instances_drained += 1;
-                    }
-                    Err(e) => {
-                        warn!(
-                            instance_id = %instance.instance_id,
-                            error = %e,
-                            "Failed to drain instance"
-                        );
-                    }
+            self.drain_instances(
+                to_drain_instances,
+                "Draining excess instance (scale down)",
+                &mut stats,
+            )
+            .await;
+        }
+
+        Ok(stats)
+    }
+
+    /// Count failed instances of a deploy by reason code.
+    async fn deploy_failures(&self, deploy_id: &str) -> SchedulerResult<Vec<(String, i64)>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT COALESCE(st.reason_code, 'unspecified') AS reason_code,
+                   COUNT(*) AS failures
+            FROM instances_desired_view d
+            JOIN instances_status_view st ON st.instance_id = d.instance_id
+            WHERE d.deploy_id = $1 AND st.status = 'failed'
+            GROUP BY 1
+            "#,
+        )
+        .bind(deploy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Mark a deploy `halted` with its aggregated failure reasons.
+    async fn halt_deploy(
+        &self,
+        group: &GroupDesiredState,
+        deploy_id: &str,
+        halt: &FailureBudgetHalt,
+    ) -> SchedulerResult<()> {
+        let event_store = EventStore::new(self.pool.clone());
+        let current_seq = event_store
+            .get_latest_aggregate_seq(&AggregateType::Deploy, deploy_id)
+            .await
+            .map_err(|e| SchedulerError::EventStore(e.to_string()))?
+            .unwrap_or(0);
+
+        let message = format!(
+            "Rollout halted: {} instances failed (failure budget {})",
+            halt.failed, self.failure_budget
+        );
+        warn!(
+            deploy_id = %deploy_id,
+            failed = halt.failed,
+            budget = self.failure_budget,
+            reasons = %halt.reasons,
+            "Deploy exceeded failure budget; halting rollout"
+        );
+
+        let event = AppendEvent {
+            aggregate_type: AggregateType::Deploy,
+            aggregate_id: deploy_id.to_string(),
+            aggregate_seq: current_seq + 1,
+            event_type: "deploy.status_changed".to_string(),
+            event_version: 1,
+            actor_type: ActorType::System,
+            actor_id: "scheduler".to_string(),
+            org_id: Some(group.org_id),
+            request_id: RequestId::new().to_string(),
+            idempotency_key: None,
+            app_id: Some(group.app_id),
+            env_id: Some(group.env_id),
+            correlation_id: Some(deploy_id.to_string()),
+            causation_id: None,
+            payload: serde_json::json!({
+                "deploy_id": deploy_id,
+                "org_id": group.org_id.to_string(),
+                "env_id": group.env_id.to_string(),
+                "status": "halted",
+                "message": message,
+                "failed_reason": halt.reasons,
+                "updated_at": chrono::Utc::now().to_rfc3339(),
+            }),
+            ..Default::default()
+        };
+
+        event_store
+            .append(event)
+            .await
+            .map_err(|e| SchedulerError::EventStore(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Record that a process type of a deploy has not converged within the deadline.
+    ///
+    /// The deploy keeps rolling; the event only surfaces it to operators.
+    async fn report_stuck_rollout(
+        &self,
+        group: &GroupDesiredState,
+        deploy_id: &str,
+        check: &ConvergenceCheck,
+    ) -> SchedulerResult<()> {
+        let event_store = EventStore::new(self.pool.clone());
+        let current_seq = event_store
+            .get_latest_aggregate_seq(&AggregateType::Deploy, deploy_id)
+            .await
+            .map_err(|e| SchedulerError::EventStore(e.to_string()))?
+            .unwrap_or(0);
+
+        let deadline = self
+            .convergence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .deadline();
+        let message = format!(
+            "Rollout stuck: process type '{}' has not converged after {}s (deadline {}s)",
+            group.process_type,
+            check.time_in_state.as_secs(),
+            deadline.as_secs()
+        );
+        warn!(
+            deploy_id = %deploy_id,
+            converging_secs = check.time_in_state.as_secs(),
+            deadline_secs = deadline.as_secs(),
+            "Rollout exceeded convergence deadline"
+        );
+
+        let event = AppendEvent {
+            aggregate_type: AggregateType::Deploy,
+            aggregate_id: deploy_id.to_string(),
+            aggregate_seq: current_seq + 1,
+            event_type: "deploy.rollout_stuck".to_string(),
+            event_version: 1,
+            actor_type: ActorType::System,
+            actor_id: "scheduler".to_string(),
+            org_id: Some(group.org_id),
+            request_id: RequestId::new().to_string(),
+            idempotency_key: None,
+            app_id: Some(group.app_id),
+            env_id: Some(group.env_id),
+            correlation_id: Some(deploy_id.to_string()),
+            causation_id: None,
+            payload: serde_json::json!({
+                "deploy_id": deploy_id,
+                "org_id": group.org_id.to_string(),
+                "env_id": group.env_id.to_string(),
+                "process_type": group.process_type,
+                "converging_secs": check.time_in_state.as_secs(),
+                "deadline_secs": deadline.as_secs(),
+                "message": message,
+            }),
+            ..Default::default()
+        };
+
+        event_store
+            .append(event)
+            .await
+            .map_err(|e| SchedulerError::EventStore(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Advance a rollout by one surge-limited step.
+    ///
+    /// New instances are started up to the group's `max_surge` above desired,
+    /// and old instances are drained only while no more than
+    /// `max_unavailable` fall short of desired.
+    async fn rolling_step(
+        &self,
+        group: &GroupDesiredState,
+        matching: &[&InstanceState],
+        old: &[&InstanceState],
+        stats: &mut GroupStats,
+    ) {
+        let matching_ready = matching.iter().filter(|i| i.is_ready()).count() as u32;
+        let matching_pending = matching.len() as u32 - matching_ready;
+        let old_running: Vec<&InstanceState> = old
+            .iter()
+            .filter(|i| i.desired_state == "running")
+            .copied()
+            .collect();
+
+        let (to_start, to_drain) = group.strategy.calculate_actions(
+            group.desired_replicas.max(0) as u32,
+            matching_ready,
+            matching_pending,
+            old_running.len() as u32,
+        );
+
+        debug!(
+            matching_ready,
+            matching_pending,
+            old_running = old_running.len(),
+            to_start,
+            to_drain,
+            "Rolling step"
+        );
+
+        self.allocate_instances(group, to_start, stats).await;
+
+        let mut drain_order = select_for_drain(old_running, |i| i.drain_priority());
+        drain_order.truncate(to_drain as usize);
+        self.drain_instances(drain_order, "Draining old instance (rolling)", stats)
+            .await;
+    }
+
+    /// Allocate `count` new instances for a group, logging failures.
+    async fn allocate_instances(
+        &self,
+        group: &GroupDesiredState,
+        count: u32,
+        stats: &mut GroupStats,
+    ) {
+        for _ in 0..count {
+            match self.allocate_instance(group).await {
+                Ok(instance_id) => {
+                    info!(
+                        instance_id = %instance_id,
+                        env_id = %group.env_id,
+                        process_type = %group.process_type,
+                        "Allocated new instance"
+                    );
+                    stats.instances_allocated += 1;
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to allocate instance");
+                    // Don't fail the whole group, continue with what we have
                 }
             }
         }
+    }
 
-        Ok(stats)
+    /// Drain the given instances, logging failures.
+    async fn drain_instances<'a>(
+        &self,
+        instances: impl IntoIterator<Item = &'a InstanceState>,
+        reason: &str,
+        stats: &mut GroupStats,
+    ) {
+        for instance in instances {
+            match self.drain_instance(instance).await {
+                Ok(_) => {
+                    info!(instance_id = %instance.instance_id, "{reason}");
+                    stats.instances_drained += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        instance_id = %instance.instance_id,
+                        error = %e,
+                        "Failed to drain instance"
+                    );
+                }
+            }
+        }
     }
 
     /// Get current instances for a group.
@@ -295,10 +847,14 @@ impl SchedulerReconciler {
     ) -> SchedulerResult<Vec<InstanceState>> {
         let rows = sqlx::query_as::<_, InstanceRow>(
             r#"
-            SELECT instance_id, node_id, desired_state, spec_hash, release_id
-            FROM instances_desired_view
-            WHERE env_id = $1 AND process_type = $2 AND desired_state != 'stopped'
-            ORDER BY created_at
+            SELECT d.instance_id, d.node_id, d.desired_state, d.spec_hash, d.release_id,
+                   d.secrets_version_id, d.config_vars_version,
+                   d.config_target_secrets_version_id, d.config_target_config_vars_version,
+                   st.status
+            FROM instances_desired_view d
+            LEFT JOIN instances_status_view st ON st.instance_id = d.instance_id
+            WHERE d.env_id = $1 AND d.process_type = $2 AND d.desired_state != 'stopped'
+            ORDER BY d.created_at
             "#,
         )
         .bind(group.env_id.to_string())
@@ -313,6 +869,15 @@ impl SchedulerReconciler {
                 node_id: r.node_id,
                 desired_state: r.desired_state,
                 spec_hash: r.spec_hash,
+                secrets_version_id: r.secrets_version_id,
+                config_vars_version: r.config_vars_version,
+                config_target: (r.config_target_secrets_version_id.is_some()
+                    || r.config_target_config_vars_version.is_some())
+                .then(|| ConfigTarget {
+                    secrets_version_id: r.config_target_secrets_version_id,
+                    config_vars_version: r.config_target_config_vars_version,
+                }),
+                status: r.status,
                 release_id: r.release_id,
             })
             .collect())
@@ -323,14 +888,23 @@ impl SchedulerReconciler {
         let request_id = RequestId::new();
         let instance_id = InstanceId::new();
 
-        // Get release info for resources
+        // Release resources, with the process type's overrides on top
         let release_info = self.get_release_info(&group.release_id).await?;
-        let required_cpu_cores = release_info.cpu.max(1.0).ceil() as i32;
-        let required_memory_bytes = release_info.memory_bytes;
+        let cpu = group.resources.cpu_request.unwrap_or(release_info.cpu);
+        let memory_bytes = group
+            .resources
+            .memory_limit_bytes
+            .unwrap_or(release_info.memory_bytes);
+        let required_cpu_cores = cpu.max(1.0).ceil() as i32;
+        let required_memory_bytes = memory_bytes;
 
         // Find best node for placement
         let node = self
-            .find_best_node(required_memory_bytes, required_cpu_cores)
+            .find_best_node(
+                required_memory_bytes,
+                required_cpu_cores,
+                &group.constraints,
+            )
             .await?;
         debug!(
             node_id = %node.node_id,
@@ -349,8 +923,9 @@ impl SchedulerReconciler {
         let overlay_ipv6 = self.allocate_instance_ipv6(&instance_id).await?;
 
         let resources_snapshot = serde_json::json!({
-            "cpu": release_info.cpu,
-            "memory_bytes": release_info.memory_bytes,
+            "cpu": cpu,
+            "memory_bytes": memory_bytes,
+            "ephemeral_disk_bytes": group.ephemeral_disk_bytes,
         });
 
         // Create instance.allocated event
@@ -375,6 +950,7 @@ impl SchedulerReconciler {
                 "process_type": group.process_type,
                 "release_id": group.release_id.to_string(),
                 "secrets_version_id": group.secrets_version_id,
+                "config_vars_version": group.config_vars_version,
                 "overlay_ipv6": overlay_ipv6,
                 "resources_snapshot": resources_snapshot,
                 "spec_hash": group.spec_hash,
@@ -392,6 +968,61 @@ impl SchedulerReconciler {
         Ok(instance_id)
     }
 
+    /// Record an in-place config update for a running instance.
+    ///
+    /// The projection bumps the instance's config generation; its node
+    /// agent then pushes the new config to guest-init without a restart.
+    /// Versions that did not change are sent as the instance has them; the
+    /// spec hash moves with the config vars version so the instance keeps
+    /// matching the group.
+    async fn update_instance_config(
+        &self,
+        group: &GroupDesiredState,
+        instance: &InstanceState,
+    ) -> SchedulerResult<()> {
+        let target = group.config_target(instance);
+        let event_store = EventStore::new(self.pool.clone());
+        let current_seq = event_store
+            .get_latest_aggregate_seq(&AggregateType::Instance, &instance.instance_id)
+            .await
+            .map_err(|e| SchedulerError::EventStore(e.to_string()))?
+            .unwrap_or(0);
+
+        let event = AppendEvent {
+            aggregate_type: AggregateType::Instance,
+            aggregate_id: instance.instance_id.clone(),
+            aggregate_seq: current_seq + 1,
+            event_type: "instance.config_updated".to_string(),
+            event_version: 1,
+            actor_type: ActorType::System,
+            actor_id: "scheduler".to_string(),
+            org_id: Some(group.org_id),
+            request_id: RequestId::new().to_string(),
+            idempotency_key: None,
+            app_id: Some(group.app_id),
+            env_id: Some(group.env_id),
+            correlation_id: None,
+            causation_id: None,
+            payload: serde_json::json!({
+                "instance_id": instance.instance_id,
+                "org_id": group.org_id.to_string(),
+                "env_id": group.env_id.to_string(),
+                "secrets_version_id": target.secrets_version_id,
+                "config_vars_version": target.config_vars_version,
+                "spec_hash": group.spec_hash_with(target.config_vars_version),
+                "reason": config_update_reason(group, instance),
+            }),
+            ..Default::default()
+        };
+
+        event_store
+            .append(event)
+            .await
+            .map_err(|e| SchedulerError::EventStore(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Drain an instance.
     async fn drain_instance(&self, instance: &InstanceState) -> SchedulerResult<()> {
         if instance.desired_state == "draining" {
@@ -445,9 +1076,22 @@ impl SchedulerReconciler {
         &self,
         required_memory_bytes: i64,
         required_cpu_cores: i32,
+        constraints: &PlacementConstraints,
     ) -> SchedulerResult<NodeCapacity> {
-        // Get all active nodes with their capacity
-        let nodes = sqlx::query_as::<_, NodeCapacityRow>(
+        let nodes = self.active_nodes().await?;
+        placement::select_node(
+            &nodes,
+            required_memory_bytes,
+            required_cpu_cores,
+            constraints,
+        )
+        .cloned()
+        .ok_or(SchedulerError::NoEligibleNodes)
+    }
+
+    /// Load capacity for all active nodes.
+    async fn active_nodes(&self) -> SchedulerResult<Vec<NodeCapacity>> {
+        let rows = sqlx::query_as::<_, NodeCapacityRow>(
             r#"
             SELECT
                 n.node_id,
@@ -464,43 +1108,20 @@ impl SchedulerReconciler {
                     (n.allocatable->>'cpu_cores')::INT,
                     0
                 ) as available_cpu_cores,
-                COALESCE((n.allocatable->>'instance_count')::INT, 0) as instance_count
+                COALESCE((n.allocatable->>'instance_count')::INT, 0) as instance_count,
+                n.labels,
+                n.taints
             FROM nodes_view n
             WHERE n.state = 'active'
-              AND COALESCE(
-                    (n.allocatable->>'available_memory_bytes')::BIGINT,
-                    (n.allocatable->>'memory_bytes')::BIGINT,
-                    0
-                ) >= $1
-              AND COALESCE(
-                    (n.allocatable->>'available_cpu_cores')::INT,
-                    (n.allocatable->>'cpu_cores')::INT,
-                    0
-                ) >= $2
-            ORDER BY
-                -- Prefer nodes with more available resources
-                COALESCE(
-                    (n.allocatable->>'available_memory_bytes')::BIGINT,
-                    (n.allocatable->>'memory_bytes')::BIGINT,
-                    0
-                ) DESC,
-                COALESCE(
-                    (n.allocatable->>'available_cpu_cores')::INT,
-                    (n.allocatable->>'cpu_cores')::INT,
-                    0
-                ) DESC,
-                -- Tie-break by node_id for determinism
-                n.node_id ASC
-            LIMIT 1
+            ORDER BY n.node_id ASC
             "#,
         )
-        .bind(required_memory_bytes)
-        .bind(required_cpu_cores)
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        match nodes {
-            Some(row) => Ok(NodeCapacity {
+        Ok(rows
+            .into_iter()
+            .map(|row| NodeCapacity {
                 node_id: row.node_id,
                 state: row.state,
                 allocatable_memory_bytes: row.allocatable_memory_bytes,
@@ -508,9 +1129,110 @@ impl SchedulerReconciler {
                 available_memory_bytes: row.available_memory_bytes,
                 available_cpu_cores: row.available_cpu_cores,
                 instance_count: row.instance_count,
-            }),
-            None => Err(SchedulerError::NoEligibleNodes),
+                labels: serde_json::from_value(row.labels).unwrap_or_default(),
+                taints: serde_json::from_value(row.taints).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Plan where an env's missing instances would land, without allocating.
+    ///
+    /// `overrides` replace the env's scale per process type. Each process type
+    /// needs `desired - running` new instances, planned against current node
+    /// capacity with the same node selection the reconciler uses.
+    pub async fn preview_placement(
+        &self,
+        env_id: &EnvId,
+        overrides: &BTreeMap<String, PreviewOverride>,
+    ) -> SchedulerResult<PlacementPreview> {
+        let rows = sqlx::query_as::<_, PreviewGroupRow>(
+            r#"
+            SELECT
+                r.process_type,
+                r.release_id,
+                COALESCE(s.desired_replicas, 1) as desired_replicas,
+                COALESCE(s.node_selector, '{}'::jsonb) as node_selector,
+                COALESCE(s.tolerations, '[]'::jsonb) as tolerations,
+                pr.cpu_request,
+                pr.memory_limit_bytes,
+                (
+                    SELECT COUNT(*)
+                    FROM instances_desired_view d
+                    WHERE d.env_id = r.env_id
+                      AND d.process_type = r.process_type
+                      AND d.desired_state = 'running'
+                ) as running
+            FROM env_desired_releases_view r
+            LEFT JOIN env_scale_view s
+                ON r.env_id = s.env_id AND r.process_type = s.process_type
+            LEFT JOIN env_process_resources_view pr
+                ON r.env_id = pr.env_id AND r.process_type = pr.process_type
+            WHERE r.env_id = $1
+            ORDER BY r.process_type ASC
+            "#,
+        )
+        .bind(env_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut groups = Vec::with_capacity(rows.len());
+        let mut demands = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut constraints = placement_constraints(row.node_selector, row.tolerations);
+            let requested = match overrides.get(&row.process_type) {
+                Some(o) => {
+                    if let Some(node_selector) = &o.node_selector {
+                        constraints.node_selector = node_selector.clone();
+                    }
+                    if let Some(tolerations) = &o.tolerations {
+                        constraints.tolerations = tolerations.clone();
+                    }
+                    o.desired
+                }
+                None => row.desired_replicas,
+            };
+            let (_, has_volumes) = self
+                .volume_hash_for_group(env_id, &row.process_type)
+                .await?;
+            let desired_replicas = if has_volumes {
+                constraints.pinned_node_id =
+                    self.volume_home_node(env_id, &row.process_type).await?;
+                if self.volume_restoring(env_id, &row.process_type).await? {
+                    0
+                } else {
+                    requested.min(1)
+                }
+            } else {
+                requested
+            };
+
+            let release_id: ReleaseId = row.release_id.parse().unwrap_or_else(|_| ReleaseId::new());
+            let release_info = self.get_release_info(&release_id).await?;
+            let running = i32::try_from(row.running).unwrap_or(i32::MAX);
+            let demand = PlacementDemand {
+                process_type: row.process_type.clone(),
+                count: (desired_replicas - running).max(0),
+                memory_bytes: row.memory_limit_bytes.unwrap_or(release_info.memory_bytes),
+                cpu_cores: row.cpu_request.unwrap_or(release_info.cpu).max(1.0).ceil() as i32,
+                constraints,
+            };
+
+            groups.push(PreviewGroup {
+                process_type: row.process_type,
+                release_id: row.release_id,
+                desired_replicas,
+                running,
+                new_instances: demand.count,
+                memory_bytes: demand.memory_bytes,
+                cpu_cores: demand.cpu_cores,
+            });
+            demands.push(demand);
         }
+
+        let nodes = self.active_nodes().await?;
+        let plan = placement::plan_placements(nodes, &demands);
+
+        Ok(PlacementPreview { groups, plan })
     }
 
     /// Get release info for resource calculations.
@@ -547,6 +1269,37 @@ impl SchedulerReconciler {
     }
 }
 
+/// Scale override for one process type in a placement preview.
+#[derive(Debug, Clone, Default)]
+pub struct PreviewOverride {
+    pub desired: i32,
+    /// Replaces the stored node selector when set.
+    pub node_selector: Option<BTreeMap<String, String>>,
+    /// Replaces the stored tolerations when set.
+    pub tolerations: Option<Vec<Toleration>>,
+}
+
+/// Dry-run placement for an env's process types.
+#[derive(Debug, Clone)]
+pub struct PlacementPreview {
+    pub groups: Vec<PreviewGroup>,
+    pub plan: PlacementPlan,
+}
+
+/// Per-process-type inputs to a placement preview.
+#[derive(Debug, Clone)]
+pub struct PreviewGroup {
+    pub process_type: String,
+    pub release_id: String,
+    pub desired_replicas: i32,
+    /// Instances already desired running for the group.
+    pub running: i32,
+    /// Instances the preview had to place.
+    pub new_instances: i32,
+    pub memory_bytes: i64,
+    pub cpu_cores: i32,
+}
+
 /// Statistics from a reconciliation pass.
 #[derive(Debug, Default, Clone)]
 pub struct ReconcileStats {
@@ -554,6 +1307,9 @@ pub struct ReconcileStats {
     pub groups_failed: i32,
     pub instances_allocated: i32,
     pub instances_drained: i32,
+    pub instances_config_updated: i32,
+    pub deploys_halted: i32,
+    pub rollouts_stuck: i32,
 }
 
 /// Statistics from reconciling a single group.
@@ -561,6 +1317,85 @@ pub struct ReconcileStats {
 struct GroupStats {
     instances_allocated: i32,
     instances_drained: i32,
+    instances_config_updated: i32,
+    /// Deploy halted by this group's failure budget check.
+    halted_deploy: Option<String>,
+    /// This group's rollout was reported stuck in this pass.
+    rollout_stuck: bool,
+}
+
+/// Convergence status of a group for deadline tracking.
+///
+/// Only active (queued or rolling) deploys are timed; a paused rollout is
+/// waiting on an operator, so pausing ends the episode.
+fn rollout_convergence(group: &GroupDesiredState, converging: bool) -> ConvergenceStatus {
+    if !converging {
+        ConvergenceStatus::Converged
+    } else if group.rollout_active && !group.rollout_paused {
+        ConvergenceStatus::Converging
+    } else {
+        ConvergenceStatus::Unknown
+    }
+}
+
+/// Outcome of a deploy that exceeded its failure budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FailureBudgetHalt {
+    /// Failed instances across the deploy.
+    failed: i64,
+    /// Aggregated reason codes, e.g. `oom_killed=3, image_pull_failed=1`.
+    reasons: String,
+}
+
+/// Check per-reason failure counts against the budget.
+///
+/// Returns the halt details when more than `budget` instances failed.
+/// Reasons are ordered by count (highest first), then by code.
+fn evaluate_failure_budget(failures: &[(String, i64)], budget: u32) -> Option<FailureBudgetHalt> {
+    let mut by_reason: BTreeMap<&str, i64> = BTreeMap::new();
+    for (reason, count) in failures {
+        *by_reason.entry(reason.as_str()).or_default() += count;
+    }
+    let failed: i64 = by_reason.values().sum();
+    if failed <= i64::from(budget) {
+        return None;
+    }
+
+    let mut reasons: Vec<(&str, i64)> = by_reason.into_iter().collect();
+    reasons.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let reasons = reasons
+        .iter()
+        .map(|(reason, count)| format!("{reason}={count}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Some(FailureBudgetHalt { failed, reasons })
+}
+
+/// Decode the `node_selector`/`tolerations` JSONB columns of env_scale_view.
+fn placement_constraints(
+    node_selector: serde_json::Value,
+    tolerations: serde_json::Value,
+) -> PlacementConstraints {
+    PlacementConstraints {
+        node_selector: serde_json::from_value(node_selector).unwrap_or_default(),
+        tolerations: serde_json::from_value(tolerations).unwrap_or_default(),
+        pinned_node_id: None,
+    }
+}
+
+/// Decode the `max_surge`/`max_unavailable` columns of env_scale_view; unset
+/// or unreadable limits fall back to the defaults (surge 1, unavailable 0).
+fn rolling_strategy(max_surge: Option<&str>, max_unavailable: Option<&str>) -> RollingStrategy {
+    let defaults = RollingStrategy::default();
+    RollingStrategy {
+        max_surge: max_surge
+            .and_then(|limit| limit.parse().ok())
+            .unwrap_or(defaults.max_surge),
+        max_unavailable: max_unavailable
+            .and_then(|limit| limit.parse().ok())
+            .unwrap_or(defaults.max_unavailable),
+    }
 }
 
 /// Release info for resource calculation.
@@ -574,21 +1409,122 @@ struct ReleaseInfo {
     memory_bytes: i64,
 }
 
+/// The `reason` of an `instance.config_updated` event.
+fn config_update_reason(group: &GroupDesiredState, instance: &InstanceState) -> &'static str {
+    match (
+        group.secrets_changed(instance),
+        group.config_vars_changed(instance),
+    ) {
+        (true, true) => "config_changed",
+        (true, false) => "secrets_version_changed",
+        _ => "config_vars_version_changed",
+    }
+}
+
 /// Compute a deterministic spec hash for a group.
+///
+/// Only changes that need a new microVM are hashed. The secrets version is
+/// delivered to running instances in place (see
+/// [`SchedulerReconciler::update_instance_config`]), so it is left out. The
+/// config vars version stays in: in `restart` reload mode a change replaces
+/// instances, and in-place modes match on the instance's own version (see
+/// [`GroupDesiredState::matches_spec`]).
+///
+/// A zero `restart_generation` is left out so groups that were never
+/// restarted keep the hash they had before restarts existed; likewise for
+/// envs without config vars and process types without resource overrides.
 fn compute_spec_hash(
+    release_id: &ReleaseId,
+    process_type: &str,
+    volume_hash: &str,
+    ephemeral_disk_bytes: i64,
+    restart_generation: i32,
+    config_vars_version: Option<i32>,
+    resources: &ProcessResources,
+) -> String {
+    hash_spec(
+        release_id,
+        process_type,
+        None,
+        volume_hash,
+        ephemeral_disk_bytes,
+        restart_generation,
+        config_vars_version,
+        resources,
+    )
+}
+
+/// The spec hash as computed before secrets were delivered in place, with
+/// the secrets version included.
+#[allow(clippy::too_many_arguments)]
+fn compute_legacy_spec_hash(
     release_id: &ReleaseId,
     process_type: &str,
     secrets_version: Option<&str>,
     volume_hash: &str,
+    ephemeral_disk_bytes: i64,
+    restart_generation: i32,
+    config_vars_version: Option<i32>,
+    resources: &ProcessResources,
+) -> String {
+    hash_spec(
+        release_id,
+        process_type,
+        Some(secrets_version.unwrap_or("none")),
+        volume_hash,
+        ephemeral_disk_bytes,
+        restart_generation,
+        config_vars_version,
+        resources,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn hash_spec(
+    release_id: &ReleaseId,
+    process_type: &str,
+    legacy_secrets_version: Option<&str>,
+    volume_hash: &str,
+    ephemeral_disk_bytes: i64,
+    restart_generation: i32,
+    config_vars_version: Option<i32>,
+    resources: &ProcessResources,
 ) -> String {
     let mut hasher = Sha256::new();
     hasher.update(release_id.to_string().as_bytes());
     hasher.update(b":");
     hasher.update(process_type.as_bytes());
     hasher.update(b":");
-    hasher.update(secrets_version.unwrap_or("none").as_bytes());
-    hasher.update(b":");
+    if let Some(secrets_version) = legacy_secrets_version {
+        hasher.update(secrets_version.as_bytes());
+        hasher.update(b":");
+    }
     hasher.update(volume_hash.as_bytes());
+    // The scratch disk is sized at boot, so a new size needs new instances.
+    // The default is left out so existing hashes stay stable.
+    if ephemeral_disk_bytes != DEFAULT_EPHEMERAL_DISK_BYTES {
+        hasher.update(b":disk:");
+        hasher.update(ephemeral_disk_bytes.to_string().as_bytes());
+    }
+    if restart_generation > 0 {
+        hasher.update(b":restart:");
+        hasher.update(restart_generation.to_string().as_bytes());
+    }
+    // Config vars are the workload environment, fixed at boot.
+    if let Some(version) = config_vars_version {
+        hasher.update(b":config_vars:");
+        hasher.update(version.to_string().as_bytes());
+    }
+    // CPU and memory size the microVM; the disk override is already folded
+    // into `ephemeral_disk_bytes`.
+    if let Some(cpu) = resources.cpu_request {
+        hasher.update(b":cpu:");
+        hasher.update(cpu.to_string().as_bytes());
+    }
+    if let Some(memory) = resources.memory_limit_bytes {
+        hasher.update(b":memory:");
+        hasher.update(memory.to_string().as_bytes());
+    }
     format!("{:x}", hasher.finalize())[..16].to_string()
 }
 
@@ -697,6 +1633,72 @@ impl SchedulerReconciler {
 
         Ok((format!("{:x}", hasher.finalize())[..16].to_string(), true))
     }
+
+    /// Home node of the volumes attached to a group, if any has one yet.
+    ///
+    /// Volumes are local, so a group that mounts them can only run where they
+    /// live. A volume gets its home node when an instance first mounts it;
+    /// until then the group places freely.
+    async fn volume_home_node(
+        &self,
+        env_id: &EnvId,
+        process_type: &str,
+    ) -> SchedulerResult<Option<String>> {
+        let nodes = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT v.home_node_id
+            FROM volume_attachments_view a
+            JOIN volumes_view v ON v.volume_id = a.volume_id
+            WHERE a.env_id = $1
+              AND a.process_type = $2
+              AND NOT a.is_deleted
+              AND v.home_node_id IS NOT NULL
+            ORDER BY v.home_node_id ASC
+            "#,
+        )
+        .bind(env_id.to_string())
+        .bind(process_type)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if nodes.len() > 1 {
+            // Attach rejects this; only reachable through legacy data.
+            warn!(
+                env_id = %env_id,
+                process_type = %process_type,
+                nodes = ?nodes,
+                "Group mounts volumes on different nodes; pinning to the first"
+            );
+        }
+
+        Ok(nodes.into_iter().next())
+    }
+
+    /// Whether a volume attached to a group is being restored in place.
+    ///
+    /// The home node overwrites the volume while it is unmounted, so the
+    /// group must not start until the restore finishes.
+    async fn volume_restoring(&self, env_id: &EnvId, process_type: &str) -> SchedulerResult<bool> {
+        let restoring = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM volume_attachments_view a
+                JOIN volumes_view v ON v.volume_id = a.volume_id
+                WHERE a.env_id = $1
+                  AND a.process_type = $2
+                  AND NOT a.is_deleted
+                  AND v.restore_status IN ('queued', 'running')
+            )
+            "#,
+        )
+        .bind(env_id.to_string())
+        .bind(process_type)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(restoring)
+    }
 }
 
 // =============================================================================
@@ -711,8 +1713,20 @@ struct GroupRow {
     process_type: String,
     release_id: String,
     deploy_id: Option<String>,
+    restart_generation: i32,
     desired_replicas: i32,
     secrets_version_id: Option<String>,
+    config_vars_version: Option<i32>,
+    deploy_status: Option<String>,
+    deploy_promoted: bool,
+    node_selector: serde_json::Value,
+    tolerations: serde_json::Value,
+    ephemeral_disk_bytes: Option<i64>,
+    cpu_request: Option<f64>,
+    memory_limit_bytes: Option<i64>,
+    max_surge: Option<String>,
+    max_unavailable: Option<String>,
+    secrets_reload: Option<serde_json::Value>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for GroupRow {
@@ -725,8 +1739,20 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for GroupRow {
             process_type: row.try_get("process_type")?,
             release_id: row.try_get("release_id")?,
             deploy_id: row.try_get("deploy_id")?,
+            restart_generation: row.try_get("restart_generation")?,
             desired_replicas: row.try_get("desired_replicas")?,
             secrets_version_id: row.try_get("secrets_version_id")?,
+            config_vars_version: row.try_get("config_vars_version")?,
+            deploy_status: row.try_get("deploy_status")?,
+            deploy_promoted: row.try_get("deploy_promoted")?,
+            node_selector: row.try_get("node_selector")?,
+            tolerations: row.try_get("tolerations")?,
+            ephemeral_disk_bytes: row.try_get("ephemeral_disk_bytes")?,
+            cpu_request: row.try_get("cpu_request")?,
+            memory_limit_bytes: row.try_get("memory_limit_bytes")?,
+            max_surge: row.try_get("max_surge")?,
+            max_unavailable: row.try_get("max_unavailable")?,
+            secrets_reload: row.try_get("secrets_reload")?,
         })
     }
 }
@@ -738,6 +1764,11 @@ struct InstanceRow {
     desired_state: String,
     spec_hash: String,
     release_id: String,
+    secrets_version_id: Option<String>,
+    config_vars_version: Option<i32>,
+    config_target_secrets_version_id: Option<String>,
+    config_target_config_vars_version: Option<i32>,
+    status: Option<String>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for InstanceRow {
@@ -749,6 +1780,11 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for InstanceRow {
             desired_state: row.try_get("desired_state")?,
             spec_hash: row.try_get("spec_hash")?,
             release_id: row.try_get("release_id")?,
+            secrets_version_id: row.try_get("secrets_version_id")?,
+            config_vars_version: row.try_get("config_vars_version")?,
+            config_target_secrets_version_id: row.try_get("config_target_secrets_version_id")?,
+            config_target_config_vars_version: row.try_get("config_target_config_vars_version")?,
+            status: row.try_get("status")?,
         })
     }
 }
@@ -762,6 +1798,8 @@ struct NodeCapacityRow {
     available_memory_bytes: i64,
     available_cpu_cores: i32,
     instance_count: i32,
+    labels: serde_json::Value,
+    taints: serde_json::Value,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for NodeCapacityRow {
@@ -775,6 +1813,36 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for NodeCapacityRow {
             available_memory_bytes: row.try_get("available_memory_bytes")?,
             available_cpu_cores: row.try_get("available_cpu_cores")?,
             instance_count: row.try_get("instance_count")?,
+            labels: row.try_get("labels")?,
+            taints: row.try_get("taints")?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct PreviewGroupRow {
+    process_type: String,
+    release_id: String,
+    desired_replicas: i32,
+    node_selector: serde_json::Value,
+    tolerations: serde_json::Value,
+    cpu_request: Option<f64>,
+    memory_limit_bytes: Option<i64>,
+    running: i64,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for PreviewGroupRow {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            process_type: row.try_get("process_type")?,
+            release_id: row.try_get("release_id")?,
+            desired_replicas: row.try_get("desired_replicas")?,
+            node_selector: row.try_get("node_selector")?,
+            tolerations: row.try_get("tolerations")?,
+            cpu_request: row.try_get("cpu_request")?,
+            memory_limit_bytes: row.try_get("memory_limit_bytes")?,
+            running: row.try_get("running")?,
         })
     }
 }
@@ -816,20 +1884,440 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for VolumeAttachmentRow {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use plfm_reconcile::RolloutLimit;
 
     #[test]
     fn test_compute_spec_hash_deterministic() {
         let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
-        let hash1 = compute_spec_hash(&release_id, "web", None, "none");
-        let hash2 = compute_spec_hash(&release_id, "web", None, "none");
+        let hash1 = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
+        let hash2 = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
         assert_eq!(hash1, hash2);
     }
 
     #[test]
     fn test_compute_spec_hash_different_inputs() {
         let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
-        let hash1 = compute_spec_hash(&release_id, "web", None, "none");
-        let hash2 = compute_spec_hash(&release_id, "worker", None, "none");
+        let hash1 = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
+        let hash2 = compute_spec_hash(
+            &release_id,
+            "worker",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
         assert_ne!(hash1, hash2);
     }
+
+    fn instance(status: Option<&str>) -> InstanceState {
+        InstanceState {
+            instance_id: "inst_1".to_string(),
+            node_id: "node_1".to_string(),
+            desired_state: "running".to_string(),
+            spec_hash: "abc".to_string(),
+            secrets_version_id: None,
+            config_vars_version: None,
+            config_target: None,
+            status: status.map(str::to_string),
+            release_id: "rel_1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_instance_drain_priority() {
+        assert!(instance(Some("ready")).is_ready());
+        assert!(!instance(None).is_ready());
+        assert_eq!(
+            instance(Some("failed")).drain_priority(),
+            DrainPriority::Failed
+        );
+        assert_eq!(instance(None).drain_priority(), DrainPriority::NotReady);
+        assert_eq!(
+            instance(Some("ready")).drain_priority(),
+            DrainPriority::Oldest
+        );
+    }
+
+    #[test]
+    fn test_evaluate_failure_budget() {
+        let failures = vec![
+            ("image_pull_failed".to_string(), 1),
+            ("oom_killed".to_string(), 2),
+        ];
+        assert_eq!(evaluate_failure_budget(&failures, 3), None);
+
+        let failures = vec![
+            ("image_pull_failed".to_string(), 1),
+            ("oom_killed".to_string(), 3),
+            ("crash_loop_backoff".to_string(), 1),
+        ];
+        assert_eq!(
+            evaluate_failure_budget(&failures, 3),
+            Some(FailureBudgetHalt {
+                failed: 5,
+                reasons: "oom_killed=3, crash_loop_backoff=1, image_pull_failed=1".to_string(),
+            })
+        );
+        assert_eq!(evaluate_failure_budget(&[], 0), None);
+    }
+
+    #[test]
+    fn test_compute_spec_hash_restart_generation() {
+        let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
+        let base = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
+        let restarted = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            1,
+            None,
+            &ProcessResources::default(),
+        );
+        let restarted_again = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            2,
+            None,
+            &ProcessResources::default(),
+        );
+        assert_ne!(base, restarted);
+        assert_ne!(restarted, restarted_again);
+    }
+
+    #[test]
+    fn test_compute_spec_hash_ephemeral_disk() {
+        let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
+        let base = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
+        let resized = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            8 << 30,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
+        assert_ne!(base, resized);
+    }
+
+    #[test]
+    fn test_compute_spec_hash_config_vars_version() {
+        let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
+        let hash = |version| {
+            compute_spec_hash(
+                &release_id,
+                "web",
+                "none",
+                DEFAULT_EPHEMERAL_DISK_BYTES,
+                0,
+                version,
+                &ProcessResources::default(),
+            )
+        };
+        assert_ne!(hash(None), hash(Some(1)));
+        assert_ne!(hash(Some(1)), hash(Some(2)));
+    }
+
+    #[test]
+    fn test_config_vars_change_replaces_in_restart_mode() {
+        let mut group = group();
+        group.config_vars_version = Some(1);
+        group.spec_hash = group.spec_hash_with(Some(1));
+        let mut current = instance(Some("ready"));
+        current.spec_hash = group.spec_hash.clone();
+        current.config_vars_version = Some(1);
+        assert!(group.matches_spec(&current));
+
+        group.config_vars_version = Some(2);
+        group.spec_hash = group.spec_hash_with(Some(2));
+        assert_eq!(group.secrets_reload, SecretsReload::Restart);
+        assert!(!group.matches_spec(&current));
+        assert!(!group.needs_config_update(&current));
+    }
+
+    #[test]
+    fn test_config_vars_change_updates_in_place() {
+        let mut group = group();
+        group.secrets_reload = SecretsReload::File;
+        group.config_vars_version = Some(2);
+        group.spec_hash = group.spec_hash_with(Some(2));
+        let mut current = instance(Some("ready"));
+        current.spec_hash = group.spec_hash_with(Some(1));
+        current.config_vars_version = Some(1);
+
+        assert!(group.matches_spec(&current));
+        assert!(group.needs_config_update(&current));
+        let target = group.config_target(&current);
+        assert_eq!(
+            target,
+            ConfigTarget {
+                secrets_version_id: None,
+                config_vars_version: Some(2),
+            }
+        );
+        assert_eq!(
+            group.spec_hash_with(target.config_vars_version),
+            group.spec_hash
+        );
+        assert_eq!(
+            config_update_reason(&group, &current),
+            "config_vars_version_changed"
+        );
+
+        // Any other spec change still replaces.
+        current.spec_hash = "other".to_string();
+        assert!(!group.matches_spec(&current));
+    }
+
+    #[test]
+    fn test_pending_config_update_is_not_repeated() {
+        let mut group = group();
+        group.secrets_version_id = Some("sv_2".to_string());
+        group.secrets_reload = SecretsReload::File;
+        group.config_vars_version = Some(2);
+
+        let mut current = instance(Some("ready"));
+        current.secrets_version_id = Some("sv_1".to_string());
+        current.config_vars_version = Some(1);
+        assert!(group.needs_config_update(&current));
+        assert_eq!(config_update_reason(&group, &current), "config_changed");
+
+        // Already the instance's target: not appended again.
+        current.config_target = Some(ConfigTarget {
+            secrets_version_id: Some("sv_2".to_string()),
+            config_vars_version: Some(2),
+        });
+        assert!(!group.needs_config_update(&current));
+
+        // A newer target is delivered again.
+        group.config_vars_version = Some(3);
+        assert!(group.needs_config_update(&current));
+    }
+
+    #[test]
+    fn test_compute_spec_hash_resource_overrides() {
+        let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
+        let hash = |resources: ProcessResources| {
+            compute_spec_hash(
+                &release_id,
+                "web",
+                "none",
+                DEFAULT_EPHEMERAL_DISK_BYTES,
+                0,
+                None,
+                &resources,
+            )
+        };
+        let base = hash(ProcessResources::default());
+        let cpu = hash(ProcessResources {
+            cpu_request: Some(2.0),
+            ..Default::default()
+        });
+        let memory = hash(ProcessResources {
+            memory_limit_bytes: Some(2 << 30),
+            ..Default::default()
+        });
+        assert_ne!(base, cpu);
+        assert_ne!(base, memory);
+        assert_ne!(cpu, memory);
+    }
+
+    #[test]
+    fn test_legacy_spec_hash_includes_secrets_version() {
+        let release_id: ReleaseId = "rel_01ABC".parse().unwrap_or_else(|_| ReleaseId::new());
+        let legacy = |secrets_version| {
+            compute_legacy_spec_hash(
+                &release_id,
+                "web",
+                secrets_version,
+                "none",
+                DEFAULT_EPHEMERAL_DISK_BYTES,
+                0,
+                None,
+                &ProcessResources::default(),
+            )
+        };
+        let current = compute_spec_hash(
+            &release_id,
+            "web",
+            "none",
+            DEFAULT_EPHEMERAL_DISK_BYTES,
+            0,
+            None,
+            &ProcessResources::default(),
+        );
+        assert_ne!(legacy(None), legacy(Some("sv_1")));
+        assert_ne!(legacy(None), current);
+        assert_ne!(legacy(Some("sv_1")), current);
+    }
+
+    #[test]
+    fn test_secrets_change_updates_in_place() {
+        let mut group = group();
+        group.secrets_version_id = Some("sv_2".to_string());
+        group.secrets_reload = SecretsReload::File;
+
+        let mut current = instance(Some("ready"));
+        current.spec_hash = "hash".to_string();
+        current.secrets_version_id = Some("sv_1".to_string());
+        assert!(group.matches_spec(&current));
+        assert!(group.needs_config_update(&current));
+
+        current.secrets_version_id = Some("sv_2".to_string());
+        assert!(!group.needs_config_update(&current));
+
+        // Allocated under the old hash with the current secrets: kept.
+        current.spec_hash = "legacy".to_string();
+        assert!(group.matches_spec(&current));
+
+        current.spec_hash = "other".to_string();
+        assert!(!group.matches_spec(&current));
+
+        // Draining instances are not updated.
+        let mut draining = instance(Some("ready"));
+        draining.desired_state = "draining".to_string();
+        assert!(!group.needs_config_update(&draining));
+
+        // Secrets removed: running instances keep their last version.
+        group.secrets_version_id = None;
+        current.secrets_version_id = Some("sv_2".to_string());
+        assert!(!group.needs_config_update(&current));
+    }
+
+    #[test]
+    fn test_secrets_change_replaces_in_restart_mode() {
+        let mut group = group();
+        group.secrets_version_id = Some("sv_2".to_string());
+        assert_eq!(group.secrets_reload, SecretsReload::Restart);
+
+        let mut current = instance(Some("ready"));
+        current.spec_hash = "hash".to_string();
+        current.secrets_version_id = Some("sv_1".to_string());
+        assert!(!group.matches_spec(&current));
+        assert!(!group.needs_config_update(&current));
+
+        current.secrets_version_id = Some("sv_2".to_string());
+        assert!(group.matches_spec(&current));
+
+        // Switching modes alone replaces nothing.
+        group.secrets_reload = SecretsReload::Signal {
+            signal: "SIGHUP".to_string(),
+        };
+        assert!(group.matches_spec(&current));
+        assert!(!group.needs_config_update(&current));
+    }
+
+    #[test]
+    fn test_rolling_strategy_from_scale_columns() {
+        assert_eq!(rolling_strategy(None, None), RollingStrategy::default());
+        let strategy = rolling_strategy(Some("25%"), Some("2"));
+        assert_eq!(strategy.max_surge, RolloutLimit::Percent(25));
+        assert_eq!(strategy.max_unavailable, RolloutLimit::Count(2));
+        assert_eq!(
+            rolling_strategy(Some("lots"), None).max_surge,
+            RolloutLimit::Count(1)
+        );
+    }
+
+    fn group() -> GroupDesiredState {
+        GroupDesiredState {
+            org_id: OrgId::new(),
+            app_id: AppId::new(),
+            env_id: EnvId::new(),
+            process_type: "web".to_string(),
+            release_id: ReleaseId::new(),
+            deploy_id: Some("dep_1".to_string()),
+            desired_replicas: 2,
+            spec_hash: "hash".to_string(),
+            legacy_spec_hash: "legacy".to_string(),
+            secrets_version_id: None,
+            secrets_reload: SecretsReload::Restart,
+            config_vars_version: None,
+            volume_hash: "none".to_string(),
+            restart_generation: 0,
+            rollout_paused: false,
+            rollout_promoted: false,
+            rollout_active: true,
+            rollout_halted: false,
+            constraints: PlacementConstraints::default(),
+            ephemeral_disk_bytes: DEFAULT_EPHEMERAL_DISK_BYTES,
+            resources: ProcessResources::default(),
+            strategy: RollingStrategy::default(),
+        }
+    }
+
+    #[test]
+    fn test_rollout_convergence() {
+        let mut group = group();
+        assert_eq!(
+            rollout_convergence(&group, true),
+            ConvergenceStatus::Converging
+        );
+        assert_eq!(
+            rollout_convergence(&group, false),
+            ConvergenceStatus::Converged
+        );
+
+        group.rollout_paused = true;
+        assert_eq!(
+            rollout_convergence(&group, true),
+            ConvergenceStatus::Unknown
+        );
+
+        // Scale changes after a deploy finished are not timed against it.
+        group.rollout_paused = false;
+        group.rollout_active = false;
+        assert_eq!(
+            rollout_convergence(&group, true),
+            ConvergenceStatus::Unknown
+        );
+
+        let key = group.convergence_key();
+        group.deploy_id = Some("dep_2".to_string());
+        assert_ne!(group.convergence_key(), key);
+    }
 }
@@ -1,6 +1,7 @@
 //! Scheduler background worker.
 //!
-//! Runs the scheduler reconciliation loop on a periodic interval.
+//! Runs the scheduler reconciliation loop on a periodic interval, on the
+//! elected leader replica only.
 
 use std::time::Duration;
 
@@ -9,19 +10,22 @@ use tokio::sync::watch;
 use tracing::{error, info, instrument};
 
 use super::reconciler::SchedulerReconciler;
+use crate::leader::{LeaderElection, LeaderRole};
 
 /// Scheduler worker that runs the reconciliation loop.
 pub struct SchedulerWorker {
     reconciler: SchedulerReconciler,
     interval: Duration,
+    election: LeaderElection,
 }
 
 impl SchedulerWorker {
     /// Create a new scheduler worker.
     pub fn new(pool: PgPool, interval: Duration) -> Self {
         Self {
-            reconciler: SchedulerReconciler::new(pool),
+            reconciler: SchedulerReconciler::new(pool.clone()),
             interval,
+            election: LeaderElection::new(pool, LeaderRole::Scheduler, interval * 3),
         }
     }
 
@@ -40,6 +44,9 @@ impl SchedulerWorker {
         loop {
             tokio::select! {
                 _ = interval.tick() => {
+                    if !self.election.ensure_leader().await {
+                        continue;
+                    }
                     if let Err(e) = self.run_reconciliation().await {
                         error!(error = %e, "Scheduler reconciliation failed");
                     }
@@ -47,6 +54,7 @@ impl SchedulerWorker {
                 _ = shutdown.changed() => {
                     if *shutdown.borrow() {
                         info!("Scheduler worker shutting down");
+                        self.election.resign().await;
                         break;
                     }
                 }
@@ -0,0 +1,347 @@
+//! Key rotation jobs: re-wrap every stored data key in the background.
+//!
+//! An operator starts a job after changing the KEK (see
+//! [`super::kms`]). The cleanup worker then advances the running job one
+//! batch per tick via [`run_pass`], persisting the cursor and counters after
+//! every batch so the job survives restarts and leader changes. Rows that
+//! fail to re-wrap are counted and left under their old key; a later job
+//! retries them.
+//!
+//! Events (audit trail, aggregate `key_rotation`):
+//! - `key_rotation.started` when an operator starts a job
+//! - `key_rotation.completed` when it completes, fails, or is cancelled
+
+use chrono::{DateTime, Utc};
+use plfm_events::{
+    EventSource, KeyRotationCompletedPayload, KeyRotationStartedPayload, NewEvent, SystemSource,
+};
+use plfm_id::OrgId;
+use sqlx::PgPool;
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+use super::rotation::{self, RotationError};
+use super::{KeyScope, SecretsCryptoError};
+use crate::db::{DbError, EventStore};
+
+/// Actor ID for events written by the job driver.
+pub const KEY_ROTATION_ACTOR_ID: &str = "key-rotation";
+
+pub const DEFAULT_BATCH_SIZE: i32 = 100;
+pub const MAX_BATCH_SIZE: i32 = 1000;
+
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error("a key rotation job is already running")]
+    AlreadyRunning,
+    #[error(transparent)]
+    Crypto(#[from] SecretsCryptoError),
+    #[error(transparent)]
+    Event(#[from] plfm_events::EventError),
+    #[error(transparent)]
+    Store(#[from] DbError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<RotationError> for JobError {
+    fn from(error: RotationError) -> Self {
+        match error {
+            RotationError::Crypto(e) => JobError::Crypto(e),
+            RotationError::Database(e) => JobError::Database(e),
+        }
+    }
+}
+
+/// A key rotation job and its progress.
+#[derive(Debug, Clone)]
+pub struct KeyRotationJob {
+    pub job_id: String,
+    pub org_id: Option<String>,
+    /// `running`, `completed`, `failed`, or `cancelled`.
+    pub state: String,
+    pub target_key_id: String,
+    pub batch_size: i32,
+    pub cursor: Option<String>,
+    pub total: i64,
+    pub scanned: i64,
+    pub rewrapped: i64,
+    pub failed: i64,
+    pub last_error: Option<String>,
+    pub requested_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for KeyRotationJob {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        Ok(Self {
+            job_id: row.try_get("job_id")?,
+            org_id: row.try_get("org_id")?,
+            state: row.try_get("state")?,
+            target_key_id: row.try_get("target_key_id")?,
+            batch_size: row.try_get("batch_size")?,
+            cursor: row.try_get("cursor")?,
+            total: row.try_get("total")?,
+            scanned: row.try_get("scanned")?,
+            rewrapped: row.try_get("rewrapped")?,
+            failed: row.try_get("failed")?,
+            last_error: row.try_get("last_error")?,
+            requested_by: row.try_get("requested_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            completed_at: row.try_get("completed_at")?,
+        })
+    }
+}
+
+const JOB_COLUMNS: &str = "job_id, org_id, state, target_key_id, batch_size, cursor, total, \
+     scanned, rewrapped, failed, last_error, requested_by, created_at, updated_at, completed_at";
+
+/// Start a job and record `key_rotation.started` under `source`.
+pub async fn start(
+    pool: &PgPool,
+    source: &impl EventSource,
+    org_id: Option<OrgId>,
+    batch_size: i32,
+) -> Result<KeyRotationJob, JobError> {
+    // Validates the provider configuration before anything is recorded.
+    let target_key_id = super::current_key_id(KeyScope::Platform)?;
+    let org = org_id.map(|id| id.to_string());
+
+    let total = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT
+            (SELECT COUNT(DISTINCT sv.material_id)
+             FROM secret_versions sv
+             WHERE ($1::TEXT IS NULL OR sv.org_id = $1))
+          + (SELECT COUNT(*)
+             FROM route_certificates rc
+             WHERE ($1::TEXT IS NULL OR rc.org_id = $1))
+          + (SELECT COUNT(*)
+             FROM notification_channels nc
+             WHERE nc.secret_material_id IS NOT NULL
+               AND ($1::TEXT IS NULL OR nc.org_id = $1))
+        "#,
+    )
+    .bind(org.as_deref())
+    .fetch_one(pool)
+    .await?;
+
+    let job_id = format!("krj_{}", plfm_id::Ulid::new());
+    let query = format!(
+        r#"
+        INSERT INTO key_rotation_jobs (
+            job_id, org_id, state, target_key_id, batch_size, total, requested_by
+        )
+        VALUES ($1, $2, 'running', $3, $4, $5, $6)
+        ON CONFLICT DO NOTHING
+        RETURNING {JOB_COLUMNS}
+        "#
+    );
+    let job = sqlx::query_as::<_, KeyRotationJob>(&query)
+        .bind(&job_id)
+        .bind(org.as_deref())
+        .bind(&target_key_id)
+        .bind(batch_size.clamp(1, MAX_BATCH_SIZE))
+        .bind(total)
+        .bind(source.actor_id())
+        .fetch_optional(pool)
+        .await?
+        .ok_or(JobError::AlreadyRunning)?;
+
+    let mut builder = NewEvent::builder(source)
+        .aggregate_id(job_id.clone())
+        .aggregate_seq(1);
+    if let Some(org_id) = org_id {
+        builder = builder.org_id(org_id);
+    }
+    let event = builder
+        .payload(&KeyRotationStartedPayload {
+            job_id: job_id.clone(),
+            org_id,
+            target_key_id,
+            total,
+        })
+        .build()?;
+    EventStore::new(pool.clone()).append(event.into()).await?;
+
+    Ok(job)
+}
+
+pub async fn get(pool: &PgPool, job_id: &str) -> Result<Option<KeyRotationJob>, sqlx::Error> {
+    let query = format!("SELECT {JOB_COLUMNS} FROM key_rotation_jobs WHERE job_id = $1");
+    sqlx::query_as::<_, KeyRotationJob>(&query)
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list(pool: &PgPool, limit: i64) -> Result<Vec<KeyRotationJob>, sqlx::Error> {
+    let query = format!(
+        "SELECT {JOB_COLUMNS} FROM key_rotation_jobs ORDER BY created_at DESC, job_id DESC LIMIT $1"
+    );
+    sqlx::query_as::<_, KeyRotationJob>(&query)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Cancel a running job. Returns `None` if the job does not exist or has
+/// already finished.
+pub async fn cancel(
+    pool: &PgPool,
+    source: &impl EventSource,
+    job_id: &str,
+) -> Result<Option<KeyRotationJob>, JobError> {
+    let Some(job) = finish(pool, job_id, "cancelled", None).await? else {
+        return Ok(None);
+    };
+    record_completed(pool, source, &job).await?;
+    Ok(Some(job))
+}
+
+/// Move a running job to a terminal state. Only one caller wins, so only
+/// one `key_rotation.completed` event is written per job.
+async fn finish(
+    pool: &PgPool,
+    job_id: &str,
+    state: &str,
+    error: Option<&str>,
+) -> Result<Option<KeyRotationJob>, sqlx::Error> {
+    let query = format!(
+        r#"
+        UPDATE key_rotation_jobs
+        SET state = $2, last_error = COALESCE($3, last_error),
+            updated_at = now(), completed_at = now()
+        WHERE job_id = $1 AND state = 'running'
+        RETURNING {JOB_COLUMNS}
+        "#
+    );
+    sqlx::query_as::<_, KeyRotationJob>(&query)
+        .bind(job_id)
+        .bind(state)
+        .bind(error)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn record_completed(
+    pool: &PgPool,
+    source: &impl EventSource,
+    job: &KeyRotationJob,
+) -> Result<(), JobError> {
+    let org_id = job
+        .org_id
+        .as_deref()
+        .and_then(|id| id.parse::<OrgId>().ok());
+    let mut builder = NewEvent::builder(source)
+        .aggregate_id(job.job_id.clone())
+        .aggregate_seq(2);
+    if let Some(org_id) = org_id {
+        builder = builder.org_id(org_id);
+    }
+    let event = builder
+        .payload(&KeyRotationCompletedPayload {
+            job_id: job.job_id.clone(),
+            outcome: job.state.clone(),
+            scanned: job.scanned,
+            rewrapped: job.rewrapped,
+            failed: job.failed,
+            error: job.last_error.clone(),
+        })
+        .build()?;
+    EventStore::new(pool.clone()).append(event.into()).await?;
+    Ok(())
+}
+
+/// Advance the running job by one batch, if there is one. Returns the job
+/// once it reaches a terminal state.
+pub async fn run_pass(pool: &PgPool) -> Result<Option<KeyRotationJob>, JobError> {
+    let query = format!("SELECT {JOB_COLUMNS} FROM key_rotation_jobs WHERE state = 'running'");
+    let Some(job) = sqlx::query_as::<_, KeyRotationJob>(&query)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    match advance(pool, &job).await {
+        Ok(Some(done)) => {
+            info!(
+                job_id = %done.job_id,
+                scanned = done.scanned,
+                rewrapped = done.rewrapped,
+                failed = done.failed,
+                "Key rotation job completed"
+            );
+            record_completed(pool, &SystemSource::new(KEY_ROTATION_ACTOR_ID), &done).await?;
+            Ok(Some(done))
+        }
+        Ok(None) => Ok(None),
+        // Configuration or KMS errors will not fix themselves between ticks.
+        Err(JobError::Crypto(e)) => {
+            error!(error = %e, job_id = %job.job_id, "Key rotation job failed");
+            let Some(done) = finish(pool, &job.job_id, "failed", Some(&e.to_string())).await?
+            else {
+                return Ok(None);
+            };
+            record_completed(pool, &SystemSource::new(KEY_ROTATION_ACTOR_ID), &done).await?;
+            Ok(Some(done))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn advance(pool: &PgPool, job: &KeyRotationJob) -> Result<Option<KeyRotationJob>, JobError> {
+    // Platform CA and identity token keys go first in a full (unscoped) job.
+    if job.org_id.is_none() && job.cursor.is_none() && job.scanned == 0 {
+        let report = rotation::rewrap_pki_authorities(pool).await?;
+        if report.rewrapped > 0 {
+            info!(
+                job_id = %job.job_id,
+                rewrapped = report.rewrapped,
+                "Re-wrapped platform signing keys"
+            );
+        }
+    }
+
+    let report = rotation::rewrap_secret_material(
+        pool,
+        job.org_id.as_deref(),
+        job.cursor.as_deref(),
+        i64::from(job.batch_size),
+    )
+    .await?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE key_rotation_jobs
+        SET cursor = COALESCE($2, cursor),
+            scanned = scanned + $3,
+            rewrapped = rewrapped + $4,
+            failed = failed + $5,
+            updated_at = now()
+        WHERE job_id = $1 AND state = 'running'
+        "#,
+    )
+    .bind(&job.job_id)
+    .bind(report.next_cursor.as_deref())
+    .bind(report.scanned as i64)
+    .bind(report.rewrapped as i64)
+    .bind(report.failed as i64)
+    .execute(pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        // Cancelled while the batch ran.
+        warn!(job_id = %job.job_id, "Key rotation job stopped during a batch");
+        return Ok(None);
+    }
+
+    if report.next_cursor.is_some() {
+        return Ok(None);
+    }
+    Ok(finish(pool, &job.job_id, "completed", None).await?)
+}
@@ -0,0 +1,820 @@
+//! Key encryption key (KEK) providers.
+//!
+//! Each wrapped data key is stored with the ID of the key that wrapped it
+//! (`master_key_id`), which also names the provider:
+//! - `<master_id>` / `<master_id>/<org_id>`: the local master key, or a key
+//!   derived from it for one org
+//! - `aws-kms:<key_id_or_alias>`
+//! - `gcp-kms:<crypto_key_name>`
+//! - `vault-transit:<key_name>`
+//!
+//! Unwrapping dispatches on the stored ID, not on the configured provider, so
+//! material stays readable while it is being re-wrapped after a provider or
+//! key change (see [`super::rotation`]).
+//!
+//! Configuration:
+//! - `PLFM_KMS_PROVIDER`: `local` (default), `aws-kms`, `gcp-kms`, `vault-transit`
+//! - `PLFM_KMS_KEY`: platform key for remote providers
+//! - `PLFM_KMS_ORG_KEY_TEMPLATE`: per-org key for remote providers, with
+//!   `{org_id}` substituted (e.g. `alias/plfm-org-{org_id}`); without it orgs
+//!   share `PLFM_KMS_KEY`. Local keys are always derived per org.
+//! - `PLFM_SECRETS_PREVIOUS_MASTER_KEYS`: comma-separated retired local master
+//!   keys (`[<id>=]<base64>`) still accepted for unwrapping
+//! - AWS: `PLFM_KMS_AWS_REGION` (or `AWS_REGION`), `AWS_ACCESS_KEY_ID`,
+//!   `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`, `PLFM_KMS_AWS_ENDPOINT`
+//! - GCP: `PLFM_KMS_GCP_ACCESS_TOKEN` (otherwise the metadata server),
+//!   `PLFM_KMS_GCP_ENDPOINT`
+//! - Vault: `VAULT_ADDR`, `VAULT_TOKEN`, `VAULT_NAMESPACE`,
+//!   `PLFM_KMS_VAULT_MOUNT` (default `transit`)
+
+use std::fmt;
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::{KeyScope, SecretsCryptoError, NONCE_BYTES};
+
+const KEY_BYTES: usize = 32;
+const WRAP_AAD: &[u8] = b"plfm-secrets-wrap-v1";
+const ORG_KEK_INFO: &str = "plfm-org-kek-v1";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const B64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A KEK provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Local,
+    AwsKms,
+    GcpKms,
+    VaultTransit,
+}
+
+impl Provider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Provider::Local => "local",
+            Provider::AwsKms => "aws-kms",
+            Provider::GcpKms => "gcp-kms",
+            Provider::VaultTransit => "vault-transit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "local" => Some(Provider::Local),
+            "aws-kms" => Some(Provider::AwsKms),
+            "gcp-kms" => Some(Provider::GcpKms),
+            "vault-transit" => Some(Provider::VaultTransit),
+            _ => None,
+        }
+    }
+
+    /// The provider new material is wrapped with.
+    pub fn configured() -> Result<Self, SecretsCryptoError> {
+        match env("PLFM_KMS_PROVIDER") {
+            None => Ok(Provider::Local),
+            Some(value) => Self::parse(&value).ok_or_else(|| {
+                SecretsCryptoError::KmsConfig(format!("unknown PLFM_KMS_PROVIDER '{value}'"))
+            }),
+        }
+    }
+}
+
+/// Identifies the KEK a data key is wrapped under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyId {
+    Local {
+        master_id: String,
+        org_id: Option<String>,
+    },
+    Remote {
+        provider: Provider,
+        key: String,
+    },
+}
+
+impl KeyId {
+    pub fn parse(value: &str) -> Self {
+        if let Some((prefix, key)) = value.split_once(':') {
+            if let Some(provider) = Provider::parse(prefix).filter(|p| *p != Provider::Local) {
+                return KeyId::Remote {
+                    provider,
+                    key: key.to_string(),
+                };
+            }
+        }
+        match value.split_once('/') {
+            Some((master_id, org_id)) => KeyId::Local {
+                master_id: master_id.to_string(),
+                org_id: Some(org_id.to_string()),
+            },
+            None => KeyId::Local {
+                master_id: value.to_string(),
+                org_id: None,
+            },
+        }
+    }
+
+    pub fn provider(&self) -> Provider {
+        match self {
+            KeyId::Local { .. } => Provider::Local,
+            KeyId::Remote { provider, .. } => *provider,
+        }
+    }
+}
+
+impl fmt::Display for KeyId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyId::Local {
+                master_id,
+                org_id: None,
+            } => f.write_str(master_id),
+            KeyId::Local {
+                master_id,
+                org_id: Some(org_id),
+            } => write!(f, "{master_id}/{org_id}"),
+            KeyId::Remote { provider, key } => write!(f, "{}:{}", provider.as_str(), key),
+        }
+    }
+}
+
+/// A wrapped data key. `nonce` is empty for remote providers.
+#[derive(Debug, Clone)]
+pub struct WrappedKey {
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+/// The key new material in `scope` is wrapped under.
+pub fn current_key(scope: KeyScope<'_>) -> Result<KeyId, SecretsCryptoError> {
+    let provider = Provider::configured()?;
+    if provider == Provider::Local {
+        let keyring = LocalKeyring::load()?;
+        return Ok(keyring.current_key(scope));
+    }
+
+    let platform_key = env("PLFM_KMS_KEY").ok_or_else(|| {
+        SecretsCryptoError::KmsConfig(format!(
+            "PLFM_KMS_KEY is required for provider {}",
+            provider.as_str()
+        ))
+    })?;
+    let key = match (scope, env("PLFM_KMS_ORG_KEY_TEMPLATE")) {
+        (KeyScope::Org(org_id), Some(template)) => template.replace("{org_id}", org_id),
+        _ => platform_key,
+    };
+    Ok(KeyId::Remote { provider, key })
+}
+
+pub async fn wrap(key_id: &KeyId, data_key: &[u8]) -> Result<WrappedKey, SecretsCryptoError> {
+    match key_id {
+        KeyId::Local { .. } => LocalKeyring::load()?.wrap(key_id, data_key),
+        KeyId::Remote { provider, key } => {
+            let ciphertext = match provider {
+                Provider::AwsKms => aws::encrypt(key, data_key).await?,
+                Provider::GcpKms => gcp::encrypt(key, data_key).await?,
+                Provider::VaultTransit => vault::encrypt(key, data_key).await?,
+                Provider::Local => unreachable!("local keys are not remote"),
+            };
+            Ok(WrappedKey {
+                ciphertext,
+                nonce: Vec::new(),
+            })
+        }
+    }
+}
+
+pub async fn unwrap(key_id: &KeyId, wrapped: &WrappedKey) -> Result<Vec<u8>, SecretsCryptoError> {
+    match key_id {
+        KeyId::Local { .. } => LocalKeyring::load()?.unwrap(key_id, wrapped),
+        KeyId::Remote { provider, key } => match provider {
+            Provider::AwsKms => aws::decrypt(key, &wrapped.ciphertext).await,
+            Provider::GcpKms => gcp::decrypt(key, &wrapped.ciphertext).await,
+            Provider::VaultTransit => vault::decrypt(key, &wrapped.ciphertext).await,
+            Provider::Local => unreachable!("local keys are not remote"),
+        },
+    }
+}
+
+// =============================================================================
+// Local master keys
+// =============================================================================
+
+#[derive(Clone)]
+struct MasterKey {
+    id: String,
+    key_bytes: [u8; KEY_BYTES],
+}
+
+impl fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MasterKey").field("id", &self.id).finish()
+    }
+}
+
+/// The current local master key plus retired ones still accepted for
+/// unwrapping.
+#[derive(Debug)]
+struct LocalKeyring {
+    current: MasterKey,
+    previous: Vec<MasterKey>,
+}
+
+fn decode_key(raw: &str) -> Result<[u8; KEY_BYTES], SecretsCryptoError> {
+    let bytes = B64
+        .decode(raw.trim())
+        .map_err(|_| SecretsCryptoError::InvalidMasterKey)?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| SecretsCryptoError::InvalidMasterKey)
+}
+
+fn master_key_id_for_bytes(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    hex::encode(digest)[..8].to_string()
+}
+
+fn load_master_key_bytes() -> Result<[u8; KEY_BYTES], SecretsCryptoError> {
+    if let Some(raw) = env("PLFM_SECRETS_MASTER_KEY").or_else(|| env("GHOST_SECRETS_MASTER_KEY")) {
+        return decode_key(&raw);
+    }
+
+    if let Some(path) =
+        env("PLFM_SECRETS_MASTER_KEY_FILE").or_else(|| env("GHOST_SECRETS_MASTER_KEY_FILE"))
+    {
+        let contents =
+            fs::read_to_string(path).map_err(|_| SecretsCryptoError::InvalidMasterKey)?;
+        return decode_key(&contents);
+    }
+
+    Err(SecretsCryptoError::MissingMasterKey)
+}
+
+impl LocalKeyring {
+    fn load() -> Result<Self, SecretsCryptoError> {
+        let key_bytes = load_master_key_bytes()?;
+        let id = env("PLFM_SECRETS_MASTER_KEY_ID")
+            .or_else(|| env("GHOST_SECRETS_MASTER_KEY_ID"))
+            .unwrap_or_else(|| master_key_id_for_bytes(&key_bytes));
+
+        let previous = env("PLFM_SECRETS_PREVIOUS_MASTER_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (id, raw) = match entry.split_once('=') {
+                    // Base64 padding also uses '=', so only treat the prefix
+                    // as an ID when the rest still decodes as a key.
+                    Some((id, raw)) if decode_key(raw).is_ok() => (Some(id.to_string()), raw),
+                    _ => (None, entry),
+                };
+                let key_bytes = decode_key(raw)?;
+                Ok(MasterKey {
+                    id: id.unwrap_or_else(|| master_key_id_for_bytes(&key_bytes)),
+                    key_bytes,
+                })
+            })
+            .collect::<Result<Vec<_>, SecretsCryptoError>>()?;
+
+        Ok(Self {
+            current: MasterKey { id, key_bytes },
+            previous,
+        })
+    }
+
+    fn current_key(&self, scope: KeyScope<'_>) -> KeyId {
+        KeyId::Local {
+            master_id: self.current.id.clone(),
+            org_id: match scope {
+                KeyScope::Platform => None,
+                KeyScope::Org(org_id) => Some(org_id.to_string()),
+            },
+        }
+    }
+
+    /// Resolve a local key ID to its KEK bytes.
+    fn kek(&self, key_id: &KeyId) -> Result<[u8; KEY_BYTES], SecretsCryptoError> {
+        let KeyId::Local { master_id, org_id } = key_id else {
+            return Err(SecretsCryptoError::UnknownMasterKey(key_id.to_string()));
+        };
+        let master = std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|k| &k.id == master_id)
+            .ok_or_else(|| SecretsCryptoError::UnknownMasterKey(key_id.to_string()))?;
+
+        Ok(match org_id {
+            None => master.key_bytes,
+            Some(org_id) => derive_org_kek(&master.key_bytes, org_id),
+        })
+    }
+
+    fn wrap(&self, key_id: &KeyId, data_key: &[u8]) -> Result<WrappedKey, SecretsCryptoError> {
+        let kek = self.kek(key_id)?;
+        let mut nonce_bytes = [0u8; NONCE_BYTES];
+        rand::rng().fill_bytes(&mut nonce_bytes);
+        let cipher =
+            Aes256Gcm::new_from_slice(&kek).map_err(|_| SecretsCryptoError::EncryptFailed)?;
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: data_key,
+                    aad: WRAP_AAD,
+                },
+            )
+            .map_err(|_| SecretsCryptoError::EncryptFailed)?;
+        Ok(WrappedKey {
+            ciphertext,
+            nonce: nonce_bytes.to_vec(),
+        })
+    }
+
+    fn unwrap(&self, key_id: &KeyId, wrapped: &WrappedKey) -> Result<Vec<u8>, SecretsCryptoError> {
+        let kek = self.kek(key_id)?;
+        if wrapped.nonce.len() != NONCE_BYTES {
+            return Err(SecretsCryptoError::DecryptFailed);
+        }
+        let cipher =
+            Aes256Gcm::new_from_slice(&kek).map_err(|_| SecretsCryptoError::DecryptFailed)?;
+        cipher
+            .decrypt(
+                Nonce::from_slice(&wrapped.nonce),
+                Payload {
+                    msg: &wrapped.ciphertext,
+                    aad: WRAP_AAD,
+                },
+            )
+            .map_err(|_| SecretsCryptoError::DecryptFailed)
+    }
+}
+
+/// Per-org KEK: HMAC-SHA256 of the org ID under the master key (the
+/// HKDF-Expand step, with the uniformly random master key as PRK).
+fn derive_org_kek(master: &[u8; KEY_BYTES], org_id: &str) -> [u8; KEY_BYTES] {
+    let mut mac = HmacSha256::new_from_slice(master).expect("HMAC accepts any key length");
+    mac.update(ORG_KEK_INFO.as_bytes());
+    mac.update(b"|");
+    mac.update(org_id.as_bytes());
+    mac.update(&[1]);
+    let mut kek = [0u8; KEY_BYTES];
+    kek.copy_from_slice(&mac.finalize().into_bytes());
+    kek
+}
+
+// =============================================================================
+// Remote providers
+// =============================================================================
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn require_env(name: &str) -> Result<String, SecretsCryptoError> {
+    env(name).ok_or_else(|| SecretsCryptoError::KmsConfig(format!("{name} is not set")))
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build KMS HTTP client")
+    })
+}
+
+/// Send a KMS request and return its JSON body. Error bodies are logged by
+/// status only; they can echo request fields.
+async fn send(
+    provider: Provider,
+    request: reqwest::RequestBuilder,
+) -> Result<Value, SecretsCryptoError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| SecretsCryptoError::Kms(format!("{}: {e}", provider.as_str())))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(SecretsCryptoError::Kms(format!(
+            "{} returned {status}",
+            provider.as_str()
+        )));
+    }
+    response.json().await.map_err(|e| {
+        SecretsCryptoError::Kms(format!("{}: invalid response: {e}", provider.as_str()))
+    })
+}
+
+fn b64_field(
+    provider: Provider,
+    body: &Value,
+    pointer: &str,
+) -> Result<Vec<u8>, SecretsCryptoError> {
+    body.pointer(pointer)
+        .and_then(Value::as_str)
+        .and_then(|s| B64.decode(s).ok())
+        .ok_or_else(|| {
+            SecretsCryptoError::Kms(format!(
+                "{}: response is missing {pointer}",
+                provider.as_str()
+            ))
+        })
+}
+
+mod aws {
+    use super::*;
+
+    /// Bound to every wrapped key; KMS refuses to decrypt without it.
+    fn encryption_context() -> Value {
+        json!({ "plfm": std::str::from_utf8(WRAP_AAD).unwrap_or_default() })
+    }
+
+    pub(super) async fn encrypt(key: &str, data_key: &[u8]) -> Result<Vec<u8>, SecretsCryptoError> {
+        let body = call(
+            "Encrypt",
+            json!({
+                "KeyId": key,
+                "Plaintext": B64.encode(data_key),
+                "EncryptionContext": encryption_context(),
+            }),
+        )
+        .await?;
+        b64_field(Provider::AwsKms, &body, "/CiphertextBlob")
+    }
+
+    pub(super) async fn decrypt(
+        key: &str,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, SecretsCryptoError> {
+        let body = call(
+            "Decrypt",
+            json!({
+                "KeyId": key,
+                "CiphertextBlob": B64.encode(ciphertext),
+                "EncryptionContext": encryption_context(),
+            }),
+        )
+        .await?;
+        b64_field(Provider::AwsKms, &body, "/Plaintext")
+    }
+
+    async fn call(action: &str, body: Value) -> Result<Value, SecretsCryptoError> {
+        let region = env("PLFM_KMS_AWS_REGION")
+            .or_else(|| env("AWS_REGION"))
+            .or_else(|| env("AWS_DEFAULT_REGION"))
+            .ok_or_else(|| {
+                SecretsCryptoError::KmsConfig("PLFM_KMS_AWS_REGION is not set".to_string())
+            })?;
+        let endpoint = env("PLFM_KMS_AWS_ENDPOINT")
+            .unwrap_or_else(|| format!("https://kms.{region}.amazonaws.com"));
+        let host = endpoint
+            .split_once("://")
+            .map_or(endpoint.as_str(), |(_, rest)| rest)
+            .trim_end_matches('/')
+            .to_string();
+        let credentials = Credentials {
+            access_key_id: require_env("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: require_env("AWS_SECRET_ACCESS_KEY")?,
+            session_token: env("AWS_SESSION_TOKEN"),
+        };
+
+        let payload = body.to_string();
+        let target = format!("TrentService.{action}");
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sigv4_authorization(
+            &credentials,
+            &region,
+            &host,
+            &amz_date,
+            &target,
+            payload.as_bytes(),
+        );
+
+        let mut request = http_client()
+            .post(&endpoint)
+            .header("content-type", "application/x-amz-json-1.1")
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-target", &target)
+            .header("authorization", authorization)
+            .body(payload);
+        if let Some(token) = &credentials.session_token {
+            request = request.header("x-amz-security-token", token);
+        }
+        send(Provider::AwsKms, request).await
+    }
+
+    pub(super) struct Credentials {
+        pub(super) access_key_id: String,
+        pub(super) secret_access_key: String,
+        pub(super) session_token: Option<String>,
+    }
+
+    fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(data.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// AWS Signature Version 4 `Authorization` header for a KMS JSON request.
+    pub(super) fn sigv4_authorization(
+        credentials: &Credentials,
+        region: &str,
+        host: &str,
+        amz_date: &str,
+        target: &str,
+        payload: &[u8],
+    ) -> String {
+        let date = &amz_date[..8];
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1"),
+            ("host", host),
+            ("x-amz-date", amz_date),
+            ("x-amz-target", target),
+        ];
+        if let Some(token) = &credentials.session_token {
+            headers.push(("x-amz-security-token", token));
+        }
+        headers.sort_by_key(|(name, _)| *name);
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{value}\n"))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "POST\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+            hex::encode(Sha256::digest(payload))
+        );
+
+        let scope = format!("{date}/{region}/kms/aws4_request");
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let k_date = hmac(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date,
+        );
+        let k_region = hmac(&k_date, region);
+        let k_service = hmac(&k_region, "kms");
+        let k_signing = hmac(&k_service, "aws4_request");
+        let signature = hex::encode(hmac(&k_signing, &string_to_sign));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        )
+    }
+}
+
+mod gcp {
+    use super::*;
+
+    const METADATA_TOKEN_URL: &str =
+        "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+    pub(super) async fn encrypt(key: &str, data_key: &[u8]) -> Result<Vec<u8>, SecretsCryptoError> {
+        let body = call(
+            key,
+            "encrypt",
+            json!({
+                "plaintext": B64.encode(data_key),
+                "additionalAuthenticatedData": B64.encode(WRAP_AAD),
+            }),
+        )
+        .await?;
+        b64_field(Provider::GcpKms, &body, "/ciphertext")
+    }
+
+    pub(super) async fn decrypt(
+        key: &str,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, SecretsCryptoError> {
+        let body = call(
+            key,
+            "decrypt",
+            json!({
+                "ciphertext": B64.encode(ciphertext),
+                "additionalAuthenticatedData": B64.encode(WRAP_AAD),
+            }),
+        )
+        .await?;
+        b64_field(Provider::GcpKms, &body, "/plaintext")
+    }
+
+    async fn call(key: &str, method: &str, body: Value) -> Result<Value, SecretsCryptoError> {
+        let endpoint = env("PLFM_KMS_GCP_ENDPOINT")
+            .unwrap_or_else(|| "https://cloudkms.googleapis.com".to_string());
+        let url = format!("{}/v1/{key}:{method}", endpoint.trim_end_matches('/'));
+        let request = http_client()
+            .post(url)
+            .bearer_auth(access_token().await?)
+            .json(&body);
+        send(Provider::GcpKms, request).await
+    }
+
+    /// An OAuth token from the environment, or from the metadata server
+    /// (cached until shortly before it expires).
+    async fn access_token() -> Result<String, SecretsCryptoError> {
+        if let Some(token) = env("PLFM_KMS_GCP_ACCESS_TOKEN") {
+            return Ok(token);
+        }
+
+        static CACHE: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+        if let Some((token, expires)) = CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            if Instant::now() < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        let body = send(
+            Provider::GcpKms,
+            http_client()
+                .get(METADATA_TOKEN_URL)
+                .header("Metadata-Flavor", "Google"),
+        )
+        .await?;
+        let token = body
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                SecretsCryptoError::Kms("gcp-kms: metadata server returned no token".to_string())
+            })?
+            .to_string();
+        let expires_in = body
+            .get("expires_in")
+            .and_then(Value::as_u64)
+            .unwrap_or(300);
+        let expires = Instant::now() + Duration::from_secs(expires_in.saturating_sub(60));
+        *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((token.clone(), expires));
+        Ok(token)
+    }
+}
+
+mod vault {
+    use super::*;
+
+    pub(super) async fn encrypt(key: &str, data_key: &[u8]) -> Result<Vec<u8>, SecretsCryptoError> {
+        let body = call("encrypt", key, json!({ "plaintext": B64.encode(data_key) })).await?;
+        body.pointer("/data/ciphertext")
+            .and_then(Value::as_str)
+            .map(|s| s.as_bytes().to_vec())
+            .ok_or_else(|| {
+                SecretsCryptoError::Kms("vault-transit: response is missing ciphertext".to_string())
+            })
+    }
+
+    pub(super) async fn decrypt(
+        key: &str,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, SecretsCryptoError> {
+        let ciphertext =
+            std::str::from_utf8(ciphertext).map_err(|_| SecretsCryptoError::DecryptFailed)?;
+        let body = call("decrypt", key, json!({ "ciphertext": ciphertext })).await?;
+        b64_field(Provider::VaultTransit, &body, "/data/plaintext")
+    }
+
+    async fn call(operation: &str, key: &str, body: Value) -> Result<Value, SecretsCryptoError> {
+        let addr = require_env("VAULT_ADDR")?;
+        let mount = env("PLFM_KMS_VAULT_MOUNT").unwrap_or_else(|| "transit".to_string());
+        let url = format!(
+            "{}/v1/{}/{operation}/{key}",
+            addr.trim_end_matches('/'),
+            mount.trim_matches('/')
+        );
+        let mut request = http_client()
+            .post(url)
+            .header("X-Vault-Token", require_env("VAULT_TOKEN")?)
+            .json(&body);
+        if let Some(namespace) = env("VAULT_NAMESPACE") {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        send(Provider::VaultTransit, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring() -> LocalKeyring {
+        LocalKeyring {
+            current: MasterKey {
+                id: "current".to_string(),
+                key_bytes: [7u8; KEY_BYTES],
+            },
+            previous: vec![MasterKey {
+                id: "retired".to_string(),
+                key_bytes: [9u8; KEY_BYTES],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_key_id_round_trip() {
+        for raw in [
+            "1a2b3c4d",
+            "1a2b3c4d/org_01HXYZ",
+            "aws-kms:arn:aws:kms:us-east-1:123:alias/plfm-org_1",
+            "gcp-kms:projects/p/locations/global/keyRings/r/cryptoKeys/k",
+            "vault-transit:plfm-org_1",
+        ] {
+            assert_eq!(KeyId::parse(raw).to_string(), raw);
+        }
+        assert_eq!(
+            KeyId::parse("aws-kms:alias/plfm").provider(),
+            Provider::AwsKms
+        );
+        assert_eq!(
+            KeyId::parse("1a2b3c4d/org_1"),
+            KeyId::Local {
+                master_id: "1a2b3c4d".to_string(),
+                org_id: Some("org_1".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_local_wrap_is_scoped_per_org() {
+        let keyring = keyring();
+        let org_a = keyring.current_key(KeyScope::Org("org_a"));
+        let org_b = keyring.current_key(KeyScope::Org("org_b"));
+        assert_eq!(org_a.to_string(), "current/org_a");
+
+        let wrapped = keyring.wrap(&org_a, &[42u8; KEY_BYTES]).unwrap();
+        assert_eq!(
+            keyring.unwrap(&org_a, &wrapped).unwrap(),
+            vec![42u8; KEY_BYTES]
+        );
+        // Another org's KEK (or the platform KEK) cannot unwrap it.
+        assert!(keyring.unwrap(&org_b, &wrapped).is_err());
+        assert!(keyring
+            .unwrap(&keyring.current_key(KeyScope::Platform), &wrapped)
+            .is_err());
+    }
+
+    #[test]
+    fn test_local_previous_keys_unwrap_only_by_id() {
+        let keyring = keyring();
+        let retired = KeyId::parse("retired/org_a");
+        let wrapped = keyring.wrap(&retired, &[1u8; KEY_BYTES]).unwrap();
+        assert_eq!(
+            keyring.unwrap(&retired, &wrapped).unwrap(),
+            vec![1u8; KEY_BYTES]
+        );
+
+        let err = keyring
+            .unwrap(&KeyId::parse("unknown/org_a"), &wrapped)
+            .unwrap_err();
+        assert!(matches!(err, SecretsCryptoError::UnknownMasterKey(_)));
+    }
+
+    #[test]
+    fn test_sigv4_authorization_shape() {
+        let credentials = aws::Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: Some("token".to_string()),
+        };
+        let header = aws::sigv4_authorization(
+            &credentials,
+            "us-east-1",
+            "kms.us-east-1.amazonaws.com",
+            "20150830T123600Z",
+            "TrentService.Encrypt",
+            b"{}",
+        );
+        assert!(header.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/kms/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target, \
+             Signature="
+        ));
+        let signature = header.rsplit('=').next().unwrap();
+        assert_eq!(signature.len(), 64);
+    }
+}
@@ -0,0 +1,185 @@
+//! Secrets encryption and decryption helpers.
+//!
+//! Implements envelope encryption for secret material:
+//! - Data key: random per secret version
+//! - Key encryption key (KEK): held by a [`kms`] provider (local master key,
+//!   AWS KMS, GCP KMS, or Vault transit), separate per org
+//!
+//! Cipher: AES-256-GCM for the payload. The wrapped data key is opaque to
+//! this module; its format belongs to the provider named by its key ID.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use rand::RngCore;
+use thiserror::Error;
+
+pub mod jobs;
+pub mod kms;
+pub mod rotation;
+
+use kms::{KeyId, WrappedKey};
+
+pub const CIPHER_NAME: &str = "aes-256-gcm";
+const DATA_KEY_BYTES: usize = 32;
+const NONCE_BYTES: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum SecretsCryptoError {
+    #[error(
+        "missing secrets master key (set PLFM_SECRETS_MASTER_KEY or PLFM_SECRETS_MASTER_KEY_FILE)"
+    )]
+    MissingMasterKey,
+    #[error("invalid secrets master key encoding")]
+    InvalidMasterKey,
+    #[error("secret encryption failed")]
+    EncryptFailed,
+    #[error("secret decryption failed")]
+    DecryptFailed,
+    #[error("unknown master key id: {0}")]
+    UnknownMasterKey(String),
+    #[error("invalid KMS configuration: {0}")]
+    KmsConfig(String),
+    #[error("KMS request failed: {0}")]
+    Kms(String),
+}
+
+/// Whose key encryption key wraps a data key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyScope<'a> {
+    /// Platform-owned material (e.g. the CA key).
+    Platform,
+    /// Tenant secret material.
+    Org(&'a str),
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptedSecret {
+    pub cipher: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub master_key_id: String,
+    pub wrapped_data_key: Vec<u8>,
+    pub wrapped_data_key_nonce: Vec<u8>,
+    pub plaintext_size_bytes: i32,
+}
+
+/// A data key re-wrapped under the current KEK.
+#[derive(Debug, Clone)]
+pub struct RewrappedKey {
+    pub master_key_id: String,
+    pub wrapped_data_key: Vec<u8>,
+    pub wrapped_data_key_nonce: Vec<u8>,
+}
+
+/// Key ID new material in `scope` is wrapped under.
+pub fn current_key_id(scope: KeyScope<'_>) -> Result<String, SecretsCryptoError> {
+    Ok(kms::current_key(scope)?.to_string())
+}
+
+pub async fn encrypt(
+    scope: KeyScope<'_>,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<EncryptedSecret, SecretsCryptoError> {
+    let key_id = kms::current_key(scope)?;
+
+    let mut data_key = [0u8; DATA_KEY_BYTES];
+    rand::rng().fill_bytes(&mut data_key);
+
+    let mut nonce_bytes = [0u8; NONCE_BYTES];
+    rand::rng().fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let cipher =
+        Aes256Gcm::new_from_slice(&data_key).map_err(|_| SecretsCryptoError::EncryptFailed)?;
+    let ciphertext = cipher
+        .encrypt(
+            nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| SecretsCryptoError::EncryptFailed)?;
+
+    let wrapped = kms::wrap(&key_id, &data_key).await?;
+
+    Ok(EncryptedSecret {
+        cipher: CIPHER_NAME.to_string(),
+        nonce: nonce_bytes.to_vec(),
+        ciphertext,
+        master_key_id: key_id.to_string(),
+        wrapped_data_key: wrapped.ciphertext,
+        wrapped_data_key_nonce: wrapped.nonce,
+        plaintext_size_bytes: plaintext.len() as i32,
+    })
+}
+
+pub async fn decrypt(
+    master_key_id: &str,
+    nonce: &[u8],
+    ciphertext: &[u8],
+    wrapped_data_key: &[u8],
+    wrapped_data_key_nonce: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, SecretsCryptoError> {
+    let data_key = kms::unwrap(
+        &KeyId::parse(master_key_id),
+        &WrappedKey {
+            ciphertext: wrapped_data_key.to_vec(),
+            nonce: wrapped_data_key_nonce.to_vec(),
+        },
+    )
+    .await?;
+
+    if nonce.len() != NONCE_BYTES {
+        return Err(SecretsCryptoError::DecryptFailed);
+    }
+    let nonce = Nonce::from_slice(nonce);
+    let cipher =
+        Aes256Gcm::new_from_slice(&data_key).map_err(|_| SecretsCryptoError::DecryptFailed)?;
+    cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| SecretsCryptoError::DecryptFailed)
+}
+
+/// Re-wrap a data key under the current KEK for `scope`.
+///
+/// The payload ciphertext is untouched. Returns `None` if the data key is
+/// already wrapped under the current key.
+pub async fn rewrap(
+    scope: KeyScope<'_>,
+    master_key_id: &str,
+    wrapped_data_key: &[u8],
+    wrapped_data_key_nonce: &[u8],
+) -> Result<Option<RewrappedKey>, SecretsCryptoError> {
+    let current = kms::current_key(scope)?;
+    let existing = KeyId::parse(master_key_id);
+    if existing == current {
+        return Ok(None);
+    }
+
+    let data_key = kms::unwrap(
+        &existing,
+        &WrappedKey {
+            ciphertext: wrapped_data_key.to_vec(),
+            nonce: wrapped_data_key_nonce.to_vec(),
+        },
+    )
+    .await?;
+    let wrapped = kms::wrap(&current, &data_key).await?;
+
+    Ok(Some(RewrappedKey {
+        master_key_id: current.to_string(),
+        wrapped_data_key: wrapped.ciphertext,
+        wrapped_data_key_nonce: wrapped.nonce,
+    }))
+}
@@ -0,0 +1,224 @@
+//! Re-wrapping stored data keys under the current KEK.
+//!
+//! After a master key, KMS key, or provider change, new material is wrapped
+//! under the new key while existing rows keep their old `master_key_id`.
+//! Re-wrapping unwraps each data key with its old KEK and wraps it with the
+//! current one for its scope; payload ciphertext is never touched. Rows are
+//! processed in `material_id` order so a caller can resume from a cursor.
+
+use sqlx::PgPool;
+use thiserror::Error;
+
+use super::{KeyScope, SecretsCryptoError};
+
+#[derive(Debug, Error)]
+pub enum RotationError {
+    #[error(transparent)]
+    Crypto(#[from] SecretsCryptoError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Outcome of one re-wrap pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewrapReport {
+    /// Rows examined.
+    pub scanned: u64,
+    /// Rows moved to the current key.
+    pub rewrapped: u64,
+    /// Rows that could not be unwrapped or re-wrapped (left unchanged).
+    pub failed: u64,
+    /// Resume after this `material_id`; `None` once the table is exhausted.
+    pub next_cursor: Option<String>,
+}
+
+/// Number of rows wrapped under each key ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyUsage {
+    pub master_key_id: String,
+    pub secret_material: i64,
+    /// Platform signing keys: CA keys and identity token keys.
+    pub pki_authorities: i64,
+}
+
+pub async fn key_usage(pool: &PgPool) -> Result<Vec<KeyUsage>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, i64, i64)>(
+        r#"
+        SELECT master_key_id,
+               COUNT(*) FILTER (WHERE source = 'secret_material'),
+               COUNT(*) FILTER (WHERE source = 'pki_authorities')
+        FROM (
+            SELECT master_key_id, 'secret_material' AS source FROM secret_material
+            UNION ALL
+            SELECT master_key_id, 'pki_authorities' AS source FROM pki_authorities
+            UNION ALL
+            SELECT master_key_id, 'pki_authorities' AS source FROM identity_token_keys
+        ) keys
+        GROUP BY master_key_id
+        ORDER BY master_key_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(master_key_id, secret_material, pki_authorities)| KeyUsage {
+                master_key_id,
+                secret_material,
+                pki_authorities,
+            },
+        )
+        .collect())
+}
+
+/// Re-wrap up to `limit` secret material rows after `after`, optionally only
+/// those belonging to `org_id`.
+pub async fn rewrap_secret_material(
+    pool: &PgPool,
+    org_id: Option<&str>,
+    after: Option<&str>,
+    limit: i64,
+) -> Result<RewrapReport, RotationError> {
+    // Fail fast on a misconfigured provider instead of failing every row.
+    super::current_key_id(KeyScope::Platform)?;
+
+    let rows = sqlx::query_as::<_, (String, String, String, Vec<u8>, Vec<u8>)>(
+        r#"
+        SELECT material_id, org_id, master_key_id, wrapped_data_key, wrapped_data_key_nonce
+        FROM (
+            SELECT sm.material_id,
+                   COALESCE(
+                       (SELECT sv.org_id FROM secret_versions sv
+                        WHERE sv.material_id = sm.material_id LIMIT 1),
+                       (SELECT rc.org_id FROM route_certificates rc
+                        WHERE rc.key_material_id = sm.material_id),
+                       (SELECT nc.org_id FROM notification_channels nc
+                        WHERE nc.secret_material_id = sm.material_id)
+                   ) AS org_id,
+                   sm.master_key_id,
+                   sm.wrapped_data_key,
+                   sm.wrapped_data_key_nonce
+            FROM secret_material sm
+            WHERE ($1::TEXT IS NULL OR sm.material_id > $1)
+        ) material
+        WHERE org_id IS NOT NULL
+          AND ($2::TEXT IS NULL OR org_id = $2)
+        ORDER BY material_id
+        LIMIT $3
+        "#,
+    )
+    .bind(after)
+    .bind(org_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    let mut report = RewrapReport {
+        next_cursor: if rows.len() as i64 == limit {
+            rows.last().map(|row| row.0.clone())
+        } else {
+            None
+        },
+        ..Default::default()
+    };
+
+    for (material_id, org_id, master_key_id, wrapped, wrapped_nonce) in rows {
+        report.scanned += 1;
+        let rewrapped = match super::rewrap(
+            KeyScope::Org(&org_id),
+            &master_key_id,
+            &wrapped,
+            &wrapped_nonce,
+        )
+        .await
+        {
+            Ok(Some(rewrapped)) => rewrapped,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    material_id = %material_id,
+                    master_key_id = %master_key_id,
+                    "Failed to re-wrap secret material"
+                );
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        // Guard on the old key ID so a concurrent pass cannot be overwritten.
+        let updated = sqlx::query(
+            r#"
+            UPDATE secret_material
+            SET master_key_id = $2, wrapped_data_key = $3, wrapped_data_key_nonce = $4
+            WHERE material_id = $1 AND master_key_id = $5
+            "#,
+        )
+        .bind(&material_id)
+        .bind(&rewrapped.master_key_id)
+        .bind(&rewrapped.wrapped_data_key)
+        .bind(&rewrapped.wrapped_data_key_nonce)
+        .bind(&master_key_id)
+        .execute(pool)
+        .await?;
+        report.rewrapped += updated.rows_affected();
+    }
+
+    Ok(report)
+}
+
+/// Re-wrap every platform signing key: CA keys and identity token keys (a
+/// handful of rows; no cursor).
+pub async fn rewrap_pki_authorities(pool: &PgPool) -> Result<RewrapReport, RotationError> {
+    let mut report = RewrapReport::default();
+    for (table, id_column) in [("pki_authorities", "ca_id"), ("identity_token_keys", "kid")] {
+        rewrap_platform_keys(pool, table, id_column, &mut report).await?;
+    }
+    Ok(report)
+}
+
+async fn rewrap_platform_keys(
+    pool: &PgPool,
+    table: &str,
+    id_column: &str,
+    report: &mut RewrapReport,
+) -> Result<(), RotationError> {
+    let rows = sqlx::query_as::<_, (String, String, Vec<u8>, Vec<u8>)>(&format!(
+        r#"
+        SELECT {id_column}, master_key_id, wrapped_data_key, wrapped_data_key_nonce
+        FROM {table}
+        ORDER BY {id_column}
+        "#
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    for (key_id, master_key_id, wrapped, wrapped_nonce) in rows {
+        report.scanned += 1;
+        let Some(rewrapped) =
+            super::rewrap(KeyScope::Platform, &master_key_id, &wrapped, &wrapped_nonce).await?
+        else {
+            continue;
+        };
+
+        let updated = sqlx::query(&format!(
+            r#"
+            UPDATE {table}
+            SET master_key_id = $2, wrapped_data_key = $3, wrapped_data_key_nonce = $4
+            WHERE {id_column} = $1 AND master_key_id = $5
+            "#
+        ))
+        .bind(&key_id)
+        .bind(&rewrapped.master_key_id)
+        .bind(&rewrapped.wrapped_data_key)
+        .bind(&rewrapped.wrapped_data_key_nonce)
+        .bind(&master_key_id)
+        .execute(pool)
+        .await?;
+        report.rewrapped += updated.rows_affected();
+    }
+
+    Ok(())
+}
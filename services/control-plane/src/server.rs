@@ -0,0 +1,305 @@
+//! Control plane process wiring.
+//!
+//! [`run`] starts the background workers, the HTTP API, and the node agent
+//! gRPC service on an open database and serves until shutdown is signalled.
+//! Shared by the `control-plane` binary and the embedded dev stack.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::Result;
+use plfm_proto::agent::v1::NodeAgentServer;
+use tokio::sync::watch;
+use tonic::transport::Server as TonicServer;
+use tracing::{error, info, warn};
+
+use crate::alerts::evaluator::AlertEvaluatorWorker;
+use crate::api;
+use crate::backups::BackupSchedulerWorker;
+use crate::cleanup::{CleanupWorker, CleanupWorkerConfig};
+use crate::db::Database;
+use crate::drift::DriftDetectorWorker;
+use crate::event_export::worker::EventExportWorker;
+use crate::event_integrity::EventCheckpointWorker;
+use crate::grpc::NodeAgentService;
+use crate::managed_dns::ManagedDnsWorker;
+use crate::node_watchdog::NodeWatchdogWorker;
+use crate::notifications::worker::NotifierWorker;
+use crate::projections::{worker::WorkerConfig, ProjectionWorker};
+use crate::readiness::{self, ReadinessConfig};
+use crate::route_verification::RouteVerifierWorker;
+use crate::scheduler::SchedulerWorker;
+use crate::state::AppState;
+
+/// Listen addresses for [`run`].
+#[derive(Debug, Clone, Copy)]
+pub struct ServerAddrs {
+    pub http: SocketAddr,
+    pub grpc: SocketAddr,
+}
+
+/// Wait until `shutdown_rx` reads `true` (or its sender is dropped).
+async fn wait_for_shutdown(mut shutdown_rx: watch::Receiver<bool>) {
+    loop {
+        if *shutdown_rx.borrow() {
+            break;
+        }
+        if shutdown_rx.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Serve the control plane until `shutdown_rx` flips to `true` or a server
+/// exits, then stop the workers.
+///
+/// Nothing starts until the startup readiness checks pass (see
+/// [`readiness::wait_until_ready`]).
+pub async fn run(
+    db: Database,
+    addrs: ServerAddrs,
+    shutdown_rx: watch::Receiver<bool>,
+) -> Result<()> {
+    let readiness_config = ReadinessConfig::from_env();
+    info!(
+        timeout_secs = readiness_config.startup_timeout.as_secs(),
+        "Waiting for startup readiness checks"
+    );
+    if let Err(failing) = readiness::wait_until_ready(&db, &readiness_config).await {
+        let reasons: Vec<String> = failing
+            .iter()
+            .map(|check| {
+                format!(
+                    "{}: {}",
+                    check.name,
+                    check.message.as_deref().unwrap_or("failed")
+                )
+            })
+            .collect();
+        anyhow::bail!("control plane is not ready: {}", reasons.join("; "));
+    }
+    info!("Startup readiness checks passed");
+
+    // Workers stop on this channel, which also fires if a server exits early.
+    let (worker_shutdown_tx, worker_shutdown_rx) = watch::channel(false);
+
+    // Start projection worker in background
+    let projection_worker = ProjectionWorker::new(db.pool().clone(), WorkerConfig::default());
+    let projection_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            if let Err(e) = projection_worker.run(shutdown_rx).await {
+                error!(error = %e, "Projection worker failed");
+            }
+        }
+    });
+
+    // Start scheduler worker in background
+    let scheduler_worker = SchedulerWorker::new(db.pool().clone(), Duration::from_secs(5));
+    let scheduler_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            scheduler_worker.run(shutdown_rx).await;
+        }
+    });
+
+    // Start cleanup worker in background
+    let cleanup_worker = CleanupWorker::new(db.pool().clone(), CleanupWorkerConfig::default());
+    let cleanup_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            cleanup_worker.run(shutdown_rx).await;
+        }
+    });
+
+    // Start route verifier worker in background
+    let route_verifier = RouteVerifierWorker::new(db.pool().clone(), Duration::from_secs(15));
+    let route_verifier_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            route_verifier.run(shutdown_rx).await;
+        }
+    });
+
+    // Start managed DNS worker in background (idle unless a platform domain is set)
+    let managed_dns = ManagedDnsWorker::new(db.pool().clone(), Duration::from_secs(30));
+    let managed_dns_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            managed_dns.run(shutdown_rx).await;
+        }
+    });
+
+    // Start drift detector worker in background
+    let drift_detector = DriftDetectorWorker::new(db.pool().clone(), Duration::from_secs(60));
+    let drift_detector_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            drift_detector.run(shutdown_rx).await;
+        }
+    });
+
+    // Start backup scheduler worker in background
+    let backup_scheduler = BackupSchedulerWorker::new(db.pool().clone(), Duration::from_secs(60));
+    let backup_scheduler_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            backup_scheduler.run(shutdown_rx).await;
+        }
+    });
+
+    // Start notifier worker in background
+    let notifier = NotifierWorker::new(db.pool().clone(), Duration::from_secs(10));
+    let notifier_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            notifier.run(shutdown_rx).await;
+        }
+    });
+
+    // Start alert evaluator worker in background
+    let alert_evaluator = AlertEvaluatorWorker::new(db.pool().clone(), Duration::from_secs(30));
+    let alert_evaluator_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            alert_evaluator.run(shutdown_rx).await;
+        }
+    });
+
+    // Start event log checkpoint worker in background
+    let event_checkpointer =
+        EventCheckpointWorker::new(db.pool().clone(), Duration::from_secs(300));
+    let event_checkpointer_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            event_checkpointer.run(shutdown_rx).await;
+        }
+    });
+
+    // Start node watchdog worker in background
+    let node_watchdog = NodeWatchdogWorker::new(db.pool().clone(), Duration::from_secs(15));
+    let node_watchdog_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            node_watchdog.run(shutdown_rx).await;
+        }
+    });
+
+    // Start event export worker in background (idle unless a sink is configured)
+    let event_exporter = EventExportWorker::new(db.pool().clone(), Duration::from_secs(5));
+    let event_exporter_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            event_exporter.run(shutdown_rx).await;
+        }
+    });
+
+    let state = AppState::new(db);
+
+    let app = api::create_router(state.clone());
+    let listener = tokio::net::TcpListener::bind(addrs.http).await?;
+    info!(addr = %addrs.http, "Listening for HTTP connections");
+
+    let server_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async move {
+                    wait_for_shutdown(shutdown_rx).await;
+                    info!("HTTP server shutting down");
+                })
+                .await
+        }
+    });
+
+    let node_agent_service = NodeAgentService::new(state);
+    info!(addr = %addrs.grpc, "Listening for gRPC connections");
+
+    let grpc_handle = tokio::spawn({
+        let shutdown_rx = worker_shutdown_rx.clone();
+        async move {
+            TonicServer::builder()
+                .add_service(NodeAgentServer::new(node_agent_service))
+                .serve_with_shutdown(addrs.grpc, async move {
+                    wait_for_shutdown(shutdown_rx).await;
+                    info!("gRPC server shutting down");
+                })
+                .await
+        }
+    });
+
+    tokio::select! {
+        _ = wait_for_shutdown(shutdown_rx) => {
+            info!("Received shutdown signal");
+        }
+        result = server_handle => {
+            match result {
+                Ok(Ok(())) => info!("HTTP server exited normally"),
+                Ok(Err(e)) => error!(error = %e, "HTTP server error"),
+                Err(e) => error!(error = %e, "HTTP server task panicked"),
+            }
+        }
+        result = grpc_handle => {
+            match result {
+                Ok(Ok(())) => info!("gRPC server exited normally"),
+                Ok(Err(e)) => error!(error = %e, "gRPC server error"),
+                Err(e) => error!(error = %e, "gRPC server task panicked"),
+            }
+        }
+    }
+
+    let _ = worker_shutdown_tx.send(true);
+
+    info!("Waiting for workers to shut down...");
+    let shutdown_timeout = Duration::from_secs(10);
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, projection_handle).await {
+        warn!(error = %e, "Projection worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, scheduler_handle).await {
+        warn!(error = %e, "Scheduler worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, cleanup_handle).await {
+        warn!(error = %e, "Cleanup worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, route_verifier_handle).await {
+        warn!(error = %e, "Route verifier worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, managed_dns_handle).await {
+        warn!(error = %e, "Managed DNS worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, drift_detector_handle).await {
+        warn!(error = %e, "Drift detector worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, backup_scheduler_handle).await {
+        warn!(error = %e, "Backup scheduler worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, notifier_handle).await {
+        warn!(error = %e, "Notifier worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, alert_evaluator_handle).await {
+        warn!(error = %e, "Alert evaluator worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, event_checkpointer_handle).await {
+        warn!(error = %e, "Event checkpoint worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, node_watchdog_handle).await {
+        warn!(error = %e, "Node watchdog worker did not shut down in time");
+    }
+
+    if let Err(e) = tokio::time::timeout(shutdown_timeout, event_exporter_handle).await {
+        warn!(error = %e, "Event export worker did not shut down in time");
+    }
+
+    Ok(())
+}
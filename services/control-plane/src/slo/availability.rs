@@ -0,0 +1,254 @@
+//! Availability and error budgets, computed from instance history.
+//!
+//! An env is available while it can serve traffic: every process type that
+//! backs one of its routes has at least one ready instance. An env without
+//! routes is available while any of its instances is ready.
+//!
+//! Only time during which some instance is live (booting, ready, draining,
+//! or failed) is observed: an env is not charged for time before its first
+//! instance or while it is scaled to zero.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::status_page::uptime::{self, Interval, Transition};
+
+/// Statuses of an instance the env means to run.
+const LIVE_STATUSES: [&str; 4] = ["booting", "ready", "draining", "failed"];
+
+/// Time an env was observed and available over some period, with the
+/// instance-seconds its instances spent ready and failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Measure {
+    pub observed_seconds: i64,
+    pub available_seconds: i64,
+    pub ready_instance_seconds: i64,
+    pub failed_instance_seconds: i64,
+}
+
+impl Measure {
+    pub fn downtime_seconds(&self) -> i64 {
+        self.observed_seconds - self.available_seconds
+    }
+}
+
+impl std::ops::AddAssign for Measure {
+    fn add_assign(&mut self, other: Self) {
+        self.observed_seconds += other.observed_seconds;
+        self.available_seconds += other.available_seconds;
+        self.ready_instance_seconds += other.ready_instance_seconds;
+        self.failed_instance_seconds += other.failed_instance_seconds;
+    }
+}
+
+/// Ranges covered by both `a` and `b` (each sorted and disjoint).
+fn intersect(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let (mut i, mut j) = (0, 0);
+    let mut both = Vec::new();
+    while i < a.len() && j < b.len() {
+        let from = a[i].0.max(b[j].0);
+        let to = a[i].1.min(b[j].1);
+        if from < to {
+            both.push((from, to));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    both
+}
+
+/// Measure one env over `[since, until]`.
+///
+/// `history` holds the env's transitions by process type, each in the order
+/// they happened and including every instance's last transition before
+/// `since`. `routed` lists the process types that back the env's routes.
+pub fn measure(
+    history: &BTreeMap<String, Vec<Transition>>,
+    routed: &[String],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Measure {
+    if since >= until {
+        return Measure::default();
+    }
+
+    let all: Vec<Transition> = history.values().flatten().cloned().collect();
+    let live = uptime::merge(
+        LIVE_STATUSES
+            .iter()
+            .flat_map(|status| uptime::status_intervals(&all, status, since, until))
+            .collect(),
+    );
+    let ready = |transitions: &[Transition]| {
+        uptime::merge(uptime::status_intervals(transitions, "ready", since, until))
+    };
+    let available = if routed.is_empty() {
+        ready(&all)
+    } else {
+        routed
+            .iter()
+            .map(|process_type| history.get(process_type).map_or(&[][..], Vec::as_slice))
+            .map(ready)
+            .reduce(|a, b| intersect(&a, &b))
+            .unwrap_or_default()
+    };
+
+    let instance_seconds =
+        |status| uptime::total(&uptime::status_intervals(&all, status, since, until)).num_seconds();
+    Measure {
+        observed_seconds: uptime::total(&live).num_seconds(),
+        available_seconds: uptime::total(&available).num_seconds(),
+        ready_instance_seconds: instance_seconds("ready"),
+        failed_instance_seconds: instance_seconds("failed"),
+    }
+}
+
+/// Availability against an objective.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Budget {
+    /// Percent of observed time available, to three decimals; `None` when
+    /// nothing was observed.
+    pub availability: Option<f64>,
+    /// Downtime the objective allows over the observed time.
+    pub budget_seconds: i64,
+    pub consumed_seconds: i64,
+    /// Negative once the budget is exhausted.
+    pub remaining_seconds: i64,
+    /// How fast the budget is being spent: 1.0 spends exactly the budget
+    /// over the window; `None` when nothing was observed.
+    pub burn_rate: Option<f64>,
+}
+
+fn round3(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+/// `measure` against `objective` (percent).
+pub fn budget(measure: &Measure, objective: f64) -> Budget {
+    let observed = measure.observed_seconds;
+    let downtime = measure.downtime_seconds();
+    let allowed = 1.0 - objective / 100.0;
+    let budget_seconds = (observed as f64 * allowed).round() as i64;
+    let (availability, burn_rate) = if observed > 0 {
+        let error_rate = downtime as f64 / observed as f64;
+        (
+            Some(round3(100.0 * (1.0 - error_rate))),
+            Some(round3(error_rate / allowed)),
+        )
+    } else {
+        (None, None)
+    };
+    Budget {
+        availability,
+        budget_seconds,
+        consumed_seconds: downtime,
+        remaining_seconds: budget_seconds - downtime,
+        burn_rate,
+    }
+}
+
+/// Start of the UTC day `at` falls in.
+pub fn day_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc()
+}
+
+/// One UTC day after `start`.
+pub fn day_end(start: DateTime<Utc>) -> DateTime<Utc> {
+    start + Duration::days(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_800_000_000, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn history(entries: &[(&str, &str, &str, i64)]) -> BTreeMap<String, Vec<Transition>> {
+        let mut history: BTreeMap<String, Vec<Transition>> = BTreeMap::new();
+        for (process_type, instance_id, status, minutes) in entries {
+            history
+                .entry(process_type.to_string())
+                .or_default()
+                .push(Transition {
+                    instance_id: instance_id.to_string(),
+                    status: status.to_string(),
+                    at: at(*minutes),
+                });
+        }
+        history
+    }
+
+    #[test]
+    fn test_measure_requires_every_routed_process_type() {
+        let history = history(&[
+            ("web", "inst_w", "ready", 0),
+            ("api", "inst_a", "booting", 0),
+            ("api", "inst_a", "ready", 10),
+            ("web", "inst_w", "failed", 60),
+            ("web", "inst_w", "ready", 70),
+        ]);
+        let routed = ["web".to_string(), "api".to_string()];
+
+        let measure = measure(&history, &routed, at(0), at(100));
+        assert_eq!(measure.observed_seconds, 6000);
+        // Both ready 10-60 and 70-100.
+        assert_eq!(measure.available_seconds, 80 * 60);
+        assert_eq!(measure.ready_instance_seconds, (90 + 90) * 60);
+        assert_eq!(measure.failed_instance_seconds, 10 * 60);
+
+        // Without routes, any ready instance counts.
+        let unrouted = super::measure(&history, &[], at(0), at(100));
+        assert_eq!(unrouted.available_seconds, 6000);
+
+        // A route to a process type with no instances is never available.
+        let missing = super::measure(&history, &["worker".to_string()], at(0), at(100));
+        assert_eq!(missing.available_seconds, 0);
+        assert_eq!(missing.observed_seconds, 6000);
+    }
+
+    #[test]
+    fn test_measure_observes_live_time_only() {
+        let history = history(&[
+            ("web", "inst_w", "ready", 50),
+            ("web", "inst_w", "stopped", 70),
+            ("web", "inst_x", "booting", 90),
+        ]);
+        let measure = measure(&history, &[], at(0), at(100));
+        // Live 50-70 and 90-100; down while inst_x boots.
+        assert_eq!(measure.observed_seconds, 30 * 60);
+        assert_eq!(measure.downtime_seconds(), 10 * 60);
+        assert_eq!(
+            super::measure(&history, &[], at(0), at(40)),
+            Measure::default()
+        );
+    }
+
+    #[test]
+    fn test_budget() {
+        let measure = Measure {
+            observed_seconds: 100_000,
+            available_seconds: 99_950,
+            ..Measure::default()
+        };
+        let budget = budget(&measure, 99.9);
+        assert_eq!(budget.availability, Some(99.95));
+        assert_eq!(budget.budget_seconds, 100);
+        assert_eq!(budget.consumed_seconds, 50);
+        assert_eq!(budget.remaining_seconds, 50);
+        assert_eq!(budget.burn_rate, Some(0.5));
+
+        let empty = super::budget(&Measure::default(), 99.9);
+        assert_eq!(empty.availability, None);
+        assert_eq!(empty.burn_rate, None);
+    }
+}
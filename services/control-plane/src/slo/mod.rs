@@ -0,0 +1,396 @@
+//! Env availability objectives (SLOs).
+//!
+//! Each env has an availability objective (default [`DEFAULT_OBJECTIVE`]
+//! percent). Availability comes from `instance_status_history` (see
+//! [`availability`]): past UTC days are rolled up into `env_slo_daily` by
+//! the cleanup worker, and the current day is measured live. A report over
+//! a window sums the days and compares them with the objective to give the
+//! error budget left and how fast it is burning.
+//!
+//! `slo_burn_rate` alert rules measure short windows live.
+//!
+//! See: docs/specs/observability/slo.md
+
+pub mod availability;
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::status_page::uptime::Transition;
+use availability::{day_end, day_start, Budget, Measure};
+
+/// Objective of envs that have not set one, in percent.
+pub const DEFAULT_OBJECTIVE: f64 = 99.9;
+
+/// Bounds of a settable objective, in percent.
+pub const MIN_OBJECTIVE: f64 = 50.0;
+pub const MAX_OBJECTIVE: f64 = 99.999;
+
+pub const DEFAULT_WINDOW_DAYS: i64 = 30;
+pub const MAX_WINDOW_DAYS: i64 = 90;
+
+/// How many past days rollups are computed (and recomputed) for. History is
+/// kept for 31 days, so older days can only come from existing rollups.
+const ROLLUP_LOOKBACK_DAYS: i64 = 30;
+
+/// How long daily rollups are kept.
+pub const ROLLUP_RETENTION_DAYS: i32 = 400;
+
+/// Window of the short-term burn rate in reports.
+const SHORT_BURN_WINDOW_SECS: i64 = 3600;
+
+// =============================================================================
+// Objectives
+// =============================================================================
+
+/// The objective of `env_id`, in percent.
+pub async fn objective(pool: &PgPool, env_id: &str) -> Result<f64, sqlx::Error> {
+    let objective: Option<f64> =
+        sqlx::query_scalar("SELECT objective FROM env_slos WHERE env_id = $1")
+            .bind(env_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(objective.unwrap_or(DEFAULT_OBJECTIVE))
+}
+
+/// The objectives the org's envs have set, by env ID.
+pub async fn objectives(pool: &PgPool, org_id: &str) -> Result<HashMap<String, f64>, sqlx::Error> {
+    let rows: Vec<(String, f64)> =
+        sqlx::query_as("SELECT env_id, objective FROM env_slos WHERE org_id = $1")
+            .bind(org_id)
+            .fetch_all(pool)
+            .await?;
+    Ok(rows.into_iter().collect())
+}
+
+pub async fn set_objective(
+    pool: &PgPool,
+    org_id: &str,
+    app_id: &str,
+    env_id: &str,
+    objective: f64,
+    updated_by: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO env_slos (env_id, org_id, app_id, objective, updated_by)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (env_id) DO UPDATE SET
+            objective = EXCLUDED.objective,
+            updated_by = EXCLUDED.updated_by,
+            updated_at = now()
+        "#,
+    )
+    .bind(env_id)
+    .bind(org_id)
+    .bind(app_id)
+    .bind(objective)
+    .bind(updated_by)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// =============================================================================
+// Measuring
+// =============================================================================
+
+#[derive(sqlx::FromRow)]
+struct HistoryRow {
+    instance_id: String,
+    process_type: String,
+    status: String,
+    reported_at: DateTime<Utc>,
+}
+
+/// The env's status transitions since `since`, plus each instance's last
+/// transition before it, by process type and in the order they happened.
+async fn load_history(
+    pool: &PgPool,
+    env_id: &str,
+    since: DateTime<Utc>,
+) -> Result<BTreeMap<String, Vec<Transition>>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, HistoryRow>(
+        r#"
+        SELECT h.instance_id, COALESCE(d.process_type, '') AS process_type,
+               h.status, h.reported_at
+        FROM (
+            (
+                SELECT DISTINCT ON (instance_id)
+                    history_id, instance_id, status, reported_at
+                FROM instance_status_history
+                WHERE env_id = $1 AND reported_at < $2
+                ORDER BY instance_id, reported_at DESC, history_id DESC
+            )
+            UNION ALL
+            SELECT history_id, instance_id, status, reported_at
+            FROM instance_status_history
+            WHERE env_id = $1 AND reported_at >= $2
+        ) h
+        LEFT JOIN instances_desired_view d ON d.instance_id = h.instance_id
+        ORDER BY h.reported_at, h.history_id
+        "#,
+    )
+    .bind(env_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let mut history: BTreeMap<String, Vec<Transition>> = BTreeMap::new();
+    for row in rows {
+        history
+            .entry(row.process_type)
+            .or_default()
+            .push(Transition {
+                instance_id: row.instance_id,
+                status: row.status,
+                at: row.reported_at,
+            });
+    }
+    Ok(history)
+}
+
+/// Process types backing the env's routes.
+async fn routed_process_types(pool: &PgPool, env_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT backend_process_type FROM routes_view
+        WHERE env_id = $1 AND NOT is_deleted
+        ORDER BY backend_process_type
+        "#,
+    )
+    .bind(env_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Measure `env_id` over `[since, until]` from its history.
+pub async fn measure_env(
+    pool: &PgPool,
+    env_id: &str,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Measure, sqlx::Error> {
+    let history = load_history(pool, env_id, since).await?;
+    let routed = routed_process_types(pool, env_id).await?;
+    Ok(availability::measure(&history, &routed, since, until))
+}
+
+// =============================================================================
+// Daily rollups
+// =============================================================================
+
+#[derive(sqlx::FromRow)]
+struct RollupTarget {
+    env_id: String,
+    org_id: String,
+    first_at: DateTime<Utc>,
+    last_day: Option<NaiveDate>,
+}
+
+/// Roll up every finished UTC day not yet rolled up (and the last one that
+/// was, in case late events changed it) for every live env. Returns the
+/// number of days written.
+pub async fn rollup(pool: &PgPool, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+    let targets = sqlx::query_as::<_, RollupTarget>(
+        r#"
+        SELECT h.env_id, h.org_id, MIN(h.reported_at) AS first_at,
+               (SELECT MAX(r.day) FROM env_slo_daily r WHERE r.env_id = h.env_id) AS last_day
+        FROM instance_status_history h
+        JOIN envs_view e ON e.env_id = h.env_id
+        WHERE NOT e.is_deleted
+        GROUP BY h.env_id, h.org_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let today = day_start(now);
+    let mut written = 0;
+    for target in targets {
+        let mut day = day_start(target.first_at).max(today - Duration::days(ROLLUP_LOOKBACK_DAYS));
+        if let Some(last_day) = target.last_day {
+            day = day.max(last_day.and_time(NaiveTime::MIN).and_utc());
+        }
+        if day >= today {
+            continue;
+        }
+
+        let history = load_history(pool, &target.env_id, day).await?;
+        let routed = routed_process_types(pool, &target.env_id).await?;
+        while day < today {
+            let measure = availability::measure(&history, &routed, day, day_end(day));
+            sqlx::query(
+                r#"
+                INSERT INTO env_slo_daily
+                    (env_id, day, org_id, observed_seconds, available_seconds,
+                     ready_instance_seconds, failed_instance_seconds)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (env_id, day) DO UPDATE SET
+                    observed_seconds = EXCLUDED.observed_seconds,
+                    available_seconds = EXCLUDED.available_seconds,
+                    ready_instance_seconds = EXCLUDED.ready_instance_seconds,
+                    failed_instance_seconds = EXCLUDED.failed_instance_seconds,
+                    computed_at = now()
+                "#,
+            )
+            .bind(&target.env_id)
+            .bind(day.date_naive())
+            .bind(&target.org_id)
+            .bind(measure.observed_seconds)
+            .bind(measure.available_seconds)
+            .bind(measure.ready_instance_seconds)
+            .bind(measure.failed_instance_seconds)
+            .execute(pool)
+            .await?;
+            written += 1;
+            day = day_end(day);
+        }
+    }
+    Ok(written)
+}
+
+/// Drop rollups older than [`ROLLUP_RETENTION_DAYS`].
+pub async fn prune_rollups(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM env_slo_daily WHERE day < CURRENT_DATE - make_interval(days => $1)",
+    )
+    .bind(ROLLUP_RETENTION_DAYS)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// =============================================================================
+// Reports
+// =============================================================================
+
+#[derive(sqlx::FromRow)]
+struct RollupRow {
+    day: NaiveDate,
+    observed_seconds: i64,
+    available_seconds: i64,
+    ready_instance_seconds: i64,
+    failed_instance_seconds: i64,
+}
+
+/// One UTC day of a report.
+#[derive(Debug, Clone, Serialize)]
+pub struct SloDay {
+    pub day: NaiveDate,
+    pub availability: Option<f64>,
+    pub observed_seconds: i64,
+    pub downtime_seconds: i64,
+}
+
+/// An env's availability over a window against its objective.
+#[derive(Debug, Clone, Serialize)]
+pub struct SloReport {
+    pub env_id: String,
+    pub objective: f64,
+    pub window_days: i64,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub observed_seconds: i64,
+    pub available_seconds: i64,
+    pub ready_instance_seconds: i64,
+    pub failed_instance_seconds: i64,
+    #[serde(flatten)]
+    pub budget: Budget,
+    /// Burn rate over the last hour, for fast-burn checks.
+    pub burn_rate_1h: Option<f64>,
+    /// Oldest first; the last entry is today so far.
+    pub days: Vec<SloDay>,
+}
+
+/// Report on `env_id` over the `window_days` UTC days ending today.
+///
+/// Days without a rollup (the current one, and any the cleanup worker has
+/// not reached yet) are measured from history while it still covers them.
+pub async fn report(
+    pool: &PgPool,
+    env_id: &str,
+    window_days: i64,
+    now: DateTime<Utc>,
+) -> Result<SloReport, sqlx::Error> {
+    let objective = objective(pool, env_id).await?;
+    let today = day_start(now);
+    let since = today - Duration::days(window_days - 1);
+
+    let rollups: HashMap<NaiveDate, Measure> = sqlx::query_as::<_, RollupRow>(
+        r#"
+        SELECT day, observed_seconds, available_seconds,
+               ready_instance_seconds, failed_instance_seconds
+        FROM env_slo_daily
+        WHERE env_id = $1 AND day >= $2 AND day < $3
+        "#,
+    )
+    .bind(env_id)
+    .bind(since.date_naive())
+    .bind(today.date_naive())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        (
+            row.day,
+            Measure {
+                observed_seconds: row.observed_seconds,
+                available_seconds: row.available_seconds,
+                ready_instance_seconds: row.ready_instance_seconds,
+                failed_instance_seconds: row.failed_instance_seconds,
+            },
+        )
+    })
+    .collect();
+
+    let oldest_live = (0..window_days)
+        .map(|i| since + Duration::days(i))
+        .find(|day| {
+            !rollups.contains_key(&day.date_naive())
+                && *day >= today - Duration::days(ROLLUP_LOOKBACK_DAYS)
+        })
+        .unwrap_or(today);
+    let short_since = now - Duration::seconds(SHORT_BURN_WINDOW_SECS);
+    let history = load_history(pool, env_id, oldest_live.min(short_since)).await?;
+    let routed = routed_process_types(pool, env_id).await?;
+
+    let mut total = Measure::default();
+    let mut days = Vec::with_capacity(window_days as usize);
+    for i in 0..window_days {
+        let day = since + Duration::days(i);
+        let measure = match rollups.get(&day.date_naive()) {
+            Some(measure) => *measure,
+            None if day >= oldest_live => {
+                availability::measure(&history, &routed, day, day_end(day).min(now))
+            }
+            None => Measure::default(),
+        };
+        total += measure;
+        days.push(SloDay {
+            day: day.date_naive(),
+            availability: availability::budget(&measure, objective).availability,
+            observed_seconds: measure.observed_seconds,
+            downtime_seconds: measure.downtime_seconds(),
+        });
+    }
+
+    let short = availability::measure(&history, &routed, short_since, now);
+    Ok(SloReport {
+        env_id: env_id.to_string(),
+        objective,
+        window_days,
+        since,
+        until: now,
+        observed_seconds: total.observed_seconds,
+        available_seconds: total.available_seconds,
+        ready_instance_seconds: total.ready_instance_seconds,
+        failed_instance_seconds: total.failed_instance_seconds,
+        budget: availability::budget(&total, objective),
+        burn_rate_1h: availability::budget(&short, objective).burn_rate,
+        days,
+    })
+}
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use crate::db::Database;
+use crate::grpc::ExecTunnels;
 
 /// Shared application state.
 ///
@@ -14,13 +15,17 @@ pub struct AppState {
 
 struct AppStateInner {
     db: Database,
+    exec_tunnels: ExecTunnels,
 }
 
 impl AppState {
     /// Create a new application state.
     pub fn new(db: Database) -> Self {
         Self {
-            inner: Arc::new(AppStateInner { db }),
+            inner: Arc::new(AppStateInner {
+                db,
+                exec_tunnels: ExecTunnels::new(),
+            }),
         }
     }
 
@@ -28,4 +33,9 @@ impl AppState {
     pub fn db(&self) -> &Database {
         &self.inner.db
     }
+
+    /// Exec tunnels opened by connected node agents.
+    pub fn exec_tunnels(&self) -> &ExecTunnels {
+        &self.inner.exec_tunnels
+    }
 }
@@ -0,0 +1,146 @@
+//! HTML rendering of a public status page.
+//!
+//! The page is a single self-contained document (inline CSS, no scripts) so
+//! it can be served as-is or saved by an uptime mirror.
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::{EnvStatus, PublicStatus};
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}%"))
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn status_label(status: EnvStatus) -> &'static str {
+    match status {
+        EnvStatus::Operational => "Operational",
+        EnvStatus::Degraded => "Degraded",
+        EnvStatus::Down => "Down",
+        EnvStatus::Stopped => "Stopped",
+    }
+}
+
+/// Render `status` as an HTML document.
+pub fn render(status: &PublicStatus) -> String {
+    let title = escape(&status.title);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>\n\
+         body{{font-family:system-ui,sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem;color:#222}}\n\
+         table{{width:100%;border-collapse:collapse}}th,td{{text-align:left;padding:.4rem;border-bottom:1px solid #ddd}}\n\
+         .operational{{color:#1a7f37}}.degraded{{color:#9a6700}}.down{{color:#cf222e}}.stopped{{color:#6e7781}}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <p class=\"{}\"><strong>{}</strong></p>\n",
+        status.status.as_str(),
+        status_label(status.status),
+    );
+
+    html.push_str(
+        "<h2>Services</h2>\n<table>\n\
+         <tr><th>Service</th><th>Status</th><th>24h</th><th>7d</th><th>30d</th></tr>\n",
+    );
+    for env in &status.envs {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&env.name),
+            env.status.as_str(),
+            status_label(env.status),
+            percent(env.uptime_24h),
+            percent(env.uptime_7d),
+            percent(env.uptime_30d),
+        );
+    }
+    html.push_str("</table>\n<h2>Recent incidents</h2>\n");
+
+    if status.incidents.is_empty() {
+        html.push_str("<p>No incidents in the last 30 days.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for incident in &status.incidents {
+            let until = incident
+                .resolved_at
+                .map_or_else(|| "ongoing".to_string(), timestamp);
+            let _ = write!(
+                html,
+                "<li><strong>{}</strong> ({}): {} &ndash; {}",
+                escape(&incident.title),
+                escape(&incident.env),
+                timestamp(incident.started_at),
+                until,
+            );
+            if let Some(summary) = &incident.summary {
+                let _ = write!(html, "<br>{}", escape(summary));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let _ = write!(
+        html,
+        "<p><small>Updated {}</small></p>\n</body>\n</html>\n",
+        timestamp(status.generated_at)
+    );
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status_page::{PublicEnv, PublicIncident};
+
+    #[test]
+    fn test_render_escapes_names() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let status = PublicStatus {
+            title: "Acme <Status>".to_string(),
+            status: EnvStatus::Degraded,
+            generated_at: now,
+            envs: vec![PublicEnv {
+                name: "web/prod".to_string(),
+                status: EnvStatus::Degraded,
+                uptime_24h: Some(99.5),
+                uptime_7d: None,
+                uptime_30d: None,
+            }],
+            incidents: vec![PublicIncident {
+                title: "prod-down".to_string(),
+                env: "web/prod".to_string(),
+                summary: Some("1 of 2 instances ready & 1 failed".to_string()),
+                started_at: now,
+                resolved_at: None,
+            }],
+        };
+
+        let html = render(&status);
+        assert!(html.contains("<title>Acme &lt;Status&gt;</title>"));
+        assert!(html.contains("<td class=\"degraded\">Degraded</td><td>99.50%</td><td>-</td>"));
+        assert!(html.contains("ready &amp; 1 failed"));
+        assert!(html.contains("&ndash; ongoing"));
+    }
+}
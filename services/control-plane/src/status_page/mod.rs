@@ -0,0 +1,529 @@
+//! Public org status pages.
+//!
+//! An org admin enables a status page and shares its link. The link carries
+//! a token that is the HMAC-SHA256 of the org ID under the page's signing
+//! key, so it needs no lookup table and rotating the key revokes every link
+//! handed out so far.
+//!
+//! The page summarizes, for the envs it shows:
+//! - current health, from the same counts as `env_unhealthy` alert rules
+//! - uptime over 24 hours, 7 days, and 30 days, from
+//!   `instance_status_history` (see [`uptime`])
+//! - incidents of the last 30 days, from `alert.fired` / `alert.resolved`
+//!   events targeting those envs
+//!
+//! See: docs/specs/observability/status-pages.md
+
+pub mod html;
+pub mod uptime;
+
+use std::collections::{HashMap, HashSet};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use plfm_events::{event_types, AlertFiredPayload, AlertResolvedPayload};
+use rand::Rng;
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::PgPool;
+use thiserror::Error;
+
+use crate::alerts::evaluator::{env_health, EnvHealth};
+use uptime::{AlertEvent, Transition};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Prefix of public status tokens.
+pub const TOKEN_PREFIX: &str = "trc_st_";
+
+/// How far back incidents are listed.
+pub const INCIDENT_WINDOW_DAYS: i64 = 30;
+
+/// Incidents listed on a page, newest first.
+pub const MAX_INCIDENTS: usize = 20;
+
+/// How long instance status history is kept: the longest uptime window plus
+/// a day of slack.
+pub const HISTORY_RETENTION_DAYS: i32 = 31;
+
+const SIGNING_KEY_BYTES: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum StatusPageError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+// =============================================================================
+// Page settings and tokens
+// =============================================================================
+
+/// The settable fields of a status page.
+#[derive(Debug, Clone)]
+pub struct PageSpec {
+    pub title: String,
+    /// Envs to show; empty shows every env in the org.
+    pub env_ids: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StatusPage {
+    pub org_id: String,
+    pub title: String,
+    pub env_ids: Vec<String>,
+    pub enabled: bool,
+    pub signing_key: Vec<u8>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StatusPage {
+    /// The page's current public token.
+    pub fn token(&self) -> String {
+        format!(
+            "{TOKEN_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(sign(&self.signing_key, &self.org_id))
+        )
+    }
+
+    /// Whether `token` was issued for this page under its current key.
+    pub fn verify_token(&self, token: &str) -> bool {
+        let Some(encoded) = token.strip_prefix(TOKEN_PREFIX) else {
+            return false;
+        };
+        let Ok(tag) = URL_SAFE_NO_PAD.decode(encoded) else {
+            return false;
+        };
+        mac(&self.signing_key, &self.org_id)
+            .verify_slice(&tag)
+            .is_ok()
+    }
+}
+
+fn mac(key: &[u8], org_id: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(b"status-page:");
+    mac.update(org_id.as_bytes());
+    mac
+}
+
+fn sign(key: &[u8], org_id: &str) -> Vec<u8> {
+    mac(key, org_id).finalize().into_bytes().to_vec()
+}
+
+fn new_signing_key() -> Vec<u8> {
+    let mut key = vec![0u8; SIGNING_KEY_BYTES];
+    rand::rng().fill(key.as_mut_slice());
+    key
+}
+
+const PAGE_COLUMNS: &str =
+    "org_id, title, env_ids, enabled, signing_key, created_by, created_at, updated_at";
+
+pub async fn get_page(pool: &PgPool, org_id: &str) -> Result<Option<StatusPage>, sqlx::Error> {
+    let query = format!("SELECT {PAGE_COLUMNS} FROM status_pages WHERE org_id = $1");
+    sqlx::query_as::<_, StatusPage>(&query)
+        .bind(org_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Create or replace the org's page. An existing page keeps its signing
+/// key, so links already shared keep working.
+pub async fn put_page(
+    pool: &PgPool,
+    org_id: &str,
+    spec: &PageSpec,
+    actor_id: &str,
+) -> Result<StatusPage, sqlx::Error> {
+    let query = format!(
+        r#"
+        INSERT INTO status_pages (org_id, title, env_ids, enabled, signing_key, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (org_id) DO UPDATE SET
+            title = EXCLUDED.title,
+            env_ids = EXCLUDED.env_ids,
+            enabled = EXCLUDED.enabled,
+            updated_at = now()
+        RETURNING {PAGE_COLUMNS}
+        "#
+    );
+    sqlx::query_as::<_, StatusPage>(&query)
+        .bind(org_id)
+        .bind(&spec.title)
+        .bind(&spec.env_ids)
+        .bind(spec.enabled)
+        .bind(new_signing_key())
+        .bind(actor_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Replace the page's signing key, revoking its current token.
+pub async fn rotate_token(pool: &PgPool, org_id: &str) -> Result<Option<StatusPage>, sqlx::Error> {
+    let query = format!(
+        r#"
+        UPDATE status_pages SET signing_key = $2, updated_at = now()
+        WHERE org_id = $1
+        RETURNING {PAGE_COLUMNS}
+        "#
+    );
+    sqlx::query_as::<_, StatusPage>(&query)
+        .bind(org_id)
+        .bind(new_signing_key())
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn delete_page(pool: &PgPool, org_id: &str) -> Result<bool, sqlx::Error> {
+    let deleted = sqlx::query("DELETE FROM status_pages WHERE org_id = $1")
+        .bind(org_id)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    Ok(deleted > 0)
+}
+
+// =============================================================================
+// Public summary
+// =============================================================================
+
+/// Health of one env, worst last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvStatus {
+    /// Scaled to zero on purpose.
+    Stopped,
+    Operational,
+    /// Some instances failed or not enough are ready.
+    Degraded,
+    /// Instances are desired but none is ready.
+    Down,
+}
+
+impl EnvStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EnvStatus::Stopped => "stopped",
+            EnvStatus::Operational => "operational",
+            EnvStatus::Degraded => "degraded",
+            EnvStatus::Down => "down",
+        }
+    }
+
+    pub fn of(health: &EnvHealth) -> Self {
+        if health.desired == 0 {
+            EnvStatus::Stopped
+        } else if health.ready == 0 {
+            EnvStatus::Down
+        } else if health.ready < health.desired || health.failed > 0 {
+            EnvStatus::Degraded
+        } else {
+            EnvStatus::Operational
+        }
+    }
+
+    /// The page's overall status: its worst env, ignoring stopped ones.
+    pub fn overall(envs: impl IntoIterator<Item = EnvStatus>) -> Self {
+        envs.into_iter()
+            .max()
+            .filter(|status| *status != EnvStatus::Stopped)
+            .unwrap_or(EnvStatus::Operational)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicEnv {
+    /// `app/env`.
+    pub name: String,
+    pub status: EnvStatus,
+    pub uptime_24h: Option<f64>,
+    pub uptime_7d: Option<f64>,
+    pub uptime_30d: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicIncident {
+    pub title: String,
+    /// `app/env` the incident affected.
+    pub env: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// What a status page shows. Carries names only, never IDs.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicStatus {
+    pub title: String,
+    pub status: EnvStatus,
+    pub generated_at: DateTime<Utc>,
+    pub envs: Vec<PublicEnv>,
+    pub incidents: Vec<PublicIncident>,
+}
+
+#[derive(sqlx::FromRow)]
+struct HistoryRow {
+    instance_id: String,
+    env_id: String,
+    status: String,
+    reported_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct AlertEventRow {
+    event_type: String,
+    occurred_at: DateTime<Utc>,
+    payload: Option<serde_json::Value>,
+}
+
+/// Status transitions of the org's instances since `since`, plus each
+/// instance's last transition before it, in the order they happened.
+async fn load_history(
+    pool: &PgPool,
+    org_id: &str,
+    since: DateTime<Utc>,
+) -> Result<HashMap<String, Vec<Transition>>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, HistoryRow>(
+        r#"
+        SELECT instance_id, env_id, status, reported_at FROM (
+            (
+                SELECT DISTINCT ON (instance_id)
+                    history_id, instance_id, env_id, status, reported_at
+                FROM instance_status_history
+                WHERE org_id = $1 AND reported_at < $2
+                ORDER BY instance_id, reported_at DESC, history_id DESC
+            )
+            UNION ALL
+            SELECT history_id, instance_id, env_id, status, reported_at
+            FROM instance_status_history
+            WHERE org_id = $1 AND reported_at >= $2
+        ) history
+        ORDER BY reported_at, history_id
+        "#,
+    )
+    .bind(org_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_env: HashMap<String, Vec<Transition>> = HashMap::new();
+    for row in rows {
+        by_env.entry(row.env_id).or_default().push(Transition {
+            instance_id: row.instance_id,
+            status: row.status,
+            at: row.reported_at,
+        });
+    }
+    Ok(by_env)
+}
+
+/// The org's alert events since `since`. Alert events always keep their
+/// JSON payload (only instance and node events may be stored protobuf-only).
+async fn load_alert_events(
+    pool: &PgPool,
+    org_id: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<AlertEvent>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, AlertEventRow>(
+        r#"
+        SELECT event_type, occurred_at, payload
+        FROM events
+        WHERE org_id = $1 AND event_type = ANY($2) AND occurred_at >= $3
+        ORDER BY event_id
+        "#,
+    )
+    .bind(org_id)
+    .bind(&[event_types::ALERT_FIRED, event_types::ALERT_RESOLVED][..])
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let events = rows
+        .into_iter()
+        .filter_map(|row| {
+            let payload = row.payload?;
+            if row.event_type == event_types::ALERT_FIRED {
+                let p: AlertFiredPayload = serde_json::from_value(payload).ok()?;
+                Some(AlertEvent::Fired {
+                    rule_id: p.rule_id,
+                    rule_name: p.rule_name,
+                    target: p.target,
+                    summary: p.summary,
+                    at: row.occurred_at,
+                })
+            } else {
+                let p: AlertResolvedPayload = serde_json::from_value(payload).ok()?;
+                let fired_at = DateTime::parse_from_rfc3339(&p.fired_at)
+                    .map(|at| at.with_timezone(&Utc))
+                    .unwrap_or(row.occurred_at);
+                Some(AlertEvent::Resolved {
+                    rule_id: p.rule_id,
+                    rule_name: p.rule_name,
+                    target: p.target,
+                    fired_at,
+                    at: row.occurred_at,
+                })
+            }
+        })
+        .collect();
+    Ok(events)
+}
+
+/// Build the public summary of `page`.
+pub async fn public_status(
+    pool: &PgPool,
+    page: &StatusPage,
+) -> Result<PublicStatus, StatusPageError> {
+    let now = Utc::now();
+    let shown: HashSet<&str> = page.env_ids.iter().map(String::as_str).collect();
+    let envs: Vec<EnvHealth> = env_health(pool, &page.org_id, None, None)
+        .await?
+        .into_iter()
+        .filter(|env| shown.is_empty() || shown.contains(env.env_id.as_str()))
+        .collect();
+
+    let history = load_history(pool, &page.org_id, now - Duration::days(30)).await?;
+    let public_envs: Vec<PublicEnv> = envs
+        .iter()
+        .map(|env| {
+            let transitions = history.get(&env.env_id).map_or(&[][..], Vec::as_slice);
+            let uptime =
+                |days| uptime::uptime_percent(transitions, now - Duration::days(days), now);
+            PublicEnv {
+                name: format!("{}/{}", env.app_name, env.env_name),
+                status: EnvStatus::of(env),
+                uptime_24h: uptime(1),
+                uptime_7d: uptime(7),
+                uptime_30d: uptime(30),
+            }
+        })
+        .collect();
+
+    // Incidents on envs the page does not show (and on platform targets,
+    // which are never env IDs) are left out.
+    let names: HashMap<&str, &str> = envs
+        .iter()
+        .zip(&public_envs)
+        .map(|(env, public)| (env.env_id.as_str(), public.name.as_str()))
+        .collect();
+    let events = load_alert_events(
+        pool,
+        &page.org_id,
+        now - Duration::days(INCIDENT_WINDOW_DAYS),
+    )
+    .await?;
+    let incidents = uptime::incidents(&events)
+        .into_iter()
+        .filter_map(|incident| {
+            let env = names.get(incident.target.as_str())?;
+            Some(PublicIncident {
+                title: incident.title,
+                env: env.to_string(),
+                summary: incident.summary,
+                started_at: incident.started_at,
+                resolved_at: incident.resolved_at,
+            })
+        })
+        .take(MAX_INCIDENTS)
+        .collect();
+
+    Ok(PublicStatus {
+        title: page.title.clone(),
+        status: EnvStatus::overall(public_envs.iter().map(|env| env.status)),
+        generated_at: now,
+        envs: public_envs,
+        incidents,
+    })
+}
+
+/// Drop history older than [`HISTORY_RETENTION_DAYS`], except each
+/// instance's last transition before the cutoff when it is `ready`: that
+/// row says the instance was up when the longest window opened.
+pub async fn prune_history(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM instance_status_history h
+        WHERE h.reported_at < now() - make_interval(days => $1)
+          AND (
+              h.status <> 'ready'
+              OR EXISTS (
+                  SELECT 1 FROM instance_status_history n
+                  WHERE n.instance_id = h.instance_id
+                    AND n.history_id > h.history_id
+                    AND n.reported_at < now() - make_interval(days => $1)
+              )
+          )
+        "#,
+    )
+    .bind(HISTORY_RETENTION_DAYS)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(key: &[u8]) -> StatusPage {
+        StatusPage {
+            org_id: "org_01HV4Z2WQXKJNM8GPQY6VBKC3D".to_string(),
+            title: "Acme".to_string(),
+            env_ids: Vec::new(),
+            enabled: true,
+            signing_key: key.to_vec(),
+            created_by: "usr_1".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_token_round_trip_and_rotation() {
+        let current = page(b"key-one");
+        let token = current.token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert!(current.verify_token(&token));
+
+        assert!(!page(b"key-two").verify_token(&token));
+        let mut other_org = page(b"key-one");
+        other_org.org_id = "org_01HV4Z2WQXKJNM8GPQY6VBKC3E".to_string();
+        assert!(!other_org.verify_token(&token));
+
+        assert!(!current.verify_token(token.trim_start_matches(TOKEN_PREFIX)));
+        assert!(!current.verify_token("trc_st_not*base64"));
+    }
+
+    #[test]
+    fn test_env_status() {
+        let health = |desired, ready, failed| EnvHealth {
+            env_id: "env_1".to_string(),
+            app_id: "app_1".to_string(),
+            app_name: "web".to_string(),
+            env_name: "prod".to_string(),
+            desired,
+            ready,
+            failed,
+        };
+        assert_eq!(EnvStatus::of(&health(0, 0, 0)), EnvStatus::Stopped);
+        assert_eq!(EnvStatus::of(&health(2, 0, 1)), EnvStatus::Down);
+        assert_eq!(EnvStatus::of(&health(2, 1, 0)), EnvStatus::Degraded);
+        assert_eq!(EnvStatus::of(&health(2, 2, 1)), EnvStatus::Degraded);
+        assert_eq!(EnvStatus::of(&health(2, 2, 0)), EnvStatus::Operational);
+
+        assert_eq!(
+            EnvStatus::overall([EnvStatus::Operational, EnvStatus::Degraded]),
+            EnvStatus::Degraded
+        );
+        assert_eq!(
+            EnvStatus::overall([EnvStatus::Stopped]),
+            EnvStatus::Operational
+        );
+        assert_eq!(EnvStatus::overall([]), EnvStatus::Operational);
+    }
+}
@@ -0,0 +1,256 @@
+//! Uptime and incidents, computed from history.
+//!
+//! An env is up while at least one of its instances is `ready`. Uptime over
+//! a window is the share of the window the env was up, counted from its
+//! first recorded transition so new envs are not charged for time before
+//! they existed.
+//!
+//! Incidents pair `alert.fired` with the matching `alert.resolved` (same
+//! rule and target). A resolution whose firing predates the queried events
+//! still yields an incident, started at the payload's `fired_at`.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// One instance status change.
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub instance_id: String,
+    pub status: String,
+    pub at: DateTime<Utc>,
+}
+
+/// A half-open time range.
+pub type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// The periods within `[start, end]` each instance spent in `status`, one
+/// entry per period (overlapping across instances).
+///
+/// `transitions` are in the order they happened and must include each
+/// instance's last transition before `start`.
+pub fn status_intervals(
+    transitions: &[Transition],
+    status: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<Interval> {
+    let mut by_instance: BTreeMap<&str, Vec<&Transition>> = BTreeMap::new();
+    for transition in transitions {
+        by_instance
+            .entry(transition.instance_id.as_str())
+            .or_default()
+            .push(transition);
+    }
+
+    let mut intervals = Vec::new();
+    for history in by_instance.values() {
+        for (i, transition) in history.iter().enumerate() {
+            if transition.status != status {
+                continue;
+            }
+            let until = history.get(i + 1).map_or(end, |next| next.at);
+            let (from, to) = (transition.at.max(start), until.min(end));
+            if from < to {
+                intervals.push((from, to));
+            }
+        }
+    }
+    intervals
+}
+
+/// `intervals` merged into sorted, disjoint ranges.
+pub fn merge(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort();
+    let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+    for (from, to) in intervals {
+        match merged.last_mut() {
+            Some(last) if from <= last.1 => last.1 = last.1.max(to),
+            _ => merged.push((from, to)),
+        }
+    }
+    merged
+}
+
+/// Total length of `intervals`, counting overlaps once per interval.
+pub fn total(intervals: &[Interval]) -> Duration {
+    intervals
+        .iter()
+        .fold(Duration::zero(), |sum, (from, to)| sum + (*to - *from))
+}
+
+/// Percentage of `[since, now]` during which an instance was ready, to three
+/// decimal places, or `None` when none of the window has been observed.
+///
+/// `transitions` are one env's, in the order they happened, and must include
+/// each instance's last transition before `since`.
+pub fn uptime_percent(
+    transitions: &[Transition],
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<f64> {
+    let first = transitions.iter().map(|t| t.at).min()?;
+    let start = since.max(first);
+    if start >= now {
+        return None;
+    }
+
+    let up = total(&merge(status_intervals(transitions, "ready", start, now)));
+    let window = (now - start).num_milliseconds() as f64;
+    let percent = up.num_milliseconds() as f64 * 100.0 / window;
+    Some((percent * 1000.0).round() / 1000.0)
+}
+
+/// An `alert.fired` or `alert.resolved` event.
+#[derive(Debug, Clone)]
+pub enum AlertEvent {
+    Fired {
+        rule_id: String,
+        rule_name: String,
+        target: String,
+        summary: String,
+        at: DateTime<Utc>,
+    },
+    Resolved {
+        rule_id: String,
+        rule_name: String,
+        target: String,
+        fired_at: DateTime<Utc>,
+        at: DateTime<Utc>,
+    },
+}
+
+/// A period during which an alert was firing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incident {
+    pub title: String,
+    /// The alert target: an env ID for env conditions.
+    pub target: String,
+    pub summary: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the alert is still firing.
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Incidents from alert events in log order, newest first.
+pub fn incidents(events: &[AlertEvent]) -> Vec<Incident> {
+    let mut incidents: Vec<Incident> = Vec::new();
+    let mut open: HashMap<(&str, &str), usize> = HashMap::new();
+
+    for event in events {
+        match event {
+            AlertEvent::Fired {
+                rule_id,
+                rule_name,
+                target,
+                summary,
+                at,
+            } => {
+                open.insert((rule_id.as_str(), target.as_str()), incidents.len());
+                incidents.push(Incident {
+                    title: rule_name.clone(),
+                    target: target.clone(),
+                    summary: Some(summary.clone()),
+                    started_at: *at,
+                    resolved_at: None,
+                });
+            }
+            AlertEvent::Resolved {
+                rule_id,
+                rule_name,
+                target,
+                fired_at,
+                at,
+            } => match open.remove(&(rule_id.as_str(), target.as_str())) {
+                Some(index) => incidents[index].resolved_at = Some(*at),
+                None => incidents.push(Incident {
+                    title: rule_name.clone(),
+                    target: target.clone(),
+                    summary: None,
+                    started_at: *fired_at,
+                    resolved_at: Some(*at),
+                }),
+            },
+        }
+    }
+
+    incidents.sort_by_key(|incident| Reverse(incident.started_at));
+    incidents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_800_000_000, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn transition(instance_id: &str, status: &str, minutes: i64) -> Transition {
+        Transition {
+            instance_id: instance_id.to_string(),
+            status: status.to_string(),
+            at: at(minutes),
+        }
+    }
+
+    #[test]
+    fn test_uptime_merges_instances() {
+        let transitions = [
+            transition("inst_a", "ready", 0),
+            transition("inst_b", "ready", 10),
+            transition("inst_a", "failed", 20),
+            transition("inst_b", "stopped", 30),
+            transition("inst_a", "ready", 80),
+        ];
+        // Up 0-30 and 80-100 of 100 minutes.
+        assert_eq!(uptime_percent(&transitions, at(0), at(100)), Some(50.0));
+        // The window starts mid-way: up 80-100 of 50-100.
+        assert_eq!(uptime_percent(&transitions, at(50), at(100)), Some(40.0));
+    }
+
+    #[test]
+    fn test_uptime_counts_from_first_transition() {
+        let transitions = [
+            transition("inst_a", "booting", 60),
+            transition("inst_a", "ready", 70),
+        ];
+        assert_eq!(uptime_percent(&transitions, at(0), at(100)), Some(75.0));
+        assert_eq!(uptime_percent(&transitions, at(0), at(60)), None);
+        assert_eq!(uptime_percent(&[], at(0), at(100)), None);
+    }
+
+    #[test]
+    fn test_incidents_pair_fired_and_resolved() {
+        let fired = |rule: &str, target: &str, minutes| AlertEvent::Fired {
+            rule_id: rule.to_string(),
+            rule_name: format!("{rule}-name"),
+            target: target.to_string(),
+            summary: "web/prod: 0 of 2 instances ready".to_string(),
+            at: at(minutes),
+        };
+        let resolved = |rule: &str, target: &str, fired, minutes| AlertEvent::Resolved {
+            rule_id: rule.to_string(),
+            rule_name: format!("{rule}-name"),
+            target: target.to_string(),
+            fired_at: at(fired),
+            at: at(minutes),
+        };
+
+        let found = incidents(&[
+            resolved("alr_1", "env_1", -30, 5),
+            fired("alr_1", "env_1", 10),
+            fired("alr_2", "env_2", 12),
+            resolved("alr_1", "env_1", 10, 20),
+        ]);
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].target, "env_2");
+        assert_eq!(found[0].resolved_at, None);
+        assert_eq!(found[1].started_at, at(10));
+        assert_eq!(found[1].resolved_at, Some(at(20)));
+        assert_eq!(found[2].started_at, at(-30));
+        assert_eq!(found[2].summary, None);
+    }
+}
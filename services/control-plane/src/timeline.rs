@@ -0,0 +1,439 @@
+//! Deploy and instance timelines.
+//!
+//! An instance timeline is the ordered list of phases the instance went
+//! through, assembled from its events plus the plan delivery log:
+//! - `allocated`: the scheduler placed it on a node (`instance.allocated`)
+//! - `plan_delivered`: its node first fetched a plan containing it
+//! - `booting`: the agent pulled the image, prepared the root disk and
+//!   started the VM
+//! - `ready`: guest-init reported ready
+//! - `draining`, `stopped`, `failed`, and desired state changes
+//!
+//! Each entry carries the time since the previous one, so the gap between
+//! `plan_delivered` and `booting` is image pull plus root disk preparation.
+//! Deploy timelines combine the deploy's own events with the phase times of
+//! the instances it created.
+//!
+//! Plan deliveries are operational state, not events: the first plan that
+//! hands an instance to its node is recorded in `instance_plan_deliveries`.
+//!
+//! See: docs/specs/api/http-api.md
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use plfm_events::event_types;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::db::EventRow;
+
+pub const PHASE_ALLOCATED: &str = "allocated";
+pub const PHASE_PLAN_DELIVERED: &str = "plan_delivered";
+pub const PHASE_BOOTING: &str = "booting";
+pub const PHASE_READY: &str = "ready";
+pub const PHASE_FAILED: &str = "failed";
+
+/// Record that a node received a plan containing these instances.
+///
+/// Only the first delivery per instance is kept.
+pub async fn record_plan_delivery(
+    pool: &PgPool,
+    node_id: &str,
+    instance_ids: &[String],
+) -> Result<(), sqlx::Error> {
+    if instance_ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO instance_plan_deliveries (instance_id, node_id, delivered_at)
+        SELECT instance_id, $1, now()
+        FROM unnest($2::TEXT[]) AS instance_id
+        ON CONFLICT (instance_id) DO NOTHING
+        "#,
+    )
+    .bind(node_id)
+    .bind(instance_ids)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Load first plan delivery times for the given instances.
+pub async fn load_plan_deliveries(
+    pool: &PgPool,
+    instance_ids: &[String],
+) -> Result<HashMap<String, DateTime<Utc>>, sqlx::Error> {
+    if instance_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+        r#"
+        SELECT instance_id, delivered_at
+        FROM instance_plan_deliveries
+        WHERE instance_id = ANY($1)
+        "#,
+    )
+    .bind(instance_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+// =============================================================================
+// Timelines
+// =============================================================================
+
+/// One phase in a timeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    pub phase: String,
+    pub at: DateTime<Utc>,
+    /// Source event; absent for entries not backed by an event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Milliseconds since the previous entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_previous_ms: Option<i64>,
+}
+
+impl TimelineEntry {
+    fn new(phase: &str, at: DateTime<Utc>, event_id: Option<i64>, detail: Option<String>) -> Self {
+        Self {
+            phase: phase.to_string(),
+            at,
+            event_id,
+            detail,
+            since_previous_ms: None,
+        }
+    }
+}
+
+/// First time an instance reached each startup phase.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PhaseTimes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_delivered_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub booting_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+/// Time spent in each startup phase, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PhaseDurations {
+    /// Allocation until the node fetched its plan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_delivery_ms: Option<i64>,
+    /// Plan delivery until boot started (image pull and root disk).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_prepare_ms: Option<i64>,
+    /// Boot start until ready.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot_ms: Option<i64>,
+    /// Allocation until ready.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_ms: Option<i64>,
+}
+
+impl PhaseTimes {
+    /// Collect first-occurrence phase times from a timeline.
+    pub fn from_entries(entries: &[TimelineEntry]) -> Self {
+        let mut times = Self::default();
+        for entry in entries {
+            let slot = match entry.phase.as_str() {
+                PHASE_ALLOCATED => &mut times.allocated_at,
+                PHASE_PLAN_DELIVERED => &mut times.plan_delivered_at,
+                PHASE_BOOTING => &mut times.booting_at,
+                PHASE_READY => &mut times.ready_at,
+                PHASE_FAILED => &mut times.failed_at,
+                _ => continue,
+            };
+            slot.get_or_insert(entry.at);
+        }
+        times
+    }
+
+    pub fn durations(&self) -> PhaseDurations {
+        PhaseDurations {
+            plan_delivery_ms: between(self.allocated_at, self.plan_delivered_at),
+            image_prepare_ms: between(self.plan_delivered_at, self.booting_at),
+            boot_ms: between(self.booting_at, self.ready_at),
+            total_ms: between(self.allocated_at, self.ready_at),
+        }
+    }
+}
+
+fn between(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Option<i64> {
+    Some((to? - from?).num_milliseconds())
+}
+
+/// Build an instance timeline from its events (any order) and the time its
+/// plan was first delivered.
+pub fn instance_timeline(
+    events: &[EventRow],
+    plan_delivered_at: Option<DateTime<Utc>>,
+) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = events
+        .iter()
+        .filter_map(|event| {
+            let payload = &event.payload;
+            let entry = match event.event_type.as_str() {
+                event_types::INSTANCE_ALLOCATED => TimelineEntry::new(
+                    PHASE_ALLOCATED,
+                    event.occurred_at,
+                    Some(event.event_id),
+                    payload_str(payload, "node_id"),
+                ),
+                event_types::INSTANCE_STATUS_CHANGED => {
+                    let status = payload_str(payload, "status")?;
+                    let detail = payload_str(payload, "reason_code")
+                        .or_else(|| payload_str(payload, "reason_detail"));
+                    TimelineEntry::new(&status, event.occurred_at, Some(event.event_id), detail)
+                }
+                event_types::INSTANCE_DESIRED_STATE_CHANGED => TimelineEntry::new(
+                    "desired_state_changed",
+                    event.occurred_at,
+                    Some(event.event_id),
+                    payload_str(payload, "desired_state"),
+                ),
+                _ => return None,
+            };
+            Some(entry)
+        })
+        .collect();
+
+    if let Some(at) = plan_delivered_at {
+        entries.push(TimelineEntry::new(PHASE_PLAN_DELIVERED, at, None, None));
+    }
+
+    finish(entries)
+}
+
+/// Build the deploy-level part of a deploy timeline from its events.
+pub fn deploy_timeline(events: &[EventRow]) -> Vec<TimelineEntry> {
+    let entries = events
+        .iter()
+        .filter_map(|event| {
+            let payload = &event.payload;
+            let (phase, detail) = match event.event_type.as_str() {
+                event_types::DEPLOY_CREATED => {
+                    ("created".to_string(), payload_str(payload, "reason"))
+                }
+                event_types::DEPLOY_STATUS_CHANGED => (
+                    payload_str(payload, "status")?,
+                    payload_str(payload, "failed_reason")
+                        .or_else(|| payload_str(payload, "message")),
+                ),
+                event_types::DEPLOY_PROMOTED => ("promoted".to_string(), None),
+                event_types::DEPLOY_ROLLOUT_STUCK => {
+                    ("rollout_stuck".to_string(), payload_str(payload, "message"))
+                }
+                _ => return None,
+            };
+            Some(TimelineEntry::new(
+                &phase,
+                event.occurred_at,
+                Some(event.event_id),
+                detail,
+            ))
+        })
+        .collect();
+
+    finish(entries)
+}
+
+/// Milestones derived from the phase times of a deploy's instances: first
+/// allocation, first ready, and last ready once every instance is ready.
+pub fn instance_milestones(instances: &[PhaseTimes]) -> Vec<TimelineEntry> {
+    let mut milestones = Vec::new();
+    if let Some(at) = instances.iter().filter_map(|t| t.allocated_at).min() {
+        milestones.push(TimelineEntry::new(
+            "first_instance_allocated",
+            at,
+            None,
+            None,
+        ));
+    }
+    if let Some(at) = instances.iter().filter_map(|t| t.ready_at).min() {
+        milestones.push(TimelineEntry::new("first_instance_ready", at, None, None));
+    }
+    if !instances.is_empty() && instances.iter().all(|t| t.ready_at.is_some()) {
+        if let Some(at) = instances.iter().filter_map(|t| t.ready_at).max() {
+            milestones.push(TimelineEntry::new("all_instances_ready", at, None, None));
+        }
+    }
+    milestones
+}
+
+/// Merge entry lists into one ordered timeline.
+pub fn merge(mut entries: Vec<TimelineEntry>, more: Vec<TimelineEntry>) -> Vec<TimelineEntry> {
+    entries.extend(more);
+    finish(entries)
+}
+
+/// Order entries by time (stable, so event order breaks ties) and fill in
+/// the time since the previous entry.
+fn finish(mut entries: Vec<TimelineEntry>) -> Vec<TimelineEntry> {
+    entries.sort_by_key(|entry| entry.at);
+    let mut previous: Option<DateTime<Utc>> = None;
+    for entry in &mut entries {
+        entry.since_previous_ms = previous.map(|prev| (entry.at - prev).num_milliseconds());
+        previous = Some(entry.at);
+    }
+    entries
+}
+
+fn payload_str(payload: &serde_json::Value, key: &str) -> Option<String> {
+    payload.get(key)?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn event(
+        event_id: i64,
+        event_type: &str,
+        at: DateTime<Utc>,
+        payload: serde_json::Value,
+    ) -> EventRow {
+        EventRow {
+            event_id,
+            occurred_at: at,
+            aggregate_type: "instance".to_string(),
+            aggregate_id: "inst_1".to_string(),
+            aggregate_seq: event_id as i32,
+            event_type: event_type.to_string(),
+            event_version: 1,
+            actor_type: "system".to_string(),
+            actor_id: "scheduler".to_string(),
+            org_id: Some("org_1".to_string()),
+            request_id: "req_1".to_string(),
+            idempotency_key: None,
+            app_id: None,
+            env_id: None,
+            correlation_id: None,
+            causation_id: None,
+            payload,
+            payload_type_url: None,
+            payload_bytes: None,
+            payload_schema_version: None,
+            traceparent: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_instance_timeline_orders_phases() {
+        let t0 = Utc::now();
+        let events = vec![
+            event(
+                3,
+                event_types::INSTANCE_STATUS_CHANGED,
+                t0 + Duration::seconds(12),
+                serde_json::json!({"status": "ready"}),
+            ),
+            event(
+                1,
+                event_types::INSTANCE_ALLOCATED,
+                t0,
+                serde_json::json!({"node_id": "node_1"}),
+            ),
+            event(
+                2,
+                event_types::INSTANCE_STATUS_CHANGED,
+                t0 + Duration::seconds(9),
+                serde_json::json!({"status": "booting"}),
+            ),
+        ];
+
+        let entries = instance_timeline(&events, Some(t0 + Duration::seconds(2)));
+        let phases: Vec<&str> = entries.iter().map(|e| e.phase.as_str()).collect();
+        assert_eq!(
+            phases,
+            vec!["allocated", "plan_delivered", "booting", "ready"]
+        );
+        assert_eq!(entries[0].since_previous_ms, None);
+        assert_eq!(entries[0].detail.as_deref(), Some("node_1"));
+        assert_eq!(entries[2].since_previous_ms, Some(7_000));
+
+        let durations = PhaseTimes::from_entries(&entries).durations();
+        assert_eq!(durations.plan_delivery_ms, Some(2_000));
+        assert_eq!(durations.image_prepare_ms, Some(7_000));
+        assert_eq!(durations.boot_ms, Some(3_000));
+        assert_eq!(durations.total_ms, Some(12_000));
+    }
+
+    #[test]
+    fn test_deploy_timeline_details() {
+        let t0 = Utc::now();
+        let events = vec![
+            event(
+                1,
+                event_types::DEPLOY_CREATED,
+                t0,
+                serde_json::json!({"release_id": "rel_1", "reason": "Enable new checkout"}),
+            ),
+            event(
+                2,
+                event_types::DEPLOY_STATUS_CHANGED,
+                t0 + Duration::seconds(30),
+                serde_json::json!({"status": "failed", "failed_reason": "healthcheck_failed"}),
+            ),
+        ];
+
+        let entries = deploy_timeline(&events);
+        assert_eq!(entries[0].phase, "created");
+        assert_eq!(entries[0].detail.as_deref(), Some("Enable new checkout"));
+        assert_eq!(entries[1].phase, "failed");
+        assert_eq!(entries[1].detail.as_deref(), Some("healthcheck_failed"));
+    }
+
+    #[test]
+    fn test_instance_milestones() {
+        let t0 = Utc::now();
+        let ready = PhaseTimes {
+            allocated_at: Some(t0),
+            ready_at: Some(t0 + Duration::seconds(5)),
+            ..Default::default()
+        };
+        let pending = PhaseTimes {
+            allocated_at: Some(t0 + Duration::seconds(1)),
+            ..Default::default()
+        };
+
+        let phases = |times: &[PhaseTimes]| -> Vec<String> {
+            instance_milestones(times)
+                .into_iter()
+                .map(|e| e.phase)
+                .collect()
+        };
+        assert_eq!(
+            phases(&[ready.clone(), pending]),
+            vec!["first_instance_allocated", "first_instance_ready"]
+        );
+        assert_eq!(
+            phases(&[ready]),
+            vec![
+                "first_instance_allocated",
+                "first_instance_ready",
+                "all_instances_ready"
+            ]
+        );
+        assert!(phases(&[]).is_empty());
+    }
+}
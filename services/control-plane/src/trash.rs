@@ -0,0 +1,833 @@
+//! Trash: the restore window for deleted apps, envs, routes and volumes.
+//!
+//! Deleting one of these only tombstones its view row (`is_deleted`,
+//! `deleted_at`). Until the retention window has passed the resource is
+//! listed in the org's trash and can be restored; after it, the cleanup
+//! worker emits `*.purged` and the tombstone becomes final.
+//!
+//! Restores cascade the way deletion did: restoring an app brings back the
+//! envs its teardown deleted, and restoring an env brings back its routes
+//! and the volumes its teardown deleted. Teardown is not undone: envs come
+//! back scaled to zero and volumes come back unattached. All events of one
+//! restore are appended as a single batch.
+//!
+//! Configuration:
+//! - `PLFM_TRASH_RETENTION_DAYS`: how long deleted resources stay
+//!   restorable (default 7)
+//!
+//! See: docs/specs/state/materialized-views.md (Trash)
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use plfm_events::{
+    AggregateType, AppPurgedPayload, AppRestoredPayload, EnvPurgedPayload, EnvRestoredPayload,
+    EventError, EventSource, NewEvent, NewEventBuilder, RoutePurgedPayload, RouteRestoredPayload,
+    SystemSource, VolumePurgedPayload, VolumeRestoredPayload,
+};
+use plfm_id::{EventId, OrgId};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
+use tracing::{info, warn};
+
+use crate::aggregates::CommandError;
+use crate::db::quotas::{check_quota, QuotaDimension};
+use crate::db::{AppendEvent, DbError, EventStore};
+
+/// Actor ID for `*.purged` events.
+pub const TRASH_ACTOR_ID: &str = "cleanup";
+
+const DEFAULT_RETENTION_DAYS: i64 = 7;
+
+/// How long deleted resources stay restorable.
+pub fn retention() -> Duration {
+    let days = std::env::var("PLFM_TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|d| *d > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    Duration::days(days)
+}
+
+/// Kinds of resources that go to the trash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    App,
+    Env,
+    Route,
+    Volume,
+}
+
+impl TrashKind {
+    pub const ALL: [TrashKind; 4] = [
+        TrashKind::App,
+        TrashKind::Env,
+        TrashKind::Route,
+        TrashKind::Volume,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrashKind::App => "app",
+            TrashKind::Env => "env",
+            TrashKind::Route => "route",
+            TrashKind::Volume => "volume",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == value)
+    }
+
+    /// Projection that applies the kind's restore and purge events.
+    pub fn projection(&self) -> &'static str {
+        match self {
+            TrashKind::App => "apps",
+            TrashKind::Env => "envs",
+            TrashKind::Route => "routes",
+            TrashKind::Volume => "volumes",
+        }
+    }
+
+    fn not_found(&self) -> CommandError {
+        match self {
+            TrashKind::App => CommandError::not_found("app_not_found", "Application not found"),
+            TrashKind::Env => CommandError::not_found("env_not_found", "Environment not found"),
+            TrashKind::Route => CommandError::not_found("route_not_found", "Route not found"),
+            TrashKind::Volume => CommandError::not_found("volume_not_found", "Volume not found"),
+        }
+    }
+
+    /// One row per resource of this kind, in the columns [`TrashRow`] reads.
+    /// A route is named by its hostname; an env's hostname is its managed
+    /// hostname.
+    fn select(&self) -> &'static str {
+        match self {
+            TrashKind::App => {
+                "SELECT 'app' AS kind, app_id AS id, org_id, name, app_id,
+                        NULL::TEXT AS env_id, NULL::TEXT AS hostname, is_deleted,
+                        deletion_requested_at, deleted_at, purged_at,
+                        NULL::TIMESTAMPTZ AS expires_at
+                 FROM apps_view"
+            }
+            TrashKind::Env => {
+                "SELECT 'env' AS kind, env_id AS id, org_id, name, app_id, env_id,
+                        managed_hostname AS hostname, is_deleted, deletion_requested_at,
+                        deleted_at, purged_at, expires_at
+                 FROM envs_view"
+            }
+            TrashKind::Route => {
+                "SELECT 'route' AS kind, route_id AS id, org_id, hostname AS name, app_id,
+                        env_id, hostname, is_deleted,
+                        NULL::TIMESTAMPTZ AS deletion_requested_at, deleted_at, purged_at,
+                        NULL::TIMESTAMPTZ AS expires_at
+                 FROM routes_view"
+            }
+            TrashKind::Volume => {
+                "SELECT 'volume' AS kind, volume_id AS id, org_id, name,
+                        NULL::TEXT AS app_id, NULL::TEXT AS env_id, NULL::TEXT AS hostname,
+                        is_deleted, NULL::TIMESTAMPTZ AS deletion_requested_at, deleted_at,
+                        purged_at, NULL::TIMESTAMPTZ AS expires_at
+                 FROM volumes_view"
+            }
+        }
+    }
+}
+
+/// A deleted resource, as listed in the trash and returned by restores.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashItem {
+    pub kind: TrashKind,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purge_after: Option<DateTime<Utc>>,
+}
+
+/// Where a resource stands with respect to the trash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashState {
+    Live,
+    /// Deletion was requested and the teardown is still running.
+    Deleting,
+    Restorable,
+    /// Purged, or past the window and about to be.
+    Expired,
+}
+
+/// The trash state of a resource. A tombstone without `deleted_at` predates
+/// the trash and is treated as expired.
+pub fn state(
+    is_deleted: bool,
+    deletion_requested_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    purged_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> TrashState {
+    if purged_at.is_some() {
+        return TrashState::Expired;
+    }
+    if !is_deleted {
+        return if deletion_requested_at.is_some() {
+            TrashState::Deleting
+        } else {
+            TrashState::Live
+        };
+    }
+    match deleted_at {
+        Some(deleted_at) if deleted_at + retention > now => TrashState::Restorable,
+        _ => TrashState::Expired,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TrashRow {
+    kind: TrashKind,
+    id: String,
+    org_id: String,
+    name: Option<String>,
+    app_id: Option<String>,
+    env_id: Option<String>,
+    hostname: Option<String>,
+    is_deleted: bool,
+    deletion_requested_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    purged_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> FromRow<'r, PgRow> for TrashRow {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let kind: String = row.try_get("kind")?;
+        Ok(Self {
+            kind: TrashKind::parse(&kind).ok_or_else(|| sqlx::Error::ColumnDecode {
+                index: "kind".to_string(),
+                source: format!("unknown trash kind {kind:?}").into(),
+            })?,
+            id: row.try_get("id")?,
+            org_id: row.try_get("org_id")?,
+            name: row.try_get("name")?,
+            app_id: row.try_get("app_id")?,
+            env_id: row.try_get("env_id")?,
+            hostname: row.try_get("hostname")?,
+            is_deleted: row.try_get("is_deleted")?,
+            deletion_requested_at: row.try_get("deletion_requested_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+            purged_at: row.try_get("purged_at")?,
+            expires_at: row.try_get("expires_at")?,
+        })
+    }
+}
+
+impl TrashRow {
+    fn state(&self, now: DateTime<Utc>, retention: Duration) -> TrashState {
+        state(
+            self.is_deleted,
+            self.deletion_requested_at,
+            self.deleted_at,
+            self.purged_at,
+            now,
+            retention,
+        )
+    }
+
+    fn item(&self, retention: Duration) -> TrashItem {
+        TrashItem {
+            kind: self.kind,
+            id: self.id.clone(),
+            name: self.name.clone(),
+            app_id: self.app_id.clone().filter(|_| self.kind != TrashKind::App),
+            env_id: self.env_id.clone().filter(|_| self.kind != TrashKind::Env),
+            deleted_at: self.deleted_at,
+            purge_after: self.deleted_at.map(|at| at + retention),
+        }
+    }
+
+    fn preview_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// Restorable resources of an org, most recently deleted first.
+pub async fn list(
+    pool: &PgPool,
+    org_id: &OrgId,
+    limit: i64,
+) -> Result<Vec<TrashItem>, sqlx::Error> {
+    let retention = retention();
+    let union = TrashKind::ALL
+        .iter()
+        .map(|kind| {
+            format!(
+                "({} WHERE org_id = $1 AND is_deleted AND purged_at IS NULL AND deleted_at > $2)",
+                kind.select()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    let sql = format!("SELECT * FROM ({union}) trash ORDER BY deleted_at DESC, id LIMIT $3");
+
+    let rows = sqlx::query_as::<_, TrashRow>(&sql)
+        .bind(org_id.to_string())
+        .bind(Utc::now() - retention)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(|row| row.item(retention)).collect())
+}
+
+/// Where a restore request addressed the resource: its org, and for envs
+/// and routes the app and env in the request path.
+#[derive(Debug, Clone, Copy)]
+pub struct RestoreScope<'a> {
+    pub org_id: &'a OrgId,
+    pub app_id: Option<&'a str>,
+    pub env_id: Option<&'a str>,
+}
+
+impl RestoreScope<'_> {
+    fn contains(&self, row: &TrashRow) -> bool {
+        row.org_id == self.org_id.to_string()
+            && self
+                .app_id
+                .is_none_or(|id| row.app_id.as_deref() == Some(id))
+            && self
+                .env_id
+                .is_none_or(|id| row.env_id.as_deref() == Some(id))
+    }
+}
+
+/// What a restore brought back.
+#[derive(Debug, Clone, Default)]
+pub struct Restored {
+    pub items: Vec<TrashItem>,
+    /// The last appended event; `None` when the resource was already live.
+    pub last_event_id: Option<EventId>,
+}
+
+impl Restored {
+    /// Projections the restore has to be applied by before it is visible.
+    pub fn projections(&self) -> Vec<&'static str> {
+        let mut projections: Vec<&'static str> = Vec::new();
+        for item in &self.items {
+            if !projections.contains(&item.kind.projection()) {
+                projections.push(item.kind.projection());
+            }
+        }
+        projections
+    }
+}
+
+/// Restore a deleted resource within `scope` and everything its deletion
+/// took with it.
+///
+/// Restoring a live resource is a no-op. Fails when the restore window has
+/// passed, while a teardown is still running, when the parent app or env is
+/// not live, when a name or hostname has been taken in the meantime, or when
+/// the restored resources would exceed a quota.
+pub async fn restore<S: EventSource>(
+    pool: &PgPool,
+    source: &S,
+    scope: RestoreScope<'_>,
+    kind: TrashKind,
+    id: &str,
+) -> Result<Restored, CommandError> {
+    let now = Utc::now();
+    let retention = retention();
+
+    let row = load_row(pool, kind, id)
+        .await?
+        .filter(|row| scope.contains(row))
+        .ok_or_else(|| kind.not_found())?;
+    match row.state(now, retention) {
+        TrashState::Live => return Ok(Restored::default()),
+        TrashState::Deleting => {
+            return Err(CommandError::conflict(
+                "restore_deletion_in_progress",
+                format!(
+                    "The {} is still being deleted; restore it once deletion completes",
+                    kind.as_str()
+                ),
+            ))
+        }
+        TrashState::Expired => {
+            return Err(CommandError::conflict(
+                "restore_window_expired",
+                format!(
+                    "The {} was deleted more than {} days ago and can no longer be restored",
+                    kind.as_str(),
+                    retention.num_days()
+                ),
+            ))
+        }
+        TrashState::Restorable => {}
+    }
+
+    let mut plan = Vec::new();
+    match kind {
+        TrashKind::App => plan_app(pool, now, row, &mut plan).await?,
+        TrashKind::Env => {
+            let app_id = row.app_id.clone().unwrap_or_default();
+            require_live_parent(pool, TrashKind::App, &app_id, now, retention).await?;
+            if row.preview_expired(now) {
+                return Err(CommandError::conflict(
+                    "restore_preview_expired",
+                    "The preview environment is past its expiry; it would be deleted again",
+                ));
+            }
+            plan_env(pool, row, &mut plan).await?;
+        }
+        TrashKind::Route => {
+            let env_id = row.env_id.clone().unwrap_or_default();
+            require_live_parent(pool, TrashKind::Env, &env_id, now, retention).await?;
+            ensure_hostname_free(pool, &row).await?;
+            plan.push(row);
+        }
+        TrashKind::Volume => plan.push(row),
+    }
+
+    check_quotas(pool, scope.org_id, &plan).await?;
+
+    let store = EventStore::new(pool.clone());
+    let mut events: Vec<AppendEvent> = Vec::with_capacity(plan.len());
+    for row in &plan {
+        events.push(restored_event(&store, source, row).await?);
+    }
+    let ids = store.append_batch(events).await.map_err(|e| match e {
+        DbError::SequenceConflict { .. } => CommandError::Concurrent {
+            aggregate_type: kind.as_str().to_string(),
+            aggregate_id: id.to_string(),
+        },
+        other => CommandError::Store(other),
+    })?;
+
+    info!(kind = kind.as_str(), id = %id, restored = plan.len(), "Restored from trash");
+    Ok(Restored {
+        items: plan.iter().map(|row| row.item(retention)).collect(),
+        last_event_id: ids.last().copied(),
+    })
+}
+
+async fn load_row(pool: &PgPool, kind: TrashKind, id: &str) -> Result<Option<TrashRow>, DbError> {
+    let id_column = match kind {
+        TrashKind::App => "app_id",
+        TrashKind::Env => "env_id",
+        TrashKind::Route => "route_id",
+        TrashKind::Volume => "volume_id",
+    };
+    let sql = format!("{} WHERE {id_column} = $1", kind.select());
+    sqlx::query_as::<_, TrashRow>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(DbError::Query)
+}
+
+async fn require_live_parent(
+    pool: &PgPool,
+    kind: TrashKind,
+    id: &str,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> Result<(), CommandError> {
+    let live = load_row(pool, kind, id)
+        .await?
+        .is_some_and(|parent| parent.state(now, retention) == TrashState::Live);
+    if live {
+        return Ok(());
+    }
+    Err(CommandError::conflict(
+        "restore_parent_deleted",
+        format!(
+            "The parent {} {id} is deleted; restore it first",
+            kind.as_str()
+        ),
+    ))
+}
+
+/// The app plus the envs its teardown deleted. Expired preview envs stay
+/// deleted.
+async fn plan_app(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    app: TrashRow,
+    plan: &mut Vec<TrashRow>,
+) -> Result<(), CommandError> {
+    let taken: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM apps_view
+            WHERE org_id = $1 AND name = $2 AND app_id <> $3 AND NOT is_deleted
+        )
+        "#,
+    )
+    .bind(&app.org_id)
+    .bind(&app.name)
+    .bind(&app.id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::Query)?;
+    if taken {
+        return Err(CommandError::conflict(
+            "app_name_exists",
+            format!(
+                "An application named '{}' already exists",
+                app.name.as_deref().unwrap_or_default()
+            ),
+        ));
+    }
+
+    let sql = format!(
+        "{} WHERE app_id = $1 AND is_deleted AND purged_at IS NULL
+             AND ($2::TIMESTAMPTZ IS NULL OR deletion_requested_at >= $2)
+         ORDER BY env_id",
+        TrashKind::Env.select()
+    );
+    let envs = sqlx::query_as::<_, TrashRow>(&sql)
+        .bind(&app.id)
+        .bind(app.deletion_requested_at)
+        .fetch_all(pool)
+        .await
+        .map_err(DbError::Query)?;
+
+    plan.push(app);
+    for env in envs {
+        if env.preview_expired(now) {
+            continue;
+        }
+        plan_env(pool, env, plan).await?;
+    }
+    Ok(())
+}
+
+/// The env plus the routes and volumes its teardown deleted. A route whose
+/// hostname was taken in the meantime stays deleted.
+async fn plan_env(
+    pool: &PgPool,
+    env: TrashRow,
+    plan: &mut Vec<TrashRow>,
+) -> Result<(), CommandError> {
+    let taken: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM envs_view
+            WHERE app_id = $1 AND name = $2 AND env_id <> $3 AND NOT is_deleted
+        )
+        "#,
+    )
+    .bind(&env.app_id)
+    .bind(&env.name)
+    .bind(&env.id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::Query)?;
+    if taken {
+        return Err(CommandError::conflict(
+            "env_name_exists",
+            format!(
+                "An environment named '{}' already exists",
+                env.name.as_deref().unwrap_or_default()
+            ),
+        ));
+    }
+    ensure_hostname_free(pool, &env).await?;
+
+    // Teardown deletes routes and volumes after the deletion request; the
+    // newest tombstone wins when a hostname was reused within the env.
+    let since = env.deletion_requested_at.or(env.deleted_at);
+    let sql = format!(
+        "SELECT DISTINCT ON (hostname) * FROM ({}) r
+         WHERE env_id = $1 AND is_deleted AND purged_at IS NULL AND deleted_at >= $2
+         ORDER BY hostname, deleted_at DESC",
+        TrashKind::Route.select()
+    );
+    let routes = sqlx::query_as::<_, TrashRow>(&sql)
+        .bind(&env.id)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .map_err(DbError::Query)?;
+
+    let sql = format!(
+        "{} WHERE is_deleted AND purged_at IS NULL AND deleted_at >= $2
+             AND volume_id IN (
+                 SELECT volume_id FROM volume_attachments_view WHERE env_id = $1
+             )
+         ORDER BY volume_id",
+        TrashKind::Volume.select()
+    );
+    let volumes = sqlx::query_as::<_, TrashRow>(&sql)
+        .bind(&env.id)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .map_err(DbError::Query)?;
+
+    plan.push(env);
+    for route in routes {
+        match ensure_hostname_free(pool, &route).await {
+            Ok(()) => plan.push(route),
+            Err(CommandError::Conflict { .. }) => {
+                warn!(route_id = %route.id, hostname = ?route.hostname, "Not restoring route; hostname is in use");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    plan.extend(volumes);
+    Ok(())
+}
+
+/// Fails when a live route or env has claimed the row's hostname.
+async fn ensure_hostname_free(pool: &PgPool, row: &TrashRow) -> Result<(), CommandError> {
+    let Some(hostname) = row.hostname.as_deref() else {
+        return Ok(());
+    };
+    let taken: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM routes_view
+            WHERE hostname = $1 AND route_id <> $2 AND NOT is_deleted
+        ) OR EXISTS (
+            SELECT 1 FROM envs_view
+            WHERE managed_hostname = $1 AND env_id <> $2 AND NOT is_deleted
+        )
+        "#,
+    )
+    .bind(hostname)
+    .bind(&row.id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::Query)?;
+    if taken {
+        return Err(CommandError::conflict(
+            "hostname_in_use",
+            format!("Hostname '{hostname}' is already in use"),
+        ));
+    }
+    Ok(())
+}
+
+async fn check_quotas(
+    pool: &PgPool,
+    org_id: &OrgId,
+    plan: &[TrashRow],
+) -> Result<(), CommandError> {
+    for (kind, dimension) in [
+        (TrashKind::App, QuotaDimension::MaxApps),
+        (TrashKind::Env, QuotaDimension::MaxEnvs),
+        (TrashKind::Route, QuotaDimension::MaxRoutes),
+        (TrashKind::Volume, QuotaDimension::MaxVolumes),
+    ] {
+        let delta = plan.iter().filter(|row| row.kind == kind).count() as i64;
+        if delta == 0 {
+            continue;
+        }
+        let exceeded = check_quota(pool, org_id, dimension, delta)
+            .await
+            .map_err(DbError::Query)?;
+        if let Some(exceeded) = exceeded {
+            return Err(CommandError::conflict(
+                "quota_exceeded",
+                format!(
+                    "Quota exceeded for {}: limit={}, current={}, requested={}",
+                    exceeded.dimension,
+                    exceeded.limit,
+                    exceeded.current_usage,
+                    exceeded.requested_delta
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn id<T: FromStr>(value: Option<&str>) -> Result<T, CommandError> {
+    let value = value.unwrap_or_default();
+    value
+        .parse()
+        .map_err(|_| EventError::InvalidPayload(format!("malformed ID {value:?}")).into())
+}
+
+/// The start of the next event of the row's aggregate.
+async fn next_event<S: EventSource>(
+    store: &EventStore,
+    source: &S,
+    row: &TrashRow,
+) -> Result<NewEventBuilder, CommandError> {
+    let aggregate_type = match row.kind {
+        TrashKind::App => AggregateType::App,
+        TrashKind::Env => AggregateType::Env,
+        TrashKind::Route => AggregateType::Route,
+        TrashKind::Volume => AggregateType::Volume,
+    };
+    let seq = store
+        .get_latest_aggregate_seq(&aggregate_type, &row.id)
+        .await?
+        .unwrap_or(0);
+    let mut builder = NewEvent::builder(source)
+        .aggregate_id(row.id.clone())
+        .aggregate_seq(seq + 1)
+        .org_id(id(Some(&row.org_id))?);
+    if row.kind != TrashKind::Volume {
+        builder = builder.app_id(id(row.app_id.as_deref())?);
+    }
+    if matches!(row.kind, TrashKind::Env | TrashKind::Route) {
+        builder = builder.env_id(id(row.env_id.as_deref())?);
+    }
+    Ok(builder)
+}
+
+async fn restored_event<S: EventSource>(
+    store: &EventStore,
+    source: &S,
+    row: &TrashRow,
+) -> Result<AppendEvent, CommandError> {
+    let builder = next_event(store, source, row).await?;
+    let org_id = id(Some(&row.org_id))?;
+    let builder = match row.kind {
+        TrashKind::App => builder.payload(&AppRestoredPayload {
+            app_id: id(Some(&row.id))?,
+            org_id,
+        }),
+        TrashKind::Env => builder.payload(&EnvRestoredPayload {
+            env_id: id(Some(&row.id))?,
+            org_id,
+            app_id: id(row.app_id.as_deref())?,
+        }),
+        TrashKind::Route => builder.payload(&RouteRestoredPayload {
+            route_id: id(Some(&row.id))?,
+            org_id,
+            env_id: id(row.env_id.as_deref())?,
+            hostname: row.hostname.clone().unwrap_or_default(),
+        }),
+        TrashKind::Volume => builder.payload(&VolumeRestoredPayload {
+            volume_id: id(Some(&row.id))?,
+            org_id,
+        }),
+    };
+    Ok(builder.build()?.into())
+}
+
+async fn purged_event<S: EventSource>(
+    store: &EventStore,
+    source: &S,
+    row: &TrashRow,
+) -> Result<AppendEvent, CommandError> {
+    let builder = next_event(store, source, row).await?;
+    let org_id = id(Some(&row.org_id))?;
+    let builder = match row.kind {
+        TrashKind::App => builder.payload(&AppPurgedPayload {
+            app_id: id(Some(&row.id))?,
+            org_id,
+        }),
+        TrashKind::Env => builder.payload(&EnvPurgedPayload {
+            env_id: id(Some(&row.id))?,
+            org_id,
+            app_id: id(row.app_id.as_deref())?,
+        }),
+        TrashKind::Route => builder.payload(&RoutePurgedPayload {
+            route_id: id(Some(&row.id))?,
+            org_id,
+            env_id: id(row.env_id.as_deref())?,
+            hostname: row.hostname.clone().unwrap_or_default(),
+        }),
+        TrashKind::Volume => builder.payload(&VolumePurgedPayload {
+            volume_id: id(Some(&row.id))?,
+            org_id,
+        }),
+    };
+    Ok(builder.build()?.into())
+}
+
+/// Purge tombstones past the restore window. Returns how many were purged.
+///
+/// Each purge is its own append, so one bad row does not hold back the rest.
+pub async fn purge_expired(pool: &PgPool) -> Result<u64, DbError> {
+    let store = EventStore::new(pool.clone());
+    let source = SystemSource::new(TRASH_ACTOR_ID);
+    let cutoff = Utc::now() - retention();
+
+    let mut purged = 0u64;
+    for kind in TrashKind::ALL {
+        let sql = format!(
+            "{} WHERE is_deleted AND purged_at IS NULL AND deleted_at <= $1
+             ORDER BY deleted_at",
+            kind.select()
+        );
+        let rows = sqlx::query_as::<_, TrashRow>(&sql)
+            .bind(cutoff)
+            .fetch_all(pool)
+            .await
+            .map_err(DbError::Query)?;
+
+        for row in rows {
+            let appended = match purged_event(&store, &source, &row).await {
+                Ok(event) => store.append(event).await.map_err(CommandError::Store),
+                Err(e) => Err(e),
+            };
+            match appended {
+                Ok(_) => purged += 1,
+                Err(e) => {
+                    warn!(error = %e, kind = kind.as_str(), id = %row.id, "Failed to purge from trash")
+                }
+            }
+        }
+    }
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state() {
+        let now = Utc::now();
+        let week = Duration::days(7);
+        let ago = |days| Some(now - Duration::days(days));
+
+        assert_eq!(state(false, None, None, None, now, week), TrashState::Live);
+        assert_eq!(
+            state(false, ago(0), None, None, now, week),
+            TrashState::Deleting
+        );
+        assert_eq!(
+            state(true, ago(3), ago(2), None, now, week),
+            TrashState::Restorable
+        );
+        assert_eq!(
+            state(true, ago(9), ago(8), None, now, week),
+            TrashState::Expired
+        );
+        assert_eq!(
+            state(true, ago(3), ago(2), ago(0), now, week),
+            TrashState::Expired
+        );
+        // Deleted before the trash existed: no age to go by.
+        assert_eq!(
+            state(true, None, None, None, now, week),
+            TrashState::Expired
+        );
+    }
+
+    #[test]
+    fn test_kind_names() {
+        for kind in TrashKind::ALL {
+            assert_eq!(TrashKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::json!(kind.as_str())
+            );
+        }
+        assert_eq!(TrashKind::parse("release"), None);
+    }
+}
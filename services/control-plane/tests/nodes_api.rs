@@ -16,6 +16,8 @@ use testcontainers::{core::IntoContainerPort, runners::AsyncRunner, GenericImage
 use tokio::net::TcpListener;
 use tokio::sync::watch;
 
+const OPERATOR_EMAIL: &str = "operator@example.com";
+
 fn unique_suffix() -> String {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -70,6 +72,11 @@ impl NodeApiTestHarness {
             .with_test_writer()
             .try_init();
 
+        // Most tests enroll without a bootstrap token; tokens presented in
+        // open mode are still verified and consumed.
+        std::env::set_var("PLFM_NODE_ENROLLMENT_MODE", "open");
+        std::env::set_var("PLFM_OPERATOR_EMAILS", OPERATOR_EMAIL);
+
         let postgres = GenericImage::new("postgres", "16-alpine")
             .with_exposed_port(5432.tcp())
             .with_env_var("POSTGRES_USER", "plfm")
@@ -252,6 +259,198 @@ async fn test_node_enrollment() {
     assert!(items.iter().any(|n| n["id"] == node_id));
 }
 
+#[tokio::test]
+async fn test_node_enrollment_pins_presented_subject() {
+    let harness = NodeApiTestHarness::new().await;
+    let enroll_url = format!("{}/v1/nodes/enroll", harness.base_url);
+
+    // A node cannot pin a subject other than its certificate's.
+    let resp = harness
+        .client
+        .post(&enroll_url)
+        .header("x-client-cert-subject", "CN=someone-else")
+        .json(&harness.enroll_payload("node-05.example.com"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "node_identity_mismatch");
+
+    let resp = harness
+        .client
+        .post(&enroll_url)
+        .header("x-client-cert-subject", "CN=node-05.example.com")
+        .json(&harness.enroll_payload("node-05.example.com"))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+}
+
+#[tokio::test]
+async fn test_node_enrollment_bootstrap_tokens_and_approval() {
+    let harness = NodeApiTestHarness::new().await;
+    let operator_token = harness.issue_user_token(OPERATOR_EMAIL).await;
+    let tokens_url = format!("{}/v1/_admin/node-bootstrap-tokens", harness.base_url);
+    let enroll_url = format!("{}/v1/nodes/enroll", harness.base_url);
+
+    // Non-operators cannot mint tokens.
+    let user_token = harness.issue_user_token("someone@example.com").await;
+    let resp = harness
+        .client
+        .post(&tokens_url)
+        .bearer_auth(&user_token)
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // A plain token admits the node directly, exactly once.
+    let resp = harness
+        .client
+        .post(&tokens_url)
+        .bearer_auth(&operator_token)
+        .json(&serde_json::json!({ "description": "node-02" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let token = body["token"].as_str().expect("missing token").to_string();
+    assert!(token.starts_with("trc_nb_"));
+    assert_eq!(body["status"], "active");
+
+    let mut payload = harness.enroll_payload("node-02.example.com");
+    payload["bootstrap_token"] = serde_json::json!(token);
+    let resp = harness
+        .client
+        .post(&enroll_url)
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+
+    let mut payload = harness.enroll_payload("node-03.example.com");
+    payload["bootstrap_token"] = serde_json::json!(token);
+    let resp = harness
+        .client
+        .post(&enroll_url)
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "invalid_bootstrap_token");
+
+    // An approval token parks the node until an operator approves it.
+    let resp = harness
+        .client
+        .post(&tokens_url)
+        .bearer_auth(&operator_token)
+        .json(&serde_json::json!({ "requires_approval": true, "ttl_hours": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let token = body["token"].as_str().expect("missing token").to_string();
+
+    let mut payload = harness.enroll_payload("node-04.example.com");
+    payload["bootstrap_token"] = serde_json::json!(token);
+    let resp = harness
+        .client
+        .post(&enroll_url)
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::ACCEPTED);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["state"], "pending_approval");
+    let node_id = body["id"].as_str().expect("missing node id").to_string();
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let heartbeat_url = format!("{}/v1/nodes/{}/heartbeat", harness.base_url, node_id);
+    let heartbeat = serde_json::json!({
+        "state": "active",
+        "available_cpu_cores": 8,
+        "available_memory_bytes": 17179869184_i64,
+        "instance_count": 0
+    });
+    let resp = harness
+        .client
+        .post(&heartbeat_url)
+        .json(&heartbeat)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "node_pending_approval");
+
+    let resp = harness
+        .client
+        .get(format!(
+            "{}/v1/nodes?state=pending_approval",
+            harness.base_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    let list: serde_json::Value = resp.json().await.unwrap();
+    let items = list["items"].as_array().expect("missing items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], node_id.as_str());
+
+    let approve_url = format!("{}/v1/_admin/nodes/{}/approve", harness.base_url, node_id);
+    let resp = harness
+        .client
+        .post(&approve_url)
+        .bearer_auth(&operator_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    // Approving twice is a conflict.
+    let resp = harness
+        .client
+        .post(&approve_url)
+        .bearer_auth(&operator_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::CONFLICT);
+
+    // The approved node is pinned to its enrolled mTLS subject.
+    let resp = harness
+        .client
+        .post(&heartbeat_url)
+        .header("x-client-cert-subject", "CN=someone-else")
+        .json(&heartbeat)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "node_identity_mismatch");
+
+    let resp = harness
+        .client
+        .post(&heartbeat_url)
+        .header("x-client-cert-subject", "CN=node-04.example.com")
+        .json(&heartbeat)
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+}
+
 #[tokio::test]
 async fn test_node_heartbeat() {
     let harness = NodeApiTestHarness::new().await;
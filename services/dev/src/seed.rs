@@ -0,0 +1,179 @@
+//! Sample data for the dev stack.
+//!
+//! Seeding goes through the public HTTP API with the dev auth stub, so it
+//! exercises the same paths as `vt`. Every step looks the object up by name
+//! first, which makes `plfm dev up` safe to re-run against the same database.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use tracing::info;
+
+const NODE_MTLS_SUBJECT: &str = "CN=plfm-dev-node";
+const ORG_NAME: &str = "dev";
+const APP_NAME: &str = "hello";
+const ENV_NAME: &str = "dev";
+
+/// IDs of the seeded org, app, and env.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub org_id: String,
+    pub app_id: String,
+    pub env_id: String,
+}
+
+pub struct Seeder {
+    client: Client,
+    api_url: String,
+    authorization: String,
+}
+
+impl Seeder {
+    pub fn new(api_url: &str, token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            authorization: format!("Bearer {token}"),
+        }
+    }
+
+    /// Poll `/readyz` until every readiness check passes.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let healthy = self
+                .client
+                .get(format!("{}/readyz", self.api_url))
+                .send()
+                .await
+                .is_ok_and(|response| response.status().is_success());
+            if healthy {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                bail!("control plane at {} did not become ready", self.api_url);
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// Enroll the dev node, or find it from a previous run.
+    pub async fn ensure_node(&self) -> Result<String> {
+        let nodes = self
+            .request(Method::GET, "/v1/nodes?limit=200", None)
+            .await?;
+        if let Some(id) = find_id(&nodes, "agent_mtls_subject", NODE_MTLS_SUBJECT) {
+            return Ok(id);
+        }
+
+        let node = self
+            .request(
+                Method::POST,
+                "/v1/nodes/enroll",
+                Some(json!({
+                    "hostname": "plfm-dev",
+                    "region": "local",
+                    "wireguard_public_key": "plfm-dev-wireguard-key-00000000000000000000",
+                    "agent_mtls_subject": NODE_MTLS_SUBJECT,
+                    "public_ipv6": "fd00::1",
+                    "public_ipv4": null,
+                    "cpu_cores": 4,
+                    "memory_bytes": 4_294_967_296_u64,
+                    "mtu": null,
+                    "labels": {},
+                    "allocatable": {},
+                })),
+            )
+            .await?;
+        let id = id_of(&node)?;
+        info!(node_id = %id, "Enrolled dev node");
+        Ok(id)
+    }
+
+    /// Create the sample org, app, and env, or find them from a previous run.
+    pub async fn ensure_sample(&self) -> Result<Sample> {
+        let org_id = self.ensure_named("/v1/orgs", ORG_NAME, json!({})).await?;
+        let app_id = self
+            .ensure_named(
+                &format!("/v1/orgs/{org_id}/apps"),
+                APP_NAME,
+                json!({ "description": "Sample app seeded by plfm dev up" }),
+            )
+            .await?;
+        let env_id = self
+            .ensure_named(
+                &format!("/v1/orgs/{org_id}/apps/{app_id}/envs"),
+                ENV_NAME,
+                json!({}),
+            )
+            .await?;
+
+        info!(%org_id, %app_id, %env_id, "Seeded sample org, app, and env");
+        Ok(Sample {
+            org_id,
+            app_id,
+            env_id,
+        })
+    }
+
+    /// Find the item called `name` in the collection at `path`, creating it
+    /// with `body` (plus the name) if absent.
+    async fn ensure_named(&self, path: &str, name: &str, mut body: Value) -> Result<String> {
+        let list = self
+            .request(Method::GET, &format!("{path}?limit=200"), None)
+            .await?;
+        if let Some(id) = find_id(&list, "name", name) {
+            return Ok(id);
+        }
+
+        body["name"] = json!(name);
+        id_of(&self.request(Method::POST, path, Some(body)).await?)
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value> {
+        let mut request = self
+            .client
+            .request(method.clone(), format!("{}{}", self.api_url, path))
+            .header("Authorization", &self.authorization);
+        if let Some(body) = body {
+            request = request
+                .header(
+                    "Idempotency-Key",
+                    format!("plfm-dev-{}", path.replace('/', "-")),
+                )
+                .json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("{method} {path} failed"))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!("{method} {path} returned {status}: {text}");
+        }
+        if status == StatusCode::NO_CONTENT || text.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).with_context(|| format!("{method} {path}: invalid JSON"))
+    }
+}
+
+fn find_id(list: &Value, field: &str, value: &str) -> Option<String> {
+    list["items"]
+        .as_array()?
+        .iter()
+        .find(|item| item[field] == value)
+        .and_then(|item| item["id"].as_str())
+        .map(str::to_string)
+}
+
+fn id_of(object: &Value) -> Result<String> {
+    object["id"]
+        .as_str()
+        .map(str::to_string)
+        .context("response has no id")
+}
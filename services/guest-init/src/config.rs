@@ -32,6 +32,10 @@ pub struct GuestConfig {
     #[serde(default)]
     pub secrets: Option<SecretsConfig>,
 
+    /// Instance identity token.
+    #[serde(default)]
+    pub identity: Option<IdentityConfig>,
+
     /// Health check configuration.
     #[serde(default)]
     pub health: Option<HealthConfig>,
@@ -149,7 +153,7 @@ fn default_mode() -> String {
 }
 
 /// Secrets configuration.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Clone, Deserialize)]
 #[allow(dead_code)] // Fields used by deserialization
 pub struct SecretsConfig {
     /// Whether secrets are required.
@@ -185,6 +189,22 @@ pub struct SecretsConfig {
     pub data: Option<String>,
 }
 
+// `data` is never printed.
+impl std::fmt::Debug for SecretsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsConfig")
+            .field("required", &self.required)
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .field("owner_uid", &self.owner_uid)
+            .field("owner_gid", &self.owner_gid)
+            .field("format", &self.format)
+            .field("bundle_version_id", &self.bundle_version_id)
+            .field("data", &self.data.as_ref().map(|_| "[REDACTED]"))
+            .finish()
+    }
+}
+
 fn default_secrets_path() -> String {
     "/run/secrets/platform.env".to_string()
 }
@@ -197,6 +217,36 @@ fn default_secrets_format() -> String {
     "dotenv".to_string()
 }
 
+/// Instance identity token to write for the workload.
+#[derive(Clone, Deserialize)]
+pub struct IdentityConfig {
+    /// Signed token (JWT).
+    pub token: String,
+
+    /// Token expiry (RFC 3339).
+    #[serde(default)]
+    pub expires_at: Option<String>,
+
+    /// Path to write the token file.
+    #[serde(default = "default_identity_path")]
+    pub path: String,
+}
+
+// `token` is never printed.
+impl std::fmt::Debug for IdentityConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentityConfig")
+            .field("token", &"[REDACTED]")
+            .field("expires_at", &self.expires_at)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+fn default_identity_path() -> String {
+    "/run/platform/identity/token".to_string()
+}
+
 /// Exec service configuration.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ExecConfig {
@@ -371,6 +421,107 @@ pub struct ConfigMessage {
     pub config: GuestConfig,
 }
 
+// =============================================================================
+// Config Update Messages
+// =============================================================================
+
+/// In-place config update pushed by the host to a running instance.
+#[derive(Debug, Deserialize)]
+pub struct ConfigUpdateMessage {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub config_version: String,
+    pub instance_id: String,
+    pub generation: u64,
+    /// New secrets to materialize, if the secrets version changed.
+    #[serde(default)]
+    pub secrets: Option<SecretsConfig>,
+    /// New config vars, if the set changed; replaces the workload's env.
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+    /// How to tell the workload about new secrets or config vars once they
+    /// are applied.
+    #[serde(default)]
+    pub reload: Option<SecretsReload>,
+    /// Renewed identity token.
+    #[serde(default)]
+    pub identity: Option<IdentityConfig>,
+}
+
+/// Workload notification after secrets are rewritten in place.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SecretsReload {
+    /// Not expected in an update; treated like `file`.
+    Restart,
+    /// The workload re-reads the file itself.
+    File,
+    /// Send a signal (e.g. `SIGHUP`) to the workload process.
+    Signal {
+        #[serde(default = "default_reload_signal")]
+        signal: String,
+    },
+    /// Run a hook command as the workload user.
+    Exec {
+        command: Vec<String>,
+        #[serde(default)]
+        timeout_seconds: Option<u32>,
+    },
+}
+
+fn default_reload_signal() -> String {
+    "SIGHUP".to_string()
+}
+
+/// Reply to a config update: `ack` once applied, or `error`.
+#[derive(Debug, Serialize)]
+pub struct ConfigUpdateReply {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub config_version: String,
+    pub generation: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ConfigUpdateReply {
+    pub fn ack(config_version: &str, generation: u64) -> Self {
+        Self {
+            msg_type: "ack".to_string(),
+            config_version: config_version.to_string(),
+            generation,
+            reason: None,
+            detail: None,
+        }
+    }
+
+    /// The update was applied, but notifying the workload failed.
+    pub fn ack_with_warning(
+        config_version: &str,
+        generation: u64,
+        reason: &str,
+        detail: &str,
+    ) -> Self {
+        Self {
+            reason: Some(reason.to_string()),
+            detail: Some(detail.to_string()),
+            ..Self::ack(config_version, generation)
+        }
+    }
+
+    pub fn error(config_version: &str, generation: u64, reason: &str, detail: &str) -> Self {
+        Self {
+            msg_type: "error".to_string(),
+            config_version: config_version.to_string(),
+            generation,
+            reason: Some(reason.to_string()),
+            detail: Some(detail.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -406,6 +557,58 @@ mod tests {
         assert_eq!(msg.config.workload.argv[0], "./server");
     }
 
+    #[test]
+    fn test_config_update_deserialization() {
+        let json = r#"{
+            "type": "config_update",
+            "config_version": "v1",
+            "instance_id": "inst_123",
+            "generation": 3,
+            "secrets": {
+                "required": true,
+                "path": "/run/secrets/platform.env",
+                "bundle_version_id": "sv_2",
+                "data": "API_KEY=rotated"
+            },
+            "reload": {"mode": "signal"}
+        }"#;
+
+        let msg: ConfigUpdateMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(msg.msg_type, "config_update");
+        assert_eq!(msg.generation, 3);
+        assert_eq!(
+            msg.reload,
+            Some(SecretsReload::Signal {
+                signal: "SIGHUP".to_string()
+            })
+        );
+        let secrets = msg.secrets.unwrap();
+        assert_eq!(secrets.bundle_version_id.as_deref(), Some("sv_2"));
+        assert!(!format!("{secrets:?}").contains("rotated"));
+
+        let json = r#"{
+            "type": "config_update",
+            "config_version": "v1",
+            "instance_id": "inst_123",
+            "generation": 3,
+            "identity": {"token": "eyJ.renewed", "expires_at": "2026-01-01T00:15:00Z"}
+        }"#;
+        let msg: ConfigUpdateMessage = serde_json::from_str(json).unwrap();
+        let identity = msg.identity.unwrap();
+        assert_eq!(identity.path, "/run/platform/identity/token");
+        assert!(!format!("{identity:?}").contains("eyJ"));
+
+        let reply = ConfigUpdateReply::error("v1", 3, "secrets_write_failed", "rename failed");
+        let json = serde_json::to_string(&reply).unwrap();
+        assert!(json.contains("\"type\":\"error\""));
+        assert!(json.contains("\"reason\":\"secrets_write_failed\""));
+
+        let reply = ConfigUpdateReply::ack_with_warning("v1", 3, "reload_failed", "exit code 1");
+        let json = serde_json::to_string(&reply).unwrap();
+        assert!(json.contains("\"type\":\"ack\""));
+        assert!(json.contains("\"reason\":\"reload_failed\""));
+    }
+
     #[test]
     fn test_status_serialization() {
         let status = StatusMessage::new("ready");
@@ -0,0 +1,377 @@
+//! In-place config updates from the host agent.
+//!
+//! Listens on vsock port 5163. When a non-disruptive change (a new secrets
+//! or config vars version, a renewed identity token) reaches a running
+//! instance, the host connects and sends one
+//! `config_update` message; guest-init applies it without restarting the
+//! workload and replies `ack`, or `error` with a reason code.
+//!
+//! New config vars replace the env guest-init keeps for the workload, which
+//! reload hooks run with; the running workload process keeps the env it
+//! started with.
+//!
+//! After rewriting the secrets file or replacing the env, the workload is
+//! notified as the update's `reload` asks: a signal, or a hook command run
+//! as the workload user. A failed notification is still acked (the change is
+//! in place), with reason `reload_failed`. A renewed identity token just
+//! replaces the token file; workloads re-read it.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use nix::sys::signal::Signal;
+use tracing::{error, info, warn};
+use vsock::{VsockAddr, VsockListener, VsockStream};
+use zeroize::Zeroizing;
+
+use crate::config::{ConfigUpdateMessage, ConfigUpdateReply, SecretsReload, WorkloadConfig};
+use crate::error::InitError;
+use crate::{identity, secrets, workload};
+
+/// Guest CID for listening (always 3 in Firecracker).
+const GUEST_CID: u32 = 3;
+
+/// Config version understood by this guest-init.
+const CONFIG_VERSION: &str = "v1";
+
+/// Reload hook timeout when the update sets none.
+const DEFAULT_RELOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run the config update service on the specified vsock port.
+pub async fn run_config_update_service(
+    port: u32,
+    instance_id: String,
+    mut workload: WorkloadConfig,
+) -> Result<()> {
+    let addr = VsockAddr::new(GUEST_CID, port);
+
+    let listener = VsockListener::bind(&addr).map_err(|e| {
+        anyhow::anyhow!(
+            "failed to bind config update service on port {}: {}",
+            port,
+            e
+        )
+    })?;
+
+    info!(port = port, "config update service listening");
+
+    loop {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                info!(peer_cid = peer.cid(), "config update connection accepted");
+
+                // Updates are applied one connection at a time, in order.
+                let instance_id = instance_id.clone();
+                let mut current = workload.clone();
+                let handled = tokio::task::spawn_blocking(move || {
+                    let result = handle_connection(stream, &instance_id, &mut current);
+                    (current, result)
+                })
+                .await;
+                match handled {
+                    Ok((updated, result)) => {
+                        workload = updated;
+                        if let Err(e) = result {
+                            error!(error = %e, "config update failed");
+                        }
+                    }
+                    Err(e) => error!(error = %e, "config update task panicked"),
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "accept failed");
+            }
+        }
+    }
+}
+
+/// Apply a single config update and reply to the host.
+fn handle_connection(
+    mut stream: VsockStream,
+    instance_id: &str,
+    workload: &mut WorkloadConfig,
+) -> Result<()> {
+    let update = read_update(&mut stream)?;
+    let generation = update.generation;
+
+    let reply = match apply(update, instance_id, workload) {
+        Ok(reload) => {
+            info!(generation, "config update applied");
+            match reload.map(|reload| reload_workload(&reload, workload)) {
+                Some(Err(e)) => {
+                    warn!(generation, error = %e, "workload reload failed");
+                    ConfigUpdateReply::ack_with_warning(
+                        CONFIG_VERSION,
+                        generation,
+                        e.reason_code(),
+                        &e.to_string(),
+                    )
+                }
+                _ => ConfigUpdateReply::ack(CONFIG_VERSION, generation),
+            }
+        }
+        Err(e) => {
+            let (reason, detail) = match e.downcast_ref::<InitError>() {
+                Some(init_err) => (init_err.reason_code(), init_err.to_string()),
+                None => ("unknown", e.to_string()),
+            };
+            warn!(generation, reason, "config update rejected");
+            ConfigUpdateReply::error(CONFIG_VERSION, generation, reason, &detail)
+        }
+    };
+
+    let json = serde_json::to_string(&reply)?;
+    stream.write_all(json.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn read_update(stream: &mut VsockStream) -> Result<ConfigUpdateMessage> {
+    let mut reader = BufReader::new(stream);
+    // The update line may carry secrets; zero it once parsed.
+    let mut line = Zeroizing::new(String::new());
+    reader.read_line(&mut line)?;
+
+    if line.is_empty() {
+        return Err(InitError::ConfigParseFailed("host closed connection".to_string()).into());
+    }
+
+    serde_json::from_str(&line).map_err(|e| {
+        InitError::ConfigParseFailed(format!("invalid config update JSON: {}", e)).into()
+    })
+}
+
+/// Validate and apply an update; returns the workload notification to run
+/// when secrets were rewritten or the env replaced.
+fn apply(
+    update: ConfigUpdateMessage,
+    instance_id: &str,
+    workload: &mut WorkloadConfig,
+) -> Result<Option<SecretsReload>> {
+    if update.msg_type != "config_update" {
+        return Err(InitError::ConfigParseFailed(format!(
+            "expected 'config_update' message, got '{}'",
+            update.msg_type
+        ))
+        .into());
+    }
+    if update.config_version != CONFIG_VERSION {
+        return Err(InitError::ConfigParseFailed(format!(
+            "unsupported config version '{}'",
+            update.config_version
+        ))
+        .into());
+    }
+    if update.instance_id != instance_id {
+        return Err(InitError::ConfigParseFailed(format!(
+            "update is for instance '{}'",
+            update.instance_id
+        ))
+        .into());
+    }
+
+    if let Some(mut identity_config) = update.identity {
+        info!(generation = update.generation, "replacing identity token");
+        identity::materialize(&mut identity_config, workload.uid, workload.gid)?;
+    }
+
+    let mut changed = false;
+    if let Some(mut secrets_config) = update.secrets {
+        info!(
+            generation = update.generation,
+            bundle_version_id = ?secrets_config.bundle_version_id,
+            "rewriting secrets"
+        );
+        // Written to a temp file and renamed, so readers never see a partial file.
+        tokio::runtime::Handle::current().block_on(secrets::materialize(&mut secrets_config))?;
+        changed = true;
+    }
+
+    if let Some(env) = update.env {
+        info!(
+            generation = update.generation,
+            count = env.len(),
+            "replacing config vars"
+        );
+        replace_env(&mut workload.env, env);
+        changed = true;
+    }
+
+    Ok(if changed { update.reload } else { None })
+}
+
+/// Replace the host-provided vars in `env` with `vars`; vars guest-init set
+/// itself are kept.
+fn replace_env(env: &mut HashMap<String, String>, vars: HashMap<String, String>) {
+    env.retain(|key, _| key == identity::IDENTITY_TOKEN_FILE_ENV);
+    env.extend(vars);
+}
+
+/// Tell the workload its secrets file was rewritten.
+fn reload_workload(reload: &SecretsReload, workload: &WorkloadConfig) -> Result<(), InitError> {
+    match reload {
+        SecretsReload::Restart | SecretsReload::File => Ok(()),
+        SecretsReload::Signal { signal } => {
+            let signal: Signal = signal
+                .parse()
+                .map_err(|_| InitError::ReloadFailed(format!("unknown signal '{signal}'")))?;
+            workload::signal_workload(signal)
+        }
+        SecretsReload::Exec {
+            command,
+            timeout_seconds,
+        } => {
+            let timeout = timeout_seconds
+                .map(|secs| Duration::from_secs(secs.into()))
+                .unwrap_or(DEFAULT_RELOAD_TIMEOUT);
+            run_reload_hook(command, workload, timeout)
+        }
+    }
+}
+
+/// Run the reload hook with the workload's user, directory and environment.
+fn run_reload_hook(
+    command: &[String],
+    workload: &WorkloadConfig,
+    timeout: Duration,
+) -> Result<(), InitError> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(InitError::ReloadFailed(
+            "reload command is empty".to_string(),
+        ));
+    };
+
+    info!(program = %program, "running reload hook");
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .current_dir(&workload.cwd)
+        .envs(&workload.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    if workload.uid != 0 || workload.gid != 0 {
+        cmd.uid(workload.uid).gid(workload.gid);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| InitError::ReloadFailed(format!("spawn {program}: {e}")))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(InitError::ReloadFailed(format!(
+                    "reload hook exited with {status}"
+                )))
+            };
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(InitError::ReloadFailed(format!(
+                "reload hook timed out after {}s",
+                timeout.as_secs()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(json: &str) -> ConfigUpdateMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_apply_rejects_other_instance() {
+        let msg = update(
+            r#"{"type":"config_update","config_version":"v1","instance_id":"inst_other","generation":2}"#,
+        );
+        let err = apply(msg, "inst_123", &mut workload()).unwrap_err();
+        assert!(err.to_string().contains("config_parse_failed"));
+    }
+
+    #[test]
+    fn test_apply_without_secrets_is_a_no_op() {
+        let msg = update(
+            r#"{"type":"config_update","config_version":"v1","instance_id":"inst_123","generation":2,"reload":{"mode":"signal"}}"#,
+        );
+        // Nothing was rewritten, so there is nothing to reload.
+        assert_eq!(apply(msg, "inst_123", &mut workload()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_apply_replaces_env() {
+        let mut workload = workload();
+        workload.env = HashMap::from([
+            ("LOG_LEVEL".to_string(), "info".to_string()),
+            ("OLD".to_string(), "1".to_string()),
+            (
+                identity::IDENTITY_TOKEN_FILE_ENV.to_string(),
+                "/run/plfm/identity/token".to_string(),
+            ),
+        ]);
+        let msg = update(
+            r#"{"type":"config_update","config_version":"v1","instance_id":"inst_123","generation":3,"env":{"LOG_LEVEL":"debug"},"reload":{"mode":"signal"}}"#,
+        );
+
+        let reload = apply(msg, "inst_123", &mut workload).unwrap();
+        assert!(matches!(reload, Some(SecretsReload::Signal { .. })));
+        assert_eq!(
+            workload.env.get("LOG_LEVEL").map(String::as_str),
+            Some("debug")
+        );
+        assert!(!workload.env.contains_key("OLD"));
+        assert!(workload.env.contains_key(identity::IDENTITY_TOKEN_FILE_ENV));
+    }
+
+    fn workload() -> WorkloadConfig {
+        WorkloadConfig {
+            argv: vec!["./server".to_string()],
+            cwd: "/".to_string(),
+            env: Default::default(),
+            uid: unsafe { libc::getuid() },
+            gid: unsafe { libc::getgid() },
+            stdin: false,
+            tty: false,
+        }
+    }
+
+    fn exec(command: &[&str], timeout_seconds: Option<u32>) -> SecretsReload {
+        SecretsReload::Exec {
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            timeout_seconds,
+        }
+    }
+
+    #[test]
+    fn test_reload_hook_exit_status() {
+        assert!(reload_workload(&exec(&["true"], None), &workload()).is_ok());
+
+        let err = reload_workload(&exec(&["false"], None), &workload()).unwrap_err();
+        assert_eq!(err.reason_code(), "reload_failed");
+
+        let err = reload_workload(&exec(&["sleep", "5"], Some(1)), &workload()).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn test_reload_rejects_unknown_signal() {
+        let reload = SecretsReload::Signal {
+            signal: "SIGNOPE".to_string(),
+        };
+        let err = reload_workload(&reload, &workload()).unwrap_err();
+        assert!(err.to_string().contains("unknown signal"));
+    }
+}
@@ -38,6 +38,14 @@ pub enum InitError {
     #[error("workload_crashed: exit_code={exit_code}")]
     WorkloadCrashed { exit_code: i32 },
 
+    /// Workload was killed by the guest kernel OOM killer.
+    #[error("oom: {0}")]
+    OomKilled(String),
+
+    /// Could not notify the workload of rewritten secrets.
+    #[error("reload_failed: {0}")]
+    ReloadFailed(String),
+
     /// IO error.
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
@@ -64,6 +72,8 @@ impl InitError {
             InitError::SecretsWriteFailed(_) => "secrets_write_failed",
             InitError::WorkloadStartFailed(_) => "workload_start_failed",
             InitError::WorkloadCrashed { .. } => "workload_crashed",
+            InitError::OomKilled(_) => "oom",
+            InitError::ReloadFailed(_) => "reload_failed",
             InitError::Io(_) => "io_error",
             InitError::Vsock(_) => "vsock_error",
             InitError::Syscall(_) => "syscall_error",
@@ -14,6 +14,7 @@ use anyhow::{Context, Result};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 use vsock::{VsockAddr, VsockStream};
+use zeroize::Zeroizing;
 
 use crate::config::{AckMessage, ConfigMessage, GuestConfig, HelloMessage, StatusMessage};
 use crate::error::InitError;
@@ -29,27 +30,30 @@ const CONNECT_TIMEOUT_SECS: u64 = 5;
 /// Global connection for status reporting.
 static VSOCK_CONN: OnceLock<std::sync::Mutex<VsockStream>> = OnceLock::new();
 
-/// Read expected instance ID from kernel cmdline.
-fn read_instance_id_from_cmdline() -> Option<String> {
-    let cmdline = std::fs::read_to_string("/proc/cmdline").ok()?;
-    for part in cmdline.split_whitespace() {
-        if let Some(id) = part.strip_prefix("platform.instance_id=") {
-            return Some(id.to_string());
-        }
-    }
-    None
+/// Value of a `key=value` parameter on the kernel cmdline.
+fn cmdline_param(cmdline: &str, key: &str) -> Option<String> {
+    cmdline.split_whitespace().find_map(|part| {
+        part.strip_prefix(key)?
+            .strip_prefix('=')
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    })
 }
 
-/// Generate a unique boot ID.
+/// Generate a unique boot ID, for agents that do not pass one on the cmdline.
 fn generate_boot_id() -> String {
     Uuid::new_v4().to_string()
 }
 
 /// Perform the config handshake with the host agent.
 pub async fn perform_handshake(port: u32) -> Result<GuestConfig> {
-    // Read instance ID from kernel cmdline
-    let instance_id = read_instance_id_from_cmdline().unwrap_or_else(|| "unknown".to_string());
-    let boot_id = generate_boot_id();
+    // The agent passes the instance ID and the boot ID it tracks this boot
+    // under on the kernel cmdline; echoing the boot ID back lets it tell this
+    // boot's status apart from earlier boots of the same instance.
+    let cmdline = std::fs::read_to_string("/proc/cmdline").unwrap_or_default();
+    let instance_id =
+        cmdline_param(&cmdline, "platform.instance_id").unwrap_or_else(|| "unknown".to_string());
+    let boot_id = cmdline_param(&cmdline, "platform.boot_id").unwrap_or_else(generate_boot_id);
 
     info!(
         instance_id = %instance_id,
@@ -102,7 +106,8 @@ fn send_message<T: serde::Serialize>(stream: &mut VsockStream, msg: &T) -> Resul
 /// Receive config message from host.
 fn receive_config(stream: &mut VsockStream) -> Result<GuestConfig> {
     let mut reader = BufReader::new(stream);
-    let mut line = String::new();
+    // The config line may carry secrets; zero it once parsed.
+    let mut line = Zeroizing::new(String::new());
 
     reader
         .read_line(&mut line)
@@ -199,4 +204,19 @@ mod tests {
         // Should be valid UUID
         assert!(Uuid::parse_str(&id1).is_ok());
     }
+
+    #[test]
+    fn test_cmdline_param() {
+        let cmdline = "console=ttyS0 panic=1 platform.instance_id=inst_1 platform.boot_id=boot_2\n";
+        assert_eq!(
+            cmdline_param(cmdline, "platform.instance_id").as_deref(),
+            Some("inst_1")
+        );
+        assert_eq!(
+            cmdline_param(cmdline, "platform.boot_id").as_deref(),
+            Some("boot_2")
+        );
+        assert_eq!(cmdline_param(cmdline, "platform.boot"), None);
+        assert_eq!(cmdline_param("platform.boot_id=", "platform.boot_id"), None);
+    }
 }
@@ -0,0 +1,94 @@
+//! Instance identity token materialization.
+//!
+//! The token is written like secrets: atomically, to a tmpfs, readable only
+//! by the workload user. The workload finds it through
+//! `PLFM_IDENTITY_TOKEN_FILE` and should re-read it before each use; the
+//! host replaces it with a renewed token well before it expires.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::config::IdentityConfig;
+use crate::secrets;
+
+/// Environment variable naming the token file.
+pub const IDENTITY_TOKEN_FILE_ENV: &str = "PLFM_IDENTITY_TOKEN_FILE";
+
+/// Token file permissions.
+const IDENTITY_TOKEN_MODE: u32 = 0o400;
+
+/// Write the token for the workload user, consuming `config.token`.
+pub fn materialize(config: &mut IdentityConfig, uid: u32, gid: u32) -> Result<()> {
+    materialize_with(config, uid, gid, true)
+}
+
+fn materialize_with(
+    config: &mut IdentityConfig,
+    uid: u32,
+    gid: u32,
+    require_tmpfs: bool,
+) -> Result<()> {
+    let token = Zeroizing::new(std::mem::take(&mut config.token));
+    secrets::write_secret_file(
+        Path::new(&config.path),
+        &token,
+        IDENTITY_TOKEN_MODE,
+        uid,
+        gid,
+        require_tmpfs,
+    )?;
+
+    info!(
+        path = %config.path,
+        expires_at = config.expires_at.as_deref().unwrap_or(""),
+        "identity token materialized"
+    );
+    Ok(())
+}
+
+/// Point the workload at the token file.
+pub fn export_path(config: &IdentityConfig, env: &mut HashMap<String, String>) {
+    env.insert(IDENTITY_TOKEN_FILE_ENV.to_string(), config.path.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_materialize_replaces_token() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("identity").join("token");
+        let config = |token: &str| IdentityConfig {
+            token: token.to_string(),
+            expires_at: None,
+            path: path.to_string_lossy().to_string(),
+        };
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+
+        let mut first = config("eyJ.first");
+        materialize_with(&mut first, uid, gid, false).unwrap();
+        assert!(first.token.is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "eyJ.first");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o400);
+
+        // A renewal replaces the read-only file in place.
+        materialize_with(&mut config("eyJ.renewed"), uid, gid, false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "eyJ.renewed");
+
+        let mut env = HashMap::new();
+        export_path(&first, &mut env);
+        assert_eq!(
+            env.get(IDENTITY_TOKEN_FILE_ENV).map(String::as_str),
+            path.to_str()
+        );
+    }
+}
@@ -4,10 +4,12 @@
 //! - Config handshake with host agent over vsock
 //! - Network configuration inside the guest
 //! - Volume mounting
-//! - Secrets materialization
+//! - Secrets and identity token materialization
 //! - Workload process spawning and supervision
 //! - Signal forwarding
 //! - Exec service for `plfm exec`
+//! - In-place config updates (new secrets versions, renewed identity
+//!   tokens) while running
 //!
 //! Reference: docs/specs/runtime/guest-init.md
 
@@ -17,10 +19,12 @@ use anyhow::Result;
 use tracing::{error, info};
 
 mod config;
+mod config_update;
 mod error;
 mod exec;
 mod handshake;
 mod health;
+mod identity;
 mod logging;
 mod mount;
 mod network;
@@ -39,6 +43,9 @@ pub const CONFIG_VSOCK_PORT: u32 = 5161;
 /// vsock port for exec service (guest listens).
 pub const EXEC_VSOCK_PORT: u32 = 5162;
 
+/// vsock port for in-place config updates (guest listens).
+pub const CONFIG_UPDATE_VSOCK_PORT: u32 = 5163;
+
 /// Boot log path.
 pub const BOOT_LOG_PATH: &str = "/run/platform/guest-init.log";
 
@@ -90,6 +97,18 @@ async fn run() -> Result<i32> {
         None
     };
 
+    info!(
+        port = CONFIG_UPDATE_VSOCK_PORT,
+        "starting config update service"
+    );
+    let config_update_handle = tokio::spawn(config_update::run_config_update_service(
+        CONFIG_UPDATE_VSOCK_PORT,
+        config.instance_id.clone(),
+        config.workload.clone(),
+    ));
+
+    let volume_growth_handle = tokio::spawn(mount::watch_volume_growth(config.mounts.clone()));
+
     info!("launching workload");
     let health_config = config.health;
     let workload_handle = tokio::spawn(workload::run(config.workload));
@@ -115,6 +134,8 @@ async fn run() -> Result<i32> {
                     if let Some(handle) = health_handle {
                         handle.abort();
                     }
+                    volume_growth_handle.abort();
+                    config_update_handle.abort();
                     return Err(e);
                 }
                 Err(e) => {
@@ -126,6 +147,8 @@ async fn run() -> Result<i32> {
                     if let Some(handle) = health_handle {
                         handle.abort();
                     }
+                    volume_growth_handle.abort();
+                    config_update_handle.abort();
                     return Err(err);
                 }
             }
@@ -138,6 +161,8 @@ async fn run() -> Result<i32> {
     if let Some(handle) = health_handle {
         handle.abort();
     }
+    volume_growth_handle.abort();
+    config_update_handle.abort();
 
     handshake::report_exit(exit_code).await?;
 
@@ -146,7 +171,7 @@ async fn run() -> Result<i32> {
 
 async fn perform_setup() -> Result<config::GuestConfig> {
     info!("performing config handshake with host agent");
-    let config = handshake::perform_handshake(CONFIG_VSOCK_PORT).await?;
+    let mut config = handshake::perform_handshake(CONFIG_VSOCK_PORT).await?;
     info!(
         instance_id = %config.instance_id,
         generation = config.generation,
@@ -165,12 +190,17 @@ async fn perform_setup() -> Result<config::GuestConfig> {
         info!("volumes mounted");
     }
 
-    if let Some(secrets_config) = &config.secrets {
+    if let Some(secrets_config) = config.secrets.as_mut() {
         info!("materializing secrets");
         secrets::materialize(secrets_config).await?;
         info!("secrets materialized");
     }
 
+    if let Some(identity_config) = config.identity.as_mut() {
+        identity::materialize(identity_config, config.workload.uid, config.workload.gid)?;
+        identity::export_path(identity_config, &mut config.workload.env);
+    }
+
     handshake::report_status("config_applied").await?;
     info!("config applied");
 
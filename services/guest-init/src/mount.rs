@@ -1,8 +1,10 @@
 //! Volume mounting.
 //!
-//! Mounts volumes according to the configuration from the host agent.
+//! Mounts volumes according to the configuration from the host agent, and
+//! grows ext4 filesystems online when the host grows the backing device.
 //! Note: This module is Linux-only and uses direct libc calls.
 
+use std::collections::HashMap;
 #[cfg(target_os = "linux")]
 use std::ffi::CString;
 #[cfg(target_os = "linux")]
@@ -11,10 +13,10 @@ use std::fs;
 use std::path::Path;
 #[cfg(target_os = "linux")]
 use std::ptr;
+use std::time::Duration;
 
 use anyhow::Result;
-#[cfg(target_os = "linux")]
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::config::MountConfig;
 use crate::error::InitError;
@@ -26,6 +28,13 @@ const RESERVED_PATHS: &[&str] = &["/proc", "/sys", "/dev", "/run/secrets", "/tmp
 #[cfg(target_os = "linux")]
 const MS_RDONLY: libc::c_ulong = 1;
 
+/// `EXT4_IOC_RESIZE_FS`: `_IOW('f', 16, __u64)`, new size in blocks.
+#[cfg(target_os = "linux")]
+const EXT4_IOC_RESIZE_FS: libc::c_ulong = 0x4008_6610;
+
+/// How often writable volumes are checked for a grown device.
+const GROW_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Mount a volume according to configuration.
 pub fn mount_volume(config: &MountConfig) -> Result<()> {
     // Validate mount point is not reserved
@@ -121,6 +130,14 @@ fn mount_block_volume(config: &MountConfig) -> Result<()> {
         "volume mounted"
     );
 
+    // The device may have been grown while the instance was down. A failed
+    // grow leaves the volume usable at its old size, so don't fail the boot.
+    if is_growable(config) {
+        if let Err(e) = grow_filesystem(config) {
+            warn!(name = %config.name, error = %e, "failed to grow filesystem");
+        }
+    }
+
     Ok(())
 }
 
@@ -134,6 +151,114 @@ fn mount_block_volume(config: &MountConfig) -> Result<()> {
     .into())
 }
 
+/// Whether a mount's filesystem is grown to follow its device.
+fn is_growable(config: &MountConfig) -> bool {
+    config.kind == "volume"
+        && config.mode != "ro"
+        && config.fs_type == "ext4"
+        && config.device.is_some()
+}
+
+/// Grow the ext4 filesystem of a mounted volume to fill its device. Returns
+/// the device size; resizing to the current size is a no-op in the kernel.
+#[cfg(target_os = "linux")]
+fn grow_filesystem(config: &MountConfig) -> Result<u64> {
+    use std::os::unix::io::AsRawFd;
+
+    let device = config.device.as_deref().unwrap_or_default();
+    let device_bytes = device_size(device).map_err(|e| InitError::MountFailed {
+        name: config.name.clone(),
+        detail: format!("failed to read size of {}: {}", device, e),
+    })?;
+
+    let stat = nix::sys::statvfs::statvfs(config.mountpoint.as_str()).map_err(|e| {
+        InitError::MountFailed {
+            name: config.name.clone(),
+            detail: format!("statvfs failed: {}", e),
+        }
+    })?;
+    let block_size = stat.block_size() as u64;
+    if block_size == 0 {
+        return Ok(device_bytes);
+    }
+    let blocks: u64 = device_bytes / block_size;
+
+    let mountpoint = fs::File::open(&config.mountpoint).map_err(|e| InitError::MountFailed {
+        name: config.name.clone(),
+        detail: format!("failed to open mountpoint: {}", e),
+    })?;
+    let result = unsafe {
+        libc::ioctl(
+            mountpoint.as_raw_fd(),
+            EXT4_IOC_RESIZE_FS as _,
+            &blocks as *const u64,
+        )
+    };
+    if result != 0 {
+        let err = std::io::Error::last_os_error();
+        return Err(InitError::MountFailed {
+            name: config.name.clone(),
+            detail: format!("ext4 online resize failed: {}", err),
+        }
+        .into());
+    }
+
+    Ok(device_bytes)
+}
+
+/// Stub for non-Linux platforms.
+#[cfg(not(target_os = "linux"))]
+fn grow_filesystem(config: &MountConfig) -> Result<u64> {
+    Err(InitError::MountFailed {
+        name: config.name.clone(),
+        detail: "online resize only supported on Linux".to_string(),
+    }
+    .into())
+}
+
+/// Watch writable volumes and grow their filesystems when the host agent
+/// grows the backing device (the guest sees a virtio capacity change).
+pub async fn watch_volume_growth(mounts: Vec<MountConfig>) {
+    let mounts: Vec<MountConfig> = mounts.into_iter().filter(is_growable).collect();
+    if mounts.is_empty() {
+        return;
+    }
+
+    let mut last_sizes: HashMap<String, u64> = HashMap::new();
+    loop {
+        tokio::time::sleep(GROW_CHECK_INTERVAL).await;
+        for config in &mounts {
+            let Some(device) = config.device.as_deref() else {
+                continue;
+            };
+            let Ok(size) = device_size(device) else {
+                continue;
+            };
+            if last_sizes.get(&config.name) == Some(&size) {
+                continue;
+            }
+            match grow_filesystem(config) {
+                Ok(size) => {
+                    if last_sizes.insert(config.name.clone(), size).is_some() {
+                        info!(name = %config.name, size_bytes = size, "volume grown");
+                    }
+                }
+                Err(e) => {
+                    warn!(name = %config.name, error = %e, "failed to grow filesystem");
+                    // Don't retry until the device changes again.
+                    last_sizes.insert(config.name.clone(), size);
+                }
+            }
+        }
+    }
+}
+
+/// Size of a block device in bytes.
+fn device_size(device: &str) -> std::io::Result<u64> {
+    use std::io::{Seek, SeekFrom};
+    std::fs::File::open(device)?.seek(SeekFrom::End(0))
+}
+
 /// Mount a tmpfs volume using libc.
 #[cfg(target_os = "linux")]
 fn mount_tmpfs(config: &MountConfig) -> Result<()> {
@@ -220,4 +345,30 @@ mod tests {
         let err = result.unwrap_err().to_string();
         assert!(err.contains("reserved"));
     }
+
+    #[test]
+    fn test_only_writable_ext4_volumes_grow() {
+        let config = MountConfig {
+            kind: "volume".to_string(),
+            name: "data".to_string(),
+            device: Some("/dev/vdc".to_string()),
+            mountpoint: "/data".to_string(),
+            fs_type: "ext4".to_string(),
+            mode: "rw".to_string(),
+        };
+        assert!(is_growable(&config));
+
+        let read_only = MountConfig {
+            mode: "ro".to_string(),
+            ..config.clone()
+        };
+        assert!(!is_growable(&read_only));
+
+        let tmpfs = MountConfig {
+            kind: "tmpfs".to_string(),
+            device: None,
+            ..config
+        };
+        assert!(!is_growable(&tmpfs));
+    }
 }
@@ -1,6 +1,8 @@
 //! Secrets materialization.
 //!
 //! Writes secrets to a file with atomic writes and correct permissions.
+//! The file always lands on a tmpfs (one is mounted over the parent
+//! directory if needed), and the in-memory copy is zeroed once written.
 
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
@@ -10,14 +12,19 @@ use std::path::Path;
 use anyhow::Result;
 use nix::unistd::{chown, Gid, Uid};
 use tracing::info;
+use zeroize::Zeroizing;
 
 use crate::config::SecretsConfig;
 use crate::error::InitError;
 
-/// Materialize secrets to the configured path.
-pub async fn materialize(config: &SecretsConfig) -> Result<()> {
-    let data = match &config.data {
-        Some(data) => data.clone(),
+/// Materialize secrets to the configured path, consuming `config.data`.
+pub async fn materialize(config: &mut SecretsConfig) -> Result<()> {
+    materialize_with(config, true).await
+}
+
+async fn materialize_with(config: &mut SecretsConfig, require_tmpfs: bool) -> Result<()> {
+    let data = match config.data.take() {
+        Some(data) => Zeroizing::new(data),
         None => {
             if config.required {
                 return Err(InitError::SecretsMissing(
@@ -30,27 +37,59 @@ pub async fn materialize(config: &SecretsConfig) -> Result<()> {
         }
     };
 
-    let path = Path::new(&config.path);
+    // Parse permissions mode (octal string like "0400")
+    let mode = parse_mode(&config.mode)?;
+
+    write_secret_file(
+        Path::new(&config.path),
+        &data,
+        mode,
+        config.owner_uid,
+        config.owner_gid,
+        require_tmpfs,
+    )?;
 
+    info!(
+        path = %config.path,
+        mode = %config.mode,
+        uid = config.owner_uid,
+        gid = config.owner_gid,
+        "secrets materialized"
+    );
+
+    Ok(())
+}
+
+/// Atomically write `data` to `path` on a tmpfs, owned by `uid`/`gid`.
+pub(crate) fn write_secret_file(
+    path: &Path,
+    data: &str,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    require_tmpfs: bool,
+) -> Result<()> {
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| {
             InitError::SecretsWriteFailed(format!("failed to create directory: {}", e))
         })?;
+        if require_tmpfs {
+            ensure_tmpfs(parent)?;
+        }
     }
 
-    // Parse permissions mode (octal string like "0400")
-    let mode = parse_mode(&config.mode)?;
-
     // Write atomically
     let tmp_path = path.with_extension("tmp");
-    write_with_permissions(&tmp_path, &data, mode)?;
+    write_with_permissions(&tmp_path, data, mode)?;
 
     // Set ownership before rename
-    let uid = Uid::from_raw(config.owner_uid);
-    let gid = Gid::from_raw(config.owner_gid);
-    chown(&tmp_path, Some(uid), Some(gid))
-        .map_err(|e| InitError::SecretsWriteFailed(format!("chown failed: {}", e)))?;
+    chown(
+        &tmp_path,
+        Some(Uid::from_raw(uid)),
+        Some(Gid::from_raw(gid)),
+    )
+    .map_err(|e| InitError::SecretsWriteFailed(format!("chown failed: {}", e)))?;
 
     // Sync to disk
     {
@@ -62,17 +101,41 @@ pub async fn materialize(config: &SecretsConfig) -> Result<()> {
     fs::rename(&tmp_path, path)
         .map_err(|e| InitError::SecretsWriteFailed(format!("rename failed: {}", e)))?;
 
-    info!(
-        path = %config.path,
-        mode = %config.mode,
-        uid = config.owner_uid,
-        gid = config.owner_gid,
-        "secrets materialized"
-    );
+    Ok(())
+}
+
+/// Mount a tmpfs over `dir` unless it is already on one.
+#[cfg(target_os = "linux")]
+fn ensure_tmpfs(dir: &Path) -> Result<()> {
+    use nix::mount::{mount, MsFlags};
+    use nix::sys::statfs::{statfs, TMPFS_MAGIC};
+
+    let fs_type = statfs(dir)
+        .map_err(|e| InitError::SecretsWriteFailed(format!("statfs failed: {}", e)))?
+        .filesystem_type();
+    if fs_type == TMPFS_MAGIC {
+        return Ok(());
+    }
+
+    mount(
+        Some("tmpfs"),
+        dir,
+        Some("tmpfs"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        Some("mode=0711,size=4m"),
+    )
+    .map_err(|e| InitError::SecretsWriteFailed(format!("tmpfs mount failed: {}", e)))?;
 
+    info!(path = %dir.display(), "mounted tmpfs for secrets");
     Ok(())
 }
 
+/// Stub for non-Linux platforms.
+#[cfg(not(target_os = "linux"))]
+fn ensure_tmpfs(_dir: &Path) -> Result<()> {
+    Err(InitError::SecretsWriteFailed("tmpfs only supported on Linux".to_string()).into())
+}
+
 /// Parse octal mode string (e.g., "0400") to u32.
 fn parse_mode(mode_str: &str) -> Result<u32> {
     let mode_str = mode_str.trim_start_matches('0');
@@ -115,7 +178,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let path = dir.path().join("secrets").join("platform.env");
 
-        let config = SecretsConfig {
+        let mut config = SecretsConfig {
             required: true,
             path: path.to_string_lossy().to_string(),
             mode: "0400".to_string(),
@@ -126,7 +189,8 @@ mod tests {
             data: Some("API_KEY=secret123\nDB_URL=postgres://...".to_string()),
         };
 
-        materialize(&config).await.unwrap();
+        materialize_with(&mut config, false).await.unwrap();
+        assert!(config.data.is_none());
 
         // Check file exists and has correct content
         let content = fs::read_to_string(&path).unwrap();
@@ -139,7 +203,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_missing_required_secrets() {
-        let config = SecretsConfig {
+        let mut config = SecretsConfig {
             required: true,
             path: "/tmp/test-secrets.env".to_string(),
             mode: "0400".to_string(),
@@ -150,7 +214,7 @@ mod tests {
             data: None, // No data!
         };
 
-        let result = materialize(&config).await;
+        let result = materialize(&mut config).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("secrets_missing"));
     }
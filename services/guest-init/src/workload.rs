@@ -4,8 +4,12 @@
 //! - Signal forwarding (SIGTERM, SIGINT, SIGHUP)
 //! - Zombie reaping
 //! - Exit code capture
+//! - OOM kill detection (reported to the host as reason `oom`)
+//! - Signalling the running workload on request (secrets reload)
 
+use std::os::unix::process::ExitStatusExt;
 use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicI32, Ordering};
 
 use anyhow::{Context, Result};
 use nix::sys::signal::{kill, Signal};
@@ -18,6 +22,22 @@ use tracing::{debug, info, warn};
 use crate::config::WorkloadConfig;
 use crate::error::InitError;
 
+/// PID of the running workload; 0 when none is running.
+static WORKLOAD_PID: AtomicI32 = AtomicI32::new(0);
+
+/// Send `signal` to the running workload.
+pub fn signal_workload(signal: Signal) -> Result<(), InitError> {
+    let pid = WORKLOAD_PID.load(Ordering::SeqCst);
+    if pid == 0 {
+        return Err(InitError::ReloadFailed(
+            "workload is not running".to_string(),
+        ));
+    }
+    info!(pid, signal = ?signal, "signalling workload");
+    kill(Pid::from_raw(pid), signal)
+        .map_err(|e| InitError::ReloadFailed(format!("kill {signal}: {e}")))
+}
+
 pub async fn run(config: WorkloadConfig) -> Result<i32> {
     if config.argv.is_empty() {
         return Err(InitError::WorkloadStartFailed("argv is empty".to_string()).into());
@@ -71,6 +91,8 @@ pub async fn run(config: WorkloadConfig) -> Result<i32> {
         }
     }
 
+    let oom_kills_before = read_oom_kill_count();
+
     // Spawn the process
     let mut child = cmd
         .spawn()
@@ -78,9 +100,12 @@ pub async fn run(config: WorkloadConfig) -> Result<i32> {
 
     let child_pid = child.id().expect("child should have pid");
     info!(pid = child_pid, "workload started");
+    WORKLOAD_PID.store(child_pid as i32, Ordering::SeqCst);
 
     // Wait for the child while handling signals
-    let exit_status = wait_with_signals(&mut child).await?;
+    let exit_status = wait_with_signals(&mut child).await;
+    WORKLOAD_PID.store(0, Ordering::SeqCst);
+    let exit_status = exit_status?;
     let exit_code = exit_status.code().unwrap_or(128);
 
     info!(exit_code = exit_code, "workload exited");
@@ -88,9 +113,34 @@ pub async fn run(config: WorkloadConfig) -> Result<i32> {
     // Reap any remaining zombies
     reap_zombies();
 
+    if exit_status.signal() == Some(libc::SIGKILL) {
+        if let (Some(before), Some(after)) = (oom_kills_before, read_oom_kill_count()) {
+            if after > before {
+                return Err(InitError::OomKilled(format!(
+                    "workload killed by OOM killer (oom_kill {before} -> {after})"
+                ))
+                .into());
+            }
+        }
+    }
+
     Ok(exit_code)
 }
 
+/// Read the kernel's cumulative OOM kill counter.
+fn read_oom_kill_count() -> Option<u64> {
+    std::fs::read_to_string("/proc/vmstat")
+        .ok()
+        .and_then(|vmstat| parse_oom_kill_count(&vmstat))
+}
+
+fn parse_oom_kill_count(vmstat: &str) -> Option<u64> {
+    vmstat.lines().find_map(|line| {
+        line.strip_prefix("oom_kill ")
+            .and_then(|value| value.trim().parse().ok())
+    })
+}
+
 /// Wait for child exit while forwarding signals.
 async fn wait_with_signals(child: &mut Child) -> Result<ExitStatus> {
     let child_pid = child.id().expect("child should have pid") as i32;
@@ -183,6 +233,13 @@ mod tests {
         assert!(result.is_ok() || result.is_err());
     }
 
+    #[test]
+    fn test_parse_oom_kill_count() {
+        let vmstat = "pgfault 1234\noom_kill 3\npgmajfault 5\n";
+        assert_eq!(parse_oom_kill_count(vmstat), Some(3));
+        assert_eq!(parse_oom_kill_count("pgfault 1234\n"), None);
+    }
+
     #[test]
     fn test_reap_zombies() {
         // Just make sure it doesn't panic with no children
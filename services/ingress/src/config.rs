@@ -2,10 +2,19 @@
 //!
 //! For now, ingress focuses on consuming routing-related events from the control plane.
 
-use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
 
+use crate::proxy::{
+    DEFAULT_MAX_CONNECTIONS_PER_IP, DEFAULT_MAX_SNIFF_BYTES, DEFAULT_OVERLOAD_ACCEPT_RATE,
+    DEFAULT_OVERLOAD_THRESHOLD, DEFAULT_SNIFF_TIMEOUT, DEFAULT_TLS_HANDSHAKE_TIMEOUT,
+};
+
 #[derive(Clone)]
 pub struct RedactedString(String);
 
@@ -32,6 +41,9 @@ pub struct ListenerBinding {
     pub bind_addr: SocketAddr,
     /// Maximum concurrent connections.
     pub max_connections: usize,
+    /// Serves internal routes only (bound to an overlay address) instead of
+    /// public routes.
+    pub internal: bool,
 }
 
 /// Ingress configuration (env-driven).
@@ -64,14 +76,118 @@ pub struct Config {
     /// Log level (trace, debug, info, warn, error).
     pub log_level: String,
 
-    /// Listener bindings (address:port pairs).
+    /// Listener bindings (address:port pairs), public and internal.
     pub listeners: Vec<ListenerBinding>,
 
     /// Enable proxy mode (start listeners). If false, only sync routes.
     pub proxy_enabled: bool,
 
-    /// Backend sync interval (how often to refresh backend instance lists).
+    /// Backend sync interval (how often to apply backend deltas).
     pub backend_sync_interval: Duration,
+
+    /// Full backend resync interval (guards against drift from missed deltas).
+    pub backend_full_resync_interval: Duration,
+
+    /// Identifies this ingress in status reports to the control plane.
+    pub ingress_id: String,
+
+    /// Public IPs reported to the control plane.
+    pub public_ips: Vec<IpAddr>,
+
+    /// How often to report programmed routes to the control plane.
+    pub status_report_interval: Duration,
+
+    /// Log JA3/JA4 fingerprints of every TLS ClientHello.
+    pub tls_fingerprint: bool,
+
+    /// Connection admission limits applied to every listener.
+    pub limits: ListenerLimits,
+}
+
+/// Slow-loris and flood protections applied to every listener.
+#[derive(Debug, Clone)]
+pub struct ListenerLimits {
+    /// Maximum time to receive the ClientHello.
+    pub sniff_timeout: Duration,
+
+    /// Maximum ClientHello bytes read for SNI inspection.
+    pub max_sniff_bytes: usize,
+
+    /// Maximum time to complete a terminated TLS handshake.
+    pub tls_handshake_timeout: Duration,
+
+    /// Concurrent connections per client; `None` disables the cap.
+    pub max_connections_per_ip: Option<usize>,
+
+    /// Accepts per second under overload; `None` disables the limiter.
+    pub overload_accept_rate: Option<u32>,
+
+    /// Listener utilization at which the accept-rate limiter engages.
+    pub overload_threshold: f64,
+}
+
+impl Default for ListenerLimits {
+    fn default() -> Self {
+        Self {
+            sniff_timeout: DEFAULT_SNIFF_TIMEOUT,
+            max_sniff_bytes: DEFAULT_MAX_SNIFF_BYTES,
+            tls_handshake_timeout: DEFAULT_TLS_HANDSHAKE_TIMEOUT,
+            max_connections_per_ip: Some(DEFAULT_MAX_CONNECTIONS_PER_IP),
+            overload_accept_rate: Some(DEFAULT_OVERLOAD_ACCEPT_RATE),
+            overload_threshold: DEFAULT_OVERLOAD_THRESHOLD,
+        }
+    }
+}
+
+impl ListenerLimits {
+    /// Load limits from `GHOST_*` environment variables. A value of 0
+    /// disables the per-IP cap or accept-rate limiter.
+    fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+
+        let sniff_timeout = env_u64("GHOST_SNIFF_TIMEOUT_MS")?
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.sniff_timeout);
+        let max_sniff_bytes = env_u64("GHOST_MAX_SNIFF_BYTES")?
+            .map(|v| (v as usize).max(512))
+            .unwrap_or(defaults.max_sniff_bytes);
+        let tls_handshake_timeout = env_u64("GHOST_TLS_HANDSHAKE_TIMEOUT_MS")?
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.tls_handshake_timeout);
+        let max_connections_per_ip = match env_u64("GHOST_MAX_CONNECTIONS_PER_IP")? {
+            Some(0) => None,
+            Some(v) => Some(v as usize),
+            None => defaults.max_connections_per_ip,
+        };
+        let overload_accept_rate = match env_u64("GHOST_OVERLOAD_ACCEPT_RATE")? {
+            Some(0) => None,
+            Some(v) => Some(u32::try_from(v).unwrap_or(u32::MAX)),
+            None => defaults.overload_accept_rate,
+        };
+        let overload_threshold = match env_u64("GHOST_OVERLOAD_THRESHOLD_PCT")? {
+            Some(pct) if (1..=99).contains(&pct) => pct as f64 / 100.0,
+            Some(_) => anyhow::bail!("GHOST_OVERLOAD_THRESHOLD_PCT must be between 1 and 99."),
+            None => defaults.overload_threshold,
+        };
+
+        Ok(Self {
+            sniff_timeout,
+            max_sniff_bytes,
+            tls_handshake_timeout,
+            max_connections_per_ip,
+            overload_accept_rate,
+            overload_threshold,
+        })
+    }
+}
+
+/// Read an optional unsigned integer environment variable.
+fn env_u64(name: &str) -> Result<Option<u64>> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.parse())
+        .transpose()
+        .with_context(|| format!("{name} must be a non-negative integer."))
 }
 
 impl Config {
@@ -122,12 +238,26 @@ impl Config {
 
         // Parse listener bindings from GHOST_LISTENERS (comma-separated addr:port)
         // Example: "[::]:443,[::]:80"
-        let listeners = parse_listeners(
+        let mut listeners = parse_listeners(
             std::env::var("GHOST_LISTENERS")
                 .ok()
                 .as_deref()
                 .unwrap_or("[::]:443"),
+            false,
         )?;
+        if listeners.is_empty() {
+            anyhow::bail!("No listeners configured. Set GHOST_LISTENERS (e.g., '[::]:443')");
+        }
+
+        // Internal listeners serve internal routes on the overlay network only.
+        // Example: "[fd00:0:0:1::10]:443"
+        listeners.extend(parse_listeners(
+            std::env::var("GHOST_INTERNAL_LISTENERS")
+                .ok()
+                .as_deref()
+                .unwrap_or(""),
+            true,
+        )?);
 
         // Enable proxy mode by default (set GHOST_PROXY_ENABLED=false for sync-only)
         let proxy_enabled = std::env::var("GHOST_PROXY_ENABLED")
@@ -143,6 +273,52 @@ impl Config {
             .unwrap_or(5000);
         let backend_sync_interval = Duration::from_millis(backend_sync_interval_ms.max(1000));
 
+        // Full backend resync interval (default 5m)
+        let backend_full_resync_interval_ms: u64 =
+            std::env::var("GHOST_BACKEND_FULL_RESYNC_INTERVAL_MS")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()
+                .context(
+                    "GHOST_BACKEND_FULL_RESYNC_INTERVAL_MS must be an integer (milliseconds).",
+                )?
+                .unwrap_or(300_000);
+        let backend_full_resync_interval =
+            Duration::from_millis(backend_full_resync_interval_ms).max(backend_sync_interval);
+
+        // Ingress ID for status reports (default: the host name)
+        let ingress_id = std::env::var("GHOST_INGRESS_ID")
+            .ok()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .context("Missing ingress id. Set GHOST_INGRESS_ID.")?;
+
+        // Public IPs for status reports (default: the specific addresses
+        // public listeners are bound to)
+        let public_ips = match std::env::var("GHOST_PUBLIC_IPS") {
+            Ok(v) => parse_ips(&v)?,
+            Err(_) => default_public_ips(&listeners),
+        };
+
+        // Status report interval (default 30s)
+        let status_report_interval_ms: u64 = std::env::var("GHOST_STATUS_REPORT_INTERVAL_MS")
+            .ok()
+            .map(|v| v.parse())
+            .transpose()
+            .context("GHOST_STATUS_REPORT_INTERVAL_MS must be an integer (milliseconds).")?
+            .unwrap_or(30_000);
+        let status_report_interval = Duration::from_millis(status_report_interval_ms.max(1000));
+
+        // Fingerprints are always computed for routes with fingerprint rules;
+        // GHOST_TLS_FINGERPRINT=true computes and logs them for every route.
+        let tls_fingerprint = std::env::var("GHOST_TLS_FINGERPRINT")
+            .map(|v| v == "1" || v.to_lowercase() == "true")
+            .unwrap_or(false);
+
+        let limits = ListenerLimits::from_env()?;
+
         Ok(Self {
             control_plane_url,
             control_plane_token,
@@ -156,12 +332,43 @@ impl Config {
             listeners,
             proxy_enabled,
             backend_sync_interval,
+            backend_full_resync_interval,
+            ingress_id,
+            public_ips,
+            status_report_interval,
+            tls_fingerprint,
+            limits,
         })
     }
 }
 
+/// Parse a comma-separated list of IP addresses.
+fn parse_ips(s: &str) -> Result<Vec<IpAddr>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse()
+                .with_context(|| format!("Invalid IP address: {}", part))
+        })
+        .collect()
+}
+
+/// Addresses of public listeners bound to a specific IP (wildcard binds say
+/// nothing about the public address).
+fn default_public_ips(listeners: &[ListenerBinding]) -> Vec<IpAddr> {
+    let mut ips: Vec<IpAddr> = listeners
+        .iter()
+        .filter(|l| !l.internal && !l.bind_addr.ip().is_unspecified())
+        .map(|l| l.bind_addr.ip())
+        .collect();
+    ips.sort();
+    ips.dedup();
+    ips
+}
+
 /// Parse listener bindings from a comma-separated string.
-fn parse_listeners(s: &str) -> Result<Vec<ListenerBinding>> {
+fn parse_listeners(s: &str, internal: bool) -> Result<Vec<ListenerBinding>> {
     let mut listeners = Vec::new();
 
     for part in s.split(',') {
@@ -177,12 +384,9 @@ fn parse_listeners(s: &str) -> Result<Vec<ListenerBinding>> {
         listeners.push(ListenerBinding {
             bind_addr,
             max_connections: 10000, // Default max connections
+            internal,
         });
     }
 
-    if listeners.is_empty() {
-        anyhow::bail!("No listeners configured. Set GHOST_LISTENERS (e.g., '[::]:443')");
-    }
-
     Ok(listeners)
 }
@@ -1,8 +1,11 @@
+pub mod config;
 pub mod persistence;
 pub mod proxy;
+pub mod server;
+pub mod sync;
 
 pub use proxy::{
-    Backend, BackendPool, BackendSelector, Listener, ListenerConfig, ProtocolHint, ProxyProtocol,
-    ProxyProtocolV2, Route, RouteTable, RoutingDecision, SharedRouteTable, SniConfig, SniInspector,
-    SniResult,
+    AccessDecision, AccessPolicy, Backend, BackendPool, BackendSelector, CertMaterial, CertStore,
+    Listener, ListenerConfig, ProtocolHint, ProxyProtocol, ProxyProtocolV2, Route, RouteTable,
+    RoutingDecision, SharedRouteTable, SniConfig, SniInspector, SniResult, TlsFingerprint,
 };
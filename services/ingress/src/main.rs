@@ -1,13 +1,8 @@
-use std::sync::Arc;
-
 use anyhow::Result;
-use plfm_ingress::{BackendSelector, Listener, ListenerConfig, RouteTable};
-use tracing::{error, info};
+use plfm_ingress::{config, server};
+use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-mod config;
-mod sync;
-
 #[tokio::main]
 async fn main() -> Result<()> {
     let config = config::Config::from_env()?;
@@ -27,70 +22,5 @@ async fn main() -> Result<()> {
         "Configuration loaded"
     );
 
-    // Create shared state
-    let route_table = Arc::new(RouteTable::new());
-    let backend_selector = Arc::new(BackendSelector::new());
-
-    if config.proxy_enabled {
-        // Start listeners
-        let mut listener_handles = Vec::new();
-
-        for binding in &config.listeners {
-            let mut listener_config = ListenerConfig::new(binding.bind_addr);
-            listener_config.max_connections = binding.max_connections;
-
-            match Listener::bind(
-                listener_config,
-                Arc::clone(&route_table),
-                Arc::clone(&backend_selector),
-            )
-            .await
-            {
-                Ok(listener) => {
-                    info!(
-                        bind_addr = %binding.bind_addr,
-                        "Listener bound"
-                    );
-                    let listener = Arc::new(listener);
-                    let handle = tokio::spawn(async move {
-                        if let Err(e) = listener.run().await {
-                            error!(error = %e, "Listener error");
-                        }
-                    });
-                    listener_handles.push(handle);
-                }
-                Err(e) => {
-                    error!(
-                        bind_addr = %binding.bind_addr,
-                        error = %e,
-                        "Failed to bind listener"
-                    );
-                    return Err(e.into());
-                }
-            }
-        }
-
-        // Start backend sync loop
-        let backend_config = config.clone();
-        let backend_route_table = Arc::clone(&route_table);
-        let backend_selector_clone = Arc::clone(&backend_selector);
-        tokio::spawn(async move {
-            if let Err(e) = sync::run_backend_sync_loop(
-                backend_config,
-                backend_route_table,
-                backend_selector_clone,
-            )
-            .await
-            {
-                error!(error = %e, "Backend sync loop failed");
-            }
-        });
-
-        // Run route sync loop (blocks until error or shutdown)
-        sync::run_route_sync_loop(&config, route_table, backend_selector).await
-    } else {
-        // Sync-only mode (for debugging/testing)
-        info!("Running in sync-only mode (proxy disabled)");
-        sync::run_route_sync_loop(&config, route_table, backend_selector).await
-    }
+    server::run(config).await
 }
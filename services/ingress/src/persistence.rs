@@ -1,6 +1,7 @@
-//! Route state persistence.
+//! Route and backend state persistence.
 //!
-//! This module handles saving and loading route state to disk for:
+//! This module handles saving and loading route state, and the last synced
+//! backend instances, to disk for:
 //! - Atomic config reload (write to temp, rename)
 //! - Fast startup with last known state
 //! - Control plane outage resilience
@@ -8,13 +9,17 @@
 //! Per docs/specs/networking/ingress-l4.md:
 //! - "Control plane outage behavior: edge continues operating on last applied config"
 //! - "config updates must be applied atomically"
+//!
+//! Backends are written to a second file next to the state file (see
+//! [`StatePersistence::backends_path`]) because the route and backend sync
+//! loops save independently.
 
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use plfm_events::{RouteProtocolHint, RouteProxyProtocol};
+use plfm_events::{RouteAccessPolicy, RouteProtocolHint, RouteProxyProtocol};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
@@ -58,6 +63,43 @@ pub struct PersistedRoute {
     pub ipv4_required: bool,
     #[serde(default)]
     pub env_ipv4_address: Option<String>,
+    /// False until hostname verification succeeds. State files written
+    /// before verification existed only hold active routes.
+    #[serde(default = "default_active")]
+    pub active: bool,
+    #[serde(default)]
+    pub internal: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<RouteAccessPolicy>,
+    /// Deleted but restorable until `route.purged`; never served.
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+/// Persisted backend snapshot file format version.
+const BACKENDS_VERSION: u32 = 1;
+
+/// Persisted backend instances.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersistedBackends {
+    /// Format version.
+    pub version: u32,
+    /// Backend sync cursor the instances reflect.
+    pub cursor: i64,
+    /// Routable instances by instance_id.
+    pub instances: BTreeMap<String, PersistedBackendInstance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedBackendInstance {
+    pub instance_id: String,
+    pub env_id: String,
+    pub process_type: String,
+    pub overlay_ipv6: String,
+}
+
+fn default_active() -> bool {
+    true
 }
 
 impl PersistedRoute {
@@ -65,12 +107,14 @@ impl PersistedRoute {
         match p {
             RouteProtocolHint::TlsPassthrough => "tls_passthrough".to_string(),
             RouteProtocolHint::TcpRaw => "tcp_raw".to_string(),
+            RouteProtocolHint::TlsTerminate => "tls_terminate".to_string(),
         }
     }
 
     pub fn protocol_hint_from_string(s: &str) -> RouteProtocolHint {
         match s {
             "tcp_raw" => RouteProtocolHint::TcpRaw,
+            "tls_terminate" => RouteProtocolHint::TlsTerminate,
             _ => RouteProtocolHint::TlsPassthrough,
         }
     }
@@ -140,30 +184,9 @@ impl StatePersistence {
     }
 
     /// Save state to disk atomically.
-    ///
-    /// Uses write-to-temp + rename pattern for atomicity.
     pub fn save(&self, state: &PersistedState) -> Result<()> {
-        // Ensure parent directory exists
-        if let Some(parent) = self.state_path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-        }
-
-        // Write to temp file
-        let tmp_path = self.state_path.with_extension("tmp");
         let content = serde_json::to_string_pretty(state).context("Failed to serialize state")?;
-
-        fs::write(&tmp_path, &content)
-            .with_context(|| format!("Failed to write temp file: {}", tmp_path.display()))?;
-
-        // Atomic rename
-        fs::rename(&tmp_path, &self.state_path).with_context(|| {
-            format!(
-                "Failed to rename {} -> {}",
-                tmp_path.display(),
-                self.state_path.display()
-            )
-        })?;
+        write_atomic(&self.state_path, &content)?;
 
         debug!(
             path = %self.state_path.display(),
@@ -188,6 +211,95 @@ impl StatePersistence {
         };
         self.save(&state)
     }
+
+    /// Path of the backend snapshot, next to the state file
+    /// (`state.json` -> `state.backends.json`).
+    pub fn backends_path(&self) -> PathBuf {
+        self.state_path.with_extension("backends.json")
+    }
+
+    /// Load the last saved backend instances.
+    ///
+    /// Returns `None` if there is no usable snapshot.
+    pub fn load_backends(&self) -> Result<Option<PersistedBackends>> {
+        let path = self.backends_path();
+        if !path.exists() {
+            debug!(path = %path.display(), "No backend snapshot");
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read backend snapshot: {}", path.display()))?;
+        let backends: PersistedBackends = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse backend snapshot: {}", path.display()))?;
+
+        if backends.version != BACKENDS_VERSION {
+            warn!(
+                file_version = backends.version,
+                current_version = BACKENDS_VERSION,
+                "Backend snapshot version mismatch, ignoring"
+            );
+            return Ok(None);
+        }
+
+        info!(
+            path = %path.display(),
+            cursor = backends.cursor,
+            instance_count = backends.instances.len(),
+            "Loaded backend snapshot from disk"
+        );
+
+        Ok(Some(backends))
+    }
+
+    /// Save backend instances atomically.
+    pub fn save_backends(
+        &self,
+        instances: &BTreeMap<String, PersistedBackendInstance>,
+        cursor: i64,
+    ) -> Result<()> {
+        let backends = PersistedBackends {
+            version: BACKENDS_VERSION,
+            cursor,
+            instances: instances.clone(),
+        };
+        let content = serde_json::to_string_pretty(&backends)
+            .context("Failed to serialize backend snapshot")?;
+        let path = self.backends_path();
+        write_atomic(&path, &content)?;
+
+        debug!(
+            path = %path.display(),
+            cursor,
+            instance_count = instances.len(),
+            "Saved backend snapshot to disk"
+        );
+
+        Ok(())
+    }
+}
+
+/// Write `content` to `path` atomically (write to temp + rename).
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    // Ensure parent directory exists
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    // Write to temp file
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)
+        .with_context(|| format!("Failed to write temp file: {}", tmp_path.display()))?;
+
+    // Atomic rename
+    fs::rename(&tmp_path, path).with_context(|| {
+        format!(
+            "Failed to rename {} -> {}",
+            tmp_path.display(),
+            path.display()
+        )
+    })
 }
 
 // PersistenceConfig is not currently used - config is handled in Config::from_env()
@@ -215,6 +327,10 @@ mod tests {
                 backend_expects_proxy_protocol: false,
                 ipv4_required: false,
                 env_ipv4_address: None,
+                internal: false,
+                access_policy: None,
+                active: true,
+                deleted: false,
             },
         );
 
@@ -259,6 +375,10 @@ mod tests {
                 backend_expects_proxy_protocol: true,
                 ipv4_required: false,
                 env_ipv4_address: None,
+                internal: false,
+                access_policy: None,
+                active: true,
+                deleted: false,
             },
         );
 
@@ -277,6 +397,33 @@ mod tests {
         let _ = fs::remove_file(&tmp);
     }
 
+    #[test]
+    fn test_backend_snapshot_roundtrip() {
+        let tmp = temp_dir().join(format!("ingress-backends-test-{}.json", std::process::id()));
+        let persistence = StatePersistence::new(tmp.clone());
+        assert!(persistence.load_backends().unwrap().is_none());
+
+        let mut instances = BTreeMap::new();
+        instances.insert(
+            "inst_1".to_string(),
+            PersistedBackendInstance {
+                instance_id: "inst_1".to_string(),
+                env_id: "env_1".to_string(),
+                process_type: "web".to_string(),
+                overlay_ipv6: "fd00::1".to_string(),
+            },
+        );
+        persistence.save_backends(&instances, 77).unwrap();
+
+        // Routes and backends live in separate files.
+        assert!(!tmp.exists());
+        let loaded = persistence.load_backends().unwrap().unwrap();
+        assert_eq!(loaded.cursor, 77);
+        assert_eq!(loaded.instances["inst_1"].overlay_ipv6, "fd00::1");
+
+        let _ = fs::remove_file(persistence.backends_path());
+    }
+
     #[test]
     fn test_proxy_protocol_conversion() {
         assert_eq!(
@@ -321,6 +468,12 @@ mod tests {
             PersistedRoute::protocol_hint_from_string("tcp_raw"),
             RouteProtocolHint::TcpRaw
         );
+        assert_eq!(
+            PersistedRoute::protocol_hint_from_string(&PersistedRoute::protocol_hint_to_string(
+                RouteProtocolHint::TlsTerminate
+            )),
+            RouteProtocolHint::TlsTerminate
+        );
         assert_eq!(
             PersistedRoute::protocol_hint_from_string("invalid"),
             RouteProtocolHint::TlsPassthrough
@@ -0,0 +1,247 @@
+//! Per-route client access policies.
+//!
+//! A route may carry allow/deny lists of client CIDRs and TLS client
+//! fingerprints (JA3 hashes or JA4 strings), synced from the control plane.
+//! They are enforced per connection before a backend is picked:
+//! - a deny entry matching the client refuses the connection
+//! - a non-empty allow list admits only clients matching one of its entries
+//!
+//! CIDR and fingerprint lists are evaluated independently; a client must
+//! pass both.
+//!
+//! Reference: docs/specs/networking/ingress-l4.md (Client access policies)
+
+use std::net::IpAddr;
+
+use plfm_events::RouteAccessPolicy;
+use tracing::warn;
+
+use super::fingerprint::TlsFingerprint;
+
+/// Outcome of checking a client against a route's policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessDecision {
+    Allow,
+    Deny { reason: &'static str },
+}
+
+/// A route's access policy, compiled for matching.
+#[derive(Debug, Clone, Default)]
+pub struct AccessPolicy {
+    allow_cidrs: Vec<Cidr>,
+    deny_cidrs: Vec<Cidr>,
+    allow_fingerprints: Vec<String>,
+    deny_fingerprints: Vec<String>,
+}
+
+impl AccessPolicy {
+    /// Compile a synced policy. Entries that fail to parse are skipped with a
+    /// warning (the control plane validates them on write).
+    pub fn from_policy(policy: &RouteAccessPolicy) -> Self {
+        let lowercase = |entries: &[String]| -> Vec<String> {
+            entries.iter().map(|e| e.to_ascii_lowercase()).collect()
+        };
+        Self {
+            allow_cidrs: parse_cidrs(&policy.allow_cidrs),
+            deny_cidrs: parse_cidrs(&policy.deny_cidrs),
+            allow_fingerprints: lowercase(&policy.allow_fingerprints),
+            deny_fingerprints: lowercase(&policy.deny_fingerprints),
+        }
+    }
+
+    /// Whether the policy admits everyone.
+    pub fn is_empty(&self) -> bool {
+        self.allow_cidrs.is_empty() && self.deny_cidrs.is_empty() && !self.needs_fingerprint()
+    }
+
+    /// Whether checking the policy requires the client's TLS fingerprint.
+    pub fn needs_fingerprint(&self) -> bool {
+        !self.allow_fingerprints.is_empty() || !self.deny_fingerprints.is_empty()
+    }
+
+    /// Check a client. `fingerprint` is `None` when the connection carried no
+    /// parseable ClientHello; such clients never match an allow list.
+    pub fn check(&self, client: IpAddr, fingerprint: Option<&TlsFingerprint>) -> AccessDecision {
+        let client = client.to_canonical();
+
+        if self.deny_cidrs.iter().any(|c| c.contains(client)) {
+            return AccessDecision::Deny {
+                reason: "client address denied",
+            };
+        }
+        if !self.allow_cidrs.is_empty() && !self.allow_cidrs.iter().any(|c| c.contains(client)) {
+            return AccessDecision::Deny {
+                reason: "client address not allowed",
+            };
+        }
+
+        let matches = |entries: &[String]| {
+            fingerprint.is_some_and(|fp| entries.iter().any(|e| fp.matches(e)))
+        };
+        if matches(&self.deny_fingerprints) {
+            return AccessDecision::Deny {
+                reason: "TLS fingerprint denied",
+            };
+        }
+        if !self.allow_fingerprints.is_empty() && !matches(&self.allow_fingerprints) {
+            return AccessDecision::Deny {
+                reason: "TLS fingerprint not allowed",
+            };
+        }
+
+        AccessDecision::Allow
+    }
+}
+
+fn parse_cidrs(entries: &[String]) -> Vec<Cidr> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let cidr = Cidr::parse(entry);
+            if cidr.is_none() {
+                warn!(entry = %entry, "Ignoring invalid CIDR in route access policy");
+            }
+            cidr
+        })
+        .collect()
+}
+
+/// An address prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// `addr/prefix`, or a bare address (a single host).
+    fn parse(entry: &str) -> Option<Self> {
+        let (addr, prefix) = match entry.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (entry.trim(), None),
+        };
+        let addr: IpAddr = addr.parse().ok()?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p.parse().ok().filter(|&p| p <= max_prefix)?,
+            None => max_prefix,
+        };
+        Some(Self {
+            addr: addr.to_canonical(),
+            prefix,
+        })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_eq(
+                u32::from(net) as u128,
+                u32::from(ip) as u128,
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_eq(u128::from(net), u128::from(ip), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether the top `prefix` of `bits` bits of `a` and `b` are equal.
+fn prefix_eq(a: u128, b: u128, bits: u8, prefix: u8) -> bool {
+    if prefix == 0 {
+        return true;
+    }
+    let shift = u32::from(bits - prefix);
+    (a >> shift) == (b >> shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(f: impl FnOnce(&mut RouteAccessPolicy)) -> AccessPolicy {
+        let mut p = RouteAccessPolicy::default();
+        f(&mut p);
+        AccessPolicy::from_policy(&p)
+    }
+
+    fn fingerprint() -> TlsFingerprint {
+        TlsFingerprint {
+            ja3: "e7d705a3286e19ea42f587b344ee6865".to_string(),
+            ja4: "t13d1516h2_8daaf6152771_e5627efa2ab1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let v4 = Cidr::parse("10.1.0.0/16").unwrap();
+        assert!(v4.contains("10.1.2.3".parse().unwrap()));
+        assert!(!v4.contains("10.2.0.1".parse().unwrap()));
+
+        let v6 = Cidr::parse("2001:db8::/32").unwrap();
+        assert!(v6.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!v6.contains("10.1.2.3".parse().unwrap()));
+
+        let any = Cidr::parse("0.0.0.0/0").unwrap();
+        assert!(any.contains("198.51.100.1".parse().unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+    }
+
+    #[test]
+    fn test_empty_policy_allows() {
+        let p = AccessPolicy::default();
+        assert!(p.is_empty());
+        assert_eq!(
+            p.check("203.0.113.7".parse().unwrap(), None),
+            AccessDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_cidr_rules() {
+        let p = policy(|p| {
+            p.allow_cidrs = vec!["203.0.113.0/24".to_string()];
+            p.deny_cidrs = vec!["203.0.113.66".to_string()];
+        });
+        assert_eq!(
+            p.check("203.0.113.7".parse().unwrap(), None),
+            AccessDecision::Allow
+        );
+        assert!(matches!(
+            p.check("203.0.113.66".parse().unwrap(), None),
+            AccessDecision::Deny { .. }
+        ));
+        assert!(matches!(
+            p.check("198.51.100.1".parse().unwrap(), None),
+            AccessDecision::Deny { .. }
+        ));
+        // IPv4-mapped IPv6 peers (dual-stack listeners) match IPv4 rules.
+        assert_eq!(
+            p.check("::ffff:203.0.113.7".parse().unwrap(), None),
+            AccessDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_rules() {
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let fp = fingerprint();
+
+        let deny = policy(|p| p.deny_fingerprints = vec![fp.ja3.to_uppercase()]);
+        assert!(deny.needs_fingerprint());
+        assert!(matches!(
+            deny.check(client, Some(&fp)),
+            AccessDecision::Deny { .. }
+        ));
+        assert_eq!(deny.check(client, None), AccessDecision::Allow);
+
+        let allow = policy(|p| p.allow_fingerprints = vec![fp.ja4.clone()]);
+        assert_eq!(allow.check(client, Some(&fp)), AccessDecision::Allow);
+        assert!(matches!(
+            allow.check(client, None),
+            AccessDecision::Deny { .. }
+        ));
+    }
+}
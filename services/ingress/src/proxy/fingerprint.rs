@@ -0,0 +1,370 @@
+//! TLS client fingerprints (JA3 and JA4) from a buffered ClientHello.
+//!
+//! Fingerprints summarize how a client speaks TLS (version, cipher suites,
+//! extensions, groups, ALPN, signature algorithms), which tends to be stable
+//! per client implementation. Route access policies use them to refuse or
+//! admit known tools without terminating TLS.
+//!
+//! GREASE values (RFC 8701) are ignored throughout.
+//!
+//! Reference: docs/specs/networking/ingress-l4.md (Client access policies)
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+/// Fingerprints of one ClientHello.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsFingerprint {
+    /// JA3 hash: MD5 of the JA3 string, 32 hex digits.
+    pub ja3: String,
+    /// JA4 fingerprint, e.g. `t13d1516h2_8daaf6152771_e5627efa2ab1`.
+    pub ja4: String,
+}
+
+impl TlsFingerprint {
+    /// Compute fingerprints from the bytes read by SNI inspection (starting
+    /// at the TLS record header). `None` if they are not a parseable
+    /// ClientHello.
+    pub fn from_client_hello(data: &[u8]) -> Option<Self> {
+        let hello = ClientHello::parse(data)?;
+        Some(Self {
+            ja3: hex(&Md5::digest(hello.ja3_string().as_bytes())),
+            ja4: hello.ja4(),
+        })
+    }
+
+    /// Whether `entry` (a JA3 hash or JA4 string, lowercase) names this client.
+    pub fn matches(&self, entry: &str) -> bool {
+        self.ja3 == entry || self.ja4 == entry
+    }
+}
+
+/// The ClientHello fields fingerprints are built from.
+#[derive(Debug, Default)]
+struct ClientHello {
+    legacy_version: u16,
+    ciphers: Vec<u16>,
+    /// Extension types in the order sent.
+    extensions: Vec<u16>,
+    groups: Vec<u16>,
+    point_formats: Vec<u8>,
+    signature_algorithms: Vec<u16>,
+    supported_versions: Vec<u16>,
+    /// First ALPN protocol offered.
+    alpn: Option<Vec<u8>>,
+    has_sni: bool,
+}
+
+const EXT_SNI: u16 = 0x0000;
+const EXT_SUPPORTED_GROUPS: u16 = 0x000a;
+const EXT_EC_POINT_FORMATS: u16 = 0x000b;
+const EXT_SIGNATURE_ALGORITHMS: u16 = 0x000d;
+const EXT_ALPN: u16 = 0x0010;
+const EXT_SUPPORTED_VERSIONS: u16 = 0x002b;
+
+fn is_grease(v: u16) -> bool {
+    (v & 0x0f0f) == 0x0a0a && (v >> 8) == (v & 0xff)
+}
+
+/// Bounds-checked big-endian reader.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        let v = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(v)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.bytes(2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(slice)
+    }
+
+    /// A length-prefixed block (1- or 2-byte length).
+    fn block8(&mut self) -> Option<Reader<'a>> {
+        let len = self.u8()? as usize;
+        Some(Reader::new(self.bytes(len)?))
+    }
+
+    fn block16(&mut self) -> Option<Reader<'a>> {
+        let len = self.u16()? as usize;
+        Some(Reader::new(self.bytes(len)?))
+    }
+
+    fn u16_list(mut self) -> Option<Vec<u16>> {
+        let mut out = Vec::new();
+        while !self.is_empty() {
+            out.push(self.u16()?);
+        }
+        Some(out)
+    }
+}
+
+impl ClientHello {
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut record = Reader::new(data);
+        if record.u8()? != 0x16 {
+            return None;
+        }
+        record.bytes(4)?; // version, length
+        if record.u8()? != 0x01 {
+            return None;
+        }
+        record.bytes(3)?; // handshake length
+
+        let mut hello = ClientHello {
+            legacy_version: record.u16()?,
+            ..Default::default()
+        };
+        record.bytes(32)?; // random
+        record.block8()?; // session id
+        hello.ciphers = record.block16()?.u16_list()?;
+        record.block8()?; // compression methods
+
+        // Extensions are optional in a ClientHello.
+        let Some(mut extensions) = record.block16() else {
+            return Some(hello);
+        };
+        while !extensions.is_empty() {
+            let ext_type = extensions.u16()?;
+            let mut body = extensions.block16()?;
+            hello.extensions.push(ext_type);
+            match ext_type {
+                EXT_SNI => hello.has_sni = true,
+                EXT_SUPPORTED_GROUPS => hello.groups = body.block16()?.u16_list()?,
+                EXT_EC_POINT_FORMATS => hello.point_formats = body.block8()?.data.to_vec(),
+                EXT_SIGNATURE_ALGORITHMS => {
+                    hello.signature_algorithms = body.block16()?.u16_list()?
+                }
+                EXT_SUPPORTED_VERSIONS => hello.supported_versions = body.block8()?.u16_list()?,
+                EXT_ALPN => {
+                    let mut list = body.block16()?;
+                    hello.alpn = list.block8().map(|p| p.data.to_vec());
+                }
+                _ => {}
+            }
+        }
+        Some(hello)
+    }
+
+    /// `version,ciphers,extensions,groups,point_formats`, decimal values
+    /// joined with `-`.
+    fn ja3_string(&self) -> String {
+        fn join<T: Copy + Into<u16>>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(|&v| -> u16 { v.into() })
+                .filter(|&v| !is_grease(v))
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join("-")
+        }
+        format!(
+            "{},{},{},{},{}",
+            self.legacy_version,
+            join(&self.ciphers),
+            join(&self.extensions),
+            join(&self.groups),
+            join(&self.point_formats),
+        )
+    }
+
+    fn ja4(&self) -> String {
+        let ciphers: Vec<u16> = self
+            .ciphers
+            .iter()
+            .copied()
+            .filter(|&v| !is_grease(v))
+            .collect();
+        let extensions: Vec<u16> = self
+            .extensions
+            .iter()
+            .copied()
+            .filter(|&v| !is_grease(v))
+            .collect();
+
+        let version = self
+            .supported_versions
+            .iter()
+            .copied()
+            .filter(|&v| !is_grease(v))
+            .max()
+            .unwrap_or(self.legacy_version);
+        let version = match version {
+            0x0304 => "13",
+            0x0303 => "12",
+            0x0302 => "11",
+            0x0301 => "10",
+            0x0300 => "s3",
+            _ => "00",
+        };
+
+        let a = format!(
+            "t{}{}{:02}{:02}{}",
+            version,
+            if self.has_sni { 'd' } else { 'i' },
+            ciphers.len().min(99),
+            extensions.len().min(99),
+            alpn_code(self.alpn.as_deref()),
+        );
+
+        let mut sorted_ciphers = ciphers;
+        sorted_ciphers.sort_unstable();
+        let b = truncated_hash(&hex_list(&sorted_ciphers));
+
+        let mut sorted_extensions: Vec<u16> = extensions
+            .into_iter()
+            .filter(|&v| v != EXT_SNI && v != EXT_ALPN)
+            .collect();
+        sorted_extensions.sort_unstable();
+        let mut c_input = hex_list(&sorted_extensions);
+        if !self.signature_algorithms.is_empty() {
+            c_input.push('_');
+            c_input.push_str(&hex_list(&self.signature_algorithms));
+        }
+        let c = truncated_hash(&c_input);
+
+        format!("{a}_{b}_{c}")
+    }
+}
+
+/// First and last character of the first ALPN value; `00` without ALPN.
+/// Non-alphanumeric values use the first and last hex digit instead.
+fn alpn_code(alpn: Option<&[u8]>) -> String {
+    let Some(alpn) = alpn.filter(|a| !a.is_empty()) else {
+        return "00".to_string();
+    };
+    let first = alpn[0];
+    let last = alpn[alpn.len() - 1];
+    if first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() {
+        format!("{}{}", first as char, last as char)
+    } else {
+        let h = hex(alpn);
+        format!("{}{}", &h[..1], &h[h.len() - 1..])
+    }
+}
+
+/// Comma-separated 4-digit lowercase hex values.
+fn hex_list(values: &[u16]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{v:04x}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// First 12 hex digits of SHA-256, or zeros for an empty input.
+fn truncated_hash(input: &str) -> String {
+    if input.is_empty() {
+        return "000000000000".to_string();
+    }
+    hex(&Sha256::digest(input.as_bytes()))[..12].to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A TLS 1.3 ClientHello with GREASE, SNI "example.com", ALPN "h2".
+    fn client_hello() -> Vec<u8> {
+        let mut ext = Vec::new();
+        let mut push_ext = |ty: u16, body: &[u8]| {
+            ext.extend_from_slice(&ty.to_be_bytes());
+            ext.extend_from_slice(&(body.len() as u16).to_be_bytes());
+            ext.extend_from_slice(body);
+        };
+        push_ext(0x0a0a, &[]); // GREASE
+        push_ext(
+            EXT_SNI,
+            &[
+                0x00, 0x0e, 0x00, 0x00, 0x0b, b'e', b'x', b'a', b'm', b'p', b'l', b'e', b'.', b'c',
+                b'o', b'm',
+            ],
+        );
+        push_ext(
+            EXT_SUPPORTED_GROUPS,
+            &[0x00, 0x06, 0x2a, 0x2a, 0x00, 0x1d, 0x00, 0x17],
+        );
+        push_ext(EXT_EC_POINT_FORMATS, &[0x01, 0x00]);
+        push_ext(
+            EXT_SIGNATURE_ALGORITHMS,
+            &[0x00, 0x04, 0x04, 0x03, 0x08, 0x04],
+        );
+        push_ext(EXT_ALPN, &[0x00, 0x03, 0x02, b'h', b'2']);
+        push_ext(EXT_SUPPORTED_VERSIONS, &[0x04, 0x03, 0x04, 0x03, 0x03]);
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0x03, 0x03]); // legacy version
+        body.extend_from_slice(&[0u8; 32]); // random
+        body.push(0); // session id
+        body.extend_from_slice(&[0x00, 0x06, 0x1a, 0x1a, 0x13, 0x01, 0xc0, 0x2f]);
+        body.extend_from_slice(&[0x01, 0x00]); // compression
+        body.extend_from_slice(&(ext.len() as u16).to_be_bytes());
+        body.extend_from_slice(&ext);
+
+        let mut handshake = vec![0x01];
+        handshake.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        handshake.extend_from_slice(&body);
+
+        let mut record = vec![0x16, 0x03, 0x01];
+        record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+        record.extend_from_slice(&handshake);
+        record
+    }
+
+    #[test]
+    fn test_ja3_string_skips_grease() {
+        let hello = ClientHello::parse(&client_hello()).unwrap();
+        assert_eq!(
+            hello.ja3_string(),
+            "771,4865-49199,0-10-11-13-16-43,29-23,0"
+        );
+    }
+
+    #[test]
+    fn test_ja4_shape() {
+        let fp = TlsFingerprint::from_client_hello(&client_hello()).unwrap();
+        let parts: Vec<&str> = fp.ja4.split('_').collect();
+        // TLS 1.3 (from supported_versions), SNI, 2 ciphers, 6 extensions, h2.
+        assert_eq!(parts[0], "t13d0206h2");
+        assert_eq!(parts[1].len(), 12);
+        assert_eq!(parts[2].len(), 12);
+        assert_eq!(fp.ja3.len(), 32);
+        assert!(fp.matches(&fp.ja4.clone()));
+    }
+
+    #[test]
+    fn test_rejects_non_client_hello() {
+        assert!(TlsFingerprint::from_client_hello(b"GET / HTTP/1.1\r\n").is_none());
+        assert!(TlsFingerprint::from_client_hello(&client_hello()[..20]).is_none());
+    }
+
+    #[test]
+    fn test_alpn_code() {
+        assert_eq!(alpn_code(None), "00");
+        assert_eq!(alpn_code(Some(b"http/1.1")), "h1");
+        assert_eq!(alpn_code(Some(&[0xab, 0x01])), "a1");
+    }
+}
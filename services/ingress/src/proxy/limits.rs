@@ -0,0 +1,219 @@
+//! Connection admission limits (slow-loris and flood protection).
+//!
+//! Applied in the accept loop, before a connection task is spawned:
+//! - [`PerIpLimiter`]: caps concurrent connections per client (IPv4 address
+//!   or IPv6 /64, since a single IPv6 client typically controls a whole /64)
+//! - [`AcceptRateLimiter`]: a token bucket that only engages once the
+//!   listener is near its connection limit, and tightens as it fills
+//!
+//! Reference: docs/specs/networking/ingress-l4.md (Connection admission)
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Default concurrent connections allowed per client.
+pub const DEFAULT_MAX_CONNECTIONS_PER_IP: usize = 256;
+
+/// Default accepts per second once the listener is overloaded.
+pub const DEFAULT_OVERLOAD_ACCEPT_RATE: u32 = 1000;
+
+/// Default utilization (active / max connections) at which the accept-rate
+/// limiter engages.
+pub const DEFAULT_OVERLOAD_THRESHOLD: f64 = 0.8;
+
+/// Client key for per-IP accounting.
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(v6) => {
+            let prefix = u128::from(v6) & !((1u128 << 64) - 1);
+            IpAddr::V6(prefix.into())
+        }
+        v4 => v4,
+    }
+}
+
+/// Concurrent connection counts per client.
+#[derive(Debug)]
+pub struct PerIpLimiter {
+    max_per_ip: usize,
+    counts: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl PerIpLimiter {
+    pub fn new(max_per_ip: usize) -> Arc<Self> {
+        Arc::new(Self {
+            max_per_ip,
+            counts: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Reserve a slot for `ip`, or `None` if the client is at its cap. The
+    /// slot is released when the guard is dropped.
+    pub fn try_acquire(self: &Arc<Self>, ip: IpAddr) -> Option<PerIpGuard> {
+        let key = client_key(ip);
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counts.entry(key).or_insert(0);
+        if *count >= self.max_per_ip {
+            return None;
+        }
+        *count += 1;
+        Some(PerIpGuard {
+            limiter: Arc::clone(self),
+            key,
+        })
+    }
+
+    /// Number of clients with open connections.
+    pub fn tracked_clients(&self) -> usize {
+        self.counts.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn release(&self, key: IpAddr) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = counts.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&key);
+            }
+        }
+    }
+}
+
+/// A reserved per-client connection slot.
+#[derive(Debug)]
+pub struct PerIpGuard {
+    limiter: Arc<PerIpLimiter>,
+    key: IpAddr,
+}
+
+impl Drop for PerIpGuard {
+    fn drop(&mut self) {
+        self.limiter.release(self.key);
+    }
+}
+
+/// Token-bucket accept limiter that engages under overload.
+///
+/// Below `threshold` utilization every connection is admitted. Above it,
+/// accepts are limited to `rate` per second (burst of one second's worth),
+/// scaled down linearly with the remaining headroom so the listener sheds
+/// new connections harder the closer it is to full.
+#[derive(Debug)]
+pub struct AcceptRateLimiter {
+    rate: f64,
+    threshold: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl AcceptRateLimiter {
+    pub fn new(rate: u32, threshold: f64) -> Self {
+        Self {
+            rate: f64::from(rate),
+            threshold: threshold.clamp(0.0, 0.99),
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(rate),
+                last: Instant::now(),
+            }),
+        }
+    }
+
+    /// Whether to admit a connection at the given utilization (0.0-1.0).
+    pub fn try_accept(&self, utilization: f64, now: Instant) -> bool {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+
+        let headroom = ((1.0 - utilization) / (1.0 - self.threshold)).clamp(0.1, 1.0);
+        let rate = self.rate * headroom;
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(self.rate);
+        bucket.last = now;
+
+        if utilization < self.threshold {
+            return true;
+        }
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_per_ip_cap_and_release() {
+        let limiter = PerIpLimiter::new(2);
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+
+        let a = limiter.try_acquire(ip).unwrap();
+        let _b = limiter.try_acquire(ip).unwrap();
+        assert!(limiter.try_acquire(ip).is_none());
+        assert!(limiter
+            .try_acquire("203.0.113.8".parse().unwrap())
+            .is_some());
+
+        drop(a);
+        assert!(limiter.try_acquire(ip).is_some());
+    }
+
+    #[test]
+    fn test_per_ip_groups_ipv6_by_64() {
+        let limiter = PerIpLimiter::new(1);
+        let _a = limiter
+            .try_acquire("2001:db8:1:2::1".parse().unwrap())
+            .unwrap();
+        assert!(limiter
+            .try_acquire("2001:db8:1:2::ffff".parse().unwrap())
+            .is_none());
+        assert!(limiter
+            .try_acquire("2001:db8:1:3::1".parse().unwrap())
+            .is_some());
+        // IPv4-mapped peers count against the IPv4 address.
+        let _v4 = limiter
+            .try_acquire("198.51.100.1".parse().unwrap())
+            .unwrap();
+        assert!(limiter
+            .try_acquire("::ffff:198.51.100.1".parse().unwrap())
+            .is_none());
+    }
+
+    #[test]
+    fn test_per_ip_forgets_idle_clients() {
+        let limiter = PerIpLimiter::new(4);
+        let guard = limiter.try_acquire("203.0.113.7".parse().unwrap());
+        assert_eq!(limiter.tracked_clients(), 1);
+        drop(guard);
+        assert_eq!(limiter.tracked_clients(), 0);
+    }
+
+    #[test]
+    fn test_accept_rate_only_under_overload() {
+        let limiter = AcceptRateLimiter::new(2, 0.8);
+        let now = Instant::now();
+
+        // Below the threshold, everything is admitted.
+        for _ in 0..10 {
+            assert!(limiter.try_accept(0.5, now));
+        }
+
+        // Over the threshold, the burst drains and further accepts fail
+        // until tokens refill.
+        assert!(limiter.try_accept(0.85, now));
+        assert!(limiter.try_accept(0.85, now));
+        assert!(!limiter.try_accept(0.85, now));
+        assert!(limiter.try_accept(0.85, now + Duration::from_secs(1)));
+    }
+}
@@ -5,7 +5,8 @@
 //!
 //! Per spec (docs/specs/networking/ingress-l4.md):
 //! - TCP proxying at Layer 4
-//! - SNI inspection for TLS passthrough routes
+//! - SNI inspection for TLS passthrough and termination routes
+//! - TLS termination with uploaded certificates
 //! - PROXY v2 header injection when enabled
 //! - Connection-level routing (not request-level)
 //!
@@ -16,15 +17,23 @@ use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::Semaphore;
 use tracing::{debug, error, info, warn, Instrument};
 
+use super::access::AccessDecision;
 use super::backend::BackendSelector;
+use super::fingerprint::TlsFingerprint;
+use super::limits::{
+    AcceptRateLimiter, PerIpLimiter, DEFAULT_MAX_CONNECTIONS_PER_IP, DEFAULT_OVERLOAD_ACCEPT_RATE,
+    DEFAULT_OVERLOAD_THRESHOLD,
+};
 use super::proxy_protocol::ProxyProtocolV2;
+use super::router::Route;
 use super::router::{ProtocolHint, ProxyProtocol, RouteTable, RoutingDecision};
 use super::sni::{SniConfig, SniInspector, SniResult};
+use super::tls::{CertStore, PrefixedStream};
 
 /// Default maximum concurrent connections per listener.
 pub const DEFAULT_MAX_CONNECTIONS: usize = 10000;
@@ -32,6 +41,9 @@ pub const DEFAULT_MAX_CONNECTIONS: usize = 10000;
 /// Default idle timeout (none for raw TCP per spec).
 pub const DEFAULT_IDLE_TIMEOUT: Option<Duration> = None;
 
+/// Default maximum time for a client to complete a terminated TLS handshake.
+pub const DEFAULT_TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Configuration for a listener.
 #[derive(Debug, Clone)]
 pub struct ListenerConfig {
@@ -43,6 +55,19 @@ pub struct ListenerConfig {
     pub sni_config: SniConfig,
     /// Idle timeout for connections.
     pub idle_timeout: Option<Duration>,
+    /// Serve internal routes (overlay-only) instead of public routes.
+    pub internal: bool,
+    /// Maximum time for a client to complete a terminated TLS handshake.
+    pub tls_handshake_timeout: Duration,
+    /// Concurrent connections allowed per client (IPv4 address or IPv6
+    /// /64); `None` disables the cap.
+    pub max_connections_per_ip: Option<usize>,
+    /// Accepts per second once the listener is overloaded; `None` disables
+    /// the limiter.
+    pub overload_accept_rate: Option<u32>,
+    /// Utilization (active / max connections) at which the accept-rate
+    /// limiter engages.
+    pub overload_threshold: f64,
 }
 
 impl ListenerConfig {
@@ -53,6 +78,11 @@ impl ListenerConfig {
             max_connections: DEFAULT_MAX_CONNECTIONS,
             sni_config: SniConfig::default(),
             idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            internal: false,
+            tls_handshake_timeout: DEFAULT_TLS_HANDSHAKE_TIMEOUT,
+            max_connections_per_ip: Some(DEFAULT_MAX_CONNECTIONS_PER_IP),
+            overload_accept_rate: Some(DEFAULT_OVERLOAD_ACCEPT_RATE),
+            overload_threshold: DEFAULT_OVERLOAD_THRESHOLD,
         }
     }
 }
@@ -68,10 +98,18 @@ pub struct ListenerStats {
     pub connections_closed: AtomicU64,
     /// Connections rejected due to max limit.
     pub connections_rejected: AtomicU64,
+    /// Connections rejected because the client was at its per-IP cap.
+    pub rejected_per_ip: AtomicU64,
+    /// Connections rejected by the accept-rate limiter under overload.
+    pub rejected_rate_limited: AtomicU64,
     /// SNI extraction successes.
     pub sni_found: AtomicU64,
     /// SNI extraction failures (timeout, not TLS, etc.).
     pub sni_failed: AtomicU64,
+    /// Clients that did not send a ClientHello within the sniff timeout.
+    pub client_hello_timeouts: AtomicU64,
+    /// ClientHellos larger than the sniff byte limit.
+    pub client_hello_too_large: AtomicU64,
     /// Routing successes.
     pub routes_matched: AtomicU64,
     /// Routing failures (no match, ambiguous).
@@ -80,6 +118,15 @@ pub struct ListenerStats {
     pub backend_connected: AtomicU64,
     /// Backend connection failures.
     pub backend_failed: AtomicU64,
+    /// TLS handshakes completed for terminated routes.
+    pub tls_terminated: AtomicU64,
+    /// TLS handshakes that failed or had no certificate to serve.
+    pub tls_handshake_failed: AtomicU64,
+    /// Terminated TLS handshakes that did not complete in time (also
+    /// counted in `tls_handshake_failed`).
+    pub tls_handshake_timeouts: AtomicU64,
+    /// Connections refused by a route's access policy.
+    pub access_denied: AtomicU64,
     /// Bytes proxied to backend.
     pub bytes_to_backend: AtomicU64,
     /// Bytes proxied from backend.
@@ -98,8 +145,14 @@ pub struct Listener {
     backend_selector: Arc<BackendSelector>,
     /// Connection semaphore for limiting concurrent connections.
     conn_semaphore: Arc<Semaphore>,
+    /// Per-client concurrent connection caps.
+    per_ip: Option<Arc<PerIpLimiter>>,
+    /// Accept-rate limiter engaged under overload.
+    accept_limiter: Option<AcceptRateLimiter>,
     /// SNI inspector.
     sni_inspector: SniInspector,
+    /// Certificates for terminated routes.
+    cert_store: Arc<CertStore>,
     /// Statistics.
     stats: Arc<ListenerStats>,
 }
@@ -117,12 +170,18 @@ impl Listener {
         info!(
             bind_addr = %local_addr,
             max_connections = config.max_connections,
+            internal = config.internal,
             "Listener bound"
         );
 
         Ok(Self {
             conn_semaphore: Arc::new(Semaphore::new(config.max_connections)),
+            per_ip: config.max_connections_per_ip.map(PerIpLimiter::new),
+            accept_limiter: config
+                .overload_accept_rate
+                .map(|rate| AcceptRateLimiter::new(rate, config.overload_threshold)),
             sni_inspector: SniInspector::with_config(config.sni_config.clone()),
+            cert_store: Arc::new(CertStore::new()),
             listener,
             config,
             route_table,
@@ -131,6 +190,12 @@ impl Listener {
         })
     }
 
+    /// Use a shared certificate store for terminated routes.
+    pub fn with_cert_store(mut self, cert_store: Arc<CertStore>) -> Self {
+        self.cert_store = cert_store;
+        self
+    }
+
     /// Get the local address this listener is bound to.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         self.listener.local_addr()
@@ -149,6 +214,20 @@ impl Listener {
         loop {
             match self.listener.accept().await {
                 Ok((stream, peer_addr)) => {
+                    // Shed new connections first when close to the limit
+                    if let Some(limiter) = &self.accept_limiter {
+                        let active =
+                            self.config.max_connections - self.conn_semaphore.available_permits();
+                        let utilization = active as f64 / self.config.max_connections.max(1) as f64;
+                        if !limiter.try_accept(utilization, std::time::Instant::now()) {
+                            self.stats
+                                .rejected_rate_limited
+                                .fetch_add(1, Ordering::Relaxed);
+                            debug!(peer_addr = %peer_addr, utilization, "Connection rejected: accept rate limited");
+                            continue;
+                        }
+                    }
+
                     // Try to acquire a permit
                     let permit = match self.conn_semaphore.clone().try_acquire_owned() {
                         Ok(permit) => permit,
@@ -161,6 +240,18 @@ impl Listener {
                         }
                     };
 
+                    let ip_guard = match &self.per_ip {
+                        Some(per_ip) => match per_ip.try_acquire(peer_addr.ip()) {
+                            Some(guard) => Some(guard),
+                            None => {
+                                self.stats.rejected_per_ip.fetch_add(1, Ordering::Relaxed);
+                                debug!(peer_addr = %peer_addr, "Connection rejected: per-IP limit reached");
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+
                     self.stats
                         .connections_accepted
                         .fetch_add(1, Ordering::Relaxed);
@@ -183,6 +274,7 @@ impl Listener {
 
                             stats.connections_active.fetch_sub(1, Ordering::Relaxed);
                             stats.connections_closed.fetch_add(1, Ordering::Relaxed);
+                            drop(ip_guard);
                             drop(permit);
                         }
                         .instrument(tracing::info_span!("connection", peer = %peer_addr)),
@@ -210,11 +302,12 @@ impl Listener {
         let routes = self.route_table.routes_for_port(local_addr.port()).await;
         let needs_sni = routes
             .iter()
-            .any(|r| r.protocol == ProtocolHint::TlsPassthrough);
+            .any(|r| r.internal == self.config.internal && r.protocol != ProtocolHint::TcpRaw);
 
         // Buffer for SNI inspection (will be forwarded to backend)
         let mut sniff_buffer = Vec::new();
         let sni: Option<String>;
+        let mut tls_without_sni = false;
 
         if needs_sni {
             let (result, _bytes_read) = self
@@ -232,6 +325,7 @@ impl Listener {
                     self.stats.sni_failed.fetch_add(1, Ordering::Relaxed);
                     debug!("No SNI in ClientHello");
                     sni = None;
+                    tls_without_sni = true;
                 }
                 SniResult::NotTls => {
                     self.stats.sni_failed.fetch_add(1, Ordering::Relaxed);
@@ -240,13 +334,24 @@ impl Listener {
                 }
                 SniResult::Timeout => {
                     self.stats.sni_failed.fetch_add(1, Ordering::Relaxed);
-                    warn!("SNI inspection timeout");
+                    self.stats
+                        .client_hello_timeouts
+                        .fetch_add(1, Ordering::Relaxed);
+                    debug!("SNI inspection timeout");
                     sni = None;
                 }
                 SniResult::IoError(e) => {
                     self.stats.sni_failed.fetch_add(1, Ordering::Relaxed);
                     return Err(io::Error::other(e.clone()));
                 }
+                SniResult::TooLarge => {
+                    self.stats.sni_failed.fetch_add(1, Ordering::Relaxed);
+                    self.stats
+                        .client_hello_too_large
+                        .fetch_add(1, Ordering::Relaxed);
+                    debug!("TLS ClientHello exceeds sniff limit");
+                    sni = None;
+                }
                 SniResult::Malformed => {
                     self.stats.sni_failed.fetch_add(1, Ordering::Relaxed);
                     debug!("Malformed TLS ClientHello");
@@ -257,8 +362,25 @@ impl Listener {
             sni = None;
         }
 
+        // TLS clients without SNI go to the port's default certificate, if any.
+        let default_route = match self.cert_store.default_route(local_addr.port()) {
+            Some(route_id) if tls_without_sni => {
+                self.route_table
+                    .route_to(local_addr, self.config.internal, &route_id)
+                    .await
+            }
+            _ => None,
+        };
+
         // Make routing decision
-        let decision = self.route_table.route(local_addr, sni.as_deref()).await;
+        let decision = match default_route {
+            Some(route) => RoutingDecision::Matched { route },
+            None => {
+                self.route_table
+                    .route(local_addr, self.config.internal, sni.as_deref())
+                    .await
+            }
+        };
 
         let route = match decision {
             RoutingDecision::Matched { route } => {
@@ -284,6 +406,88 @@ impl Listener {
             "Route matched"
         );
 
+        // Fingerprints are only computed when logged or needed by a policy.
+        let fingerprint = if self.config.sni_config.fingerprint || route.access.needs_fingerprint()
+        {
+            TlsFingerprint::from_client_hello(&sniff_buffer)
+        } else {
+            None
+        };
+        if let Some(fp) = &fingerprint {
+            debug!(route_id = %route.id, ja3 = %fp.ja3, ja4 = %fp.ja4, "TLS client fingerprint");
+        }
+        if let AccessDecision::Deny { reason } =
+            route.access.check(peer_addr.ip(), fingerprint.as_ref())
+        {
+            self.stats.access_denied.fetch_add(1, Ordering::Relaxed);
+            debug!(route_id = %route.id, peer_addr = %peer_addr, reason, "Connection refused by access policy");
+            return Ok(());
+        }
+
+        if route.protocol == ProtocolHint::TlsTerminate {
+            let Some(mut tls) = self.terminate(client, sniff_buffer, &route).await else {
+                return Ok(());
+            };
+            return self
+                .forward(&mut tls, &route, peer_addr, local_addr, &[])
+                .await;
+        }
+
+        self.forward(&mut client, &route, peer_addr, local_addr, &sniff_buffer)
+            .await
+    }
+
+    /// Complete the TLS handshake with the route's certificate, replaying
+    /// the ClientHello bytes consumed by SNI inspection.
+    async fn terminate(
+        &self,
+        client: TcpStream,
+        sniff_buffer: Vec<u8>,
+        route: &Route,
+    ) -> Option<tokio_rustls::server::TlsStream<PrefixedStream<TcpStream>>> {
+        let Some(acceptor) = self.cert_store.acceptor(&route.id) else {
+            self.stats
+                .tls_handshake_failed
+                .fetch_add(1, Ordering::Relaxed);
+            warn!(route_id = %route.id, "No certificate loaded for TLS termination route");
+            return None;
+        };
+
+        let handshake = acceptor.accept(PrefixedStream::new(sniff_buffer, client));
+        match tokio::time::timeout(self.config.tls_handshake_timeout, handshake).await {
+            Ok(Ok(tls)) => {
+                self.stats.tls_terminated.fetch_add(1, Ordering::Relaxed);
+                Some(tls)
+            }
+            Ok(Err(e)) => {
+                self.stats
+                    .tls_handshake_failed
+                    .fetch_add(1, Ordering::Relaxed);
+                debug!(route_id = %route.id, error = %e, "TLS handshake failed");
+                None
+            }
+            Err(_) => {
+                self.stats
+                    .tls_handshake_failed
+                    .fetch_add(1, Ordering::Relaxed);
+                self.stats
+                    .tls_handshake_timeouts
+                    .fetch_add(1, Ordering::Relaxed);
+                debug!(route_id = %route.id, "TLS handshake timeout");
+                None
+            }
+        }
+    }
+
+    /// Connect to a backend for `route` and proxy `client` to it.
+    async fn forward<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut S,
+        route: &Route,
+        peer_addr: SocketAddr,
+        local_addr: SocketAddr,
+        sniff_buffer: &[u8],
+    ) -> io::Result<()> {
         // Get backend pool and connect
         let pool = self.backend_selector.get_or_create_pool(&route.id).await;
 
@@ -315,12 +519,12 @@ impl Listener {
 
         // Forward any buffered data from SNI inspection
         if !sniff_buffer.is_empty() {
-            backend.write_all(&sniff_buffer).await?;
+            backend.write_all(sniff_buffer).await?;
         }
 
         // Proxy the connection bidirectionally
         let (bytes_to_backend, bytes_from_backend) =
-            proxy_bidirectional(&mut client, &mut backend, self.config.idle_timeout).await?;
+            proxy_bidirectional(client, &mut backend, self.config.idle_timeout).await?;
 
         self.stats
             .bytes_to_backend
@@ -342,13 +546,17 @@ impl Listener {
 /// Proxy data bidirectionally between two streams.
 ///
 /// Returns (bytes_to_b, bytes_from_b).
-async fn proxy_bidirectional(
-    a: &mut TcpStream,
-    b: &mut TcpStream,
+async fn proxy_bidirectional<A, B>(
+    a: &mut A,
+    b: &mut B,
     idle_timeout: Option<Duration>,
-) -> io::Result<(u64, u64)> {
-    let (mut a_read, mut a_write) = a.split();
-    let (mut b_read, mut b_write) = b.split();
+) -> io::Result<(u64, u64)>
+where
+    A: AsyncRead + AsyncWrite + Unpin,
+    B: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut a_read, mut a_write) = tokio::io::split(a);
+    let (mut b_read, mut b_write) = tokio::io::split(b);
 
     let a_to_b = async {
         let mut total = 0u64;
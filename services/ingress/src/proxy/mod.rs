@@ -3,7 +3,10 @@
 //! This module provides:
 //! - TCP listener management
 //! - SNI inspection for TLS passthrough
+//! - Connection admission limits (per-IP caps, accept-rate limiting)
+//! - TLS client fingerprinting (JA3/JA4) and per-route access policies
 //! - Backend selection and load balancing
+//! - TLS termination with uploaded certificates
 //! - PROXY protocol v2 injection
 //! - Connection proxying
 //!
@@ -28,16 +31,27 @@
 //! listener.run().await?;
 //! ```
 
+mod access;
 mod backend;
+mod fingerprint;
+mod limits;
 mod listener;
 mod proxy_protocol;
 mod router;
 mod sni;
+mod tls;
 
+pub use access::{AccessDecision, AccessPolicy};
 pub use backend::{Backend, BackendPool, BackendPoolStats, BackendSelector, HealthStatus};
-pub use listener::{Listener, ListenerConfig, ListenerStats};
+pub use fingerprint::TlsFingerprint;
+pub use limits::{
+    AcceptRateLimiter, PerIpLimiter, DEFAULT_MAX_CONNECTIONS_PER_IP, DEFAULT_OVERLOAD_ACCEPT_RATE,
+    DEFAULT_OVERLOAD_THRESHOLD,
+};
+pub use listener::{Listener, ListenerConfig, ListenerStats, DEFAULT_TLS_HANDSHAKE_TIMEOUT};
 pub use proxy_protocol::ProxyProtocolV2;
 pub use router::{
     ProtocolHint, ProxyProtocol, Route, RouteTable, RoutingDecision, SharedRouteTable,
 };
-pub use sni::{SniConfig, SniInspector, SniResult};
+pub use sni::{SniConfig, SniInspector, SniResult, DEFAULT_MAX_SNIFF_BYTES, DEFAULT_SNIFF_TIMEOUT};
+pub use tls::{CertMaterial, CertStore, PrefixedStream};
@@ -9,6 +9,8 @@
 //! - Routes bind hostname+port to environment/backend
 //! - Config updates must be applied atomically
 //! - Config reload must not drop established connections
+//! - Internal routes are only served by internal (overlay) listeners, and
+//!   public routes only by public listeners
 //!
 //! Reference: docs/specs/networking/ingress-l4.md
 
@@ -19,6 +21,8 @@ use std::sync::Arc;
 use arc_swap::ArcSwap;
 use tracing::{debug, info, warn};
 
+use super::access::AccessPolicy;
+
 /// Protocol hint for a route.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProtocolHint {
@@ -26,6 +30,8 @@ pub enum ProtocolHint {
     TlsPassthrough,
     /// Raw TCP without payload inspection.
     TcpRaw,
+    /// TLS terminated at the edge with the route's uploaded certificate.
+    TlsTerminate,
 }
 
 /// PROXY protocol configuration for a route.
@@ -56,6 +62,10 @@ pub struct Route {
     pub backend_port: u16,
     pub allow_non_tls_fallback: bool,
     pub env_ipv4_address: Option<String>,
+    /// Served only by internal listeners on the overlay network.
+    pub internal: bool,
+    /// Client CIDR and TLS fingerprint rules checked before proxying.
+    pub access: AccessPolicy,
 }
 
 impl Route {
@@ -245,9 +255,15 @@ impl RouteTable {
 
     /// Make a routing decision based on listener address and optional SNI.
     ///
+    /// Only routes whose `internal` flag matches the listener's are considered.
     /// For IPv4 listeners, only routes with matching env_ipv4_address are considered.
     /// For IPv6 listeners, all routes are considered (current default behavior).
-    pub async fn route(&self, listener_addr: SocketAddr, sni: Option<&str>) -> RoutingDecision {
+    pub async fn route(
+        &self,
+        listener_addr: SocketAddr,
+        internal: bool,
+        sni: Option<&str>,
+    ) -> RoutingDecision {
         let port = listener_addr.port();
         let snapshot = self.snapshot.load();
 
@@ -265,7 +281,7 @@ impl RouteTable {
             };
 
             if let Some(route) = snapshot.by_key.get(&key) {
-                if Self::route_matches_listener(&listener_ipv4, route) {
+                if Self::route_matches_listener(&listener_ipv4, internal, route) {
                     debug!(
                         route_id = %route.id,
                         hostname = %normalized,
@@ -290,7 +306,7 @@ impl RouteTable {
             .map(|routes| {
                 routes
                     .iter()
-                    .filter(|r| Self::route_matches_listener(&listener_ipv4, r))
+                    .filter(|r| Self::route_matches_listener(&listener_ipv4, internal, r))
                     .collect()
             })
             .unwrap_or_default();
@@ -301,11 +317,11 @@ impl RouteTable {
             },
             1 => {
                 let route = eligible_routes[0];
-                if route.protocol == ProtocolHint::TlsPassthrough && !route.allow_non_tls_fallback {
+                if route.protocol != ProtocolHint::TcpRaw && !route.allow_non_tls_fallback {
                     warn!(
                         route_id = %route.id,
                         port = port,
-                        "TLS passthrough route without SNI and fallback disabled"
+                        "TLS route without SNI and fallback disabled"
                     );
                     return RoutingDecision::NoMatch {
                         reason: format!(
@@ -333,7 +349,33 @@ impl RouteTable {
         }
     }
 
-    fn route_matches_listener(listener_ipv4: &Option<String>, route: &Route) -> bool {
+    /// Route a connection to a specific route (e.g. the owner of a port's
+    /// default certificate), if it is bound to this listener.
+    pub async fn route_to(
+        &self,
+        listener_addr: SocketAddr,
+        internal: bool,
+        route_id: &str,
+    ) -> Option<Route> {
+        let snapshot = self.snapshot.load();
+        let route = snapshot.by_id.get(route_id)?;
+        let listener_ipv4 = match listener_addr {
+            SocketAddr::V4(addr) => Some(addr.ip().to_string()),
+            SocketAddr::V6(_) => None,
+        };
+        (route.port == listener_addr.port()
+            && Self::route_matches_listener(&listener_ipv4, internal, route))
+        .then(|| route.clone())
+    }
+
+    fn route_matches_listener(
+        listener_ipv4: &Option<String>,
+        internal: bool,
+        route: &Route,
+    ) -> bool {
+        if route.internal != internal {
+            return false;
+        }
         match listener_ipv4 {
             Some(ip) => route.env_ipv4_address.as_ref() == Some(ip),
             None => true,
@@ -397,6 +439,8 @@ mod tests {
             backend_port: 8080,
             allow_non_tls_fallback: false,
             env_ipv4_address: None,
+            internal: false,
+            access: AccessPolicy::default(),
         }
     }
 
@@ -432,7 +476,7 @@ mod tests {
         let addr: SocketAddr = "[::]:443".parse().unwrap();
 
         // Match with SNI
-        match table.route(addr, Some("example.com")).await {
+        match table.route(addr, false, Some("example.com")).await {
             RoutingDecision::Matched { route } => {
                 assert_eq!(route.id, "r1");
             }
@@ -440,7 +484,7 @@ mod tests {
         }
 
         // No match
-        match table.route(addr, Some("unknown.com")).await {
+        match table.route(addr, false, Some("unknown.com")).await {
             RoutingDecision::NoMatch { .. } => {}
             other => panic!("Expected NoMatch, got {:?}", other),
         }
@@ -455,7 +499,7 @@ mod tests {
         let addr: SocketAddr = "[::]:443".parse().unwrap();
 
         // Without SNI, should be ambiguous
-        match table.route(addr, None).await {
+        match table.route(addr, false, None).await {
             RoutingDecision::Ambiguous { .. } => {}
             other => panic!("Expected Ambiguous, got {:?}", other),
         }
@@ -473,7 +517,7 @@ mod tests {
         let addr: SocketAddr = "[::]:443".parse().unwrap();
 
         // Without SNI, should match the single route
-        match table.route(addr, None).await {
+        match table.route(addr, false, None).await {
             RoutingDecision::Matched { route } => {
                 assert_eq!(route.id, "r1");
             }
@@ -506,11 +550,38 @@ mod tests {
         let addr: SocketAddr = "[::]:5432".parse().unwrap();
 
         // Raw TCP routes without SNI should match if unambiguous
-        match table.route(addr, None).await {
+        match table.route(addr, false, None).await {
             RoutingDecision::Matched { route } => {
                 assert_eq!(route.protocol, ProtocolHint::TcpRaw);
             }
             other => panic!("Expected Matched, got {:?}", other),
         }
     }
+
+    #[tokio::test]
+    async fn test_internal_routes_only_on_internal_listeners() {
+        let table = RouteTable::new();
+        table.upsert(make_route("r1", "example.com", 443)).await;
+        let mut internal = make_route("r2", "api.billing.internal", 443);
+        internal.internal = true;
+        table.upsert(internal).await;
+
+        let addr: SocketAddr = "[::]:443".parse().unwrap();
+
+        match table.route(addr, true, Some("api.billing.internal")).await {
+            RoutingDecision::Matched { route } => assert_eq!(route.id, "r2"),
+            other => panic!("Expected Matched, got {:?}", other),
+        }
+        match table.route(addr, false, Some("api.billing.internal")).await {
+            RoutingDecision::NoMatch { .. } => {}
+            other => panic!("Expected NoMatch, got {:?}", other),
+        }
+        match table.route(addr, true, Some("example.com")).await {
+            RoutingDecision::NoMatch { .. } => {}
+            other => panic!("Expected NoMatch, got {:?}", other),
+        }
+
+        assert!(table.route_to(addr, false, "r2").await.is_none());
+        assert!(table.route_to(addr, true, "r2").await.is_some());
+    }
 }
@@ -34,6 +34,9 @@ pub enum SniResult {
     IoError(String),
     /// ClientHello is malformed or incomplete within bounds.
     Malformed,
+    /// ClientHello record exceeds the sniff byte limit and no SNI was found
+    /// in the bytes read.
+    TooLarge,
 }
 
 /// Configuration for SNI inspection.
@@ -43,6 +46,9 @@ pub struct SniConfig {
     pub timeout: Duration,
     /// Maximum bytes to read.
     pub max_bytes: usize,
+    /// Compute and log JA3/JA4 fingerprints of every ClientHello, not only
+    /// for routes whose access policy has fingerprint rules.
+    pub fingerprint: bool,
 }
 
 impl Default for SniConfig {
@@ -50,6 +56,7 @@ impl Default for SniConfig {
         Self {
             timeout: DEFAULT_SNIFF_TIMEOUT,
             max_bytes: DEFAULT_MAX_SNIFF_BYTES,
+            fingerprint: false,
         }
     }
 }
@@ -88,9 +95,12 @@ impl SniInspector {
             timeout(self.config.timeout, self.read_client_hello(stream, buffer)).await;
 
         match read_result {
-            Ok(Ok(bytes_read)) => {
+            Ok(Ok((bytes_read, truncated))) => {
                 buffer.truncate(bytes_read);
-                let result = parse_sni(&buffer[..bytes_read]);
+                let result = match parse_sni(&buffer[..bytes_read]) {
+                    SniResult::Malformed if truncated => SniResult::TooLarge,
+                    result => result,
+                };
                 (result, bytes_read)
             }
             Ok(Err(e)) => {
@@ -106,18 +116,21 @@ impl SniInspector {
     }
 
     /// Read enough of the ClientHello to extract SNI.
+    ///
+    /// Returns the bytes read and whether the record was cut short by
+    /// `max_bytes`.
     async fn read_client_hello<R: AsyncRead + Unpin>(
         &self,
         stream: &mut R,
         buffer: &mut [u8],
-    ) -> io::Result<usize> {
+    ) -> io::Result<(usize, bool)> {
         let mut total_read = 0;
 
         // Read TLS record header (5 bytes minimum)
         while total_read < 5 {
             let n = stream.read(&mut buffer[total_read..]).await?;
             if n == 0 {
-                return Ok(total_read);
+                return Ok((total_read, false));
             }
             total_read += n;
         }
@@ -125,7 +138,7 @@ impl SniInspector {
         // Check if this looks like a TLS ClientHello
         // Record type 0x16 = Handshake
         if buffer[0] != 0x16 {
-            return Ok(total_read);
+            return Ok((total_read, false));
         }
 
         // TLS version (we accept 0x0301 through 0x0303)
@@ -137,6 +150,7 @@ impl SniInspector {
 
         // Record length
         let record_len = u16::from_be_bytes([buffer[3], buffer[4]]) as usize;
+        let truncated = 5 + record_len > self.config.max_bytes;
         let target_len = (5 + record_len).min(self.config.max_bytes);
 
         // Read enough to parse the ClientHello
@@ -148,7 +162,7 @@ impl SniInspector {
             total_read += n;
         }
 
-        Ok(total_read)
+        Ok((total_read, truncated))
     }
 }
 
@@ -378,6 +392,19 @@ mod tests {
         assert!(matches!(result, SniResult::Malformed));
     }
 
+    #[tokio::test]
+    async fn test_inspect_too_large() {
+        let inspector = SniInspector::with_config(SniConfig {
+            max_bytes: 32,
+            ..SniConfig::default()
+        });
+        let mut stream = EXAMPLE_CLIENT_HELLO;
+        let mut buffer = Vec::new();
+        let (result, bytes_read) = inspector.inspect(&mut stream, &mut buffer).await;
+        assert!(matches!(result, SniResult::TooLarge));
+        assert_eq!(bytes_read, 32);
+    }
+
     #[test]
     fn test_normalize_trailing_dot() {
         // Test the normalize function directly
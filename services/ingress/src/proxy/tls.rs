@@ -0,0 +1,277 @@
+//! TLS termination for `tls_terminate` routes.
+//!
+//! Certificates uploaded to the control plane are delivered to the ingress
+//! (see `sync::sync_certificates`) and held here, one acceptor per route.
+//! The listener picks the route from the ClientHello SNI as for passthrough,
+//! then completes the handshake with that route's certificate and forwards
+//! plaintext TCP to the backend.
+//!
+//! A certificate may be marked the default for its listen port; clients that
+//! send no SNI on that port are served by its route.
+//!
+//! Reference: docs/specs/networking/ingress-l4.md
+
+use std::collections::HashMap;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arc_swap::ArcSwap;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+use zeroize::Zeroizing;
+
+/// Certificate and key for one route, as delivered by the control plane.
+pub struct CertMaterial {
+    pub route_id: String,
+    pub listen_port: u16,
+    pub chain_pem: String,
+    pub key_pem: Zeroizing<String>,
+    pub fingerprint_sha256: String,
+    pub default_for_non_sni: bool,
+}
+
+impl std::fmt::Debug for CertMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertMaterial")
+            .field("route_id", &self.route_id)
+            .field("listen_port", &self.listen_port)
+            .field("fingerprint_sha256", &self.fingerprint_sha256)
+            .field("default_for_non_sni", &self.default_for_non_sni)
+            .field("key_pem", &"<redacted>")
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+struct RouteCert {
+    acceptor: TlsAcceptor,
+    fingerprint_sha256: String,
+}
+
+#[derive(Default)]
+struct CertSnapshot {
+    by_route: HashMap<String, RouteCert>,
+    default_by_port: HashMap<u16, String>,
+}
+
+/// Route certificates, swapped atomically as a whole.
+pub struct CertStore {
+    snapshot: ArcSwap<CertSnapshot>,
+}
+
+impl Default for CertStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CertStore {
+    pub fn new() -> Self {
+        Self {
+            snapshot: ArcSwap::from_pointee(CertSnapshot::default()),
+        }
+    }
+
+    /// Replace every certificate. Entries that fail to load are skipped
+    /// (and logged); returns how many were loaded.
+    pub fn replace(&self, material: Vec<CertMaterial>) -> usize {
+        let mut snapshot = CertSnapshot::default();
+        for m in material {
+            let acceptor = match build_acceptor(&m.chain_pem, &m.key_pem) {
+                Ok(acceptor) => acceptor,
+                Err(e) => {
+                    warn!(
+                        route_id = %m.route_id,
+                        fingerprint_sha256 = %m.fingerprint_sha256,
+                        error = %e,
+                        "Skipping unloadable route certificate"
+                    );
+                    continue;
+                }
+            };
+            if m.default_for_non_sni {
+                snapshot
+                    .default_by_port
+                    .insert(m.listen_port, m.route_id.clone());
+            }
+            snapshot.by_route.insert(
+                m.route_id,
+                RouteCert {
+                    acceptor,
+                    fingerprint_sha256: m.fingerprint_sha256,
+                },
+            );
+        }
+
+        let loaded = snapshot.by_route.len();
+        self.snapshot.store(Arc::new(snapshot));
+        info!(certificates = loaded, "Route certificates updated");
+        loaded
+    }
+
+    /// Acceptor for a route's certificate.
+    pub fn acceptor(&self, route_id: &str) -> Option<TlsAcceptor> {
+        self.snapshot
+            .load()
+            .by_route
+            .get(route_id)
+            .map(|c| c.acceptor.clone())
+    }
+
+    /// Fingerprint of the certificate loaded for a route.
+    pub fn fingerprint(&self, route_id: &str) -> Option<String> {
+        self.snapshot
+            .load()
+            .by_route
+            .get(route_id)
+            .map(|c| c.fingerprint_sha256.clone())
+    }
+
+    /// Route whose certificate serves non-SNI clients on `port`.
+    pub fn default_route(&self, port: u16) -> Option<String> {
+        self.snapshot.load().default_by_port.get(&port).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshot.load().by_route.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn build_acceptor(chain_pem: &str, key_pem: &str) -> io::Result<TlsAcceptor> {
+    let certs: Vec<CertificateDer<'static>> =
+        rustls_pemfile::certs(&mut chain_pem.as_bytes()).collect::<Result<_, _>>()?;
+    if certs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no certificates in chain",
+        ));
+    }
+    let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut key_pem.as_bytes())?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no private key"))?;
+
+    // TLS 1.2 minimum, 1.3 preferred (ingress-l7.md, TLS policies).
+    let config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_protocol_versions(&[&rustls::version::TLS13, &rustls::version::TLS12])
+            .map_err(io::Error::other)?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(io::Error::other)?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// A stream that first yields bytes already read from it.
+///
+/// SNI inspection consumes the ClientHello; for termination those bytes
+/// must be replayed into the TLS handshake.
+pub struct PrefixedStream<S> {
+    prefix: Vec<u8>,
+    pos: usize,
+    inner: S,
+}
+
+impl<S> PrefixedStream<S> {
+    pub fn new(prefix: Vec<u8>, inner: S) -> Self {
+        Self {
+            prefix,
+            pos: 0,
+            inner,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PrefixedStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.pos < self.prefix.len() {
+            let n = (self.prefix.len() - self.pos).min(buf.remaining());
+            let start = self.pos;
+            buf.put_slice(&self.prefix[start..start + n]);
+            self.pos += n;
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    fn material(route_id: &str, default_for_non_sni: bool) -> CertMaterial {
+        let cert = rcgen::generate_simple_self_signed(vec!["api.example.com".to_string()]).unwrap();
+        CertMaterial {
+            route_id: route_id.to_string(),
+            listen_port: 443,
+            chain_pem: cert.cert.pem(),
+            key_pem: Zeroizing::new(cert.key_pair.serialize_pem()),
+            fingerprint_sha256: "ab".repeat(32),
+            default_for_non_sni,
+        }
+    }
+
+    #[test]
+    fn test_replace_loads_and_skips_invalid() {
+        let store = CertStore::new();
+        let mut bad = material("route_bad", false);
+        bad.key_pem = Zeroizing::new("not a key".to_string());
+
+        let loaded = store.replace(vec![material("route_1", true), bad]);
+        assert_eq!(loaded, 1);
+        assert!(store.acceptor("route_1").is_some());
+        assert!(store.acceptor("route_bad").is_none());
+        assert_eq!(store.default_route(443).as_deref(), Some("route_1"));
+        assert!(store.default_route(8443).is_none());
+
+        store.replace(Vec::new());
+        assert!(store.is_empty());
+        assert!(store.default_route(443).is_none());
+    }
+
+    #[test]
+    fn test_material_debug_redacts_key() {
+        let m = material("route_1", false);
+        assert!(!format!("{m:?}").contains("PRIVATE KEY"));
+    }
+
+    #[tokio::test]
+    async fn test_prefixed_stream_replays_prefix() {
+        let inner: &[u8] = b" world";
+        let mut stream = PrefixedStream::new(b"hello".to_vec(), inner);
+        let mut out = String::new();
+        stream.read_to_string(&mut out).await.unwrap();
+        assert_eq!(out, "hello world");
+    }
+}
@@ -0,0 +1,115 @@
+//! Ingress process wiring.
+//!
+//! [`run`] restores the last-known routes and backends, binds the configured
+//! listeners, keeps backends in sync, reports status to the control plane,
+//! and runs the route sync loop. Shared by the `ingress` binary and the
+//! embedded dev stack.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use tracing::{error, info};
+
+use crate::config::Config;
+use crate::sync;
+use crate::{BackendSelector, CertStore, Listener, ListenerConfig, RouteTable};
+
+/// Run the ingress until the route sync loop exits.
+pub async fn run(config: Config) -> Result<()> {
+    // Create shared state
+    let route_table = Arc::new(RouteTable::new());
+    let backend_selector = Arc::new(BackendSelector::new());
+    let cert_store = Arc::new(CertStore::new());
+
+    if config.proxy_enabled {
+        // Serve last-known config until the first successful sync
+        sync::restore_last_known(&config, &route_table, &backend_selector).await;
+
+        // Start listeners
+        let mut listener_handles = Vec::new();
+
+        for binding in &config.listeners {
+            let mut listener_config = ListenerConfig::new(binding.bind_addr);
+            listener_config.max_connections = binding.max_connections;
+            listener_config.internal = binding.internal;
+            listener_config.sni_config.fingerprint = config.tls_fingerprint;
+            listener_config.sni_config.timeout = config.limits.sniff_timeout;
+            listener_config.sni_config.max_bytes = config.limits.max_sniff_bytes;
+            listener_config.tls_handshake_timeout = config.limits.tls_handshake_timeout;
+            listener_config.max_connections_per_ip = config.limits.max_connections_per_ip;
+            listener_config.overload_accept_rate = config.limits.overload_accept_rate;
+            listener_config.overload_threshold = config.limits.overload_threshold;
+
+            match Listener::bind(
+                listener_config,
+                Arc::clone(&route_table),
+                Arc::clone(&backend_selector),
+            )
+            .await
+            {
+                Ok(listener) => {
+                    let listener = listener.with_cert_store(Arc::clone(&cert_store));
+                    info!(
+                        bind_addr = %binding.bind_addr,
+                        internal = binding.internal,
+                        "Listener bound"
+                    );
+                    let listener = Arc::new(listener);
+                    let handle = tokio::spawn(async move {
+                        if let Err(e) = listener.run().await {
+                            error!(error = %e, "Listener error");
+                        }
+                    });
+                    listener_handles.push(handle);
+                }
+                Err(e) => {
+                    error!(
+                        bind_addr = %binding.bind_addr,
+                        error = %e,
+                        "Failed to bind listener"
+                    );
+                    return Err(e.into());
+                }
+            }
+        }
+
+        // Start backend sync loop
+        let backend_config = config.clone();
+        let backend_route_table = Arc::clone(&route_table);
+        let backend_selector_clone = Arc::clone(&backend_selector);
+        tokio::spawn(async move {
+            if let Err(e) = sync::run_backend_sync_loop(
+                backend_config,
+                backend_route_table,
+                backend_selector_clone,
+            )
+            .await
+            {
+                error!(error = %e, "Backend sync loop failed");
+            }
+        });
+
+        // Start status report loop
+        let report_config = config.clone();
+        let report_route_table = Arc::clone(&route_table);
+        let report_backend_selector = Arc::clone(&backend_selector);
+        tokio::spawn(async move {
+            if let Err(e) = sync::run_status_report_loop(
+                report_config,
+                report_route_table,
+                report_backend_selector,
+            )
+            .await
+            {
+                error!(error = %e, "Status report loop failed");
+            }
+        });
+
+        // Run route sync loop (blocks until error or shutdown)
+        sync::run_route_sync_loop(&config, route_table, backend_selector, cert_store).await
+    } else {
+        // Sync-only mode (for debugging/testing)
+        info!("Running in sync-only mode (proxy disabled)");
+        sync::run_route_sync_loop(&config, route_table, backend_selector, cert_store).await
+    }
+}
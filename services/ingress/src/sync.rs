@@ -6,6 +6,16 @@
 //! Per docs/specs/networking/ingress-l4.md:
 //! - Config updates must be applied atomically
 //! - Control plane outage: edge continues operating on last applied config
+//!
+//! With a state file configured, the last applied routes and backend
+//! instances are restored at startup ([`restore_last_known`]) so a restart
+//! during a control plane outage keeps serving traffic.
+//!
+//! Certificates for `tls_terminate` routes are fetched in full from the
+//! control plane at startup and whenever a `route.cert_*` event is seen.
+//!
+//! Deleted routes stay in the state, unserved, until `route.purged`, so a
+//! `route.restored` can bring them back without the route's full spec.
 
 use std::{
     collections::BTreeMap,
@@ -13,20 +23,28 @@ use std::{
     net::Ipv6Addr,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 
 use anyhow::{Context, Result};
 use plfm_events::{
-    RouteCreatedPayload, RouteDeletedPayload, RouteProtocolHint, RouteProxyProtocol,
-    RouteUpdatedPayload,
+    RouteAccessPolicy, RouteCreatedPayload, RouteDeletedPayload, RouteProtocolHint,
+    RouteProxyProtocol, RoutePurgedPayload, RouteRestoredPayload, RouteUpdatedPayload,
+    RouteVerificationSucceededPayload,
 };
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
+use zeroize::Zeroizing;
 
 use crate::config::Config;
-use plfm_ingress::persistence::{PersistedRoute, StatePersistence};
-use plfm_ingress::{Backend, BackendSelector, ProtocolHint, ProxyProtocol, Route, RouteTable};
+use crate::persistence::{
+    PersistedBackendInstance, PersistedBackends, PersistedRoute, StatePersistence,
+};
+use crate::{
+    AccessPolicy, Backend, BackendSelector, CertMaterial, CertStore, ProtocolHint, ProxyProtocol,
+    Route, RouteTable,
+};
 
 #[derive(Debug, Deserialize)]
 struct EventsResponse {
@@ -56,6 +74,14 @@ struct RouteState {
     backend_expects_proxy_protocol: bool,
     ipv4_required: bool,
     env_ipv4_address: Option<String>,
+    /// Served only once hostname verification has succeeded.
+    active: bool,
+    /// Served only by internal listeners.
+    internal: bool,
+    /// Client access rules; `None` admits everyone.
+    access_policy: Option<RouteAccessPolicy>,
+    /// Deleted but still restorable; never served.
+    deleted: bool,
 }
 
 impl RouteState {
@@ -72,7 +98,11 @@ impl RouteState {
             proxy_protocol: payload.proxy_protocol,
             backend_expects_proxy_protocol: payload.backend_expects_proxy_protocol,
             ipv4_required: payload.ipv4_required,
+            active: payload.verification_token.is_none(),
             env_ipv4_address: payload.env_ipv4_address,
+            internal: payload.internal,
+            access_policy: payload.access_policy.filter(|p| !p.is_empty()),
+            deleted: false,
         }
     }
 
@@ -90,6 +120,10 @@ impl RouteState {
             backend_expects_proxy_protocol: p.backend_expects_proxy_protocol,
             ipv4_required: p.ipv4_required,
             env_ipv4_address: p.env_ipv4_address.clone(),
+            active: p.active,
+            internal: p.internal,
+            access_policy: p.access_policy.clone(),
+            deleted: p.deleted,
         }
     }
 
@@ -107,6 +141,10 @@ impl RouteState {
             backend_expects_proxy_protocol: self.backend_expects_proxy_protocol,
             ipv4_required: self.ipv4_required,
             env_ipv4_address: self.env_ipv4_address.clone(),
+            active: self.active,
+            internal: self.internal,
+            access_policy: self.access_policy.clone(),
+            deleted: self.deleted,
         }
     }
 
@@ -155,6 +193,15 @@ impl RouteState {
             }
         }
 
+        // An update carries the whole policy; an empty one clears it.
+        if let Some(v) = payload.access_policy {
+            let v = Some(v).filter(|p| !p.is_empty());
+            if v != self.access_policy {
+                self.access_policy = v;
+                changed.push("access_policy");
+            }
+        }
+
         changed
     }
 }
@@ -170,6 +217,7 @@ fn route_state_to_proxy_route(state: &RouteState) -> Route {
     let protocol = match state.protocol_hint {
         RouteProtocolHint::TlsPassthrough => ProtocolHint::TlsPassthrough,
         RouteProtocolHint::TcpRaw => ProtocolHint::TcpRaw,
+        RouteProtocolHint::TlsTerminate => ProtocolHint::TlsTerminate,
     };
     let allow_non_tls_fallback = matches!(state.protocol_hint, RouteProtocolHint::TcpRaw);
 
@@ -188,12 +236,22 @@ fn route_state_to_proxy_route(state: &RouteState) -> Route {
         backend_port: state.backend_port as u16,
         allow_non_tls_fallback,
         env_ipv4_address: state.env_ipv4_address.clone(),
+        internal: state.internal,
+        access: state
+            .access_policy
+            .as_ref()
+            .map(AccessPolicy::from_policy)
+            .unwrap_or_default(),
     }
 }
 
 /// Update the shared route table from internal state.
 async fn update_proxy_route_table(routes: &BTreeMap<String, RouteState>, route_table: &RouteTable) {
-    let proxy_routes: Vec<Route> = routes.values().map(route_state_to_proxy_route).collect();
+    let proxy_routes: Vec<Route> = routes
+        .values()
+        .filter(|r| r.active && !r.deleted)
+        .map(route_state_to_proxy_route)
+        .collect();
     route_table.update(proxy_routes).await;
 }
 
@@ -289,6 +347,8 @@ fn apply_route_event(
                 proxy_protocol = proxy_protocol_label(state.proxy_protocol),
                 backend_expects_proxy_protocol = state.backend_expects_proxy_protocol,
                 ipv4_required = state.ipv4_required,
+                active = state.active,
+                internal = state.internal,
                 replaced,
                 "route upserted"
             );
@@ -321,12 +381,36 @@ fn apply_route_event(
                 "route updated"
             );
         }
+        "route.verification_succeeded" => {
+            let payload: RouteVerificationSucceededPayload = serde_json::from_value(payload)
+                .context("invalid route.verification_succeeded payload JSON")?;
+            let route_id = payload.route_id.to_string();
+
+            let Some(state) = routes.get_mut(&route_id) else {
+                warn!(event_id, route_id = %route_id, "route.verification_succeeded for unknown route_id");
+                return Ok(());
+            };
+
+            state.active = true;
+            info!(
+                event_id,
+                route_id = %route_id,
+                hostname = %state.hostname,
+                "route hostname verified; route active"
+            );
+        }
         "route.deleted" => {
             let payload: RouteDeletedPayload =
                 serde_json::from_value(payload).context("invalid route.deleted payload JSON")?;
             let route_id = payload.route_id.to_string();
 
-            let existed = routes.remove(&route_id).is_some();
+            let existed = match routes.get_mut(&route_id) {
+                Some(state) => {
+                    state.deleted = true;
+                    true
+                }
+                None => false,
+            };
             info!(
                 event_id,
                 route_id = %route_id,
@@ -336,37 +420,158 @@ fn apply_route_event(
                 "route deleted"
             );
         }
+        "route.restored" => {
+            let payload: RouteRestoredPayload =
+                serde_json::from_value(payload).context("invalid route.restored payload JSON")?;
+            let route_id = payload.route_id.to_string();
+
+            let Some(state) = routes.get_mut(&route_id) else {
+                warn!(event_id, route_id = %route_id, "route.restored for unknown route_id");
+                return Ok(());
+            };
+
+            state.deleted = false;
+            info!(
+                event_id,
+                route_id = %route_id,
+                hostname = %payload.hostname,
+                "route restored"
+            );
+        }
+        "route.purged" => {
+            let payload: RoutePurgedPayload =
+                serde_json::from_value(payload).context("invalid route.purged payload JSON")?;
+            let route_id = payload.route_id.to_string();
+
+            let existed = routes.remove(&route_id).is_some();
+            debug!(event_id, route_id = %route_id, existed, "route purged");
+        }
         _ => {}
     }
 
     Ok(())
 }
 
-/// Poll route events and update the shared route table.
-pub async fn run_route_sync_loop(
+/// Response for listing route certificates.
+#[derive(Deserialize)]
+struct RouteCertificatesResponse {
+    items: Vec<RouteCertificateItem>,
+}
+
+/// Route certificate item from the admin API (includes the private key).
+#[derive(Deserialize)]
+struct RouteCertificateItem {
+    route_id: String,
+    listen_port: u16,
+    certificate_pem: String,
+    private_key_pem: String,
+    fingerprint_sha256: String,
+    #[serde(default)]
+    default_for_non_sni: bool,
+}
+
+/// Fetch every route certificate for the org and replace the cert store.
+///
+/// On failure the store keeps its previous contents.
+pub async fn sync_certificates(
+    client: &reqwest::Client,
     config: &Config,
-    route_table: Arc<RouteTable>,
-    _backend_selector: Arc<BackendSelector>,
-) -> Result<()> {
-    let mut headers = HeaderMap::new();
-    if let Some(token) = &config.control_plane_token {
-        let raw = token.expose().trim();
-        let bearer = if raw.starts_with("Bearer ") || raw.starts_with("bearer ") {
-            raw.to_string()
-        } else {
-            format!("Bearer {raw}")
-        };
+    cert_store: &CertStore,
+) -> Result<usize> {
+    let base = config.control_plane_url.trim_end_matches('/');
+    let url = format!("{base}/v1/_admin/route-certificates");
 
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&bearer).context("Invalid control-plane token format")?,
+    let resp = client
+        .get(&url)
+        .query(&[("org_id", config.org_id.as_str())])
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!(
+            "route certificates query failed (status={}): {}",
+            status,
+            body
         );
     }
 
-    let client = reqwest::Client::builder()
-        .user_agent("plfm-ingress/0.1.0")
-        .default_headers(headers)
-        .build()?;
+    let certs: RouteCertificatesResponse = resp.json().await?;
+    let material = certs
+        .items
+        .into_iter()
+        .map(|item| CertMaterial {
+            route_id: item.route_id,
+            listen_port: item.listen_port,
+            chain_pem: item.certificate_pem,
+            key_pem: Zeroizing::new(item.private_key_pem),
+            fingerprint_sha256: item.fingerprint_sha256,
+            default_for_non_sni: item.default_for_non_sni,
+        })
+        .collect();
+
+    Ok(cert_store.replace(material))
+}
+
+/// Load the last saved backend instances, if a state file is configured.
+fn load_backend_index(persistence: &StatePersistence) -> BackendIndex {
+    match persistence.load_backends() {
+        Ok(Some(backends)) => BackendIndex::from_persisted(backends),
+        Ok(None) => BackendIndex::default(),
+        Err(e) => {
+            warn!(error = %e, "Failed to load backend snapshot, starting empty");
+            BackendIndex::default()
+        }
+    }
+}
+
+/// Restore the last applied routes and backend sets from the state file.
+///
+/// Runs before listeners are bound, so an edge restarted while the control
+/// plane is unreachable serves its last-known-good config instead of nothing.
+/// The sync loops take over once they reach the control plane.
+pub async fn restore_last_known(
+    config: &Config,
+    route_table: &RouteTable,
+    backend_selector: &BackendSelector,
+) {
+    let Some(persistence) = config.state_file.clone().map(StatePersistence::new) else {
+        return;
+    };
+
+    match persistence.load() {
+        Ok(state) => {
+            let routes: BTreeMap<String, RouteState> = state
+                .routes
+                .iter()
+                .map(|(id, r)| (id.clone(), RouteState::from_persisted(r)))
+                .collect();
+            update_proxy_route_table(&routes, route_table).await;
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to load persisted state, starting without routes");
+            return;
+        }
+    }
+
+    let index = load_backend_index(&persistence);
+    apply_backends(&index, route_table, backend_selector, &mut BTreeMap::new()).await;
+    info!(
+        route_count = route_table.len().await,
+        instance_count = index.instances.len(),
+        "Restored last-known routes and backends"
+    );
+}
+
+/// Poll route events and update the shared route table and cert store.
+pub async fn run_route_sync_loop(
+    config: &Config,
+    route_table: Arc<RouteTable>,
+    _backend_selector: Arc<BackendSelector>,
+    cert_store: Arc<CertStore>,
+) -> Result<()> {
+    let client = control_plane_client(config)?;
 
     let mut routes: BTreeMap<String, RouteState> = BTreeMap::new();
 
@@ -410,7 +615,18 @@ pub async fn run_route_sync_loop(
         }
     };
 
+    // Certificates are not persisted; fetch them on startup, then again
+    // after any batch containing a route.cert_* event.
+    let mut certs_dirty = true;
+
     loop {
+        if certs_dirty {
+            match sync_certificates(&client, config, &cert_store).await {
+                Ok(_) => certs_dirty = false,
+                Err(e) => warn!(error = %e, "failed to sync route certificates; retrying"),
+            }
+        }
+
         let resp = fetch_events(
             &client,
             &config.control_plane_url,
@@ -457,6 +673,11 @@ pub async fn run_route_sync_loop(
                 continue;
             };
 
+            if item.event_type.starts_with("route.cert_") {
+                certs_dirty = true;
+                continue;
+            }
+
             apply_route_event(&mut routes, item.event_id, &item.event_type, payload)?;
             routes_changed = true;
         }
@@ -485,15 +706,131 @@ pub async fn run_route_sync_loop(
     }
 }
 
-/// Sync backend instances for all routes.
-///
-/// This fetches instance lists from the control plane and updates the backend
-/// selector with healthy instances for each route.
-pub async fn sync_backends(
-    config: &Config,
-    route_table: &RouteTable,
-    backend_selector: &BackendSelector,
-) -> Result<()> {
+/// Response from the backend sync endpoint.
+#[derive(Debug, Deserialize)]
+struct BackendSyncResponse {
+    version: u32,
+    mode: BackendSyncMode,
+    cursor: i64,
+    #[serde(default)]
+    items: Vec<BackendInstance>,
+    #[serde(default)]
+    removed: Vec<String>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BackendSyncMode {
+    Full,
+    Delta,
+}
+
+/// A routable instance, as reported by the control plane.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct BackendInstance {
+    instance_id: String,
+    env_id: String,
+    process_type: String,
+    overlay_ipv6: String,
+}
+
+/// Backend sync protocol version this edge understands.
+const BACKEND_SYNC_VERSION: u32 = 1;
+
+/// Routable instances for the org, kept current with backend deltas.
+#[derive(Debug, Default)]
+struct BackendIndex {
+    instances: BTreeMap<String, BackendInstance>,
+    /// Event cursor the index reflects; `None` until the first full sync.
+    cursor: Option<i64>,
+}
+
+impl BackendIndex {
+    /// Apply one delta page. Returns whether any instance changed.
+    fn apply_delta(&mut self, resp: BackendSyncResponse) -> bool {
+        let mut changed = false;
+        for id in resp.removed {
+            changed |= self.instances.remove(&id).is_some();
+        }
+        for inst in resp.items {
+            if self.instances.get(&inst.instance_id) != Some(&inst) {
+                self.instances.insert(inst.instance_id.clone(), inst);
+                changed = true;
+            }
+        }
+        self.cursor = Some(resp.cursor);
+        changed
+    }
+
+    /// Replace the index with a full snapshot.
+    fn replace(&mut self, instances: Vec<BackendInstance>, cursor: i64) {
+        self.instances = instances
+            .into_iter()
+            .map(|inst| (inst.instance_id.clone(), inst))
+            .collect();
+        self.cursor = Some(cursor);
+    }
+
+    fn from_persisted(p: PersistedBackends) -> Self {
+        Self {
+            instances: p
+                .instances
+                .into_values()
+                .map(|inst| {
+                    let inst = BackendInstance {
+                        instance_id: inst.instance_id,
+                        env_id: inst.env_id,
+                        process_type: inst.process_type,
+                        overlay_ipv6: inst.overlay_ipv6,
+                    };
+                    (inst.instance_id.clone(), inst)
+                })
+                .collect(),
+            cursor: Some(p.cursor),
+        }
+    }
+
+    fn to_persisted(&self) -> BTreeMap<String, PersistedBackendInstance> {
+        self.instances
+            .iter()
+            .map(|(id, inst)| {
+                (
+                    id.clone(),
+                    PersistedBackendInstance {
+                        instance_id: inst.instance_id.clone(),
+                        env_id: inst.env_id.clone(),
+                        process_type: inst.process_type.clone(),
+                        overlay_ipv6: inst.overlay_ipv6.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Backends for a route: the routable instances of its env and process type.
+    fn backends_for(&self, route: &Route) -> Vec<Backend> {
+        self.instances
+            .values()
+            .filter(|inst| {
+                inst.env_id == route.env_id && inst.process_type == route.backend_process_type
+            })
+            .filter_map(|inst| {
+                let addr: Ipv6Addr = inst.overlay_ipv6.parse().ok()?;
+                Some(Backend::new(
+                    addr,
+                    route.backend_port,
+                    inst.instance_id.clone(),
+                ))
+            })
+            .collect()
+    }
+}
+
+fn control_plane_client(config: &Config) -> Result<reqwest::Client> {
     let mut headers = HeaderMap::new();
     if let Some(token) = &config.control_plane_token {
         let raw = token.expose().trim();
@@ -509,118 +846,270 @@ pub async fn sync_backends(
         );
     }
 
-    let client = reqwest::Client::builder()
+    Ok(reqwest::Client::builder()
         .user_agent("plfm-ingress/0.1.0")
         .default_headers(headers)
-        .build()?;
+        .build()?)
+}
 
-    // Get all route IDs
-    let route_ids = route_table.route_ids().await;
+async fn fetch_backend_page(
+    client: &reqwest::Client,
+    config: &Config,
+    since: Option<i64>,
+    after: Option<&str>,
+) -> Result<BackendSyncResponse> {
+    let base = config.control_plane_url.trim_end_matches('/');
+    let url = format!("{}/v1/orgs/{}/backends", base, config.org_id);
 
-    for route_id in route_ids {
-        let Some(route) = route_table.get(&route_id).await else {
-            continue;
-        };
+    let mut query: Vec<(&str, String)> = Vec::new();
+    if let Some(since) = since {
+        query.push(("since", since.to_string()));
+    }
+    if let Some(after) = after {
+        query.push(("after", after.to_string()));
+    }
 
-        // Fetch instances for this route's environment and process type
-        match fetch_route_backends(&client, config, &route).await {
-            Ok(backends) => {
-                backend_selector
-                    .update_route_backends(&route_id, backends)
-                    .await;
-            }
-            Err(e) => {
-                warn!(
-                    route_id = %route_id,
-                    error = %e,
-                    "Failed to fetch backends"
-                );
-            }
+    let resp = client.get(&url).query(&query).send().await?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("backend sync failed (status={}): {}", status, body);
+    }
+
+    let page: BackendSyncResponse = resp.json().await?;
+    if page.version != BACKEND_SYNC_VERSION {
+        anyhow::bail!(
+            "unsupported backend sync version {} (expected {})",
+            page.version,
+            BACKEND_SYNC_VERSION
+        );
+    }
+    Ok(page)
+}
+
+/// Fetch a full snapshot into the index.
+async fn full_backend_sync(
+    client: &reqwest::Client,
+    config: &Config,
+    index: &mut BackendIndex,
+) -> Result<()> {
+    let mut instances = Vec::new();
+    let mut cursor = None;
+    let mut after: Option<String> = None;
+
+    loop {
+        let page = fetch_backend_page(client, config, None, after.as_deref()).await?;
+        if page.mode != BackendSyncMode::Full {
+            anyhow::bail!("expected a full backend snapshot");
+        }
+        // Resume deltas from the first page so nothing is missed between pages.
+        cursor.get_or_insert(page.cursor);
+        instances.extend(page.items);
+        match page.next_after.filter(|_| page.has_more) {
+            Some(next) => after = Some(next),
+            None => break,
         }
     }
 
+    let cursor = cursor.unwrap_or_default();
+    info!(
+        instance_count = instances.len(),
+        cursor, "Full backend sync complete"
+    );
+    index.replace(instances, cursor);
     Ok(())
 }
 
-/// Response for listing instances.
-#[derive(Debug, Deserialize)]
-struct InstancesResponse {
-    items: Vec<InstanceItem>,
+/// Apply every delta available after the index cursor.
+async fn delta_backend_sync(
+    client: &reqwest::Client,
+    config: &Config,
+    index: &mut BackendIndex,
+) -> Result<()> {
+    loop {
+        let page = fetch_backend_page(client, config, index.cursor, None).await?;
+        if page.mode != BackendSyncMode::Delta {
+            anyhow::bail!("expected a backend delta");
+        }
+        let has_more = page.has_more;
+        let cursor = page.cursor;
+        if index.apply_delta(page) {
+            debug!(cursor, "Backend delta applied");
+        }
+        if !has_more {
+            return Ok(());
+        }
+    }
 }
 
-/// Instance item from API.
-#[derive(Debug, Deserialize)]
-struct InstanceItem {
-    id: String,
-    #[serde(default)]
-    overlay_ipv6: Option<String>,
+/// Push backends to every route whose backend set differs from what was last
+/// pushed (new routes, changed routes, or changed instances).
+async fn apply_backends(
+    index: &BackendIndex,
+    route_table: &RouteTable,
+    backend_selector: &BackendSelector,
+    pushed: &mut BTreeMap<String, Vec<Backend>>,
+) {
+    let route_ids = route_table.route_ids().await;
+    pushed.retain(|id, _| route_ids.contains(id));
+
+    for route_id in route_ids {
+        let Some(route) = route_table.get(&route_id).await else {
+            continue;
+        };
+        let backends = index.backends_for(&route);
+        if pushed.get(&route_id) == Some(&backends) {
+            continue;
+        }
+        debug!(
+            route_id = %route_id,
+            backend_count = backends.len(),
+            "Route backends changed"
+        );
+        backend_selector
+            .update_route_backends(&route_id, backends.clone())
+            .await;
+        pushed.insert(route_id, backends);
+    }
 }
 
-/// Fetch backends for a specific route.
-async fn fetch_route_backends(
-    client: &reqwest::Client,
-    config: &Config,
-    route: &Route,
-) -> Result<Vec<Backend>> {
-    let base = config.control_plane_url.trim_end_matches('/');
+/// Run the backend sync loop.
+///
+/// Starts with a full snapshot, then applies deltas since the last cursor on
+/// every tick. A full resync runs every `backend_full_resync_interval` (and
+/// after any failed delta) to guard against drift.
+///
+/// With a state file configured, the index starts from the last saved
+/// snapshot and is saved again whenever its cursor moves.
+pub async fn run_backend_sync_loop(
+    config: Config,
+    route_table: Arc<RouteTable>,
+    backend_selector: Arc<BackendSelector>,
+) -> Result<()> {
+    let client = control_plane_client(&config)?;
+    let persistence = config.state_file.clone().map(StatePersistence::new);
+    let mut index = persistence
+        .as_ref()
+        .map(load_backend_index)
+        .unwrap_or_default();
+    let mut saved_cursor = index.cursor;
+    let mut pushed: BTreeMap<String, Vec<Backend>> = BTreeMap::new();
+    let mut last_full: Option<Instant> = None;
+
+    // Keep serving the restored sets until the control plane answers.
+    if index.cursor.is_some() {
+        apply_backends(&index, &route_table, &backend_selector, &mut pushed).await;
+    }
 
-    // Fetch instances for this environment and process type
-    // API: GET /v1/orgs/{org}/apps/{app}/envs/{env}/instances?process_type={pt}&status=ready
-    let url = format!(
-        "{}/v1/orgs/{}/apps/{}/envs/{}/instances",
-        base, config.org_id, route.app_id, route.env_id
-    );
+    loop {
+        let full_due =
+            last_full.is_none_or(|at| at.elapsed() >= config.backend_full_resync_interval);
 
-    let resp = client
-        .get(&url)
-        .query(&[
-            ("process_type", route.backend_process_type.as_str()),
-            ("status", "ready"),
-            ("limit", "100"),
-        ])
-        .send()
-        .await?;
+        let result = if full_due {
+            full_backend_sync(&client, &config, &mut index).await
+        } else {
+            delta_backend_sync(&client, &config, &mut index).await
+        };
 
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        anyhow::bail!("instances query failed (status={}): {}", status, body);
+        match result {
+            Ok(()) => {
+                if full_due {
+                    last_full = Some(Instant::now());
+                }
+                // Routes may have changed even when instances did not.
+                apply_backends(&index, &route_table, &backend_selector, &mut pushed).await;
+
+                if let (Some(p), Some(cursor)) = (&persistence, index.cursor) {
+                    // A full sync may correct drift without moving the cursor.
+                    if full_due || saved_cursor != Some(cursor) {
+                        match p.save_backends(&index.to_persisted(), cursor) {
+                            Ok(()) => saved_cursor = Some(cursor),
+                            Err(e) => warn!(error = %e, "Failed to persist backends"),
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, full = full_due, "Backend sync failed");
+                // Resync in full next time rather than trust a partial delta.
+                last_full = None;
+            }
+        }
+
+        tokio::time::sleep(config.backend_sync_interval).await;
     }
+}
 
-    let instances: InstancesResponse = resp.json().await?;
+/// Status report for `PUT /v1/orgs/{org_id}/ingresses/{ingress_id}`.
+#[derive(Debug, Serialize)]
+struct IngressStatusReport {
+    public_ips: Vec<String>,
+    routes: Vec<ProgrammedRoute>,
+}
 
-    // Convert to backends
-    let backends: Vec<Backend> = instances
-        .items
-        .into_iter()
-        .filter_map(|inst| {
-            let overlay_ipv6 = inst.overlay_ipv6.as_ref()?;
-            let addr: Ipv6Addr = overlay_ipv6.parse().ok()?;
-            Some(Backend::new(addr, route.backend_port, inst.id))
-        })
-        .collect();
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct ProgrammedRoute {
+    route_id: String,
+    backend_count: i32,
+}
 
-    debug!(
-        route_id = %route.id,
-        backend_count = backends.len(),
-        "Fetched backends"
-    );
+/// Routes in the route table, with the number of backends programmed for each.
+async fn programmed_routes(
+    route_table: &RouteTable,
+    backend_selector: &BackendSelector,
+) -> Vec<ProgrammedRoute> {
+    let mut route_ids = route_table.route_ids().await;
+    route_ids.sort();
 
-    Ok(backends)
+    let mut routes = Vec::new();
+    for route_id in route_ids {
+        let backend_count = match backend_selector.get_pool(&route_id).await {
+            Some(pool) => pool.len().await as i32,
+            None => 0,
+        };
+        routes.push(ProgrammedRoute {
+            route_id,
+            backend_count,
+        });
+    }
+    routes
 }
 
-/// Run periodic backend sync loop.
-pub async fn run_backend_sync_loop(
+/// Report programmed routes and public IPs to the control plane, which
+/// aggregates them into each route's ingress status.
+pub async fn run_status_report_loop(
     config: Config,
     route_table: Arc<RouteTable>,
     backend_selector: Arc<BackendSelector>,
 ) -> Result<()> {
+    let client = control_plane_client(&config)?;
+    let base = config.control_plane_url.trim_end_matches('/');
+    let url = format!(
+        "{}/v1/orgs/{}/ingresses/{}",
+        base, config.org_id, config.ingress_id
+    );
+    let public_ips: Vec<String> = config.public_ips.iter().map(|ip| ip.to_string()).collect();
+
     loop {
-        if let Err(e) = sync_backends(&config, &route_table, &backend_selector).await {
-            warn!(error = %e, "Backend sync failed");
+        let report = IngressStatusReport {
+            public_ips: public_ips.clone(),
+            routes: programmed_routes(&route_table, &backend_selector).await,
+        };
+        let route_count = report.routes.len();
+
+        match client.put(&url).json(&report).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                debug!(route_count, "Ingress status reported");
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                warn!(%status, body = %body, "Ingress status report rejected");
+            }
+            Err(e) => warn!(error = %e, "Failed to report ingress status"),
         }
 
-        tokio::time::sleep(config.backend_sync_interval).await;
+        tokio::time::sleep(config.status_report_interval).await;
     }
 }
 
@@ -644,6 +1133,10 @@ mod tests {
             backend_expects_proxy_protocol: false,
             ipv4_required: false,
             env_ipv4_address: None,
+            active: true,
+            internal: false,
+            access_policy: None,
+            deleted: false,
         };
 
         let payload = RouteUpdatedPayload {
@@ -656,6 +1149,7 @@ mod tests {
             backend_expects_proxy_protocol: Some(true),
             ipv4_required: None,
             env_ipv4_address: None,
+            access_policy: None,
         };
 
         let changed = state.apply_update(payload);
@@ -674,4 +1168,279 @@ mod tests {
         assert!(state.backend_expects_proxy_protocol);
         assert!(!state.ipv4_required);
     }
+
+    #[test]
+    fn test_unverified_route_activates_on_verification() {
+        let route_id = RouteId::new();
+        let org_id = OrgId::new();
+        let env_id = EnvId::new();
+        let created = serde_json::json!({
+            "route_id": route_id,
+            "org_id": org_id,
+            "app_id": plfm_id::AppId::new(),
+            "env_id": env_id,
+            "hostname": "api.example.com",
+            "listen_port": 443,
+            "protocol_hint": "tls_passthrough",
+            "backend_process_type": "web",
+            "backend_port": 8080,
+            "proxy_protocol": "off",
+            "backend_expects_proxy_protocol": false,
+            "ipv4_required": false,
+            "verification_token": "0123456789abcdef"
+        });
+
+        let mut routes = BTreeMap::new();
+        apply_route_event(&mut routes, 1, "route.created", created).unwrap();
+        assert!(!routes[&route_id.to_string()].active);
+
+        let verified = serde_json::json!({
+            "route_id": route_id,
+            "org_id": org_id,
+            "env_id": env_id,
+            "hostname": "api.example.com",
+            "method": "txt"
+        });
+        apply_route_event(&mut routes, 2, "route.verification_succeeded", verified).unwrap();
+        assert!(routes[&route_id.to_string()].active);
+    }
+
+    #[test]
+    fn test_internal_route_is_served_by_internal_listeners() {
+        let route_id = RouteId::new();
+        let created = serde_json::json!({
+            "route_id": route_id,
+            "org_id": OrgId::new(),
+            "app_id": plfm_id::AppId::new(),
+            "env_id": EnvId::new(),
+            "hostname": "api.billing.internal",
+            "listen_port": 443,
+            "protocol_hint": "tls_passthrough",
+            "backend_process_type": "web",
+            "backend_port": 8080,
+            "proxy_protocol": "off",
+            "backend_expects_proxy_protocol": false,
+            "ipv4_required": false,
+            "internal": true
+        });
+
+        let mut routes = BTreeMap::new();
+        apply_route_event(&mut routes, 1, "route.created", created).unwrap();
+        let state = &routes[&route_id.to_string()];
+        assert!(state.active);
+        assert!(state.internal);
+        assert!(route_state_to_proxy_route(state).internal);
+        assert!(RouteState::from_persisted(&state.to_persisted()).internal);
+    }
+
+    #[test]
+    fn test_deleted_route_is_kept_until_purged() {
+        let route_id = RouteId::new();
+        let org_id = OrgId::new();
+        let env_id = EnvId::new();
+        let created = serde_json::json!({
+            "route_id": route_id,
+            "org_id": org_id,
+            "app_id": plfm_id::AppId::new(),
+            "env_id": env_id,
+            "hostname": "api.example.com",
+            "listen_port": 443,
+            "protocol_hint": "tls_passthrough",
+            "backend_process_type": "web",
+            "backend_port": 8080,
+            "proxy_protocol": "off",
+            "backend_expects_proxy_protocol": false,
+            "ipv4_required": false
+        });
+        let tombstone = serde_json::json!({
+            "route_id": route_id,
+            "org_id": org_id,
+            "env_id": env_id,
+            "hostname": "api.example.com"
+        });
+        let id = route_id.to_string();
+
+        let mut routes = BTreeMap::new();
+        apply_route_event(&mut routes, 1, "route.created", created).unwrap();
+        apply_route_event(&mut routes, 2, "route.deleted", tombstone.clone()).unwrap();
+        assert!(routes[&id].deleted);
+        assert!(RouteState::from_persisted(&routes[&id].to_persisted()).deleted);
+
+        apply_route_event(&mut routes, 3, "route.restored", tombstone.clone()).unwrap();
+        assert!(!routes[&id].deleted);
+        assert!(routes[&id].active);
+
+        apply_route_event(&mut routes, 4, "route.deleted", tombstone.clone()).unwrap();
+        apply_route_event(&mut routes, 5, "route.purged", tombstone).unwrap();
+        assert!(routes.is_empty());
+    }
+
+    fn backend_instance(id: &str, env_id: &str, process_type: &str, ip: &str) -> BackendInstance {
+        BackendInstance {
+            instance_id: id.to_string(),
+            env_id: env_id.to_string(),
+            process_type: process_type.to_string(),
+            overlay_ipv6: ip.to_string(),
+        }
+    }
+
+    fn delta(cursor: i64, items: Vec<BackendInstance>, removed: &[&str]) -> BackendSyncResponse {
+        BackendSyncResponse {
+            version: BACKEND_SYNC_VERSION,
+            mode: BackendSyncMode::Delta,
+            cursor,
+            items,
+            removed: removed.iter().map(|id| id.to_string()).collect(),
+            has_more: false,
+            next_after: None,
+        }
+    }
+
+    #[test]
+    fn test_backend_index_applies_deltas() {
+        let mut index = BackendIndex::default();
+        index.replace(
+            vec![
+                backend_instance("inst_a", "env_1", "web", "fd00::a"),
+                backend_instance("inst_b", "env_1", "web", "fd00::b"),
+                backend_instance("inst_w", "env_1", "worker", "fd00::c"),
+            ],
+            10,
+        );
+        assert_eq!(index.cursor, Some(10));
+
+        // Re-listing an unchanged instance is not a change.
+        assert!(!index.apply_delta(delta(
+            12,
+            vec![backend_instance("inst_a", "env_1", "web", "fd00::a")],
+            &[]
+        )));
+        assert_eq!(index.cursor, Some(12));
+
+        assert!(index.apply_delta(delta(
+            15,
+            vec![backend_instance("inst_d", "env_1", "web", "fd00::d")],
+            &["inst_b", "inst_unknown"]
+        )));
+
+        let route = route_state_to_proxy_route(&RouteState {
+            route_id: "route_1".to_string(),
+            hostname: "example.invalid".to_string(),
+            listen_port: 443,
+            app_id: "app_1".to_string(),
+            env_id: "env_1".to_string(),
+            backend_process_type: "web".to_string(),
+            backend_port: 8080,
+            protocol_hint: RouteProtocolHint::TlsPassthrough,
+            proxy_protocol: RouteProxyProtocol::Off,
+            backend_expects_proxy_protocol: false,
+            ipv4_required: false,
+            env_ipv4_address: None,
+            active: true,
+            internal: false,
+            access_policy: None,
+            deleted: false,
+        });
+        let backends = index.backends_for(&route);
+        let ids: Vec<&str> = backends.iter().map(|b| b.instance_id.as_str()).collect();
+        assert_eq!(ids, vec!["inst_a", "inst_d"]);
+        assert!(backends.iter().all(|b| b.port == 8080));
+    }
+
+    #[test]
+    fn test_backend_index_persisted_roundtrip() {
+        let mut index = BackendIndex::default();
+        index.replace(
+            vec![
+                backend_instance("inst_a", "env_1", "web", "fd00::a"),
+                backend_instance("inst_w", "env_1", "worker", "fd00::c"),
+            ],
+            42,
+        );
+
+        let restored = BackendIndex::from_persisted(PersistedBackends {
+            version: 1,
+            cursor: 42,
+            instances: index.to_persisted(),
+        });
+        assert_eq!(restored.cursor, Some(42));
+        assert_eq!(restored.instances, index.instances);
+    }
+
+    #[tokio::test]
+    async fn test_programmed_routes_counts_backends() {
+        let route_table = RouteTable::new();
+        let backend_selector = BackendSelector::new();
+        let mut route = route_state_to_proxy_route(&RouteState {
+            route_id: "route_1".to_string(),
+            hostname: "a.example.invalid".to_string(),
+            listen_port: 443,
+            app_id: "app_1".to_string(),
+            env_id: "env_1".to_string(),
+            backend_process_type: "web".to_string(),
+            backend_port: 8080,
+            protocol_hint: RouteProtocolHint::TlsPassthrough,
+            proxy_protocol: RouteProxyProtocol::Off,
+            backend_expects_proxy_protocol: false,
+            ipv4_required: false,
+            env_ipv4_address: None,
+            active: true,
+            internal: false,
+            access_policy: None,
+            deleted: false,
+        });
+        route_table.upsert(route.clone()).await;
+        route.id = "route_2".to_string();
+        route.hostname = "b.example.invalid".to_string();
+        route_table.upsert(route).await;
+
+        backend_selector
+            .update_route_backends(
+                "route_1",
+                vec![Backend::new(
+                    "fd00::a".parse().unwrap(),
+                    8080,
+                    "inst_a".to_string(),
+                )],
+            )
+            .await;
+
+        let routes = programmed_routes(&route_table, &backend_selector).await;
+        assert_eq!(
+            routes,
+            vec![
+                ProgrammedRoute {
+                    route_id: "route_1".to_string(),
+                    backend_count: 1,
+                },
+                ProgrammedRoute {
+                    route_id: "route_2".to_string(),
+                    backend_count: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backend_sync_response_parses() {
+        let json = serde_json::json!({
+            "version": 1,
+            "mode": "full",
+            "cursor": 7,
+            "items": [{
+                "instance_id": "inst_a",
+                "app_id": "app_1",
+                "env_id": "env_1",
+                "process_type": "web",
+                "overlay_ipv6": "fd00::a"
+            }],
+            "removed": [],
+            "has_more": true,
+            "next_after": "inst_a"
+        });
+        let resp: BackendSyncResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(resp.mode, BackendSyncMode::Full);
+        assert_eq!(resp.next_after.as_deref(), Some("inst_a"));
+        assert_eq!(resp.items.len(), 1);
+    }
 }
@@ -0,0 +1,119 @@
+mod harness;
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use harness::{make_backend, make_route, IngressHandle, TcpEchoBackend};
+use plfm_ingress::{ListenerConfig, ProtocolHint};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+async fn echo(stream: &mut TcpStream, payload: &[u8]) -> std::io::Result<Vec<u8>> {
+    stream.write_all(payload).await?;
+    stream.flush().await?;
+    let mut buf = vec![0u8; 64];
+    let n = stream.read(&mut buf).await?;
+    Ok(buf[..n].to_vec())
+}
+
+async fn spawn_raw_ingress(config: ListenerConfig) -> (IngressHandle, TcpEchoBackend) {
+    let backend = TcpEchoBackend::spawn_v6().await.unwrap();
+    let ingress = IngressHandle::spawn_v6_with_config(config).await.unwrap();
+
+    let mut route = make_route(
+        "r-limits",
+        "limits.example.test",
+        ingress.listen_addr.port(),
+        ProtocolHint::TcpRaw,
+        backend.addr.port(),
+    );
+    route.allow_non_tls_fallback = true;
+    ingress.add_route(route).await;
+    ingress
+        .add_backend("r-limits", make_backend(backend.addr, "inst-limits"))
+        .await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    (ingress, backend)
+}
+
+#[tokio::test]
+async fn per_ip_cap_rejects_excess_connections() {
+    let mut config = ListenerConfig::new("[::1]:0".parse().unwrap());
+    config.max_connections_per_ip = Some(1);
+    let (ingress, _backend) = spawn_raw_ingress(config).await;
+
+    let mut first = TcpStream::connect(ingress.listen_addr).await.unwrap();
+    let data = timeout(TEST_TIMEOUT, echo(&mut first, b"one"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(data, b"one");
+
+    // A second concurrent connection from the same client is closed.
+    let mut second = TcpStream::connect(ingress.listen_addr).await.unwrap();
+    let mut buf = [0u8; 8];
+    let n = timeout(TEST_TIMEOUT, second.read(&mut buf))
+        .await
+        .unwrap()
+        .unwrap_or(0);
+    assert_eq!(n, 0, "connection over the per-IP cap should be closed");
+    assert_eq!(
+        ingress
+            .listener
+            .stats()
+            .rejected_per_ip
+            .load(Ordering::Relaxed),
+        1
+    );
+
+    // Closing the first connection frees the slot.
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut third = TcpStream::connect(ingress.listen_addr).await.unwrap();
+    let data = timeout(TEST_TIMEOUT, echo(&mut third, b"three"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(data, b"three");
+}
+
+#[tokio::test]
+async fn silent_tls_client_times_out_sniffing() {
+    let mut config = ListenerConfig::new("[::1]:0".parse().unwrap());
+    config.sni_config.timeout = Duration::from_millis(50);
+    let backend = TcpEchoBackend::spawn_v6().await.unwrap();
+    let ingress = IngressHandle::spawn_v6_with_config(config).await.unwrap();
+    let route = make_route(
+        "r-tls",
+        "tls.example.test",
+        ingress.listen_addr.port(),
+        ProtocolHint::TlsPassthrough,
+        backend.addr.port(),
+    );
+    ingress.add_route(route).await;
+    ingress
+        .add_backend("r-tls", make_backend(backend.addr, "inst-tls"))
+        .await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Connect and send nothing, like a slow-loris client.
+    let mut stream = TcpStream::connect(ingress.listen_addr).await.unwrap();
+    let mut buf = [0u8; 8];
+    let n = timeout(TEST_TIMEOUT, stream.read(&mut buf))
+        .await
+        .expect("ingress should close a client that never sends a ClientHello")
+        .unwrap_or(0);
+    assert_eq!(n, 0);
+    assert_eq!(
+        ingress
+            .listener
+            .stats()
+            .client_hello_timeouts
+            .load(Ordering::Relaxed),
+        1
+    );
+}
@@ -28,8 +28,8 @@ use tokio::sync::oneshot;
 use tokio_rustls::{TlsAcceptor, TlsConnector};
 
 use plfm_ingress::{
-    Backend, BackendSelector, Listener, ListenerConfig, ProtocolHint, ProxyProtocol, Route,
-    RouteTable,
+    AccessPolicy, Backend, BackendSelector, Listener, ListenerConfig, ProtocolHint, ProxyProtocol,
+    Route, RouteTable,
 };
 
 #[allow(dead_code)]
@@ -370,14 +370,19 @@ pub struct IngressHandle {
     pub listen_addr: SocketAddr,
     pub route_table: Arc<RouteTable>,
     pub backend_selector: Arc<BackendSelector>,
+    #[allow(dead_code)]
+    pub listener: Arc<Listener>,
 }
 
 impl IngressHandle {
     pub async fn spawn_v6() -> io::Result<Self> {
+        Self::spawn_v6_with_config(ListenerConfig::new("[::1]:0".parse().unwrap())).await
+    }
+
+    pub async fn spawn_v6_with_config(config: ListenerConfig) -> io::Result<Self> {
         let route_table = Arc::new(RouteTable::new());
         let backend_selector = Arc::new(BackendSelector::new());
 
-        let config = ListenerConfig::new("[::1]:0".parse().unwrap());
         let listener = Listener::bind(
             config,
             Arc::clone(&route_table),
@@ -388,8 +393,9 @@ impl IngressHandle {
         let listen_addr = listener.local_addr()?;
         let listener = Arc::new(listener);
 
+        let running = Arc::clone(&listener);
         tokio::spawn(async move {
-            let _ = listener.run().await;
+            let _ = running.run().await;
         });
 
         tokio::time::sleep(Duration::from_millis(10)).await;
@@ -398,6 +404,7 @@ impl IngressHandle {
             listen_addr,
             route_table,
             backend_selector,
+            listener,
         })
     }
 
@@ -431,6 +438,8 @@ pub fn make_route(
         backend_port,
         allow_non_tls_fallback: false,
         env_ipv4_address: None,
+        internal: false,
+        access: AccessPolicy::default(),
     }
 }
 
@@ -12,6 +12,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use serde::Serialize;
 use tokio::sync::oneshot;
 use tracing::{debug, info, warn};
 
@@ -37,6 +38,11 @@ pub enum ImageMessage {
 
     /// Periodic garbage collection check.
     GCCheck { tick_id: u64 },
+
+    /// Report cached images and in-progress pulls (node-local API).
+    Snapshot {
+        reply_to: oneshot::Sender<ImageCacheSnapshot>,
+    },
 }
 
 /// Result of a successful image pull.
@@ -52,6 +58,28 @@ pub struct ImagePullResult {
     pub size_bytes: u64,
 }
 
+/// Cached images and in-progress pulls, as reported to the node-local API.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageCacheSnapshot {
+    pub images: Vec<CachedImage>,
+    /// Digests currently being pulled.
+    pub pulls_in_progress: Vec<String>,
+    pub cache_bytes: u64,
+    pub max_cache_bytes: u64,
+}
+
+/// One cached image in an [`ImageCacheSnapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedImage {
+    pub digest: String,
+    pub root_disk_path: String,
+    pub size_bytes: u64,
+    /// Instances referencing the image.
+    pub refs: Vec<String>,
+    /// Seconds since the image was last used.
+    pub idle_secs: u64,
+}
+
 // =============================================================================
 // Actor State
 // =============================================================================
@@ -177,6 +205,37 @@ impl ImagePullActor {
         self.current_cache_bytes
     }
 
+    /// Snapshot of the cache, sorted by digest.
+    pub fn snapshot(&self) -> ImageCacheSnapshot {
+        let now = Instant::now();
+        let mut images: Vec<CachedImage> = self
+            .cache
+            .values()
+            .map(|entry| {
+                let mut refs: Vec<String> = entry.refs.iter().cloned().collect();
+                refs.sort();
+                CachedImage {
+                    digest: entry.digest.clone(),
+                    root_disk_path: entry.root_disk_path.clone(),
+                    size_bytes: entry.size_bytes,
+                    refs,
+                    idle_secs: now.duration_since(entry.last_used_at).as_secs(),
+                }
+            })
+            .collect();
+        images.sort_by(|a, b| a.digest.cmp(&b.digest));
+
+        let mut pulls_in_progress: Vec<String> = self.in_progress.keys().cloned().collect();
+        pulls_in_progress.sort();
+
+        ImageCacheSnapshot {
+            images,
+            pulls_in_progress,
+            cache_bytes: self.current_cache_bytes,
+            max_cache_bytes: self.max_cache_bytes,
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Message Handlers
     // -------------------------------------------------------------------------
@@ -435,6 +494,10 @@ impl Actor for ImagePullActor {
             ImageMessage::GCCheck { tick_id } => {
                 self.handle_gc_check(tick_id);
             }
+
+            ImageMessage::Snapshot { reply_to } => {
+                let _ = reply_to.send(self.snapshot());
+            }
         }
 
         Ok(true)
@@ -502,6 +565,30 @@ mod tests {
         assert_eq!(actor.cache_size_bytes(), 0);
     }
 
+    #[test]
+    fn test_snapshot_sorted() {
+        let mut actor = ImagePullActor::new("/var/lib/images".to_string(), 1024);
+        for digest in ["sha256:bbb", "sha256:aaa"] {
+            actor.cache.insert(
+                digest.to_string(),
+                ImageCacheEntry {
+                    digest: digest.to_string(),
+                    root_disk_path: format!("/var/lib/images/{digest}.ext4"),
+                    size_bytes: 100,
+                    pulled_at: Instant::now(),
+                    last_used_at: Instant::now(),
+                    refs: HashSet::from(["inst-2".to_string(), "inst-1".to_string()]),
+                },
+            );
+        }
+
+        let snapshot = actor.snapshot();
+        let digests: Vec<&str> = snapshot.images.iter().map(|i| i.digest.as_str()).collect();
+        assert_eq!(digests, vec!["sha256:aaa", "sha256:bbb"]);
+        assert_eq!(snapshot.images[0].refs, vec!["inst-1", "inst-2"]);
+        assert_eq!(snapshot.max_cache_bytes, 1024);
+    }
+
     #[tokio::test]
     async fn test_image_pull_result() {
         let result = ImagePullResult {
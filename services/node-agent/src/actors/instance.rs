@@ -21,15 +21,17 @@ use async_trait::async_trait;
 use tracing::{debug, error, info, warn};
 
 use super::framework::{Actor, ActorContext, ActorError};
-use crate::client::InstancePlan;
+use crate::boot_failure::{classify_boot_timeout, classify_guest_reason, classify_start_error};
+use crate::client::{FailureReason, InstancePlan};
 use crate::exec::{
     EndReason, ExecRequest, ExecService, ExecSession, ExecSessionManager, ExecSessionState,
 };
-use crate::runtime::{Runtime, VmHandle};
-use crate::state::StateStore;
+use crate::runtime::{RecoveredVm, Runtime, VmHandle};
+use crate::state::{InstancePhase as StoredPhase, InstanceRecord, StateStore};
 
 const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
 const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const BOOT_TIMEOUT: Duration = Duration::from_secs(60);
 
 // =============================================================================
 // Messages
@@ -132,6 +134,9 @@ pub struct InstanceActorState {
 
     /// Error message if failed.
     pub error_message: Option<String>,
+
+    /// Classified failure reason if failed.
+    pub failure_reason: Option<FailureReason>,
 }
 
 impl InstanceActorState {
@@ -149,6 +154,7 @@ impl InstanceActorState {
             last_health_check_at: None,
             drain_started_at: None,
             error_message: None,
+            failure_reason: None,
         }
     }
 }
@@ -166,6 +172,8 @@ pub struct InstanceActor<R: Runtime + Send + Sync + 'static> {
     vm_handle: Option<VmHandle>,
     current_spec: Option<InstancePlan>,
     exec_session_manager: Arc<ExecSessionManager>,
+    /// Whether the state store was checked for a VM to re-adopt.
+    adoption_checked: bool,
 }
 
 impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
@@ -182,6 +190,7 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
             vm_handle: None,
             current_spec: None,
             exec_session_manager: Arc::new(ExecSessionManager::new()),
+            adoption_checked: false,
         }
     }
 
@@ -198,6 +207,7 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
             vm_handle: None,
             current_spec: None,
             exec_session_manager: Arc::new(ExecSessionManager::new()),
+            adoption_checked: false,
         }
     }
 
@@ -243,7 +253,21 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
         match (self.state.phase, desired_state) {
             // Start from preparing/failed
             (InstancePhase::Preparing | InstancePhase::Failed, DesiredInstanceState::Running) => {
-                self.start_instance(&spec).await?;
+                let exhausted = self
+                    .with_store(|store| store.retries().is_exhausted(&self.instance_id))
+                    .unwrap_or(false);
+                if exhausted {
+                    warn!(
+                        instance_id = %self.instance_id,
+                        "Retry budget exhausted, not starting instance"
+                    );
+                    self.transition_to_failed(
+                        Some(FailureReason::CrashLoopBackoff),
+                        "retry budget exhausted".to_string(),
+                    );
+                } else if !self.try_adopt(&spec).await {
+                    self.start_instance(&spec).await?;
+                }
             }
 
             // Already running, check for spec changes
@@ -309,7 +333,7 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                 }
 
                 let Some(handle) = &self.vm_handle else {
-                    self.transition_to_failed("Missing VM handle".to_string());
+                    self.transition_to_failed(None, "Missing VM handle".to_string());
                     return Ok(());
                 };
 
@@ -319,7 +343,10 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                     }
                     Ok(false) => {
                         warn!(instance_id = %self.instance_id, "Health check failed");
-                        self.transition_to_failed("Health check failed".to_string());
+                        self.transition_to_failed(
+                            Some(FailureReason::HealthcheckFailed),
+                            "Health check failed".to_string(),
+                        );
                     }
                     Err(e) => {
                         warn!(
@@ -344,19 +371,19 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
             }
 
             InstancePhase::Booting => {
-                if let Some(started) = self.state.boot_started_at {
-                    if started.elapsed() > std::time::Duration::from_secs(60) {
+                let Some(handle) = &self.vm_handle else {
+                    if self
+                        .state
+                        .boot_started_at
+                        .is_some_and(|started| started.elapsed() > BOOT_TIMEOUT)
+                    {
                         warn!(instance_id = %self.instance_id, "Boot timeout");
-                        self.transition_to_failed("Boot timeout".to_string());
-                        return Ok(());
+                        self.transition_to_failed(None, "Boot timeout".to_string());
                     }
-                }
-
-                let Some(handle) = &self.vm_handle else {
                     return Ok(());
                 };
 
-                let boot_state = {
+                let boot_record = {
                     let store = match self.state_store.lock() {
                         Ok(s) => s,
                         Err(e) => {
@@ -368,10 +395,29 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                         .get_boot_status(&self.instance_id, &handle.boot_id)
                         .ok()
                         .flatten()
-                        .map(|r| r.state)
                 };
 
-                if let Some(state) = boot_state {
+                if self
+                    .state
+                    .boot_started_at
+                    .is_some_and(|started| started.elapsed() > BOOT_TIMEOUT)
+                    && !boot_record.as_ref().is_some_and(|r| r.state == "ready")
+                {
+                    let reason = classify_boot_timeout(
+                        boot_record.as_ref().map(|r| r.state.as_str()),
+                        self.runtime.boot_failure_hint(handle),
+                    );
+                    warn!(
+                        instance_id = %self.instance_id,
+                        reason_code = ?reason,
+                        "Boot timeout"
+                    );
+                    self.transition_to_failed(Some(reason), "Boot timeout".to_string());
+                    return Ok(());
+                }
+
+                if let Some(record) = boot_record {
+                    let state = record.state;
                     match state.as_str() {
                         "ready" => {
                             let boot_duration = self.state.boot_started_at.map(|t| t.elapsed());
@@ -382,10 +428,27 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                             );
                             self.state.phase = InstancePhase::Ready;
                             self.state.last_health_check_at = Some(Instant::now());
+                            self.with_store(|store| store.retries().clear(&self.instance_id));
                         }
                         "failed" | "exited" => {
-                            warn!(instance_id = %self.instance_id, boot_state = %state, "Guest-init failed");
-                            self.transition_to_failed(format!("Guest-init {state}"));
+                            let reason = if state == "failed" {
+                                classify_guest_reason(record.reason.as_deref())
+                            } else {
+                                self.runtime
+                                    .boot_failure_hint(handle)
+                                    .unwrap_or(FailureReason::GuestInitFailed)
+                            };
+                            warn!(
+                                instance_id = %self.instance_id,
+                                boot_state = %state,
+                                reason_code = ?reason,
+                                "Guest-init failed"
+                            );
+                            let message = match record.detail {
+                                Some(detail) => format!("Guest-init {state}: {detail}"),
+                                None => format!("Guest-init {state}"),
+                            };
+                            self.transition_to_failed(Some(reason), message);
                         }
                         _ => {}
                     }
@@ -434,6 +497,157 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
         Ok(())
     }
 
+    /// Handle one message; returns `false` when the actor should stop.
+    async fn dispatch(&mut self, msg: InstanceMessage) -> Result<bool, ActorError> {
+        match msg {
+            InstanceMessage::ApplyDesired {
+                spec_revision,
+                spec,
+                desired_state,
+            } => {
+                self.handle_apply_desired(spec_revision, *spec, desired_state)
+                    .await?;
+            }
+
+            InstanceMessage::Tick { tick_id } => {
+                self.handle_tick(tick_id).await?;
+            }
+
+            InstanceMessage::ExecRequest {
+                session_id,
+                command,
+                grant_token: _,
+            } => {
+                self.handle_exec_request(session_id, command).await?;
+            }
+
+            InstanceMessage::Stop { reason } => {
+                self.handle_stop(reason).await?;
+                return Ok(false); // Signal actor to stop
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Take over a VM left running by a previous agent process (recorded in
+    /// the state store, possibly restored from an export). Only tried once,
+    /// on the first start of the actor.
+    async fn try_adopt(&mut self, spec: &InstancePlan) -> bool {
+        if self.adoption_checked || self.vm_handle.is_some() {
+            return false;
+        }
+        self.adoption_checked = true;
+
+        let Some(record) = self
+            .with_store(|store| store.get_instance(&self.instance_id))
+            .flatten()
+        else {
+            return false;
+        };
+        let Some(guest_cid) = record.guest_cid else {
+            return false;
+        };
+        if !matches!(record.phase, StoredPhase::Starting | StoredPhase::Running) {
+            return false;
+        }
+
+        let vm = RecoveredVm {
+            instance_id: self.instance_id.clone(),
+            boot_id: record.boot_id.clone(),
+            guest_cid,
+        };
+        let handle = match self.runtime.adopt_vm(spec, &vm).await {
+            Ok(Some(handle)) => handle,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!(instance_id = %self.instance_id, error = %e, "VM adoption failed");
+                return false;
+            }
+        };
+
+        info!(
+            instance_id = %self.instance_id,
+            boot_id = %handle.boot_id,
+            "Re-adopted VM from previous agent process"
+        );
+        self.vm_handle = Some(handle);
+        if record.phase == StoredPhase::Running {
+            self.state.phase = InstancePhase::Ready;
+            // Health-check on the next tick.
+            self.state.last_health_check_at = None;
+        } else {
+            self.state.phase = InstancePhase::Booting;
+            self.state.boot_started_at = Some(Instant::now());
+        }
+
+        // The image changed while the agent was down: replace the VM.
+        if record.rootdisk_digest.as_deref() != Some(spec.image.resolved_digest.as_str()) {
+            info!(
+                instance_id = %self.instance_id,
+                "Adopted VM runs an outdated image, restarting"
+            );
+            let _ = self.stop_instance(StopReason::ReleaseUpdate).await;
+            return false;
+        }
+
+        true
+    }
+
+    /// Record the instance in the state store so a restarted agent can
+    /// re-adopt its VM.
+    fn persist(&self) {
+        let phase = match self.state.phase {
+            InstancePhase::Preparing => StoredPhase::Creating,
+            InstancePhase::Booting => StoredPhase::Starting,
+            InstancePhase::Ready => StoredPhase::Running,
+            InstancePhase::Draining => StoredPhase::Stopping,
+            InstancePhase::Stopped => StoredPhase::Stopped,
+            InstancePhase::Failed => StoredPhase::Failed,
+        };
+        self.with_store(|store| match &self.vm_handle {
+            Some(handle) => {
+                let now = chrono::Utc::now().timestamp();
+                store.upsert_instance(&InstanceRecord {
+                    instance_id: self.instance_id.clone(),
+                    phase,
+                    spec_revision: self.state.last_applied_spec_revision as i64,
+                    boot_id: handle.boot_id.clone(),
+                    socket_path: None,
+                    rootdisk_digest: self
+                        .current_spec
+                        .as_ref()
+                        .map(|spec| spec.image.resolved_digest.clone()),
+                    guest_cid: Some(handle.guest_cid),
+                    created_at: now,
+                    updated_at: now,
+                })
+            }
+            None if phase == StoredPhase::Stopped => store.delete_instance(&self.instance_id),
+            None => store.set_instance_phase(&self.instance_id, phase),
+        });
+    }
+
+    fn with_store<T>(
+        &self,
+        f: impl FnOnce(&StateStore) -> Result<T, crate::state::StateStoreError>,
+    ) -> Option<T> {
+        let store = match self.state_store.lock() {
+            Ok(store) => store,
+            Err(e) => {
+                warn!(error = %e, "Failed to acquire state store lock");
+                return None;
+            }
+        };
+        match f(&store) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!(instance_id = %self.instance_id, error = %e, "State store error");
+                None
+            }
+        }
+    }
+
     async fn start_instance(&mut self, spec: &InstancePlan) -> Result<(), ActorError> {
         let image_label = spec
             .image
@@ -454,7 +668,10 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                 error = %e,
                 "Failed to prepare resources"
             );
-            self.transition_to_failed(format!("resource preparation failed: {}", e));
+            self.transition_to_failed(
+                Some(FailureReason::RootfsBuildFailed),
+                format!("resource preparation failed: {}", e),
+            );
             return Err(ActorError::Transient(e.to_string()));
         }
 
@@ -469,6 +686,9 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                     boot_id = %handle.boot_id,
                     "VM started, waiting for guest-init ready"
                 );
+                // The boot timeout covers the guest boot, not time spent
+                // pulling the image or queued for a boot slot.
+                self.state.boot_started_at = Some(Instant::now());
                 self.vm_handle = Some(handle);
                 Ok(())
             }
@@ -478,7 +698,7 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
                     error = %e,
                     "Failed to start instance"
                 );
-                self.transition_to_failed(e.to_string());
+                self.transition_to_failed(Some(classify_start_error(&e)), e.to_string());
                 Err(ActorError::Transient(e.to_string()))
             }
         }
@@ -527,11 +747,21 @@ impl<R: Runtime + Send + Sync + 'static> InstanceActor<R> {
         }
     }
 
-    fn transition_to_failed(&mut self, error_message: String) {
+    fn transition_to_failed(&mut self, reason: Option<FailureReason>, error_message: String) {
         self.state.phase = InstancePhase::Failed;
+        self.state.failure_reason = reason;
         self.state.error_message = Some(error_message);
         self.state.drain_started_at = None;
         self.vm_handle = None;
+
+        // Count the failure against the instance's retry budget.
+        let exhausted = self.with_store(|store| store.retries().record_failure(&self.instance_id));
+        if exhausted == Some(true) {
+            warn!(
+                instance_id = %self.instance_id,
+                "Instance exhausted its retry budget"
+            );
+        }
     }
 
     async fn handle_exec_request(
@@ -658,35 +888,19 @@ impl<R: Runtime + Send + Sync + 'static> Actor for InstanceActor<R> {
         msg: InstanceMessage,
         _ctx: &mut ActorContext,
     ) -> Result<bool, ActorError> {
-        match msg {
-            InstanceMessage::ApplyDesired {
-                spec_revision,
-                spec,
-                desired_state,
-            } => {
-                self.handle_apply_desired(spec_revision, *spec, desired_state)
-                    .await?;
-            }
-
-            InstanceMessage::Tick { tick_id } => {
-                self.handle_tick(tick_id).await?;
-            }
-
-            InstanceMessage::ExecRequest {
-                session_id,
-                command,
-                grant_token: _,
-            } => {
-                self.handle_exec_request(session_id, command).await?;
-            }
-
-            InstanceMessage::Stop { reason } => {
-                self.handle_stop(reason).await?;
-                return Ok(false); // Signal actor to stop
-            }
+        let before = (
+            self.state.phase,
+            self.vm_handle.as_ref().map(|h| h.boot_id.clone()),
+        );
+        let result = self.dispatch(msg).await;
+        let after = (
+            self.state.phase,
+            self.vm_handle.as_ref().map(|h| h.boot_id.clone()),
+        );
+        if before != after {
+            self.persist();
         }
-
-        Ok(true)
+        result
     }
 
     async fn on_start(&mut self, _ctx: &mut ActorContext) -> Result<(), ActorError> {
@@ -699,7 +913,7 @@ impl<R: Runtime + Send + Sync + 'static> Actor for InstanceActor<R> {
         // Recovery: check if VM is still running
         if self.state.phase == InstancePhase::Ready || self.state.phase == InstancePhase::Booting {
             if self.vm_handle.is_none() {
-                self.transition_to_failed("Missing VM handle on restart".to_string());
+                self.transition_to_failed(None, "Missing VM handle on restart".to_string());
             }
 
             info!(
@@ -866,5 +1080,175 @@ mod tests {
 
         assert_eq!(actor.state.phase, InstancePhase::Failed);
         assert!(actor.state.error_message.is_some());
+        assert_eq!(
+            actor.state.failure_reason,
+            Some(FailureReason::HealthcheckFailed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guest_failure_reason_is_classified() {
+        let runtime = std::sync::Arc::new(crate::runtime::MockRuntime::new());
+        let state_store = test_state_store();
+        let mut actor = InstanceActor::new(
+            "inst_test".to_string(),
+            runtime.clone(),
+            state_store.clone(),
+        );
+        let handle = runtime.start_vm(&test_plan()).await.unwrap();
+
+        state_store
+            .lock()
+            .unwrap()
+            .upsert_boot_status(&crate::state::BootStatusRecord {
+                instance_id: "inst_test".to_string(),
+                boot_id: handle.boot_id.clone(),
+                state: "failed".to_string(),
+                reason: Some("net_config_failed".to_string()),
+                detail: Some("eth0 not found".to_string()),
+                exit_code: None,
+                guest_timestamp: "2026-01-01T00:00:00Z".to_string(),
+                recorded_at: 0,
+            })
+            .unwrap();
+
+        actor.vm_handle = Some(handle);
+        actor.state.phase = InstancePhase::Booting;
+        actor.state.boot_started_at = Some(std::time::Instant::now());
+
+        actor.handle_tick(1).await.unwrap();
+
+        assert_eq!(actor.state.phase, InstancePhase::Failed);
+        assert_eq!(
+            actor.state.failure_reason,
+            Some(FailureReason::NetworkSetupFailed)
+        );
+        assert!(actor
+            .state
+            .error_message
+            .as_deref()
+            .unwrap()
+            .contains("eth0 not found"));
+    }
+
+    #[tokio::test]
+    async fn test_boot_without_handshake_times_out() {
+        let runtime = std::sync::Arc::new(crate::runtime::MockRuntime::new());
+        let state_store = test_state_store();
+        let mut actor = InstanceActor::new("inst_test".to_string(), runtime.clone(), state_store);
+        let handle = runtime.start_vm(&test_plan()).await.unwrap();
+
+        actor.vm_handle = Some(handle);
+        actor.state.phase = InstancePhase::Booting;
+        actor.state.boot_started_at =
+            Some(std::time::Instant::now() - (BOOT_TIMEOUT + std::time::Duration::from_secs(1)));
+
+        actor.handle_tick(1).await.unwrap();
+
+        assert_eq!(actor.state.phase, InstancePhase::Failed);
+        assert_eq!(
+            actor.state.failure_reason,
+            Some(FailureReason::ConfigHandshakeTimeout)
+        );
+    }
+
+    async fn apply_running(
+        actor: &mut InstanceActor<crate::runtime::MockRuntime>,
+        plan: InstancePlan,
+    ) {
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let mut ctx = ActorContext::new("instance:inst_test".to_string(), rx);
+        actor
+            .handle(
+                InstanceMessage::ApplyDesired {
+                    spec_revision: 1,
+                    spec: Box::new(plan),
+                    desired_state: DesiredInstanceState::Running,
+                },
+                &mut ctx,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_started_vm_is_recorded_and_readopted() {
+        let runtime = std::sync::Arc::new(crate::runtime::MockRuntime::new());
+        let state_store = test_state_store();
+
+        let mut first = InstanceActor::new(
+            "inst_test".to_string(),
+            runtime.clone(),
+            state_store.clone(),
+        );
+        apply_running(&mut first, test_plan()).await;
+        let boot_id = first.vm_handle.as_ref().unwrap().boot_id.clone();
+
+        let record = state_store
+            .lock()
+            .unwrap()
+            .get_instance("inst_test")
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.phase, StoredPhase::Starting);
+        assert_eq!(record.boot_id, boot_id);
+        assert_eq!(record.guest_cid, Some(3));
+        state_store
+            .lock()
+            .unwrap()
+            .set_instance_phase("inst_test", StoredPhase::Running)
+            .unwrap();
+
+        // A new agent process (fresh actor, same state store) takes the VM
+        // over instead of booting another one.
+        let mut second = InstanceActor::new("inst_test".to_string(), runtime, state_store);
+        apply_running(&mut second, test_plan()).await;
+        assert_eq!(second.state.phase, InstancePhase::Ready);
+        assert_eq!(second.vm_handle.as_ref().unwrap().boot_id, boot_id);
+    }
+
+    #[tokio::test]
+    async fn test_readopted_vm_with_stale_image_is_replaced() {
+        let runtime = std::sync::Arc::new(crate::runtime::MockRuntime::new());
+        let state_store = test_state_store();
+
+        let mut first = InstanceActor::new(
+            "inst_test".to_string(),
+            runtime.clone(),
+            state_store.clone(),
+        );
+        apply_running(&mut first, test_plan()).await;
+        let boot_id = first.vm_handle.as_ref().unwrap().boot_id.clone();
+
+        let mut plan = test_plan();
+        plan.image.resolved_digest = "sha256:newer".to_string();
+        let mut second = InstanceActor::new("inst_test".to_string(), runtime, state_store);
+        apply_running(&mut second, plan).await;
+        assert_eq!(second.state.phase, InstancePhase::Booting);
+        assert_ne!(second.vm_handle.as_ref().unwrap().boot_id, boot_id);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retry_budget_survives_restart() {
+        let runtime = std::sync::Arc::new(crate::runtime::MockRuntime::new());
+        let state_store = test_state_store();
+        for _ in 0..=plfm_reconcile::DEFAULT_MAX_RETRIES {
+            state_store
+                .lock()
+                .unwrap()
+                .retries()
+                .record_failure("inst_test")
+                .unwrap();
+        }
+
+        // A fresh actor (agent restart) does not boot an exhausted instance.
+        let mut actor = InstanceActor::new("inst_test".to_string(), runtime, state_store);
+        apply_running(&mut actor, test_plan()).await;
+        assert_eq!(actor.state.phase, InstancePhase::Failed);
+        assert_eq!(
+            actor.state.failure_reason,
+            Some(FailureReason::CrashLoopBackoff)
+        );
+        assert!(actor.vm_handle.is_none());
     }
 }
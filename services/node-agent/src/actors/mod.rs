@@ -30,7 +30,7 @@ pub use framework::{
     Actor, ActorContext, ActorError, ActorHandle, ActorRef, BackoffPolicy, Message, RestartPolicy,
     Supervisor,
 };
-pub use image::{ImageMessage, ImagePullActor};
+pub use image::{CachedImage, ImageCacheSnapshot, ImageMessage, ImagePullActor};
 pub use instance::{InstanceActor, InstanceActorState, InstanceMessage};
 pub use stream::{ControlPlaneStreamActor, StreamMessage};
-pub use supervisor::NodeSupervisor;
+pub use supervisor::{LocalRequest, NodeSupervisor, SupervisorStatus};
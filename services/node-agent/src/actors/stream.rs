@@ -37,6 +37,9 @@ pub enum StreamMessage {
 
     /// Connection was lost.
     Disconnected { reason: String },
+
+    /// Fetch and publish the current plan now (forced reconcile).
+    RefreshPlan,
 }
 
 // =============================================================================
@@ -248,6 +251,9 @@ impl ControlPlaneStreamActor {
             available_cpu_cores: 8,
             available_memory_bytes: 16 * 1024 * 1024 * 1024,
             instance_count,
+            instance_statuses: None,
+            ephemeral_disk_used_bytes: None,
+            ephemeral_disk_available_bytes: None,
         };
 
         debug!(node_id = %self.node_id, "Sending heartbeat");
@@ -345,6 +351,12 @@ impl Actor for ControlPlaneStreamActor {
             StreamMessage::Disconnected { reason } => {
                 self.handle_disconnected(reason).await?;
             }
+
+            StreamMessage::RefreshPlan => {
+                if let Err(e) = self.fetch_and_publish_plan().await {
+                    warn!(error = %e, "Failed to fetch plan for forced reconcile");
+                }
+            }
         }
 
         Ok(true)
@@ -418,7 +430,8 @@ mod tests {
             data_dir: "/tmp/test".to_string(),
             heartbeat_interval_secs: 30,
             log_level: "info".to_string(),
-            exec_listen_addr: "127.0.0.1:0".parse().unwrap(),
+            exec_listen_addr: None,
+            local_api_socket: None,
         };
         let client = std::sync::Arc::new(crate::client::ControlPlaneClient::new(&config));
         let (plan_tx, _plan_rx) = tokio::sync::mpsc::channel(4);
@@ -25,7 +25,7 @@
 //! 3. When image is ready, supervisor spawns InstanceActor with the rootdisk path
 //! 4. InstanceActor boots the VM using the prepared rootdisk
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
@@ -35,8 +35,10 @@ use std::time::Duration;
 use tokio::sync::{mpsc, oneshot, watch};
 use tracing::{debug, error, info, warn};
 
+use serde::Serialize;
+
 use super::framework::{ActorHandle, RestartPolicy, Supervisor};
-use super::image::{ImageMessage, ImagePullActor};
+use super::image::{ImageCacheSnapshot, ImageMessage, ImagePullActor};
 use super::instance::{DesiredInstanceState, InstanceActor, InstanceMessage};
 use super::stream::{ControlPlaneStreamActor, StreamMessage};
 use crate::client::{
@@ -45,6 +47,7 @@ use crate::client::{
 use crate::config::Config;
 use crate::runtime::Runtime;
 use crate::state::StateStore;
+use crate::volume::{VolumeResizer, VolumeRestorer, VolumeSnapshotter};
 
 // =============================================================================
 // Node Supervisor
@@ -60,6 +63,37 @@ struct PendingInstance {
     revision: u64,
 }
 
+/// Requests from the node-local API (`crate::local_api`), answered from the
+/// supervisor loop so they see the same state it acts on.
+#[derive(Debug)]
+pub enum LocalRequest {
+    /// Report supervisor state.
+    Status {
+        reply_to: oneshot::Sender<SupervisorStatus>,
+    },
+    /// Report the image cache (forwarded to the image pull actor).
+    Images {
+        reply_to: oneshot::Sender<ImageCacheSnapshot>,
+    },
+    /// Run a reconcile pass now; replies with the spec revision applied.
+    TriggerReconcile { reply_to: oneshot::Sender<u64> },
+}
+
+/// Supervisor state reported to the node-local API.
+#[derive(Debug, Clone, Serialize)]
+pub struct SupervisorStatus {
+    pub node_id: String,
+    pub spec_revision: u64,
+    pub last_cursor_event_id: i64,
+    pub last_plan_id: Option<String>,
+    pub running_actors: usize,
+    pub degraded_actors: usize,
+    /// Instances with a live instance actor.
+    pub active_instances: Vec<String>,
+    /// Instances waiting for their image.
+    pub pending_instances: Vec<String>,
+}
+
 /// Root supervisor for the node agent.
 pub struct NodeSupervisor<R: Runtime + Send + Sync + 'static> {
     config: Config,
@@ -68,9 +102,14 @@ pub struct NodeSupervisor<R: Runtime + Send + Sync + 'static> {
     state_store: Arc<std::sync::Mutex<StateStore>>,
     plan_rx: mpsc::Receiver<NodePlan>,
     plan_tx: mpsc::Sender<NodePlan>,
+    local_rx: mpsc::Receiver<LocalRequest>,
+    local_tx: mpsc::Sender<LocalRequest>,
     instance_count: Arc<AtomicUsize>,
     last_cursor_event_id: i64,
     last_plan_id: Option<String>,
+    /// Desired instances from the last applied plan, re-applied on a forced
+    /// reconcile.
+    last_desired: Option<Vec<DesiredInstanceAssignment>>,
     supervisor: Supervisor,
     stream_handle: Option<ActorHandle<StreamMessage>>,
     image_handle: Option<ActorHandle<ImageMessage>>,
@@ -78,6 +117,12 @@ pub struct NodeSupervisor<R: Runtime + Send + Sync + 'static> {
     pending_instances: HashMap<String, PendingInstance>,
     shutdown: watch::Receiver<bool>,
     spec_revision: u64,
+    /// Grows volumes to their planned sizes.
+    volumes: VolumeResizer,
+    /// Runs in-place volume restores.
+    restores: VolumeRestorer,
+    /// Takes queued volume snapshots.
+    snapshots: VolumeSnapshotter,
 }
 
 impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
@@ -90,6 +135,7 @@ impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
     ) -> Self {
         let supervisor = Supervisor::new(RestartPolicy::default(), shutdown.clone());
         let (plan_tx, plan_rx) = mpsc::channel(16);
+        let (local_tx, local_rx) = mpsc::channel(16);
         let instance_count = Arc::new(AtomicUsize::new(0));
 
         Self {
@@ -99,9 +145,12 @@ impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
             state_store,
             plan_rx,
             plan_tx,
+            local_rx,
+            local_tx,
             instance_count,
             last_cursor_event_id: 0,
             last_plan_id: None,
+            last_desired: None,
             supervisor,
             stream_handle: None,
             image_handle: None,
@@ -109,6 +158,9 @@ impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
             pending_instances: HashMap::new(),
             shutdown,
             spec_revision: 0,
+            volumes: VolumeResizer::new(),
+            restores: VolumeRestorer::new(),
+            snapshots: VolumeSnapshotter::new(),
         }
     }
 
@@ -175,10 +227,19 @@ impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
             self.stop_instance(&instance_id).await;
         }
 
-        // Ensure each desired instance exists
+        // Ensure each desired instance exists, growing its volumes first so
+        // a (re)booted VM sees the new size.
         for plan in desired {
+            if plan.desired_state == InstanceDesiredState::Running {
+                if let Some(workload) = plan.workload.as_ref() {
+                    self.volumes
+                        .reconcile(self.runtime.as_ref(), workload)
+                        .await;
+                }
+            }
             self.ensure_instance(plan, revision).await;
         }
+        self.volumes.flush(&self.control_plane).await;
 
         debug!(
             running_instances = self.instance_handles.len(),
@@ -199,9 +260,105 @@ impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
 
         self.last_cursor_event_id = plan.cursor_event_id;
         self.last_plan_id = Some(plan.plan_id.clone());
+        self.last_desired = Some(plan.instances.clone());
+
+        if !plan.volume_restores.is_empty() || !plan.volume_snapshots.is_empty() {
+            let mounted: HashSet<&str> = plan
+                .instances
+                .iter()
+                .filter(|a| a.desired_state != InstanceDesiredState::Stopped)
+                .filter_map(|a| a.workload.as_ref()?.mounts.as_ref())
+                .flatten()
+                .map(|m| m.volume_id.as_str())
+                .collect();
+            self.restores
+                .reconcile(
+                    &self.runtime,
+                    &self.control_plane,
+                    &plan.volume_restores,
+                    &mounted,
+                )
+                .await;
+            self.snapshots
+                .reconcile(
+                    &self.runtime,
+                    &self.control_plane,
+                    &plan.volume_snapshots,
+                    &mounted,
+                )
+                .await;
+        }
+
         self.apply_instances(plan.instances).await;
     }
 
+    async fn handle_local_request(&mut self, request: LocalRequest) {
+        match request {
+            LocalRequest::Status { reply_to } => {
+                let _ = reply_to.send(self.status());
+            }
+            LocalRequest::Images { reply_to } => match &self.image_handle {
+                Some(handle) => {
+                    if let Err(e) = handle.try_send(ImageMessage::Snapshot { reply_to }) {
+                        warn!(error = %e, "Failed to query image actor");
+                    }
+                }
+                // Dropping reply_to tells the caller the image actor is gone.
+                None => drop(reply_to),
+            },
+            LocalRequest::TriggerReconcile { reply_to } => {
+                let revision = self.force_reconcile().await;
+                let _ = reply_to.send(revision);
+            }
+        }
+    }
+
+    /// Current supervisor state.
+    pub fn status(&self) -> SupervisorStatus {
+        let mut active_instances: Vec<String> = self.instance_handles.keys().cloned().collect();
+        active_instances.sort();
+        let mut pending_instances: Vec<String> = self.pending_instances.keys().cloned().collect();
+        pending_instances.sort();
+
+        SupervisorStatus {
+            node_id: self.config.node_id.to_string(),
+            spec_revision: self.spec_revision,
+            last_cursor_event_id: self.last_cursor_event_id,
+            last_plan_id: self.last_plan_id.clone(),
+            running_actors: self.supervisor.running_count(),
+            degraded_actors: self.supervisor.degraded_count(),
+            active_instances,
+            pending_instances,
+        }
+    }
+
+    /// Reconcile now instead of waiting for the next plan: restart crashed
+    /// actors, re-apply the last plan, and ask the stream actor to fetch a
+    /// fresh one (applied when it arrives if it changed).
+    async fn force_reconcile(&mut self) -> u64 {
+        info!("Forced reconcile requested via local API");
+
+        self.supervisor.check_and_restart().await;
+
+        if let Some(handle) = &self.stream_handle {
+            if let Err(e) = handle.try_send(StreamMessage::RefreshPlan) {
+                warn!(error = %e, "Failed to request plan refresh");
+            }
+        }
+
+        if let Some(desired) = self.last_desired.clone() {
+            self.apply_instances(desired).await;
+        }
+        self.check_pending_instances().await;
+
+        self.spec_revision
+    }
+
+    /// Sender for node-local API requests.
+    pub fn local_api_sender(&self) -> mpsc::Sender<LocalRequest> {
+        self.local_tx.clone()
+    }
+
     /// Ensure an instance actor exists and has the correct spec.
     async fn ensure_instance(&mut self, assignment: DesiredInstanceAssignment, revision: u64) {
         let instance_id = assignment.instance_id.clone();
@@ -489,6 +646,12 @@ impl<R: Runtime + Send + Sync + 'static> NodeSupervisor<R> {
                     }
                 }
 
+                request = self.local_rx.recv() => {
+                    if let Some(request) = request {
+                        self.handle_local_request(request).await;
+                    }
+                }
+
                 _ = check_interval.tick() => {
                     tick_id += 1;
                     self.instance_count
@@ -626,7 +789,8 @@ mod tests {
             data_dir: "/tmp/test".to_string(),
             heartbeat_interval_secs: 30,
             log_level: "info".to_string(),
-            exec_listen_addr: "127.0.0.1:0".parse().unwrap(),
+            exec_listen_addr: None,
+            local_api_socket: None,
         }
     }
 
@@ -758,6 +922,8 @@ mod tests {
             created_at: Utc::now(),
             cursor_event_id: 1,
             instances: vec![test_assignment("inst_1")],
+            volume_restores: Vec::new(),
+            volume_snapshots: Vec::new(),
         };
         supervisor.handle_plan(plan).await;
         assert_eq!(supervisor.instance_count(), 1);
@@ -769,6 +935,8 @@ mod tests {
             created_at: Utc::now(),
             cursor_event_id: 1,
             instances: vec![test_assignment("inst_2")],
+            volume_restores: Vec::new(),
+            volume_snapshots: Vec::new(),
         };
         supervisor.handle_plan(plan).await;
         assert_eq!(supervisor.instance_count(), 1);
@@ -776,6 +944,43 @@ mod tests {
         supervisor.supervisor.stop_all().await;
     }
 
+    #[tokio::test]
+    async fn test_force_reconcile_reapplies_last_plan() {
+        let config = test_config();
+        let runtime = Arc::new(MockRuntime::new());
+        let (_, shutdown_rx) = watch::channel(false);
+        let control_plane = Arc::new(ControlPlaneClient::new(&config));
+        let state_store = test_state_store();
+        let node_id = config.node_id.to_string();
+
+        let mut supervisor =
+            NodeSupervisor::new(config, runtime, control_plane, state_store, shutdown_rx);
+
+        let plan = NodePlan {
+            spec_version: "v1".to_string(),
+            node_id,
+            plan_id: "plan-1".to_string(),
+            created_at: Utc::now(),
+            cursor_event_id: 1,
+            instances: vec![test_assignment("inst_1")],
+            volume_restores: Vec::new(),
+            volume_snapshots: Vec::new(),
+        };
+        supervisor.handle_plan(plan).await;
+        assert_eq!(supervisor.status().active_instances, vec!["inst_1"]);
+
+        // Lose the actor; a forced reconcile brings it back without a new plan.
+        supervisor.stop_instance("inst_1").await;
+        assert_eq!(supervisor.instance_count(), 0);
+
+        let revision = supervisor.force_reconcile().await;
+        assert_eq!(revision, 2);
+        assert_eq!(supervisor.instance_count(), 1);
+        assert_eq!(supervisor.status().last_plan_id.as_deref(), Some("plan-1"));
+
+        supervisor.supervisor.stop_all().await;
+    }
+
     #[tokio::test]
     async fn test_node_supervisor_direct_spawn() {
         let config = test_config();
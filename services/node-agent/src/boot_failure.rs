@@ -0,0 +1,209 @@
+//! Boot failure classification.
+//!
+//! Maps runtime errors, guest-init failure reports and serial console output
+//! onto the stable `FailureReason` codes carried by `instance.status_changed`,
+//! so the control plane can apply a different retry policy per failure class.
+//!
+//! Reference: docs/specs/runtime/firecracker-boot.md
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::client::FailureReason;
+
+/// A boot step failure tagged with its reason code.
+///
+/// Runtimes wrap errors in this type so callers can recover the reason with
+/// [`classify_start_error`] after the error has been through `anyhow`.
+#[derive(Debug, thiserror::Error)]
+#[error("{detail}")]
+pub struct BootFailure {
+    pub reason: FailureReason,
+    pub detail: String,
+}
+
+impl BootFailure {
+    pub fn new(reason: FailureReason, detail: impl Into<String>) -> Self {
+        Self {
+            reason,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Wrap an error as a [`BootFailure`] with the given reason.
+pub fn boot_failure(reason: FailureReason, err: impl std::fmt::Display) -> anyhow::Error {
+    BootFailure::new(reason, err.to_string()).into()
+}
+
+/// Reason code for an error returned by `Runtime::start_vm`.
+///
+/// Untagged errors are attributed to the VMM itself.
+pub fn classify_start_error(err: &anyhow::Error) -> FailureReason {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<BootFailure>())
+        .map(|failure| failure.reason)
+        .unwrap_or(FailureReason::FirecrackerStartFailed)
+}
+
+/// Reason code for a guest-init failure report (`reason` in the status
+/// message, see docs/specs/runtime/guest-init.md).
+pub fn classify_guest_reason(reason: Option<&str>) -> FailureReason {
+    match reason {
+        Some("handshake_failed") | Some("config_parse_failed") | Some("vsock_error") => {
+            FailureReason::ConfigHandshakeTimeout
+        }
+        Some("net_config_failed") => FailureReason::NetworkSetupFailed,
+        Some("mount_failed") => FailureReason::VolumeAttachFailed,
+        Some("secrets_missing") => FailureReason::SecretsMissing,
+        Some("secrets_write_failed") => FailureReason::SecretsInjectionFailed,
+        Some("oom") => FailureReason::OomKilled,
+        _ => FailureReason::GuestInitFailed,
+    }
+}
+
+/// Reason code suggested by a line of guest serial console output.
+pub fn classify_console_line(line: &str) -> Option<FailureReason> {
+    if line.contains("Kernel panic") {
+        Some(FailureReason::KernelPanic)
+    } else if line.contains("Out of memory: Kill") || line.contains("invoked oom-killer") {
+        Some(FailureReason::OomKilled)
+    } else {
+        None
+    }
+}
+
+/// Reason code for a VM that did not report ready within the boot timeout.
+///
+/// `boot_state` is the last status guest-init reported for the boot, if any.
+/// A guest that never reported at all never completed the config handshake.
+pub fn classify_boot_timeout(
+    boot_state: Option<&str>,
+    console_hint: Option<FailureReason>,
+) -> FailureReason {
+    if let Some(hint) = console_hint {
+        return hint;
+    }
+    match boot_state {
+        None => FailureReason::ConfigHandshakeTimeout,
+        Some(_) => FailureReason::GuestInitFailed,
+    }
+}
+
+/// Failure hints scraped from each instance's serial console.
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleHints {
+    hints: Arc<Mutex<HashMap<String, FailureReason>>>,
+}
+
+impl ConsoleHints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inspect a console line. A kernel panic overrides any earlier hint.
+    pub fn observe(&self, instance_id: &str, line: &str) {
+        let Some(reason) = classify_console_line(line) else {
+            return;
+        };
+        if let Ok(mut hints) = self.hints.lock() {
+            match hints.get(instance_id) {
+                Some(FailureReason::KernelPanic) => {}
+                Some(_) if reason != FailureReason::KernelPanic => {}
+                _ => {
+                    hints.insert(instance_id.to_string(), reason);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, instance_id: &str) -> Option<FailureReason> {
+        self.hints.lock().ok()?.get(instance_id).copied()
+    }
+
+    pub fn clear(&self, instance_id: &str) {
+        if let Ok(mut hints) = self.hints.lock() {
+            hints.remove(instance_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_classify_start_error() {
+        let err = boot_failure(FailureReason::ImagePullFailed, "registry unreachable");
+        assert_eq!(classify_start_error(&err), FailureReason::ImagePullFailed);
+
+        let wrapped = Err::<(), _>(err).context("starting vm").unwrap_err();
+        assert_eq!(
+            classify_start_error(&wrapped),
+            FailureReason::ImagePullFailed
+        );
+
+        let untagged = anyhow::anyhow!("socket did not appear");
+        assert_eq!(
+            classify_start_error(&untagged),
+            FailureReason::FirecrackerStartFailed
+        );
+    }
+
+    #[test]
+    fn test_classify_guest_reason() {
+        assert_eq!(
+            classify_guest_reason(Some("net_config_failed")),
+            FailureReason::NetworkSetupFailed
+        );
+        assert_eq!(
+            classify_guest_reason(Some("secrets_write_failed")),
+            FailureReason::SecretsInjectionFailed
+        );
+        assert_eq!(classify_guest_reason(Some("oom")), FailureReason::OomKilled);
+        assert_eq!(
+            classify_guest_reason(Some("workload_crashed")),
+            FailureReason::GuestInitFailed
+        );
+        assert_eq!(classify_guest_reason(None), FailureReason::GuestInitFailed);
+    }
+
+    #[test]
+    fn test_console_hints() {
+        let hints = ConsoleHints::new();
+        hints.observe("inst_a", "[    0.5] virtio_blk virtio0: [vda] 1024 blocks");
+        assert_eq!(hints.get("inst_a"), None);
+
+        hints.observe("inst_a", "[   12.1] Out of memory: Killed process 42 (app)");
+        assert_eq!(hints.get("inst_a"), Some(FailureReason::OomKilled));
+
+        hints.observe(
+            "inst_a",
+            "[   12.2] Kernel panic - not syncing: System is deadlocked on memory",
+        );
+        assert_eq!(hints.get("inst_a"), Some(FailureReason::KernelPanic));
+
+        hints.observe("inst_a", "[   12.3] app invoked oom-killer");
+        assert_eq!(hints.get("inst_a"), Some(FailureReason::KernelPanic));
+
+        hints.clear("inst_a");
+        assert_eq!(hints.get("inst_a"), None);
+    }
+
+    #[test]
+    fn test_classify_boot_timeout() {
+        assert_eq!(
+            classify_boot_timeout(None, None),
+            FailureReason::ConfigHandshakeTimeout
+        );
+        assert_eq!(
+            classify_boot_timeout(Some("config_applied"), None),
+            FailureReason::GuestInitFailed
+        );
+        assert_eq!(
+            classify_boot_timeout(None, Some(FailureReason::KernelPanic)),
+            FailureReason::KernelPanic
+        );
+    }
+}
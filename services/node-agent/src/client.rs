@@ -3,12 +3,18 @@
 //! Provides methods for communicating with the control plane:
 //! - Fetching the current plan
 //! - Reporting instance status
+//! - Reporting volume resize outcomes
+//! - Reporting in-place volume restore progress
+//! - Obtaining instance identity tokens
+//! - Uploading workload log batches
+//! - Uploading guest console tails
 
 use std::collections::HashMap;
 use std::time::Duration;
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use plfm_events::SecretsReload;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error};
 
@@ -84,6 +90,118 @@ impl ControlPlaneClient {
         Ok(())
     }
 
+    /// Report the outcome of a volume resize. Returns `Ok(false)` when the
+    /// control plane rejected the report (e.g. the volume was deleted or is
+    /// homed elsewhere); such reports must not be retried.
+    pub async fn report_volume_resize(&self, report: &VolumeResizeReport) -> Result<bool> {
+        let url = format!(
+            "{}/v1/nodes/{}/volumes/{}/resize",
+            self.base_url, self.node_id, report.volume_id
+        );
+        debug!(
+            volume_id = %report.volume_id,
+            size_bytes = report.size_bytes,
+            "Reporting volume resize"
+        );
+
+        let response = self.client.post(&url).json(report).send().await?;
+
+        let status_code = response.status();
+        if status_code.is_client_error() {
+            let body = response.text().await.unwrap_or_default();
+            debug!(status = %status_code, body = %body, "Volume resize report rejected");
+            return Ok(false);
+        }
+        if !status_code.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            error!(status = %status_code, body = %body, "Failed to report volume resize");
+            anyhow::bail!("Failed to report volume resize: {} - {}", status_code, body);
+        }
+
+        Ok(true)
+    }
+
+    /// Report progress or the outcome of an in-place restore. Returns
+    /// `Ok(false)` when the control plane rejected the report (e.g. the
+    /// restore already finished); such reports must not be retried.
+    pub async fn report_volume_restore(&self, report: &VolumeRestoreReport) -> Result<bool> {
+        let url = format!(
+            "{}/v1/nodes/{}/restores/{}",
+            self.base_url, self.node_id, report.restore_id
+        );
+        debug!(
+            restore_id = %report.restore_id,
+            status = report.status,
+            bytes_restored = ?report.bytes_restored,
+            "Reporting volume restore"
+        );
+
+        let response = self.client.post(&url).json(report).send().await?;
+
+        let status_code = response.status();
+        if status_code.is_client_error() {
+            let body = response.text().await.unwrap_or_default();
+            debug!(status = %status_code, body = %body, "Volume restore report rejected");
+            return Ok(false);
+        }
+        if !status_code.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            error!(status = %status_code, body = %body, "Failed to report volume restore");
+            anyhow::bail!(
+                "Failed to report volume restore: {} - {}",
+                status_code,
+                body
+            );
+        }
+
+        let accepted = response
+            .json::<ReportAcceptedResponse>()
+            .await
+            .map(|r| r.accepted)
+            .unwrap_or(true);
+        Ok(accepted)
+    }
+
+    /// Report progress or the outcome of a volume snapshot. Returns
+    /// `Ok(false)` when the control plane rejected the report (e.g. the
+    /// snapshot already finished); such reports must not be retried.
+    pub async fn report_volume_snapshot(&self, report: &VolumeSnapshotReport) -> Result<bool> {
+        let url = format!(
+            "{}/v1/nodes/{}/snapshots/{}",
+            self.base_url, self.node_id, report.snapshot_id
+        );
+        debug!(
+            snapshot_id = %report.snapshot_id,
+            status = report.status,
+            "Reporting volume snapshot"
+        );
+
+        let response = self.client.post(&url).json(report).send().await?;
+
+        let status_code = response.status();
+        if status_code.is_client_error() {
+            let body = response.text().await.unwrap_or_default();
+            debug!(status = %status_code, body = %body, "Volume snapshot report rejected");
+            return Ok(false);
+        }
+        if !status_code.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            error!(status = %status_code, body = %body, "Failed to report volume snapshot");
+            anyhow::bail!(
+                "Failed to report volume snapshot: {} - {}",
+                status_code,
+                body
+            );
+        }
+
+        let accepted = response
+            .json::<ReportAcceptedResponse>()
+            .await
+            .map(|r| r.accepted)
+            .unwrap_or(true);
+        Ok(accepted)
+    }
+
     /// Fetch decrypted secret material for a version.
     pub async fn fetch_secret_material(&self, version_id: &str) -> Result<SecretMaterialResponse> {
         let url = format!(
@@ -109,22 +227,85 @@ impl ControlPlaneClient {
         Ok(payload)
     }
 
-    /// Send workload log entries to the control plane.
-    pub async fn send_workload_logs(&self, entries: Vec<WorkloadLogEntry>) -> Result<()> {
-        if entries.is_empty() {
-            return Ok(());
+    /// Issue (or renew) the identity token of an instance on this node.
+    pub async fn issue_identity_token(&self, instance_id: &str) -> Result<IdentityTokenResponse> {
+        let url = format!(
+            "{}/v1/nodes/{}/instances/{}/identity-token",
+            self.base_url, self.node_id, instance_id
+        );
+        debug!(url = %url, "Requesting identity token");
+
+        let response = self.client.post(&url).send().await?;
+
+        if !response.status().is_success() {
+            let status_code = response.status();
+            let body = response.text().await.unwrap_or_default();
+            error!(status = %status_code, body = %body, "Failed to issue identity token");
+            anyhow::bail!("Failed to issue identity token: {} - {}", status_code, body);
         }
 
+        let payload: IdentityTokenResponse = response.json().await?;
+        Ok(payload)
+    }
+
+    /// Upload an encoded workload log batch (see
+    /// [`crate::log_shipping`]). `content_encoding` names the compression of
+    /// `body`, if any.
+    ///
+    /// Transport errors are returned as `Err`; the caller retries them like
+    /// [`LogUploadOutcome::Retry`].
+    pub async fn send_workload_logs(
+        &self,
+        body: Vec<u8>,
+        content_encoding: Option<&str>,
+    ) -> Result<LogUploadOutcome> {
         let url = format!("{}/v1/nodes/{}/logs", self.base_url, self.node_id);
-        let request = WorkloadLogRequest { entries };
 
-        let response = self.client.post(&url).json(&request).send().await?;
+        let mut request = self
+            .client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        if let Some(encoding) = content_encoding {
+            request = request.header(reqwest::header::CONTENT_ENCODING, encoding);
+        }
+        let response = request.send().await?;
+
+        let status_code = response.status();
+        if status_code.is_success() {
+            return Ok(LogUploadOutcome::Accepted);
+        }
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response.text().await.unwrap_or_default();
+        if status_code == reqwest::StatusCode::TOO_MANY_REQUESTS || status_code.is_server_error() {
+            debug!(status = %status_code, body = %body, "Workload log upload deferred");
+            return Ok(LogUploadOutcome::Retry { retry_after });
+        }
+        error!(status = %status_code, body = %body, "Workload log upload rejected");
+        Ok(LogUploadOutcome::Rejected {
+            status: status_code.as_u16(),
+        })
+    }
+
+    /// Upload the tail of an instance's serial console, replacing the one
+    /// the control plane holds (see [`crate::console`]).
+    pub async fn upload_console(&self, upload: &ConsoleUpload) -> Result<()> {
+        let url = format!(
+            "{}/v1/nodes/{}/instances/{}/console",
+            self.base_url, self.node_id, upload.instance_id
+        );
+
+        let response = self.client.put(&url).json(upload).send().await?;
 
         if !response.status().is_success() {
             let status_code = response.status();
             let body = response.text().await.unwrap_or_default();
-            error!(status = %status_code, body = %body, "Failed to send workload logs");
-            anyhow::bail!("Failed to send workload logs: {} - {}", status_code, body);
+            anyhow::bail!("Failed to upload console: {} - {}", status_code, body);
         }
 
         Ok(())
@@ -160,6 +341,30 @@ pub struct NodePlan {
     pub created_at: DateTime<Utc>,
     pub cursor_event_id: i64,
     pub instances: Vec<DesiredInstanceAssignment>,
+    /// In-place restores of volumes homed on this node; absent from older
+    /// control planes.
+    #[serde(default)]
+    pub volume_restores: Vec<VolumeRestoreAssignment>,
+    /// Snapshots to take of volumes homed on this node; absent from older
+    /// control planes.
+    #[serde(default)]
+    pub volume_snapshots: Vec<VolumeSnapshotAssignment>,
+}
+
+/// A snapshot to take of a volume on this node.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VolumeSnapshotAssignment {
+    pub snapshot_id: String,
+    pub volume_id: String,
+}
+
+/// A snapshot to write over a volume on this node.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VolumeRestoreAssignment {
+    pub restore_id: String,
+    pub volume_id: String,
+    pub snapshot_id: String,
+    pub size_bytes: i64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -264,6 +469,9 @@ pub struct WorkloadMount {
     pub filesystem: String,
     #[serde(default)]
     pub device_hint: Option<String>,
+    /// Desired volume size; absent from older control planes.
+    #[serde(default)]
+    pub size_bytes: Option<i64>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -278,6 +486,9 @@ pub struct WorkloadSecrets {
     pub uid: Option<i32>,
     #[serde(default)]
     pub gid: Option<i32>,
+    /// How a new version is applied in place; absent means restart.
+    #[serde(default)]
+    pub reload: Option<SecretsReload>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -328,8 +539,25 @@ pub struct SecretMaterialResponse {
     pub data: String,
 }
 
+/// Instance identity token issued by the control plane.
+#[derive(Clone, Deserialize)]
+pub struct IdentityTokenResponse {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub renew_after: DateTime<Utc>,
+}
+
+impl std::fmt::Debug for IdentityTokenResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentityTokenResponse")
+            .field("expires_at", &self.expires_at)
+            .field("renew_after", &self.renew_after)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Workload log entry sent by node agents.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkloadLogEntry {
     pub ts: DateTime<Utc>,
     pub instance_id: String,
@@ -338,9 +566,22 @@ pub struct WorkloadLogEntry {
     pub truncated: bool,
 }
 
+/// Body of a workload log upload.
 #[derive(Debug, Serialize)]
-struct WorkloadLogRequest {
-    entries: Vec<WorkloadLogEntry>,
+pub struct WorkloadLogRequest<'a> {
+    pub entries: &'a [WorkloadLogEntry],
+}
+
+/// How the control plane answered a workload log upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogUploadOutcome {
+    /// The batch was stored.
+    Accepted,
+    /// The control plane is overloaded or failing (`429`, `5xx`); retry
+    /// the same batch, no sooner than `retry_after` if given.
+    Retry { retry_after: Option<Duration> },
+    /// The batch was refused (other `4xx`); retrying will not help.
+    Rejected { status: u16 },
 }
 
 /// Instance status report sent to the control plane.
@@ -350,6 +591,9 @@ pub struct InstanceStatusReport {
     pub status: InstanceStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub boot_id: Option<String>,
+    /// Plan generation the reporting boot runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason_code: Option<FailureReason>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -358,6 +602,71 @@ pub struct InstanceStatusReport {
     pub exit_code: Option<i32>,
 }
 
+/// Tail of an instance's serial console, uploaded to the control plane.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleUpload {
+    #[serde(skip)]
+    pub instance_id: String,
+    pub boot_id: String,
+    pub content: String,
+    /// Whether older output was cut off.
+    pub truncated: bool,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Outcome of growing a volume, reported to the control plane.
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeResizeReport {
+    #[serde(skip)]
+    pub volume_id: String,
+    pub size_bytes: i64,
+    /// Failure class (e.g. `insufficient_space`); absent on success.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+/// Progress or outcome of an in-place restore, reported to the control plane.
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeRestoreReport {
+    #[serde(skip)]
+    pub restore_id: String,
+    /// `running`, `succeeded`, or `failed`.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_restored: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_total: Option<i64>,
+    /// Failure class (e.g. `snapshot_unavailable`); set when failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+/// Progress or outcome of a volume snapshot, reported to the control plane.
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeSnapshotReport {
+    #[serde(skip)]
+    pub snapshot_id: String,
+    /// `running`, `succeeded`, or `failed`.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+    /// Failure class (e.g. `volume_missing`); set when failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+/// `{"accepted": bool}` acknowledgement of a node report.
+#[derive(Debug, Deserialize)]
+struct ReportAcceptedResponse {
+    accepted: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum FailureReason {
@@ -374,6 +683,9 @@ pub enum FailureReason {
     CrashLoopBackoff,
     TerminatedByOperator,
     NodeDraining,
+    KernelPanic,
+    ConfigHandshakeTimeout,
+    SecretsFetchFailed,
 }
 
 /// Instance status.
@@ -418,6 +730,19 @@ pub struct HeartbeatRequest {
 
     /// Number of running instances.
     pub instance_count: i32,
+
+    /// Status of every tracked instance, used by the control plane to detect
+    /// drift from desired state. `None` sends no inventory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_statuses: Option<HashMap<String, InstanceStatus>>,
+
+    /// Bytes allocated on the host by instance scratch disks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_used_bytes: Option<i64>,
+
+    /// Free bytes on the filesystem holding scratch disks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ephemeral_disk_available_bytes: Option<i64>,
 }
 
 /// Node state.
@@ -518,6 +843,7 @@ mod tests {
             instance_id: "inst_123".to_string(),
             status: InstanceStatus::Ready,
             boot_id: Some("boot_456".to_string()),
+            generation: Some(3),
             reason_code: None,
             error_message: None,
             exit_code: None,
@@ -526,6 +852,7 @@ mod tests {
         let json = serde_json::to_string(&report).unwrap();
         assert!(json.contains("\"status\":\"ready\""));
         assert!(json.contains("\"boot_id\":\"boot_456\""));
+        assert!(json.contains("\"generation\":3"));
         assert!(!json.contains("error_message")); // Should be skipped
     }
 }
@@ -0,0 +1,307 @@
+//! Node-wide concurrency limits on instance operations.
+//!
+//! A plan that adds many instances at once would otherwise pull every image,
+//! boot every VM and tear down every replaced instance at the same time,
+//! saturating the disk and the network. Each kind of operation goes through
+//! a gate that admits a bounded number at once; the rest wait in FIFO order.
+//!
+//! - `pulls`: registry pulls and root disk builds. Cache hits skip the gate.
+//! - `boots`: Firecracker process start, disk preparation and VM boot, after
+//!   the image is available.
+//! - `teardowns`: VM shutdown and cleanup.
+//!
+//! The gates are shared by everything in the agent process (the runtime and
+//! the image pre-pull actor), in both actor and legacy mode. Counters are
+//! reported under `concurrency` by the local API's `GET /state`.
+//!
+//! Configuration (`GHOST_*` names are accepted as fallbacks):
+//! - `PLFM_MAX_CONCURRENT_PULLS`: default 2
+//! - `PLFM_MAX_CONCURRENT_BOOTS`: default 4
+//! - `PLFM_MAX_CONCURRENT_TEARDOWNS`: default 8
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::{debug, warn};
+
+/// Limits for each gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyConfig {
+    pub max_pulls: usize,
+    pub max_boots: usize,
+    pub max_teardowns: usize,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_pulls: 2,
+            max_boots: 4,
+            max_teardowns: 8,
+        }
+    }
+}
+
+impl ConcurrencyConfig {
+    /// Read `PLFM_MAX_CONCURRENT_PULLS`, `PLFM_MAX_CONCURRENT_BOOTS` and
+    /// `PLFM_MAX_CONCURRENT_TEARDOWNS`.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Some(limit) = limit_from_env("MAX_CONCURRENT_PULLS")? {
+            config.max_pulls = limit;
+        }
+        if let Some(limit) = limit_from_env("MAX_CONCURRENT_BOOTS")? {
+            config.max_boots = limit;
+        }
+        if let Some(limit) = limit_from_env("MAX_CONCURRENT_TEARDOWNS")? {
+            config.max_teardowns = limit;
+        }
+        Ok(config)
+    }
+}
+
+fn limit_from_env(name: &str) -> Result<Option<usize>> {
+    let Ok(value) =
+        std::env::var(format!("PLFM_{name}")).or_else(|_| std::env::var(format!("GHOST_{name}")))
+    else {
+        return Ok(None);
+    };
+    match value.trim().parse::<usize>() {
+        Ok(limit) if limit > 0 => Ok(Some(limit)),
+        _ => Err(anyhow::anyhow!("PLFM_{name} must be a positive integer")),
+    }
+}
+
+/// Admits at most `limit` operations of one kind at a time.
+#[derive(Debug)]
+pub struct Gate {
+    name: &'static str,
+    limit: usize,
+    semaphore: Semaphore,
+    in_flight: AtomicU64,
+    queued: AtomicU64,
+    admitted: AtomicU64,
+    waited: AtomicU64,
+    wait_ms_total: AtomicU64,
+    max_wait_ms: AtomicU64,
+}
+
+impl Gate {
+    pub fn new(name: &'static str, limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            name,
+            limit,
+            semaphore: Semaphore::new(limit),
+            in_flight: AtomicU64::new(0),
+            queued: AtomicU64::new(0),
+            admitted: AtomicU64::new(0),
+            waited: AtomicU64::new(0),
+            wait_ms_total: AtomicU64::new(0),
+            max_wait_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait for a slot. The slot is held until the returned permit drops.
+    /// `subject` (an instance ID or image digest) is only used for logging.
+    pub async fn acquire(&self, subject: &str) -> GatePermit<'_> {
+        let permit = match self.semaphore.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let start = Instant::now();
+                self.queued.fetch_add(1, Ordering::Relaxed);
+                debug!(gate = self.name, subject = %subject, limit = self.limit, "Waiting for a free slot");
+                let permit = self
+                    .semaphore
+                    .acquire()
+                    .await
+                    .expect("gate semaphore is never closed");
+                self.queued.fetch_sub(1, Ordering::Relaxed);
+                self.record_wait(subject, start.elapsed());
+                permit
+            }
+        };
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        self.admitted.fetch_add(1, Ordering::Relaxed);
+        GatePermit {
+            gate: self,
+            _permit: permit,
+        }
+    }
+
+    fn record_wait(&self, subject: &str, waited: Duration) {
+        let ms = waited.as_millis() as u64;
+        self.waited.fetch_add(1, Ordering::Relaxed);
+        self.wait_ms_total.fetch_add(ms, Ordering::Relaxed);
+        self.max_wait_ms.fetch_max(ms, Ordering::Relaxed);
+        if waited >= SLOW_WAIT {
+            warn!(gate = self.name, subject = %subject, waited_ms = ms, limit = self.limit, "Waited for a free slot");
+        } else {
+            debug!(gate = self.name, subject = %subject, waited_ms = ms, "Acquired slot after waiting");
+        }
+    }
+
+    pub fn stats(&self) -> GateStats {
+        GateStats {
+            limit: self.limit,
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            queued: self.queued.load(Ordering::Relaxed),
+            admitted: self.admitted.load(Ordering::Relaxed),
+            waited: self.waited.load(Ordering::Relaxed),
+            wait_ms_total: self.wait_ms_total.load(Ordering::Relaxed),
+            max_wait_ms: self.max_wait_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Waits at least this long are logged at warn level.
+const SLOW_WAIT: Duration = Duration::from_secs(30);
+
+/// A held slot; released on drop.
+#[derive(Debug)]
+pub struct GatePermit<'a> {
+    gate: &'a Gate,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl Drop for GatePermit<'_> {
+    fn drop(&mut self) {
+        self.gate.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Counters for one gate since the agent started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GateStats {
+    pub limit: usize,
+    /// Operations currently holding a slot.
+    pub in_flight: u64,
+    /// Operations currently waiting for a slot.
+    pub queued: u64,
+    /// Operations admitted in total.
+    pub admitted: u64,
+    /// Admitted operations that had to wait.
+    pub waited: u64,
+    pub wait_ms_total: u64,
+    pub max_wait_ms: u64,
+}
+
+/// The agent's gates.
+#[derive(Debug)]
+pub struct Gates {
+    pub pulls: Gate,
+    pub boots: Gate,
+    pub teardowns: Gate,
+}
+
+impl Gates {
+    pub fn new(config: ConcurrencyConfig) -> Self {
+        Self {
+            pulls: Gate::new("pulls", config.max_pulls),
+            boots: Gate::new("boots", config.max_boots),
+            teardowns: Gate::new("teardowns", config.max_teardowns),
+        }
+    }
+}
+
+static GATES: OnceLock<Gates> = OnceLock::new();
+
+/// Set the limits. Must run before the first operation; later calls are
+/// ignored with a warning.
+pub fn configure(config: ConcurrencyConfig) {
+    if GATES.set(Gates::new(config)).is_err() {
+        warn!("Concurrency limits already in use; ignoring new configuration");
+    }
+}
+
+/// The agent's gates, with default limits unless `configure` ran first.
+pub fn gates() -> &'static Gates {
+    GATES.get_or_init(|| Gates::new(ConcurrencyConfig::default()))
+}
+
+/// Counters for every gate.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ConcurrencyStats {
+    pub pulls: GateStats,
+    pub boots: GateStats,
+    pub teardowns: GateStats,
+}
+
+pub fn stats() -> ConcurrencyStats {
+    let gates = gates();
+    ConcurrencyStats {
+        pulls: gates.pulls.stats(),
+        boots: gates.boots.stats(),
+        teardowns: gates.teardowns.stats(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_gate_limits_in_flight() {
+        let gate = Arc::new(Gate::new("test", 2));
+        let first = gate.acquire("a").await;
+        let _second = gate.acquire("b").await;
+        assert_eq!(gate.stats().in_flight, 2);
+
+        let waiter = {
+            let gate = Arc::clone(&gate);
+            tokio::spawn(async move {
+                let _permit = gate.acquire("c").await;
+            })
+        };
+        while gate.stats().queued == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        waiter.await.unwrap();
+
+        let stats = gate.stats();
+        assert_eq!(stats.in_flight, 1);
+        assert_eq!(stats.queued, 0);
+        assert_eq!(stats.admitted, 3);
+        assert_eq!(stats.waited, 1);
+    }
+
+    #[tokio::test]
+    async fn test_gate_admits_in_order() {
+        let gate = Arc::new(Gate::new("test", 1));
+        let held = gate.acquire("first").await;
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut waiters = Vec::new();
+        for i in 0..3 {
+            let waiter_gate = Arc::clone(&gate);
+            let tx = tx.clone();
+            waiters.push(tokio::spawn(async move {
+                let _permit = waiter_gate.acquire("waiter").await;
+                tx.send(i).unwrap();
+            }));
+            while gate.stats().queued < i + 1 {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        drop(held);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        let order: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_zero_limit_admits_one() {
+        assert_eq!(Gate::new("test", 0).stats().limit, 1);
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use plfm_id::NodeId;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -10,7 +11,11 @@ pub struct Config {
     pub data_dir: String,
     pub heartbeat_interval_secs: u64,
     pub log_level: String,
-    pub exec_listen_addr: SocketAddr,
+    /// Listen address for the legacy TCP exec gateway; `None` disables it
+    /// and exec sessions arrive only over the gRPC exec tunnel.
+    pub exec_listen_addr: Option<SocketAddr>,
+    /// Unix socket for the node-local debug API; `None` disables it.
+    pub local_api_socket: Option<PathBuf>,
 }
 
 impl Config {
@@ -38,8 +43,16 @@ impl Config {
 
         let exec_listen_addr = std::env::var("GHOST_EXEC_LISTEN_ADDR")
             .or_else(|_| std::env::var("PLFM_EXEC_LISTEN_ADDR"))
-            .unwrap_or_else(|_| "0.0.0.0:5090".to_string())
-            .parse()?;
+            .ok()
+            .map(|addr| addr.parse())
+            .transpose()?;
+
+        // Defaults to <data_dir>/agent.sock; set to "off" (or empty) to disable.
+        let local_api_socket = match std::env::var("GHOST_LOCAL_API_SOCKET") {
+            Ok(v) if v.is_empty() || v == "off" => None,
+            Ok(v) => Some(PathBuf::from(v)),
+            Err(_) => Some(PathBuf::from(&data_dir).join("agent.sock")),
+        };
 
         Ok(Self {
             node_id,
@@ -49,6 +62,7 @@ impl Config {
             heartbeat_interval_secs,
             log_level,
             exec_listen_addr,
+            local_api_socket,
         })
     }
 }
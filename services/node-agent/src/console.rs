@@ -0,0 +1,423 @@
+//! Guest serial console capture.
+//!
+//! Firecracker writes the guest serial console (kernel messages, guest-init
+//! output, anything the workload prints to `/dev/console`) to its stdout,
+//! and its own errors to stderr. Besides shipping those lines as workload
+//! logs, the runtime appends both streams to
+//! `<data_dir>/console/<instance_id>.log`, so kernel panics and early-boot
+//! failures can be read back even when log shipping is down or the lines
+//! never reached the control plane.
+//!
+//! The file is bounded: once it reaches [`MAX_CONSOLE_BYTES`] it is rotated
+//! to `<instance_id>.log.1`, replacing the previous rotation. Each boot
+//! starts with a `--- boot <boot_id> at <time> ---` marker, so a crash loop
+//! keeps the tail of the previous boots. Files outlive the instance
+//! directory and are pruned [`CONSOLE_RETENTION`] after their last write.
+//!
+//! The tail is served by the local API (`GET /instances/{id}/console`) and
+//! uploaded to the control plane, which serves it at
+//! `GET /v1/instances/{id}/console`.
+//!
+//! Reference: docs/specs/runtime/firecracker-boot.md (Serial console)
+
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::client::{ConsoleUpload, ControlPlaneClient};
+
+/// Size at which a console file is rotated. At most twice this is kept per
+/// instance.
+pub const MAX_CONSOLE_BYTES: u64 = 256 * 1024;
+
+/// Tail returned when the caller does not ask for a size.
+pub const DEFAULT_TAIL_BYTES: u64 = 64 * 1024;
+
+/// How long console files are kept after their last write.
+pub const CONSOLE_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Tail uploaded to the control plane.
+const UPLOAD_BYTES: u64 = 64 * 1024;
+
+/// Least time between uploads while the console is being written.
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Console files for every instance on the node.
+#[derive(Debug, Clone)]
+pub struct ConsoleLogs {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+/// The end of an instance's console output.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleTail {
+    pub instance_id: String,
+    /// Console output, lossily decoded as UTF-8.
+    pub content: String,
+    /// Whether older output exists beyond `content`.
+    pub truncated: bool,
+    /// When the console was last written.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConsoleLogs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: MAX_CONSOLE_BYTES,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_max_bytes(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+        }
+    }
+
+    /// Console file for an instance; `None` for IDs that are not safe to
+    /// use as a file name.
+    fn path(&self, instance_id: &str) -> Option<PathBuf> {
+        let valid = !instance_id.is_empty()
+            && instance_id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+        valid.then(|| self.dir.join(format!("{instance_id}.log")))
+    }
+
+    /// Start capturing a boot of `instance_id`, appending to its console
+    /// file after a boot marker.
+    pub fn open(&self, instance_id: &str, boot_id: &str) -> io::Result<ConsoleWriter> {
+        let path = self
+            .path(instance_id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid instance id"))?;
+        fs::create_dir_all(&self.dir)?;
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        let writer = ConsoleWriter {
+            inner: Arc::new(WriterInner {
+                state: Mutex::new(WriterState {
+                    path,
+                    file: Some(file),
+                    size,
+                    max_bytes: self.max_bytes,
+                }),
+                dirty: AtomicBool::new(false),
+            }),
+        };
+        writer.write_line(&format!(
+            "--- boot {boot_id} at {} ---",
+            Utc::now().to_rfc3339()
+        ));
+        Ok(writer)
+    }
+
+    /// The last `max_bytes` of an instance's console, or `None` if nothing
+    /// was captured for it.
+    pub fn tail(&self, instance_id: &str, max_bytes: u64) -> io::Result<Option<ConsoleTail>> {
+        let Some(path) = self.path(instance_id) else {
+            return Ok(None);
+        };
+        let updated_at = match fs::metadata(&path) {
+            Ok(metadata) => metadata.modified()?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut content = read_tail(&path, max_bytes)?;
+        let mut truncated = content.len() as u64 == max_bytes && file_len(&path)? > max_bytes;
+        let remaining = max_bytes - content.len() as u64;
+        let rotated = rotated_path(&path);
+        if remaining > 0 && rotated.exists() {
+            let mut older = read_tail(&rotated, remaining)?;
+            truncated = file_len(&rotated)? > remaining;
+            older.extend_from_slice(&content);
+            content = older;
+        } else if rotated.exists() {
+            truncated = true;
+        }
+
+        // Start at a line boundary rather than mid-line, unless the tail is
+        // shorter than its last line.
+        if truncated {
+            if let Some(newline) = content.iter().position(|&b| b == b'\n') {
+                if newline + 1 < content.len() {
+                    content.drain(..=newline);
+                }
+            }
+        }
+
+        Ok(Some(ConsoleTail {
+            instance_id: instance_id.to_string(),
+            content: String::from_utf8_lossy(&content).into_owned(),
+            truncated,
+            updated_at: updated_at.into(),
+        }))
+    }
+
+    /// Remove console files not written to within `retention`, except
+    /// those of instances for which `keep` returns true (running VMs can be
+    /// quiet for longer than the retention).
+    pub fn prune(&self, retention: Duration, keep: impl Fn(&str) -> bool) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+            Err(e) => {
+                warn!(dir = %self.dir.display(), error = %e, "Failed to list console logs");
+                return;
+            }
+        };
+        let now = SystemTime::now();
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let instance_id = name
+                .strip_suffix(".log")
+                .or_else(|| name.strip_suffix(".log.1"))
+                .unwrap_or(&name);
+            if keep(instance_id) {
+                continue;
+            }
+            let expired = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| {
+                    now.duration_since(modified).unwrap_or_default() >= retention
+                });
+            if expired {
+                debug!(path = %entry.path().display(), "Pruning console log");
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// Appends one boot's console output. Clones share the file.
+#[derive(Debug, Clone)]
+pub struct ConsoleWriter {
+    inner: Arc<WriterInner>,
+}
+
+#[derive(Debug)]
+struct WriterInner {
+    state: Mutex<WriterState>,
+    /// Set on every write, cleared by [`ConsoleWriter::take_dirty`].
+    dirty: AtomicBool,
+}
+
+#[derive(Debug)]
+struct WriterState {
+    path: PathBuf,
+    /// `None` after a write error; capture stops for the rest of the boot.
+    file: Option<File>,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl ConsoleWriter {
+    /// Append a line of console output.
+    pub fn write_line(&self, line: &str) {
+        let mut state = self.inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = state.write_line(line) {
+            warn!(path = %state.path.display(), error = %e, "Failed to write console log; capture stopped");
+            state.file = None;
+        }
+        self.inner.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether anything was written since the last call.
+    pub fn take_dirty(&self) -> bool {
+        self.inner.dirty.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl WriterState {
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.file.is_none() {
+            return Ok(());
+        }
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_bytes {
+            self.file = None;
+            fs::rename(&self.path, rotated_path(&self.path))?;
+            self.file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+            self.size = 0;
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(line.as_bytes())?;
+            file.write_all(b"\n")?;
+            self.size += len;
+        }
+        Ok(())
+    }
+}
+
+/// Upload the console tail of one boot whenever it changed, at most every
+/// [`UPLOAD_INTERVAL`], and once more when `done` resolves (the VMM's
+/// output streams closed). Failed uploads are retried on the next tick;
+/// the final one is attempted once.
+pub async fn ship_console(
+    logs: ConsoleLogs,
+    writer: ConsoleWriter,
+    instance_id: String,
+    boot_id: String,
+    client: Arc<ControlPlaneClient>,
+    done: impl Future<Output = ()>,
+) {
+    tokio::pin!(done);
+    let mut interval = tokio::time::interval(UPLOAD_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = &mut done => break,
+            _ = interval.tick() => {
+                upload_if_dirty(&logs, &writer, &instance_id, &boot_id, &client).await;
+            }
+        }
+    }
+    upload_if_dirty(&logs, &writer, &instance_id, &boot_id, &client).await;
+}
+
+async fn upload_if_dirty(
+    logs: &ConsoleLogs,
+    writer: &ConsoleWriter,
+    instance_id: &str,
+    boot_id: &str,
+    client: &ControlPlaneClient,
+) {
+    if !writer.take_dirty() {
+        return;
+    }
+    let tail = match logs.tail(instance_id, UPLOAD_BYTES) {
+        Ok(Some(tail)) => tail,
+        Ok(None) => return,
+        Err(e) => {
+            warn!(instance_id = %instance_id, error = %e, "Failed to read console log");
+            return;
+        }
+    };
+    let upload = ConsoleUpload {
+        instance_id: instance_id.to_string(),
+        boot_id: boot_id.to_string(),
+        content: tail.content,
+        truncated: tail.truncated,
+        captured_at: tail.updated_at,
+    };
+    if let Err(e) = client.upload_console(&upload).await {
+        debug!(instance_id = %instance_id, error = %e, "Console upload failed");
+        writer.inner.dirty.store(true, Ordering::Relaxed);
+    }
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+fn file_len(path: &Path) -> io::Result<u64> {
+    Ok(fs::metadata(path)?.len())
+}
+
+/// The last `max_bytes` of a file.
+fn read_tail(path: &Path, max_bytes: u64) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))?;
+    let mut buf = Vec::with_capacity(len.min(max_bytes) as usize);
+    file.take(max_bytes).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_and_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = ConsoleLogs::new(dir.path());
+        assert!(logs.tail("inst_a", 1024).unwrap().is_none());
+
+        let writer = logs.open("inst_a", "boot_1").unwrap();
+        writer.write_line("Linux version 6.1");
+        writer.clone().write_line("Kernel panic - not syncing");
+        assert!(writer.take_dirty());
+        assert!(!writer.take_dirty());
+
+        let tail = logs.tail("inst_a", 1024).unwrap().unwrap();
+        assert!(tail.content.starts_with("--- boot boot_1 at "));
+        assert!(tail
+            .content
+            .ends_with("Linux version 6.1\nKernel panic - not syncing\n"));
+        assert!(!tail.truncated);
+
+        // A short tail starts at a line boundary.
+        let tail = logs.tail("inst_a", 30).unwrap().unwrap();
+        assert_eq!(tail.content, "Kernel panic - not syncing\n");
+        assert!(tail.truncated);
+
+        // The next boot appends after a marker.
+        logs.open("inst_a", "boot_2").unwrap().write_line("second");
+        let tail = logs.tail("inst_a", 4096).unwrap().unwrap();
+        assert!(tail.content.contains("Kernel panic"));
+        assert!(tail.content.contains("--- boot boot_2 at "));
+    }
+
+    #[test]
+    fn test_rotation_bounds_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = ConsoleLogs::with_max_bytes(dir.path(), 100);
+        let writer = logs.open("inst_a", "boot_1").unwrap();
+        for i in 0..50 {
+            writer.write_line(&format!("line {i:04}"));
+        }
+
+        let current = file_len(&dir.path().join("inst_a.log")).unwrap();
+        let rotated = file_len(&dir.path().join("inst_a.log.1")).unwrap();
+        assert!(current <= 100 && rotated <= 100);
+
+        // The tail spans the rotation.
+        let tail = logs.tail("inst_a", 150).unwrap().unwrap();
+        assert!(tail.truncated);
+        assert!(tail.content.ends_with("line 0049\n"));
+        assert!(tail.content.len() > 100);
+
+        // Output older than the previous rotation is gone.
+        let tail = logs.tail("inst_a", 10_000).unwrap().unwrap();
+        assert!(!tail.truncated);
+        assert!(!tail.content.contains("line 0000"));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_ids_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = ConsoleLogs::new(dir.path().join("console"));
+        assert!(logs.open("../etc/passwd", "boot_1").is_err());
+        assert!(logs.tail("../inst_a", 1024).unwrap().is_none());
+
+        logs.open("inst_a", "boot_1").unwrap();
+        logs.open("inst_b", "boot_1").unwrap();
+        logs.prune(CONSOLE_RETENTION, |_| false);
+        assert!(logs.tail("inst_a", 1024).unwrap().is_some());
+        logs.prune(Duration::ZERO, |id| id == "inst_b");
+        assert!(logs.tail("inst_a", 1024).unwrap().is_none());
+        assert!(logs.tail("inst_b", 1024).unwrap().is_some());
+    }
+}
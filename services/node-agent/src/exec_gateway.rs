@@ -1,6 +1,8 @@
 //! Exec gateway server for node-agent.
 //!
 //! Accepts connections from the control plane and proxies exec streams to guest-init.
+//! Only used by control planes that predate the gRPC exec tunnel
+//! (see [`crate::exec_tunnel`]); enabled by setting `GHOST_EXEC_LISTEN_ADDR`.
 
 use std::collections::HashMap;
 use std::io::{Read, Write};
@@ -113,10 +115,6 @@ fn run_exec_session(
     guest_cid: u32,
     init: ExecConnectInit,
 ) -> Result<()> {
-    let addr = VsockAddr::new(guest_cid, crate::exec::EXEC_PORT);
-    let mut vsock = VsockStream::connect(&addr)
-        .map_err(|e| anyhow!("Failed to connect to guest exec service: {e}"))?;
-
     let request = ExecRequest {
         command: init.command,
         env: init.env,
@@ -125,11 +123,7 @@ fn run_exec_session(
         rows: init.rows,
         stdin: init.stdin,
     };
-
-    let request_json = serde_json::to_string(&request)?;
-    vsock.write_all(request_json.as_bytes())?;
-    vsock.write_all(b"\n")?;
-    vsock.flush()?;
+    let vsock = connect_guest(guest_cid, &request)?;
 
     let mut vsock_reader = vsock.try_clone()?;
     let mut vsock_writer = vsock;
@@ -201,15 +195,7 @@ fn run_exec_session(
     }
 
     if !exit_sent.load(Ordering::SeqCst) {
-        let payload = ExitPayload {
-            msg_type: "exit",
-            exit_code: 128,
-            reason: "client_disconnect".to_string(),
-        };
-        let payload = serde_json::to_vec(&payload)?;
-        let mut frame = Vec::with_capacity(1 + payload.len());
-        frame.push(frame_type::EXIT);
-        frame.extend_from_slice(&payload);
+        let frame = exit_frame(128, "client_disconnect")?;
         let _ = write_framed_blocking(&mut tcp_stream, &frame);
     }
 
@@ -221,6 +207,33 @@ fn run_exec_session(
     Ok(())
 }
 
+/// Connect to the guest exec service and send the exec request.
+pub(crate) fn connect_guest(guest_cid: u32, request: &ExecRequest) -> Result<VsockStream> {
+    let addr = VsockAddr::new(guest_cid, crate::exec::EXEC_PORT);
+    let mut vsock = VsockStream::connect(&addr)
+        .map_err(|e| anyhow!("Failed to connect to guest exec service: {e}"))?;
+
+    let request_json = serde_json::to_string(request)?;
+    vsock.write_all(request_json.as_bytes())?;
+    vsock.write_all(b"\n")?;
+    vsock.flush()?;
+    Ok(vsock)
+}
+
+/// Build an exit frame for a session that ended without one from the guest.
+pub(crate) fn exit_frame(exit_code: i32, reason: &str) -> Result<Vec<u8>> {
+    let payload = ExitPayload {
+        msg_type: "exit",
+        exit_code,
+        reason: reason.to_string(),
+    };
+    let payload = serde_json::to_vec(&payload)?;
+    let mut frame = Vec::with_capacity(1 + payload.len());
+    frame.push(frame_type::EXIT);
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
 async fn read_framed(stream: &mut tokio::net::TcpStream) -> Result<Option<Vec<u8>>> {
     let mut len_buf = [0u8; 4];
     match stream.read_exact(&mut len_buf).await {
@@ -240,15 +253,7 @@ async fn send_exit_frame(
     exit_code: i32,
     reason: &str,
 ) -> Result<()> {
-    let payload = ExitPayload {
-        msg_type: "exit",
-        exit_code,
-        reason: reason.to_string(),
-    };
-    let payload = serde_json::to_vec(&payload)?;
-    let mut frame = Vec::with_capacity(1 + payload.len());
-    frame.push(frame_type::EXIT);
-    frame.extend_from_slice(&payload);
+    let frame = exit_frame(exit_code, reason)?;
     let len = frame.len() as u32;
     stream.write_all(&len.to_be_bytes()).await?;
     stream.write_all(&frame).await?;
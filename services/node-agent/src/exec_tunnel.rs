@@ -0,0 +1,301 @@
+//! Exec tunnel client for node-agent.
+//!
+//! Keeps a bidirectional `ExecTunnel` stream open to the control plane over
+//! the agent's gRPC channel. The control plane multiplexes exec sessions over
+//! it by session ID; each session is bridged to the guest exec service over
+//! vsock, exactly as the legacy TCP exec gateway does.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use plfm_proto::agent::v1::{
+    exec_tunnel_server_message::Body, node_agent_client::NodeAgentClient, ExecTunnelAgentMessage,
+    ExecTunnelOpen,
+};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Channel;
+use tonic::Request;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::exec::{frame_type, ExecRequest};
+use crate::exec_gateway::{connect_guest, exit_frame};
+use crate::instance::InstanceManager;
+
+/// Frames buffered per direction before senders wait.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Keepalive ping interval, so idle tunnels survive NAT and load balancers.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A tunnel that stayed up this long resets the reconnect backoff.
+const STABLE_CONNECTION: Duration = Duration::from_secs(60);
+
+/// Exec tunnel client.
+pub struct ExecTunnelClient {
+    config: Config,
+    instance_manager: Arc<InstanceManager>,
+}
+
+impl ExecTunnelClient {
+    pub fn new(config: Config, instance_manager: Arc<InstanceManager>) -> Self {
+        Self {
+            config,
+            instance_manager,
+        }
+    }
+
+    /// Keep the tunnel open until shutdown, reconnecting with backoff.
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let started = Instant::now();
+            tokio::select! {
+                result = self.run_once() => match result {
+                    Ok(()) => info!("Exec tunnel closed by control plane"),
+                    Err(e) => warn!(error = %e, "Exec tunnel failed"),
+                },
+                _ = shutdown.changed() => {
+                    info!("Exec tunnel shutting down");
+                    return;
+                }
+            }
+
+            if started.elapsed() >= STABLE_CONNECTION {
+                backoff = INITIAL_BACKOFF;
+            }
+
+            debug!(backoff_secs = backoff.as_secs(), "Reconnecting exec tunnel");
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shutdown.changed() => {
+                    info!("Exec tunnel shutting down");
+                    return;
+                }
+            }
+            backoff = next_backoff(backoff);
+        }
+    }
+
+    async fn run_once(&self) -> Result<()> {
+        let channel = Channel::from_shared(self.config.control_plane_grpc_url.clone())?
+            .http2_keep_alive_interval(KEEPALIVE_INTERVAL)
+            .keep_alive_while_idle(true)
+            .connect()
+            .await?;
+        let mut client = NodeAgentClient::new(channel);
+
+        let (outbound, outbound_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let mut request = Request::new(ReceiverStream::new(outbound_rx));
+        request
+            .metadata_mut()
+            .insert("x-node-id", self.config.node_id.to_string().parse()?);
+
+        let mut inbound = client.exec_tunnel(request).await?.into_inner();
+        info!(node_id = %self.config.node_id, "Exec tunnel connected");
+
+        // Dropping a session's sender ends the session.
+        let mut sessions: HashMap<String, mpsc::Sender<Vec<u8>>> = HashMap::new();
+
+        while let Some(message) = inbound.message().await? {
+            sessions.retain(|_, input| !input.is_closed());
+
+            match message.body {
+                Some(Body::Open(open)) => {
+                    let (input, input_rx) = mpsc::channel(CHANNEL_CAPACITY);
+                    sessions.insert(message.session_id.clone(), input);
+                    tokio::spawn(run_session(
+                        Arc::clone(&self.instance_manager),
+                        message.session_id,
+                        open,
+                        input_rx,
+                        outbound.clone(),
+                    ));
+                }
+                Some(Body::Frame(frame)) => {
+                    if let Some(input) = sessions.get(&message.session_id) {
+                        let _ = input.send(frame).await;
+                    }
+                }
+                Some(Body::Close(close)) => {
+                    debug!(
+                        session_id = %message.session_id,
+                        reason = %close.reason,
+                        "Exec session closed by control plane"
+                    );
+                    sessions.remove(&message.session_id);
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_session(
+    instance_manager: Arc<InstanceManager>,
+    session_id: String,
+    open: ExecTunnelOpen,
+    input: mpsc::Receiver<Vec<u8>>,
+    outbound: mpsc::Sender<ExecTunnelAgentMessage>,
+) {
+    info!(session_id = %session_id, instance_id = %open.instance_id, "Exec session opened over tunnel");
+
+    let Some(guest_cid) = instance_manager
+        .guest_cid_for_instance(&open.instance_id)
+        .await
+    else {
+        if let Ok(frame) = exit_frame(128, "instance_not_ready") {
+            let _ = outbound.send(agent_message(&session_id, frame)).await;
+        }
+        return;
+    };
+
+    let request = exec_request(open);
+    let result = tokio::task::spawn_blocking({
+        let session_id = session_id.clone();
+        move || bridge_session(guest_cid, request, &session_id, input, outbound)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => debug!(session_id = %session_id, "Exec session finished"),
+        Ok(Err(e)) => warn!(error = %e, session_id = %session_id, "Exec session failed"),
+        Err(e) => warn!(error = %e, session_id = %session_id, "Exec session task panicked"),
+    }
+}
+
+/// Proxy frames between the tunnel and the guest until the control plane
+/// ends the session or the tunnel goes away.
+fn bridge_session(
+    guest_cid: u32,
+    request: ExecRequest,
+    session_id: &str,
+    mut input: mpsc::Receiver<Vec<u8>>,
+    outbound: mpsc::Sender<ExecTunnelAgentMessage>,
+) -> Result<()> {
+    let vsock = match connect_guest(guest_cid, &request) {
+        Ok(vsock) => vsock,
+        Err(e) => {
+            let frame = exit_frame(128, "instance_not_ready")?;
+            let _ = outbound.blocking_send(agent_message(session_id, frame));
+            return Err(e);
+        }
+    };
+
+    let mut vsock_reader = vsock.try_clone()?;
+    let mut vsock_writer = vsock;
+
+    let exit_sent = Arc::new(AtomicBool::new(false));
+    let exit_sent_reader = Arc::clone(&exit_sent);
+    let reader_outbound = outbound.clone();
+    let reader_session_id = session_id.to_string();
+
+    let reader_thread = std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = match vsock_reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) => {
+                    warn!(error = %e, "Vsock read error");
+                    break;
+                }
+            };
+
+            let frame = &buf[..n];
+            if frame[0] == frame_type::EXIT {
+                exit_sent_reader.store(true, Ordering::SeqCst);
+            }
+
+            let message = agent_message(&reader_session_id, frame.to_vec());
+            if reader_outbound.blocking_send(message).is_err() || frame[0] == frame_type::EXIT {
+                break;
+            }
+        }
+    });
+
+    while let Some(frame) = input.blocking_recv() {
+        if let Err(e) = vsock_writer
+            .write_all(&frame)
+            .and_then(|()| vsock_writer.flush())
+        {
+            warn!(error = %e, "Vsock write error");
+            break;
+        }
+    }
+
+    // Unblocks the reader thread if the guest is still running.
+    let _ = vsock_writer.shutdown(Shutdown::Both);
+    reader_thread
+        .join()
+        .map_err(|_| anyhow!("exec tunnel reader thread panicked"))?;
+
+    if !exit_sent.load(Ordering::SeqCst) {
+        let frame = exit_frame(128, "client_disconnect")?;
+        let _ = outbound.blocking_send(agent_message(session_id, frame));
+    }
+
+    Ok(())
+}
+
+fn exec_request(open: ExecTunnelOpen) -> ExecRequest {
+    ExecRequest {
+        command: open.command,
+        env: open.env,
+        tty: open.tty,
+        cols: u16::try_from(open.cols).unwrap_or(u16::MAX),
+        rows: u16::try_from(open.rows).unwrap_or(u16::MAX),
+        stdin: open.stdin,
+    }
+}
+
+fn agent_message(session_id: &str, frame: Vec<u8>) -> ExecTunnelAgentMessage {
+    ExecTunnelAgentMessage {
+        session_id: session_id.to_string(),
+        frame,
+    }
+}
+
+fn next_backoff(backoff: Duration) -> Duration {
+    (backoff * 2).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_backoff_caps() {
+        assert_eq!(next_backoff(INITIAL_BACKOFF), Duration::from_secs(2));
+        assert_eq!(next_backoff(Duration::from_secs(20)), MAX_BACKOFF);
+        assert_eq!(next_backoff(MAX_BACKOFF), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_exec_request_clamps_terminal_size() {
+        let request = exec_request(ExecTunnelOpen {
+            instance_id: "inst_1".to_string(),
+            command: vec!["sh".to_string()],
+            tty: true,
+            cols: 120,
+            rows: 100_000,
+            env: HashMap::new(),
+            stdin: true,
+        });
+        assert_eq!(request.cols, 120);
+        assert_eq!(request.rows, u16::MAX);
+        assert_eq!(request.command, vec!["sh".to_string()]);
+    }
+}
@@ -3,18 +3,36 @@
 //! This module provides an HTTP client for Firecracker's Unix socket API.
 //! It handles configuration of the microVM before boot and instance actions.
 //!
+//! Every call has a timeout ([`ApiTimeouts`]). Pre-boot configuration PUTs
+//! replace the resource they name, so they are retried a bounded number of
+//! times when the call failed without an answer (timeout, connection error);
+//! a fault from Firecracker itself is never retried. Actions are not
+//! idempotent and are never retried; instead the client polls
+//! `describe_instance` until the VM reaches the state the action leads to.
+//!
 //! Reference: https://github.com/firecracker-microvm/firecracker/blob/main/src/api_server/swagger/firecracker.yaml
 
+use std::future::Future;
 use std::path::Path;
+use std::time::Duration;
 
-use hyper::{body::Buf, Body, Client, Method, Request};
+use hyper::{body::Bytes, Body, Client, Method, Request};
 use hyperlocal::{UnixClientExt, UnixConnector, Uri};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 use super::config::{BootSource, DriveConfig, MachineConfig, NetworkInterface, VsockConfig};
 
+/// Attempts for a configuration PUT, including the first.
+const MAX_PUT_ATTEMPTS: u32 = 3;
+
+/// Backoff before the first retry; doubled for each further retry.
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Interval between `describe_instance` polls while waiting for a state.
+const STATE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 /// Errors from the Firecracker API.
 #[derive(Debug, Error)]
 pub enum ApiError {
@@ -24,17 +42,67 @@ pub enum ApiError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
-    #[error("API error {status}: {message}")]
-    Api { status: u16, message: String },
+    #[error("Invalid request: {0}")]
+    Request(#[from] hyper::http::Error),
+
+    /// Firecracker rejected the call; `fault_message` is its explanation.
+    #[error("{request} failed with {status}: {fault_message}")]
+    Fault {
+        request: String,
+        status: u16,
+        fault_message: String,
+    },
+
+    #[error("{request} timed out after {after:?}")]
+    Timeout { request: String, after: Duration },
+
+    #[error("VM did not reach state {expected} (last seen: {})", .last_seen.as_deref().unwrap_or("unknown"))]
+    UnexpectedState {
+        expected: VmState,
+        last_seen: Option<String>,
+    },
 
     #[error("Socket not found: {0}")]
     SocketNotFound(String),
 }
 
+impl ApiError {
+    /// Whether the call ended without an answer from Firecracker, so it may
+    /// or may not have taken effect. These are the only errors an idempotent
+    /// call is repeated for: a fault is Firecracker's verdict on the request
+    /// and would be given again.
+    pub fn is_unanswered(&self) -> bool {
+        matches!(self, ApiError::Http(_) | ApiError::Timeout { .. })
+    }
+}
+
+/// Per-call timeouts for the Firecracker API.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiTimeouts {
+    /// Configuration calls and `describe_instance`.
+    pub request: Duration,
+    /// Instance actions (`InstanceStart`, `SendCtrlAltDel`) and VM state
+    /// changes.
+    pub action: Duration,
+    /// How long to poll for the state an action leads to.
+    pub state_change: Duration,
+}
+
+impl Default for ApiTimeouts {
+    fn default() -> Self {
+        Self {
+            request: Duration::from_secs(5),
+            action: Duration::from_secs(15),
+            state_change: Duration::from_secs(10),
+        }
+    }
+}
+
 /// Firecracker API client for Unix socket communication.
 pub struct FirecrackerClient {
     socket_path: String,
     client: Client<UnixConnector>,
+    timeouts: ApiTimeouts,
 }
 
 impl FirecrackerClient {
@@ -45,9 +113,16 @@ impl FirecrackerClient {
         Self {
             socket_path,
             client,
+            timeouts: ApiTimeouts::default(),
         }
     }
 
+    /// Override the per-call timeouts.
+    pub fn with_timeouts(mut self, timeouts: ApiTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
     /// Check if the socket exists.
     pub fn socket_exists(&self) -> bool {
         Path::new(&self.socket_path).exists()
@@ -55,172 +130,285 @@ impl FirecrackerClient {
 
     /// Configure the machine (vCPUs, memory).
     pub async fn put_machine_config(&self, config: &MachineConfig) -> Result<(), ApiError> {
-        self.put("/machine-config", config).await
+        self.put_config("/machine-config", config).await
     }
 
     /// Configure the boot source (kernel, initrd, boot args).
     pub async fn put_boot_source(&self, config: &BootSource) -> Result<(), ApiError> {
-        self.put("/boot-source", config).await
+        self.put_config("/boot-source", config).await
     }
 
     /// Add or update a drive.
     pub async fn put_drive(&self, config: &DriveConfig) -> Result<(), ApiError> {
         let path = format!("/drives/{}", config.drive_id);
-        self.put(&path, config).await
+        self.put_config(&path, config).await
+    }
+
+    /// Point a drive at its backing file again after the file was grown.
+    /// Firecracker re-reads the size and notifies the guest of the new
+    /// capacity.
+    pub async fn patch_drive_path(
+        &self,
+        drive_id: &str,
+        path_on_host: &Path,
+    ) -> Result<(), ApiError> {
+        #[derive(Serialize)]
+        struct DrivePatch<'a> {
+            drive_id: &'a str,
+            path_on_host: &'a Path,
+        }
+        let path = format!("/drives/{drive_id}");
+        let body = serde_json::to_vec(&DrivePatch {
+            drive_id,
+            path_on_host,
+        })?;
+        self.send(Method::PATCH, &path, Some(body), self.timeouts.request)
+            .await?;
+        Ok(())
     }
 
     /// Add or update a network interface.
     pub async fn put_network_interface(&self, config: &NetworkInterface) -> Result<(), ApiError> {
         let path = format!("/network-interfaces/{}", config.iface_id);
-        self.put(&path, config).await
+        self.put_config(&path, config).await
     }
 
     /// Configure vsock device.
     pub async fn put_vsock(&self, config: &VsockConfig) -> Result<(), ApiError> {
-        self.put("/vsock", config).await
+        self.put_config("/vsock", config).await
     }
 
-    /// Start the microVM instance.
-    pub async fn start_instance(&self) -> Result<(), ApiError> {
-        #[derive(Serialize)]
-        struct Action {
-            action_type: &'static str,
+    /// Start the microVM instance and wait until Firecracker reports it
+    /// running.
+    ///
+    /// If the action call fails without a fault (timeout, dropped
+    /// connection) the VM may have started anyway; the state poll decides.
+    pub async fn start_instance(&self) -> Result<InstanceInfo, ApiError> {
+        if let Err(e) = self.action("InstanceStart").await {
+            if !e.is_unanswered() {
+                return Err(e);
+            }
+            warn!(error = %e, "InstanceStart outcome unknown, checking VM state");
         }
-        self.put(
-            "/actions",
-            &Action {
-                action_type: "InstanceStart",
-            },
-        )
-        .await
+        self.wait_for_state(VmState::Running).await
     }
 
     /// Send CtrlAltDel to the guest (graceful shutdown).
     pub async fn send_ctrl_alt_del(&self) -> Result<(), ApiError> {
-        #[derive(Serialize)]
-        struct Action {
-            action_type: &'static str,
-        }
-        self.put(
-            "/actions",
-            &Action {
-                action_type: "SendCtrlAltDel",
-            },
+        self.action("SendCtrlAltDel").await
+    }
+
+    /// Pause the microVM and wait until it is paused.
+    pub async fn pause(&self) -> Result<InstanceInfo, ApiError> {
+        self.set_vm_state(VmState::Paused).await
+    }
+
+    /// Resume the microVM and wait until it is running.
+    pub async fn resume(&self) -> Result<InstanceInfo, ApiError> {
+        self.set_vm_state(VmState::Running).await
+    }
+
+    /// Describe the instance (`GET /`): its state and VMM version.
+    pub async fn describe_instance(&self) -> Result<InstanceInfo, ApiError> {
+        let body = self
+            .send(Method::GET, "/", None, self.timeouts.request)
+            .await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Poll `describe_instance` until the VM reports `expected`.
+    pub async fn wait_for_state(&self, expected: VmState) -> Result<InstanceInfo, ApiError> {
+        poll_state(
+            expected,
+            self.timeouts.state_change,
+            STATE_POLL_INTERVAL,
+            || self.describe_instance(),
         )
         .await
     }
 
-    /// Pause the microVM.
-    pub async fn pause(&self) -> Result<(), ApiError> {
+    async fn set_vm_state(&self, state: VmState) -> Result<InstanceInfo, ApiError> {
         #[derive(Serialize)]
         struct State {
             state: &'static str,
         }
-        self.patch("/vm", &State { state: "Paused" }).await
+        let requested = match state {
+            VmState::Paused => "Paused",
+            _ => "Resumed",
+        };
+        let body = serde_json::to_vec(&State { state: requested })?;
+        if let Err(e) = self
+            .send(Method::PATCH, "/vm", Some(body), self.timeouts.action)
+            .await
+        {
+            if !e.is_unanswered() {
+                return Err(e);
+            }
+            warn!(error = %e, state = %state, "VM state change outcome unknown, checking VM state");
+        }
+        self.wait_for_state(state).await
     }
 
-    /// Resume the microVM.
-    pub async fn resume(&self) -> Result<(), ApiError> {
+    /// Perform an instance action (`PUT /actions`). Never retried.
+    async fn action(&self, action_type: &'static str) -> Result<(), ApiError> {
         #[derive(Serialize)]
-        struct State {
-            state: &'static str,
+        struct Action {
+            action_type: &'static str,
         }
-        self.patch("/vm", &State { state: "Resumed" }).await
+        let body = serde_json::to_vec(&Action { action_type })?;
+        self.send(Method::PUT, "/actions", Some(body), self.timeouts.action)
+            .await?;
+        Ok(())
     }
 
-    /// Get instance info.
-    pub async fn get_instance_info(&self) -> Result<InstanceInfo, ApiError> {
-        self.get("/").await
+    /// Perform a pre-boot configuration PUT, retrying failures that may not
+    /// have reached Firecracker. Safe because the PUT replaces the resource.
+    async fn put_config<T: Serialize>(&self, path: &str, body: &T) -> Result<(), ApiError> {
+        let body = serde_json::to_vec(body)?;
+        let mut attempt = 1;
+        loop {
+            match self
+                .send(Method::PUT, path, Some(body.clone()), self.timeouts.request)
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(e) if e.is_unanswered() && attempt < MAX_PUT_ATTEMPTS => {
+                    warn!(path = path, attempt = attempt, error = %e, "Retrying Firecracker API call");
+                    tokio::time::sleep(retry_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    error!(path = path, attempt = attempt, error = %e, "Firecracker API error");
+                    return Err(e);
+                }
+            }
+        }
     }
 
-    /// Perform a PUT request.
-    async fn put<T: Serialize>(&self, path: &str, body: &T) -> Result<(), ApiError> {
-        let body_bytes = serde_json::to_vec(body)?;
-        let uri = Uri::new(&self.socket_path, path);
-
-        debug!(path = path, "PUT request to Firecracker API");
-
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri(uri)
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json")
-            .body(Body::from(body_bytes))?;
-
-        let response = self.client.request(request).await?;
-        let status = response.status();
+    /// Send one request and return the response body of a 2xx response.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<Bytes, ApiError> {
+        let request_name = format!("{method} {path}");
+        debug!(request = %request_name, "Firecracker API request");
+
+        let builder = Request::builder()
+            .method(method)
+            .uri(Uri::new(&self.socket_path, path))
+            .header("Accept", "application/json");
+        let request = match body {
+            Some(body) => builder
+                .header("Content-Type", "application/json")
+                .body(Body::from(body))?,
+            None => builder.body(Body::empty())?,
+        };
+
+        let exchange = async {
+            let response = self.client.request(request).await?;
+            let status = response.status();
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            Ok::<_, ApiError>((status, body))
+        };
+        let (status, body) = tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| ApiError::Timeout {
+                request: request_name.clone(),
+                after: timeout,
+            })??;
 
         if status.is_success() {
-            Ok(())
+            Ok(body)
         } else {
-            let body = hyper::body::aggregate(response.into_body()).await?;
-            let message = String::from_utf8_lossy(body.chunk()).to_string();
-            error!(status = %status, message = %message, "Firecracker API error");
-            Err(ApiError::Api {
+            Err(ApiError::Fault {
+                request: request_name,
                 status: status.as_u16(),
-                message,
+                fault_message: fault_message(&body),
             })
         }
     }
+}
 
-    /// Perform a PATCH request.
-    async fn patch<T: Serialize>(&self, path: &str, body: &T) -> Result<(), ApiError> {
-        let body_bytes = serde_json::to_vec(body)?;
-        let uri = Uri::new(&self.socket_path, path);
-
-        debug!(path = path, "PATCH request to Firecracker API");
-
-        let request = Request::builder()
-            .method(Method::PATCH)
-            .uri(uri)
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json")
-            .body(Body::from(body_bytes))?;
+/// Backoff before retry number `attempt` (1-based).
+fn retry_backoff(attempt: u32) -> Duration {
+    RETRY_BACKOFF * 2u32.pow(attempt.saturating_sub(1))
+}
 
-        let response = self.client.request(request).await?;
-        let status = response.status();
+/// The `fault_message` of a Firecracker error body, or the body itself when
+/// it is not a fault object.
+fn fault_message(body: &[u8]) -> String {
+    #[derive(Deserialize)]
+    struct Fault {
+        fault_message: String,
+    }
+    match serde_json::from_slice::<Fault>(body) {
+        Ok(fault) => fault.fault_message,
+        Err(_) => String::from_utf8_lossy(body).trim().to_string(),
+    }
+}
 
-        if status.is_success() {
-            Ok(())
-        } else {
-            let body = hyper::body::aggregate(response.into_body()).await?;
-            let message = String::from_utf8_lossy(body.chunk()).to_string();
-            Err(ApiError::Api {
-                status: status.as_u16(),
-                message,
-            })
+/// Call `describe` every `interval` until the VM reports `expected`, for at
+/// most `timeout`. Unanswered calls are tolerated until then; a fault ends
+/// the wait.
+async fn poll_state<F, Fut>(
+    expected: VmState,
+    timeout: Duration,
+    interval: Duration,
+    mut describe: F,
+) -> Result<InstanceInfo, ApiError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<InstanceInfo, ApiError>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut last_seen = None;
+    loop {
+        match describe().await {
+            Ok(info) if info.state == expected.as_str() => return Ok(info),
+            Ok(info) => last_seen = Some(info.state),
+            Err(e) if e.is_unanswered() => {
+                debug!(error = %e, "describe_instance failed while waiting for VM state");
+            }
+            Err(e) => return Err(e),
         }
+        if tokio::time::Instant::now() + interval > deadline {
+            return Err(ApiError::UnexpectedState {
+                expected,
+                last_seen,
+            });
+        }
+        tokio::time::sleep(interval).await;
     }
+}
 
-    /// Perform a GET request.
-    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
-        let uri = Uri::new(&self.socket_path, path);
-
-        debug!(path = path, "GET request to Firecracker API");
-
-        let request = Request::builder()
-            .method(Method::GET)
-            .uri(uri)
-            .header("Accept", "application/json")
-            .body(Body::empty())?;
-
-        let response = self.client.request(request).await?;
-        let status = response.status();
-        let body = hyper::body::aggregate(response.into_body()).await?;
+/// VM state as reported by `describe_instance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmState {
+    NotStarted,
+    Running,
+    Paused,
+}
 
-        if status.is_success() {
-            let result = serde_json::from_reader(body.reader())?;
-            Ok(result)
-        } else {
-            let message = String::from_utf8_lossy(body.chunk()).to_string();
-            Err(ApiError::Api {
-                status: status.as_u16(),
-                message,
-            })
+impl VmState {
+    /// The state as Firecracker spells it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VmState::NotStarted => "Not started",
+            VmState::Running => "Running",
+            VmState::Paused => "Paused",
         }
     }
 }
 
+impl std::fmt::Display for VmState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Instance information from Firecracker.
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct InstanceInfo {
@@ -234,11 +422,125 @@ pub struct InstanceInfo {
     pub vmm_version: String,
 }
 
-impl From<hyper::http::Error> for ApiError {
-    fn from(err: hyper::http::Error) -> Self {
-        ApiError::Api {
-            status: 0,
-            message: err.to_string(),
+impl InstanceInfo {
+    /// Whether Firecracker reports the VM in `state`.
+    pub fn is(&self, state: VmState) -> bool {
+        self.state == state.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    fn info(state: &str) -> InstanceInfo {
+        InstanceInfo {
+            app_name: "Firecracker".to_string(),
+            id: "inst_1".to_string(),
+            state: state.to_string(),
+            vmm_version: "1.7.0".to_string(),
         }
     }
+
+    fn timeout() -> ApiError {
+        ApiError::Timeout {
+            request: "GET /".to_string(),
+            after: Duration::from_secs(1),
+        }
+    }
+
+    fn fault(status: u16) -> ApiError {
+        ApiError::Fault {
+            request: "PUT /actions".to_string(),
+            status,
+            fault_message: "nope".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fault_message() {
+        assert_eq!(
+            fault_message(br#"{"fault_message":"The requested operation is not supported after starting the microVM."}"#),
+            "The requested operation is not supported after starting the microVM."
+        );
+        assert_eq!(fault_message(b"Bad Request\n"), "Bad Request");
+    }
+
+    #[test]
+    fn test_only_unanswered_errors_are_retried() {
+        assert!(timeout().is_unanswered());
+        assert!(!fault(500).is_unanswered());
+        assert!(!fault(400).is_unanswered());
+        assert!(!ApiError::UnexpectedState {
+            expected: VmState::Running,
+            last_seen: None,
+        }
+        .is_unanswered());
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        assert_eq!(retry_backoff(1), Duration::from_millis(100));
+        assert_eq!(retry_backoff(2), Duration::from_millis(200));
+    }
+
+    async fn poll(
+        responses: Vec<Result<InstanceInfo, ApiError>>,
+        expected: VmState,
+    ) -> Result<InstanceInfo, ApiError> {
+        let responses = Mutex::new(VecDeque::from(responses));
+        poll_state(
+            expected,
+            Duration::from_millis(50),
+            Duration::from_millis(1),
+            || {
+                let next = responses
+                    .lock()
+                    .unwrap()
+                    .pop_front()
+                    .unwrap_or_else(|| Ok(info("Not started")));
+                async move { next }
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_poll_state_waits_for_transition() {
+        let result = poll(
+            vec![Ok(info("Not started")), Err(timeout()), Ok(info("Running"))],
+            VmState::Running,
+        )
+        .await
+        .unwrap();
+        assert!(result.is(VmState::Running));
+    }
+
+    #[tokio::test]
+    async fn test_poll_state_gives_up() {
+        let err = poll(vec![], VmState::Running).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ApiError::UnexpectedState {
+                expected: VmState::Running,
+                last_seen: Some(ref state),
+            } if state == "Not started"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_poll_state_stops_on_fault() {
+        let err = poll(vec![Err(fault(400))], VmState::Running)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::Fault { status: 400, .. }));
+
+        let err = poll(vec![Err(fault(500))], VmState::Running)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::Fault { status: 500, .. }));
+    }
 }
@@ -68,6 +68,21 @@ impl BootSource {
         self
     }
 
+    /// Append the `platform.*` parameters guest-init reads from
+    /// `/proc/cmdline`: the instance ID and the boot ID it reports in its
+    /// handshake, so guest and agent agree on which boot is running.
+    pub fn with_platform_args(mut self, instance_id: &str, boot_id: &str) -> Self {
+        let args = self.boot_args.get_or_insert_with(String::new);
+        if !args.is_empty() {
+            args.push(' ');
+        }
+        args.push_str(&format!(
+            "platform.instance_id={} platform.boot_id={}",
+            instance_id, boot_id
+        ));
+        self
+    }
+
     /// Set initrd path.
     pub fn with_initrd(mut self, path: PathBuf) -> Self {
         self.initrd_path = Some(path);
@@ -313,6 +328,23 @@ mod tests {
         assert_eq!(config.mem_size_mib, 512);
     }
 
+    #[test]
+    fn test_boot_source_platform_args() {
+        let source = BootSource::new("/kernel".into()).with_platform_args("inst_1", "boot_2");
+        assert_eq!(
+            source.boot_args.as_deref(),
+            Some("console=ttyS0 reboot=k panic=1 pci=off ipv6.disable=0 platform.instance_id=inst_1 platform.boot_id=boot_2")
+        );
+
+        let mut source = BootSource::new("/kernel".into());
+        source.boot_args = None;
+        let source = source.with_platform_args("inst_1", "boot_2");
+        assert_eq!(
+            source.boot_args.as_deref(),
+            Some("platform.instance_id=inst_1 platform.boot_id=boot_2")
+        );
+    }
+
     #[test]
     fn test_generate_mac_address() {
         let mac1 = generate_mac_address("instance-1");
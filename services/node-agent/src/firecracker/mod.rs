@@ -8,6 +8,7 @@
 //! - `api`: HTTP client for Firecracker's Unix socket API
 //! - `config`: VM configuration structures (machine, boot, drives, network)
 //! - `jailer`: Sandbox configuration and cgroup setup
+//! - `orphans`: Orphaned VM adoption and garbage collection at startup
 //! - `runtime`: Full `Runtime` trait implementation
 //!
 //! ## Reference
@@ -20,9 +21,10 @@
 mod api;
 mod config;
 mod jailer;
+pub mod orphans;
 mod runtime;
 
-pub use api::FirecrackerClient;
+pub use api::{ApiError, ApiTimeouts, FirecrackerClient, VmState};
 pub use config::{BootSource, DriveConfig, MachineConfig, NetworkInterface, VsockConfig};
 pub use jailer::JailerConfig;
 pub use runtime::{FirecrackerRuntime, FirecrackerRuntimeConfig};
@@ -0,0 +1,588 @@
+//! Orphaned VM discovery, adoption and garbage collection at agent start.
+//!
+//! A crashed agent, a lost state store or a failed stop can leave host
+//! resources behind that no actor owns: Firecracker processes, TAP devices,
+//! cgroups and instance directories. Before the supervisor starts, the agent
+//! scans for resources matching its naming scheme and, per instance:
+//!
+//! - keeps it if the state store already records a live VM for an instance in
+//!   the current plan (the instance actor re-adopts it)
+//! - adopts it if it is an unrecorded, live VM for an instance in the current
+//!   plan running the desired image: a state store record is written so the
+//!   instance actor re-adopts it
+//! - collects it otherwise: kills the process, deletes the TAP device and
+//!   cgroup, removes the instance directory
+//!
+//! Live VMs are only collected when the current plan is known; without it
+//! they are left alone (deferred) and only dead leftovers are collected.
+//!
+//! Configuration: `GHOST_ORPHAN_GC` = `enforce` (default), `dry-run` (log
+//! what would be done) or `off`.
+//!
+//! Naming scheme (see `FirecrackerRuntime`, `network::tap`, `jailer`):
+//! - `<data_dir>/instances/<instance_id>/` with `firecracker.pid` and `vm.json`
+//! - processes with `--id <instance_id>` and an API socket under the
+//!   instances directory
+//! - TAP devices `tap-<last 8 chars of instance_id>`
+//! - cgroups `/sys/fs/cgroup/firecracker/<instance_id>`
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::client::{InstanceDesiredState, InstancePlan, NodePlan};
+use crate::network::{remove_tap, TapConfig};
+use crate::state::{InstancePhase, InstanceRecord, StateStore};
+
+/// Per-instance VM metadata written next to the pid file at boot.
+pub const VM_METADATA_FILE: &str = "vm.json";
+
+/// What identifies a VM across agent processes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmMetadata {
+    pub boot_id: String,
+    pub guest_cid: u32,
+    pub image_digest: String,
+}
+
+/// Orphan collection mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanGcMode {
+    Off,
+    DryRun,
+    Enforce,
+}
+
+impl OrphanGcMode {
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("GHOST_ORPHAN_GC").as_deref() {
+            Err(_) | Ok("") | Ok("enforce") => Ok(Self::Enforce),
+            Ok("dry-run") => Ok(Self::DryRun),
+            Ok("off") => Ok(Self::Off),
+            Ok(other) => bail!("GHOST_ORPHAN_GC must be enforce, dry-run or off (got {other})"),
+        }
+    }
+}
+
+/// Host locations scanned for VM resources.
+#[derive(Debug, Clone)]
+pub struct HostPaths {
+    pub instances_dir: PathBuf,
+    pub proc_dir: PathBuf,
+    pub net_dir: PathBuf,
+    pub cgroup_dir: PathBuf,
+}
+
+impl HostPaths {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            instances_dir: data_dir.join("instances"),
+            proc_dir: PathBuf::from("/proc"),
+            net_dir: PathBuf::from("/sys/class/net"),
+            cgroup_dir: PathBuf::from("/sys/fs/cgroup/firecracker"),
+        }
+    }
+}
+
+/// Host resources belonging to one instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostResources {
+    /// `None` for a TAP device that matches no known instance.
+    pub instance_id: Option<String>,
+    /// Live Firecracker (or jailer) processes.
+    pub pids: Vec<u32>,
+    pub instance_dir: Option<PathBuf>,
+    pub tap: Option<String>,
+    pub cgroup: Option<PathBuf>,
+    pub metadata: Option<VmMetadata>,
+}
+
+/// What to do with an instance's resources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Disposition {
+    /// Owned by a state store record; the instance actor handles it.
+    Keep,
+    /// Record it in the state store so the instance actor re-adopts it.
+    Adopt,
+    /// Live, but the plan is unknown; leave it alone.
+    Defer,
+    Collect {
+        reason: &'static str,
+    },
+}
+
+/// Outcome of a sweep.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub kept: usize,
+    pub adopted: usize,
+    pub deferred: usize,
+    pub collected: usize,
+}
+
+/// Find VM resources on the host, grouped by instance. `known_ids` (state
+/// store and plan instances) are used to attribute TAP devices, whose names
+/// carry only an instance ID suffix.
+pub fn scan(paths: &HostPaths, known_ids: &[String]) -> Vec<HostResources> {
+    let mut found: BTreeMap<String, HostResources> = BTreeMap::new();
+
+    for (id, dir) in list_dirs(&paths.instances_dir) {
+        let res = slot(&mut found, &id);
+        res.metadata = fs::read_to_string(dir.join(VM_METADATA_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        res.instance_dir = Some(dir);
+    }
+    for (pid, id) in firecracker_processes(paths) {
+        slot(&mut found, &id).pids.push(pid);
+    }
+    for (id, dir) in list_dirs(&paths.cgroup_dir) {
+        slot(&mut found, &id).cgroup = Some(dir);
+    }
+
+    let mut candidates: Vec<String> = found.keys().cloned().collect();
+    candidates.extend(known_ids.iter().cloned());
+    let tap_owner: HashMap<String, String> = candidates
+        .into_iter()
+        .map(|id| (TapConfig::new(&id, "").tap_name(), id))
+        .collect();
+
+    let mut unattributed = Vec::new();
+    for (name, _) in list_dirs(&paths.net_dir) {
+        if !name.starts_with("tap-") {
+            continue;
+        }
+        match tap_owner.get(&name) {
+            Some(id) => slot(&mut found, id).tap = Some(name),
+            None => unattributed.push(HostResources {
+                tap: Some(name),
+                ..Default::default()
+            }),
+        }
+    }
+
+    found.into_values().chain(unattributed).collect()
+}
+
+/// Decide what to do with an instance's resources. `desired` is `None` when
+/// the current plan could not be fetched.
+pub fn classify(
+    res: &HostResources,
+    record: Option<&InstanceRecord>,
+    desired: Option<&HashMap<String, &InstancePlan>>,
+) -> Disposition {
+    if res.instance_id.is_none() {
+        return Disposition::Collect {
+            reason: "TAP device matches no known instance",
+        };
+    }
+    if res.pids.is_empty() {
+        // Leftovers of a dead VM would conflict with a fresh boot.
+        return Disposition::Collect {
+            reason: "VM not running",
+        };
+    }
+    let Some(desired) = desired else {
+        return Disposition::Defer;
+    };
+    let Some(plan) = res.instance_id.as_ref().and_then(|id| desired.get(id)) else {
+        return Disposition::Collect {
+            reason: "instance not in current plan",
+        };
+    };
+
+    if record.is_some_and(|r| matches!(r.phase, InstancePhase::Starting | InstancePhase::Running)) {
+        return Disposition::Keep;
+    }
+    match &res.metadata {
+        Some(meta) if meta.image_digest == plan.image.resolved_digest => Disposition::Adopt,
+        Some(_) => Disposition::Collect {
+            reason: "VM runs an outdated image",
+        },
+        None => Disposition::Collect {
+            reason: "no VM metadata to adopt from",
+        },
+    }
+}
+
+/// Scan, classify and act on every instance's resources.
+pub async fn sweep(
+    paths: &HostPaths,
+    store: &std::sync::Mutex<StateStore>,
+    plan: Option<&NodePlan>,
+    mode: OrphanGcMode,
+) -> SweepReport {
+    let mut report = SweepReport::default();
+    if mode == OrphanGcMode::Off {
+        info!("Orphan scan disabled (GHOST_ORPHAN_GC=off)");
+        return report;
+    }
+    let dry_run = mode == OrphanGcMode::DryRun;
+
+    let records: HashMap<String, InstanceRecord> = {
+        let store = store.lock().unwrap_or_else(|e| e.into_inner());
+        match store.list_instances() {
+            Ok(records) => records
+                .into_iter()
+                .map(|r| (r.instance_id.clone(), r))
+                .collect(),
+            Err(e) => {
+                warn!(error = %e, "Orphan scan skipped: cannot read state store");
+                return report;
+            }
+        }
+    };
+    let desired: Option<HashMap<String, &InstancePlan>> = plan.map(|plan| {
+        plan.instances
+            .iter()
+            .filter(|a| a.desired_state != InstanceDesiredState::Stopped)
+            .filter_map(|a| a.workload.as_ref().map(|w| (a.instance_id.clone(), w)))
+            .collect()
+    });
+    if desired.is_none() {
+        warn!("Current plan unavailable; live VMs outside the state store are left alone");
+    }
+
+    let mut known_ids: Vec<String> = records.keys().cloned().collect();
+    if let Some(desired) = &desired {
+        known_ids.extend(desired.keys().cloned());
+    }
+
+    for res in scan(paths, &known_ids) {
+        let record = res.instance_id.as_ref().and_then(|id| records.get(id));
+        let disposition = classify(&res, record, desired.as_ref());
+        let instance_id = res.instance_id.as_deref().unwrap_or("-");
+
+        match disposition {
+            Disposition::Keep => report.kept += 1,
+            Disposition::Defer => {
+                info!(
+                    instance_id,
+                    pids = ?res.pids,
+                    "Leaving VM alone until the plan is known"
+                );
+                report.deferred += 1;
+            }
+            Disposition::Adopt => {
+                let Some(meta) = &res.metadata else { continue };
+                info!(
+                    instance_id,
+                    pids = ?res.pids,
+                    boot_id = %meta.boot_id,
+                    dry_run,
+                    "Adopting orphaned VM"
+                );
+                if !dry_run {
+                    let now = chrono::Utc::now().timestamp();
+                    let record = InstanceRecord {
+                        instance_id: instance_id.to_string(),
+                        phase: InstancePhase::Running,
+                        spec_revision: 0,
+                        boot_id: meta.boot_id.clone(),
+                        socket_path: None,
+                        rootdisk_digest: Some(meta.image_digest.clone()),
+                        guest_cid: Some(meta.guest_cid),
+                        created_at: now,
+                        updated_at: now,
+                    };
+                    let store = store.lock().unwrap_or_else(|e| e.into_inner());
+                    if let Err(e) = store.upsert_instance(&record) {
+                        warn!(instance_id, error = %e, "Failed to record adopted VM");
+                        continue;
+                    }
+                }
+                report.adopted += 1;
+            }
+            Disposition::Collect { reason } => {
+                warn!(
+                    instance_id,
+                    reason,
+                    pids = ?res.pids,
+                    tap = ?res.tap,
+                    cgroup = ?res.cgroup,
+                    instance_dir = ?res.instance_dir,
+                    dry_run,
+                    "Collecting orphaned VM resources"
+                );
+                if !dry_run {
+                    collect(&res).await;
+                    if record.is_some() {
+                        let store = store.lock().unwrap_or_else(|e| e.into_inner());
+                        let _ = store.delete_instance(instance_id);
+                    }
+                }
+                report.collected += 1;
+            }
+        }
+    }
+
+    info!(
+        kept = report.kept,
+        adopted = report.adopted,
+        deferred = report.deferred,
+        collected = report.collected,
+        dry_run,
+        "Orphan scan complete"
+    );
+    report
+}
+
+/// Remove an instance's resources, continuing past failures.
+async fn collect(res: &HostResources) {
+    for &pid in &res.pids {
+        if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } != 0 {
+            warn!(pid, error = %std::io::Error::last_os_error(), "Failed to kill orphaned process");
+        }
+    }
+    if !res.pids.is_empty() {
+        // Let the kernel reap the processes before removing their cgroup.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    if let Some(tap) = &res.tap {
+        if let Err(e) = remove_tap(tap) {
+            warn!(tap = %tap, error = %e, "Failed to delete orphaned TAP device");
+        }
+    }
+    if let Some(cgroup) = &res.cgroup {
+        if let Err(e) = fs::remove_dir(cgroup) {
+            warn!(cgroup = %cgroup.display(), error = %e, "Failed to remove orphaned cgroup");
+        }
+    }
+    if let Some(dir) = &res.instance_dir {
+        if let Err(e) = fs::remove_dir_all(dir) {
+            warn!(dir = %dir.display(), error = %e, "Failed to remove orphaned instance directory");
+        }
+    }
+}
+
+fn slot<'a>(found: &'a mut BTreeMap<String, HostResources>, id: &str) -> &'a mut HostResources {
+    found
+        .entry(id.to_string())
+        .or_insert_with(|| HostResources {
+            instance_id: Some(id.to_string()),
+            ..Default::default()
+        })
+}
+
+/// Subdirectories (or entries, for `/sys/class/net`) of `dir` by name.
+fn list_dirs(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Some((e.file_name().into_string().ok()?, e.path())))
+        .collect()
+}
+
+/// Live Firecracker/jailer processes belonging to this agent, as
+/// `(pid, instance_id)`.
+fn firecracker_processes(paths: &HostPaths) -> Vec<(u32, String)> {
+    list_dirs(&paths.proc_dir)
+        .into_iter()
+        .filter_map(|(name, dir)| {
+            let pid: u32 = name.parse().ok()?;
+            let cmdline = fs::read(dir.join("cmdline")).ok()?;
+            let args: Vec<String> = cmdline
+                .split(|&b| b == 0)
+                .map(|a| String::from_utf8_lossy(a).into_owned())
+                .collect();
+            let binary = Path::new(args.first()?).file_name()?.to_str()?;
+            if binary != "firecracker" && binary != "jailer" {
+                return None;
+            }
+            // Only VMs started by this agent: the API socket lives under our
+            // instances directory.
+            let socket = flag_value(&args, "--api-sock")?;
+            if !Path::new(socket).starts_with(&paths.instances_dir) {
+                return None;
+            }
+            Some((pid, flag_value(&args, "--id")?.to_string()))
+        })
+        .collect()
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let pos = args.iter().position(|a| a == flag)?;
+    args.get(pos + 1).map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> (tempfile::TempDir, HostPaths) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = HostPaths {
+            instances_dir: dir.path().join("data/instances"),
+            proc_dir: dir.path().join("proc"),
+            net_dir: dir.path().join("net"),
+            cgroup_dir: dir.path().join("cgroup"),
+        };
+        for d in [
+            &paths.instances_dir,
+            &paths.proc_dir,
+            &paths.net_dir,
+            &paths.cgroup_dir,
+        ] {
+            fs::create_dir_all(d).unwrap();
+        }
+        (dir, paths)
+    }
+
+    fn add_instance_dir(paths: &HostPaths, id: &str, digest: Option<&str>) {
+        let dir = paths.instances_dir.join(id);
+        fs::create_dir_all(&dir).unwrap();
+        if let Some(digest) = digest {
+            let meta = VmMetadata {
+                boot_id: format!("boot_{id}"),
+                guest_cid: 7,
+                image_digest: digest.to_string(),
+            };
+            fs::write(
+                dir.join(VM_METADATA_FILE),
+                serde_json::to_string(&meta).unwrap(),
+            )
+            .unwrap();
+        }
+    }
+
+    fn add_process(paths: &HostPaths, pid: u32, id: &str) {
+        let dir = paths.proc_dir.join(pid.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        let socket = paths.instances_dir.join(id).join("firecracker.socket");
+        let cmdline = format!(
+            "/usr/bin/firecracker\0--api-sock\0{}\0--id\0{id}\0",
+            socket.display()
+        );
+        fs::write(dir.join("cmdline"), cmdline).unwrap();
+    }
+
+    #[test]
+    fn test_scan_groups_resources_by_instance() {
+        let (_dir, paths) = host();
+        add_instance_dir(&paths, "inst_0000aaaa", Some("sha256:a"));
+        add_process(&paths, 4242, "inst_0000aaaa");
+        fs::create_dir_all(paths.net_dir.join("tap-0000aaaa")).unwrap();
+        fs::create_dir_all(paths.net_dir.join("tap-ffffffff")).unwrap();
+        fs::create_dir_all(paths.net_dir.join("eth0")).unwrap();
+        fs::create_dir_all(paths.cgroup_dir.join("inst_0000bbbb")).unwrap();
+        // Another tool's firecracker (socket elsewhere) is ignored.
+        let other = paths.proc_dir.join("999");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(
+            other.join("cmdline"),
+            "firecracker\0--api-sock\0/tmp/other.sock\0--id\0x\0",
+        )
+        .unwrap();
+
+        let found = scan(&paths, &[]);
+        assert_eq!(found.len(), 3);
+
+        let a = &found[0];
+        assert_eq!(a.instance_id.as_deref(), Some("inst_0000aaaa"));
+        assert_eq!(a.pids, vec![4242]);
+        assert_eq!(a.tap.as_deref(), Some("tap-0000aaaa"));
+        assert_eq!(a.metadata.as_ref().unwrap().guest_cid, 7);
+
+        let b = &found[1];
+        assert_eq!(b.instance_id.as_deref(), Some("inst_0000bbbb"));
+        assert!(b.cgroup.is_some());
+
+        let stray = &found[2];
+        assert_eq!(stray.instance_id, None);
+        assert_eq!(stray.tap.as_deref(), Some("tap-ffffffff"));
+    }
+
+    fn live(id: &str, digest: Option<&str>) -> HostResources {
+        HostResources {
+            instance_id: Some(id.to_string()),
+            pids: vec![1],
+            metadata: digest.map(|d| VmMetadata {
+                boot_id: "boot_1".to_string(),
+                guest_cid: 3,
+                image_digest: d.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn plan(digest: &str) -> InstancePlan {
+        serde_json::from_value(serde_json::json!({
+            "spec_version": "v1",
+            "org_id": "org", "app_id": "app", "env_id": "env",
+            "process_type": "web",
+            "instance_id": "inst_a",
+            "generation": 1,
+            "release_id": "rel",
+            "image": { "ref": "img:1", "digest": digest, "resolved_digest": digest, "os": "linux", "arch": "amd64" },
+            "manifest_hash": "h",
+            "command": ["./start"],
+            "resources": { "cpu_request": 1.0, "memory_limit_bytes": 1 },
+            "network": { "overlay_ipv6": "fd00::1", "gateway_ipv6": "fd00::1" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_classify() {
+        let p = plan("sha256:a");
+        let desired: HashMap<String, &InstancePlan> = [("inst_a".to_string(), &p)].into();
+
+        // Unrecorded live VM on the desired image is adopted.
+        assert_eq!(
+            classify(&live("inst_a", Some("sha256:a")), None, Some(&desired)),
+            Disposition::Adopt
+        );
+        // Outdated image or no metadata: collected.
+        assert!(matches!(
+            classify(&live("inst_a", Some("sha256:old")), None, Some(&desired)),
+            Disposition::Collect { .. }
+        ));
+        assert!(matches!(
+            classify(&live("inst_a", None), None, Some(&desired)),
+            Disposition::Collect { .. }
+        ));
+        // Not in the plan: collected; plan unknown: deferred.
+        assert!(matches!(
+            classify(&live("inst_b", Some("sha256:a")), None, Some(&desired)),
+            Disposition::Collect { .. }
+        ));
+        assert_eq!(
+            classify(&live("inst_b", Some("sha256:a")), None, None),
+            Disposition::Defer
+        );
+        // Dead leftovers are always collected.
+        let dead = HostResources {
+            instance_id: Some("inst_a".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            classify(&dead, None, None),
+            Disposition::Collect { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_dry_run_and_enforce() {
+        let (_dir, paths) = host();
+        add_instance_dir(&paths, "inst_dead", None);
+        let store = std::sync::Mutex::new(StateStore::open_in_memory().unwrap());
+
+        let report = sweep(&paths, &store, None, OrphanGcMode::DryRun).await;
+        assert_eq!(report.collected, 1);
+        assert!(paths.instances_dir.join("inst_dead").exists());
+
+        let report = sweep(&paths, &store, None, OrphanGcMode::Enforce).await;
+        assert_eq!(report.collected, 1);
+        assert!(!paths.instances_dir.join("inst_dead").exists());
+
+        let report = sweep(&paths, &store, None, OrphanGcMode::Off).await;
+        assert_eq!(report, SweepReport::default());
+    }
+}
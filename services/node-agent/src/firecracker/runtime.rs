@@ -18,28 +18,29 @@ use async_trait::async_trait;
 use chrono::Utc;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::sync::mpsc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
-use crate::client::{ControlPlaneClient, InstancePlan, WorkloadLogEntry};
+use crate::boot_failure::{boot_failure, ConsoleHints};
+use crate::client::{ControlPlaneClient, FailureReason, InstancePlan, WorkloadLogEntry};
+use crate::concurrency;
+use crate::console::{self, ConsoleLogs, ConsoleWriter};
 use crate::image::{parse_image_ref, ImagePuller};
-use crate::network::{create_tap, TapConfig, TapDevice};
-use crate::runtime::{Runtime, VmHandle};
+use crate::log_shipping::{LogShipper, LogShippingConfig, SpoolWriter};
+use crate::network::{adopt_tap, create_tap, TapConfig, TapDevice};
+use crate::runtime::{new_boot_id, RecoveredVm, Runtime, VmHandle};
+use crate::storage::{self, StorageBackend, StorageConfig};
+use crate::volume::{RestoreProgress, VolumeResizeError, VolumeRestoreError, VolumeSnapshotError};
 
-use super::api::FirecrackerClient;
+use super::api::{FirecrackerClient, VmState};
 use super::config::{
     generate_mac_address, BootSource, DriveConfig, MachineConfig, NetworkInterface, VsockConfig,
 };
 use super::jailer::SandboxManager;
-
-/// Default timeout for Firecracker API operations.
-const API_TIMEOUT: Duration = Duration::from_secs(30);
+use super::orphans::{VmMetadata, VM_METADATA_FILE};
 
 /// Default timeout for VM boot.
 const BOOT_TIMEOUT: Duration = Duration::from_secs(60);
-const LOG_BATCH_SIZE: usize = 100;
-const LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LOG_LINE_BYTES: usize = 16 * 1024;
 const DEFAULT_SCRATCH_DISK_BYTES: u64 = 1024 * 1024 * 1024;
 const GUEST_CID_START: u64 = 3;
@@ -63,8 +64,13 @@ pub struct FirecrackerRuntimeConfig {
     pub vm_uid: u32,
     /// GID to run VMs as (when using jailer).
     pub vm_gid: u32,
-    /// Scratch disk size in bytes.
+    /// Scratch disk size in bytes for plans that do not set
+    /// `resources.ephemeral_disk_bytes`.
     pub scratch_disk_bytes: u64,
+    /// Where volumes and instance disks live.
+    pub storage: StorageConfig,
+    /// Workload log buffering and upload.
+    pub logs: LogShippingConfig,
 }
 
 impl Default for FirecrackerRuntimeConfig {
@@ -79,6 +85,8 @@ impl Default for FirecrackerRuntimeConfig {
             vm_uid: 1000,
             vm_gid: 1000,
             scratch_disk_bytes: DEFAULT_SCRATCH_DISK_BYTES,
+            storage: StorageConfig::default(),
+            logs: LogShippingConfig::default(),
         }
     }
 }
@@ -91,8 +99,11 @@ struct InstanceState {
     /// Boot ID.
     #[allow(dead_code)]
     boot_id: String,
-    /// Firecracker process handle.
-    process: Child,
+    /// Firecracker process handle (`None` for VMs adopted from a previous
+    /// agent process, which are signalled by pid instead).
+    process: Option<Child>,
+    /// Firecracker process id.
+    pid: Option<u32>,
     /// API client for this instance.
     client: FirecrackerClient,
     /// Socket path.
@@ -102,8 +113,6 @@ struct InstanceState {
     guest_cid: u32,
     /// Image digest for cache release.
     image_digest: String,
-    /// Scratch disk path for cleanup.
-    scratch_path: PathBuf,
     /// TAP device for networking.
     tap_device: Option<TapDevice>,
     /// Sandbox manager (if using jailer).
@@ -114,10 +123,17 @@ struct InstanceState {
 pub struct FirecrackerRuntime {
     config: FirecrackerRuntimeConfig,
     instances: RwLock<HashMap<String, InstanceState>>,
-    boot_counter: AtomicU64,
     guest_cid_counter: AtomicU64,
     image_puller: Arc<ImagePuller>,
     control_plane: Option<Arc<ControlPlaneClient>>,
+    /// Ships guest stdout/stderr; `None` without a control plane.
+    log_shipper: Option<Arc<LogShipper>>,
+    /// Failure hints scraped from guest console output.
+    console_hints: ConsoleHints,
+    /// Captured guest console output, kept after the instance stops.
+    console: ConsoleLogs,
+    /// Volumes, root and scratch disks, and snapshots.
+    storage: Arc<dyn StorageBackend>,
 }
 
 impl FirecrackerRuntime {
@@ -127,20 +143,41 @@ impl FirecrackerRuntime {
         image_puller: Arc<ImagePuller>,
         control_plane: Option<Arc<ControlPlaneClient>>,
     ) -> Self {
+        let storage = storage::open(&config.storage, &config.data_dir);
+        let log_shipper = control_plane.as_ref().map(|client| {
+            Arc::new(LogShipper::new(
+                config.logs.clone(),
+                config.data_dir.join("logs"),
+                Arc::clone(client),
+            ))
+        });
         Self {
-            config,
             instances: RwLock::new(HashMap::new()),
-            boot_counter: AtomicU64::new(0),
             guest_cid_counter: AtomicU64::new(GUEST_CID_START),
             image_puller,
             control_plane,
+            log_shipper,
+            console_hints: ConsoleHints::new(),
+            console: ConsoleLogs::new(config.data_dir.join("console")),
+            config,
+            storage,
         }
     }
 
-    /// Generate a new boot ID.
-    fn next_boot_id(&self) -> String {
-        let counter = self.boot_counter.fetch_add(1, Ordering::SeqCst);
-        format!("boot_{:016x}", counter)
+    /// Prepare the storage backend; call once before starting VMs.
+    pub async fn init_storage(&self) -> Result<()> {
+        let storage = Arc::clone(&self.storage);
+        tokio::task::spawn_blocking(move || storage.init()).await??;
+        info!(backend = self.storage.name(), "Storage backend ready");
+        Ok(())
+    }
+
+    /// Ship workload logs buffered by a previous agent process; call once
+    /// at startup.
+    pub fn resume_log_shipping(&self) {
+        if let Some(shipper) = &self.log_shipper {
+            shipper.resume();
+        }
     }
 
     async fn allocate_guest_cid(&self) -> u32 {
@@ -170,19 +207,23 @@ impl FirecrackerRuntime {
         self.config.data_dir.join("instances").join(instance_id)
     }
 
-    fn scratch_path(&self, instance_id: &str) -> PathBuf {
-        self.instance_dir(instance_id).join("scratch.ext4")
+    fn pid_path(&self, instance_id: &str) -> PathBuf {
+        self.instance_dir(instance_id).join("firecracker.pid")
     }
 
     fn vsock_path(&self, instance_id: &str) -> PathBuf {
         self.instance_dir(instance_id).join("vsock.sock")
     }
 
-    fn volume_path(&self, volume_id: &str) -> PathBuf {
-        self.config
-            .data_dir
-            .join("volumes")
-            .join(format!("{volume_id}.ext4"))
+    /// Drop the storage backend's disks for an instance.
+    async fn release_instance_disks(&self, instance_id: &str) {
+        let storage = Arc::clone(&self.storage);
+        let instance_id = instance_id.to_string();
+        if let Err(e) =
+            tokio::task::spawn_blocking(move || storage.release_instance(&instance_id)).await
+        {
+            warn!(error = %e, "Failed to release instance disks");
+        }
     }
 
     /// Start Firecracker process (without jailer).
@@ -214,6 +255,13 @@ impl FirecrackerRuntime {
             .stderr(Stdio::piped())
             .spawn()?;
 
+        // Recorded so a later agent process can re-adopt (or clean up) the VM.
+        if let Some(pid) = child.id() {
+            if let Err(e) = fs::write(self.pid_path(instance_id), pid.to_string()) {
+                warn!(instance_id = %instance_id, error = %e, "Failed to write pid file");
+            }
+        }
+
         // Wait for socket to appear
         let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
         while tokio::time::Instant::now() < deadline {
@@ -240,6 +288,7 @@ impl FirecrackerRuntime {
         root_disk_path: &Path,
         scratch_path: &Path,
         guest_cid: u32,
+        boot_id: &str,
     ) -> Result<Option<TapDevice>> {
         let instance_id = &plan.instance_id;
 
@@ -257,7 +306,8 @@ impl FirecrackerRuntime {
         client.put_machine_config(&machine).await?;
 
         // Configure boot source
-        let mut boot_source = BootSource::new(self.config.kernel_path.clone());
+        let mut boot_source = BootSource::new(self.config.kernel_path.clone())
+            .with_platform_args(instance_id, boot_id);
         if let Some(initrd) = &self.config.initrd_path {
             boot_source = boot_source.with_initrd(initrd.clone());
         }
@@ -275,12 +325,15 @@ impl FirecrackerRuntime {
         mounts.sort_by(|a, b| a.volume_id.cmp(&b.volume_id));
 
         for (idx, mount) in mounts.iter().enumerate() {
-            let path = self.volume_path(&mount.volume_id);
+            let path = self.storage.volume_path(&mount.volume_id);
             if !path.exists() {
-                return Err(anyhow!(
-                    "volume device missing for {} at {}",
-                    mount.volume_id,
-                    path.display()
+                return Err(boot_failure(
+                    FailureReason::VolumeAttachFailed,
+                    format!(
+                        "volume device missing for {} at {}",
+                        mount.volume_id,
+                        path.display()
+                    ),
                 ));
             }
 
@@ -297,7 +350,10 @@ impl FirecrackerRuntime {
             let tap_config = TapConfig::new(instance_id, &plan.network.overlay_ipv6);
             let tap_device = create_tap(&tap_config).map_err(|e| {
                 error!(instance_id = %instance_id, error = %e, "Failed to create TAP device");
-                anyhow!("Failed to create TAP device: {}", e)
+                boot_failure(
+                    FailureReason::NetworkSetupFailed,
+                    format!("Failed to create TAP device: {}", e),
+                )
             })?;
 
             // Configure network interface in Firecracker
@@ -307,7 +363,10 @@ impl FirecrackerRuntime {
             client.put_network_interface(&net_iface).await.map_err(|e| {
                 error!(instance_id = %instance_id, error = %e, "Failed to configure network interface");
                 // TAP will be cleaned up when tap_device is dropped
-                anyhow!("Failed to configure network interface: {}", e)
+                boot_failure(
+                    FailureReason::NetworkSetupFailed,
+                    format!("Failed to configure network interface: {}", e),
+                )
             })?;
 
             info!(
@@ -324,7 +383,7 @@ impl FirecrackerRuntime {
             None
         };
 
-        // Start the instance
+        // Start the instance; returns once Firecracker reports it running.
         client.start_instance().await?;
 
         info!(instance_id = %instance_id, "VM started successfully");
@@ -334,6 +393,7 @@ impl FirecrackerRuntime {
     fn spawn_log_pipeline(
         &self,
         instance_id: &str,
+        boot_id: &str,
         stdout: Option<tokio::process::ChildStdout>,
         stderr: Option<tokio::process::ChildStderr>,
     ) {
@@ -341,31 +401,80 @@ impl FirecrackerRuntime {
             return;
         }
 
-        let Some(control_plane) = self.control_plane.clone() else {
-            if let Some(stdout) = stdout {
-                tokio::spawn(drain_stream(stdout));
-            }
-            if let Some(stderr) = stderr {
-                tokio::spawn(drain_stream(stderr));
+        let console = match self.console.open(instance_id, boot_id) {
+            Ok(console) => Some(console),
+            Err(e) => {
+                warn!(instance_id = %instance_id, error = %e, "Failed to open console log; console output will not be captured");
+                None
             }
-            return;
         };
 
-        let (tx, rx) = mpsc::channel(LOG_BATCH_SIZE * 2);
-        tokio::spawn(run_log_shipper(rx, control_plane));
+        let writer = match self.log_shipper.as_ref().map(|s| s.writer(instance_id)) {
+            Some(Ok(writer)) => Some(writer),
+            Some(Err(e)) => {
+                warn!(instance_id = %instance_id, error = %e, "Failed to open log spool; workload logs will not be shipped");
+                None
+            }
+            None => None,
+        };
 
-        let instance_id = instance_id.to_string();
-        if let Some(stdout) = stdout {
-            let tx_clone = tx.clone();
-            tokio::spawn(run_log_reader(
-                stdout,
-                "stdout",
-                instance_id.clone(),
-                tx_clone,
-            ));
+        let mut readers = Vec::new();
+        match writer {
+            Some(writer) => {
+                if let Some(stdout) = stdout {
+                    readers.push(tokio::spawn(run_log_reader(
+                        stdout,
+                        "stdout",
+                        instance_id.to_string(),
+                        writer.clone(),
+                        console.clone(),
+                        self.console_hints.clone(),
+                    )));
+                }
+                if let Some(stderr) = stderr {
+                    readers.push(tokio::spawn(run_log_reader(
+                        stderr,
+                        "stderr",
+                        instance_id.to_string(),
+                        writer,
+                        console.clone(),
+                        self.console_hints.clone(),
+                    )));
+                }
+            }
+            None => {
+                if let Some(stdout) = stdout {
+                    readers.push(tokio::spawn(drain_stream(
+                        stdout,
+                        instance_id.to_string(),
+                        console.clone(),
+                        self.console_hints.clone(),
+                    )));
+                }
+                if let Some(stderr) = stderr {
+                    readers.push(tokio::spawn(drain_stream(
+                        stderr,
+                        instance_id.to_string(),
+                        console.clone(),
+                        self.console_hints.clone(),
+                    )));
+                }
+            }
         }
-        if let Some(stderr) = stderr {
-            tokio::spawn(run_log_reader(stderr, "stderr", instance_id, tx));
+
+        if let (Some(console), Some(client)) = (console, self.control_plane.clone()) {
+            let logs = self.console.clone();
+            let instance_id = instance_id.to_string();
+            let boot_id = boot_id.to_string();
+            tokio::spawn(async move {
+                let readers_done = async {
+                    for reader in readers {
+                        let _ = reader.await;
+                    }
+                };
+                console::ship_console(logs, console, instance_id, boot_id, client, readers_done)
+                    .await;
+            });
         }
     }
 }
@@ -376,44 +485,88 @@ impl Runtime for FirecrackerRuntime {
         let instance_id = &plan.instance_id;
         info!(instance_id = %instance_id, "Starting Firecracker VM");
 
-        let boot_id = self.next_boot_id();
+        let boot_id = new_boot_id();
         let guest_cid = self.allocate_guest_cid().await;
+        self.console_hints.clear(instance_id);
+        {
+            let instances = self.instances.read().await;
+            self.console.prune(console::CONSOLE_RETENTION, |id| {
+                id == instance_id.as_str() || instances.contains_key(id)
+            });
+        }
 
-        let image_ref = plan
-            .image
-            .image_ref
-            .as_deref()
-            .ok_or_else(|| anyhow!("Missing image ref for instance {}", instance_id))?;
-        let (registry, repo, _) = parse_image_ref(image_ref)
-            .map_err(|e| anyhow!("Invalid image reference {}: {}", image_ref, e))?;
+        let image_ref = plan.image.image_ref.as_deref().ok_or_else(|| {
+            boot_failure(
+                FailureReason::ImagePullFailed,
+                format!("Missing image ref for instance {}", instance_id),
+            )
+        })?;
+        let (registry, repo, _) = parse_image_ref(image_ref).map_err(|e| {
+            boot_failure(
+                FailureReason::ImagePullFailed,
+                format!("Invalid image reference {}: {}", image_ref, e),
+            )
+        })?;
         let pull_result = self
             .image_puller
             .ensure_image(image_ref, &registry, &repo, &plan.image.resolved_digest)
             .await
-            .map_err(|e| anyhow!("Failed to pull image: {}", e))?;
-        let root_disk_path = pull_result.root_disk_path.clone();
+            .map_err(|e| {
+                boot_failure(
+                    FailureReason::ImagePullFailed,
+                    format!("Failed to pull image: {}", e),
+                )
+            })?;
         let image_digest = pull_result.digest.clone();
 
+        // Held until the VM is booted or the boot has failed.
+        let _slot = concurrency::gates().boots.acquire(instance_id).await;
+
         // Start Firecracker process
         let (mut process, socket_path) = self.start_firecracker_direct(instance_id).await?;
 
-        let scratch_path = self.scratch_path(instance_id);
-        if let Err(e) = ensure_scratch_disk(&scratch_path, self.config.scratch_disk_bytes) {
-            let _ = process.kill().await;
-            self.image_puller.release_image(&image_digest).await;
-            return Err(e);
-        }
+        let disks = {
+            let storage = Arc::clone(&self.storage);
+            let instance_id = instance_id.clone();
+            let image_digest = image_digest.clone();
+            let image_path = pull_result.root_disk_path.clone();
+            let scratch_bytes = scratch_disk_bytes(plan, self.config.scratch_disk_bytes);
+            tokio::task::spawn_blocking(move || -> Result<(PathBuf, PathBuf)> {
+                let root = storage.root_disk(&instance_id, &image_digest, &image_path)?;
+                let scratch = storage.scratch_disk(&instance_id, scratch_bytes)?;
+                Ok((root, scratch))
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|disks| disks)
+        };
+        let (root_disk_path, scratch_path) = match disks {
+            Ok(disks) => disks,
+            Err(e) => {
+                let _ = process.kill().await;
+                self.release_instance_disks(instance_id).await;
+                self.image_puller.release_image(&image_digest).await;
+                return Err(boot_failure(FailureReason::RootfsBuildFailed, e));
+            }
+        };
 
         let stdout = process.stdout.take();
         let stderr = process.stderr.take();
-        self.spawn_log_pipeline(instance_id, stdout, stderr);
+        self.spawn_log_pipeline(instance_id, &boot_id, stdout, stderr);
 
         // Create API client
         let client = FirecrackerClient::new(&socket_path);
 
         // Configure and boot (this also creates the TAP device if needed)
         let tap_device = match self
-            .configure_and_boot(&client, plan, &root_disk_path, &scratch_path, guest_cid)
+            .configure_and_boot(
+                &client,
+                plan,
+                &root_disk_path,
+                &scratch_path,
+                guest_cid,
+                &boot_id,
+            )
             .await
         {
             Ok(tap) => tap,
@@ -421,22 +574,37 @@ impl Runtime for FirecrackerRuntime {
                 error!(instance_id = %instance_id, error = %e, "Failed to configure VM");
                 // Kill the process on failure
                 let _ = process.kill().await;
-                let _ = fs::remove_file(&scratch_path);
+                self.release_instance_disks(instance_id).await;
                 self.image_puller.release_image(&image_digest).await;
                 return Err(e);
             }
         };
 
+        // Lets a later agent process adopt the VM even without a state store
+        // record (see `orphans`).
+        let metadata = VmMetadata {
+            boot_id: boot_id.clone(),
+            guest_cid,
+            image_digest: image_digest.clone(),
+        };
+        let metadata_path = self.instance_dir(instance_id).join(VM_METADATA_FILE);
+        if let Err(e) = fs::write(
+            &metadata_path,
+            serde_json::to_vec(&metadata).unwrap_or_default(),
+        ) {
+            warn!(instance_id = %instance_id, error = %e, "Failed to write VM metadata");
+        }
+
         // Store instance state
         let state = InstanceState {
             instance_id: instance_id.clone(),
             boot_id: boot_id.clone(),
-            process,
+            pid: process.id(),
+            process: Some(process),
             client,
             socket_path,
             guest_cid,
             image_digest,
-            scratch_path,
             tap_device,
             sandbox: None,
         };
@@ -456,9 +624,14 @@ impl Runtime for FirecrackerRuntime {
     async fn stop_vm(&self, handle: &VmHandle) -> Result<()> {
         let instance_id = &handle.instance_id;
         info!(instance_id = %instance_id, "Stopping Firecracker VM");
+        self.console_hints.clear(instance_id);
+        let _slot = concurrency::gates().teardowns.acquire(instance_id).await;
 
-        let mut instances = self.instances.write().await;
-        let state = instances
+        // Not held across the shutdown, so teardowns can overlap.
+        let state = self
+            .instances
+            .write()
+            .await
             .remove(instance_id)
             .ok_or_else(|| anyhow!("Instance not found: {}", instance_id))?;
 
@@ -475,9 +648,14 @@ impl Runtime for FirecrackerRuntime {
         }
 
         // Kill the process if still running
-        let mut process = state.process;
-        if let Err(e) = process.kill().await {
-            warn!(instance_id = %instance_id, error = %e, "Failed to kill process");
+        match (state.process, state.pid) {
+            (Some(mut process), _) => {
+                if let Err(e) = process.kill().await {
+                    warn!(instance_id = %instance_id, error = %e, "Failed to kill process");
+                }
+            }
+            (None, Some(pid)) => kill_pid(pid),
+            (None, None) => {}
         }
 
         // Clean up TAP device if present
@@ -494,6 +672,7 @@ impl Runtime for FirecrackerRuntime {
             }
         }
 
+        self.release_instance_disks(instance_id).await;
         self.image_puller.release_image(&state.image_digest).await;
 
         // Clean up instance directory
@@ -512,10 +691,10 @@ impl Runtime for FirecrackerRuntime {
             .ok_or_else(|| anyhow!("Instance not found: {}", handle.instance_id))?;
 
         // Try to get info from Firecracker API
-        match state.client.get_instance_info().await {
+        match state.client.describe_instance().await {
             Ok(info) => {
                 // Check if the instance is running
-                Ok(info.state == "Running")
+                Ok(info.is(VmState::Running))
             }
             Err(_) => {
                 // If API fails, consider unhealthy
@@ -523,96 +702,246 @@ impl Runtime for FirecrackerRuntime {
             }
         }
     }
-}
 
-fn ensure_scratch_disk(path: &PathBuf, size: u64) -> Result<()> {
-    if path.exists() {
-        return Ok(());
+    async fn adopt_vm(&self, plan: &InstancePlan, vm: &RecoveredVm) -> Result<Option<VmHandle>> {
+        let instance_id = &plan.instance_id;
+        let socket_path = self.socket_path(instance_id);
+        let pid = fs::read_to_string(self.pid_path(instance_id))
+            .ok()
+            .and_then(|p| p.trim().parse::<u32>().ok())
+            .filter(|&pid| pid_alive(pid));
+        let Some(pid) = pid else {
+            info!(instance_id = %instance_id, "No running VM to adopt");
+            return Ok(None);
+        };
+
+        let client = FirecrackerClient::new(&socket_path);
+        let running =
+            matches!(client.describe_instance().await, Ok(info) if info.is(VmState::Running));
+
+        let tap_device = if plan.network.overlay_ipv6.is_empty() {
+            None
+        } else {
+            adopt_tap(&TapConfig::new(instance_id, &plan.network.overlay_ipv6))
+        };
+        let network_ok = plan.network.overlay_ipv6.is_empty() || tap_device.is_some();
+
+        // The VM must hold a reference on its root disk image like any other.
+        let image_ok = match plan.image.image_ref.as_deref() {
+            Some(image_ref) => match parse_image_ref(image_ref) {
+                Ok((registry, repo, _)) => self
+                    .image_puller
+                    .ensure_image(image_ref, &registry, &repo, &plan.image.resolved_digest)
+                    .await
+                    .is_ok(),
+                Err(_) => false,
+            },
+            None => false,
+        };
+
+        if !(running && network_ok && image_ok) {
+            // A half-working VM would conflict with the fresh boot (same
+            // socket, TAP and disks), so get rid of it.
+            warn!(
+                instance_id = %instance_id,
+                pid,
+                running,
+                network_ok,
+                image_ok,
+                "VM from previous agent process cannot be adopted, killing it"
+            );
+            kill_pid(pid);
+            if image_ok {
+                self.image_puller
+                    .release_image(&plan.image.resolved_digest)
+                    .await;
+            }
+            return Ok(None);
+        }
+
+        info!(
+            instance_id = %instance_id,
+            boot_id = %vm.boot_id,
+            pid,
+            guest_cid = vm.guest_cid,
+            "Adopted running Firecracker VM"
+        );
+
+        let state = InstanceState {
+            instance_id: instance_id.clone(),
+            boot_id: vm.boot_id.clone(),
+            process: None,
+            pid: Some(pid),
+            client,
+            socket_path,
+            guest_cid: vm.guest_cid,
+            image_digest: plan.image.resolved_digest.clone(),
+            tap_device,
+            sandbox: None,
+        };
+        self.instances
+            .write()
+            .await
+            .insert(instance_id.clone(), state);
+
+        Ok(Some(VmHandle {
+            boot_id: vm.boot_id.clone(),
+            instance_id: instance_id.clone(),
+            guest_cid: vm.guest_cid,
+        }))
     }
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+    fn boot_failure_hint(&self, handle: &VmHandle) -> Option<FailureReason> {
+        self.console_hints.get(&handle.instance_id)
     }
 
-    let file = fs::File::create(path)?;
-    file.set_len(size)?;
-    drop(file);
+    async fn resize_volume(
+        &self,
+        plan: &InstancePlan,
+        volume_id: &str,
+        size_bytes: u64,
+    ) -> Result<bool, VolumeResizeError> {
+        let path = self.storage.volume_path(volume_id);
+        let grown = {
+            let storage = Arc::clone(&self.storage);
+            let volume_id = volume_id.to_string();
+            tokio::task::spawn_blocking(move || storage.grow_volume(&volume_id, size_bytes))
+                .await
+                .map_err(|e| VolumeResizeError::Failed(e.to_string()))??
+        };
+        if !grown {
+            return Ok(false);
+        }
+
+        // A VM that is not running picks up the new size when it boots.
+        let instances = self.instances.read().await;
+        if let (Some(state), Some(drive_id)) = (
+            instances.get(&plan.instance_id),
+            volume_drive_id(plan, volume_id),
+        ) {
+            state
+                .client
+                .patch_drive_path(&drive_id, &path)
+                .await
+                .map_err(|e| {
+                    VolumeResizeError::Failed(format!(
+                        "volume grown but the running VM was not notified: {e}"
+                    ))
+                })?;
+        }
+
+        Ok(true)
+    }
 
-    let status = std::process::Command::new("mkfs.ext4")
-        .args(["-F", "-q"])
-        .arg(path)
-        .status()
-        .map_err(|e| anyhow!("mkfs.ext4 failed: {e}"))?;
+    async fn restore_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        size_bytes: u64,
+        progress: Arc<RestoreProgress>,
+    ) -> Result<(), VolumeRestoreError> {
+        let storage = Arc::clone(&self.storage);
+        let (volume_id, snapshot_id) = (volume_id.to_string(), snapshot_id.to_string());
+        tokio::task::spawn_blocking(move || {
+            storage.restore_volume(&volume_id, &snapshot_id, size_bytes, &progress)
+        })
+        .await
+        .map_err(|e| VolumeRestoreError::Failed(e.to_string()))?
+    }
 
-    if !status.success() {
-        return Err(anyhow!("mkfs.ext4 failed"));
+    async fn snapshot_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        in_use: bool,
+    ) -> Result<u64, VolumeSnapshotError> {
+        let storage = Arc::clone(&self.storage);
+        let (volume_id, snapshot_id) = (volume_id.to_string(), snapshot_id.to_string());
+        tokio::task::spawn_blocking(move || {
+            storage.snapshot_volume(&volume_id, &snapshot_id, in_use)
+        })
+        .await
+        .map_err(|e| VolumeSnapshotError::Failed(e.to_string()))?
     }
+}
 
-    Ok(())
+/// Drive ID of a volume in `plan`; volume drives are attached in volume ID
+/// order (see `configure_and_boot`).
+fn volume_drive_id(plan: &InstancePlan, volume_id: &str) -> Option<String> {
+    let mut volume_ids: Vec<&str> = plan
+        .mounts
+        .as_ref()?
+        .iter()
+        .map(|mount| mount.volume_id.as_str())
+        .collect();
+    volume_ids.sort();
+    volume_ids
+        .iter()
+        .position(|id| *id == volume_id)
+        .map(|idx| format!("vol-{}", idx))
+}
+
+/// Whether a process exists.
+fn pid_alive(pid: u32) -> bool {
+    // Signal 0 checks for existence without delivering anything.
+    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+}
+
+/// SIGKILL a process by pid (VMs adopted from a previous agent process have
+/// no `Child` handle).
+fn kill_pid(pid: u32) {
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } != 0 {
+        warn!(pid, error = %std::io::Error::last_os_error(), "Failed to kill process");
+    }
+}
+
+/// Scratch disk size for an instance: the plan's size, else the node default.
+fn scratch_disk_bytes(plan: &InstancePlan, fallback: u64) -> u64 {
+    plan.resources
+        .ephemeral_disk_bytes
+        .and_then(|bytes| u64::try_from(bytes).ok())
+        .filter(|bytes| *bytes > 0)
+        .unwrap_or(fallback)
 }
 
 async fn run_log_reader<R: tokio::io::AsyncRead + Unpin>(
     reader: R,
     stream: &'static str,
     instance_id: String,
-    sender: mpsc::Sender<WorkloadLogEntry>,
+    writer: SpoolWriter,
+    console: Option<ConsoleWriter>,
+    hints: ConsoleHints,
 ) {
     let mut lines = BufReader::new(reader).lines();
     while let Ok(Some(line)) = lines.next_line().await {
+        hints.observe(&instance_id, &line);
+        if let Some(console) = &console {
+            console.write_line(&line);
+        }
         let (line, truncated) = normalize_log_line(&line);
-        let entry = WorkloadLogEntry {
+        writer.append(&WorkloadLogEntry {
             ts: Utc::now(),
             instance_id: instance_id.clone(),
             stream: stream.to_string(),
             line,
             truncated,
-        };
-
-        if sender.send(entry).await.is_err() {
-            break;
-        }
+        });
     }
 }
 
-async fn drain_stream<R: tokio::io::AsyncRead + Unpin>(reader: R) {
-    let mut lines = BufReader::new(reader).lines();
-    while let Ok(Some(_)) = lines.next_line().await {}
-}
-
-async fn run_log_shipper(
-    mut receiver: mpsc::Receiver<WorkloadLogEntry>,
-    control_plane: Arc<ControlPlaneClient>,
+async fn drain_stream<R: tokio::io::AsyncRead + Unpin>(
+    reader: R,
+    instance_id: String,
+    console: Option<ConsoleWriter>,
+    hints: ConsoleHints,
 ) {
-    let mut buffer: Vec<WorkloadLogEntry> = Vec::with_capacity(LOG_BATCH_SIZE);
-    let mut ticker = tokio::time::interval(LOG_FLUSH_INTERVAL);
-
-    loop {
-        tokio::select! {
-            Some(entry) = receiver.recv() => {
-                buffer.push(entry);
-                if buffer.len() >= LOG_BATCH_SIZE {
-                    flush_log_batch(&mut buffer, &control_plane).await;
-                }
-            }
-            _ = ticker.tick() => {
-                if !buffer.is_empty() {
-                    flush_log_batch(&mut buffer, &control_plane).await;
-                }
-            }
-            else => break,
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        hints.observe(&instance_id, &line);
+        if let Some(console) = &console {
+            console.write_line(&line);
         }
     }
-
-    if !buffer.is_empty() {
-        flush_log_batch(&mut buffer, &control_plane).await;
-    }
-}
-
-async fn flush_log_batch(buffer: &mut Vec<WorkloadLogEntry>, control_plane: &ControlPlaneClient) {
-    let batch = std::mem::take(buffer);
-    if let Err(e) = control_plane.send_workload_logs(batch).await {
-        warn!(error = %e, "Failed to ship workload logs");
-    }
 }
 
 fn normalize_log_line(line: &str) -> (String, bool) {
@@ -667,15 +996,34 @@ mod tests {
     }
 
     #[test]
-    fn test_boot_id_generation() {
-        let config = FirecrackerRuntimeConfig::default();
-        let runtime = FirecrackerRuntime::new(config, test_image_puller(), None);
-
-        let id1 = runtime.next_boot_id();
-        let id2 = runtime.next_boot_id();
-
-        assert!(id1.starts_with("boot_"));
-        assert!(id2.starts_with("boot_"));
-        assert_ne!(id1, id2);
+    fn test_scratch_disk_bytes_from_plan() {
+        let plan = |resources: serde_json::Value| -> InstancePlan {
+            serde_json::from_value(serde_json::json!({
+                "spec_version": "v1",
+                "org_id": "org", "app_id": "app", "env_id": "env",
+                "process_type": "web",
+                "instance_id": "inst_a",
+                "generation": 1,
+                "release_id": "rel",
+                "image": { "digest": "sha256:a", "resolved_digest": "sha256:a", "os": "linux", "arch": "amd64" },
+                "manifest_hash": "h",
+                "command": ["./start"],
+                "resources": resources,
+                "network": { "overlay_ipv6": "fd00::1", "gateway_ipv6": "fd00::1" }
+            }))
+            .unwrap()
+        };
+        let fallback = DEFAULT_SCRATCH_DISK_BYTES;
+
+        let sized = plan(serde_json::json!({
+            "cpu_request": 1.0,
+            "memory_limit_bytes": 1,
+            "ephemeral_disk_bytes": 8_589_934_592i64
+        }));
+        assert_eq!(scratch_disk_bytes(&sized, fallback), 8_589_934_592);
+
+        let default_sized =
+            plan(serde_json::json!({ "cpu_request": 1.0, "memory_limit_bytes": 1 }));
+        assert_eq!(scratch_disk_bytes(&default_sized, fallback), fallback);
     }
 }
@@ -3,19 +3,23 @@ use std::time::Duration;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use plfm_events::SecretsReload;
 use plfm_proto::agent::v1::{
     node_agent_client::NodeAgentClient, GetPlanRequest, GetSecretMaterialRequest,
-    HeartbeatRequest as ProtoHeartbeatRequest, ReportInstanceStatusRequest,
-    SendWorkloadLogsRequest, WorkloadLogEntry,
+    HeartbeatRequest as ProtoHeartbeatRequest, InstanceInventory,
+    InstanceStatusReport as ProtoInstanceStatusReport, ReportInstanceStatusRequest,
+    SecretsReload as ProtoSecretsReload, SendWorkloadLogsRequest, WorkloadLogEntry,
 };
 use plfm_proto::events::v1::{
-    InstanceDesiredState as ProtoInstanceDesiredState, InstanceStatus as ProtoInstanceStatus,
+    InstanceDesiredState as ProtoInstanceDesiredState,
+    InstanceFailureReason as ProtoInstanceFailureReason, InstanceStatus as ProtoInstanceStatus,
     NodeState as ProtoNodeState,
 };
 use tonic::transport::Channel;
 use tonic::Request;
 use tracing::debug;
 
+use crate::client::FailureReason;
 use crate::config::Config;
 
 pub struct ControlPlaneGrpcClient {
@@ -60,7 +64,7 @@ impl ControlPlaneGrpcClient {
                     node_id: i.node_id,
                     instance_id: i.instance_id,
                     generation: i.generation,
-                    desired_state: map_instance_desired_state(desired_state),
+                    desired_state: desired_state.into(),
                     drain_grace_seconds: i.drain_grace_seconds,
                     workload: i.workload.map(|w| InstancePlan {
                         spec_version: w.spec_version,
@@ -135,6 +139,7 @@ impl ControlPlaneGrpcClient {
                                         read_only: m.read_only,
                                         filesystem: m.filesystem,
                                         device_hint: m.device_hint,
+                                        size_bytes: m.size_bytes,
                                     })
                                     .collect(),
                             )
@@ -146,6 +151,7 @@ impl ControlPlaneGrpcClient {
                             mode: s.mode,
                             uid: s.uid,
                             gid: s.gid,
+                            reload: s.reload.and_then(secrets_reload_from_proto),
                         }),
                         spec_hash: w.spec_hash,
                     }),
@@ -176,17 +182,9 @@ impl ControlPlaneGrpcClient {
             "Reporting instance status via gRPC"
         );
 
-        let proto_status = plfm_proto::agent::v1::InstanceStatusReport {
-            instance_id: status.instance_id.clone(),
-            status: map_instance_status_to_proto(&status.status).into(),
-            boot_id: status.boot_id.clone(),
-            error_message: status.error_message.clone(),
-            exit_code: status.exit_code,
-        };
-
         let request = ReportInstanceStatusRequest {
             node_id: self.node_id.clone(),
-            status: Some(proto_status),
+            status: Some(status.into()),
         };
 
         self.client.report_instance_status(request).await?;
@@ -248,10 +246,19 @@ impl ControlPlaneGrpcClient {
         request: &ClientHeartbeatRequest,
     ) -> Result<ClientHeartbeatResponse> {
         let mut grpc_request = Request::new(ProtoHeartbeatRequest {
-            state: map_node_state_to_proto(&request.state).into(),
+            state: ProtoNodeState::from(request.state).into(),
             available_cpu_cores: request.available_cpu_cores,
             available_memory_bytes: request.available_memory_bytes,
             instance_count: request.instance_count,
+            inventory: request
+                .instance_statuses
+                .as_ref()
+                .map(|statuses| InstanceInventory {
+                    statuses: statuses
+                        .iter()
+                        .map(|(id, status)| (id.clone(), status.to_string()))
+                        .collect(),
+                }),
         });
 
         grpc_request
@@ -268,32 +275,77 @@ impl ControlPlaneGrpcClient {
     }
 }
 
-fn map_instance_desired_state(state: ProtoInstanceDesiredState) -> LocalInstanceDesiredState {
-    match state {
-        ProtoInstanceDesiredState::Running => LocalInstanceDesiredState::Running,
-        ProtoInstanceDesiredState::Draining => LocalInstanceDesiredState::Draining,
-        ProtoInstanceDesiredState::Stopped => LocalInstanceDesiredState::Stopped,
-        ProtoInstanceDesiredState::Unspecified => LocalInstanceDesiredState::Stopped,
+impl From<ProtoInstanceDesiredState> for LocalInstanceDesiredState {
+    fn from(state: ProtoInstanceDesiredState) -> Self {
+        match state {
+            ProtoInstanceDesiredState::Running => Self::Running,
+            ProtoInstanceDesiredState::Draining => Self::Draining,
+            ProtoInstanceDesiredState::Stopped => Self::Stopped,
+            ProtoInstanceDesiredState::Unspecified => Self::Stopped,
+        }
+    }
+}
+
+impl From<InstanceStatus> for ProtoInstanceStatus {
+    fn from(status: InstanceStatus) -> Self {
+        match status {
+            InstanceStatus::Booting => Self::Booting,
+            InstanceStatus::Ready => Self::Ready,
+            InstanceStatus::Draining => Self::Draining,
+            InstanceStatus::Stopped => Self::Stopped,
+            InstanceStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<FailureReason> for ProtoInstanceFailureReason {
+    fn from(reason: FailureReason) -> Self {
+        match reason {
+            FailureReason::ImagePullFailed => Self::ImagePullFailed,
+            FailureReason::RootfsBuildFailed => Self::RootfsBuildFailed,
+            FailureReason::FirecrackerStartFailed => Self::FirecrackerStartFailed,
+            FailureReason::GuestInitFailed => Self::GuestInitFailed,
+            FailureReason::NetworkSetupFailed => Self::NetworkSetupFailed,
+            FailureReason::VolumeAttachFailed => Self::VolumeAttachFailed,
+            FailureReason::SecretsMissing => Self::SecretsMissing,
+            FailureReason::SecretsInjectionFailed => Self::SecretsInjectionFailed,
+            FailureReason::HealthcheckFailed => Self::HealthcheckFailed,
+            FailureReason::OomKilled => Self::OomKilled,
+            FailureReason::CrashLoopBackoff => Self::CrashLoopBackoff,
+            FailureReason::TerminatedByOperator => Self::TerminatedByOperator,
+            FailureReason::NodeDraining => Self::NodeDraining,
+            FailureReason::KernelPanic => Self::KernelPanic,
+            FailureReason::ConfigHandshakeTimeout => Self::ConfigHandshakeTimeout,
+            FailureReason::SecretsFetchFailed => Self::SecretsFetchFailed,
+        }
     }
 }
 
-fn map_instance_status_to_proto(status: &InstanceStatus) -> ProtoInstanceStatus {
-    match status {
-        InstanceStatus::Booting => ProtoInstanceStatus::Booting,
-        InstanceStatus::Ready => ProtoInstanceStatus::Ready,
-        InstanceStatus::Draining => ProtoInstanceStatus::Draining,
-        InstanceStatus::Stopped => ProtoInstanceStatus::Stopped,
-        InstanceStatus::Failed => ProtoInstanceStatus::Failed,
+impl From<ClientNodeState> for ProtoNodeState {
+    fn from(state: ClientNodeState) -> Self {
+        match state {
+            ClientNodeState::Active => Self::Active,
+            ClientNodeState::Draining => Self::Draining,
+            ClientNodeState::Disabled => Self::Disabled,
+            ClientNodeState::Degraded => Self::Degraded,
+            ClientNodeState::Offline => Self::Offline,
+        }
     }
 }
 
-fn map_node_state_to_proto(state: &ClientNodeState) -> ProtoNodeState {
-    match state {
-        ClientNodeState::Active => ProtoNodeState::Active,
-        ClientNodeState::Draining => ProtoNodeState::Draining,
-        ClientNodeState::Disabled => ProtoNodeState::Disabled,
-        ClientNodeState::Degraded => ProtoNodeState::Degraded,
-        ClientNodeState::Offline => ProtoNodeState::Offline,
+impl From<&InstanceStatusReport> for ProtoInstanceStatusReport {
+    fn from(report: &InstanceStatusReport) -> Self {
+        Self {
+            instance_id: report.instance_id.clone(),
+            status: ProtoInstanceStatus::from(report.status).into(),
+            boot_id: report.boot_id.clone(),
+            error_message: report.error_message.clone(),
+            generation: report.generation,
+            exit_code: report.exit_code,
+            reason_code: report
+                .reason_code
+                .map(|reason| ProtoInstanceFailureReason::from(reason).into()),
+        }
     }
 }
 
@@ -389,6 +441,7 @@ pub struct WorkloadMount {
     pub read_only: bool,
     pub filesystem: String,
     pub device_hint: Option<String>,
+    pub size_bytes: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -399,6 +452,7 @@ pub struct WorkloadSecrets {
     pub mode: Option<i32>,
     pub uid: Option<i32>,
     pub gid: Option<i32>,
+    pub reload: Option<SecretsReload>,
 }
 
 #[derive(Debug, Clone)]
@@ -423,6 +477,8 @@ pub struct InstanceStatusReport {
     pub instance_id: String,
     pub status: InstanceStatus,
     pub boot_id: Option<String>,
+    pub generation: Option<i32>,
+    pub reason_code: Option<FailureReason>,
     pub error_message: Option<String>,
     pub exit_code: Option<i32>,
 }
@@ -448,12 +504,30 @@ impl std::fmt::Display for InstanceStatus {
     }
 }
 
+/// Decode a plan's secrets reload mode; unknown modes are dropped, which
+/// leaves the agent treating the process type as `restart`.
+fn secrets_reload_from_proto(reload: ProtoSecretsReload) -> Option<SecretsReload> {
+    match reload.mode.as_str() {
+        "restart" => Some(SecretsReload::Restart),
+        "file" => Some(SecretsReload::File),
+        "signal" => Some(SecretsReload::Signal {
+            signal: reload.signal.unwrap_or_else(|| "SIGHUP".to_string()),
+        }),
+        "exec" => Some(SecretsReload::Exec {
+            command: reload.command,
+            timeout_seconds: reload.timeout_seconds,
+        }),
+        _ => None,
+    }
+}
+
 #[derive(Debug)]
 pub struct ClientHeartbeatRequest {
     pub state: ClientNodeState,
     pub available_cpu_cores: i32,
     pub available_memory_bytes: i64,
     pub instance_count: i32,
+    pub instance_statuses: Option<HashMap<String, InstanceStatus>>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -470,3 +544,116 @@ pub struct ClientHeartbeatResponse {
     pub accepted: bool,
     pub next_heartbeat_secs: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_report_conversion() {
+        let report = InstanceStatusReport {
+            instance_id: "inst_1".to_string(),
+            status: InstanceStatus::Failed,
+            boot_id: Some("boot-1".to_string()),
+            generation: Some(2),
+            reason_code: Some(FailureReason::GuestInitFailed),
+            error_message: Some("init exited".to_string()),
+            exit_code: Some(1),
+        };
+
+        let proto = ProtoInstanceStatusReport::from(&report);
+        assert_eq!(proto.status, ProtoInstanceStatus::Failed as i32);
+        assert_eq!(
+            proto.reason_code,
+            Some(ProtoInstanceFailureReason::GuestInitFailed as i32)
+        );
+        assert_eq!(proto.boot_id.as_deref(), Some("boot-1"));
+        assert_eq!(proto.exit_code, Some(1));
+        assert_eq!(proto.generation, Some(2));
+    }
+
+    #[test]
+    fn test_secrets_reload_from_proto() {
+        let signal = secrets_reload_from_proto(ProtoSecretsReload {
+            mode: "signal".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            signal,
+            Some(SecretsReload::Signal {
+                signal: "SIGHUP".to_string()
+            })
+        );
+
+        let exec = secrets_reload_from_proto(ProtoSecretsReload {
+            mode: "exec".to_string(),
+            command: vec!["/app/reload".to_string()],
+            timeout_seconds: Some(5),
+            ..Default::default()
+        });
+        assert_eq!(
+            exec,
+            Some(SecretsReload::Exec {
+                command: vec!["/app/reload".to_string()],
+                timeout_seconds: Some(5),
+            })
+        );
+
+        let unknown = secrets_reload_from_proto(ProtoSecretsReload {
+            mode: "teleport".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(unknown, None);
+    }
+
+    #[test]
+    fn test_enum_names_match_proto() {
+        for status in [
+            InstanceStatus::Booting,
+            InstanceStatus::Ready,
+            InstanceStatus::Draining,
+            InstanceStatus::Stopped,
+            InstanceStatus::Failed,
+        ] {
+            assert_eq!(
+                ProtoInstanceStatus::from(status).as_str_name(),
+                format!("INSTANCE_STATUS_{}", status.to_string().to_uppercase())
+            );
+        }
+
+        // The agent's failure reasons must stay aligned with the shared
+        // domain enum, which is what the control plane decodes them into.
+        for reason in [
+            FailureReason::ImagePullFailed,
+            FailureReason::RootfsBuildFailed,
+            FailureReason::FirecrackerStartFailed,
+            FailureReason::GuestInitFailed,
+            FailureReason::NetworkSetupFailed,
+            FailureReason::VolumeAttachFailed,
+            FailureReason::SecretsMissing,
+            FailureReason::SecretsInjectionFailed,
+            FailureReason::HealthcheckFailed,
+            FailureReason::OomKilled,
+            FailureReason::CrashLoopBackoff,
+            FailureReason::TerminatedByOperator,
+            FailureReason::NodeDraining,
+            FailureReason::KernelPanic,
+            FailureReason::ConfigHandshakeTimeout,
+            FailureReason::SecretsFetchFailed,
+        ] {
+            let domain = plfm_events::InstanceFailureReason::try_from(
+                ProtoInstanceFailureReason::from(reason),
+            )
+            .unwrap();
+            assert_eq!(
+                serde_json::to_value(reason).unwrap(),
+                serde_json::Value::from(domain.as_str())
+            );
+        }
+
+        assert_eq!(
+            LocalInstanceDesiredState::from(ProtoInstanceDesiredState::Unspecified),
+            LocalInstanceDesiredState::Stopped
+        );
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! The node agent sends periodic heartbeats to the control plane to:
 //! - Indicate the node is alive and healthy
-//! - Report current resource availability
-//! - Report instance counts
+//! - Report current resource availability, including scratch disk usage
+//! - Report instance counts and the instance inventory used for drift detection
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use std::path::PathBuf;
+
 use anyhow::Result;
 use tokio::sync::watch;
 use tracing::{debug, error, info, warn};
@@ -15,7 +17,7 @@ use tracing::{debug, error, info, warn};
 use crate::client::{ControlPlaneClient, HeartbeatRequest, NodeState};
 use crate::config::Config;
 use crate::instance::InstanceManager;
-use crate::resources::SystemResources;
+use crate::resources::{EphemeralDiskUsage, SystemResources};
 
 /// Run the heartbeat loop until shutdown.
 pub async fn run_heartbeat_loop(
@@ -25,6 +27,7 @@ pub async fn run_heartbeat_loop(
 ) -> Result<()> {
     let client = ControlPlaneClient::new(&config);
     let interval = Duration::from_secs(config.heartbeat_interval_secs);
+    let data_dir = PathBuf::from(&config.data_dir);
 
     info!(
         node_id = %config.node_id,
@@ -39,13 +42,18 @@ pub async fn run_heartbeat_loop(
         tokio::select! {
             _ = interval_timer.tick() => {
                 let instance_count = instance_manager.instance_count().await;
+                let instance_statuses = instance_manager.instance_statuses().await;
                 let resources = SystemResources::measure();
+                let disk = EphemeralDiskUsage::measure(&data_dir);
 
                 let request = HeartbeatRequest {
                     state: NodeState::Active,
                     available_cpu_cores: resources.cpu_cores,
                     available_memory_bytes: resources.available_memory_bytes,
                     instance_count,
+                    instance_statuses: Some(instance_statuses),
+                    ephemeral_disk_used_bytes: Some(disk.used_bytes),
+                    ephemeral_disk_available_bytes: disk.available_bytes,
                 };
 
                 match client.send_heartbeat(&request).await {
@@ -91,6 +99,9 @@ pub async fn run_heartbeat_loop(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+
+    use crate::client::InstanceStatus;
 
     #[test]
     fn test_heartbeat_request_serialization() {
@@ -99,10 +110,25 @@ mod tests {
             available_cpu_cores: 8,
             available_memory_bytes: 16 * 1024 * 1024 * 1024,
             instance_count: 5,
+            instance_statuses: None,
+            ephemeral_disk_used_bytes: None,
+            ephemeral_disk_available_bytes: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("\"state\":\"active\""));
         assert!(json.contains("\"instance_count\":5"));
+        assert!(!json.contains("instance_statuses"));
+        assert!(!json.contains("ephemeral_disk"));
+
+        let request = HeartbeatRequest {
+            instance_statuses: Some(HashMap::from([(
+                "inst_1".to_string(),
+                InstanceStatus::Ready,
+            )])),
+            ..request
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"instance_statuses\":{\"inst_1\":\"ready\"}"));
     }
 }
@@ -16,6 +16,7 @@ use tracing::{debug, info};
 use super::cache::ImageCache;
 use super::oci::{OciClient, OciConfig, OciError};
 use super::rootdisk::{RootDiskBuilder, RootDiskConfig, RootDiskError};
+use crate::concurrency;
 
 /// Errors from image pulling operations.
 #[derive(Debug, Error)]
@@ -202,7 +203,8 @@ impl ImagePuller {
             });
         }
 
-        // Actually pull and build
+        // Actually pull and build, once a pull slot is free
+        let _slot = concurrency::gates().pulls.acquire(digest).await;
         info!(
             digest = %digest,
             image_ref = %image_ref,
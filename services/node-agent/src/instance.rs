@@ -8,17 +8,28 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use crate::boot_failure::{classify_boot_timeout, classify_guest_reason, classify_start_error};
 use crate::client::{
     ControlPlaneClient, DesiredInstanceAssignment, FailureReason, InstanceDesiredState,
     InstancePlan, InstanceStatus, InstanceStatusReport,
 };
 use crate::runtime::{Runtime, VmHandle};
-use crate::state::StateStore;
-use crate::vsock::{ConfigStore, PendingConfig};
+use crate::secrets::{CachedSecret, SecretCache, SecretPayload};
+use crate::state::{BootStatusRecord, StateStore};
+use crate::volume::VolumeResizer;
+use crate::vsock::{push_config_update, ConfigStore, IdentityConfig, PendingConfig};
+
+/// How long a VM may take to report ready before the boot is failed.
+const BOOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Retry delay after failing to issue or deliver an identity token.
+const IDENTITY_RETRY_DELAY: chrono::Duration = chrono::Duration::seconds(30);
 
 /// Tracks a single instance's state.
 #[derive(Debug, Clone)]
@@ -28,9 +39,13 @@ pub struct InstanceState {
     pub last_reported_status: Option<InstanceStatus>,
     pub boot_id: Option<String>,
     pub vm_handle: Option<VmHandle>,
+    /// When the current VM was started.
+    pub boot_started_at: Option<Instant>,
     pub reason_code: Option<FailureReason>,
     pub error_message: Option<String>,
     pub exit_code: Option<i32>,
+    /// When to renew the identity token (or retry issuing one).
+    pub identity_renew_at: Option<DateTime<Utc>>,
 }
 
 impl InstanceState {
@@ -41,9 +56,11 @@ impl InstanceState {
             last_reported_status: None,
             boot_id: None,
             vm_handle: None,
+            boot_started_at: None,
             reason_code: None,
             error_message: None,
             exit_code: None,
+            identity_renew_at: None,
         }
     }
 
@@ -62,6 +79,7 @@ impl InstanceState {
             instance_id: self.plan.instance_id.clone(),
             status: self.status,
             boot_id: self.boot_id.clone(),
+            generation: Some(self.plan.generation),
             reason_code: self.reason_code,
             error_message: self.error_message.clone(),
             exit_code: self.exit_code,
@@ -91,6 +109,12 @@ pub struct InstanceManager {
 
     /// Config generation counter.
     config_generation: AtomicU64,
+
+    /// Encrypted cache of secret material by version.
+    secret_cache: Option<Arc<SecretCache>>,
+
+    /// Grows volumes to their planned sizes.
+    volumes: VolumeResizer,
 }
 
 impl InstanceManager {
@@ -109,9 +133,18 @@ impl InstanceManager {
             state_store,
             control_plane,
             config_generation: AtomicU64::new(1),
+            secret_cache: None,
+            volumes: VolumeResizer::new(),
         }
     }
 
+    /// Serve secret material from `cache` when possible, so instances can
+    /// start while the control plane is briefly unreachable.
+    pub fn with_secret_cache(mut self, cache: Arc<SecretCache>) -> Self {
+        self.secret_cache = Some(cache);
+        self
+    }
+
     /// Get the current instance count.
     pub async fn instance_count(&self) -> i32 {
         let instances = self.instances.read().await;
@@ -121,6 +154,15 @@ impl InstanceManager {
             .count() as i32
     }
 
+    /// Status of every tracked instance, by instance ID.
+    pub async fn instance_statuses(&self) -> HashMap<String, InstanceStatus> {
+        let instances = self.instances.read().await;
+        instances
+            .iter()
+            .map(|(id, instance)| (id.clone(), instance.status))
+            .collect()
+    }
+
     pub async fn last_cursor_event_id(&self) -> i64 {
         *self.last_cursor_event_id.read().await
     }
@@ -154,6 +196,12 @@ impl InstanceManager {
             .iter()
             .map(|assignment| assignment.instance_id.clone())
             .collect();
+        let desired_secret_versions: Vec<String> = desired_instances
+            .iter()
+            .filter(|assignment| assignment.desired_state == InstanceDesiredState::Running)
+            .filter_map(|assignment| assignment.workload.as_ref())
+            .filter_map(|plan| plan.secrets.as_ref()?.secret_version_id.clone())
+            .collect();
 
         // Find instances to stop (in current state but not in desired)
         let instances_to_stop: Vec<String> = {
@@ -189,14 +237,76 @@ impl InstanceManager {
             }
         }
 
+        self.evict_superseded_secrets(desired_secret_versions).await;
+        self.volumes.flush(&self.control_plane).await;
+
         *self.last_cursor_event_id.write().await = cursor_event_id;
         *self.last_plan_id.write().await = Some(plan_id);
     }
 
+    /// Drop cached secret versions that neither the plan nor a live instance
+    /// references.
+    async fn evict_superseded_secrets(&self, mut live: Vec<String>) {
+        let Some(cache) = self.secret_cache.as_ref() else {
+            return;
+        };
+        {
+            let instances = self.instances.read().await;
+            live.extend(
+                instances
+                    .values()
+                    .filter(|i| matches!(i.status, InstanceStatus::Booting | InstanceStatus::Ready))
+                    .filter_map(|i| i.plan.secrets.as_ref()?.secret_version_id.clone()),
+            );
+        }
+        if let Err(e) = cache.evict_superseded(live.iter().map(String::as_str)) {
+            warn!(error = %e, "Failed to evict superseded secret versions");
+        }
+    }
+
+    /// Load secret material for a pinned version: from the cache if present,
+    /// otherwise from the control plane (then cached). Either way the data
+    /// is verified against its `data_hash`.
+    async fn load_secret_material(&self, version_id: &str) -> anyhow::Result<SecretPayload> {
+        if let Some(cache) = self.secret_cache.as_ref() {
+            match cache.get(version_id) {
+                Ok(Some(cached)) => return Ok(cached.data.into()),
+                Ok(None) => {}
+                Err(e) => warn!(version_id = %version_id, error = %e, "Secret cache read failed"),
+            }
+        }
+
+        let payload = self.control_plane.fetch_secret_material(version_id).await?;
+        let material = CachedSecret {
+            version_id: payload.version_id,
+            format: payload.format,
+            data_hash: payload.data_hash,
+            data: payload.data,
+        };
+        material.verify()?;
+        if material.version_id != version_id {
+            anyhow::bail!(
+                "control plane returned version {} for {}",
+                material.version_id,
+                version_id
+            );
+        }
+
+        if let Some(cache) = self.secret_cache.as_ref() {
+            if let Err(e) = cache.put(&material) {
+                warn!(version_id = %version_id, error = %e, "Failed to cache secret material");
+            }
+        }
+        Ok(material.data.into())
+    }
+
     /// Ensure an instance is running with the given plan.
     async fn ensure_instance(&self, plan: InstancePlan) {
         let instance_id = plan.instance_id.clone();
 
+        // Before any (re)boot, so a new VM sees the grown volume.
+        self.volumes.reconcile(self.runtime.as_ref(), &plan).await;
+
         // Check if instance already exists
         let existing = {
             let instances = self.instances.read().await;
@@ -219,6 +329,8 @@ impl InstanceManager {
                     );
                     self.stop_instance(&instance_id).await;
                     self.start_instance(plan).await;
+                } else if existing.plan.generation != plan.generation {
+                    self.update_instance_config(&existing, plan).await;
                 } else {
                     debug!(instance_id = %instance_id, "Instance already running with correct config");
                 }
@@ -230,6 +342,144 @@ impl InstanceManager {
         }
     }
 
+    /// Deliver a new config generation to a running instance in place.
+    ///
+    /// Only a ready guest can take the update; otherwise, or if delivery
+    /// fails, the instance keeps its current plan and the next plan retries.
+    async fn update_instance_config(&self, existing: &InstanceState, plan: InstancePlan) {
+        let instance_id = plan.instance_id.clone();
+        let handle = match (&existing.status, &existing.vm_handle) {
+            (InstanceStatus::Ready, Some(handle)) => handle.clone(),
+            _ => {
+                debug!(instance_id = %instance_id, "Config update deferred until instance is ready");
+                return;
+            }
+        };
+
+        let old_version = secret_version_of(&existing.plan);
+        let new_version = secret_version_of(&plan);
+        let secrets_data = match new_version.as_deref() {
+            Some(version_id) if new_version != old_version => {
+                match self.load_secret_material(version_id).await {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        warn!(instance_id = %instance_id, error = %e, "Failed to fetch secrets for config update");
+                        return;
+                    }
+                }
+            }
+            _ => None,
+        };
+        let env = changed_env_vars(&existing.plan, &plan);
+        let env_changed = env.is_some();
+
+        let update_plan = plan.clone();
+        let delivered = tokio::task::spawn_blocking(move || {
+            push_config_update(handle.guest_cid, &update_plan, secrets_data, env, None)
+        })
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result);
+
+        match delivered {
+            Ok(reply) => {
+                if let Some(reason) = reply.reason.as_deref() {
+                    // The new config is in place; only notifying the
+                    // workload failed, which a retry would repeat.
+                    warn!(
+                        instance_id = %instance_id,
+                        reason,
+                        detail = reply.detail.as_deref().unwrap_or(""),
+                        "Config updated but workload reload failed"
+                    );
+                }
+                info!(
+                    instance_id = %instance_id,
+                    generation = plan.generation,
+                    old_secret_version = ?old_version,
+                    new_secret_version = ?new_version,
+                    env_changed,
+                    "Config updated in place"
+                );
+                let mut instances = self.instances.write().await;
+                if let Some(instance) = instances.get_mut(&instance_id) {
+                    if instance.boot_id == existing.boot_id {
+                        instance.plan = plan;
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(instance_id = %instance_id, error = %e, "Config update failed; will retry");
+            }
+        }
+    }
+
+    /// Issue an identity token for an instance; returns it with its renewal
+    /// time.
+    async fn issue_identity(
+        &self,
+        instance_id: &str,
+    ) -> anyhow::Result<(IdentityConfig, DateTime<Utc>)> {
+        let issued = self.control_plane.issue_identity_token(instance_id).await?;
+        let identity = IdentityConfig::new(issued.token.into(), issued.expires_at);
+        Ok((identity, issued.renew_after))
+    }
+
+    /// Renew identity tokens that are due and push them to their guests.
+    ///
+    /// Only ready guests can take the update. A failed renewal is retried
+    /// after [`IDENTITY_RETRY_DELAY`]; the old token stays valid until it
+    /// expires.
+    pub async fn renew_identity_tokens(&self) {
+        let now = Utc::now();
+        let due: Vec<(String, InstanceState)> = {
+            let instances = self.instances.read().await;
+            instances
+                .iter()
+                .filter(|(_, state)| identity_renewal_due(state, now))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        };
+
+        for (instance_id, state) in due {
+            let Some(handle) = state.vm_handle.clone() else {
+                continue;
+            };
+
+            let renewed = match self.issue_identity(&instance_id).await {
+                Ok((identity, renew_at)) => {
+                    let plan = state.plan.clone();
+                    tokio::task::spawn_blocking(move || {
+                        push_config_update(handle.guest_cid, &plan, None, None, Some(identity))
+                    })
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result)
+                    .map(|_| renew_at)
+                }
+                Err(e) => Err(e),
+            };
+
+            let renew_at = match renewed {
+                Ok(renew_at) => {
+                    debug!(instance_id = %instance_id, renew_at = %renew_at, "Identity token renewed");
+                    renew_at
+                }
+                Err(e) => {
+                    warn!(instance_id = %instance_id, error = %e, "Identity token renewal failed; will retry");
+                    Utc::now() + IDENTITY_RETRY_DELAY
+                }
+            };
+
+            let mut instances = self.instances.write().await;
+            if let Some(instance) = instances.get_mut(&instance_id) {
+                if instance.boot_id == state.boot_id {
+                    instance.identity_renew_at = Some(renew_at);
+                }
+            }
+        }
+    }
+
     /// Start a new instance.
     async fn start_instance(&self, plan: InstancePlan) {
         let instance_id = plan.instance_id.clone();
@@ -279,11 +529,11 @@ impl InstanceManager {
             .as_ref()
             .and_then(|secrets| secrets.secret_version_id.as_deref());
         let secrets_data = match secret_version_id {
-            Some(version_id) => match self.control_plane.fetch_secret_material(version_id).await {
-                Ok(payload) => Some(payload.data),
+            Some(version_id) => match self.load_secret_material(version_id).await {
+                Ok(data) => Some(data),
                 Err(e) => {
                     state.status = InstanceStatus::Failed;
-                    state.reason_code = Some(FailureReason::SecretsInjectionFailed);
+                    state.reason_code = Some(FailureReason::SecretsFetchFailed);
                     state.error_message = Some(format!("Failed to fetch secrets: {e}"));
                     error!(instance_id = %instance_id, error = %e, "Failed to fetch secrets");
                     let mut instances = self.instances.write().await;
@@ -294,6 +544,20 @@ impl InstanceManager {
             None => None,
         };
 
+        // Booting without a token is better than not booting; renewal
+        // retries shortly.
+        let identity = match self.issue_identity(&instance_id).await {
+            Ok((identity, renew_at)) => {
+                state.identity_renew_at = Some(renew_at);
+                Some(identity)
+            }
+            Err(e) => {
+                warn!(instance_id = %instance_id, error = %e, "Failed to issue identity token; starting without one");
+                state.identity_renew_at = Some(Utc::now() + IDENTITY_RETRY_DELAY);
+                None
+            }
+        };
+
         let generation = self.config_generation.fetch_add(1, Ordering::SeqCst);
         let overlay_ipv6 = if plan.network.overlay_ipv6.is_empty() {
             "fd00::1".to_string()
@@ -312,6 +576,7 @@ impl InstanceManager {
             gateway_ipv6,
             generation,
             secrets_data,
+            identity,
         };
 
         self.config_store.add(&instance_id, pending).await;
@@ -320,11 +585,12 @@ impl InstanceManager {
             Ok(handle) => {
                 state.boot_id = Some(handle.boot_id.clone());
                 state.vm_handle = Some(handle);
+                state.boot_started_at = Some(Instant::now());
                 info!(instance_id = %instance_id, "VM started, waiting for guest-init ready");
             }
             Err(e) => {
                 state.status = InstanceStatus::Failed;
-                state.reason_code = Some(FailureReason::FirecrackerStartFailed);
+                state.reason_code = Some(classify_start_error(&e));
                 state.error_message = Some(e.to_string());
                 error!(instance_id = %instance_id, error = %e, "Failed to start instance");
                 self.config_store.remove(&instance_id).await;
@@ -436,12 +702,21 @@ impl InstanceManager {
     }
 
     pub async fn update_from_boot_status(&self) {
-        let booting_instances: Vec<(String, Option<String>)> = {
+        let booting_instances: Vec<(String, String, Option<Instant>, Option<VmHandle>)> = {
             let instances = self.instances.read().await;
             instances
                 .iter()
                 .filter(|(_, state)| state.status == InstanceStatus::Booting)
-                .map(|(id, state)| (id.clone(), state.boot_id.clone()))
+                .filter_map(|(id, state)| {
+                    state.boot_id.clone().map(|boot_id| {
+                        (
+                            id.clone(),
+                            boot_id,
+                            state.boot_started_at,
+                            state.vm_handle.clone(),
+                        )
+                    })
+                })
                 .collect()
         };
 
@@ -449,7 +724,7 @@ impl InstanceManager {
             return;
         }
 
-        let boot_statuses: Vec<(String, String)> = {
+        let boot_statuses: Vec<(String, Option<BootStatusRecord>)> = {
             let store = match self.state_store.lock() {
                 Ok(s) => s,
                 Err(e) => {
@@ -460,43 +735,99 @@ impl InstanceManager {
 
             booting_instances
                 .iter()
-                .filter_map(|(instance_id, boot_id)| {
-                    boot_id.as_ref().and_then(|bid| {
-                        store
-                            .get_boot_status(instance_id, bid)
-                            .ok()
-                            .flatten()
-                            .map(|record| (instance_id.clone(), record.state))
-                    })
+                .map(|(instance_id, boot_id, _, _)| {
+                    let record = store.get_boot_status(instance_id, boot_id).ok().flatten();
+                    (instance_id.clone(), record)
                 })
                 .collect()
         };
 
+        let mut timed_out = Vec::new();
         let mut instances = self.instances.write().await;
-        for (instance_id, boot_state) in boot_statuses {
-            if let Some(instance) = instances.get_mut(&instance_id) {
-                match boot_state.as_str() {
-                    "ready" => {
-                        info!(instance_id = %instance_id, "Guest-init ready, marking instance Ready");
-                        instance.status = InstanceStatus::Ready;
-                    }
-                    "failed" => {
-                        warn!(instance_id = %instance_id, "Guest-init failed");
-                        instance.status = InstanceStatus::Failed;
-                        instance.reason_code = Some(FailureReason::GuestInitFailed);
-                    }
-                    "exited" => {
-                        warn!(instance_id = %instance_id, "Guest-init exited");
-                        instance.status = InstanceStatus::Failed;
-                        instance.reason_code = Some(FailureReason::GuestInitFailed);
-                    }
-                    _ => {}
+        for ((instance_id, record), (_, _, started_at, handle)) in
+            boot_statuses.into_iter().zip(booting_instances)
+        {
+            let Some(instance) = instances.get_mut(&instance_id) else {
+                continue;
+            };
+            let console_hint = handle
+                .as_ref()
+                .and_then(|handle| self.runtime.boot_failure_hint(handle));
+
+            match record.as_ref() {
+                Some(record) if record.state == "ready" => {
+                    info!(instance_id = %instance_id, "Guest-init ready, marking instance Ready");
+                    instance.status = InstanceStatus::Ready;
+                    continue;
+                }
+                Some(record) if record.state == "failed" => {
+                    let reason = classify_guest_reason(record.reason.as_deref());
+                    warn!(
+                        instance_id = %instance_id,
+                        guest_reason = ?record.reason,
+                        reason_code = ?reason,
+                        "Guest-init failed"
+                    );
+                    instance.status = InstanceStatus::Failed;
+                    instance.reason_code = Some(reason);
+                    instance.error_message = record.detail.clone();
+                    continue;
+                }
+                Some(record) if record.state == "exited" => {
+                    warn!(instance_id = %instance_id, "Guest-init exited");
+                    instance.status = InstanceStatus::Failed;
+                    instance.reason_code =
+                        Some(console_hint.unwrap_or(FailureReason::GuestInitFailed));
+                    instance.exit_code = record.exit_code;
+                    continue;
                 }
+                _ => {}
             }
+
+            if started_at.is_some_and(|t| t.elapsed() > BOOT_TIMEOUT) {
+                let boot_state = record.as_ref().map(|r| r.state.as_str());
+                let reason = classify_boot_timeout(boot_state, console_hint);
+                warn!(
+                    instance_id = %instance_id,
+                    boot_state = ?boot_state,
+                    reason_code = ?reason,
+                    "Boot timeout"
+                );
+                instance.status = InstanceStatus::Failed;
+                instance.reason_code = Some(reason);
+                instance.error_message = Some(format!(
+                    "VM did not report ready within {}s",
+                    BOOT_TIMEOUT.as_secs()
+                ));
+                timed_out.push(instance_id);
+            }
+        }
+        drop(instances);
+
+        for instance_id in timed_out {
+            self.config_store.remove(&instance_id).await;
         }
     }
 }
 
+/// A ready instance whose identity token is due for renewal.
+fn identity_renewal_due(state: &InstanceState, now: DateTime<Utc>) -> bool {
+    state.status == InstanceStatus::Ready
+        && state.vm_handle.is_some()
+        && state.identity_renew_at.is_some_and(|at| at <= now)
+}
+
+fn secret_version_of(plan: &InstancePlan) -> Option<String> {
+    plan.secrets.as_ref()?.secret_version_id.clone()
+}
+
+/// The new plan's config vars, if they differ from the old plan's.
+fn changed_env_vars(old: &InstancePlan, new: &InstancePlan) -> Option<HashMap<String, String>> {
+    let vars = |plan: &InstancePlan| plan.env_vars.clone().unwrap_or_default();
+    let new_vars = vars(new);
+    (new_vars != vars(old)).then_some(new_vars)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -563,6 +894,7 @@ mod tests {
         assert_eq!(report.instance_id, "inst_123");
         assert_eq!(report.status, InstanceStatus::Ready);
         assert_eq!(report.boot_id, Some("boot_abc".to_string()));
+        assert_eq!(report.generation, Some(1));
     }
 
     #[test]
@@ -585,4 +917,46 @@ mod tests {
         state.status = InstanceStatus::Ready;
         assert!(state.needs_status_report());
     }
+
+    #[test]
+    fn test_identity_renewal_due() {
+        let now = Utc::now();
+        let mut state = InstanceState::from_plan(test_plan());
+        state.identity_renew_at = Some(now - chrono::Duration::seconds(1));
+        // Not ready yet: the guest cannot take an update.
+        assert!(!identity_renewal_due(&state, now));
+
+        state.status = InstanceStatus::Ready;
+        state.vm_handle = Some(VmHandle {
+            boot_id: "boot_abc".to_string(),
+            instance_id: "inst_123".to_string(),
+            guest_cid: 3,
+        });
+        assert!(identity_renewal_due(&state, now));
+
+        state.identity_renew_at = Some(now + chrono::Duration::minutes(5));
+        assert!(!identity_renewal_due(&state, now));
+    }
+
+    #[test]
+    fn test_changed_env_vars() {
+        let old = test_plan();
+        let mut new = test_plan();
+        assert_eq!(changed_env_vars(&old, &new), None);
+
+        new.env_vars = Some(HashMap::from([(
+            "LOG_LEVEL".to_string(),
+            "debug".to_string(),
+        )]));
+        assert_eq!(
+            changed_env_vars(&old, &new),
+            Some(HashMap::from([(
+                "LOG_LEVEL".to_string(),
+                "debug".to_string()
+            )]))
+        );
+
+        // Removing every var sends the empty set.
+        assert_eq!(changed_env_vars(&new, &old), Some(HashMap::new()));
+    }
 }
@@ -1,13 +1,22 @@
 pub mod actors;
+pub mod boot_failure;
 pub mod client;
+pub mod concurrency;
+pub mod console;
 pub mod exec;
 pub mod exec_gateway;
+pub mod exec_tunnel;
 pub mod firecracker;
 pub mod grpc_client;
 pub mod image;
+pub mod local_api;
+pub mod log_shipping;
 pub mod network;
 pub mod resources;
+pub mod secrets;
 pub mod state;
+pub mod storage;
+pub mod volume;
 pub mod vsock;
 
 pub mod config;
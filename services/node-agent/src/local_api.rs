@@ -0,0 +1,563 @@
+//! Node-local introspection API on a unix socket.
+//!
+//! For operators on the node: shows what the agent thinks is running and
+//! forces a reconcile pass without restarting the agent. Plain HTTP/1.1 with
+//! JSON bodies, one request per connection:
+//!
+//! - `GET /instances`: instances in the state store, with their actor state
+//! - `GET /instances/{id}/console?bytes=N`: the last N bytes (default 64 KiB)
+//!   of the instance's captured serial console (see [`crate::console`])
+//! - `GET /images`: the image cache
+//! - `GET /state`: node cursor, retry budget counts, log shipping counters
+//!   and supervisor state
+//! - `GET /state/export`: a state store export (see [`crate::state::StateExport`])
+//! - `POST /reconcile/trigger`: run a reconcile pass now
+//!
+//! The socket is created mode 0600, so access is limited to the agent's user
+//! (root on production nodes). Only available in actor mode.
+//!
+//! Configuration: `GHOST_LOCAL_API_SOCKET` (default `<data_dir>/agent.sock`,
+//! `off` to disable).
+//!
+//! Example: `curl --unix-socket /var/lib/ghost/agent.sock http://localhost/state`
+//!
+//! See: docs/specs/runtime/agent-actors.md (Local API)
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot, watch};
+use tracing::{debug, info, warn};
+
+use crate::actors::{LocalRequest, SupervisorStatus};
+use crate::concurrency;
+use crate::console::{ConsoleLogs, DEFAULT_TAIL_BYTES, MAX_CONSOLE_BYTES};
+use crate::log_shipping;
+use crate::state::StateStore;
+
+/// Largest request head accepted.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// Time allowed for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time allowed for the supervisor to answer. Reconcile passes can wait on
+/// actor restarts, so this is generous.
+const SUPERVISOR_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Unix-socket HTTP server for node-local introspection.
+pub struct LocalApi {
+    socket_path: PathBuf,
+    node_id: String,
+    requests: mpsc::Sender<LocalRequest>,
+    state_store: Arc<Mutex<StateStore>>,
+    console: ConsoleLogs,
+}
+
+/// An instance as seen by the agent.
+#[derive(Debug, Serialize)]
+struct InstanceView {
+    instance_id: String,
+    phase: &'static str,
+    spec_revision: i64,
+    boot_id: String,
+    rootdisk_digest: Option<String>,
+    updated_at: i64,
+    /// `active` (instance actor running), `pending` (waiting for its image)
+    /// or `none`.
+    actor: &'static str,
+}
+
+struct Response {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Response {
+    fn ok(body: impl Serialize) -> Self {
+        Self::with_status(200, body)
+    }
+
+    fn with_status(status: u16, body: impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_value(body).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::with_status(status, json!({ "error": message }))
+    }
+}
+
+impl LocalApi {
+    pub fn new(
+        socket_path: PathBuf,
+        node_id: String,
+        requests: mpsc::Sender<LocalRequest>,
+        state_store: Arc<Mutex<StateStore>>,
+        console: ConsoleLogs,
+    ) -> Self {
+        Self {
+            socket_path,
+            node_id,
+            requests,
+            state_store,
+            console,
+        }
+    }
+
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        // A socket left behind by a previous agent process blocks bind.
+        match std::fs::remove_file(&self.socket_path) {
+            Ok(()) => debug!(path = %self.socket_path.display(), "Removed stale local API socket"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to remove {}", self.socket_path.display()))
+            }
+        }
+        let listener = UnixListener::bind(&self.socket_path)
+            .with_context(|| format!("Failed to bind {}", self.socket_path.display()))?;
+        std::fs::set_permissions(&self.socket_path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to chmod {}", self.socket_path.display()))?;
+        info!(path = %self.socket_path.display(), "Local API started");
+
+        let api = Arc::new(self);
+        loop {
+            let stream = tokio::select! {
+                _ = shutdown.changed() => {
+                    info!("Local API shutting down");
+                    let _ = std::fs::remove_file(&api.socket_path);
+                    return Ok(());
+                }
+                result = listener.accept() => match result {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!(error = %e, "Local API accept error");
+                        continue;
+                    }
+                },
+            };
+
+            let api = Arc::clone(&api);
+            tokio::spawn(async move {
+                if let Err(e) = api.serve(stream).await {
+                    debug!(error = %e, "Local API connection error");
+                }
+            });
+        }
+    }
+
+    async fn serve(&self, mut stream: UnixStream) -> Result<()> {
+        let response =
+            match tokio::time::timeout(READ_TIMEOUT, read_request_line(&mut stream)).await {
+                Ok(Ok(Some((method, path)))) => self.dispatch(&method, &path).await,
+                Ok(Ok(None)) => Response::error(400, "malformed request"),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => Response::error(408, "request timeout"),
+            };
+        write_response(&mut stream, &response).await
+    }
+
+    async fn dispatch(&self, method: &str, path: &str) -> Response {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let path = path
+            .strip_suffix('/')
+            .filter(|p| !p.is_empty())
+            .unwrap_or(path);
+        match (method, path) {
+            ("GET", "/instances") => self.instances().await,
+            ("GET", "/images") => {
+                match self.ask(|reply_to| LocalRequest::Images { reply_to }).await {
+                    Some(images) => Response::ok(images),
+                    None => supervisor_unavailable(),
+                }
+            }
+            ("GET", "/state") => self.state().await,
+            ("GET", "/state/export") => match self.store(|store| store.export_state(&self.node_id))
+            {
+                Ok(export) => Response::ok(export),
+                Err(e) => Response::error(500, &e),
+            },
+            ("POST", "/reconcile/trigger") => {
+                info!("Reconcile triggered via local API");
+                match self
+                    .ask(|reply_to| LocalRequest::TriggerReconcile { reply_to })
+                    .await
+                {
+                    Some(spec_revision) => Response::with_status(
+                        202,
+                        json!({ "triggered": true, "spec_revision": spec_revision }),
+                    ),
+                    None => supervisor_unavailable(),
+                }
+            }
+            (_, "/instances" | "/images" | "/state" | "/state/export" | "/reconcile/trigger") => {
+                Response::error(405, "method not allowed")
+            }
+            _ => match path
+                .strip_prefix("/instances/")
+                .and_then(|rest| rest.strip_suffix("/console"))
+            {
+                Some(instance_id) if method == "GET" => self.console(instance_id, query),
+                Some(_) => Response::error(405, "method not allowed"),
+                None => Response::error(404, "not found"),
+            },
+        }
+    }
+
+    async fn instances(&self) -> Response {
+        let records = match self.store(|store| store.list_instances()) {
+            Ok(records) => records,
+            Err(e) => return Response::error(500, &e),
+        };
+        let status = self.ask(|reply_to| LocalRequest::Status { reply_to }).await;
+
+        let instances: Vec<InstanceView> = records
+            .into_iter()
+            .map(|record| {
+                let actor = match &status {
+                    Some(s) if s.active_instances.contains(&record.instance_id) => "active",
+                    Some(s) if s.pending_instances.contains(&record.instance_id) => "pending",
+                    _ => "none",
+                };
+                InstanceView {
+                    phase: record.phase.as_str(),
+                    instance_id: record.instance_id,
+                    spec_revision: record.spec_revision,
+                    boot_id: record.boot_id,
+                    rootdisk_digest: record.rootdisk_digest,
+                    updated_at: record.updated_at,
+                    actor,
+                }
+            })
+            .collect();
+
+        // Instances with an actor but no stored record yet (just assigned).
+        let unrecorded: Vec<&String> = status
+            .as_ref()
+            .map(|s| {
+                s.active_instances
+                    .iter()
+                    .chain(&s.pending_instances)
+                    .filter(|id| !instances.iter().any(|i| &i.instance_id == *id))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Response::ok(json!({
+            "instances": instances,
+            "unrecorded_instances": unrecorded,
+            "supervisor_available": status.is_some(),
+        }))
+    }
+
+    async fn state(&self) -> Response {
+        let node = match self.store(|store| store.get_node_state()) {
+            Ok(node) => node,
+            Err(e) => return Response::error(500, &e),
+        };
+        let retries = match self.store(|store| store.retries().stats()) {
+            Ok(stats) => stats,
+            Err(e) => return Response::error(500, &e),
+        };
+        let supervisor: Option<SupervisorStatus> =
+            self.ask(|reply_to| LocalRequest::Status { reply_to }).await;
+
+        Response::ok(json!({
+            "cursor_event_id": node.cursor_event_id,
+            "plan_id": node.plan_id,
+            "event_cursor": node.event_cursor,
+            "last_heartbeat": node.last_heartbeat,
+            "retries": {
+                "tracked": retries.tracked,
+                "exhausted": retries.exhausted,
+                "exhaustions": retries.exhaustions,
+            },
+            "logs": log_shipping::stats(),
+            "concurrency": concurrency::stats(),
+            "supervisor": supervisor,
+        }))
+    }
+
+    fn console(&self, instance_id: &str, query: &str) -> Response {
+        let bytes = match query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("bytes="))
+            .map(str::parse::<u64>)
+        {
+            None => DEFAULT_TAIL_BYTES,
+            Some(Ok(bytes)) if bytes > 0 => bytes.min(2 * MAX_CONSOLE_BYTES),
+            Some(_) => return Response::error(400, "bytes must be a positive integer"),
+        };
+        match self.console.tail(instance_id, bytes) {
+            Ok(Some(tail)) => Response::ok(tail),
+            Ok(None) => Response::error(404, "no console output captured for instance"),
+            Err(e) => Response::error(500, &format!("failed to read console log: {e}")),
+        }
+    }
+
+    fn store<T, E: std::fmt::Display>(
+        &self,
+        f: impl FnOnce(&StateStore) -> Result<T, E>,
+    ) -> Result<T, String> {
+        let store = self.state_store.lock().unwrap_or_else(|e| e.into_inner());
+        f(&store).map_err(|e| format!("state store error: {e}"))
+    }
+
+    /// Send a request to the supervisor and wait for its reply.
+    async fn ask<T>(&self, request: impl FnOnce(oneshot::Sender<T>) -> LocalRequest) -> Option<T> {
+        let (tx, rx) = oneshot::channel();
+        self.requests.send(request(tx)).await.ok()?;
+        tokio::time::timeout(SUPERVISOR_TIMEOUT, rx)
+            .await
+            .ok()?
+            .ok()
+    }
+}
+
+fn supervisor_unavailable() -> Response {
+    Response::error(503, "supervisor unavailable")
+}
+
+/// Read the request head and return its method and path, or `None` if the
+/// head is malformed or too large. Request bodies are ignored.
+async fn read_request_line(stream: &mut UnixStream) -> Result<Option<(String, String)>> {
+    let mut head = Vec::with_capacity(512);
+    let mut buf = [0u8; 512];
+    loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.windows(4).any(|w| w == b"\r\n\r\n") || head.len() > MAX_REQUEST_HEAD {
+            break;
+        }
+    }
+    if head.len() > MAX_REQUEST_HEAD {
+        return Ok(None);
+    }
+
+    let Some(line) = head.split(|&b| b == b'\n').next() else {
+        return Ok(None);
+    };
+    let line = String::from_utf8_lossy(line);
+    let mut parts = line.trim_end().split(' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(path), Some(version))
+            if version.starts_with("HTTP/1.") && path.starts_with('/') =>
+        {
+            Ok(Some((method.to_string(), path.to_string())))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn write_response(stream: &mut UnixStream, response: &Response) -> Result<()> {
+    let mut body = serde_json::to_vec_pretty(&response.body)?;
+    body.push(b'\n');
+    let reason = match response.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::ImageCacheSnapshot;
+    use crate::state::{InstancePhase, InstanceRecord};
+
+    fn status() -> SupervisorStatus {
+        SupervisorStatus {
+            node_id: "node_test".to_string(),
+            spec_revision: 7,
+            last_cursor_event_id: 42,
+            last_plan_id: Some("plan_1".to_string()),
+            running_actors: 3,
+            degraded_actors: 0,
+            active_instances: vec!["inst_a".to_string(), "inst_new".to_string()],
+            pending_instances: vec![],
+        }
+    }
+
+    /// Start the API with a fake supervisor that answers every request.
+    async fn start() -> (tempfile::TempDir, PathBuf, watch::Sender<bool>) {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("agent.sock");
+        let console = ConsoleLogs::new(dir.path().join("console"));
+        console
+            .open("inst_a", "boot_1")
+            .unwrap()
+            .write_line("Kernel panic - not syncing: VFS: Unable to mount root fs");
+
+        let store = StateStore::open_in_memory().unwrap();
+        store
+            .upsert_instance(&InstanceRecord {
+                instance_id: "inst_a".to_string(),
+                phase: InstancePhase::Running,
+                spec_revision: 7,
+                boot_id: "boot_1".to_string(),
+                socket_path: None,
+                rootdisk_digest: Some("sha256:abc".to_string()),
+                guest_cid: Some(3),
+                created_at: 1,
+                updated_at: 2,
+            })
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                match request {
+                    LocalRequest::Status { reply_to } => {
+                        let _ = reply_to.send(status());
+                    }
+                    LocalRequest::Images { reply_to } => {
+                        let _ = reply_to.send(ImageCacheSnapshot {
+                            images: vec![],
+                            pulls_in_progress: vec!["sha256:def".to_string()],
+                            cache_bytes: 0,
+                            max_cache_bytes: 1024,
+                        });
+                    }
+                    LocalRequest::TriggerReconcile { reply_to } => {
+                        let _ = reply_to.send(7);
+                    }
+                }
+            }
+        });
+
+        let api = LocalApi::new(
+            socket_path.clone(),
+            "node_test".to_string(),
+            tx,
+            Arc::new(Mutex::new(store)),
+            console,
+        );
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        tokio::spawn(api.run(shutdown_rx));
+        for _ in 0..50 {
+            if socket_path.exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        (dir, socket_path, shutdown_tx)
+    }
+
+    async fn request(socket: &PathBuf, method: &str, path: &str) -> (u16, serde_json::Value) {
+        let mut stream = UnixStream::connect(socket).await.unwrap();
+        stream
+            .write_all(format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).await.unwrap();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_instances_merge_actor_state() {
+        let (_dir, socket, _shutdown) = start().await;
+
+        let (status, body) = request(&socket, "GET", "/instances").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["instances"][0]["instance_id"], "inst_a");
+        assert_eq!(body["instances"][0]["phase"], "running");
+        assert_eq!(body["instances"][0]["actor"], "active");
+        assert_eq!(body["unrecorded_instances"], json!(["inst_new"]));
+    }
+
+    #[tokio::test]
+    async fn test_state_and_images() {
+        let (_dir, socket, _shutdown) = start().await;
+
+        let (status, body) = request(&socket, "GET", "/state").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["supervisor"]["spec_revision"], 7);
+        assert_eq!(body["retries"]["exhausted"], 0);
+        assert!(body["logs"]["lines_dropped_buffer_full"].is_u64());
+        assert_eq!(body["concurrency"]["boots"]["limit"], 4);
+
+        let (status, body) = request(&socket, "GET", "/state/export").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["node_id"], "node_test");
+        assert_eq!(body["instances"][0]["guest_cid"], 3);
+
+        let (status, body) = request(&socket, "GET", "/images").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["pulls_in_progress"], json!(["sha256:def"]));
+    }
+
+    #[tokio::test]
+    async fn test_trigger_reconcile() {
+        let (_dir, socket, _shutdown) = start().await;
+
+        let (status, body) = request(&socket, "POST", "/reconcile/trigger").await;
+        assert_eq!(status, 202);
+        assert_eq!(body["triggered"], true);
+        assert_eq!(body["spec_revision"], 7);
+
+        let (status, _) = request(&socket, "GET", "/reconcile/trigger").await;
+        assert_eq!(status, 405);
+        let (status, _) = request(&socket, "GET", "/nope").await;
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_console_tail() {
+        let (_dir, socket, _shutdown) = start().await;
+
+        let (status, body) = request(&socket, "GET", "/instances/inst_a/console").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["instance_id"], "inst_a");
+        assert!(body["content"].as_str().unwrap().contains("Kernel panic"));
+        assert_eq!(body["truncated"], false);
+
+        let (status, body) = request(&socket, "GET", "/instances/inst_a/console?bytes=20").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["truncated"], true);
+
+        let (status, _) = request(&socket, "GET", "/instances/inst_b/console").await;
+        assert_eq!(status, 404);
+        let (status, _) = request(&socket, "GET", "/instances/inst_a/console?bytes=x").await;
+        assert_eq!(status, 400);
+        let (status, _) = request(&socket, "POST", "/instances/inst_a/console").await;
+        assert_eq!(status, 405);
+    }
+
+    #[tokio::test]
+    async fn test_socket_permissions() {
+        let (_dir, socket, _shutdown) = start().await;
+        let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,1026 @@
+//! Workload log shipping.
+//!
+//! Guest stdout and stderr lines are appended to a bounded ring buffer on
+//! disk per instance (`<data_dir>/logs/<instance_id>/`), and one shipper task
+//! per instance uploads them to `POST /v1/nodes/{node_id}/logs` in compressed
+//! batches. Reading the guest never waits on the network: while the control
+//! plane is slow or down, lines accumulate on disk and ship once uploads
+//! succeed again.
+//!
+//! - Uploads that fail with a transport error, `429` or `5xx` are retried
+//!   with exponential backoff, honoring `Retry-After`. Other `4xx` responses
+//!   drop the batch.
+//! - When an instance's buffer is full, the drop policy discards either the
+//!   oldest buffered lines or the new ones. Drops are counted and logged.
+//! - The read position is persisted after every upload, so a restarted agent
+//!   resumes where the previous one stopped. Delivery is at least once.
+//!
+//! Counters are reported under `logs` by the local API's `GET /state`.
+//!
+//! Configuration:
+//! - `PLFM_LOG_BUFFER_BYTES`: buffer size per instance (default 16 MiB)
+//! - `PLFM_LOG_COMPRESSION`: `gzip` (default), `zstd` or `none`
+//! - `PLFM_LOG_DROP_POLICY`: `oldest` (default) or `newest`
+//!
+//! See: docs/specs/observability/logging.md
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use crate::actors::BackoffPolicy;
+use crate::client::{ControlPlaneClient, LogUploadOutcome, WorkloadLogEntry, WorkloadLogRequest};
+
+/// Default buffer size per instance.
+const DEFAULT_BUFFER_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Smallest accepted `PLFM_LOG_BUFFER_BYTES`.
+const MIN_BUFFER_BYTES: u64 = 256 * 1024;
+
+/// The buffer is split into this many segment files; the oldest segment is
+/// the unit of eviction.
+const SEGMENTS_PER_BUFFER: u64 = 16;
+
+/// Entries per upload. The control plane accepts at most 500.
+const BATCH_ENTRIES: usize = 500;
+
+/// Uncompressed bytes per upload.
+const BATCH_BYTES: usize = 1024 * 1024;
+
+/// How long a partial batch waits for more lines.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Persisted read position, next to the segment files.
+const CURSOR_FILE: &str = "cursor";
+
+/// Compression applied to uploaded batches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogCompression {
+    None,
+    #[default]
+    Gzip,
+    Zstd,
+}
+
+impl FromStr for LogCompression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            other => {
+                anyhow::bail!("unknown log compression '{other}' (expected gzip, zstd or none)")
+            }
+        }
+    }
+}
+
+impl LogCompression {
+    /// `Content-Encoding` of a compressed body.
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gzip"),
+            Self::Zstd => Some("zstd"),
+        }
+    }
+
+    pub fn compress(self, body: Vec<u8>) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(body),
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(&body)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::stream::encode_all(body.as_slice(), 3),
+        }
+    }
+}
+
+/// What to discard when an instance's buffer is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Evict the oldest buffered lines to make room.
+    #[default]
+    Oldest,
+    /// Keep the buffered lines and discard new ones.
+    Newest,
+}
+
+impl FromStr for DropPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "oldest" => Ok(Self::Oldest),
+            "newest" => Ok(Self::Newest),
+            other => anyhow::bail!("unknown log drop policy '{other}' (expected oldest or newest)"),
+        }
+    }
+}
+
+/// Log shipping settings for a node.
+#[derive(Debug, Clone)]
+pub struct LogShippingConfig {
+    /// Disk buffer size per instance.
+    pub buffer_bytes: u64,
+    pub compression: LogCompression,
+    pub drop_policy: DropPolicy,
+    /// Maximum entries per upload.
+    pub batch_entries: usize,
+    /// Maximum uncompressed bytes per upload.
+    pub batch_bytes: usize,
+    /// How long a partial batch waits for more lines.
+    pub flush_interval: Duration,
+    /// Delay between retries of a failed upload.
+    pub backoff: BackoffPolicy,
+}
+
+impl Default for LogShippingConfig {
+    fn default() -> Self {
+        Self {
+            buffer_bytes: DEFAULT_BUFFER_BYTES,
+            compression: LogCompression::default(),
+            drop_policy: DropPolicy::default(),
+            batch_entries: BATCH_ENTRIES,
+            batch_bytes: BATCH_BYTES,
+            flush_interval: FLUSH_INTERVAL,
+            backoff: BackoffPolicy {
+                base: Duration::from_secs(1),
+                max: Duration::from_secs(60),
+                jitter: 0.25,
+            },
+        }
+    }
+}
+
+impl LogShippingConfig {
+    /// Read `PLFM_LOG_BUFFER_BYTES`, `PLFM_LOG_COMPRESSION` and
+    /// `PLFM_LOG_DROP_POLICY`.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Ok(value) = std::env::var("PLFM_LOG_BUFFER_BYTES") {
+            let bytes: u64 = value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("PLFM_LOG_BUFFER_BYTES must be a byte count"))?;
+            config.buffer_bytes = bytes.max(MIN_BUFFER_BYTES);
+        }
+        if let Ok(value) = std::env::var("PLFM_LOG_COMPRESSION") {
+            config.compression = value.parse()?;
+        }
+        if let Ok(value) = std::env::var("PLFM_LOG_DROP_POLICY") {
+            config.drop_policy = value.parse()?;
+        }
+        Ok(config)
+    }
+}
+
+/// Node-wide log shipping counters.
+#[derive(Debug)]
+struct LogStats {
+    lines_received: AtomicU64,
+    lines_shipped: AtomicU64,
+    lines_dropped_buffer_full: AtomicU64,
+    lines_dropped_rejected: AtomicU64,
+    lines_dropped_write_failed: AtomicU64,
+    batches_shipped: AtomicU64,
+    upload_retries: AtomicU64,
+    bytes_uploaded: AtomicU64,
+}
+
+static STATS: LogStats = LogStats {
+    lines_received: AtomicU64::new(0),
+    lines_shipped: AtomicU64::new(0),
+    lines_dropped_buffer_full: AtomicU64::new(0),
+    lines_dropped_rejected: AtomicU64::new(0),
+    lines_dropped_write_failed: AtomicU64::new(0),
+    batches_shipped: AtomicU64::new(0),
+    upload_retries: AtomicU64::new(0),
+    bytes_uploaded: AtomicU64::new(0),
+};
+
+/// Log shipping counters since the agent started.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LogStatsSnapshot {
+    pub lines_received: u64,
+    pub lines_shipped: u64,
+    pub lines_dropped_buffer_full: u64,
+    pub lines_dropped_rejected: u64,
+    pub lines_dropped_write_failed: u64,
+    pub batches_shipped: u64,
+    pub upload_retries: u64,
+    /// Compressed bytes of accepted uploads.
+    pub bytes_uploaded: u64,
+}
+
+pub fn stats() -> LogStatsSnapshot {
+    LogStatsSnapshot {
+        lines_received: STATS.lines_received.load(Ordering::Relaxed),
+        lines_shipped: STATS.lines_shipped.load(Ordering::Relaxed),
+        lines_dropped_buffer_full: STATS.lines_dropped_buffer_full.load(Ordering::Relaxed),
+        lines_dropped_rejected: STATS.lines_dropped_rejected.load(Ordering::Relaxed),
+        lines_dropped_write_failed: STATS.lines_dropped_write_failed.load(Ordering::Relaxed),
+        batches_shipped: STATS.batches_shipped.load(Ordering::Relaxed),
+        upload_retries: STATS.upload_retries.load(Ordering::Relaxed),
+        bytes_uploaded: STATS.bytes_uploaded.load(Ordering::Relaxed),
+    }
+}
+
+/// A position in a spool: byte offset and line count within a segment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Position {
+    segment: u64,
+    offset: u64,
+    lines: u64,
+}
+
+#[derive(Debug)]
+struct Segment {
+    seq: u64,
+    bytes: u64,
+    lines: u64,
+}
+
+#[derive(Debug, Default)]
+struct SpoolState {
+    /// Segment files, oldest first; new lines go to the last one.
+    segments: VecDeque<Segment>,
+    /// Append handle on the last segment.
+    writer: Option<File>,
+    /// Start of the lines not yet shipped.
+    read: Position,
+    /// End of the batch being uploaded.
+    in_flight: Option<Position>,
+    /// Size of all segment files.
+    total_bytes: u64,
+    /// Lines appended since the shipper last read.
+    appended: usize,
+    /// Lines dropped since the last drop report.
+    dropped: u64,
+    /// Open [`SpoolWriter`]s.
+    writers: usize,
+}
+
+/// Lines read from a spool for one upload.
+#[derive(Debug)]
+pub struct Batch {
+    pub entries: Vec<WorkloadLogEntry>,
+    /// Whether the batch hit the entry or byte limit.
+    pub full: bool,
+    /// Lines consumed, including unreadable ones that were skipped.
+    consumed: u64,
+    end: Position,
+}
+
+/// Disk ring buffer of one instance's log lines.
+///
+/// Lines are stored as JSON, one per line, in numbered segment files.
+#[derive(Debug)]
+pub struct LogSpool {
+    dir: PathBuf,
+    instance_id: String,
+    buffer_bytes: u64,
+    segment_bytes: u64,
+    batch_entries: usize,
+    drop_policy: DropPolicy,
+    state: Mutex<SpoolState>,
+    notify: Notify,
+}
+
+impl LogSpool {
+    /// Open the spool in `dir`, picking up segments and the read position
+    /// left by a previous agent process.
+    pub fn open(dir: PathBuf, instance_id: &str, config: &LogShippingConfig) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(seq) = segment_seq(&path) else {
+                continue;
+            };
+            // Count complete lines only; a torn final line from a crash is
+            // cut off by the next append.
+            let mut reader = BufReader::new(File::open(&path)?);
+            let (mut lines, mut bytes) = (0u64, 0u64);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                let n = reader.read_until(b'\n', &mut line)?;
+                if n == 0 || line.last() != Some(&b'\n') {
+                    break;
+                }
+                bytes += n as u64;
+                lines += 1;
+            }
+            segments.push(Segment { seq, bytes, lines });
+        }
+        segments.sort_by_key(|segment| segment.seq);
+
+        let read = fs::read(dir.join(CURSOR_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Position>(&bytes).ok())
+            .filter(|read| {
+                segments
+                    .iter()
+                    .any(|s| s.seq == read.segment && s.bytes >= read.offset)
+            })
+            .unwrap_or(Position {
+                segment: segments.first().map_or(0, |s| s.seq),
+                offset: 0,
+                lines: 0,
+            });
+
+        let state = SpoolState {
+            total_bytes: segments.iter().map(|s| s.bytes).sum(),
+            segments: segments.into(),
+            read,
+            ..Default::default()
+        };
+        Ok(Self {
+            dir,
+            instance_id: instance_id.to_string(),
+            buffer_bytes: config.buffer_bytes,
+            segment_bytes: (config.buffer_bytes / SEGMENTS_PER_BUFFER).max(1),
+            batch_entries: config.batch_entries,
+            drop_policy: config.drop_policy,
+            state: Mutex::new(state),
+            notify: Notify::new(),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SpoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn segment_path(&self, seq: u64) -> PathBuf {
+        self.dir.join(format!("{seq:016}.log"))
+    }
+
+    /// A handle for appending lines; the shipper stops once every writer is
+    /// dropped and the spool is drained.
+    pub fn writer(self: &Arc<Self>) -> SpoolWriter {
+        self.lock().writers += 1;
+        SpoolWriter {
+            spool: Arc::clone(self),
+        }
+    }
+
+    fn append(&self, entry: &WorkloadLogEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        let mut guard = self.lock();
+        let state = &mut *guard;
+        STATS.lines_received.fetch_add(1, Ordering::Relaxed);
+
+        if self.drop_policy == DropPolicy::Newest && state.total_bytes + len > self.buffer_bytes {
+            self.record_drop(state, 1);
+            return Ok(());
+        }
+
+        let rotate = match state.segments.back() {
+            Some(segment) => segment.bytes > 0 && segment.bytes + len > self.segment_bytes,
+            None => true,
+        };
+        if rotate {
+            let seq = state.segments.back().map_or(0, |s| s.seq + 1);
+            state.writer = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.segment_path(seq))?,
+            );
+            state.segments.push_back(Segment {
+                seq,
+                bytes: 0,
+                lines: 0,
+            });
+            if state.segments.len() == 1 {
+                state.read = Position {
+                    segment: seq,
+                    offset: 0,
+                    lines: 0,
+                };
+            }
+        }
+
+        while state.total_bytes + len > self.buffer_bytes && state.segments.len() > 1 {
+            self.evict_oldest(state);
+        }
+
+        let back = state.segments.back().map(|s| (s.seq, s.bytes));
+        let Some((seq, bytes)) = back else {
+            return Ok(());
+        };
+        if state.writer.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.segment_path(seq))?;
+            // Cut a torn final line left by a crash.
+            file.set_len(bytes)?;
+            state.writer = Some(file);
+        }
+        if let Some(writer) = state.writer.as_mut() {
+            writer.write_all(&line)?;
+        }
+        if let Some(segment) = state.segments.back_mut() {
+            segment.bytes += len;
+            segment.lines += 1;
+        }
+        state.total_bytes += len;
+        state.appended += 1;
+        if state.appended >= self.batch_entries {
+            self.notify.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Delete the oldest segment, counting its unshipped lines as dropped.
+    fn evict_oldest(&self, state: &mut SpoolState) {
+        let Some(segment) = state.segments.pop_front() else {
+            return;
+        };
+        if let Err(e) = fs::remove_file(self.segment_path(segment.seq)) {
+            warn!(instance_id = %self.instance_id, error = %e, "Failed to remove log segment");
+        }
+        state.total_bytes -= segment.bytes;
+
+        let shipped = state.in_flight.unwrap_or(state.read);
+        let unshipped = match shipped.segment.cmp(&segment.seq) {
+            std::cmp::Ordering::Greater => 0,
+            std::cmp::Ordering::Equal => segment.lines.saturating_sub(shipped.lines),
+            std::cmp::Ordering::Less => segment.lines,
+        };
+        if state.read.segment <= segment.seq {
+            state.read = Position {
+                segment: state.segments.front().map_or(segment.seq + 1, |s| s.seq),
+                offset: 0,
+                lines: 0,
+            };
+        }
+        self.record_drop(state, unshipped);
+    }
+
+    fn record_drop(&self, state: &mut SpoolState, lines: u64) {
+        if lines == 0 {
+            return;
+        }
+        if state.dropped == 0 {
+            warn!(
+                instance_id = %self.instance_id,
+                policy = ?self.drop_policy,
+                "Log buffer full; dropping lines"
+            );
+        }
+        state.dropped += lines;
+        STATS
+            .lines_dropped_buffer_full
+            .fetch_add(lines, Ordering::Relaxed);
+    }
+
+    /// Read up to `max_entries` lines or `max_bytes` from the read position.
+    pub fn read_batch(&self, max_entries: usize, max_bytes: usize) -> io::Result<Batch> {
+        let mut state = self.lock();
+        state.appended = 0;
+
+        let mut pos = state.read;
+        let mut entries = Vec::new();
+        let mut consumed = 0u64;
+        let mut bytes = 0usize;
+        let mut full = false;
+
+        'segments: while let Some(index) = state.segments.iter().position(|s| s.seq == pos.segment)
+        {
+            let segment = &state.segments[index];
+            if pos.offset >= segment.bytes {
+                match state.segments.get(index + 1) {
+                    Some(next) => {
+                        pos = Position {
+                            segment: next.seq,
+                            offset: 0,
+                            lines: 0,
+                        };
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let segment_end = segment.bytes;
+            let mut file = File::open(self.segment_path(segment.seq))?;
+            file.seek(SeekFrom::Start(pos.offset))?;
+            let mut reader = BufReader::new(file.take(segment.bytes - pos.offset));
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                let n = reader.read_until(b'\n', &mut line)?;
+                if n == 0 {
+                    // End of the segment (or of what is left of a file
+                    // truncated behind our back); move on to the next one.
+                    pos.offset = segment_end;
+                    continue 'segments;
+                }
+                if line.last() != Some(&b'\n') {
+                    break 'segments;
+                }
+                pos.offset += n as u64;
+                pos.lines += 1;
+                consumed += 1;
+                bytes += n;
+                match serde_json::from_slice::<WorkloadLogEntry>(&line) {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => {
+                        debug!(instance_id = %self.instance_id, error = %e, "Skipping unreadable log line")
+                    }
+                }
+                if entries.len() >= max_entries || bytes >= max_bytes {
+                    full = true;
+                    break 'segments;
+                }
+            }
+        }
+
+        if consumed > 0 {
+            state.in_flight = Some(pos);
+        }
+        Ok(Batch {
+            entries,
+            full,
+            consumed,
+            end: pos,
+        })
+    }
+
+    /// Mark a batch as shipped (or discarded) and delete the segments it
+    /// finished.
+    pub fn commit(&self, batch: &Batch) -> io::Result<()> {
+        let mut guard = self.lock();
+        let state = &mut *guard;
+        state.in_flight = None;
+
+        // Segments evicted while the batch was in flight already moved the
+        // read position past them.
+        if state.segments.iter().any(|s| s.seq == batch.end.segment) {
+            state.read = batch.end;
+        }
+        while let Some(front) = state.segments.front() {
+            if front.seq >= state.read.segment {
+                break;
+            }
+            let (seq, bytes) = (front.seq, front.bytes);
+            state.segments.pop_front();
+            state.total_bytes -= bytes;
+            fs::remove_file(self.segment_path(seq))?;
+        }
+
+        if state.dropped > 0 {
+            warn!(
+                instance_id = %self.instance_id,
+                dropped = state.dropped,
+                "Log buffer has room again; lines were dropped"
+            );
+            state.dropped = 0;
+        }
+
+        let cursor = serde_json::to_vec(&state.read)?;
+        let tmp = self.dir.join(format!("{CURSOR_FILE}.tmp"));
+        fs::write(&tmp, cursor)?;
+        fs::rename(tmp, self.dir.join(CURSOR_FILE))
+    }
+
+    /// Whether no writer is left and every line has been shipped.
+    fn is_finished(&self) -> bool {
+        let state = self.lock();
+        let drained = match state.segments.back() {
+            Some(last) => state.read.segment >= last.seq && state.read.offset >= last.bytes,
+            None => true,
+        };
+        state.writers == 0 && drained
+    }
+
+    /// Wait until a full batch is buffered, the last writer is dropped, or
+    /// `timeout` elapses.
+    async fn wait(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.notify.notified()).await;
+    }
+}
+
+/// Appends lines to a [`LogSpool`].
+#[derive(Debug)]
+pub struct SpoolWriter {
+    spool: Arc<LogSpool>,
+}
+
+impl SpoolWriter {
+    /// Buffer a line for shipping. Never waits on the network.
+    pub fn append(&self, entry: &WorkloadLogEntry) {
+        if let Err(e) = self.spool.append(entry) {
+            STATS
+                .lines_dropped_write_failed
+                .fetch_add(1, Ordering::Relaxed);
+            warn!(
+                instance_id = %self.spool.instance_id,
+                error = %e,
+                "Failed to buffer log line"
+            );
+        }
+    }
+}
+
+impl Clone for SpoolWriter {
+    fn clone(&self) -> Self {
+        self.spool.writer()
+    }
+}
+
+impl Drop for SpoolWriter {
+    fn drop(&mut self) {
+        self.spool.lock().writers -= 1;
+        self.spool.notify.notify_one();
+    }
+}
+
+fn segment_seq(path: &Path) -> Option<u64> {
+    if path.extension()? != "log" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Ships every instance's log spool to the control plane.
+pub struct LogShipper {
+    config: LogShippingConfig,
+    root: PathBuf,
+    client: Arc<ControlPlaneClient>,
+    spools: Mutex<HashMap<String, Arc<LogSpool>>>,
+}
+
+impl LogShipper {
+    /// `root` holds one spool directory per instance.
+    pub fn new(config: LogShippingConfig, root: PathBuf, client: Arc<ControlPlaneClient>) -> Self {
+        Self {
+            config,
+            root,
+            client,
+            spools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Ship the spools left by a previous agent process.
+    pub fn resume(self: &Arc<Self>) {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return;
+        };
+        let mut resumed = 0;
+        for entry in entries.flatten() {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let Some(instance_id) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let mut spools = self.spools.lock().unwrap_or_else(|e| e.into_inner());
+            match self.spool(&mut spools, &instance_id) {
+                Ok(_) => resumed += 1,
+                Err(e) => warn!(instance_id = %instance_id, error = %e, "Failed to open log spool"),
+            }
+        }
+        if resumed > 0 {
+            info!(count = resumed, "Resumed shipping buffered workload logs");
+        }
+    }
+
+    /// A writer for an instance's spool. The instance's shipper runs until
+    /// every writer is dropped and its buffered lines are shipped.
+    pub fn writer(self: &Arc<Self>, instance_id: &str) -> io::Result<SpoolWriter> {
+        let mut spools = self.spools.lock().unwrap_or_else(|e| e.into_inner());
+        let spool = self.spool(&mut spools, instance_id)?;
+        Ok(spool.writer())
+    }
+
+    /// The open spool for an instance, opening it and starting its shipper
+    /// if needed.
+    fn spool(
+        self: &Arc<Self>,
+        spools: &mut HashMap<String, Arc<LogSpool>>,
+        instance_id: &str,
+    ) -> io::Result<Arc<LogSpool>> {
+        if let Some(spool) = spools.get(instance_id) {
+            return Ok(Arc::clone(spool));
+        }
+        let spool = Arc::new(LogSpool::open(
+            self.root.join(instance_id),
+            instance_id,
+            &self.config,
+        )?);
+        spools.insert(instance_id.to_string(), Arc::clone(&spool));
+        tokio::spawn(run_shipper(Arc::clone(self), Arc::clone(&spool)));
+        Ok(spool)
+    }
+
+    /// Close and delete a finished spool. Checked under the registry lock so
+    /// a concurrent [`LogShipper::writer`] cannot pick up a deleted spool.
+    fn release_if_finished(&self, spool: &LogSpool) -> bool {
+        let mut spools = self.spools.lock().unwrap_or_else(|e| e.into_inner());
+        if !spool.is_finished() {
+            return false;
+        }
+        spools.remove(&spool.instance_id);
+        if let Err(e) = fs::remove_dir_all(&spool.dir) {
+            warn!(instance_id = %spool.instance_id, error = %e, "Failed to remove log spool");
+        }
+        true
+    }
+
+    /// Upload a batch, retrying until the control plane accepts or rejects
+    /// it.
+    async fn ship(&self, spool: &LogSpool, entries: &[WorkloadLogEntry]) {
+        let lines = entries.len() as u64;
+        let body = serde_json::to_vec(&WorkloadLogRequest { entries })
+            .map_err(io::Error::from)
+            .and_then(|body| self.config.compression.compress(body));
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(instance_id = %spool.instance_id, error = %e, "Failed to encode log batch");
+                STATS
+                    .lines_dropped_rejected
+                    .fetch_add(lines, Ordering::Relaxed);
+                return;
+            }
+        };
+        let encoding = self.config.compression.content_encoding();
+
+        let mut attempt = 0;
+        loop {
+            let retry_after = match self.client.send_workload_logs(body.clone(), encoding).await {
+                Ok(LogUploadOutcome::Accepted) => {
+                    STATS.lines_shipped.fetch_add(lines, Ordering::Relaxed);
+                    STATS.batches_shipped.fetch_add(1, Ordering::Relaxed);
+                    STATS
+                        .bytes_uploaded
+                        .fetch_add(body.len() as u64, Ordering::Relaxed);
+                    return;
+                }
+                Ok(LogUploadOutcome::Rejected { status }) => {
+                    warn!(
+                        instance_id = %spool.instance_id,
+                        status,
+                        lines,
+                        "Control plane rejected log batch; dropping it"
+                    );
+                    STATS
+                        .lines_dropped_rejected
+                        .fetch_add(lines, Ordering::Relaxed);
+                    return;
+                }
+                Ok(LogUploadOutcome::Retry { retry_after }) => retry_after,
+                Err(e) => {
+                    debug!(instance_id = %spool.instance_id, error = %e, "Log upload failed");
+                    None
+                }
+            };
+
+            let delay = self
+                .config
+                .backoff
+                .delay(attempt)
+                .max(retry_after.unwrap_or_default());
+            if attempt == 0 {
+                warn!(
+                    instance_id = %spool.instance_id,
+                    retry_in_ms = delay.as_millis() as u64,
+                    "Log upload failed; buffering on disk and retrying"
+                );
+            }
+            STATS.upload_retries.fetch_add(1, Ordering::Relaxed);
+            attempt = attempt.saturating_add(1);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+async fn run_shipper(shipper: Arc<LogShipper>, spool: Arc<LogSpool>) {
+    let config = &shipper.config;
+    let mut full = false;
+    loop {
+        // Let a partial batch fill up before uploading it.
+        if !full {
+            spool.wait(config.flush_interval).await;
+        }
+
+        let batch = match spool.read_batch(config.batch_entries, config.batch_bytes) {
+            Ok(batch) => batch,
+            Err(e) => {
+                warn!(instance_id = %spool.instance_id, error = %e, "Failed to read log spool");
+                full = false;
+                continue;
+            }
+        };
+        full = batch.full;
+
+        if batch.consumed == 0 {
+            if shipper.release_if_finished(&spool) {
+                debug!(instance_id = %spool.instance_id, "Log shipper finished");
+                return;
+            }
+            continue;
+        }
+
+        if !batch.entries.is_empty() {
+            shipper.ship(&spool, &batch.entries).await;
+        }
+        if let Err(e) = spool.commit(&batch) {
+            warn!(instance_id = %spool.instance_id, error = %e, "Failed to advance log spool");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(line: &str) -> WorkloadLogEntry {
+        WorkloadLogEntry {
+            ts: Utc::now(),
+            instance_id: "inst_1".to_string(),
+            stream: "stdout".to_string(),
+            line: line.to_string(),
+            truncated: false,
+        }
+    }
+
+    fn config(buffer_bytes: u64, drop_policy: DropPolicy) -> LogShippingConfig {
+        LogShippingConfig {
+            buffer_bytes,
+            drop_policy,
+            ..Default::default()
+        }
+    }
+
+    fn lines(batch: &Batch) -> Vec<String> {
+        batch.entries.iter().map(|e| e.line.clone()).collect()
+    }
+
+    #[test]
+    fn test_spool_ships_and_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(1024 * 1024, DropPolicy::Oldest);
+        let spool = Arc::new(LogSpool::open(dir.path().join("inst_1"), "inst_1", &config).unwrap());
+        let writer = spool.writer();
+        for i in 0..5 {
+            writer.append(&entry(&format!("line {i}")));
+        }
+
+        let batch = spool.read_batch(3, usize::MAX).unwrap();
+        assert!(batch.full);
+        assert_eq!(lines(&batch), ["line 0", "line 1", "line 2"]);
+        spool.commit(&batch).unwrap();
+        drop(writer);
+        assert!(!spool.is_finished());
+
+        // A new agent process continues after the committed batch.
+        let spool = LogSpool::open(dir.path().join("inst_1"), "inst_1", &config).unwrap();
+        let batch = spool.read_batch(10, usize::MAX).unwrap();
+        assert!(!batch.full);
+        assert_eq!(lines(&batch), ["line 3", "line 4"]);
+        spool.commit(&batch).unwrap();
+        assert!(spool.is_finished());
+        assert_eq!(spool.read_batch(10, usize::MAX).unwrap().consumed, 0);
+    }
+
+    #[test]
+    fn test_spool_drops_oldest_when_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool = Arc::new(
+            LogSpool::open(
+                dir.path().to_path_buf(),
+                "inst_1",
+                &config(4096, DropPolicy::Oldest),
+            )
+            .unwrap(),
+        );
+        let writer = spool.writer();
+        for i in 0..200 {
+            writer.append(&entry(&format!("line {i}")));
+        }
+
+        let state = spool.lock();
+        assert!(state.total_bytes <= 4096);
+        assert!(state.dropped > 0);
+        drop(state);
+
+        let batch = spool.read_batch(usize::MAX, usize::MAX).unwrap();
+        assert_eq!(batch.entries.last().unwrap().line, "line 199");
+        assert_ne!(batch.entries[0].line, "line 0");
+        assert_eq!(
+            batch.entries.len() as u64 + spool.lock().dropped,
+            200,
+            "every line is either buffered or counted as dropped"
+        );
+    }
+
+    #[test]
+    fn test_spool_drops_newest_when_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool = Arc::new(
+            LogSpool::open(
+                dir.path().to_path_buf(),
+                "inst_1",
+                &config(4096, DropPolicy::Newest),
+            )
+            .unwrap(),
+        );
+        let writer = spool.writer();
+        for i in 0..200 {
+            writer.append(&entry(&format!("line {i}")));
+        }
+
+        let batch = spool.read_batch(usize::MAX, usize::MAX).unwrap();
+        assert_eq!(batch.entries[0].line, "line 0");
+        assert_eq!(
+            batch.entries.len() as u64 + spool.lock().dropped,
+            200,
+            "every line is either buffered or counted as dropped"
+        );
+
+        // Shipping frees the buffer for new lines.
+        spool.commit(&batch).unwrap();
+        writer.append(&entry("after"));
+        let batch = spool.read_batch(usize::MAX, usize::MAX).unwrap();
+        assert_eq!(lines(&batch), ["after"]);
+    }
+
+    #[test]
+    fn test_spool_skips_torn_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(1024 * 1024, DropPolicy::Oldest);
+        let spool = Arc::new(LogSpool::open(dir.path().to_path_buf(), "inst_1", &config).unwrap());
+        spool.writer().append(&entry("complete"));
+        drop(spool);
+
+        // A crash left half a line behind.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(format!("{:016}.log", 0)))
+            .unwrap();
+        file.write_all(b"{\"ts\":").unwrap();
+
+        let spool = Arc::new(LogSpool::open(dir.path().to_path_buf(), "inst_1", &config).unwrap());
+        spool.writer().append(&entry("next"));
+        let batch = spool.read_batch(usize::MAX, usize::MAX).unwrap();
+        assert_eq!(lines(&batch), ["complete", "next"]);
+    }
+
+    #[test]
+    fn test_compression_round_trip() {
+        let body = br#"{"entries":[]}"#.repeat(100);
+
+        let gzip = LogCompression::Gzip.compress(body.clone()).unwrap();
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(gzip.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+
+        let zstd = LogCompression::Zstd.compress(body.clone()).unwrap();
+        assert_eq!(zstd::stream::decode_all(zstd.as_slice()).unwrap(), body);
+
+        assert_eq!(LogCompression::None.compress(body.clone()).unwrap(), body);
+        assert_eq!(LogCompression::Zstd.content_encoding(), Some("zstd"));
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        assert_eq!(
+            "zstd".parse::<LogCompression>().unwrap(),
+            LogCompression::Zstd
+        );
+        assert_eq!(
+            " GZIP ".parse::<LogCompression>().unwrap(),
+            LogCompression::Gzip
+        );
+        assert!("brotli".parse::<LogCompression>().is_err());
+        assert_eq!("newest".parse::<DropPolicy>().unwrap(), DropPolicy::Newest);
+        assert!("random".parse::<DropPolicy>().is_err());
+    }
+}
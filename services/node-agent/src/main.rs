@@ -3,25 +3,33 @@
 //! This is the main entry point for the node agent.
 //! See the library crate (`plfm_node_agent`) for documentation.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use tokio::sync::watch;
 use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 // Use the library crate
 use plfm_node_agent::actors::NodeSupervisor;
+use plfm_node_agent::concurrency::{self, ConcurrencyConfig};
 use plfm_node_agent::config::Config;
+use plfm_node_agent::console::ConsoleLogs;
 use plfm_node_agent::exec_gateway::ExecGateway;
-use plfm_node_agent::firecracker::{FirecrackerRuntime, FirecrackerRuntimeConfig};
+use plfm_node_agent::exec_tunnel::ExecTunnelClient;
+use plfm_node_agent::firecracker::{orphans, FirecrackerRuntime, FirecrackerRuntimeConfig};
 use plfm_node_agent::heartbeat;
 use plfm_node_agent::image::{
     ImageCache, ImageCacheConfig, ImagePuller, ImagePullerConfig, OciConfig, RootDiskConfig,
 };
+use plfm_node_agent::local_api::LocalApi;
+use plfm_node_agent::log_shipping::LogShippingConfig;
+use plfm_node_agent::network::{DnsConfig, DnsServer, Nat64Config};
 use plfm_node_agent::reconciler::{Reconciler, ReconcilerConfig};
-use plfm_node_agent::state::StateStore;
+use plfm_node_agent::secrets::{SecretCache, SecretCacheConfig};
+use plfm_node_agent::state::{StateExport, StateStore};
+use plfm_node_agent::storage::StorageConfig;
 use plfm_node_agent::vsock::{ConfigDeliveryService, ConfigStore};
 use plfm_node_agent::{ControlPlaneClient, InstanceManager, MockRuntime};
 
@@ -91,12 +99,92 @@ async fn build_firecracker_runtime(
     {
         fc_config.use_jailer = value == "1" || value.to_lowercase() == "true";
     }
+    fc_config.storage = StorageConfig::from_env()?;
+    fc_config.logs = LogShippingConfig::from_env()?;
 
-    Ok(Arc::new(FirecrackerRuntime::new(
-        fc_config,
-        image_puller,
-        Some(control_plane_client),
-    )))
+    let runtime = FirecrackerRuntime::new(fc_config, image_puller, Some(control_plane_client));
+    runtime.init_storage().await?;
+    runtime.resume_log_shipping();
+    Ok(Arc::new(runtime))
+}
+
+/// Start the node-local API for an actor-mode supervisor, if configured.
+fn spawn_local_api<R: plfm_node_agent::runtime::Runtime + Send + Sync + 'static>(
+    config: &Config,
+    supervisor: &NodeSupervisor<R>,
+    state_store: &Arc<std::sync::Mutex<StateStore>>,
+    shutdown_rx: &watch::Receiver<bool>,
+) {
+    let Some(socket_path) = config.local_api_socket.clone() else {
+        info!("Local API disabled (GHOST_LOCAL_API_SOCKET=off)");
+        return;
+    };
+    let api = LocalApi::new(
+        socket_path,
+        config.node_id.to_string(),
+        supervisor.local_api_sender(),
+        Arc::clone(state_store),
+        ConsoleLogs::new(PathBuf::from(&config.data_dir).join("console")),
+    );
+    let shutdown_rx = shutdown_rx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = api.run(shutdown_rx).await {
+            error!(error = %e, "Local API failed");
+        }
+    });
+}
+
+/// `node-agent state export <file>` / `node-agent state import <file> [--force]`.
+///
+/// Export works while the agent runs; import must run with the agent stopped.
+/// `-` means stdout/stdin.
+fn run_state_command(config: &Config, args: &[String]) -> Result<()> {
+    let usage =
+        "usage: node-agent state export <file|-> | node-agent state import <file|-> [--force]";
+    let (command, path) = match args {
+        [command, path, ..] => (command.as_str(), path.as_str()),
+        _ => anyhow::bail!(usage),
+    };
+    let force = args[2..].iter().any(|a| a == "--force");
+
+    let state_db_path = PathBuf::from(&config.data_dir).join("node-agent.db");
+    let store = StateStore::open(&state_db_path)
+        .with_context(|| format!("Failed to open state store {}", state_db_path.display()))?;
+    let node_id = config.node_id.to_string();
+
+    match command {
+        "export" => {
+            let export = store.export_state(&node_id)?;
+            let json = serde_json::to_string_pretty(&export)?;
+            if path == "-" {
+                println!("{json}");
+            } else {
+                std::fs::write(path, json).with_context(|| format!("Failed to write {path}"))?;
+                eprintln!(
+                    "Exported {} instances (cursor {}) to {path}",
+                    export.instances.len(),
+                    export.node_state.cursor_event_id
+                );
+            }
+        }
+        "import" => {
+            let json = if path == "-" {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?
+            };
+            let export: StateExport =
+                serde_json::from_str(&json).context("Invalid state export")?;
+            let summary = store.import_state(&export, &node_id, force)?;
+            eprintln!(
+                "Imported {} instances and {} boot records (replaced {}). \
+                 Running VMs are re-adopted when the agent starts.",
+                summary.instances, summary.boot_status, summary.replaced_instances
+            );
+        }
+        _ => anyhow::bail!(usage),
+    }
+    Ok(())
 }
 
 #[tokio::main]
@@ -104,6 +192,11 @@ async fn main() -> Result<()> {
     // Load configuration
     let config = Config::from_env()?;
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("state") {
+        return run_state_command(&config, &args[1..]);
+    }
+
     // Initialize tracing (prefer RUST_LOG, fallback to GHOST_LOG_LEVEL)
     tracing_subscriber::registry()
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| config.log_level.clone().into()))
@@ -118,6 +211,15 @@ async fn main() -> Result<()> {
         "Configuration loaded"
     );
 
+    let limits = ConcurrencyConfig::from_env()?;
+    info!(
+        max_pulls = limits.max_pulls,
+        max_boots = limits.max_boots,
+        max_teardowns = limits.max_teardowns,
+        "Concurrency limits"
+    );
+    concurrency::configure(limits);
+
     // Create shutdown channel
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
@@ -132,6 +234,21 @@ async fn main() -> Result<()> {
     let state_store = Arc::new(std::sync::Mutex::new(
         StateStore::open(&state_db_path).expect("Failed to open state store"),
     ));
+    {
+        let store = state_store.lock().unwrap_or_else(|e| e.into_inner());
+        let retries = store.retries();
+        match retries.prune() {
+            Ok(pruned) => info!(pruned, "Pruned expired retry records"),
+            Err(e) => warn!(error = %e, "Failed to prune retry records"),
+        }
+        if let Ok(stats) = retries.stats() {
+            info!(
+                tracked = stats.tracked,
+                exhausted = stats.exhausted,
+                "Loaded retry budgets"
+            );
+        }
+    }
 
     // Config delivery service for guest-init
     let config_store = Arc::new(ConfigStore::new());
@@ -143,6 +260,32 @@ async fn main() -> Result<()> {
         }
     });
 
+    // NAT64 for IPv4-only upstreams; DNS64 below points guests at it
+    match Nat64Config::from_env()? {
+        Some(nat64) => {
+            if let Err(e) = nat64.setup() {
+                error!(error = %e, "NAT64 setup failed; IPv4-only destinations unreachable");
+            }
+        }
+        None => info!("NAT64 disabled (GHOST_NAT64_PREFIX not set)"),
+    }
+
+    // Node-local DNS for internal route names and DNS64
+    match DnsConfig::from_env()? {
+        Some(dns_config) => {
+            let dns_server = DnsServer::new(dns_config);
+            let shutdown_rx = shutdown_rx.clone();
+            tokio::spawn(async move {
+                if let Err(e) = dns_server.run(shutdown_rx).await {
+                    error!(error = %e, "Node-local DNS failed");
+                }
+            });
+        }
+        None => info!(
+            "Node-local DNS disabled (GHOST_INTERNAL_INGRESS_ADDRS and GHOST_NAT64_PREFIX not set)"
+        ),
+    }
+
     let use_legacy = std::env::var("VT_USE_LEGACY")
         .map(|v| v == "1" || v.to_lowercase() == "true")
         .unwrap_or(false);
@@ -158,6 +301,32 @@ async fn main() -> Result<()> {
         if runtime_kind == "firecracker" {
             let runtime =
                 build_firecracker_runtime(&config, Arc::clone(&control_plane_client)).await?;
+
+            // Adopt or collect VMs no actor owns before any actor starts.
+            let plan = match tokio::time::timeout(
+                std::time::Duration::from_secs(10),
+                control_plane_client.fetch_plan(),
+            )
+            .await
+            {
+                Ok(Ok(plan)) => Some(plan),
+                Ok(Err(e)) => {
+                    warn!(error = %e, "Failed to fetch plan for orphan scan");
+                    None
+                }
+                Err(_) => {
+                    warn!("Timed out fetching plan for orphan scan");
+                    None
+                }
+            };
+            orphans::sweep(
+                &orphans::HostPaths::new(Path::new(&config.data_dir)),
+                &state_store,
+                plan.as_ref(),
+                orphans::OrphanGcMode::from_env()?,
+            )
+            .await;
+
             let mut supervisor = NodeSupervisor::new(
                 config.clone(),
                 Arc::clone(&runtime),
@@ -167,6 +336,7 @@ async fn main() -> Result<()> {
             );
 
             supervisor.start();
+            spawn_local_api(&config, &supervisor, &state_store, &shutdown_rx);
 
             let supervisor_handle = tokio::spawn(async move {
                 supervisor.run().await;
@@ -191,6 +361,7 @@ async fn main() -> Result<()> {
             );
 
             supervisor.start();
+            spawn_local_api(&config, &supervisor, &state_store, &shutdown_rx);
 
             let supervisor_handle = tokio::spawn(async move {
                 supervisor.run().await;
@@ -211,6 +382,9 @@ async fn main() -> Result<()> {
     } else {
         // === Legacy mode (backward compatible) ===
         info!("Using legacy reconciliation mode");
+        if config.local_api_socket.is_some() {
+            info!("Local API is only available in actor mode");
+        }
 
         let runtime: Arc<dyn plfm_node_agent::runtime::Runtime> = if runtime_kind == "firecracker" {
             build_firecracker_runtime(&config, Arc::clone(&control_plane_client)).await?
@@ -218,21 +392,39 @@ async fn main() -> Result<()> {
             Arc::new(MockRuntime::new())
         };
 
-        let instance_manager = Arc::new(InstanceManager::new(
+        let mut instance_manager = InstanceManager::new(
             runtime,
             Arc::clone(&config_store),
             Arc::clone(&state_store),
             Arc::clone(&control_plane_client),
-        ));
-
-        // Start exec gateway listener
-        let exec_gateway = ExecGateway::new(config.exec_listen_addr, Arc::clone(&instance_manager));
-        let exec_handle = tokio::spawn(async move {
-            if let Err(e) = exec_gateway.run().await {
-                error!(error = %e, "Exec gateway failed");
+        );
+        match SecretCache::open(SecretCacheConfig::from_data_dir(&PathBuf::from(
+            &config.data_dir,
+        ))) {
+            Ok(cache) => instance_manager = instance_manager.with_secret_cache(Arc::new(cache)),
+            Err(e) => {
+                warn!(error = %e, "Secret cache unavailable; fetching secrets on every start")
             }
+        }
+        let instance_manager = Arc::new(instance_manager);
+
+        // Exec sessions arrive over the gRPC exec tunnel
+        let exec_tunnel = ExecTunnelClient::new(config.clone(), Arc::clone(&instance_manager));
+        let exec_handle = tokio::spawn({
+            let shutdown_rx = shutdown_rx.clone();
+            async move { exec_tunnel.run(shutdown_rx).await }
         });
 
+        // Legacy TCP exec gateway, for control planes without the tunnel
+        if let Some(exec_listen_addr) = config.exec_listen_addr {
+            let exec_gateway = ExecGateway::new(exec_listen_addr, Arc::clone(&instance_manager));
+            tokio::spawn(async move {
+                if let Err(e) = exec_gateway.run().await {
+                    error!(error = %e, "Exec gateway failed");
+                }
+            });
+        }
+
         // Start the heartbeat loop
         let heartbeat_handle = tokio::spawn({
             let config = config.clone();
@@ -270,7 +462,7 @@ async fn main() -> Result<()> {
                 info!("Reconciler exited");
             }
             _ = exec_handle => {
-                warn!("Exec gateway exited");
+                warn!("Exec tunnel exited");
             }
             _ = config_delivery_handle => {
                 warn!("Config delivery service exited");
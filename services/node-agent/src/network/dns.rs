@@ -0,0 +1,667 @@
+//! Node-local DNS responder on the overlay.
+//!
+//! Guests use the node as their resolver (`fd00::53` by default). Names under
+//! the internal route suffix are answered here with the internal ingress
+//! addresses, so internal routes resolve only from inside the overlay; every
+//! other query is relayed unchanged to the upstream resolver.
+//!
+//! Internal names are answered without consulting the route table: a name
+//! with no route resolves, and the connection is refused at the ingress.
+//!
+//! With a NAT64 prefix configured (see [`super::nat64`]), the responder also
+//! does DNS64: an AAAA query for a name with no AAAA records is answered
+//! with the name's A records embedded in the prefix, so IPv4-only upstreams
+//! are reachable through the node's NAT64.
+//!
+//! Configuration:
+//! - `GHOST_INTERNAL_INGRESS_ADDRS`: comma-separated IPv6 addresses of the
+//!   internal ingress listeners
+//! - `GHOST_NAT64_PREFIX`: NAT64 prefix for DNS64; the responder is off
+//!   when neither this nor `GHOST_INTERNAL_INGRESS_ADDRS` is set
+//! - `GHOST_DNS_LISTEN_ADDR`: bind address (default `[fd00::53]:53`)
+//! - `GHOST_INTERNAL_ROUTE_SUFFIX`: internal hostname suffix (default
+//!   `internal`); must match the control plane's `PLFM_INTERNAL_ROUTE_SUFFIX`
+//! - `GHOST_DNS_UPSTREAM`: upstream resolver (default: first `nameserver` in
+//!   `/etc/resolv.conf`)
+//!
+//! See: docs/specs/networking/ingress-l4.md and
+//! docs/specs/networking/egress-nat64.md
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use super::nat64;
+
+const DEFAULT_LISTEN_ADDR: &str = "[fd00::53]:53";
+const DEFAULT_SUFFIX: &str = "internal";
+
+/// TTL of internal answers. Short, so ingress address changes apply quickly.
+const INTERNAL_TTL: u32 = 30;
+
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Largest UDP DNS message we accept (EDNS0 payload size).
+const MAX_MESSAGE: usize = 4096;
+
+const HEADER_LEN: usize = 12;
+const TYPE_A: u16 = 1;
+const TYPE_CNAME: u16 = 5;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+/// Node-local DNS configuration.
+#[derive(Debug, Clone)]
+pub struct DnsConfig {
+    pub listen_addr: SocketAddr,
+    pub internal_suffix: String,
+    pub internal_targets: Vec<Ipv6Addr>,
+    pub upstream: Option<SocketAddr>,
+    /// NAT64 prefix to synthesize AAAA records in (DNS64).
+    pub dns64_prefix: Option<Ipv6Addr>,
+}
+
+impl DnsConfig {
+    /// Load from the environment; `None` when neither internal ingress
+    /// addresses nor a NAT64 prefix are configured.
+    pub fn from_env() -> Result<Option<Self>> {
+        let targets = std::env::var("GHOST_INTERNAL_INGRESS_ADDRS").unwrap_or_default();
+        let dns64_prefix = nat64::prefix_from_env()?;
+        if targets.trim().is_empty() && dns64_prefix.is_none() {
+            return Ok(None);
+        }
+        let internal_targets = targets
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(|t| {
+                t.parse::<Ipv6Addr>()
+                    .with_context(|| format!("Invalid internal ingress address: {t}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let listen_addr = std::env::var("GHOST_DNS_LISTEN_ADDR")
+            .unwrap_or_else(|_| DEFAULT_LISTEN_ADDR.to_string())
+            .parse()
+            .context("GHOST_DNS_LISTEN_ADDR must be an address:port")?;
+
+        let internal_suffix = std::env::var("GHOST_INTERNAL_ROUTE_SUFFIX")
+            .ok()
+            .map(|v| v.trim().trim_matches('.').to_ascii_lowercase())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| DEFAULT_SUFFIX.to_string());
+
+        let upstream = match std::env::var("GHOST_DNS_UPSTREAM") {
+            Ok(v) => Some(
+                parse_upstream(v.trim())
+                    .with_context(|| format!("Invalid GHOST_DNS_UPSTREAM: {v}"))?,
+            ),
+            Err(_) => std::fs::read_to_string("/etc/resolv.conf")
+                .ok()
+                .and_then(|conf| resolv_conf_nameserver(&conf)),
+        };
+
+        Ok(Some(Self {
+            listen_addr,
+            internal_suffix,
+            internal_targets,
+            upstream,
+            dns64_prefix,
+        }))
+    }
+}
+
+/// `addr` or `addr:port`, port 53 by default.
+fn parse_upstream(s: &str) -> Option<SocketAddr> {
+    s.parse::<SocketAddr>()
+        .ok()
+        .or_else(|| s.parse().ok().map(|ip| SocketAddr::new(ip, 53)))
+}
+
+fn resolv_conf_nameserver(conf: &str) -> Option<SocketAddr> {
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .find_map(|rest| parse_upstream(rest.trim()))
+}
+
+/// The single question of a standard query.
+#[derive(Debug, PartialEq, Eq)]
+struct Question {
+    /// Lower-cased name without the trailing dot.
+    name: String,
+    qtype: u16,
+    qclass: u16,
+    /// Offset just past the question section.
+    end: usize,
+}
+
+/// Parse a standard query with exactly one question; anything else is
+/// relayed upstream untouched.
+fn parse_query(msg: &[u8]) -> Option<Question> {
+    if msg.len() < HEADER_LEN {
+        return None;
+    }
+    let flags = u16::from_be_bytes([msg[2], msg[3]]);
+    let qdcount = u16::from_be_bytes([msg[4], msg[5]]);
+    // QR must be clear and the opcode QUERY.
+    if flags & 0x8000 != 0 || (flags >> 11) & 0xf != 0 || qdcount != 1 {
+        return None;
+    }
+
+    let mut labels = Vec::new();
+    let mut pos = HEADER_LEN;
+    loop {
+        let len = *msg.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            break;
+        }
+        // Compression pointers do not appear in a query's first name.
+        if len > 63 {
+            return None;
+        }
+        let label = msg.get(pos..pos + len)?;
+        labels.push(std::str::from_utf8(label).ok()?.to_ascii_lowercase());
+        pos += len;
+    }
+    let tail = msg.get(pos..pos + 4)?;
+    Some(Question {
+        name: labels.join("."),
+        qtype: u16::from_be_bytes([tail[0], tail[1]]),
+        qclass: u16::from_be_bytes([tail[2], tail[3]]),
+        end: pos + 4,
+    })
+}
+
+fn is_internal(name: &str, suffix: &str) -> bool {
+    name == suffix || name.ends_with(&format!(".{suffix}"))
+}
+
+/// Authoritative answer for an internal name: the targets for `IN AAAA`,
+/// an empty answer (NODATA) for any other type.
+fn internal_answer(query: &[u8], question: &Question, targets: &[Ipv6Addr]) -> Vec<u8> {
+    let answers: &[Ipv6Addr] = if question.qtype == TYPE_AAAA && question.qclass == CLASS_IN {
+        targets
+    } else {
+        &[]
+    };
+
+    let mut out = Vec::with_capacity(question.end + answers.len() * 28);
+    out.extend_from_slice(&query[..2]);
+    let rd = u16::from_be_bytes([query[2], query[3]]) & 0x0100;
+    // QR | AA | RD (echoed) | RA, NOERROR.
+    out.extend_from_slice(&(0x8000 | 0x0400 | rd | 0x0080u16).to_be_bytes());
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&query[HEADER_LEN..question.end]);
+    for addr in answers {
+        // Name: pointer to the question name.
+        out.extend_from_slice(&0xc00cu16.to_be_bytes());
+        out.extend_from_slice(&TYPE_AAAA.to_be_bytes());
+        out.extend_from_slice(&CLASS_IN.to_be_bytes());
+        out.extend_from_slice(&INTERNAL_TTL.to_be_bytes());
+        out.extend_from_slice(&16u16.to_be_bytes());
+        out.extend_from_slice(&addr.octets());
+    }
+    out
+}
+
+/// SERVFAIL for a query we could not relay.
+fn servfail(query: &[u8]) -> Option<Vec<u8>> {
+    if query.len() < HEADER_LEN {
+        return None;
+    }
+    let mut out = query[..HEADER_LEN].to_vec();
+    let flags = u16::from_be_bytes([query[2], query[3]]);
+    // QR | opcode and RD echoed | RA | SERVFAIL.
+    let flags = 0x8000 | (flags & 0x7900) | 0x0080 | 2;
+    out[2..4].copy_from_slice(&flags.to_be_bytes());
+    // No sections beyond the header.
+    out[4..HEADER_LEN].fill(0);
+    Some(out)
+}
+
+/// UDP DNS responder bound to the node's overlay resolver address.
+pub struct DnsServer {
+    config: DnsConfig,
+}
+
+impl DnsServer {
+    pub fn new(config: DnsConfig) -> Self {
+        Self { config }
+    }
+
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        let socket = Arc::new(
+            UdpSocket::bind(self.config.listen_addr)
+                .await
+                .with_context(|| format!("Failed to bind DNS on {}", self.config.listen_addr))?,
+        );
+        info!(
+            listen_addr = %self.config.listen_addr,
+            internal_suffix = %self.config.internal_suffix,
+            upstream = ?self.config.upstream,
+            dns64_prefix = ?self.config.dns64_prefix,
+            "Node-local DNS started"
+        );
+        if self.config.upstream.is_none() {
+            warn!("No upstream resolver; only internal names will resolve");
+        }
+
+        let config = Arc::new(self.config);
+        let mut buf = vec![0u8; MAX_MESSAGE];
+        loop {
+            let (len, peer) = tokio::select! {
+                _ = shutdown.changed() => {
+                    info!("Node-local DNS shutting down");
+                    return Ok(());
+                }
+                result = socket.recv_from(&mut buf) => match result {
+                    Ok(received) => received,
+                    Err(e) => {
+                        warn!(error = %e, "DNS receive error");
+                        continue;
+                    }
+                },
+            };
+            let query = buf[..len].to_vec();
+
+            let question = parse_query(&query);
+            if let Some(question) = &question {
+                if is_internal(&question.name, &config.internal_suffix) {
+                    debug!(name = %question.name, qtype = question.qtype, "Internal DNS answer");
+                    let answer = internal_answer(&query, question, &config.internal_targets);
+                    if let Err(e) = socket.send_to(&answer, peer).await {
+                        debug!(error = %e, peer = %peer, "DNS send error");
+                    }
+                    continue;
+                }
+            }
+            let dns64 = question
+                .filter(|q| q.qtype == TYPE_AAAA && q.qclass == CLASS_IN)
+                .zip(config.dns64_prefix);
+
+            let socket = Arc::clone(&socket);
+            let upstream = config.upstream;
+            tokio::spawn(async move {
+                let response = match (upstream, dns64) {
+                    (Some(upstream), Some((question, prefix))) => {
+                        relay_dns64(&query, &question, upstream, prefix).await
+                    }
+                    (Some(upstream), None) => relay(&query, upstream).await,
+                    (None, _) => None,
+                };
+                if let Some(response) = response.or_else(|| servfail(&query)) {
+                    if let Err(e) = socket.send_to(&response, peer).await {
+                        debug!(error = %e, peer = %peer, "DNS send error");
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Relay an AAAA query; when the name has no AAAA records, answer with
+/// addresses synthesized from its A records instead.
+async fn relay_dns64(
+    query: &[u8],
+    question: &Question,
+    upstream: SocketAddr,
+    prefix: Ipv6Addr,
+) -> Option<Vec<u8>> {
+    let response = relay(query, upstream).await?;
+    if !needs_synthesis(&response) {
+        return Some(response);
+    }
+
+    let mut a_query = query.to_vec();
+    a_query[question.end - 4..question.end - 2].copy_from_slice(&TYPE_A.to_be_bytes());
+    let synthesized = match relay(&a_query, upstream).await {
+        Some(a_response) => synthesize(query, question, &a_response, prefix),
+        None => None,
+    };
+    if synthesized.is_some() {
+        debug!(name = %question.name, "DNS64 answer");
+    }
+    Some(synthesized.unwrap_or(response))
+}
+
+fn rcode(msg: &[u8]) -> u8 {
+    msg[3] & 0x0f
+}
+
+/// A NOERROR response without any AAAA answer.
+fn needs_synthesis(response: &[u8]) -> bool {
+    match answers(response) {
+        Some(records) => rcode(response) == 0 && !records.iter().any(|rr| rr.rtype == TYPE_AAAA),
+        None => false,
+    }
+}
+
+/// An answer record; the owner name is kept uncompressed.
+struct Record {
+    name: Vec<u8>,
+    rtype: u16,
+    rclass: u16,
+    ttl: u32,
+    rdata: std::ops::Range<usize>,
+}
+
+/// Read a possibly compressed name at `pos`; returns it uncompressed in
+/// wire format, and the offset just past it.
+fn read_name(msg: &[u8], mut pos: usize) -> Option<(Vec<u8>, usize)> {
+    let mut name = Vec::new();
+    let mut end = None;
+    let mut jumps = 0;
+    loop {
+        let len = *msg.get(pos)? as usize;
+        if len == 0 {
+            name.push(0);
+            return Some((name, end.unwrap_or(pos + 1)));
+        }
+        if len & 0xc0 == 0xc0 {
+            let pointer = ((len & 0x3f) << 8) | *msg.get(pos + 1)? as usize;
+            end.get_or_insert(pos + 2);
+            jumps += 1;
+            if jumps > 16 {
+                return None;
+            }
+            pos = pointer;
+            continue;
+        }
+        if len > 63 {
+            return None;
+        }
+        name.extend_from_slice(msg.get(pos..pos + 1 + len)?);
+        if name.len() > 255 {
+            return None;
+        }
+        pos += 1 + len;
+    }
+}
+
+/// Answer section of a response.
+fn answers(msg: &[u8]) -> Option<Vec<Record>> {
+    if msg.len() < HEADER_LEN {
+        return None;
+    }
+    let qdcount = u16::from_be_bytes([msg[4], msg[5]]);
+    let ancount = u16::from_be_bytes([msg[6], msg[7]]);
+
+    let mut pos = HEADER_LEN;
+    for _ in 0..qdcount {
+        pos = read_name(msg, pos)?.1 + 4;
+    }
+
+    let mut records = Vec::with_capacity(ancount as usize);
+    for _ in 0..ancount {
+        let (name, end) = read_name(msg, pos)?;
+        let fixed = msg.get(end..end + 10)?;
+        let rdlength = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        let rdata = end + 10..end + 10 + rdlength;
+        msg.get(rdata.clone())?;
+        records.push(Record {
+            name,
+            rtype: u16::from_be_bytes([fixed[0], fixed[1]]),
+            rclass: u16::from_be_bytes([fixed[2], fixed[3]]),
+            ttl: u32::from_be_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]),
+            rdata: rdata.clone(),
+        });
+        pos = rdata.end;
+    }
+    Some(records)
+}
+
+/// Build the DNS64 answer to `query` from the A response: CNAMEs are kept,
+/// A records become AAAA records under `prefix`. `None` when no address
+/// could be synthesized.
+fn synthesize(
+    query: &[u8],
+    question: &Question,
+    a_response: &[u8],
+    prefix: Ipv6Addr,
+) -> Option<Vec<u8>> {
+    if a_response.len() < HEADER_LEN || rcode(a_response) != 0 {
+        return None;
+    }
+
+    let mut body = Vec::new();
+    let mut count: u16 = 0;
+    let mut synthesized = false;
+    for rr in answers(a_response)? {
+        let (rtype, rdata) = match rr.rtype {
+            TYPE_A if rr.rclass == CLASS_IN && rr.rdata.len() == 4 => {
+                let v4 = Ipv4Addr::from(<[u8; 4]>::try_from(&a_response[rr.rdata]).ok()?);
+                if !nat64::synthesizable(prefix, v4) {
+                    continue;
+                }
+                synthesized = true;
+                (TYPE_AAAA, nat64::embed(prefix, v4).octets().to_vec())
+            }
+            TYPE_CNAME => (TYPE_CNAME, read_name(a_response, rr.rdata.start)?.0),
+            _ => continue,
+        };
+        body.extend_from_slice(&rr.name);
+        body.extend_from_slice(&rtype.to_be_bytes());
+        body.extend_from_slice(&rr.rclass.to_be_bytes());
+        body.extend_from_slice(&rr.ttl.to_be_bytes());
+        body.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        body.extend_from_slice(&rdata);
+        count += 1;
+    }
+    if !synthesized {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(question.end + body.len());
+    out.extend_from_slice(&query[..2]);
+    out.extend_from_slice(&a_response[2..4]);
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&count.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&query[HEADER_LEN..question.end]);
+    out.extend_from_slice(&body);
+    Some(out)
+}
+
+/// Forward one query upstream and wait for its response.
+async fn relay(query: &[u8], upstream: SocketAddr) -> Option<Vec<u8>> {
+    let bind: SocketAddr = if upstream.is_ipv6() {
+        "[::]:0".parse().ok()?
+    } else {
+        "0.0.0.0:0".parse().ok()?
+    };
+    let socket = UdpSocket::bind(bind).await.ok()?;
+    socket.connect(upstream).await.ok()?;
+    socket.send(query).await.ok()?;
+
+    let mut buf = vec![0u8; MAX_MESSAGE];
+    match tokio::time::timeout(UPSTREAM_TIMEOUT, socket.recv(&mut buf)).await {
+        Ok(Ok(len)) if len >= 2 && buf[..2] == query[..2] => {
+            buf.truncate(len);
+            Some(buf)
+        }
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => {
+            debug!(error = %e, upstream = %upstream, "Upstream DNS error");
+            None
+        }
+        Err(_) => {
+            debug!(upstream = %upstream, "Upstream DNS timeout");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut msg = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            msg.push(label.len() as u8);
+            msg.extend_from_slice(label.as_bytes());
+        }
+        msg.push(0);
+        msg.extend_from_slice(&qtype.to_be_bytes());
+        msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+        msg
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let msg = query("API.Billing.internal", TYPE_AAAA);
+        let question = parse_query(&msg).unwrap();
+        assert_eq!(question.name, "api.billing.internal");
+        assert_eq!(question.qtype, TYPE_AAAA);
+        assert_eq!(question.end, msg.len());
+
+        // Responses and truncated messages are not queries.
+        let mut response = msg.clone();
+        response[2] |= 0x80;
+        assert!(parse_query(&response).is_none());
+        assert!(parse_query(&msg[..msg.len() - 2]).is_none());
+    }
+
+    #[test]
+    fn test_is_internal() {
+        assert!(is_internal("api.billing.internal", "internal"));
+        assert!(!is_internal("api.example.com", "internal"));
+        assert!(!is_internal("api.notinternal", "internal"));
+    }
+
+    #[test]
+    fn test_internal_answer_aaaa() {
+        let msg = query("api.billing.internal", TYPE_AAAA);
+        let question = parse_query(&msg).unwrap();
+        let targets: Vec<Ipv6Addr> = vec!["fd00::10".parse().unwrap(), "fd00::11".parse().unwrap()];
+
+        let answer = internal_answer(&msg, &question, &targets);
+        assert_eq!(&answer[..2], &[0x12, 0x34]);
+        let flags = u16::from_be_bytes([answer[2], answer[3]]);
+        assert_eq!(flags & 0x8000, 0x8000);
+        assert_eq!(flags & 0x0400, 0x0400);
+        assert_eq!(flags & 0x000f, 0);
+        assert_eq!(u16::from_be_bytes([answer[6], answer[7]]), 2);
+        assert_eq!(answer.len(), msg.len() + 2 * 28);
+        assert_eq!(&answer[answer.len() - 16..], &targets[1].octets());
+    }
+
+    #[test]
+    fn test_internal_answer_other_types_are_nodata() {
+        let msg = query("api.billing.internal", 1);
+        let question = parse_query(&msg).unwrap();
+        let answer = internal_answer(&msg, &question, &["fd00::10".parse().unwrap()]);
+        assert_eq!(u16::from_be_bytes([answer[6], answer[7]]), 0);
+        assert_eq!(answer.len(), msg.len());
+    }
+
+    #[test]
+    fn test_servfail() {
+        let msg = query("example.com", TYPE_AAAA);
+        let response = servfail(&msg).unwrap();
+        assert_eq!(response.len(), HEADER_LEN);
+        let flags = u16::from_be_bytes([response[2], response[3]]);
+        assert_eq!(flags & 0x000f, 2);
+        assert_eq!(flags & 0x0100, 0x0100);
+    }
+
+    /// Response to `query` with the given compressed answer records.
+    fn response(query: &[u8], rcode: u8, answers: &[Vec<u8>]) -> Vec<u8> {
+        let mut msg = query.to_vec();
+        msg[2] |= 0x80;
+        msg[3] = 0x80 | rcode;
+        msg[6..8].copy_from_slice(&(answers.len() as u16).to_be_bytes());
+        for answer in answers {
+            msg.extend_from_slice(answer);
+        }
+        msg
+    }
+
+    fn record(name: &[u8], rtype: u16, ttl: u32, rdata: &[u8]) -> Vec<u8> {
+        let mut rr = name.to_vec();
+        rr.extend_from_slice(&rtype.to_be_bytes());
+        rr.extend_from_slice(&CLASS_IN.to_be_bytes());
+        rr.extend_from_slice(&ttl.to_be_bytes());
+        rr.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        rr.extend_from_slice(rdata);
+        rr
+    }
+
+    #[test]
+    fn test_needs_synthesis() {
+        let aaaa = query("v4only.example.com", TYPE_AAAA);
+        assert!(needs_synthesis(&response(&aaaa, 0, &[])));
+        // NXDOMAIN is passed through.
+        assert!(!needs_synthesis(&response(&aaaa, 3, &[])));
+
+        let v6 = record(&[0xc0, 0x0c], TYPE_AAAA, 60, &[0x20; 16]);
+        assert!(!needs_synthesis(&response(&aaaa, 0, &[v6])));
+    }
+
+    #[test]
+    fn test_synthesize_follows_cnames() {
+        let aaaa = query("www.example.com", TYPE_AAAA);
+        let question = parse_query(&aaaa).unwrap();
+        let mut a_query = aaaa.clone();
+        a_query[question.end - 4..question.end - 2].copy_from_slice(&TYPE_A.to_be_bytes());
+
+        // www.example.com CNAME cdn.example.com; cdn.example.com A 93.184.216.34
+        let cname_offset = aaaa.len() as u16 + 12;
+        let cname = record(&[0xc0, 0x0c], TYPE_CNAME, 300, b"\x03cdn\xc0\x10");
+        let pointer = (0xc000 | cname_offset).to_be_bytes();
+        let a = record(&pointer, TYPE_A, 60, &[93, 184, 216, 34]);
+        let a_response = response(&a_query, 0, &[cname, a]);
+
+        let answer = synthesize(&aaaa, &question, &a_response, nat64::WELL_KNOWN_PREFIX).unwrap();
+        assert_eq!(&answer[..2], &[0x12, 0x34]);
+        // The question is the original AAAA one.
+        assert_eq!(&answer[HEADER_LEN..question.end], &aaaa[HEADER_LEN..]);
+
+        let records = answers(&answer).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].rtype, TYPE_CNAME);
+        assert_eq!(records[1].rtype, TYPE_AAAA);
+        assert_eq!(records[1].ttl, 60);
+        assert_eq!(records[1].name, b"\x03cdn\x07example\x03com\x00");
+        assert_eq!(
+            &answer[records[1].rdata.clone()],
+            &"64:ff9b::5db8:d822".parse::<Ipv6Addr>().unwrap().octets()
+        );
+    }
+
+    #[test]
+    fn test_synthesize_skips_private_addresses() {
+        let aaaa = query("db.example.com", TYPE_AAAA);
+        let question = parse_query(&aaaa).unwrap();
+        let a = record(&[0xc0, 0x0c], TYPE_A, 60, &[10, 0, 0, 5]);
+        let a_response = response(&aaaa, 0, &[a]);
+
+        assert!(synthesize(&aaaa, &question, &a_response, nat64::WELL_KNOWN_PREFIX).is_none());
+        let local_prefix: Ipv6Addr = "2001:db8:64::".parse().unwrap();
+        assert!(synthesize(&aaaa, &question, &a_response, local_prefix).is_some());
+    }
+
+    #[test]
+    fn test_resolv_conf_nameserver() {
+        let conf =
+            "# generated\nsearch example.com\nnameserver 2001:db8::53\nnameserver 10.0.0.2\n";
+        assert_eq!(
+            resolv_conf_nameserver(conf),
+            Some("[2001:db8::53]:53".parse().unwrap())
+        );
+        assert_eq!(
+            parse_upstream("10.0.0.2:5353"),
+            Some("10.0.0.2:5353".parse().unwrap())
+        );
+    }
+}
@@ -8,9 +8,15 @@
 //! - IPv6 link-local gateway on host side (fe80::1)
 //! - Proxy NDP or routing for instance overlay IPv6
 //! - MTU matching overlay (1420 default)
+//! - Node-local DNS on the overlay for internal route names (see [`dns`])
+//! - NAT64 and DNS64 for IPv4-only upstreams (see [`nat64`])
 
 #![allow(dead_code)]
 
+pub mod dns;
+pub mod nat64;
 mod tap;
 
-pub use tap::{create_tap, TapConfig, TapDevice, TapError};
+pub use dns::{DnsConfig, DnsServer};
+pub use nat64::{Nat64Config, Nat64Translator};
+pub use tap::{adopt_tap, create_tap, remove_tap, TapConfig, TapDevice, TapError};
@@ -0,0 +1,210 @@
+//! Node-local NAT64 for IPv4-only upstreams.
+//!
+//! The overlay is IPv6-only. With a NAT64 prefix configured, the node-local
+//! DNS responder synthesizes AAAA records for names that only have A records
+//! (DNS64, see [`super::dns`]), and guests reach those addresses through
+//! their default route to the node, where a stateful NAT64 translator maps
+//! them onto the node's IPv4 addresses. Workloads need no configuration.
+//!
+//! Configuration:
+//! - `GHOST_NAT64_PREFIX`: /96 translation prefix (e.g. `64:ff9b::/96`);
+//!   NAT64 and DNS64 are off without it
+//! - `GHOST_NAT64_TRANSLATOR`: `jool` (default) runs a Jool netfilter
+//!   instance on the node; `external` only does DNS64, for networks whose
+//!   router already translates the prefix
+//! - `GHOST_NAT64_POOL4`: IPv4 addresses (`addr` or `addr/prefix`) to
+//!   translate to; default: the node's own addresses
+//!
+//! See: docs/specs/networking/egress-nat64.md
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use tracing::info;
+
+/// RFC 6052 well-known prefix `64:ff9b::/96`.
+pub const WELL_KNOWN_PREFIX: Ipv6Addr = Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0);
+
+/// Name of the Jool instance managed by the agent.
+const JOOL_INSTANCE: &str = "plfm";
+
+/// Who translates packets sent to the NAT64 prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nat64Translator {
+    /// A Jool instance on this node.
+    Jool,
+    /// Something upstream of the node; the agent only does DNS64.
+    External,
+}
+
+/// Node-local NAT64 configuration.
+#[derive(Debug, Clone)]
+pub struct Nat64Config {
+    /// /96 prefix the IPv4 address is embedded in.
+    pub prefix: Ipv6Addr,
+    pub translator: Nat64Translator,
+    pub pool4: Option<String>,
+}
+
+impl Nat64Config {
+    /// Load from the environment; `None` when no prefix is configured.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(prefix) = prefix_from_env()? else {
+            return Ok(None);
+        };
+
+        let translator = match std::env::var("GHOST_NAT64_TRANSLATOR")
+            .unwrap_or_default()
+            .trim()
+        {
+            "" | "jool" => Nat64Translator::Jool,
+            "external" => Nat64Translator::External,
+            other => anyhow::bail!("GHOST_NAT64_TRANSLATOR must be jool or external, got {other}"),
+        };
+
+        let pool4 = std::env::var("GHOST_NAT64_POOL4")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        Ok(Some(Self {
+            prefix,
+            translator,
+            pool4,
+        }))
+    }
+
+    /// Start the translator if the agent manages it. Idempotent, so an
+    /// agent restart keeps existing translation state.
+    pub fn setup(&self) -> Result<()> {
+        if self.translator == Nat64Translator::External {
+            info!(prefix = %format_prefix(self.prefix), "NAT64 handled upstream; DNS64 only");
+            return Ok(());
+        }
+
+        if run_jool(&["-i", JOOL_INSTANCE, "global", "display"]).is_ok() {
+            info!(instance = JOOL_INSTANCE, "Reusing existing NAT64 instance");
+            return Ok(());
+        }
+
+        run("modprobe", &["jool"]).context("failed to load the jool module")?;
+        let pool6 = format_prefix(self.prefix);
+        run_jool(&[
+            "instance",
+            "add",
+            JOOL_INSTANCE,
+            "--netfilter",
+            "--pool6",
+            &pool6,
+        ])?;
+        if let Some(pool4) = &self.pool4 {
+            for protocol in ["--tcp", "--udp", "--icmp"] {
+                run_jool(&["-i", JOOL_INSTANCE, "pool4", "add", protocol, pool4])?;
+            }
+        }
+
+        info!(
+            instance = JOOL_INSTANCE,
+            pool6 = %pool6,
+            pool4 = self.pool4.as_deref().unwrap_or("node addresses"),
+            "NAT64 started"
+        );
+        Ok(())
+    }
+}
+
+/// `GHOST_NAT64_PREFIX`, shared with the DNS64 responder.
+pub fn prefix_from_env() -> Result<Option<Ipv6Addr>> {
+    match std::env::var("GHOST_NAT64_PREFIX") {
+        Ok(v) if !v.trim().is_empty() => parse_prefix(v.trim())
+            .map(Some)
+            .with_context(|| format!("Invalid GHOST_NAT64_PREFIX: {v}")),
+        _ => Ok(None),
+    }
+}
+
+/// Parse a /96 prefix; its last 32 bits must be zero.
+fn parse_prefix(s: &str) -> Result<Ipv6Addr> {
+    let (addr, len) = s.split_once('/').unwrap_or((s, "96"));
+    anyhow::ensure!(len == "96", "only /96 NAT64 prefixes are supported");
+    let addr: Ipv6Addr = addr.parse()?;
+    anyhow::ensure!(
+        addr.segments()[6..] == [0, 0],
+        "host bits of the prefix must be zero"
+    );
+    Ok(addr)
+}
+
+fn format_prefix(prefix: Ipv6Addr) -> String {
+    format!("{prefix}/96")
+}
+
+/// Address of `v4` under the NAT64 prefix (RFC 6052, /96).
+pub fn embed(prefix: Ipv6Addr, v4: Ipv4Addr) -> Ipv6Addr {
+    let mut octets = prefix.octets();
+    octets[12..].copy_from_slice(&v4.octets());
+    Ipv6Addr::from(octets)
+}
+
+/// Whether DNS64 may synthesize an address for `v4`. Addresses that are
+/// never reachable through the translator are skipped, as are private
+/// addresses under the well-known prefix (RFC 6052 section 3.1).
+pub fn synthesizable(prefix: Ipv6Addr, v4: Ipv4Addr) -> bool {
+    if v4.is_unspecified() || v4.is_loopback() || v4.is_link_local() || v4.is_broadcast() {
+        return false;
+    }
+    !(prefix == WELL_KNOWN_PREFIX && v4.is_private())
+}
+
+fn run_jool(args: &[&str]) -> Result<()> {
+    run("jool", args)
+}
+
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to execute {program}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{program} {} failed: {}", args.join(" "), stderr.trim());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prefix() {
+        assert_eq!(parse_prefix("64:ff9b::/96").unwrap(), WELL_KNOWN_PREFIX);
+        assert_eq!(
+            parse_prefix("2001:db8:64::").unwrap(),
+            "2001:db8:64::".parse::<Ipv6Addr>().unwrap()
+        );
+        assert!(parse_prefix("64:ff9b::/64").is_err());
+        assert!(parse_prefix("64:ff9b::1/96").is_err());
+    }
+
+    #[test]
+    fn test_embed() {
+        let addr = embed(WELL_KNOWN_PREFIX, Ipv4Addr::new(192, 0, 2, 33));
+        assert_eq!(addr, "64:ff9b::c000:221".parse::<Ipv6Addr>().unwrap());
+    }
+
+    #[test]
+    fn test_synthesizable() {
+        let public = Ipv4Addr::new(93, 184, 216, 34);
+        let private = Ipv4Addr::new(10, 0, 0, 1);
+        let local_prefix: Ipv6Addr = "2001:db8:64::".parse().unwrap();
+
+        assert!(synthesizable(WELL_KNOWN_PREFIX, public));
+        assert!(!synthesizable(WELL_KNOWN_PREFIX, private));
+        assert!(synthesizable(local_prefix, private));
+        assert!(!synthesizable(local_prefix, Ipv4Addr::LOCALHOST));
+    }
+}
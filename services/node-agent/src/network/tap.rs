@@ -222,6 +222,21 @@ pub fn create_tap(config: &TapConfig) -> Result<TapDevice, TapError> {
     })
 }
 
+/// Take ownership of an existing TAP device (left behind by a previous agent
+/// process for a VM being re-adopted). Returns `None` if it does not exist.
+pub fn adopt_tap(config: &TapConfig) -> Option<TapDevice> {
+    let tap_name = config.tap_name();
+    if !tap_exists(&tap_name) {
+        return None;
+    }
+    debug!(tap = %tap_name, instance_id = %config.instance_id, "Adopted TAP device");
+    Some(TapDevice {
+        name: tap_name,
+        instance_id: config.instance_id.clone(),
+        overlay_ipv6: config.overlay_ipv6.clone(),
+    })
+}
+
 /// Delete a TAP device and clean up routes.
 fn delete_tap(tap_name: &str, overlay_ipv6: &str) -> Result<(), TapError> {
     info!(tap = %tap_name, "Deleting TAP device");
@@ -247,6 +262,12 @@ fn delete_tap(tap_name: &str, overlay_ipv6: &str) -> Result<(), TapError> {
     Ok(())
 }
 
+/// Delete a TAP device by name (routes through it go with it).
+pub fn remove_tap(tap_name: &str) -> Result<(), TapError> {
+    info!(tap = %tap_name, "Deleting TAP device");
+    run_ip(&["link", "delete", tap_name]).map_err(|e| TapError::DeleteFailed(e.to_string()))
+}
+
 /// Run an `ip` command and return result.
 fn run_ip(args: &[&str]) -> Result<()> {
     let output = Command::new("ip")
@@ -278,7 +299,6 @@ fn enable_ipv6_forwarding(iface: &str) -> Result<()> {
 }
 
 /// Check if a TAP device exists.
-#[allow(dead_code)]
 pub fn tap_exists(tap_name: &str) -> bool {
     std::path::Path::new(&format!("/sys/class/net/{}", tap_name)).exists()
 }
@@ -126,6 +126,7 @@ impl Reconciler {
         debug!("Checking instance health");
         self.instance_manager.update_from_boot_status().await;
         self.instance_manager.check_health().await;
+        self.instance_manager.renew_identity_tokens().await;
         self.report_status_transitions().await;
     }
 
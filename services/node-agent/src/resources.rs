@@ -1,3 +1,5 @@
+use std::path::Path;
+
 #[derive(Debug, Clone)]
 pub struct SystemResources {
     pub cpu_cores: i32,
@@ -18,6 +20,52 @@ impl SystemResources {
     }
 }
 
+/// Instance scratch disk usage on this node.
+#[derive(Debug, Clone, Default)]
+pub struct EphemeralDiskUsage {
+    /// Bytes allocated on the host by instance scratch disks.
+    pub used_bytes: i64,
+    /// Free bytes on the filesystem holding the scratch disks.
+    pub available_bytes: Option<i64>,
+}
+
+impl EphemeralDiskUsage {
+    /// Measure the scratch disks under `<data_dir>/instances/*/scratch.ext4`.
+    ///
+    /// Scratch disks are sparse, so usage counts allocated blocks rather
+    /// than the provisioned size.
+    pub fn measure(data_dir: &Path) -> Self {
+        let used_bytes = std::fs::read_dir(data_dir.join("instances"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| std::fs::metadata(entry.path().join("scratch.ext4")).ok())
+                    .map(|meta| allocated_bytes(&meta))
+                    .sum()
+            })
+            .unwrap_or(0);
+        let available_bytes = crate::volume::available_bytes(data_dir)
+            .ok()
+            .and_then(|bytes| i64::try_from(bytes).ok());
+
+        Self {
+            used_bytes,
+            available_bytes,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn allocated_bytes(meta: &std::fs::Metadata) -> i64 {
+    use std::os::unix::fs::MetadataExt;
+    i64::try_from(meta.blocks().saturating_mul(512)).unwrap_or(i64::MAX)
+}
+
+#[cfg(not(unix))]
+fn allocated_bytes(meta: &std::fs::Metadata) -> i64 {
+    i64::try_from(meta.len()).unwrap_or(i64::MAX)
+}
+
 fn get_cpu_count() -> i32 {
     #[cfg(unix)]
     {
@@ -121,6 +169,30 @@ mod tests {
         assert!(resources.available_memory_bytes <= resources.total_memory_bytes);
     }
 
+    #[test]
+    fn test_measure_ephemeral_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = EphemeralDiskUsage::measure(dir.path());
+        assert_eq!(empty.used_bytes, 0);
+
+        let instance_dir = dir.path().join("instances").join("inst_1");
+        std::fs::create_dir_all(&instance_dir).unwrap();
+        let scratch = instance_dir.join("scratch.ext4");
+        std::fs::write(&scratch, vec![1u8; 64 * 1024]).unwrap();
+        // Provisioned at 1 GiB, but only the first 64 KiB is allocated.
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&scratch)
+            .unwrap()
+            .set_len(1 << 30)
+            .unwrap();
+
+        let usage = EphemeralDiskUsage::measure(dir.path());
+        assert!(usage.used_bytes >= 64 * 1024);
+        assert!(usage.used_bytes < 1 << 30);
+        assert!(usage.available_bytes.is_some_and(|bytes| bytes > 0));
+    }
+
     #[test]
     fn test_get_cpu_count() {
         let count = get_cpu_count();
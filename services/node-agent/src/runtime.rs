@@ -3,21 +3,24 @@
 //! The runtime interface abstracts VM lifecycle operations:
 //! - Starting/stopping Firecracker microVMs
 //! - Health checks
+//! - Growing, restoring and snapshotting volumes
 //!
 //! A mock implementation is provided for testing and development.
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use plfm_id::BootId;
 use tracing::{debug, info};
 
-use crate::client::InstancePlan;
+use crate::client::{FailureReason, InstancePlan};
+use crate::volume::{RestoreProgress, VolumeResizeError, VolumeRestoreError, VolumeSnapshotError};
 
 /// Handle to a running VM.
 #[derive(Debug, Clone)]
 pub struct VmHandle {
-    /// Boot ID (unique per boot).
+    /// Boot ID (unique per boot, see [`new_boot_id`]).
     pub boot_id: String,
 
     /// Instance ID.
@@ -27,6 +30,24 @@ pub struct VmHandle {
     pub guest_cid: u32,
 }
 
+/// A VM recorded by a previous agent process.
+#[derive(Debug, Clone)]
+pub struct RecoveredVm {
+    pub instance_id: String,
+    pub boot_id: String,
+    pub guest_cid: u32,
+}
+
+/// Generate the ID of a new VM boot.
+///
+/// Boot IDs are ULID-based (`boot_<ulid>`), so they stay unique across agent
+/// restarts and a later boot of an instance sorts after an earlier one. The
+/// control plane relies on that ordering to reject status reports from
+/// superseded boots.
+pub fn new_boot_id() -> String {
+    BootId::new().to_string()
+}
+
 /// VM runtime interface.
 #[async_trait]
 pub trait Runtime: Send + Sync {
@@ -38,13 +59,65 @@ pub trait Runtime: Send + Sync {
 
     /// Check if a VM is healthy.
     async fn check_vm_health(&self, handle: &VmHandle) -> Result<bool>;
+
+    /// Take over a VM started by a previous agent process. Returns `None` if
+    /// it is no longer running (or cannot be adopted), in which case the
+    /// caller boots a fresh one.
+    async fn adopt_vm(&self, plan: &InstancePlan, vm: &RecoveredVm) -> Result<Option<VmHandle>> {
+        let _ = (plan, vm);
+        Ok(None)
+    }
+
+    /// Failure class suggested by the VM's console output (e.g. a kernel
+    /// panic), used to classify boots that never report ready.
+    fn boot_failure_hint(&self, handle: &VmHandle) -> Option<FailureReason> {
+        let _ = handle;
+        None
+    }
+
+    /// Grow the backing storage of a volume mounted by `plan` to
+    /// `size_bytes`; if the VM for `plan` is running it is told about the new
+    /// size. Returns `false` if the volume was already that large.
+    async fn resize_volume(
+        &self,
+        plan: &InstancePlan,
+        volume_id: &str,
+        size_bytes: u64,
+    ) -> Result<bool, VolumeResizeError> {
+        let _ = (plan, volume_id, size_bytes);
+        Ok(false)
+    }
+
+    /// Overwrite the backing storage of an unmounted volume with the image of
+    /// `snapshot_id`, keeping it at least `size_bytes` large. Bytes written
+    /// are counted in `progress`.
+    async fn restore_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        size_bytes: u64,
+        progress: Arc<RestoreProgress>,
+    ) -> Result<(), VolumeRestoreError> {
+        let _ = (volume_id, snapshot_id, size_bytes, progress);
+        Ok(())
+    }
+
+    /// Take snapshot `snapshot_id` of a volume on this node; `in_use` is set
+    /// when an instance in the plan mounts it. Returns the bytes held by the
+    /// snapshot.
+    async fn snapshot_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        in_use: bool,
+    ) -> Result<u64, VolumeSnapshotError> {
+        let _ = (volume_id, snapshot_id, in_use);
+        Ok(0)
+    }
 }
 
 /// Mock runtime for testing and development.
 pub struct MockRuntime {
-    /// Counter for generating boot IDs.
-    boot_counter: AtomicU64,
-
     /// Whether VMs should "fail" to start.
     fail_starts: bool,
 }
@@ -52,25 +125,13 @@ pub struct MockRuntime {
 impl MockRuntime {
     /// Create a new mock runtime.
     pub fn new() -> Self {
-        Self {
-            boot_counter: AtomicU64::new(0),
-            fail_starts: false,
-        }
+        Self { fail_starts: false }
     }
 
     /// Create a mock runtime that fails all starts.
     #[allow(dead_code)]
     pub fn failing() -> Self {
-        Self {
-            boot_counter: AtomicU64::new(0),
-            fail_starts: true,
-        }
-    }
-
-    /// Generate a new boot ID.
-    fn next_boot_id(&self) -> String {
-        let counter = self.boot_counter.fetch_add(1, Ordering::SeqCst);
-        format!("boot_{:016x}", counter)
+        Self { fail_starts: true }
     }
 }
 
@@ -103,7 +164,7 @@ impl Runtime for MockRuntime {
         // Simulate some startup delay
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
-        let boot_id = self.next_boot_id();
+        let boot_id = new_boot_id();
         debug!(
             instance_id = %plan.instance_id,
             boot_id = %boot_id,
@@ -145,6 +206,21 @@ impl Runtime for MockRuntime {
         // Mock always returns healthy
         Ok(true)
     }
+
+    async fn adopt_vm(&self, plan: &InstancePlan, vm: &RecoveredVm) -> Result<Option<VmHandle>> {
+        info!(
+            instance_id = %plan.instance_id,
+            boot_id = %vm.boot_id,
+            "[MOCK] Adopting VM"
+        );
+
+        // Mock VMs never die, so every recorded VM is adoptable.
+        Ok(Some(VmHandle {
+            boot_id: vm.boot_id.clone(),
+            instance_id: vm.instance_id.clone(),
+            guest_cid: vm.guest_cid,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +280,14 @@ mod tests {
         assert!(handle.boot_id.starts_with("boot_"));
     }
 
+    #[test]
+    fn test_new_boot_id() {
+        let first = new_boot_id();
+        let second = new_boot_id();
+        assert_ne!(first, second);
+        assert!(first.parse::<BootId>().is_ok());
+    }
+
     #[tokio::test]
     async fn test_mock_runtime_stop() {
         let runtime = MockRuntime::new();
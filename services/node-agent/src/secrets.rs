@@ -0,0 +1,479 @@
+//! Secret material on the host: in-memory payloads and the encrypted
+//! on-disk cache.
+//!
+//! Plaintext never touches the host filesystem. It is held in
+//! [`SecretPayload`] buffers, which are zeroed on drop and redacted from
+//! `Debug`, and streamed straight into the guest over vsock.
+//!
+//! Secret versions are immutable, so material fetched once for a version can
+//! be reused for every later instance start that pins the same `version_id`,
+//! including while the control plane is briefly unreachable. Entries are
+//! encrypted with AES-256-GCM under a node-local key (the version ID is bound
+//! as associated data so files cannot be swapped), and the plaintext is
+//! checked against the version's `data_hash` on every read.
+//!
+//! Versions no longer referenced by the desired plan are superseded and
+//! evicted.
+//!
+//! Reference: docs/specs/secrets/delivery.md
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+use zeroize::Zeroizing;
+
+/// File header identifying the cache entry format.
+const ENTRY_MAGIC: &[u8; 8] = b"PLFMSC01";
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const ENTRY_EXTENSION: &str = "sec";
+
+#[derive(Debug, Error)]
+pub enum SecretCacheError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid version ID: {0}")]
+    InvalidVersionId(String),
+
+    #[error("invalid cache key: {0}")]
+    InvalidKey(String),
+
+    #[error("cache entry for {0} is corrupt")]
+    Corrupt(String),
+
+    #[error("secret material hash mismatch for {version_id}: expected {expected}, got {actual}")]
+    HashMismatch {
+        version_id: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Decrypted secret material held in memory only.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretPayload(Zeroizing<String>);
+
+impl SecretPayload {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretPayload {
+    fn from(data: String) -> Self {
+        Self(Zeroizing::new(data))
+    }
+}
+
+impl fmt::Debug for SecretPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretPayload([REDACTED; {} bytes])", self.len())
+    }
+}
+
+impl Serialize for SecretPayload {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Configuration for the secret cache.
+#[derive(Debug, Clone)]
+pub struct SecretCacheConfig {
+    /// Directory holding encrypted entries.
+    pub dir: PathBuf,
+    /// 32-byte cache key, created on first use. Keep it off the disk that
+    /// holds `dir` (e.g. on tmpfs) to make a copy of the cache useless.
+    pub key_path: PathBuf,
+}
+
+impl SecretCacheConfig {
+    /// Defaults under the agent data directory; `PLFM_SECRET_CACHE_KEY_FILE`
+    /// overrides the key location.
+    pub fn from_data_dir(data_dir: &Path) -> Self {
+        let dir = data_dir.join("secrets");
+        let key_path = std::env::var("PLFM_SECRET_CACHE_KEY_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| dir.join("cache.key"));
+        Self { dir, key_path }
+    }
+}
+
+/// Decrypted secret material for one version.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedSecret {
+    pub version_id: String,
+    pub format: String,
+    pub data_hash: String,
+    pub data: String,
+}
+
+impl fmt::Debug for CachedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedSecret")
+            .field("version_id", &self.version_id)
+            .field("format", &self.format)
+            .field("data_hash", &self.data_hash)
+            .field("data", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl CachedSecret {
+    /// Check `data` against `data_hash` (`sha256:<hex>` or bare hex).
+    pub fn verify(&self) -> Result<(), SecretCacheError> {
+        let actual = hex::encode(Sha256::digest(self.data.as_bytes()));
+        let expected = self
+            .data_hash
+            .strip_prefix("sha256:")
+            .unwrap_or(&self.data_hash);
+        if expected.eq_ignore_ascii_case(&actual) {
+            Ok(())
+        } else {
+            Err(SecretCacheError::HashMismatch {
+                version_id: self.version_id.clone(),
+                expected: self.data_hash.clone(),
+                actual: format!("sha256:{actual}"),
+            })
+        }
+    }
+}
+
+/// Cache statistics.
+#[derive(Debug, Default)]
+pub struct SecretCacheStats {
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+    pub evictions: AtomicU64,
+}
+
+/// Encrypted on-disk secret cache.
+pub struct SecretCache {
+    dir: PathBuf,
+    cipher: Aes256Gcm,
+    stats: SecretCacheStats,
+}
+
+impl SecretCache {
+    /// Open the cache, creating the directory and key if needed.
+    pub fn open(config: SecretCacheConfig) -> Result<Self, SecretCacheError> {
+        create_private_dir(&config.dir)?;
+        let key = load_or_create_key(&config.key_path)?;
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|_| SecretCacheError::InvalidKey("wrong key length".to_string()))?;
+        Ok(Self {
+            dir: config.dir,
+            cipher,
+            stats: SecretCacheStats::default(),
+        })
+    }
+
+    pub fn stats(&self) -> &SecretCacheStats {
+        &self.stats
+    }
+
+    /// Read and verify a cached version. Corrupt or tampered entries are
+    /// removed and reported as a miss.
+    pub fn get(&self, version_id: &str) -> Result<Option<CachedSecret>, SecretCacheError> {
+        let path = self.entry_path(version_id)?;
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.stats.misses.fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        match self.decode(version_id, &bytes) {
+            Ok(secret) => {
+                self.stats.hits.fetch_add(1, Ordering::Relaxed);
+                debug!(version_id = %version_id, "Secret cache hit");
+                Ok(Some(secret))
+            }
+            Err(e) => {
+                warn!(version_id = %version_id, error = %e, "Discarding unusable secret cache entry");
+                let _ = fs::remove_file(&path);
+                self.stats.misses.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Verify and store a version. The write is atomic (temp file + rename).
+    pub fn put(&self, secret: &CachedSecret) -> Result<(), SecretCacheError> {
+        secret.verify()?;
+        let path = self.entry_path(&secret.version_id)?;
+
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(secret)
+                .map_err(|_| SecretCacheError::Corrupt(secret.version_id.clone()))?,
+        );
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: secret.version_id.as_bytes(),
+                },
+            )
+            .map_err(|_| SecretCacheError::Corrupt(secret.version_id.clone()))?;
+
+        let mut contents = Vec::with_capacity(ENTRY_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        contents.extend_from_slice(ENTRY_MAGIC);
+        contents.extend_from_slice(&nonce);
+        contents.extend_from_slice(&ciphertext);
+
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut file = fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .mode(0o600)
+                .open(&tmp_path)?;
+            file.write_all(&contents)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, &path)?;
+
+        debug!(version_id = %secret.version_id, "Cached secret material");
+        Ok(())
+    }
+
+    /// Remove every cached version not in `live`. Returns the number of
+    /// entries removed.
+    pub fn evict_superseded<'a>(
+        &self,
+        live: impl IntoIterator<Item = &'a str>,
+    ) -> Result<u64, SecretCacheError> {
+        let live: HashSet<&str> = live.into_iter().collect();
+        let mut evicted = 0;
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_entry = path
+                .extension()
+                .map(|e| e == ENTRY_EXTENSION || e == "tmp")
+                .unwrap_or(false);
+            if !is_entry {
+                continue;
+            }
+            let Some(version_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if live.contains(version_id) {
+                continue;
+            }
+            fs::remove_file(&path)?;
+            evicted += 1;
+        }
+
+        if evicted > 0 {
+            self.stats.evictions.fetch_add(evicted, Ordering::Relaxed);
+            info!(evicted, "Evicted superseded secret versions");
+        }
+        Ok(evicted)
+    }
+
+    fn entry_path(&self, version_id: &str) -> Result<PathBuf, SecretCacheError> {
+        let valid = !version_id.is_empty()
+            && version_id.len() <= 128
+            && version_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(SecretCacheError::InvalidVersionId(version_id.to_string()));
+        }
+        Ok(self.dir.join(format!("{version_id}.{ENTRY_EXTENSION}")))
+    }
+
+    fn decode(&self, version_id: &str, bytes: &[u8]) -> Result<CachedSecret, SecretCacheError> {
+        let corrupt = || SecretCacheError::Corrupt(version_id.to_string());
+        let rest = bytes.strip_prefix(ENTRY_MAGIC).ok_or_else(corrupt)?;
+        if rest.len() < NONCE_LEN {
+            return Err(corrupt());
+        }
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let plaintext = Zeroizing::new(
+            self.cipher
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: version_id.as_bytes(),
+                    },
+                )
+                .map_err(|_| corrupt())?,
+        );
+        let secret: CachedSecret = serde_json::from_slice(&plaintext).map_err(|_| corrupt())?;
+        if secret.version_id != version_id {
+            return Err(corrupt());
+        }
+        secret.verify()?;
+        Ok(secret)
+    }
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+}
+
+fn load_or_create_key(path: &Path) -> Result<[u8; KEY_LEN], SecretCacheError> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            if bytes.len() != KEY_LEN {
+                return Err(SecretCacheError::InvalidKey(format!(
+                    "{} must contain exactly {KEY_LEN} bytes",
+                    path.display()
+                )));
+            }
+            let mut key = [0u8; KEY_LEN];
+            key.copy_from_slice(&bytes);
+            Ok(key)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut key = [0u8; KEY_LEN];
+            rand::rng().fill_bytes(&mut key);
+            let mut file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .open(path)?;
+            file.write_all(&key)?;
+            file.sync_all()?;
+            info!(path = %path.display(), "Created secret cache key");
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(version_id: &str, data: &str) -> CachedSecret {
+        CachedSecret {
+            version_id: version_id.to_string(),
+            format: "platform_env_v1".to_string(),
+            data_hash: format!("sha256:{}", hex::encode(Sha256::digest(data.as_bytes()))),
+            data: data.to_string(),
+        }
+    }
+
+    fn open(dir: &Path) -> SecretCache {
+        SecretCache::open(SecretCacheConfig {
+            dir: dir.join("secrets"),
+            key_path: dir.join("key"),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_put_get_roundtrip_survives_reopen() {
+        let tmp = tempfile::tempdir().unwrap();
+        let entry = secret("sv_1", "DATABASE_URL=postgres://x\n");
+
+        open(tmp.path()).put(&entry).unwrap();
+        let cache = open(tmp.path());
+        assert_eq!(cache.get("sv_1").unwrap(), Some(entry));
+        assert_eq!(cache.stats().hits.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.get("sv_2").unwrap(), None);
+    }
+
+    #[test]
+    fn test_entries_are_not_plaintext() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = open(tmp.path());
+        cache.put(&secret("sv_1", "API_TOKEN=hunter2\n")).unwrap();
+
+        let raw = fs::read(tmp.path().join("secrets/sv_1.sec")).unwrap();
+        assert!(!raw.windows(7).any(|w| w == b"hunter2"));
+    }
+
+    #[test]
+    fn test_put_rejects_hash_mismatch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut entry = secret("sv_1", "A=1\n");
+        entry.data = "A=2\n".to_string();
+
+        let err = open(tmp.path()).put(&entry).unwrap_err();
+        assert!(matches!(err, SecretCacheError::HashMismatch { .. }));
+    }
+
+    #[test]
+    fn test_swapped_or_corrupt_entries_are_discarded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = open(tmp.path());
+        cache.put(&secret("sv_1", "A=1\n")).unwrap();
+
+        let dir = tmp.path().join("secrets");
+        fs::copy(dir.join("sv_1.sec"), dir.join("sv_2.sec")).unwrap();
+        assert_eq!(cache.get("sv_2").unwrap(), None);
+        assert!(!dir.join("sv_2.sec").exists());
+
+        fs::write(dir.join("sv_1.sec"), b"PLFMSC01garbage").unwrap();
+        assert_eq!(cache.get("sv_1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_evict_superseded_keeps_live_versions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = open(tmp.path());
+        cache.put(&secret("sv_old", "A=1\n")).unwrap();
+        cache.put(&secret("sv_new", "A=2\n")).unwrap();
+
+        assert_eq!(cache.evict_superseded(["sv_new"]).unwrap(), 1);
+        assert_eq!(cache.get("sv_old").unwrap(), None);
+        assert!(cache.get("sv_new").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_payload_debug_is_redacted() {
+        let payload = SecretPayload::from("API_TOKEN=hunter2\n".to_string());
+        let debug = format!("{payload:?}");
+        assert!(!debug.contains("hunter2"));
+        assert_eq!(
+            serde_json::to_string(&payload).unwrap(),
+            r#""API_TOKEN=hunter2\n""#
+        );
+    }
+
+    #[test]
+    fn test_rejects_path_like_version_ids() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = open(tmp.path()).get("../escape").unwrap_err();
+        assert!(matches!(err, SecretCacheError::InvalidVersionId(_)));
+    }
+}
@@ -0,0 +1,187 @@
+//! State store export and import for disaster recovery.
+//!
+//! An export is a JSON document holding the node cursor and every instance
+//! and boot status record. Importing it into a fresh state store lets a
+//! rebuilt agent resume from the exported cursor and re-adopt VMs that are
+//! still running (see `InstanceActor`), instead of killing every workload on
+//! the node because the database was lost or corrupted.
+//!
+//! Driven by `node-agent state export|import` and the local API's
+//! `GET /state/export`.
+
+use serde::{Deserialize, Serialize};
+
+use super::store::{BootStatusRecord, InstanceRecord, NodeState, StateStore, StateStoreError};
+
+/// Current export format version.
+pub const STATE_EXPORT_VERSION: u32 = 1;
+
+/// A portable snapshot of the state store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateExport {
+    pub version: u32,
+    /// Node the state belongs to.
+    pub node_id: String,
+    /// Unix seconds.
+    pub exported_at: i64,
+    pub node_state: NodeState,
+    pub instances: Vec<InstanceRecord>,
+    pub boot_status: Vec<BootStatusRecord>,
+}
+
+/// Result of an import.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    pub instances: usize,
+    pub boot_status: usize,
+    /// Instance records that were already in the store and got replaced.
+    pub replaced_instances: usize,
+}
+
+impl StateStore {
+    /// Snapshot the store.
+    pub fn export_state(&self, node_id: &str) -> Result<StateExport, StateStoreError> {
+        Ok(StateExport {
+            version: STATE_EXPORT_VERSION,
+            node_id: node_id.to_string(),
+            exported_at: chrono::Utc::now().timestamp(),
+            node_state: self.get_node_state()?,
+            instances: self.list_instances()?,
+            boot_status: self.list_boot_status()?,
+        })
+    }
+
+    /// Replace the store's contents with an export.
+    ///
+    /// Refuses to overwrite a store that already tracks instances, or to load
+    /// another node's export, unless `force` is set.
+    pub fn import_state(
+        &self,
+        export: &StateExport,
+        node_id: &str,
+        force: bool,
+    ) -> Result<ImportSummary, StateStoreError> {
+        if export.version != STATE_EXPORT_VERSION {
+            return Err(StateStoreError::Invalid(format!(
+                "unsupported export version {} (expected {})",
+                export.version, STATE_EXPORT_VERSION
+            )));
+        }
+        if export.node_id != node_id && !force {
+            return Err(StateStoreError::Invalid(format!(
+                "export belongs to node {}, this node is {} (use --force to import anyway)",
+                export.node_id, node_id
+            )));
+        }
+
+        self.transaction(|store| {
+            let existing = store.list_instances()?.len();
+            if existing > 0 && !force {
+                return Err(StateStoreError::Invalid(format!(
+                    "state store already tracks {existing} instances (use --force to replace them)"
+                )));
+            }
+
+            store.clear_instances()?;
+            store.set_node_state(&export.node_state)?;
+            for record in &export.instances {
+                store.upsert_instance(record)?;
+            }
+            for record in &export.boot_status {
+                store.upsert_boot_status(record)?;
+            }
+
+            Ok(ImportSummary {
+                instances: export.instances.len(),
+                boot_status: export.boot_status.len(),
+                replaced_instances: existing,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::InstancePhase;
+
+    fn populated_store() -> StateStore {
+        let store = StateStore::open_in_memory().unwrap();
+        store.set_cursor_event_id(42).unwrap();
+        store.set_plan_id(Some("plan_7")).unwrap();
+        store
+            .upsert_instance(&InstanceRecord {
+                instance_id: "inst_a".to_string(),
+                phase: InstancePhase::Running,
+                spec_revision: 7,
+                boot_id: "boot_1".to_string(),
+                socket_path: None,
+                rootdisk_digest: Some("sha256:abc".to_string()),
+                guest_cid: Some(5),
+                created_at: 100,
+                updated_at: 200,
+            })
+            .unwrap();
+        store
+            .upsert_boot_status(&BootStatusRecord {
+                instance_id: "inst_a".to_string(),
+                boot_id: "boot_1".to_string(),
+                state: "ready".to_string(),
+                reason: None,
+                detail: None,
+                exit_code: None,
+                guest_timestamp: "2026-01-01T00:00:00Z".to_string(),
+                recorded_at: 150,
+            })
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let export = populated_store().export_state("node_1").unwrap();
+        let json = serde_json::to_string(&export).unwrap();
+        let export: StateExport = serde_json::from_str(&json).unwrap();
+
+        let fresh = StateStore::open_in_memory().unwrap();
+        let summary = fresh.import_state(&export, "node_1", false).unwrap();
+        assert_eq!(summary.instances, 1);
+        assert_eq!(summary.boot_status, 1);
+        assert_eq!(summary.replaced_instances, 0);
+
+        let node = fresh.get_node_state().unwrap();
+        assert_eq!(node.cursor_event_id, 42);
+        assert_eq!(node.plan_id.as_deref(), Some("plan_7"));
+        let inst = fresh.get_instance("inst_a").unwrap().unwrap();
+        assert_eq!(inst.phase, InstancePhase::Running);
+        assert_eq!(inst.guest_cid, Some(5));
+        assert!(fresh.get_boot_status("inst_a", "boot_1").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_import_refuses_populated_store_without_force() {
+        let export = populated_store().export_state("node_1").unwrap();
+        let target = populated_store();
+        target.set_cursor_event_id(99).unwrap();
+
+        assert!(target.import_state(&export, "node_1", false).is_err());
+        // Nothing changed.
+        assert_eq!(target.get_node_state().unwrap().cursor_event_id, 99);
+
+        let summary = target.import_state(&export, "node_1", true).unwrap();
+        assert_eq!(summary.replaced_instances, 1);
+        assert_eq!(target.get_node_state().unwrap().cursor_event_id, 42);
+    }
+
+    #[test]
+    fn test_import_checks_node_and_version() {
+        let mut export = populated_store().export_state("node_1").unwrap();
+        let fresh = StateStore::open_in_memory().unwrap();
+        assert!(fresh.import_state(&export, "node_2", false).is_err());
+        assert!(fresh.import_state(&export, "node_2", true).is_ok());
+
+        export.version = STATE_EXPORT_VERSION + 1;
+        let fresh = StateStore::open_in_memory().unwrap();
+        assert!(fresh.import_state(&export, "node_1", true).is_err());
+    }
+}
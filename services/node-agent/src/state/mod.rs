@@ -5,10 +5,14 @@
 //! - Instance records (phase, spec revision, boot ID, socket paths)
 //!
 //! The state store enables the agent to recover after restarts
-//! and track which instances are running.
+//! and track which instances are running. It can be exported to a file and
+//! imported on a rebuilt node (see [`StateExport`]).
 
+mod export;
 mod store;
 
+pub use export::{ImportSummary, StateExport, STATE_EXPORT_VERSION};
+
 pub use store::{
     BootStatusRecord, InstancePhase, InstanceRecord, NodeState, StateStore, StateStoreError,
 };
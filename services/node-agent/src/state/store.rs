@@ -5,7 +5,12 @@
 
 use std::path::Path;
 
+use plfm_reconcile::{
+    PersistentRetryTracker, RetryRecord, RetryStore, DEFAULT_MAX_RETRIES, DEFAULT_RETRY_TTL,
+    DEFAULT_RETRY_WINDOW,
+};
 use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::debug;
 
@@ -23,7 +28,8 @@ pub enum StateStoreError {
 }
 
 /// Instance lifecycle phase.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InstancePhase {
     /// Instance is being created.
     Creating,
@@ -40,7 +46,7 @@ pub enum InstancePhase {
 }
 
 impl InstancePhase {
-    fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::Creating => "creating",
             Self::Starting => "starting",
@@ -65,7 +71,7 @@ impl InstancePhase {
 }
 
 /// Node-level state.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NodeState {
     pub cursor_event_id: i64,
     pub plan_id: Option<String>,
@@ -76,7 +82,7 @@ pub struct NodeState {
 }
 
 /// Instance record in the state store.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceRecord {
     /// Instance ID.
     pub instance_id: String,
@@ -90,13 +96,16 @@ pub struct InstanceRecord {
     pub socket_path: Option<String>,
     /// Root disk digest.
     pub rootdisk_digest: Option<String>,
+    /// Guest vsock CID (needed to re-adopt the VM after an agent restart).
+    #[serde(default)]
+    pub guest_cid: Option<u32>,
     /// Created timestamp (Unix seconds).
     pub created_at: i64,
     /// Updated timestamp (Unix seconds).
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BootStatusRecord {
     pub instance_id: String,
     pub boot_id: String,
@@ -175,9 +184,28 @@ impl StateStore {
             );
 
             CREATE INDEX IF NOT EXISTS idx_boot_status_state ON boot_status(state);
+
+            CREATE TABLE IF NOT EXISTS retries (
+                resource_key TEXT PRIMARY KEY,
+                count INTEGER NOT NULL,
+                first_failure_at INTEGER NOT NULL,
+                last_failure_at INTEGER NOT NULL,
+                exhausted_at INTEGER,
+                exhaustions INTEGER NOT NULL DEFAULT 0
+            );
             "#,
         )?;
 
+        // Columns added after the initial schema.
+        let has_guest_cid = self
+            .conn
+            .prepare("SELECT 1 FROM pragma_table_info('instances') WHERE name = 'guest_cid'")?
+            .exists([])?;
+        if !has_guest_cid {
+            self.conn
+                .execute_batch("ALTER TABLE instances ADD COLUMN guest_cid INTEGER")?;
+        }
+
         debug!("State store schema initialized");
         Ok(())
     }
@@ -244,7 +272,7 @@ impl StateStore {
         instance_id: &str,
     ) -> Result<Option<InstanceRecord>, StateStoreError> {
         let mut stmt = self.conn.prepare(
-            "SELECT instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at
+            "SELECT instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at, guest_cid
              FROM instances WHERE instance_id = ?1",
         )?;
 
@@ -261,6 +289,7 @@ impl StateStore {
                 rootdisk_digest: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                guest_cid: row.get(8)?,
             })
         })
         .optional()
@@ -271,15 +300,16 @@ impl StateStore {
     pub fn upsert_instance(&self, record: &InstanceRecord) -> Result<(), StateStoreError> {
         self.conn.execute(
             r#"
-            INSERT INTO instances (instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            INSERT INTO instances (instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at, guest_cid)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
             ON CONFLICT(instance_id) DO UPDATE SET
                 phase = excluded.phase,
                 spec_revision = excluded.spec_revision,
                 boot_id = excluded.boot_id,
                 socket_path = excluded.socket_path,
                 rootdisk_digest = excluded.rootdisk_digest,
-                updated_at = excluded.updated_at
+                updated_at = excluded.updated_at,
+                guest_cid = excluded.guest_cid
             "#,
             params![
                 record.instance_id,
@@ -290,6 +320,7 @@ impl StateStore {
                 record.rootdisk_digest,
                 record.created_at,
                 record.updated_at,
+                record.guest_cid,
             ],
         )?;
         Ok(())
@@ -321,7 +352,7 @@ impl StateStore {
     /// List all instances.
     pub fn list_instances(&self) -> Result<Vec<InstanceRecord>, StateStoreError> {
         let mut stmt = self.conn.prepare(
-            "SELECT instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at
+            "SELECT instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at, guest_cid
              FROM instances ORDER BY created_at",
         )?;
 
@@ -339,6 +370,7 @@ impl StateStore {
                     rootdisk_digest: row.get(5)?,
                     created_at: row.get(6)?,
                     updated_at: row.get(7)?,
+                    guest_cid: row.get(8)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -352,7 +384,7 @@ impl StateStore {
         phase: InstancePhase,
     ) -> Result<Vec<InstanceRecord>, StateStoreError> {
         let mut stmt = self.conn.prepare(
-            "SELECT instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at
+            "SELECT instance_id, phase, spec_revision, boot_id, socket_path, rootdisk_digest, created_at, updated_at, guest_cid
              FROM instances WHERE phase = ?1 ORDER BY created_at",
         )?;
 
@@ -370,6 +402,7 @@ impl StateStore {
                     rootdisk_digest: row.get(5)?,
                     created_at: row.get(6)?,
                     updated_at: row.get(7)?,
+                    guest_cid: row.get(8)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -471,6 +504,139 @@ impl StateStore {
         )?;
         Ok(())
     }
+
+    /// List all boot status records.
+    pub fn list_boot_status(&self) -> Result<Vec<BootStatusRecord>, StateStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT instance_id, boot_id, state, reason, detail, exit_code, guest_timestamp, recorded_at
+             FROM boot_status ORDER BY instance_id, recorded_at",
+        )?;
+
+        let records = stmt
+            .query_map([], |row| {
+                Ok(BootStatusRecord {
+                    instance_id: row.get(0)?,
+                    boot_id: row.get(1)?,
+                    state: row.get(2)?,
+                    reason: row.get(3)?,
+                    detail: row.get(4)?,
+                    exit_code: row.get(5)?,
+                    guest_timestamp: row.get(6)?,
+                    recorded_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// Retry budgets backed by this store: 3 failures per 10 minutes, with
+    /// exhausted resources held for an hour (also across agent restarts).
+    pub fn retries(&self) -> PersistentRetryTracker<&Self> {
+        PersistentRetryTracker::new(
+            self,
+            DEFAULT_MAX_RETRIES,
+            DEFAULT_RETRY_WINDOW,
+            DEFAULT_RETRY_TTL,
+        )
+    }
+
+    /// Run `f` in a transaction, rolling back if it fails.
+    pub(super) fn transaction<T>(
+        &self,
+        f: impl FnOnce(&Self) -> Result<T, StateStoreError>,
+    ) -> Result<T, StateStoreError> {
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        match f(self) {
+            Ok(value) => {
+                self.conn.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Delete all instance and boot status records.
+    pub(super) fn clear_instances(&self) -> Result<(), StateStoreError> {
+        self.conn
+            .execute_batch("DELETE FROM instances; DELETE FROM boot_status;")?;
+        Ok(())
+    }
+}
+
+/// Retry budgets survive agent restarts (see [`plfm_reconcile::PersistentRetryTracker`]).
+impl RetryStore for StateStore {
+    type Error = StateStoreError;
+
+    fn get_retry(&self, resource_key: &str) -> Result<Option<RetryRecord>, StateStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT resource_key, count, first_failure_at, last_failure_at, exhausted_at, exhaustions
+             FROM retries WHERE resource_key = ?1",
+        )?;
+
+        stmt.query_row(params![resource_key], retry_from_row)
+            .optional()
+            .map_err(Into::into)
+    }
+
+    fn put_retry(&self, record: &RetryRecord) -> Result<(), StateStoreError> {
+        self.conn.execute(
+            r#"
+            INSERT INTO retries (resource_key, count, first_failure_at, last_failure_at, exhausted_at, exhaustions)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(resource_key) DO UPDATE SET
+                count = excluded.count,
+                first_failure_at = excluded.first_failure_at,
+                last_failure_at = excluded.last_failure_at,
+                exhausted_at = excluded.exhausted_at,
+                exhaustions = excluded.exhaustions
+            "#,
+            params![
+                record.resource_key,
+                record.count,
+                record.first_failure_at,
+                record.last_failure_at,
+                record.exhausted_at,
+                record.exhaustions,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn delete_retry(&self, resource_key: &str) -> Result<(), StateStoreError> {
+        self.conn.execute(
+            "DELETE FROM retries WHERE resource_key = ?1",
+            params![resource_key],
+        )?;
+        Ok(())
+    }
+
+    fn list_retries(&self) -> Result<Vec<RetryRecord>, StateStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT resource_key, count, first_failure_at, last_failure_at, exhausted_at, exhaustions
+             FROM retries ORDER BY resource_key",
+        )?;
+
+        let records = stmt
+            .query_map([], retry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+}
+
+fn retry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RetryRecord> {
+    Ok(RetryRecord {
+        resource_key: row.get(0)?,
+        count: row.get(1)?,
+        first_failure_at: row.get(2)?,
+        last_failure_at: row.get(3)?,
+        exhausted_at: row.get(4)?,
+        exhaustions: row.get(5)?,
+    })
 }
 
 #[cfg(test)]
@@ -509,6 +675,7 @@ mod tests {
             boot_id: "boot-abc".to_string(),
             socket_path: Some("/run/fc.sock".to_string()),
             rootdisk_digest: Some("sha256:abc".to_string()),
+            guest_cid: Some(3),
             created_at: 1000,
             updated_at: 1000,
         };
@@ -617,4 +784,34 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[test]
+    fn test_retry_records_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node-agent.db");
+        let tracker = |store| {
+            plfm_reconcile::PersistentRetryTracker::new(
+                store,
+                1,
+                std::time::Duration::from_secs(60),
+                std::time::Duration::from_secs(600),
+            )
+        };
+
+        {
+            let store = StateStore::open(&path).unwrap();
+            let retries = tracker(&store);
+            assert!(!retries.record_failure_at("inst-123", 1000).unwrap());
+            assert!(retries.record_failure_at("inst-123", 1010).unwrap());
+        }
+
+        // Reopened (agent restart): still exhausted until the TTL passes.
+        let store = StateStore::open(&path).unwrap();
+        let retries = tracker(&store);
+        assert!(retries.is_exhausted_at("inst-123", 1100).unwrap());
+        assert_eq!(retries.stats_at(1100).unwrap().exhaustions, 1);
+        assert!(!retries.is_exhausted_at("inst-123", 1611).unwrap());
+        assert_eq!(retries.prune_at(1611).unwrap(), 1);
+        assert!(store.list_retries().unwrap().is_empty());
+    }
 }
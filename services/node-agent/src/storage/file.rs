@@ -0,0 +1,284 @@
+//! File storage backend.
+//!
+//! Layout under the agent data dir:
+//! - `volumes/<volume_id>.ext4`: volume file, or a symlink to an LVM LV
+//! - `instances/<instance_id>/scratch.ext4`: instance scratch disk
+//! - `snapshots/<snapshot_id>.ext4`: snapshot images
+//!
+//! Root disks are the image cache's root disk files, shared read-only.
+
+use std::fs;
+use std::io::{Seek, SeekFrom};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use super::StorageBackend;
+use crate::volume::{
+    self, RestoreProgress, VolumeResizeError, VolumeRestoreError, VolumeSnapshotError,
+};
+
+/// `FICLONE` ioctl: share all extents of one file with another (btrfs, XFS).
+const FICLONE: u64 = 0x4004_9409;
+
+/// Keeps volumes, scratch disks and snapshots as files under the data dir.
+pub struct FileBackend {
+    data_dir: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+        }
+    }
+
+    fn instance_dir(&self, instance_id: &str) -> PathBuf {
+        self.data_dir.join("instances").join(instance_id)
+    }
+
+    fn snapshot_path(&self, snapshot_id: &str) -> PathBuf {
+        self.data_dir
+            .join("snapshots")
+            .join(format!("{snapshot_id}.ext4"))
+    }
+}
+
+impl StorageBackend for FileBackend {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn init(&self) -> Result<()> {
+        for dir in ["volumes", "instances", "snapshots"] {
+            fs::create_dir_all(self.data_dir.join(dir))?;
+        }
+        Ok(())
+    }
+
+    fn volume_path(&self, volume_id: &str) -> PathBuf {
+        self.data_dir
+            .join("volumes")
+            .join(format!("{volume_id}.ext4"))
+    }
+
+    fn root_disk(
+        &self,
+        _instance_id: &str,
+        _image_digest: &str,
+        image_path: &Path,
+    ) -> Result<PathBuf> {
+        Ok(image_path.to_path_buf())
+    }
+
+    fn scratch_disk(&self, instance_id: &str, size_bytes: u64) -> Result<PathBuf> {
+        let path = self.instance_dir(instance_id).join("scratch.ext4");
+        if path.exists() {
+            return Ok(path);
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = fs::File::create(&path)?;
+        file.set_len(size_bytes)?;
+        drop(file);
+
+        if let Err(e) = mkfs_ext4(&path) {
+            let _ = fs::remove_file(&path);
+            return Err(e);
+        }
+
+        Ok(path)
+    }
+
+    fn release_instance(&self, _instance_id: &str) {
+        // The scratch disk goes with the instance directory.
+    }
+
+    fn grow_volume(&self, volume_id: &str, size_bytes: u64) -> Result<bool, VolumeResizeError> {
+        volume::grow(&self.volume_path(volume_id), size_bytes)
+    }
+
+    fn snapshot_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        in_use: bool,
+    ) -> Result<u64, VolumeSnapshotError> {
+        let dest = self.snapshot_path(snapshot_id);
+        if let Ok(metadata) = fs::metadata(&dest) {
+            return Ok(metadata.len());
+        }
+
+        let path = self.volume_path(volume_id);
+        let mut source = fs::File::open(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                VolumeSnapshotError::Missing(path.display().to_string())
+            }
+            _ => VolumeSnapshotError::Failed(format!("open {}: {e}", path.display())),
+        })?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                VolumeSnapshotError::Failed(format!("create {}: {e}", parent.display()))
+            })?;
+        }
+
+        let staging = dest.with_extension("ext4.tmp");
+        let result = snapshot_into(&mut source, &path, &staging, in_use).and_then(|size| {
+            fs::rename(&staging, &dest).map(|()| size).map_err(|e| {
+                VolumeSnapshotError::Failed(format!("rename {}: {e}", staging.display()))
+            })
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&staging);
+        }
+        result
+    }
+
+    fn restore_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        size_bytes: u64,
+        progress: &RestoreProgress,
+    ) -> Result<(), VolumeRestoreError> {
+        volume::restore(
+            &self.volume_path(volume_id),
+            &self.snapshot_path(snapshot_id),
+            size_bytes,
+            progress,
+        )
+    }
+}
+
+/// Write a snapshot of `source` (the volume at `path`) to `staging`.
+///
+/// A reflink is atomic, so it is safe while a VM writes to the volume. A
+/// plain copy of a volume in use would be torn, so it is only taken of
+/// volumes no instance mounts.
+fn snapshot_into(
+    source: &mut fs::File,
+    path: &Path,
+    staging: &Path,
+    in_use: bool,
+) -> Result<u64, VolumeSnapshotError> {
+    let mut dest = fs::File::create(staging)
+        .map_err(|e| VolumeSnapshotError::Failed(format!("create {}: {e}", staging.display())))?;
+
+    if reflink(source, &dest).is_ok() {
+        return dest
+            .sync_all()
+            .and_then(|()| dest.metadata())
+            .map(|metadata| metadata.len())
+            .map_err(|e| VolumeSnapshotError::Failed(format!("sync {}: {e}", staging.display())));
+    }
+
+    if in_use {
+        return Err(VolumeSnapshotError::InUse(
+            "the node's filesystem cannot reflink and the volume is mounted; \
+             detach it or use a reflink-capable filesystem or the zfs backend"
+                .to_string(),
+        ));
+    }
+
+    // Seeking sizes LVM-backed volumes too.
+    let size = source
+        .seek(SeekFrom::End(0))
+        .and_then(|size| source.seek(SeekFrom::Start(0)).map(|_| size))
+        .map_err(|e| VolumeSnapshotError::Failed(format!("size of {}: {e}", path.display())))?;
+    let available = volume::available_bytes(staging)
+        .map_err(|e| VolumeSnapshotError::Failed(format!("statvfs {}: {e}", staging.display())))?;
+    if available < size {
+        return Err(VolumeSnapshotError::InsufficientSpace(format!(
+            "need {size} bytes for the snapshot, {available} available"
+        )));
+    }
+
+    let copied = std::io::copy(source, &mut dest)
+        .and_then(|copied| dest.sync_all().map(|()| copied))
+        .map_err(|e| VolumeSnapshotError::Failed(format!("copy {}: {e}", path.display())))?;
+    Ok(copied)
+}
+
+/// Share the extents of `source` with `dest`.
+fn reflink(source: &fs::File, dest: &fs::File) -> std::io::Result<()> {
+    if unsafe { libc::ioctl(dest.as_raw_fd(), FICLONE as _, source.as_raw_fd()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Format `path` (a file or block device) as ext4.
+pub(super) fn mkfs_ext4(path: &Path) -> Result<()> {
+    let status = std::process::Command::new("mkfs.ext4")
+        .args(["-F", "-q"])
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow!("mkfs.ext4 failed: {e}"))?;
+
+    if !status.success() {
+        return Err(anyhow!("mkfs.ext4 failed"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_unmounted_volume_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FileBackend::new(dir.path());
+        backend.init().unwrap();
+        fs::write(backend.volume_path("vol_a"), vec![7u8; 8192]).unwrap();
+
+        let size = backend.snapshot_volume("vol_a", "snap_a", false).unwrap();
+        assert_eq!(size, 8192);
+        let snapshot = backend.snapshot_path("snap_a");
+        assert_eq!(fs::read(&snapshot).unwrap(), vec![7u8; 8192]);
+        assert!(!snapshot.with_extension("ext4.tmp").exists());
+
+        // Retaking it keeps the first image.
+        fs::write(backend.volume_path("vol_a"), vec![9u8; 8192]).unwrap();
+        assert_eq!(
+            backend.snapshot_volume("vol_a", "snap_a", false).unwrap(),
+            8192
+        );
+        assert_eq!(fs::read(&snapshot).unwrap(), vec![7u8; 8192]);
+    }
+
+    #[test]
+    fn snapshot_of_missing_volume_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FileBackend::new(dir.path());
+
+        let err = backend
+            .snapshot_volume("vol_a", "snap_a", false)
+            .unwrap_err();
+        assert_eq!(err.reason(), "volume_missing");
+        assert!(!backend.snapshot_path("snap_a").exists());
+    }
+
+    #[test]
+    fn restores_its_own_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FileBackend::new(dir.path());
+        backend.init().unwrap();
+        fs::write(backend.volume_path("vol_a"), vec![1u8; 4096]).unwrap();
+        backend.snapshot_volume("vol_a", "snap_a", false).unwrap();
+        fs::write(backend.volume_path("vol_a"), vec![2u8; 4096]).unwrap();
+
+        backend
+            .restore_volume("vol_a", "snap_a", 4096, &RestoreProgress::default())
+            .unwrap();
+        assert_eq!(
+            fs::read(backend.volume_path("vol_a")).unwrap(),
+            vec![1u8; 4096]
+        );
+    }
+}
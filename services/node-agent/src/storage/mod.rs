@@ -0,0 +1,163 @@
+//! Node storage backends.
+//!
+//! A backend decides where the disks of a node live: volumes, the per-instance
+//! root and scratch disks, and volume snapshots. Two backends exist:
+//!
+//! - [`FileBackend`] (`file`, the default) keeps everything as files under the
+//!   agent data dir. Instances share the image's root disk read-only and get a
+//!   freshly formatted scratch file. Snapshots are reflinked on filesystems
+//!   that support it (btrfs, XFS) and copied otherwise.
+//! - [`ZfsBackend`] (`zfs`) keeps volumes, images and instance disks as zvols
+//!   under one dataset. Root and scratch disks are copy-on-write clones, and
+//!   snapshots are native ZFS snapshots.
+//!
+//! The backend is chosen with `PLFM_STORAGE_BACKEND`; the ZFS dataset comes
+//! from `PLFM_ZFS_DATASET`.
+
+mod file;
+mod zfs;
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::volume::{RestoreProgress, VolumeResizeError, VolumeRestoreError, VolumeSnapshotError};
+
+pub use file::FileBackend;
+pub use zfs::ZfsBackend;
+
+/// Default parent dataset for the ZFS backend.
+pub const DEFAULT_ZFS_DATASET: &str = "plfm";
+
+/// Which storage backend a node uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageBackendKind {
+    #[default]
+    File,
+    Zfs,
+}
+
+impl FromStr for StorageBackendKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "file" => Ok(Self::File),
+            "zfs" => Ok(Self::Zfs),
+            other => anyhow::bail!("unknown storage backend '{other}' (expected file or zfs)"),
+        }
+    }
+}
+
+/// Storage settings for a node.
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    pub backend: StorageBackendKind,
+    /// Parent dataset for the ZFS backend (e.g. `tank/plfm`).
+    pub zfs_dataset: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackendKind::File,
+            zfs_dataset: DEFAULT_ZFS_DATASET.to_string(),
+        }
+    }
+}
+
+impl StorageConfig {
+    /// Read `PLFM_STORAGE_BACKEND` and `PLFM_ZFS_DATASET`.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Ok(value) = std::env::var("PLFM_STORAGE_BACKEND") {
+            config.backend = value.parse()?;
+        }
+        if let Ok(dataset) = std::env::var("PLFM_ZFS_DATASET") {
+            config.zfs_dataset = dataset.trim_matches('/').to_string();
+        }
+        Ok(config)
+    }
+}
+
+/// Where a node keeps volumes, instance disks and snapshots.
+///
+/// Every method may block (filesystem work, `zfs` commands); async callers
+/// run them with `spawn_blocking`.
+pub trait StorageBackend: Send + Sync {
+    /// Backend name, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Create whatever the backend needs before the first instance starts.
+    fn init(&self) -> Result<()>;
+
+    /// Device or file attached to VMs for a volume.
+    fn volume_path(&self, volume_id: &str) -> PathBuf;
+
+    /// Root disk for an instance booting the image whose root disk file is
+    /// `image_path`.
+    fn root_disk(
+        &self,
+        instance_id: &str,
+        image_digest: &str,
+        image_path: &Path,
+    ) -> Result<PathBuf>;
+
+    /// Formatted scratch disk of `size_bytes` for an instance. An existing
+    /// one is reused.
+    fn scratch_disk(&self, instance_id: &str, size_bytes: u64) -> Result<PathBuf>;
+
+    /// Drop the root and scratch disks of a stopped instance.
+    fn release_instance(&self, instance_id: &str);
+
+    /// Grow a volume to at least `size_bytes`. Returns `false` if it was
+    /// already that large.
+    fn grow_volume(&self, volume_id: &str, size_bytes: u64) -> Result<bool, VolumeResizeError>;
+
+    /// Take snapshot `snapshot_id` of a volume, returning the bytes it holds.
+    /// Taking a snapshot that already exists is a no-op.
+    fn snapshot_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        in_use: bool,
+    ) -> Result<u64, VolumeSnapshotError>;
+
+    /// Overwrite an unmounted volume with snapshot `snapshot_id`, keeping it at
+    /// least `size_bytes` large.
+    fn restore_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        size_bytes: u64,
+        progress: &RestoreProgress,
+    ) -> Result<(), VolumeRestoreError>;
+}
+
+/// Build the backend selected by `config`.
+pub fn open(config: &StorageConfig, data_dir: &Path) -> Arc<dyn StorageBackend> {
+    match config.backend {
+        StorageBackendKind::File => Arc::new(FileBackend::new(data_dir)),
+        StorageBackendKind::Zfs => Arc::new(ZfsBackend::new(&config.zfs_dataset, data_dir)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_backend_kind() {
+        assert_eq!(
+            "file".parse::<StorageBackendKind>().unwrap(),
+            StorageBackendKind::File
+        );
+        assert_eq!(
+            " ZFS ".parse::<StorageBackendKind>().unwrap(),
+            StorageBackendKind::Zfs
+        );
+        assert!("lvm".parse::<StorageBackendKind>().is_err());
+    }
+}
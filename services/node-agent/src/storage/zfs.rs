@@ -0,0 +1,494 @@
+//! ZFS storage backend.
+//!
+//! Everything lives in zvols under one parent dataset `<ds>`:
+//! - `<ds>/volumes/<volume_id>`: volumes, provisioned out of band like their
+//!   file counterparts; snapshots are `<ds>/volumes/<volume_id>@<snapshot_id>`
+//! - `<ds>/images/<digest>@base`: an image's root disk, imported once
+//! - `<ds>/templates/scratch-<bytes>@base`: a formatted scratch disk per size
+//! - `<ds>/instances/<instance_id>-root` and `-scratch`: per-instance clones
+//!   of the two above
+//! - `<ds>/restores/<snapshot_id>`: temporary clones used by restores
+//!
+//! Clones share blocks with their origin, so booting an instance costs no
+//! copy of its image or scratch disk.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::file::mkfs_ext4;
+use super::StorageBackend;
+use crate::volume::{
+    self, RestoreProgress, VolumeResizeError, VolumeRestoreError, VolumeSnapshotError,
+};
+
+/// zvol sizes are rounded up to this, a multiple of any volblocksize.
+const VOLSIZE_ALIGN: u64 = 1024 * 1024;
+
+/// How long to wait for udev to create a zvol's device node.
+const DEVICE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Child datasets created under the parent dataset.
+const CHILD_DATASETS: [&str; 5] = ["volumes", "images", "templates", "instances", "restores"];
+
+/// Keeps volumes and instance disks as zvols under one dataset.
+pub struct ZfsBackend {
+    dataset: String,
+    data_dir: PathBuf,
+    zfs: PathBuf,
+    zvol_dir: PathBuf,
+    /// Serializes image imports and scratch template creation.
+    provisioning: Mutex<()>,
+}
+
+/// A failed `zfs` command.
+#[derive(Debug, Error)]
+#[error("{0}")]
+struct ZfsError(String);
+
+impl ZfsError {
+    fn is_missing(&self) -> bool {
+        self.0.contains("does not exist")
+    }
+
+    fn is_out_of_space(&self) -> bool {
+        self.0.contains("out of space")
+    }
+}
+
+impl ZfsBackend {
+    pub fn new(dataset: &str, data_dir: &Path) -> Self {
+        Self {
+            dataset: dataset.to_string(),
+            data_dir: data_dir.to_path_buf(),
+            zfs: PathBuf::from("zfs"),
+            zvol_dir: PathBuf::from("/dev/zvol"),
+            provisioning: Mutex::new(()),
+        }
+    }
+
+    fn volume_dataset(&self, volume_id: &str) -> String {
+        format!("{}/volumes/{volume_id}", self.dataset)
+    }
+
+    fn image_dataset(&self, image_digest: &str) -> String {
+        format!(
+            "{}/images/{}",
+            self.dataset,
+            dataset_component(image_digest)
+        )
+    }
+
+    fn template_dataset(&self, size_bytes: u64) -> String {
+        format!("{}/templates/scratch-{size_bytes}", self.dataset)
+    }
+
+    fn instance_dataset(&self, instance_id: &str, disk: &str) -> String {
+        format!("{}/instances/{instance_id}-{disk}", self.dataset)
+    }
+
+    fn device(&self, dataset: &str) -> PathBuf {
+        self.zvol_dir.join(dataset)
+    }
+
+    /// Run `zfs` and return its trimmed stdout.
+    fn zfs(&self, args: &[&str]) -> Result<String, ZfsError> {
+        let output = Command::new(&self.zfs)
+            .args(args)
+            .output()
+            .map_err(|e| ZfsError(format!("zfs: {e}")))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(ZfsError(format!("zfs {}: {stderr}", args[0])));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        self.zfs(&["list", "-H", "-o", "name", name]).is_ok()
+    }
+
+    /// A numeric property, in bytes.
+    fn get_bytes(&self, name: &str, property: &str) -> Result<u64, ZfsError> {
+        let value = self.zfs(&["get", "-Hp", "-o", "value", property, name])?;
+        value
+            .parse()
+            .map_err(|_| ZfsError(format!("unexpected {property} of {name}: {value}")))
+    }
+
+    /// Wait for the device node of a zvol to appear.
+    fn wait_for_device(&self, dataset: &str) -> Result<PathBuf> {
+        let device = self.device(dataset);
+        let deadline = Instant::now() + DEVICE_TIMEOUT;
+        while !device.exists() {
+            if Instant::now() >= deadline {
+                return Err(anyhow!("device for {dataset} did not appear"));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Ok(device)
+    }
+
+    /// Clone `origin` to `clone` unless it already exists.
+    fn clone_once(&self, origin: &str, clone: &str) -> Result<PathBuf> {
+        if !self.exists(clone) {
+            self.zfs(&["clone", origin, clone])?;
+        }
+        self.wait_for_device(clone)
+    }
+
+    /// Create a sparse zvol for `dataset`, fill it with `fill`, and snapshot
+    /// it as `@base`. Leftovers of an interrupted attempt are replaced.
+    fn create_base(
+        &self,
+        dataset: &str,
+        size_bytes: u64,
+        fill: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<()> {
+        if self.exists(dataset) {
+            self.zfs(&["destroy", "-r", dataset])?;
+        }
+        let size = align_volsize(size_bytes).to_string();
+        self.zfs(&["create", "-s", "-V", &size, dataset])?;
+
+        let filled = self
+            .wait_for_device(dataset)
+            .and_then(|device| fill(&device))
+            .and_then(|()| {
+                self.zfs(&["snapshot", &format!("{dataset}@base")])?;
+                Ok(())
+            });
+        if filled.is_err() {
+            let _ = self.zfs(&["destroy", "-r", dataset]);
+        }
+        filled
+    }
+
+    /// Copy snapshot `full` over the volume through a temporary clone, for
+    /// snapshots that are not the volume's latest (a rollback would destroy
+    /// the newer ones).
+    fn restore_via_clone(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        full: &str,
+        size_bytes: u64,
+        progress: &RestoreProgress,
+    ) -> Result<(), VolumeRestoreError> {
+        let clone = format!("{}/restores/{snapshot_id}", self.dataset);
+        if self.exists(&clone) {
+            self.zfs(&["destroy", &clone])
+                .map_err(|e| VolumeRestoreError::Failed(e.to_string()))?;
+        }
+        let device = self
+            .clone_once(full, &clone)
+            .map_err(|e| VolumeRestoreError::Failed(e.to_string()))?;
+
+        let result = volume::restore(&self.volume_path(volume_id), &device, size_bytes, progress);
+        if let Err(e) = self.zfs(&["destroy", &clone]) {
+            warn!(dataset = %clone, error = %e, "Failed to destroy restore clone");
+        }
+        result
+    }
+}
+
+impl StorageBackend for ZfsBackend {
+    fn name(&self) -> &'static str {
+        "zfs"
+    }
+
+    fn init(&self) -> Result<()> {
+        self.zfs(&["list", "-H", "-o", "name", &self.dataset])
+            .with_context(|| format!("ZFS dataset {} must exist", self.dataset))?;
+        for child in CHILD_DATASETS {
+            let name = format!("{}/{child}", self.dataset);
+            self.zfs(&["create", "-p", &name])?;
+        }
+        fs::create_dir_all(self.data_dir.join("instances"))?;
+        info!(dataset = %self.dataset, "ZFS storage ready");
+        Ok(())
+    }
+
+    fn volume_path(&self, volume_id: &str) -> PathBuf {
+        self.device(&self.volume_dataset(volume_id))
+    }
+
+    fn root_disk(
+        &self,
+        instance_id: &str,
+        image_digest: &str,
+        image_path: &Path,
+    ) -> Result<PathBuf> {
+        let image = self.image_dataset(image_digest);
+        let base = format!("{image}@base");
+        {
+            let _guard = self.provisioning.lock().unwrap_or_else(|e| e.into_inner());
+            if !self.exists(&base) {
+                let size = fs::metadata(image_path)
+                    .with_context(|| format!("stat {}", image_path.display()))?
+                    .len();
+                info!(image_digest = %image_digest, dataset = %image, "Importing root disk into ZFS");
+                self.create_base(&image, size, |device| {
+                    let mut source = fs::File::open(image_path)?;
+                    let mut dest = fs::OpenOptions::new().write(true).open(device)?;
+                    std::io::copy(&mut source, &mut dest)?;
+                    dest.sync_all()?;
+                    Ok(())
+                })?;
+            }
+        }
+
+        self.clone_once(&base, &self.instance_dataset(instance_id, "root"))
+    }
+
+    fn scratch_disk(&self, instance_id: &str, size_bytes: u64) -> Result<PathBuf> {
+        let clone = self.instance_dataset(instance_id, "scratch");
+        if self.exists(&clone) {
+            return self.wait_for_device(&clone);
+        }
+
+        let size = align_volsize(size_bytes);
+        let template = self.template_dataset(size);
+        let base = format!("{template}@base");
+        {
+            let _guard = self.provisioning.lock().unwrap_or_else(|e| e.into_inner());
+            if !self.exists(&base) {
+                self.create_base(&template, size, mkfs_ext4)?;
+            }
+        }
+
+        self.clone_once(&base, &clone)
+    }
+
+    fn release_instance(&self, instance_id: &str) {
+        for disk in ["root", "scratch"] {
+            let clone = self.instance_dataset(instance_id, disk);
+            if let Err(e) = self.zfs(&["destroy", &clone]) {
+                if !e.is_missing() {
+                    warn!(instance_id = %instance_id, dataset = %clone, error = %e, "Failed to destroy instance disk");
+                }
+            }
+        }
+    }
+
+    fn grow_volume(&self, volume_id: &str, size_bytes: u64) -> Result<bool, VolumeResizeError> {
+        let name = self.volume_dataset(volume_id);
+        let current = self.get_bytes(&name, "volsize").map_err(|e| {
+            if e.is_missing() {
+                VolumeResizeError::Missing(name.clone())
+            } else {
+                VolumeResizeError::Failed(e.to_string())
+            }
+        })?;
+        if current >= size_bytes {
+            return Ok(false);
+        }
+
+        let size = align_volsize(size_bytes);
+        self.zfs(&["set", &format!("volsize={size}"), &name])
+            .map_err(|e| {
+                if e.is_out_of_space() {
+                    VolumeResizeError::InsufficientSpace(e.to_string())
+                } else {
+                    VolumeResizeError::Failed(e.to_string())
+                }
+            })?;
+        Ok(true)
+    }
+
+    fn snapshot_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        _in_use: bool,
+    ) -> Result<u64, VolumeSnapshotError> {
+        // ZFS snapshots are atomic, so mounted volumes need no special care.
+        let name = self.volume_dataset(volume_id);
+        let full = format!("{name}@{snapshot_id}");
+        if !self.exists(&name) {
+            return Err(VolumeSnapshotError::Missing(name));
+        }
+        if !self.exists(&full) {
+            self.zfs(&["snapshot", &full]).map_err(|e| {
+                if e.is_out_of_space() {
+                    VolumeSnapshotError::InsufficientSpace(e.to_string())
+                } else {
+                    VolumeSnapshotError::Failed(e.to_string())
+                }
+            })?;
+        }
+        self.get_bytes(&full, "referenced")
+            .map_err(|e| VolumeSnapshotError::Failed(e.to_string()))
+    }
+
+    fn restore_volume(
+        &self,
+        volume_id: &str,
+        snapshot_id: &str,
+        size_bytes: u64,
+        progress: &RestoreProgress,
+    ) -> Result<(), VolumeRestoreError> {
+        let name = self.volume_dataset(volume_id);
+        let full = format!("{name}@{snapshot_id}");
+        if !self.exists(&name) {
+            return Err(VolumeRestoreError::Missing(name));
+        }
+        if !self.exists(&full) {
+            // Not taken here (e.g. fetched from elsewhere): write the image.
+            let image = self
+                .data_dir
+                .join("snapshots")
+                .join(format!("{snapshot_id}.ext4"));
+            return volume::restore(&self.volume_path(volume_id), &image, size_bytes, progress);
+        }
+
+        let latest = self
+            .zfs(&[
+                "list",
+                "-H",
+                "-t",
+                "snapshot",
+                "-o",
+                "name",
+                "-s",
+                "createtxg",
+                "-d",
+                "1",
+                &name,
+            ])
+            .map_err(|e| VolumeRestoreError::Failed(e.to_string()))?;
+        if latest.lines().last() == Some(full.as_str()) {
+            let referenced = self.get_bytes(&full, "referenced").unwrap_or(0);
+            progress
+                .total
+                .store(referenced, std::sync::atomic::Ordering::Relaxed);
+            self.zfs(&["rollback", &full])
+                .map_err(|e| VolumeRestoreError::Failed(e.to_string()))?;
+            progress
+                .written
+                .store(referenced, std::sync::atomic::Ordering::Relaxed);
+        } else {
+            self.restore_via_clone(volume_id, snapshot_id, &full, size_bytes, progress)?;
+        }
+
+        // A rollback also rolls back volsize; the volume keeps its size.
+        self.grow_volume(volume_id, size_bytes)
+            .map(|_| ())
+            .map_err(|e| match e {
+                VolumeResizeError::InsufficientSpace(msg) => {
+                    VolumeRestoreError::InsufficientSpace(msg)
+                }
+                other => VolumeRestoreError::Failed(other.to_string()),
+            })
+    }
+}
+
+/// `size_bytes` rounded up to a valid zvol size.
+fn align_volsize(size_bytes: u64) -> u64 {
+    size_bytes.max(1).div_ceil(VOLSIZE_ALIGN) * VOLSIZE_ALIGN
+}
+
+/// `value` with characters ZFS does not allow in dataset names replaced.
+fn dataset_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A backend whose `zfs` is a script that logs its arguments. Every
+    /// dataset exists, snapshots do not, and properties read as 1 GiB.
+    fn fake_backend(dir: &Path) -> (ZfsBackend, PathBuf) {
+        let log = dir.join("zfs.log");
+        let script = dir.join("zfs");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n\
+                 echo \"$*\" >> {log}\n\
+                 case \"$*\" in\n\
+                 *list*@*) echo \"dataset does not exist\" >&2; exit 1 ;;\n\
+                 get*) echo 1073741824 ;;\n\
+                 esac\n",
+                log = log.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut backend = ZfsBackend::new("tank/plfm", dir);
+        backend.zfs = script;
+        backend.zvol_dir = dir.join("zvol");
+        (backend, log)
+    }
+
+    #[test]
+    fn names_datasets_under_parent() {
+        let backend = ZfsBackend::new("tank/plfm", Path::new("/var/lib/plfm-agent"));
+        assert_eq!(
+            backend.volume_path("vol_a"),
+            PathBuf::from("/dev/zvol/tank/plfm/volumes/vol_a")
+        );
+        assert_eq!(
+            backend.image_dataset("sha256:abc"),
+            "tank/plfm/images/sha256-abc"
+        );
+        assert_eq!(
+            backend.instance_dataset("inst_a", "scratch"),
+            "tank/plfm/instances/inst_a-scratch"
+        );
+    }
+
+    #[test]
+    fn aligns_volsize() {
+        assert_eq!(align_volsize(0), VOLSIZE_ALIGN);
+        assert_eq!(align_volsize(VOLSIZE_ALIGN), VOLSIZE_ALIGN);
+        assert_eq!(align_volsize(VOLSIZE_ALIGN + 1), 2 * VOLSIZE_ALIGN);
+    }
+
+    #[test]
+    fn grows_volume_only_when_smaller() {
+        let dir = tempfile::tempdir().unwrap();
+        let (backend, log) = fake_backend(dir.path());
+
+        assert!(!backend.grow_volume("vol_a", 1 << 30).unwrap());
+        assert!(backend.grow_volume("vol_a", (1 << 30) + 1).unwrap());
+
+        let log = fs::read_to_string(log).unwrap();
+        assert!(log.contains("get -Hp -o value volsize tank/plfm/volumes/vol_a"));
+        assert!(log.contains("set volsize=1074790400 tank/plfm/volumes/vol_a"));
+    }
+
+    #[test]
+    fn snapshots_natively() {
+        let dir = tempfile::tempdir().unwrap();
+        let (backend, log) = fake_backend(dir.path());
+
+        let size = backend.snapshot_volume("vol_a", "snap_a", true).unwrap();
+        assert_eq!(size, 1 << 30);
+
+        let log = fs::read_to_string(log).unwrap();
+        assert!(log.contains("snapshot tank/plfm/volumes/vol_a@snap_a"));
+        assert!(log.contains("get -Hp -o value referenced tank/plfm/volumes/vol_a@snap_a"));
+    }
+
+    #[test]
+    fn classifies_zfs_errors() {
+        assert!(ZfsError("cannot open 'x': dataset does not exist".into()).is_missing());
+        assert!(ZfsError("cannot set property for 'x': out of space".into()).is_out_of_space());
+    }
+}
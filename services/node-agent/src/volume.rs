@@ -0,0 +1,745 @@
+//! Volume backing storage and resizing.
+//!
+//! Volumes live at `<data_dir>/volumes/<volume_id>.ext4`, either as a file or
+//! as a symlink to an LVM logical volume. Growing a volume extends the file
+//! or the LV; the ext4 filesystem on it is grown online by guest-init once
+//! the guest sees the larger device.
+//!
+//! [`VolumeResizer`] tracks the size last applied to each volume on this node
+//! and queues the outcome of every resize for the control plane.
+//!
+//! In-place restores overwrite a volume with a snapshot image kept at
+//! `<data_dir>/snapshots/<snapshot_id>.ext4`. [`VolumeRestorer`] runs each
+//! restore in the background and reports its progress.
+//!
+//! [`VolumeSnapshotter`] takes the snapshots queued for volumes on this node.
+//! Where volumes, snapshots and instance disks actually live is up to the
+//! node's storage backend (see [`crate::storage`]); the helpers here cover
+//! file and LVM backed volumes.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::client::{
+    ControlPlaneClient, InstancePlan, VolumeResizeReport, VolumeRestoreAssignment,
+    VolumeRestoreReport, VolumeSnapshotAssignment, VolumeSnapshotReport,
+};
+use crate::runtime::Runtime;
+
+/// Buffer size for copying snapshot images.
+const RESTORE_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// How often a running restore reports its progress.
+const RESTORE_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Errors from growing a volume.
+#[derive(Debug, Error)]
+pub enum VolumeResizeError {
+    #[error("insufficient space on node: {0}")]
+    InsufficientSpace(String),
+
+    #[error("volume backing storage not found at {0}")]
+    Missing(String),
+
+    #[error("failed to grow volume: {0}")]
+    Failed(String),
+}
+
+impl VolumeResizeError {
+    /// Failure class reported to the control plane.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            VolumeResizeError::InsufficientSpace(_) => "insufficient_space",
+            VolumeResizeError::Missing(_) => "volume_missing",
+            VolumeResizeError::Failed(_) => "resize_failed",
+        }
+    }
+}
+
+/// Grow the volume at `path` to at least `size_bytes`. Returns `false` if it
+/// was already that large.
+pub fn grow(path: &Path, size_bytes: u64) -> Result<bool, VolumeResizeError> {
+    let metadata = fs::metadata(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => VolumeResizeError::Missing(path.display().to_string()),
+        _ => VolumeResizeError::Failed(format!("stat {}: {e}", path.display())),
+    })?;
+
+    if metadata.file_type().is_block_device() {
+        grow_logical_volume(path, size_bytes)
+    } else {
+        grow_file(path, metadata.len(), size_bytes)
+    }
+}
+
+fn grow_file(path: &Path, current: u64, size_bytes: u64) -> Result<bool, VolumeResizeError> {
+    if current >= size_bytes {
+        return Ok(false);
+    }
+
+    // The file may be sparse, but the volume promises its full size, so the
+    // whole increase must be free up front.
+    let needed = size_bytes - current;
+    let available = available_bytes(path)
+        .map_err(|e| VolumeResizeError::Failed(format!("statvfs {}: {e}", path.display())))?;
+    if available < needed {
+        return Err(VolumeResizeError::InsufficientSpace(format!(
+            "need {needed} more bytes, {available} available"
+        )));
+    }
+
+    fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_len(size_bytes))
+        .map_err(|e| VolumeResizeError::Failed(format!("extend {}: {e}", path.display())))?;
+
+    Ok(true)
+}
+
+fn grow_logical_volume(path: &Path, size_bytes: u64) -> Result<bool, VolumeResizeError> {
+    let current = fs::File::open(path)
+        .and_then(|mut device| device.seek(SeekFrom::End(0)))
+        .map_err(|e| VolumeResizeError::Failed(format!("size of {}: {e}", path.display())))?;
+    if current >= size_bytes {
+        return Ok(false);
+    }
+
+    let device = fs::canonicalize(path)
+        .map_err(|e| VolumeResizeError::Failed(format!("resolve {}: {e}", path.display())))?;
+    let output = Command::new("lvextend")
+        .arg("--size")
+        .arg(format!("{size_bytes}b"))
+        .arg(&device)
+        .output()
+        .map_err(|e| VolumeResizeError::Failed(format!("lvextend: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.contains("Insufficient free space") {
+            return Err(VolumeResizeError::InsufficientSpace(stderr));
+        }
+        return Err(VolumeResizeError::Failed(format!("lvextend: {stderr}")));
+    }
+
+    Ok(true)
+}
+
+/// Errors from restoring a volume in place.
+#[derive(Debug, Error)]
+pub enum VolumeRestoreError {
+    #[error("snapshot image not found at {0}")]
+    SnapshotUnavailable(String),
+
+    #[error("snapshot does not fit in the volume: {0}")]
+    SnapshotTooLarge(String),
+
+    #[error("insufficient space on node: {0}")]
+    InsufficientSpace(String),
+
+    #[error("volume backing storage not found at {0}")]
+    Missing(String),
+
+    #[error("failed to restore volume: {0}")]
+    Failed(String),
+}
+
+impl VolumeRestoreError {
+    /// Failure class reported to the control plane.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            VolumeRestoreError::SnapshotUnavailable(_) => "snapshot_unavailable",
+            VolumeRestoreError::SnapshotTooLarge(_) => "snapshot_too_large",
+            VolumeRestoreError::InsufficientSpace(_) => "insufficient_space",
+            VolumeRestoreError::Missing(_) => "volume_missing",
+            VolumeRestoreError::Failed(_) => "restore_failed",
+        }
+    }
+}
+
+/// Bytes written by a running restore.
+#[derive(Debug, Default)]
+pub struct RestoreProgress {
+    pub written: AtomicU64,
+    pub total: AtomicU64,
+}
+
+/// Overwrite the volume at `path` with the snapshot image at `snapshot`,
+/// keeping the volume at least `size_bytes` large.
+///
+/// File-backed volumes are written beside the original and swapped in, so a
+/// failed restore leaves the old data in place. LVM volumes are overwritten
+/// directly.
+pub fn restore(
+    path: &Path,
+    snapshot: &Path,
+    size_bytes: u64,
+    progress: &RestoreProgress,
+) -> Result<(), VolumeRestoreError> {
+    let mut source = fs::File::open(snapshot).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            VolumeRestoreError::SnapshotUnavailable(snapshot.display().to_string())
+        }
+        _ => VolumeRestoreError::Failed(format!("open {}: {e}", snapshot.display())),
+    })?;
+    // Seeking sizes block devices too (a native snapshot exposed as one).
+    let total = source
+        .seek(SeekFrom::End(0))
+        .and_then(|size| source.seek(SeekFrom::Start(0)).map(|_| size))
+        .map_err(|e| VolumeRestoreError::Failed(format!("size of {}: {e}", snapshot.display())))?;
+    let metadata = fs::metadata(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => VolumeRestoreError::Missing(path.display().to_string()),
+        _ => VolumeRestoreError::Failed(format!("stat {}: {e}", path.display())),
+    })?;
+    progress.total.store(total, Ordering::Relaxed);
+
+    if metadata.file_type().is_block_device() {
+        let mut device = fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| VolumeRestoreError::Failed(format!("open {}: {e}", path.display())))?;
+        let capacity = device
+            .seek(SeekFrom::End(0))
+            .and_then(|size| device.seek(SeekFrom::Start(0)).map(|_| size))
+            .map_err(|e| VolumeRestoreError::Failed(format!("size of {}: {e}", path.display())))?;
+        if total > capacity {
+            return Err(VolumeRestoreError::SnapshotTooLarge(format!(
+                "snapshot is {total} bytes, volume is {capacity}"
+            )));
+        }
+        copy_with_progress(&mut source, &mut device, progress)
+            .and_then(|()| device.sync_all())
+            .map_err(|e| VolumeRestoreError::Failed(format!("write {}: {e}", path.display())))?;
+        return Ok(());
+    }
+
+    let capacity = size_bytes.max(metadata.len());
+    if total > capacity {
+        return Err(VolumeRestoreError::SnapshotTooLarge(format!(
+            "snapshot is {total} bytes, volume is {capacity}"
+        )));
+    }
+    let available = available_bytes(path)
+        .map_err(|e| VolumeRestoreError::Failed(format!("statvfs {}: {e}", path.display())))?;
+    if available < total {
+        return Err(VolumeRestoreError::InsufficientSpace(format!(
+            "need {total} bytes to stage the restore, {available} available"
+        )));
+    }
+
+    let staging = path.with_extension("ext4.restoring");
+    let staged = fs::File::create(&staging).and_then(|mut file| {
+        copy_with_progress(&mut source, &mut file, progress)?;
+        file.set_len(capacity)?;
+        file.sync_all()
+    });
+    if let Err(e) = staged.and_then(|()| fs::rename(&staging, path)) {
+        let _ = fs::remove_file(&staging);
+        return Err(VolumeRestoreError::Failed(format!(
+            "write {}: {e}",
+            staging.display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Errors from snapshotting a volume.
+#[derive(Debug, Error)]
+pub enum VolumeSnapshotError {
+    #[error("volume backing storage not found at {0}")]
+    Missing(String),
+
+    #[error("volume is in use: {0}")]
+    InUse(String),
+
+    #[error("insufficient space on node: {0}")]
+    InsufficientSpace(String),
+
+    #[error("failed to snapshot volume: {0}")]
+    Failed(String),
+}
+
+impl VolumeSnapshotError {
+    /// Failure class reported to the control plane.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            VolumeSnapshotError::Missing(_) => "volume_missing",
+            VolumeSnapshotError::InUse(_) => "volume_in_use",
+            VolumeSnapshotError::InsufficientSpace(_) => "insufficient_space",
+            VolumeSnapshotError::Failed(_) => "snapshot_failed",
+        }
+    }
+}
+
+fn copy_with_progress(
+    source: &mut impl Read,
+    dest: &mut impl Write,
+    progress: &RestoreProgress,
+) -> std::io::Result<()> {
+    let mut buf = vec![0u8; RESTORE_CHUNK_BYTES];
+    loop {
+        let n = source.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        dest.write_all(&buf[..n])?;
+        progress.written.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// Free bytes (for unprivileged users) on the filesystem holding `path`.
+pub(crate) fn available_bytes(path: &Path) -> std::io::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Grows volumes to the sizes requested by the plan and collects the
+/// outcomes for the control plane.
+///
+/// Each size is attempted once per agent process: a failed resize is retried
+/// when the requested size changes again or after the agent restarts.
+#[derive(Default)]
+pub struct VolumeResizer {
+    /// Size last attempted, by volume ID.
+    attempted: Mutex<HashMap<String, i64>>,
+    /// Outcomes not yet reported.
+    pending: Mutex<Vec<VolumeResizeReport>>,
+}
+
+impl VolumeResizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grow any volume mounted by `plan` whose requested size has not been
+    /// attempted yet.
+    pub async fn reconcile(&self, runtime: &dyn Runtime, plan: &InstancePlan) {
+        let Some(mounts) = plan.mounts.as_ref() else {
+            return;
+        };
+
+        for mount in mounts {
+            let Some(size_bytes) = mount.size_bytes.filter(|&size| size > 0) else {
+                continue;
+            };
+            {
+                let mut attempted = self.attempted.lock().await;
+                if attempted
+                    .get(&mount.volume_id)
+                    .is_some_and(|&last| last >= size_bytes)
+                {
+                    continue;
+                }
+                attempted.insert(mount.volume_id.clone(), size_bytes);
+            }
+
+            let result = runtime
+                .resize_volume(plan, &mount.volume_id, size_bytes as u64)
+                .await;
+            let report = match result {
+                Ok(grown) => {
+                    if grown {
+                        info!(volume_id = %mount.volume_id, size_bytes, "Volume grown");
+                    }
+                    VolumeResizeReport {
+                        volume_id: mount.volume_id.clone(),
+                        size_bytes,
+                        reason: None,
+                        error_message: None,
+                    }
+                }
+                Err(e) => {
+                    warn!(volume_id = %mount.volume_id, size_bytes, error = %e, "Volume resize failed");
+                    VolumeResizeReport {
+                        volume_id: mount.volume_id.clone(),
+                        size_bytes,
+                        reason: Some(e.reason().to_string()),
+                        error_message: Some(e.to_string()),
+                    }
+                }
+            };
+            self.pending.lock().await.push(report);
+        }
+    }
+
+    /// Report queued outcomes; ones that fail to send are kept for the next
+    /// call.
+    pub async fn flush(&self, client: &ControlPlaneClient) {
+        let reports = std::mem::take(&mut *self.pending.lock().await);
+        for report in reports {
+            match client.report_volume_resize(&report).await {
+                Ok(accepted) => {
+                    debug!(volume_id = %report.volume_id, accepted, "Reported volume resize");
+                }
+                Err(e) => {
+                    warn!(
+                        volume_id = %report.volume_id,
+                        error = %e,
+                        "Failed to report volume resize, will retry"
+                    );
+                    self.pending.lock().await.push(report);
+                }
+            }
+        }
+    }
+}
+
+/// Runs the in-place restores assigned to this node.
+///
+/// Each restore is started once per agent process and runs in its own task,
+/// reporting progress until it finishes. A restore interrupted by an agent
+/// restart is still queued or running on the control plane, so the next plan
+/// starts it again from the beginning.
+#[derive(Default)]
+pub struct VolumeRestorer {
+    /// Restores started by this process, by restore ID.
+    started: Mutex<HashSet<String>>,
+}
+
+impl VolumeRestorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start any restore in `restores` that is not already running. Volumes
+    /// still mounted by an instance in the plan are skipped until a later
+    /// plan no longer runs it.
+    pub async fn reconcile<R: Runtime + 'static>(
+        &self,
+        runtime: &Arc<R>,
+        client: &Arc<ControlPlaneClient>,
+        restores: &[VolumeRestoreAssignment],
+        mounted: &HashSet<&str>,
+    ) {
+        for restore in restores {
+            if mounted.contains(restore.volume_id.as_str()) {
+                warn!(
+                    restore_id = %restore.restore_id,
+                    volume_id = %restore.volume_id,
+                    "Volume is still mounted by a planned instance; deferring restore"
+                );
+                continue;
+            }
+            if !self.started.lock().await.insert(restore.restore_id.clone()) {
+                continue;
+            }
+
+            info!(
+                restore_id = %restore.restore_id,
+                volume_id = %restore.volume_id,
+                snapshot_id = %restore.snapshot_id,
+                "Starting in-place volume restore"
+            );
+            tokio::spawn(run_restore(
+                Arc::clone(runtime),
+                Arc::clone(client),
+                restore.clone(),
+            ));
+        }
+    }
+}
+
+async fn run_restore<R: Runtime + 'static>(
+    runtime: Arc<R>,
+    client: Arc<ControlPlaneClient>,
+    restore: VolumeRestoreAssignment,
+) {
+    let progress = Arc::new(RestoreProgress::default());
+    let running = |progress: &RestoreProgress| VolumeRestoreReport {
+        restore_id: restore.restore_id.clone(),
+        status: "running",
+        bytes_restored: Some(progress.written.load(Ordering::Relaxed) as i64),
+        bytes_total: Some(progress.total.load(Ordering::Relaxed) as i64).filter(|&t| t > 0),
+        reason: None,
+        error_message: None,
+    };
+
+    if let Err(e) = client.report_volume_restore(&running(&progress)).await {
+        warn!(restore_id = %restore.restore_id, error = %e, "Failed to report restore start");
+    }
+
+    let result = {
+        let task = runtime.restore_volume(
+            &restore.volume_id,
+            &restore.snapshot_id,
+            restore.size_bytes.max(0) as u64,
+            Arc::clone(&progress),
+        );
+        tokio::pin!(task);
+        let mut ticker = tokio::time::interval(RESTORE_PROGRESS_INTERVAL);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                result = &mut task => break result,
+                _ = ticker.tick() => {
+                    if let Err(e) = client.report_volume_restore(&running(&progress)).await {
+                        debug!(restore_id = %restore.restore_id, error = %e, "Failed to report restore progress");
+                    }
+                }
+            }
+        }
+    };
+
+    let mut report = running(&progress);
+    match result {
+        Ok(()) => {
+            info!(
+                restore_id = %restore.restore_id,
+                volume_id = %restore.volume_id,
+                bytes = report.bytes_restored,
+                "Volume restored from snapshot"
+            );
+            report.status = "succeeded";
+        }
+        Err(e) => {
+            warn!(
+                restore_id = %restore.restore_id,
+                volume_id = %restore.volume_id,
+                error = %e,
+                "Volume restore failed"
+            );
+            report.status = "failed";
+            report.reason = Some(e.reason().to_string());
+            report.error_message = Some(e.to_string());
+        }
+    }
+
+    // The outcome must reach the control plane: it holds the volume's process
+    // types down until then.
+    loop {
+        match client.report_volume_restore(&report).await {
+            Ok(accepted) => {
+                debug!(restore_id = %restore.restore_id, accepted, "Reported volume restore");
+                return;
+            }
+            Err(e) => {
+                warn!(
+                    restore_id = %restore.restore_id,
+                    error = %e,
+                    "Failed to report volume restore, will retry"
+                );
+                tokio::time::sleep(RESTORE_PROGRESS_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Takes the snapshots queued for volumes on this node.
+///
+/// Like restores, each snapshot is started once per agent process and runs in
+/// its own task; one interrupted by an agent restart is still queued or
+/// running on the control plane and is taken again from the next plan.
+#[derive(Default)]
+pub struct VolumeSnapshotter {
+    /// Snapshots started by this process, by snapshot ID.
+    started: Mutex<HashSet<String>>,
+}
+
+impl VolumeSnapshotter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start any snapshot in `snapshots` that is not already running.
+    /// `mounted` holds the volumes used by instances in the plan.
+    pub async fn reconcile<R: Runtime + 'static>(
+        &self,
+        runtime: &Arc<R>,
+        client: &Arc<ControlPlaneClient>,
+        snapshots: &[VolumeSnapshotAssignment],
+        mounted: &HashSet<&str>,
+    ) {
+        for snapshot in snapshots {
+            if !self
+                .started
+                .lock()
+                .await
+                .insert(snapshot.snapshot_id.clone())
+            {
+                continue;
+            }
+
+            let in_use = mounted.contains(snapshot.volume_id.as_str());
+            info!(
+                snapshot_id = %snapshot.snapshot_id,
+                volume_id = %snapshot.volume_id,
+                in_use,
+                "Taking volume snapshot"
+            );
+            tokio::spawn(run_snapshot(
+                Arc::clone(runtime),
+                Arc::clone(client),
+                snapshot.clone(),
+                in_use,
+            ));
+        }
+    }
+}
+
+async fn run_snapshot<R: Runtime + 'static>(
+    runtime: Arc<R>,
+    client: Arc<ControlPlaneClient>,
+    snapshot: VolumeSnapshotAssignment,
+    in_use: bool,
+) {
+    let mut report = VolumeSnapshotReport {
+        snapshot_id: snapshot.snapshot_id.clone(),
+        status: "running",
+        size_bytes: None,
+        reason: None,
+        error_message: None,
+    };
+    if let Err(e) = client.report_volume_snapshot(&report).await {
+        warn!(snapshot_id = %snapshot.snapshot_id, error = %e, "Failed to report snapshot start");
+    }
+
+    match runtime
+        .snapshot_volume(&snapshot.volume_id, &snapshot.snapshot_id, in_use)
+        .await
+    {
+        Ok(size_bytes) => {
+            info!(
+                snapshot_id = %snapshot.snapshot_id,
+                volume_id = %snapshot.volume_id,
+                size_bytes,
+                "Volume snapshot taken"
+            );
+            report.status = "succeeded";
+            report.size_bytes = Some(size_bytes as i64);
+        }
+        Err(e) => {
+            warn!(
+                snapshot_id = %snapshot.snapshot_id,
+                volume_id = %snapshot.volume_id,
+                error = %e,
+                "Volume snapshot failed"
+            );
+            report.status = "failed";
+            report.reason = Some(e.reason().to_string());
+            report.error_message = Some(e.to_string());
+        }
+    }
+
+    // Until the outcome is recorded the snapshot stays queued, which holds
+    // back retention pruning for the volume.
+    loop {
+        match client.report_volume_snapshot(&report).await {
+            Ok(accepted) => {
+                debug!(snapshot_id = %snapshot.snapshot_id, accepted, "Reported volume snapshot");
+                return;
+            }
+            Err(e) => {
+                warn!(
+                    snapshot_id = %snapshot.snapshot_id,
+                    error = %e,
+                    "Failed to report volume snapshot, will retry"
+                );
+                tokio::time::sleep(RESTORE_PROGRESS_INTERVAL).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_backing_file_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vol.ext4");
+        fs::File::create(&path).unwrap().set_len(1024).unwrap();
+
+        assert!(grow(&path, 4096).unwrap());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 4096);
+
+        // Already large enough: nothing to do, and never shrinks.
+        assert!(!grow(&path, 4096).unwrap());
+        assert!(!grow(&path, 2048).unwrap());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 4096);
+    }
+
+    #[test]
+    fn missing_volume_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = grow(&dir.path().join("missing.ext4"), 4096).unwrap_err();
+        assert_eq!(err.reason(), "volume_missing");
+    }
+
+    #[test]
+    fn growth_beyond_free_space_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vol.ext4");
+        fs::File::create(&path).unwrap();
+
+        let err = grow(&path, u64::MAX / 2).unwrap_err();
+        assert_eq!(err.reason(), "insufficient_space");
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn restores_file_volume_and_keeps_its_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vol.ext4");
+        fs::write(&path, vec![1u8; 8192]).unwrap();
+        let snapshot = dir.path().join("snap.ext4");
+        fs::write(&snapshot, vec![2u8; 4096]).unwrap();
+
+        let progress = RestoreProgress::default();
+        restore(&path, &snapshot, 8192, &progress).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        assert_eq!(data.len(), 8192);
+        assert!(data[..4096].iter().all(|&b| b == 2));
+        assert_eq!(progress.written.load(Ordering::Relaxed), 4096);
+        assert_eq!(progress.total.load(Ordering::Relaxed), 4096);
+        assert!(!path.with_extension("ext4.restoring").exists());
+    }
+
+    #[test]
+    fn missing_snapshot_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vol.ext4");
+        fs::write(&path, vec![1u8; 1024]).unwrap();
+
+        let err = restore(
+            &path,
+            &dir.path().join("missing.ext4"),
+            1024,
+            &RestoreProgress::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.reason(), "snapshot_unavailable");
+    }
+
+    #[test]
+    fn oversized_snapshot_leaves_volume_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vol.ext4");
+        fs::write(&path, vec![1u8; 1024]).unwrap();
+        let snapshot = dir.path().join("snap.ext4");
+        fs::write(&snapshot, vec![2u8; 4096]).unwrap();
+
+        let err = restore(&path, &snapshot, 1024, &RestoreProgress::default()).unwrap_err();
+        assert_eq!(err.reason(), "snapshot_too_large");
+        assert_eq!(fs::read(&path).unwrap(), vec![1u8; 1024]);
+    }
+}
@@ -9,29 +9,52 @@
 //! 3. Host sends config message
 //! 4. Guest sends ack message
 //! 5. Guest sends status updates as boot progresses
+//!
+//! Once the instance is running, non-disruptive config changes (a new
+//! secrets version, a renewed identity token) are pushed the other way: the
+//! host connects to the guest's config update service on vsock port 5163,
+//! sends a `config_update` message and waits for the guest's ack.
+//!
+//! Secret material and identity tokens are held only in memory
+//! ([`SecretPayload`], zeroed on drop) and serialized straight onto the
+//! vsock stream; guest-init writes them to a tmpfs. They are never written
+//! to host disk.
 
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use plfm_events::SecretsReload;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 use vsock::{VsockAddr, VsockListener, VsockStream, VMADDR_CID_HOST};
 
 use crate::client::InstancePlan;
+use crate::secrets::SecretPayload;
 use crate::state::{BootStatusRecord, StateStore};
 
 /// Vsock port for config handshake.
 pub const CONFIG_PORT: u32 = 5161;
 
+/// Vsock port for in-place config updates (guest listens).
+pub const CONFIG_UPDATE_PORT: u32 = 5163;
+
+/// How long to wait for guest-init to apply a config update.
+const CONFIG_UPDATE_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Current protocol version.
 pub const PROTOCOL_VERSION: u32 = 1;
 
 /// Config version string.
 pub const CONFIG_VERSION: &str = "v1";
 
+/// Where guest-init writes the instance identity token.
+pub const IDENTITY_TOKEN_PATH: &str = "/run/platform/identity/token";
+
 // =============================================================================
 // Message Types
 // =============================================================================
@@ -62,6 +85,8 @@ pub struct ConfigMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     secrets: Option<SecretsConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<IdentityConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     health: Option<HealthConfig>,
     exec: ExecConfig,
 }
@@ -114,7 +139,25 @@ pub struct SecretsConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     bundle_version_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    data: Option<String>,
+    data: Option<SecretPayload>,
+}
+
+/// Instance identity token for guest-init to write for the workload.
+#[derive(Debug, Clone, Serialize)]
+pub struct IdentityConfig {
+    pub token: SecretPayload,
+    pub expires_at: DateTime<Utc>,
+    pub path: String,
+}
+
+impl IdentityConfig {
+    pub fn new(token: SecretPayload, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            token,
+            expires_at,
+            path: IDENTITY_TOKEN_PATH.to_string(),
+        }
+    }
 }
 
 /// Exec service configuration.
@@ -138,6 +181,42 @@ pub struct HealthConfig {
     failure_threshold: i32,
 }
 
+/// Config update pushed to a running guest.
+#[derive(Debug, Serialize)]
+pub struct ConfigUpdateMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    config_version: String,
+    instance_id: String,
+    generation: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secrets: Option<SecretsConfig>,
+    /// New config vars, when they changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<HashMap<String, String>>,
+    /// How the workload is told about new secrets or config vars; sent with
+    /// `secrets` or `env`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reload: Option<SecretsReload>,
+    /// Renewed identity token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<IdentityConfig>,
+}
+
+/// Reply from guest-init to a config update: `ack` once applied, or
+/// `error` with a reason code. An `ack` carries a reason when the update
+/// was applied but notifying the workload failed (`reload_failed`).
+#[derive(Debug, Deserialize)]
+pub struct ConfigUpdateReply {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub generation: u64,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
 /// Ack message from guest-init.
 #[derive(Debug, Deserialize)]
 pub struct AckMessage {
@@ -177,8 +256,10 @@ pub struct PendingConfig {
     pub gateway_ipv6: String,
     /// Config generation number.
     pub generation: u64,
-    /// Secrets data (decrypted, dotenv format).
-    pub secrets_data: Option<String>,
+    /// Secrets data (decrypted, dotenv format). Memory only.
+    pub secrets_data: Option<SecretPayload>,
+    /// Identity token, when one could be issued. Memory only.
+    pub identity: Option<IdentityConfig>,
 }
 
 /// Store for pending instance configurations.
@@ -330,8 +411,11 @@ fn handle_connection(
     // Build config message
     let config_msg = build_config_message(&hello.instance_id, &pending);
 
-    // Send config
-    send_message(&mut stream, &config_msg).context("Failed to send config")?;
+    // Send config, then drop (and zero) every copy of the secrets.
+    let sent = send_message(&mut stream, &config_msg).context("Failed to send config");
+    drop(config_msg);
+    drop(pending);
+    sent?;
     debug!(instance_id = %hello.instance_id, "Sent config to guest-init");
 
     // Read ack
@@ -464,22 +548,7 @@ fn build_config_message(instance_id: &str, pending: &PendingConfig) -> ConfigMes
         })
         .unwrap_or_default();
 
-    let secrets = match (pending.secrets_data.as_ref(), plan.secrets.as_ref()) {
-        (Some(data), Some(secrets)) => Some(SecretsConfig {
-            required: secrets.required,
-            path: secrets.mount_path.clone(),
-            mode: secrets
-                .mode
-                .map(|mode| format!("{:04o}", mode))
-                .unwrap_or_else(|| "0400".to_string()),
-            owner_uid: secrets.uid.unwrap_or(0) as u32,
-            owner_gid: secrets.gid.unwrap_or(0) as u32,
-            format: "platform_env_v1".to_string(),
-            bundle_version_id: secrets.secret_version_id.clone(),
-            data: Some(data.clone()),
-        }),
-        _ => None,
-    };
+    let secrets = secrets_config(plan, pending.secrets_data.as_ref());
 
     let exec = ExecConfig {
         vsock_port: 5162,
@@ -506,11 +575,106 @@ fn build_config_message(instance_id: &str, pending: &PendingConfig) -> ConfigMes
         network,
         mounts,
         secrets,
+        identity: pending.identity.clone(),
         health,
         exec,
     }
 }
 
+/// Secrets section of a config message; `None` without material to send.
+fn secrets_config(plan: &InstancePlan, data: Option<&SecretPayload>) -> Option<SecretsConfig> {
+    match (data, plan.secrets.as_ref()) {
+        (Some(data), Some(secrets)) => Some(SecretsConfig {
+            required: secrets.required,
+            path: secrets.mount_path.clone(),
+            mode: secrets
+                .mode
+                .map(|mode| format!("{:04o}", mode))
+                .unwrap_or_else(|| "0400".to_string()),
+            owner_uid: secrets.uid.unwrap_or(0) as u32,
+            owner_gid: secrets.gid.unwrap_or(0) as u32,
+            format: "platform_env_v1".to_string(),
+            bundle_version_id: secrets.secret_version_id.clone(),
+            data: Some(data.clone()),
+        }),
+        _ => None,
+    }
+}
+
+/// Build the config update message for a running instance's new plan.
+///
+/// `env` is the plan's config vars when they changed. The reload mode is
+/// the one the plan's secrets carry.
+fn build_config_update_message(
+    plan: &InstancePlan,
+    secrets_data: Option<&SecretPayload>,
+    env: Option<HashMap<String, String>>,
+    identity: Option<IdentityConfig>,
+) -> ConfigUpdateMessage {
+    let secrets = secrets_config(plan, secrets_data);
+    let reload = if secrets.is_some() || env.is_some() {
+        plan.secrets
+            .as_ref()
+            .and_then(|secrets| secrets.reload.clone())
+    } else {
+        None
+    };
+    ConfigUpdateMessage {
+        msg_type: "config_update".to_string(),
+        config_version: CONFIG_VERSION.to_string(),
+        instance_id: plan.instance_id.clone(),
+        generation: plan.generation.max(0) as u64,
+        secrets,
+        env,
+        reload,
+        identity,
+    }
+}
+
+/// Push a new config generation to a running guest and wait until it is
+/// applied, returning the guest's `ack`. Blocking; call from `spawn_blocking`.
+pub fn push_config_update(
+    guest_cid: u32,
+    plan: &InstancePlan,
+    secrets_data: Option<SecretPayload>,
+    env: Option<HashMap<String, String>>,
+    identity: Option<IdentityConfig>,
+) -> Result<ConfigUpdateReply> {
+    let addr = VsockAddr::new(guest_cid, CONFIG_UPDATE_PORT);
+    let mut stream = VsockStream::connect(&addr)
+        .map_err(|e| anyhow!("Failed to connect to guest config update service: {e}"))?;
+    stream.set_read_timeout(Some(CONFIG_UPDATE_TIMEOUT))?;
+
+    // Send, then drop (and zero) every copy of the secrets.
+    let update = build_config_update_message(plan, secrets_data.as_ref(), env, identity);
+    let sent = send_message(&mut stream, &update).context("Failed to send config update");
+    drop(update);
+    drop(secrets_data);
+    sent?;
+
+    let reply = read_message::<ConfigUpdateReply>(&mut stream)
+        .context("Failed to read config update reply")?;
+    check_config_update_reply(&reply, plan.generation.max(0) as u64)?;
+    Ok(reply)
+}
+
+fn check_config_update_reply(reply: &ConfigUpdateReply, generation: u64) -> Result<()> {
+    match reply.msg_type.as_str() {
+        "ack" if reply.generation == generation => Ok(()),
+        "ack" => Err(anyhow!(
+            "guest acked generation {}, expected {}",
+            reply.generation,
+            generation
+        )),
+        "error" => Err(anyhow!(
+            "guest rejected config update: {}: {}",
+            reply.reason.as_deref().unwrap_or("unknown"),
+            reply.detail.as_deref().unwrap_or("")
+        )),
+        other => Err(anyhow!("Expected 'ack' message, got '{other}'")),
+    }
+}
+
 /// Read a JSON message from the stream.
 fn read_message<T: serde::de::DeserializeOwned>(stream: &mut VsockStream) -> Result<T> {
     let mut reader = BufReader::new(stream);
@@ -526,9 +690,12 @@ fn read_message<T: serde::de::DeserializeOwned>(stream: &mut VsockStream) -> Res
 }
 
 /// Send a JSON message to the stream.
+///
+/// Serializes directly onto the socket rather than into an intermediate
+/// buffer, so config messages carrying secrets leave no plaintext copy
+/// behind in host memory.
 fn send_message<T: serde::Serialize>(stream: &mut VsockStream, msg: &T) -> Result<()> {
-    let json = serde_json::to_string(msg).context("Failed to serialize message")?;
-    stream.write_all(json.as_bytes())?;
+    serde_json::to_writer(&mut *stream, msg).context("Failed to serialize message")?;
     stream.write_all(b"\n")?;
     stream.flush()?;
     Ok(())
@@ -617,11 +784,23 @@ mod tests {
         assert_eq!(status_failed.reason, Some("mount_failed".to_string()));
     }
 
-    #[tokio::test]
-    async fn test_config_store() {
-        let store = ConfigStore::new();
+    #[test]
+    fn test_config_update_reply() {
+        let ack: ConfigUpdateReply =
+            serde_json::from_str(r#"{"type":"ack","config_version":"v1","generation":3}"#).unwrap();
+        assert!(check_config_update_reply(&ack, 3).is_ok());
+        assert!(check_config_update_reply(&ack, 4).is_err());
+
+        let rejected: ConfigUpdateReply = serde_json::from_str(
+            r#"{"type":"error","generation":3,"reason":"secrets_write_failed","detail":"rename failed"}"#,
+        )
+        .unwrap();
+        let err = check_config_update_reply(&rejected, 3).unwrap_err();
+        assert!(err.to_string().contains("secrets_write_failed"));
+    }
 
-        let plan = InstancePlan {
+    fn test_plan() -> InstancePlan {
+        InstancePlan {
             spec_version: "v1".to_string(),
             org_id: "org_test".to_string(),
             app_id: "app_test".to_string(),
@@ -664,7 +843,82 @@ mod tests {
             secrets: None,
             health: None,
             spec_hash: None,
-        };
+        }
+    }
+
+    #[test]
+    fn test_config_update_carries_reload_with_secrets() {
+        let mut plan = test_plan();
+        plan.generation = 2;
+        plan.secrets = Some(crate::client::WorkloadSecrets {
+            required: true,
+            secret_version_id: Some("sv_2".to_string()),
+            mount_path: "/run/secrets/platform.env".to_string(),
+            mode: None,
+            uid: None,
+            gid: None,
+            reload: Some(SecretsReload::Signal {
+                signal: "SIGHUP".to_string(),
+            }),
+        });
+
+        let data = SecretPayload::from("API_KEY=rotated\n".to_string());
+        let json =
+            serde_json::to_value(build_config_update_message(&plan, Some(&data), None, None))
+                .unwrap();
+        assert_eq!(json["generation"], 2);
+        assert_eq!(json["secrets"]["bundle_version_id"], "sv_2");
+        assert_eq!(json["reload"]["mode"], "signal");
+        assert_eq!(json["reload"]["signal"], "SIGHUP");
+
+        // Nothing to reload without new secrets or config vars.
+        let json =
+            serde_json::to_value(build_config_update_message(&plan, None, None, None)).unwrap();
+        assert!(json.get("secrets").is_none());
+        assert!(json.get("reload").is_none());
+
+        // New config vars alone are reloaded too.
+        let env = HashMap::from([("LOG_LEVEL".to_string(), "debug".to_string())]);
+        let json = serde_json::to_value(build_config_update_message(&plan, None, Some(env), None))
+            .unwrap();
+        assert!(json.get("secrets").is_none());
+        assert_eq!(json["env"]["LOG_LEVEL"], "debug");
+        assert_eq!(json["reload"]["mode"], "signal");
+    }
+
+    #[test]
+    fn test_config_update_carries_renewed_identity() {
+        let mut plan = test_plan();
+        plan.generation = 3;
+        let expires_at = Utc::now();
+        let identity =
+            IdentityConfig::new(SecretPayload::from("eyJ.token".to_string()), expires_at);
+
+        let json = serde_json::to_value(build_config_update_message(
+            &plan,
+            None,
+            None,
+            Some(identity),
+        ))
+        .unwrap();
+        // A renewal re-sends the current generation and no secrets or env.
+        assert_eq!(json["generation"], 3);
+        assert!(json.get("secrets").is_none());
+        assert!(json.get("env").is_none());
+        assert_eq!(json["identity"]["token"], "eyJ.token");
+        assert_eq!(json["identity"]["path"], IDENTITY_TOKEN_PATH);
+        assert!(!format!(
+            "{:?}",
+            IdentityConfig::new(SecretPayload::from("eyJ.token".to_string()), expires_at)
+        )
+        .contains("eyJ"));
+    }
+
+    #[tokio::test]
+    async fn test_config_store() {
+        let store = ConfigStore::new();
+
+        let plan = test_plan();
 
         let pending = PendingConfig {
             plan,
@@ -672,6 +926,7 @@ mod tests {
             gateway_ipv6: "fd00::1".to_string(),
             generation: 1,
             secrets_data: None,
+            identity: None,
         };
 
         store.add("inst_test", pending.clone()).await;
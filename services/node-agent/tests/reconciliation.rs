@@ -29,7 +29,8 @@ fn test_config() -> Config {
         data_dir: "/tmp/node-agent-test".to_string(),
         heartbeat_interval_secs: 30,
         log_level: "debug".to_string(),
-        exec_listen_addr: "127.0.0.1:0".parse().unwrap(),
+        exec_listen_addr: None,
+        local_api_socket: None,
     }
 }
 
@@ -0,0 +1,59 @@
+#!/usr/bin/env bash
+# Point-in-time restore harness for the control-plane database.
+#
+# 1. Starts Postgres archiving WAL to MinIO through WAL-G, and takes a base
+#    backup.
+# 2. Commits a row, records the target time, then commits a row after it.
+# 3. Restores into a fresh data directory with
+#    `plfm-admin restore --target-time`, starts Postgres on it, and checks
+#    that exactly the rows committed before the target survived.
+#
+# Requires Docker with the compose plugin. Usage: test/backup-restore/run.sh
+
+set -euo pipefail
+
+cd "$(dirname "$0")"
+compose=(docker compose -p plfm-backup-restore -f docker-compose.yml)
+
+cleanup() {
+    "${compose[@]}" --profile restore down -v >/dev/null 2>&1 || true
+}
+trap cleanup EXIT
+
+psql() {
+    "${compose[@]}" exec -T postgres psql -v ON_ERROR_STOP=1 -qAt -U plfm -d plfm -c "$1"
+}
+
+echo "==> Starting Postgres and MinIO"
+"${compose[@]}" up -d --build --wait postgres
+
+echo "==> Taking a base backup"
+psql "CREATE TABLE pitr_probe (id INT PRIMARY KEY, note TEXT NOT NULL)"
+psql "INSERT INTO pitr_probe VALUES (1, 'before base backup')"
+"${compose[@]}" exec -T -u postgres postgres wal-g backup-push /var/lib/postgresql/data
+
+echo "==> Writing around the target time"
+psql "INSERT INTO pitr_probe VALUES (2, 'before target')"
+sleep 1
+target=$(psql "SELECT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"')")
+sleep 1
+psql "INSERT INTO pitr_probe VALUES (3, 'after target')"
+
+# Archive the segment holding the last commit so recovery can see past the
+# target.
+segment=$(psql "SELECT pg_walfile_name(pg_switch_wal())")
+for _ in $(seq 1 60); do
+    archived=$(psql "SELECT coalesce(last_archived_wal, '') FROM pg_stat_archiver")
+    [[ "$archived" > "$segment" || "$archived" == "$segment" ]] && break
+    sleep 1
+done
+[[ "$archived" > "$segment" || "$archived" == "$segment" ]] || {
+    echo "WAL segment $segment was not archived" >&2
+    exit 1
+}
+
+echo "==> Restoring to $target"
+"${compose[@]}" stop postgres
+"${compose[@]}" run --rm -e TARGET_TIME="$target" restore
+
+echo "PASS: point-in-time restore"
@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+# Runs inside the restore container of the backup/restore harness: restores
+# to $TARGET_TIME with plfm-admin, starts Postgres, and checks the rows.
+
+set -euo pipefail
+
+data_dir=/tmp/restore
+
+plfm-admin backup list
+plfm-admin restore --target-time "$TARGET_TIME" --data-dir "$data_dir"
+
+pg_ctl -D "$data_dir" -o "-c listen_addresses='' -c unix_socket_directories=/tmp" -w -t 120 start
+
+for _ in $(seq 1 60); do
+    [[ "$(psql -qAt -c 'SELECT pg_is_in_recovery()')" == "f" ]] && break
+    sleep 1
+done
+[[ "$(psql -qAt -c 'SELECT pg_is_in_recovery()')" == "f" ]] || {
+    echo "restored cluster did not promote" >&2
+    exit 1
+}
+
+rows=$(psql -qAt -c "SELECT string_agg(id::text, ',' ORDER BY id) FROM pitr_probe")
+pg_ctl -D "$data_dir" -m fast stop
+if [[ "$rows" != "1,2" ]]; then
+    echo "expected rows 1,2 after restore, got: $rows" >&2
+    exit 1
+fi
+echo "restored rows: $rows"
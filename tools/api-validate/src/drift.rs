@@ -0,0 +1,434 @@
+//! Cross-checks the route manifest against the OpenAPI operations.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::route_manifest::{path_params, RouteEntry, RouteManifest, StructShape};
+
+/// An operation documented in `openapi.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecOperation {
+    /// Lowercase HTTP method.
+    pub method: String,
+    pub path: String,
+    /// Names of the parameters declared `in: path`.
+    pub path_params: Vec<String>,
+    /// Names of the parameters declared `in: query`.
+    pub query_params: Vec<String>,
+    /// The JSON schema of the first 2xx response, when it is an object.
+    pub response: Option<SpecSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecSchema {
+    pub name: String,
+    pub properties: BTreeSet<String>,
+    pub required: BTreeSet<String>,
+}
+
+/// One way the router and the spec disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub method: String,
+    pub path: String,
+    pub kind: FindingKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// Served by the router but missing from the spec.
+    UndocumentedRoute,
+    /// Documented in the spec but not served.
+    UnservedRoute,
+    /// A path parameter in the template is not declared `in: path`.
+    UndocumentedPathParam(String),
+    /// The router and spec templates name their parameters differently.
+    PathParamNames {
+        router: Vec<String>,
+        spec: Vec<String>,
+    },
+    /// A field of the handler's `Query<T>` is not a documented parameter.
+    UndocumentedQueryParam(String),
+    /// The handler sends a field the response schema does not have.
+    UndocumentedResponseField { schema: String, field: String },
+    /// The response schema requires a field the handler may not send.
+    MissingResponseField { schema: String, field: String },
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: ", self.method.to_uppercase(), self.path)?;
+        match &self.kind {
+            FindingKind::UndocumentedRoute => write!(f, "route not documented"),
+            FindingKind::UnservedRoute => write!(f, "documented route not served"),
+            FindingKind::UndocumentedPathParam(name) => {
+                write!(f, "path parameter `{name}` not documented")
+            }
+            FindingKind::PathParamNames { router, spec } => write!(
+                f,
+                "path parameters differ (router: {}; spec: {})",
+                router.join(", "),
+                spec.join(", ")
+            ),
+            FindingKind::UndocumentedQueryParam(name) => {
+                write!(f, "query parameter `{name}` not documented")
+            }
+            FindingKind::UndocumentedResponseField { schema, field } => {
+                write!(f, "response field `{field}` missing from schema {schema}")
+            }
+            FindingKind::MissingResponseField { schema, field } => {
+                write!(
+                    f,
+                    "schema {schema} requires `{field}` but it may be omitted"
+                )
+            }
+        }
+    }
+}
+
+/// Known drift that does not fail validation.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    /// Internal route prefixes kept out of the public spec.
+    pub ignore_prefixes: Vec<String>,
+    /// Findings, as displayed, that predate the check.
+    pub known: BTreeSet<String>,
+}
+
+/// Findings not covered by the baseline, and baseline entries that no
+/// longer match anything (and should be deleted).
+#[derive(Debug, Default)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+    pub stale: Vec<String>,
+}
+
+impl Report {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty() && self.stale.is_empty()
+    }
+}
+
+/// Compare every route against the spec.
+pub fn cross_check(manifest: &RouteManifest, operations: &[SpecOperation]) -> Vec<Finding> {
+    let spec: BTreeMap<(String, String), &SpecOperation> = operations
+        .iter()
+        .map(|op| ((op.method.clone(), normalize(&op.path)), op))
+        .collect();
+    let served: BTreeSet<(String, String)> = manifest
+        .routes
+        .iter()
+        .map(|route| (route.method.clone(), normalize(&route.path)))
+        .collect();
+
+    let mut findings = Vec::new();
+    for route in &manifest.routes {
+        match spec.get(&(route.method.clone(), normalize(&route.path))) {
+            Some(op) => check_operation(route, op, &mut findings),
+            None => findings.push(finding(route, FindingKind::UndocumentedRoute)),
+        }
+    }
+    for op in operations {
+        if !served.contains(&(op.method.clone(), normalize(&op.path))) {
+            findings.push(Finding {
+                method: op.method.clone(),
+                path: op.path.clone(),
+                kind: FindingKind::UnservedRoute,
+            });
+        }
+    }
+    findings
+}
+
+/// Drop ignored and known findings, and report unused baseline entries.
+pub fn apply_baseline(findings: Vec<Finding>, baseline: &Baseline) -> Report {
+    let mut matched = BTreeSet::new();
+    let mut report = Report::default();
+
+    for finding in findings {
+        if baseline
+            .ignore_prefixes
+            .iter()
+            .any(|prefix| is_under(&finding.path, prefix))
+        {
+            continue;
+        }
+        let key = finding.to_string();
+        if baseline.known.contains(&key) {
+            matched.insert(key);
+        } else {
+            report.findings.push(finding);
+        }
+    }
+
+    report.stale = baseline.known.difference(&matched).cloned().collect();
+    report
+}
+
+fn check_operation(route: &RouteEntry, op: &SpecOperation, findings: &mut Vec<Finding>) {
+    let spec_template = path_params(&op.path);
+    if route.path_params != spec_template {
+        findings.push(finding(
+            route,
+            FindingKind::PathParamNames {
+                router: route.path_params.clone(),
+                spec: spec_template.clone(),
+            },
+        ));
+    }
+    for name in &spec_template {
+        if !op.path_params.contains(name) {
+            findings.push(finding(
+                route,
+                FindingKind::UndocumentedPathParam(name.clone()),
+            ));
+        }
+    }
+
+    if let Some(shape) = route.query.as_ref().and_then(|query| query.shape.as_ref()) {
+        for field in shape.fields.iter().filter(|f| !f.skip_deserializing) {
+            if !op.query_params.contains(&field.name) {
+                findings.push(finding(
+                    route,
+                    FindingKind::UndocumentedQueryParam(field.name.clone()),
+                ));
+            }
+        }
+    }
+
+    let response = route
+        .response
+        .as_ref()
+        .and_then(|response| response.shape.as_ref());
+    if let (Some(shape), Some(schema)) = (response, &op.response) {
+        check_response(route, shape, schema, findings);
+    }
+}
+
+fn check_response(
+    route: &RouteEntry,
+    shape: &StructShape,
+    schema: &SpecSchema,
+    findings: &mut Vec<Finding>,
+) {
+    if shape.open {
+        return;
+    }
+    let sent: BTreeMap<&str, bool> = shape
+        .fields
+        .iter()
+        .filter(|f| !f.skip_serializing)
+        .map(|f| (f.name.as_str(), f.optional))
+        .collect();
+
+    for name in sent.keys() {
+        if !schema.properties.contains(*name) {
+            findings.push(finding(
+                route,
+                FindingKind::UndocumentedResponseField {
+                    schema: schema.name.clone(),
+                    field: name.to_string(),
+                },
+            ));
+        }
+    }
+    for name in &schema.required {
+        if sent.get(name.as_str()) != Some(&false) {
+            findings.push(finding(
+                route,
+                FindingKind::MissingResponseField {
+                    schema: schema.name.clone(),
+                    field: name.clone(),
+                },
+            ));
+        }
+    }
+}
+
+fn finding(route: &RouteEntry, kind: FindingKind) -> Finding {
+    Finding {
+        method: route.method.clone(),
+        path: route.path.clone(),
+        kind,
+    }
+}
+
+/// `/orgs/{org_id}` and `/orgs/{id}` name the same route.
+fn normalize(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with('{') && segment.ends_with('}') {
+                "{}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_under(path: &str, prefix: &str) -> bool {
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_manifest::{FieldShape, TypeRef};
+
+    fn field(name: &str, optional: bool) -> FieldShape {
+        FieldShape {
+            name: name.to_string(),
+            optional,
+            skip_serializing: false,
+            skip_deserializing: false,
+        }
+    }
+
+    fn route(method: &str, path: &str) -> RouteEntry {
+        RouteEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            handler: "apps::handler".to_string(),
+            path_params: path_params(path),
+            query: None,
+            response: None,
+        }
+    }
+
+    fn operation(method: &str, path: &str) -> SpecOperation {
+        SpecOperation {
+            method: method.to_string(),
+            path: path.to_string(),
+            path_params: path_params(path),
+            query_params: Vec::new(),
+            response: None,
+        }
+    }
+
+    fn manifest(routes: Vec<RouteEntry>) -> RouteManifest {
+        RouteManifest { routes }
+    }
+
+    #[test]
+    fn test_missing_and_unserved_routes() {
+        let manifest = manifest(vec![
+            route("get", "/orgs"),
+            route("post", "/orgs/{org_id}/apps"),
+        ]);
+        let ops = vec![
+            operation("get", "/orgs"),
+            operation("delete", "/orgs/{org_id}"),
+        ];
+
+        let findings: Vec<String> = cross_check(&manifest, &ops)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "POST /orgs/{org_id}/apps: route not documented",
+                "DELETE /orgs/{org_id}: documented route not served",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_params() {
+        let manifest = manifest(vec![route("get", "/apps/{app_id}")]);
+        let mut op = operation("get", "/apps/{id}");
+        op.path_params.clear();
+
+        let kinds: Vec<FindingKind> = cross_check(&manifest, &[op])
+            .into_iter()
+            .map(|f| f.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                FindingKind::PathParamNames {
+                    router: vec!["app_id".to_string()],
+                    spec: vec!["id".to_string()],
+                },
+                FindingKind::UndocumentedPathParam("id".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_and_response_fields() {
+        let mut route = route("get", "/apps");
+        route.query = Some(TypeRef {
+            name: "ListAppsQuery".to_string(),
+            shape: Some(StructShape {
+                fields: vec![field("limit", true), field("label_selector", true)],
+                open: false,
+            }),
+        });
+        route.response = Some(TypeRef {
+            name: "ListAppsResponse".to_string(),
+            shape: Some(StructShape {
+                fields: vec![
+                    field("items", false),
+                    field("next_cursor", true),
+                    field("total", false),
+                ],
+                open: false,
+            }),
+        });
+        let mut op = operation("get", "/apps");
+        op.query_params = vec!["limit".to_string(), "cursor".to_string()];
+        op.response = Some(SpecSchema {
+            name: "ListAppsResponse".to_string(),
+            properties: ["items", "next_cursor"].map(String::from).into(),
+            required: ["items", "next_cursor"].map(String::from).into(),
+        });
+
+        let findings: Vec<String> = cross_check(&manifest(vec![route]), &[op])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "GET /apps: query parameter `label_selector` not documented",
+                "GET /apps: response field `total` missing from schema ListAppsResponse",
+                "GET /apps: schema ListAppsResponse requires `next_cursor` but it may be omitted",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_baseline() {
+        let findings = cross_check(
+            &manifest(vec![
+                route("get", "/_admin/leaders"),
+                route("get", "/orgs"),
+                route("post", "/orgs"),
+            ]),
+            &[],
+        );
+        let baseline = Baseline {
+            ignore_prefixes: vec!["/_admin".to_string()],
+            known: [
+                "GET /orgs: route not documented",
+                "GET /gone: route not documented",
+            ]
+            .map(String::from)
+            .into(),
+        };
+
+        let report = apply_baseline(findings, &baseline);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(
+            report.findings[0].to_string(),
+            "POST /orgs: route not documented"
+        );
+        assert_eq!(report.stale, vec!["GET /gone: route not documented"]);
+        assert!(!report.is_clean());
+    }
+}
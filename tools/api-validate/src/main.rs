@@ -1,9 +1,18 @@
+mod drift;
+mod openapi;
+mod route_manifest;
+
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
+use crate::drift::Baseline;
+use crate::route_manifest::{RouteManifest, TypeRef};
+
 fn sha256_hex(bytes: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(bytes);
@@ -44,6 +53,114 @@ fn require_file(path: &Path) -> Result<()> {
     }
 }
 
+/// `api/openapi/route-baseline.yaml`.
+#[derive(Debug, Default, Deserialize)]
+struct BaselineFile {
+    #[serde(default)]
+    ignore_prefixes: Vec<String>,
+    #[serde(default)]
+    known: BTreeSet<String>,
+}
+
+fn load_baseline(path: &Path) -> Result<Baseline> {
+    if !path.exists() {
+        return Ok(Baseline::default());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let file: BaselineFile = serde_yaml::from_str(&contents)
+        .with_context(|| format!("invalid baseline: {}", path.display()))?;
+    Ok(Baseline {
+        ignore_prefixes: file.ignore_prefixes,
+        known: file.known,
+    })
+}
+
+fn type_json(type_ref: &TypeRef) -> serde_json::Value {
+    serde_json::json!({
+        "type": type_ref.name,
+        "fields": type_ref.shape.as_ref().map(|shape| {
+            shape
+                .fields
+                .iter()
+                .map(|field| serde_json::json!({
+                    "name": field.name,
+                    "optional": field.optional,
+                    "skip_serializing": field.skip_serializing,
+                    "skip_deserializing": field.skip_deserializing,
+                }))
+                .collect::<Vec<_>>()
+        }),
+        "open": type_ref.shape.as_ref().map(|shape| shape.open),
+    })
+}
+
+fn write_route_manifest(manifest: &RouteManifest, path: &Path) -> Result<()> {
+    let routes: Vec<serde_json::Value> = manifest
+        .routes
+        .iter()
+        .map(|route| {
+            serde_json::json!({
+                "method": route.method,
+                "path": route.path,
+                "handler": route.handler,
+                "path_params": route.path_params,
+                "query": route.query.as_ref().map(type_json),
+                "response": route.response.as_ref().map(type_json),
+            })
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&serde_json::json!({ "routes": routes }))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, json + "\n").with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Check the control-plane router against the OpenAPI document.
+fn validate_routes(repo_root: &Path, api_openapi: &Path) -> Result<usize> {
+    let manifest = RouteManifest::generate(
+        &repo_root.join("services/control-plane/src/api/v1"),
+        &repo_root.join("libs/api-types/src"),
+    )?;
+
+    // `--write-route-manifest <path>` dumps the generated manifest for inspection.
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--write-route-manifest" {
+            let path = args
+                .next()
+                .ok_or_else(|| anyhow!("--write-route-manifest requires a path"))?;
+            write_route_manifest(&manifest, Path::new(&path))?;
+        }
+    }
+
+    let contents = std::fs::read_to_string(api_openapi)
+        .with_context(|| format!("failed to read {}", api_openapi.display()))?;
+    let spec: serde_yaml::Value = serde_yaml::from_str(&contents)
+        .with_context(|| format!("invalid YAML: {}", api_openapi.display()))?;
+    let operations = openapi::operations(&spec)?;
+
+    let baseline_path = repo_root.join("api/openapi/route-baseline.yaml");
+    let baseline = load_baseline(&baseline_path)?;
+    let report = drift::apply_baseline(drift::cross_check(&manifest, &operations), &baseline);
+    if !report.is_clean() {
+        let mut message = String::from("router and OpenAPI spec disagree:");
+        for finding in &report.findings {
+            message.push_str(&format!("\n  {finding}"));
+        }
+        for entry in &report.stale {
+            message.push_str(&format!(
+                "\n  no longer drifts, remove from {}: {entry}",
+                baseline_path.display()
+            ));
+        }
+        return Err(anyhow!(message));
+    }
+    Ok(manifest.routes.len())
+}
+
 fn main() -> Result<()> {
     let repo_root = std::env::current_dir().context("failed to determine current directory")?;
 
@@ -94,9 +211,13 @@ fn main() -> Result<()> {
         validate_json_file(path)?;
     }
 
+    // Semantic check: the router and the spec describe the same API.
+    let route_count = validate_routes(&repo_root, &api_openapi)?;
+
     println!(
-        "OK: OpenAPI YAML parsed and copies match; {} JSON schemas parsed",
-        schema_files.len()
+        "OK: OpenAPI YAML parsed and copies match; {} JSON schemas parsed; {} routes match the spec",
+        schema_files.len(),
+        route_count
     );
     Ok(())
 }
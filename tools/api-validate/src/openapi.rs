@@ -0,0 +1,173 @@
+//! Operations read from `openapi.yaml`.
+
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Result};
+use serde_yaml::Value;
+
+use crate::drift::{SpecOperation, SpecSchema};
+
+const METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Every operation under `paths`, with `$ref`s resolved.
+pub fn operations(spec: &Value) -> Result<Vec<SpecOperation>> {
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_mapping)
+        .ok_or_else(|| anyhow!("OpenAPI document has no paths"))?;
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let path = path
+            .as_str()
+            .ok_or_else(|| anyhow!("non-string OpenAPI path key"))?;
+        let item = resolve(spec, item)?;
+        let shared = parameters(spec, item)?;
+
+        for method in METHODS {
+            let Some(op) = item.get(*method) else {
+                continue;
+            };
+            let mut params = shared.clone();
+            params.extend(parameters(spec, op)?);
+
+            let in_location = |location: &str| {
+                params
+                    .iter()
+                    .filter(|(_, l)| l == location)
+                    .map(|(name, _)| name.clone())
+                    .collect::<Vec<_>>()
+            };
+            operations.push(SpecOperation {
+                method: method.to_string(),
+                path: path.to_string(),
+                path_params: in_location("path"),
+                query_params: in_location("query"),
+                response: success_schema(spec, op)?,
+            });
+        }
+    }
+    Ok(operations)
+}
+
+/// (name, location) of the parameters declared on a path item or operation.
+fn parameters(spec: &Value, node: &Value) -> Result<Vec<(String, String)>> {
+    let Some(list) = node.get("parameters").and_then(Value::as_sequence) else {
+        return Ok(Vec::new());
+    };
+    list.iter()
+        .map(|param| {
+            let param = resolve(spec, param)?;
+            let field = |key: &str| {
+                param
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("parameter without `{key}`"))
+            };
+            Ok((field("name")?, field("in")?))
+        })
+        .collect()
+}
+
+/// The object schema of the first 2xx JSON response, if there is one.
+fn success_schema(spec: &Value, op: &Value) -> Result<Option<SpecSchema>> {
+    let Some(responses) = op.get("responses").and_then(Value::as_mapping) else {
+        return Ok(None);
+    };
+    let mut codes: Vec<(String, &Value)> = responses
+        .iter()
+        .filter_map(|(code, response)| {
+            let code = match code {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            code.starts_with('2').then_some((code, response))
+        })
+        .collect();
+    codes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (_, response) in codes {
+        let response = resolve(spec, response)?;
+        let Some(schema) = response
+            .get("content")
+            .and_then(|content| content.get("application/json"))
+            .and_then(|media| media.get("schema"))
+        else {
+            continue;
+        };
+        let name = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.rsplit('/').next())
+            .unwrap_or("inline")
+            .to_string();
+        return Ok(
+            object_shape(spec, schema)?.map(|(properties, required)| SpecSchema {
+                name,
+                properties,
+                required,
+            }),
+        );
+    }
+    Ok(None)
+}
+
+type Shape = (BTreeSet<String>, BTreeSet<String>);
+
+/// Properties and required names of an object schema, merging `allOf`.
+///
+/// `None` when the shape is not a closed object (`oneOf`, free-form maps,
+/// arrays), which the drift checks skip.
+fn object_shape(spec: &Value, schema: &Value) -> Result<Option<Shape>> {
+    let schema = resolve(spec, schema)?;
+
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_sequence) {
+        let mut merged = Shape::default();
+        for part in parts {
+            let Some((properties, required)) = object_shape(spec, part)? else {
+                return Ok(None);
+            };
+            merged.0.extend(properties);
+            merged.1.extend(required);
+        }
+        return Ok(Some(merged));
+    }
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_mapping) else {
+        return Ok(None);
+    };
+    let properties = properties
+        .keys()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+    let required = schema
+        .get("required")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+    Ok(Some((properties, required)))
+}
+
+/// Follow local `$ref`s (`#/components/...`).
+fn resolve<'a>(spec: &'a Value, mut node: &'a Value) -> Result<&'a Value> {
+    for _ in 0..32 {
+        let Some(reference) = node.get("$ref").and_then(Value::as_str) else {
+            return Ok(node);
+        };
+        let pointer = reference
+            .strip_prefix("#/")
+            .ok_or_else(|| anyhow!("unsupported $ref: {reference}"))?;
+        node = pointer.split('/').try_fold(spec, |value, key| {
+            value
+                .get(key)
+                .ok_or_else(|| anyhow!("unresolved $ref: {reference}"))
+        })?;
+    }
+    Err(anyhow!("$ref chain too long"))
+}
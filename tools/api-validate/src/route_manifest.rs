@@ -0,0 +1,1068 @@
+//! Route manifest generated from the control-plane router source.
+//!
+//! Walks the `Router` builder chains under `services/control-plane/src/api/v1`
+//! starting at `routes()` in `mod.rs`, following `nest`/`merge` into other
+//! route functions. For each handler it records the path parameters, the
+//! fields of its `Query<T>` extractor, and the fields of the type it returns
+//! as JSON when that can be read off the source. Types not defined in the
+//! handler modules are looked up in `libs/api-types`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+const METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Every route served under `/v1`.
+#[derive(Debug, Default)]
+pub struct RouteManifest {
+    pub routes: Vec<RouteEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteEntry {
+    /// Lowercase HTTP method.
+    pub method: String,
+    /// Path relative to `/v1`, in axum template syntax (`/orgs/{org_id}`).
+    pub path: String,
+    /// Handler as `module::function`.
+    pub handler: String,
+    pub path_params: Vec<String>,
+    /// The `Query<T>` extractor, if the handler takes one.
+    pub query: Option<TypeRef>,
+    /// The type serialized as the JSON response body, if known.
+    pub response: Option<TypeRef>,
+}
+
+/// A named type and, when it is a plain struct in the API sources, its
+/// serialized fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRef {
+    pub name: String,
+    pub shape: Option<StructShape>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructShape {
+    pub fields: Vec<FieldShape>,
+    /// Has `#[serde(flatten)]` fields, so the field list is incomplete.
+    pub open: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldShape {
+    /// Name on the wire, after serde renames.
+    pub name: String,
+    /// Has `skip_serializing_if`, so it may be absent from responses.
+    pub optional: bool,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+}
+
+impl RouteManifest {
+    /// Generate the manifest from the control-plane `api/v1` directory,
+    /// reading shared request/response types from `types_dir`.
+    pub fn generate(v1_dir: &Path, types_dir: &Path) -> Result<Self> {
+        let modules = read_modules(v1_dir)?;
+        let types = read_modules(types_dir)?.into_values().collect();
+        Self::from_sources(modules, types)
+    }
+
+    /// Generate the manifest from module sources keyed by module name, with
+    /// `""` for `mod.rs`.
+    pub fn from_modules(modules: BTreeMap<String, String>) -> Result<Self> {
+        Self::from_sources(modules, Vec::new())
+    }
+
+    fn from_sources(modules: BTreeMap<String, String>, types: Vec<String>) -> Result<Self> {
+        let sources = Sources { modules, types };
+        let mut routes = Vec::new();
+        sources.walk_router_fn("", "routes", "", &mut routes, 0)?;
+        routes.sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)));
+        Ok(Self { routes })
+    }
+}
+
+/// Sources of the `.rs` files in `dir`, comments stripped, keyed by module
+/// name (`""` for `mod.rs`).
+fn read_modules(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut modules = BTreeMap::new();
+    for entry in
+        std::fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("rs") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let name = if stem == "mod" { "" } else { stem };
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        modules.insert(name.to_string(), strip_comments(&source));
+    }
+    Ok(modules)
+}
+
+struct Sources {
+    modules: BTreeMap<String, String>,
+    /// Shared type definitions, searched for shapes only.
+    types: Vec<String>,
+}
+
+/// Guards against route functions that (indirectly) call themselves.
+const MAX_DEPTH: usize = 16;
+
+impl Sources {
+    fn walk_router_fn(
+        &self,
+        module: &str,
+        function: &str,
+        prefix: &str,
+        out: &mut Vec<RouteEntry>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow!(
+                "route functions nest too deeply at {module}::{function}"
+            ));
+        }
+        let (module, item) = self
+            .find_fn(module, function)
+            .ok_or_else(|| anyhow!("route function not found: {module}::{function}"))?;
+        let expr = tail_expression(&item.body);
+        self.walk_router_expr(module, expr, prefix, out, depth)
+    }
+
+    fn walk_router_expr(
+        &self,
+        module: &str,
+        expr: &str,
+        prefix: &str,
+        out: &mut Vec<RouteEntry>,
+        depth: usize,
+    ) -> Result<()> {
+        let calls = split_calls(expr);
+        let Some((head, rest)) = calls.split_first() else {
+            return Err(anyhow!("expected a router expression in {module}: {expr}"));
+        };
+
+        if head.name != "Router::new" {
+            let (target_module, function) = resolve_path(module, &head.name);
+            self.walk_router_fn(&target_module, function, prefix, out, depth + 1)?;
+        }
+
+        for call in rest {
+            match call.name.as_str() {
+                "route" => {
+                    let [path, methods] = call.args.as_slice() else {
+                        return Err(anyhow!("unexpected route() arguments in {module}"));
+                    };
+                    let path = join_path(prefix, &string_literal(path)?);
+                    for method_call in split_calls(methods) {
+                        let method = last_segment(&method_call.name);
+                        if !METHODS.contains(&method) {
+                            continue;
+                        }
+                        let Some(handler) = method_call.args.first() else {
+                            continue;
+                        };
+                        out.push(self.route_entry(module, method, &path, handler.trim()));
+                    }
+                }
+                "nest" => {
+                    let [path, router] = call.args.as_slice() else {
+                        return Err(anyhow!("unexpected nest() arguments in {module}"));
+                    };
+                    let prefix = join_path(prefix, &string_literal(path)?);
+                    self.walk_router_expr(module, router, &prefix, out, depth + 1)?;
+                }
+                "merge" => {
+                    let [router] = call.args.as_slice() else {
+                        return Err(anyhow!("unexpected merge() arguments in {module}"));
+                    };
+                    self.walk_router_expr(module, router, prefix, out, depth + 1)?;
+                }
+                // Layers and state do not change the route table.
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn route_entry(&self, module: &str, method: &str, path: &str, handler: &str) -> RouteEntry {
+        let (handler_module, function) = resolve_path(module, handler);
+        let found = self.find_fn(&handler_module, function);
+
+        let (query, response, handler) = match found {
+            Some((handler_module, item)) => {
+                let query = find_generic_arg(&item.params, "Query")
+                    .map(|name| self.type_ref(handler_module, &name));
+                let response =
+                    response_type(&item).map(|name| self.type_ref(handler_module, &name));
+                (query, response, qualified(handler_module, function))
+            }
+            None => (None, None, qualified(&handler_module, function)),
+        };
+
+        RouteEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            handler,
+            path_params: path_params(path),
+            query,
+            response,
+        }
+    }
+
+    /// Find a function, first in `module` and then in any module (for
+    /// imported handlers).
+    fn find_fn<'a>(&'a self, module: &'a str, name: &str) -> Option<(&'a str, FnItem)> {
+        if let Some(item) = self.modules.get(module).and_then(|src| find_fn(src, name)) {
+            return Some((module, item));
+        }
+        self.modules
+            .iter()
+            .find_map(|(m, src)| find_fn(src, name).map(|item| (m.as_str(), item)))
+    }
+
+    fn type_ref(&self, module: &str, name: &str) -> TypeRef {
+        let shape = self
+            .modules
+            .get(module)
+            .and_then(|src| find_struct(src, name))
+            .or_else(|| self.modules.values().find_map(|src| find_struct(src, name)))
+            .or_else(|| self.types.iter().find_map(|src| find_struct(src, name)));
+        TypeRef {
+            name: name.to_string(),
+            shape,
+        }
+    }
+}
+
+fn qualified(module: &str, function: &str) -> String {
+    if module.is_empty() {
+        function.to_string()
+    } else {
+        format!("{module}::{function}")
+    }
+}
+
+/// Resolve `a::b::f` relative to `module` into (module, function).
+fn resolve_path<'a>(module: &str, path: &'a str) -> (String, &'a str) {
+    let segments: Vec<&str> = path
+        .split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !matches!(*s, "self" | "super" | "crate" | "api" | "v1"))
+        .collect();
+    match segments.as_slice() {
+        [] => (module.to_string(), path),
+        [function] => (module.to_string(), function),
+        [.., parent, function] => (parent.to_string(), function),
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    match (prefix, path) {
+        ("", "/") => "/".to_string(),
+        (_, "/") => prefix.to_string(),
+        _ => format!("{prefix}{path}"),
+    }
+}
+
+/// Names of the `{param}` segments in a path template.
+pub fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| name.trim_start_matches('*').to_string())
+        .collect()
+}
+
+fn string_literal(expr: &str) -> Result<String> {
+    let expr = expr.trim();
+    expr.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("expected a string literal path, found `{expr}`"))
+}
+
+// =============================================================================
+// Handler inspection
+// =============================================================================
+
+struct FnItem {
+    params: String,
+    ret: String,
+    body: String,
+}
+
+/// The JSON response type of a handler: from a `Json<T>` return type, or
+/// from the first `Json(..)` in the body built from a named struct.
+fn response_type(item: &FnItem) -> Option<String> {
+    if let Some(name) = find_generic_arg(&item.ret, "Json") {
+        return Some(name);
+    }
+
+    let body = item.body.as_str();
+    let mut search = 0;
+    while let Some(offset) = find_word(&body[search..], "Json(") {
+        let open = search + offset + "Json".len();
+        let close = matching(body, open)?;
+        let arg = body[open + 1..close].trim();
+        search = close;
+
+        if let Some(name) = constructed_type(arg) {
+            return Some(name);
+        }
+        if is_ident(arg) {
+            if let Some(name) = binding_type(body, arg) {
+                return Some(name);
+            }
+        }
+    }
+    None
+}
+
+/// `Type { .. }`, `Type::from(..)` or `path::Type::new(..)` → `Type`.
+fn constructed_type(expr: &str) -> Option<String> {
+    let end = expr
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(expr.len());
+    let path = &expr[..end];
+    let rest = expr[end..].trim_start();
+
+    let name = if rest.starts_with('{') {
+        last_segment(path)
+    } else if rest.starts_with('(') {
+        let (ty, _) = path.rsplit_once("::")?;
+        last_segment(ty)
+    } else {
+        return None;
+    };
+    let is_type = name.chars().next().is_some_and(char::is_uppercase)
+        && !matches!(name, "Some" | "Ok" | "Vec" | "Box" | "Self");
+    is_type.then(|| name.to_string())
+}
+
+/// The type of `let [mut] name[: Type] = ..;` in a function body.
+fn binding_type(body: &str, name: &str) -> Option<String> {
+    for pattern in [format!("let {name}"), format!("let mut {name}")] {
+        let mut search = 0;
+        while let Some(offset) = find_word(&body[search..], &pattern) {
+            let start = search + offset + pattern.len();
+            search = start;
+            let rest = body[start..].trim_start();
+            if let Some(annotated) = rest.strip_prefix(':') {
+                let end = annotated.find('=').unwrap_or(annotated.len());
+                let ty = annotated[..end].trim();
+                if is_path(ty) {
+                    return Some(last_segment(ty).to_string());
+                }
+            } else if let Some(value) = rest.strip_prefix('=') {
+                if let Some(ty) = constructed_type(value.trim_start()) {
+                    return Some(ty);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The last path segment of `T` in the first `Wrapper<T>` in `text`.
+fn find_generic_arg(text: &str, wrapper: &str) -> Option<String> {
+    let pattern = format!("{wrapper}<");
+    let offset = find_word(text, &pattern)?;
+    let start = offset + pattern.len();
+    let mut depth = 1;
+    for (i, c) in text[start..].char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    let ty = text[start..start + i].trim();
+                    return is_path(ty).then(|| last_segment(ty).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn is_path(s: &str) -> bool {
+    !s.is_empty() && s.split("::").all(|seg| is_ident(seg.trim()))
+}
+
+/// Find `needle` where it is not preceded by an identifier character.
+fn find_word(haystack: &str, needle: &str) -> Option<usize> {
+    let mut search = 0;
+    while let Some(offset) = haystack[search..].find(needle) {
+        let at = search + offset;
+        let preceded = haystack[..at]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if !preceded {
+            return Some(at);
+        }
+        search = at + needle.len();
+    }
+    None
+}
+
+fn find_fn(src: &str, name: &str) -> Option<FnItem> {
+    let pattern = format!("fn {name}");
+    let mut search = 0;
+    while let Some(offset) = find_word(&src[search..], &pattern) {
+        let start = search + offset + pattern.len();
+        search = start;
+        let rest = &src[start..];
+        let generics = rest.len() - rest.trim_start().len();
+        let open = match rest.trim_start().chars().next() {
+            Some('(') => start + generics,
+            Some('<') => {
+                let generic_close = start + generics + rest.trim_start().find('>')?;
+                generic_close + 1 + src[generic_close + 1..].find('(')?
+            }
+            _ => continue,
+        };
+        let close = matching(src, open)?;
+        let body_open = close + next_top_level(&src[close..], '{')?;
+        let body_close = matching(src, body_open)?;
+        let ret = src[close + 1..body_open].trim();
+        return Some(FnItem {
+            params: src[open + 1..close].to_string(),
+            ret: ret.strip_prefix("->").unwrap_or(ret).trim().to_string(),
+            body: src[body_open + 1..body_close].to_string(),
+        });
+    }
+    None
+}
+
+/// The final expression of a function body.
+fn tail_expression(body: &str) -> &str {
+    let mut start = 0;
+    let bytes = body.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => match matching(body, i) {
+                Some(close) => i = close,
+                None => break,
+            },
+            b'"' => i = skip_string(body, i),
+            b';' => start = i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    body[start..].trim()
+}
+
+// =============================================================================
+// Struct inspection
+// =============================================================================
+
+fn find_struct(src: &str, name: &str) -> Option<StructShape> {
+    let pattern = format!("struct {name}");
+    let mut search = 0;
+    while let Some(offset) = find_word(&src[search..], &pattern) {
+        let at = search + offset;
+        let start = at + pattern.len();
+        search = start;
+        let rest = &src[start..];
+        if !rest.trim_start().starts_with('{') {
+            // Tuple, unit and generic structs have no named field list.
+            if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            return None;
+        }
+        let open = start + (rest.len() - rest.trim_start().len());
+        let close = matching(src, open)?;
+        let rename_all = item_attributes(src, at)
+            .iter()
+            .flat_map(|attr| serde_args(attr))
+            .find_map(|(key, value)| if key == "rename_all" { value } else { None });
+        return Some(parse_fields(&src[open + 1..close], rename_all.as_deref()));
+    }
+    None
+}
+
+/// The attributes directly above the item whose keyword is at `at`.
+fn item_attributes(src: &str, at: usize) -> Vec<String> {
+    let line_start = src[..at].rfind('\n').map_or(0, |i| i + 1);
+    let mut start = line_start;
+    for line in src[..line_start].lines().rev() {
+        let trimmed = line.trim();
+        if trimmed.ends_with(['}', ';', '{']) {
+            break;
+        }
+        start -= line.len() + 1;
+    }
+
+    let header = &src[start..line_start];
+    let mut attrs = Vec::new();
+    let mut search = 0;
+    while let Some(offset) = header[search..].find("#[") {
+        let open = search + offset + 1;
+        let Some(close) = matching(header, open) else {
+            break;
+        };
+        attrs.push(header[open + 1..close].to_string());
+        search = close;
+    }
+    attrs
+}
+
+fn parse_fields(body: &str, rename_all: Option<&str>) -> StructShape {
+    let mut fields = Vec::new();
+    let mut open = false;
+
+    for raw in split_top_level(body, ',') {
+        let mut rest = raw.trim();
+        let mut attrs = Vec::new();
+        while rest.starts_with("#[") {
+            let Some(close) = matching(rest, 1) else {
+                break;
+            };
+            attrs.push(rest[2..close].to_string());
+            rest = rest[close + 1..].trim_start();
+        }
+        let rest = strip_visibility(rest);
+        let Some((ident, _)) = rest.split_once(':') else {
+            continue;
+        };
+        let ident = ident.trim().trim_start_matches("r#");
+        if !is_ident(ident) {
+            continue;
+        }
+
+        let args: Vec<(String, Option<String>)> =
+            attrs.iter().flat_map(|a| serde_args(a)).collect();
+        let has = |key: &str| args.iter().any(|(k, _)| k == key);
+        if has("flatten") {
+            open = true;
+            continue;
+        }
+        let name = args
+            .iter()
+            .find_map(|(k, v)| (k == "rename").then(|| v.clone()).flatten())
+            .unwrap_or_else(|| rename(ident, rename_all));
+
+        fields.push(FieldShape {
+            name,
+            optional: has("skip_serializing_if"),
+            skip_serializing: has("skip") || has("skip_serializing"),
+            skip_deserializing: has("skip") || has("skip_deserializing"),
+        });
+    }
+
+    StructShape { fields, open }
+}
+
+fn strip_visibility(field: &str) -> &str {
+    let Some(rest) = field.strip_prefix("pub") else {
+        return field;
+    };
+    // A private field whose name starts with `pub` (e.g. `public_path`).
+    if !rest.starts_with(|c: char| c.is_whitespace() || c == '(') {
+        return field;
+    }
+    let rest = rest.trim_start();
+    match rest.strip_prefix('(') {
+        Some(inner) => inner.split_once(')').map_or(rest, |(_, r)| r.trim_start()),
+        None => rest,
+    }
+}
+
+/// `key` / `key = "value"` pairs from a `serde(..)` attribute.
+fn serde_args(attr: &str) -> Vec<(String, Option<String>)> {
+    let Some(inner) = attr
+        .trim()
+        .strip_prefix("serde")
+        .map(str::trim_start)
+        .and_then(|s| s.strip_prefix('('))
+        .and_then(|s| s.strip_suffix(')'))
+    else {
+        return Vec::new();
+    };
+    split_top_level(inner, ',')
+        .into_iter()
+        .filter(|arg| !arg.trim().is_empty())
+        .map(|arg| match arg.split_once('=') {
+            Some((key, value)) => (
+                key.trim().to_string(),
+                Some(value.trim().trim_matches('"').to_string()),
+            ),
+            None => (arg.trim().to_string(), None),
+        })
+        .collect()
+}
+
+fn rename(ident: &str, rename_all: Option<&str>) -> String {
+    match rename_all {
+        Some("camelCase") => {
+            let mut out = String::new();
+            let mut upper = false;
+            for c in ident.chars() {
+                if c == '_' {
+                    upper = true;
+                } else if upper {
+                    out.extend(c.to_uppercase());
+                    upper = false;
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+        Some("kebab-case") => ident.replace('_', "-"),
+        Some("SCREAMING_SNAKE_CASE") => ident.to_uppercase(),
+        Some("lowercase") => ident.to_lowercase(),
+        _ => ident.to_string(),
+    }
+}
+
+// =============================================================================
+// Lexing helpers
+// =============================================================================
+
+/// A call in a method chain: `name(args)`.
+#[derive(Debug)]
+struct Call {
+    name: String,
+    args: Vec<String>,
+}
+
+/// Split `a::b(x).c(y, z)` into its calls.
+fn split_calls(expr: &str) -> Vec<Call> {
+    let mut calls = Vec::new();
+    let mut rest = expr.trim();
+    loop {
+        let Some(open) = rest.find('(') else {
+            break;
+        };
+        let name = rest[..open].trim().trim_start_matches('.').trim();
+        let Some(close) = matching(rest, open) else {
+            break;
+        };
+        calls.push(Call {
+            name: name.to_string(),
+            args: split_top_level(&rest[open + 1..close], ',')
+                .into_iter()
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect(),
+        });
+        rest = rest[close + 1..].trim_start();
+        if !rest.starts_with('.') {
+            break;
+        }
+    }
+    calls
+}
+
+/// Split on `sep` outside brackets and string literals.
+fn split_top_level(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' | b'<' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            // `->` and `=>` are not closing angle brackets.
+            b'>' if i > 0 && matches!(bytes[i - 1], b'-' | b'=') => {}
+            b'>' => depth = depth.saturating_sub(1),
+            b'"' => i = skip_string(text, i),
+            c if c as char == sep && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Index of the first `target` in `text` outside brackets and strings.
+fn next_top_level(text: &str, target: char) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c as char == target && depth == 0 {
+            return Some(i);
+        }
+        match c {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            b'"' => i = skip_string(text, i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Index of the bracket closing the one at `open`.
+fn matching(text: &str, open: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let (open_c, close_c) = match bytes.get(open)? {
+        b'(' => (b'(', b')'),
+        b'[' => (b'[', b']'),
+        b'{' => (b'{', b'}'),
+        _ => return None,
+    };
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            c if c == open_c => depth += 1,
+            c if c == close_c => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            b'"' => i = skip_string(text, i),
+            b'\'' => i = skip_char_literal(text, i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Index of the closing quote of the string literal starting at `start`,
+/// including raw strings (`r#"..."#`).
+fn skip_string(text: &str, start: usize) -> usize {
+    let bytes = text.as_bytes();
+    let mut hashes = 0;
+    let mut j = start;
+    while j > 0 && bytes[j - 1] == b'#' {
+        hashes += 1;
+        j -= 1;
+    }
+    let raw = j > 0 && bytes[j - 1] == b'r';
+
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if !raw => i += 1,
+            b'"' if bytes[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|&&b| b == b'#')
+                .count()
+                == hashes =>
+            {
+                return i + hashes;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Skip a char literal (`'x'`, `'\n'`); lifetimes are left alone.
+fn skip_char_literal(text: &str, start: usize) -> usize {
+    let bytes = text.as_bytes();
+    match (
+        bytes.get(start + 1),
+        bytes.get(start + 2),
+        bytes.get(start + 3),
+    ) {
+        (Some(b'\\'), _, _) => text[start + 3..]
+            .find('\'')
+            .map_or(start, |end| start + 3 + end),
+        (Some(_), Some(b'\''), _) => start + 2,
+        _ => {
+            // Multi-byte char literal.
+            let mut chars = text[start + 1..].char_indices();
+            match (chars.next(), chars.next()) {
+                (Some(_), Some((end, '\''))) => start + 1 + end,
+                _ => start,
+            }
+        }
+    }
+}
+
+/// Remove `//` and `/* */` comments, keeping string literals intact.
+fn strip_comments(src: &str) -> String {
+    let bytes = src.as_bytes();
+    let mut out = String::with_capacity(src.len());
+    let mut i = 0;
+    let mut copied = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = skip_string(src, i),
+            b'\'' => i = skip_char_literal(src, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                out.push_str(&src[copied..i]);
+                i = src[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                copied = i;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                out.push_str(&src[copied..i]);
+                i = src[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+                copied = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    out.push_str(&src[copied.min(src.len())..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules(sources: &[(&str, &str)]) -> BTreeMap<String, String> {
+        sources
+            .iter()
+            .map(|(name, src)| (name.to_string(), strip_comments(src)))
+            .collect()
+    }
+
+    const MOD_RS: &str = r#"
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        // Apps are nested under orgs: /v1/orgs/{org_id}/apps
+        .nest("/orgs/{org_id}/apps", apps::routes().merge(labels::routes()))
+        .route(
+            "/orgs/{org_id}/search",
+            axum::routing::get(search::search),
+        )
+}
+"#;
+
+    const APPS_RS: &str = r##"
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_apps).post(create_app))
+        .route("/{app_id}", get(get_app))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListAppsQuery {
+    /// Max number of items to return.
+    pub limit: Option<i64>,
+    #[serde(rename = "label_selector")]
+    pub selector: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppResponse {
+    pub app_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip)]
+    pub internal: bool,
+}
+
+async fn list_apps(
+    Path(org_id): Path<String>,
+    Query(query): Query<ListAppsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sql = r#"SELECT "}" FROM apps"#;
+    Ok(Json(Vec::new()))
+}
+
+async fn create_app(Json(req): Json<CreateAppRequest>) -> Result<impl IntoResponse, ApiError> {
+    let response = AppResponse::from(req);
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn get_app() -> Result<Json<AppResponse>, ApiError> {
+    todo!()
+}
+"##;
+
+    const LABELS_RS: &str = r#"
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/{app_id}/labels", patch(super::apps_labels::patch_labels))
+}
+"#;
+
+    #[test]
+    fn test_walks_nested_routers() {
+        let manifest = RouteManifest::from_modules(modules(&[
+            ("", MOD_RS),
+            ("apps", APPS_RS),
+            ("labels", LABELS_RS),
+        ]))
+        .unwrap();
+
+        let routes: Vec<(String, String, String)> = manifest
+            .routes
+            .iter()
+            .map(|r| (r.method.clone(), r.path.clone(), r.handler.clone()))
+            .collect();
+        let expected = [
+            ("get", "/orgs/{org_id}/apps", "apps::list_apps"),
+            ("post", "/orgs/{org_id}/apps", "apps::create_app"),
+            ("get", "/orgs/{org_id}/apps/{app_id}", "apps::get_app"),
+            (
+                "patch",
+                "/orgs/{org_id}/apps/{app_id}/labels",
+                "apps_labels::patch_labels",
+            ),
+            ("get", "/orgs/{org_id}/search", "search::search"),
+        ]
+        .map(|(m, p, h)| (m.to_string(), p.to_string(), h.to_string()));
+        assert_eq!(routes, expected);
+        assert_eq!(manifest.routes[2].path_params, vec!["org_id", "app_id"]);
+    }
+
+    #[test]
+    fn test_reads_query_and_response_types() {
+        let manifest = RouteManifest::from_modules(modules(&[
+            ("", MOD_RS),
+            ("apps", APPS_RS),
+            ("labels", LABELS_RS),
+        ]))
+        .unwrap();
+        let route = |method: &str, path: &str| {
+            manifest
+                .routes
+                .iter()
+                .find(|r| r.method == method && r.path == path)
+                .unwrap()
+        };
+
+        let list = route("get", "/orgs/{org_id}/apps");
+        let query = list.query.as_ref().unwrap();
+        assert_eq!(query.name, "ListAppsQuery");
+        let names: Vec<&str> = query
+            .shape
+            .as_ref()
+            .unwrap()
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["limit", "label_selector"]);
+        assert!(list.response.is_none());
+
+        let create = route("post", "/orgs/{org_id}/apps");
+        let response = create.response.as_ref().unwrap();
+        assert_eq!(response.name, "AppResponse");
+        let shape = response.shape.as_ref().unwrap();
+        assert_eq!(
+            shape.fields,
+            vec![
+                FieldShape {
+                    name: "appId".to_string(),
+                    optional: false,
+                    skip_serializing: false,
+                    skip_deserializing: false,
+                },
+                FieldShape {
+                    name: "description".to_string(),
+                    optional: true,
+                    skip_serializing: false,
+                    skip_deserializing: false,
+                },
+                FieldShape {
+                    name: "internal".to_string(),
+                    optional: false,
+                    skip_serializing: true,
+                    skip_deserializing: true,
+                },
+            ]
+        );
+
+        let get = route("get", "/orgs/{org_id}/apps/{app_id}");
+        assert_eq!(get.response.as_ref().unwrap().name, "AppResponse");
+    }
+
+    #[test]
+    fn test_reads_shared_types() {
+        let search = r#"
+async fn search() -> Result<Json<SearchResponse>, ApiError> {
+    todo!()
+}
+"#;
+        let shared = strip_comments(
+            r#"
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub items: Vec<String>,
+}
+"#,
+        );
+        let manifest = RouteManifest::from_sources(
+            modules(&[
+                ("", MOD_RS),
+                ("apps", APPS_RS),
+                ("labels", LABELS_RS),
+                ("search", search),
+            ]),
+            vec![shared],
+        )
+        .unwrap();
+
+        let route = manifest
+            .routes
+            .iter()
+            .find(|r| r.path == "/orgs/{org_id}/search")
+            .unwrap();
+        let response = route.response.as_ref().unwrap();
+        assert_eq!(response.name, "SearchResponse");
+        let names: Vec<&str> = response
+            .shape
+            .as_ref()
+            .unwrap()
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["items"]);
+    }
+
+    #[test]
+    fn test_strip_visibility() {
+        assert_eq!(strip_visibility("pub name: String"), "name: String");
+        assert_eq!(strip_visibility("pub(crate) name: String"), "name: String");
+        assert_eq!(
+            strip_visibility("public_path: String"),
+            "public_path: String"
+        );
+        assert_eq!(strip_visibility("name: String"), "name: String");
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_strings() {
+        let src = "let url = \"https://example.com\"; // trailing\n/* block */ let x = 1;";
+        assert_eq!(
+            strip_comments(src),
+            "let url = \"https://example.com\"; \n let x = 1;"
+        );
+    }
+}